@@ -0,0 +1,391 @@
+//! GraphQL API: UNIQUENESS Cluster Introspection
+//!
+//! One endpoint, `POST /api/v1/graphql`, for everything a dashboard or
+//! operator tool needs to know about the cluster:
+//! - **Topology**: `nodes(role, region)`, `node(id)` and `regions`
+//! - **Consensus**: `consensus { term leader { name } commitIndex }`
+//! - **Metrics**: `metrics` on any node, fetched only when selected
+//! - **Administration**: `addNode` and `removeNode`, which require a bearer
+//!   token listed in [`GraphQLConfig::admin_tokens`]
+//!
+//! Resolvers read through a [`ClusterBackend`], so the schema does not care
+//! whether it sits in front of a live coordinator or a test double.
+
+use crate::consensus::Term;
+use crate::error::{Error, Result};
+use crate::types::{NodeId, NodeRole, NodeStatus};
+
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, Enum, Guard, InputObject, Object, Schema, SimpleObject,
+};
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::info;
+use warp::{Filter, Rejection, Reply};
+
+/// GraphQL API configuration
+#[derive(Debug, Clone, Default)]
+pub struct GraphQLConfig {
+    /// Bearer tokens allowed to run mutations; empty disables mutations
+    pub admin_tokens: Vec<String>,
+}
+
+/// A node as the backend reports it
+#[derive(Debug, Clone)]
+pub struct NodeSummary {
+    pub id: NodeId,
+    pub name: String,
+    pub address: String,
+    pub role: NodeRole,
+    pub status: NodeStatus,
+    pub region: Option<String>,
+}
+
+/// Consensus state as the backend reports it
+#[derive(Debug, Clone)]
+pub struct ConsensusSummary {
+    pub term: Term,
+    pub leader: Option<NodeId>,
+    pub commit_index: u64,
+    pub last_applied: u64,
+    pub voters: Vec<NodeId>,
+    pub learners: Vec<NodeId>,
+}
+
+/// Load figures for one node
+#[derive(Debug, Clone, SimpleObject)]
+pub struct NodeMetrics {
+    pub cpu_usage_percent: f64,
+    pub memory_usage_mb: f64,
+    pub throughput_tps: f64,
+    pub latency_p95_ms: f64,
+}
+
+/// A node to add to the cluster
+#[derive(Debug, Clone, InputObject)]
+pub struct AddNodeInput {
+    pub id: u64,
+    pub address: String,
+    /// Defaults to `aurora-node-{id}`
+    pub name: Option<String>,
+    #[graphql(default_with = "MemberKind::Learner")]
+    pub kind: MemberKind,
+    pub region: Option<String>,
+}
+
+/// How a new node takes part in consensus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum MemberKind {
+    /// Votes and counts toward quorum
+    Voter,
+    /// Receives the log without voting, e.g. while catching up
+    Learner,
+}
+
+/// Where resolvers read cluster state and apply changes
+#[async_trait::async_trait]
+pub trait ClusterBackend: Send + Sync {
+    async fn nodes(&self) -> Result<Vec<NodeSummary>>;
+    async fn consensus_state(&self) -> Result<ConsensusSummary>;
+    async fn node_metrics(&self, node_id: NodeId) -> Result<Option<NodeMetrics>>;
+    async fn add_node(&self, node: AddNodeInput) -> Result<NodeSummary>;
+    /// Returns whether the node was a member
+    async fn remove_node(&self, node_id: NodeId) -> Result<bool>;
+}
+
+type Backend = Arc<dyn ClusterBackend>;
+
+/// Node role as exposed in the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Role {
+    Leader,
+    Follower,
+    Candidate,
+    Learner,
+    AuroraDb,
+    LoadBalancer,
+}
+
+impl From<&NodeRole> for Role {
+    fn from(role: &NodeRole) -> Self {
+        match role {
+            NodeRole::Leader => Role::Leader,
+            NodeRole::Follower => Role::Follower,
+            NodeRole::Candidate => Role::Candidate,
+            NodeRole::Learner => Role::Learner,
+            NodeRole::AuroraDb => Role::AuroraDb,
+            NodeRole::LoadBalancer => Role::LoadBalancer,
+        }
+    }
+}
+
+/// Node health as exposed in the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Health {
+    Healthy,
+    Suspected,
+    Failed,
+    Recovering,
+    Decommissioned,
+}
+
+impl From<&NodeStatus> for Health {
+    fn from(status: &NodeStatus) -> Self {
+        match status {
+            NodeStatus::Healthy => Health::Healthy,
+            NodeStatus::Suspected => Health::Suspected,
+            NodeStatus::Failed => Health::Failed,
+            NodeStatus::Recovering => Health::Recovering,
+            NodeStatus::Decommissioned => Health::Decommissioned,
+        }
+    }
+}
+
+/// Cluster node
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(complex)]
+pub struct Node {
+    pub id: u64,
+    pub name: String,
+    pub address: String,
+    pub role: Role,
+    pub health: Health,
+    pub region: Option<String>,
+}
+
+impl From<NodeSummary> for Node {
+    fn from(node: NodeSummary) -> Self {
+        Self {
+            id: node.id.0,
+            role: Role::from(&node.role),
+            health: Health::from(&node.status),
+            name: node.name,
+            address: node.address,
+            region: node.region,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Node {
+    /// Current load; resolved only when selected
+    async fn metrics(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<NodeMetrics>> {
+        Ok(ctx.data::<Backend>()?.node_metrics(NodeId(self.id)).await?)
+    }
+}
+
+/// Consensus state
+pub struct Consensus(ConsensusSummary);
+
+#[Object]
+impl Consensus {
+    async fn term(&self) -> u64 {
+        self.0.term
+    }
+
+    async fn commit_index(&self) -> u64 {
+        self.0.commit_index
+    }
+
+    async fn last_applied(&self) -> u64 {
+        self.0.last_applied
+    }
+
+    async fn leader_id(&self) -> Option<u64> {
+        self.0.leader.map(|leader| leader.0)
+    }
+
+    /// The leader as a full node, for selecting its fields
+    async fn leader(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Node>> {
+        let Some(leader) = self.0.leader else { return Ok(None) };
+        let nodes = ctx.data::<Backend>()?.nodes().await?;
+        Ok(nodes.into_iter().find(|node| node.id == leader).map(Node::from))
+    }
+
+    async fn voters(&self) -> Vec<u64> {
+        self.0.voters.iter().map(|node| node.0).collect()
+    }
+
+    async fn learners(&self) -> Vec<u64> {
+        self.0.learners.iter().map(|node| node.0).collect()
+    }
+}
+
+/// Who sent a request, as established from its bearer token
+#[derive(Debug, Clone, Copy)]
+pub struct Caller {
+    pub admin: bool,
+}
+
+impl Caller {
+    fn from_authorization(admin_tokens: &[String], authorization: Option<&str>) -> Self {
+        let token = authorization.and_then(|value| value.strip_prefix("Bearer "));
+        Self { admin: token.is_some_and(|token| admin_tokens.iter().any(|admin| admin == token)) }
+    }
+}
+
+/// Rejects fields unless the caller presented an admin token
+struct AdminGuard;
+
+impl Guard for AdminGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        match ctx.data_opt::<Caller>() {
+            Some(caller) if caller.admin => Ok(()),
+            _ => Err("Unauthorized: an admin token is required".into()),
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Cluster members, optionally only those with `role` or in `region`
+    async fn nodes(
+        &self,
+        ctx: &Context<'_>,
+        role: Option<Role>,
+        region: Option<String>,
+    ) -> async_graphql::Result<Vec<Node>> {
+        let nodes = ctx.data::<Backend>()?.nodes().await?;
+        Ok(nodes.into_iter()
+            .map(Node::from)
+            .filter(|node| role.is_none_or(|role| node.role == role))
+            .filter(|node| region.is_none() || node.region == region)
+            .collect())
+    }
+
+    async fn node(&self, ctx: &Context<'_>, id: u64) -> async_graphql::Result<Option<Node>> {
+        let nodes = ctx.data::<Backend>()?.nodes().await?;
+        Ok(nodes.into_iter().find(|node| node.id == NodeId(id)).map(Node::from))
+    }
+
+    /// Regions any member is in, sorted
+    async fn regions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<String>> {
+        let nodes = ctx.data::<Backend>()?.nodes().await?;
+        Ok(nodes.into_iter().filter_map(|node| node.region).collect::<BTreeSet<_>>().into_iter().collect())
+    }
+
+    async fn consensus(&self, ctx: &Context<'_>) -> async_graphql::Result<Consensus> {
+        Ok(Consensus(ctx.data::<Backend>()?.consensus_state().await?))
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    #[graphql(guard = "AdminGuard")]
+    async fn add_node(&self, ctx: &Context<'_>, input: AddNodeInput) -> async_graphql::Result<Node> {
+        info!("Adding node {} at {} as {:?}", input.id, input.address, input.kind);
+        Ok(ctx.data::<Backend>()?.add_node(input).await?.into())
+    }
+
+    #[graphql(guard = "AdminGuard")]
+    async fn remove_node(&self, ctx: &Context<'_>, id: u64) -> async_graphql::Result<bool> {
+        info!("Removing node {}", id);
+        Ok(ctx.data::<Backend>()?.remove_node(NodeId(id)).await?)
+    }
+}
+
+/// The coordinator's GraphQL schema
+pub type ClusterSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// GraphQL API server for Aurora Coordinator
+pub struct GraphQLAPI {
+    /// Server address
+    address: String,
+
+    /// API version
+    version: String,
+
+    /// Schema with the backend attached
+    schema: ClusterSchema,
+
+    /// Configuration
+    config: GraphQLConfig,
+}
+
+impl GraphQLAPI {
+    /// Create new GraphQL API server
+    pub fn new(address: &str, backend: Arc<dyn ClusterBackend>, config: GraphQLConfig) -> Self {
+        let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+            .data::<Backend>(backend)
+            .finish();
+        Self {
+            address: address.to_string(),
+            version: "v1".to_string(),
+            schema,
+            config,
+        }
+    }
+
+    pub fn schema(&self) -> &ClusterSchema {
+        &self.schema
+    }
+
+    /// Schema definition language, for clients and code generators
+    pub fn sdl(&self) -> String {
+        self.schema.sdl()
+    }
+
+    /// Caller presenting `authorization` (a `Bearer` header value, if any)
+    pub fn caller(&self, authorization: Option<&str>) -> Caller {
+        Caller::from_authorization(&self.config.admin_tokens, authorization)
+    }
+
+    /// Run one request as `caller`
+    pub async fn execute(&self, request: impl Into<async_graphql::Request>, caller: Caller) -> async_graphql::Response {
+        self.schema.execute(request.into().data(caller)).await
+    }
+
+    /// Start the GraphQL API server
+    pub async fn start(&self) -> Result<()> {
+        let (address, server) = self.bind()?;
+        info!("Starting GraphQL API server on {}", address);
+        server.await;
+        Ok(())
+    }
+
+    /// Bind the configured address, returning the bound address and the
+    /// server future to drive
+    pub fn bind(&self) -> Result<(SocketAddr, impl std::future::Future<Output = ()> + 'static)> {
+        let address: SocketAddr = self.address.parse().map_err(|_| Error::Config {
+            message: format!("Invalid GraphQL API address '{}'", self.address),
+            field: Some("address".to_string()),
+        })?;
+        warp::serve(self.routes()).try_bind_ephemeral(address).map_err(|e| Error::Network {
+            message: format!("Failed to bind GraphQL API: {}", e),
+            peer: Some(self.address.clone()),
+        })
+    }
+
+    /// `POST /api/{version}/graphql` with a JSON `{query, variables}` body
+    pub fn routes(&self) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+        let schema = self.schema.clone();
+        let admin_tokens = Arc::new(self.config.admin_tokens.clone());
+
+        warp::path("api")
+            .and(warp::path(self.version.clone()))
+            .and(warp::path("graphql"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json::<async_graphql::Request>())
+            .then(move |authorization: Option<String>, request: async_graphql::Request| {
+                let schema = schema.clone();
+                let admin_tokens = admin_tokens.clone();
+                async move {
+                    let caller = Caller::from_authorization(&admin_tokens, authorization.as_deref());
+                    let response = schema.execute(request.data(caller)).await;
+                    warp::reply::json(&response)
+                }
+            })
+    }
+}
+
+// UNIQUENESS Validation:
+// - [x] Topology, consensus and per-node metrics in one request
+// - [x] Nested selection: metrics and leader resolved only when asked for
+// - [x] Token-guarded membership mutations
@@ -0,0 +1,353 @@
+//! WebSocket API: UNIQUENESS Real-Time Cluster Events
+//!
+//! Pushes membership, leadership, term and health changes to subscribed
+//! clients as JSON text messages:
+//! - **Sources**: [`EventPublisher`] is a `MembershipManager` node callback
+//!   and a `HybridConsensus` leadership callback
+//! - **Filters**: `GET /api/v1/events?events=leadership,term` delivers only
+//!   those kinds; without `events` a client gets everything
+//! - **Backpressure**: every client reads from a bounded buffer. Publishers
+//!   never wait; a client that falls more than the buffer behind skips the
+//!   oldest events and receives `{"type":"lagged","missed":N}` in their place
+//!
+//! Every event carries a `sequence` that increases by one per published
+//! event, so a client can also see gaps that its filter did not cause.
+
+use crate::consensus::hybrid::LeadershipCallback;
+use crate::consensus::Term;
+use crate::error::{Error, Result};
+use crate::membership::NodeEventCallback;
+use crate::types::{ClusterMember, NodeId, NodeStatus};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+use warp::ws::{Message, WebSocket, Ws};
+use warp::{Filter, Rejection, Reply};
+
+/// WebSocket API configuration
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Events buffered per client before the oldest are dropped
+    pub client_buffer: usize,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self { client_buffer: 1024 }
+    }
+}
+
+/// A change in the cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClusterEvent {
+    NodeJoined { node_id: NodeId, address: String },
+    NodeFailed { node_id: NodeId },
+    NodeLeft { node_id: NodeId },
+    NodeRecovered { node_id: NodeId, address: String },
+    LeaderChanged { leader: Option<NodeId>, term: Term },
+    TermChanged { term: Term },
+    HealthChanged { node_id: NodeId, from: NodeStatus, to: NodeStatus },
+}
+
+impl ClusterEvent {
+    /// Kind a subscription filters on
+    pub fn kind(&self) -> EventKind {
+        match self {
+            ClusterEvent::NodeJoined { .. }
+            | ClusterEvent::NodeFailed { .. }
+            | ClusterEvent::NodeLeft { .. }
+            | ClusterEvent::NodeRecovered { .. } => EventKind::Membership,
+            ClusterEvent::LeaderChanged { .. } => EventKind::Leadership,
+            ClusterEvent::TermChanged { .. } => EventKind::Term,
+            ClusterEvent::HealthChanged { .. } => EventKind::Health,
+        }
+    }
+}
+
+/// Event kinds a client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Membership,
+    Leadership,
+    Term,
+    Health,
+}
+
+impl FromStr for EventKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "membership" => Ok(EventKind::Membership),
+            "leadership" => Ok(EventKind::Leadership),
+            "term" => Ok(EventKind::Term),
+            "health" => Ok(EventKind::Health),
+            other => Err(Error::Config {
+                message: format!("Unknown event kind '{}'", other),
+                field: Some("events".to_string()),
+            }),
+        }
+    }
+}
+
+/// Event kinds a subscription receives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    kinds: Vec<EventKind>,
+}
+
+impl EventFilter {
+    /// Every kind of event
+    pub fn all() -> Self {
+        Self { kinds: vec![EventKind::Membership, EventKind::Leadership, EventKind::Term, EventKind::Health] }
+    }
+
+    /// Only the listed kinds
+    pub fn only(kinds: &[EventKind]) -> Self {
+        Self { kinds: kinds.to_vec() }
+    }
+
+    /// Parse a comma-separated list such as `leadership,health`
+    pub fn parse(list: &str) -> Result<Self> {
+        let kinds = list.split(',')
+            .filter(|kind| !kind.trim().is_empty())
+            .map(EventKind::from_str)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { kinds })
+    }
+
+    pub fn matches(&self, event: &ClusterEvent) -> bool {
+        self.kinds.contains(&event.kind())
+    }
+}
+
+/// An event as sent to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedEvent {
+    pub sequence: u64,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: ClusterEvent,
+}
+
+/// What a subscription yields next
+#[derive(Debug, Clone)]
+pub enum Delivery {
+    Event(PublishedEvent),
+    /// This many events were dropped because the client fell behind
+    Lagged(u64),
+}
+
+impl Delivery {
+    /// JSON text message for the client
+    pub fn to_json(&self) -> String {
+        match self {
+            Delivery::Event(event) => serde_json::to_string(event).unwrap_or_default(),
+            Delivery::Lagged(missed) => serde_json::json!({ "type": "lagged", "missed": missed }).to_string(),
+        }
+    }
+}
+
+/// Publishes cluster events to every subscription
+///
+/// Register a clone with `MembershipManager::add_callback` and
+/// `HybridConsensus::add_leadership_callback`.
+#[derive(Clone)]
+pub struct EventPublisher {
+    sender: broadcast::Sender<PublishedEvent>,
+    sequence: Arc<AtomicU64>,
+}
+
+impl EventPublisher {
+    /// Publisher whose subscriptions each buffer `client_buffer` events
+    pub fn new(client_buffer: usize) -> Self {
+        let (sender, _) = broadcast::channel(client_buffer.max(1));
+        Self { sender, sequence: Arc::new(AtomicU64::new(0)) }
+    }
+
+    /// Publish an event; never waits for subscribers
+    pub fn publish(&self, event: ClusterEvent) {
+        let published = PublishedEvent {
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed) + 1,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event,
+        };
+        // An error only means nobody is subscribed
+        let _ = self.sender.send(published);
+    }
+
+    /// Receive events published from now on that pass `filter`
+    pub fn subscribe(&self, filter: EventFilter) -> Subscription {
+        Subscription { receiver: self.sender.subscribe(), filter }
+    }
+
+    /// Clients currently subscribed
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// One client's view of the event stream
+pub struct Subscription {
+    receiver: broadcast::Receiver<PublishedEvent>,
+    filter: EventFilter,
+}
+
+impl Subscription {
+    /// Next matching event or lag notice; `None` once the publisher is gone
+    pub async fn next(&mut self) -> Option<Delivery> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(published) if self.filter.matches(&published.event) => return Some(Delivery::Event(published)),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(missed)) => return Some(Delivery::Lagged(missed)),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeEventCallback for EventPublisher {
+    async fn on_node_join(&self, member: ClusterMember) {
+        self.publish(ClusterEvent::NodeJoined { node_id: member.node_id, address: member.address });
+    }
+
+    async fn on_node_leave(&self, node_id: NodeId) {
+        self.publish(ClusterEvent::NodeLeft { node_id });
+    }
+
+    async fn on_node_failure(&self, node_id: NodeId) {
+        self.publish(ClusterEvent::NodeFailed { node_id });
+    }
+
+    async fn on_node_recovery(&self, member: ClusterMember) {
+        self.publish(ClusterEvent::NodeRecovered { node_id: member.node_id, address: member.address });
+    }
+
+    async fn on_node_status_change(&self, node_id: NodeId, from: NodeStatus, to: NodeStatus) {
+        self.publish(ClusterEvent::HealthChanged { node_id, from, to });
+    }
+}
+
+#[async_trait::async_trait]
+impl LeadershipCallback for EventPublisher {
+    async fn on_leader_change(&self, leader: Option<NodeId>, term: Term) {
+        self.publish(ClusterEvent::LeaderChanged { leader, term });
+    }
+
+    async fn on_term_change(&self, term: Term) {
+        self.publish(ClusterEvent::TermChanged { term });
+    }
+}
+
+/// WebSocket API server for Aurora Coordinator
+pub struct WebSocketAPI {
+    /// Server address
+    address: String,
+
+    /// API version
+    version: String,
+
+    /// Source of every client's events
+    publisher: EventPublisher,
+}
+
+impl WebSocketAPI {
+    /// Create new WebSocket API server
+    pub fn new(address: &str, config: WebSocketConfig) -> Self {
+        Self {
+            address: address.to_string(),
+            version: "v1".to_string(),
+            publisher: EventPublisher::new(config.client_buffer),
+        }
+    }
+
+    /// Publisher to register with membership and consensus
+    pub fn publisher(&self) -> EventPublisher {
+        self.publisher.clone()
+    }
+
+    /// Start the WebSocket API server
+    pub async fn start(&self) -> Result<()> {
+        let (address, server) = self.bind()?;
+        info!("Starting WebSocket API server on {}", address);
+        server.await;
+        Ok(())
+    }
+
+    /// Bind the configured address, returning the bound address and the
+    /// server future to drive
+    pub fn bind(&self) -> Result<(SocketAddr, impl std::future::Future<Output = ()> + 'static)> {
+        let address: SocketAddr = self.address.parse().map_err(|_| Error::Config {
+            message: format!("Invalid WebSocket API address '{}'", self.address),
+            field: Some("address".to_string()),
+        })?;
+        warp::serve(self.routes()).try_bind_ephemeral(address).map_err(|e| Error::Network {
+            message: format!("Failed to bind WebSocket API: {}", e),
+            peer: Some(self.address.clone()),
+        })
+    }
+
+    /// `GET /api/{version}/events`, upgraded to a WebSocket
+    pub fn routes(&self) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+        let publisher = self.publisher.clone();
+
+        warp::path("api")
+            .and(warp::path(self.version.clone()))
+            .and(warp::path("events"))
+            .and(warp::path::end())
+            .and(warp::ws())
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |ws: Ws, query: HashMap<String, String>| -> Box<dyn Reply> {
+                let filter = match query.get("events").map(|list| EventFilter::parse(list)) {
+                    None => EventFilter::all(),
+                    Some(Ok(filter)) => filter,
+                    Some(Err(e)) => {
+                        return Box::new(warp::reply::with_status(e.to_string(), warp::http::StatusCode::BAD_REQUEST));
+                    }
+                };
+                let subscription = publisher.subscribe(filter);
+                Box::new(ws.on_upgrade(move |socket| stream_events(socket, subscription)))
+            })
+    }
+}
+
+/// Forward a subscription to one client until either side goes away
+async fn stream_events(socket: WebSocket, mut subscription: Subscription) {
+    let (mut outgoing, mut incoming) = socket.split();
+
+    loop {
+        tokio::select! {
+            delivery = subscription.next() => {
+                let Some(delivery) = delivery else { break };
+                if let Delivery::Lagged(missed) = delivery {
+                    debug!("WebSocket client lagged, dropped {} events", missed);
+                }
+                if outgoing.send(Message::text(delivery.to_json())).await.is_err() {
+                    break;
+                }
+            }
+            message = incoming.next() => match message {
+                // Clients have nothing to say; pings are answered by warp
+                Some(Ok(message)) if !message.is_close() => continue,
+                _ => break,
+            },
+        }
+    }
+
+    let _ = outgoing.close().await;
+}
+
+// UNIQUENESS Validation:
+// - [x] Membership, leadership, term and health events pushed as they happen
+// - [x] Per-client subscription filters
+// - [x] Bounded per-client buffers; slow clients lag instead of blocking publishers
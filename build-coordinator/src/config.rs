@@ -124,6 +124,9 @@ pub struct ConsensusConfig {
 
     /// Peer nodes for consensus cluster
     pub peer_nodes: Vec<crate::types::NodeId>,
+
+    /// Behavior while the cluster has lost quorum
+    pub quorum_loss: QuorumLossConfig,
 }
 
 impl Default for ConsensusConfig {
@@ -141,6 +144,33 @@ impl Default for ConsensusConfig {
             min_stable_term: 3,
             election_timeout_variance_ms: 50,
             peer_nodes: vec![], // Will be populated at runtime
+            quorum_loss: QuorumLossConfig::default(),
+        }
+    }
+}
+
+/// Degraded-mode behavior when a node cannot reach a majority
+///
+/// `leader_lease` must be shorter than `election_timeout_min`, so that no new
+/// leader can be elected while a partitioned former leader still serves reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumLossConfig {
+    /// Let a former leader within its lease serve reads flagged stale
+    pub serve_stale_reads: bool,
+
+    /// How long a leader may assume leadership after last confirming a majority
+    pub leader_lease: Duration,
+
+    /// A peer not heard from for this long counts as unreachable
+    pub peer_timeout: Duration,
+}
+
+impl Default for QuorumLossConfig {
+    fn default() -> Self {
+        Self {
+            serve_stale_reads: false,
+            leader_lease: Duration::from_millis(120),
+            peer_timeout: Duration::from_millis(100), // Two heartbeat intervals
         }
     }
 }
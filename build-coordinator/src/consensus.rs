@@ -8,9 +8,11 @@ pub mod raft;
 pub mod paxos;
 pub mod state_machine;
 pub mod log_manager;
+pub mod quorum;
 
 pub use hybrid::HybridConsensus;
 pub use raft::{RaftConsensus, RaftNode};
+pub use quorum::{QuorumHealth, QuorumMonitor, ReadConsistency, ReadResult};
 pub use paxos::PaxosConsensus;
 
 use crate::config::ConsensusConfig;
@@ -0,0 +1,227 @@
+//! Commit Latency Breakdown: Where a Commit Spends Its Time
+//!
+//! Splits the latency of every committed operation into the stages of the
+//! commit path, so a slow commit can be pinned on replication, disk or apply:
+//! - **Propose Queue**: Waiting for the log before the entry is appended
+//! - **Replication**: Round trip to a quorum until the entry commits
+//! - **Fsync**: Forcing the log to disk, when the log is durable
+//! - **Apply**: Applying the committed entry to the state machine
+//!
+//! The commit path marks each stage as it ends; a stage lasts from the
+//! previous mark, so the stages of an operation add up to its total commit
+//! latency. Each stage and the total feed an HDR histogram, and the most
+//! recent breakdowns are kept for inspection by log index.
+
+use crate::consensus::LogIndex;
+use crate::monitoring::hdr_histograms::{HDRConfig, HDRHistogram, HDRStats};
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Committed operations whose breakdown is kept for lookup
+const RECENT_COMMITS: usize = 1024;
+
+/// One stage of the commit path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommitStage {
+    ProposeQueue,
+    Replication,
+    Fsync,
+    Apply,
+}
+
+impl CommitStage {
+    /// Every stage, in commit-path order
+    pub const ALL: [CommitStage; 4] = [Self::ProposeQueue, Self::Replication, Self::Fsync, Self::Apply];
+
+    fn slot(self) -> usize {
+        self as usize
+    }
+}
+
+/// Where the time of one committed operation went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitBreakdown {
+    pub index: LogIndex,
+    pub propose_queue: Duration,
+    pub replication: Duration,
+    /// `None` when the log was not forced to disk
+    pub fsync: Option<Duration>,
+    pub apply: Duration,
+    /// From the proposal to the end of apply
+    pub total: Duration,
+}
+
+impl CommitBreakdown {
+    /// Time spent in `stage`
+    pub fn stage(&self, stage: CommitStage) -> Duration {
+        match stage {
+            CommitStage::ProposeQueue => self.propose_queue,
+            CommitStage::Replication => self.replication,
+            CommitStage::Fsync => self.fsync.unwrap_or_default(),
+            CommitStage::Apply => self.apply,
+        }
+    }
+}
+
+/// Commit latency of every operation recorded so far, per stage
+#[derive(Debug, Clone)]
+pub struct CommitLatencySummary {
+    /// Operations committed and applied
+    pub operations: u64,
+    /// Latency histogram statistics of each stage, in commit-path order;
+    /// fsync counts only the operations that were synced
+    pub stages: Vec<(CommitStage, HDRStats)>,
+    /// Total commit latency
+    pub total: HDRStats,
+}
+
+impl CommitLatencySummary {
+    /// Statistics of `stage`
+    pub fn stage(&self, stage: CommitStage) -> &HDRStats {
+        &self.stages[stage.slot()].1
+    }
+
+    /// Stage taking the most time summed over all operations, which is
+    /// what commits are bound by
+    pub fn dominant_stage(&self) -> Option<CommitStage> {
+        self.stages.iter()
+            .filter(|(_, stats)| stats.count > 0)
+            .map(|(stage, stats)| (*stage, stats.mean.unwrap_or(0.0) * stats.count as f64))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(stage, _)| stage)
+    }
+}
+
+/// An operation between its proposal and its apply
+struct PendingCommit {
+    proposed_at: Instant,
+    last_mark: Instant,
+    stages: [Option<Duration>; 4],
+}
+
+/// Records the commit path stages of each operation
+pub struct CommitLatencyTracker {
+    pending: Mutex<HashMap<LogIndex, PendingCommit>>,
+    recent: Mutex<VecDeque<CommitBreakdown>>,
+    histograms: Mutex<(Vec<HDRHistogram>, HDRHistogram)>,
+}
+
+impl CommitLatencyTracker {
+    /// Create a tracker whose histograms use `config`
+    pub fn new(config: HDRConfig) -> Self {
+        let stages = CommitStage::ALL.iter().map(|_| HDRHistogram::new(config.clone())).collect();
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_COMMITS)),
+            histograms: Mutex::new((stages, HDRHistogram::new(config))),
+        }
+    }
+
+    /// The entry proposed at `proposed_at` was appended at `index`; the wait
+    /// for the log is its propose-queue stage
+    pub fn proposed(&self, index: LogIndex, proposed_at: Instant) {
+        let now = Instant::now();
+        let mut stages = [None; 4];
+        stages[CommitStage::ProposeQueue.slot()] = Some(now - proposed_at);
+        self.pending.lock().insert(index, PendingCommit { proposed_at, last_mark: now, stages });
+    }
+
+    /// A quorum has `index`
+    pub fn replicated(&self, index: LogIndex) {
+        self.mark(index, CommitStage::Replication);
+    }
+
+    /// The log holding `index` was forced to disk
+    pub fn synced(&self, index: LogIndex) {
+        self.mark(index, CommitStage::Fsync);
+    }
+
+    /// `index` was applied to the state machine, completing its commit
+    pub fn applied(&self, index: LogIndex) {
+        let Some(mut pending) = self.pending.lock().remove(&index) else {
+            return;
+        };
+        let now = Instant::now();
+        pending.stages[CommitStage::Apply.slot()] = Some(now - pending.last_mark);
+        let stage = |stage: CommitStage| pending.stages[stage.slot()];
+        let breakdown = CommitBreakdown {
+            index,
+            propose_queue: stage(CommitStage::ProposeQueue).unwrap_or_default(),
+            replication: stage(CommitStage::Replication).unwrap_or_default(),
+            fsync: stage(CommitStage::Fsync),
+            apply: stage(CommitStage::Apply).unwrap_or_default(),
+            total: now - pending.proposed_at,
+        };
+
+        {
+            let mut histograms = self.histograms.lock();
+            let (stages, total) = &mut *histograms;
+            for (slot, duration) in pending.stages.iter().enumerate() {
+                if let Some(duration) = duration {
+                    record(&mut stages[slot], *duration);
+                }
+            }
+            record(total, breakdown.total);
+        }
+
+        let mut recent = self.recent.lock();
+        if recent.len() == RECENT_COMMITS {
+            recent.pop_front();
+        }
+        recent.push_back(breakdown);
+    }
+
+    /// Forget `index`, which will not commit
+    pub fn abandon(&self, index: LogIndex) {
+        self.pending.lock().remove(&index);
+    }
+
+    /// Breakdown of the committed operation at `index`, if still kept
+    pub fn breakdown(&self, index: LogIndex) -> Option<CommitBreakdown> {
+        self.recent.lock().iter().rev().find(|breakdown| breakdown.index == index).cloned()
+    }
+
+    /// Breakdowns of the most recently committed operations, oldest first
+    pub fn recent(&self) -> Vec<CommitBreakdown> {
+        self.recent.lock().iter().cloned().collect()
+    }
+
+    /// Latency statistics of every operation committed so far
+    pub fn summary(&self) -> CommitLatencySummary {
+        let histograms = self.histograms.lock();
+        let (stages, total) = &*histograms;
+        CommitLatencySummary {
+            operations: total.count(),
+            stages: CommitStage::ALL.iter().map(|stage| (*stage, stages[stage.slot()].stats())).collect(),
+            total: total.stats(),
+        }
+    }
+
+    /// Close the stage of `index` that ends now
+    fn mark(&self, index: LogIndex, stage: CommitStage) {
+        if let Some(pending) = self.pending.lock().get_mut(&index) {
+            let now = Instant::now();
+            let elapsed = now - pending.last_mark;
+            let slot = &mut pending.stages[stage.slot()];
+            *slot = Some(slot.unwrap_or_default() + elapsed);
+            pending.last_mark = now;
+        }
+    }
+}
+
+impl Default for CommitLatencyTracker {
+    fn default() -> Self {
+        Self::new(HDRConfig::default())
+    }
+}
+
+/// Record `duration`, at least the histogram's 1ns floor
+fn record(histogram: &mut HDRHistogram, duration: Duration) {
+    let nanos = (duration.as_nanos() as u64).max(1);
+    if let Err(e) = histogram.record(nanos) {
+        warn!("Dropped commit latency sample of {}ns: {}", nanos, e);
+    }
+}
@@ -71,6 +71,21 @@ pub struct HybridConsensus {
 
     /// Performance metrics
     metrics: Arc<RwLock<HybridMetrics>>,
+
+    /// Leader and term as last reported to callbacks
+    observed_leadership: Arc<RwLock<(Option<NodeId>, Term)>>,
+
+    /// Leadership and term change callbacks
+    leadership_callbacks: Arc<RwLock<Vec<Box<dyn LeadershipCallback>>>>,
+}
+
+/// Callback trait for leadership events
+#[async_trait::async_trait]
+pub trait LeadershipCallback: Send + Sync {
+    /// A different node leads, or the same node was elected for a new term;
+    /// `None` while no leader is known
+    async fn on_leader_change(&self, leader: Option<NodeId>, term: Term);
+    async fn on_term_change(&self, term: Term);
 }
 
 /// Performance metrics for hybrid consensus
@@ -114,6 +129,8 @@ impl HybridConsensus {
             config,
             mode_change_notify: Arc::new(Notify::new()),
             metrics: Arc::new(RwLock::new(HybridMetrics::default())),
+            observed_leadership: Arc::new(RwLock::new((None, 0))),
+            leadership_callbacks: Arc::new(RwLock::new(Vec::new())),
         })
     }
 
@@ -361,10 +378,33 @@ impl HybridConsensus {
         Ok(())
     }
 
+    /// Add a leadership event callback
+    pub async fn add_leadership_callback(&self, callback: Box<dyn LeadershipCallback>) {
+        let mut callbacks = self.leadership_callbacks.write().await;
+        callbacks.push(callback);
+    }
+
     /// Handle leadership change
-    pub async fn handle_leadership_change(&self, new_leader: NodeId) -> Result<()> {
-        info!("Handling leadership change to node {}", new_leader);
-        // Update internal state for new leader
+    ///
+    /// Safe to call with an unchanged leader and term; callbacks only hear
+    /// about actual changes.
+    pub async fn handle_leadership_change(&self, new_leader: Option<NodeId>, term: Term) -> Result<()> {
+        let (previous_leader, previous_term) = {
+            let mut observed = self.observed_leadership.write().await;
+            std::mem::replace(&mut *observed, (new_leader, term))
+        };
+        if (previous_leader, previous_term) == (new_leader, term) {
+            return Ok(());
+        }
+        info!("Handling leadership change to {:?} in term {}", new_leader, term);
+
+        let callbacks = self.leadership_callbacks.read().await;
+        for callback in callbacks.iter() {
+            if term != previous_term {
+                callback.on_term_change(term).await;
+            }
+            callback.on_leader_change(new_leader, term).await;
+        }
         Ok(())
     }
 
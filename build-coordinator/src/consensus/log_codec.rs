@@ -0,0 +1,251 @@
+//! Log Codecs: Pluggable Encoding for Consensus Log Entries
+//!
+//! Every encoded entry starts with a small header naming the format and the
+//! codec version that wrote it, so any node can read an entry whatever codec
+//! produced it:
+//! - **Bincode**: Compact binary encoding, the default
+//! - **JSON**: Self-describing text, readable with ordinary tools when
+//!   debugging a log
+//!
+//! Nodes exchange [`CodecCapabilities`] when they connect and agree on a
+//! format and version both understand, which lets a cluster run mixed
+//! versions while an upgrade rolls through.
+
+use crate::error::{Error, Result};
+use crate::types::LogEntry;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Newest codec version this build reads and writes
+pub const LOG_CODEC_VERSION: u16 = 1;
+
+/// Oldest codec version this build still reads and writes
+pub const MIN_LOG_CODEC_VERSION: u16 = 1;
+
+/// Header bytes: magic, format tag, version (little-endian u16)
+const HEADER_LEN: usize = 4;
+const MAGIC: u8 = 0xA7;
+
+/// Encoding of log entry bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LogFormat {
+    /// Compact binary encoding
+    Bincode,
+    /// Self-describing JSON
+    Json,
+}
+
+impl LogFormat {
+    /// Every format this build understands, most compact first
+    pub const ALL: [LogFormat; 2] = [LogFormat::Bincode, LogFormat::Json];
+
+    /// Codec that writes this format
+    pub fn codec(self) -> Arc<dyn LogCodec> {
+        match self {
+            LogFormat::Bincode => Arc::new(BincodeCodec),
+            LogFormat::Json => Arc::new(JsonCodec),
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            LogFormat::Bincode => 1,
+            LogFormat::Json => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(LogFormat::Bincode),
+            2 => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LogFormat::Bincode => "bincode",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Bincode
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Encoding for consensus log entries
+pub trait LogCodec: Send + Sync + fmt::Debug {
+    /// Format this codec writes
+    fn format(&self) -> LogFormat;
+
+    /// Encode an entry body, without the header
+    fn encode_body(&self, entry: &LogEntry) -> Result<Vec<u8>>;
+
+    /// Decode an entry body written by this codec
+    fn decode_body(&self, body: &[u8]) -> Result<LogEntry>;
+
+    /// Encode an entry with a header for `version`
+    fn encode_with_version(&self, entry: &LogEntry, version: u16) -> Result<Vec<u8>> {
+        check_version(self.format(), version)?;
+        let body = self.encode_body(entry)?;
+        let mut encoded = Vec::with_capacity(HEADER_LEN + body.len());
+        encoded.push(MAGIC);
+        encoded.push(self.format().tag());
+        encoded.extend_from_slice(&version.to_le_bytes());
+        encoded.extend_from_slice(&body);
+        Ok(encoded)
+    }
+
+    /// Encode an entry with a header for the current version
+    fn encode(&self, entry: &LogEntry) -> Result<Vec<u8>> {
+        self.encode_with_version(entry, LOG_CODEC_VERSION)
+    }
+}
+
+/// Compact binary codec
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeCodec;
+
+impl LogCodec for BincodeCodec {
+    fn format(&self) -> LogFormat {
+        LogFormat::Bincode
+    }
+
+    fn encode_body(&self, entry: &LogEntry) -> Result<Vec<u8>> {
+        bincode::serialize(entry).map_err(|e| serialization_error(LogFormat::Bincode, format!("Failed to encode log entry: {}", e)))
+    }
+
+    fn decode_body(&self, body: &[u8]) -> Result<LogEntry> {
+        bincode::deserialize(body).map_err(|e| serialization_error(LogFormat::Bincode, format!("Failed to decode log entry: {}", e)))
+    }
+}
+
+/// Self-describing JSON codec, for inspecting logs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl LogCodec for JsonCodec {
+    fn format(&self) -> LogFormat {
+        LogFormat::Json
+    }
+
+    fn encode_body(&self, entry: &LogEntry) -> Result<Vec<u8>> {
+        serde_json::to_vec(entry).map_err(|e| serialization_error(LogFormat::Json, format!("Failed to encode log entry: {}", e)))
+    }
+
+    fn decode_body(&self, body: &[u8]) -> Result<LogEntry> {
+        serde_json::from_slice(body).map_err(|e| serialization_error(LogFormat::Json, format!("Failed to decode log entry: {}", e)))
+    }
+}
+
+/// Format and version named by an encoded entry's header
+pub fn peek_header(bytes: &[u8]) -> Result<(LogFormat, u16)> {
+    if bytes.len() < HEADER_LEN || bytes[0] != MAGIC {
+        return Err(Error::Serialization {
+            message: "Log entry has no codec header".into(),
+            format: "unknown".into(),
+        });
+    }
+    let format = LogFormat::from_tag(bytes[1]).ok_or_else(|| Error::Serialization {
+        message: format!("Unknown log format tag {}", bytes[1]),
+        format: "unknown".into(),
+    })?;
+    Ok((format, u16::from_le_bytes([bytes[2], bytes[3]])))
+}
+
+/// Decode an entry written by any codec and version this build understands
+pub fn decode(bytes: &[u8]) -> Result<LogEntry> {
+    let (format, version) = peek_header(bytes)?;
+    check_version(format, version)?;
+    format.codec().decode_body(&bytes[HEADER_LEN..])
+}
+
+/// Formats and versions a node can read and write, exchanged on connect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodecCapabilities {
+    /// Supported formats, most preferred first
+    pub formats: Vec<LogFormat>,
+    pub min_version: u16,
+    pub max_version: u16,
+}
+
+/// Format and version two nodes agreed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedCodec {
+    pub format: LogFormat,
+    pub version: u16,
+}
+
+impl NegotiatedCodec {
+    /// Encode an entry the way the peer expects it
+    pub fn encode(&self, entry: &LogEntry) -> Result<Vec<u8>> {
+        self.format.codec().encode_with_version(entry, self.version)
+    }
+}
+
+impl CodecCapabilities {
+    /// Capabilities of this build, preferring `preferred`
+    pub fn local(preferred: LogFormat) -> Self {
+        let mut formats = vec![preferred];
+        formats.extend(LogFormat::ALL.iter().copied().filter(|&format| format != preferred));
+        Self {
+            formats,
+            min_version: MIN_LOG_CODEC_VERSION,
+            max_version: LOG_CODEC_VERSION,
+        }
+    }
+
+    /// Agree on the newest version both sides support and the first of our
+    /// preferred formats the peer also supports
+    pub fn negotiate(&self, remote: &CodecCapabilities) -> Result<NegotiatedCodec> {
+        let version = self.max_version.min(remote.max_version);
+        if version < self.min_version.max(remote.min_version) {
+            return Err(Error::Config {
+                message: format!(
+                    "No common log codec version: local supports {}..={}, peer {}..={}",
+                    self.min_version, self.max_version, remote.min_version, remote.max_version
+                ),
+                field: Some("log_format".into()),
+            });
+        }
+
+        let format = self.formats.iter()
+            .copied()
+            .find(|format| remote.formats.contains(format))
+            .ok_or_else(|| Error::Config {
+                message: format!("No common log format: local supports {:?}, peer {:?}", self.formats, remote.formats),
+                field: Some("log_format".into()),
+            })?;
+
+        Ok(NegotiatedCodec { format, version })
+    }
+}
+
+fn check_version(format: LogFormat, version: u16) -> Result<()> {
+    if (MIN_LOG_CODEC_VERSION..=LOG_CODEC_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(serialization_error(format, format!(
+            "Unsupported log codec version {} (supported {}..={})",
+            version, MIN_LOG_CODEC_VERSION, LOG_CODEC_VERSION
+        )))
+    }
+}
+
+fn serialization_error(format: LogFormat, message: String) -> Error {
+    Error::Serialization {
+        message,
+        format: format.name().into(),
+    }
+}
@@ -6,6 +6,7 @@
 //! - **Snapshot Integration**: Fast recovery
 //! - **Memory-Mapped I/O**: High-performance log access
 
+use crate::consensus::log_codec::{self, LogCodec, LogFormat};
 use crate::error::{Error, Result};
 use crate::types::{LogEntry, LogIndex};
 
@@ -37,6 +38,9 @@ pub struct LogManager {
     /// File handle for log
     log_file: Arc<RwLock<Option<File>>>,
 
+    /// Codec for newly written entries
+    codec: Arc<dyn LogCodec>,
+
     /// Configuration
     config: LogConfig,
 }
@@ -48,6 +52,8 @@ pub struct LogConfig {
     pub log_path: String,
     pub snapshot_path: String,
     pub sync_interval: u64, // Sync to disk every N entries
+    /// Encoding for new entries; entries in any format are readable
+    pub format: LogFormat,
 }
 
 impl Default for LogConfig {
@@ -57,6 +63,7 @@ impl Default for LogConfig {
             log_path: "coordinator.log".to_string(),
             snapshot_path: "coordinator.snapshot".to_string(),
             sync_interval: 100,
+            format: LogFormat::Bincode,
         }
     }
 }
@@ -77,6 +84,7 @@ impl LogManager {
             max_memory_entries: config.max_memory_entries,
             next_index,
             log_file: Arc::new(RwLock::new(None)),
+            codec: config.format.codec(),
             config,
         };
 
@@ -134,6 +142,14 @@ impl LogManager {
         Ok(index)
     }
 
+    /// Append an entry and sync it to disk before returning, for records
+    /// that must survive a crash the moment they are acknowledged
+    pub async fn append_durable(&self, entry: LogEntry) -> Result<LogIndex> {
+        let index = self.append(entry).await?;
+        self.sync_to_disk().await?;
+        Ok(index)
+    }
+
     /// Get entry at specific index
     pub async fn get(&self, index: LogIndex) -> Result<Option<LogEntry>> {
         let mem_log = self.memory_log.read().await;
@@ -222,8 +238,7 @@ impl LogManager {
     /// Write entry to disk
     async fn write_entry_to_disk(&self, entry: &LogEntry) -> Result<()> {
         if let Some(ref mut file) = *self.log_file.write().await {
-            let serialized = bincode::serialize(entry)
-                .map_err(|e| Error::Serialization(format!("Failed to serialize entry: {}", e)))?;
+            let serialized = self.codec.encode(entry)?;
 
             let size = serialized.len() as u32;
             file.write_all(&size.to_le_bytes())?;
@@ -249,8 +264,7 @@ impl LogManager {
             let mut entry_data = vec![0u8; size as usize];
             reader.read_exact(&mut entry_data)?;
 
-            let entry: LogEntry = bincode::deserialize(&entry_data)
-                .map_err(|e| Error::Serialization(format!("Failed to deserialize entry: {}", e)))?;
+            let entry = log_codec::decode(&entry_data)?;
 
             if entry.index == index {
                 return Ok(Some(entry));
@@ -330,7 +344,7 @@ impl LogManager {
                 break; // End of file or corruption
             }
 
-            match bincode::deserialize::<LogEntry>(&entry_data) {
+            match log_codec::decode(&entry_data) {
                 Ok(entry) => {
                     recovered_log.push_back(entry.clone());
                     next_index = entry.index + 1;
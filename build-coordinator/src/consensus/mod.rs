@@ -10,9 +10,15 @@ pub mod paxos;
 pub mod hybrid;
 pub mod state_machine;
 pub mod log_manager;
+pub mod quorum;
+pub mod log_codec;
+pub mod commit_latency;
 
-pub use hybrid::HybridConsensus;
+pub use hybrid::{HybridConsensus, LeadershipCallback};
 pub use raft::{RaftConsensus, RaftNode};
+pub use log_codec::{CodecCapabilities, LogCodec, LogFormat, NegotiatedCodec};
+pub use commit_latency::{CommitBreakdown, CommitLatencySummary, CommitLatencyTracker, CommitStage};
+pub use quorum::{QuorumHealth, QuorumMonitor, ReadConsistency, ReadResult};
 pub use paxos::{PaxosConsensus, PaxosInstance};
 pub use state_machine::StateMachine;
 pub use log_manager::LogManager;
@@ -7,6 +7,8 @@
 //! - **Optimizations**: Pre-vote, leadership transfer, etc.
 
 use crate::config::ConsensusConfig;
+use crate::consensus::commit_latency::CommitLatencyTracker;
+use crate::consensus::quorum::{QuorumHealth, QuorumMonitor, ReadConsistency, ReadResult};
 use crate::error::{Error, Result};
 use crate::types::{LogEntry, LogIndex, NodeId, Term};
 
@@ -74,6 +76,12 @@ pub struct RaftConsensus {
 
     /// State machine for applying entries
     state_machine: Arc<crate::consensus::state_machine::StateMachine>,
+
+    /// Peer reachability and quorum-loss degraded mode
+    quorum: Arc<RwLock<QuorumMonitor>>,
+
+    /// Per-stage latency of each committed proposal
+    commit_latency: Arc<CommitLatencyTracker>,
 }
 
 /// Raft node state
@@ -103,6 +111,7 @@ impl RaftConsensus {
         }
 
         let election_timeout = Instant::now() + Self::random_election_timeout(config);
+        let quorum = QuorumMonitor::new(config.quorum_loss.clone(), &peers);
 
         Ok(Self {
             node_id,
@@ -120,6 +129,8 @@ impl RaftConsensus {
             config: config.clone(),
             shutdown_notify: Arc::new(Notify::new()),
             state_machine,
+            quorum: Arc::new(RwLock::new(quorum)),
+            commit_latency: Arc::new(CommitLatencyTracker::default()),
         })
     }
 
@@ -143,23 +154,61 @@ impl RaftConsensus {
 
     /// Propose a new log entry
     pub async fn propose(&self, entry: LogEntry) -> Result<LogIndex> {
+        let proposed_at = Instant::now();
+
         // Only leader can accept proposals
         if *self.role.read().await != RaftRole::Leader {
             return Err(Error::Consensus("Not the leader".into()));
         }
 
+        // A leader that lost quorum could never commit the entry
+        self.quorum.write().await.check_write(Instant::now())?;
+
         let mut log = self.log.write().await;
         let index = log.len() as LogIndex;
         log.push(entry);
+        self.commit_latency.proposed(index, proposed_at);
 
         debug!("Proposed entry at index {}", index);
 
         // Start replication to followers
-        self.replicate_log().await?;
+        if let Err(e) = self.replicate_log().await {
+            self.commit_latency.abandon(index);
+            return Err(e);
+        }
+        self.commit_latency.replicated(index);
 
         Ok(index)
     }
 
+    /// Read a key, honoring the quorum-loss policy
+    ///
+    /// With quorum the read is served by the leader as linearizable. Without
+    /// quorum it is refused, or served with `stale: true` by a former leader
+    /// within its lease when `quorum_loss.serve_stale_reads` is enabled.
+    pub async fn read(&self, key: &str) -> Result<ReadResult> {
+        let consistency = self.quorum.write().await.check_read(Instant::now())?;
+        Ok(ReadResult {
+            value: self.state_machine.query(key).await,
+            stale: consistency == ReadConsistency::Stale,
+        })
+    }
+
+    /// Record a response from a peer (vote, heartbeat or append ack)
+    pub async fn record_peer_ack(&self, peer: NodeId) {
+        self.quorum.write().await.record_peer_ack(peer, Instant::now());
+    }
+
+    /// Quorum health, `QuorumLost` while a majority is unreachable
+    pub async fn quorum_health(&self) -> QuorumHealth {
+        self.quorum.write().await.health(Instant::now())
+    }
+
+    /// Where the time of committed proposals went, per commit stage
+    pub fn commit_latency(&self) -> Arc<CommitLatencyTracker> {
+        Arc::clone(&self.commit_latency)
+    }
+
     /// Get current leader
     pub async fn current_leader(&self) -> Option<NodeId> {
         // In Raft, we need to track who the current leader is
@@ -211,6 +260,7 @@ impl RaftConsensus {
         let election_timeout = Arc::clone(&self.election_timeout);
         let current_term = Arc::clone(&self.current_term);
         let voted_for = Arc::clone(&self.voted_for);
+        let quorum = Arc::clone(&self.quorum);
         let shutdown_notify = Arc::clone(&self.shutdown_notify);
         let config = self.config.clone();
 
@@ -228,6 +278,7 @@ impl RaftConsensus {
                                 Arc::clone(&current_term),
                                 Arc::clone(&voted_for),
                                 Arc::clone(&election_timeout),
+                                Arc::clone(&quorum),
                                 &config,
                             ).await;
                         }
@@ -247,6 +298,7 @@ impl RaftConsensus {
         let last_applied = Arc::clone(&self.last_applied);
         let state_machine = Arc::clone(&self.state_machine);
         let shutdown_notify = Arc::clone(&self.shutdown_notify);
+        let commit_latency = Arc::clone(&self.commit_latency);
 
         tokio::spawn(async move {
             loop {
@@ -265,6 +317,7 @@ impl RaftConsensus {
                                     warn!("Failed to apply log entry {}: {}", next_idx, e);
                                 } else {
                                     *last_applied_val = next_idx;
+                                    commit_latency.applied(next_idx);
                                     debug!("Applied log entry {}", next_idx);
                                 }
                             }
@@ -284,6 +337,7 @@ impl RaftConsensus {
         current_term: Arc<RwLock<Term>>,
         voted_for: Arc<RwLock<Option<NodeId>>>,
         election_timeout: Arc<RwLock<Instant>>,
+        quorum: Arc<RwLock<QuorumMonitor>>,
         config: &ConsensusConfig,
     ) {
         // Increment term
@@ -295,6 +349,7 @@ impl RaftConsensus {
 
         // Become candidate
         *role.write().await = RaftRole::Candidate;
+        quorum.write().await.set_leader(false, Instant::now());
         *voted_for.write().await = Some(0); // Vote for self (placeholder ID)
         *election_timeout.write().await = Instant::now() + Self::random_election_timeout(config);
 
@@ -307,6 +362,7 @@ impl RaftConsensus {
 
         // Become leader if we got majority votes
         *role.write().await = RaftRole::Leader;
+        quorum.write().await.set_leader(true, Instant::now());
         info!("Became leader for term {}", new_term);
     }
 
@@ -329,3 +385,4 @@ impl RaftConsensus {
 // - [x] Log replication framework
 // - [x] State machine application
 // - [x] Memory-safe concurrent operations
+// - [x] Read-only degraded mode on quorum loss
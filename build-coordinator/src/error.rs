@@ -15,6 +15,13 @@ pub enum Error {
         operation: String,
     },
     
+    /// Quorum lost: writes (and unflagged reads) are refused
+    #[error("Quorum lost: {message} (for {since:?})")]
+    QuorumLost {
+        message: String,
+        since: std::time::Duration,
+    },
+    
     /// Network communication errors
     #[error("Network error: {message}")]
     Network {
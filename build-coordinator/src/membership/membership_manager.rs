@@ -84,6 +84,9 @@ pub trait NodeEventCallback: Send + Sync {
     async fn on_node_leave(&self, node_id: NodeId);
     async fn on_node_failure(&self, node_id: NodeId);
     async fn on_node_recovery(&self, member: ClusterMember);
+
+    /// A member's health status moved from `from` to `to`
+    async fn on_node_status_change(&self, _node_id: NodeId, _from: NodeStatus, _to: NodeStatus) {}
 }
 
 impl MembershipManager {
@@ -199,6 +202,40 @@ impl MembershipManager {
             .collect()
     }
 
+    /// Publish a key of this node's metadata (e.g. `region`, `capacity`)
+    /// to the cluster through gossip
+    pub async fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        self.swim.set_local_metadata(key, value).await
+    }
+
+    /// Withdraw a key of this node's metadata
+    pub async fn remove_metadata(&self, key: &str) {
+        self.swim.remove_local_metadata(key).await;
+    }
+
+    /// Metadata a node has published, as far as gossip has delivered it here
+    pub async fn node_metadata(&self, node_id: NodeId) -> Option<HashMap<String, String>> {
+        self.swim.node_metadata(node_id).await
+    }
+
+    /// Healthy members whose metadata carries every label in `labels`,
+    /// most spare `capacity` first
+    ///
+    /// Used to steer traffic to a region and to restrict scheduling to
+    /// suitably labeled nodes. Members with no `capacity` sort last.
+    pub async fn healthy_members_matching(&self, labels: &[(&str, &str)]) -> Vec<ClusterMember> {
+        let mut matching = Vec::new();
+        for member in self.healthy_members().await {
+            let metadata = self.node_metadata(member.node_id).await.unwrap_or_default();
+            if labels.iter().all(|(key, value)| metadata.get(*key).map(String::as_str) == Some(*value)) {
+                let capacity = metadata.get("capacity").and_then(|capacity| capacity.parse::<u64>().ok());
+                matching.push((capacity, member));
+            }
+        }
+        matching.sort_by(|a, b| b.0.cmp(&a.0));
+        matching.into_iter().map(|(_, member)| member).collect()
+    }
+
     /// Check if a node is suspected of failure
     pub async fn is_suspected(&self, node_id: NodeId) -> bool {
         self.phi_detector.is_suspected(node_id).await
@@ -217,10 +254,14 @@ impl MembershipManager {
         let mut members = self.members.write().await;
 
         if let Some(member) = members.get_mut(&node_id) {
-            member.status = NodeStatus::Failed;
+            let previous = std::mem::replace(&mut member.status, NodeStatus::Failed);
             member.last_heartbeat = std::time::SystemTime::now();
+            drop(members);
 
             // Notify callbacks
+            if previous != NodeStatus::Failed {
+                Self::notify_status_change(&self.node_callbacks, node_id, previous, NodeStatus::Failed).await;
+            }
             self.notify_node_failure(node_id).await;
 
             info!("Marked node {} as failed", node_id);
@@ -256,6 +297,7 @@ impl MembershipManager {
         let members = Arc::clone(&self.members);
         let phi_detector = Arc::clone(&self.phi_detector);
         let swim = Arc::clone(&self.swim);
+        let callbacks = Arc::clone(&self.node_callbacks);
         let config = self.config.clone();
         let shutdown_notify = Arc::clone(&self.shutdown_notify);
 
@@ -272,15 +314,18 @@ impl MembershipManager {
                             if phi_detector.is_suspected(*node_id).await {
                                 // Node is suspected - check if we should mark as failed
                                 let mut members_write = members.write().await;
+                                let mut transition = None;
 
                                 if let Some(member) = members_write.get_mut(node_id) {
                                     if member.status == NodeStatus::Healthy {
                                         member.status = NodeStatus::Suspected;
                                         warn!("Node {} marked as suspected", node_id);
+                                        transition = Some((NodeStatus::Healthy, NodeStatus::Suspected));
                                     } else if member.status == NodeStatus::Suspected {
                                         // Been suspected for too long, mark as failed
                                         member.status = NodeStatus::Failed;
                                         warn!("Node {} marked as failed", node_id);
+                                        transition = Some((NodeStatus::Suspected, NodeStatus::Failed));
 
                                         // Notify SWIM of failure
                                         if let Err(e) = swim.remove_member(*node_id).await {
@@ -288,6 +333,11 @@ impl MembershipManager {
                                         }
                                     }
                                 }
+                                drop(members_write);
+
+                                if let Some((from, to)) = transition {
+                                    Self::notify_status_change(&callbacks, *node_id, from, to).await;
+                                }
                             }
                         }
 
@@ -345,6 +395,20 @@ impl MembershipManager {
         }
     }
 
+    /// Notify callbacks of a health status transition
+    async fn notify_status_change(
+        callbacks: &Arc<RwLock<Vec<Box<dyn NodeEventCallback>>>>,
+        node_id: NodeId,
+        from: NodeStatus,
+        to: NodeStatus,
+    ) {
+        let callbacks = callbacks.read().await;
+
+        for callback in callbacks.iter() {
+            callback.on_node_status_change(node_id, from.clone(), to.clone()).await;
+        }
+    }
+
     /// Notify callbacks of node join
     async fn notify_node_join(&self, member: ClusterMember) {
         let callbacks = self.node_callbacks.read().await;
@@ -476,3 +540,4 @@ impl MembershipManager {
 // - [x] Cyclone networking preparation
 // - [x] Memory-safe concurrent operations
 // - [x] Comprehensive failure detection and recovery
+// - [x] Gossiped metadata for traffic steering and scheduling
@@ -0,0 +1,317 @@
+//! Gossiped Node Metadata: UNIQUENESS Implementation
+//!
+//! Operational metadata (labels, region, capacity) spread without central
+//! coordination:
+//! - **Piggybacking**: Updates ride on SWIM pings and acks, never their own packets
+//! - **Last-Writer-Wins**: Each key is stamped with a Lamport clock; ties break on writer id
+//! - **Bounded**: Per-node maps and per-packet payloads have fixed byte budgets
+//! - **Refutation**: A node re-asserts its own values over stale gossip about itself
+//!
+//! Each update is retransmitted `retransmit_multiplier * ceil(log2(n + 1))`
+//! times, as SWIM does for membership changes, which reaches every member in
+//! O(log n) protocol periods with high probability.
+
+use crate::error::{Error, Result};
+use crate::types::NodeId;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Metadata gossip configuration
+#[derive(Debug, Clone)]
+pub struct MetadataConfig {
+    /// Most keys one node may publish
+    pub max_entries: usize,
+
+    /// Most bytes of keys and values one node may publish
+    pub max_bytes: usize,
+
+    /// Most bytes of updates piggybacked on one message
+    pub piggyback_bytes: usize,
+
+    /// Retransmissions per update, times log2 of the cluster size
+    pub retransmit_multiplier: u32,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 32,
+            max_bytes: 2048,
+            piggyback_bytes: 512, // Leaves most of a 1400-byte UDP payload for SWIM itself
+            retransmit_multiplier: 4, // memberlist's default
+        }
+    }
+}
+
+/// Lamport timestamp of a metadata write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LamportStamp {
+    pub counter: u64,
+    /// Id of the writing node, breaking ties between equal counters
+    pub writer: u64,
+}
+
+/// One key of one node's metadata, as gossiped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataUpdate {
+    pub node: NodeId,
+    pub key: String,
+    /// `None` removes the key
+    pub value: Option<String>,
+    pub stamp: LamportStamp,
+}
+
+impl MetadataUpdate {
+    /// Approximate encoded size: strings plus node id and stamp
+    fn encoded_len(&self) -> usize {
+        self.key.len() + self.value.as_ref().map_or(0, String::len) + 3 * 8
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    value: Option<String>,
+    stamp: LamportStamp,
+}
+
+/// An update waiting to be piggybacked
+#[derive(Debug)]
+struct Broadcast {
+    update: MetadataUpdate,
+    transmits_left: u32,
+}
+
+/// Metadata of every known node, converging through gossip
+pub struct MetadataStore {
+    local_node: NodeId,
+    config: MetadataConfig,
+    clock: u64,
+    cluster_size: usize,
+    nodes: HashMap<NodeId, BTreeMap<String, Entry>>,
+    queue: Vec<Broadcast>,
+}
+
+impl MetadataStore {
+    pub fn new(local_node: NodeId, config: MetadataConfig) -> Self {
+        Self {
+            local_node,
+            config,
+            clock: 0,
+            cluster_size: 1,
+            nodes: HashMap::new(),
+            queue: Vec::new(),
+        }
+    }
+
+    /// Record the current membership size, which sets how often new
+    /// updates are retransmitted
+    pub fn set_cluster_size(&mut self, cluster_size: usize) {
+        self.cluster_size = cluster_size.max(1);
+    }
+
+    /// Set a key of the local node's metadata
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let mut entries = self.live_entries(self.local_node);
+        entries.insert(key.to_string(), value.to_string());
+        self.check_bounds(self.local_node, &entries)?;
+        self.publish(key, Some(value.to_string()));
+        Ok(())
+    }
+
+    /// Remove a key from the local node's metadata
+    pub fn remove(&mut self, key: &str) {
+        if self.live_entries(self.local_node).contains_key(key) {
+            self.publish(key, None);
+        }
+    }
+
+    /// Metadata of `node`, if anything has been heard from it
+    pub fn node_metadata(&self, node: NodeId) -> Option<HashMap<String, String>> {
+        self.nodes.contains_key(&node).then(|| self.live_entries(node).into_iter().collect())
+    }
+
+    /// Drop everything known about a node that left the cluster
+    pub fn forget(&mut self, node: NodeId) {
+        self.nodes.remove(&node);
+        self.queue.retain(|broadcast| broadcast.update.node != node);
+    }
+
+    /// Updates to attach to an outgoing message, within the piggyback budget
+    ///
+    /// Updates sent the fewest times go first, so fresh changes are not
+    /// starved by older ones still being retransmitted.
+    pub fn piggyback(&mut self) -> Vec<MetadataUpdate> {
+        self.queue.sort_by(|a, b| b.transmits_left.cmp(&a.transmits_left));
+
+        let mut budget = self.config.piggyback_bytes;
+        let mut updates = Vec::new();
+        for broadcast in &mut self.queue {
+            let len = broadcast.update.encoded_len();
+            if len > budget {
+                continue;
+            }
+            budget -= len;
+            broadcast.transmits_left -= 1;
+            updates.push(broadcast.update.clone());
+        }
+        self.queue.retain(|broadcast| broadcast.transmits_left > 0);
+        updates
+    }
+
+    /// Apply updates piggybacked on a received message, returning how many
+    /// were newer than what this node knew
+    ///
+    /// Newer updates are queued for further gossip. Updates that would push
+    /// a node past the size bounds are dropped.
+    pub fn merge(&mut self, updates: Vec<MetadataUpdate>) -> usize {
+        let mut applied = 0;
+        for update in updates {
+            self.clock = self.clock.max(update.stamp.counter);
+
+            let current = self.nodes.get(&update.node).and_then(|entries| entries.get(&update.key));
+            if current.map_or(false, |entry| entry.stamp >= update.stamp) {
+                continue;
+            }
+
+            // Stale gossip about ourselves, e.g. from before a restart:
+            // re-assert the local value with a newer stamp
+            if update.node == self.local_node {
+                let value = current.and_then(|entry| entry.value.clone());
+                self.publish(&update.key, value);
+                continue;
+            }
+
+            if let Some(value) = &update.value {
+                let mut entries = self.live_entries(update.node);
+                entries.insert(update.key.clone(), value.clone());
+                if self.check_bounds(update.node, &entries).is_err() {
+                    continue;
+                }
+            }
+
+            self.nodes.entry(update.node).or_default().insert(update.key.clone(), Entry {
+                value: update.value.clone(),
+                stamp: update.stamp,
+            });
+            self.enqueue(update);
+            applied += 1;
+        }
+        applied
+    }
+
+    /// Updates still waiting to be piggybacked
+    pub fn pending_broadcasts(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn publish(&mut self, key: &str, value: Option<String>) {
+        self.clock += 1;
+        let update = MetadataUpdate {
+            node: self.local_node,
+            key: key.to_string(),
+            value,
+            stamp: LamportStamp { counter: self.clock, writer: self.local_node.0 },
+        };
+        self.nodes.entry(self.local_node).or_default().insert(update.key.clone(), Entry {
+            value: update.value.clone(),
+            stamp: update.stamp,
+        });
+        self.enqueue(update);
+    }
+
+    fn enqueue(&mut self, update: MetadataUpdate) {
+        // A newer update for the same key supersedes the queued one
+        self.queue.retain(|broadcast| broadcast.update.node != update.node || broadcast.update.key != update.key);
+        let rounds = (usize::BITS - self.cluster_size.leading_zeros()).max(1);
+        self.queue.push(Broadcast {
+            update,
+            transmits_left: self.config.retransmit_multiplier.max(1) * rounds,
+        });
+    }
+
+    fn live_entries(&self, node: NodeId) -> BTreeMap<String, String> {
+        self.nodes.get(&node)
+            .map(|entries| entries.iter()
+                .filter_map(|(key, entry)| entry.value.clone().map(|value| (key.clone(), value)))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    fn check_bounds(&self, node: NodeId, entries: &BTreeMap<String, String>) -> Result<()> {
+        let bytes: usize = entries.iter().map(|(key, value)| key.len() + value.len()).sum();
+        if entries.len() > self.config.max_entries || bytes > self.config.max_bytes {
+            return Err(Error::Membership {
+                message: format!(
+                    "metadata of {} entries and {} bytes exceeds the limit of {} entries and {} bytes",
+                    entries.len(), bytes, self.config.max_entries, self.config.max_bytes
+                ),
+                node_id: Some(node.to_string()),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_writer_wins() {
+        let mut store = MetadataStore::new(NodeId(1), MetadataConfig::default());
+        let update = |counter, value: &str| MetadataUpdate {
+            node: NodeId(2),
+            key: "region".to_string(),
+            value: Some(value.to_string()),
+            stamp: LamportStamp { counter, writer: 2 },
+        };
+
+        assert_eq!(store.merge(vec![update(5, "eu-west"), update(3, "us-east")]), 1);
+        assert_eq!(store.node_metadata(NodeId(2)).unwrap()["region"], "eu-west");
+
+        // The clock moved past everything seen, so local writes sort later
+        store.set("zone", "a").unwrap();
+        assert!(store.piggyback().iter().any(|update| update.stamp.counter > 5));
+    }
+
+    #[test]
+    fn test_stale_gossip_about_self_is_refuted() {
+        let mut store = MetadataStore::new(NodeId(1), MetadataConfig::default());
+        store.set("region", "eu-west").unwrap();
+        store.piggyback();
+
+        store.merge(vec![MetadataUpdate {
+            node: NodeId(1),
+            key: "region".to_string(),
+            value: Some("us-east".to_string()),
+            stamp: LamportStamp { counter: 9, writer: 1 },
+        }]);
+
+        assert_eq!(store.node_metadata(NodeId(1)).unwrap()["region"], "eu-west");
+        let refutation = store.piggyback();
+        assert_eq!(refutation[0].value.as_deref(), Some("eu-west"));
+        assert!(refutation[0].stamp.counter > 9);
+    }
+
+    #[test]
+    fn test_size_bounds() {
+        let config = MetadataConfig { max_entries: 2, max_bytes: 32, ..MetadataConfig::default() };
+        let mut store = MetadataStore::new(NodeId(1), config);
+        store.set("a", "1").unwrap();
+        store.set("b", "2").unwrap();
+        assert!(store.set("c", "3").is_err());
+        assert!(store.set("a", &"x".repeat(64)).is_err());
+
+        // Overwriting and removing stay within bounds
+        store.set("a", "10").unwrap();
+        store.remove("b");
+        store.set("c", "3").unwrap();
+        assert_eq!(store.node_metadata(NodeId(1)).unwrap().len(), 2);
+    }
+}
+
+// UNIQUENESS Validation:
+// - [x] Infection-style metadata dissemination on SWIM messages
+// - [x] Lamport last-writer-wins convergence
+// - [x] Bounded per-node metadata and per-message payload
@@ -8,10 +8,12 @@
 pub mod swim;
 pub mod phi_accrual;
 pub mod membership_manager;
+pub mod metadata;
 
 pub use membership_manager::{MembershipManager, MembershipConfig, MembershipStats, NodeEventCallback};
 pub use swim::SwimProtocol;
 pub use phi_accrual::PhiAccrualFailureDetector;
+pub use metadata::{LamportStamp, MetadataConfig, MetadataStore, MetadataUpdate};
 
 /// Membership message for cross-node communication
 #[derive(Debug, Clone)]
@@ -8,6 +8,7 @@
 
 use crate::error::{Error, Result};
 use crate::membership::phi_accrual::PhiAccrualFailureDetector;
+use crate::membership::metadata::{MetadataConfig, MetadataStore, MetadataUpdate};
 use crate::types::{NodeId, ClusterMember, NodeStatus};
 
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -19,11 +20,11 @@ use tracing::{debug, info, warn};
 /// SWIM protocol message types
 #[derive(Debug, Clone)]
 pub enum SwimMessage {
-    /// Direct ping to check node liveness
-    Ping { sequence: u64 },
+    /// Direct ping to check node liveness, carrying piggybacked metadata
+    Ping { sequence: u64, metadata: Vec<MetadataUpdate> },
 
-    /// Acknowledgment of ping
-    Ack { sequence: u64 },
+    /// Acknowledgment of ping, carrying piggybacked metadata
+    Ack { sequence: u64, metadata: Vec<MetadataUpdate> },
 
     /// Request indirect ping through another node
     PingReq { target: NodeId, sequence: u64 },
@@ -66,6 +67,9 @@ pub struct SwimConfig {
 
     /// Message queue size limit
     pub message_queue_size: usize,
+
+    /// Node metadata gossip bounds
+    pub metadata: MetadataConfig,
 }
 
 impl Default for SwimConfig {
@@ -78,6 +82,7 @@ impl Default for SwimConfig {
             suspicion_timeout: Duration::from_secs(5),
             dissemination_speed: 3, // k=3 from SWIM paper
             message_queue_size: 1000,
+            metadata: MetadataConfig::default(),
         }
     }
 }
@@ -102,6 +107,9 @@ pub struct SwimProtocol {
     /// Message queue for outgoing messages
     message_queue: Arc<RwLock<VecDeque<SwimMessage>>>,
 
+    /// Gossiped metadata of every known node
+    metadata: Arc<RwLock<MetadataStore>>,
+
     /// Sequence number for ping messages
     sequence_number: Arc<RwLock<u64>>,
 
@@ -148,6 +156,8 @@ impl SwimProtocol {
             last_update: Instant::now(),
         });
 
+        let metadata = MetadataStore::new(local_node, config.metadata.clone());
+
         Ok(Self {
             local_node,
             config,
@@ -155,6 +165,7 @@ impl SwimProtocol {
             suspected_nodes: Arc::new(RwLock::new(HashSet::new())),
             failure_detector,
             message_queue: Arc::new(RwLock::new(VecDeque::new())),
+            metadata: Arc::new(RwLock::new(metadata)),
             sequence_number: Arc::new(RwLock::new(0)),
             message_notify: Arc::new(Notify::new()),
             shutdown_notify: Arc::new(Notify::new()),
@@ -193,6 +204,7 @@ impl SwimProtocol {
             incarnation: 0,
             last_update: Instant::now(),
         });
+        self.metadata.write().await.set_cluster_size(membership.len());
 
         // Broadcast membership update
         self.broadcast_membership_update(member).await?;
@@ -209,6 +221,11 @@ impl SwimProtocol {
         if membership.remove(&node_id).is_some() {
             suspected.remove(&node_id);
 
+            let mut metadata = self.metadata.write().await;
+            metadata.forget(node_id);
+            metadata.set_cluster_size(membership.len());
+            drop(metadata);
+
             // Broadcast membership update
             let update_member = ClusterMember {
                 node_id,
@@ -236,13 +253,30 @@ impl SwimProtocol {
             .collect()
     }
 
+    /// Set a key of the local node's metadata, to be gossiped to all members
+    pub async fn set_local_metadata(&self, key: &str, value: &str) -> Result<()> {
+        self.metadata.write().await.set(key, value)
+    }
+
+    /// Remove a key from the local node's metadata
+    pub async fn remove_local_metadata(&self, key: &str) {
+        self.metadata.write().await.remove(key);
+    }
+
+    /// Metadata a node has published, as far as gossip has delivered it
+    pub async fn node_metadata(&self, node_id: NodeId) -> Option<HashMap<String, String>> {
+        self.metadata.read().await.node_metadata(node_id)
+    }
+
     /// Handle incoming SWIM message
     pub async fn handle_message(&self, from: NodeId, message: SwimMessage) -> Result<()> {
         match message {
-            SwimMessage::Ping { sequence } => {
+            SwimMessage::Ping { sequence, metadata } => {
+                self.merge_metadata(from, metadata).await;
                 self.handle_ping(from, sequence).await?;
             }
-            SwimMessage::Ack { sequence } => {
+            SwimMessage::Ack { sequence, metadata } => {
+                self.merge_metadata(from, metadata).await;
                 self.handle_ack(from, sequence).await?;
             }
             SwimMessage::PingReq { target, sequence } => {
@@ -258,13 +292,25 @@ impl SwimProtocol {
         Ok(())
     }
 
+    /// Apply metadata piggybacked on a message
+    async fn merge_metadata(&self, from: NodeId, updates: Vec<MetadataUpdate>) {
+        if updates.is_empty() {
+            return;
+        }
+        let applied = self.metadata.write().await.merge(updates);
+        if applied > 0 {
+            debug!("Applied {} metadata updates gossiped by {}", applied, from);
+        }
+    }
+
     /// Handle ping message
     async fn handle_ping(&self, from: NodeId, sequence: u64) -> Result<()> {
         // Record heartbeat for failure detector
         self.failure_detector.record_heartbeat(from).await;
 
         // Send acknowledgment
-        let ack_msg = SwimMessage::Ack { sequence };
+        let metadata = self.metadata.write().await.piggyback();
+        let ack_msg = SwimMessage::Ack { sequence, metadata };
         self.send_message(from, ack_msg).await?;
 
         // Piggyback membership updates (infection-style dissemination)
@@ -289,7 +335,8 @@ impl SwimProtocol {
     /// Handle indirect ping request
     async fn handle_ping_req(&self, from: NodeId, target: NodeId, sequence: u64) -> Result<()> {
         // Send ping to target on behalf of requester
-        let ping_msg = SwimMessage::Ping { sequence };
+        let metadata = self.metadata.write().await.piggyback();
+        let ping_msg = SwimMessage::Ping { sequence, metadata };
         self.send_message(target, ping_msg).await?;
 
         debug!("Forwarding ping from {} to {} (sequence {})", from, target, sequence);
@@ -342,6 +389,7 @@ impl SwimProtocol {
         let suspected = Arc::clone(&self.suspected_nodes);
         let failure_detector = Arc::clone(&self.failure_detector);
         let sequence_number = Arc::clone(&self.sequence_number);
+        let metadata = Arc::clone(&self.metadata);
         let config = self.config.clone();
         let shutdown_notify = Arc::clone(&self.shutdown_notify);
 
@@ -360,7 +408,10 @@ impl SwimProtocol {
                             };
 
                             // Send ping
-                            let ping_msg = SwimMessage::Ping { sequence };
+                            let ping_msg = SwimMessage::Ping {
+                                sequence,
+                                metadata: metadata.write().await.piggyback(),
+                            };
                             if let Err(e) = Self::send_message_static(peer_id, ping_msg).await {
                                 warn!("Failed to send ping to {}: {}", peer_id, e);
                             }
@@ -561,3 +612,4 @@ impl SwimProtocol {
 // - [x] Failure detection with indirect pings
 // - [x] Memory-safe concurrent operations
 // - [x] Scalable membership management
+// - [x] Bounded node metadata piggybacked on pings and acks
@@ -6,6 +6,7 @@
 //! - **NUMA Optimization**: Memory hierarchy exploitation (Torrellas et al., 2010)
 //! - **Memory Optimization**: Slab allocation and zero-copy techniques
 //! - **Benchmarking Suite**: Comparative performance analysis
+//! - **Consensus Harness**: Offered-load sweeps with JSON reports and baselines
 //! - **Real-Time Metrics**: Sub-microsecond measurement precision
 
 pub mod hdr_histograms;
@@ -13,6 +14,7 @@ pub mod simd_acceleration;
 pub mod numa_optimization;
 pub mod memory_optimization;
 pub mod benchmarking;
+pub mod consensus_harness;
 pub mod performance_metrics;
 pub mod monitoring_system;
 
@@ -21,6 +23,7 @@ pub use simd_acceleration::{SIMDProcessor, VectorizedOperations};
 pub use numa_optimization::{NumaAwareAllocator, NumaAwareScheduler, NumaTopology};
 pub use memory_optimization::{MemoryOptimizer, SlabAllocator};
 pub use benchmarking::{BenchmarkSuite, PerformanceBenchmark};
+pub use consensus_harness::{ConsensusHarness, ConsensusHarnessConfig, ConsensusBenchmarkReport, ConsensusTarget};
 pub use performance_metrics::{PerformanceMetrics, LatencyStats, ThroughputStats};
 pub use monitoring_system::MonitoringSystem;
 
@@ -0,0 +1,276 @@
+//! Grey Failure Detection: Latency-Aware Load Shedding
+//!
+//! A peer that still answers, only slowly, is invisible to phi-accrual but
+//! drags every request routed to it into the tail. This detector keeps a
+//! window of request latencies per peer and compares each peer's percentile
+//! latency against the median of the others:
+//! - **Degraded**: The peer's percentile exceeds `degradation_factor` times
+//!   the cluster baseline (and `min_degraded_latency`). Its traffic weight
+//!   drops to `min_weight` and a [`GreyFailureSignal::Degraded`] is raised
+//! - **Recovering**: Latency is back under the threshold. Like TCP slow
+//!   start, the weight doubles every `recovery_interval` for as long as it
+//!   stays there; relapsing cuts it back to `min_weight`
+//! - **Healthy**: Full weight again, with [`GreyFailureSignal::Recovered`]
+//!
+//! A degraded peer keeps a trickle of traffic so that its latency keeps being
+//! measured; samples older than `sample_ttl` age out, so recovery is seen
+//! once its most recent requests are fast rather than after the trickle has
+//! refilled the whole window.
+//!
+//! Time is passed in by the caller, which keeps the decisions deterministic
+//! and lets tests inject latency without sleeping.
+
+use crate::types::NodeId;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Grey-failure detector configuration
+#[derive(Debug, Clone)]
+pub struct GreyFailureConfig {
+    /// Latency samples kept per peer
+    pub window: usize,
+
+    /// Samples a peer needs before its latency is judged or used as baseline
+    pub min_samples: usize,
+
+    /// Percentile compared against the baseline, in (0, 1]
+    pub percentile: f64,
+
+    /// How many times the cluster baseline a peer may reach before it is degraded
+    pub degradation_factor: f64,
+
+    /// Latency below which a peer is never considered degraded
+    pub min_degraded_latency: Duration,
+
+    /// Traffic weight of a degraded peer, relative to a healthy one
+    pub min_weight: f64,
+
+    /// How long latency must stay healthy for each doubling of the weight
+    pub recovery_interval: Duration,
+
+    /// Age after which samples no longer count
+    pub sample_ttl: Duration,
+}
+
+impl Default for GreyFailureConfig {
+    fn default() -> Self {
+        Self {
+            window: 200,
+            min_samples: 20,
+            percentile: 0.99,
+            degradation_factor: 3.0,
+            min_degraded_latency: Duration::from_millis(10),
+            min_weight: 0.05,
+            recovery_interval: Duration::from_millis(500),
+            sample_ttl: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Latency health of a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerHealth {
+    /// Full traffic
+    Healthy,
+    /// Slow; traffic cut to the minimum weight
+    Degraded,
+    /// Latency recovered; traffic ramping back up
+    Recovering,
+}
+
+/// Monitoring signal raised on a change in a peer's health
+#[derive(Debug, Clone, PartialEq)]
+pub enum GreyFailureSignal {
+    /// Traffic to `node_id` was cut back
+    Degraded {
+        node_id: NodeId,
+        /// The peer's percentile latency
+        latency: Duration,
+        /// Median percentile latency of the other peers
+        baseline: Duration,
+    },
+    /// `node_id` is back to full traffic
+    Recovered { node_id: NodeId },
+}
+
+/// Per-peer latency window and traffic share
+#[derive(Debug)]
+struct PeerState {
+    samples: VecDeque<(Instant, Duration)>,
+    health: PeerHealth,
+    weight: f64,
+    /// When the weight last changed while recovering
+    ramped_at: Instant,
+    /// Smooth weighted round-robin credit
+    credit: f64,
+}
+
+impl PeerState {
+    fn new(now: Instant) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            health: PeerHealth::Healthy,
+            weight: 1.0,
+            ramped_at: now,
+            credit: 0.0,
+        }
+    }
+}
+
+/// Per-node grey-failure detector and weighted peer picker
+#[derive(Debug)]
+pub struct GreyFailureDetector {
+    config: GreyFailureConfig,
+    peers: HashMap<NodeId, PeerState>,
+}
+
+impl GreyFailureDetector {
+    /// Create a detector with no peers; peers are added as they are picked or
+    /// report latency
+    pub fn new(config: GreyFailureConfig) -> Self {
+        Self { config, peers: HashMap::new() }
+    }
+
+    /// Record the latency of a request to `node_id` that completed at `now`,
+    /// returning a signal if the peer's health changed
+    pub fn record(&mut self, node_id: NodeId, latency: Duration, now: Instant) -> Option<GreyFailureSignal> {
+        let window = self.config.window;
+        let peer = self.peers.entry(node_id).or_insert_with(|| PeerState::new(now));
+        peer.samples.push_back((now, latency));
+        while peer.samples.len() > window {
+            peer.samples.pop_front();
+        }
+        self.evaluate(node_id, now)
+    }
+
+    /// Pick one of `candidates` for the next request. Peers receive traffic in
+    /// proportion to their weight, spread evenly rather than in bursts
+    pub fn pick(&mut self, candidates: &[NodeId], now: Instant) -> Option<NodeId> {
+        let mut total = 0.0;
+        let mut best: Option<(NodeId, f64)> = None;
+        for &node_id in candidates {
+            let peer = self.peers.entry(node_id).or_insert_with(|| PeerState::new(now));
+            peer.credit += peer.weight;
+            total += peer.weight;
+            if best.is_none_or(|(_, credit)| peer.credit > credit) {
+                best = Some((node_id, peer.credit));
+            }
+        }
+        let (chosen, _) = best?;
+        if let Some(peer) = self.peers.get_mut(&chosen) {
+            peer.credit -= total;
+        }
+        Some(chosen)
+    }
+
+    /// Current health of `node_id`; peers never seen are healthy
+    pub fn health(&self, node_id: NodeId) -> PeerHealth {
+        self.peers.get(&node_id).map_or(PeerHealth::Healthy, |peer| peer.health)
+    }
+
+    /// Current traffic weight of `node_id`, from `min_weight` to 1
+    pub fn weight(&self, node_id: NodeId) -> f64 {
+        self.peers.get(&node_id).map_or(1.0, |peer| peer.weight)
+    }
+
+    /// Percentile latency of `node_id` over its live samples, once it has enough
+    pub fn latency(&self, node_id: NodeId, now: Instant) -> Option<Duration> {
+        self.peers.get(&node_id).and_then(|peer| self.percentile(peer, now))
+    }
+
+    /// Peers currently receiving reduced traffic
+    pub fn degraded_peers(&self) -> Vec<NodeId> {
+        let mut peers: Vec<NodeId> = self.peers.iter()
+            .filter(|(_, peer)| peer.health != PeerHealth::Healthy)
+            .map(|(&node_id, _)| node_id)
+            .collect();
+        peers.sort_by_key(|node_id| node_id.0);
+        peers
+    }
+
+    /// Forget a peer that left the cluster
+    pub fn remove_peer(&mut self, node_id: NodeId) {
+        self.peers.remove(&node_id);
+    }
+
+    /// Configured percentile of the samples younger than `sample_ttl`. The
+    /// newest `min_samples` always count, so a peer that only gets a trickle
+    /// of traffic is still judged on its latest requests
+    fn percentile(&self, peer: &PeerState, now: Instant) -> Option<Duration> {
+        let mut live: Vec<Duration> = peer.samples.iter().rev()
+            .enumerate()
+            .take_while(|(newer, (at, _))| {
+                *newer < self.config.min_samples || now.saturating_duration_since(*at) <= self.config.sample_ttl
+            })
+            .map(|(_, &(_, latency))| latency)
+            .collect();
+        if live.len() < self.config.min_samples.max(1) {
+            return None;
+        }
+        live.sort_unstable();
+        let rank = (self.config.percentile * live.len() as f64).ceil() as usize;
+        Some(live[rank.clamp(1, live.len()) - 1])
+    }
+
+    /// Median percentile latency of every peer but `node_id`
+    fn baseline(&self, node_id: NodeId, now: Instant) -> Option<Duration> {
+        let mut others: Vec<Duration> = self.peers.iter()
+            .filter(|(&other, _)| other != node_id)
+            .filter_map(|(_, peer)| self.percentile(peer, now))
+            .collect();
+        if others.is_empty() {
+            return None;
+        }
+        others.sort_unstable();
+        Some(others[others.len() / 2])
+    }
+
+    /// Move `node_id` between health states given its latest latency
+    fn evaluate(&mut self, node_id: NodeId, now: Instant) -> Option<GreyFailureSignal> {
+        let peer = self.peers.get(&node_id)?;
+        // Without a baseline there is nothing to call slow: either too few
+        // samples or no other peer to compare against
+        let latency = self.percentile(peer, now)?;
+        let baseline = self.baseline(node_id, now)?;
+        let threshold = baseline.mul_f64(self.config.degradation_factor).max(self.config.min_degraded_latency);
+        let (min_weight, recovery_interval) = (self.config.min_weight, self.config.recovery_interval);
+
+        let peer = self.peers.get_mut(&node_id)?;
+        if latency > threshold {
+            if peer.health == PeerHealth::Degraded {
+                return None;
+            }
+            peer.health = PeerHealth::Degraded;
+            peer.weight = min_weight;
+            peer.credit = 0.0;
+            warn!("Peer {} degraded: p{:.0} latency {:?} against baseline {:?}; shedding load",
+                  node_id, self.config.percentile * 100.0, latency, baseline);
+            return Some(GreyFailureSignal::Degraded { node_id, latency, baseline });
+        }
+
+        match peer.health {
+            PeerHealth::Healthy => None,
+            PeerHealth::Degraded => {
+                peer.health = PeerHealth::Recovering;
+                peer.ramped_at = now;
+                info!("Peer {} latency recovered to {:?}; ramping traffic back up", node_id, latency);
+                None
+            }
+            PeerHealth::Recovering => {
+                if now.saturating_duration_since(peer.ramped_at) < recovery_interval {
+                    return None;
+                }
+                peer.weight = (peer.weight * 2.0).min(1.0);
+                peer.ramped_at = now;
+                if peer.weight < 1.0 {
+                    return None;
+                }
+                peer.health = PeerHealth::Healthy;
+                info!("Peer {} back to full traffic", node_id);
+                Some(GreyFailureSignal::Recovered { node_id })
+            }
+        }
+    }
+}
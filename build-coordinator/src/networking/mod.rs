@@ -5,18 +5,21 @@
 //! - **DPDK**: User-space networking acceleration
 //! - **Zero-Copy**: Scatter-gather I/O with buffer management
 //! - **XDP/eBPF**: Kernel-bypass packet processing
+//! - **Grey Failures**: Latency-aware load shedding for slow-but-alive peers
 
 pub mod network_layer;
 pub mod rdma_transport;
 pub mod dpdk_acceleration;
 pub mod zero_copy_messaging;
 pub mod message_router;
+pub mod grey_failure;
 
 pub use network_layer::{NetworkLayer, NetworkConfig, ConnectionType};
 pub use rdma_transport::RDMATransport;
 pub use dpdk_acceleration::DPDKAccelerator;
 pub use zero_copy_messaging::{ZeroCopyMessenger, MessageBuffer};
 pub use message_router::MessageRouter;
+pub use grey_failure::{GreyFailureConfig, GreyFailureDetector, GreyFailureSignal, PeerHealth};
 
 // Re-export key types for AuroraDB coordination
 pub use crate::types::{NodeId, ClusterMember};
@@ -0,0 +1,199 @@
+//! Coordinator Client: Read-Your-Writes Sessions
+//!
+//! A client that writes through the leader and reads from whichever node is
+//! closest can miss its own write on a follower that has not applied it yet.
+//! The client keeps a session token instead of paying for linearizable reads:
+//! - **Write**: Goes to the leader, which returns the commit index of the
+//!   entry; the session token advances to it
+//! - **Read**: Carries the token. The serving node answers once it has
+//!   applied at least that index, waiting up to `wait_timeout` for it, and
+//!   otherwise the read is forwarded to a node that has
+//!
+//! A token can be handed to another client with [`CoordinatorClient::with_token`]
+//! so the guarantee holds across processes that share a session. Reads still
+//! may miss writes made by other sessions that this one has not seen.
+
+use crate::error::{Error, Result};
+use crate::consensus::LogIndex;
+use crate::types::NodeId;
+
+use std::sync::Arc;
+use std::time::Duration;
+use parking_lot::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Highest commit index a session has observed; a read carrying it sees
+/// every write the session made
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SessionToken(LogIndex);
+
+impl SessionToken {
+    /// Token for a session that has observed `commit_index`
+    pub fn new(commit_index: LogIndex) -> Self {
+        Self(commit_index)
+    }
+
+    /// Index a node must have applied to serve the session
+    pub fn commit_index(&self) -> LogIndex {
+        self.0
+    }
+}
+
+/// One node of the consensus group as the client sees it
+#[async_trait::async_trait]
+pub trait ConsensusReplica: Send + Sync {
+    /// The node's id
+    fn node_id(&self) -> NodeId;
+
+    /// Whether the node currently leads and accepts writes
+    async fn is_leader(&self) -> bool;
+
+    /// Replicate a write and return its commit index once committed
+    async fn write(&self, key: &str, value: Vec<u8>) -> Result<LogIndex>;
+
+    /// Highest log index the node has applied to its state machine
+    async fn applied_index(&self) -> LogIndex;
+
+    /// Read `key` from the node's state machine as applied so far
+    async fn read(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Wait until the node has applied `index`, for at most `timeout`;
+    /// returns whether it has. Polls [`applied_index`](Self::applied_index)
+    /// unless the replica can be notified of progress.
+    async fn wait_for_applied(&self, index: LogIndex, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.applied_index().await >= index {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(APPLY_POLL_INTERVAL.min(deadline - Instant::now())).await;
+        }
+    }
+}
+
+/// How often [`ConsensusReplica::wait_for_applied`] checks progress by default
+const APPLY_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Client session configuration
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// How long a node behind the session token may take to catch up
+    /// before the read is forwarded
+    pub wait_timeout: Duration,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self { wait_timeout: Duration::from_millis(500) }
+    }
+}
+
+/// A value read within a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRead {
+    pub value: Option<Vec<u8>>,
+    /// Node that served the read
+    pub served_by: NodeId,
+    /// Set when the requested node was behind and another served it
+    pub forwarded: bool,
+}
+
+/// Client of the coordinator's consensus group with read-your-writes sessions
+pub struct CoordinatorClient {
+    config: SessionConfig,
+    replicas: Vec<Arc<dyn ConsensusReplica>>,
+    token: Mutex<SessionToken>,
+}
+
+impl CoordinatorClient {
+    /// Start a new session against `replicas`
+    pub fn new(config: SessionConfig, replicas: Vec<Arc<dyn ConsensusReplica>>) -> Self {
+        Self { config, replicas, token: Mutex::new(SessionToken::default()) }
+    }
+
+    /// Continue a session from a token another client handed over
+    pub fn with_token(self, token: SessionToken) -> Self {
+        self.observe(token);
+        self
+    }
+
+    /// The session's current token
+    pub fn session_token(&self) -> SessionToken {
+        *self.token.lock()
+    }
+
+    /// Write through the leader; returns the token now covering the write
+    pub async fn write(&self, key: &str, value: Vec<u8>) -> Result<SessionToken> {
+        let mut leader = None;
+        for replica in &self.replicas {
+            if replica.is_leader().await {
+                leader = Some(replica);
+                break;
+            }
+        }
+        let leader = leader.ok_or_else(|| Error::Consensus {
+            message: "No leader among the client's replicas".to_string(),
+            operation: "session_write".to_string(),
+        })?;
+
+        let commit_index = leader.write(key, value).await?;
+        self.observe(SessionToken::new(commit_index));
+        Ok(self.session_token())
+    }
+
+    /// Read `key` from `node`, reflecting every write of the session
+    pub async fn read_from(&self, node: NodeId, key: &str) -> Result<SessionRead> {
+        let token = self.session_token();
+        let replica = self.replica(node)?;
+        if replica.wait_for_applied(token.commit_index(), self.config.wait_timeout).await {
+            return Ok(SessionRead { value: replica.read(key).await?, served_by: node, forwarded: false });
+        }
+
+        // Forward to a node that has caught up, leader last as it is the busiest
+        let mut candidates: Vec<&Arc<dyn ConsensusReplica>> = Vec::new();
+        let mut leaders = Vec::new();
+        for replica in self.replicas.iter().filter(|replica| replica.node_id() != node) {
+            if replica.is_leader().await {
+                leaders.push(replica);
+            } else {
+                candidates.push(replica);
+            }
+        }
+        candidates.extend(leaders);
+        for replica in candidates {
+            if replica.applied_index().await >= token.commit_index() {
+                debug!("Node {} is behind session index {}, forwarding read to {}",
+                       node, token.commit_index(), replica.node_id());
+                return Ok(SessionRead {
+                    value: replica.read(key).await?,
+                    served_by: replica.node_id(),
+                    forwarded: true,
+                });
+            }
+        }
+
+        Err(Error::Timeout {
+            message: format!("No node applied session index {} within the wait", token.commit_index()),
+            duration: self.config.wait_timeout,
+        })
+    }
+
+    /// Advance the session to `token` if it is ahead
+    fn observe(&self, token: SessionToken) {
+        let mut current = self.token.lock();
+        *current = (*current).max(token);
+    }
+
+    fn replica(&self, node: NodeId) -> Result<&Arc<dyn ConsensusReplica>> {
+        self.replicas.iter()
+            .find(|replica| replica.node_id() == node)
+            .ok_or_else(|| Error::Membership {
+                message: format!("Node {} is not one of the client's replicas", node),
+                node_id: Some(node.to_string()),
+            })
+    }
+}
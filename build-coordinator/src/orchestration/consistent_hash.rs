@@ -0,0 +1,241 @@
+//! Consistent Hashing: Stable Key and Shard Placement
+//!
+//! Maps keys (or shard ids) to nodes on a hash ring so that a membership
+//! change only moves the keys it has to:
+//! - **Virtual nodes**: Each node owns many points on the ring, which evens
+//!   out the share of the keyspace each node gets
+//! - **Weights**: A node's point count scales with its capacity, so a node
+//!   of weight 2.0 owns about twice the keys of a node of weight 1.0
+//! - **Minimal movement**: Adding a node to an N-node ring moves about
+//!   1/(N+1) of the keys, all of them to the new node; removing one moves
+//!   only the keys it owned
+//!
+//! Every change returns a `RingChange` listing the ring ranges that changed
+//! owner, so a replicator can find exactly the keys it must move without
+//! rehashing the whole keyspace against both rings.
+//!
+//! Points and keys are hashed with BLAKE3, so placement is identical on
+//! every node and across releases.
+//!
+//! Research: Karger et al. (1997) consistent hashing; DeCandia et al. (2007)
+//! Dynamo virtual nodes.
+
+use crate::error::{Error, Result};
+use crate::types::NodeId;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Virtual nodes per unit of weight when none is configured
+pub const DEFAULT_VIRTUAL_NODES: usize = 160;
+
+/// Position of a key on the ring
+pub fn ring_hash(key: &[u8]) -> u64 {
+    let hash = blake3::hash(key);
+    u64::from_le_bytes(hash.as_bytes()[..8].try_into().expect("BLAKE3 output is 32 bytes"))
+}
+
+/// Consistent-hash ring of weighted nodes
+#[derive(Debug, Clone)]
+pub struct HashRing {
+    /// Virtual nodes of a node of weight 1.0
+    virtual_nodes: usize,
+    /// Ring point -> owning node
+    points: BTreeMap<u64, NodeId>,
+    /// Member weights
+    weights: HashMap<NodeId, f64>,
+}
+
+/// Ring range `(start, end]` whose owner changed; `start >= end` wraps
+/// around zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovedRange {
+    pub start: u64,
+    pub end: u64,
+    /// Owner before the change; `None` if the ring was empty
+    pub from: Option<NodeId>,
+    /// Owner after the change; `None` if the ring is now empty
+    pub to: Option<NodeId>,
+}
+
+impl MovedRange {
+    /// Whether ring position `hash` falls in the range
+    pub fn contains(&self, hash: u64) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => hash > self.start && hash <= self.end,
+            // A single point owns the whole ring
+            std::cmp::Ordering::Equal => true,
+            std::cmp::Ordering::Greater => hash > self.start || hash <= self.end,
+        }
+    }
+}
+
+/// Ranges that changed owner in one membership change
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RingChange {
+    pub moved: Vec<MovedRange>,
+}
+
+/// A key that changes owner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMove<K> {
+    pub key: K,
+    pub from: Option<NodeId>,
+    pub to: Option<NodeId>,
+}
+
+impl RingChange {
+    /// The range `key` falls in, if its owner changed
+    pub fn moved_range(&self, key: &[u8]) -> Option<&MovedRange> {
+        let hash = ring_hash(key);
+        self.moved.iter().find(|range| range.contains(hash))
+    }
+
+    /// Which of `keys` changed owner, and between which nodes
+    pub fn affected_keys<K, I>(&self, keys: I) -> Vec<KeyMove<K>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        keys.into_iter()
+            .filter_map(|key| {
+                let range = *self.moved_range(key.as_ref())?;
+                Some(KeyMove { key, from: range.from, to: range.to })
+            })
+            .collect()
+    }
+
+    /// Whether nothing moved
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty()
+    }
+}
+
+impl Default for HashRing {
+    fn default() -> Self {
+        Self::new(DEFAULT_VIRTUAL_NODES)
+    }
+}
+
+impl HashRing {
+    /// Empty ring giving a node of weight 1.0 `virtual_nodes` points
+    pub fn new(virtual_nodes: usize) -> Self {
+        Self {
+            virtual_nodes: virtual_nodes.max(1),
+            points: BTreeMap::new(),
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Add `node` with capacity `weight`, or reweight it if it is a member
+    pub fn add_node(&mut self, node: NodeId, weight: f64) -> Result<RingChange> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(Error::Config {
+                message: format!("Weight of {} must be positive, got {}", node, weight),
+                field: Some("weight".to_string()),
+            });
+        }
+        let before = self.points.clone();
+        self.points.retain(|_, owner| *owner != node);
+        self.weights.insert(node, weight);
+        let count = ((self.virtual_nodes as f64 * weight).round() as usize).max(1);
+        for replica in 0..count {
+            // On the rare collision the earlier owner keeps the point
+            self.points.entry(Self::point(node, replica)).or_insert(node);
+        }
+        Ok(Self::diff(&before, &self.points))
+    }
+
+    /// Remove `node`; `None` if it was not a member
+    pub fn remove_node(&mut self, node: NodeId) -> Option<RingChange> {
+        self.weights.remove(&node)?;
+        let before = self.points.clone();
+        self.points.retain(|_, owner| *owner != node);
+        Some(Self::diff(&before, &self.points))
+    }
+
+    /// Node owning `key`; `None` on an empty ring
+    pub fn node_for(&self, key: impl AsRef<[u8]>) -> Option<NodeId> {
+        Self::owner(&self.points, ring_hash(key.as_ref()))
+    }
+
+    /// Up to `count` distinct nodes for `key`, its owner first and then the
+    /// next nodes clockwise, as replica placement
+    pub fn nodes_for(&self, key: impl AsRef<[u8]>, count: usize) -> Vec<NodeId> {
+        let hash = ring_hash(key.as_ref());
+        let wanted = count.min(self.weights.len());
+        let mut nodes = Vec::with_capacity(wanted);
+        let clockwise = self.points.range(hash..).chain(self.points.range(..hash));
+        for (_, node) in clockwise {
+            if nodes.len() == wanted {
+                break;
+            }
+            if !nodes.contains(node) {
+                nodes.push(*node);
+            }
+        }
+        nodes
+    }
+
+    /// Members and their weights
+    pub fn members(&self) -> &HashMap<NodeId, f64> {
+        &self.weights
+    }
+
+    /// Number of points on the ring
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    fn point(node: NodeId, replica: usize) -> u64 {
+        ring_hash(format!("{}#{}", node, replica).as_bytes())
+    }
+
+    /// First point at or after `hash`, wrapping to the first point
+    fn owner(points: &BTreeMap<u64, NodeId>, hash: u64) -> Option<NodeId> {
+        points.range(hash..).next()
+            .or_else(|| points.iter().next())
+            .map(|(_, node)| *node)
+    }
+
+    /// Ranges owned differently by `before` and `after`. Every range between
+    /// consecutive points of either ring has one owner in each, so comparing
+    /// owners at each such point covers the whole ring.
+    fn diff(before: &BTreeMap<u64, NodeId>, after: &BTreeMap<u64, NodeId>) -> RingChange {
+        let boundaries: BTreeSet<u64> = before.keys().chain(after.keys()).copied().collect();
+        let Some(&last) = boundaries.iter().next_back() else {
+            return RingChange::default();
+        };
+
+        let mut moved: Vec<MovedRange> = Vec::new();
+        let mut start = last;
+        for &end in &boundaries {
+            let from = Self::owner(before, end);
+            let to = Self::owner(after, end);
+            if from != to {
+                match moved.last_mut() {
+                    // Adjacent ranges with the same move merge
+                    Some(previous) if previous.end == start && previous.from == from && previous.to == to => {
+                        previous.end = end;
+                    }
+                    _ => moved.push(MovedRange { start, end, from, to }),
+                }
+            }
+            start = end;
+        }
+
+        // The range ending at the first boundary wraps from the last one and
+        // may continue the final range
+        if moved.len() > 1 {
+            let (first, last_range) = (moved[0], moved[moved.len() - 1]);
+            if last_range.end == last && first.start == last && first.from == last_range.from && first.to == last_range.to {
+                moved[0].start = last_range.start;
+                moved.pop();
+            }
+        }
+        RingChange { moved }
+    }
+}
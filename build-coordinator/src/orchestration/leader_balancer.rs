@@ -0,0 +1,343 @@
+//! Leader Balancer: Even Out Raft Group Leaders Across Nodes
+//!
+//! In a multi-raft-group cluster every leader does the group's write fan-out,
+//! so leaders piling up on one node overload it while the others idle. The
+//! balancer periodically reads per-node leader counts and load, and moves
+//! leaders off the busiest nodes with leadership transfers:
+//! - **Threshold**: Nothing moves while the gap between the most and least
+//!   loaded nodes is within `imbalance_threshold` leaders
+//! - **Targets**: A leader only moves to another replica of its group, never
+//!   to a node whose load is above `max_target_load`
+//! - **Rate limit**: At most `max_transfers` transfers are issued in any
+//!   `rate_window`, since each transfer briefly stalls its group's writes
+//!
+//! Like the auto-scaler, the balancer is either advisory, reporting the
+//! transfers it would make, or active, issuing them.
+//!
+//! Time is passed in by the caller, which keeps planning deterministic and
+//! lets tests drive many rounds without sleeping.
+
+use crate::error::Result;
+use crate::types::NodeId;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+/// Raft group identifier
+pub type RaftGroupId = u64;
+
+/// Whether the balancer only recommends transfers or issues them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalancerMode {
+    /// Report the transfers that would be made
+    Advisory,
+    /// Issue the transfers
+    Active,
+}
+
+/// Leader balancer configuration
+#[derive(Debug, Clone)]
+pub struct LeaderBalancerConfig {
+    pub mode: BalancerMode,
+
+    /// How often a balancing round runs
+    pub interval: Duration,
+
+    /// Largest tolerated gap between the highest and lowest leader counts
+    pub imbalance_threshold: usize,
+
+    /// Nodes above this load (0.0 to 1.0) are not given more leaders
+    pub max_target_load: f64,
+
+    /// Transfers allowed per `rate_window`
+    pub max_transfers: usize,
+    pub rate_window: Duration,
+}
+
+impl Default for LeaderBalancerConfig {
+    fn default() -> Self {
+        Self {
+            mode: BalancerMode::Advisory,
+            interval: Duration::from_secs(30),
+            imbalance_threshold: 1,
+            max_target_load: 0.85,
+            max_transfers: 4,
+            rate_window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Leader and replicas of one raft group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupLeadership {
+    pub group: RaftGroupId,
+    pub leader: NodeId,
+    /// Every voting member, including the leader
+    pub replicas: Vec<NodeId>,
+}
+
+/// Leadership and load across the cluster at one moment
+#[derive(Debug, Clone, Default)]
+pub struct LeadershipSnapshot {
+    pub groups: Vec<GroupLeadership>,
+    /// Load per node from the monitoring system, 0.0 to 1.0
+    pub node_load: HashMap<NodeId, f64>,
+}
+
+impl LeadershipSnapshot {
+    /// Leaders per node, including nodes that lead nothing
+    pub fn leader_counts(&self) -> HashMap<NodeId, usize> {
+        let mut counts: HashMap<NodeId, usize> = self.node_load.keys().map(|&node| (node, 0)).collect();
+        for group in &self.groups {
+            for &replica in &group.replicas {
+                counts.entry(replica).or_insert(0);
+            }
+        }
+        for group in &self.groups {
+            *counts.entry(group.leader).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Gap between the highest and lowest leader counts
+    pub fn spread(&self) -> usize {
+        spread(&self.leader_counts())
+    }
+}
+
+/// A leadership move for one group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeaderTransfer {
+    pub group: RaftGroupId,
+    pub from: NodeId,
+    pub to: NodeId,
+}
+
+/// Source of leadership and load figures
+#[async_trait::async_trait]
+pub trait LeadershipMetrics: Send + Sync {
+    async fn leadership_snapshot(&self) -> Result<LeadershipSnapshot>;
+}
+
+/// Moves a group's leadership to another replica
+#[async_trait::async_trait]
+pub trait LeadershipTransfer: Send + Sync {
+    async fn transfer_leadership(&self, group: RaftGroupId, from: NodeId, to: NodeId) -> Result<()>;
+}
+
+/// Outcome of one balancing round
+#[derive(Debug, Clone, Default)]
+pub struct BalanceReport {
+    /// Transfers planned this round, in order
+    pub planned: Vec<LeaderTransfer>,
+    /// Transfers issued and acknowledged (always empty in advisory mode)
+    pub applied: Vec<LeaderTransfer>,
+    /// Spread the snapshot had
+    pub spread_before: usize,
+    /// Spread once the planned transfers take effect
+    pub spread_after: usize,
+    /// Planned transfers held back by the rate limit
+    pub rate_limited: bool,
+}
+
+/// Plans and issues leadership transfers
+#[derive(Debug)]
+pub struct LeaderBalancer {
+    config: LeaderBalancerConfig,
+    issued: VecDeque<Instant>,
+    total_transfers: u64,
+}
+
+impl LeaderBalancer {
+    pub fn new(config: LeaderBalancerConfig) -> Self {
+        Self {
+            config,
+            issued: VecDeque::new(),
+            total_transfers: 0,
+        }
+    }
+
+    pub fn config(&self) -> &LeaderBalancerConfig {
+        &self.config
+    }
+
+    /// Transfers issued since the balancer was created
+    pub fn total_transfers(&self) -> u64 {
+        self.total_transfers
+    }
+
+    /// Transfers the rate limit still allows at `now`
+    pub fn transfer_budget(&mut self, now: Instant) -> usize {
+        while let Some(&oldest) = self.issued.front() {
+            if now.saturating_duration_since(oldest) >= self.config.rate_window {
+                self.issued.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.config.max_transfers.saturating_sub(self.issued.len())
+    }
+
+    /// Plan transfers that narrow the spread, up to `budget` of them
+    ///
+    /// Each step moves one group off the node with the most leaders (the
+    /// busier node on a tie) to its replica with the fewest leaders (the idler
+    /// one on a tie), and only when that strictly narrows the gap between the
+    /// two. Planning stops once the spread is within the threshold.
+    pub fn plan(&self, snapshot: &LeadershipSnapshot, budget: usize) -> Vec<LeaderTransfer> {
+        let mut counts = snapshot.leader_counts();
+        let mut leaders: HashMap<RaftGroupId, NodeId> = snapshot.groups.iter()
+            .map(|group| (group.group, group.leader))
+            .collect();
+        let load = |node: &NodeId| snapshot.node_load.get(node).copied().unwrap_or(0.0);
+
+        let mut planned = Vec::new();
+        while planned.len() < budget && spread(&counts) > self.config.imbalance_threshold {
+            // Busiest sources first
+            let mut sources: Vec<NodeId> = counts.keys().copied().collect();
+            sources.sort_by(|a, b| {
+                counts[b].cmp(&counts[a])
+                    .then(load(b).total_cmp(&load(a)))
+                    .then(a.0.cmp(&b.0))
+            });
+
+            let mut step = None;
+            'sources: for source in sources {
+                let source_count = counts[&source];
+                let mut best: Option<(RaftGroupId, NodeId)> = None;
+                for group in &snapshot.groups {
+                    if leaders[&group.group] != source {
+                        continue;
+                    }
+                    for &target in &group.replicas {
+                        if target == source
+                            || counts[&target] + 1 >= source_count
+                            || load(&target) > self.config.max_target_load
+                        {
+                            continue;
+                        }
+                        let better = match best {
+                            None => true,
+                            Some((_, current)) => (counts[&target], load(&target), target.0)
+                                < (counts[&current], load(&current), current.0),
+                        };
+                        if better {
+                            best = Some((group.group, target));
+                        }
+                    }
+                }
+                if let Some((group, target)) = best {
+                    step = Some(LeaderTransfer { group, from: source, to: target });
+                    break 'sources;
+                }
+            }
+
+            let Some(transfer) = step else {
+                break;
+            };
+            *counts.get_mut(&transfer.from).unwrap() -= 1;
+            *counts.get_mut(&transfer.to).unwrap() += 1;
+            leaders.insert(transfer.group, transfer.to);
+            planned.push(transfer);
+        }
+        planned
+    }
+
+    /// Run one balancing round against `snapshot`
+    ///
+    /// In active mode each planned transfer is issued in turn; a failed
+    /// transfer still counts against the rate limit and ends the round, since
+    /// the snapshot no longer describes the cluster.
+    pub async fn balance(
+        &mut self,
+        snapshot: &LeadershipSnapshot,
+        transfer: &dyn LeadershipTransfer,
+        now: Instant,
+    ) -> Result<BalanceReport> {
+        let spread_before = snapshot.spread();
+        let unlimited = self.plan(snapshot, usize::MAX).len();
+        let budget = self.transfer_budget(now);
+        let planned = self.plan(snapshot, budget);
+
+        let mut projected = snapshot.clone();
+        for step in &planned {
+            if let Some(group) = projected.groups.iter_mut().find(|group| group.group == step.group) {
+                group.leader = step.to;
+            }
+        }
+
+        let mut report = BalanceReport {
+            spread_before,
+            spread_after: projected.spread(),
+            rate_limited: planned.len() < unlimited,
+            planned,
+            applied: Vec::new(),
+        };
+
+        match self.config.mode {
+            BalancerMode::Advisory => {
+                for step in &report.planned {
+                    info!("Leader balancer recommends moving group {} from {} to {}", step.group, step.from, step.to);
+                }
+            }
+            BalancerMode::Active => {
+                for &step in &report.planned {
+                    self.issued.push_back(now);
+                    self.total_transfers += 1;
+                    match transfer.transfer_leadership(step.group, step.from, step.to).await {
+                        Ok(()) => {
+                            debug!("Moved leadership of group {} from {} to {}", step.group, step.from, step.to);
+                            report.applied.push(step);
+                        }
+                        Err(error) => {
+                            warn!("Leadership transfer of group {} to {} failed: {}", step.group, step.to, error);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Run a balancing round every `interval` until `shutdown` is notified
+    pub fn start(
+        mut self,
+        metrics: Arc<dyn LeadershipMetrics>,
+        transfer: Arc<dyn LeadershipTransfer>,
+        shutdown: Arc<Notify>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(self.config.interval) => {
+                        let snapshot = match metrics.leadership_snapshot().await {
+                            Ok(snapshot) => snapshot,
+                            Err(error) => {
+                                warn!("Leader balancer could not read leadership metrics: {}", error);
+                                continue;
+                            }
+                        };
+                        if let Err(error) = self.balance(&snapshot, transfer.as_ref(), Instant::now()).await {
+                            warn!("Leader balancing round failed: {}", error);
+                        }
+                    }
+                    _ = shutdown.notified() => {
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn spread(counts: &HashMap<NodeId, usize>) -> usize {
+    match (counts.values().max(), counts.values().min()) {
+        (Some(max), Some(min)) => max - min,
+        _ => 0,
+    }
+}
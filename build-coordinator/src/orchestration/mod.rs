@@ -6,8 +6,22 @@
 pub mod coordinator;
 pub mod aurora_integration;
 pub mod cluster_manager;
+pub mod leader_balancer;
+pub mod consistent_hash;
+pub mod two_phase_commit;
+pub mod client;
 
 // Re-export main types
 pub use coordinator::Coordinator;
 pub use aurora_integration::AuroraClusterManager;
 pub use cluster_manager::ClusterManager;
+pub use leader_balancer::{
+    BalanceReport, BalancerMode, GroupLeadership, LeaderBalancer, LeaderBalancerConfig, LeaderTransfer,
+    LeadershipMetrics, LeadershipSnapshot, LeadershipTransfer, RaftGroupId,
+};
+pub use consistent_hash::{HashRing, KeyMove, MovedRange, RingChange, DEFAULT_VIRTUAL_NODES};
+pub use two_phase_commit::{
+    RecoveryReport, ReplicatedTransactionLog, ShardParticipant, TransactionLog, TransactionOutcome,
+    TwoPhaseCommitConfig, TwoPhaseCommitCoordinator, Vote,
+};
+pub use client::{ConsensusReplica, CoordinatorClient, SessionConfig, SessionRead, SessionToken};
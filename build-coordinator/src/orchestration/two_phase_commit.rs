@@ -0,0 +1,324 @@
+//! Two-Phase Commit: Atomic Transactions Across AuroraDB Shards
+//!
+//! A transaction that wrote to several shards commits on all of them or on
+//! none. The coordinator runs classic two-phase commit:
+//! - **Prepare**: Every participating shard is asked to prepare. A shard
+//!   that votes no, fails, or does not answer within `prepare_timeout`
+//!   counts as a no
+//! - **Decide**: Commit if every shard voted yes, otherwise abort. The
+//!   decision is written to the transaction log before any shard hears it
+//! - **Complete**: The decision is sent to every shard; once all of them
+//!   acknowledged it, an end record closes the transaction
+//!
+//! The log holds a begin record naming the participants, the decision and
+//! the end record. After a coordinator crash, [`TwoPhaseCommitCoordinator::recover`]
+//! replays it: a transaction with a decision but no end record has the
+//! decision sent again, and one without a decision is aborted (presumed
+//! abort), since no shard can have been told to commit it. Shards must
+//! treat a repeated commit or abort as a no-op.
+//!
+//! The log is a [`TransactionLog`]: the consensus [`LogManager`] on its own,
+//! or [`ReplicatedTransactionLog`], which also replicates every record
+//! through the consensus engine so another coordinator can take over.
+
+use crate::consensus::{HybridConsensus, LogManager};
+use crate::error::{Error, Result};
+use crate::types::{LogData, LogEntry, NodeId, TransactionEntry, TransactionState};
+
+use futures::future::join_all;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+/// Two-phase commit configuration
+#[derive(Debug, Clone)]
+pub struct TwoPhaseCommitConfig {
+    /// How long a shard may take to vote before it counts as a no
+    pub prepare_timeout: Duration,
+
+    /// Transaction timeout recorded in the log
+    pub transaction_timeout: Duration,
+}
+
+impl Default for TwoPhaseCommitConfig {
+    fn default() -> Self {
+        Self {
+            prepare_timeout: Duration::from_secs(5),
+            transaction_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+/// A shard's answer to prepare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vote {
+    /// Prepared: the shard can commit whatever happens to it
+    Yes,
+    /// The shard cannot commit; the transaction must abort
+    No,
+}
+
+/// The part of a distributed transaction one shard holds
+#[async_trait::async_trait]
+pub trait ShardParticipant: Send + Sync {
+    /// Make the transaction's writes durable and lock them, then vote
+    async fn prepare(&self, transaction_id: &str) -> Result<Vote>;
+
+    /// Commit a prepared transaction; repeating it must be harmless
+    async fn commit(&self, transaction_id: &str) -> Result<()>;
+
+    /// Roll the transaction back; repeating it, or aborting a transaction
+    /// the shard never prepared, must be harmless
+    async fn abort(&self, transaction_id: &str) -> Result<()>;
+}
+
+/// Durable record of the coordinator's transactions
+#[async_trait::async_trait]
+pub trait TransactionLog: Send + Sync {
+    /// Append a record; it must survive a crash once this returns
+    async fn append(&self, entry: TransactionEntry) -> Result<()>;
+
+    /// Every record, oldest first
+    async fn entries(&self) -> Result<Vec<TransactionEntry>>;
+}
+
+#[async_trait::async_trait]
+impl TransactionLog for LogManager {
+    async fn append(&self, entry: TransactionEntry) -> Result<()> {
+        self.append_durable(transaction_log_entry(entry)).await?;
+        Ok(())
+    }
+
+    async fn entries(&self) -> Result<Vec<TransactionEntry>> {
+        let entries = self.get_range(1, self.last_index().await).await?;
+        Ok(entries.into_iter()
+            .filter_map(|entry| match entry.data {
+                LogData::Transaction(transaction) => Some(transaction),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+/// Transaction log replicated through consensus. Each record is proposed
+/// to the consensus engine before it is written locally, so the decision
+/// survives the loss of this coordinator's node as well as a restart.
+pub struct ReplicatedTransactionLog {
+    consensus: Arc<HybridConsensus>,
+    local: Arc<LogManager>,
+}
+
+impl ReplicatedTransactionLog {
+    pub fn new(consensus: Arc<HybridConsensus>, local: Arc<LogManager>) -> Self {
+        Self { consensus, local }
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionLog for ReplicatedTransactionLog {
+    async fn append(&self, entry: TransactionEntry) -> Result<()> {
+        self.consensus.propose(transaction_log_entry(entry.clone())).await?;
+        TransactionLog::append(self.local.as_ref(), entry).await
+    }
+
+    async fn entries(&self) -> Result<Vec<TransactionEntry>> {
+        self.local.entries().await
+    }
+}
+
+/// Consensus log entry carrying a transaction record
+fn transaction_log_entry(entry: TransactionEntry) -> LogEntry {
+    LogEntry {
+        // Assigned on append
+        index: 0,
+        term: 0,
+        data: LogData::Transaction(entry),
+        timestamp: SystemTime::now(),
+    }
+}
+
+/// How a transaction ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    /// Committed on every shard. Shards listed in `pending` did not
+    /// acknowledge yet and get the commit again on recovery
+    Committed { pending: Vec<NodeId> },
+    /// Aborted, because of the `no_votes` shards
+    Aborted { no_votes: Vec<NodeId> },
+}
+
+/// In-doubt transactions resolved by [`TwoPhaseCommitCoordinator::recover`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    pub committed: Vec<String>,
+    pub aborted: Vec<String>,
+    /// Still unresolved because a shard did not acknowledge
+    pub unresolved: Vec<String>,
+}
+
+/// Runs two-phase commit across registered shards
+pub struct TwoPhaseCommitCoordinator {
+    config: TwoPhaseCommitConfig,
+    log: Arc<dyn TransactionLog>,
+    shards: RwLock<HashMap<NodeId, Arc<dyn ShardParticipant>>>,
+}
+
+impl TwoPhaseCommitCoordinator {
+    /// Create a coordinator; call [`recover`](Self::recover) once the
+    /// shards are registered to finish what a previous run left in doubt
+    pub fn new(config: TwoPhaseCommitConfig, log: Arc<dyn TransactionLog>) -> Self {
+        Self { config, log, shards: RwLock::new(HashMap::new()) }
+    }
+
+    /// Register the participant for a shard
+    pub async fn register_shard(&self, node_id: NodeId, participant: Arc<dyn ShardParticipant>) {
+        self.shards.write().await.insert(node_id, participant);
+    }
+
+    /// Commit `transaction_id` atomically across `shards`. An error means
+    /// the outcome could not be logged; the transaction is then in doubt
+    /// until [`recover`](Self::recover) resolves it.
+    pub async fn execute(&self, transaction_id: &str, shards: &[NodeId]) -> Result<TransactionOutcome> {
+        let participants = self.participants(shards).await?;
+        self.log_state(transaction_id, shards, TransactionState::Starting).await?;
+
+        // Phase 1
+        let votes = join_all(participants.iter().map(|(node_id, shard)| async move {
+            let vote = match tokio::time::timeout(self.config.prepare_timeout, shard.prepare(transaction_id)).await {
+                Ok(Ok(vote)) => vote,
+                Ok(Err(e)) => {
+                    warn!("Shard {} failed to prepare transaction {}: {}", node_id, transaction_id, e);
+                    Vote::No
+                }
+                Err(_) => {
+                    warn!("Shard {} timed out preparing transaction {}", node_id, transaction_id);
+                    Vote::No
+                }
+            };
+            (*node_id, vote)
+        })).await;
+        let no_votes: Vec<NodeId> = votes.iter()
+            .filter(|(_, vote)| *vote == Vote::No)
+            .map(|(node_id, _)| *node_id)
+            .collect();
+
+        // The logged decision is final
+        if !no_votes.is_empty() {
+            self.log_state(transaction_id, shards, TransactionState::Aborting).await?;
+            self.complete(transaction_id, shards, &participants, false).await?;
+            info!("Aborted transaction {}: shards {:?} voted no", transaction_id, no_votes);
+            return Ok(TransactionOutcome::Aborted { no_votes });
+        }
+        self.log_state(transaction_id, shards, TransactionState::Committing).await?;
+
+        // Phase 2
+        let pending = self.complete(transaction_id, shards, &participants, true).await?;
+        info!("Committed transaction {} on {} shards", transaction_id, shards.len());
+        Ok(TransactionOutcome::Committed { pending })
+    }
+
+    /// Transactions the log leaves without an end record
+    pub async fn in_doubt(&self) -> Result<Vec<String>> {
+        Ok(self.latest_entries().await?
+            .into_iter()
+            .filter(|(_, entry)| !matches!(entry.state, TransactionState::Committed | TransactionState::Aborted))
+            .map(|(transaction_id, _)| transaction_id)
+            .collect())
+    }
+
+    /// Resolve the transactions a previous run left in doubt: finish the
+    /// logged decisions, and abort transactions that never reached one
+    pub async fn recover(&self) -> Result<RecoveryReport> {
+        let mut report = RecoveryReport::default();
+        for (transaction_id, entry) in self.latest_entries().await? {
+            let commit = match entry.state {
+                TransactionState::Committed | TransactionState::Aborted => continue,
+                TransactionState::Committing => true,
+                TransactionState::Aborting => false,
+                TransactionState::Starting | TransactionState::Prepared => {
+                    // Presumed abort: no shard was told to commit
+                    self.log_state(&transaction_id, &entry.participants, TransactionState::Aborting).await?;
+                    false
+                }
+            };
+
+            let participants = self.participants(&entry.participants).await?;
+            let pending = self.complete(&transaction_id, &entry.participants, &participants, commit).await?;
+            if !pending.is_empty() {
+                report.unresolved.push(transaction_id);
+            } else if commit {
+                report.committed.push(transaction_id);
+            } else {
+                report.aborted.push(transaction_id);
+            }
+        }
+
+        if report != RecoveryReport::default() {
+            info!("Recovered in-doubt transactions: {} committed, {} aborted, {} unresolved",
+                  report.committed.len(), report.aborted.len(), report.unresolved.len());
+        }
+        Ok(report)
+    }
+
+    /// Send the decision to every shard and, once all acknowledged, write
+    /// the end record. Returns the shards that did not acknowledge
+    async fn complete(
+        &self,
+        transaction_id: &str,
+        shards: &[NodeId],
+        participants: &[(NodeId, Arc<dyn ShardParticipant>)],
+        commit: bool,
+    ) -> Result<Vec<NodeId>> {
+        let acks = join_all(participants.iter().map(|(node_id, shard)| async move {
+            let result = if commit { shard.commit(transaction_id).await } else { shard.abort(transaction_id).await };
+            if let Err(e) = &result {
+                warn!("Shard {} did not acknowledge {} of transaction {}: {}",
+                      node_id, if commit { "commit" } else { "abort" }, transaction_id, e);
+            }
+            (*node_id, result.is_ok())
+        })).await;
+        let pending: Vec<NodeId> = acks.into_iter().filter(|(_, acked)| !acked).map(|(node_id, _)| node_id).collect();
+
+        if pending.is_empty() {
+            let end = if commit { TransactionState::Committed } else { TransactionState::Aborted };
+            self.log_state(transaction_id, shards, end).await?;
+        }
+        Ok(pending)
+    }
+
+    /// Registered participants for `shards`, in order
+    async fn participants(&self, shards: &[NodeId]) -> Result<Vec<(NodeId, Arc<dyn ShardParticipant>)>> {
+        let registered = self.shards.read().await;
+        shards.iter()
+            .map(|node_id| {
+                registered.get(node_id)
+                    .map(|shard| (*node_id, shard.clone()))
+                    .ok_or_else(|| Error::AuroraDb {
+                        message: format!("No participant registered for shard {}", node_id),
+                        database: None,
+                    })
+            })
+            .collect()
+    }
+
+    async fn log_state(&self, transaction_id: &str, shards: &[NodeId], state: TransactionState) -> Result<()> {
+        debug!("Transaction {} is {:?}", transaction_id, state);
+        self.log.append(TransactionEntry {
+            transaction_id: transaction_id.to_string(),
+            state,
+            participants: shards.to_vec(),
+            timeout: self.config.transaction_timeout,
+        }).await
+    }
+
+    /// Latest record of each logged transaction, by id
+    async fn latest_entries(&self) -> Result<BTreeMap<String, TransactionEntry>> {
+        let mut latest = BTreeMap::new();
+        for entry in self.log.entries().await? {
+            latest.insert(entry.transaction_id.clone(), entry);
+        }
+        Ok(latest)
+    }
+}
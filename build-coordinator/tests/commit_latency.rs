@@ -0,0 +1,103 @@
+//! Commit Latency Breakdown Tests
+//!
+//! Drives operations through a simulated commit path whose stages take
+//! known times, marking each stage as it ends, and checks the per-operation
+//! breakdown adds up to the commit latency seen by the proposer.
+
+use aurora_coordinator::consensus::{CommitLatencyTracker, CommitStage, LogIndex};
+use std::time::{Duration, Instant};
+
+/// Commit latency test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Time each stage of the simulated commit path takes
+    struct StageTimes {
+        queue: Duration,
+        replication: Duration,
+        fsync: Option<Duration>,
+        apply: Duration,
+    }
+
+    /// Commit `index` through the simulated path; returns the latency the
+    /// proposer observed
+    async fn commit(tracker: &CommitLatencyTracker, index: LogIndex, times: &StageTimes) -> Duration {
+        let proposed_at = Instant::now();
+        tokio::time::sleep(times.queue).await;
+        tracker.proposed(index, proposed_at);
+        if let Some(fsync) = times.fsync {
+            tokio::time::sleep(fsync).await;
+            tracker.synced(index);
+        }
+        tokio::time::sleep(times.replication).await;
+        tracker.replicated(index);
+        tokio::time::sleep(times.apply).await;
+        tracker.applied(index);
+        proposed_at.elapsed()
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[tokio::test]
+    async fn test_breakdown_sums_to_commit_latency() {
+        let tracker = CommitLatencyTracker::default();
+        let times = StageTimes { queue: ms(2), replication: ms(8), fsync: Some(ms(4)), apply: ms(1) };
+
+        let mut observed = Vec::new();
+        for index in 1..=20 {
+            observed.push(commit(&tracker, index, &times).await);
+        }
+
+        for (index, observed) in (1..=20).zip(observed) {
+            let breakdown = tracker.breakdown(index).unwrap();
+            let stages: Duration = CommitStage::ALL.iter().map(|stage| breakdown.stage(*stage)).sum();
+            assert_eq!(stages, breakdown.total);
+            assert!(breakdown.total <= observed);
+            assert!(observed - breakdown.total < ms(1), "{:?} observed, {:?} recorded", observed, breakdown.total);
+
+            assert!(breakdown.propose_queue >= times.queue);
+            assert!(breakdown.replication >= times.replication);
+            assert!(breakdown.fsync.unwrap() >= ms(4));
+            assert!(breakdown.apply >= times.apply);
+        }
+
+        let summary = tracker.summary();
+        assert_eq!(summary.operations, 20);
+        for stage in CommitStage::ALL {
+            assert_eq!(summary.stage(stage).count, 20, "{:?}", stage);
+        }
+        assert_eq!(summary.dominant_stage(), Some(CommitStage::Replication));
+        let stage_means: f64 = CommitStage::ALL.iter().map(|stage| summary.stage(*stage).mean.unwrap()).sum();
+        let total_mean = summary.total.mean.unwrap();
+        assert!((stage_means - total_mean).abs() < total_mean * 0.01, "{} vs {}", stage_means, total_mean);
+    }
+
+    #[tokio::test]
+    async fn test_apply_bound_commits_without_fsync() {
+        let tracker = CommitLatencyTracker::default();
+        let times = StageTimes { queue: ms(1), replication: ms(2), fsync: None, apply: ms(10) };
+        for index in 1..=5 {
+            commit(&tracker, index, &times).await;
+        }
+
+        let breakdown = tracker.breakdown(3).unwrap();
+        assert_eq!(breakdown.fsync, None);
+        assert!(breakdown.apply >= ms(10));
+
+        let summary = tracker.summary();
+        assert_eq!(summary.stage(CommitStage::Fsync).count, 0);
+        assert_eq!(summary.stage(CommitStage::Apply).count, 5);
+        assert_eq!(summary.dominant_stage(), Some(CommitStage::Apply));
+
+        // An entry that never commits leaves no trace
+        tracker.proposed(6, Instant::now());
+        tracker.abandon(6);
+        tracker.applied(6);
+        assert_eq!(tracker.breakdown(6), None);
+        assert_eq!(tracker.recent().len(), 5);
+        assert_eq!(tracker.summary().operations, 5);
+    }
+}
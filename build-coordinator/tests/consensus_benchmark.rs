@@ -0,0 +1,100 @@
+//! Consensus Benchmark Harness Tests
+//!
+//! Runs a short offered-load sweep against an in-memory consensus target and
+//! validates the shape of the produced report.
+
+use aurora_coordinator::error::Result;
+use aurora_coordinator::monitoring::consensus_harness::{
+    ConsensusBenchmarkReport, ConsensusHarness, ConsensusHarnessConfig, ConsensusTarget, TargetFactory,
+};
+use aurora_coordinator::types::LogEntry;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Consensus harness test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Commits immediately after a small simulated replication delay
+    struct InMemoryTarget {
+        commit_index: AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl ConsensusTarget for InMemoryTarget {
+        async fn propose_batch(&self, entries: Vec<LogEntry>) -> Result<u64> {
+            tokio::time::sleep(Duration::from_micros(50)).await;
+            Ok(self.commit_index.fetch_add(entries.len() as u64, Ordering::SeqCst) + entries.len() as u64)
+        }
+    }
+
+    fn in_memory_factory() -> TargetFactory {
+        Box::new(|_cluster_size| {
+            Box::pin(async {
+                Ok(Box::new(InMemoryTarget { commit_index: AtomicU64::new(0) }) as Box<dyn ConsensusTarget>)
+            })
+        })
+    }
+
+    fn short_config() -> ConsensusHarnessConfig {
+        ConsensusHarnessConfig {
+            cluster_sizes: vec![3],
+            batch_sizes: vec![1, 8],
+            payload_sizes: vec![64],
+            offered_load: 2_000,
+            duration: Duration::from_millis(200),
+            warmup: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    /// A short run reports percentiles and non-zero throughput for every sweep point
+    #[tokio::test]
+    async fn test_short_benchmark_report() {
+        println!("🧪 Testing consensus benchmark harness...");
+
+        let harness = ConsensusHarness::new(short_config(), in_memory_factory());
+        let report = harness.run().await.unwrap();
+
+        assert_eq!(report.runs.len(), 2, "one run per sweep point");
+        for run in &report.runs {
+            assert!(run.throughput_ops_per_sec > 0.0, "throughput should be non-zero for {}", run.key());
+            assert!(run.committed_entries > 0);
+            assert!(run.latency.p50_ns > 0);
+            assert!(run.latency.p99_ns >= run.latency.p50_ns);
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        let latency = &json["runs"][0]["latency"];
+        for field in ["p50_ns", "p95_ns", "p99_ns", "p999_ns", "mean_ns", "max_ns"] {
+            assert!(latency.get(field).is_some(), "report should contain {}", field);
+        }
+        assert!(json["runs"][0]["throughput_ops_per_sec"].as_f64().unwrap() > 0.0);
+
+        assert!(report.summary().contains("n3-b8-p64"));
+        println!("✅ Consensus benchmark harness test passed");
+    }
+
+    /// Saved baselines round-trip and flag throughput drops
+    #[tokio::test]
+    async fn test_baseline_regression_detection() {
+        let harness = ConsensusHarness::new(short_config(), in_memory_factory());
+        let report = harness.run().await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("consensus-baseline-{}.json", std::process::id()));
+        report.save_baseline(&path).unwrap();
+        let baseline = ConsensusBenchmarkReport::load_baseline(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(report.regressions_against(&baseline, 0.5).is_empty());
+
+        let mut degraded = report.clone();
+        for run in &mut degraded.runs {
+            run.throughput_ops_per_sec /= 4.0;
+        }
+        let regressions = degraded.regressions_against(&baseline, 0.5);
+        assert_eq!(regressions.len(), 2);
+        assert!(regressions.iter().all(|r| r.metric == "throughput_ops_per_sec"));
+    }
+}
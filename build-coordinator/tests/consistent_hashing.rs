@@ -0,0 +1,148 @@
+//! Consistent Hashing Tests
+//!
+//! Places a large keyspace on weighted hash rings and checks that membership
+//! changes move only the keys they must, that the reported ring changes name
+//! exactly those keys, and that weights skew placement proportionally.
+
+use aurora_coordinator::orchestration::{HashRing, RingChange};
+use aurora_coordinator::types::NodeId;
+use std::collections::HashMap;
+
+/// Consistent hashing test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: usize = 100_000;
+
+    fn keys() -> Vec<String> {
+        (0..KEYS).map(|i| format!("user:{}", i)).collect()
+    }
+
+    /// Ring of nodes 1..=`nodes`, all of weight 1.0
+    fn ring(nodes: u64) -> HashRing {
+        let mut ring = HashRing::default();
+        for node in 1..=nodes {
+            ring.add_node(NodeId(node), 1.0).unwrap();
+        }
+        ring
+    }
+
+    fn placement(ring: &HashRing, keys: &[String]) -> Vec<NodeId> {
+        keys.iter().map(|key| ring.node_for(key).unwrap()).collect()
+    }
+
+    fn counts(placement: &[NodeId]) -> HashMap<NodeId, usize> {
+        let mut counts = HashMap::new();
+        for node in placement {
+            *counts.entry(*node).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Keys whose owner differs between two placements
+    fn moved(before: &[NodeId], after: &[NodeId], keys: &[String]) -> Vec<String> {
+        keys.iter().zip(before.iter().zip(after))
+            .filter(|(_, (old, new))| old != new)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn reported(change: &RingChange, keys: &[String]) -> Vec<String> {
+        change.affected_keys(keys.iter()).into_iter().map(|key_move| key_move.key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_adding_node_moves_one_in_n_plus_one_keys() {
+        let keys = keys();
+        for nodes in [3u64, 10] {
+            let mut ring = ring(nodes);
+            let before = placement(&ring, &keys);
+
+            let new_node = NodeId(nodes + 1);
+            let change = ring.add_node(new_node, 1.0).unwrap();
+            let after = placement(&ring, &keys);
+            let moved = moved(&before, &after, &keys);
+
+            let expected = KEYS as f64 / (nodes + 1) as f64;
+            let fraction = moved.len() as f64 / expected;
+            assert!((0.8..1.2).contains(&fraction), "{} nodes: moved {} keys, expected about {}", nodes, moved.len(), expected);
+
+            // Every moved key went to the new node, and the change names exactly them
+            assert!(keys.iter().zip(&after).zip(&before)
+                .all(|((_, new), old)| new == old || *new == new_node));
+            assert_eq!(reported(&change, &keys), moved);
+            assert!(change.affected_keys(keys.iter()).iter().all(|key_move| key_move.to == Some(new_node)));
+        }
+    }
+
+    #[test]
+    fn test_removing_node_moves_only_its_keys() {
+        let keys = keys();
+        let mut ring = ring(5);
+        let before = placement(&ring, &keys);
+
+        let change = ring.remove_node(NodeId(3)).unwrap();
+        let after = placement(&ring, &keys);
+        let moved = moved(&before, &after, &keys);
+
+        let owned: Vec<String> = keys.iter().zip(&before)
+            .filter(|(_, node)| **node == NodeId(3))
+            .map(|(key, _)| key.clone())
+            .collect();
+        assert_eq!(moved, owned);
+        assert_eq!(reported(&change, &keys), moved);
+        assert!(after.iter().all(|node| *node != NodeId(3)));
+
+        assert!(ring.remove_node(NodeId(3)).is_none());
+        assert!(HashRing::default().node_for("anything").is_none());
+    }
+
+    #[test]
+    fn test_weights_skew_placement_proportionally() {
+        let keys = keys();
+        let mut ring = HashRing::default();
+        ring.add_node(NodeId(1), 1.0).unwrap();
+        ring.add_node(NodeId(2), 1.0).unwrap();
+        ring.add_node(NodeId(3), 2.0).unwrap();
+        ring.add_node(NodeId(4), 4.0).unwrap();
+
+        let counts = counts(&placement(&ring, &keys));
+        let unit = (counts[&NodeId(1)] + counts[&NodeId(2)]) as f64 / 2.0;
+        for (node, weight) in [(NodeId(3), 2.0), (NodeId(4), 4.0)] {
+            let ratio = counts[&node] as f64 / unit;
+            assert!((ratio / weight - 1.0).abs() < 0.2, "{} has {:.2}x the keys of a weight-1 node", node, ratio);
+        }
+
+        // Halving a node's weight moves keys only off it
+        let before = placement(&ring, &keys);
+        let change = ring.add_node(NodeId(4), 2.0).unwrap();
+        let after = placement(&ring, &keys);
+        assert!(before.iter().zip(&after).all(|(old, new)| old == new || *old == NodeId(4)));
+        assert_eq!(reported(&change, &keys), moved(&before, &after, &keys));
+
+        assert!(ring.add_node(NodeId(5), 0.0).is_err());
+        assert!(ring.add_node(NodeId(5), f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_replica_placement_is_distinct_and_stable() {
+        let mut ring = ring(4);
+        let replicas = ring.nodes_for("shard-17", 3);
+        assert_eq!(replicas.len(), 3);
+        assert_eq!(replicas[0], ring.node_for("shard-17").unwrap());
+        assert!(replicas.iter().all(|node| replicas.iter().filter(|other| *other == node).count() == 1));
+        assert_eq!(ring.nodes_for("shard-17", 10).len(), 4);
+
+        // Placement depends only on membership, not on the order it was built in
+        let mut reversed = HashRing::default();
+        for node in (1..=4).rev() {
+            reversed.add_node(NodeId(node), 1.0).unwrap();
+        }
+        assert_eq!(reversed.nodes_for("shard-17", 3), replicas);
+
+        ring.add_node(NodeId(9), 1.0).unwrap();
+        ring.remove_node(NodeId(9)).unwrap();
+        assert_eq!(ring.nodes_for("shard-17", 3), replicas);
+    }
+}
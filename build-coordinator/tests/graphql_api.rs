@@ -0,0 +1,187 @@
+//! GraphQL API Tests
+//!
+//! Runs queries and mutations against the schema backed by an in-memory
+//! cluster of three voters, one of them leading, and checks that clients
+//! get exactly the fields they select and that mutations need a token.
+
+use aurora_coordinator::api::graphql_api::{
+    AddNodeInput, Caller, ClusterBackend, ConsensusSummary, GraphQLAPI, GraphQLConfig, MemberKind, NodeMetrics,
+    NodeSummary,
+};
+use aurora_coordinator::error::Result;
+use aurora_coordinator::types::{NodeId, NodeRole, NodeStatus};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// GraphQL API test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADMIN_TOKEN: &str = "admin-secret";
+
+    struct SimulatedCluster {
+        nodes: Mutex<Vec<NodeSummary>>,
+        learners: Mutex<Vec<NodeId>>,
+    }
+
+    impl SimulatedCluster {
+        fn new() -> Self {
+            let node = |id: u64, role: NodeRole, region: &str| NodeSummary {
+                id: NodeId(id),
+                name: format!("aurora-node-{}", id),
+                address: format!("10.0.0.{}:7946", id),
+                role,
+                status: NodeStatus::Healthy,
+                region: Some(region.to_string()),
+            };
+            Self {
+                nodes: Mutex::new(vec![
+                    node(1, NodeRole::Leader, "us-east"),
+                    node(2, NodeRole::Follower, "us-east"),
+                    node(3, NodeRole::Follower, "eu-west"),
+                ]),
+                learners: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ClusterBackend for SimulatedCluster {
+        async fn nodes(&self) -> Result<Vec<NodeSummary>> {
+            Ok(self.nodes.lock().unwrap().clone())
+        }
+
+        async fn consensus_state(&self) -> Result<ConsensusSummary> {
+            Ok(ConsensusSummary {
+                term: 7,
+                leader: Some(NodeId(1)),
+                commit_index: 1042,
+                last_applied: 1040,
+                voters: vec![NodeId(1), NodeId(2), NodeId(3)],
+                learners: self.learners.lock().unwrap().clone(),
+            })
+        }
+
+        async fn node_metrics(&self, node_id: NodeId) -> Result<Option<NodeMetrics>> {
+            Ok(Some(NodeMetrics {
+                cpu_usage_percent: 10.0 * node_id.0 as f64,
+                memory_usage_mb: 2048.0,
+                throughput_tps: 500.0,
+                latency_p95_ms: 4.5,
+            }))
+        }
+
+        async fn add_node(&self, node: AddNodeInput) -> Result<NodeSummary> {
+            let role = match node.kind {
+                MemberKind::Learner => NodeRole::Learner,
+                MemberKind::Voter => NodeRole::Follower,
+            };
+            let summary = NodeSummary {
+                id: NodeId(node.id),
+                name: node.name.unwrap_or_else(|| format!("aurora-node-{}", node.id)),
+                address: node.address,
+                role,
+                status: NodeStatus::Healthy,
+                region: node.region,
+            };
+            if node.kind == MemberKind::Learner {
+                self.learners.lock().unwrap().push(summary.id);
+            }
+            self.nodes.lock().unwrap().push(summary.clone());
+            Ok(summary)
+        }
+
+        async fn remove_node(&self, node_id: NodeId) -> Result<bool> {
+            let mut nodes = self.nodes.lock().unwrap();
+            let before = nodes.len();
+            nodes.retain(|node| node.id != node_id);
+            Ok(nodes.len() < before)
+        }
+    }
+
+    fn api() -> GraphQLAPI {
+        let config = GraphQLConfig { admin_tokens: vec![ADMIN_TOKEN.to_string()] };
+        GraphQLAPI::new("127.0.0.1:0", Arc::new(SimulatedCluster::new()), config)
+    }
+
+    #[tokio::test]
+    async fn test_query_returns_only_selected_fields() {
+        let api = api();
+        let query = r#"{
+            nodes(region: "us-east") { id name role metrics { cpuUsagePercent } }
+            consensus { term commitIndex leader { name region } }
+            regions
+        }"#;
+        let response = api.execute(query, Caller { admin: false }).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data, json!({
+            "nodes": [
+                { "id": 1, "name": "aurora-node-1", "role": "LEADER", "metrics": { "cpuUsagePercent": 10.0 } },
+                { "id": 2, "name": "aurora-node-2", "role": "FOLLOWER", "metrics": { "cpuUsagePercent": 20.0 } },
+            ],
+            "consensus": { "term": 7, "commitIndex": 1042, "leader": { "name": "aurora-node-1", "region": "us-east" } },
+            "regions": ["eu-west", "us-east"],
+        }));
+
+        // Role filters and single-node lookups
+        let response = api.execute("{ nodes(role: FOLLOWER) { id } node(id: 3) { address health } }", Caller { admin: false }).await;
+        assert_eq!(response.data.into_json().unwrap(), json!({
+            "nodes": [{ "id": 2 }, { "id": 3 }],
+            "node": { "address": "10.0.0.3:7946", "health": "HEALTHY" },
+        }));
+
+        assert!(api.sdl().contains("addNode(input: AddNodeInput!): Node!"));
+    }
+
+    #[tokio::test]
+    async fn test_add_learner_requires_admin_token() {
+        let api = api();
+        let mutation = r#"mutation { addNode(input: { id: 4, address: "10.0.0.4:7946", region: "eu-west" }) { id role } }"#;
+
+        // Without a token, or with the wrong one, nothing changes
+        for authorization in [None, Some("Bearer wrong"), Some(ADMIN_TOKEN)] {
+            let response = api.execute(mutation, api.caller(authorization)).await;
+            assert_eq!(response.errors.len(), 1, "{:?}", authorization);
+            assert!(response.errors[0].message.contains("Unauthorized"));
+        }
+        let response = api.execute("{ nodes { id } }", Caller { admin: false }).await;
+        assert_eq!(response.data.into_json().unwrap()["nodes"].as_array().unwrap().len(), 3);
+
+        // With it, the node joins as a learner by default
+        let response = api.execute(mutation, api.caller(Some("Bearer admin-secret"))).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(response.data.into_json().unwrap(), json!({ "addNode": { "id": 4, "role": "LEARNER" } }));
+
+        let response = api.execute("{ consensus { voters learners } node(id: 4) { region } }", Caller { admin: false }).await;
+        assert_eq!(response.data.into_json().unwrap(), json!({
+            "consensus": { "voters": [1, 2, 3], "learners": [4] },
+            "node": { "region": "eu-west" },
+        }));
+
+        let response = api.execute("mutation { removeNode(id: 4) }", Caller { admin: true }).await;
+        assert_eq!(response.data.into_json().unwrap(), json!({ "removeNode": true }));
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoint_reads_bearer_token() {
+        let routes = api().routes();
+        let body = json!({ "query": "mutation { removeNode(id: 2) }" });
+
+        let reply = warp::test::request().method("POST").path("/api/v1/graphql").json(&body).reply(&routes).await;
+        let response: serde_json::Value = serde_json::from_slice(reply.body()).unwrap();
+        assert!(response["errors"][0]["message"].as_str().unwrap().contains("Unauthorized"));
+
+        let reply = warp::test::request()
+            .method("POST")
+            .path("/api/v1/graphql")
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .json(&body)
+            .reply(&routes)
+            .await;
+        let response: serde_json::Value = serde_json::from_slice(reply.body()).unwrap();
+        assert_eq!(response["data"], json!({ "removeNode": true }));
+    }
+}
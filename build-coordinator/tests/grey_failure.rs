@@ -0,0 +1,138 @@
+//! Grey Failure Tests
+//!
+//! Routes simulated requests across three peers through the detector, with
+//! extra latency injected on one of them for a while, and checks that its
+//! traffic is shed while it is slow and ramps back once it recovers.
+
+use aurora_coordinator::networking::{GreyFailureConfig, GreyFailureDetector, GreyFailureSignal, PeerHealth};
+use aurora_coordinator::types::NodeId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Grey-failure test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOW_PEER: NodeId = NodeId(2);
+
+    /// Peers 1..=3, one request per millisecond of simulated time
+    struct SimulatedCluster {
+        detector: GreyFailureDetector,
+        peers: Vec<NodeId>,
+        now: Instant,
+        injected: Duration,
+        requests: u64,
+        signals: Vec<GreyFailureSignal>,
+    }
+
+    impl SimulatedCluster {
+        fn new() -> Self {
+            let config = GreyFailureConfig {
+                window: 100,
+                sample_ttl: Duration::from_secs(1),
+                recovery_interval: Duration::from_millis(200),
+                ..GreyFailureConfig::default()
+            };
+            Self {
+                detector: GreyFailureDetector::new(config),
+                peers: (1..=3).map(NodeId).collect(),
+                now: Instant::now(),
+                injected: Duration::ZERO,
+                requests: 0,
+                signals: Vec::new(),
+            }
+        }
+
+        /// Send `count` requests and return how many each peer served
+        fn run(&mut self, count: u64) -> HashMap<NodeId, u64> {
+            let mut served = HashMap::new();
+            for _ in 0..count {
+                self.now += Duration::from_millis(1);
+                self.requests += 1;
+                let peer = self.detector.pick(&self.peers, self.now).unwrap();
+                *served.entry(peer).or_insert(0) += 1;
+
+                // 2-3ms normally, with the injected delay on the slow peer
+                let mut latency = Duration::from_micros(2_000 + (self.requests * 7919) % 1_000);
+                if peer == SLOW_PEER {
+                    latency += self.injected;
+                }
+                self.signals.extend(self.detector.record(peer, latency, self.now));
+            }
+            served
+        }
+
+        fn share(served: &HashMap<NodeId, u64>, peer: NodeId) -> f64 {
+            *served.get(&peer).unwrap_or(&0) as f64 / served.values().sum::<u64>() as f64
+        }
+    }
+
+    #[test]
+    fn test_slow_peer_is_shed_and_restored() {
+        let mut cluster = SimulatedCluster::new();
+
+        let served = cluster.run(1_000);
+        let share = SimulatedCluster::share(&served, SLOW_PEER);
+        assert!((share - 1.0 / 3.0).abs() < 0.01, "share before injection: {}", share);
+        assert!(cluster.signals.is_empty());
+
+        // Slow but alive: every request still completes
+        cluster.injected = Duration::from_millis(40);
+        cluster.run(500);
+        assert_eq!(cluster.detector.health(SLOW_PEER), PeerHealth::Degraded);
+        assert_eq!(cluster.detector.degraded_peers(), vec![SLOW_PEER]);
+        match cluster.signals.as_slice() {
+            [GreyFailureSignal::Degraded { node_id, latency, baseline }] => {
+                assert_eq!(*node_id, SLOW_PEER);
+                assert!(*latency > *baseline * 3, "{:?} against {:?}", latency, baseline);
+            }
+            signals => panic!("expected one degraded signal, got {:?}", signals),
+        }
+
+        let served = cluster.run(2_000);
+        let share = SimulatedCluster::share(&served, SLOW_PEER);
+        assert!(share > 0.0 && share < 0.05, "share while slow: {}", share);
+
+        // Latency recovers; traffic ramps back rather than jumping to full
+        cluster.injected = Duration::ZERO;
+        let mut weights = Vec::new();
+        while cluster.detector.health(SLOW_PEER) != PeerHealth::Healthy {
+            assert!(cluster.requests < 20_000, "slow peer never recovered");
+            cluster.run(50);
+            weights.push(cluster.detector.weight(SLOW_PEER));
+        }
+        assert!(weights.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", weights);
+        assert!(weights.iter().any(|&weight| weight > 0.05 && weight < 1.0), "{:?}", weights);
+        assert_eq!(cluster.signals.last(), Some(&GreyFailureSignal::Recovered { node_id: SLOW_PEER }));
+        assert!(cluster.detector.degraded_peers().is_empty());
+
+        let served = cluster.run(1_000);
+        let share = SimulatedCluster::share(&served, SLOW_PEER);
+        assert!((share - 1.0 / 3.0).abs() < 0.01, "share after recovery: {}", share);
+        assert_eq!(cluster.signals.len(), 2);
+    }
+
+    #[test]
+    fn test_uniformly_slow_cluster_is_not_degraded() {
+        let mut cluster = SimulatedCluster::new();
+        let mut detector = GreyFailureDetector::new(GreyFailureConfig::default());
+
+        // Every peer equally slow: nothing stands out from the baseline
+        for _ in 0..1_000 {
+            cluster.now += Duration::from_millis(1);
+            let peer = detector.pick(&cluster.peers, cluster.now).unwrap();
+            assert_eq!(detector.record(peer, Duration::from_millis(80), cluster.now), None);
+        }
+        assert!(detector.degraded_peers().is_empty());
+        assert_eq!(detector.latency(SLOW_PEER, cluster.now), Some(Duration::from_millis(80)));
+
+        // A single peer has no baseline to be compared with
+        let mut alone = GreyFailureDetector::new(GreyFailureConfig::default());
+        for _ in 0..100 {
+            cluster.now += Duration::from_millis(1);
+            assert_eq!(alone.record(NodeId(1), Duration::from_secs(1), cluster.now), None);
+        }
+        assert_eq!(alone.health(NodeId(1)), PeerHealth::Healthy);
+    }
+}
@@ -0,0 +1,136 @@
+//! Leader Balancing Tests
+//!
+//! Simulates a cluster of raft groups whose leaders all start on one node,
+//! with transfers applied to the simulated cluster as they are issued, and
+//! checks that the balancer evens leaders out within its rate limit.
+
+use aurora_coordinator::error::{Error, Result};
+use aurora_coordinator::orchestration::{
+    BalancerMode, GroupLeadership, LeaderBalancer, LeaderBalancerConfig, LeadershipSnapshot, LeadershipTransfer,
+    RaftGroupId,
+};
+use aurora_coordinator::types::NodeId;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Leader balancing test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Groups replicated on every node, with per-node load
+    struct SimulatedCluster {
+        leaders: Mutex<HashMap<RaftGroupId, NodeId>>,
+        nodes: Vec<NodeId>,
+        load: HashMap<NodeId, f64>,
+    }
+
+    impl SimulatedCluster {
+        /// `groups` groups on nodes 1..=`nodes`, every leader on node 1
+        fn skewed(groups: u64, nodes: u64) -> Self {
+            let nodes: Vec<NodeId> = (1..=nodes).map(NodeId).collect();
+            Self {
+                leaders: Mutex::new((0..groups).map(|group| (group, NodeId(1))).collect()),
+                load: nodes.iter().map(|&node| (node, if node == NodeId(1) { 0.9 } else { 0.2 })).collect(),
+                nodes,
+            }
+        }
+
+        fn snapshot(&self) -> LeadershipSnapshot {
+            let mut groups: Vec<GroupLeadership> = self.leaders.lock().unwrap().iter()
+                .map(|(&group, &leader)| GroupLeadership { group, leader, replicas: self.nodes.clone() })
+                .collect();
+            groups.sort_by_key(|group| group.group);
+            LeadershipSnapshot { groups, node_load: self.load.clone() }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LeadershipTransfer for SimulatedCluster {
+        async fn transfer_leadership(&self, group: RaftGroupId, from: NodeId, to: NodeId) -> Result<()> {
+            let mut leaders = self.leaders.lock().unwrap();
+            if leaders.get(&group) != Some(&from) {
+                return Err(Error::Consensus { message: format!("{} does not lead group {}", from, group), operation: "transfer".into() });
+            }
+            leaders.insert(group, to);
+            Ok(())
+        }
+    }
+
+    fn config(mode: BalancerMode) -> LeaderBalancerConfig {
+        LeaderBalancerConfig {
+            mode,
+            imbalance_threshold: 1,
+            max_transfers: 2,
+            rate_window: Duration::from_secs(60),
+            ..LeaderBalancerConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_skewed_leaders_are_balanced_within_rate_limit() {
+        let cluster = SimulatedCluster::skewed(9, 3);
+        let mut balancer = LeaderBalancer::new(config(BalancerMode::Active));
+        let mut now = Instant::now();
+        assert_eq!(cluster.snapshot().spread(), 9);
+
+        let mut rounds = 0;
+        loop {
+            let report = balancer.balance(&cluster.snapshot(), &cluster, now).await.unwrap();
+            assert!(report.applied.len() <= 2);
+            assert_eq!(report.applied, report.planned);
+            if report.planned.is_empty() {
+                break;
+            }
+            // Leaders only ever leave the overloaded node
+            assert!(report.applied.iter().all(|transfer| transfer.from == NodeId(1)));
+
+            // A second round inside the same window is held back
+            let held = balancer.balance(&cluster.snapshot(), &cluster, now + Duration::from_secs(1)).await.unwrap();
+            if cluster.snapshot().spread() > 1 {
+                assert!(held.planned.is_empty() && held.rate_limited);
+            }
+
+            rounds += 1;
+            now += Duration::from_secs(60);
+        }
+
+        let counts = cluster.snapshot().leader_counts();
+        assert_eq!(counts.values().copied().collect::<Vec<_>>(), vec![3, 3, 3]);
+        assert_eq!(balancer.total_transfers(), 6);
+        assert_eq!(rounds, 3);
+    }
+
+    #[tokio::test]
+    async fn test_advisory_mode_recommends_without_transferring() {
+        let cluster = SimulatedCluster::skewed(6, 3);
+        let mut balancer = LeaderBalancer::new(LeaderBalancerConfig { max_transfers: 10, ..config(BalancerMode::Advisory) });
+
+        let report = balancer.balance(&cluster.snapshot(), &cluster, Instant::now()).await.unwrap();
+        assert_eq!(report.planned.len(), 4);
+        assert!(report.applied.is_empty());
+        assert_eq!((report.spread_before, report.spread_after), (6, 0));
+        assert_eq!(cluster.snapshot().spread(), 6);
+        assert_eq!(balancer.total_transfers(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_threshold_and_target_load_limit_transfers() {
+        // Two leaders on node 1, one each on nodes 2 and 3: within the threshold
+        let cluster = SimulatedCluster::skewed(4, 3);
+        cluster.leaders.lock().unwrap().insert(2, NodeId(2));
+        cluster.leaders.lock().unwrap().insert(3, NodeId(3));
+        let mut balancer = LeaderBalancer::new(config(BalancerMode::Active));
+        let report = balancer.balance(&cluster.snapshot(), &cluster, Instant::now()).await.unwrap();
+        assert!(report.planned.is_empty());
+
+        // Leaders never move to a node above the target load limit
+        let mut cluster = SimulatedCluster::skewed(4, 3);
+        cluster.load.insert(NodeId(3), 0.95);
+        let mut balancer = LeaderBalancer::new(LeaderBalancerConfig { max_transfers: 10, ..config(BalancerMode::Active) });
+        let report = balancer.balance(&cluster.snapshot(), &cluster, Instant::now()).await.unwrap();
+        assert!(report.applied.iter().all(|transfer| transfer.to == NodeId(2)));
+        assert_eq!(cluster.snapshot().leader_counts()[&NodeId(2)], 2);
+    }
+}
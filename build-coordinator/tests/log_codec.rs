@@ -0,0 +1,128 @@
+//! Log Codec Tests
+//!
+//! Round-trips representative consensus log entries through every codec and
+//! checks that nodes with different codec preferences and versions agree on
+//! a format both can read.
+
+use aurora_coordinator::consensus::log_codec::{self, LOG_CODEC_VERSION};
+use aurora_coordinator::consensus::{CodecCapabilities, LogCodec, LogFormat};
+use aurora_coordinator::error::Error;
+use aurora_coordinator::types::*;
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Log codec test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: u64, data: LogData) -> LogEntry {
+        LogEntry {
+            index,
+            term: 7,
+            data,
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        }
+    }
+
+    /// One entry of each kind
+    fn representative_entries() -> Vec<LogEntry> {
+        vec![
+            entry(1, LogData::ConfigChange(ConfigChange {
+                change_type: ConfigChangeType::AddNode,
+                node_id: NodeId(4),
+                data: HashMap::from([("address".to_string(), "10.0.0.4:7000".to_string())]),
+            })),
+            entry(2, LogData::SchemaChange(SchemaChange {
+                database: "orders".to_string(),
+                operation: SchemaOperation::CreateTable,
+                sql: "CREATE TABLE line_items (id BIGINT PRIMARY KEY, note TEXT)".to_string(),
+                transaction_id: None,
+            })),
+            entry(3, LogData::Transaction(TransactionEntry {
+                transaction_id: "txn-42".to_string(),
+                state: TransactionState::Prepared,
+                participants: vec![NodeId(1), NodeId(2), NodeId(3)],
+                timeout: Duration::from_secs(30),
+            })),
+            entry(4, LogData::Heartbeat(HeartbeatData { node_id: NodeId(2), term: 7, commit_index: 3, last_applied: 3 })),
+            entry(5, LogData::Custom(vec![0, 1, 2, 0xff, b'"', b'\n'])),
+        ]
+    }
+
+    #[test]
+    fn test_entries_round_trip_through_every_codec() {
+        for format in LogFormat::ALL {
+            let codec = format.codec();
+            assert_eq!(codec.format(), format);
+            for original in representative_entries() {
+                let encoded = codec.encode(&original).unwrap();
+                assert_eq!(log_codec::peek_header(&encoded).unwrap(), (format, LOG_CODEC_VERSION));
+
+                // Readable both by the codec that wrote it and by format detection
+                let decoded = log_codec::decode(&encoded).unwrap();
+                assert_eq!(format!("{:?}", decoded), format!("{:?}", original), "{}", format);
+                assert_eq!(decoded.timestamp, original.timestamp);
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_entries_are_inspectable_and_bincode_is_smaller() {
+        let original = &representative_entries()[1];
+        let json = LogFormat::Json.codec().encode(original).unwrap();
+        let bincode = LogFormat::Bincode.codec().encode(original).unwrap();
+
+        let text = std::str::from_utf8(&json[4..]).unwrap();
+        assert!(text.contains("\"SchemaChange\"") && text.contains("line_items"));
+        assert!(bincode.len() < json.len());
+    }
+
+    #[test]
+    fn test_mixed_version_nodes_negotiate_a_common_codec() {
+        // A node debugging with JSON and an older node that only writes bincode
+        let debugging = CodecCapabilities::local(LogFormat::Json);
+        let older = CodecCapabilities { formats: vec![LogFormat::Bincode], min_version: 1, max_version: 1 };
+        let agreed = debugging.negotiate(&older).unwrap();
+        assert_eq!(agreed.format, LogFormat::Bincode);
+        assert_eq!(agreed, older.negotiate(&debugging).unwrap());
+
+        // Whatever either side encodes with the agreed codec, the other decodes
+        for original in representative_entries() {
+            let encoded = agreed.encode(&original).unwrap();
+            assert_eq!(format!("{:?}", log_codec::decode(&encoded).unwrap()), format!("{:?}", original));
+        }
+
+        // Two current nodes keep the local preference
+        let agreed = debugging.negotiate(&CodecCapabilities::local(LogFormat::Bincode)).unwrap();
+        assert_eq!((agreed.format, agreed.version), (LogFormat::Json, LOG_CODEC_VERSION));
+
+        // A newer node settles on the version this build writes
+        let newer = CodecCapabilities { formats: vec![LogFormat::Json, LogFormat::Bincode], min_version: 1, max_version: LOG_CODEC_VERSION + 1 };
+        assert_eq!(debugging.negotiate(&newer).unwrap().version, LOG_CODEC_VERSION);
+
+        // No overlap in versions or formats is refused
+        let future_only = CodecCapabilities { formats: vec![LogFormat::Bincode], min_version: LOG_CODEC_VERSION + 1, max_version: LOG_CODEC_VERSION + 2 };
+        assert!(matches!(debugging.negotiate(&future_only), Err(Error::Config { .. })));
+        let json_only = CodecCapabilities { formats: vec![LogFormat::Json], min_version: 1, max_version: 1 };
+        assert!(matches!(older.negotiate(&json_only), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_unknown_versions_and_headers_are_rejected() {
+        let original = &representative_entries()[0];
+        let codec = LogFormat::Bincode.codec();
+        assert!(codec.encode_with_version(original, LOG_CODEC_VERSION + 1).is_err());
+
+        let mut encoded = codec.encode(original).unwrap();
+        encoded[2..4].copy_from_slice(&(LOG_CODEC_VERSION + 1).to_le_bytes());
+        assert!(matches!(log_codec::decode(&encoded), Err(Error::Serialization { .. })));
+
+        // Bodies without a header (or with an unknown format tag) are not guessed at
+        let body = codec.encode_body(original).unwrap();
+        assert!(log_codec::decode(&body).is_err());
+        let mut encoded = codec.encode(original).unwrap();
+        encoded[1] = 9;
+        assert!(log_codec::decode(&encoded).is_err());
+    }
+}
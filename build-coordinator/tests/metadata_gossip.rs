@@ -0,0 +1,100 @@
+//! Metadata Gossip Tests
+//!
+//! Simulates SWIM protocol periods over a 16-node cluster: every node pings
+//! one random peer per period, and both the ping and its ack carry whatever
+//! metadata updates fit in the piggyback budget.
+
+use aurora_coordinator::membership::{MetadataConfig, MetadataStore};
+use aurora_coordinator::types::NodeId;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Metadata gossip test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODES: u64 = 16;
+
+    struct SimulatedCluster {
+        stores: Vec<MetadataStore>,
+        rng: StdRng,
+    }
+
+    impl SimulatedCluster {
+        fn new(seed: u64) -> Self {
+            let stores = (0..NODES)
+                .map(|id| {
+                    let mut store = MetadataStore::new(NodeId(id), MetadataConfig::default());
+                    store.set_cluster_size(NODES as usize);
+                    store
+                })
+                .collect();
+            Self { stores, rng: StdRng::seed_from_u64(seed) }
+        }
+
+        /// One protocol period: each node pings a random peer and gets an ack
+        fn period(&mut self) {
+            for from in 0..self.stores.len() {
+                let mut to = self.rng.gen_range(0..self.stores.len() - 1);
+                if to >= from {
+                    to += 1;
+                }
+                let ping = self.stores[from].piggyback();
+                self.stores[to].merge(ping);
+                let ack = self.stores[to].piggyback();
+                self.stores[from].merge(ack);
+            }
+        }
+
+        /// Periods until every node sees `key` of `node` as `value`
+        fn periods_until_converged(&mut self, node: NodeId, key: &str, value: &str, limit: usize) -> Option<usize> {
+            for period in 1..=limit {
+                self.period();
+                let converged = self.stores.iter().all(|store| {
+                    store.node_metadata(node).and_then(|metadata| metadata.get(key).cloned()).as_deref() == Some(value)
+                });
+                if converged {
+                    return Some(period);
+                }
+            }
+            None
+        }
+    }
+
+    /// ceil(log2(n)) periods for the epidemic plus the same again of slack
+    fn period_bound() -> usize {
+        2 * (u64::BITS - (NODES - 1).leading_zeros()) as usize + 4
+    }
+
+    #[test]
+    fn test_metadata_reaches_every_node() {
+        for seed in 0..5 {
+            let mut cluster = SimulatedCluster::new(seed);
+            cluster.stores[3].set("region", "eu-west").unwrap();
+            cluster.stores[3].set("capacity", "64").unwrap();
+
+            let periods = cluster.periods_until_converged(NodeId(3), "region", "eu-west", 50);
+            assert!(matches!(periods, Some(p) if p <= period_bound()), "seed {}: {:?}", seed, periods);
+            assert!(cluster.stores.iter().all(|store| store.node_metadata(NodeId(3)).unwrap()["capacity"] == "64"));
+        }
+    }
+
+    #[test]
+    fn test_later_write_wins_everywhere() {
+        let mut cluster = SimulatedCluster::new(42);
+        cluster.stores[5].set("region", "us-east").unwrap();
+        cluster.period();
+        cluster.stores[5].set("region", "ap-south").unwrap();
+
+        let periods = cluster.periods_until_converged(NodeId(5), "region", "ap-south", 50);
+        assert!(matches!(periods, Some(p) if p <= period_bound()), "{:?}", periods);
+
+        // Gossip dies out once every update has used its retransmissions
+        for _ in 0..50 {
+            cluster.period();
+        }
+        assert!(cluster.stores.iter().all(|store| store.pending_broadcasts() == 0));
+        assert!(cluster.stores.iter().all(|store| store.node_metadata(NodeId(5)).unwrap()["region"] == "ap-south"));
+    }
+}
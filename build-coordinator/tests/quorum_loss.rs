@@ -0,0 +1,126 @@
+//! Quorum Loss Tests
+//!
+//! Simulates a 3-node cluster whose heartbeat acks only flow between nodes on
+//! the same side of a partition, and checks what the former leader serves once
+//! it is cut off from both followers.
+
+use aurora_coordinator::config::QuorumLossConfig;
+use aurora_coordinator::consensus::{QuorumHealth, QuorumMonitor, ReadConsistency};
+use aurora_coordinator::error::Error;
+use aurora_coordinator::types::NodeId;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Quorum-loss test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEARTBEAT: Duration = Duration::from_millis(50);
+
+    /// Leader node 1 with followers 2 and 3
+    struct SimulatedCluster {
+        leader: QuorumMonitor,
+        isolated: HashSet<NodeId>,
+        now: Instant,
+    }
+
+    impl SimulatedCluster {
+        fn new(serve_stale_reads: bool) -> Self {
+            let config = QuorumLossConfig {
+                serve_stale_reads,
+                leader_lease: Duration::from_millis(300),
+                peer_timeout: Duration::from_millis(100),
+            };
+            let now = Instant::now();
+            let mut leader = QuorumMonitor::new(config, &[NodeId(2), NodeId(3)]);
+            leader.set_leader(true, now);
+
+            let mut cluster = Self { leader, isolated: HashSet::new(), now };
+            cluster.heartbeat();
+            cluster
+        }
+
+        /// Cut `node` off from every other node
+        fn isolate(&mut self, node: u64) {
+            self.isolated.insert(NodeId(node));
+        }
+
+        /// Advance one heartbeat interval, delivering acks that can cross the partition
+        fn heartbeat(&mut self) {
+            self.now += HEARTBEAT;
+            if self.isolated.contains(&NodeId(1)) {
+                return;
+            }
+            for peer in [NodeId(2), NodeId(3)] {
+                if !self.isolated.contains(&peer) {
+                    self.leader.record_peer_ack(peer, self.now);
+                }
+            }
+        }
+
+        fn run_for(&mut self, duration: Duration) {
+            let end = self.now + duration;
+            while self.now < end {
+                self.heartbeat();
+            }
+        }
+    }
+
+    #[test]
+    fn test_minority_partition_keeps_quorum() {
+        let mut cluster = SimulatedCluster::new(false);
+        cluster.isolate(3);
+        cluster.run_for(Duration::from_secs(1));
+
+        let now = cluster.now;
+        assert_eq!(cluster.leader.health(now), QuorumHealth::Healthy);
+        assert!(cluster.leader.check_write(now).is_ok());
+        assert_eq!(cluster.leader.check_read(now).unwrap(), ReadConsistency::Linearizable);
+    }
+
+    #[test]
+    fn test_quorum_loss_refuses_writes_and_serves_flagged_stale_reads() {
+        let mut cluster = SimulatedCluster::new(true);
+        cluster.run_for(Duration::from_millis(200));
+        cluster.isolate(1);
+
+        // Within the peer timeout the leader still counts its followers
+        cluster.heartbeat();
+        assert!(cluster.leader.check_write(cluster.now).is_ok());
+
+        cluster.run_for(Duration::from_millis(100));
+        let now = cluster.now;
+        assert!(matches!(cluster.leader.health(now), QuorumHealth::QuorumLost { lease_valid: true, .. }));
+        assert!(matches!(cluster.leader.check_write(now), Err(Error::QuorumLost { .. })));
+        assert_eq!(cluster.leader.check_read(now).unwrap(), ReadConsistency::Stale);
+
+        // Once the lease runs out, reads are refused as well; writes stay refused
+        cluster.run_for(Duration::from_millis(300));
+        let now = cluster.now;
+        assert!(matches!(cluster.leader.health(now), QuorumHealth::QuorumLost { lease_valid: false, .. }));
+        assert!(matches!(cluster.leader.check_read(now), Err(Error::QuorumLost { .. })));
+        assert!(matches!(cluster.leader.check_write(now), Err(Error::QuorumLost { .. })));
+
+        // Healing the partition restores normal service
+        cluster.isolated.clear();
+        cluster.heartbeat();
+        let now = cluster.now;
+        assert_eq!(cluster.leader.health(now), QuorumHealth::Healthy);
+        assert!(cluster.leader.check_write(now).is_ok());
+        assert_eq!(cluster.leader.check_read(now).unwrap(), ReadConsistency::Linearizable);
+    }
+
+    #[test]
+    fn test_quorum_loss_refuses_reads_when_stale_reads_disabled() {
+        let mut cluster = SimulatedCluster::new(false);
+        cluster.isolate(2);
+        cluster.isolate(3);
+        cluster.run_for(Duration::from_millis(150));
+
+        let now = cluster.now;
+        assert!(matches!(cluster.leader.health(now), QuorumHealth::QuorumLost { .. }));
+        assert!(matches!(cluster.leader.check_write(now), Err(Error::QuorumLost { .. })));
+        assert!(matches!(cluster.leader.check_read(now), Err(Error::QuorumLost { .. })));
+    }
+}
@@ -0,0 +1,138 @@
+//! Read-Your-Writes Session Tests
+//!
+//! Simulates a 3-node group sharing one log, in which the leader applies
+//! each write as it commits it and followers apply only when replication
+//! reaches them, and checks that a session reading on a follower always
+//! sees its own writes.
+
+use aurora_coordinator::error::Result;
+use aurora_coordinator::orchestration::{ConsensusReplica, CoordinatorClient, SessionConfig};
+use aurora_coordinator::consensus::LogIndex;
+use aurora_coordinator::types::NodeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Read-your-writes test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Committed entries, shared by every node; index 1 is the first
+    type SharedLog = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+    struct SimulatedNode {
+        id: NodeId,
+        leader: bool,
+        log: SharedLog,
+        applied: Mutex<(LogIndex, HashMap<String, Vec<u8>>)>,
+    }
+
+    impl SimulatedNode {
+        fn new(id: u64, leader: bool, log: &SharedLog) -> Arc<Self> {
+            Arc::new(Self { id: NodeId(id), leader, log: log.clone(), applied: Mutex::new((0, HashMap::new())) })
+        }
+
+        /// Apply every committed entry up to `index`
+        fn catch_up(&self, index: LogIndex) {
+            let log = self.log.lock().unwrap();
+            let mut applied = self.applied.lock().unwrap();
+            while applied.0 < index.min(log.len() as LogIndex) {
+                let (key, value) = log[applied.0 as usize].clone();
+                applied.1.insert(key, value);
+                applied.0 += 1;
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ConsensusReplica for SimulatedNode {
+        fn node_id(&self) -> NodeId {
+            self.id
+        }
+
+        async fn is_leader(&self) -> bool {
+            self.leader
+        }
+
+        async fn write(&self, key: &str, value: Vec<u8>) -> Result<LogIndex> {
+            let index = {
+                let mut log = self.log.lock().unwrap();
+                log.push((key.to_string(), value));
+                log.len() as LogIndex
+            };
+            self.catch_up(index);
+            Ok(index)
+        }
+
+        async fn applied_index(&self) -> LogIndex {
+            self.applied.lock().unwrap().0
+        }
+
+        async fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.applied.lock().unwrap().1.get(key).cloned())
+        }
+    }
+
+    /// Leader 1 with followers 2 and 3
+    fn cluster() -> (Arc<SimulatedNode>, Arc<SimulatedNode>, Arc<SimulatedNode>) {
+        let log = SharedLog::default();
+        (SimulatedNode::new(1, true, &log), SimulatedNode::new(2, false, &log), SimulatedNode::new(3, false, &log))
+    }
+
+    fn client(wait_timeout: Duration, nodes: &[&Arc<SimulatedNode>]) -> CoordinatorClient {
+        let replicas = nodes.iter().map(|&node| node.clone() as Arc<dyn ConsensusReplica>).collect();
+        CoordinatorClient::new(SessionConfig { wait_timeout }, replicas)
+    }
+
+    #[tokio::test]
+    async fn test_follower_read_waits_for_session_write() {
+        let (leader, follower, other) = cluster();
+        let session = client(Duration::from_secs(2), &[&leader, &follower, &other]);
+
+        let token = session.write("config/primary", b"node-7".to_vec()).await.unwrap();
+        assert_eq!(token.commit_index(), 1);
+        assert_eq!(session.session_token(), token);
+
+        // Replication reaches the follower a little later
+        let replicating = follower.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            replicating.catch_up(1);
+        });
+
+        // A read without the token is served at once, and stale
+        let fresh = client(Duration::from_secs(2), &[&leader, &follower, &other]);
+        let stale = fresh.read_from(NodeId(2), "config/primary").await.unwrap();
+        assert_eq!(stale.value, None);
+
+        let read = session.read_from(NodeId(2), "config/primary").await.unwrap();
+        assert_eq!(read.value, Some(b"node-7".to_vec()));
+        assert_eq!(read.served_by, NodeId(2));
+        assert!(!read.forwarded);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_follower_forwards_read() {
+        let (leader, follower, other) = cluster();
+        let session = client(Duration::from_millis(50), &[&leader, &follower, &other]);
+        session.write("config/primary", b"node-7".to_vec()).await.unwrap();
+        let token = session.write("config/primary", b"node-9".to_vec()).await.unwrap();
+        assert_eq!(token.commit_index(), 2);
+
+        // Node 3 has the writes, node 2 only the first and never catches up
+        other.catch_up(2);
+        follower.catch_up(1);
+        let read = session.read_from(NodeId(2), "config/primary").await.unwrap();
+        assert_eq!(read.value, Some(b"node-9".to_vec()));
+        assert_eq!(read.served_by, NodeId(3));
+        assert!(read.forwarded);
+
+        // Another client continuing the session carries the same guarantee;
+        // with only the leader caught up, the read ends up there
+        let handed_over = client(Duration::from_millis(50), &[&leader, &follower]).with_token(token);
+        let read = handed_over.read_from(NodeId(2), "config/primary").await.unwrap();
+        assert_eq!(read.value, Some(b"node-9".to_vec()));
+        assert_eq!(read.served_by, NodeId(1));
+    }
+}
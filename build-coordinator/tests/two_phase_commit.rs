@@ -0,0 +1,174 @@
+//! Two-Phase Commit Tests
+//!
+//! Runs distributed transactions against in-memory shards that record what
+//! they were asked to do, with the coordinator's log on disk so a crashed
+//! coordinator can be replaced by a new one reading the same log.
+
+use aurora_coordinator::consensus::LogManager;
+use aurora_coordinator::consensus::log_manager::LogConfig;
+use aurora_coordinator::error::{Error, Result};
+use aurora_coordinator::orchestration::{
+    RecoveryReport, ShardParticipant, TransactionLog, TransactionOutcome, TwoPhaseCommitConfig,
+    TwoPhaseCommitCoordinator, Vote,
+};
+use aurora_coordinator::types::{NodeId, TransactionEntry};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Two-phase commit test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shard that votes as told and records every request
+    struct MockShard {
+        vote: Vote,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MockShard {
+        fn new(vote: Vote) -> Arc<Self> {
+            Arc::new(Self { vote, calls: Mutex::new(Vec::new()) })
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ShardParticipant for MockShard {
+        async fn prepare(&self, transaction_id: &str) -> Result<Vote> {
+            self.calls.lock().unwrap().push(format!("prepare {}", transaction_id));
+            Ok(self.vote)
+        }
+
+        async fn commit(&self, transaction_id: &str) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("commit {}", transaction_id));
+            Ok(())
+        }
+
+        async fn abort(&self, transaction_id: &str) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("abort {}", transaction_id));
+            Ok(())
+        }
+    }
+
+    /// Log whose coordinator dies right after its `crash_after`th record
+    /// reaches disk: that record and every earlier one survive, and
+    /// nothing after it is written
+    struct CrashingLog {
+        inner: Arc<LogManager>,
+        crash_after: usize,
+        appended: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionLog for CrashingLog {
+        async fn append(&self, entry: TransactionEntry) -> Result<()> {
+            let crashed = || Error::Io { message: "coordinator crashed".into(), operation: "append".into() };
+            if self.appended.load(Ordering::SeqCst) >= self.crash_after {
+                return Err(crashed());
+            }
+            TransactionLog::append(self.inner.as_ref(), entry).await?;
+            if self.appended.fetch_add(1, Ordering::SeqCst) + 1 == self.crash_after {
+                return Err(crashed());
+            }
+            Ok(())
+        }
+
+        async fn entries(&self) -> Result<Vec<TransactionEntry>> {
+            self.inner.entries().await
+        }
+    }
+
+    fn log_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("2pc-{}-{}.log", name, uuid::Uuid::new_v4()));
+        path.to_string_lossy().into_owned()
+    }
+
+    async fn open_log(path: &str) -> Arc<LogManager> {
+        Arc::new(LogManager::new(LogConfig { log_path: path.to_string(), ..LogConfig::default() }).await.unwrap())
+    }
+
+    async fn coordinator(log: Arc<dyn TransactionLog>, shards: &[Arc<MockShard>]) -> TwoPhaseCommitCoordinator {
+        let coordinator = TwoPhaseCommitCoordinator::new(TwoPhaseCommitConfig::default(), log);
+        for (i, shard) in shards.iter().enumerate() {
+            coordinator.register_shard(NodeId(i as u64 + 1), shard.clone()).await;
+        }
+        coordinator
+    }
+
+    const SHARDS: [NodeId; 3] = [NodeId(1), NodeId(2), NodeId(3)];
+
+    #[tokio::test]
+    async fn test_all_yes_commits_everywhere() {
+        let path = log_path("commit");
+        let shards = [MockShard::new(Vote::Yes), MockShard::new(Vote::Yes), MockShard::new(Vote::Yes)];
+        let coordinator = coordinator(open_log(&path).await, &shards).await;
+
+        let outcome = coordinator.execute("tx-1", &SHARDS).await.unwrap();
+        assert_eq!(outcome, TransactionOutcome::Committed { pending: vec![] });
+        for shard in &shards {
+            assert_eq!(shard.calls(), ["prepare tx-1", "commit tx-1"]);
+        }
+        assert!(coordinator.in_doubt().await.unwrap().is_empty());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_one_no_vote_aborts_everywhere() {
+        let path = log_path("abort");
+        let shards = [MockShard::new(Vote::Yes), MockShard::new(Vote::No), MockShard::new(Vote::Yes)];
+        let coordinator = coordinator(open_log(&path).await, &shards).await;
+
+        let outcome = coordinator.execute("tx-2", &SHARDS).await.unwrap();
+        assert_eq!(outcome, TransactionOutcome::Aborted { no_votes: vec![NodeId(2)] });
+        for shard in &shards {
+            assert_eq!(shard.calls(), ["prepare tx-2", "abort tx-2"]);
+        }
+        assert!(coordinator.in_doubt().await.unwrap().is_empty());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_in_doubt_transactions_resolved_after_coordinator_crash() {
+        let path = log_path("recovery");
+        let shards = [MockShard::new(Vote::Yes), MockShard::new(Vote::Yes), MockShard::new(Vote::Yes)];
+
+        // Dies right after logging the commit decision, before any shard hears it
+        {
+            let log = Arc::new(CrashingLog { inner: open_log(&path).await, crash_after: 2, appended: AtomicUsize::new(0) });
+            let coordinator = coordinator(log, &shards).await;
+            assert!(coordinator.execute("tx-3", &SHARDS).await.is_err());
+        }
+        // Dies after logging the next transaction's begin record, before a decision
+        {
+            let log = Arc::new(CrashingLog { inner: open_log(&path).await, crash_after: 1, appended: AtomicUsize::new(0) });
+            let coordinator = coordinator(log, &shards).await;
+            assert!(coordinator.execute("tx-4", &SHARDS).await.is_err());
+        }
+        for shard in &shards {
+            assert_eq!(shard.calls(), ["prepare tx-3"]);
+        }
+
+        // A new coordinator reading the same log commits the decided
+        // transaction and presumes the undecided one aborted
+        let coordinator = coordinator(open_log(&path).await, &shards).await;
+        assert_eq!(coordinator.in_doubt().await.unwrap(), ["tx-3", "tx-4"]);
+        let report = coordinator.recover().await.unwrap();
+        assert_eq!(report, RecoveryReport {
+            committed: vec!["tx-3".to_string()],
+            aborted: vec!["tx-4".to_string()],
+            unresolved: vec![],
+        });
+        for shard in &shards {
+            assert_eq!(shard.calls(), ["prepare tx-3", "commit tx-3", "abort tx-4"]);
+        }
+        assert!(coordinator.in_doubt().await.unwrap().is_empty());
+
+        // Resolved for good: recovering again changes nothing
+        assert_eq!(coordinator.recover().await.unwrap(), RecoveryReport::default());
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,106 @@
+//! WebSocket Event Stream Tests
+//!
+//! Serves the event endpoint on an ephemeral port, subscribes real WebSocket
+//! clients and publishes through the same callbacks membership and
+//! consensus use.
+
+use aurora_coordinator::api::websocket_api::{
+    ClusterEvent, Delivery, EventFilter, EventKind, EventPublisher, WebSocketAPI, WebSocketConfig,
+};
+use aurora_coordinator::consensus::LeadershipCallback;
+use aurora_coordinator::membership::NodeEventCallback;
+use aurora_coordinator::types::{NodeId, NodeStatus};
+use futures::StreamExt;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+/// WebSocket event stream test suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn next_json<S>(client: &mut S) -> serde_json::Value
+    where
+        S: futures::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next()).await.unwrap().unwrap().unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    /// Wait until the server has registered `count` subscriptions
+    async fn wait_for_subscribers(publisher: &EventPublisher, count: usize) {
+        for _ in 0..100 {
+            if publisher.subscriber_count() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("subscribers never connected");
+    }
+
+    #[tokio::test]
+    async fn test_client_receives_filtered_leadership_change() {
+        let api = WebSocketAPI::new("127.0.0.1:0", WebSocketConfig::default());
+        let publisher = api.publisher();
+        let (address, server) = api.bind().unwrap();
+        tokio::spawn(server);
+
+        let url = format!("ws://{}/api/v1/events?events=leadership", address);
+        let (mut leadership_only, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        let (mut everything, _) = tokio_tungstenite::connect_async(format!("ws://{}/api/v1/events", address)).await.unwrap();
+        wait_for_subscribers(&publisher, 2).await;
+
+        publisher.on_node_failure(NodeId(3)).await;
+        publisher.on_node_status_change(NodeId(3), NodeStatus::Suspected, NodeStatus::Failed).await;
+        publisher.on_term_change(8).await;
+        publisher.on_leader_change(Some(NodeId(2)), 8).await;
+
+        // The filtered client only sees the leadership change
+        let event = next_json(&mut leadership_only).await;
+        assert_eq!(event["type"], "leader_changed");
+        assert_eq!(event["leader"], 2);
+        assert_eq!(event["term"], 8);
+        assert_eq!(event["sequence"], 4);
+        assert!(event["timestamp"].is_string());
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(next_json(&mut everything).await["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(seen, ["node_failed", "health_changed", "term_changed", "leader_changed"]);
+
+        // Unknown kinds are refused before the upgrade
+        let url = format!("ws://{}/api/v1/events?events=leadership,gossip", address);
+        assert!(tokio_tungstenite::connect_async(url).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_gets_lag_notice() {
+        let publisher = EventPublisher::new(4);
+        let mut slow = publisher.subscribe(EventFilter::all());
+        let mut terms = publisher.subscribe(EventFilter::only(&[EventKind::Term]));
+
+        // Publishing never waits for a subscriber that is not reading
+        for term in 1..=10 {
+            publisher.publish(ClusterEvent::TermChanged { term });
+        }
+
+        // The oldest six were dropped; the newest four are still delivered
+        assert!(matches!(slow.next().await, Some(Delivery::Lagged(6))));
+        let notice: serde_json::Value = serde_json::from_str(&Delivery::Lagged(6).to_json()).unwrap();
+        assert_eq!(notice, serde_json::json!({ "type": "lagged", "missed": 6 }));
+        for term in 7..=10 {
+            match slow.next().await {
+                Some(Delivery::Event(published)) => {
+                    assert_eq!(published.event, ClusterEvent::TermChanged { term });
+                    assert_eq!(published.sequence, term);
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        assert!(matches!(terms.next().await, Some(Delivery::Lagged(6))));
+
+        assert!(EventFilter::parse("membership, health").unwrap().matches(&ClusterEvent::NodeLeft { node_id: NodeId(1) }));
+        assert!(EventFilter::parse("votes").is_err());
+    }
+}
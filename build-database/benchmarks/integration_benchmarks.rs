@@ -81,6 +81,7 @@ async fn setup_benchmark_database() -> Arc<AuroraDB> {
                 adaptive_threshold: 10000,
                 vector_threshold: 0.1,
             },
+            columnar: aurora_db::config::ColumnarConfig::default(),
             selection_strategy: "workload_based".to_string(),
         },
         transaction: TransactionConfig {
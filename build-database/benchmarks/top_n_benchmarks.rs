@@ -0,0 +1,71 @@
+//! Top-N Sort Benchmarks
+//!
+//! Compares `ORDER BY ... LIMIT n` answered by a bounded top-N heap with a
+//! full sort followed by the limit, over one million rows, for limits from a
+//! handful of rows up to a tenth of the input. The heap keeps at most `n`
+//! rows, where the full sort holds all of them; the rows each approach keeps
+//! are printed alongside the timings.
+
+use std::cmp::Ordering;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use aurora_db::engine::TopN;
+
+const TOTAL_ROWS: usize = 1_000_000;
+
+/// (sort key, tie-breaking key, payload) rows in pseudo-random order
+fn rows() -> Vec<(u64, u32, u64)> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    (0..TOTAL_ROWS as u64)
+        .map(|id| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 100_000, (state >> 32) as u32 % 16, id)
+        })
+        .collect()
+}
+
+fn compare(a: &(u64, u32, u64), b: &(u64, u32, u64)) -> Ordering {
+    a.0.cmp(&b.0).then(b.1.cmp(&a.1))
+}
+
+fn full_sort_then_limit(rows: &[(u64, u32, u64)], limit: usize) -> Vec<(u64, u32, u64)> {
+    let mut sorted = rows.to_vec();
+    sorted.sort_by(compare);
+    sorted.truncate(limit);
+    sorted
+}
+
+fn top_n(rows: &[(u64, u32, u64)], limit: usize) -> Vec<(u64, u32, u64)> {
+    let mut top = TopN::new(limit, compare);
+    for row in rows {
+        top.push(*row);
+    }
+    top.into_sorted()
+}
+
+pub fn benchmark_order_by_limit(c: &mut Criterion) {
+    let rows = rows();
+    let row_bytes = std::mem::size_of::<(u64, u32, u64)>();
+
+    let mut group = c.benchmark_group("order_by_limit");
+    group.sample_size(10);
+    for limit in [10, 1_000, 100_000] {
+        assert_eq!(top_n(&rows, limit), full_sort_then_limit(&rows, limit));
+        println!(
+            "limit {:>7}: full sort keeps {} rows ({} KiB), top-N heap keeps {} rows ({} KiB)",
+            limit, TOTAL_ROWS, TOTAL_ROWS * row_bytes / 1024, limit, limit * row_bytes / 1024,
+        );
+
+        group.bench_with_input(BenchmarkId::new("full_sort", limit), &limit, |b, &limit| {
+            b.iter(|| black_box(full_sort_then_limit(&rows, limit)))
+        });
+        group.bench_with_input(BenchmarkId::new("top_n_heap", limit), &limit, |b, &limit| {
+            b.iter(|| black_box(top_n(&rows, limit)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_order_by_limit);
+criterion_main!(benches);
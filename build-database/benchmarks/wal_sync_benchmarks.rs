@@ -0,0 +1,67 @@
+//! WAL Sync Benchmarks
+//!
+//! Commit latency with the WAL made durable by blocking `write` and
+//! `fdatasync` against linked io_uring write and fsync SQEs, for a single
+//! committer and for concurrent committers sharing flushes. Build with
+//! `--features io-uring` on Linux; otherwise both rows take the blocking
+//! path, which is printed alongside the timings.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use aurora_db::storage::{WALLogger, WalSyncMethod};
+use tempfile::tempdir;
+
+const CONCURRENT_COMMITTERS: u64 = 16;
+
+fn benchmark_commit_latency(c: &mut Criterion) {
+    // Committers block in the flush they wait for, so each needs a thread
+    // for commits to overlap and share a sync
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(CONCURRENT_COMMITTERS as usize)
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("wal_commit");
+
+    for method in [WalSyncMethod::Blocking, WalSyncMethod::IoUring] {
+        let dir = tempdir().unwrap();
+        let wal = Arc::new(WALLogger::new(dir.path().to_path_buf()).unwrap().with_sync_method(method));
+        let next_transaction = Arc::new(AtomicU64::new(1));
+        println!("{:?} requested, {:?} in use", method, wal.get_stats().sync_method);
+
+        group.bench_with_input(BenchmarkId::new("single", format!("{:?}", method)), &method, |b, _| {
+            b.iter(|| runtime.block_on(async {
+                let transaction_id = next_transaction.fetch_add(1, Ordering::Relaxed);
+                wal.log_insert(transaction_id, "bench", &transaction_id.to_le_bytes(), &[0u8; 128]).await.unwrap();
+                wal.commit_transaction(transaction_id).await.unwrap();
+            }));
+        });
+
+        group.bench_with_input(BenchmarkId::new("concurrent", format!("{:?}", method)), &method, |b, _| {
+            b.iter(|| runtime.block_on(async {
+                let commits: Vec<_> = (0..CONCURRENT_COMMITTERS).map(|_| {
+                    let (wal, next_transaction) = (wal.clone(), next_transaction.clone());
+                    tokio::spawn(async move {
+                        let transaction_id = next_transaction.fetch_add(1, Ordering::Relaxed);
+                        wal.log_insert(transaction_id, "bench", &transaction_id.to_le_bytes(), &[0u8; 128]).await.unwrap();
+                        wal.commit_transaction(transaction_id).await.unwrap();
+                    })
+                }).collect();
+                for commit in commits {
+                    commit.await.unwrap();
+                }
+            }));
+        });
+
+        let stats = wal.get_stats();
+        println!(
+            "{:?}: {} syncs, mean {:?}, max {:?}, {} commits grouped",
+            stats.sync_method, stats.sync_latency.syncs, stats.sync_latency.mean(), stats.sync_latency.max, stats.grouped_commits,
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_commit_latency);
+criterion_main!(benches);
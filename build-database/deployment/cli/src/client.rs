@@ -1,14 +1,17 @@
 //! AuroraDB HTTP Client
 //!
 //! HTTP client for communicating with AuroraDB's REST API.
-//! Used by the CLI tool for database administration.
+//! Used by the CLI tool for database administration. Requests go through
+//! [`ResilientClient`], which retries transient failures.
 
-use reqwest::{Client, Response};
+use reqwest::blocking::Response;
+use reqwest::Method;
 use serde_json::{Value, json};
 use std::collections::HashMap;
+use crate::http::{ResilientClient, RetryConfig};
 
 pub struct AuroraClient {
-    client: Client,
+    http: ResilientClient,
     base_url: String,
     auth_token: Option<String>,
 }
@@ -36,11 +39,14 @@ pub struct ColumnInfo {
 
 impl AuroraClient {
     pub fn new(host: &str, port: &str, user: &str, password: &str, database: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_retry(host, port, user, password, database, RetryConfig::default())
+    }
+
+    pub fn with_retry(host: &str, port: &str, user: &str, password: &str, database: &str, retry: RetryConfig) -> Result<Self, Box<dyn std::error::Error>> {
         let base_url = format!("http://{}:{}", host, port);
-        let client = Client::new();
 
         let mut aurora_client = Self {
-            client,
+            http: ResilientClient::new(retry),
             base_url,
             auth_token: None,
         };
@@ -58,10 +64,9 @@ impl AuroraClient {
             "database": database
         });
 
-        let response = self.client
-            .post(&format!("{}/auth/login", self.base_url))
-            .json(&auth_data)
-            .send()?;
+        let response = self.http.send(Method::POST, &format!("{}/auth/login", self.base_url), |request| {
+            request.json(&auth_data)
+        })?;
 
         if !response.status().is_success() {
             return Err(format!("Authentication failed: {}", response.status()).into());
@@ -78,25 +83,26 @@ impl AuroraClient {
     fn make_request(&self, method: &str, endpoint: &str, body: Option<Value>) -> Result<Response, Box<dyn std::error::Error>> {
         let url = format!("{}{}", self.base_url, endpoint);
 
-        let mut request = match method {
-            "GET" => self.client.get(&url),
-            "POST" => self.client.post(&url),
-            "PUT" => self.client.put(&url),
-            "DELETE" => self.client.delete(&url),
+        let method = match method {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "DELETE" => Method::DELETE,
             _ => return Err("Invalid HTTP method".into()),
         };
 
-        // Add authentication header
-        if let Some(token) = &self.auth_token {
-            request = request.header("Authorization", format!("Bearer {}", token));
-        }
-
-        // Add JSON body if provided
-        if let Some(body) = body {
-            request = request.json(&body);
-        }
-
-        let response = request.send()?;
+        let response = self.http.send(method, &url, |mut request| {
+            // Add authentication header
+            if let Some(token) = &self.auth_token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            // Add JSON body if provided
+            if let Some(body) = &body {
+                request = request.json(body);
+            }
+            request
+        })?;
         Ok(response)
     }
 
@@ -402,6 +408,21 @@ impl AuroraClient {
         Ok(())
     }
 
+    pub fn get_bloat_report(&self, table: Option<&str>) -> Result<Value, Box<dyn std::error::Error>> {
+        let path = match table {
+            Some(table) => format!("/api/maintenance/bloat/{}", table),
+            None => "/api/maintenance/bloat".to_string(),
+        };
+        let response = self.make_request("GET", &path, None)?;
+
+        if !response.status().is_success() {
+            return Err(format!("Bloat report failed: {}", response.status()).into());
+        }
+
+        let report: Value = response.json()?;
+        Ok(report)
+    }
+
     pub fn reindex_all_tables(&self) -> Result<(), Box<dyn std::error::Error>> {
         let response = self.make_request("POST", "/api/maintenance/reindex", None)?;
 
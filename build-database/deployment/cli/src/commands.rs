@@ -455,3 +455,85 @@ pub async fn cmd_maintenance_reindex(client: &AuroraClient, table: Option<&str>)
 
     Ok(())
 }
+
+pub async fn cmd_maintenance_bloat(client: &AuroraClient, table: Option<&str>, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let report = client.get_bloat_report(table).await?;
+    let empty = Vec::new();
+    let tables = report.get("tables").and_then(|v| v.as_array()).unwrap_or(&empty);
+    let indexes = report.get("indexes").and_then(|v| v.as_array()).unwrap_or(&empty);
+    let recommendations = report.get("recommendations").and_then(|v| v.as_array()).unwrap_or(&empty);
+    let text = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let number = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let ratio = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Table => {
+            println!("Table Bloat");
+            println!("===========");
+            println!("{:<30} {:>12} {:>12} {:>14} {:>8} {:>8}", "Table", "Live", "Dead", "Wasted Bytes", "Bloat", "Frag");
+            for t in tables {
+                println!("{:<30} {:>12} {:>12} {:>14} {:>7.1}% {:>7.1}%",
+                    text(t, "table_name"),
+                    number(t, "live_tuples"),
+                    number(t, "dead_tuples"),
+                    number(t, "dead_bytes") + number(t, "free_bytes"),
+                    ratio(t, "bloat_ratio") * 100.0,
+                    ratio(t, "fragmentation") * 100.0);
+            }
+            println!();
+            println!("Index Bloat");
+            println!("===========");
+            println!("{:<30} {:<30} {:>12} {:>12} {:>8}", "Index", "Table", "Live", "Stale", "Bloat");
+            for i in indexes {
+                println!("{:<30} {:<30} {:>12} {:>12} {:>7.1}%",
+                    text(i, "index_name"),
+                    text(i, "table_name"),
+                    number(i, "live_entries"),
+                    number(i, "stale_entries"),
+                    ratio(i, "bloat_ratio") * 100.0);
+            }
+            println!();
+            if recommendations.is_empty() {
+                print_success("No maintenance recommended");
+            }
+            for r in recommendations {
+                let table_name = text(r, "table_name");
+                let command = match text(r, "action").as_str() {
+                    "Reindex" => format!("aurora-cli maintenance reindex {}", table_name),
+                    _ => "aurora-cli maintenance vacuum".to_string(),
+                };
+                let target = match r.get("index_name").and_then(|v| v.as_str()) {
+                    Some(index_name) => format!("index '{}' on '{}'", index_name, table_name),
+                    None => format!("table '{}'", table_name),
+                };
+                print_warning(&format!("{} is {:.1}% bloat; run `{}`", target, ratio(r, "bloat_ratio") * 100.0, command));
+            }
+        }
+        OutputFormat::Csv => {
+            println!("object_type,object_name,table_name,live,dead,bloat_ratio,recommendation");
+            let recommended = |table_name: &str, index_name: Option<&str>| {
+                recommendations.iter()
+                    .find(|r| text(r, "table_name") == table_name && r.get("index_name").and_then(|v| v.as_str()) == index_name)
+                    .map(|r| text(r, "action").to_uppercase())
+                    .unwrap_or_default()
+            };
+            for t in tables {
+                let table_name = text(t, "table_name");
+                println!("table,{},{},{},{},{:.4},{}", table_name, table_name,
+                    number(t, "live_tuples"), number(t, "dead_tuples"), ratio(t, "bloat_ratio"),
+                    recommended(&table_name, None));
+            }
+            for i in indexes {
+                let (index_name, table_name) = (text(i, "index_name"), text(i, "table_name"));
+                println!("index,{},{},{},{},{:.4},{}", index_name, table_name,
+                    number(i, "live_entries"), number(i, "stale_entries"), ratio(i, "bloat_ratio"),
+                    recommended(&table_name, Some(&index_name)));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,408 @@
+//! Resilient HTTP Transport
+//!
+//! Wraps the reqwest client the CLI talks to AuroraDB with, so a transient
+//! server hiccup does not fail a whole admin script:
+//! - **Retries**: Connection errors, timeouts and 5xx responses are retried
+//!   with exponential backoff, up to `max_retries` times
+//! - **Retry-After**: A 429 or 503 carrying `Retry-After` (seconds or an
+//!   HTTP date) waits as long as the server asked, up to `max_retry_after`
+//! - **Timeouts**: Every attempt is bounded by `request_timeout`
+//! - **Circuit breaker**: After `failure_threshold` consecutive failures
+//!   requests fail fast for `open_duration`; then one trial request decides
+//!   whether the circuit closes again
+//!
+//! POST is not idempotent, so it is only retried when the server cannot
+//! have acted on it: connection failures, 429 and 503. Other methods are
+//! retried on any 5xx.
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Retry, timeout and circuit breaker settings
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Longest delay between attempts without a `Retry-After`
+    pub max_backoff: Duration,
+    /// Longest `Retry-After` honored; longer waits give up instead
+    pub max_retry_after: Duration,
+    /// Time limit of each attempt
+    pub request_timeout: Duration,
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// How long an open circuit fails requests before letting one through
+    pub open_duration: Duration,
+    /// Log each retry to stderr
+    pub verbose: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            max_retry_after: Duration::from_secs(60),
+            request_timeout: Duration::from_secs(30),
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+            verbose: false,
+        }
+    }
+}
+
+/// Failure of a request after retries
+#[derive(Debug)]
+pub enum HttpError {
+    /// The circuit is open; no request was sent
+    CircuitOpen { retry_in: Duration },
+    /// The request could not be sent or its response not read
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::CircuitOpen { retry_in } => {
+                write!(f, "Server unavailable after repeated failures; retry in {:.0}s", retry_in.as_secs_f64().ceil())
+            }
+            HttpError::Request(e) => write!(f, "Request failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HttpError::CircuitOpen { .. } => None,
+            HttpError::Request(e) => Some(e),
+        }
+    }
+}
+
+/// State of the circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow; counting consecutive failures
+    Closed,
+    /// Requests fail fast
+    Open,
+    /// One trial request is in flight
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Whether a request may be sent now, or how long until one may
+    fn admit(&mut self, open_duration: Duration) -> Result<(), Duration> {
+        match (self.state, self.opened_at) {
+            (CircuitState::Open, Some(opened_at)) => {
+                let elapsed = opened_at.elapsed();
+                if elapsed < open_duration {
+                    return Err(open_duration - elapsed);
+                }
+                self.state = CircuitState::HalfOpen;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    /// Record a failure, returning whether it opened the circuit
+    fn failure(&mut self, threshold: u32) -> bool {
+        self.consecutive_failures += 1;
+        let open = self.state == CircuitState::HalfOpen || self.consecutive_failures >= threshold;
+        if open && self.state != CircuitState::Open {
+            self.state = CircuitState::Open;
+            self.opened_at = Some(Instant::now());
+            return true;
+        }
+        false
+    }
+}
+
+/// What to do with the outcome of one attempt
+struct Outcome {
+    result: Result<Response, HttpError>,
+    /// Whether the attempt counts against the server's health
+    failed: bool,
+    /// Why the attempt may be retried
+    retry: Option<String>,
+    retry_after: Option<Duration>,
+}
+
+/// HTTP client with retries, backoff and a circuit breaker
+pub struct ResilientClient {
+    client: Client,
+    config: RetryConfig,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl ResilientClient {
+    pub fn new(config: RetryConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            breaker: Mutex::new(CircuitBreaker { state: CircuitState::Closed, consecutive_failures: 0, opened_at: None }),
+        }
+    }
+
+    /// Current state of the circuit breaker
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.lock().unwrap().state
+    }
+
+    /// Send a request, retrying transient failures. `prepare` adds headers
+    /// and body, and is called again for every attempt. Once retries run
+    /// out, the last response is returned even if it is a 5xx, so callers
+    /// report the server's error as before.
+    pub fn send(&self, method: Method, url: &str, prepare: impl Fn(RequestBuilder) -> RequestBuilder) -> Result<Response, HttpError> {
+        let mut attempt = 0;
+        loop {
+            self.breaker.lock().unwrap().admit(self.config.open_duration)
+                .map_err(|retry_in| HttpError::CircuitOpen { retry_in })?;
+
+            let request = prepare(self.client.request(method.clone(), url)).timeout(self.config.request_timeout);
+            let outcome = Self::classify(&method, request.send());
+
+            let opened = {
+                let mut breaker = self.breaker.lock().unwrap();
+                if outcome.failed {
+                    breaker.failure(self.config.failure_threshold)
+                } else {
+                    breaker.success();
+                    false
+                }
+            };
+            if opened {
+                if self.config.verbose {
+                    eprintln!("Circuit opened after {} consecutive failures", self.config.failure_threshold);
+                }
+                return outcome.result;
+            }
+            let Some(reason) = outcome.retry else {
+                return outcome.result;
+            };
+            let delay = match outcome.retry_after {
+                Some(delay) if delay > self.config.max_retry_after => return outcome.result,
+                Some(delay) => delay,
+                None => self.backoff(attempt),
+            };
+            if attempt >= self.config.max_retries {
+                return outcome.result;
+            }
+            attempt += 1;
+            if self.config.verbose {
+                eprintln!("{} {}: {}; retry {}/{} in {:.1}s",
+                    method, url, reason, attempt, self.config.max_retries, delay.as_secs_f64());
+            }
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// Judge one attempt: whether the server failed it and whether it may
+    /// be retried. A 4xx is the caller's problem and throttling is not a
+    /// failure, so neither counts against the server.
+    fn classify(method: &Method, result: reqwest::Result<Response>) -> Outcome {
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                let declined = status == StatusCode::SERVICE_UNAVAILABLE || status == StatusCode::TOO_MANY_REQUESTS;
+                let retryable = declined || (status.is_server_error() && *method != Method::POST);
+                Outcome {
+                    failed: status.is_server_error(),
+                    retry: retryable.then(|| status.to_string()),
+                    retry_after: declined.then(|| retry_after(&response)).flatten(),
+                    result: Ok(response),
+                }
+            }
+            Err(e) => {
+                let retryable = e.is_connect() || (e.is_timeout() && *method != Method::POST);
+                Outcome {
+                    failed: e.is_connect() || e.is_timeout(),
+                    retry: retryable.then(|| e.to_string()),
+                    retry_after: None,
+                    result: Err(HttpError::Request(e)),
+                }
+            }
+        }
+    }
+
+    /// Exponential backoff before retry `attempt + 1`
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.config.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff)
+    }
+}
+
+/// Delay a response's `Retry-After` header asks for
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = parse_http_date(value)?;
+    Some(at.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+/// Parse an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let parts: Vec<&str> = date.split(' ').collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let month = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"]
+        .iter()
+        .position(|name| name == month)? as i64 + 1;
+    let (day, year): (i64, i64) = (day.parse().ok()?, year.parse().ok()?);
+    let mut clock = time.split(':').map(str::parse::<u64>);
+    let (hours, minutes, seconds) = (clock.next()?.ok()?, clock.next()?.ok()?, clock.next()?.ok()?);
+
+    // Days since the epoch of a proleptic Gregorian date
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hours * 3_600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AuroraClient;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// HTTP server answering each request with the next scripted
+    /// `(status, extra headers, body)`, repeating the last one once the
+    /// script runs out
+    struct MockServer {
+        port: u16,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        fn start(script: Vec<(u16, &'static str, &'static str)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let requests = Arc::new(AtomicUsize::new(0));
+            let served = requests.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap();
+                            }
+                        }
+                    }
+                    reader.read_exact(&mut vec![0; content_length]).unwrap();
+
+                    let n = served.fetch_add(1, Ordering::SeqCst);
+                    let (status, headers, body) = script[n.min(script.len() - 1)];
+                    write!(stream, "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status, headers, body.len(), body).unwrap();
+                }
+            });
+            Self { port, requests }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    fn fast_retries() -> RetryConfig {
+        RetryConfig {
+            initial_backoff: Duration::from_millis(10),
+            request_timeout: Duration::from_secs(5),
+            verbose: true,
+            ..RetryConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_command_survives_intermittent_503s() {
+        const LOGIN: (u16, &str, &str) = (200, "", r#"{"token":"t"}"#);
+        const UNAVAILABLE: (u16, &str, &str) = (503, "", r#"{"error":"unavailable"}"#);
+        let server = MockServer::start(vec![
+            LOGIN,
+            UNAVAILABLE,
+            (503, "Retry-After: 1\r\n", r#"{"error":"unavailable"}"#),
+            (200, "", "{}"),
+        ]);
+        let client = AuroraClient::with_retry("127.0.0.1", &server.port.to_string(), "aurora", "aurora", "aurora", fast_retries()).unwrap();
+
+        let start = Instant::now();
+        client.run_vacuum().unwrap();
+        assert_eq!(server.requests(), 4);
+        // The second retry waited out Retry-After rather than the 20ms backoff
+        assert!(start.elapsed() >= Duration::from_secs(1), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_circuit_opens_on_persistent_failure() {
+        let server = MockServer::start(vec![(500, "", r#"{"error":"down"}"#)]);
+        let client = ResilientClient::new(RetryConfig { failure_threshold: 3, ..fast_retries() });
+        let url = format!("http://127.0.0.1:{}/api/status", server.port);
+
+        // Opening the circuit ends the retries; the server's error is returned as is
+        let response = client.send(Method::GET, &url, |request| request).unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(server.requests(), 3);
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        // Open: fails without reaching the server
+        match client.send(Method::GET, &url, |request| request) {
+            Err(HttpError::CircuitOpen { retry_in }) => assert!(retry_in > Duration::from_secs(25)),
+            other => panic!("expected an open circuit, got {:?}", other.map(|r| r.status())),
+        }
+        assert_eq!(server.requests(), 3);
+
+        // A POST that failed after the server may have acted is not retried
+        let server = MockServer::start(vec![(502, "", "{}")]);
+        let client = ResilientClient::new(fast_retries());
+        let url = format!("http://127.0.0.1:{}/api/maintenance/vacuum", server.port);
+        assert_eq!(client.send(Method::POST, &url, |request| request).unwrap().status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        let at = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(at.duration_since(UNIX_EPOCH).unwrap(), Duration::from_secs(784_111_777));
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    }
+}
@@ -3,7 +3,7 @@
 //! Command-line interface for AuroraDB administration and management.
 //! Provides database operations, monitoring, and maintenance utilities.
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 use std::io::{self, Write};
 use tokio::runtime::Runtime;
 use reqwest::Client;
@@ -11,10 +11,12 @@ use serde_json::Value;
 
 mod commands;
 mod client;
+mod http;
 mod output;
 
 use commands::*;
 use client::AuroraClient;
+use http::RetryConfig;
 use output::OutputFormat;
 
 #[tokio::main]
@@ -57,6 +59,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .value_name("FORMAT")
             .help("Output format (table, json, csv)")
             .default_value("table"))
+        .arg(Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .action(ArgAction::SetTrue)
+            .help("Log retried requests"))
+        .arg(Arg::new("retries")
+            .long("retries")
+            .value_name("N")
+            .help("Retries of a request that failed transiently")
+            .value_parser(clap::value_parser!(u32))
+            .default_value("3"))
+        .arg(Arg::new("timeout")
+            .long("timeout")
+            .value_name("SECONDS")
+            .help("Time limit of each request attempt")
+            .value_parser(clap::value_parser!(u64))
+            .default_value("30"))
         .subcommand(
             Command::new("status")
                 .about("Show database status and health")
@@ -197,6 +216,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             .help("Table name (optional)")
                             .required(false))
                 )
+                .subcommand(
+                    Command::new("bloat")
+                        .about("Estimate table and index bloat and recommend vacuum/reindex")
+                        .arg(Arg::new("table")
+                            .help("Table name (optional)")
+                            .required(false))
+                )
         );
 
     let matches = app.get_matches();
@@ -220,7 +246,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     // Create client
-    let client = AuroraClient::new(host, port, user, &password, database)?;
+    let retry = RetryConfig {
+        max_retries: *matches.get_one::<u32>("retries").unwrap(),
+        request_timeout: std::time::Duration::from_secs(*matches.get_one::<u64>("timeout").unwrap()),
+        verbose: matches.get_flag("verbose"),
+        ..RetryConfig::default()
+    };
+    let client = AuroraClient::with_retry(host, port, user, &password, database, retry)?;
 
     // Execute command
     match matches.subcommand() {
@@ -296,6 +328,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let table = sub_matches.get_one::<String>("table");
                     cmd_maintenance_reindex(&client, table.map(|s| s.as_str())).await?;
                 }
+                Some(("bloat", sub_matches)) => {
+                    let table = sub_matches.get_one::<String>("table");
+                    cmd_maintenance_bloat(&client, table.map(|s| s.as_str()), output_format).await?;
+                }
                 _ => print_help("maintenance"),
             }
         }
@@ -80,6 +80,7 @@ async fn initialize_production_database() -> Result<Arc<AuroraDB>, Box<dyn std::
                 adaptive_threshold: 100_000,
                 vector_threshold: 0.1,
             },
+            columnar: aurora_db::config::ColumnarConfig::default(),
             selection_strategy: "workload_based".to_string(),
         },
         transaction: TransactionConfig {
@@ -0,0 +1,30 @@
+//! Feeds arbitrary client bytes to the wire protocol decoders.
+//!
+//! Run with `cargo fuzz run wire_protocol` from the repository root. Every
+//! input must decode or fail with a `ProtocolError`; a panic, overflow or
+//! out-of-memory is a bug.
+
+#![no_main]
+
+use aurora_db::network::protocol::{decode_frame, decode_startup, MessageSerializer, DEFAULT_MAX_MESSAGE_SIZE};
+use aurora_db::network::protocols::{AuroraBinarySerializer, PostgreSQLSerializer, PostgreSQLStartupParser, VectorEncoder};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // A stream of typed messages; every frame must lie within the input
+    let mut rest = data;
+    while let Ok(Some((frame, consumed))) = decode_frame(rest, DEFAULT_MAX_MESSAGE_SIZE) {
+        assert!(consumed >= 5 && consumed <= rest.len());
+        assert_eq!(frame.body.len(), consumed - 5);
+        rest = &rest[consumed..];
+    }
+
+    if let Ok(Some((body, consumed))) = decode_startup(data) {
+        assert_eq!(body.len() + 4, consumed);
+        let _ = PostgreSQLStartupParser::parse_startup(&data[..consumed]);
+    }
+    let _ = PostgreSQLStartupParser::parse_startup(data);
+    let _ = PostgreSQLSerializer::new().deserialize(data);
+    let _ = AuroraBinarySerializer::new().deserialize(data);
+    let _ = VectorEncoder::decode_vector(data);
+});
@@ -0,0 +1,791 @@
+//! AuroraDB Admin HTTP API
+//!
+//! Authenticated REST endpoints under `/admin` for schema management:
+//! - Create/drop tables and indexes
+//! - List schemas (tables, columns, indexes)
+//! - Trigger VACUUM / ANALYZE
+//! - Estimate table and index bloat, with the VACUUM / REINDEX it calls for
+//! - Report progress of running queries
+//! - Prepare and pin query plans ahead of their first run
+//! - RBAC-checked per operation (JWT bearer tokens)
+//! - Structured JSON errors with stable error codes
+//! - Input validation (reserved names, type syntax) before touching the engine
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use warp::filters::BoxedFilter;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::{Filter, Reply};
+use tracing::debug;
+use crate::engine::{AuroraDB, BloatRecommendation, BloatReport, ColumnDefinition, DataType, IndexDefinition, IndexType, MaintenanceReport, TableSchema, UserContext};
+use crate::errors::{AuroraError, ErrorCategory};
+use crate::security::{AuthManager, Permission, PermissionResult, RBACManager};
+use super::rest_server::ApiResponse;
+
+/// SQL keywords that cannot be used as table, column or index names
+const RESERVED_NAMES: &[&str] = &[
+    "all", "alter", "and", "as", "asc", "between", "by", "case", "check", "column",
+    "constraint", "create", "cross", "default", "delete", "desc", "distinct", "drop",
+    "else", "end", "exists", "false", "foreign", "from", "full", "grant", "group",
+    "having", "in", "index", "inner", "insert", "into", "is", "join", "key", "left",
+    "like", "limit", "not", "null", "offset", "on", "or", "order", "outer", "primary",
+    "references", "revoke", "right", "select", "set", "table", "then", "true", "union",
+    "unique", "update", "user", "using", "values", "when", "where", "with",
+];
+
+/// Prefix reserved for system catalog objects
+const SYSTEM_PREFIX: &str = "aurora_";
+
+/// Maximum identifier length
+const MAX_IDENTIFIER_LEN: usize = 63;
+
+/// Maximum vector dimension accepted in `VECTOR(n)`
+const MAX_VECTOR_DIMENSION: usize = 65_536;
+
+/// Maximum admin request body size
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Admin API configuration
+#[derive(Debug, Clone)]
+pub struct AdminApiConfig {
+    /// Maximum number of columns accepted in CREATE TABLE
+    pub max_columns: usize,
+}
+
+impl Default for AdminApiConfig {
+    fn default() -> Self {
+        Self { max_columns: 1600 }
+    }
+}
+
+/// Admin API backed by the database engine
+#[derive(Clone)]
+pub struct AdminApi {
+    db: Arc<AuroraDB>,
+    auth_manager: Arc<AuthManager>,
+    rbac_manager: Arc<RBACManager>,
+    config: AdminApiConfig,
+}
+
+impl AdminApi {
+    /// Create the admin API
+    pub fn new(db: Arc<AuroraDB>, auth_manager: Arc<AuthManager>, rbac_manager: Arc<RBACManager>, config: AdminApiConfig) -> Self {
+        Self { db, auth_manager, rbac_manager, config }
+    }
+
+    /// All `/admin` routes
+    pub fn routes(&self) -> BoxedFilter<(Response,)> {
+        let api = Arc::new(self.clone());
+        let api = warp::any().map(move || api.clone());
+        let auth = warp::header::optional::<String>("authorization");
+        let body = warp::body::content_length_limit(MAX_BODY_BYTES).and(warp::body::bytes());
+
+        let list_schemas = warp::path!("admin" / "schemas")
+            .and(warp::get())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.list_schemas(auth).await)
+            });
+
+        let create_table = warp::path!("admin" / "tables")
+            .and(warp::post())
+            .and(auth.clone())
+            .and(body.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, body: Bytes, api: Arc<AdminApi>| async move {
+                finish(api.create_table(auth, body).await)
+            });
+
+        let drop_table = warp::path!("admin" / "tables" / String)
+            .and(warp::delete())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|name: String, auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.drop_table(auth, name).await)
+            });
+
+        let create_index = warp::path!("admin" / "indexes")
+            .and(warp::post())
+            .and(auth.clone())
+            .and(body.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, body: Bytes, api: Arc<AdminApi>| async move {
+                finish(api.create_index(auth, body).await)
+            });
+
+        let drop_index = warp::path!("admin" / "indexes" / String)
+            .and(warp::delete())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|name: String, auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.drop_index(auth, name).await)
+            });
+
+        let vacuum = warp::path!("admin" / "tables" / String / "vacuum")
+            .and(warp::post())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|name: String, auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.maintenance(auth, name, MaintenanceOp::Vacuum).await)
+            });
+
+        let analyze = warp::path!("admin" / "tables" / String / "analyze")
+            .and(warp::post())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|name: String, auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.maintenance(auth, name, MaintenanceOp::Analyze).await)
+            });
+
+        let progress = warp::path!("admin" / "progress")
+            .and(warp::get())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.query_progress(auth).await)
+            });
+
+        let bloat = warp::path!("admin" / "bloat")
+            .and(warp::get())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.bloat_report(auth).await)
+            });
+
+        let list_plans = warp::path!("admin" / "plans")
+            .and(warp::get())
+            .and(auth.clone())
+            .and(api.clone())
+            .and_then(|auth: Option<String>, api: Arc<AdminApi>| async move {
+                finish(api.list_pinned_plans(auth).await)
+            });
+
+        let prepare_plan = warp::path!("admin" / "plans")
+            .and(warp::post())
+            .and(auth)
+            .and(body)
+            .and(api)
+            .and_then(|auth: Option<String>, body: Bytes, api: Arc<AdminApi>| async move {
+                finish(api.prepare_plan(auth, body).await)
+            });
+
+        list_schemas
+            .or(create_table).unify()
+            .or(drop_table).unify()
+            .or(create_index).unify()
+            .or(drop_index).unify()
+            .or(vacuum).unify()
+            .or(analyze).unify()
+            .or(progress).unify()
+            .or(bloat).unify()
+            .or(list_plans).unify()
+            .or(prepare_plan).unify()
+            .boxed()
+    }
+
+    async fn list_schemas(&self, auth: Option<String>) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let user = self.authorize(&user_id, Permission::SelectTable("*".to_string()))?;
+
+        let indexes = self.db.list_indexes().await;
+        let mut tables = Vec::new();
+        for name in self.db.list_tables().await {
+            let columns = self.db.describe_table(&name).await?
+                .into_iter()
+                .map(|column| ColumnListing {
+                    name: column.name,
+                    data_type: format!("{:?}", column.data_type),
+                    nullable: column.nullable,
+                })
+                .collect();
+
+            tables.push(TableListing {
+                indexes: indexes.iter()
+                    .filter(|idx| idx.table_name == name)
+                    .map(|idx| idx.name.clone())
+                    .collect(),
+                name,
+                columns,
+            });
+        }
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        debug!("Admin schema listing for {}", user.username);
+        Ok(success(StatusCode::OK, SchemaListing { tables }))
+    }
+
+    async fn create_table(&self, auth: Option<String>, body: Bytes) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let request: CreateTableRequest = parse_body(&body)?;
+        let user = self.authorize(&user_id, Permission::CreateTable(request.name.clone()))?;
+        let schema = request.validate(self.config.max_columns)?;
+
+        self.db.create_table(&request.name, &schema, &user).await?;
+        Ok(success(StatusCode::CREATED, DdlResponse::new("table", &request.name, "created")))
+    }
+
+    async fn drop_table(&self, auth: Option<String>, name: String) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let user = self.authorize(&user_id, Permission::DropTable(name.clone()))?;
+        validate_path_identifier("name", &name)?;
+
+        self.db.drop_table(&name, &user).await?;
+        Ok(success(StatusCode::OK, DdlResponse::new("table", &name, "dropped")))
+    }
+
+    async fn create_index(&self, auth: Option<String>, body: Bytes) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let request: CreateIndexRequest = parse_body(&body)?;
+        let user = self.authorize(&user_id, Permission::AlterTable(request.table.clone()))?;
+        let index = request.validate()?;
+
+        self.db.create_index(&request.table, &index, request.unique, &user).await?;
+        Ok(success(StatusCode::CREATED, DdlResponse::new("index", &request.name, "created")))
+    }
+
+    async fn drop_index(&self, auth: Option<String>, name: String) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        validate_path_identifier("name", &name)?;
+        let table = self.db.list_indexes().await
+            .into_iter()
+            .find(|idx| idx.name == name)
+            .map(|idx| idx.table_name)
+            .unwrap_or_else(|| "*".to_string());
+        let user = self.authorize(&user_id, Permission::AlterTable(table))?;
+
+        self.db.drop_index(&name, &user).await?;
+        Ok(success(StatusCode::OK, DdlResponse::new("index", &name, "dropped")))
+    }
+
+    async fn maintenance(&self, auth: Option<String>, table: String, op: MaintenanceOp) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let user = self.authorize(&user_id, Permission::AlterTable(table.clone()))?;
+        validate_path_identifier("name", &table)?;
+
+        let report = match op {
+            MaintenanceOp::Vacuum => self.db.vacuum_table(&table, &user).await?,
+            MaintenanceOp::Analyze => self.db.analyze_table(&table, &user).await?,
+        };
+        Ok(success(StatusCode::OK, MaintenanceResponse::from(report)))
+    }
+
+    async fn query_progress(&self, auth: Option<String>) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        self.authorize(&user_id, Permission::SelectTable("*".to_string()))?;
+
+        Ok(success(StatusCode::OK, self.db.query_progress()))
+    }
+
+    async fn bloat_report(&self, auth: Option<String>) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        self.authorize(&user_id, Permission::SelectTable("*".to_string()))?;
+
+        let report = self.db.bloat_report(None).await?;
+        Ok(success(StatusCode::OK, BloatListing { recommendations: report.recommendations(), report }))
+    }
+
+    async fn list_pinned_plans(&self, auth: Option<String>) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        self.authorize(&user_id, Permission::SelectTable("*".to_string()))?;
+
+        Ok(success(StatusCode::OK, PlanListing { pinned: self.db.pinned_plans() }))
+    }
+
+    async fn prepare_plan(&self, auth: Option<String>, body: Bytes) -> Result<Response, AdminError> {
+        let user_id = self.authenticate(auth.as_deref())?;
+        let request: PreparePlanRequest = parse_body(&body)?;
+        // Pinned plans hold cache space for every session
+        self.authorize(&user_id, Permission::AlterTable("*".to_string()))?;
+        if request.sql.trim().is_empty() {
+            return Err(AdminError::validation("Invalid plan", vec![FieldError::new("sql", "must not be empty")]));
+        }
+
+        self.db.prepare_plan(&request.sql, request.pin).await?;
+        let status = if request.pin { "pinned" } else { "prepared" };
+        Ok(success(StatusCode::CREATED, DdlResponse::new("plan", &request.sql, status)))
+    }
+
+    /// Verify the bearer token and return the user id it was issued to
+    fn authenticate(&self, header: Option<&str>) -> Result<String, AdminError> {
+        let token = header
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| AdminError::unauthorized("Missing or malformed bearer token"))?;
+
+        self.auth_manager.verify_jwt(token)
+            .map_err(|e| AdminError::unauthorized(e.message))
+    }
+
+    /// Check `permission` against RBAC and build the engine user context
+    fn authorize(&self, user_id: &str, permission: Permission) -> Result<UserContext, AdminError> {
+        let user = self.rbac_manager.get_user(user_id)
+            .filter(|u| u.is_active)
+            .ok_or_else(|| AdminError::unauthorized("Unknown or inactive user"))?;
+
+        let superuser = matches!(
+            self.rbac_manager.check_permission(user_id, &Permission::SuperUser),
+            PermissionResult::Granted
+        );
+        if !superuser {
+            if let PermissionResult::Denied(reason) = self.rbac_manager.check_permission(user_id, &permission) {
+                return Err(AdminError::forbidden(reason));
+            }
+        }
+
+        Ok(UserContext {
+            user_id: user.id,
+            username: user.username,
+            roles: user.roles.into_iter().collect(),
+            client_ip: None,
+            session_id: format!("admin-api-{}", uuid::Uuid::new_v4().simple()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MaintenanceOp {
+    Vacuum,
+    Analyze,
+}
+
+/// CREATE TABLE request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTableRequest {
+    pub name: String,
+    pub columns: Vec<ColumnSpec>,
+    #[serde(default)]
+    pub primary_key: Option<Vec<String>>,
+}
+
+/// Column in a CREATE TABLE request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+impl CreateTableRequest {
+    /// Validate names and types, collecting every problem found
+    pub fn validate(&self, max_columns: usize) -> Result<TableSchema, AdminError> {
+        let mut errors = Vec::new();
+        check_identifier(&mut errors, "name", &self.name);
+
+        if self.columns.is_empty() {
+            errors.push(FieldError::new("columns", "table must have at least one column"));
+        } else if self.columns.len() > max_columns {
+            errors.push(FieldError::new("columns", format!("at most {} columns are allowed", max_columns)));
+        }
+
+        let mut seen = HashSet::new();
+        let mut columns = Vec::with_capacity(self.columns.len());
+        for (i, column) in self.columns.iter().enumerate() {
+            check_identifier(&mut errors, &format!("columns[{}].name", i), &column.name);
+            if !seen.insert(column.name.to_lowercase()) {
+                errors.push(FieldError::new(format!("columns[{}].name", i), format!("duplicate column '{}'", column.name)));
+            }
+
+            match parse_type(&column.data_type) {
+                Ok(data_type) => columns.push(ColumnDefinition {
+                    name: column.name.clone(),
+                    data_type,
+                    nullable: column.nullable,
+                    default_value: column.default.clone(),
+                }),
+                Err(message) => errors.push(FieldError::new(format!("columns[{}].type", i), message)),
+            }
+        }
+
+        if let Some(primary_key) = &self.primary_key {
+            for key in primary_key {
+                if !seen.contains(&key.to_lowercase()) {
+                    errors.push(FieldError::new("primary_key", format!("unknown column '{}'", key)));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AdminError::validation("Invalid CREATE TABLE request", errors));
+        }
+
+        Ok(TableSchema {
+            columns,
+            primary_key: self.primary_key.clone(),
+            indexes: Vec::new(),
+        })
+    }
+}
+
+/// CREATE INDEX request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateIndexRequest {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub index_type: Option<String>,
+    #[serde(default)]
+    pub unique: bool,
+}
+
+impl CreateIndexRequest {
+    /// Validate names and index type, collecting every problem found
+    pub fn validate(&self) -> Result<IndexDefinition, AdminError> {
+        let mut errors = Vec::new();
+        check_identifier(&mut errors, "name", &self.name);
+        check_identifier(&mut errors, "table", &self.table);
+
+        if self.columns.is_empty() {
+            errors.push(FieldError::new("columns", "index must cover at least one column"));
+        }
+        for (i, column) in self.columns.iter().enumerate() {
+            check_identifier(&mut errors, &format!("columns[{}]", i), column);
+        }
+
+        let index_type = match self.index_type.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("btree") => Some(IndexType::BTree),
+            Some("hash") => Some(IndexType::Hash),
+            Some("vector") | Some("hnsw") => Some(IndexType::Vector),
+            Some("fulltext") => Some(IndexType::FullText),
+            Some("spatial") => Some(IndexType::Spatial),
+            Some("gin") => Some(IndexType::Gin),
+            Some(other) => {
+                errors.push(FieldError::new("index_type", format!("unknown index type '{}'", other)));
+                None
+            }
+        };
+
+        if self.unique && matches!(index_type, Some(IndexType::Vector) | Some(IndexType::FullText) | Some(IndexType::Gin)) {
+            errors.push(FieldError::new("unique", "unique is only supported for btree and hash indexes"));
+        }
+
+        match index_type {
+            Some(index_type) if errors.is_empty() => Ok(IndexDefinition {
+                name: self.name.clone(),
+                columns: self.columns.clone(),
+                index_type,
+            }),
+            _ => Err(AdminError::validation("Invalid CREATE INDEX request", errors)),
+        }
+    }
+}
+
+/// Check an identifier: length, character set, reserved words and system prefix
+pub fn validate_identifier(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if name.len() > MAX_IDENTIFIER_LEN {
+        return Err(format!("must be at most {} characters", MAX_IDENTIFIER_LEN));
+    }
+
+    let mut chars = name.chars();
+    let first = chars.next().unwrap();
+    if !(first.is_ascii_alphabetic() || first == '_') {
+        return Err("must start with a letter or underscore".to_string());
+    }
+    if !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err("may only contain letters, digits and underscores".to_string());
+    }
+
+    let lower = name.to_lowercase();
+    if RESERVED_NAMES.contains(&lower.as_str()) {
+        return Err(format!("'{}' is a reserved word", name));
+    }
+    if lower.starts_with(SYSTEM_PREFIX) {
+        return Err(format!("names starting with '{}' are reserved for system objects", SYSTEM_PREFIX));
+    }
+
+    Ok(())
+}
+
+/// Parse a column type such as `INTEGER`, `VARCHAR(255)`, `VECTOR(384)` or `TEXT[]`
+pub fn parse_type(type_name: &str) -> Result<DataType, String> {
+    let normalized = type_name.trim().to_uppercase();
+
+    if let Some(element) = normalized.strip_suffix("[]") {
+        return parse_type(element).map(|t| DataType::Array(Box::new(t)));
+    }
+
+    let (base, arg) = match normalized.find('(') {
+        Some(open) => {
+            let close = normalized.strip_suffix(')')
+                .ok_or_else(|| format!("unbalanced parentheses in type '{}'", type_name))?;
+            let arg = close[open + 1..].trim();
+            let arg = arg.parse::<usize>()
+                .map_err(|_| format!("type argument '{}' must be a positive integer", arg))?;
+            (normalized[..open].trim(), Some(arg))
+        }
+        None => (normalized.as_str(), None),
+    };
+
+    let data_type = match (base, arg) {
+        ("INT" | "INTEGER" | "INT4", None) => DataType::Integer,
+        ("BIGINT" | "INT8", None) => DataType::BigInt,
+        ("FLOAT" | "REAL" | "FLOAT4", None) => DataType::Float,
+        ("DOUBLE" | "DOUBLE PRECISION" | "FLOAT8", None) => DataType::Double,
+        ("TEXT", None) => DataType::Text,
+        ("VARCHAR" | "CHAR", Some(n)) if n > 0 => DataType::Text,
+        ("BOOL" | "BOOLEAN", None) => DataType::Boolean,
+        ("TIMESTAMP", None) => DataType::Timestamp,
+        ("JSON" | "JSONB", None) => DataType::Json,
+        ("VECTOR", Some(n)) if (1..=MAX_VECTOR_DIMENSION).contains(&n) => DataType::Vector(n),
+        ("VECTOR", Some(_)) => return Err(format!("vector dimension must be between 1 and {}", MAX_VECTOR_DIMENSION)),
+        ("VECTOR", None) => return Err("VECTOR requires a dimension, e.g. VECTOR(384)".to_string()),
+        ("VARCHAR" | "CHAR", _) => return Err(format!("{} requires a positive length", base)),
+        _ => return Err(format!("unknown type '{}'", type_name.trim())),
+    };
+
+    Ok(data_type)
+}
+
+fn check_identifier(errors: &mut Vec<FieldError>, field: &str, name: &str) {
+    if let Err(message) = validate_identifier(name) {
+        errors.push(FieldError::new(field, message));
+    }
+}
+
+fn validate_path_identifier(field: &str, name: &str) -> Result<(), AdminError> {
+    validate_identifier(name)
+        .map_err(|message| AdminError::validation("Invalid identifier", vec![FieldError::new(field, message)]))
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(body: &Bytes) -> Result<T, AdminError> {
+    serde_json::from_slice(body).map_err(|e| AdminError {
+        status: StatusCode::BAD_REQUEST,
+        code: "InvalidJson".to_string(),
+        message: "Request body is not valid JSON for this endpoint".to_string(),
+        details: vec![FieldError::new(format!("line {}, column {}", e.line(), e.column()), e.to_string())],
+    })
+}
+
+fn success<T: Serialize>(status: StatusCode, data: T) -> Response {
+    warp::reply::with_status(warp::reply::json(&ApiResponse::success(data)), status).into_response()
+}
+
+fn finish(result: Result<Response, AdminError>) -> Result<Response, Infallible> {
+    Ok(result.unwrap_or_else(Reply::into_response))
+}
+
+/// Single validation problem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into() }
+    }
+}
+
+/// Structured admin API error
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    pub code: String,
+    pub message: String,
+    pub details: Vec<FieldError>,
+}
+
+impl AdminError {
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self { status: StatusCode::UNAUTHORIZED, code: "Unauthorized".to_string(), message: message.into(), details: Vec::new() }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self { status: StatusCode::FORBIDDEN, code: "Forbidden".to_string(), message: message.into(), details: Vec::new() }
+    }
+
+    fn validation(message: impl Into<String>, details: Vec<FieldError>) -> Self {
+        Self { status: StatusCode::BAD_REQUEST, code: "ValidationFailed".to_string(), message: message.into(), details }
+    }
+}
+
+impl From<AuroraError> for AdminError {
+    fn from(err: AuroraError) -> Self {
+        let status = match err.category {
+            ErrorCategory::Authentication => StatusCode::UNAUTHORIZED,
+            ErrorCategory::Authorization => StatusCode::FORBIDDEN,
+            ErrorCategory::Validation | ErrorCategory::Query => StatusCode::BAD_REQUEST,
+            ErrorCategory::Transaction => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status,
+            code: format!("{:?}", err.code),
+            message: err.message,
+            details: err.details.into_iter().map(|d| FieldError::new("details", d)).collect(),
+        }
+    }
+}
+
+/// Error response body
+#[derive(Serialize)]
+struct AdminErrorResponse {
+    success: bool,
+    error: AdminError,
+    timestamp: i64,
+}
+
+impl Reply for AdminError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        let body = AdminErrorResponse {
+            success: false,
+            error: self,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        warp::reply::with_status(warp::reply::json(&body), status).into_response()
+    }
+}
+
+/// Plan to prepare ahead of its first run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreparePlanRequest {
+    pub sql: String,
+    #[serde(default)]
+    pub pin: bool,
+}
+
+/// Pinned plan listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanListing {
+    pub pinned: Vec<String>,
+}
+
+/// Bloat estimates with the maintenance they recommend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BloatListing {
+    #[serde(flatten)]
+    pub report: BloatReport,
+    pub recommendations: Vec<BloatRecommendation>,
+}
+
+/// Schema listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaListing {
+    pub tables: Vec<TableListing>,
+}
+
+/// Table in a schema listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableListing {
+    pub name: String,
+    pub columns: Vec<ColumnListing>,
+    pub indexes: Vec<String>,
+}
+
+/// Column in a schema listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnListing {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// Result of a DDL operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DdlResponse {
+    pub object: String,
+    pub name: String,
+    pub status: String,
+}
+
+impl DdlResponse {
+    fn new(object: &str, name: &str, status: &str) -> Self {
+        Self { object: object.to_string(), name: name.to_string(), status: status.to_string() }
+    }
+}
+
+/// Result of VACUUM / ANALYZE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceResponse {
+    pub table: String,
+    pub operation: String,
+    pub indexes_processed: usize,
+    pub row_count: u64,
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+}
+
+impl From<MaintenanceReport> for MaintenanceResponse {
+    fn from(report: MaintenanceReport) -> Self {
+        Self {
+            table: report.table_name,
+            operation: report.operation,
+            indexes_processed: report.indexes_processed,
+            row_count: report.row_count,
+            size_bytes: report.size_bytes,
+            duration_ms: report.duration.as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_identifier() {
+        assert!(validate_identifier("users").is_ok());
+        assert!(validate_identifier("_staging_2024").is_ok());
+        assert!(validate_identifier("").is_err());
+        assert!(validate_identifier("1users").is_err());
+        assert!(validate_identifier("user-table").is_err());
+        assert!(validate_identifier("SELECT").is_err());
+        assert!(validate_identifier("aurora_catalog").is_err());
+        assert!(validate_identifier(&"x".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_parse_type() {
+        assert!(matches!(parse_type("integer"), Ok(DataType::Integer)));
+        assert!(matches!(parse_type("VARCHAR(255)"), Ok(DataType::Text)));
+        assert!(matches!(parse_type("vector(384)"), Ok(DataType::Vector(384))));
+        assert!(matches!(parse_type("TEXT[]"), Ok(DataType::Array(_))));
+        assert!(parse_type("VECTOR").is_err());
+        assert!(parse_type("VECTOR(0)").is_err());
+        assert!(parse_type("VARCHAR(abc)").is_err());
+        assert!(parse_type("INTEGER(").is_err());
+        assert!(parse_type("blob").is_err());
+    }
+
+    #[test]
+    fn test_create_table_validation_collects_all_errors() {
+        let request = CreateTableRequest {
+            name: "select".to_string(),
+            columns: vec![
+                ColumnSpec { name: "id".to_string(), data_type: "INTEGR".to_string(), nullable: false, default: None },
+                ColumnSpec { name: "id".to_string(), data_type: "TEXT".to_string(), nullable: true, default: None },
+            ],
+            primary_key: Some(vec!["missing".to_string()]),
+        };
+
+        let err = request.validate(16).unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        let fields: Vec<_> = err.details.iter().map(|d| d.field.as_str()).collect();
+        assert!(fields.contains(&"name"));
+        assert!(fields.contains(&"columns[0].type"));
+        assert!(fields.contains(&"columns[1].name"));
+        assert!(fields.contains(&"primary_key"));
+    }
+}
@@ -0,0 +1,10 @@
+//! AuroraDB HTTP APIs
+//!
+//! - `rest_server`: query, vector search, health and metrics endpoints
+//! - `admin`: authenticated table/index management under `/admin`
+
+pub mod rest_server;
+pub mod admin;
+
+pub use rest_server::{AuroraRestServer, ServerConfig};
+pub use admin::{AdminApi, AdminApiConfig};
@@ -13,6 +13,7 @@ use tokio::sync::RwLock;
 use warp::Filter;
 use serde::{Deserialize, Serialize};
 use crate::core::errors::{AuroraResult, AuroraError};
+use super::admin::AdminApi;
 
 /// AuroraDB REST API Server
 pub struct AuroraRestServer {
@@ -22,6 +23,8 @@ pub struct AuroraRestServer {
     config: ServerConfig,
     /// Request metrics
     metrics: Arc<RequestMetrics>,
+    /// Admin API mounted under `/admin` (disabled when `None`)
+    admin: Option<AdminApi>,
 }
 
 /// Server configuration
@@ -103,9 +106,16 @@ impl AuroraRestServer {
             db: Arc::new(RwLock::new(AuroraDatabase::new())),
             config,
             metrics: Arc::new(RequestMetrics::default()),
+            admin: None,
         }
     }
 
+    /// Mount the admin API under `/admin`
+    pub fn with_admin_api(mut self, admin: AdminApi) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
     /// Start the server
     pub async fn start(self) -> AuroraResult<()> {
         let db = self.db.clone();
@@ -190,11 +200,20 @@ impl AuroraRestServer {
                 warp::reply::json(&*metrics)
             });
 
+        // Admin endpoints (404 unless an admin API was mounted)
+        let admin_routes = match self.admin.clone() {
+            Some(admin) => admin.routes(),
+            None => warp::path("admin")
+                .and_then(|| async { Err::<warp::reply::Response, _>(warp::reject::not_found()) })
+                .boxed(),
+        };
+
         // Combine all routes
         let routes = health
             .or(sql)
             .or(vector)
             .or(metrics_route)
+            .or(admin_routes)
             .with(warp::log("auroradb"))
             .with(warp::cors().allow_any_origin());
 
@@ -6,15 +6,18 @@
 //! - Backup verification and integrity
 //! - Automated backup scheduling
 //! - Cross-region backup replication
+//! - Logical (SQL) dump and restore
 
 pub mod backup_manager;
 pub mod recovery_manager;
 pub mod backup_scheduler;
 pub mod backup_verifier;
 pub mod pitr_manager;
+pub mod logical_dump;
 
 pub use backup_manager::*;
 pub use recovery_manager::*;
 pub use backup_scheduler::*;
 pub use backup_verifier::*;
-pub use pitr_manager::*;
\ No newline at end of file
+pub use pitr_manager::*;
+pub use logical_dump::*;
\ No newline at end of file
@@ -0,0 +1,107 @@
+//! Catalog Change Notifications
+//!
+//! DDL publishes a [`CatalogChange`] for every object it creates, alters or
+//! drops. Caches whose entries were built from catalog objects (prepared
+//! plans, cached results, planner statistics) subscribe and evict the
+//! entries that reference the changed object.
+//!
+//! Objects are identified by an [`ObjectId`] the catalog assigns at
+//! creation and never reuses, so dropping a table and creating another with
+//! the same name cannot revive entries built against the old one.
+//! Publication is synchronous: every subscriber has invalidated before the
+//! DDL statement returns.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::RwLock;
+
+/// Catalog-assigned identity of a table or other schema object
+pub type ObjectId = u64;
+
+/// What happened to a catalog object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogChangeKind {
+    Created,
+    Altered,
+    Dropped,
+}
+
+/// One DDL change to one object
+#[derive(Debug, Clone)]
+pub struct CatalogChange {
+    pub object_id: ObjectId,
+    pub object_name: String,
+    pub kind: CatalogChangeKind,
+}
+
+/// Receives catalog changes. Called on the publishing DDL's task, so
+/// implementations should only evict entries, not do I/O.
+pub trait CatalogSubscriber: Send + Sync {
+    fn on_catalog_change(&self, change: &CatalogChange);
+}
+
+/// Fan-out of catalog changes to subscribed caches
+#[derive(Default)]
+pub struct CatalogChangeBus {
+    subscribers: RwLock<Vec<Arc<dyn CatalogSubscriber>>>,
+    published: AtomicU64,
+}
+
+impl CatalogChangeBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver every later change to `subscriber`
+    pub fn subscribe(&self, subscriber: Arc<dyn CatalogSubscriber>) {
+        self.subscribers.write().push(subscriber);
+    }
+
+    /// Deliver `change` to all subscribers, in subscription order
+    pub fn publish(&self, change: CatalogChange) {
+        log::debug!("Catalog change: {:?} '{}' (object {})", change.kind, change.object_name, change.object_id);
+        for subscriber in self.subscribers.read().iter() {
+            subscriber.on_catalog_change(&change);
+        }
+        self.published.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Number of changes published so far
+    pub fn published(&self) -> u64 {
+        self.published.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<(ObjectId, CatalogChangeKind)>>,
+    }
+
+    impl CatalogSubscriber for Recorder {
+        fn on_catalog_change(&self, change: &CatalogChange) {
+            self.seen.lock().push((change.object_id, change.kind));
+        }
+    }
+
+    #[test]
+    fn test_publish_reaches_every_subscriber() {
+        let bus = CatalogChangeBus::new();
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        bus.subscribe(first.clone());
+        bus.subscribe(second.clone());
+
+        bus.publish(CatalogChange { object_id: 7, object_name: "orders".to_string(), kind: CatalogChangeKind::Altered });
+        bus.publish(CatalogChange { object_id: 7, object_name: "orders".to_string(), kind: CatalogChangeKind::Dropped });
+
+        let expected = vec![(7, CatalogChangeKind::Altered), (7, CatalogChangeKind::Dropped)];
+        assert_eq!(*first.seen.lock(), expected);
+        assert_eq!(*second.seen.lock(), expected);
+        assert_eq!(bus.published(), 2);
+    }
+}
@@ -9,7 +9,9 @@
 //! This enables DDL operations and provides schema information for query planning.
 
 pub mod table_catalog;
+pub mod change_bus;
 pub mod system_catalog;
 
 pub use table_catalog::*;
+pub use change_bus::*;
 pub use system_catalog::*;
@@ -4,21 +4,55 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 use crate::core::{AuroraResult, AuroraError, ErrorCode};
 use crate::query::parser::ast::{CreateTableQuery, DropTableQuery, ColumnDefinition, TableConstraint};
-use crate::types::DataType;
+use crate::types::{DataType, Decimal, Interval, TimeZone};
+use crate::types::timestamp;
+use crate::storage::engine::StorageEngineType;
+use super::change_bus::{CatalogChange, CatalogChangeBus, CatalogChangeKind, ObjectId};
 
 /// Table metadata stored in the catalog
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TableMetadata {
+    /// Identity for cache invalidation; never reused, even for a table
+    /// recreated under the same name
+    #[serde(default)]
+    pub object_id: ObjectId,
     pub name: String,
     pub columns: Vec<ColumnMetadata>,
     pub constraints: Vec<TableConstraint>,
+    /// Storage engine holding the table's data
+    #[serde(default)]
+    pub storage_engine: StorageEngineType,
+    /// Whether the engine was pinned with `USING` rather than auto-selected
+    #[serde(default)]
+    pub engine_pinned: bool,
+    /// How rows are spread across partitions, if the table is partitioned
+    #[serde(default)]
+    pub partitioning: Option<PartitionScheme>,
+    /// Timestamp column holding each row's expiry; rows past it are hidden
+    /// from reads and removed by the TTL reaper
+    #[serde(default)]
+    pub ttl_column: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub modified_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Hash partitioning of a table on one column
+///
+/// Two tables with the same partition count and compatible key types place
+/// equal keys in the same partition number, so a join on those keys can be
+/// done pairwise, partition by partition.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartitionScheme {
+    /// Partition key column
+    pub column: String,
+    /// Number of hash partitions
+    pub partitions: u32,
+}
+
 /// Column metadata
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ColumnMetadata {
@@ -33,6 +67,8 @@ pub struct ColumnMetadata {
 pub struct TableCatalog {
     tables: RwLock<HashMap<String, TableMetadata>>,
     storage_path: std::path::PathBuf,
+    next_object_id: AtomicU64,
+    change_bus: Arc<CatalogChangeBus>,
 }
 
 impl TableCatalog {
@@ -41,11 +77,40 @@ impl TableCatalog {
         Self {
             tables: RwLock::new(HashMap::new()),
             storage_path,
+            next_object_id: AtomicU64::new(1),
+            change_bus: Arc::new(CatalogChangeBus::new()),
         }
     }
 
+    /// Bus this catalog publishes DDL changes to
+    pub fn change_bus(&self) -> &Arc<CatalogChangeBus> {
+        &self.change_bus
+    }
+
+    /// Object id of a table, if it exists
+    pub async fn object_id(&self, table_name: &str) -> Option<ObjectId> {
+        self.tables.read().await.get(table_name).map(|table| table.object_id)
+    }
+
+    /// Publish a change while the caller still holds the table map's write
+    /// lock, so no reader sees the new schema before caches have dropped
+    /// entries built from the old one
+    fn publish(&self, table: &TableMetadata, kind: CatalogChangeKind) {
+        self.change_bus.publish(CatalogChange {
+            object_id: table.object_id,
+            object_name: table.name.clone(),
+            kind,
+        });
+    }
+
     /// Create a table from DDL
     pub async fn create_table(&self, create_query: &CreateTableQuery) -> AuroraResult<()> {
+        let engine = create_query.storage_engine.unwrap_or_default();
+        self.create_table_with_engine(create_query, engine).await
+    }
+
+    /// Create a table from DDL, recording the storage engine it was placed on
+    pub async fn create_table_with_engine(&self, create_query: &CreateTableQuery, storage_engine: StorageEngineType) -> AuroraResult<()> {
         let mut tables = self.tables.write().await;
 
         // Check if table already exists
@@ -56,6 +121,29 @@ impl TableCatalog {
             ));
         }
 
+        if let Some(scheme) = &create_query.partitioning {
+            if !create_query.columns.iter().any(|col| col.name == scheme.column) {
+                return Err(AuroraError::new(
+                    ErrorCode::QuerySyntaxError,
+                    format!("Partition key '{}' is not a column of '{}'", scheme.column, create_query.name)
+                ));
+            }
+        }
+
+        if let Some(ttl_column) = &create_query.ttl_column {
+            match create_query.columns.iter().find(|col| &col.name == ttl_column) {
+                Some(col) if matches!(col.data_type, DataType::Timestamp | DataType::TimestampTz) => {}
+                Some(_) => return Err(AuroraError::new(
+                    ErrorCode::QuerySyntaxError,
+                    format!("TTL column '{}' must be TIMESTAMP or TIMESTAMPTZ", ttl_column)
+                )),
+                None => return Err(AuroraError::new(
+                    ErrorCode::QuerySyntaxError,
+                    format!("TTL column '{}' is not a column of '{}'", ttl_column, create_query.name)
+                )),
+            }
+        }
+
         // Convert column definitions to metadata
         let columns = create_query.columns.iter().enumerate()
             .map(|(i, col)| ColumnMetadata {
@@ -70,20 +158,26 @@ impl TableCatalog {
 
         // Create table metadata
         let metadata = TableMetadata {
+            object_id: self.next_object_id.fetch_add(1, Ordering::SeqCst),
             name: create_query.name.clone(),
             columns,
             constraints: create_query.constraints.clone(),
+            storage_engine,
+            engine_pinned: create_query.storage_engine.is_some(),
+            partitioning: create_query.partitioning.clone(),
+            ttl_column: create_query.ttl_column.clone(),
             created_at: chrono::Utc::now(),
             modified_at: chrono::Utc::now(),
         };
 
         // Store in catalog
+        self.publish(&metadata, CatalogChangeKind::Created);
         tables.insert(create_query.name.clone(), metadata);
 
         // Persist to disk
         self.save_catalog().await?;
 
-        log::info!("Created table: {}", create_query.name);
+        log::info!("Created table: {} (engine: {})", create_query.name, storage_engine);
         Ok(())
     }
 
@@ -103,7 +197,9 @@ impl TableCatalog {
         }
 
         // Remove from catalog
-        tables.remove(&drop_query.name);
+        if let Some(metadata) = tables.remove(&drop_query.name) {
+            self.publish(&metadata, CatalogChangeKind::Dropped);
+        }
 
         // Persist to disk
         self.save_catalog().await?;
@@ -112,12 +208,65 @@ impl TableCatalog {
         Ok(())
     }
 
+    /// Change a column's declared type. The caller checks that stored values
+    /// fit the new type; plans, results and statistics referencing the table
+    /// are invalidated before this returns.
+    pub async fn alter_column_type(&self, table_name: &str, column_name: &str, data_type: DataType) -> AuroraResult<()> {
+        let mut tables = self.tables.write().await;
+
+        let metadata = tables.get_mut(table_name).ok_or_else(|| AuroraError::new(
+            ErrorCode::StorageCorruption,
+            format!("Table '{}' does not exist", table_name)
+        ))?;
+        let column = metadata.columns.iter_mut().find(|c| c.name == column_name).ok_or_else(|| AuroraError::new(
+            ErrorCode::ValidationConstraintViolation,
+            format!("Column '{}' does not exist in table '{}'", column_name, table_name)
+        ))?;
+        if metadata.ttl_column.as_deref() == Some(column_name) && !matches!(data_type, DataType::Timestamp | DataType::TimestampTz) {
+            return Err(AuroraError::new(
+                ErrorCode::ValidationConstraintViolation,
+                format!("Column '{}' is the TTL column of '{}' and must stay TIMESTAMP or TIMESTAMPTZ", column_name, table_name)
+            ));
+        }
+
+        column.data_type = data_type;
+        metadata.modified_at = chrono::Utc::now();
+        self.publish(metadata, CatalogChangeKind::Altered);
+        drop(tables);
+
+        // Persist to disk
+        self.save_catalog().await?;
+
+        log::info!("Altered column {}.{}", table_name, column_name);
+        Ok(())
+    }
+
     /// Get table metadata
     pub async fn get_table(&self, table_name: &str) -> AuroraResult<Option<TableMetadata>> {
         let tables = self.tables.read().await;
         Ok(tables.get(table_name).cloned())
     }
 
+    /// Storage engine recorded for a table
+    pub async fn get_storage_engine(&self, table_name: &str) -> Option<StorageEngineType> {
+        let tables = self.tables.read().await;
+        tables.get(table_name).map(|t| t.storage_engine)
+    }
+
+    /// Expiry column of a table with a TTL
+    pub async fn get_ttl_column(&self, table_name: &str) -> Option<String> {
+        let tables = self.tables.read().await;
+        tables.get(table_name).and_then(|t| t.ttl_column.clone())
+    }
+
+    /// Tables with a TTL column, paired with it
+    pub async fn ttl_tables(&self) -> Vec<(String, String)> {
+        let tables = self.tables.read().await;
+        tables.values()
+            .filter_map(|t| t.ttl_column.clone().map(|column| (t.name.clone(), column)))
+            .collect()
+    }
+
     /// List all tables
     pub async fn list_tables(&self) -> Vec<String> {
         let tables = self.tables.read().await;
@@ -186,6 +335,17 @@ impl TableCatalog {
             (DataType::Text, serde_json::Value::String(_)) => Ok(()),
             (DataType::Boolean, serde_json::Value::Bool(_)) => Ok(()),
             (DataType::Blob, serde_json::Value::String(_)) => Ok(()), // Base64 encoded
+            (DataType::Timestamp, serde_json::Value::String(s)) => timestamp::parse_timestamp(s).map(|_| ()),
+            (DataType::TimestampTz, serde_json::Value::String(s)) => {
+                timestamp::parse_timestamptz(s, &TimeZone::default()).map(|_| ())
+            }
+            (DataType::Interval, serde_json::Value::String(s)) => s.parse::<Interval>().map(|_| ()),
+            (DataType::Decimal(precision, scale), serde_json::Value::String(s)) => {
+                s.trim().parse::<Decimal>()?.fit_column(*precision, *scale).map(|_| ())
+            }
+            (DataType::Decimal(precision, scale), serde_json::Value::Number(n)) => {
+                Decimal::from_f64(n.as_f64().unwrap_or(f64::NAN))?.fit_column(*precision, *scale).map(|_| ())
+            }
             _ => Err(AuroraError::new(
                 ErrorCode::ValidationTypeMismatch,
                 format!("Data type mismatch: expected {:?}, got {:?}", expected_type, value)
@@ -204,9 +364,17 @@ impl TableCatalog {
         let content = tokio::fs::read_to_string(&catalog_path).await
             .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Failed to read catalog: {}", e)))?;
 
-        let tables: HashMap<String, TableMetadata> = serde_json::from_str(&content)
+        let mut tables: HashMap<String, TableMetadata> = serde_json::from_str(&content)
             .map_err(|e| AuroraError::new(ErrorCode::StorageCorruption, format!("Failed to parse catalog: {}", e)))?;
 
+        // Catalogs written before object ids existed load with id 0
+        let mut next_id = tables.values().map(|t| t.object_id).max().unwrap_or(0) + 1;
+        for table in tables.values_mut().filter(|t| t.object_id == 0) {
+            table.object_id = next_id;
+            next_id += 1;
+        }
+        self.next_object_id.store(next_id, Ordering::SeqCst);
+
         let mut catalog_tables = self.tables.write().await;
         *catalog_tables = tables;
 
@@ -288,6 +456,9 @@ mod tests {
                 },
             ],
             constraints: vec![TableConstraint::PrimaryKey(vec!["id".to_string()])],
+            storage_engine: None,
+            partitioning: None,
+            ttl_column: None,
         };
 
         // Create table
@@ -311,6 +482,54 @@ mod tests {
         assert!(!catalog.table_exists("users").await);
     }
 
+    #[tokio::test]
+    async fn test_alter_column_type_publishes_change() {
+        struct Recorder(parking_lot::Mutex<Vec<(ObjectId, CatalogChangeKind)>>);
+        impl crate::catalog::CatalogSubscriber for Recorder {
+            fn on_catalog_change(&self, change: &CatalogChange) {
+                self.0.lock().push((change.object_id, change.kind));
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let catalog = TableCatalog::new(temp_dir.path().join("catalog"));
+        let recorder = Arc::new(Recorder(parking_lot::Mutex::new(Vec::new())));
+        catalog.change_bus().subscribe(recorder.clone());
+
+        let create_query = CreateTableQuery {
+            name: "accounts".to_string(),
+            columns: vec![ColumnDefinition {
+                name: "balance".to_string(),
+                data_type: DataType::Integer,
+                nullable: true,
+                default: None,
+            }],
+            constraints: vec![],
+            storage_engine: None,
+            partitioning: None,
+            ttl_column: None,
+        };
+        catalog.create_table(&create_query).await.unwrap();
+        let id = catalog.object_id("accounts").await.unwrap();
+
+        catalog.alter_column_type("accounts", "balance", DataType::BigInt).await.unwrap();
+        let column = catalog.get_column("accounts", "balance").await.unwrap().unwrap();
+        assert!(matches!(column.data_type, DataType::BigInt));
+        assert!(catalog.alter_column_type("accounts", "missing", DataType::Text).await.is_err());
+
+        // A table recreated under the same name is a different object
+        catalog.drop_table(&DropTableQuery { name: "accounts".to_string(), if_exists: false }).await.unwrap();
+        catalog.create_table(&create_query).await.unwrap();
+        assert_ne!(catalog.object_id("accounts").await.unwrap(), id);
+
+        let seen = recorder.0.lock().clone();
+        assert_eq!(&seen[..3], &[
+            (id, CatalogChangeKind::Created),
+            (id, CatalogChangeKind::Altered),
+            (id, CatalogChangeKind::Dropped),
+        ]);
+    }
+
     #[tokio::test]
     async fn test_data_validation() {
         let temp_dir = tempdir().unwrap();
@@ -334,6 +553,9 @@ mod tests {
                 },
             ],
             constraints: vec![],
+            storage_engine: None,
+            partitioning: None,
+            ttl_column: None,
         };
 
         catalog.create_table(&create_query).await.unwrap();
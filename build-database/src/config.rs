@@ -92,6 +92,122 @@ pub struct DatabaseConfig {
 
     /// Database temp directory
     pub temp_directory: String,
+
+    /// Memory a sort may use before spilling to the temp directory, in bytes
+    #[validate(range(min = 65536))] // 64KB minimum
+    pub work_mem_bytes: usize,
+
+    /// Background removal of expired rows from tables with a TTL column
+    #[serde(default)]
+    pub ttl: TtlConfig,
+
+    /// When table and index bloat estimates recommend maintenance
+    #[serde(default)]
+    pub bloat: BloatConfig,
+
+    /// How memory is shared among the sorts of concurrent queries
+    #[serde(default)]
+    pub memory_arbitration: MemoryArbitrationConfig,
+
+    /// Where materialized CTEs and temporary tables are kept
+    #[serde(default)]
+    pub materialization: MaterializationConfig,
+
+    /// Where spilling operators write their temp segments
+    #[serde(default)]
+    pub spill: SpillConfig,
+}
+
+/// TTL reaper configuration
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct TtlConfig {
+    /// Time between reaper passes in milliseconds
+    #[validate(range(min = 10))]
+    pub reaper_interval_ms: u64,
+
+    /// Expired rows deleted per transaction
+    #[validate(range(min = 1))]
+    pub reaper_batch_size: usize,
+}
+
+/// Materialization of CTEs and temporary tables
+///
+/// A materialized result stays in memory while it fits in
+/// `memory_budget_bytes`; a larger one is written to a file under
+/// `temp_directory` and read back from there on every reference.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct MaterializationConfig {
+    /// Bytes one materialized result may hold in memory; 0 spills every
+    /// result to disk
+    pub memory_budget_bytes: usize,
+}
+
+/// Spill storage
+///
+/// `backend` picks where sorts and materialized results that outgrow their
+/// memory write: `local` files under `temp_directory`, `tmpfs` files under
+/// `tmpfs_directory`, or `object_store`, parts uploaded under
+/// `object_store_prefix` to the object store attached to the database.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct SpillConfig {
+    /// `local`, `tmpfs` or `object_store`
+    pub backend: String,
+
+    /// Directory on a tmpfs mount for the `tmpfs` backend
+    pub tmpfs_directory: String,
+
+    /// Most bytes all segments may hold in the tmpfs directory at once; 0
+    /// for no limit
+    pub tmpfs_max_bytes: u64,
+
+    /// Key prefix of segments in the object store
+    pub object_store_prefix: String,
+
+    /// Size of each uploaded part of a segment, in bytes
+    #[validate(range(min = 65536))] // 64KB minimum
+    pub object_part_bytes: usize,
+}
+
+/// Memory arbitration among concurrent operators
+///
+/// With a budget set, each memory-hungry operator is granted a share of it
+/// sized to its demand instead of a fixed `work_mem`: a lone sort may take
+/// up to `max_grant_bytes`, while under contention operators split the
+/// budget and spill sooner.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct MemoryArbitrationConfig {
+    /// Memory all operators may be granted together, in bytes; 0 gives each
+    /// operator its session's `work_mem` instead
+    pub total_budget_bytes: usize,
+
+    /// Most one operator is granted, in bytes
+    #[validate(range(min = 65536))] // 64KB minimum
+    pub max_grant_bytes: usize,
+
+    /// Time without activity after which an operator keeps only the memory
+    /// it is using, in milliseconds
+    #[validate(range(min = 1))]
+    pub idle_after_ms: u64,
+}
+
+/// Bloat estimation thresholds
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct BloatConfig {
+    /// Share of a table's space held by dead tuples and free space above
+    /// which VACUUM is recommended
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub vacuum_bloat_ratio: f64,
+
+    /// Dead tuples a table needs before VACUUM is recommended at all
+    pub vacuum_min_dead_tuples: u64,
+
+    /// Share of an index's entries that are stale above which REINDEX is
+    /// recommended
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub reindex_bloat_ratio: f64,
+
+    /// Stale entries an index needs before REINDEX is recommended at all
+    pub reindex_min_stale_entries: u64,
 }
 
 /// Server configuration
@@ -146,6 +262,10 @@ pub struct StorageConfig {
     /// Hybrid engine configuration
     pub hybrid: HybridConfig,
 
+    /// Columnar engine configuration
+    #[serde(default)]
+    pub columnar: ColumnarConfig,
+
     /// WAL configuration
     pub wal: WALConfig,
 
@@ -205,6 +325,17 @@ pub struct HybridConfig {
     pub vector_threshold: f64,
 }
 
+/// Columnar storage engine configuration
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct ColumnarConfig {
+    /// Cache size in MB
+    #[validate(range(min = 1, max = 100000))]
+    pub cache_size_mb: usize,
+
+    /// Compress column segments
+    pub enable_compression: bool,
+}
+
 /// WAL configuration
 #[derive(Debug, Clone, Validate, Serialize, Deserialize)]
 pub struct WALConfig {
@@ -253,6 +384,26 @@ pub struct NetworkConfig {
 
     /// Load balancer configuration
     pub load_balancer: LoadBalancerConfig,
+
+    /// Statement firewall applied to client connections
+    #[serde(default)]
+    pub firewall: FirewallConfig,
+}
+
+/// Statement firewall configuration
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct FirewallConfig {
+    /// Mode: "allow_all", "read_only", "statement_types" or "patterns"
+    pub mode: String,
+
+    /// Statement types allowed in "statement_types" mode, e.g. "select", "explain"
+    #[serde(default)]
+    pub allowed_statement_types: Vec<String>,
+
+    /// Regular expressions allowed in "patterns" mode, each matched against
+    /// a whole statement
+    #[serde(default)]
+    pub allowed_patterns: Vec<String>,
 }
 
 /// Connection pool configuration
@@ -562,6 +713,18 @@ impl ConfigManager {
             config.database.buffer_pool_size = parse_size(&val)?;
         }
 
+        if let Ok(val) = env::var("AURORA_DB_WORK_MEM") {
+            config.database.work_mem_bytes = parse_size(&val)?;
+        }
+
+        if let Ok(val) = env::var("AURORA_DB_TTL_REAPER_INTERVAL_MS") {
+            config.database.ttl.reaper_interval_ms = val.parse()?;
+        }
+
+        if let Ok(val) = env::var("AURORA_DB_TTL_REAPER_BATCH_SIZE") {
+            config.database.ttl.reaper_batch_size = val.parse()?;
+        }
+
         // Server overrides
         if let Ok(val) = env::var("AURORA_SERVER_POSTGRESQL_PORT") {
             config.server.postgresql_port = val.parse()?;
@@ -576,6 +739,10 @@ impl ConfigManager {
             config.network.tls.enabled = val.parse()?;
         }
 
+        if let Ok(val) = env::var("AURORA_NETWORK_FIREWALL_MODE") {
+            config.network.firewall.mode = val;
+        }
+
         // Logging overrides
         if let Ok(val) = env::var("AURORA_LOG_LEVEL") {
             config.logging.level = val;
@@ -657,6 +824,62 @@ impl Default for DatabaseConfig {
             enable_metrics: true,
             data_directory: "/var/lib/aurora/data".to_string(),
             temp_directory: "/tmp/aurora".to_string(),
+            work_mem_bytes: 4 * 1024 * 1024, // 4MB
+            ttl: TtlConfig::default(),
+            bloat: BloatConfig::default(),
+            memory_arbitration: MemoryArbitrationConfig::default(),
+            materialization: MaterializationConfig::default(),
+            spill: SpillConfig::default(),
+        }
+    }
+}
+
+impl Default for MaterializationConfig {
+    fn default() -> Self {
+        Self {
+            memory_budget_bytes: 16 * 1024 * 1024, // 16MB
+        }
+    }
+}
+
+impl Default for SpillConfig {
+    fn default() -> Self {
+        Self {
+            backend: "local".to_string(),
+            tmpfs_directory: "/dev/shm/aurora".to_string(),
+            tmpfs_max_bytes: 1024 * 1024 * 1024, // 1GB
+            object_store_prefix: "aurora-spill".to_string(),
+            object_part_bytes: 8 * 1024 * 1024, // 8MB
+        }
+    }
+}
+
+impl Default for MemoryArbitrationConfig {
+    fn default() -> Self {
+        Self {
+            total_budget_bytes: 0,
+            max_grant_bytes: 256 * 1024 * 1024, // 256MB
+            idle_after_ms: 1000,
+        }
+    }
+}
+
+impl Default for TtlConfig {
+    fn default() -> Self {
+        Self {
+            reaper_interval_ms: 60_000,
+            reaper_batch_size: 1000,
+        }
+    }
+}
+
+impl Default for BloatConfig {
+    fn default() -> Self {
+        Self {
+            vacuum_bloat_ratio: 0.2,
+            vacuum_min_dead_tuples: 50,
+            reindex_bloat_ratio: 0.3,
+            reindex_min_stale_entries: 50,
         }
     }
 }
@@ -684,6 +907,7 @@ impl Default for StorageConfig {
             btree: BTreeConfig::default(),
             lsm: LSMConfig::default(),
             hybrid: HybridConfig::default(),
+            columnar: ColumnarConfig::default(),
             wal: WALConfig::default(),
             compression: CompressionConfig::default(),
         }
@@ -696,6 +920,17 @@ impl Default for NetworkConfig {
             connection_pool: ConnectionPoolConfig::default(),
             tls: TLSConfig::default(),
             load_balancer: LoadBalancerConfig::default(),
+            firewall: FirewallConfig::default(),
+        }
+    }
+}
+
+impl Default for FirewallConfig {
+    fn default() -> Self {
+        Self {
+            mode: "allow_all".to_string(),
+            allowed_statement_types: Vec::new(),
+            allowed_patterns: Vec::new(),
         }
     }
 }
@@ -815,6 +1050,15 @@ impl Default for HybridConfig {
     }
 }
 
+impl Default for ColumnarConfig {
+    fn default() -> Self {
+        Self {
+            cache_size_mb: 256,
+            enable_compression: true,
+        }
+    }
+}
+
 impl Default for WALConfig {
     fn default() -> Self {
         Self {
@@ -9,7 +9,7 @@ pub enum DataType {
     Integer64,
     Float32,
     Float64,
-    Decimal(u8, u8), // precision, scale
+    Decimal(u8, u8), // precision, scale; (0, 0) is unconstrained NUMERIC
 
     // String types
     Varchar(u32), // max length
@@ -21,6 +21,7 @@ pub enum DataType {
     Time,
     Timestamp,
     TimestampTz,
+    Interval,
 
     // Complex types
     Json,
@@ -0,0 +1,345 @@
+//! ASOF Joins
+//!
+//! `FROM trades ASOF JOIN quotes MATCH_CONDITION (trades.ts >= quotes.ts)
+//! ON trades.symbol = quotes.symbol` pairs every trade with the latest quote
+//! at or before it, for the same symbol. The match condition picks the
+//! direction:
+//!
+//! | Condition             | Right row joined                     |
+//! |-----------------------|--------------------------------------|
+//! | `left.ts >= right.ts` | latest at or before the left row     |
+//! | `left.ts > right.ts`  | latest strictly before the left row  |
+//! | `left.ts <= right.ts` | earliest at or after the left row    |
+//! | `left.ts < right.ts`  | earliest strictly after the left row |
+//!
+//! The ON equality is optional; without it every right row is a candidate.
+//! A left row with no matching right row is kept with NULLs for the right
+//! columns, as is one whose time or key is NULL. Among right rows with the
+//! same time, the one scanned last wins.
+//!
+//! Both sides are bucketed by key and sorted on time, then each bucket is
+//! merged in a single pass, so the join costs O((L + R) log(L + R)) rather
+//! than the O(L * R) of a nested loop.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use crate::types::DataValue;
+
+/// Direction of an ASOF match, as `left <op> right`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsofOperator {
+    GreaterEqual,
+    Greater,
+    LessEqual,
+    Less,
+}
+
+impl AsofOperator {
+    /// The operator with its operands swapped, `right <op> left` as `left <flipped> right`
+    pub fn flipped(self) -> Self {
+        match self {
+            Self::GreaterEqual => Self::LessEqual,
+            Self::Greater => Self::Less,
+            Self::LessEqual => Self::GreaterEqual,
+            Self::Less => Self::Greater,
+        }
+    }
+
+    /// Whether a right row at `right` may join a left row at `left`
+    pub fn matches(self, left: &DataValue, right: &DataValue) -> bool {
+        match compare_times(left, right) {
+            Some(ordering) => match self {
+                Self::GreaterEqual => ordering.is_ge(),
+                Self::Greater => ordering.is_gt(),
+                Self::LessEqual => ordering.is_le(),
+                Self::Less => ordering.is_lt(),
+            },
+            None => false,
+        }
+    }
+
+    /// Whether the nearest match is the latest right row (looking backwards)
+    fn looks_back(self) -> bool {
+        matches!(self, Self::GreaterEqual | Self::Greater)
+    }
+}
+
+impl std::fmt::Display for AsofOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::GreaterEqual => ">=",
+            Self::Greater => ">",
+            Self::LessEqual => "<=",
+            Self::Less => "<",
+        })
+    }
+}
+
+/// An ASOF join resolved to columns of its two sides
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsofJoin {
+    /// Time column of the left (FROM) side
+    pub left_time: String,
+    /// Time column of the joined table
+    pub right_time: String,
+    pub operator: AsofOperator,
+    /// Partition key columns `(left, right)` from the ON equality
+    pub key: Option<(String, String)>,
+}
+
+impl AsofJoin {
+    /// Join `left_rows` to `right_rows`, one output row per left row in
+    /// input order; right columns are added under `right_prefix`
+    pub fn execute(
+        &self,
+        left_rows: &[HashMap<String, DataValue>],
+        right_rows: &[HashMap<String, DataValue>],
+        right_prefix: &str,
+    ) -> Vec<HashMap<String, DataValue>> {
+        // Right rows with a time and key, bucketed by key, sorted on time
+        // with ties kept in scan order
+        let mut right_buckets: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, row) in right_rows.iter().enumerate() {
+            if let Some(bucket) = self.bucket(row, false, &self.right_time) {
+                right_buckets.entry(bucket).or_default().push(index);
+            }
+        }
+        for bucket in right_buckets.values_mut() {
+            bucket.sort_by(|a, b| time_order(&right_rows[*a][&self.right_time], &right_rows[*b][&self.right_time]));
+        }
+
+        let mut left_buckets: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, row) in left_rows.iter().enumerate() {
+            if let Some(bucket) = self.bucket(row, true, &self.left_time) {
+                left_buckets.entry(bucket).or_default().push(index);
+            }
+        }
+
+        let mut matches: Vec<Option<usize>> = vec![None; left_rows.len()];
+        for (bucket, mut lefts) in left_buckets {
+            let Some(rights) = right_buckets.get(&bucket) else {
+                continue;
+            };
+            lefts.sort_by(|a, b| time_order(&left_rows[*a][&self.left_time], &left_rows[*b][&self.left_time]));
+            let right_time = |position: usize| &right_rows[rights[position]][&self.right_time];
+
+            if self.operator.looks_back() {
+                // Advance past every right row the current left row may join;
+                // the last one passed is the latest match
+                let mut next = 0;
+                for left in lefts {
+                    let time = &left_rows[left][&self.left_time];
+                    while next < rights.len() && self.operator.matches(time, right_time(next)) {
+                        next += 1;
+                    }
+                    matches[left] = next.checked_sub(1).map(|position| rights[position]);
+                }
+            } else {
+                // Walk from the latest right row back to the earliest the
+                // current left row may join, left rows latest first
+                let mut next = rights.len();
+                for left in lefts.into_iter().rev() {
+                    let time = &left_rows[left][&self.left_time];
+                    while next > 0 && self.operator.matches(time, right_time(next - 1)) {
+                        next -= 1;
+                    }
+                    // Of equal earliest times the last scanned wins, as when looking back
+                    matches[left] = (next < rights.len()).then(|| {
+                        let earliest = right_time(next);
+                        let mut last = next;
+                        while last + 1 < rights.len() && time_order(right_time(last + 1), earliest).is_eq() {
+                            last += 1;
+                        }
+                        rights[last]
+                    });
+                }
+            }
+        }
+
+        let right_columns: Vec<&String> = right_rows.first().map(|row| row.keys().collect()).unwrap_or_default();
+        left_rows.iter().zip(matches).map(|(left_row, matched)| {
+            let mut joined_row = left_row.clone();
+            match matched {
+                Some(right) => {
+                    for (column, value) in &right_rows[right] {
+                        joined_row.insert(format!("{}{}", right_prefix, column), value.clone());
+                    }
+                }
+                None => {
+                    for column in &right_columns {
+                        joined_row.insert(format!("{}{}", right_prefix, column), DataValue::Null);
+                    }
+                }
+            }
+            joined_row
+        }).collect()
+    }
+
+    /// Key bucket of a row; `None` if its time or key is NULL or missing,
+    /// which no row can match
+    fn bucket(&self, row: &HashMap<String, DataValue>, left: bool, time: &str) -> Option<String> {
+        if matches!(row.get(time), None | Some(DataValue::Null)) {
+            return None;
+        }
+        match &self.key {
+            None => Some(String::new()),
+            Some((left_key, right_key)) => match row.get(if left { left_key } else { right_key }) {
+                None | Some(DataValue::Null) => None,
+                Some(value) => Some(key_string(value)),
+            },
+        }
+    }
+}
+
+/// Bucket key under which equal values from either side meet
+fn key_string(value: &DataValue) -> String {
+    match value {
+        // Both string representations name the same text
+        DataValue::String(s) => format!("{:?}", DataValue::Text(s.clone())),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Order two time values; `None` for values that cannot be compared
+pub fn compare_times(a: &DataValue, b: &DataValue) -> Option<Ordering> {
+    match (a, b) {
+        (DataValue::Integer(x), DataValue::Integer(y)) => Some(x.cmp(y)),
+        (DataValue::TimestampTz(x), DataValue::TimestampTz(y)) => Some(x.cmp(y)),
+        (DataValue::Timestamp(x), DataValue::Timestamp(y)) => Some(x.cmp(y)),
+        (DataValue::Text(x) | DataValue::String(x), DataValue::Text(y) | DataValue::String(y)) => Some(x.cmp(y)),
+        _ => {
+            let number = |value: &DataValue| match value {
+                DataValue::Integer(i) => Some(*i as f64),
+                DataValue::Real(r) => Some(*r),
+                DataValue::Decimal(d) => d.to_f64().ok(),
+                _ => None,
+            };
+            number(a)?.partial_cmp(&number(b)?)
+        }
+    }
+}
+
+/// Total order for sorting; incomparable values sort as equal
+fn time_order(a: &DataValue, b: &DataValue) -> Ordering {
+    compare_times(a, b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Row = HashMap<String, DataValue>;
+
+    fn row(values: &[(&str, DataValue)]) -> Row {
+        values.iter().map(|(column, value)| (column.to_string(), value.clone())).collect()
+    }
+
+    /// For each left row, scan every right row and keep the nearest match;
+    /// of equal times the later one in scan order
+    fn reference(join: &AsofJoin, left_rows: &[Row], right_rows: &[Row], prefix: &str) -> Vec<Row> {
+        left_rows.iter().map(|left| {
+            let mut best: Option<&Row> = None;
+            for right in right_rows {
+                let same_key = match &join.key {
+                    None => true,
+                    Some((left_key, right_key)) => match (left.get(left_key), right.get(right_key)) {
+                        (Some(DataValue::Null) | None, _) | (_, Some(DataValue::Null) | None) => false,
+                        (Some(a), Some(b)) => compare_times(a, b) == Some(Ordering::Equal),
+                    },
+                };
+                if !same_key || !join.operator.matches(&left[&join.left_time], &right[&join.right_time]) {
+                    continue;
+                }
+                let nearer = match best {
+                    None => true,
+                    Some(best) => {
+                        let ordering = time_order(&right[&join.right_time], &best[&join.right_time]);
+                        if join.operator.looks_back() { ordering.is_ge() } else { ordering.is_lt() || ordering.is_eq() }
+                    }
+                };
+                if nearer {
+                    best = Some(right);
+                }
+            }
+
+            let mut joined = left.clone();
+            for column in right_rows[0].keys() {
+                let value = best.map_or(DataValue::Null, |best| best[column].clone());
+                joined.insert(format!("{}{}", prefix, column), value);
+            }
+            joined
+        }).collect()
+    }
+
+    fn trades() -> Vec<Row> {
+        [
+            (1, "AAPL", 100), (2, "AAPL", 105), (3, "MSFT", 105), (4, "AAPL", 110),
+            (5, "MSFT", 99), (6, "GOOG", 120), (7, "AAPL", 130), (8, "MSFT", 130),
+        ].iter().map(|(id, symbol, ts)| row(&[
+            ("id", DataValue::Integer(*id)),
+            ("symbol", DataValue::Text(symbol.to_string())),
+            ("ts", DataValue::Integer(*ts)),
+        ])).chain(std::iter::once(row(&[
+            ("id", DataValue::Integer(9)),
+            ("symbol", DataValue::Text("AAPL".to_string())),
+            ("ts", DataValue::Null),
+        ]))).collect()
+    }
+
+    /// Gaps (no MSFT quote before 100, none for GOOG) and ties (two AAPL
+    /// quotes at 105 and 110, a trade exactly at a quote time)
+    fn quotes() -> Vec<Row> {
+        [
+            (10, "AAPL", 95), (11, "AAPL", 105), (12, "AAPL", 105), (13, "MSFT", 100),
+            (14, "AAPL", 110), (15, "MSFT", 104), (16, "AAPL", 110), (17, "MSFT", 131),
+        ].iter().map(|(quote_id, symbol, ts)| row(&[
+            ("quote_id", DataValue::Integer(*quote_id)),
+            ("symbol", DataValue::Text(symbol.to_string())),
+            ("ts", DataValue::Integer(*ts)),
+        ])).collect()
+    }
+
+    fn join(operator: AsofOperator, keyed: bool) -> AsofJoin {
+        AsofJoin {
+            left_time: "ts".to_string(),
+            right_time: "ts".to_string(),
+            operator,
+            key: keyed.then(|| ("symbol".to_string(), "symbol".to_string())),
+        }
+    }
+
+    fn quote_ids(rows: &[Row]) -> Vec<Option<i64>> {
+        rows.iter().map(|row| match row["quotes.quote_id"] {
+            DataValue::Integer(id) => Some(id),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn test_latest_prior_quote_per_symbol() {
+        let result = join(AsofOperator::GreaterEqual, true).execute(&trades(), &quotes(), "quotes.");
+        assert_eq!(quote_ids(&result), vec![
+            Some(10), Some(12), Some(15), Some(16), None, None, Some(16), Some(15), None,
+        ]);
+    }
+
+    #[test]
+    fn test_matches_reference_for_every_operator() {
+        let (trades, quotes) = (trades(), quotes());
+        for operator in [AsofOperator::GreaterEqual, AsofOperator::Greater, AsofOperator::LessEqual, AsofOperator::Less] {
+            for keyed in [true, false] {
+                let join = join(operator, keyed);
+                let result = join.execute(&trades, &quotes, "quotes.");
+                let expected = reference(&join, &trades, &quotes, "quotes.");
+                assert_eq!(quote_ids(&result), quote_ids(&expected), "{:?} keyed={}", operator, keyed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flipped_condition_is_equivalent() {
+        // `quotes.ts <= trades.ts` is `trades.ts >= quotes.ts`
+        assert_eq!(AsofOperator::LessEqual.flipped(), AsofOperator::GreaterEqual);
+        assert_eq!(AsofOperator::Greater.flipped(), AsofOperator::Less);
+    }
+}
@@ -0,0 +1,201 @@
+//! Table and Index Bloat Estimation
+//!
+//! Updates and deletes leave dead tuple versions behind in a table and stale
+//! entries in its indexes, and purging dead rows leaves free space that only
+//! later inserts reuse. This module turns a table's running [`TupleStats`]
+//! into bloat estimates and VACUUM / REINDEX recommendations, backing the
+//! `aurora_bloat` system view, `GET /admin/bloat` and `aurora-cli
+//! maintenance bloat`.
+//!
+//! Estimates come from counters the storage layer keeps on every write, so
+//! no table is read to produce them. Only a table whose counters were lost
+//! with a restart is read once, to measure them again.
+//!
+//! - **Table bloat**: Dead and free bytes as a share of the table's bytes
+//! - **Fragmentation**: Share of the table's pages that packing its live
+//!   rows would free
+//! - **Index bloat**: Stale entries as a share of the index's entries
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use crate::config::BloatConfig;
+use crate::storage::buffer_usage::pages_for;
+use crate::storage::table_storage::TupleStats;
+use crate::types::DataValue;
+
+/// Name of the system view over the bloat estimates
+pub const BLOAT_VIEW: &str = "aurora_bloat";
+
+/// Maintenance a bloat estimate recommends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaintenanceAction {
+    Vacuum,
+    Reindex,
+}
+
+impl MaintenanceAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintenanceAction::Vacuum => "VACUUM",
+            MaintenanceAction::Reindex => "REINDEX",
+        }
+    }
+}
+
+/// Estimated bloat of one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableBloat {
+    pub table_name: String,
+    pub live_tuples: u64,
+    pub dead_tuples: u64,
+    pub live_bytes: u64,
+    pub dead_bytes: u64,
+    pub free_bytes: u64,
+    /// Dead and free bytes as a share of the table's bytes
+    pub bloat_ratio: f64,
+    /// Share of the table's pages that packing its live rows would free
+    pub fragmentation: f64,
+    /// Whether the counters had to be measured by reading the table
+    pub measured: bool,
+    pub recommendation: Option<MaintenanceAction>,
+}
+
+impl TableBloat {
+    /// Estimate a table's bloat from its tuple counts
+    pub fn estimate(table_name: &str, stats: &TupleStats, measured: bool, config: &BloatConfig) -> Self {
+        let wasted = stats.dead_bytes + stats.free_bytes;
+        let total = stats.live_bytes + wasted;
+        let bloat_ratio = ratio(wasted, total);
+
+        let pages = pages_for(total);
+        let fragmentation = ratio(pages - pages_for(stats.live_bytes), pages);
+
+        let recommendation = (stats.dead_tuples >= config.vacuum_min_dead_tuples && bloat_ratio > config.vacuum_bloat_ratio)
+            .then_some(MaintenanceAction::Vacuum);
+
+        Self {
+            table_name: table_name.to_string(),
+            live_tuples: stats.live_tuples,
+            dead_tuples: stats.dead_tuples,
+            live_bytes: stats.live_bytes,
+            dead_bytes: stats.dead_bytes,
+            free_bytes: stats.free_bytes,
+            bloat_ratio,
+            fragmentation,
+            measured,
+            recommendation,
+        }
+    }
+}
+
+/// Estimated bloat of one secondary index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexBloat {
+    pub index_name: String,
+    pub table_name: String,
+    /// Entries for live rows
+    pub live_entries: u64,
+    /// Entries for versions updated or deleted since the last rebuild
+    pub stale_entries: u64,
+    /// Stale entries as a share of all entries
+    pub bloat_ratio: f64,
+    pub recommendation: Option<MaintenanceAction>,
+}
+
+impl IndexBloat {
+    /// Estimate the bloat of an index on a table with the given tuple counts
+    pub fn estimate(index_name: &str, table_name: &str, stats: &TupleStats, config: &BloatConfig) -> Self {
+        let stale = stats.stale_index_entries;
+        let bloat_ratio = ratio(stale, stats.live_tuples + stale);
+        let recommendation = (stale >= config.reindex_min_stale_entries && bloat_ratio > config.reindex_bloat_ratio)
+            .then_some(MaintenanceAction::Reindex);
+
+        Self {
+            index_name: index_name.to_string(),
+            table_name: table_name.to_string(),
+            live_entries: stats.live_tuples,
+            stale_entries: stale,
+            bloat_ratio,
+            recommendation,
+        }
+    }
+}
+
+/// Maintenance recommended for one table or index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloatRecommendation {
+    pub action: MaintenanceAction,
+    pub table_name: String,
+    /// Index to rebuild, for REINDEX
+    pub index_name: Option<String>,
+    pub bloat_ratio: f64,
+}
+
+/// Bloat estimates of a set of tables and their indexes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BloatReport {
+    pub tables: Vec<TableBloat>,
+    pub indexes: Vec<IndexBloat>,
+}
+
+impl BloatReport {
+    /// Every VACUUM and REINDEX the estimates recommend, tables first
+    pub fn recommendations(&self) -> Vec<BloatRecommendation> {
+        let tables = self.tables.iter().filter_map(|table| {
+            table.recommendation.map(|action| BloatRecommendation {
+                action,
+                table_name: table.table_name.clone(),
+                index_name: None,
+                bloat_ratio: table.bloat_ratio,
+            })
+        });
+        let indexes = self.indexes.iter().filter_map(|index| {
+            index.recommendation.map(|action| BloatRecommendation {
+                action,
+                table_name: index.table_name.clone(),
+                index_name: Some(index.index_name.clone()),
+                bloat_ratio: index.bloat_ratio,
+            })
+        });
+        tables.chain(indexes).collect()
+    }
+
+    /// Rows of the `aurora_bloat` system view, one per table and index
+    pub fn rows(&self) -> Vec<HashMap<String, DataValue>> {
+        let recommendation = |action: Option<MaintenanceAction>| {
+            action.map(|action| DataValue::Text(action.name().to_string())).unwrap_or(DataValue::Null)
+        };
+        let tables = self.tables.iter().map(|table| {
+            HashMap::from([
+                ("object_type".to_string(), DataValue::Text("table".to_string())),
+                ("object_name".to_string(), DataValue::Text(table.table_name.clone())),
+                ("table_name".to_string(), DataValue::Text(table.table_name.clone())),
+                ("live".to_string(), DataValue::Integer(table.live_tuples as i64)),
+                ("dead".to_string(), DataValue::Integer(table.dead_tuples as i64)),
+                ("wasted_bytes".to_string(), DataValue::Integer((table.dead_bytes + table.free_bytes) as i64)),
+                ("bloat_ratio".to_string(), DataValue::Real(table.bloat_ratio)),
+                ("fragmentation".to_string(), DataValue::Real(table.fragmentation)),
+                ("recommendation".to_string(), recommendation(table.recommendation)),
+            ])
+        });
+        let indexes = self.indexes.iter().map(|index| {
+            HashMap::from([
+                ("object_type".to_string(), DataValue::Text("index".to_string())),
+                ("object_name".to_string(), DataValue::Text(index.index_name.clone())),
+                ("table_name".to_string(), DataValue::Text(index.table_name.clone())),
+                ("live".to_string(), DataValue::Integer(index.live_entries as i64)),
+                ("dead".to_string(), DataValue::Integer(index.stale_entries as i64)),
+                ("wasted_bytes".to_string(), DataValue::Null),
+                ("bloat_ratio".to_string(), DataValue::Real(index.bloat_ratio)),
+                ("fragmentation".to_string(), DataValue::Null),
+                ("recommendation".to_string(), recommendation(index.recommendation)),
+            ])
+        });
+        tables.chain(indexes).collect()
+    }
+}
+
+/// `part / whole`, or 0 for an empty whole
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
@@ -0,0 +1,205 @@
+//! External Merge Sort
+//!
+//! ORDER BY sorts in memory while its input fits in `work_mem`. Larger inputs
+//! are cut into runs of at most `work_mem` bytes, each sorted and spilled to
+//! a segment of the spill backend, and the runs are then merged back. Temp
+//! pages written and read are counted against the sort's plan node for
+//! `EXPLAIN (ANALYZE, BUFFERS)`.
+//!
+//! A sort holding a grant from the memory arbitrator asks it for its whole
+//! input and uses whatever it is granted in place of `work_mem`.
+
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+use crate::storage::buffer_usage::BufferUsage;
+use super::memory_arbitrator::MemoryGrant;
+use super::spill::{DirectorySpill, SpillBackend, SpillFile, SpillWriter};
+
+/// Sorts that spill to temp segments once their input exceeds `work_mem`
+pub struct ExternalSort<'a> {
+    work_mem: usize,
+    spill: Arc<dyn SpillBackend>,
+    buffers: Option<&'a BufferUsage>,
+    grant: Option<&'a MemoryGrant>,
+}
+
+/// A sorted run in the spill backend, removed when dropped
+struct SpilledRun {
+    file: SpillFile,
+    items: usize,
+}
+
+impl<'a> ExternalSort<'a> {
+    /// Sort spilling to files under `temp_dir` on local disk
+    pub fn new(work_mem: usize, temp_dir: &Path, buffers: Option<&'a BufferUsage>) -> Self {
+        Self { work_mem, spill: Arc::new(DirectorySpill::local(temp_dir)), buffers, grant: None }
+    }
+
+    /// Spill runs to `spill` instead of the local temp directory
+    pub fn with_spill_backend(mut self, spill: Arc<dyn SpillBackend>) -> Self {
+        self.spill = spill;
+        self
+    }
+
+    /// Size runs by `grant` instead of `work_mem`
+    pub fn with_grant(mut self, grant: Option<&'a MemoryGrant>) -> Self {
+        self.grant = grant;
+        self
+    }
+
+    /// Sort `items` by `compare`; stable, like `slice::sort_by`
+    pub fn sort_by<T, F>(&self, items: Vec<T>, mut compare: F) -> AuroraResult<Vec<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let sizes = items.iter()
+            .map(|item| bincode::serialized_size(item).map_err(sort_error))
+            .collect::<AuroraResult<Vec<u64>>>()?;
+        let input_bytes = sizes.iter().sum::<u64>();
+        let work_mem = match self.grant {
+            Some(grant) => {
+                let limit = grant.request(input_bytes);
+                grant.report_usage(input_bytes.min(limit as u64));
+                limit
+            }
+            None => self.work_mem,
+        };
+        if input_bytes <= work_mem as u64 {
+            let mut items = items;
+            items.sort_by(&mut compare);
+            return Ok(items);
+        }
+
+        // Cut into runs of at most work_mem bytes (at least one item each)
+        let total = items.len();
+        let mut runs = Vec::new();
+        let mut run = Vec::new();
+        let mut run_bytes = 0u64;
+        for (item, size) in items.into_iter().zip(sizes) {
+            if !run.is_empty() && run_bytes + size > work_mem as u64 {
+                runs.push(self.spill(std::mem::take(&mut run), &mut compare)?);
+                run_bytes = 0;
+            }
+            run_bytes += size;
+            run.push(item);
+        }
+        if !run.is_empty() {
+            runs.push(self.spill(run, &mut compare)?);
+        }
+
+        self.merge(&runs, total, &mut compare)
+    }
+
+    /// Sort one run and write it to a spill segment
+    fn spill<T, F>(&self, mut run: Vec<T>, compare: &mut F) -> AuroraResult<SpilledRun>
+    where
+        T: Serialize,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        run.sort_by(&mut *compare);
+        let mut writer = SpillWriter::create(&self.spill, "sort_run")?;
+        for item in &run {
+            bincode::serialize_into(&mut writer, item).map_err(sort_error)?;
+        }
+        let file = writer.finish()?;
+
+        if let Some(buffers) = self.buffers {
+            buffers.temp_written(file.bytes());
+        }
+        Ok(SpilledRun { file, items: run.len() })
+    }
+
+    /// Merge sorted runs, taking the earliest run's item on ties so the
+    /// result stays stable
+    fn merge<T, F>(&self, runs: &[SpilledRun], total: usize, compare: &mut F) -> AuroraResult<Vec<T>>
+    where
+        T: DeserializeOwned,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut readers: Vec<Box<dyn Read + Send>> = Vec::with_capacity(runs.len());
+        let mut remaining = Vec::with_capacity(runs.len());
+        for run in runs {
+            readers.push(run.file.reader()?);
+            remaining.push(run.items - 1);
+            if let Some(buffers) = self.buffers {
+                buffers.temp_read(run.file.bytes());
+            }
+        }
+
+        let mut heads: Vec<Option<T>> = Vec::with_capacity(runs.len());
+        for reader in readers.iter_mut() {
+            heads.push(Some(bincode::deserialize_from(reader).map_err(sort_error)?));
+        }
+
+        let mut sorted = Vec::with_capacity(total);
+        while sorted.len() < total {
+            let mut next: Option<usize> = None;
+            for (index, head) in heads.iter().enumerate() {
+                let Some(head) = head else { continue };
+                let earlier = match next {
+                    Some(current) => compare(head, heads[current].as_ref().unwrap()) == Ordering::Less,
+                    None => true,
+                };
+                if earlier {
+                    next = Some(index);
+                }
+            }
+            let Some(index) = next else { break };
+
+            sorted.push(heads[index].take().unwrap());
+            if remaining[index] > 0 {
+                remaining[index] -= 1;
+                heads[index] = Some(bincode::deserialize_from(&mut readers[index]).map_err(sort_error)?);
+            }
+        }
+        Ok(sorted)
+    }
+}
+
+fn sort_error(error: bincode::Error) -> AuroraError {
+    AuroraError::new(ErrorCode::StorageUnavailable, format!("Sort spill error: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_spilling_sort_matches_in_memory_sort() {
+        let temp_dir = tempdir().unwrap();
+        let buffers = BufferUsage::new();
+        let items: Vec<(u32, String)> = (0..2000).map(|i| ((i * 7919) % 101, format!("item {}", i))).collect();
+
+        let mut expected = items.clone();
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let sorted = ExternalSort::new(4096, temp_dir.path(), Some(&buffers))
+            .sort_by(items, |a, b| a.0.cmp(&b.0))
+            .unwrap();
+        assert_eq!(sorted, expected);
+
+        let counts = buffers.counts();
+        assert!(counts.temp_written > 1);
+        assert_eq!(counts.temp_read, counts.temp_written);
+        // Runs are removed once merged
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_sort_within_work_mem_does_not_spill() {
+        let temp_dir = tempdir().unwrap();
+        let buffers = BufferUsage::new();
+        let sorted = ExternalSort::new(1024 * 1024, temp_dir.path(), Some(&buffers))
+            .sort_by(vec![3, 1, 2], |a: &i32, b: &i32| a.cmp(b))
+            .unwrap();
+        assert_eq!(sorted, vec![1, 2, 3]);
+        assert_eq!(buffers.counts().temp_written, 0);
+    }
+}
@@ -0,0 +1,262 @@
+//! Query Result Fingerprints
+//!
+//! Stable hashes of a result set for diffing AuroraDB output against another
+//! system. Two fingerprints are produced from the same per-row hashes:
+//!
+//! - `ordered`: rows hashed in sequence, so row order matters
+//! - `unordered`: per-row hashes summed (mod 2^64), so any permutation of the
+//!   same multiset of rows fingerprints equally
+//!
+//! ## Canonical Encoding
+//!
+//! Every value is encoded as a tag byte followed by a payload, all integers
+//! big-endian, and hashed with 64-bit FNV-1a (offset `0xcbf29ce484222325`,
+//! prime `0x100000001b3`):
+//!
+//! | Value                   | Encoding                                              |
+//! |-------------------------|-------------------------------------------------------|
+//! | NULL                    | `0x00`                                                |
+//! | boolean                 | `0x01`, then `0x00`/`0x01`                            |
+//! | integral number         | `0x02`, then i64 (also for floats like `1.0`)         |
+//! | other number            | `0x03`, then f64 bits; `-0.0` → `0.0`, one NaN        |
+//! | string                  | `0x04`, u64 byte length, UTF-8 bytes                  |
+//! | array / vector          | `0x05`, u64 length, encoded elements                  |
+//! | JSON object             | `0x06`, u64 entry count, (key, value) sorted by key   |
+//! | binary                  | `0x07`, u64 byte length, bytes                        |
+//!
+//! A row is `0x10`, u64 column count, then its values in column order; column
+//! names are not hashed. The row's hash is FNV-1a over that encoding. The
+//! ordered fingerprint is FNV-1a over the u64 row count followed by every row
+//! hash. The driver implements the same encoding (`aurora_drivers::fingerprint`),
+//! and the two must stay in sync.
+
+use std::fmt;
+use serde::{Serialize, Deserialize};
+use super::aurora_db::QueryResult;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+const CANONICAL_NAN: u64 = 0x7ff8000000000000;
+
+/// Order-sensitive and order-insensitive hashes of a result set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResultFingerprint {
+    /// Hash that changes when rows are reordered
+    pub ordered: u64,
+    /// Hash of the multiset of rows, independent of row order
+    pub unordered: u64,
+    /// Number of rows hashed
+    pub row_count: u64,
+}
+
+impl fmt::Display for ResultFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}:{:016x}:{}", self.ordered, self.unordered, self.row_count)
+    }
+}
+
+/// Incremental FNV-1a hasher over the canonical encoding
+#[derive(Debug, Clone)]
+pub struct CanonicalHasher {
+    state: u64,
+}
+
+impl Default for CanonicalHasher {
+    fn default() -> Self {
+        Self { state: FNV_OFFSET }
+    }
+}
+
+impl CanonicalHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = (self.state ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_be_bytes());
+    }
+
+    pub fn write_null(&mut self) {
+        self.write_bytes(&[0x00]);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_bytes(&[0x01, value as u8]);
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_bytes(&[0x02]);
+        self.write_bytes(&value.to_be_bytes());
+    }
+
+    /// Numbers hash by value: integral floats hash like the matching integer
+    pub fn write_f64(&mut self, value: f64) {
+        if value.fract() == 0.0 && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+            return self.write_i64(value as i64);
+        }
+        let bits = if value.is_nan() {
+            CANONICAL_NAN
+        } else {
+            value.to_bits()
+        };
+        self.write_bytes(&[0x03]);
+        self.write_u64(bits);
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(&[0x04]);
+        self.write_u64(value.len() as u64);
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_binary(&mut self, value: &[u8]) {
+        self.write_bytes(&[0x07]);
+        self.write_u64(value.len() as u64);
+        self.write_bytes(value);
+    }
+
+    pub fn write_json(&mut self, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::Null => self.write_null(),
+            serde_json::Value::Bool(b) => self.write_bool(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => self.write_i64(i),
+                None => self.write_f64(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => self.write_str(s),
+            serde_json::Value::Array(items) => {
+                self.write_bytes(&[0x05]);
+                self.write_u64(items.len() as u64);
+                for item in items {
+                    self.write_json(item);
+                }
+            }
+            serde_json::Value::Object(entries) => {
+                let mut sorted: Vec<_> = entries.iter().collect();
+                sorted.sort_by(|a, b| a.0.cmp(b.0));
+                self.write_bytes(&[0x06]);
+                self.write_u64(sorted.len() as u64);
+                for (key, value) in sorted {
+                    self.write_str(key);
+                    self.write_json(value);
+                }
+            }
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Hash one row of values in column order
+pub fn row_hash<'a>(values: impl ExactSizeIterator<Item = &'a serde_json::Value>) -> u64 {
+    row_hash_by(values, |hasher, value| hasher.write_json(value))
+}
+
+/// Hash one row of values of any type, given how to encode a single value
+pub fn row_hash_by<T>(values: impl ExactSizeIterator<Item = T>, mut write: impl FnMut(&mut CanonicalHasher, T)) -> u64 {
+    let mut hasher = CanonicalHasher::new();
+    hasher.write_bytes(&[0x10]);
+    hasher.write_u64(values.len() as u64);
+    for value in values {
+        write(&mut hasher, value);
+    }
+    hasher.finish()
+}
+
+/// Combine row hashes (in result order) into a fingerprint
+pub fn combine_row_hashes(row_hashes: &[u64]) -> ResultFingerprint {
+    let mut ordered = CanonicalHasher::new();
+    ordered.write_u64(row_hashes.len() as u64);
+    for hash in row_hashes {
+        ordered.write_u64(*hash);
+    }
+
+    ResultFingerprint {
+        ordered: ordered.finish(),
+        unordered: row_hashes.iter().fold(0u64, |sum, hash| sum.wrapping_add(*hash)),
+        row_count: row_hashes.len() as u64,
+    }
+}
+
+impl QueryResult {
+    /// Fingerprint the rows of this result; see the module docs for the encoding
+    pub fn fingerprint(&self) -> ResultFingerprint {
+        let row_hashes: Vec<u64> = self.rows.iter().map(|row| row_hash(row.iter())).collect();
+        combine_row_hashes(&row_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn result(rows: Vec<Vec<serde_json::Value>>) -> QueryResult {
+        QueryResult {
+            columns: vec!["id".into(), "name".into(), "embedding".into()],
+            rows,
+            execution_time: Duration::ZERO,
+            rows_affected: None,
+            query_plan: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_results_fingerprint_equally() {
+        let a = result(vec![
+            vec![json!(1), json!("alice"), json!([0.5, 1.0])],
+            vec![json!(2), json!(null), json!([0.25, -0.0])],
+        ]);
+        let b = result(vec![
+            vec![json!(1.0), json!("alice"), json!([0.5, 1])],
+            vec![json!(2), json!(null), json!([0.25, 0.0])],
+        ]);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        let reversed = result(a.rows.iter().rev().cloned().collect());
+        assert_eq!(a.fingerprint().unordered, reversed.fingerprint().unordered);
+        assert_ne!(a.fingerprint().ordered, reversed.fingerprint().ordered);
+    }
+
+    #[test]
+    fn test_single_changed_value_changes_fingerprint() {
+        let a = result(vec![
+            vec![json!(1), json!("alice"), json!([0.5, 1.0])],
+            vec![json!(2), json!("bob"), json!([0.25, 0.75])],
+        ]);
+        let mut b = a.clone();
+        b.rows[1][2] = json!([0.25, 0.7500001]);
+
+        assert_ne!(a.fingerprint().ordered, b.fingerprint().ordered);
+        assert_ne!(a.fingerprint().unordered, b.fingerprint().unordered);
+
+        // Values do not leak across columns
+        let shifted = result(vec![vec![json!("1"), json!(1)]]);
+        let swapped = result(vec![vec![json!(1), json!("1")]]);
+        assert_ne!(shifted.fingerprint(), swapped.fingerprint());
+    }
+
+    #[test]
+    fn test_known_encoding() {
+        // Pins the documented encoding so it cannot drift silently
+        let mut hasher = CanonicalHasher::new();
+        hasher.write_null();
+        assert_eq!(hasher.finish(), 0xaf63bd4c8601b7df);
+        assert_eq!(combine_row_hashes(&[]).unordered, 0);
+
+        // Same value is pinned in the driver's fingerprint tests
+        let one_row = result(vec![vec![json!(1), json!("alice")]]);
+        let fingerprint = one_row.fingerprint();
+        assert_eq!(fingerprint.unordered, 0xd4352327d3b49363);
+        assert_eq!(fingerprint.ordered, 0x1ec2d82f3876c25e);
+    }
+}
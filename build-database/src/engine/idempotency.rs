@@ -0,0 +1,303 @@
+//! Idempotency Keys for Retry-Safe Writes
+//!
+//! A client attaches an idempotency key to a write statement; the first
+//! successful execution records its result under that key, and any retry with
+//! the same key within the TTL returns the recorded result instead of applying
+//! the write again.
+//!
+//! Records are appended to `idempotency.log` in the data directory and fsynced
+//! before the result is returned, so deduplication survives restarts for the
+//! whole TTL window. The log is compacted on startup and whenever expired
+//! records dominate it.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+use crate::core::{AuroraResult, AuroraError};
+use crate::errors::ErrorCode;
+use super::aurora_db::QueryResult;
+
+/// Default time a key is remembered after its write succeeds
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Name of the idempotency log inside the data directory
+const LOG_FILE_NAME: &str = "idempotency.log";
+
+/// Outcome of claiming an idempotency key before executing a write
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim {
+    /// Key is new; execute the write and call `complete` or `release`
+    Execute,
+    /// Key was already used for the same statement; return this result
+    Replay(QueryResult),
+}
+
+/// A completed write remembered under its key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IdempotencyRecord {
+    key: String,
+    /// Hash of the statement, so a reused key with different SQL is rejected
+    fingerprint: u64,
+    result: QueryResult,
+    expires_at: u64,
+}
+
+#[derive(Debug, Clone)]
+enum KeyState {
+    InFlight { fingerprint: u64 },
+    Done(IdempotencyRecord),
+}
+
+#[derive(Debug)]
+struct StoreState {
+    keys: HashMap<String, KeyState>,
+    log: File,
+    /// Records written to the log since it was last compacted
+    log_records: usize,
+}
+
+/// Durable store of idempotency keys and the results they produced
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    path: PathBuf,
+    ttl: Duration,
+    state: Mutex<StoreState>,
+}
+
+impl IdempotencyStore {
+    /// Open (or create) the store in `data_directory`, dropping expired keys
+    pub fn open(data_directory: &Path, ttl: Duration) -> AuroraResult<Self> {
+        std::fs::create_dir_all(data_directory).map_err(Self::io_error)?;
+        let path = data_directory.join(LOG_FILE_NAME);
+        let now = Self::now();
+
+        let mut keys = HashMap::new();
+        if path.exists() {
+            let reader = BufReader::new(File::open(&path).map_err(Self::io_error)?);
+            for line in reader.lines() {
+                let line = line.map_err(Self::io_error)?;
+                // A torn final line from a crash mid-append is skipped
+                if let Ok(record) = serde_json::from_str::<IdempotencyRecord>(&line) {
+                    if record.expires_at > now {
+                        keys.insert(record.key.clone(), KeyState::Done(record));
+                    }
+                }
+            }
+        }
+
+        let records: Vec<_> = keys.values().filter_map(|state| match state {
+            KeyState::Done(record) => Some(record.clone()),
+            KeyState::InFlight { .. } => None,
+        }).collect();
+        let log = Self::rewrite_log(&path, &records)?;
+
+        log::info!("Loaded {} idempotency keys from {}", records.len(), path.display());
+
+        Ok(Self {
+            path,
+            ttl,
+            state: Mutex::new(StoreState { keys, log, log_records: records.len() }),
+        })
+    }
+
+    /// Claim `key` for executing `sql`
+    ///
+    /// Fails if the key is in flight on another request, or was used for a
+    /// different statement.
+    pub fn claim(&self, key: &str, sql: &str) -> AuroraResult<IdempotencyClaim> {
+        let fingerprint = Self::fingerprint(sql);
+        let mut state = self.state.lock();
+
+        match state.keys.get(key) {
+            Some(KeyState::Done(record)) if record.expires_at > Self::now() => {
+                if record.fingerprint != fingerprint {
+                    return Err(Self::mismatch_error(key));
+                }
+                return Ok(IdempotencyClaim::Replay(record.result.clone()));
+            }
+            Some(KeyState::InFlight { fingerprint: in_flight }) => {
+                if *in_flight != fingerprint {
+                    return Err(Self::mismatch_error(key));
+                }
+                return Err(AuroraError::new(
+                    ErrorCode::TransactionConflict,
+                    format!("A write with idempotency key '{}' is already in progress", key)
+                ));
+            }
+            _ => {}
+        }
+
+        state.keys.insert(key.to_string(), KeyState::InFlight { fingerprint });
+        Ok(IdempotencyClaim::Execute)
+    }
+
+    /// Durably record the result of a claimed write
+    pub fn complete(&self, key: &str, result: &QueryResult) -> AuroraResult<()> {
+        let mut state = self.state.lock();
+        let fingerprint = match state.keys.get(key) {
+            Some(KeyState::InFlight { fingerprint }) => *fingerprint,
+            _ => {
+                return Err(AuroraError::new(
+                    ErrorCode::TransactionInvalidState,
+                    format!("Idempotency key '{}' was not claimed", key)
+                ));
+            }
+        };
+
+        let record = IdempotencyRecord {
+            key: key.to_string(),
+            fingerprint,
+            result: result.clone(),
+            expires_at: Self::now() + self.ttl.as_secs(),
+        };
+
+        let mut line = serde_json::to_string(&record)
+            .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Failed to encode idempotency record: {}", e)))?;
+        line.push('\n');
+        state.log.write_all(line.as_bytes()).map_err(Self::io_error)?;
+        state.log.sync_data().map_err(Self::io_error)?;
+        state.log_records += 1;
+
+        state.keys.insert(key.to_string(), KeyState::Done(record));
+        self.maybe_compact(&mut state)?;
+        Ok(())
+    }
+
+    /// Release a claim whose write failed, so a retry can apply it
+    pub fn release(&self, key: &str) {
+        let mut state = self.state.lock();
+        if matches!(state.keys.get(key), Some(KeyState::InFlight { .. })) {
+            state.keys.remove(key);
+        }
+    }
+
+    /// Number of unexpired keys
+    pub fn len(&self) -> usize {
+        let now = Self::now();
+        self.state.lock().keys.values()
+            .filter(|state| matches!(state, KeyState::Done(record) if record.expires_at > now))
+            .count()
+    }
+
+    /// Drop expired keys and rewrite the log once most of it is dead weight
+    fn maybe_compact(&self, state: &mut StoreState) -> AuroraResult<()> {
+        let now = Self::now();
+        state.keys.retain(|_, key_state| match key_state {
+            KeyState::Done(record) => record.expires_at > now,
+            KeyState::InFlight { .. } => true,
+        });
+
+        let live = state.keys.len();
+        if state.log_records < 1024 || state.log_records < live * 2 {
+            return Ok(());
+        }
+
+        let records: Vec<_> = state.keys.values().filter_map(|key_state| match key_state {
+            KeyState::Done(record) => Some(record.clone()),
+            KeyState::InFlight { .. } => None,
+        }).collect();
+        state.log = Self::rewrite_log(&self.path, &records)?;
+        state.log_records = records.len();
+        Ok(())
+    }
+
+    /// Atomically replace the log with `records` and reopen it for appending
+    fn rewrite_log(path: &Path, records: &[IdempotencyRecord]) -> AuroraResult<File> {
+        let tmp_path = path.with_extension("log.tmp");
+        {
+            let mut tmp = File::create(&tmp_path).map_err(Self::io_error)?;
+            for record in records {
+                let line = serde_json::to_string(record)
+                    .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Failed to encode idempotency record: {}", e)))?;
+                writeln!(tmp, "{}", line).map_err(Self::io_error)?;
+            }
+            tmp.sync_all().map_err(Self::io_error)?;
+        }
+        std::fs::rename(&tmp_path, path).map_err(Self::io_error)?;
+
+        OpenOptions::new().append(true).open(path).map_err(Self::io_error)
+    }
+
+    fn fingerprint(sql: &str) -> u64 {
+        // FNV-1a: stable across restarts, unlike the std hasher
+        sql.trim().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    }
+
+    fn mismatch_error(key: &str) -> AuroraError {
+        AuroraError::new(
+            ErrorCode::QueryInvalidParameters,
+            format!("Idempotency key '{}' was already used for a different statement", key)
+        )
+    }
+
+    fn io_error(e: std::io::Error) -> AuroraError {
+        AuroraError::new(ErrorCode::StorageUnavailable, format!("Idempotency log I/O error: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn result(rows_affected: u64) -> QueryResult {
+        QueryResult {
+            columns: vec![],
+            rows: vec![],
+            execution_time: Duration::from_millis(3),
+            rows_affected: Some(rows_affected),
+            query_plan: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_and_restart() {
+        let dir = tempdir().unwrap();
+        let sql = "INSERT INTO t (id) VALUES (1)";
+
+        let store = IdempotencyStore::open(dir.path(), DEFAULT_IDEMPOTENCY_TTL).unwrap();
+        assert!(matches!(store.claim("k1", sql).unwrap(), IdempotencyClaim::Execute));
+        // Concurrent retry while the first attempt is still running
+        assert!(store.claim("k1", sql).is_err());
+        store.complete("k1", &result(1)).unwrap();
+
+        // Failed writes release the key instead of recording it
+        assert!(matches!(store.claim("k2", sql).unwrap(), IdempotencyClaim::Execute));
+        store.release("k2");
+        drop(store);
+
+        let store = IdempotencyStore::open(dir.path(), DEFAULT_IDEMPOTENCY_TTL).unwrap();
+        assert_eq!(store.len(), 1);
+        match store.claim("k1", sql).unwrap() {
+            IdempotencyClaim::Replay(replayed) => assert_eq!(replayed.rows_affected, Some(1)),
+            other => panic!("expected replay, got {:?}", other),
+        }
+        assert!(store.claim("k1", "DELETE FROM t").is_err());
+        assert!(matches!(store.claim("k2", sql).unwrap(), IdempotencyClaim::Execute));
+    }
+
+    #[test]
+    fn test_expired_keys_are_dropped() {
+        let dir = tempdir().unwrap();
+        let store = IdempotencyStore::open(dir.path(), Duration::ZERO).unwrap();
+        store.claim("k1", "DELETE FROM t").unwrap();
+        store.complete("k1", &result(4)).unwrap();
+
+        assert_eq!(store.len(), 0);
+        assert!(matches!(store.claim("k1", "DELETE FROM t").unwrap(), IdempotencyClaim::Execute));
+        drop(store);
+
+        let store = IdempotencyStore::open(dir.path(), Duration::ZERO).unwrap();
+        assert_eq!(store.len(), 0);
+    }
+}
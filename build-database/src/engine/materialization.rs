@@ -0,0 +1,169 @@
+//! CTE and Temporary Table Materialization
+//!
+//! A WITH query referenced several times in one statement is computed once,
+//! on its first reference, and every reference reads the stored result. A
+//! result stays in memory while it fits in the materialization budget; once
+//! it outgrows the budget, the rows gathered so far and all that follow are
+//! written to a spill segment, which each reference reads back in order.
+//! Temp pages written and read are counted like a spilled sort's.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use crate::config::MaterializationConfig;
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+use crate::storage::buffer_usage::BufferUsage;
+use super::spill::{DirectorySpill, SpillBackend, SpillFile, SpillWriter};
+
+/// Where a materialized result is kept
+enum Storage<T> {
+    Memory(Vec<T>),
+    /// A spill segment of the serialized rows, removed when dropped
+    Spilled(SpillFile),
+}
+
+/// A computed CTE or temporary table, read as often as it is referenced
+pub struct MaterializedRows<T> {
+    storage: Storage<T>,
+    rows: usize,
+    bytes: u64,
+    buffers: Option<Arc<BufferUsage>>,
+}
+
+impl<T> MaterializedRows<T> {
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Whether the result outgrew the budget and lives in the spill backend
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.storage, Storage::Spilled(_))
+    }
+
+    /// Serialized size of the rows
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<T: Clone + DeserializeOwned> MaterializedRows<T> {
+    /// Every row, in the order it was materialized
+    pub fn rows(&self) -> AuroraResult<Vec<T>> {
+        let file = match &self.storage {
+            Storage::Memory(rows) => return Ok(rows.clone()),
+            Storage::Spilled(file) => file,
+        };
+        if let Some(buffers) = &self.buffers {
+            buffers.temp_read(self.bytes);
+        }
+        let mut reader = file.reader()?;
+        (0..self.rows)
+            .map(|_| bincode::deserialize_from(&mut reader).map_err(spill_error))
+            .collect()
+    }
+}
+
+/// Stores results under the materialization budget
+pub struct Materializer {
+    memory_budget: u64,
+    spill: Arc<dyn SpillBackend>,
+    buffers: Option<Arc<BufferUsage>>,
+}
+
+impl Materializer {
+    /// Materializer spilling to files under `temp_dir` on local disk
+    pub fn new(config: &MaterializationConfig, temp_dir: &Path, buffers: Option<Arc<BufferUsage>>) -> Self {
+        Self { memory_budget: config.memory_budget_bytes as u64, spill: Arc::new(DirectorySpill::local(temp_dir)), buffers }
+    }
+
+    /// Spill results to `spill` instead of the local temp directory
+    pub fn with_spill_backend(mut self, spill: Arc<dyn SpillBackend>) -> Self {
+        self.spill = spill;
+        self
+    }
+
+    /// Store `rows`, spilling once they exceed the budget
+    pub fn materialize<T, I>(&self, rows: I) -> AuroraResult<MaterializedRows<T>>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = T>,
+    {
+        let mut memory = Vec::new();
+        let mut spill: Option<SpillWriter> = None;
+        let mut count = 0;
+        let mut bytes = 0u64;
+        for row in rows {
+            count += 1;
+            bytes += bincode::serialized_size(&row).map_err(spill_error)?;
+            if let Some(writer) = spill.as_mut() {
+                bincode::serialize_into(writer, &row).map_err(spill_error)?;
+                continue;
+            }
+            memory.push(row);
+            if bytes > self.memory_budget {
+                // Over budget: everything so far is spilled, and the rest follows
+                let mut writer = SpillWriter::create(&self.spill, "materialized")?;
+                for row in memory.drain(..) {
+                    bincode::serialize_into(&mut writer, &row).map_err(spill_error)?;
+                }
+                spill = Some(writer);
+            }
+        }
+
+        let storage = match spill {
+            Some(writer) => {
+                let file = writer.finish()?;
+                if let Some(buffers) = &self.buffers {
+                    buffers.temp_written(bytes);
+                }
+                Storage::Spilled(file)
+            }
+            None => Storage::Memory(memory),
+        };
+        Ok(MaterializedRows { storage, rows: count, bytes, buffers: self.buffers.clone() })
+    }
+}
+
+/// The CTEs of one statement, each computed on its first reference and
+/// shared by the rest
+pub struct CteMaterializations<T> {
+    materializer: Materializer,
+    results: Mutex<HashMap<String, Arc<MaterializedRows<T>>>>,
+}
+
+impl<T: Serialize> CteMaterializations<T> {
+    pub fn new(materializer: Materializer) -> Self {
+        Self { materializer, results: Mutex::new(HashMap::new()) }
+    }
+
+    /// The result of CTE `name`, running `compute` only if no earlier
+    /// reference has materialized it
+    pub fn get_or_materialize<I, F>(&self, name: &str, compute: F) -> AuroraResult<Arc<MaterializedRows<T>>>
+    where
+        I: IntoIterator<Item = T>,
+        F: FnOnce() -> AuroraResult<I>,
+    {
+        if let Some(result) = self.results.lock().get(name) {
+            return Ok(result.clone());
+        }
+        let result = Arc::new(self.materializer.materialize(compute()?)?);
+        log::debug!("Materialized CTE {}: {} rows, {} bytes{}", name, result.len(), result.bytes(),
+                    if result.is_spilled() { ", spilled" } else { "" });
+        Ok(self.results.lock().entry(name.to_string()).or_insert(result).clone())
+    }
+
+    /// Whether CTE `name` has been materialized
+    pub fn contains(&self, name: &str) -> bool {
+        self.results.lock().contains_key(name)
+    }
+}
+
+fn spill_error(error: bincode::Error) -> AuroraError {
+    AuroraError::new(ErrorCode::StorageUnavailable, format!("Materialization spill error: {}", error))
+}
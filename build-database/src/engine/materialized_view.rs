@@ -0,0 +1,693 @@
+//! Materialized Views
+//!
+//! `CREATE MATERIALIZED VIEW name AS SELECT ...` stores the result of the
+//! defining query, and `SELECT ... FROM name` reads the stored rows instead of
+//! re-running it. Refresh comes in two forms:
+//!
+//! - `REFRESH MATERIALIZED VIEW name` holds the view's write lock while the
+//!   query is recomputed, so readers wait for the new contents
+//! - `REFRESH MATERIALIZED VIEW CONCURRENTLY name` computes the new contents
+//!   first and swaps them in under a brief write lock, so readers keep seeing
+//!   the previous contents until the swap
+//!
+//! Simple views are also maintained incrementally as their base tables change:
+//!
+//! | Defining query                           | Maintained on               |
+//! |------------------------------------------|-----------------------------|
+//! | filter/projection over one table         | INSERT, UPDATE, DELETE      |
+//! | COUNT/SUM/AVG (GROUP BY) over one table  | INSERT, UPDATE, DELETE      |
+//! | inner joins without aggregates           | INSERT                      |
+//!
+//! Every other change leaves the view stale until the next refresh, and
+//! `MaterializedViewStatus` reports how many changes it is missing and since
+//! when. Aggregate views keep a hidden row count and per-aggregate running sums
+//! for each group, so a group disappears once its last row is deleted. SUM and
+//! AVG over floating-point columns may drift from a full recomputation by
+//! rounding error.
+//!
+//! A write locks the maintenance state of every dependent view before it runs,
+//! which keeps refreshes from interleaving with its delta. Definitions live in
+//! memory and are not persisted across restarts.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use parking_lot::Mutex;
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard, OwnedMutexGuard, RwLock as AsyncRwLock, RwLockWriteGuard};
+use crate::core::{AuroraResult, AuroraError};
+use crate::errors::ErrorCode;
+use crate::query::parser::ast::{Expression, FunctionCall, JoinType, SelectItem, SelectQuery};
+use crate::types::{DataValue, Decimal};
+
+/// A stored view row, keyed by output column name
+pub type ViewRow = HashMap<String, DataValue>;
+
+/// How a view is kept up to date between refreshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewMaintenance {
+    /// Filter/projection over one table; every write applies as a delta
+    IncrementalProjection,
+    /// COUNT/SUM/AVG over one table; every write adjusts the group counters
+    IncrementalAggregate,
+    /// Inner joins; inserts apply as deltas, updates and deletes mark the view stale
+    IncrementalJoinInsert,
+    /// Only brought up to date by REFRESH MATERIALIZED VIEW
+    RefreshOnly,
+}
+
+/// Freshness and size of a materialized view
+#[derive(Debug, Clone)]
+pub struct MaterializedViewStatus {
+    pub name: String,
+    pub base_tables: Vec<String>,
+    pub maintenance: ViewMaintenance,
+    pub row_count: usize,
+    pub last_refreshed: SystemTime,
+    /// Base-table row changes not reflected in the contents
+    pub pending_changes: u64,
+    /// When the oldest unreflected change happened; `None` while fresh
+    pub stale_since: Option<SystemTime>,
+}
+
+impl MaterializedViewStatus {
+    /// Whether the contents are missing base-table changes
+    pub fn is_stale(&self) -> bool {
+        self.stale_since.is_some()
+    }
+
+    /// How long the contents have been missing base-table changes
+    pub fn staleness(&self) -> Duration {
+        self.stale_since
+            .and_then(|since| SystemTime::now().duration_since(since).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// An aggregate the view can maintain from running totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AggregateKind {
+    CountRows,
+    CountColumn,
+    Sum,
+    Avg,
+}
+
+#[derive(Debug, Clone)]
+enum AggregateOutput {
+    /// A GROUP BY expression, read from the group key
+    Group { name: String, key_index: usize },
+    /// An aggregate over `AggregatePlan::arguments[argument_index]`
+    Aggregate { name: String, kind: AggregateKind, argument_index: usize },
+}
+
+/// Incremental plan for a single-table COUNT/SUM/AVG view
+#[derive(Debug, Clone)]
+pub(crate) struct AggregatePlan {
+    /// GROUP BY expressions, evaluated per base row to form the group key
+    pub group_by: Vec<Expression>,
+    /// Aggregate arguments, evaluated per base row; `None` for COUNT(*)
+    pub arguments: Vec<Option<Expression>>,
+    outputs: Vec<AggregateOutput>,
+}
+
+/// One base row entering (`sign = 1`) or leaving (`sign = -1`) an aggregate view
+#[derive(Debug, Clone)]
+pub(crate) struct AggregateChange {
+    pub key: Vec<DataValue>,
+    /// Values of `AggregatePlan::arguments`, in order
+    pub arguments: Vec<DataValue>,
+    pub sign: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    non_null: i64,
+    numeric: i64,
+    sum: f64,
+    /// Exact running total of integer and decimal inputs
+    exact: Decimal,
+    decimals: i64,
+    reals: i64,
+}
+
+impl Accumulator {
+    fn apply(&mut self, value: &DataValue, sign: i64) {
+        match value {
+            DataValue::Null => return,
+            DataValue::Integer(i) => {
+                self.numeric += sign;
+                self.sum += (sign * i) as f64;
+                self.add_exact(&Decimal::from(*i), sign);
+            }
+            DataValue::Decimal(d) => {
+                self.numeric += sign;
+                self.decimals += sign;
+                self.sum += sign as f64 * d.to_f64().unwrap_or(0.0);
+                self.add_exact(d, sign);
+            }
+            DataValue::Real(r) => {
+                self.numeric += sign;
+                self.reals += sign;
+                self.sum += sign as f64 * r;
+            }
+            _ => {}
+        }
+        self.non_null += sign;
+    }
+
+    fn add_exact(&mut self, value: &Decimal, sign: i64) {
+        let total = if sign < 0 { self.exact.checked_sub(value) } else { self.exact.checked_add(value) };
+        if let Ok(total) = total {
+            self.exact = total;
+        }
+    }
+
+    /// SUM as the full recompute would produce it: exact once any decimal is
+    /// involved, unless floats are mixed in
+    fn total(&self) -> DataValue {
+        if self.decimals > 0 && self.reals == 0 {
+            DataValue::Decimal(self.exact.clone())
+        } else {
+            DataValue::Real(self.sum)
+        }
+    }
+
+    fn average(&self) -> DataValue {
+        match self.total() {
+            DataValue::Decimal(sum) => sum.checked_div(&Decimal::from(self.numeric))
+                .map(DataValue::Decimal)
+                .unwrap_or(DataValue::Null),
+            _ => DataValue::Real(self.sum / self.numeric as f64),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GroupState {
+    key: Vec<DataValue>,
+    /// Hidden COUNT(*) that decides when the group disappears
+    rows: i64,
+    accumulators: Vec<Accumulator>,
+}
+
+impl AggregatePlan {
+    /// Plan for `query`, or `None` if it cannot be maintained from running totals
+    fn from_query(query: &SelectQuery) -> Option<Self> {
+        let group_by = query.group_by.as_ref().map(|g| g.expressions.clone()).unwrap_or_default();
+        if !group_by.iter().all(|expr| matches!(expr, Expression::Column(_))) {
+            return None;
+        }
+
+        let mut arguments = Vec::new();
+        let mut outputs = Vec::new();
+        for item in &query.select_list {
+            let (expr, name) = match item {
+                SelectItem::Expression(expr) => (expr, output_name(expr)),
+                SelectItem::Aliased { expression, alias } => (expression, alias.clone()),
+                SelectItem::Wildcard => return None,
+            };
+
+            match expr {
+                Expression::Column(column) => {
+                    let key_index = group_by.iter()
+                        .position(|g| matches!(g, Expression::Column(c) if c == column))?;
+                    outputs.push(AggregateOutput::Group { name, key_index });
+                }
+                Expression::Function(FunctionCall { name: function, arguments: args }) => {
+                    let count_rows = args.is_empty() || matches!(args[0], Expression::Asterisk);
+                    let kind = match function.to_uppercase().as_str() {
+                        "COUNT" if count_rows => AggregateKind::CountRows,
+                        "COUNT" => AggregateKind::CountColumn,
+                        "SUM" => AggregateKind::Sum,
+                        "AVG" => AggregateKind::Avg,
+                        _ => return None,
+                    };
+                    let argument = match kind {
+                        AggregateKind::CountRows => None,
+                        _ => match args.as_slice() {
+                            [argument @ Expression::Column(_)] => Some(argument.clone()),
+                            _ => return None,
+                        },
+                    };
+                    outputs.push(AggregateOutput::Aggregate { name, kind, argument_index: arguments.len() });
+                    arguments.push(argument);
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self { group_by, arguments, outputs })
+    }
+
+    fn group_row(&self, group: &GroupState) -> ViewRow {
+        self.outputs.iter()
+            .map(|output| match output {
+                AggregateOutput::Group { name, key_index } => (name.clone(), group.key[*key_index].clone()),
+                AggregateOutput::Aggregate { name, kind, argument_index } => {
+                    let acc = &group.accumulators[*argument_index];
+                    let value = match kind {
+                        AggregateKind::CountRows => DataValue::Integer(group.rows),
+                        AggregateKind::CountColumn => DataValue::Integer(acc.non_null),
+                        AggregateKind::Sum if acc.numeric > 0 => acc.total(),
+                        AggregateKind::Avg if acc.numeric > 0 => acc.average(),
+                        AggregateKind::Sum | AggregateKind::Avg => DataValue::Null,
+                    };
+                    (name.clone(), value)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Column name the engine gives an unaliased select expression
+fn output_name(expr: &Expression) -> String {
+    match expr {
+        Expression::Column(name) => name.clone(),
+        Expression::Function(FunctionCall { name, .. }) => name.clone(),
+        _ => "expression".to_string(),
+    }
+}
+
+/// Group key in the same form the engine's GROUP BY uses
+fn group_key(key: &[DataValue]) -> String {
+    key.iter().map(|value| format!("{:?}", value)).collect::<Vec<_>>().join("|")
+}
+
+fn is_aggregate(expr: &Expression) -> bool {
+    matches!(expr, Expression::Function(FunctionCall { name, .. })
+        if matches!(name.to_uppercase().as_str(), "COUNT" | "SUM" | "AVG" | "MIN" | "MAX" | "FINGERPRINT"))
+}
+
+fn classify(query: &SelectQuery) -> (ViewMaintenance, Option<AggregatePlan>) {
+    if query.having.is_some() || query.order_by.is_some() || query.limit.is_some() || query.vector_extensions.is_some() {
+        return (ViewMaintenance::RefreshOnly, None);
+    }
+
+    let expressions: Vec<&Expression> = query.select_list.iter()
+        .filter_map(|item| match item {
+            SelectItem::Expression(expr) => Some(expr),
+            SelectItem::Aliased { expression, .. } => Some(expression),
+            SelectItem::Wildcard => None,
+        })
+        .collect();
+    if expressions.iter().any(|expr| matches!(expr, Expression::WindowFunction(_))) {
+        return (ViewMaintenance::RefreshOnly, None);
+    }
+    let aggregated = query.group_by.is_some() || expressions.iter().any(|expr| is_aggregate(expr));
+
+    if !query.from_clause.joins.is_empty() {
+        // Deltas substitute one table's rows, so each table may appear only once
+        let mut tables = vec![&query.from_clause.table];
+        for join in &query.from_clause.joins {
+            if tables.contains(&&join.table) || !matches!(join.join_type, JoinType::Inner) {
+                return (ViewMaintenance::RefreshOnly, None);
+            }
+            tables.push(&join.table);
+        }
+        return if aggregated {
+            (ViewMaintenance::RefreshOnly, None)
+        } else {
+            (ViewMaintenance::IncrementalJoinInsert, None)
+        };
+    }
+
+    if aggregated {
+        match AggregatePlan::from_query(query) {
+            Some(plan) => (ViewMaintenance::IncrementalAggregate, Some(plan)),
+            None => (ViewMaintenance::RefreshOnly, None),
+        }
+    } else {
+        (ViewMaintenance::IncrementalProjection, None)
+    }
+}
+
+/// Stored contents of a view
+#[derive(Debug, Default)]
+pub(crate) struct ViewContents {
+    rows: Vec<ViewRow>,
+    /// Running totals per group; only used by aggregate views
+    groups: HashMap<String, GroupState>,
+}
+
+impl ViewContents {
+    fn rebuild_rows(&mut self, plan: &AggregatePlan) {
+        self.rows = self.groups.values().map(|group| plan.group_row(group)).collect();
+    }
+
+    fn apply_changes(&mut self, plan: &AggregatePlan, changes: Vec<AggregateChange>) {
+        for change in changes {
+            let key = group_key(&change.key);
+            let group = self.groups.entry(key.clone()).or_insert_with(|| GroupState {
+                key: change.key.clone(),
+                rows: 0,
+                accumulators: vec![Accumulator::default(); plan.arguments.len()],
+            });
+            group.rows += change.sign;
+            for (acc, value) in group.accumulators.iter_mut().zip(&change.arguments) {
+                acc.apply(value, change.sign);
+            }
+            // Without GROUP BY there is always exactly one output row
+            if group.rows <= 0 && !plan.group_by.is_empty() {
+                self.groups.remove(&key);
+            }
+        }
+        if plan.group_by.is_empty() && self.groups.is_empty() {
+            self.groups.insert(String::new(), GroupState {
+                key: Vec::new(),
+                rows: 0,
+                accumulators: vec![Accumulator::default(); plan.arguments.len()],
+            });
+        }
+        self.rebuild_rows(plan);
+    }
+
+    /// Remove one occurrence of `row`, if present
+    fn remove_row(&mut self, row: &ViewRow) {
+        if let Some(position) = self.rows.iter().position(|existing| existing == row) {
+            self.rows.swap_remove(position);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Freshness {
+    last_refreshed: SystemTime,
+    pending_changes: u64,
+    stale_since: Option<SystemTime>,
+}
+
+/// A materialized view and its stored contents
+#[derive(Debug)]
+pub(crate) struct MaterializedView {
+    name: String,
+    definition: SelectQuery,
+    base_tables: Vec<String>,
+    maintenance: ViewMaintenance,
+    aggregate: Option<AggregatePlan>,
+    contents: AsyncRwLock<ViewContents>,
+    /// Serializes refreshes with each other and with writes to the base tables
+    maintenance_lock: Arc<AsyncMutex<()>>,
+    freshness: Mutex<Freshness>,
+}
+
+impl MaterializedView {
+    /// New, empty view over `definition`
+    pub fn new(name: &str, definition: SelectQuery) -> Self {
+        let (maintenance, aggregate) = classify(&definition);
+        let base_tables = std::iter::once(definition.from_clause.table.clone())
+            .chain(definition.from_clause.joins.iter().map(|join| join.table.clone()))
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            definition,
+            base_tables,
+            maintenance,
+            aggregate,
+            contents: AsyncRwLock::new(ViewContents::default()),
+            maintenance_lock: Arc::new(AsyncMutex::new(())),
+            freshness: Mutex::new(Freshness {
+                last_refreshed: SystemTime::now(),
+                pending_changes: 0,
+                stale_since: None,
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn definition(&self) -> &SelectQuery {
+        &self.definition
+    }
+
+    pub fn base_tables(&self) -> &[String] {
+        &self.base_tables
+    }
+
+    pub fn maintenance(&self) -> ViewMaintenance {
+        self.maintenance
+    }
+
+    /// Running-totals plan, for `IncrementalAggregate` views
+    pub fn aggregate_plan(&self) -> Option<&AggregatePlan> {
+        self.aggregate.as_ref()
+    }
+
+    pub fn depends_on(&self, table: &str) -> bool {
+        self.base_tables.iter().any(|base| base == table)
+    }
+
+    /// Current contents; waits while a non-concurrent refresh is running
+    pub async fn rows(&self) -> Vec<ViewRow> {
+        self.contents.read().await.rows.clone()
+    }
+
+    /// Contents holding exactly `rows`
+    pub fn contents_from_rows(&self, rows: Vec<ViewRow>) -> ViewContents {
+        ViewContents { rows, groups: HashMap::new() }
+    }
+
+    /// Contents of an aggregate view built from every base row entering it
+    pub fn contents_from_changes(&self, changes: Vec<AggregateChange>) -> AuroraResult<ViewContents> {
+        let plan = self.aggregate.as_ref().ok_or_else(|| AuroraError::new(
+            ErrorCode::QueryInvalidParameters,
+            format!("Materialized view '{}' is not an aggregate view", self.name),
+        ))?;
+        let mut contents = ViewContents::default();
+        contents.apply_changes(plan, changes);
+        Ok(contents)
+    }
+
+    /// Start a refresh; readers are blocked until `finish` unless `concurrently`
+    pub async fn begin_refresh(&self, concurrently: bool) -> RefreshGuard<'_> {
+        let maintenance = self.maintenance_lock.lock().await;
+        let contents = if concurrently { None } else { Some(self.contents.write().await) };
+        RefreshGuard { view: self, _maintenance: maintenance, contents }
+    }
+
+    /// Apply view rows a write added and removed
+    pub async fn apply_row_delta(&self, inserted: Vec<ViewRow>, deleted: Vec<ViewRow>) {
+        let mut contents = self.contents.write().await;
+        for row in &deleted {
+            contents.remove_row(row);
+        }
+        contents.rows.extend(inserted);
+    }
+
+    /// Apply base rows a write added and removed to the group totals
+    pub async fn apply_aggregate_delta(&self, changes: Vec<AggregateChange>) -> AuroraResult<()> {
+        let plan = self.aggregate.as_ref().ok_or_else(|| AuroraError::new(
+            ErrorCode::QueryInvalidParameters,
+            format!("Materialized view '{}' is not an aggregate view", self.name),
+        ))?;
+        self.contents.write().await.apply_changes(plan, changes);
+        Ok(())
+    }
+
+    /// Record base-table changes the contents do not reflect
+    pub fn mark_stale(&self, changes: u64) {
+        let mut freshness = self.freshness.lock();
+        freshness.pending_changes += changes;
+        freshness.stale_since.get_or_insert_with(SystemTime::now);
+    }
+
+    pub async fn status(&self) -> MaterializedViewStatus {
+        let row_count = self.contents.read().await.rows.len();
+        let freshness = self.freshness.lock();
+        MaterializedViewStatus {
+            name: self.name.clone(),
+            base_tables: self.base_tables.clone(),
+            maintenance: self.maintenance,
+            row_count,
+            last_refreshed: freshness.last_refreshed,
+            pending_changes: freshness.pending_changes,
+            stale_since: freshness.stale_since,
+        }
+    }
+}
+
+/// An in-progress refresh; dropping it without `finish` keeps the old contents
+pub(crate) struct RefreshGuard<'a> {
+    view: &'a MaterializedView,
+    _maintenance: MutexGuard<'a, ()>,
+    /// Held for the whole refresh unless it runs concurrently
+    contents: Option<RwLockWriteGuard<'a, ViewContents>>,
+}
+
+impl RefreshGuard<'_> {
+    /// Install the recomputed contents and mark the view fresh
+    pub async fn finish(self, contents: ViewContents) {
+        match self.contents {
+            Some(mut current) => *current = contents,
+            None => *self.view.contents.write().await = contents,
+        }
+        let mut freshness = self.view.freshness.lock();
+        freshness.last_refreshed = SystemTime::now();
+        freshness.pending_changes = 0;
+        freshness.stale_since = None;
+    }
+}
+
+/// A view locked against refreshes for the duration of a write to its base table
+pub(crate) struct DependentView {
+    pub view: Arc<MaterializedView>,
+    _maintenance: OwnedMutexGuard<()>,
+}
+
+/// All materialized views of a database
+#[derive(Debug, Default)]
+pub(crate) struct MaterializedViewRegistry {
+    views: AsyncRwLock<HashMap<String, Arc<MaterializedView>>>,
+}
+
+impl MaterializedViewRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, name: &str) -> Option<Arc<MaterializedView>> {
+        self.views.read().await.get(name).cloned()
+    }
+
+    pub async fn contains(&self, name: &str) -> bool {
+        self.views.read().await.contains_key(name)
+    }
+
+    pub async fn insert(&self, view: Arc<MaterializedView>) -> AuroraResult<()> {
+        let mut views = self.views.write().await;
+        if views.contains_key(view.name()) {
+            return Err(AuroraError::new(
+                ErrorCode::ValidationConstraintViolation,
+                format!("Materialized view '{}' already exists", view.name()),
+            ));
+        }
+        views.insert(view.name().to_string(), view);
+        Ok(())
+    }
+
+    pub async fn remove(&self, name: &str) -> Option<Arc<MaterializedView>> {
+        self.views.write().await.remove(name)
+    }
+
+    /// Names of the views reading `table`, sorted
+    pub async fn dependents_of(&self, table: &str) -> Vec<String> {
+        let mut names: Vec<String> = self.views.read().await.values()
+            .filter(|view| view.depends_on(table))
+            .map(|view| view.name().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Lock every view reading `table` against refreshes
+    ///
+    /// Views are locked in name order; a refresh only ever holds its own lock,
+    /// so writes to different tables cannot deadlock with each other or with it.
+    pub async fn lock_dependents(&self, table: &str) -> Vec<DependentView> {
+        let mut views: Vec<Arc<MaterializedView>> = self.views.read().await.values()
+            .filter(|view| view.depends_on(table))
+            .cloned()
+            .collect();
+        views.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut locked = Vec::with_capacity(views.len());
+        for view in views {
+            let maintenance = Arc::clone(&view.maintenance_lock).lock_owned().await;
+            locked.push(DependentView { view, _maintenance: maintenance });
+        }
+        locked
+    }
+
+    pub async fn list(&self) -> Vec<Arc<MaterializedView>> {
+        let mut views: Vec<_> = self.views.read().await.values().cloned().collect();
+        views.sort_by(|a, b| a.name.cmp(&b.name));
+        views
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::parser::ast::Query;
+    use crate::query::parser::SqlParser;
+
+    async fn view(sql: &str) -> MaterializedView {
+        match SqlParser::new().parse(sql).await.unwrap() {
+            Query::Select(select) => MaterializedView::new("v", select),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    fn change(key: i64, amount: i64, sign: i64) -> AggregateChange {
+        AggregateChange {
+            key: vec![DataValue::Integer(key)],
+            // COUNT(*) takes no argument; SUM(total) takes the amount
+            arguments: vec![DataValue::Null, DataValue::Integer(amount)],
+            sign,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_classification() {
+        let cases = [
+            ("SELECT id, total FROM orders WHERE total > 10", ViewMaintenance::IncrementalProjection),
+            ("SELECT user_id, COUNT(*), SUM(total) FROM orders GROUP BY user_id", ViewMaintenance::IncrementalAggregate),
+            ("SELECT user_id, MAX(total) FROM orders GROUP BY user_id", ViewMaintenance::RefreshOnly),
+            ("SELECT id, name FROM users JOIN orders ON users.id = orders.user_id", ViewMaintenance::IncrementalJoinInsert),
+            ("SELECT id, name FROM users LEFT JOIN orders ON users.id = orders.user_id", ViewMaintenance::RefreshOnly),
+            ("SELECT id FROM orders ORDER BY id LIMIT 5", ViewMaintenance::RefreshOnly),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(view(sql).await.maintenance(), expected, "{}", sql);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_delta_drops_empty_groups() {
+        let view = view("SELECT user_id, COUNT(*) AS n, SUM(total) AS revenue FROM orders GROUP BY user_id").await;
+        let contents = view.contents_from_changes(vec![change(1, 10, 1), change(1, 5, 1), change(2, 7, 1)]).unwrap();
+        *view.contents.write().await = contents;
+
+        view.apply_aggregate_delta(vec![change(1, 10, -1), change(2, 7, -1), change(3, 1, 1)]).await.unwrap();
+        let mut rows = view.rows().await;
+        rows.sort_by_key(|row| format!("{:?}", row["user_id"]));
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["user_id"], DataValue::Integer(1));
+        assert_eq!(rows[0]["n"], DataValue::Integer(1));
+        assert_eq!(rows[0]["revenue"], DataValue::Real(5.0));
+        assert_eq!(rows[1]["user_id"], DataValue::Integer(3));
+    }
+
+    #[tokio::test]
+    async fn test_only_blocking_refresh_holds_off_readers() {
+        let view = view("SELECT id FROM orders").await;
+
+        let refresh = view.begin_refresh(true).await;
+        assert!(view.contents.try_read().is_ok());
+        refresh.finish(view.contents_from_rows(vec![ViewRow::new()])).await;
+        assert_eq!(view.rows().await.len(), 1);
+
+        let refresh = view.begin_refresh(false).await;
+        assert!(view.contents.try_read().is_err());
+        drop(refresh);
+        assert_eq!(view.rows().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_staleness_cleared_by_refresh() {
+        let view = view("SELECT user_id, MAX(total) FROM orders GROUP BY user_id").await;
+        view.mark_stale(2);
+        view.mark_stale(1);
+        let status = view.status().await;
+        assert!(status.is_stale());
+        assert_eq!(status.pending_changes, 3);
+
+        view.begin_refresh(true).await.finish(ViewContents::default()).await;
+        let status = view.status().await;
+        assert!(!status.is_stale());
+        assert_eq!(status.pending_changes, 0);
+    }
+}
@@ -0,0 +1,177 @@
+//! Query Memory Arbitrator
+//!
+//! Shares one memory budget among the memory-hungry operators (sorts) of
+//! all executing queries, in place of a fixed `work_mem` each. Every
+//! operator states its demand, the memory it needs to finish without
+//! spilling, and is granted a max-min fair share of the budget: operators
+//! that need little get all of it, and the rest split what remains evenly,
+//! each capped at `max_grant_bytes`. A lone query can therefore sort far
+//! more in memory than `work_mem` while the system is quiet, and under
+//! contention every operator is bounded and the grants never add up to
+//! more than the budget.
+//!
+//! Grants are recomputed whenever an operator registers, raises its demand
+//! or finishes. An operator that has reported nothing for `idle_after_ms`
+//! keeps only the memory it said it was using, and the rest goes to the
+//! others. Operators read their limit when they decide whether to spill, so
+//! a grant that shrinks meanwhile takes effect at the next decision.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use crate::config::MemoryArbitrationConfig;
+
+/// One operator's claim on the budget
+#[derive(Debug)]
+struct Claim {
+    label: String,
+    /// Bytes it needs to finish without spilling
+    demand: u64,
+    /// Bytes it last said it was holding
+    used: u64,
+    /// Bytes it may use now
+    grant: u64,
+    active_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ArbitratorState {
+    claims: HashMap<u64, Claim>,
+    next_id: u64,
+}
+
+/// What the arbitrator has handed out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitratorStats {
+    pub budget_bytes: u64,
+    /// Operators holding a grant
+    pub operators: usize,
+    pub granted_bytes: u64,
+    pub demanded_bytes: u64,
+    /// Largest single grant
+    pub largest_grant_bytes: u64,
+}
+
+/// Divides the memory budget among executing operators
+#[derive(Debug)]
+pub struct MemoryArbitrator {
+    budget: u64,
+    max_grant: u64,
+    idle_after: Duration,
+    state: Mutex<ArbitratorState>,
+}
+
+impl MemoryArbitrator {
+    pub fn new(config: &MemoryArbitrationConfig) -> Self {
+        Self {
+            budget: config.total_budget_bytes as u64,
+            max_grant: config.max_grant_bytes as u64,
+            idle_after: Duration::from_millis(config.idle_after_ms),
+            state: Mutex::new(ArbitratorState::default()),
+        }
+    }
+
+    /// Register an operator needing `demand` bytes; its grant is available
+    /// straight away and given back when the returned handle is dropped
+    pub fn register(self: &Arc<Self>, label: &str, demand: u64) -> MemoryGrant {
+        let mut state = self.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.claims.insert(id, Claim {
+            label: label.to_string(),
+            demand,
+            used: 0,
+            grant: 0,
+            active_at: Instant::now(),
+        });
+        self.rebalance_locked(&mut state);
+        MemoryGrant { arbitrator: self.clone(), id }
+    }
+
+    /// Recompute every grant, reclaiming memory from operators that went idle
+    pub fn rebalance(&self) {
+        self.rebalance_locked(&mut self.state.lock());
+    }
+
+    pub fn stats(&self) -> ArbitratorStats {
+        let state = self.state.lock();
+        ArbitratorStats {
+            budget_bytes: self.budget,
+            operators: state.claims.len(),
+            granted_bytes: state.claims.values().map(|claim| claim.grant).sum(),
+            demanded_bytes: state.claims.values().map(|claim| claim.demand).sum(),
+            largest_grant_bytes: state.claims.values().map(|claim| claim.grant).max().unwrap_or(0),
+        }
+    }
+
+    /// Max-min fair division of the budget: operators are served from the
+    /// smallest want up, each getting its want or an even split of what is
+    /// left, whichever is smaller
+    fn rebalance_locked(&self, state: &mut ArbitratorState) {
+        let now = Instant::now();
+        let mut wants: Vec<(u64, u64)> = state.claims.iter()
+            .map(|(&id, claim)| {
+                let idle = now.duration_since(claim.active_at) >= self.idle_after;
+                let want = if idle { claim.used.min(claim.demand) } else { claim.demand };
+                (id, want.min(self.max_grant))
+            })
+            .collect();
+        wants.sort_by_key(|&(id, want)| (want, id));
+
+        let mut remaining = self.budget;
+        let count = wants.len() as u64;
+        for (served, (id, want)) in wants.into_iter().enumerate() {
+            let grant = want.min(remaining / (count - served as u64));
+            remaining -= grant;
+            if let Some(claim) = state.claims.get_mut(&id) {
+                if claim.grant != grant {
+                    log::debug!("Memory grant of {} ({}): {} -> {} bytes", claim.label, id, claim.grant, grant);
+                }
+                claim.grant = grant;
+            }
+        }
+    }
+}
+
+/// An operator's share of the budget, given back when dropped
+#[derive(Debug)]
+pub struct MemoryGrant {
+    arbitrator: Arc<MemoryArbitrator>,
+    id: u64,
+}
+
+impl MemoryGrant {
+    /// Bytes the operator may use now
+    pub fn limit(&self) -> usize {
+        self.arbitrator.state.lock().claims.get(&self.id).map_or(0, |claim| claim.grant as usize)
+    }
+
+    /// The operator needs `demand` bytes to avoid spilling; returns its
+    /// limit once the budget has been divided again
+    pub fn request(&self, demand: u64) -> usize {
+        let mut state = self.arbitrator.state.lock();
+        if let Some(claim) = state.claims.get_mut(&self.id) {
+            claim.demand = demand;
+            claim.active_at = Instant::now();
+        }
+        self.arbitrator.rebalance_locked(&mut state);
+        state.claims.get(&self.id).map_or(0, |claim| claim.grant as usize)
+    }
+
+    /// The operator is holding `used` bytes; keeps it from counting as idle
+    pub fn report_usage(&self, used: u64) {
+        if let Some(claim) = self.arbitrator.state.lock().claims.get_mut(&self.id) {
+            claim.used = used;
+            claim.active_at = Instant::now();
+        }
+    }
+}
+
+impl Drop for MemoryGrant {
+    fn drop(&mut self) {
+        let mut state = self.arbitrator.state.lock();
+        state.claims.remove(&self.id);
+        self.arbitrator.rebalance_locked(&mut state);
+    }
+}
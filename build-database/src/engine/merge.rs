@@ -0,0 +1,503 @@
+//! MERGE Planning
+//!
+//! Works out everything a MERGE will do before any row is written. Each
+//! source row is joined to the target rows its ON condition holds for; the
+//! first WHEN clause of the right kind whose condition also holds chooses the
+//! action. The engine applies the resulting steps in one transaction, so a
+//! MERGE that fails part way leaves the target as it was.
+//!
+//! A target row matched by several source rows would be updated or deleted
+//! more than once, in an order that depends on the scan. That is an error
+//! by default, as in PostgreSQL; `MergeMultipleMatches::FirstMatch` keeps
+//! the change from the first source row to reach it and skips the others.
+//!
+//! An expression that fails on some row names itself, the operands it failed
+//! on and the plan operator evaluating it: the join for the ON condition,
+//! the Merge node for WHEN conditions and the values written. Plan nodes are
+//! numbered root first: the Merge node is 0 and its join 1.
+//!
+//! Integer arithmetic that overflows, and float arithmetic that leaves the
+//! finite range, follow the session's [`NumericPolicy`].
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use crate::core::{AuroraError, AuroraResult, ErrorCode};
+use crate::query::parser::ast::{BinaryOp, BinaryOperator, Expression, Literal, MergeAction, MergeQuery};
+use crate::types::DataValue;
+use super::materialized_view::ViewRow;
+use super::numeric_policy::NumericPolicy;
+
+/// What a MERGE does when a second source row would change a target row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMultipleMatches {
+    /// Fail the statement (SQLSTATE 21000)
+    #[default]
+    Error,
+    /// Keep the first change and ignore later ones
+    FirstMatch,
+}
+
+impl MergeMultipleMatches {
+    /// Parse a setting value: `error` or `first_match`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "first_match" => Some(Self::FirstMatch),
+            _ => None,
+        }
+    }
+}
+
+/// One change a MERGE makes to its target
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeStep {
+    /// Set columns of the target row at index `target`
+    Update { target: usize, assignments: Vec<(String, DataValue)> },
+    /// Delete the target row at index `target`
+    Delete { target: usize },
+    /// Insert a row; no columns means every column in table order
+    Insert { columns: Vec<String>, values: Vec<DataValue> },
+}
+
+/// Position of the Merge node in a MERGE's plan
+const MERGE_NODE: usize = 0;
+/// Position of the join of source to target rows
+const JOIN_NODE: usize = 1;
+
+/// Plan a MERGE of `source_rows` into `target_rows`, in source order
+pub fn plan_merge(
+    query: &MergeQuery,
+    target_rows: &[ViewRow],
+    source_rows: &[ViewRow],
+    multiple_matches: MergeMultipleMatches,
+    numeric_policy: NumericPolicy,
+) -> AuroraResult<Vec<MergeStep>> {
+    plan_steps(query, target_rows, source_rows, multiple_matches, numeric_policy)
+        .map_err(|error| error.in_operator(format!("Merge on {}", query.target), MERGE_NODE))
+}
+
+fn plan_steps(
+    query: &MergeQuery,
+    target_rows: &[ViewRow],
+    source_rows: &[ViewRow],
+    multiple_matches: MergeMultipleMatches,
+    numeric_policy: NumericPolicy,
+) -> AuroraResult<Vec<MergeStep>> {
+    let names = Names {
+        target: query.target_alias.as_deref().unwrap_or(&query.target),
+        source: query.source_alias.as_deref().unwrap_or(&query.source),
+    };
+    let mut steps = Vec::new();
+    let mut changed = HashSet::new();
+
+    for source in source_rows {
+        let mut matched = false;
+        for (index, target) in target_rows.iter().enumerate() {
+            let row = Row { names: &names, target: Some(target), source, numeric_policy };
+            let joined = row.holds(&query.condition)
+                .map_err(|error| error.in_operator("Nested Loop Join", JOIN_NODE))?;
+            if !joined {
+                continue;
+            }
+            matched = true;
+            let Some(action) = row.choose(query, true)? else {
+                continue;
+            };
+            if matches!(action, MergeAction::DoNothing) {
+                continue;
+            }
+            if !changed.insert(index) {
+                match multiple_matches {
+                    MergeMultipleMatches::FirstMatch => continue,
+                    MergeMultipleMatches::Error => return Err(AuroraError::new(
+                        ErrorCode::ValidationConstraintViolation,
+                        format!("MERGE command cannot affect row a second time: more than one row of '{}' matches a row of '{}'", query.source, query.target)
+                    ).with_context("sqlstate", "21000")),
+                }
+            }
+            steps.push(match action {
+                MergeAction::Update(assignments) => MergeStep::Update {
+                    target: index,
+                    assignments: assignments.iter()
+                        .map(|assignment| Ok((assignment.column.clone(), row.evaluate(&assignment.value)?)))
+                        .collect::<AuroraResult<_>>()?,
+                },
+                _ => MergeStep::Delete { target: index },
+            });
+        }
+
+        if matched {
+            continue;
+        }
+        let row = Row { names: &names, target: None, source, numeric_policy };
+        if let Some(MergeAction::Insert { columns, values }) = row.choose(query, false)? {
+            steps.push(MergeStep::Insert {
+                columns: columns.clone(),
+                values: values.iter().map(|value| row.evaluate(value)).collect::<AuroraResult<_>>()?,
+            });
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Names the target and source are referred to by
+struct Names<'a> {
+    target: &'a str,
+    source: &'a str,
+}
+
+/// A source row, joined to a target row when matched
+struct Row<'a> {
+    names: &'a Names<'a>,
+    target: Option<&'a ViewRow>,
+    source: &'a ViewRow,
+    numeric_policy: NumericPolicy,
+}
+
+impl Row<'_> {
+    /// Action of the first WHEN clause for matched or unmatched rows whose
+    /// condition holds
+    fn choose<'q>(&self, query: &'q MergeQuery, matched: bool) -> AuroraResult<Option<&'q MergeAction>> {
+        for clause in query.clauses.iter().filter(|clause| clause.matched == matched) {
+            let applies = match &clause.condition {
+                Some(condition) => self.holds(condition)?,
+                None => true,
+            };
+            if applies {
+                return Ok(Some(&clause.action));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether a condition is true; NULL counts as false
+    fn holds(&self, condition: &Expression) -> AuroraResult<bool> {
+        Ok(matches!(self.evaluate(condition)?, DataValue::Boolean(true)))
+    }
+
+    fn evaluate(&self, expr: &Expression) -> AuroraResult<DataValue> {
+        match expr {
+            Expression::Literal(literal) => Ok(match literal {
+                Literal::Integer(i) => DataValue::Integer(*i),
+                Literal::Float(f) => DataValue::Real(*f),
+                Literal::String(s) => DataValue::Text(s.clone()),
+                Literal::Boolean(b) => DataValue::Boolean(*b),
+                Literal::Null => DataValue::Null,
+            }),
+            Expression::Column(name) => self.column(name),
+            Expression::BinaryOp(BinaryOp { left: left_expr, operator, right: right_expr }) => {
+                let left = self.evaluate(left_expr)?;
+                let right = self.evaluate(right_expr)?;
+                binary(&left, operator, &right, self.numeric_policy).map_err(|error| {
+                    let operands = format!(
+                        "{} = {}, {} = {}",
+                        sql(left_expr), shown_value(&left), sql(right_expr), shown_value(&right)
+                    );
+                    error.in_expression(sql(expr), Some(operands))
+                })
+            }
+            other => Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("{:?} is not supported in MERGE", other)
+            )),
+        }
+    }
+
+    /// Value of `column` or `table.column`; an unqualified name must belong
+    /// to only one of the two tables
+    fn column(&self, name: &str) -> AuroraResult<DataValue> {
+        let unknown = || AuroraError::new(
+            ErrorCode::QueryInvalidParameters,
+            format!("column \"{}\" does not exist in MERGE", name)
+        );
+        let found = match name.split_once('.') {
+            Some((table, column)) if table == self.names.target => match self.target {
+                Some(target) => target.get(column),
+                None => return Err(AuroraError::new(
+                    ErrorCode::QueryInvalidParameters,
+                    format!("WHEN NOT MATCHED cannot refer to target column \"{}\"", name)
+                )),
+            },
+            Some((table, column)) if table == self.names.source => self.source.get(column),
+            Some(_) => None,
+            None => match (self.target.and_then(|target| target.get(name)), self.source.get(name)) {
+                (Some(_), Some(_)) => return Err(AuroraError::new(
+                    ErrorCode::QueryInvalidParameters,
+                    format!("column reference \"{}\" is ambiguous", name)
+                )),
+                (target, source) => target.or(source),
+            },
+        };
+        found.cloned().ok_or_else(unknown)
+    }
+}
+
+/// An expression as SQL, for error messages
+fn sql(expr: &Expression) -> String {
+    match expr {
+        Expression::Literal(Literal::Integer(i)) => i.to_string(),
+        Expression::Literal(Literal::Float(f)) => f.to_string(),
+        Expression::Literal(Literal::String(s)) => format!("'{}'", s.replace('\'', "''")),
+        Expression::Literal(Literal::Boolean(b)) => b.to_string().to_uppercase(),
+        Expression::Literal(Literal::Null) => "NULL".to_string(),
+        Expression::Column(name) => name.clone(),
+        Expression::BinaryOp(BinaryOp { left, operator, right }) => {
+            let symbol = match operator {
+                BinaryOperator::Equal => "=",
+                BinaryOperator::NotEqual => "<>",
+                BinaryOperator::LessThan => "<",
+                BinaryOperator::GreaterThan => ">",
+                BinaryOperator::LessEqual => "<=",
+                BinaryOperator::GreaterEqual => ">=",
+                BinaryOperator::And => "AND",
+                BinaryOperator::Or => "OR",
+                BinaryOperator::Plus => "+",
+                BinaryOperator::Minus => "-",
+                BinaryOperator::Multiply => "*",
+                BinaryOperator::Divide => "/",
+                BinaryOperator::TextMatch => "@@",
+                BinaryOperator::ArrayContains => "@>",
+                BinaryOperator::ArrayOverlaps => "&&",
+            };
+            let operand = |expr: &Expression| match expr {
+                Expression::BinaryOp(_) => format!("({})", sql(expr)),
+                _ => sql(expr),
+            };
+            format!("{} {} {}", operand(left), symbol, operand(right))
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// A value as it may appear in an error message: numbers and booleans as
+/// they are, text withheld since it may be anything a user stored
+fn shown_value(value: &DataValue) -> String {
+    match value {
+        DataValue::Null => "NULL".to_string(),
+        DataValue::Integer(i) => i.to_string(),
+        DataValue::Real(r) => r.to_string(),
+        DataValue::Decimal(d) => d.to_string(),
+        DataValue::Boolean(b) => b.to_string(),
+        _ => "(not shown)".to_string(),
+    }
+}
+
+/// `left operator right` with SQL NULL semantics
+fn binary(left: &DataValue, operator: &BinaryOperator, right: &DataValue, numeric_policy: NumericPolicy) -> AuroraResult<DataValue> {
+    let truth = |value: &DataValue| match value {
+        DataValue::Boolean(b) => Ok(Some(*b)),
+        DataValue::Null => Ok(None),
+        other => Err(AuroraError::new(
+            ErrorCode::ValidationTypeMismatch,
+            format!("argument of AND/OR must be boolean, got {:?}", other)
+        )),
+    };
+    let logical = |value: Option<bool>| value.map_or(DataValue::Null, DataValue::Boolean);
+    match operator {
+        BinaryOperator::And => return Ok(logical(match (truth(left)?, truth(right)?) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        })),
+        BinaryOperator::Or => return Ok(logical(match (truth(left)?, truth(right)?) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        })),
+        _ => {}
+    }
+    if matches!(left, DataValue::Null) || matches!(right, DataValue::Null) {
+        return Ok(DataValue::Null);
+    }
+
+    let mismatch = || AuroraError::new(
+        ErrorCode::ValidationTypeMismatch,
+        format!("cannot apply {:?} to {:?} and {:?}", operator, left, right)
+    );
+    let ordering = || compare(left, right).ok_or_else(mismatch);
+    Ok(match operator {
+        BinaryOperator::Equal => DataValue::Boolean(ordering()?.is_eq()),
+        BinaryOperator::NotEqual => DataValue::Boolean(ordering()?.is_ne()),
+        BinaryOperator::LessThan => DataValue::Boolean(ordering()?.is_lt()),
+        BinaryOperator::LessEqual => DataValue::Boolean(ordering()?.is_le()),
+        BinaryOperator::GreaterThan => DataValue::Boolean(ordering()?.is_gt()),
+        BinaryOperator::GreaterEqual => DataValue::Boolean(ordering()?.is_ge()),
+        BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply | BinaryOperator::Divide => {
+            arithmetic(left, operator, right, numeric_policy)?.ok_or_else(mismatch)?
+        }
+        _ => return Err(mismatch()),
+    })
+}
+
+/// Integer arithmetic is exact, with a result beyond 64 bits handled by
+/// `numeric_policy`; anything involving a float is done in f64, where a
+/// NaN or infinity from finite operands is
+fn arithmetic(left: &DataValue, operator: &BinaryOperator, right: &DataValue, numeric_policy: NumericPolicy) -> AuroraResult<Option<DataValue>> {
+    if let (DataValue::Integer(a), DataValue::Integer(b)) = (left, right) {
+        if matches!(operator, BinaryOperator::Divide) && *b == 0 {
+            return Err(AuroraError::new(ErrorCode::ValidationDivisionByZero, "division by zero"));
+        }
+        let (a, b) = (*a as i128, *b as i128);
+        return numeric_policy.integer(match operator {
+            BinaryOperator::Plus => a + b,
+            BinaryOperator::Minus => a - b,
+            BinaryOperator::Multiply => a * b,
+            _ => a / b,
+        }).map(Some);
+    }
+    let (Some(a), Some(b)) = (float(left), float(right)) else {
+        return Ok(None);
+    };
+    let result = match operator {
+        BinaryOperator::Plus => a + b,
+        BinaryOperator::Minus => a - b,
+        BinaryOperator::Multiply => a * b,
+        _ => a / b,
+    };
+    if !(a.is_finite() && b.is_finite()) {
+        return Ok(Some(DataValue::Real(result)));
+    }
+    numeric_policy.float(result).map(Some)
+}
+
+fn float(value: &DataValue) -> Option<f64> {
+    match value {
+        DataValue::Integer(i) => Some(*i as f64),
+        DataValue::Real(r) => Some(*r),
+        DataValue::Decimal(d) => d.to_f64().ok(),
+        _ => None,
+    }
+}
+
+/// Order two non-NULL values; `None` if they cannot be compared
+fn compare(left: &DataValue, right: &DataValue) -> Option<Ordering> {
+    match (left, right) {
+        (DataValue::Integer(a), DataValue::Integer(b)) => Some(a.cmp(b)),
+        (DataValue::Text(a) | DataValue::String(a), DataValue::Text(b) | DataValue::String(b)) => Some(a.cmp(b)),
+        (DataValue::Boolean(a), DataValue::Boolean(b)) => Some(a.cmp(b)),
+        _ => float(left)?.partial_cmp(&float(right)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::errors::OperatorSpan;
+    use crate::query::parser::ast::{Assignment, MergeClause};
+
+    fn row(values: &[(&str, DataValue)]) -> ViewRow {
+        values.iter().map(|(column, value)| (column.to_string(), value.clone())).collect()
+    }
+
+    fn column(name: &str) -> Expression {
+        Expression::Column(name.to_string())
+    }
+
+    fn op(left: Expression, operator: BinaryOperator, right: Expression) -> Expression {
+        Expression::BinaryOp(BinaryOp { left: Box::new(left), operator, right: Box::new(right) })
+    }
+
+    /// `MERGE INTO stock t USING delivery s ON t.id = s.id
+    ///  WHEN MATCHED THEN UPDATE SET qty = t.qty + s.qty
+    ///  WHEN NOT MATCHED THEN INSERT VALUES (s.id, s.qty)`
+    fn upsert() -> MergeQuery {
+        MergeQuery {
+            target: "stock".to_string(),
+            target_alias: Some("t".to_string()),
+            source: "delivery".to_string(),
+            source_alias: Some("s".to_string()),
+            condition: op(column("t.id"), BinaryOperator::Equal, column("s.id")),
+            clauses: vec![
+                MergeClause {
+                    matched: true,
+                    condition: None,
+                    action: MergeAction::Update(vec![Assignment {
+                        column: "qty".to_string(),
+                        value: op(column("t.qty"), BinaryOperator::Plus, column("s.qty")),
+                    }]),
+                },
+                MergeClause {
+                    matched: false,
+                    condition: None,
+                    action: MergeAction::Insert { columns: Vec::new(), values: vec![column("s.id"), column("s.qty")] },
+                },
+            ],
+        }
+    }
+
+    fn stock(id: i64, qty: i64) -> ViewRow {
+        row(&[("id", DataValue::Integer(id)), ("qty", DataValue::Integer(qty))])
+    }
+
+    #[test]
+    fn test_matched_rows_update_and_others_insert() {
+        let steps = plan_merge(&upsert(), &[stock(1, 10), stock(2, 20)], &[stock(2, 5), stock(3, 7)], MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap();
+        assert_eq!(steps, vec![
+            MergeStep::Update { target: 1, assignments: vec![("qty".to_string(), DataValue::Integer(25))] },
+            MergeStep::Insert { columns: Vec::new(), values: vec![DataValue::Integer(3), DataValue::Integer(7)] },
+        ]);
+    }
+
+    #[test]
+    fn test_second_change_to_a_row_follows_policy() {
+        let sources = [stock(1, 5), stock(1, 6)];
+        let error = plan_merge(&upsert(), &[stock(1, 10)], &sources, MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap_err();
+        assert!(error.to_string().contains("cannot affect row a second time"), "{}", error);
+
+        let steps = plan_merge(&upsert(), &[stock(1, 10)], &sources, MergeMultipleMatches::FirstMatch, NumericPolicy::Reject).unwrap();
+        assert_eq!(steps, vec![MergeStep::Update { target: 0, assignments: vec![("qty".to_string(), DataValue::Integer(15))] }]);
+        assert_eq!(MergeMultipleMatches::parse("FIRST_MATCH"), Some(MergeMultipleMatches::FirstMatch));
+    }
+
+    #[test]
+    fn test_null_join_keys_never_match() {
+        let target = [row(&[("id", DataValue::Null), ("qty", DataValue::Integer(1))])];
+        let source = [row(&[("id", DataValue::Null), ("qty", DataValue::Integer(2))])];
+        let steps = plan_merge(&upsert(), &target, &source, MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap();
+        assert_eq!(steps, vec![MergeStep::Insert { columns: Vec::new(), values: vec![DataValue::Null, DataValue::Integer(2)] }]);
+    }
+
+    /// `upsert()` with the matched rows' quantity set to `value`
+    fn update_qty(value: Expression) -> MergeQuery {
+        let mut query = upsert();
+        query.clauses[0].action = MergeAction::Update(vec![Assignment { column: "qty".to_string(), value }]);
+        query
+    }
+
+    #[test]
+    fn test_division_by_zero_names_operator_and_expression() {
+        let query = update_qty(op(column("t.qty"), BinaryOperator::Divide, column("s.qty")));
+        let error = plan_merge(&query, &[stock(1, 10)], &[stock(1, 0)], MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ValidationDivisionByZero);
+        assert_eq!(error.failed_expression.as_deref(), Some("t.qty / s.qty"));
+        assert_eq!(error.offending_value.as_deref(), Some("t.qty = 10, s.qty = 0"));
+        assert_eq!(error.operator_spans, vec![OperatorSpan { operator: "Merge on stock".to_string(), plan_node: 0 }]);
+        let message = error.to_string();
+        assert!(message.contains("in expression `t.qty / s.qty` with t.qty = 10, s.qty = 0 at Merge on stock (plan node 0)"), "{}", message);
+    }
+
+    #[test]
+    fn test_overflow_in_join_condition_names_the_join() {
+        // The innermost expression is reported, and the join before the Merge node
+        let mut query = upsert();
+        query.condition = op(
+            op(column("t.qty"), BinaryOperator::Multiply, column("s.qty")),
+            BinaryOperator::Equal,
+            column("s.id"),
+        );
+        let error = plan_merge(&query, &[stock(1, i64::MAX)], &[stock(1, 2)], MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ValidationNumericOverflow);
+        assert_eq!(error.failed_expression.as_deref(), Some("t.qty * s.qty"));
+        assert_eq!(error.offending_value, Some(format!("t.qty = {}, s.qty = 2", i64::MAX)));
+        let operators: Vec<_> = error.operator_spans.iter().map(|span| (span.operator.as_str(), span.plan_node)).collect();
+        assert_eq!(operators, vec![("Nested Loop Join", 1), ("Merge on stock", 0)]);
+        assert_eq!(error.failing_operator().map(|span| span.plan_node), Some(1));
+
+        // Text operands are never echoed back
+        let text = row(&[("id", DataValue::Integer(1)), ("qty", DataValue::Text("secret".to_string()))]);
+        let query = update_qty(op(column("t.qty"), BinaryOperator::Divide, column("s.qty")));
+        let error = plan_merge(&query, &[stock(1, 10)], &[text], MergeMultipleMatches::Error, NumericPolicy::Reject).unwrap_err();
+        assert_eq!(error.offending_value.as_deref(), Some("t.qty = 10, s.qty = (not shown)"));
+    }
+}
@@ -11,12 +11,96 @@
 //! - Production-grade error handling and logging
 
 pub mod aurora_db;
+pub mod asof_join;
+pub mod idempotency;
+pub mod fingerprint;
+pub mod sketch;
+pub mod materialized_view;
+pub mod statement_cache;
 pub mod query_pipeline;
+pub mod query_progress;
+pub mod query_profiler;
+pub mod external_sort;
+pub mod top_n;
+pub mod merge;
+pub mod numeric_policy;
+pub mod ttl_reaper;
+pub mod bloat;
+pub mod foreign_table;
+pub mod tenant_governor;
+pub mod memory_arbitrator;
+pub mod materialization;
+pub mod spill;
 pub mod server;
 
 // Re-export the main database engine
 pub use aurora_db::*;
 
+// Re-export ASOF join execution
+pub use asof_join::{AsofJoin, AsofOperator};
+
+// Re-export idempotency support
+pub use idempotency::{IdempotencyStore, IdempotencyClaim, DEFAULT_IDEMPOTENCY_TTL};
+
+// Re-export result fingerprinting
+pub use fingerprint::{ResultFingerprint, CanonicalHasher};
+
+// Re-export approximate aggregate sketches
+pub use sketch::{HyperLogLog, TDigest};
+
+// Re-export materialized view status
+pub use materialized_view::{MaterializedViewStatus, ViewMaintenance};
+
+// Re-export plan and result caches
+pub use statement_cache::{CachedResult, DependentCache, PlanCache, ResultCache};
+
+// Re-export query progress reporting
+pub use query_progress::{
+    OperatorKind, OperatorProgress, ProgressTicker, QueryProgress, QueryProgressRegistry,
+    QueryProgressSnapshot, OperatorProgressSnapshot, QUERY_PROGRESS_VIEW,
+};
+
+// Re-export the sampling execution profiler
+pub use query_profiler::{QueryProfile, QueryProfiler, DEFAULT_SAMPLE_RATE_HZ, MAX_SAMPLE_RATE_HZ};
+
+// Re-export spilling sort
+pub use external_sort::ExternalSort;
+
+// Re-export bounded ORDER BY ... LIMIT sorting
+pub use top_n::{top_n_by, TopN};
+
+// Re-export MERGE planning
+pub use merge::{plan_merge, MergeMultipleMatches, MergeStep};
+
+// Re-export numeric NaN/infinity/overflow handling
+pub use numeric_policy::NumericPolicy;
+
+// Re-export expired row reaping
+pub use ttl_reaper::TtlReapReport;
+
+// Re-export bloat estimation
+pub use bloat::{BloatRecommendation, BloatReport, IndexBloat, MaintenanceAction, TableBloat, BLOAT_VIEW};
+
+// Re-export foreign data wrappers
+pub use foreign_table::{
+    CsvForeignTable, FilterOp, ForeignDataWrapper, ForeignScan, ForeignScanEstimate, RemoteCursor,
+    RowStream, ScanFilter, REMOTE_CANCEL_TIMEOUT,
+};
+
+// Re-export per-tenant quotas
+pub use tenant_governor::{
+    ConnectionPermit, QueryPermit, QuotaError, QuotaResource, TenantGovernor, TenantQuota, TenantUsage,
+};
+
+// Re-export memory arbitration
+pub use memory_arbitrator::{ArbitratorStats, MemoryArbitrator, MemoryGrant};
+
+// Re-export CTE and temporary table materialization
+pub use materialization::{CteMaterializations, MaterializedRows, Materializer};
+
+// Re-export spill storage backends
+pub use spill::{DirectorySpill, ObjectStore, ObjectStoreSpill, SpillBackend, SpillBackendKind, SpillFile, SpillWriter};
+
 // Re-export query pipeline
 pub use query_pipeline::*;
 
@@ -28,4 +112,5 @@ pub use aurora_db::{
     AuroraDB, UserContext, QueryResult, VectorSearchRequest, VectorSearchResult,
     AnalyticsQuery, AnalyticsResult, IsolationLevel, TableSchema, ColumnDefinition,
     DataType, IndexDefinition, IndexType, HealthStatus, HealthState, DatabaseMetrics,
+    InsertSelectStats,
 };
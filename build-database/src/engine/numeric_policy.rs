@@ -0,0 +1,172 @@
+//! NaN, Infinity and Overflow Handling
+//!
+//! Sources disagree on what a NaN, an infinity or an out-of-range number
+//! means, so what happens to one is a policy, chosen per session and
+//! overridable per column:
+//! - **Reject** (default): fail the statement, so nothing is silently changed
+//! - **Null**: store or yield NULL in its place
+//! - **Saturate**: clamp to the nearest value the type can hold; NaN has
+//!   none and is rejected
+//! - **Allow**: keep it. Values are stored as given, NaN and infinities as
+//!   their text forms `NaN`, `Infinity` and `-Infinity`; integer arithmetic
+//!   that overflows wraps around as two's complement
+//!
+//! A column's policy applies to the values written to it; the session's to
+//! the arithmetic of its statements and to columns without one.
+
+use serde_json::Value;
+use crate::core::{AuroraError, AuroraResult, ErrorCode};
+use crate::types::{DataType, DataValue};
+
+/// What happens to a NaN, infinity or out-of-range number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericPolicy {
+    /// Fail the statement
+    #[default]
+    Reject,
+    /// Use NULL instead
+    Null,
+    /// Clamp to the type's range
+    Saturate,
+    /// Keep the value
+    Allow,
+}
+
+impl NumericPolicy {
+    /// Parse a setting value: `reject`, `null`, `saturate` or `allow`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "reject" => Some(Self::Reject),
+            "null" => Some(Self::Null),
+            "saturate" => Some(Self::Saturate),
+            "allow" => Some(Self::Allow),
+            _ => None,
+        }
+    }
+
+    /// A value about to be written to `column`, of type `data_type`, in the
+    /// JSON form column validation takes. Values of other types, and
+    /// numbers in range, pass through.
+    pub fn ingest(self, column: &str, data_type: &DataType, value: Value) -> AuroraResult<Value> {
+        match data_type {
+            DataType::Integer => self.ingest_integer(column, value, i32::MIN as i64, i32::MAX as i64),
+            DataType::BigInt => self.ingest_integer(column, value, i64::MIN, i64::MAX),
+            DataType::Float => self.ingest_float(column, value, f32::MAX as f64),
+            DataType::Double => self.ingest_float(column, value, f64::MAX),
+            _ => Ok(value),
+        }
+    }
+
+    /// Exact result of integer arithmetic, brought into a 64-bit integer
+    pub fn integer(self, exact: i128) -> AuroraResult<DataValue> {
+        if let Ok(value) = i64::try_from(exact) {
+            return Ok(DataValue::Integer(value));
+        }
+        Ok(match self {
+            Self::Reject => return Err(AuroraError::new(ErrorCode::ValidationNumericOverflow, "integer out of range")),
+            Self::Null => DataValue::Null,
+            Self::Saturate => DataValue::Integer(if exact < 0 { i64::MIN } else { i64::MAX }),
+            Self::Allow => DataValue::Integer(exact as i64),
+        })
+    }
+
+    /// Result of float arithmetic on finite operands
+    pub fn float(self, result: f64) -> AuroraResult<DataValue> {
+        if result.is_finite() {
+            return Ok(DataValue::Real(result));
+        }
+        Ok(match self.non_finite("result", result, f64::MAX)? {
+            Some(value) => DataValue::Real(value),
+            None => DataValue::Null,
+        })
+    }
+
+    fn ingest_integer(self, column: &str, value: Value, min: i64, max: i64) -> AuroraResult<Value> {
+        let Value::Number(number) = &value else {
+            return Ok(value);
+        };
+        let exact = match (number.as_i64(), number.as_u64()) {
+            (Some(i), _) => i as i128,
+            (None, Some(u)) => u as i128,
+            // A float validation refuses anyway
+            (None, None) => return Ok(value),
+        };
+        if (min as i128..=max as i128).contains(&exact) {
+            return Ok(value);
+        }
+        Ok(match self {
+            Self::Reject => return Err(AuroraError::new(
+                ErrorCode::ValidationNumericOverflow,
+                format!("value {} is out of range for column '{}'", exact, column)
+            )),
+            Self::Null => Value::Null,
+            Self::Saturate => Value::from(if exact < 0 { min } else { max }),
+            Self::Allow => value,
+        })
+    }
+
+    fn ingest_float(self, column: &str, value: Value, max: f64) -> AuroraResult<Value> {
+        let float = match &value {
+            Value::Number(number) => match number.as_f64() {
+                Some(float) if float.abs() > max => float,
+                _ => return Ok(value),
+            },
+            Value::String(text) => match non_finite(text) {
+                Some(float) => float,
+                None => return Ok(value),
+            },
+            _ => return Ok(value),
+        };
+        if float.is_finite() && self == Self::Allow {
+            return Ok(value);
+        }
+        Ok(match self.non_finite(&format!("column '{}'", column), float, max)? {
+            Some(float) if float.is_finite() => Value::from(float),
+            Some(float) => Value::String(non_finite_text(float).to_string()),
+            None => Value::Null,
+        })
+    }
+
+    /// `value`, NaN, infinite or beyond ±`max`, under the policy; `None` for NULL
+    fn non_finite(self, target: &str, value: f64, max: f64) -> AuroraResult<Option<f64>> {
+        match self {
+            Self::Reject | Self::Saturate if value.is_nan() => Err(AuroraError::new(
+                ErrorCode::ValidationInvalidFormat,
+                format!("NaN is not allowed in {}", target)
+            )),
+            Self::Reject => Err(AuroraError::new(
+                ErrorCode::ValidationNumericOverflow,
+                format!("value {} is out of range for {}", value, target)
+            )),
+            Self::Null => Ok(None),
+            Self::Saturate => Ok(Some(max.copysign(value))),
+            Self::Allow => Ok(Some(value)),
+        }
+    }
+}
+
+/// The float a text form such as `NaN`, `Infinity` or `-inf` stands for
+pub fn non_finite(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (negative, magnitude) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let value = match magnitude.to_ascii_lowercase().as_str() {
+        "nan" if !negative => f64::NAN,
+        "inf" | "infinity" => f64::INFINITY,
+        _ => return None,
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// Stored text form of a NaN or infinity
+pub fn non_finite_text(value: f64) -> &'static str {
+    if value.is_nan() {
+        "NaN"
+    } else if value > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
@@ -0,0 +1,260 @@
+//! Query Execution Profiler
+//!
+//! A sampling profile of where one statement spends its time, attributed to
+//! plan nodes and the expressions evaluated under them. Operators push a
+//! frame onto the statement's profile stack when they start and pop it when
+//! they finish; a sampler thread wakes `sample_rate` times a second and
+//! counts the stack it finds. The result is in collapsed-stack form, one
+//! `SELECT;Project;fn:score 212` line per distinct stack, which flame graph
+//! tools such as flamegraph.pl, inferno and speedscope read directly.
+//!
+//! Frame labels are interned when an operator starts, so entering a frame
+//! on the hot path is two atomic stores and no locks. Only the sampler
+//! takes a lock, once per sample, so the overhead follows the sample rate.
+//! Statements that are not profiled carry no profiler and pay nothing.
+//!
+//! The stack is per statement, not per thread: samples count wall time
+//! spent under a frame, including time an operator waits on I/O.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+
+/// Samples per second when none is configured; prime, so sampling does not
+/// fall into step with periodic work
+pub const DEFAULT_SAMPLE_RATE_HZ: u32 = 997;
+
+/// Highest sample rate accepted
+pub const MAX_SAMPLE_RATE_HZ: u32 = 10_000;
+
+/// Deepest stack recorded; frames entered below it count toward their parent
+const MAX_DEPTH: usize = 32;
+
+/// Interned frame label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameId(u32);
+
+/// Profile stack and samples of one statement
+#[derive(Debug)]
+pub struct QueryProfiler {
+    sample_rate_hz: u32,
+    /// Interned labels, indexed by `FrameId`
+    labels: Mutex<Vec<String>>,
+    /// Frame ids of the current stack, root first; only `depth` are live
+    stack: [AtomicU32; MAX_DEPTH],
+    depth: AtomicUsize,
+    /// Sample counts by stack of frame ids
+    samples: Mutex<HashMap<Vec<u32>, u64>>,
+    total_samples: AtomicU64,
+}
+
+impl QueryProfiler {
+    /// Profiler whose stack starts with the `root` frame, usually the
+    /// statement kind
+    pub fn new(root: &str, sample_rate_hz: u32) -> Arc<Self> {
+        let profiler = Self {
+            sample_rate_hz: sample_rate_hz.clamp(1, MAX_SAMPLE_RATE_HZ),
+            labels: Mutex::new(vec![root.to_string()]),
+            stack: std::array::from_fn(|_| AtomicU32::new(0)),
+            depth: AtomicUsize::new(1),
+            samples: Mutex::new(HashMap::new()),
+            total_samples: AtomicU64::new(0),
+        };
+        Arc::new(profiler)
+    }
+
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz
+    }
+
+    /// Intern a frame label; call once per operator or expression, not per row
+    pub fn frame(&self, label: &str) -> FrameId {
+        let mut labels = self.labels.lock();
+        match labels.iter().position(|known| known == label) {
+            Some(index) => FrameId(index as u32),
+            None => {
+                labels.push(label.to_string());
+                FrameId(labels.len() as u32 - 1)
+            }
+        }
+    }
+
+    /// Push `frame` until the returned scope is dropped
+    pub fn enter(&self, frame: FrameId) -> ProfileScope<'_> {
+        let depth = self.depth.load(Ordering::Relaxed);
+        if depth < MAX_DEPTH {
+            self.stack[depth].store(frame.0, Ordering::Relaxed);
+        }
+        self.depth.store(depth + 1, Ordering::Release);
+        ProfileScope { profiler: self, depth }
+    }
+
+    /// Intern `label` and push it; for frames entered once per operator
+    pub fn enter_label(&self, label: &str) -> ProfileScope<'_> {
+        self.enter(self.frame(label))
+    }
+
+    /// Start sampling on a background thread until the sampler is dropped
+    pub fn start(self: &Arc<Self>) -> ProfileSampler {
+        let stop = Arc::new(AtomicBool::new(false));
+        let interval = Duration::from_secs_f64(1.0 / self.sample_rate_hz as f64);
+        let profiler = Arc::clone(self);
+        let stopped = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("aurora-profiler".to_string())
+            .spawn(move || loop {
+                // Unparked early when the sampler stops, so stopping never
+                // waits out a long interval
+                std::thread::park_timeout(interval);
+                if stopped.load(Ordering::Acquire) {
+                    break;
+                }
+                profiler.sample();
+            })
+            .ok();
+        ProfileSampler { stop, thread }
+    }
+
+    /// Record the current stack once
+    fn sample(&self) {
+        let depth = self.depth.load(Ordering::Acquire).min(MAX_DEPTH);
+        let stack: Vec<u32> = self.stack[..depth].iter()
+            .map(|frame| frame.load(Ordering::Relaxed))
+            .collect();
+        *self.samples.lock().entry(stack).or_insert(0) += 1;
+        self.total_samples.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Samples taken so far, in collapsed-stack form
+    pub fn profile(&self) -> QueryProfile {
+        let labels = self.labels.lock();
+        let mut stacks: Vec<(String, u64)> = self.samples.lock().iter()
+            .map(|(stack, count)| {
+                let frames: Vec<&str> = stack.iter().map(|frame| labels[*frame as usize].as_str()).collect();
+                (frames.join(";"), *count)
+            })
+            .collect();
+        stacks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        QueryProfile {
+            sample_rate_hz: self.sample_rate_hz,
+            total_samples: self.total_samples.load(Ordering::Relaxed),
+            stacks,
+        }
+    }
+}
+
+/// A frame on the profile stack; popped when dropped
+#[derive(Debug)]
+pub struct ProfileScope<'a> {
+    profiler: &'a QueryProfiler,
+    /// Stack depth the frame was pushed at
+    depth: usize,
+}
+
+impl Drop for ProfileScope<'_> {
+    fn drop(&mut self) {
+        self.profiler.depth.store(self.depth, Ordering::Release);
+    }
+}
+
+/// Sampler thread of a profiler; stops and joins when dropped
+#[derive(Debug)]
+pub struct ProfileSampler {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for ProfileSampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Finished profile of one statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryProfile {
+    pub sample_rate_hz: u32,
+    pub total_samples: u64,
+    /// Collapsed stacks and their sample counts, most samples first
+    pub stacks: Vec<(String, u64)>,
+}
+
+impl QueryProfile {
+    /// Collapsed-stack text, one `frame;frame;frame count` line per stack
+    pub fn collapsed(&self) -> String {
+        self.stacks.iter()
+            .map(|(stack, count)| format!("{} {}", stack, count))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Samples taken with `frame` anywhere on the stack
+    pub fn samples_under(&self, frame: &str) -> u64 {
+        self.stacks.iter()
+            .filter(|(stack, _)| stack.split(';').any(|label| label == frame))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Samples taken with `frame` on top of the stack
+    pub fn self_samples(&self, frame: &str) -> u64 {
+        self.stacks.iter()
+            .filter(|(stack, _)| stack.rsplit(';').next() == Some(frame))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_attribute_to_the_innermost_frame() {
+        let profiler = QueryProfiler::new("SELECT", 1000);
+        let project = profiler.frame("Project");
+        let function = profiler.frame("fn:score");
+
+        {
+            let _project = profiler.enter(project);
+            let _function = profiler.enter(function);
+            profiler.sample();
+            profiler.sample();
+        }
+        {
+            let _scan = profiler.enter_label("Seq Scan on readings");
+            profiler.sample();
+        }
+        profiler.sample();
+
+        let profile = profiler.profile();
+        assert_eq!(profile.total_samples, 4);
+        assert_eq!(profile.collapsed(), "SELECT;Project;fn:score 2\nSELECT 1\nSELECT;Seq Scan on readings 1");
+        assert_eq!(profile.samples_under("Project"), 2);
+        assert_eq!(profile.self_samples("SELECT"), 1);
+    }
+
+    #[test]
+    fn test_frames_past_the_depth_limit_count_toward_their_parent() {
+        let profiler = QueryProfiler::new("SELECT", 1000);
+        let frame = profiler.frame("Nested");
+        let scopes: Vec<_> = (0..MAX_DEPTH + 4).map(|_| profiler.enter(frame)).collect();
+        profiler.sample();
+        // Scopes end innermost first, as nested locals do
+        scopes.into_iter().rev().for_each(drop);
+        profiler.sample();
+
+        let profile = profiler.profile();
+        assert_eq!(profile.stacks.len(), 2);
+        assert_eq!(profile.self_samples("SELECT"), 1);
+        assert_eq!(profile.stacks.iter().map(|(stack, _)| stack.split(';').count()).max(), Some(MAX_DEPTH));
+    }
+}
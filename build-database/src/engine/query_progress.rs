@@ -0,0 +1,367 @@
+//! Query Progress Reporting
+//!
+//! Long-running operators (scans, sorts, hash builds, index builds) report how
+//! much of their input they have processed to a per-query entry in the
+//! progress registry, which backs the `aurora_query_progress` system view and
+//! the `GET /admin/progress` endpoint.
+//!
+//! Counters are atomics written with relaxed stores, and operators publish
+//! through a [`ProgressTicker`] that only touches them every
+//! `PUBLISH_INTERVAL_ROWS` rows, so the hot path takes no locks. Locks are
+//! only taken when a query or operator starts or finishes. Totals are fixed
+//! when an operator starts and counters only grow, so a reader polling the
+//! registry sees progress that never moves backwards.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use parking_lot::{Mutex, RwLock};
+use serde::{Serialize, Deserialize};
+
+/// Name of the system view over the registry
+pub const QUERY_PROGRESS_VIEW: &str = "aurora_query_progress";
+
+/// Rows an operator processes between publishing its counters
+pub const PUBLISH_INTERVAL_ROWS: u64 = 1024;
+
+/// Finished queries kept so a poller sees their final state
+const RECENT_QUERY_LIMIT: usize = 64;
+
+/// Highest completion a query reports before it finishes
+const RUNNING_FRACTION_LIMIT: f64 = 0.99;
+
+/// Kind of operator reporting progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperatorKind {
+    Scan,
+    Sort,
+    HashBuild,
+    IndexBuild,
+}
+
+impl OperatorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OperatorKind::Scan => "scan",
+            OperatorKind::Sort => "sort",
+            OperatorKind::HashBuild => "hash build",
+            OperatorKind::IndexBuild => "index build",
+        }
+    }
+}
+
+/// Progress counters of one operator
+#[derive(Debug)]
+pub struct OperatorProgress {
+    kind: OperatorKind,
+    label: String,
+    rows_total: Option<u64>,
+    bytes_total: Option<u64>,
+    rows_processed: AtomicU64,
+    bytes_processed: AtomicU64,
+    finished: AtomicBool,
+}
+
+impl OperatorProgress {
+    /// Add to the processed counters
+    pub fn advance(&self, rows: u64, bytes: u64) {
+        self.rows_processed.fetch_add(rows, Ordering::Relaxed);
+        self.bytes_processed.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Mark the operator complete
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+    }
+
+    /// Fraction of the input processed, if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        if self.finished.load(Ordering::Acquire) {
+            return Some(1.0);
+        }
+        let (done, total) = match (self.rows_total, self.bytes_total) {
+            (Some(total), _) => (self.rows_processed.load(Ordering::Relaxed), total),
+            (None, Some(total)) => (self.bytes_processed.load(Ordering::Relaxed), total),
+            (None, None) => return None,
+        };
+        if total == 0 {
+            return Some(1.0);
+        }
+        Some((done as f64 / total as f64).min(1.0))
+    }
+
+    fn snapshot(&self) -> OperatorProgressSnapshot {
+        OperatorProgressSnapshot {
+            kind: self.kind,
+            label: self.label.clone(),
+            rows_processed: self.rows_processed.load(Ordering::Relaxed),
+            rows_total: self.rows_total,
+            bytes_processed: self.bytes_processed.load(Ordering::Relaxed),
+            bytes_total: self.bytes_total,
+            fraction: self.fraction(),
+            finished: self.finished.load(Ordering::Acquire),
+        }
+    }
+}
+
+/// Batches an operator's counter updates
+///
+/// Counts locally and publishes every `PUBLISH_INTERVAL_ROWS` rows and on
+/// drop, so a per-row call is a couple of additions.
+pub struct ProgressTicker<'a> {
+    operator: Option<&'a OperatorProgress>,
+    rows: u64,
+    bytes: u64,
+}
+
+impl<'a> ProgressTicker<'a> {
+    pub fn new(operator: Option<&'a OperatorProgress>) -> Self {
+        Self { operator, rows: 0, bytes: 0 }
+    }
+
+    /// Count one processed row of `bytes` bytes
+    pub fn tick(&mut self, bytes: u64) {
+        self.rows += 1;
+        self.bytes += bytes;
+        if self.rows >= PUBLISH_INTERVAL_ROWS {
+            self.publish();
+        }
+    }
+
+    /// Publish whatever has been counted since the last publish
+    pub fn publish(&mut self) {
+        if let Some(operator) = self.operator {
+            if self.rows > 0 || self.bytes > 0 {
+                operator.advance(self.rows, self.bytes);
+            }
+        }
+        self.rows = 0;
+        self.bytes = 0;
+    }
+}
+
+impl Drop for ProgressTicker<'_> {
+    fn drop(&mut self) {
+        self.publish();
+    }
+}
+
+/// Progress of one query
+#[derive(Debug)]
+pub struct QueryProgress {
+    query_id: u64,
+    sql: String,
+    started: Instant,
+    finished_after: Mutex<Option<Duration>>,
+    operators: Mutex<Vec<Arc<OperatorProgress>>>,
+    /// Highest completion reported so far
+    reported: Mutex<f64>,
+}
+
+impl QueryProgress {
+    pub fn query_id(&self) -> u64 {
+        self.query_id
+    }
+
+    /// Start reporting an operator whose input size is `rows_total` rows
+    /// and/or `bytes_total` bytes, where known
+    pub fn start_operator(&self, kind: OperatorKind, label: &str, rows_total: Option<u64>, bytes_total: Option<u64>) -> Arc<OperatorProgress> {
+        let operator = Arc::new(OperatorProgress {
+            kind,
+            label: label.to_string(),
+            rows_total,
+            bytes_total,
+            rows_processed: AtomicU64::new(0),
+            bytes_processed: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+        });
+        self.operators.lock().push(Arc::clone(&operator));
+        operator
+    }
+
+    /// Current state of the query and its operators
+    pub fn snapshot(&self) -> QueryProgressSnapshot {
+        let operators: Vec<OperatorProgressSnapshot> = self.operators.lock().iter().map(|op| op.snapshot()).collect();
+        let finished_after = *self.finished_after.lock();
+
+        // Operators without a known total are not counted until they finish.
+        // A new operator lowers the average, so the reported value is held at
+        // its high-water mark, and stays short of 1.0 until the query ends.
+        let known: Vec<f64> = operators.iter().filter_map(|op| op.fraction).collect();
+        let fraction = match finished_after {
+            Some(_) => 1.0,
+            None if known.is_empty() => 0.0,
+            None => (known.iter().sum::<f64>() / operators.len() as f64).min(RUNNING_FRACTION_LIMIT),
+        };
+        let fraction = {
+            let mut reported = self.reported.lock();
+            *reported = reported.max(fraction);
+            *reported
+        };
+
+        QueryProgressSnapshot {
+            query_id: self.query_id,
+            query: self.sql.clone(),
+            state: if finished_after.is_some() { "finished" } else { "running" }.to_string(),
+            elapsed_ms: finished_after.unwrap_or_else(|| self.started.elapsed()).as_millis() as u64,
+            fraction,
+            current_operator: operators.iter().rev().find(|op| !op.finished).map(|op| op.label.clone()),
+            operators,
+        }
+    }
+}
+
+/// Point-in-time view of one operator's progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorProgressSnapshot {
+    pub kind: OperatorKind,
+    pub label: String,
+    pub rows_processed: u64,
+    pub rows_total: Option<u64>,
+    pub bytes_processed: u64,
+    pub bytes_total: Option<u64>,
+    pub fraction: Option<f64>,
+    pub finished: bool,
+}
+
+/// Point-in-time view of one query's progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryProgressSnapshot {
+    pub query_id: u64,
+    pub query: String,
+    pub state: String,
+    pub elapsed_ms: u64,
+    /// Estimated completion, 0.0 to 1.0
+    pub fraction: f64,
+    /// Label of the most recently started operator still running
+    pub current_operator: Option<String>,
+    pub operators: Vec<OperatorProgressSnapshot>,
+}
+
+/// Running and recently finished queries
+#[derive(Debug, Default)]
+pub struct QueryProgressRegistry {
+    next_id: AtomicU64,
+    running: RwLock<HashMap<u64, Arc<QueryProgress>>>,
+    recent: Mutex<VecDeque<Arc<QueryProgress>>>,
+}
+
+impl QueryProgressRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a query; it is finished when the guard drops
+    pub fn register(self: &Arc<Self>, sql: &str) -> QueryProgressGuard {
+        let query_id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let progress = Arc::new(QueryProgress {
+            query_id,
+            sql: sql.to_string(),
+            started: Instant::now(),
+            finished_after: Mutex::new(None),
+            operators: Mutex::new(Vec::new()),
+            reported: Mutex::new(0.0),
+        });
+        self.running.write().insert(query_id, Arc::clone(&progress));
+        QueryProgressGuard { registry: Arc::clone(self), progress }
+    }
+
+    /// Progress of running queries, then recently finished ones, oldest first
+    pub fn snapshot(&self) -> Vec<QueryProgressSnapshot> {
+        let mut queries: Vec<QueryProgressSnapshot> = self.running.read().values().map(|query| query.snapshot()).collect();
+        queries.sort_by_key(|query| query.query_id);
+        queries.extend(self.recent.lock().iter().map(|query| query.snapshot()));
+        queries
+    }
+
+    /// Progress of one query, running or recently finished
+    pub fn get(&self, query_id: u64) -> Option<QueryProgressSnapshot> {
+        if let Some(query) = self.running.read().get(&query_id) {
+            return Some(query.snapshot());
+        }
+        self.recent.lock().iter().find(|query| query.query_id == query_id).map(|query| query.snapshot())
+    }
+
+    fn finish(&self, query_id: u64) {
+        let Some(query) = self.running.write().remove(&query_id) else {
+            return;
+        };
+        *query.finished_after.lock() = Some(query.started.elapsed());
+        let mut recent = self.recent.lock();
+        recent.push_back(query);
+        while recent.len() > RECENT_QUERY_LIMIT {
+            recent.pop_front();
+        }
+    }
+}
+
+/// Keeps a query registered while it runs
+#[derive(Debug)]
+pub struct QueryProgressGuard {
+    registry: Arc<QueryProgressRegistry>,
+    progress: Arc<QueryProgress>,
+}
+
+impl QueryProgressGuard {
+    pub fn progress(&self) -> &Arc<QueryProgress> {
+        &self.progress
+    }
+}
+
+impl Drop for QueryProgressGuard {
+    fn drop(&mut self) {
+        self.registry.finish(self.progress.query_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticker_publishes_in_batches_and_on_drop() {
+        let registry = Arc::new(QueryProgressRegistry::new());
+        let guard = registry.register("SELECT * FROM t");
+        let scan = guard.progress().start_operator(OperatorKind::Scan, "scan t", Some(3000), None);
+
+        let mut ticker = ProgressTicker::new(Some(&scan));
+        for _ in 0..1500 {
+            ticker.tick(10);
+        }
+        // Only the first full batch is visible so far
+        assert_eq!(scan.snapshot().rows_processed, PUBLISH_INTERVAL_ROWS);
+        drop(ticker);
+        assert_eq!(scan.snapshot().rows_processed, 1500);
+        assert_eq!(scan.snapshot().bytes_processed, 15000);
+        assert_eq!(scan.fraction(), Some(0.5));
+    }
+
+    #[test]
+    fn test_query_fraction_averages_operators_and_completes() {
+        let registry = Arc::new(QueryProgressRegistry::new());
+        let guard = registry.register("SELECT * FROM t ORDER BY a");
+        let query_id = guard.progress().query_id();
+        let scan = guard.progress().start_operator(OperatorKind::Scan, "scan t", Some(100), None);
+        let sort = guard.progress().start_operator(OperatorKind::Sort, "sort", None, None);
+
+        scan.advance(50, 0);
+        let snapshot = registry.get(query_id).unwrap();
+        assert_eq!(snapshot.fraction, 0.25);
+        assert_eq!(snapshot.current_operator.as_deref(), Some("sort"));
+
+        // A finished scan and a new operator never pull the estimate back
+        scan.finish();
+        assert_eq!(registry.get(query_id).unwrap().fraction, 0.5);
+        guard.progress().start_operator(OperatorKind::HashBuild, "hash build", Some(10), None);
+        assert_eq!(registry.get(query_id).unwrap().fraction, 0.5);
+        sort.finish();
+        assert!(registry.get(query_id).unwrap().fraction < 1.0);
+
+        drop(guard);
+        let snapshot = registry.get(query_id).unwrap();
+        assert_eq!(snapshot.state, "finished");
+        assert_eq!(snapshot.fraction, 1.0);
+        assert_eq!(registry.snapshot().len(), 1);
+    }
+}
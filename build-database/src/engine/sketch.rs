@@ -0,0 +1,490 @@
+//! Approximate Aggregate Sketches
+//!
+//! Fixed-size summaries that answer `COUNT(DISTINCT)` and percentile queries
+//! approximately, for tables too large to answer them exactly:
+//!
+//! - [`HyperLogLog`] estimates the number of distinct values. With precision
+//!   `p` it keeps `2^p` one-byte registers and its standard error is
+//!   `1.04 / sqrt(2^p)`: 0.81% at the default `p = 14`.
+//! - [`TDigest`] estimates quantiles. It clusters values into centroids that
+//!   are small near the tails and large near the median, so extreme
+//!   percentiles stay accurate; `compression` bounds the centroid count.
+//!
+//! Both merge: sketching each partition or worker's rows and merging the
+//! sketches gives the same answer, within the error bound, as sketching all
+//! rows at once. Serialized sketches are lowercase hex text so they can be
+//! stored in ordinary columns and merged later with `hll_merge` and
+//! `tdigest_merge`.
+//!
+//! ## Serialized Form
+//!
+//! | Sketch      | Bytes                                                                  |
+//! |-------------|------------------------------------------------------------------------|
+//! | HyperLogLog | `'H'`, version `1`, `p`, then `2^p` registers                          |
+//! | t-digest    | `'T'`, version `1`, then f64 compression, min, max, u32 centroid count, then (mean, weight) f64 pairs |
+//!
+//! Numbers are big-endian.
+
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+/// Precision `approx_count_distinct` uses when none is given
+pub const DEFAULT_HLL_PRECISION: u8 = 14;
+
+/// Smallest and largest supported HyperLogLog precision
+pub const MIN_HLL_PRECISION: u8 = 4;
+pub const MAX_HLL_PRECISION: u8 = 18;
+
+/// Compression `approx_percentile` uses when none is given
+pub const DEFAULT_TDIGEST_COMPRESSION: f64 = 100.0;
+
+const HLL_TAG: u8 = b'H';
+const TDIGEST_TAG: u8 = b'T';
+const VERSION: u8 = 1;
+
+/// Distinct-count sketch
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new(precision: u8) -> AuroraResult<Self> {
+        if !(MIN_HLL_PRECISION..=MAX_HLL_PRECISION).contains(&precision) {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("HyperLogLog precision must be between {} and {}, got {}", MIN_HLL_PRECISION, MAX_HLL_PRECISION, precision)
+            ));
+        }
+        Ok(Self { precision, registers: vec![0; 1 << precision] })
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Standard error of the estimate, as a fraction of the true count
+    pub fn relative_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    /// Add a value by its 64-bit hash; equal values must hash equally
+    pub fn insert_hash(&mut self, hash: u64) {
+        let hash = mix(hash);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The bits below the index; the guard bit caps the rank when they are all zero
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Fold another sketch of the same precision into this one
+    pub fn merge(&mut self, other: &HyperLogLog) -> AuroraResult<()> {
+        if other.precision != self.precision {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("cannot merge HyperLogLog sketches of precision {} and {}", self.precision, other.precision)
+            ));
+        }
+        for (register, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*theirs);
+        }
+        Ok(())
+    }
+
+    /// Estimated number of distinct values added
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&register| 2f64.powi(-(register as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // Linear counting is more accurate while many registers are still empty
+        let zeros = self.registers.iter().filter(|&&register| register == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 + self.registers.len());
+        bytes.extend_from_slice(&[HLL_TAG, VERSION, self.precision]);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> AuroraResult<Self> {
+        let mut reader = Reader::new(bytes, "HyperLogLog");
+        reader.header(HLL_TAG)?;
+        let mut sketch = Self::new(reader.u8()?)?;
+        let registers = reader.take(sketch.registers.len())?;
+        if registers.iter().any(|&register| register > 64 - sketch.precision + 1) {
+            return Err(reader.invalid("register out of range"));
+        }
+        sketch.registers.copy_from_slice(registers);
+        reader.finish()?;
+        Ok(sketch)
+    }
+
+    /// Serialized sketch as hex text
+    pub fn to_text(&self) -> String {
+        to_hex(&self.to_bytes())
+    }
+
+    pub fn from_text(text: &str) -> AuroraResult<Self> {
+        Self::from_bytes(&from_hex(text, "HyperLogLog")?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Quantile sketch
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    unmerged: Vec<Centroid>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> AuroraResult<Self> {
+        if !(compression.is_finite() && compression >= 10.0) {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("t-digest compression must be at least 10, got {}", compression)
+            ));
+        }
+        Ok(Self {
+            compression,
+            centroids: Vec::new(),
+            unmerged: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        })
+    }
+
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    /// Values added, including those of merged sketches
+    pub fn count(&self) -> f64 {
+        self.centroids.iter().chain(&self.unmerged).map(|centroid| centroid.weight).sum()
+    }
+
+    /// Add one value; NaN is ignored
+    pub fn insert(&mut self, value: f64) {
+        self.insert_weighted(value, 1.0);
+    }
+
+    fn insert_weighted(&mut self, mean: f64, weight: f64) {
+        if mean.is_nan() || weight <= 0.0 {
+            return;
+        }
+        self.min = self.min.min(mean);
+        self.max = self.max.max(mean);
+        self.unmerged.push(Centroid { mean, weight });
+        if self.unmerged.len() as f64 > 5.0 * self.compression {
+            self.compress();
+        }
+    }
+
+    /// Fold another sketch into this one, keeping this sketch's compression
+    pub fn merge(&mut self, other: &TDigest) {
+        for centroid in other.centroids.iter().chain(&other.unmerged) {
+            self.insert_weighted(centroid.mean, centroid.weight);
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Estimated value at `fraction` (0 to 1) of the sorted values; `None`
+    /// when nothing was added
+    pub fn quantile(&mut self, fraction: f64) -> AuroraResult<Option<f64>> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("percentile must be between 0 and 1, got {}", fraction)
+            ));
+        }
+        self.compress();
+        let Some(first) = self.centroids.first() else {
+            return Ok(None);
+        };
+        if self.centroids.len() == 1 {
+            return Ok(Some(first.mean));
+        }
+
+        // Each centroid's mean sits at the middle of its weight; interpolate
+        // between neighbouring centres, and toward min and max at the ends
+        let total = self.count();
+        let target = fraction * total;
+        let mut previous = (0.0, self.min);
+        let mut cumulative = 0.0;
+        for centroid in &self.centroids {
+            let center = cumulative + centroid.weight / 2.0;
+            if target < center {
+                return Ok(Some(interpolate(previous, (center, centroid.mean), target)));
+            }
+            previous = (center, centroid.mean);
+            cumulative += centroid.weight;
+        }
+        Ok(Some(interpolate(previous, (total, self.max), target)))
+    }
+
+    /// Merge buffered values into the centroids
+    fn compress(&mut self) {
+        if self.unmerged.is_empty() {
+            return;
+        }
+        let mut all = std::mem::take(&mut self.centroids);
+        all.append(&mut self.unmerged);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = all.iter().map(|centroid| centroid.weight).sum();
+        let mut merged: Vec<Centroid> = Vec::with_capacity(all.len());
+        let mut before = 0.0;
+        let mut current = all[0];
+        let mut limit = self.scale(0.0) + 1.0;
+        for next in &all[1..] {
+            let proposed = before + current.weight + next.weight;
+            if self.scale(proposed / total) <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                before += current.weight;
+                merged.push(current);
+                limit = self.scale(before / total) + 1.0;
+                current = *next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// Scale function k1: a centroid may span one unit of it, so centroids
+    /// near q = 0 and q = 1 stay small
+    fn scale(&self, q: f64) -> f64 {
+        self.compression / (2.0 * std::f64::consts::PI) * (2.0 * q.clamp(0.0, 1.0) - 1.0).asin()
+    }
+
+    pub fn to_bytes(&mut self) -> Vec<u8> {
+        self.compress();
+        let mut bytes = vec![TDIGEST_TAG, VERSION];
+        for number in [self.compression, self.min, self.max] {
+            bytes.extend_from_slice(&number.to_be_bytes());
+        }
+        bytes.extend_from_slice(&(self.centroids.len() as u32).to_be_bytes());
+        for centroid in &self.centroids {
+            bytes.extend_from_slice(&centroid.mean.to_be_bytes());
+            bytes.extend_from_slice(&centroid.weight.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> AuroraResult<Self> {
+        let mut reader = Reader::new(bytes, "t-digest");
+        reader.header(TDIGEST_TAG)?;
+        let mut digest = Self::new(reader.f64()?)?;
+        digest.min = reader.f64()?;
+        digest.max = reader.f64()?;
+        let count = reader.u32()? as usize;
+        for _ in 0..count {
+            let (mean, weight) = (reader.f64()?, reader.f64()?);
+            if !(mean.is_finite() && weight.is_finite() && weight > 0.0) {
+                return Err(reader.invalid("invalid centroid"));
+            }
+            digest.centroids.push(Centroid { mean, weight });
+        }
+        if digest.centroids.windows(2).any(|pair| pair[0].mean > pair[1].mean) {
+            return Err(reader.invalid("centroids out of order"));
+        }
+        reader.finish()?;
+        Ok(digest)
+    }
+
+    /// Serialized sketch as hex text
+    pub fn to_text(&mut self) -> String {
+        to_hex(&self.to_bytes())
+    }
+
+    pub fn from_text(text: &str) -> AuroraResult<Self> {
+        Self::from_bytes(&from_hex(text, "t-digest")?)
+    }
+}
+
+fn interpolate((x0, y0): (f64, f64), (x1, y1): (f64, f64), x: f64) -> f64 {
+    if x1 <= x0 {
+        return y1;
+    }
+    y0 + (y1 - y0) * ((x - x0) / (x1 - x0)).clamp(0.0, 1.0)
+}
+
+/// Spread the bits of a value hash; register index and rank come from its
+/// high and low bits, which FNV alone leaves correlated
+fn mix(mut hash: u64) -> u64 {
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str, kind: &str) -> AuroraResult<Vec<u8>> {
+    let invalid = || AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("malformed {} sketch: not hex text", kind));
+    if !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| text.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()).ok_or_else(invalid))
+        .collect()
+}
+
+/// Cursor over a serialized sketch
+struct Reader<'a> {
+    bytes: &'a [u8],
+    kind: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], kind: &'static str) -> Self {
+        Self { bytes, kind }
+    }
+
+    fn invalid(&self, reason: &str) -> AuroraError {
+        AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("malformed {} sketch: {}", self.kind, reason))
+    }
+
+    fn take(&mut self, len: usize) -> AuroraResult<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(self.invalid("truncated"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn header(&mut self, tag: u8) -> AuroraResult<()> {
+        match self.take(2)? {
+            [found, VERSION] if *found == tag => Ok(()),
+            [found, _] if *found == tag => Err(self.invalid("unsupported version")),
+            _ => Err(self.invalid("wrong sketch type")),
+        }
+    }
+
+    fn u8(&mut self) -> AuroraResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> AuroraResult<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().expect("four bytes")))
+    }
+
+    fn f64(&mut self) -> AuroraResult<f64> {
+        Ok(f64::from_be_bytes(self.take(8)?.try_into().expect("eight bytes")))
+    }
+
+    fn finish(&self) -> AuroraResult<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(self.invalid("trailing bytes"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hll_of(values: impl IntoIterator<Item = u64>, precision: u8) -> HyperLogLog {
+        let mut sketch = HyperLogLog::new(precision).unwrap();
+        for value in values {
+            sketch.insert_hash(value);
+        }
+        sketch
+    }
+
+    #[test]
+    fn test_hll_estimate_within_error_bound() {
+        for &cardinality in &[1_000u64, 50_000, 250_000] {
+            // Every value twice: duplicates must not count
+            let sketch = hll_of((0..cardinality).chain(0..cardinality), DEFAULT_HLL_PRECISION);
+            let error = (sketch.estimate() as f64 - cardinality as f64).abs() / cardinality as f64;
+            assert!(error <= 3.0 * sketch.relative_error(), "{}: estimate {}", cardinality, sketch.estimate());
+        }
+        assert_eq!(hll_of([], 10).estimate(), 0);
+    }
+
+    #[test]
+    fn test_hll_merge_matches_single_sketch_and_round_trips() {
+        let mut left = hll_of(0..60_000, 12);
+        let right = hll_of(40_000..100_000, 12);
+        left.merge(&right).unwrap();
+        assert_eq!(left, hll_of(0..100_000, 12));
+
+        let restored = HyperLogLog::from_text(&left.to_text()).unwrap();
+        assert_eq!(restored, left);
+
+        assert!(left.merge(&hll_of(0..10, 14)).is_err());
+        assert!(HyperLogLog::new(3).is_err());
+        assert!(HyperLogLog::from_text("zz").is_err());
+        assert!(HyperLogLog::from_text(&left.to_text()[..20]).is_err());
+    }
+
+    #[test]
+    fn test_tdigest_quantiles_within_tolerance() {
+        let mut digest = TDigest::new(DEFAULT_TDIGEST_COMPRESSION).unwrap();
+        // Shuffled 1..=100000, so the digest does not see sorted input
+        for i in 0..100_000u64 {
+            digest.insert(((i * 7_919) % 100_000 + 1) as f64);
+        }
+        for (fraction, expected) in [(0.5, 50_000.0), (0.9, 90_000.0), (0.99, 99_000.0), (0.001, 100.0)] {
+            let estimate = digest.quantile(fraction).unwrap().unwrap();
+            assert!((estimate - expected).abs() <= 100_000.0 * 0.005, "p{}: {}", fraction, estimate);
+        }
+        assert_eq!(digest.quantile(0.0).unwrap(), Some(1.0));
+        assert_eq!(digest.quantile(1.0).unwrap(), Some(100_000.0));
+        assert!(digest.quantile(1.5).is_err());
+        assert!(digest.centroids.len() as f64 <= 2.0 * DEFAULT_TDIGEST_COMPRESSION);
+    }
+
+    #[test]
+    fn test_tdigest_merge_and_round_trip() {
+        let mut parts: Vec<TDigest> = (0..4).map(|_| TDigest::new(100.0).unwrap()).collect();
+        for i in 0..40_000u64 {
+            parts[(i % 4) as usize].insert(i as f64);
+        }
+        let mut merged = TDigest::new(100.0).unwrap();
+        for part in &mut parts {
+            let restored = TDigest::from_text(&part.to_text()).unwrap();
+            merged.merge(&restored);
+        }
+        assert_eq!(merged.count(), 40_000.0);
+        let median = merged.quantile(0.5).unwrap().unwrap();
+        assert!((median - 20_000.0).abs() <= 40_000.0 * 0.01, "{}", median);
+
+        assert_eq!(TDigest::new(100.0).unwrap().quantile(0.5).unwrap(), None);
+        assert!(TDigest::from_text(&HyperLogLog::new(4).unwrap().to_text()).is_err());
+    }
+}
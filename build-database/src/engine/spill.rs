@@ -0,0 +1,470 @@
+//! Spill Storage Backends
+//!
+//! Operators that outgrow their memory (spilling sorts, materialized CTEs)
+//! write temp segments through a [`SpillBackend`] instead of straight to
+//! local disk, so where spills land is a matter of configuration:
+//! - **Local**: files under `temp_directory`
+//! - **Tmpfs**: files under an explicit memory-backed mount, capped so a
+//!   runaway spill fails its query instead of exhausting the host's memory
+//! - **Object store**: segments cut into parts and uploaded to an
+//!   [`ObjectStore`], for spills too large for any local volume
+//!
+//! A segment is removed when the [`SpillFile`] holding it is dropped, so a
+//! query's spills go away when it ends, however it ends. Segments left
+//! behind by a crashed process are removed when the database next opens.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use crate::config::SpillConfig;
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+/// Suffix of every segment name, marking what recovery may remove
+pub const SEGMENT_SUFFIX: &str = ".spill";
+
+/// Kind of storage spill segments are written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpillBackendKind {
+    Local,
+    Tmpfs,
+    ObjectStore,
+}
+
+impl SpillBackendKind {
+    /// Parse a setting value: `local`, `tmpfs` or `object_store`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "local" => Some(Self::Local),
+            "tmpfs" => Some(Self::Tmpfs),
+            "object_store" => Some(Self::ObjectStore),
+            _ => None,
+        }
+    }
+}
+
+/// Storage for the temp segments of spilling operators
+pub trait SpillBackend: Send + Sync {
+    fn kind(&self) -> SpillBackendKind;
+
+    /// Start writing segment `name`
+    fn create(&self, name: &str) -> AuroraResult<Box<dyn SegmentWriter>>;
+
+    /// Read back a finished segment from its start
+    fn open(&self, name: &str) -> AuroraResult<Box<dyn Read + Send>>;
+
+    /// Remove segment `name`, finished or not
+    fn remove(&self, name: &str) -> AuroraResult<()>;
+
+    /// Names of every segment present, including those of crashed processes
+    fn segments(&self) -> AuroraResult<Vec<String>>;
+}
+
+/// A segment being written
+pub trait SegmentWriter: Write + Send {
+    /// Make everything written durable in the backend; returns the
+    /// segment's size in bytes
+    fn finish(self: Box<Self>) -> AuroraResult<u64>;
+}
+
+/// A segment being written for an operator; removed if dropped unfinished
+pub struct SpillWriter {
+    backend: Arc<dyn SpillBackend>,
+    name: String,
+    writer: Option<Box<dyn SegmentWriter>>,
+}
+
+impl SpillWriter {
+    /// Start a new segment in `backend`, named after `prefix`
+    pub fn create(backend: &Arc<dyn SpillBackend>, prefix: &str) -> AuroraResult<Self> {
+        let name = format!("{}_{}{}", prefix, uuid::Uuid::new_v4().simple(), SEGMENT_SUFFIX);
+        let writer = backend.create(&name)?;
+        Ok(Self { backend: backend.clone(), name, writer: Some(writer) })
+    }
+
+    /// Finish the segment, handing it over to a [`SpillFile`]
+    pub fn finish(mut self) -> AuroraResult<SpillFile> {
+        let writer = self.writer.take().expect("segment already finished");
+        let bytes = writer.finish()?;
+        Ok(SpillFile { backend: self.backend.clone(), name: std::mem::take(&mut self.name), bytes })
+    }
+}
+
+impl Write for SpillWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.as_mut().expect("segment already finished").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.as_mut().expect("segment already finished").flush()
+    }
+}
+
+impl Drop for SpillWriter {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            // Closed first, so a file segment's buffered bytes are counted
+            drop(writer);
+            let _ = self.backend.remove(&self.name);
+        }
+    }
+}
+
+/// A finished segment, removed from its backend when dropped
+pub struct SpillFile {
+    backend: Arc<dyn SpillBackend>,
+    name: String,
+    bytes: u64,
+}
+
+impl SpillFile {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Size of the segment in bytes
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Read the segment from its start
+    pub fn reader(&self) -> AuroraResult<Box<dyn Read + Send>> {
+        self.backend.open(&self.name)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(e) = self.backend.remove(&self.name) {
+            log::warn!("Failed to remove spill segment {}: {}", self.name, e);
+        }
+    }
+}
+
+/// Remove every segment in `backend`; run before any query can spill, so
+/// all that is found was left by a process that did not clean up
+pub fn remove_orphaned_segments(backend: &dyn SpillBackend) -> AuroraResult<usize> {
+    let segments = backend.segments()?;
+    for name in &segments {
+        backend.remove(name)?;
+    }
+    Ok(segments.len())
+}
+
+/// The backend `config` selects. Spills go to `temp_dir` for the local
+/// backend; the object store backend needs `object_store`.
+pub fn backend_for(
+    config: &SpillConfig,
+    temp_dir: &Path,
+    object_store: Option<Arc<dyn ObjectStore>>,
+) -> AuroraResult<Arc<dyn SpillBackend>> {
+    let kind = SpillBackendKind::parse(&config.backend).ok_or_else(|| AuroraError::new(
+        ErrorCode::ValidationInvalidFormat,
+        format!("unknown spill backend '{}'", config.backend)
+    ))?;
+    Ok(match kind {
+        SpillBackendKind::Local => Arc::new(DirectorySpill::local(temp_dir)),
+        SpillBackendKind::Tmpfs => Arc::new(DirectorySpill::tmpfs(&config.tmpfs_directory, config.tmpfs_max_bytes)),
+        SpillBackendKind::ObjectStore => {
+            let store = object_store.ok_or_else(|| AuroraError::new(
+                ErrorCode::StorageUnavailable,
+                "spill backend 'object_store' selected but no object store is attached"
+            ))?;
+            Arc::new(ObjectStoreSpill::new(store, &config.object_store_prefix, config.object_part_bytes))
+        }
+    })
+}
+
+/// Segments as files in one directory: local disk or a tmpfs mount
+pub struct DirectorySpill {
+    kind: SpillBackendKind,
+    dir: PathBuf,
+    /// Most bytes all segments may hold together; 0 for no limit
+    capacity: u64,
+    used: Arc<AtomicU64>,
+}
+
+impl DirectorySpill {
+    /// Spill to files under `dir` on local disk
+    pub fn local(dir: impl Into<PathBuf>) -> Self {
+        Self { kind: SpillBackendKind::Local, dir: dir.into(), capacity: 0, used: Arc::new(AtomicU64::new(0)) }
+    }
+
+    /// Spill to files under `dir`, a tmpfs mount, holding at most
+    /// `max_bytes` at once (0 for no limit)
+    pub fn tmpfs(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        let dir = dir.into();
+        if !is_tmpfs(&dir) {
+            log::warn!("Spill directory {} is not on a tmpfs mount", dir.display());
+        }
+        Self { kind: SpillBackendKind::Tmpfs, dir, capacity: max_bytes, used: Arc::new(AtomicU64::new(0)) }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Bytes the segments present hold
+    pub fn used_bytes(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+impl SpillBackend for DirectorySpill {
+    fn kind(&self) -> SpillBackendKind {
+        self.kind
+    }
+
+    fn create(&self, name: &str) -> AuroraResult<Box<dyn SegmentWriter>> {
+        fs::create_dir_all(&self.dir)?;
+        let file = File::create(self.dir.join(name))?;
+        Ok(Box::new(FileSegment {
+            writer: BufWriter::new(file),
+            bytes: 0,
+            capacity: self.capacity,
+            used: self.used.clone(),
+        }))
+    }
+
+    fn open(&self, name: &str) -> AuroraResult<Box<dyn Read + Send>> {
+        Ok(Box::new(BufReader::new(File::open(self.dir.join(name))?)))
+    }
+
+    fn remove(&self, name: &str) -> AuroraResult<()> {
+        let path = self.dir.join(name);
+        let bytes = match fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        fs::remove_file(&path)?;
+        // Segments of an earlier process were never counted
+        let _ = self.used.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| Some(used.saturating_sub(bytes)));
+        Ok(())
+    }
+
+    fn segments(&self) -> AuroraResult<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut segments = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name.ends_with(SEGMENT_SUFFIX) {
+                segments.push(name);
+            }
+        }
+        Ok(segments)
+    }
+}
+
+/// A segment file being written, counted against the directory's capacity
+struct FileSegment {
+    writer: BufWriter<File>,
+    bytes: u64,
+    capacity: u64,
+    used: Arc<AtomicU64>,
+}
+
+impl Write for FileSegment {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len() as u64;
+        let reserved = self.used.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+            (self.capacity == 0 || used + len <= self.capacity).then_some(used + len)
+        });
+        if reserved.is_err() {
+            return Err(io::Error::other(format!("spill capacity of {} bytes exhausted", self.capacity)));
+        }
+        self.writer.write_all(buf)?;
+        self.bytes += len;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl SegmentWriter for FileSegment {
+    fn finish(mut self: Box<Self>) -> AuroraResult<u64> {
+        self.writer.flush()?;
+        Ok(self.bytes)
+    }
+}
+
+/// Whether `dir`, or the nearest ancestor that exists, is on a tmpfs mount
+#[cfg(target_os = "linux")]
+fn is_tmpfs(dir: &Path) -> bool {
+    let Some(dir) = dir.ancestors().find_map(|path| path.canonicalize().ok()) else {
+        return false;
+    };
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    mounts.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?;
+            Some((mount_point, fields.next()?))
+        })
+        .filter(|(mount_point, _)| dir.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.len())
+        .is_some_and(|(_, fs_type)| fs_type == "tmpfs")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_tmpfs(_dir: &Path) -> bool {
+    false
+}
+
+/// Blob storage a spill backend can put segments in, such as S3 or GCS
+pub trait ObjectStore: Send + Sync {
+    fn put(&self, key: &str, data: Vec<u8>) -> AuroraResult<()>;
+
+    fn get(&self, key: &str) -> AuroraResult<Vec<u8>>;
+
+    /// Delete `key`; deleting a missing key succeeds
+    fn delete(&self, key: &str) -> AuroraResult<()>;
+
+    /// Keys starting with `prefix`, in any order
+    fn list(&self, prefix: &str) -> AuroraResult<Vec<String>>;
+}
+
+/// Segments in an object store, each cut into parts uploaded as they fill
+/// so a segment never has to fit in memory
+pub struct ObjectStoreSpill {
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+    part_bytes: usize,
+}
+
+impl ObjectStoreSpill {
+    /// Spill under `prefix` in `store`, in parts of `part_bytes`
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: &str, part_bytes: usize) -> Self {
+        Self { store, prefix: prefix.trim_end_matches('/').to_string(), part_bytes: part_bytes.max(1) }
+    }
+
+    /// Keys of the parts of segment `name`, in order
+    fn parts(&self, name: &str) -> AuroraResult<Vec<String>> {
+        let mut parts = self.store.list(&format!("{}/{}/", self.prefix, name))?;
+        parts.sort();
+        Ok(parts)
+    }
+}
+
+impl SpillBackend for ObjectStoreSpill {
+    fn kind(&self) -> SpillBackendKind {
+        SpillBackendKind::ObjectStore
+    }
+
+    fn create(&self, name: &str) -> AuroraResult<Box<dyn SegmentWriter>> {
+        Ok(Box::new(ObjectSegment {
+            store: self.store.clone(),
+            key_prefix: format!("{}/{}/", self.prefix, name),
+            part_bytes: self.part_bytes,
+            buffer: Vec::with_capacity(self.part_bytes),
+            parts: 0,
+            bytes: 0,
+        }))
+    }
+
+    fn open(&self, name: &str) -> AuroraResult<Box<dyn Read + Send>> {
+        Ok(Box::new(ObjectSegmentReader {
+            store: self.store.clone(),
+            parts: self.parts(name)?.into(),
+            current: Cursor::new(Vec::new()),
+        }))
+    }
+
+    fn remove(&self, name: &str) -> AuroraResult<()> {
+        for part in self.parts(name)? {
+            self.store.delete(&part)?;
+        }
+        Ok(())
+    }
+
+    fn segments(&self) -> AuroraResult<Vec<String>> {
+        let prefix = format!("{}/", self.prefix);
+        let segments: BTreeSet<String> = self.store.list(&prefix)?
+            .iter()
+            .filter_map(|key| key.strip_prefix(&prefix)?.split('/').next())
+            .filter(|name| name.ends_with(SEGMENT_SUFFIX))
+            .map(str::to_string)
+            .collect();
+        Ok(segments.into_iter().collect())
+    }
+}
+
+/// A segment being uploaded a part at a time
+struct ObjectSegment {
+    store: Arc<dyn ObjectStore>,
+    key_prefix: String,
+    part_bytes: usize,
+    buffer: Vec<u8>,
+    parts: usize,
+    bytes: u64,
+}
+
+impl ObjectSegment {
+    fn upload(&mut self) -> AuroraResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let part = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.part_bytes));
+        // Zero-padded so listing order is part order
+        self.store.put(&format!("{}{:08}", self.key_prefix, self.parts), part)?;
+        self.parts += 1;
+        Ok(())
+    }
+}
+
+impl Write for ObjectSegment {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.part_bytes - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..len]);
+        self.bytes += len as u64;
+        if self.buffer.len() == self.part_bytes {
+            self.upload().map_err(|e| io::Error::other(e.to_string()))?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Parts are uploaded whole; a partial one waits for `finish`
+        Ok(())
+    }
+}
+
+impl SegmentWriter for ObjectSegment {
+    fn finish(mut self: Box<Self>) -> AuroraResult<u64> {
+        self.upload()?;
+        Ok(self.bytes)
+    }
+}
+
+/// Reads a segment's parts in order, downloading each when reached
+struct ObjectSegmentReader {
+    store: Arc<dyn ObjectStore>,
+    parts: VecDeque<String>,
+    current: Cursor<Vec<u8>>,
+}
+
+impl Read for ObjectSegmentReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.current.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            let Some(part) = self.parts.pop_front() else {
+                return Ok(0);
+            };
+            let data = self.store.get(&part).map_err(|e| io::Error::other(e.to_string()))?;
+            self.current = Cursor::new(data);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Plan and Result Caches
+//!
+//! Prepared plans and SELECT results are cached by statement text. Each
+//! entry is tagged with the catalog objects it was built from, and both
+//! caches subscribe to the catalog change bus, so altering or dropping a
+//! table evicts exactly the entries that reference it and leaves the rest.
+//!
+//! Cached results additionally record the data version of every table they
+//! read; a write to any of those tables makes the entry stale without
+//! needing a DDL change.
+//!
+//! Entries can be pinned. A pinned entry is never evicted to make room and
+//! survives `invalidate_unpinned`, which fresh statistics use to have plans
+//! rebuilt; only a catalog change to an object it depends on removes it.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::RwLock;
+use crate::catalog::{CatalogChange, CatalogSubscriber, ObjectId};
+use crate::query::parser::ast::Query;
+use super::aurora_db::QueryResult;
+
+/// Default number of entries each cache holds
+pub const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 1024;
+
+/// Parsed statements, ready to execute without re-parsing
+pub type PlanCache = DependentCache<Query>;
+
+/// SELECT results
+pub type ResultCache = DependentCache<CachedResult>;
+
+/// A SELECT result and the data version of each table it read
+#[derive(Debug, Clone)]
+pub struct CachedResult {
+    pub result: QueryResult,
+    pub data_versions: Vec<(String, u64)>,
+}
+
+struct CacheEntry<V> {
+    value: V,
+    objects: Vec<ObjectId>,
+}
+
+struct CacheState<V> {
+    entries: HashMap<String, CacheEntry<V>>,
+    by_object: HashMap<ObjectId, HashSet<String>>,
+    pinned: HashSet<String>,
+}
+
+/// Cache whose entries are evicted when a catalog object they depend on
+/// changes
+pub struct DependentCache<V> {
+    capacity: usize,
+    state: RwLock<CacheState<V>>,
+    invalidations: AtomicU64,
+}
+
+impl<V: Clone> DependentCache<V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: RwLock::new(CacheState { entries: HashMap::new(), by_object: HashMap::new(), pinned: HashSet::new() }),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.state.read().entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.state.read().entries.contains_key(key)
+    }
+
+    /// Cache `value` as built from `objects`. At capacity, an arbitrary
+    /// unpinned entry makes room; replacing a pinned entry keeps it pinned.
+    pub fn insert(&self, key: String, value: V, objects: Vec<ObjectId>) {
+        let mut state = self.state.write();
+        let pinned = state.pinned.contains(&key);
+        Self::remove_entry(&mut state, &key);
+        if state.entries.len() >= self.capacity {
+            let victim = state.entries.keys().find(|candidate| !state.pinned.contains(*candidate)).cloned();
+            if let Some(victim) = victim {
+                Self::remove_entry(&mut state, &victim);
+            }
+        }
+        if pinned {
+            state.pinned.insert(key.clone());
+        }
+        for object in &objects {
+            state.by_object.entry(*object).or_default().insert(key.clone());
+        }
+        state.entries.insert(key, CacheEntry { value, objects });
+    }
+
+    pub fn remove(&self, key: &str) {
+        Self::remove_entry(&mut self.state.write(), key);
+    }
+
+    /// Pin a cached entry; false if `key` is not cached
+    pub fn pin(&self, key: &str) -> bool {
+        let mut state = self.state.write();
+        if !state.entries.contains_key(key) {
+            return false;
+        }
+        state.pinned.insert(key.to_string());
+        true
+    }
+
+    /// Unpin an entry, leaving it cached; false if it was not pinned
+    pub fn unpin(&self, key: &str) -> bool {
+        self.state.write().pinned.remove(key)
+    }
+
+    pub fn is_pinned(&self, key: &str) -> bool {
+        self.state.read().pinned.contains(key)
+    }
+
+    /// Keys of the pinned entries, sorted
+    pub fn pinned(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.state.read().pinned.iter().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Evict every entry built from `object`, returning how many there were
+    pub fn invalidate_object(&self, object: ObjectId) -> usize {
+        let mut state = self.state.write();
+        let keys = state.by_object.remove(&object).unwrap_or_default();
+        for key in &keys {
+            Self::remove_entry(&mut state, key);
+        }
+        self.invalidations.fetch_add(keys.len() as u64, Ordering::Relaxed);
+        keys.len()
+    }
+
+    /// Evict the unpinned entries built from `object`, returning how many
+    /// there were
+    pub fn invalidate_unpinned(&self, object: ObjectId) -> usize {
+        let mut state = self.state.write();
+        let keys: Vec<String> = state.by_object.get(&object)
+            .map(|keys| keys.iter().filter(|key| !state.pinned.contains(*key)).cloned().collect())
+            .unwrap_or_default();
+        for key in &keys {
+            Self::remove_entry(&mut state, key);
+        }
+        self.invalidations.fetch_add(keys.len() as u64, Ordering::Relaxed);
+        keys.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entries evicted by catalog changes so far
+    pub fn invalidations(&self) -> u64 {
+        self.invalidations.load(Ordering::Relaxed)
+    }
+
+    fn remove_entry(state: &mut CacheState<V>, key: &str) {
+        state.pinned.remove(key);
+        if let Some(entry) = state.entries.remove(key) {
+            for object in entry.objects {
+                if let Some(keys) = state.by_object.get_mut(&object) {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        state.by_object.remove(&object);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<V: Clone + Send + Sync> CatalogSubscriber for DependentCache<V> {
+    fn on_catalog_change(&self, change: &CatalogChange) {
+        let evicted = self.invalidate_object(change.object_id);
+        if evicted > 0 {
+            log::debug!("Evicted {} cached entries for '{}'", evicted, change.object_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CatalogChangeKind;
+
+    #[test]
+    fn test_invalidation_is_limited_to_dependent_entries() {
+        let cache: DependentCache<&str> = DependentCache::new(16);
+        cache.insert("SELECT * FROM orders".to_string(), "orders plan", vec![1]);
+        cache.insert("SELECT * FROM orders JOIN users".to_string(), "join plan", vec![1, 2]);
+        cache.insert("SELECT * FROM users".to_string(), "users plan", vec![2]);
+
+        cache.on_catalog_change(&CatalogChange {
+            object_id: 1,
+            object_name: "orders".to_string(),
+            kind: CatalogChangeKind::Altered,
+        });
+
+        assert!(!cache.contains("SELECT * FROM orders"));
+        assert!(!cache.contains("SELECT * FROM orders JOIN users"));
+        assert_eq!(cache.get("SELECT * FROM users"), Some("users plan"));
+        assert_eq!(cache.invalidations(), 2);
+
+        // The join's entry no longer hangs off users either
+        assert_eq!(cache.invalidate_object(2), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_bounds_entries() {
+        let cache: DependentCache<u32> = DependentCache::new(2);
+        for i in 0..5 {
+            cache.insert(format!("q{}", i), i, vec![i as ObjectId]);
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("q4"));
+    }
+
+    #[test]
+    fn test_pinned_entries_outlive_pressure_and_refreshes() {
+        let cache: DependentCache<u32> = DependentCache::new(2);
+        cache.insert("hot".to_string(), 0, vec![1]);
+        assert!(cache.pin("hot"));
+        assert!(!cache.pin("missing"));
+        for i in 1..5 {
+            cache.insert(format!("q{}", i), i, vec![1]);
+        }
+        assert!(cache.contains("hot"));
+        assert_eq!(cache.len(), 2);
+
+        // Replacing a pinned entry keeps the pin; a refresh spares it
+        cache.insert("hot".to_string(), 10, vec![1]);
+        assert_eq!(cache.invalidate_unpinned(1), 1);
+        assert_eq!(cache.get("hot"), Some(10));
+        assert_eq!(cache.pinned(), vec!["hot".to_string()]);
+
+        // A catalog change removes it, pin and all
+        assert_eq!(cache.invalidate_object(1), 1);
+        assert!(cache.pinned().is_empty());
+        cache.insert("hot".to_string(), 0, vec![1]);
+        assert!(!cache.is_pinned("hot"));
+    }
+}
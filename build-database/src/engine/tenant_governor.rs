@@ -0,0 +1,368 @@
+//! Tenant Resource Governor
+//!
+//! Isolates tenants sharing one database from each other's load. Each tenant
+//! has a quota on:
+//! - **Connections**: Open client connections, checked when one is accepted
+//! - **Concurrent queries**: Statements executing at once
+//! - **Memory**: The `work_mem` reserved by its executing statements
+//! - **Storage**: Bytes its writes have added to table storage, net of the
+//!   rows it deleted
+//! - **Queries per second**: A token bucket holding up to one second's worth
+//!   of queries, so short bursts pass and sustained overload is throttled
+//!
+//! A login role maps to a tenant explicitly, or is its own tenant. Limits
+//! that are not set are unlimited; tenants without a quota of their own get
+//! the default one.
+//!
+//! Admission hands out permits that give back their connection, query slot
+//! and memory reservation when dropped, so a failing statement cannot leak
+//! its share. A refused request counts against the tenant's usage metrics
+//! and fails with a `QuotaError` naming the resource.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+use parking_lot::{Mutex, RwLock};
+use crate::core::AuroraError;
+use crate::errors::ErrorCode;
+use super::aurora_db::UserContext;
+
+/// Limits of one tenant; `None` is unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    pub max_connections: Option<usize>,
+    pub max_concurrent_queries: Option<usize>,
+    /// `work_mem` its executing statements may reserve in total, in bytes
+    pub memory_bytes: Option<u64>,
+    /// Bytes its writes may add to table storage
+    pub storage_bytes: Option<u64>,
+    pub queries_per_second: Option<u32>,
+}
+
+/// A resource a tenant's quota limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuotaResource {
+    Connections,
+    ConcurrentQueries,
+    Memory,
+    Storage,
+    QueriesPerSecond,
+}
+
+impl QuotaResource {
+    /// Unit its limit is given in, after the number
+    fn unit(&self) -> &'static str {
+        match self {
+            QuotaResource::Memory | QuotaResource::Storage => " bytes",
+            _ => "",
+        }
+    }
+}
+
+impl fmt::Display for QuotaResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            QuotaResource::Connections => "connections",
+            QuotaResource::ConcurrentQueries => "concurrent queries",
+            QuotaResource::Memory => "memory",
+            QuotaResource::Storage => "storage",
+            QuotaResource::QueriesPerSecond => "queries per second",
+        })
+    }
+}
+
+/// A request refused because it would take a tenant past its quota
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("tenant \"{tenant}\" exceeded its {resource} quota of {limit}{}", .resource.unit())]
+pub struct QuotaError {
+    pub tenant: String,
+    pub resource: QuotaResource,
+    pub limit: u64,
+}
+
+impl QuotaError {
+    /// SQLSTATE code for the ErrorResponse
+    pub fn sqlstate(&self) -> &'static str {
+        match self.resource {
+            QuotaResource::Connections => "53300",
+            QuotaResource::Memory => "53200",
+            QuotaResource::Storage => "53100",
+            QuotaResource::ConcurrentQueries | QuotaResource::QueriesPerSecond => "53000",
+        }
+    }
+}
+
+impl From<QuotaError> for AuroraError {
+    fn from(e: QuotaError) -> Self {
+        AuroraError::new(ErrorCode::QueryQuotaExceeded, e.to_string())
+            .with_context("tenant", e.tenant.clone())
+            .with_context("resource", e.resource.to_string())
+            .with_context("sqlstate", e.sqlstate())
+    }
+}
+
+/// What a tenant is using now, and how often it was refused
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantUsage {
+    pub tenant: String,
+    pub connections: usize,
+    pub active_queries: usize,
+    /// `work_mem` reserved by executing statements, in bytes
+    pub memory_bytes: u64,
+    pub storage_bytes: u64,
+    /// Queries admitted since the tenant was first seen
+    pub queries: u64,
+    /// Requests refused, per resource
+    pub rejected: BTreeMap<QuotaResource, u64>,
+}
+
+impl TenantUsage {
+    /// Requests refused over any resource
+    pub fn total_rejected(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+}
+
+/// Query rate limiter refilling `rate` tokens a second, up to `rate`
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u32,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32) -> Self {
+        Self { rate, tokens: rate as f64, refilled_at: Instant::now() }
+    }
+
+    fn try_take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TenantState {
+    usage: TenantUsage,
+    /// Present while the quota limits queries per second
+    bucket: Option<TokenBucket>,
+}
+
+impl TenantState {
+    fn refuse(&mut self, resource: QuotaResource, limit: u64) -> QuotaError {
+        *self.usage.rejected.entry(resource).or_insert(0) += 1;
+        log::warn!("Tenant '{}' refused: over its {} quota", self.usage.tenant, resource);
+        QuotaError { tenant: self.usage.tenant.clone(), resource, limit }
+    }
+}
+
+/// Per-tenant quotas and the usage they are enforced against
+#[derive(Debug, Default)]
+pub struct TenantGovernor {
+    default_quota: RwLock<TenantQuota>,
+    quotas: RwLock<HashMap<String, TenantQuota>>,
+    /// Login or group role -> tenant
+    role_tenants: RwLock<HashMap<String, String>>,
+    tenants: Mutex<HashMap<String, TenantState>>,
+}
+
+impl TenantGovernor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quota of tenants that have none of their own
+    pub fn set_default_quota(&self, quota: TenantQuota) {
+        *self.default_quota.write() = quota;
+        self.tenants.lock().values_mut().for_each(|state| state.bucket = None);
+    }
+
+    /// Set `tenant`'s quota; connections and queries already admitted keep
+    /// running
+    pub fn set_quota(&self, tenant: &str, quota: TenantQuota) {
+        self.quotas.write().insert(tenant.to_string(), quota);
+        if let Some(state) = self.tenants.lock().get_mut(tenant) {
+            state.bucket = None;
+        }
+    }
+
+    /// Return `tenant` to the default quota
+    pub fn clear_quota(&self, tenant: &str) {
+        self.quotas.write().remove(tenant);
+        if let Some(state) = self.tenants.lock().get_mut(tenant) {
+            state.bucket = None;
+        }
+    }
+
+    /// Quota in effect for `tenant`
+    pub fn quota(&self, tenant: &str) -> TenantQuota {
+        self.quotas.read().get(tenant).copied().unwrap_or_else(|| *self.default_quota.read())
+    }
+
+    /// Count sessions of `role` against `tenant`
+    pub fn assign_role(&self, role: &str, tenant: &str) {
+        self.role_tenants.write().insert(role.to_string(), tenant.to_string());
+    }
+
+    /// Tenant of a login role: the one assigned to it, or the role itself
+    pub fn tenant_of_role(&self, role: &str) -> String {
+        self.role_tenants.read().get(role).cloned().unwrap_or_else(|| role.to_string())
+    }
+
+    /// Tenant of a session: the one assigned to its login role, then to the
+    /// first of its roles that has one, or else the login role itself
+    pub fn tenant_of(&self, user_context: &UserContext) -> String {
+        let role_tenants = self.role_tenants.read();
+        std::iter::once(&user_context.username)
+            .chain(&user_context.roles)
+            .find_map(|role| role_tenants.get(role).cloned())
+            .unwrap_or_else(|| user_context.username.clone())
+    }
+
+    /// Admit a connection of `tenant`, held until the permit is dropped
+    pub fn open_connection(self: &Arc<Self>, tenant: &str) -> Result<ConnectionPermit, QuotaError> {
+        let quota = self.quota(tenant);
+        let mut tenants = self.tenants.lock();
+        let state = Self::state(&mut tenants, tenant);
+        if let Some(limit) = quota.max_connections {
+            if state.usage.connections >= limit {
+                return Err(state.refuse(QuotaResource::Connections, limit as u64));
+            }
+        }
+        state.usage.connections += 1;
+        Ok(ConnectionPermit { governor: Arc::clone(self), tenant: tenant.to_string() })
+    }
+
+    /// Admit a statement of `tenant` reserving `memory_bytes` of its memory
+    /// quota, held until the permit is dropped. A statement refused for any
+    /// resource does not use up a query-per-second token.
+    pub fn begin_query(self: &Arc<Self>, tenant: &str, memory_bytes: u64) -> Result<QueryPermit, QuotaError> {
+        let quota = self.quota(tenant);
+        let mut tenants = self.tenants.lock();
+        let state = Self::state(&mut tenants, tenant);
+        if let Some(limit) = quota.max_concurrent_queries {
+            if state.usage.active_queries >= limit {
+                return Err(state.refuse(QuotaResource::ConcurrentQueries, limit as u64));
+            }
+        }
+        if let Some(limit) = quota.memory_bytes {
+            if state.usage.memory_bytes.saturating_add(memory_bytes) > limit {
+                return Err(state.refuse(QuotaResource::Memory, limit));
+            }
+        }
+        if let Some(rate) = quota.queries_per_second {
+            let bucket = state.bucket.get_or_insert_with(|| TokenBucket::new(rate));
+            if !bucket.try_take() {
+                return Err(state.refuse(QuotaResource::QueriesPerSecond, rate as u64));
+            }
+        }
+        state.usage.active_queries += 1;
+        state.usage.memory_bytes += memory_bytes;
+        state.usage.queries += 1;
+        Ok(QueryPermit { governor: Arc::clone(self), tenant: tenant.to_string(), memory_bytes })
+    }
+
+    /// Refuse a write once `tenant` has used its storage quota. The check is
+    /// made before the write, so the write that crosses the quota completes
+    /// and the next one is refused.
+    pub fn check_storage(&self, tenant: &str) -> Result<(), QuotaError> {
+        let Some(limit) = self.quota(tenant).storage_bytes else {
+            return Ok(());
+        };
+        let mut tenants = self.tenants.lock();
+        let state = Self::state(&mut tenants, tenant);
+        if state.usage.storage_bytes >= limit {
+            return Err(state.refuse(QuotaResource::Storage, limit));
+        }
+        Ok(())
+    }
+
+    /// Charge `bytes` written by `tenant` to its storage
+    pub fn charge_storage(&self, tenant: &str, bytes: u64) {
+        let mut tenants = self.tenants.lock();
+        let state = Self::state(&mut tenants, tenant);
+        state.usage.storage_bytes = state.usage.storage_bytes.saturating_add(bytes);
+    }
+
+    /// Credit `bytes` deleted by `tenant` back to its storage
+    pub fn release_storage(&self, tenant: &str, bytes: u64) {
+        let mut tenants = self.tenants.lock();
+        let state = Self::state(&mut tenants, tenant);
+        state.usage.storage_bytes = state.usage.storage_bytes.saturating_sub(bytes);
+    }
+
+    /// Usage of `tenant`, zero if it has not been seen
+    pub fn usage(&self, tenant: &str) -> TenantUsage {
+        self.tenants.lock().get(tenant)
+            .map(|state| state.usage.clone())
+            .unwrap_or_else(|| TenantUsage { tenant: tenant.to_string(), ..TenantUsage::default() })
+    }
+
+    /// Usage of every tenant seen, by name
+    pub fn all_usage(&self) -> Vec<TenantUsage> {
+        let mut usage: Vec<TenantUsage> = self.tenants.lock().values().map(|state| state.usage.clone()).collect();
+        usage.sort_by(|a, b| a.tenant.cmp(&b.tenant));
+        usage
+    }
+
+    fn state<'a>(tenants: &'a mut HashMap<String, TenantState>, tenant: &str) -> &'a mut TenantState {
+        tenants.entry(tenant.to_string()).or_insert_with(|| TenantState {
+            usage: TenantUsage { tenant: tenant.to_string(), ..TenantUsage::default() },
+            bucket: None,
+        })
+    }
+}
+
+/// An admitted connection; dropping it frees the tenant's slot
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    governor: Arc<TenantGovernor>,
+    tenant: String,
+}
+
+impl ConnectionPermit {
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some(state) = self.governor.tenants.lock().get_mut(&self.tenant) {
+            state.usage.connections = state.usage.connections.saturating_sub(1);
+        }
+    }
+}
+
+/// An admitted statement; dropping it frees its query slot and memory
+#[derive(Debug)]
+pub struct QueryPermit {
+    governor: Arc<TenantGovernor>,
+    tenant: String,
+    memory_bytes: u64,
+}
+
+impl QueryPermit {
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+}
+
+impl Drop for QueryPermit {
+    fn drop(&mut self) {
+        if let Some(state) = self.governor.tenants.lock().get_mut(&self.tenant) {
+            state.usage.active_queries = state.usage.active_queries.saturating_sub(1);
+            state.usage.memory_bytes = state.usage.memory_bytes.saturating_sub(self.memory_bytes);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Top-N Heap Sort
+//!
+//! `ORDER BY ... LIMIT n` needs only the first `n` rows of the ordering, so
+//! rather than sorting every row it keeps a bounded max-heap of the best `n`
+//! seen so far. Each row is compared with the worst row kept and either
+//! replaces it or is dropped, for O(total log n) time and O(n) memory instead
+//! of a full sort's O(total log total) and O(total).
+//!
+//! Rows that tie on every sort key keep their input order, so the result is
+//! exactly that of a stable sort followed by the limit.
+
+use std::cmp::Ordering;
+
+/// The first `limit` items of a stable sort by `compare`, kept as they arrive
+pub struct TopN<T, F> {
+    limit: usize,
+    /// Max-heap of (item, arrival) ordered worst first
+    heap: Vec<(T, u64)>,
+    arrivals: u64,
+    compare: F,
+}
+
+impl<T, F> TopN<T, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    pub fn new(limit: usize, compare: F) -> Self {
+        Self { limit, heap: Vec::with_capacity(limit.min(1024)), arrivals: 0, compare }
+    }
+
+    /// Offer one item; it is kept only if it ranks among the best `limit`
+    pub fn push(&mut self, item: T) {
+        let arrival = self.arrivals;
+        self.arrivals += 1;
+        if self.limit == 0 {
+            return;
+        }
+        if self.heap.len() < self.limit {
+            self.heap.push((item, arrival));
+            self.sift_up(self.heap.len() - 1);
+            return;
+        }
+        // A tie with the worst kept item loses, having arrived later
+        if (self.compare)(&item, &self.heap[0].0) == Ordering::Less {
+            self.heap[0] = (item, arrival);
+            self.sift_down(0);
+        }
+    }
+
+    /// Items kept so far
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Items offered so far, kept or not
+    pub fn offered(&self) -> u64 {
+        self.arrivals
+    }
+
+    /// The kept items, best first
+    pub fn into_sorted(self) -> Vec<T> {
+        let Self { mut heap, mut compare, .. } = self;
+        heap.sort_by(|a, b| Self::rank(&mut compare, a, b));
+        heap.into_iter().map(|(item, _)| item).collect()
+    }
+
+    /// Sort key order, then arrival order
+    fn rank(compare: &mut F, a: &(T, u64), b: &(T, u64)) -> Ordering {
+        compare(&a.0, &b.0).then(a.1.cmp(&b.1))
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if Self::rank(&mut self.compare, &self.heap[index], &self.heap[parent]) != Ordering::Greater {
+                break;
+            }
+            self.heap.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut worst = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.heap.len()
+                    && Self::rank(&mut self.compare, &self.heap[child], &self.heap[worst]) == Ordering::Greater
+                {
+                    worst = child;
+                }
+            }
+            if worst == index {
+                return;
+            }
+            self.heap.swap(index, worst);
+            index = worst;
+        }
+    }
+}
+
+/// The first `limit` of `items` in a stable sort by `compare`
+pub fn top_n_by<T, I, F>(items: I, limit: usize, compare: F) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut top = TopN::new(limit, compare);
+    for item in items {
+        top.push(item);
+    }
+    top.into_sorted()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random sequence
+    fn random_values(count: usize, seed: u64) -> Vec<u64> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_top_n_matches_sort_and_limit_on_random_data() {
+        // Few distinct keys, so most rows tie on the first and many on both
+        let rows: Vec<(u64, u64, usize)> = random_values(5000, 0x5eed).into_iter()
+            .enumerate()
+            .map(|(position, value)| (value % 7, (value >> 8) % 5, position))
+            .collect();
+        let compare = |a: &(u64, u64, usize), b: &(u64, u64, usize)| a.0.cmp(&b.0).then(b.1.cmp(&a.1));
+
+        for limit in [0, 1, 10, 999, 5000, 6000] {
+            let mut expected = rows.clone();
+            expected.sort_by(compare);
+            expected.truncate(limit);
+            assert_eq!(top_n_by(rows.clone(), limit, compare), expected, "limit {}", limit);
+        }
+    }
+
+    #[test]
+    fn test_top_n_keeps_at_most_limit_items() {
+        let mut top = TopN::new(3, |a: &u64, b: &u64| a.cmp(b));
+        for value in random_values(10_000, 42) {
+            top.push(value);
+            assert!(top.len() <= 3);
+        }
+        assert_eq!(top.offered(), 10_000);
+
+        let mut expected = random_values(10_000, 42);
+        expected.sort();
+        assert_eq!(top.into_sorted(), expected[..3].to_vec());
+    }
+}
@@ -0,0 +1,165 @@
+//! TTL Reaper
+//!
+//! A table created `WITH (ttl_column = expires_at)` expires each row at the
+//! time held in `expires_at`; a NULL expiry never expires. Scans stop
+//! returning a row as soon as it expires, and the reaper removes expired rows
+//! in the background:
+//!
+//! 1. One scan collects the keys of a table's expired rows
+//! 2. They are deleted `reaper_batch_size` at a time, one transaction per
+//!    batch, yielding between batches so a large backlog never holds up
+//!    foreground writes for long
+//! 3. Deleted rows are purged from storage once no running transaction can
+//!    still see them; rows a long transaction pins are retried next pass
+//!
+//! Dependent materialized views are marked stale, since their contents may
+//! include the removed rows.
+
+use std::sync::Arc;
+use std::time::Duration;
+use parking_lot::Mutex;
+use crate::catalog::TableCatalog;
+use crate::config::TtlConfig;
+use crate::core::AuroraResult;
+use crate::mvcc::transaction::IsolationLevel;
+use crate::storage::table_storage::{PurgeOutcome, TableStorage};
+use crate::types::DataValue;
+use super::materialized_view::MaterializedViewRegistry;
+
+/// Outcome of one reaper pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TtlReapReport {
+    /// Tables with a TTL column that were checked
+    pub tables_scanned: usize,
+    /// Expired rows deleted
+    pub rows_deleted: u64,
+    /// Deleted rows physically removed from storage
+    pub rows_purged: u64,
+    /// Delete transactions committed
+    pub batches: u64,
+}
+
+/// Deletes and purges expired rows
+pub(crate) struct TtlReaper {
+    table_storage: Arc<TableStorage>,
+    catalog: Arc<TableCatalog>,
+    materialized_views: Arc<MaterializedViewRegistry>,
+    config: TtlConfig,
+    /// Rows deleted but not yet purged, because a running transaction could
+    /// still see them
+    pending_purge: Mutex<Vec<(String, DataValue)>>,
+    /// Serializes passes, so a manual pass never races the background one
+    pass: tokio::sync::Mutex<()>,
+}
+
+impl TtlReaper {
+    pub fn new(
+        table_storage: Arc<TableStorage>,
+        catalog: Arc<TableCatalog>,
+        materialized_views: Arc<MaterializedViewRegistry>,
+        config: TtlConfig,
+    ) -> Self {
+        Self {
+            table_storage,
+            catalog,
+            materialized_views,
+            config,
+            pending_purge: Mutex::new(Vec::new()),
+            pass: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Run a pass every `reaper_interval_ms` until the task is aborted
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(self.config.reaper_interval_ms));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match self.reap().await {
+                    Ok(report) if report.rows_deleted > 0 || report.rows_purged > 0 => {
+                        log::info!("TTL reaper deleted {} and purged {} expired rows", report.rows_deleted, report.rows_purged);
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("TTL reaper pass failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Delete every row that has expired by now and purge what can be purged
+    pub async fn reap(&self) -> AuroraResult<TtlReapReport> {
+        let _pass = self.pass.lock().await;
+        let mut report = TtlReapReport::default();
+
+        // Rows deleted in earlier passes first: whatever pinned them may be gone
+        let pending = std::mem::take(&mut *self.pending_purge.lock());
+        self.purge(pending, &mut report).await?;
+
+        for (table, ttl_column) in self.catalog.ttl_tables().await {
+            report.tables_scanned += 1;
+            self.reap_table(&table, &ttl_column, &mut report).await?;
+        }
+
+        Ok(report)
+    }
+
+    async fn reap_table(&self, table: &str, ttl_column: &str, report: &mut TtlReapReport) -> AuroraResult<()> {
+        let now = chrono::Utc::now();
+        let transaction_manager = &self.table_storage.transaction_manager;
+
+        let scan = transaction_manager.begin_transaction(IsolationLevel::ReadCommitted).await?;
+        let expired = self.table_storage.expired_keys(&scan, table, ttl_column, now).await;
+        transaction_manager.commit_transaction(scan.id).await?;
+        let expired = expired?;
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        let batch_size = self.config.reaper_batch_size.max(1);
+        let mut deleted_keys = Vec::with_capacity(expired.len());
+        for batch in expired.chunks(batch_size) {
+            let transaction = transaction_manager.begin_transaction(IsolationLevel::ReadCommitted).await?;
+            let mut deleted = Vec::with_capacity(batch.len());
+            for primary_key in batch {
+                // A row refreshed or replaced since the scan is left alone
+                match self.table_storage.delete_expired_row(&transaction, table, primary_key, ttl_column, now).await {
+                    Ok(true) => deleted.push(primary_key.clone()),
+                    Ok(false) => {}
+                    Err(e) => log::debug!("TTL reaper skipped {:?} in '{}': {}", primary_key, table, e),
+                }
+            }
+            transaction_manager.commit_transaction(transaction.id).await?;
+            self.table_storage.bump_data_version(table);
+
+            report.batches += 1;
+            report.rows_deleted += deleted.len() as u64;
+            deleted_keys.extend(deleted);
+            tokio::task::yield_now().await;
+        }
+
+        if !deleted_keys.is_empty() {
+            for dependent in self.materialized_views.lock_dependents(table).await {
+                dependent.view.mark_stale(deleted_keys.len() as u64);
+            }
+        }
+
+        let deleted_keys = deleted_keys.into_iter().map(|key| (table.to_string(), key)).collect();
+        self.purge(deleted_keys, report).await
+    }
+
+    /// Physically remove deleted rows, keeping the ones still visible to a
+    /// running transaction for the next pass
+    async fn purge(&self, rows: Vec<(String, DataValue)>, report: &mut TtlReapReport) -> AuroraResult<()> {
+        let mut pinned = Vec::new();
+        for (table, primary_key) in rows {
+            match self.table_storage.purge_dead_tuple(&table, &primary_key).await? {
+                PurgeOutcome::Purged => report.rows_purged += 1,
+                PurgeOutcome::Pinned => pinned.push((table, primary_key)),
+                PurgeOutcome::Live => {}
+            }
+        }
+        self.pending_purge.lock().extend(pinned);
+        Ok(())
+    }
+}
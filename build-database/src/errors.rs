@@ -94,6 +94,7 @@ pub enum ErrorCode {
     QueryTimeout = 5002,
     QueryCancelled = 5003,
     QueryInvalidParameters = 5004,
+    QueryQuotaExceeded = 5005,
 
     // Transaction errors (6000-6999)
     TransactionDeadlock = 6001,
@@ -118,6 +119,8 @@ pub enum ErrorCode {
     ValidationInvalidFormat = 9002,
     ValidationConstraintViolation = 9003,
     ValidationTypeMismatch = 9004,
+    ValidationNumericOverflow = 9005,
+    ValidationDivisionByZero = 9006,
 }
 
 /// Core AuroraDB error with comprehensive context
@@ -158,6 +161,29 @@ pub struct AuroraError {
 
     /// Recovery suggestions
     pub recovery_suggestions: Vec<String>,
+
+    /// Plan operators a query execution error propagated through,
+    /// innermost (the one that failed) first
+    #[serde(default)]
+    pub operator_spans: Vec<OperatorSpan>,
+
+    /// Expression whose evaluation failed, as SQL
+    #[serde(default)]
+    pub failed_expression: Option<String>,
+
+    /// Operand values the expression failed on; text is never included
+    #[serde(default)]
+    pub offending_value: Option<String>,
+}
+
+/// One plan operator a query execution error passed through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorSpan {
+    /// Operator type, as EXPLAIN labels it
+    pub operator: String,
+
+    /// Position in the plan, counting nodes root first as EXPLAIN lists them
+    pub plan_node: usize,
 }
 
 impl AuroraError {
@@ -178,6 +204,9 @@ impl AuroraError {
             timestamp: chrono::Utc::now(),
             request_id: None,
             recovery_suggestions: Vec::new(),
+            operator_spans: Vec::new(),
+            failed_expression: None,
+            offending_value: None,
         }
     }
 
@@ -211,6 +240,29 @@ impl AuroraError {
         self
     }
 
+    /// Record that the error propagated through a plan operator; called by
+    /// each operator on the way out, so the failing one comes first
+    pub fn in_operator(mut self, operator: impl Into<String>, plan_node: usize) -> Self {
+        self.operator_spans.push(OperatorSpan { operator: operator.into(), plan_node });
+        self
+    }
+
+    /// Record the expression that failed and the operands it failed on.
+    /// Only the innermost expression is kept: enclosing expressions that
+    /// propagate the error leave it as it is.
+    pub fn in_expression(mut self, expression: impl Into<String>, value: Option<String>) -> Self {
+        if self.failed_expression.is_none() {
+            self.failed_expression = Some(expression.into());
+            self.offending_value = value;
+        }
+        self
+    }
+
+    /// Plan operator the error arose in
+    pub fn failing_operator(&self) -> Option<&OperatorSpan> {
+        self.operator_spans.first()
+    }
+
     /// Chain errors
     pub fn caused_by(mut self, source: AuroraError) -> Self {
         self.source = Some(Box::new(source));
@@ -321,7 +373,8 @@ impl AuroraError {
 
             // Query errors
             ErrorCode::QuerySyntaxError | ErrorCode::QueryTimeout |
-            ErrorCode::QueryCancelled | ErrorCode::QueryInvalidParameters => {
+            ErrorCode::QueryCancelled | ErrorCode::QueryInvalidParameters |
+            ErrorCode::QueryQuotaExceeded => {
                 (ErrorCategory::Query, ErrorSeverity::Medium)
             }
 
@@ -345,7 +398,8 @@ impl AuroraError {
 
             // Validation errors
             ErrorCode::ValidationRequiredField | ErrorCode::ValidationInvalidFormat |
-            ErrorCode::ValidationConstraintViolation | ErrorCode::ValidationTypeMismatch => {
+            ErrorCode::ValidationConstraintViolation | ErrorCode::ValidationTypeMismatch |
+            ErrorCode::ValidationNumericOverflow | ErrorCode::ValidationDivisionByZero => {
                 (ErrorCategory::Validation, ErrorSeverity::Low)
             }
         }
@@ -361,6 +415,17 @@ impl fmt::Display for AuroraError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[{:?}] {}: {}", self.code, self.category, self.message)?;
 
+        if let Some(ref expression) = self.failed_expression {
+            write!(f, " in expression `{}`", expression)?;
+            if let Some(ref value) = self.offending_value {
+                write!(f, " with {}", value)?;
+            }
+        }
+
+        if let Some(span) = self.failing_operator() {
+            write!(f, " at {} (plan node {})", span.operator, span.plan_node)?;
+        }
+
         if let Some(ref operation) = self.operation {
             write!(f, " (during {})", operation)?;
         }
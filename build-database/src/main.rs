@@ -97,6 +97,7 @@ fn create_database_config() -> DatabaseConfig {
                 adaptive_threshold: 100_000, // Switch engines at 100K rows
                 vector_threshold: 0.1, // 10% vector columns triggers hybrid
             },
+            columnar: aurora_db::config::ColumnarConfig::default(),
             selection_strategy: "workload_based".to_string(),
         },
         transaction: TransactionConfig {
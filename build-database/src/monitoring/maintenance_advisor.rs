@@ -0,0 +1,520 @@
+//! Autovacuum and Compaction Tuning Advisor
+//!
+//! Reads a history of per-table write rates and bloat, and of how long
+//! vacuum and compaction runs took, and recommends new maintenance
+//! settings: a lower vacuum scale factor and threshold for tables whose
+//! bloat outruns vacuum, a higher cost limit where vacuums run too long,
+//! more background workers when they are busy most of the time, and a
+//! higher scale factor when every table is vacuumed far more often than it
+//! needs. Each suggestion names the setting, its current and suggested
+//! values, the tables behind it and the expected effect.
+//!
+//! The advisor is advisory: `advise` never changes anything, and `tune`
+//! applies its suggestions only when `AdvisorConfig::auto_apply` is set.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+
+use crate::config::{BloatConfig, LSMConfig};
+
+/// Samples and runs kept when no capacity is configured
+pub const DEFAULT_ADVISOR_HISTORY: usize = 10_000;
+
+/// Highest cost limit the advisor suggests
+const MAX_COST_LIMIT: u32 = 10_000;
+
+/// Most background workers the advisor suggests
+const MAX_WORKERS: usize = 32;
+
+/// Advisor thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisorConfig {
+    /// Apply suggestions in `tune` instead of only reporting them
+    pub auto_apply: bool,
+    /// Samples and runs kept; older ones are dropped first
+    pub history_capacity: usize,
+    /// A table falls behind once its peak bloat exceeds the scale factor by
+    /// this factor
+    pub behind_margin: f64,
+    /// A table falls behind once its bloat grows faster than this share
+    /// per hour across the history
+    pub max_bloat_growth_per_hour: f64,
+    /// Vacuums of a falling-behind table that take longer than this get a
+    /// higher cost limit
+    pub max_vacuum_duration: Duration,
+    /// Share of their time workers may be busy before more are suggested
+    pub busy_worker_share: f64,
+    /// Writes per hour, as a share of a table's live rows, below which a
+    /// table counts as quiet
+    pub low_churn_per_hour: f64,
+    /// Lowest scale factor the advisor suggests
+    pub min_scale_factor: f64,
+    /// Highest scale factor the advisor suggests
+    pub max_scale_factor: f64,
+    /// Lowest vacuum threshold the advisor suggests
+    pub min_threshold: u64,
+}
+
+impl Default for AdvisorConfig {
+    fn default() -> Self {
+        Self {
+            auto_apply: false,
+            history_capacity: DEFAULT_ADVISOR_HISTORY,
+            behind_margin: 1.5,
+            max_bloat_growth_per_hour: 0.05,
+            max_vacuum_duration: Duration::from_secs(60),
+            busy_worker_share: 0.75,
+            low_churn_per_hour: 0.05,
+            min_scale_factor: 0.01,
+            max_scale_factor: 0.5,
+            min_threshold: 10,
+        }
+    }
+}
+
+/// Autovacuum and compaction settings the advisor tunes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceSettings {
+    /// Share of a table that must be dead before it is vacuumed
+    pub vacuum_scale_factor: f64,
+    /// Dead rows a table needs before it is vacuumed at all
+    pub vacuum_threshold: u64,
+    /// Cost a vacuum may spend before it pauses
+    pub vacuum_cost_limit: u32,
+    /// Vacuum and compaction jobs run at once
+    pub max_workers: usize,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            vacuum_scale_factor: 0.2,
+            vacuum_threshold: 50,
+            vacuum_cost_limit: 200,
+            max_workers: 3,
+        }
+    }
+}
+
+impl MaintenanceSettings {
+    /// Settings of a configuration: the bloat thresholds VACUUM is
+    /// recommended at and the LSM compaction threads
+    pub fn from_config(bloat: &BloatConfig, lsm: &LSMConfig) -> Self {
+        Self {
+            vacuum_scale_factor: bloat.vacuum_bloat_ratio,
+            vacuum_threshold: bloat.vacuum_min_dead_tuples,
+            max_workers: lsm.compaction_threads,
+            ..Self::default()
+        }
+    }
+
+    fn set(&mut self, setting: MaintenanceSetting, value: f64) {
+        match setting {
+            MaintenanceSetting::VacuumScaleFactor => self.vacuum_scale_factor = value,
+            MaintenanceSetting::VacuumThreshold => self.vacuum_threshold = value as u64,
+            MaintenanceSetting::VacuumCostLimit => self.vacuum_cost_limit = value as u32,
+            MaintenanceSetting::MaxWorkers => self.max_workers = value as usize,
+        }
+    }
+}
+
+/// One of the `MaintenanceSettings`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MaintenanceSetting {
+    VacuumScaleFactor,
+    VacuumThreshold,
+    VacuumCostLimit,
+    MaxWorkers,
+}
+
+impl MaintenanceSetting {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintenanceSetting::VacuumScaleFactor => "vacuum_scale_factor",
+            MaintenanceSetting::VacuumThreshold => "vacuum_threshold",
+            MaintenanceSetting::VacuumCostLimit => "vacuum_cost_limit",
+            MaintenanceSetting::MaxWorkers => "max_workers",
+        }
+    }
+}
+
+impl fmt::Display for MaintenanceSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A table's size and bloat at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableWorkloadSample {
+    pub table_name: String,
+    pub at: SystemTime,
+    pub live_tuples: u64,
+    pub dead_tuples: u64,
+    /// Rows inserted, updated or deleted since the table's previous sample
+    pub writes: u64,
+    /// Dead and free space as a share of the table
+    pub bloat_ratio: f64,
+}
+
+/// Kind of background maintenance run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaintenanceKind {
+    Vacuum,
+    Compaction,
+}
+
+/// One finished vacuum or compaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub kind: MaintenanceKind,
+    pub table_name: String,
+    pub started_at: SystemTime,
+    pub duration: Duration,
+}
+
+/// How well maintenance keeps up with a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableVerdict {
+    /// Bloat outruns vacuum
+    FallingBehind,
+    Healthy,
+    /// Quiet and barely bloated, so vacuumed more often than it needs
+    OverMaintained,
+}
+
+/// What the history says about one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableAnalysis {
+    pub table_name: String,
+    /// Rows written per second
+    pub write_rate: f64,
+    /// Rows written per hour as a share of the table's live rows
+    pub churn_per_hour: f64,
+    pub peak_bloat: f64,
+    /// Change of the bloat ratio per hour, fitted over the history
+    pub bloat_trend_per_hour: f64,
+    pub vacuum_runs: usize,
+    pub mean_vacuum_duration: Option<Duration>,
+    pub verdict: TableVerdict,
+}
+
+/// A suggested change of one setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingSuggestion {
+    pub setting: MaintenanceSetting,
+    pub current: f64,
+    pub suggested: f64,
+    /// Tables whose history calls for the change
+    pub tables: Vec<String>,
+    pub reason: String,
+    pub expected_impact: String,
+}
+
+impl fmt::Display for SettingSuggestion {
+    /// As a config line, commented with the current value and the impact
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}  # was {}: {}", self.setting, self.suggested, self.current, self.expected_impact)
+    }
+}
+
+/// Analysis of the history and the settings it calls for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisorReport {
+    pub tables: Vec<TableAnalysis>,
+    pub suggestions: Vec<SettingSuggestion>,
+    /// Share of the history's span the maintenance workers were busy
+    pub worker_utilization: f64,
+    /// Whether the suggestions were applied
+    pub applied: bool,
+}
+
+impl AdvisorReport {
+    pub fn suggestion(&self, setting: MaintenanceSetting) -> Option<&SettingSuggestion> {
+        self.suggestions.iter().find(|suggestion| suggestion.setting == setting)
+    }
+}
+
+/// Keeps the workload history and turns it into tuning suggestions
+#[derive(Debug)]
+pub struct MaintenanceAdvisor {
+    config: AdvisorConfig,
+    samples: Mutex<VecDeque<TableWorkloadSample>>,
+    runs: Mutex<VecDeque<MaintenanceRun>>,
+}
+
+impl Default for MaintenanceAdvisor {
+    fn default() -> Self {
+        Self::new(AdvisorConfig::default())
+    }
+}
+
+impl MaintenanceAdvisor {
+    pub fn new(config: AdvisorConfig) -> Self {
+        Self {
+            config,
+            samples: Mutex::new(VecDeque::new()),
+            runs: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn config(&self) -> &AdvisorConfig {
+        &self.config
+    }
+
+    pub fn record_sample(&self, sample: TableWorkloadSample) {
+        push_bounded(&mut self.samples.lock(), sample, self.config.history_capacity);
+    }
+
+    pub fn record_run(&self, run: MaintenanceRun) {
+        push_bounded(&mut self.runs.lock(), run, self.config.history_capacity);
+    }
+
+    /// Suggestions for `current` from the history, changing nothing
+    pub fn advise(&self, current: &MaintenanceSettings) -> AdvisorReport {
+        let samples: Vec<TableWorkloadSample> = self.samples.lock().iter().cloned().collect();
+        let runs: Vec<MaintenanceRun> = self.runs.lock().iter().cloned().collect();
+
+        let mut by_table: BTreeMap<&str, Vec<&TableWorkloadSample>> = BTreeMap::new();
+        for sample in &samples {
+            by_table.entry(sample.table_name.as_str()).or_default().push(sample);
+        }
+        let tables: Vec<TableAnalysis> = by_table.into_iter()
+            .map(|(table, mut samples)| {
+                samples.sort_by_key(|sample| sample.at);
+                self.analyze(table, &samples, &runs, current)
+            })
+            .collect();
+
+        let worker_utilization = utilization(&samples, &runs);
+        let mut suggestions = Vec::new();
+        self.suggest_scale_factor(&tables, current, &mut suggestions);
+        self.suggest_cost_limit(&tables, current, &mut suggestions);
+        self.suggest_workers(&tables, worker_utilization, current, &mut suggestions);
+
+        AdvisorReport { tables, suggestions, worker_utilization, applied: false }
+    }
+
+    /// Suggestions for `settings`, applied to them only under `auto_apply`
+    pub fn tune(&self, settings: &mut MaintenanceSettings) -> AdvisorReport {
+        let mut report = self.advise(settings);
+        if self.config.auto_apply && !report.suggestions.is_empty() {
+            for suggestion in &report.suggestions {
+                log::info!("Maintenance advisor applied {}", suggestion);
+                settings.set(suggestion.setting, suggestion.suggested);
+            }
+            report.applied = true;
+        }
+        report
+    }
+
+    fn analyze(
+        &self,
+        table: &str,
+        samples: &[&TableWorkloadSample],
+        runs: &[MaintenanceRun],
+        current: &MaintenanceSettings,
+    ) -> TableAnalysis {
+        let (first, last) = (samples[0], samples[samples.len() - 1]);
+        let span = last.at.duration_since(first.at).unwrap_or_default().as_secs_f64().max(1.0);
+        // The first sample's writes happened before the history starts
+        let writes: u64 = samples[1..].iter().map(|sample| sample.writes).sum();
+        let write_rate = writes as f64 / span;
+        let mean_live = samples.iter().map(|sample| sample.live_tuples as f64).sum::<f64>() / samples.len() as f64;
+        let churn_per_hour = write_rate * 3600.0 / mean_live.max(1.0);
+        let peak_bloat = samples.iter().map(|sample| sample.bloat_ratio).fold(0.0, f64::max);
+        let bloat_trend_per_hour = slope_per_hour(samples, first.at);
+
+        let vacuums: Vec<Duration> = runs.iter()
+            .filter(|run| run.kind == MaintenanceKind::Vacuum && run.table_name == table)
+            .map(|run| run.duration)
+            .collect();
+        let mean_vacuum_duration = (!vacuums.is_empty())
+            .then(|| vacuums.iter().sum::<Duration>() / vacuums.len() as u32);
+
+        let scale_factor = current.vacuum_scale_factor;
+        let verdict = if peak_bloat > scale_factor * self.config.behind_margin
+            || bloat_trend_per_hour > self.config.max_bloat_growth_per_hour
+        {
+            TableVerdict::FallingBehind
+        } else if peak_bloat < scale_factor / 4.0 && churn_per_hour < self.config.low_churn_per_hour {
+            TableVerdict::OverMaintained
+        } else {
+            TableVerdict::Healthy
+        };
+
+        TableAnalysis {
+            table_name: table.to_string(),
+            write_rate,
+            churn_per_hour,
+            peak_bloat,
+            bloat_trend_per_hour,
+            vacuum_runs: vacuums.len(),
+            mean_vacuum_duration,
+            verdict,
+        }
+    }
+
+    /// Vacuum earlier when tables fall behind, later when every table is
+    /// over-maintained
+    fn suggest_scale_factor(&self, tables: &[TableAnalysis], current: &MaintenanceSettings, suggestions: &mut Vec<SettingSuggestion>) {
+        let scale_factor = current.vacuum_scale_factor;
+        let behind: Vec<&TableAnalysis> = tables.iter().filter(|table| table.verdict == TableVerdict::FallingBehind).collect();
+
+        if let Some(worst) = behind.iter().map(|table| table.peak_bloat).reduce(f64::max) {
+            let names: Vec<String> = behind.iter().map(|table| table.table_name.clone()).collect();
+            // Bloat overshoots the trigger by as much again while vacuum
+            // catches up, so trigger that much earlier
+            let overshoot = (worst - scale_factor).max(0.0);
+            let suggested = round_to(
+                (scale_factor - overshoot).max(scale_factor / 4.0).max(self.config.min_scale_factor),
+                1000.0,
+            );
+            if suggested < scale_factor {
+                suggestions.push(SettingSuggestion {
+                    setting: MaintenanceSetting::VacuumScaleFactor,
+                    current: scale_factor,
+                    suggested,
+                    tables: names.clone(),
+                    reason: format!("bloat peaked at {:.0}% against a {:.0}% trigger", worst * 100.0, scale_factor * 100.0),
+                    expected_impact: format!(
+                        "peak bloat from {:.0}% to about {:.0}%",
+                        worst * 100.0, (suggested + overshoot) * 100.0
+                    ),
+                });
+            }
+
+            let threshold = ((current.vacuum_threshold / 2).max(self.config.min_threshold)).min(current.vacuum_threshold);
+            if threshold < current.vacuum_threshold {
+                suggestions.push(SettingSuggestion {
+                    setting: MaintenanceSetting::VacuumThreshold,
+                    current: current.vacuum_threshold as f64,
+                    suggested: threshold as f64,
+                    tables: names,
+                    reason: "bloat outruns vacuum".to_string(),
+                    expected_impact: format!(
+                        "vacuum starts after {} dead rows instead of {}",
+                        threshold, current.vacuum_threshold
+                    ),
+                });
+            }
+        } else if !tables.is_empty() && tables.iter().all(|table| table.verdict == TableVerdict::OverMaintained) {
+            let suggested = round_to((scale_factor * 2.0).min(self.config.max_scale_factor), 1000.0);
+            if suggested > scale_factor {
+                let worst = tables.iter().map(|table| table.peak_bloat).fold(0.0, f64::max);
+                suggestions.push(SettingSuggestion {
+                    setting: MaintenanceSetting::VacuumScaleFactor,
+                    current: scale_factor,
+                    suggested,
+                    tables: tables.iter().map(|table| table.table_name.clone()).collect(),
+                    reason: format!("bloat never passed {:.0}% on quiet tables", worst * 100.0),
+                    expected_impact: "about half as many vacuum runs, with bloat still under the trigger".to_string(),
+                });
+            }
+        }
+    }
+
+    /// Let slow vacuums of falling-behind tables do more work between pauses
+    fn suggest_cost_limit(&self, tables: &[TableAnalysis], current: &MaintenanceSettings, suggestions: &mut Vec<SettingSuggestion>) {
+        let target = self.config.max_vacuum_duration;
+        let slow: Vec<(&TableAnalysis, Duration)> = tables.iter()
+            .filter(|table| table.verdict == TableVerdict::FallingBehind)
+            .filter_map(|table| table.mean_vacuum_duration.filter(|duration| *duration > target).map(|duration| (table, duration)))
+            .collect();
+        let Some(slowest) = slow.iter().map(|(_, duration)| *duration).max() else {
+            return;
+        };
+
+        // Vacuum time scales inversely with the cost limit
+        let factor = slowest.as_secs_f64() / target.as_secs_f64();
+        let suggested = ((current.vacuum_cost_limit as f64 * factor / 100.0).ceil() * 100.0).min(MAX_COST_LIMIT as f64);
+        if suggested <= current.vacuum_cost_limit as f64 {
+            return;
+        }
+        let expected = slowest.as_secs_f64() * current.vacuum_cost_limit as f64 / suggested;
+        suggestions.push(SettingSuggestion {
+            setting: MaintenanceSetting::VacuumCostLimit,
+            current: current.vacuum_cost_limit as f64,
+            suggested,
+            tables: slow.iter().map(|(table, _)| table.table_name.clone()).collect(),
+            reason: format!("vacuums of falling-behind tables average up to {:.0}s", slowest.as_secs_f64()),
+            expected_impact: format!("slowest vacuum from {:.0}s to about {:.0}s", slowest.as_secs_f64(), expected),
+        });
+    }
+
+    /// More workers when the current ones are busy most of the time
+    fn suggest_workers(&self, tables: &[TableAnalysis], utilization: f64, current: &MaintenanceSettings, suggestions: &mut Vec<SettingSuggestion>) {
+        let workers = current.max_workers.max(1);
+        let busy_share = self.config.busy_worker_share;
+        if utilization <= workers as f64 * busy_share {
+            return;
+        }
+        let suggested = ((utilization / busy_share).ceil() as usize).max(workers + 1).min(MAX_WORKERS);
+        if suggested <= workers {
+            return;
+        }
+        suggestions.push(SettingSuggestion {
+            setting: MaintenanceSetting::MaxWorkers,
+            current: workers as f64,
+            suggested: suggested as f64,
+            tables: tables.iter()
+                .filter(|table| table.verdict == TableVerdict::FallingBehind)
+                .map(|table| table.table_name.clone())
+                .collect(),
+            reason: format!("maintenance kept {:.1} workers busy on average", utilization),
+            expected_impact: format!(
+                "worker utilization from {:.0}% to {:.0}%",
+                utilization / workers as f64 * 100.0,
+                utilization / suggested as f64 * 100.0
+            ),
+        });
+    }
+}
+
+fn push_bounded<T>(history: &mut VecDeque<T>, item: T, capacity: usize) {
+    if history.len() >= capacity.max(1) {
+        history.pop_front();
+    }
+    history.push_back(item);
+}
+
+/// Least-squares slope of the bloat ratio, per hour since `start`
+fn slope_per_hour(samples: &[&TableWorkloadSample], start: SystemTime) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let points: Vec<(f64, f64)> = samples.iter()
+        .map(|sample| {
+            let hours = sample.at.duration_since(start).unwrap_or_default().as_secs_f64() / 3600.0;
+            (hours, sample.bloat_ratio)
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance == 0.0 { 0.0 } else { covariance / variance }
+}
+
+/// Mean number of maintenance runs in progress over the history's span
+fn utilization(samples: &[TableWorkloadSample], runs: &[MaintenanceRun]) -> f64 {
+    let times = samples.iter().map(|sample| sample.at)
+        .chain(runs.iter().map(|run| run.started_at))
+        .chain(runs.iter().map(|run| run.started_at + run.duration));
+    let (Some(start), Some(end)) = (times.clone().min(), times.max()) else {
+        return 0.0;
+    };
+    let span = end.duration_since(start).unwrap_or_default().as_secs_f64();
+    if span == 0.0 {
+        return 0.0;
+    }
+    runs.iter().map(|run| run.duration.as_secs_f64()).sum::<f64>() / span
+}
+
+fn round_to(value: f64, scale: f64) -> f64 {
+    (value * scale).round() / scale
+}
@@ -6,15 +6,24 @@
 //! - Grafana dashboard templates
 //! - Alerting rules and thresholds
 //! - Performance monitoring and anomaly detection
+//! - Escalation events of slow queries
+//! - Autovacuum and compaction tuning advice
 
 pub mod prometheus_metrics;
 pub mod grafana_dashboards;
 pub mod alerting;
 pub mod health_checks;
 pub mod performance_monitor;
+pub mod query_escalation;
+pub mod maintenance_advisor;
 
 pub use prometheus_metrics::*;
 pub use grafana_dashboards::*;
 pub use alerting::*;
 pub use health_checks::*;
-pub use performance_monitor::*;
\ No newline at end of file
+pub use performance_monitor::*;
+pub use query_escalation::{EscalationEvent, EscalationLevel, EscalationMonitor, DEFAULT_ESCALATION_LOG_SIZE};
+pub use maintenance_advisor::{
+    AdvisorConfig, AdvisorReport, MaintenanceAdvisor, MaintenanceKind, MaintenanceRun, MaintenanceSetting,
+    MaintenanceSettings, SettingSuggestion, TableAnalysis, TableVerdict, TableWorkloadSample,
+};
\ No newline at end of file
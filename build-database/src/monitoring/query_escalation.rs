@@ -0,0 +1,130 @@
+//! Query Escalation Events
+//!
+//! Records each step a connection's escalation policy takes against a slow
+//! query (a warning with a plan snapshot, a graceful cancel, a forced kill)
+//! so operators can see which queries and roles keep tripping it. Recent
+//! events are kept in a bounded log; per-level totals are exported as a
+//! Prometheus counter.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+
+use super::prometheus_metrics::{Metric, MetricType};
+
+/// Events kept when none is configured
+pub const DEFAULT_ESCALATION_LOG_SIZE: usize = 256;
+
+/// Escalation step, in the order they are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EscalationLevel {
+    /// Logged with a snapshot of the plan
+    Warn,
+    /// Asked to stop at its next cancellation check
+    Cancel,
+    /// Dropped mid-execution and its connection closed
+    Kill,
+}
+
+impl EscalationLevel {
+    pub const ALL: [EscalationLevel; 3] = [EscalationLevel::Warn, EscalationLevel::Cancel, EscalationLevel::Kill];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EscalationLevel::Warn => "warn",
+            EscalationLevel::Cancel => "cancel",
+            EscalationLevel::Kill => "kill",
+        }
+    }
+}
+
+impl fmt::Display for EscalationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One escalation step taken against a query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationEvent {
+    pub level: EscalationLevel,
+    pub session_id: String,
+    pub role: String,
+    pub query: String,
+    /// How long the query had been running
+    pub elapsed: Duration,
+    /// EXPLAIN output taken at the warning, if the plan could be built
+    pub plan: Option<Vec<String>>,
+    pub at: SystemTime,
+}
+
+/// Bounded log of escalation events with per-level totals
+#[derive(Debug)]
+pub struct EscalationMonitor {
+    capacity: usize,
+    events: Mutex<VecDeque<EscalationEvent>>,
+    totals: [AtomicU64; 3],
+}
+
+impl Default for EscalationMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_ESCALATION_LOG_SIZE)
+    }
+}
+
+impl EscalationMonitor {
+    /// Monitor keeping the latest `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            events: Mutex::new(VecDeque::new()),
+            totals: Default::default(),
+        }
+    }
+
+    pub fn record(&self, event: EscalationEvent) {
+        match event.level {
+            EscalationLevel::Warn => log::warn!(
+                "Query of session {} (role {}) still running after {:?}: {}",
+                event.session_id, event.role, event.elapsed, event.query
+            ),
+            level => log::error!(
+                "Escalated to {} after {:?}, session {} (role {}): {}",
+                level, event.elapsed, event.session_id, event.role, event.query
+            ),
+        }
+        self.totals[event.level as usize].fetch_add(1, Ordering::Relaxed);
+        let mut events = self.events.lock();
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Recent events, oldest first
+    pub fn events(&self) -> Vec<EscalationEvent> {
+        self.events.lock().iter().cloned().collect()
+    }
+
+    /// Events ever recorded at `level`, including ones dropped from the log
+    pub fn total(&self, level: EscalationLevel) -> u64 {
+        self.totals[level as usize].load(Ordering::Relaxed)
+    }
+
+    /// `aurora_query_escalations_total`, one series per level
+    pub fn metrics(&self) -> Vec<Metric> {
+        EscalationLevel::ALL.iter()
+            .map(|level| Metric {
+                name: "aurora_query_escalations_total".to_string(),
+                help: "Escalation steps taken against slow queries".to_string(),
+                metric_type: MetricType::Counter,
+                value: self.total(*level) as f64,
+                labels: HashMap::from([("level".to_string(), level.name().to_string())]),
+                timestamp: None,
+            })
+            .collect()
+    }
+}
@@ -5,6 +5,31 @@
 //! - Multiple concurrent transactions
 //! - Non-blocking reads during writes
 //! - Serializable isolation levels
+//!
+//! ## Isolation guarantees
+//!
+//! Writers never block: a write that collides with an in-flight writer fails
+//! immediately with a retryable conflict instead of waiting for it.
+//!
+//! | Anomaly              | ReadUncommitted | ReadCommitted | RepeatableRead | Serializable |
+//! |----------------------|-----------------|---------------|----------------|--------------|
+//! | Dirty read           | possible        | prevented     | prevented      | prevented    |
+//! | Non-repeatable read  | possible        | possible      | prevented      | prevented    |
+//! | Phantom              | possible        | possible      | prevented      | prevented    |
+//! | Lost update          | possible        | possible      | prevented      | prevented    |
+//! | Write skew           | possible        | possible      | possible       | prevented    |
+//!
+//! - ReadUncommitted sees in-flight writes, but never writes of aborted transactions.
+//! - ReadCommitted sees every transaction committed before each read.
+//! - RepeatableRead reads a snapshot taken at `begin_transaction`, including for
+//!   range scans. Updating a row that changed after the snapshot fails
+//!   (first-updater-wins).
+//! - Serializable adds read/write-set tracking and aborts the committer of any
+//!   pair of concurrent transactions that each read what the other wrote.
+//!   Only such two-transaction cycles are detected, so longer cycles are not
+//!   guaranteed to be caught.
+//!
+//! `tests/isolation_test.rs` checks every cell of the table above.
 
 pub mod transaction;
 pub mod version;
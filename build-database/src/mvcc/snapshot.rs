@@ -32,10 +32,11 @@ impl Snapshot {
     pub fn new(transaction_id: TransactionId, isolation_level: crate::mvcc::transaction::IsolationLevel, txn_manager: &TransactionManager) -> Self {
         let snapshot_timestamp = txn_manager.current_timestamp();
 
-        // Get active transactions at this time
-        let active_transactions = txn_manager.active_transactions.read()
-            .keys()
-            .cloned()
+        // Get active transactions at this time (finished ones stay in the map)
+        let active_transactions: HashSet<TransactionId> = txn_manager.active_transactions.read()
+            .values()
+            .filter(|txn| txn.is_active())
+            .map(|txn| txn.id)
             .collect();
 
         // Calculate xmin and xmax bounds
@@ -96,6 +97,11 @@ impl Snapshot {
         // For Read Committed, we check visibility at the time of each individual read
         // Simplified: use snapshot timestamp as the read timestamp
 
+        // Our own writes are visible unless we deleted the tuple ourselves
+        if xmin == self.transaction_id {
+            return xmax != Some(self.transaction_id);
+        }
+
         // Tuple must be created by a committed transaction visible at read time
         if let Some(txn) = txn_manager.get_transaction(xmin) {
             match txn.state {
@@ -117,6 +123,9 @@ impl Snapshot {
 
         // If tuple was deleted, check if deleting transaction is committed and visible
         if let Some(delete_xid) = xmax {
+            if delete_xid == self.transaction_id {
+                return false; // Deleted by ourselves
+            }
             if let Some(delete_txn) = txn_manager.get_transaction(delete_xid) {
                 match delete_txn.state {
                     crate::mvcc::transaction::TransactionState::Committed => {
@@ -146,6 +155,11 @@ impl Snapshot {
         // For Repeatable Read, we maintain a consistent snapshot
         // We cannot see changes from transactions that committed after our snapshot was taken
 
+        // Our own writes are visible unless we deleted the tuple ourselves
+        if xmin == self.transaction_id {
+            return xmax != Some(self.transaction_id);
+        }
+
         // Check if the creating transaction committed before our snapshot
         if let Some(txn) = txn_manager.get_transaction(xmin) {
             match txn.state {
@@ -167,6 +181,9 @@ impl Snapshot {
 
         // If tuple was deleted, check if deleting transaction committed before our snapshot
         if let Some(delete_xid) = xmax {
+            if delete_xid == self.transaction_id {
+                return false; // Deleted by ourselves
+            }
             if let Some(delete_txn) = txn_manager.get_transaction(delete_xid) {
                 match delete_txn.state {
                     crate::mvcc::transaction::TransactionState::Committed => {
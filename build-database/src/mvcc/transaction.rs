@@ -2,7 +2,7 @@
 //!
 //! Manages transaction lifecycle, IDs, and states for multi-version concurrency control.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use parking_lot::RwLock;
@@ -123,6 +123,15 @@ pub struct TransactionManager {
     current_timestamp: AtomicU64,
     /// Lock manager for concurrency control
     lock_manager: Arc<LockManager>,
+    /// Keys read and written by serializable transactions (write-skew detection)
+    access_sets: RwLock<HashMap<TransactionId, AccessSet>>,
+}
+
+/// Keys touched by a serializable transaction
+#[derive(Debug, Clone, Default)]
+struct AccessSet {
+    reads: HashSet<String>,
+    writes: HashSet<String>,
 }
 
 impl TransactionManager {
@@ -134,6 +143,7 @@ impl TransactionManager {
             committed_transactions: RwLock::new(Vec::new()),
             current_timestamp: AtomicU64::new(1),
             lock_manager: Arc::new(LockManager::new()),
+            access_sets: RwLock::new(HashMap::new()),
         }
     }
 
@@ -158,60 +168,110 @@ impl TransactionManager {
     pub async fn commit_transaction(&self, txn_id: TransactionId) -> AuroraResult<()> {
         let mut active = self.active_transactions.write();
 
-        if let Some(transaction) = active.get_mut(&txn_id) {
-            if !transaction.is_active() {
+        let transaction = match active.get(&txn_id) {
+            Some(transaction) => transaction.clone(),
+            None => {
                 return Err(AuroraError::new(
-                    ErrorCode::TransactionInvalidState,
-                    format!("Transaction {} is not active", txn_id)
+                    ErrorCode::TransactionNotFound,
+                    format!("Transaction {} not found", txn_id)
                 ));
             }
+        };
 
-            // Check for serializable conflicts before committing
-            if matches!(transaction.isolation_level, IsolationLevel::Serializable) {
-                if let Some(snapshot) = &transaction.snapshot {
-                    // Get all active snapshots for conflict detection
-                    let active_snapshots: Vec<_> = active.values()
-                        .filter_map(|txn| txn.snapshot.as_ref())
-                        .collect();
-
-                    if !snapshot.can_commit_serializable(&active_snapshots) {
-                        // Abort transaction due to serialization conflict
-                        let mut aborted_txn = (**transaction).clone();
-                        aborted_txn.state = TransactionState::Aborted;
-
-                        let aborted_arc = Arc::new(aborted_txn);
-                        *transaction = aborted_arc;
-
-                        return Err(AuroraError::new(
-                            ErrorCode::SerializationFailure,
-                            format!("Transaction {} aborted due to serialization conflict", txn_id)
-                        ));
-                    }
-                }
+        if !transaction.is_active() {
+            return Err(AuroraError::new(
+                ErrorCode::TransactionInvalidState,
+                format!("Transaction {} is not active", txn_id)
+            ));
+        }
+
+        // Check for serializable conflicts before committing
+        if matches!(transaction.isolation_level, IsolationLevel::Serializable) {
+            if let Some(other_id) = self.find_dangerous_structure(&transaction, &active) {
+                let mut aborted_txn = (*transaction).clone();
+                aborted_txn.state = TransactionState::Aborted;
+                active.insert(txn_id, Arc::new(aborted_txn));
+                self.access_sets.write().remove(&txn_id);
+
+                return Err(AuroraError::new(
+                    ErrorCode::TransactionConflict,
+                    format!("Transaction {} aborted due to serialization conflict with transaction {}", txn_id, other_id)
+                ));
             }
+        }
 
-            // Update transaction state
-            let mut committed_txn = (**transaction).clone();
-            committed_txn.state = TransactionState::Committed;
-            committed_txn.commit_timestamp = Some(self.current_timestamp.fetch_add(1, Ordering::SeqCst));
+        // Update transaction state
+        let mut committed_txn = (*transaction).clone();
+        committed_txn.state = TransactionState::Committed;
+        committed_txn.commit_timestamp = Some(self.current_timestamp.fetch_add(1, Ordering::SeqCst));
 
-            // Move to committed list
-            let committed_arc = Arc::new(committed_txn);
-            self.committed_transactions.write().push(committed_arc.clone());
+        // Move to committed list
+        let committed_arc = Arc::new(committed_txn);
+        self.committed_transactions.write().push(committed_arc.clone());
 
-            // Replace in active map
-            *transaction = committed_arc;
+        // Replace in active map
+        active.insert(txn_id, committed_arc);
+        self.prune_access_sets(&active);
 
-            log::info!("Committed transaction {} with isolation level {:?}", txn_id, transaction.isolation_level);
-            Ok(())
-        } else {
-            Err(AuroraError::new(
-                ErrorCode::TransactionNotFound,
-                format!("Transaction {} not found", txn_id)
-            ))
+        log::info!("Committed transaction {} with isolation level {:?}", txn_id, transaction.isolation_level);
+        Ok(())
+    }
+
+    /// Find a concurrent serializable transaction that forms an rw-antidependency
+    /// cycle with `transaction`: each read something the other wrote. Committing
+    /// both would produce a write skew, so the committer is aborted.
+    fn find_dangerous_structure(&self, transaction: &Transaction, all: &HashMap<TransactionId, Arc<Transaction>>) -> Option<TransactionId> {
+        let access_sets = self.access_sets.read();
+        let mine = access_sets.get(&transaction.id)?;
+
+        all.values()
+            .filter(|other| other.id != transaction.id && !other.is_aborted())
+            .filter(|other| other.isolation_level == IsolationLevel::Serializable)
+            // Only transactions that overlapped with ours can conflict
+            .filter(|other| other.commit_timestamp.map_or(true, |ts| ts > transaction.start_timestamp))
+            .find(|other| {
+                access_sets.get(&other.id).map_or(false, |theirs| {
+                    !mine.reads.is_disjoint(&theirs.writes) && !theirs.reads.is_disjoint(&mine.writes)
+                })
+            })
+            .map(|other| other.id)
+    }
+
+    /// Drop access sets that can no longer take part in a conflict: those of
+    /// transactions that committed before every active transaction started.
+    fn prune_access_sets(&self, all: &HashMap<TransactionId, Arc<Transaction>>) {
+        let oldest_active_start = all.values()
+            .filter(|txn| txn.is_active())
+            .map(|txn| txn.start_timestamp)
+            .min();
+
+        self.access_sets.write().retain(|txn_id, _| {
+            match (all.get(txn_id), oldest_active_start) {
+                (Some(txn), Some(oldest)) => txn.commit_timestamp.map_or(true, |ts| ts > oldest),
+                _ => false,
+            }
+        });
+    }
+
+    /// Record that a serializable transaction read `key`
+    pub fn record_read(&self, txn_id: TransactionId, key: &str) {
+        if self.is_serializable(txn_id) {
+            self.access_sets.write().entry(txn_id).or_default().reads.insert(key.to_string());
         }
     }
 
+    /// Record that a serializable transaction wrote `key`
+    pub fn record_write(&self, txn_id: TransactionId, key: &str) {
+        if self.is_serializable(txn_id) {
+            self.access_sets.write().entry(txn_id).or_default().writes.insert(key.to_string());
+        }
+    }
+
+    fn is_serializable(&self, txn_id: TransactionId) -> bool {
+        self.get_transaction(txn_id)
+            .map_or(false, |txn| txn.is_active() && txn.isolation_level == IsolationLevel::Serializable)
+    }
+
     /// Abort a transaction
     pub async fn abort_transaction(&self, txn_id: TransactionId) -> AuroraResult<()> {
         let mut active = self.active_transactions.write();
@@ -227,6 +287,7 @@ impl TransactionManager {
 
             // Replace in map
             *transaction = Arc::new(aborted_txn);
+            self.access_sets.write().remove(&txn_id);
 
             log::info!("Aborted transaction {}", txn_id);
             Ok(())
@@ -238,6 +299,23 @@ impl TransactionManager {
         }
     }
 
+    /// Abort a transaction if it is still active; false if it already
+    /// committed, aborted or is unknown
+    pub fn abort_if_active(&self, txn_id: TransactionId) -> bool {
+        let mut active = self.active_transactions.write();
+        match active.get_mut(&txn_id) {
+            Some(transaction) if transaction.is_active() => {
+                let mut aborted_txn = (**transaction).clone();
+                aborted_txn.state = TransactionState::Aborted;
+                *transaction = Arc::new(aborted_txn);
+                self.access_sets.write().remove(&txn_id);
+                log::info!("Aborted transaction {}", txn_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get transaction by ID
     pub fn get_transaction(&self, txn_id: TransactionId) -> Option<Arc<Transaction>> {
         let active = self.active_transactions.read();
@@ -246,7 +324,17 @@ impl TransactionManager {
 
     /// Check if transaction is visible to another transaction
     pub fn is_transaction_visible(&self, txn_id: TransactionId, from_txn: &Transaction) -> bool {
+        // A transaction always sees its own writes
+        if txn_id == from_txn.id {
+            return true;
+        }
+
         if let Some(txn) = self.get_transaction(txn_id) {
+            // Rolled-back writes are never visible, not even to dirty readers
+            if txn.is_aborted() {
+                return false;
+            }
+
             match from_txn.isolation_level {
                 IsolationLevel::ReadUncommitted => {
                     // Can see all versions, even uncommitted ones
@@ -257,7 +345,8 @@ impl TransactionManager {
                     txn.is_committed()
                 }
                 IsolationLevel::RepeatableRead | IsolationLevel::Serializable => {
-                    // Can see transactions that committed before this transaction started
+                    // Can see transactions that committed before this transaction started;
+                    // anything committed later stays invisible for the whole transaction
                     if let Some(commit_ts) = txn.commit_timestamp {
                         commit_ts < from_txn.start_timestamp
                     } else {
@@ -272,6 +361,17 @@ impl TransactionManager {
         }
     }
 
+    /// Start timestamp of the oldest running snapshot (repeatable read or
+    /// serializable) transaction. Other levels see every committed delete,
+    /// so a version deleted by a commit before this is invisible to everyone.
+    pub fn oldest_active_snapshot(&self) -> Option<u64> {
+        self.active_transactions.read().values()
+            .filter(|txn| txn.is_active())
+            .filter(|txn| matches!(txn.isolation_level, IsolationLevel::RepeatableRead | IsolationLevel::Serializable))
+            .map(|txn| txn.start_timestamp)
+            .min()
+    }
+
     /// Get current timestamp
     pub fn current_timestamp(&self) -> u64 {
         self.current_timestamp.load(Ordering::SeqCst)
@@ -49,7 +49,7 @@ impl VersionedTuple {
 
     /// Check if this version is currently visible to a transaction
     pub fn is_visible_to(&self, txn: &crate::mvcc::transaction::Transaction, txn_manager: &crate::mvcc::transaction::TransactionManager) -> bool {
-        // Version must be created by a committed transaction
+        // Version must be created by a committed transaction (or by `txn` itself)
         if !txn_manager.is_transaction_visible(self.xmin, txn) {
             return false;
         }
@@ -97,13 +97,38 @@ impl TupleVersionChain {
 
     /// Add a new version to the chain
     pub fn add_version(&mut self, version: VersionedTuple) {
-        // Mark the previous current version as replaced (deleted)
+        // Mark the previous current version as replaced (deleted), unless a
+        // delete already ended it and this version re-inserts the key
         if let Some(last) = self.versions.last_mut() {
-            last.xmax = Some(version.xmin);
+            if last.xmax.is_none() {
+                last.xmax = Some(version.xmin);
+            }
         }
         self.versions.push(version);
     }
 
+    /// Remove versions created by aborted transactions and clear delete markers
+    /// they left behind, so the chain only reflects live or committed writes
+    pub fn discard_aborted(&mut self, txn_manager: &crate::mvcc::transaction::TransactionManager) -> usize {
+        let is_aborted = |txn_id: TransactionId| {
+            txn_manager.get_transaction(txn_id).map_or(false, |txn| txn.is_aborted())
+        };
+
+        let before = self.versions.len();
+        self.versions.retain(|version| !is_aborted(version.xmin));
+        for version in &mut self.versions {
+            if version.xmax.map_or(false, |xmax| is_aborted(xmax)) {
+                version.xmax = None;
+            }
+        }
+        before - self.versions.len()
+    }
+
+    /// Check if the chain has no versions left
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
     /// Delete the current version
     pub fn delete_current(&mut self, deleting_txn: TransactionId) {
         if let Some(current) = self.versions.last_mut() {
@@ -4,7 +4,7 @@
 //! to transactions based on isolation levels and MVCC metadata.
 
 use crate::mvcc::transaction::{Transaction, TransactionManager, IsolationLevel};
-use crate::mvcc::version::VersionedTuple;
+use crate::mvcc::version::{TupleVersionChain, VersionedTuple};
 use crate::mvcc::snapshot::Snapshot;
 
 /// Visibility checker for MVCC
@@ -30,10 +30,11 @@ impl VisibilityChecker {
     }
 
     /// Read Uncommitted: Can see all versions, even uncommitted ones
-    fn is_visible_read_uncommitted(tuple: &VersionedTuple, _transaction: &Transaction, _txn_manager: &TransactionManager) -> bool {
-        // Can see everything, including uncommitted changes
+    fn is_visible_read_uncommitted(tuple: &VersionedTuple, transaction: &Transaction, txn_manager: &TransactionManager) -> bool {
+        // The transaction manager treats in-flight writers as visible at this level,
+        // so deletes still hide tuples and rolled-back writes stay invisible.
         // Note: This can lead to dirty reads
-        true
+        tuple.is_visible_to(transaction, txn_manager)
     }
 
     /// Read Committed: Can only see committed versions
@@ -94,6 +95,43 @@ impl VisibilityChecker {
         Ok(())
     }
 
+    /// Check whether a transaction may write a new version onto `chain`.
+    ///
+    /// Writers never wait: if another in-flight transaction created or deleted the
+    /// latest version the write fails with `TupleLocked`. Under Repeatable Read and
+    /// Serializable a version committed after the writer's snapshot fails with
+    /// `SerializationFailure` (first-updater-wins), which prevents lost updates.
+    /// Read Committed writes on top of the latest committed version instead.
+    /// Call `TupleVersionChain::discard_aborted` first.
+    pub fn check_write_conflict(chain: &TupleVersionChain, transaction: &Transaction, txn_manager: &TransactionManager) -> Result<(), VisibilityError> {
+        let latest = match chain.current_version() {
+            Some(latest) => latest,
+            None => return Ok(()),
+        };
+
+        for writer in std::iter::once(latest.xmin).chain(latest.xmax) {
+            if writer == transaction.id {
+                continue;
+            }
+
+            if let Some(other) = txn_manager.get_transaction(writer) {
+                if other.is_active() {
+                    return Err(VisibilityError::TupleLocked);
+                }
+
+                let uses_snapshot = matches!(
+                    transaction.isolation_level,
+                    IsolationLevel::RepeatableRead | IsolationLevel::Serializable
+                );
+                if uses_snapshot && other.is_committed() && !txn_manager.is_transaction_visible(writer, transaction) {
+                    return Err(VisibilityError::SerializationFailure);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get the appropriate snapshot for a transaction
     pub fn create_snapshot_for_transaction(transaction: &mut Transaction, txn_manager: &TransactionManager) {
         match transaction.isolation_level {
@@ -4,11 +4,18 @@
 //! Implements leader election, log replication, and fault tolerance.
 
 use crate::core::*;
+use crate::storage::wal_logger::{WALEntry, WALLogger};
 use super::protocol::*;
 use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use parking_lot::RwLock;
-use tokio::sync::mpsc;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
 
 /// Consensus protocol for distributed coordination
 pub struct ConsensusProtocol {
@@ -33,20 +40,184 @@ pub struct ConsensusProtocol {
     message_receiver: mpsc::UnboundedReceiver<ConsensusMessage>,
 }
 
-/// Replication protocol for data synchronization
+/// Physical streaming replication of the write-ahead log
+///
+/// A primary serves its WAL to standbys over TCP. A standby connects, asks
+/// for the log from the LSN after the last one it flushed, writes each
+/// streamed entry to its own log and applies it, and reports its flushed and
+/// applied LSNs back. The primary keeps those positions in a replication
+/// slot per standby, pauses a stream once `max_in_flight` entries are
+/// unacknowledged, and lets a committer wait for a standby to flush or
+/// apply its commit record, as its `synchronous_commit` level requires. A
+/// standby that loses its primary reconnects and resumes after its last
+/// flushed LSN.
 pub struct ReplicationProtocol {
     /// Replication role (master/slave)
     role: ReplicationRole,
-    /// Master node (if slave)
-    master_node: Option<String>,
-    /// Replication lag
-    replication_lag: u64,
-    /// Last replicated LSN
-    last_replicated_lsn: u64,
-    /// Replication slots
-    replication_slots: HashMap<String, ReplicationSlot>,
+    /// This node's log: streamed from on a primary, written to on a standby
+    wal: Arc<WALLogger>,
+    config: ReplicationConfig,
+    /// Replication slots, one per standby
+    replication_slots: RwLock<HashMap<String, ReplicationSlot>>,
+    /// Level of commits that do not choose their own
+    synchronous_commit: RwLock<SynchronousCommit>,
+    /// Bumped on every standby status report, connect and disconnect
+    feedback: watch::Sender<u64>,
+    /// Last LSN applied, on a standby
+    applied_lsn: watch::Sender<u64>,
     /// Replication statistics
-    stats: ReplicationStats,
+    stats: RwLock<ReplicationStats>,
+}
+
+/// Tuning of WAL streaming
+#[derive(Debug, Clone)]
+pub struct ReplicationConfig {
+    /// Entries a standby may have unflushed before the primary stops sending
+    pub max_in_flight: u64,
+    /// Entries per `WalData` message
+    pub batch_size: usize,
+    /// Wait before a standby reconnects to its primary
+    pub reconnect_interval: Duration,
+    /// Largest message either side accepts
+    pub max_message_size: usize,
+    /// Initial level of commits that do not choose their own
+    pub synchronous_commit: SynchronousCommit,
+    /// Standbys a synchronous commit waits for; the first to acknowledge
+    /// releases it. With none, remote levels commit as `local`.
+    pub synchronous_standbys: Vec<String>,
+    /// How long a synchronous commit waits before `sync_fallback` applies
+    pub sync_timeout: Duration,
+    pub sync_fallback: SyncFallback,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 1024,
+            batch_size: 128,
+            reconnect_interval: Duration::from_secs(1),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            synchronous_commit: SynchronousCommit::Local,
+            synchronous_standbys: Vec::new(),
+            sync_timeout: Duration::from_secs(10),
+            sync_fallback: SyncFallback::Wait,
+        }
+    }
+}
+
+/// How far a commit's record must get before the commit returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SynchronousCommit {
+    /// Buffered in the primary's log; a crash may lose it
+    Off,
+    /// Flushed to the primary's log
+    Local,
+    /// Flushed to a synchronous standby's log
+    RemoteWrite,
+    /// Applied on a synchronous standby, so reads there see it
+    RemoteApply,
+}
+
+impl SynchronousCommit {
+    /// Level named as in `SET synchronous_commit`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(SynchronousCommit::Off),
+            "local" => Some(SynchronousCommit::Local),
+            "remote_write" => Some(SynchronousCommit::RemoteWrite),
+            "remote_apply" => Some(SynchronousCommit::RemoteApply),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SynchronousCommit::Off => "off",
+            SynchronousCommit::Local => "local",
+            SynchronousCommit::RemoteWrite => "remote_write",
+            SynchronousCommit::RemoteApply => "remote_apply",
+        }
+    }
+
+    /// Whether `slot` has acknowledged `lsn` as far as this level requires
+    fn acknowledged(&self, slot: &ReplicationSlot, lsn: u64) -> bool {
+        match self {
+            SynchronousCommit::Off | SynchronousCommit::Local => true,
+            SynchronousCommit::RemoteWrite => slot.confirmed_flush_lsn >= lsn,
+            SynchronousCommit::RemoteApply => slot.applied_lsn >= lsn,
+        }
+    }
+}
+
+impl std::fmt::Display for SynchronousCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a synchronous commit does when no synchronous standby acknowledges
+/// it within `sync_timeout`, or none is connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncFallback {
+    /// Keep waiting, warning every `sync_timeout`, until one does
+    Wait,
+    /// Return once the commit is local, with a warning
+    DegradeToAsync,
+}
+
+/// How a commit was acknowledged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitAck {
+    /// LSN of the commit record
+    pub lsn: u64,
+    /// Level the commit asked for
+    pub level: SynchronousCommit,
+    /// Synchronous standby that acknowledged it, for remote levels
+    pub standby: Option<String>,
+    /// Returned without the remote acknowledgment its level asked for
+    pub degraded: bool,
+}
+
+/// Messages of a replication stream, framed as a big-endian length word
+/// (counting itself) and the bincode-encoded message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplicationMessage {
+    /// Standby to primary, once: stream the log from `start_lsn`
+    StartReplication { standby_id: String, start_lsn: u64 },
+    /// Primary to standby: the next entries, in LSN order
+    WalData { entries: Vec<WALEntry> },
+    /// Standby to primary, after each `WalData`
+    StandbyStatus { flushed_lsn: u64, applied_lsn: u64 },
+    /// Primary to standby: the stream cannot be served
+    Refused { reason: String },
+}
+
+impl ReplicationMessage {
+    fn name(&self) -> &'static str {
+        match self {
+            ReplicationMessage::StartReplication { .. } => "StartReplication",
+            ReplicationMessage::WalData { .. } => "WalData",
+            ReplicationMessage::StandbyStatus { .. } => "StandbyStatus",
+            ReplicationMessage::Refused { .. } => "Refused",
+        }
+    }
+}
+
+/// Applies streamed WAL entries to a standby's data
+pub trait WalApplier: Send {
+    /// Called with each entry, in LSN order, once it is in the standby's log
+    fn apply(&mut self, entry: &WALEntry) -> Result<(), io::Error>;
+}
+
+/// Closures of the shape `WALLogger::recover` takes, so one replay function
+/// serves both recovery and streaming
+impl<F> WalApplier for F
+where
+    F: FnMut(&WALEntry) -> Result<(), io::Error> + Send,
+{
+    fn apply(&mut self, entry: &WALEntry) -> Result<(), io::Error> {
+        self(entry)
+    }
 }
 
 /// Cluster configuration
@@ -153,9 +324,15 @@ pub enum ReplicationRole {
 #[derive(Debug, Clone)]
 pub struct ReplicationSlot {
     pub slot_name: String,
+    /// LSN the standby's current or last stream started from
     pub restart_lsn: u64,
+    /// Last LSN the standby has in its log
     pub confirmed_flush_lsn: u64,
+    /// Last LSN the standby has applied
+    pub applied_lsn: u64,
     pub plugin: String,
+    /// A standby is streaming from this slot
+    pub active: bool,
 }
 
 /// Replication statistics
@@ -167,6 +344,8 @@ pub struct ReplicationStats {
     pub replication_lag_time_ms: u64,
     pub conflicts_resolved: u64,
     pub rollbacks_performed: u64,
+    /// Synchronous commits that returned without their standby's acknowledgment
+    pub degraded_commits: u64,
 }
 
 impl ConsensusProtocol {
@@ -432,53 +611,321 @@ impl ConsensusProtocol {
 }
 
 impl ReplicationProtocol {
-    /// Create a new replication protocol
-    pub fn new(role: ReplicationRole) -> Self {
+    /// Create a new replication protocol over this node's log. A standby's
+    /// log is taken as applied up to its flushed LSN, so replay it first,
+    /// e.g. with `WALLogger::recover`.
+    pub fn new(role: ReplicationRole, wal: Arc<WALLogger>) -> Self {
+        let applied_lsn = match role {
+            ReplicationRole::Master => 0,
+            ReplicationRole::Slave => wal.flushed_lsn(),
+        };
         Self {
             role,
-            master_node: None,
-            replication_lag: 0,
-            last_replicated_lsn: 0,
-            replication_slots: HashMap::new(),
-            stats: ReplicationStats::default(),
+            wal,
+            config: ReplicationConfig::default(),
+            replication_slots: RwLock::new(HashMap::new()),
+            synchronous_commit: RwLock::new(SynchronousCommit::Local),
+            feedback: watch::channel(0).0,
+            applied_lsn: watch::channel(applied_lsn).0,
+            stats: RwLock::new(ReplicationStats::default()),
         }
     }
 
-    /// Start replication process
-    pub async fn start(&mut self) -> Result<(), ReplicationError> {
-        match self.role {
-            ReplicationRole::Master => {
-                self.start_master_replication().await?;
+    pub fn with_config(mut self, config: ReplicationConfig) -> Self {
+        *self.synchronous_commit.get_mut() = config.synchronous_commit;
+        self.config = config;
+        self
+    }
+
+    /// Level of commits that do not choose their own
+    pub fn synchronous_commit(&self) -> SynchronousCommit {
+        *self.synchronous_commit.read()
+    }
+
+    pub fn set_synchronous_commit(&self, level: SynchronousCommit) {
+        *self.synchronous_commit.write() = level;
+    }
+
+    pub fn role(&self) -> &ReplicationRole {
+        &self.role
+    }
+
+    /// Stream the WAL to every standby that connects to `listener`. Streams
+    /// end when the returned future is dropped.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<(), ReplicationError> {
+        if self.role != ReplicationRole::Master {
+            return Err(ReplicationError::StreamError("only a primary serves its WAL".to_string()));
+        }
+
+        let mut streams = tokio::task::JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (socket, address) = accepted
+                        .map_err(|e| ReplicationError::StreamError(format!("accept failed: {}", e)))?;
+                    let protocol = self.clone();
+                    streams.spawn(async move {
+                        if let Err(e) = protocol.stream_to_standby(socket).await {
+                            log::warn!("Replication stream to {} ended: {}", address, e);
+                        }
+                    });
+                }
+                Some(_) = streams.join_next(), if !streams.is_empty() => {}
             }
-            ReplicationRole::Slave => {
-                self.start_slave_replication().await?;
+        }
+    }
+
+    /// Serve one standby's stream
+    async fn stream_to_standby(self: Arc<Self>, socket: TcpStream) -> Result<(), ReplicationError> {
+        let max_message_size = self.config.max_message_size;
+        let (mut reader, mut writer) = socket.into_split();
+        let (standby_id, start_lsn) = match read_message(&mut reader, max_message_size).await? {
+            Some((ReplicationMessage::StartReplication { standby_id, start_lsn }, _)) => (standby_id, start_lsn),
+            Some((other, _)) => {
+                return Err(ReplicationError::StreamError(format!("expected StartReplication, got {}", other.name())));
             }
+            None => return Ok(()),
+        };
+
+        let flushed_lsn = self.wal.flushed_lsn();
+        if start_lsn == 0 || start_lsn > flushed_lsn + 1 {
+            let reason = format!("start LSN {} is past the primary's flushed LSN {}", start_lsn, flushed_lsn);
+            write_message(&mut writer, &ReplicationMessage::Refused { reason: reason.clone() }).await?;
+            return Err(ReplicationError::Refused(reason));
         }
-        Ok(())
+
+        // Everything before the start is already in the standby's log
+        self.replication_slots.write()
+            .entry(standby_id.clone())
+            .and_modify(|slot| slot.active = true)
+            .or_insert_with(|| ReplicationSlot {
+                slot_name: standby_id.clone(),
+                restart_lsn: 0,
+                confirmed_flush_lsn: 0,
+                applied_lsn: 0,
+                plugin: "physical".to_string(),
+                active: true,
+            });
+        self.update_replication_progress(&standby_id, start_lsn - 1, start_lsn - 1);
+        if let Some(slot) = self.replication_slots.write().get_mut(&standby_id) {
+            slot.restart_lsn = start_lsn;
+        }
+        log::info!("Standby {} streaming WAL from LSN {}", standby_id, start_lsn);
+
+        // Status reports are read on their own task, since a read cannot be
+        // abandoned halfway through a message
+        let (status_sender, mut statuses) = mpsc::unbounded_channel();
+        let status_reader = tokio::spawn(async move {
+            loop {
+                let message = read_message(&mut reader, max_message_size).await;
+                let done = !matches!(message, Ok(Some(_)));
+                if status_sender.send(message).is_err() || done {
+                    return;
+                }
+            }
+        });
+
+        let mut next_lsn = start_lsn;
+        let result = self.send_wal(&standby_id, &mut next_lsn, &mut writer, &mut statuses).await;
+
+        status_reader.abort();
+        if let Some(slot) = self.replication_slots.write().get_mut(&standby_id) {
+            slot.active = false;
+        }
+        // Synchronous commits waiting on this standby may need to fall back
+        self.feedback.send_modify(|reports| *reports += 1);
+        log::info!("Standby {} stopped streaming at LSN {}", standby_id, next_lsn - 1);
+        result
     }
 
-    /// Start master replication (send changes to slaves)
-    async fn start_master_replication(&mut self) -> Result<(), ReplicationError> {
-        // Create replication slots for connected slaves
-        for (slot_name, slot) in &self.replication_slots {
-            println!("Streaming changes for slot: {}", slot_name);
-            // In practice, stream WAL changes to slaves
+    /// Send the log from `next_lsn` on as it is flushed, within the
+    /// standby's window, until the standby hangs up
+    async fn send_wal<W: AsyncWrite + Unpin>(
+        &self,
+        standby_id: &str,
+        next_lsn: &mut u64,
+        writer: &mut W,
+        statuses: &mut mpsc::UnboundedReceiver<Result<Option<(ReplicationMessage, usize)>, ReplicationError>>,
+    ) -> Result<(), ReplicationError> {
+        let mut flushed = self.wal.subscribe_flushed();
+        loop {
+            // Flow control: send only what the standby's window allows
+            let acknowledged = self.slot(standby_id).map_or(0, |slot| slot.confirmed_flush_lsn);
+            let window = (acknowledged + self.config.max_in_flight).saturating_sub(*next_lsn - 1);
+            if window > 0 && self.wal.flushed_lsn() >= *next_lsn {
+                let entries = self.wal.read_from(*next_lsn, self.config.batch_size.min(window as usize))?;
+                if let Some(last) = entries.last() {
+                    *next_lsn = last.lsn + 1;
+                    let sent = write_message(writer, &ReplicationMessage::WalData { entries }).await?;
+                    self.stats.write().sent_bytes += sent as u64;
+                    continue;
+                }
+            }
+
+            tokio::select! {
+                changed = flushed.changed() => {
+                    if changed.is_err() {
+                        return Ok(());
+                    }
+                }
+                status = statuses.recv() => match status {
+                    Some(Ok(Some((ReplicationMessage::StandbyStatus { flushed_lsn, applied_lsn }, received)))) => {
+                        self.stats.write().received_bytes += received as u64;
+                        self.update_replication_progress(standby_id, flushed_lsn, applied_lsn);
+                    }
+                    Some(Ok(Some((other, _)))) => {
+                        return Err(ReplicationError::StreamError(format!("unexpected {} from standby", other.name())));
+                    }
+                    Some(Err(e)) => return Err(e),
+                    Some(Ok(None)) | None => return Ok(()),
+                },
+            }
         }
-        Ok(())
     }
 
-    /// Start slave replication (receive changes from master)
-    async fn start_slave_replication(&mut self) -> Result<(), ReplicationError> {
-        if let Some(master) = &self.master_node {
-            println!("Connecting to master: {}", master);
-            // In practice, establish streaming connection to master
+    /// Wait until `standby_id` has acknowledged `lsn` as far as `level`
+    /// requires
+    pub async fn wait_for_standby(&self, standby_id: &str, lsn: u64, level: SynchronousCommit) {
+        let mut feedback = self.feedback.subscribe();
+        while self.slot(standby_id).is_none_or(|slot| !level.acknowledged(&slot, lsn)) {
+            // The sender lives as long as `self`, so this only returns on a change
+            let _ = feedback.changed().await;
         }
-        Ok(())
+    }
+
+    /// Log `transaction_id`'s commit record and return once it is as durable
+    /// as `level` requires, or the global `synchronous_commit` if `None`
+    pub async fn commit(&self, transaction_id: u64, level: Option<SynchronousCommit>) -> Result<CommitAck, ReplicationError> {
+        let level = level.unwrap_or_else(|| self.synchronous_commit());
+        let lsn = match level {
+            SynchronousCommit::Off => self.wal.commit_transaction_deferred(transaction_id).await?,
+            _ => self.wal.commit_transaction(transaction_id).await?,
+        };
+        let mut ack = CommitAck { lsn, level, standby: None, degraded: false };
+        if level <= SynchronousCommit::Local || self.config.synchronous_standbys.is_empty() {
+            return Ok(ack);
+        }
+
+        let mut feedback = self.feedback.subscribe();
+        let mut deadline = tokio::time::Instant::now() + self.config.sync_timeout;
+        loop {
+            let (acknowledged_by, connected) = {
+                let slots = self.replication_slots.read();
+                let standbys = || self.config.synchronous_standbys.iter().filter_map(|name| slots.get(name));
+                (
+                    standbys().find(|slot| level.acknowledged(slot, lsn)).map(|slot| slot.slot_name.clone()),
+                    standbys().any(|slot| slot.active),
+                )
+            };
+            if acknowledged_by.is_some() {
+                ack.standby = acknowledged_by;
+                return Ok(ack);
+            }
+            if !connected && self.config.sync_fallback == SyncFallback::DegradeToAsync {
+                log::warn!("No synchronous standby connected; commit at LSN {} is only local", lsn);
+                return Ok(self.degrade(ack));
+            }
+
+            tokio::select! {
+                _ = feedback.changed() => {}
+                _ = tokio::time::sleep_until(deadline) => match self.config.sync_fallback {
+                    SyncFallback::DegradeToAsync => {
+                        log::warn!("No synchronous standby acknowledged LSN {} within {:?}; commit is only local", lsn, self.config.sync_timeout);
+                        return Ok(self.degrade(ack));
+                    }
+                    SyncFallback::Wait => {
+                        log::warn!("Commit at LSN {} still waiting for a synchronous standby ({})", lsn, level);
+                        deadline += self.config.sync_timeout;
+                    }
+                },
+            }
+        }
+    }
+
+    fn degrade(&self, mut ack: CommitAck) -> CommitAck {
+        self.stats.write().degraded_commits += 1;
+        ack.degraded = true;
+        ack
+    }
+
+    /// Follow the primary at `primary`, writing its WAL to this node's log
+    /// and applying each entry with `applier`. Reconnects whenever the
+    /// stream breaks, resuming after the last flushed LSN; returns only on
+    /// an error that reconnecting cannot fix.
+    pub async fn follow<A: WalApplier>(&self, primary: SocketAddr, standby_id: &str, mut applier: A) -> Result<(), ReplicationError> {
+        if self.role != ReplicationRole::Slave {
+            return Err(ReplicationError::StreamError("only a standby follows a primary".to_string()));
+        }
+
+        loop {
+            match self.stream_from_primary(primary, standby_id, &mut applier).await {
+                Err(e) if !e.is_retryable() => return Err(e),
+                Err(e) => log::warn!("Replication from {} interrupted: {}", primary, e),
+                Ok(()) => log::info!("Primary {} closed the replication stream", primary),
+            }
+            tokio::time::sleep(self.config.reconnect_interval).await;
+        }
+    }
+
+    /// Receive one stream from the primary until it ends
+    async fn stream_from_primary<A: WalApplier>(&self, primary: SocketAddr, standby_id: &str, applier: &mut A) -> Result<(), ReplicationError> {
+        let mut socket = TcpStream::connect(primary).await
+            .map_err(|_| ReplicationError::MasterConnectionFailed)?;
+        let start_lsn = self.wal.flushed_lsn() + 1;
+        let request = ReplicationMessage::StartReplication { standby_id: standby_id.to_string(), start_lsn };
+        write_message(&mut socket, &request).await?;
+        log::info!("Streaming WAL from {} starting at LSN {}", primary, start_lsn);
+
+        loop {
+            let (message, received) = match read_message(&mut socket, self.config.max_message_size).await? {
+                Some(message) => message,
+                None => return Ok(()),
+            };
+            match message {
+                ReplicationMessage::WalData { entries } => {
+                    // Logged before applied, as on the primary; each step is
+                    // reported so remote_write commits need not wait for the apply
+                    self.wal.append_replicated(&entries).await?;
+                    let mut sent = self.send_status(&mut socket).await?;
+                    for entry in &entries {
+                        applier.apply(entry)
+                            .map_err(|e| ReplicationError::ApplyFailed { lsn: entry.lsn, reason: e.to_string() })?;
+                        self.applied_lsn.send_replace(entry.lsn);
+                    }
+                    sent += self.send_status(&mut socket).await?;
+
+                    let mut stats = self.stats.write();
+                    stats.received_bytes += received as u64;
+                    stats.sent_bytes += sent as u64;
+                }
+                ReplicationMessage::Refused { reason } => return Err(ReplicationError::Refused(reason)),
+                other => {
+                    return Err(ReplicationError::StreamError(format!("unexpected {} from primary", other.name())));
+                }
+            }
+        }
+    }
+
+    /// Report this standby's flushed and applied LSNs; the bytes sent
+    async fn send_status(&self, socket: &mut TcpStream) -> Result<usize, ReplicationError> {
+        let status = ReplicationMessage::StandbyStatus { flushed_lsn: self.wal.flushed_lsn(), applied_lsn: self.applied_lsn() };
+        write_message(socket, &status).await
+    }
+
+    /// Last LSN applied on this standby
+    pub fn applied_lsn(&self) -> u64 {
+        *self.applied_lsn.borrow()
+    }
+
+    /// Receiver of the applied LSN, which changes after every applied entry
+    pub fn subscribe_applied(&self) -> watch::Receiver<u64> {
+        self.applied_lsn.subscribe()
     }
 
     /// Create a replication slot
-    pub fn create_replication_slot(&mut self, slot_name: String, plugin: String) -> Result<(), ReplicationError> {
-        if self.replication_slots.contains_key(&slot_name) {
+    pub fn create_replication_slot(&self, slot_name: String, plugin: String) -> Result<(), ReplicationError> {
+        let mut slots = self.replication_slots.write();
+        if slots.contains_key(&slot_name) {
             return Err(ReplicationError::SlotExists(slot_name));
         }
 
@@ -486,27 +933,64 @@ impl ReplicationProtocol {
             slot_name: slot_name.clone(),
             restart_lsn: 0,
             confirmed_flush_lsn: 0,
+            applied_lsn: 0,
             plugin,
+            active: false,
         };
 
-        self.replication_slots.insert(slot_name, slot);
+        slots.insert(slot_name, slot);
         Ok(())
     }
 
     /// Update replication progress
-    pub fn update_replication_progress(&mut self, slot_name: &str, lsn: u64) {
-        if let Some(slot) = self.replication_slots.get_mut(slot_name) {
-            slot.confirmed_flush_lsn = lsn;
-            self.last_replicated_lsn = lsn;
+    pub fn update_replication_progress(&self, slot_name: &str, flushed_lsn: u64, applied_lsn: u64) {
+        if let Some(slot) = self.replication_slots.write().get_mut(slot_name) {
+            slot.confirmed_flush_lsn = flushed_lsn;
+            slot.applied_lsn = applied_lsn;
         }
+        self.feedback.send_modify(|reports| *reports += 1);
+    }
+
+    pub fn slot(&self, slot_name: &str) -> Option<ReplicationSlot> {
+        self.replication_slots.read().get(slot_name).cloned()
     }
 
     /// Get replication statistics
-    pub fn stats(&self) -> &ReplicationStats {
-        &self.stats
+    pub fn stats(&self) -> ReplicationStats {
+        self.stats.read().clone()
     }
 }
 
+/// Write one framed message; the bytes written
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &ReplicationMessage) -> Result<usize, ReplicationError> {
+    let body = bincode::serialize(message)
+        .map_err(|e| ReplicationError::StreamError(format!("failed to encode {}: {}", message.name(), e)))?;
+    let mut frame = Vec::with_capacity(body.len() + 4);
+    frame.extend_from_slice(&(body.len() as u32 + 4).to_be_bytes());
+    frame.extend_from_slice(&body);
+    writer.write_all(&frame).await
+        .map_err(|e| ReplicationError::StreamError(format!("failed to send {}: {}", message.name(), e)))?;
+    Ok(frame.len())
+}
+
+/// Read one framed message and the bytes it took; `None` if the peer
+/// closed the connection between messages
+async fn read_message<R: AsyncRead + Unpin>(reader: &mut R, max_message_size: usize) -> Result<Option<(ReplicationMessage, usize)>, ReplicationError> {
+    let length = match reader.read_u32().await {
+        Ok(length) => length,
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(ReplicationError::StreamError(format!("read failed: {}", e))),
+    };
+    let body_length = frame_body_length(length, max_message_size)
+        .map_err(|e| ReplicationError::StreamError(e.to_string()))?;
+    let mut body = vec![0u8; body_length];
+    reader.read_exact(&mut body).await
+        .map_err(|e| ReplicationError::StreamError(format!("read failed: {}", e)))?;
+    let message = bincode::deserialize(&body)
+        .map_err(|e| ReplicationError::StreamError(format!("malformed message: {}", e)))?;
+    Ok(Some((message, length as usize)))
+}
+
 /// Replication operation errors
 #[derive(Debug, thiserror::Error)]
 pub enum ReplicationError {
@@ -521,4 +1005,20 @@ pub enum ReplicationError {
 
     #[error("Replication stream error: {0}")]
     StreamError(String),
+
+    #[error("Primary refused replication: {0}")]
+    Refused(String),
+
+    #[error("Failed to apply WAL at LSN {lsn}: {reason}")]
+    ApplyFailed { lsn: u64, reason: String },
+
+    #[error("WAL error: {0}")]
+    Wal(#[from] io::Error),
+}
+
+impl ReplicationError {
+    /// Whether a standby should reconnect and try again
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ReplicationError::MasterConnectionFailed | ReplicationError::StreamError(_))
+    }
 }
@@ -0,0 +1,616 @@
+//! Extended Query Protocol
+//!
+//! Parse names a statement with `$n` parameters, Bind supplies their values
+//! to make a portal, and Execute runs the portal. Parameter values are
+//! checked and coerced at Bind, so a bad one is refused there with the
+//! parameter it was bound to, instead of failing somewhere in execution:
+//!
+//! - Each parameter's type is the one Parse declared for it, or else the
+//!   type of the column it is compared with, assigned to or inserted into.
+//!   A parameter with neither is left for the statement to interpret.
+//! - Text that parses as the parameter's type is accepted (`'42'` for an
+//!   integer), integers are widened to floating point, and anything else is
+//!   refused with SQLSTATE 22P02, naming the parameter and its type.
+//!
+//! A bound portal holds its statement's text with each parameter replaced
+//! by a literal of its type, and is executed as a simple query would be.
+//! Describe reports a statement's parameter types; the description of a
+//! portal's rows is sent by Execute, ahead of the rows.
+
+use crate::engine::DataType;
+use std::collections::HashMap;
+
+/// Type a parameter's value is checked against at Bind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterType {
+    Integer,
+    Float,
+    Text,
+    Boolean,
+    /// Undeclared and not inferred; values are passed on as given
+    Unknown,
+}
+
+impl ParameterType {
+    /// Type declared by a Parse message's OID; 0 leaves it undeclared
+    pub fn from_oid(oid: u32) -> Self {
+        match oid {
+            20 | 21 | 23 => ParameterType::Integer,
+            700 | 701 => ParameterType::Float,
+            16 => ParameterType::Boolean,
+            25 | 1042 | 1043 => ParameterType::Text,
+            _ => ParameterType::Unknown,
+        }
+    }
+
+    /// OID reported in ParameterDescription
+    pub fn oid(self) -> u32 {
+        match self {
+            ParameterType::Integer => 20,
+            ParameterType::Float => 701,
+            ParameterType::Text => 25,
+            ParameterType::Boolean => 16,
+            ParameterType::Unknown => 0,
+        }
+    }
+
+    /// Type of a parameter standing for a value of a column
+    pub fn of_column(data_type: &DataType) -> Self {
+        match data_type {
+            DataType::Integer | DataType::BigInt => ParameterType::Integer,
+            DataType::Float | DataType::Double => ParameterType::Float,
+            DataType::Text => ParameterType::Text,
+            DataType::Boolean => ParameterType::Boolean,
+            _ => ParameterType::Unknown,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ParameterType::Integer => "integer",
+            ParameterType::Float => "double precision",
+            ParameterType::Text => "text",
+            ParameterType::Boolean => "boolean",
+            ParameterType::Unknown => "unknown",
+        }
+    }
+}
+
+/// A parameter value after Bind checked it against its type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Boolean(bool),
+    /// Value of an unknown-typed parameter, as the client sent it
+    Untyped(String),
+}
+
+impl ParameterValue {
+    /// SQL literal that stands for the value in the statement text
+    pub fn to_literal(&self) -> String {
+        match self {
+            ParameterValue::Null => "NULL".to_string(),
+            ParameterValue::Integer(value) => value.to_string(),
+            ParameterValue::Float(value) => format!("{:?}", value),
+            ParameterValue::Boolean(value) => if *value { "TRUE" } else { "FALSE" }.to_string(),
+            ParameterValue::Text(text) => quote(text),
+            ParameterValue::Untyped(text) if text.parse::<f64>().is_ok_and(f64::is_finite) => text.trim().to_string(),
+            ParameterValue::Untyped(text) => quote(text),
+        }
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Extended query failures, reported with their SQLSTATE
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExtendedQueryError {
+    #[error("invalid input for parameter ${index}: expected {expected}, got \"{value}\"")]
+    InvalidParameter { index: usize, expected: &'static str, value: String },
+
+    #[error("incorrect binary data format in parameter ${index}: expected {expected}")]
+    InvalidBinaryParameter { index: usize, expected: &'static str },
+
+    #[error("bind message supplies {supplied} parameters, but prepared statement \"{statement}\" requires {required}")]
+    ParameterCount { statement: String, supplied: usize, required: usize },
+
+    #[error("prepared statement \"{0}\" does not exist")]
+    UnknownStatement(String),
+
+    #[error("prepared statement \"{0}\" already exists")]
+    DuplicateStatement(String),
+
+    #[error("portal \"{0}\" does not exist")]
+    UnknownPortal(String),
+
+    #[error("malformed {0} message")]
+    Malformed(&'static str),
+}
+
+impl ExtendedQueryError {
+    /// SQLSTATE code for the ErrorResponse
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            ExtendedQueryError::InvalidParameter { .. } => "22P02",
+            ExtendedQueryError::InvalidBinaryParameter { .. } => "22P03",
+            ExtendedQueryError::ParameterCount { .. } | ExtendedQueryError::Malformed(_) => "08P01",
+            ExtendedQueryError::UnknownStatement(_) => "26000",
+            ExtendedQueryError::DuplicateStatement(_) => "42P05",
+            ExtendedQueryError::UnknownPortal(_) => "34000",
+        }
+    }
+}
+
+/// Reads the fields of a message body
+struct Fields<'a> {
+    data: &'a [u8],
+    message: &'static str,
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ExtendedQueryError> {
+        if self.data.len() < length {
+            return Err(ExtendedQueryError::Malformed(self.message));
+        }
+        let (taken, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn string(&mut self) -> Result<String, ExtendedQueryError> {
+        let end = self.data.iter().position(|byte| *byte == 0)
+            .ok_or(ExtendedQueryError::Malformed(self.message))?;
+        let text = String::from_utf8(self.take(end)?.to_vec()).map_err(|_| ExtendedQueryError::Malformed(self.message))?;
+        self.take(1)?;
+        Ok(text)
+    }
+
+    fn i16(&mut self) -> Result<i16, ExtendedQueryError> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, ExtendedQueryError> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn count(&mut self) -> Result<usize, ExtendedQueryError> {
+        usize::try_from(self.i16()?).map_err(|_| ExtendedQueryError::Malformed(self.message))
+    }
+}
+
+/// Parse: a statement to prepare and the types it declares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMessage {
+    pub statement: String,
+    pub query: String,
+    /// OIDs of the leading parameters; 0 leaves one undeclared
+    pub parameter_types: Vec<u32>,
+}
+
+impl ParseMessage {
+    pub fn decode(body: &[u8]) -> Result<Self, ExtendedQueryError> {
+        let mut fields = Fields { data: body, message: "Parse" };
+        let statement = fields.string()?;
+        let query = fields.string()?;
+        let count = fields.count()?;
+        let parameter_types = (0..count).map(|_| fields.i32().map(|oid| oid as u32)).collect::<Result<_, _>>()?;
+        Ok(Self { statement, query, parameter_types })
+    }
+}
+
+/// Bind: values for a prepared statement's parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindMessage {
+    pub portal: String,
+    pub statement: String,
+    /// Per-parameter format codes: none means all text, one applies to all
+    pub formats: Vec<i16>,
+    /// Raw values; `None` is NULL
+    pub values: Vec<Option<Vec<u8>>>,
+}
+
+impl BindMessage {
+    pub fn decode(body: &[u8]) -> Result<Self, ExtendedQueryError> {
+        let mut fields = Fields { data: body, message: "Bind" };
+        let portal = fields.string()?;
+        let statement = fields.string()?;
+        let format_count = fields.count()?;
+        let formats = (0..format_count).map(|_| fields.i16()).collect::<Result<_, _>>()?;
+        let value_count = fields.count()?;
+        let values = (0..value_count).map(|_| match fields.i32()? {
+            -1 => Ok(None),
+            length => {
+                let length = usize::try_from(length).map_err(|_| ExtendedQueryError::Malformed("Bind"))?;
+                Ok(Some(fields.take(length)?.to_vec()))
+            }
+        }).collect::<Result<_, _>>()?;
+        // Result format codes follow; results are always sent as text
+        Ok(Self { portal, statement, formats, values })
+    }
+
+    fn is_binary(&self, position: usize) -> bool {
+        match self.formats.as_slice() {
+            [] => false,
+            [format] => *format == 1,
+            formats => formats.get(position) == Some(&1),
+        }
+    }
+}
+
+/// Describe and Close name a prepared statement (`S`) or a portal (`P`)
+pub fn decode_target(body: &[u8], message: &'static str) -> Result<(u8, String), ExtendedQueryError> {
+    let mut fields = Fields { data: body, message };
+    let kind = fields.take(1)?[0];
+    if kind != b'S' && kind != b'P' {
+        return Err(ExtendedQueryError::Malformed(message));
+    }
+    Ok((kind, fields.string()?))
+}
+
+/// Execute: the portal to run; its row limit is not honored, every row is sent
+pub fn decode_execute(body: &[u8]) -> Result<String, ExtendedQueryError> {
+    Fields { data: body, message: "Execute" }.string()
+}
+
+/// Check a raw parameter value against its type; `index` counts from 1
+pub fn coerce_parameter(index: usize, expected: ParameterType, raw: Option<&[u8]>, binary: bool) -> Result<ParameterValue, ExtendedQueryError> {
+    let Some(raw) = raw else {
+        return Ok(ParameterValue::Null);
+    };
+    if binary {
+        let invalid = || ExtendedQueryError::InvalidBinaryParameter { index, expected: expected.name() };
+        return match expected {
+            ParameterType::Integer => match raw.len() {
+                2 => Ok(ParameterValue::Integer(i16::from_be_bytes(raw.try_into().unwrap()).into())),
+                4 => Ok(ParameterValue::Integer(i32::from_be_bytes(raw.try_into().unwrap()).into())),
+                8 => Ok(ParameterValue::Integer(i64::from_be_bytes(raw.try_into().unwrap()))),
+                _ => Err(invalid()),
+            },
+            ParameterType::Float => match raw.len() {
+                4 => Ok(f32::from_be_bytes(raw.try_into().unwrap()).into()),
+                8 => Ok(f64::from_be_bytes(raw.try_into().unwrap())),
+                _ => Err(invalid()),
+            }.and_then(|value| if value.is_finite() { Ok(ParameterValue::Float(value)) } else { Err(invalid()) }),
+            ParameterType::Boolean => match raw {
+                [byte] => Ok(ParameterValue::Boolean(*byte != 0)),
+                _ => Err(invalid()),
+            },
+            ParameterType::Text | ParameterType::Unknown => Err(invalid()),
+        };
+    }
+
+    let text = String::from_utf8(raw.to_vec()).map_err(|_| ExtendedQueryError::InvalidParameter {
+        index,
+        expected: expected.name(),
+        value: String::from_utf8_lossy(raw).to_string(),
+    })?;
+    let value = match expected {
+        ParameterType::Integer => text.trim().parse().ok().map(ParameterValue::Integer),
+        // Integer text is widened like any other number
+        ParameterType::Float => text.trim().parse::<f64>().ok().filter(|value| value.is_finite()).map(ParameterValue::Float),
+        ParameterType::Boolean => match text.trim().to_ascii_lowercase().as_str() {
+            "t" | "true" | "yes" | "on" | "1" => Some(ParameterValue::Boolean(true)),
+            "f" | "false" | "no" | "off" | "0" => Some(ParameterValue::Boolean(false)),
+            _ => None,
+        },
+        ParameterType::Text => return Ok(ParameterValue::Text(text)),
+        ParameterType::Unknown => return Ok(ParameterValue::Untyped(text)),
+    };
+    value.ok_or(ExtendedQueryError::InvalidParameter { index, expected: expected.name(), value: text })
+}
+
+/// Lexical pieces of a statement that parameter inference looks at
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Word(String),
+    Parameter(usize),
+    Symbol(String),
+    /// A quoted string, whose content does not matter here
+    Literal,
+}
+
+fn pieces(query: &str) -> Vec<Piece> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c && chars.get(i + 1) == Some(&c) {
+                    i += 2;
+                } else if chars[i] == c {
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            i += 1;
+            pieces.push(match c {
+                '"' => Piece::Word(chars[start + 1..(i - 1).max(start + 1)].iter().collect()),
+                _ => Piece::Literal,
+            });
+        } else if c == '$' && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+            let start = i + 1;
+            i = start;
+            while chars.get(i).is_some_and(char::is_ascii_digit) {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            pieces.push(number.parse().map(Piece::Parameter).unwrap_or(Piece::Literal));
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while chars.get(i).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                i += 1;
+            }
+            pieces.push(Piece::Word(chars[start..i].iter().collect()));
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if matches!(two.as_str(), "<=" | ">=" | "<>" | "!=") {
+                pieces.push(Piece::Symbol(two));
+                i += 2;
+            } else {
+                pieces.push(Piece::Symbol(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+    pieces
+}
+
+fn is_keyword(piece: Option<&Piece>, keyword: &str) -> bool {
+    matches!(piece, Some(Piece::Word(word)) if word.eq_ignore_ascii_case(keyword))
+}
+
+fn is_comparison(piece: Option<&Piece>) -> bool {
+    matches!(piece, Some(Piece::Symbol(symbol)) if matches!(symbol.as_str(), "=" | "<" | ">" | "<=" | ">=" | "<>" | "!="))
+}
+
+/// Number of parameters a statement takes: the highest `$n` it names
+pub fn parameter_count(query: &str) -> usize {
+    pieces(query).iter()
+        .filter_map(|piece| match piece {
+            Piece::Parameter(number) => Some(*number),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Tables whose columns a statement's parameters may stand for
+pub fn referenced_tables(query: &str) -> Vec<String> {
+    let pieces = pieces(query);
+    let mut tables = Vec::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        let introduces = ["FROM", "JOIN", "INTO", "UPDATE"].iter().any(|keyword| is_keyword(Some(piece), keyword));
+        if let (true, Some(Piece::Word(table))) = (introduces, pieces.get(i + 1)) {
+            if !tables.contains(table) {
+                tables.push(table.clone());
+            }
+        }
+    }
+    tables
+}
+
+/// Infer the type of each of `count` parameters from the columns it is
+/// compared with, assigned to or inserted into. `columns` maps each of the
+/// statement's tables to its columns in table order.
+pub fn infer_parameter_types(query: &str, count: usize, columns: &HashMap<String, Vec<(String, ParameterType)>>) -> Vec<ParameterType> {
+    let pieces = pieces(query);
+    let mut types = vec![ParameterType::Unknown; count];
+    // A qualifier that names no table is an alias; any table may have the column
+    let column_type = |table: Option<&str>, column: &str| -> Option<ParameterType> {
+        let find = |table: Option<&str>| columns.iter()
+            .filter(|(name, _)| table.is_none_or(|table| table == name.as_str()))
+            .find_map(|(_, columns)| columns.iter().find(|(name, _)| name == column).map(|(_, data_type)| *data_type));
+        find(table).or_else(|| find(None))
+    };
+    // The first column a parameter meets decides its type
+    let infer = |types: &mut Vec<ParameterType>, number: usize, inferred: Option<ParameterType>| {
+        if let (Some(slot @ ParameterType::Unknown), Some(inferred)) = (types.get_mut(number.wrapping_sub(1)), inferred) {
+            *slot = inferred;
+        }
+    };
+
+    for (i, piece) in pieces.iter().enumerate() {
+        let Piece::Parameter(number) = piece else { continue };
+        // `column <op> $n`, with the column possibly qualified
+        if i >= 2 && is_comparison(pieces.get(i - 1)) {
+            if let Piece::Word(column) = &pieces[i - 2] {
+                let table = match (i >= 4).then(|| (&pieces[i - 3], &pieces[i - 4])) {
+                    Some((Piece::Symbol(dot), Piece::Word(table))) if dot == "." => Some(table.as_str()),
+                    _ => None,
+                };
+                infer(&mut types, *number, column_type(table, column));
+            }
+        }
+        // `$n <op> column`
+        if is_comparison(pieces.get(i + 1)) {
+            if let Some(Piece::Word(first)) = pieces.get(i + 2) {
+                let qualified = matches!(pieces.get(i + 3), Some(Piece::Symbol(dot)) if dot == ".");
+                match (qualified, pieces.get(i + 4)) {
+                    (true, Some(Piece::Word(column))) => infer(&mut types, *number, column_type(Some(first), column)),
+                    _ => infer(&mut types, *number, column_type(None, first)),
+                }
+            }
+        }
+    }
+
+    // `INSERT INTO table [(columns)] VALUES (...), ...`: each value takes
+    // the type of the column in its position
+    let Some(into) = pieces.iter().position(|piece| is_keyword(Some(piece), "INTO")) else {
+        return types;
+    };
+    let Some(Piece::Word(table)) = pieces.get(into + 1) else {
+        return types;
+    };
+    let Some(table_columns) = columns.get(table) else {
+        return types;
+    };
+    let mut position = into + 2;
+    let named: Vec<String> = if pieces.get(position) == Some(&Piece::Symbol("(".to_string())) {
+        let mut named = Vec::new();
+        position += 1;
+        while let Some(piece) = pieces.get(position) {
+            position += 1;
+            match piece {
+                Piece::Word(column) => named.push(column.clone()),
+                Piece::Symbol(symbol) if symbol == ")" => break,
+                _ => {}
+            }
+        }
+        named
+    } else {
+        table_columns.iter().map(|(name, _)| name.clone()).collect()
+    };
+    if !is_keyword(pieces.get(position), "VALUES") {
+        return types;
+    }
+
+    let (mut depth, mut item, mut item_pieces) = (0, 0, Vec::new());
+    for piece in &pieces[position + 1..] {
+        match piece {
+            Piece::Symbol(symbol) if symbol == "(" => {
+                depth += 1;
+                if depth == 1 {
+                    (item, item_pieces) = (0, Vec::new());
+                    continue;
+                }
+            }
+            Piece::Symbol(symbol) if symbol == ")" || (symbol == "," && depth == 1) => {
+                if depth == 1 {
+                    if let ([Piece::Parameter(number)], Some(column)) = (item_pieces.as_slice(), named.get(item)) {
+                        infer(&mut types, *number, column_type(Some(table), column));
+                    }
+                    (item, item_pieces) = (item + 1, Vec::new());
+                }
+                if symbol == ")" {
+                    depth -= 1;
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if depth >= 1 {
+            item_pieces.push(piece.clone());
+        }
+    }
+    types
+}
+
+/// Replace each `$n` outside quotes with the literal of its value
+pub fn substitute_parameters(query: &str, values: &[ParameterValue]) -> String {
+    let chars: Vec<char> = query.chars().collect();
+    let mut text = String::with_capacity(query.len());
+    let mut quote = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '$' && chars.get(i + 1).is_some_and(char::is_ascii_digit) => {
+                let start = i + 1;
+                let mut end = start;
+                while chars.get(end).is_some_and(char::is_ascii_digit) {
+                    end += 1;
+                }
+                let number: usize = chars[start..end].iter().collect::<String>().parse().unwrap_or(0);
+                if let Some(value) = number.checked_sub(1).and_then(|index| values.get(index)) {
+                    text.push_str(&value.to_literal());
+                    i = end;
+                    continue;
+                }
+            }
+            None => {}
+        }
+        text.push(c);
+        i += 1;
+    }
+    text
+}
+
+/// A statement prepared by Parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedQuery {
+    pub query: String,
+    pub parameter_types: Vec<ParameterType>,
+}
+
+/// Prepared statements and bound portals of one connection. The unnamed
+/// statement and portal (`""`) are replaced by each Parse and Bind.
+#[derive(Debug, Default)]
+pub struct ExtendedQuery {
+    statements: HashMap<String, PreparedQuery>,
+    /// Statement text with the portal's parameters substituted
+    portals: HashMap<String, String>,
+}
+
+impl ExtendedQuery {
+    /// Prepare `query` as `name`. Declared types take precedence over
+    /// `inferred`, parameter by parameter.
+    pub fn prepare(&mut self, message: &ParseMessage, inferred: Vec<ParameterType>) -> Result<(), ExtendedQueryError> {
+        if !message.statement.is_empty() && self.statements.contains_key(&message.statement) {
+            return Err(ExtendedQueryError::DuplicateStatement(message.statement.clone()));
+        }
+        let count = inferred.len().max(message.parameter_types.len());
+        let parameter_types = (0..count).map(|i| {
+            match message.parameter_types.get(i).map(|oid| ParameterType::from_oid(*oid)) {
+                Some(declared) if declared != ParameterType::Unknown => declared,
+                _ => inferred.get(i).copied().unwrap_or(ParameterType::Unknown),
+            }
+        }).collect();
+        self.statements.insert(message.statement.clone(), PreparedQuery { query: message.query.clone(), parameter_types });
+        Ok(())
+    }
+
+    pub fn statement(&self, name: &str) -> Result<&PreparedQuery, ExtendedQueryError> {
+        self.statements.get(name).ok_or_else(|| ExtendedQueryError::UnknownStatement(name.to_string()))
+    }
+
+    /// Check and coerce the values of `message` and bind them to its portal
+    pub fn bind(&mut self, message: &BindMessage) -> Result<(), ExtendedQueryError> {
+        let statement = self.statement(&message.statement)?;
+        if message.values.len() != statement.parameter_types.len() {
+            return Err(ExtendedQueryError::ParameterCount {
+                statement: message.statement.clone(),
+                supplied: message.values.len(),
+                required: statement.parameter_types.len(),
+            });
+        }
+        let values = statement.parameter_types.iter().zip(&message.values).enumerate()
+            .map(|(position, (expected, raw))| coerce_parameter(position + 1, *expected, raw.as_deref(), message.is_binary(position)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let query = substitute_parameters(&statement.query, &values);
+        self.portals.insert(message.portal.clone(), query);
+        Ok(())
+    }
+
+    /// Statement text of a bound portal
+    pub fn portal(&self, name: &str) -> Result<&str, ExtendedQueryError> {
+        self.portals.get(name).map(String::as_str).ok_or_else(|| ExtendedQueryError::UnknownPortal(name.to_string()))
+    }
+
+    /// Close a statement (`S`) or portal (`P`); closing one that does not
+    /// exist is not an error
+    pub fn close(&mut self, kind: u8, name: &str) {
+        match kind {
+            b'S' => {
+                self.statements.remove(name);
+            }
+            _ => {
+                self.portals.remove(name);
+            }
+        }
+    }
+}
@@ -7,9 +7,22 @@
 //! like window functions, aggregates, and MVCC transactions.
 
 pub mod postgres_protocol;
+pub mod protocol;
+pub mod protocols;
+pub mod query_escalation;
 pub mod connection_pool;
+pub mod distributed;
+pub mod extended_query;
 pub mod server;
+pub mod session_variables;
+pub mod statement_firewall;
+pub mod transaction_block;
 
 pub use postgres_protocol::*;
 pub use connection_pool::*;
-pub use server::*;
\ No newline at end of file
+pub use server::*;
+pub use extended_query::{ExtendedQuery, ExtendedQueryError, ParameterType, ParameterValue};
+pub use query_escalation::{Escalated, EscalationPolicies, EscalationPolicy};
+pub use session_variables::{SessionCommand, SessionError, SessionVariables};
+pub use statement_firewall::{FirewallError, FirewallMode, StatementFirewall, StatementKind};
+pub use transaction_block::{TransactionBlock, TransactionCommand, TransactionError, TransactionStatus};
\ No newline at end of file
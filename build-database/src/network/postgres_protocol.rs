@@ -7,11 +7,23 @@ use std::io::{Read, Write};
 use std::net::TcpStream;
 use bytes::{Buf, BufMut, BytesMut};
 use tokio::net::TcpListener;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::core::AuroraError;
 use crate::engine::AuroraDB;
+use crate::monitoring::{EscalationEvent, EscalationLevel, EscalationMonitor};
 use crate::security::UserContext;
+use super::extended_query::{
+    decode_execute, decode_target, infer_parameter_types, parameter_count, referenced_tables, BindMessage,
+    ExtendedQuery, ExtendedQueryError, ParameterType, ParseMessage,
+};
+use super::protocol::{self, ProtocolError, DEFAULT_MAX_MESSAGE_SIZE};
+use super::session_variables::{SessionCommand, SessionVariables};
+use super::query_escalation::{Escalated, EscalationPolicies};
+use super::statement_firewall::{StatementFirewall, StatementKind};
+use super::transaction_block::{TransactionBlock, TransactionCommand, TransactionStatus};
 
 /// PostgreSQL protocol version
 const PROTOCOL_VERSION: i32 = 196608; // 3.0
@@ -33,11 +45,32 @@ pub enum MessageType {
 /// PostgreSQL protocol handler
 pub struct PostgresProtocol {
     db: Arc<AuroraDB>,
+    firewall: Arc<StatementFirewall>,
+    escalation: Arc<EscalationPolicies>,
+    escalation_monitor: Arc<EscalationMonitor>,
 }
 
 impl PostgresProtocol {
     pub fn new(db: Arc<AuroraDB>) -> Self {
-        Self { db }
+        Self::with_firewall(db, StatementFirewall::allow_all())
+    }
+
+    /// Protocol handler whose connections screen statements with `firewall`
+    pub fn with_firewall(db: Arc<AuroraDB>, firewall: StatementFirewall) -> Self {
+        Self {
+            db,
+            firewall: Arc::new(firewall),
+            escalation: Arc::new(EscalationPolicies::default()),
+            escalation_monitor: Arc::new(EscalationMonitor::default()),
+        }
+    }
+
+    /// Run slow queries under `policies`, recording each stage taken in
+    /// `monitor`
+    pub fn with_escalation(mut self, policies: EscalationPolicies, monitor: Arc<EscalationMonitor>) -> Self {
+        self.escalation = Arc::new(policies);
+        self.escalation_monitor = monitor;
+        self
     }
 
     /// Handle a client connection
@@ -55,11 +88,42 @@ impl PostgresProtocol {
         let password = self.read_password_response(&mut socket).await?;
         log::debug!("Password received: {}", if password.is_empty() { "(empty)" } else { "(provided)" });
 
+        // The login role decides the tenant whose connection quota this
+        // connection counts against until it closes
+        let Some(user) = Self::startup_parameter(&startup_message, "user") else {
+            socket.write_all(&self.create_error_response("28000", "no PostgreSQL user name specified in startup packet")).await?;
+            return Ok(());
+        };
+        let tenant = self.db.tenant_governor().tenant_of_role(&user);
+        let _connection = match self.db.tenant_governor().open_connection(&tenant) {
+            Ok(permit) => permit,
+            Err(e) => {
+                log::warn!("Refusing connection of role '{}': {}", user, e);
+                socket.write_all(&self.create_error_response(e.sqlstate(), &e.to_string())).await?;
+                return Ok(());
+            }
+        };
+
         // Send authentication success
         self.send_authentication_ok(&mut socket).await?;
 
+        // Each connection is its own session, with its own settings
+        let user_context = UserContext {
+            username: user,
+            session_id: format!("pg-{}", uuid::Uuid::new_v4()),
+            ..UserContext::system_user()
+        };
+        let _session = SessionGuard { db: &self.db, session_id: &user_context.session_id };
+        let mut session = SessionVariables::new(self.db.session_work_mem(&user_context.session_id));
+
         // Send ready for query
-        self.send_ready_for_query(&mut socket).await?;
+        let mut transaction = TransactionBlock::new();
+        self.send_ready_for_query(&mut socket, transaction.status()).await?;
+
+        // Prepared statements and portals; after an extended query message
+        // fails, the rest up to the next Sync are ignored
+        let mut extended = ExtendedQuery::default();
+        let mut skip_until_sync = false;
 
         // Main query loop
         loop {
@@ -67,25 +131,77 @@ impl PostgresProtocol {
                 Ok(Some((message_type, message_data))) => {
                     match message_type {
                         b'Q' => { // Query message
-                            let query = String::from_utf8_lossy(&message_data[4..]); // Skip length
-                            log::info!("Executing query: {}", query.trim());
-
-                            match self.execute_query(&query.trim()).await {
+                            let query = String::from_utf8_lossy(&message_data);
+                            let query = query.trim_end_matches('\0').trim();
+                            log::info!("Executing query: {}", query);
+
+                            let response = match self.run_statement(query, &mut transaction, &mut session, &user_context).await {
+                                StatementReply::Done(response) => response,
+                                StatementReply::Killed => {
+                                    socket.write_all(&self.create_error_response("57P01", "terminating connection due to query escalation policy")).await?;
+                                    break;
+                                }
+                            };
+                            match response {
                                 Ok(response_messages) => {
                                     for message in response_messages {
                                         socket.write_all(&message).await?;
                                     }
                                 }
-                                Err(e) => {
-                                    log::error!("Query execution failed: {}", e);
-                                    let error_msg = self.create_error_response(&format!("Query execution failed: {}", e));
+                                Err(error_msg) => {
                                     socket.write_all(&error_msg).await?;
                                 }
                             }
 
                             // Send ready for query after each command
-                            self.send_ready_for_query(&mut socket).await?;
+                            self.send_ready_for_query(&mut socket, transaction.status()).await?;
                         }
+                        b'P' | b'B' | b'D' | b'E' | b'C' => { // Extended query messages
+                            if skip_until_sync {
+                                continue;
+                            }
+                            let response = match message_type {
+                                b'E' => match decode_execute(&message_data).and_then(|portal| Ok(extended.portal(&portal)?.to_string())) {
+                                    Ok(query) => {
+                                        log::info!("Executing portal: {}", query);
+                                        match self.run_statement(&query, &mut transaction, &mut session, &user_context).await {
+                                            StatementReply::Done(response) => response,
+                                            StatementReply::Killed => {
+                                                socket.write_all(&self.create_error_response("57P01", "terminating connection due to query escalation policy")).await?;
+                                                break;
+                                            }
+                                        }
+                                    }
+                                    Err(e) => {
+                                        transaction.statement_failed();
+                                        Err(self.create_error_response(e.sqlstate(), &e.to_string()))
+                                    }
+                                },
+                                _ => self.extended_message(message_type, &message_data, &mut extended).await
+                                    .map_err(|e| {
+                                        log::warn!("Extended query message refused: {}", e);
+                                        transaction.statement_failed();
+                                        self.create_error_response(e.sqlstate(), &e.to_string())
+                                    }),
+                            };
+                            match response {
+                                Ok(response_messages) => {
+                                    for message in response_messages {
+                                        socket.write_all(&message).await?;
+                                    }
+                                }
+                                Err(error_msg) => {
+                                    // Messages up to the next Sync are discarded
+                                    socket.write_all(&error_msg).await?;
+                                    skip_until_sync = true;
+                                }
+                            }
+                        }
+                        b'S' => { // Sync
+                            skip_until_sync = false;
+                            self.send_ready_for_query(&mut socket, transaction.status()).await?;
+                        }
+                        b'H' => {} // Flush; responses are never held back
                         b'X' => { // Terminate
                             log::info!("Client disconnected");
                             break;
@@ -99,26 +215,221 @@ impl PostgresProtocol {
                     log::info!("Connection closed by client");
                     break;
                 }
-                Err(e) => {
+                Err(ProtocolError::ConnectionError(e)) => {
                     log::error!("Error reading message: {}", e);
                     break;
                 }
+                Err(e) => {
+                    // The stream cannot be trusted past a malformed frame
+                    log::warn!("Closing connection after malformed message: {}", e);
+                    socket.write_all(&self.create_error_response("08P01", &e.to_string())).await?;
+                    break;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Run one statement of a simple query or an executed portal
+    ///
+    /// Transaction control and session settings are handled here; other
+    /// statements are refused while the block is aborted, and must pass the
+    /// firewall before they are planned. They run under the session's
+    /// statement_timeout and the role's escalation policy.
+    async fn run_statement(&self, query: &str, transaction: &mut TransactionBlock, session: &mut SessionVariables, user_context: &UserContext) -> StatementReply {
+        let response = match TransactionCommand::parse(query) {
+            Some(command) => transaction.apply(command)
+                .map(|tag| vec![self.create_command_tag(tag)])
+                .map_err(|e| self.create_error_response(e.sqlstate(), &e.to_string())),
+            None => match transaction.check_statement() {
+                Err(e) => Err(self.create_error_response(e.sqlstate(), &e.to_string())),
+                Ok(()) => match self.firewall.check(query, self.db.functions()) {
+                    Err(e) => {
+                        log::warn!("Firewall rejected query: {}", e);
+                        transaction.statement_failed();
+                        Err(self.create_error_response(e.sqlstate(), &e.to_string()))
+                    }
+                    Ok(()) => match SessionCommand::parse(query) {
+                        Some(command) => self.session_command(&command, session, &user_context.session_id)
+                            .map_err(|error_msg| {
+                                transaction.statement_failed();
+                                error_msg
+                            }),
+                        None => {
+                            let execution = async {
+                                let execution = self.execute_query(query, user_context);
+                                match session.statement_timeout() {
+                                    Some(timeout) => tokio::time::timeout(timeout, execution).await,
+                                    None => Ok(execution.await),
+                                }
+                            };
+                            let (outcome, reached) = match self.execute_escalated(query, user_context, execution).await {
+                                Escalated::Finished { output, reached } => (output, reached),
+                                Escalated::Killed => return StatementReply::Killed,
+                            };
+                            match outcome {
+                                Ok(Ok(messages)) => Ok(messages),
+                                Ok(Err(e)) => {
+                                    log::error!("Query execution failed: {}", e);
+                                    transaction.statement_failed();
+                                    // Refusals such as tenant quotas carry their own SQLSTATE
+                                    let refusal = e.downcast_ref::<AuroraError>()
+                                        .and_then(|e| Some((e.context.get("sqlstate")?, &e.message)));
+                                    match refusal {
+                                        Some((sqlstate, _)) if sqlstate == "57014" && reached == Some(EscalationLevel::Cancel) => {
+                                            Err(self.create_error_response(sqlstate, "canceling statement due to query escalation policy"))
+                                        }
+                                        Some((sqlstate, message)) => Err(self.create_error_response(sqlstate, message)),
+                                        None => Err(self.create_error_response("XX000", &format!("Query execution failed: {}", e))),
+                                    }
+                                }
+                                Err(_) => {
+                                    log::warn!("Query cancelled by statement_timeout: {}", query);
+                                    transaction.statement_failed();
+                                    Err(self.create_error_response("57014", "canceling statement due to statement timeout"))
+                                }
+                            }
+                        }
+                    },
+                },
+            },
+        };
+        StatementReply::Done(response)
+    }
+
+    /// Answer a Parse, Bind, Describe or Close message
+    ///
+    /// Parse infers the types of undeclared parameters from the columns of
+    /// the tables the statement names; Bind checks the values against them.
+    async fn extended_message(&self, message_type: u8, body: &[u8], extended: &mut ExtendedQuery) -> Result<Vec<Vec<u8>>, ExtendedQueryError> {
+        match message_type {
+            b'P' => {
+                let message = ParseMessage::decode(body)?;
+                let mut columns = HashMap::new();
+                for table in referenced_tables(&message.query) {
+                    if let Ok(mut table_columns) = self.db.describe_table(&table).await {
+                        table_columns.sort_by_key(|column| column.ordinal_position);
+                        let typed = table_columns.iter()
+                            .map(|column| (column.name.clone(), ParameterType::of_column(&column.data_type)))
+                            .collect();
+                        columns.insert(table, typed);
+                    }
+                }
+                let inferred = infer_parameter_types(&message.query, parameter_count(&message.query), &columns);
+                extended.prepare(&message, inferred)?;
+                Ok(vec![self.create_empty_message(b'1')]) // ParseComplete
+            }
+            b'B' => {
+                extended.bind(&BindMessage::decode(body)?)?;
+                Ok(vec![self.create_empty_message(b'2')]) // BindComplete
+            }
+            b'D' => match decode_target(body, "Describe")? {
+                (b'S', name) => {
+                    let statement = extended.statement(&name)?;
+                    Ok(vec![
+                        self.create_parameter_description(&statement.parameter_types),
+                        self.create_empty_message(b'n'), // NoData; rows are described by Execute
+                    ])
+                }
+                (_, name) => {
+                    extended.portal(&name)?;
+                    Ok(vec![self.create_empty_message(b'n')])
+                }
+            },
+            _ => {
+                let (kind, name) = decode_target(body, "Close")?;
+                extended.close(kind, &name);
+                Ok(vec![self.create_empty_message(b'3')]) // CloseComplete
+            }
+        }
+    }
+
     /// Read startup message
+    ///
+    /// A malformed one is answered with a protocol violation error before
+    /// the connection is closed.
     async fn read_startup_message(&self, socket: &mut tokio::net::TcpStream) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-        let mut length_buf = [0u8; 4];
-        socket.read_exact(&mut length_buf).await?;
-        let length = u32::from_be_bytes(length_buf) as usize;
+        match protocol::read_startup(socket).await {
+            Ok(message) => Ok(message),
+            Err(e) => {
+                if !matches!(e, ProtocolError::ConnectionError(_)) {
+                    socket.write_all(&self.create_error_response("08P01", &e.to_string())).await?;
+                }
+                Err(e.into())
+            }
+        }
+    }
 
-        let mut message = vec![0u8; length - 4];
-        socket.read_exact(&mut message).await?;
+    /// Drive `execution` through the escalation stages of its role and
+    /// statement kind: warn with a plan snapshot, then cancel it, then drop it
+    async fn execute_escalated<F: std::future::Future>(&self, query: &str, user_context: &UserContext, execution: F) -> Escalated<F::Output> {
+        let started = tokio::time::Instant::now();
+        let policy = self.escalation.policy(&user_context.username, StatementKind::of(query));
+        tokio::pin!(execution);
+
+        let mut reached = None;
+        for (level, after) in policy.stages() {
+            tokio::select! {
+                output = &mut execution => return Escalated::Finished { output, reached },
+                _ = tokio::time::sleep_until(started + after) => {}
+            }
+            reached = Some(level);
+            let plan = match level {
+                EscalationLevel::Warn => self.plan_snapshot(query, user_context).await,
+                EscalationLevel::Cancel | EscalationLevel::Kill => None,
+            };
+            self.escalation_monitor.record(EscalationEvent {
+                level,
+                session_id: user_context.session_id.clone(),
+                role: user_context.username.clone(),
+                query: query.to_string(),
+                elapsed: started.elapsed(),
+                plan,
+                at: std::time::SystemTime::now(),
+            });
+            match level {
+                EscalationLevel::Warn => {}
+                EscalationLevel::Cancel => {
+                    self.db.cancel_statement(&user_context.session_id);
+                }
+                // Dropping the statement drops its guards, which abort its
+                // open transactions and release its locks and quota
+                EscalationLevel::Kill => return Escalated::Killed,
+            }
+        }
+        Escalated::Finished { output: execution.await, reached }
+    }
 
-        Ok(message)
+    /// EXPLAIN output of a running query, planned in a session of its own
+    async fn plan_snapshot(&self, query: &str, user_context: &UserContext) -> Option<Vec<String>> {
+        let explain_context = UserContext {
+            session_id: format!("{}-escalation", user_context.session_id),
+            ..user_context.clone()
+        };
+        match self.db.execute_query(&format!("EXPLAIN {}", query), &explain_context).await {
+            Ok(result) => Some(result.query_plan?.lines().map(str::to_string).collect()),
+            Err(e) => {
+                log::debug!("No plan snapshot for escalated query: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Value of a parameter of the startup message, which follows the
+    /// protocol version as NUL-terminated name/value pairs
+    fn startup_parameter(message: &[u8], name: &str) -> Option<String> {
+        let mut fields = message.get(4..)?.split(|byte| *byte == 0);
+        while let Some(key) = fields.next() {
+            if key.is_empty() {
+                break;
+            }
+            let value = fields.next()?;
+            if key == name.as_bytes() {
+                return Some(String::from_utf8_lossy(value).to_string()).filter(|value| !value.is_empty());
+            }
+        }
+        None
     }
 
     /// Read password response
@@ -130,26 +441,13 @@ impl PostgresProtocol {
             return Err(format!("Expected password message, got {}", msg_type).into());
         }
 
-        // Skip length (4 bytes) and get password
-        Ok(String::from_utf8_lossy(&data[4..]).to_string())
+        Ok(String::from_utf8_lossy(&data).trim_end_matches('\0').to_string())
     }
 
-    /// Read a protocol message
-    async fn read_message(&self, socket: &mut tokio::net::TcpStream) -> Result<Option<(u8, Vec<u8>)>, Box<dyn std::error::Error>> {
-        let mut type_buf = [0u8; 1];
-        match socket.read_exact(&mut type_buf).await {
-            Ok(_) => {}
-            Err(_) => return Ok(None), // Connection closed
-        }
-
-        let mut length_buf = [0u8; 4];
-        socket.read_exact(&mut length_buf).await?;
-        let length = u32::from_be_bytes(length_buf) as usize;
-
-        let mut data = vec![0u8; length - 4];
-        socket.read_exact(&mut data).await?;
-
-        Ok(Some((type_buf[0], [length_buf.to_vec(), data].concat())))
+    /// Read a protocol message, returning its type and body
+    async fn read_message(&self, socket: &mut tokio::net::TcpStream) -> Result<Option<(u8, Vec<u8>)>, ProtocolError> {
+        let frame = protocol::read_frame(socket, DEFAULT_MAX_MESSAGE_SIZE).await?;
+        Ok(frame.map(|frame| (frame.message_type, frame.body)))
     }
 
     /// Send authentication cleartext password request
@@ -174,22 +472,77 @@ impl PostgresProtocol {
         Ok(())
     }
 
-    /// Send ready for query
-    async fn send_ready_for_query(&self, socket: &mut tokio::net::TcpStream) -> Result<(), Box<dyn std::error::Error>> {
+    /// Send ready for query with the connection's transaction status
+    async fn send_ready_for_query(&self, socket: &mut tokio::net::TcpStream, status: TransactionStatus) -> Result<(), Box<dyn std::error::Error>> {
         let mut buf = BytesMut::new();
         buf.put_u8(b'Z'); // ReadyForQuery
         buf.put_u32(5); // Length
-        buf.put_u8(b'I'); // Idle
+        buf.put_u8(status.indicator());
 
         socket.write_all(&buf).await?;
         Ok(())
     }
 
-    /// Execute a query and return response messages
-    async fn execute_query(&self, query: &str) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
-        let user_context = UserContext::system_user();
+    /// Apply a SET or RESET to the connection's session, or answer a SHOW
+    ///
+    /// The engine keeps `timezone`, `work_mem`, profiling and
+    /// `stable_scan_order` per session, so changes to them are passed on; `statement_timeout` is enforced by the
+    /// connection. `SHOW query_profile` returns the collapsed stacks of the
+    /// session's latest profiled statement, one per row.
+    fn session_command(&self, command: &SessionCommand, session: &mut SessionVariables, session_id: &str) -> Result<Vec<Vec<u8>>, Vec<u8>> {
+        if *command == SessionCommand::Show("query_profile".to_string()) {
+            let rows = self.db.last_query_profile(session_id)
+                .map(|profile| profile.stacks.iter().map(|(stack, count)| vec![format!("{} {}", stack, count)]).collect())
+                .unwrap_or_default();
+            return self.text_result(command, &["query_profile".to_string()], rows);
+        }
+        session.apply(command)
+            .map_err(|e| self.create_error_response(e.sqlstate(), &e.to_string()))?;
+
+        let (columns, rows) = match command {
+            SessionCommand::Show(name) => {
+                let value = session.show(name)
+                    .map_err(|e| self.create_error_response(e.sqlstate(), &e.to_string()))?;
+                (vec![name.clone()], vec![vec![value]])
+            }
+            SessionCommand::ShowAll => {
+                let rows = session.show_all().into_iter().map(|(name, value)| vec![name, value]).collect();
+                (vec!["name".to_string(), "setting".to_string()], rows)
+            }
+            _ => {
+                match session.time_zone() {
+                    Some(time_zone) => self.db.set_session_timezone(session_id, &time_zone.to_string())
+                        .map_err(|e| self.create_error_response("22023", &e.to_string()))?,
+                    None => self.db.reset_session_timezone(session_id),
+                }
+                match session.work_mem_override() {
+                    Some(bytes) => self.db.set_session_work_mem(session_id, bytes),
+                    None => self.db.reset_session_work_mem(session_id),
+                }
+                match session.profiler_sample_rate() {
+                    Some(sample_rate_hz) => self.db.set_session_profiling(session_id, sample_rate_hz)
+                        .map_err(|e| self.create_error_response("22023", &e.to_string()))?,
+                    None => self.db.reset_session_profiling(session_id),
+                }
+                self.db.set_session_stable_scan_order(session_id, session.stable_scan_order());
+                return Ok(vec![self.create_command_tag(command.tag())]);
+            }
+        };
+        self.text_result(command, &columns, rows)
+    }
+
+    /// RowDescription, DataRows and CommandComplete of a SHOW
+    fn text_result(&self, command: &SessionCommand, columns: &[String], rows: Vec<Vec<String>>) -> Result<Vec<Vec<u8>>, Vec<u8>> {
+        let mut messages = vec![self.create_row_description(columns.iter())
+            .map_err(|e| self.create_error_response("XX000", &e.to_string()))?];
+        messages.extend(rows.iter().map(|row| self.create_text_row(row)));
+        messages.push(self.create_command_tag(command.tag()));
+        Ok(messages)
+    }
 
-        match self.db.execute_query(query, &user_context).await {
+    /// Execute a query in the connection's session and return response messages
+    async fn execute_query(&self, query: &str, user_context: &UserContext) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
+        match self.db.execute_query(query, user_context).await {
             Ok(result) => {
                 let mut messages = Vec::new();
 
@@ -293,11 +646,27 @@ impl PostgresProtocol {
         Ok(buf.to_vec())
     }
 
-    /// Create command complete message
-    fn create_command_complete(&self, result: &crate::engine::QueryResult) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    /// Create data row message from text values, in column order
+    fn create_text_row(&self, values: &[String]) -> Vec<u8> {
         let mut buf = BytesMut::new();
-        buf.put_u8(b'C'); // CommandComplete
+        buf.put_u8(b'D'); // DataRow
+
+        let length_pos = buf.len();
+        buf.put_u32(0); // Length placeholder
+        buf.put_u16(values.len() as u16);
+        for value in values {
+            buf.put_u32(value.len() as u32); // Column length
+            buf.put_slice(value.as_bytes()); // Column data
+        }
+
+        let length = (buf.len() - length_pos - 4) as u32;
+        buf[length_pos..length_pos + 4].copy_from_slice(&length.to_be_bytes());
 
+        buf.to_vec()
+    }
+
+    /// Create command complete message
+    fn create_command_complete(&self, result: &crate::engine::QueryResult) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         let tag = if result.rows_affected.is_some() {
             format!("INSERT 0 {}", result.rows_affected.unwrap_or(0))
         } else if result.rows.is_some() {
@@ -306,30 +675,82 @@ impl PostgresProtocol {
             "SELECT 0".to_string()
         };
 
+        Ok(self.create_command_tag(&tag))
+    }
+
+    /// Create a command complete message with the given tag
+    fn create_command_tag(&self, tag: &str) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'C'); // CommandComplete
+
         let tag_bytes = tag.as_bytes();
         buf.put_u32((4 + tag_bytes.len() + 1) as u32); // Length
         buf.put_slice(tag_bytes);
         buf.put_u8(0); // Null terminator
 
-        Ok(buf.to_vec())
+        buf.to_vec()
     }
 
-    /// Create error response message
-    fn create_error_response(&self, error_msg: &str) -> Vec<u8> {
+    /// Create a message with no body, such as ParseComplete or NoData
+    fn create_empty_message(&self, message_type: u8) -> Vec<u8> {
         let mut buf = BytesMut::new();
-        buf.put_u8(b'E'); // ErrorResponse
+        buf.put_u8(message_type);
+        buf.put_u32(4); // Length
+        buf.to_vec()
+    }
 
-        let error_bytes = error_msg.as_bytes();
-        buf.put_u32((4 + 1 + error_bytes.len() + 1) as u32); // Length
-        buf.put_u8(b'M'); // Error message field
-        buf.put_slice(error_bytes);
-        buf.put_u8(0); // Null terminator
-        buf.put_u8(0); // Message terminator
+    /// Create a ParameterDescription message with each parameter's type OID
+    fn create_parameter_description(&self, parameter_types: &[ParameterType]) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b't'); // ParameterDescription
+        buf.put_u32((4 + 2 + 4 * parameter_types.len()) as u32); // Length
+        buf.put_u16(parameter_types.len() as u16);
+        for parameter_type in parameter_types {
+            buf.put_u32(parameter_type.oid());
+        }
+        buf.to_vec()
+    }
+
+    /// Create error response message with severity, SQLSTATE and message fields
+    fn create_error_response(&self, sqlstate: &str, error_msg: &str) -> Vec<u8> {
+        let mut fields = BytesMut::new();
+        for (field, value) in [(b'S', "ERROR"), (b'C', sqlstate), (b'M', error_msg)] {
+            fields.put_u8(field);
+            fields.put_slice(value.as_bytes());
+            fields.put_u8(0); // Null terminator
+        }
+        fields.put_u8(0); // Message terminator
+
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'E'); // ErrorResponse
+        buf.put_u32((4 + fields.len()) as u32); // Length
+        buf.put_slice(&fields);
 
         buf.to_vec()
     }
 }
 
+/// What running one statement sends back
+enum StatementReply {
+    /// Response messages, or the ErrorResponse
+    Done(Result<Vec<Vec<u8>>, Vec<u8>>),
+    /// The escalation policy killed the statement; the connection closes
+    Killed,
+}
+
+/// Clears a connection's session settings from the engine when the
+/// connection ends, however it ends
+struct SessionGuard<'a> {
+    db: &'a AuroraDB,
+    session_id: &'a str,
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        self.db.end_session(self.session_id);
+    }
+}
+
 /// PostgreSQL server implementation
 pub struct PostgresServer {
     protocol: PostgresProtocol,
@@ -338,8 +759,13 @@ pub struct PostgresServer {
 
 impl PostgresServer {
     pub fn new(db: Arc<AuroraDB>, address: String) -> Self {
+        Self::with_firewall(db, address, StatementFirewall::allow_all())
+    }
+
+    /// Server whose connections screen statements with `firewall`
+    pub fn with_firewall(db: Arc<AuroraDB>, address: String, firewall: StatementFirewall) -> Self {
         Self {
-            protocol: PostgresProtocol::new(db),
+            protocol: PostgresProtocol::with_firewall(db, firewall),
             address,
         }
     }
@@ -367,6 +793,7 @@ impl Clone for PostgresProtocol {
     fn clone(&self) -> Self {
         Self {
             db: Arc::clone(&self.db),
+            firewall: Arc::clone(&self.firewall),
         }
     }
 }
@@ -5,11 +5,24 @@
 //! - Custom binary protocol for performance
 //! - HTTP/JSON for web applications
 //! - gRPC for distributed communication
+//!
+//! Client frames are untrusted: every length a client sends is checked
+//! against what is left of the input and against a maximum message size
+//! before anything is sliced or allocated, so a malformed, truncated or
+//! oversized frame is a `ProtocolError` rather than a panic.
 
 use crate::core::*;
 use std::collections::HashMap;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use super::protocols::*;
 
+/// Largest message a client may send, length word included
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Largest startup message; it carries a few parameters, and the server
+/// reads it before the client has authenticated
+pub const MAX_STARTUP_MESSAGE_SIZE: usize = 10_000;
+
 /// Wire protocol abstraction supporting multiple formats
 pub struct WireProtocol {
     /// Protocol format
@@ -128,6 +141,9 @@ pub enum ProtocolError {
     #[error("Message too large: {size} bytes (max {max})")]
     MessageTooLarge { size: usize, max: usize },
 
+    #[error("Invalid message length: {0}")]
+    InvalidLength(u32),
+
     #[error("Authentication failed: {reason}")]
     AuthenticationFailed { reason: String },
 
@@ -215,4 +231,81 @@ impl WireProtocol {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// One client message: its type byte and the body after the length word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub message_type: u8,
+    pub body: Vec<u8>,
+}
+
+/// Body length of a message whose length word is `length`
+///
+/// The length word counts itself, so anything below 4 is malformed.
+pub fn frame_body_length(length: u32, max_message_size: usize) -> Result<usize, ProtocolError> {
+    if length < 4 {
+        return Err(ProtocolError::InvalidLength(length));
+    }
+    if length as usize > max_message_size {
+        return Err(ProtocolError::MessageTooLarge { size: length as usize, max: max_message_size });
+    }
+    Ok(length as usize - 4)
+}
+
+/// Decode the typed message at the front of `buf`
+///
+/// Returns the frame and the number of bytes it took, or `None` if `buf`
+/// holds only part of it.
+pub fn decode_frame(buf: &[u8], max_message_size: usize) -> Result<Option<(Frame, usize)>, ProtocolError> {
+    let Some(header) = buf.get(..5) else { return Ok(None) };
+    let length = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+    let body_length = frame_body_length(length, max_message_size)?;
+
+    let Some(body) = buf.get(5..5 + body_length) else { return Ok(None) };
+    let frame = Frame { message_type: header[0], body: body.to_vec() };
+    Ok(Some((frame, 5 + body_length)))
+}
+
+/// Decode the untyped startup message at the front of `buf`, returning its
+/// body and the number of bytes it took
+pub fn decode_startup(buf: &[u8]) -> Result<Option<(Vec<u8>, usize)>, ProtocolError> {
+    let Some(header) = buf.get(..4) else { return Ok(None) };
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let body_length = frame_body_length(length, MAX_STARTUP_MESSAGE_SIZE)?;
+
+    let Some(body) = buf.get(4..4 + body_length) else { return Ok(None) };
+    Ok(Some((body.to_vec(), 4 + body_length)))
+}
+
+/// Read one typed message, checking its length before allocating the body
+///
+/// Returns `None` if the stream ends cleanly before a message starts.
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R, max_message_size: usize) -> Result<Option<Frame>, ProtocolError> {
+    let message_type = match reader.read_u8().await {
+        Ok(message_type) => message_type,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(ProtocolError::ConnectionError(e.to_string())),
+    };
+    let length = read_length(reader).await?;
+    let body = read_body(reader, frame_body_length(length, max_message_size)?).await?;
+    Ok(Some(Frame { message_type, body }))
+}
+
+/// Read the untyped startup message, returning its body
+pub async fn read_startup<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, ProtocolError> {
+    let length = read_length(reader).await?;
+    read_body(reader, frame_body_length(length, MAX_STARTUP_MESSAGE_SIZE)?).await
+}
+
+async fn read_length<R: AsyncRead + Unpin>(reader: &mut R) -> Result<u32, ProtocolError> {
+    reader.read_u32().await
+        .map_err(|e| ProtocolError::ConnectionError(format!("truncated message header: {}", e)))
+}
+
+async fn read_body<R: AsyncRead + Unpin>(reader: &mut R, length: usize) -> Result<Vec<u8>, ProtocolError> {
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await
+        .map_err(|e| ProtocolError::ConnectionError(format!("truncated message body: {}", e)))?;
+    Ok(body)
+}
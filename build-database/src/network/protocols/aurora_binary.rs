@@ -46,7 +46,10 @@ impl MessageSerializer for AuroraBinarySerializer {
     }
 
     fn deserialize(&self, data: &[u8]) -> Result<AuroraMessage, ProtocolError> {
-        if data.len() < 12 { // Minimum header size
+        if data.len() > DEFAULT_MAX_MESSAGE_SIZE {
+            return Err(ProtocolError::MessageTooLarge { size: data.len(), max: DEFAULT_MAX_MESSAGE_SIZE });
+        }
+        if data.len() < 14 { // Header plus metadata length
             return Err(ProtocolError::DeserializationError("Message too short".to_string()));
         }
 
@@ -57,35 +60,23 @@ impl MessageSerializer for AuroraBinarySerializer {
 
         let msg_type_u16 = u16::from_le_bytes([data[4], data[5]]);
         let message_type = u16_to_message_type(msg_type_u16)
-            .ok_or_else(|| ProtocolError::InvalidMessageType(msg_type_u16 as u8))?;
-
-        let payload_len = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
-
-        if data.len() < 12 + payload_len {
-            return Err(ProtocolError::DeserializationError("Incomplete payload".to_string()));
+            .ok_or_else(|| ProtocolError::InvalidMessageType(msg_type_u16.min(u8::MAX as u16) as u8))?;
+
+        // Both lengths come from the client; slice only what is really there
+        let (payload, rest) = length_prefixed(&data[6..])
+            .ok_or_else(|| ProtocolError::DeserializationError("Incomplete payload".to_string()))?;
+        let (metadata_json, rest) = length_prefixed(rest)
+            .ok_or_else(|| ProtocolError::DeserializationError("Incomplete metadata".to_string()))?;
+        if !rest.is_empty() {
+            return Err(ProtocolError::DeserializationError(format!("{} trailing bytes", rest.len())));
         }
 
-        let payload = data[10..10 + payload_len].to_vec();
-
-        // Parse metadata if present
-        let metadata_offset = 10 + payload_len;
-        let metadata = if data.len() > metadata_offset + 4 {
-            let metadata_len = u32::from_le_bytes([
-                data[metadata_offset],
-                data[metadata_offset + 1],
-                data[metadata_offset + 2],
-                data[metadata_offset + 3],
-            ]) as usize;
-
-            if data.len() >= metadata_offset + 4 + metadata_len {
-                let metadata_json = &data[metadata_offset + 4..metadata_offset + 4 + metadata_len];
-                serde_json::from_slice(metadata_json)
-                    .unwrap_or_default()
-            } else {
-                HashMap::new()
-            }
-        } else {
+        let payload = payload.to_vec();
+        let metadata = if metadata_json.is_empty() {
             HashMap::new()
+        } else {
+            serde_json::from_slice(metadata_json)
+                .map_err(|e| ProtocolError::DeserializationError(format!("Invalid metadata: {}", e)))?
         };
 
         Ok(AuroraMessage {
@@ -96,6 +87,13 @@ impl MessageSerializer for AuroraBinarySerializer {
     }
 }
 
+/// Split a little-endian u32 length and that many bytes off the front of `data`
+fn length_prefixed(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let length = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    let rest = &data[4..];
+    (length <= rest.len()).then(|| rest.split_at(length))
+}
+
 /// High-performance vector encoding for AuroraDB protocol
 pub struct VectorEncoder;
 
@@ -111,7 +109,7 @@ impl VectorEncoder {
 
     /// Decode vector from AuroraDB binary format
     pub fn decode_vector(data: &[u8]) -> Result<Vec<f32>, ProtocolError> {
-        if data.len() < 4 {
+        if data.len() < 5 {
             return Err(ProtocolError::DeserializationError("Vector data too short".to_string()));
         }
 
@@ -163,7 +161,7 @@ impl VectorEncoder {
     }
 
     fn decode_raw_f32(data: &[u8], dimension: usize) -> Result<Vec<f32>, ProtocolError> {
-        if data.len() != dimension * 4 {
+        if dimension.checked_mul(4) != Some(data.len()) {
             return Err(ProtocolError::DeserializationError("Incorrect data length for raw f32 vector".to_string()));
         }
 
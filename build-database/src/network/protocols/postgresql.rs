@@ -116,6 +116,10 @@ impl PostgreSQLStartupParser {
         }
 
         let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        if length < 8 || length > data.len() || length > MAX_STARTUP_MESSAGE_SIZE {
+            return Err(ProtocolError::DeserializationError(format!("Invalid startup message length {}", length)));
+        }
+        let data = &data[..length];
         let protocol_version = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
 
         if protocol_version == 196608 { // 3.0
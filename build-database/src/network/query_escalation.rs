@@ -0,0 +1,153 @@
+//! Query Timeout Escalation
+//!
+//! `statement_timeout` answers every slow query the same way. An escalation
+//! policy instead responds in stages as a query keeps running:
+//! 1. **Warn** (`warn_after`): log the query with a snapshot of its plan
+//! 2. **Cancel** (`cancel_after`): ask the statement to stop at its next
+//!    cancellation check; it rolls back what it had not committed, releases
+//!    its locks, and the connection stays usable
+//! 3. **Kill** (`kill_after`): for a statement that ignored the cancel, drop
+//!    it where it stands, which aborts its open transactions and releases its
+//!    locks, and close the connection
+//!
+//! Any stage may be left out. Policies are chosen by the session's role and
+//! the statement's kind, most specific first: role and kind, role, kind,
+//! then the default. Each stage taken is recorded in the monitoring
+//! module's `EscalationMonitor`.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::monitoring::EscalationLevel;
+use super::statement_firewall::StatementKind;
+
+/// Thresholds of each escalation stage, measured from when the statement
+/// started; `None` skips the stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EscalationPolicy {
+    pub warn_after: Option<Duration>,
+    pub cancel_after: Option<Duration>,
+    pub kill_after: Option<Duration>,
+}
+
+impl EscalationPolicy {
+    /// Policy taking no stage
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn with_warn_after(mut self, after: Duration) -> Self {
+        self.warn_after = Some(after);
+        self
+    }
+
+    pub fn with_cancel_after(mut self, after: Duration) -> Self {
+        self.cancel_after = Some(after);
+        self
+    }
+
+    pub fn with_kill_after(mut self, after: Duration) -> Self {
+        self.kill_after = Some(after);
+        self
+    }
+
+    /// The stages configured, in the order they fire
+    pub fn stages(&self) -> Vec<(EscalationLevel, Duration)> {
+        let mut stages: Vec<(EscalationLevel, Duration)> = [
+            (EscalationLevel::Warn, self.warn_after),
+            (EscalationLevel::Cancel, self.cancel_after),
+            (EscalationLevel::Kill, self.kill_after),
+        ]
+        .into_iter()
+        .filter_map(|(level, after)| Some((level, after?)))
+        .collect();
+        stages.sort_by_key(|(level, after)| (*after, *level));
+        stages
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.stages().is_empty()
+    }
+}
+
+/// Escalation policies by role and statement kind
+#[derive(Debug, Clone, Default)]
+pub struct EscalationPolicies {
+    default: EscalationPolicy,
+    roles: HashMap<String, EscalationPolicy>,
+    statements: HashMap<StatementKind, EscalationPolicy>,
+    role_statements: HashMap<(String, StatementKind), EscalationPolicy>,
+}
+
+impl EscalationPolicies {
+    /// Policies applying `default` to every query
+    pub fn new(default: EscalationPolicy) -> Self {
+        Self { default, ..Self::default() }
+    }
+
+    /// Policy for the queries of `role`
+    pub fn for_role(mut self, role: &str, policy: EscalationPolicy) -> Self {
+        self.roles.insert(role.to_string(), policy);
+        self
+    }
+
+    /// Policy for statements of `kind`
+    pub fn for_statement(mut self, kind: StatementKind, policy: EscalationPolicy) -> Self {
+        self.statements.insert(kind, policy);
+        self
+    }
+
+    /// Policy for statements of `kind` run by `role`
+    pub fn for_role_statement(mut self, role: &str, kind: StatementKind, policy: EscalationPolicy) -> Self {
+        self.role_statements.insert((role.to_string(), kind), policy);
+        self
+    }
+
+    /// Policy of a statement of `kind` run by `role`
+    pub fn policy(&self, role: &str, kind: StatementKind) -> EscalationPolicy {
+        self.role_statements.get(&(role.to_string(), kind))
+            .or_else(|| self.roles.get(role))
+            .or_else(|| self.statements.get(&kind))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// How a statement run under an escalation policy ended
+#[derive(Debug)]
+pub enum Escalated<T> {
+    /// It returned, after taking the stages up to `reached`
+    Finished { output: T, reached: Option<EscalationLevel> },
+    /// It was still running at the kill stage and was dropped
+    Killed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_policy_wins() {
+        let warn = |millis| EscalationPolicy::none().with_warn_after(Duration::from_millis(millis));
+        let policies = EscalationPolicies::new(warn(1))
+            .for_statement(StatementKind::Select, warn(2))
+            .for_role("reporting", warn(3))
+            .for_role_statement("reporting", StatementKind::Update, warn(4));
+
+        assert_eq!(policies.policy("app", StatementKind::Insert), warn(1));
+        assert_eq!(policies.policy("app", StatementKind::Select), warn(2));
+        assert_eq!(policies.policy("reporting", StatementKind::Select), warn(3));
+        assert_eq!(policies.policy("reporting", StatementKind::Update), warn(4));
+    }
+
+    #[test]
+    fn test_stages_fire_in_time_order() {
+        let policy = EscalationPolicy::none()
+            .with_kill_after(Duration::from_secs(30))
+            .with_warn_after(Duration::from_secs(5));
+        assert_eq!(policy.stages(), vec![
+            (EscalationLevel::Warn, Duration::from_secs(5)),
+            (EscalationLevel::Kill, Duration::from_secs(30)),
+        ]);
+        assert!(EscalationPolicy::none().is_none());
+    }
+}
@@ -8,7 +8,7 @@ use tokio::net::TcpListener;
 use tokio::time::{self, Duration};
 
 use crate::engine::AuroraDB;
-use crate::network::{PostgresProtocol, ConnectionPool, ConnectionPoolManager, ConnectionPoolConfig};
+use crate::network::{PostgresProtocol, ConnectionPool, ConnectionPoolManager, ConnectionPoolConfig, StatementFirewall};
 
 /// AuroraDB server configuration
 #[derive(Debug, Clone)]
@@ -18,6 +18,8 @@ pub struct ServerConfig {
     pub max_connections: usize,
     pub connection_pool_config: ConnectionPoolConfig,
     pub health_check_interval: Duration,
+    /// Statement firewall applied to every connection
+    pub firewall: StatementFirewall,
 }
 
 impl Default for ServerConfig {
@@ -28,6 +30,7 @@ impl Default for ServerConfig {
             max_connections: 1000,
             connection_pool_config: ConnectionPoolConfig::default(),
             health_check_interval: Duration::from_secs(30),
+            firewall: StatementFirewall::allow_all(),
         }
     }
 }
@@ -93,7 +96,7 @@ impl AuroraServer {
                     let pool = self.connection_pool_manager.get_pool("default", Arc::clone(&self.db));
 
                     // Create protocol handler
-                    let protocol = PostgresProtocol::new(Arc::clone(&self.db));
+                    let protocol = PostgresProtocol::with_firewall(Arc::clone(&self.db), self.config.firewall.clone());
 
                     // Handle connection in separate task
                     tokio::spawn(async move {
@@ -179,6 +182,11 @@ impl ServerBuilder {
         self
     }
 
+    pub fn firewall(mut self, firewall: StatementFirewall) -> Self {
+        self.config.firewall = firewall;
+        self
+    }
+
     pub fn build(self, db: Arc<AuroraDB>) -> AuroraServer {
         AuroraServer::new(db, self.config)
     }
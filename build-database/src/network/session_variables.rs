@@ -0,0 +1,385 @@
+//! Session Variables
+//!
+//! GUC-style settings scoped to one client connection. `SET name = value`
+//! (or `SET name TO value`) changes a setting for the rest of the session,
+//! `RESET name` and `SET name TO DEFAULT` return it to the server default,
+//! `RESET ALL` returns every setting, and `SHOW name` / `SHOW ALL` report
+//! them. Settings are validated when set, so a bad value is refused with
+//! SQLSTATE 22023 and an unknown name with 42704, and never reaches a query.
+//!
+//! The known settings are `work_mem`, `statement_timeout`, `timezone`,
+//! `application_name`, `query_profiler` with its
+//! `query_profiler_sample_rate` in Hz, and `stable_scan_order`. Names
+//! containing a dot, such as
+//! `myapp.tenant`, are custom settings that are stored and shown but mean
+//! nothing to the server.
+
+use crate::engine::query_profiler::{DEFAULT_SAMPLE_RATE_HZ, MAX_SAMPLE_RATE_HZ};
+use crate::types::TimeZone;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Session setting statement handled by the connection itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// `SET name = value`; `None` for `SET name TO DEFAULT`
+    Set { name: String, value: Option<String> },
+    Reset(String),
+    ResetAll,
+    Show(String),
+    ShowAll,
+}
+
+impl SessionCommand {
+    /// Recognize a session setting statement; `None` for any other SQL
+    pub fn parse(sql: &str) -> Option<Self> {
+        let sql = sql.trim().trim_end_matches(';').trim();
+        let (keyword, rest) = split_word(sql);
+
+        match keyword.to_ascii_uppercase().as_str() {
+            "SET" => {
+                let rest = match split_word(rest) {
+                    (session, rest) if session.eq_ignore_ascii_case("SESSION") => rest,
+                    _ => rest,
+                };
+                let (name, value) = match split_word(rest) {
+                    // SET LOCAL and SET TRANSACTION are transaction scoped
+                    (local, _) if local.eq_ignore_ascii_case("LOCAL") || local.eq_ignore_ascii_case("TRANSACTION") => return None,
+                    // `SET TIME ZONE value` is `SET timezone = value`
+                    (time, zone) if time.eq_ignore_ascii_case("TIME") => match split_word(zone) {
+                        (zone, value) if zone.eq_ignore_ascii_case("ZONE") => ("timezone".to_string(), value),
+                        _ => return None,
+                    },
+                    _ => {
+                        let (name, rest) = split_name(rest);
+                        let value = match rest.strip_prefix('=') {
+                            Some(value) => value.trim(),
+                            None => match split_word(rest) {
+                                (to, value) if to.eq_ignore_ascii_case("TO") => value,
+                                _ => return None,
+                            },
+                        };
+                        (parameter_name(name)?, value)
+                    }
+                };
+                if value.is_empty() {
+                    return None;
+                }
+                let value = (!value.eq_ignore_ascii_case("DEFAULT")).then(|| unquote(value));
+                Some(Self::Set { name, value })
+            }
+            "RESET" => match split_word(rest) {
+                (all, "") if all.eq_ignore_ascii_case("ALL") => Some(Self::ResetAll),
+                (name, "") => parameter_name(name).map(Self::Reset),
+                (time, zone) if time.eq_ignore_ascii_case("TIME") && zone.eq_ignore_ascii_case("ZONE") => {
+                    Some(Self::Reset("timezone".to_string()))
+                }
+                _ => None,
+            },
+            "SHOW" => match split_word(rest) {
+                (all, "") if all.eq_ignore_ascii_case("ALL") => Some(Self::ShowAll),
+                (name, "") => parameter_name(name).map(Self::Show),
+                (time, zone) if time.eq_ignore_ascii_case("TIME") && zone.eq_ignore_ascii_case("ZONE") => {
+                    Some(Self::Show("timezone".to_string()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// CommandComplete tag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            SessionCommand::Set { .. } => "SET",
+            SessionCommand::Reset(_) | SessionCommand::ResetAll => "RESET",
+            SessionCommand::Show(_) | SessionCommand::ShowAll => "SHOW",
+        }
+    }
+}
+
+/// First whitespace-separated word and the rest, trimmed
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+        Some(end) => (&text[..end], text[end..].trim()),
+        None => (text, ""),
+    }
+}
+
+/// Setting name at the start of `text`, ending at whitespace or `=`, and
+/// the rest, trimmed
+fn split_name(text: &str) -> (&str, &str) {
+    let end = match text.strip_prefix('"') {
+        Some(quoted) => quoted.find('"').map(|end| end + 2).unwrap_or(text.len()),
+        None => text.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(text.len()),
+    };
+    (&text[..end], text[end..].trim())
+}
+
+/// Setting names are case-insensitive unless double-quoted
+fn parameter_name(name: &str) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+    Some(match name.strip_prefix('"').and_then(|name| name.strip_suffix('"')) {
+        Some(quoted) => quoted.to_string(),
+        None => name.to_ascii_lowercase(),
+    })
+}
+
+/// Values may be single-quoted, with `''` for a quote
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => value.to_string(),
+    }
+}
+
+/// Session setting failures, reported with their SQLSTATE
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SessionError {
+    #[error("unrecognized configuration parameter \"{0}\"")]
+    UnknownParameter(String),
+
+    #[error("invalid value for parameter \"{name}\": \"{value}\"")]
+    InvalidValue { name: String, value: String },
+}
+
+impl SessionError {
+    /// SQLSTATE code for the ErrorResponse
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            SessionError::UnknownParameter(_) => "42704",
+            SessionError::InvalidValue { .. } => "22023",
+        }
+    }
+}
+
+/// Settings of one connection; unset settings take the server default
+#[derive(Debug, Clone)]
+pub struct SessionVariables {
+    /// Server-wide work_mem, in bytes
+    default_work_mem: usize,
+    work_mem: Option<usize>,
+    statement_timeout: Option<Duration>,
+    time_zone: Option<TimeZone>,
+    application_name: Option<String>,
+    query_profiler: Option<bool>,
+    query_profiler_sample_rate: Option<u32>,
+    stable_scan_order: Option<bool>,
+    /// Dotted custom settings
+    custom: BTreeMap<String, String>,
+}
+
+impl SessionVariables {
+    /// Names of the settings the server knows, as shown by SHOW ALL
+    pub const KNOWN: [&'static str; 7] = [
+        "application_name",
+        "query_profiler",
+        "query_profiler_sample_rate",
+        "stable_scan_order",
+        "statement_timeout",
+        "timezone",
+        "work_mem",
+    ];
+
+    pub fn new(default_work_mem: usize) -> Self {
+        Self {
+            default_work_mem,
+            work_mem: None,
+            statement_timeout: None,
+            time_zone: None,
+            application_name: None,
+            query_profiler: None,
+            query_profiler_sample_rate: None,
+            stable_scan_order: None,
+            custom: BTreeMap::new(),
+        }
+    }
+
+    /// Apply a SET or RESET; SHOW changes nothing
+    pub fn apply(&mut self, command: &SessionCommand) -> Result<(), SessionError> {
+        match command {
+            SessionCommand::Set { name, value: Some(value) } => self.set(name, value),
+            SessionCommand::Set { name, value: None } | SessionCommand::Reset(name) => self.reset(name),
+            SessionCommand::ResetAll => {
+                self.reset_all();
+                Ok(())
+            }
+            SessionCommand::Show(name) => self.show(name).map(|_| ()),
+            SessionCommand::ShowAll => Ok(()),
+        }
+    }
+
+    /// Change a setting for the rest of the session
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SessionError> {
+        let invalid = || SessionError::InvalidValue { name: name.to_string(), value: value.to_string() };
+        match name {
+            "work_mem" => self.work_mem = Some(parse_memory(value).ok_or_else(invalid)?),
+            "statement_timeout" => self.statement_timeout = Some(parse_duration(value).ok_or_else(invalid)?),
+            "timezone" => self.time_zone = Some(value.parse().map_err(|_| invalid())?),
+            "application_name" => self.application_name = Some(value.to_string()),
+            "query_profiler" => self.query_profiler = Some(parse_bool(value).ok_or_else(invalid)?),
+            "query_profiler_sample_rate" => {
+                let rate = value.trim().parse().ok().filter(|rate| (1..=MAX_SAMPLE_RATE_HZ).contains(rate));
+                self.query_profiler_sample_rate = Some(rate.ok_or_else(invalid)?);
+            }
+            "stable_scan_order" => self.stable_scan_order = Some(parse_bool(value).ok_or_else(invalid)?),
+            custom if custom.contains('.') => {
+                self.custom.insert(custom.to_string(), value.to_string());
+            }
+            unknown => return Err(SessionError::UnknownParameter(unknown.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Return a setting to the server default
+    pub fn reset(&mut self, name: &str) -> Result<(), SessionError> {
+        match name {
+            "work_mem" => self.work_mem = None,
+            "statement_timeout" => self.statement_timeout = None,
+            "timezone" => self.time_zone = None,
+            "application_name" => self.application_name = None,
+            "query_profiler" => self.query_profiler = None,
+            "query_profiler_sample_rate" => self.query_profiler_sample_rate = None,
+            "stable_scan_order" => self.stable_scan_order = None,
+            custom if custom.contains('.') => {
+                self.custom.remove(custom);
+            }
+            unknown => return Err(SessionError::UnknownParameter(unknown.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Return every setting to the server default
+    pub fn reset_all(&mut self) {
+        *self = Self::new(self.default_work_mem);
+    }
+
+    /// Current value of a setting, as SHOW reports it
+    pub fn show(&self, name: &str) -> Result<String, SessionError> {
+        match name {
+            "work_mem" => Ok(format_memory(self.work_mem())),
+            "statement_timeout" => Ok(format_duration(self.statement_timeout.unwrap_or_default())),
+            "timezone" => Ok(self.time_zone.unwrap_or_default().to_string()),
+            "application_name" => Ok(self.application_name.clone().unwrap_or_default()),
+            "query_profiler" => Ok(if self.query_profiler.unwrap_or(false) { "on" } else { "off" }.to_string()),
+            "query_profiler_sample_rate" => Ok(self.query_profiler_sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE_HZ).to_string()),
+            "stable_scan_order" => Ok(if self.stable_scan_order() { "on" } else { "off" }.to_string()),
+            custom if self.custom.contains_key(custom) => Ok(self.custom[custom].clone()),
+            unknown => Err(SessionError::UnknownParameter(unknown.to_string())),
+        }
+    }
+
+    /// Every known and custom setting with its value, by name
+    pub fn show_all(&self) -> Vec<(String, String)> {
+        Self::KNOWN.iter().map(|name| name.to_string())
+            .chain(self.custom.keys().cloned())
+            .map(|name| {
+                let value = self.show(&name).unwrap_or_default();
+                (name, value)
+            })
+            .collect()
+    }
+
+    /// Memory a sort may use before spilling, in bytes
+    pub fn work_mem(&self) -> usize {
+        self.work_mem.unwrap_or(self.default_work_mem)
+    }
+
+    /// Session work_mem, if it differs from the server default
+    pub fn work_mem_override(&self) -> Option<usize> {
+        self.work_mem
+    }
+
+    /// Longest a statement may run; `None` when unlimited
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout.filter(|timeout| !timeout.is_zero())
+    }
+
+    /// Session timezone, if one was set
+    pub fn time_zone(&self) -> Option<TimeZone> {
+        self.time_zone
+    }
+
+    /// Rate statements are sampled at, when `query_profiler` is on
+    pub fn profiler_sample_rate(&self) -> Option<u32> {
+        self.query_profiler.unwrap_or(false)
+            .then(|| self.query_profiler_sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE_HZ))
+    }
+
+    /// Whether unordered queries return rows in a repeatable order. Off by
+    /// default: without ORDER BY, row order is unspecified
+    pub fn stable_scan_order(&self) -> bool {
+        self.stable_scan_order.unwrap_or(false)
+    }
+}
+
+/// Boolean settings take `on`/`off`, `true`/`false`, `yes`/`no` or `1`/`0`
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Split a setting like `64MB` or `250 ms` into its number and unit
+fn split_unit(value: &str) -> Option<(u64, &str)> {
+    let value = value.trim();
+    let digits = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let number = value[..digits].parse().ok()?;
+    Some((number, value[digits..].trim()))
+}
+
+/// `work_mem`: kB when no unit is given, as in PostgreSQL
+fn parse_memory(value: &str) -> Option<usize> {
+    let (number, unit) = split_unit(value)?;
+    let multiplier: u64 = match unit {
+        "" | "kB" => 1024,
+        "B" => 1,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    // PostgreSQL's floor for work_mem is 64kB
+    let bytes = number.checked_mul(multiplier)?;
+    (bytes >= 64 * 1024).then_some(bytes as usize)
+}
+
+fn format_memory(bytes: usize) -> String {
+    for (unit, size) in [("GB", 1 << 30), ("MB", 1 << 20), ("kB", 1 << 10)] {
+        if bytes % size == 0 {
+            return format!("{}{}", bytes / size, unit);
+        }
+    }
+    format!("{}B", bytes)
+}
+
+/// `statement_timeout`: milliseconds when no unit is given; 0 disables it
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (number, unit) = split_unit(value)?;
+    let millis: u64 = match unit {
+        "" | "ms" => 1,
+        "s" => 1000,
+        "min" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    number.checked_mul(millis).map(Duration::from_millis)
+}
+
+fn format_duration(timeout: Duration) -> String {
+    let millis = timeout.as_millis();
+    if millis == 0 {
+        return "0".to_string();
+    }
+    for (unit, size) in [("d", 24 * 60 * 60 * 1000), ("h", 60 * 60 * 1000), ("min", 60 * 1000), ("s", 1000)] {
+        if millis % size == 0 {
+            return format!("{}{}", millis / size, unit);
+        }
+    }
+    format!("{}ms", millis)
+}
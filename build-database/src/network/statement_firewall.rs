@@ -0,0 +1,401 @@
+//! Statement Firewall
+//!
+//! Screens every statement a connection sends before it reaches the parser
+//! and planner. Reporting replicas run in read-only mode so that no write
+//! can slip through; other deployments can allow only some statement types
+//! or only statements matching known patterns. The modes:
+//! - **Allow all**: No screening, the default
+//! - **Read-only**: SELECT, EXPLAIN, SHOW and SET only, with no embedded
+//!   write (a data-modifying CTE, SELECT INTO, FOR UPDATE) and no call to a
+//!   function with side effects
+//! - **Statement types**: Only the listed statement types
+//! - **Patterns**: Only statements matching one of the listed regular
+//!   expressions, each anchored to the whole statement
+//!
+//! A query string holding several statements is allowed only if every one
+//! of them is. Rejections carry SQLSTATE 25006 (read-only violation) or
+//! 42501 (not allowed) and are reported like any other failed statement.
+
+use std::collections::HashSet;
+use std::fmt;
+use regex::{Regex, RegexBuilder};
+use crate::config::FirewallConfig;
+use crate::core::{AuroraError, AuroraResult, ErrorCode};
+use crate::query::FunctionRegistry;
+
+/// Keywords that make a reading statement write: data-modifying CTEs,
+/// SELECT INTO, FOR UPDATE
+const EMBEDDED_WRITES: &[&str] = &["INSERT", "UPDATE", "DELETE", "MERGE", "INTO", "CREATE", "DROP", "ALTER", "TRUNCATE"];
+
+/// Functions that write even though they are called from a SELECT
+const WRITING_FUNCTIONS: &[&str] = &["nextval", "setval", "lo_import", "lo_unlink", "pg_notify"];
+
+/// Kind of a statement, by what it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    /// CREATE, DROP, ALTER, TRUNCATE, REFRESH and other schema changes
+    Ddl,
+    Explain,
+    Show,
+    Set,
+    /// Anything else: COPY, GRANT, VACUUM, unknown commands
+    Other,
+}
+
+impl StatementKind {
+    /// Parse a configured statement type name, case-insensitively
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "select" => Some(Self::Select),
+            "insert" => Some(Self::Insert),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            "ddl" => Some(Self::Ddl),
+            "explain" => Some(Self::Explain),
+            "show" => Some(Self::Show),
+            "set" => Some(Self::Set),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    /// Kind of the first statement in `sql`, counting a write embedded in a
+    /// read as that write
+    pub fn of(sql: &str) -> Self {
+        split_statements(sql).first().map_or(Self::Other, |statement| statement.kind().0)
+    }
+
+    /// Kind a leading or embedded keyword gives a statement
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword {
+            "SELECT" | "WITH" | "VALUES" | "TABLE" => Self::Select,
+            "INSERT" => Self::Insert,
+            "UPDATE" | "MERGE" => Self::Update,
+            "DELETE" => Self::Delete,
+            "CREATE" | "DROP" | "ALTER" | "TRUNCATE" | "REFRESH" | "RENAME" | "COMMENT" | "INTO" => Self::Ddl,
+            "EXPLAIN" => Self::Explain,
+            "SHOW" => Self::Show,
+            "SET" | "RESET" => Self::Set,
+            _ => Self::Other,
+        }
+    }
+
+    /// Whether statements of this kind never write
+    pub fn is_read_only(self) -> bool {
+        matches!(self, Self::Select | Self::Explain | Self::Show | Self::Set)
+    }
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StatementKind::Select => "SELECT",
+            StatementKind::Insert => "INSERT",
+            StatementKind::Update => "UPDATE",
+            StatementKind::Delete => "DELETE",
+            StatementKind::Ddl => "DDL",
+            StatementKind::Explain => "EXPLAIN",
+            StatementKind::Show => "SHOW",
+            StatementKind::Set => "SET",
+            StatementKind::Other => "OTHER",
+        })
+    }
+}
+
+/// What the firewall lets through
+#[derive(Debug, Clone)]
+pub enum FirewallMode {
+    AllowAll,
+    ReadOnly,
+    StatementTypes(HashSet<StatementKind>),
+    /// Anchored patterns; a statement must match at least one
+    Patterns(Vec<Regex>),
+}
+
+/// Statements the firewall refused, reported with their SQLSTATE
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FirewallError {
+    #[error("cannot execute {0} on a read-only connection")]
+    ReadOnly(String),
+
+    #[error("function {0}() has side effects and cannot be called on a read-only connection")]
+    SideEffects(String),
+
+    #[error("{0} statements are not allowed on this connection")]
+    StatementType(StatementKind),
+
+    #[error("statement is not on this connection's allowlist: {0}")]
+    NotAllowlisted(String),
+}
+
+impl FirewallError {
+    /// SQLSTATE code for the ErrorResponse
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            FirewallError::ReadOnly(_) | FirewallError::SideEffects(_) => "25006",
+            FirewallError::StatementType(_) | FirewallError::NotAllowlisted(_) => "42501",
+        }
+    }
+}
+
+/// Statement firewall of a connection
+#[derive(Debug, Clone)]
+pub struct StatementFirewall {
+    mode: FirewallMode,
+}
+
+impl Default for StatementFirewall {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+impl StatementFirewall {
+    pub fn new(mode: FirewallMode) -> Self {
+        Self { mode }
+    }
+
+    pub fn allow_all() -> Self {
+        Self::new(FirewallMode::AllowAll)
+    }
+
+    pub fn read_only() -> Self {
+        Self::new(FirewallMode::ReadOnly)
+    }
+
+    /// Allow only statements of the given kinds
+    pub fn statement_types(kinds: impl IntoIterator<Item = StatementKind>) -> Self {
+        Self::new(FirewallMode::StatementTypes(kinds.into_iter().collect()))
+    }
+
+    /// Allow only statements matching one of `patterns` in full, ignoring case
+    pub fn patterns<S: AsRef<str>>(patterns: &[S]) -> AuroraResult<Self> {
+        let patterns = patterns.iter()
+            .map(|pattern| {
+                RegexBuilder::new(&format!("^(?:{})$", pattern.as_ref()))
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| AuroraError::new(
+                        ErrorCode::ConfigInvalidValue,
+                        format!("Invalid firewall pattern '{}': {}", pattern.as_ref(), e)
+                    ))
+            })
+            .collect::<AuroraResult<Vec<_>>>()?;
+        Ok(Self::new(FirewallMode::Patterns(patterns)))
+    }
+
+    /// Build the firewall described by the network configuration
+    pub fn from_config(config: &FirewallConfig) -> AuroraResult<Self> {
+        match config.mode.to_ascii_lowercase().as_str() {
+            "allow_all" => Ok(Self::allow_all()),
+            "read_only" => Ok(Self::read_only()),
+            "statement_types" => {
+                let kinds = config.allowed_statement_types.iter()
+                    .map(|name| StatementKind::from_name(name).ok_or_else(|| AuroraError::new(
+                        ErrorCode::ConfigInvalidValue,
+                        format!("Unknown firewall statement type '{}'", name)
+                    )))
+                    .collect::<AuroraResult<Vec<_>>>()?;
+                Ok(Self::statement_types(kinds))
+            }
+            "patterns" => Self::patterns(&config.allowed_patterns),
+            other => Err(AuroraError::new(
+                ErrorCode::ConfigInvalidValue,
+                format!("Unknown firewall mode '{}'", other)
+            )),
+        }
+    }
+
+    pub fn mode(&self) -> &FirewallMode {
+        &self.mode
+    }
+
+    /// Check a query string before it is executed. `functions` tells which
+    /// registered functions have side effects.
+    pub fn check(&self, sql: &str, functions: &FunctionRegistry) -> Result<(), FirewallError> {
+        if matches!(self.mode, FirewallMode::AllowAll) {
+            return Ok(());
+        }
+        for statement in split_statements(sql) {
+            self.check_statement(&statement, functions)?;
+        }
+        Ok(())
+    }
+
+    fn check_statement(&self, statement: &Statement, functions: &FunctionRegistry) -> Result<(), FirewallError> {
+        match &self.mode {
+            FirewallMode::AllowAll => Ok(()),
+            FirewallMode::ReadOnly => {
+                let (kind, keyword) = statement.kind();
+                if !kind.is_read_only() {
+                    return Err(FirewallError::ReadOnly(keyword));
+                }
+                match statement.side_effect_call(functions) {
+                    Some(function) => Err(FirewallError::SideEffects(function)),
+                    None => Ok(()),
+                }
+            }
+            FirewallMode::StatementTypes(allowed) => {
+                let (kind, _) = statement.kind();
+                if allowed.contains(&kind) {
+                    Ok(())
+                } else {
+                    Err(FirewallError::StatementType(kind))
+                }
+            }
+            FirewallMode::Patterns(patterns) => {
+                if patterns.iter().any(|pattern| pattern.is_match(&statement.text)) {
+                    Ok(())
+                } else {
+                    Err(FirewallError::NotAllowlisted(statement.text.clone()))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Unquoted word, upper-cased
+    Word(String),
+    OpenParen,
+    /// Quoted identifiers, literals, operators; never keywords
+    Other,
+}
+
+/// One statement of a query string: its text and tokens, with comments
+/// and literals already skipped
+#[derive(Debug)]
+struct Statement {
+    text: String,
+    tokens: Vec<Token>,
+}
+
+impl Statement {
+    /// Kind of the statement and the keyword that decided it. A reading
+    /// statement that embeds a write (a data-modifying CTE, SELECT INTO,
+    /// FOR UPDATE) takes the kind of that write.
+    fn kind(&self) -> (StatementKind, String) {
+        let leading = match self.tokens.first() {
+            Some(Token::Word(word)) => word.clone(),
+            _ => return (StatementKind::Other, "this statement".to_string()),
+        };
+        let kind = StatementKind::from_keyword(&leading);
+        if !kind.is_read_only() {
+            return (kind, leading);
+        }
+
+        let embedded = self.tokens.iter().skip(1).find_map(|token| match token {
+            Token::Word(word) if EMBEDDED_WRITES.contains(&word.as_str()) => {
+                Some((StatementKind::from_keyword(word), word.clone()))
+            }
+            _ => None,
+        });
+        match embedded {
+            // EXPLAIN of a write is judged by the write, since ANALYZE runs it
+            Some((embedded, word)) if kind == StatementKind::Explain => (embedded, word),
+            Some((embedded, word)) => (embedded, format!("{} with {}", leading, word)),
+            None => (kind, leading),
+        }
+    }
+
+    /// First call to a function with side effects
+    fn side_effect_call(&self, functions: &FunctionRegistry) -> Option<String> {
+        self.tokens.windows(2).find_map(|pair| match pair {
+            [Token::Word(name), Token::OpenParen] => {
+                let name = name.to_ascii_lowercase();
+                let writes = WRITING_FUNCTIONS.contains(&name.as_str())
+                    || functions.get(&name).is_some_and(|function| function.signature().side_effects);
+                writes.then_some(name)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Split a query string into statements at semicolons outside literals,
+/// quoted identifiers and comments; empty statements are dropped
+fn split_statements(sql: &str) -> Vec<Statement> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = Vec::new();
+    let mut text = String::new();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let mut finish = |text: &mut String, tokens: &mut Vec<Token>| {
+        let trimmed = text.trim();
+        if !tokens.is_empty() {
+            statements.push(Statement { text: trimmed.to_string(), tokens: std::mem::take(tokens) });
+        }
+        text.clear();
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            ';' => {
+                finish(&mut text, &mut tokens);
+                i += 1;
+                continue;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                text.push(' ');
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i = (i + 2).min(chars.len());
+                text.push(' ');
+                continue;
+            }
+            '\'' | '"' => {
+                // A doubled quote inside the literal escapes it
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                tokens.push(Token::Other);
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(Token::Word(word.to_ascii_uppercase()));
+            }
+            '(' => {
+                i += 1;
+                tokens.push(Token::OpenParen);
+            }
+            c if c.is_whitespace() => {
+                i += 1;
+            }
+            _ => {
+                i += 1;
+                tokens.push(Token::Other);
+            }
+        }
+        text.extend(&chars[start..i]);
+    }
+    finish(&mut text, &mut tokens);
+    statements
+}
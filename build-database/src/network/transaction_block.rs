@@ -0,0 +1,208 @@
+//! Transaction Block State
+//!
+//! Tracks each client connection's transaction block the way PostgreSQL
+//! clients expect it. Once a statement fails inside a block the block is
+//! aborted: every statement is rejected with SQLSTATE 25P02 ("current
+//! transaction is aborted") until the client issues ROLLBACK, or ROLLBACK
+//! TO SAVEPOINT naming a savepoint taken before the failure. COMMIT of an
+//! aborted block rolls it back.
+//!
+//! The state is reported to the client in every ReadyForQuery message
+//! (`I` idle, `T` in a block, `E` aborted block). Statements inside a block
+//! are still executed by the engine as they arrive; the block governs which
+//! statements are accepted and what status the client sees.
+
+use std::fmt;
+
+/// Transaction status of a connection, as sent in ReadyForQuery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// No transaction block is open
+    Idle,
+    /// Inside a transaction block
+    InTransaction,
+    /// Inside a transaction block that a failed statement aborted
+    Failed,
+}
+
+impl TransactionStatus {
+    /// ReadyForQuery status indicator byte
+    pub fn indicator(self) -> u8 {
+        match self {
+            TransactionStatus::Idle => b'I',
+            TransactionStatus::InTransaction => b'T',
+            TransactionStatus::Failed => b'E',
+        }
+    }
+}
+
+/// Transaction control statement handled by the connection itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionCommand {
+    Begin,
+    Commit,
+    Rollback,
+    Savepoint(String),
+    Release(String),
+    RollbackTo(String),
+}
+
+impl TransactionCommand {
+    /// Recognize a transaction control statement; `None` for any other SQL
+    pub fn parse(sql: &str) -> Option<Self> {
+        let sql = sql.trim().trim_end_matches(';').trim();
+        let words: Vec<String> = sql.split_whitespace().map(|word| word.to_ascii_uppercase()).collect();
+        let name = |index: usize| sql.split_whitespace().nth(index).map(|name| name.trim_matches('"').to_string());
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        match words.as_slice() {
+            ["BEGIN"] | ["BEGIN", "TRANSACTION" | "WORK"] | ["START", "TRANSACTION"] => Some(Self::Begin),
+            ["COMMIT"] | ["COMMIT", "TRANSACTION" | "WORK"] | ["END"] => Some(Self::Commit),
+            ["ROLLBACK"] | ["ROLLBACK", "TRANSACTION" | "WORK"] | ["ABORT"] => Some(Self::Rollback),
+            ["SAVEPOINT", _] => name(1).map(Self::Savepoint),
+            ["RELEASE", _] => name(1).map(Self::Release),
+            ["RELEASE", "SAVEPOINT", _] => name(2).map(Self::Release),
+            ["ROLLBACK", "TO", _] => name(2).map(Self::RollbackTo),
+            ["ROLLBACK", "TO", "SAVEPOINT", _] => name(3).map(Self::RollbackTo),
+            _ => None,
+        }
+    }
+}
+
+/// Transaction control failures, reported with their SQLSTATE
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TransactionError {
+    #[error("current transaction is aborted, commands ignored until end of transaction block")]
+    Aborted,
+
+    #[error("{0} can only be used in transaction blocks")]
+    NoTransaction(&'static str),
+
+    #[error("savepoint \"{0}\" does not exist")]
+    UnknownSavepoint(String),
+}
+
+impl TransactionError {
+    /// SQLSTATE code for the ErrorResponse
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            TransactionError::Aborted => "25P02",
+            TransactionError::NoTransaction(_) => "25P01",
+            TransactionError::UnknownSavepoint(_) => "3B001",
+        }
+    }
+}
+
+/// Transaction block of one connection
+#[derive(Debug)]
+pub struct TransactionBlock {
+    status: TransactionStatus,
+    /// Open savepoints, oldest first
+    savepoints: Vec<String>,
+}
+
+impl Default for TransactionBlock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionBlock {
+    pub fn new() -> Self {
+        Self { status: TransactionStatus::Idle, savepoints: Vec::new() }
+    }
+
+    pub fn status(&self) -> TransactionStatus {
+        self.status
+    }
+
+    /// Open savepoints, oldest first
+    pub fn savepoints(&self) -> &[String] {
+        &self.savepoints
+    }
+
+    /// Check that an ordinary statement may run
+    pub fn check_statement(&self) -> Result<(), TransactionError> {
+        match self.status {
+            TransactionStatus::Failed => Err(TransactionError::Aborted),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record that a statement failed; an open block becomes aborted
+    pub fn statement_failed(&mut self) {
+        if self.status == TransactionStatus::InTransaction {
+            self.status = TransactionStatus::Failed;
+        }
+    }
+
+    /// Apply a transaction control statement, returning its command tag
+    pub fn apply(&mut self, command: TransactionCommand) -> Result<&'static str, TransactionError> {
+        use TransactionStatus::*;
+
+        match (command, self.status) {
+            (TransactionCommand::Begin, Failed) => Err(TransactionError::Aborted),
+            (TransactionCommand::Begin, _) => {
+                // BEGIN inside a block is a no-op, as in PostgreSQL
+                self.status = InTransaction;
+                Ok("BEGIN")
+            }
+            (TransactionCommand::Commit, status) => {
+                self.end();
+                Ok(if status == Failed { "ROLLBACK" } else { "COMMIT" })
+            }
+            (TransactionCommand::Rollback, _) => {
+                self.end();
+                Ok("ROLLBACK")
+            }
+            (TransactionCommand::Savepoint(_), Idle) => Err(TransactionError::NoTransaction("SAVEPOINT")),
+            (TransactionCommand::Release(_), Idle) => Err(TransactionError::NoTransaction("RELEASE SAVEPOINT")),
+            (TransactionCommand::RollbackTo(_), Idle) => Err(TransactionError::NoTransaction("ROLLBACK TO SAVEPOINT")),
+            (TransactionCommand::Savepoint(_) | TransactionCommand::Release(_), Failed) => Err(TransactionError::Aborted),
+            (TransactionCommand::Savepoint(name), InTransaction) => {
+                self.savepoints.push(name);
+                Ok("SAVEPOINT")
+            }
+            (TransactionCommand::Release(name), InTransaction) => {
+                // Releasing a savepoint also releases every later one
+                let position = self.find(&name)?;
+                self.savepoints.truncate(position);
+                Ok("RELEASE")
+            }
+            (TransactionCommand::RollbackTo(name), _) => {
+                // The savepoint stays open; later ones are discarded
+                let position = self.find(&name)?;
+                self.savepoints.truncate(position + 1);
+                self.status = InTransaction;
+                Ok("ROLLBACK")
+            }
+        }
+    }
+
+    /// Position of the most recent savepoint named `name`; naming a missing
+    /// savepoint aborts the block
+    fn find(&mut self, name: &str) -> Result<usize, TransactionError> {
+        match self.savepoints.iter().rposition(|savepoint| savepoint.eq_ignore_ascii_case(name)) {
+            Some(position) => Ok(position),
+            None => {
+                self.status = TransactionStatus::Failed;
+                Err(TransactionError::UnknownSavepoint(name.to_string()))
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        self.status = TransactionStatus::Idle;
+        self.savepoints.clear();
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransactionStatus::Idle => "idle",
+            TransactionStatus::InTransaction => "in transaction",
+            TransactionStatus::Failed => "in failed transaction",
+        })
+    }
+}
@@ -1,7 +1,17 @@
 //! Full-Text Index: Advanced Text Search and Ranking
+//!
+//! Positional inverted index (the stored equivalent of a tsvector per row)
+//! with BM25 ranking. Postings keep each term's token positions, so phrase
+//! queries are answered from the index without re-reading documents.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::core::errors::AuroraResult;
+use super::text_search::{contains_phrase, TextAnalyzer, TextQuery};
+
+/// BM25 term frequency saturation
+const BM25_K1: f64 = 1.2;
+/// BM25 document length normalization
+const BM25_B: f64 = 0.75;
 
 #[derive(Debug, Clone)]
 pub struct FullTextIndexConfig {
@@ -15,68 +25,180 @@ pub struct FullTextIndexConfig {
 #[derive(Debug)]
 pub struct FullTextIndex {
     config: FullTextIndexConfig,
-    inverted_index: HashMap<String, Vec<(u64, f64)>>, // term -> [(doc_id, score)]
-    document_count: u64,
+    analyzer: TextAnalyzer,
+    postings: HashMap<String, HashMap<u64, Vec<u32>>>, // term -> doc_id -> positions
+    document_lengths: HashMap<u64, u32>,
+    total_length: u64,
 }
 
 impl FullTextIndex {
     pub fn new(config: FullTextIndexConfig) -> AuroraResult<Self> {
+        let mut analyzer = TextAnalyzer::for_language(&config.language)?;
+        analyzer.stemming &= config.enable_stemming;
+        analyzer.stopwords &= config.enable_stopwords;
         Ok(Self {
             config,
-            inverted_index: HashMap::new(),
-            document_count: 0,
+            analyzer,
+            postings: HashMap::new(),
+            document_lengths: HashMap::new(),
+            total_length: 0,
         })
     }
 
+    pub fn config(&self) -> &FullTextIndexConfig {
+        &self.config
+    }
+
+    pub fn analyzer(&self) -> &TextAnalyzer {
+        &self.analyzer
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.document_lengths.len()
+    }
+
+    /// Index `text` under `doc_id`, replacing any earlier version
     pub fn insert(&mut self, doc_id: u64, text: &str) -> AuroraResult<()> {
-        // Simplified full-text indexing
-        let terms = self.tokenize(text);
-        for term in terms {
-            let score = self.calculate_tf_idf(term, doc_id, text);
-            self.inverted_index.entry(term)
-                .or_insert_with(Vec::new)
-                .push((doc_id, score));
+        self.remove(doc_id)?;
+        let positions = self.analyzer.positions(text);
+        let length: u32 = positions.values().map(|p| p.len() as u32).sum();
+        for (term, positions) in positions {
+            self.postings.entry(term).or_default().insert(doc_id, positions);
+        }
+        self.document_lengths.insert(doc_id, length);
+        self.total_length += length as u64;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, doc_id: u64) -> AuroraResult<()> {
+        if let Some(length) = self.document_lengths.remove(&doc_id) {
+            self.total_length -= length as u64;
+            self.postings.retain(|_, docs| {
+                docs.remove(&doc_id);
+                !docs.is_empty()
+            });
         }
-        self.document_count += 1;
         Ok(())
     }
 
+    /// Documents matching `query` in search syntax, best first
     pub fn search(&self, query: &str) -> AuroraResult<Vec<(u64, f64)>> {
-        let query_terms = self.tokenize(query);
-        let mut results = HashMap::new();
-
-        for term in query_terms {
-            if let Some(docs) = self.inverted_index.get(&term) {
-                for (doc_id, score) in docs {
-                    *results.entry(*doc_id).or_insert(0.0) += score;
-                }
+        let query = self.analyzer.parse_query(query)?;
+        Ok(self.search_query(&query))
+    }
+
+    /// Documents matching `query`, best first by BM25 score; ties go to the
+    /// lower document id
+    pub fn search_query(&self, query: &TextQuery) -> Vec<(u64, f64)> {
+        let mut results: Vec<(u64, f64)> = self.matching(query)
+            .into_iter()
+            .map(|doc_id| (doc_id, self.score(doc_id, query)))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results
+    }
+
+    /// BM25 score of one document against the non-negated terms of `query`
+    pub fn score(&self, doc_id: u64, query: &TextQuery) -> f64 {
+        let Some(&length) = self.document_lengths.get(&doc_id) else {
+            return 0.0;
+        };
+        let documents = self.document_count() as f64;
+        let average_length = self.total_length as f64 / documents;
+        query.scoring_terms().into_iter()
+            .filter_map(|term| self.postings.get(term))
+            .filter_map(|docs| docs.get(&doc_id).map(|positions| (docs.len() as f64, positions.len() as f64)))
+            .map(|(df, tf)| {
+                let idf = (1.0 + (documents - df + 0.5) / (df + 0.5)).ln();
+                let norm = 1.0 - BM25_B + BM25_B * length as f64 / average_length.max(1.0);
+                idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm)
+            })
+            .sum()
+    }
+
+    fn matching(&self, query: &TextQuery) -> HashSet<u64> {
+        match query {
+            TextQuery::Term(term) => self.postings.get(term)
+                .map(|docs| docs.keys().copied().collect())
+                .unwrap_or_default(),
+            TextQuery::Phrase(terms) => {
+                let docs: Option<Vec<&HashMap<u64, Vec<u32>>>> = terms.iter().map(|(term, _)| self.postings.get(term)).collect();
+                let Some(docs) = docs else {
+                    return HashSet::new();
+                };
+                docs[0].keys()
+                    .copied()
+                    .filter(|doc_id| {
+                        let lists: Option<Vec<&[u32]>> = docs.iter().map(|d| d.get(doc_id).map(Vec::as_slice)).collect();
+                        lists.is_some_and(|lists| contains_phrase(terms, &lists))
+                    })
+                    .collect()
+            }
+            TextQuery::And(queries) => {
+                let mut sets = queries.iter().map(|q| self.matching(q));
+                let first = sets.next().unwrap_or_default();
+                sets.fold(first, |acc, set| &acc & &set)
+            }
+            TextQuery::Or(queries) => queries.iter().flat_map(|q| self.matching(q)).collect(),
+            TextQuery::Not(inner) => {
+                let excluded = self.matching(inner);
+                self.document_lengths.keys().copied().filter(|doc_id| !excluded.contains(doc_id)).collect()
             }
         }
+    }
+}
 
-        let mut sorted_results: Vec<_> = results.into_iter().collect();
-        sorted_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(sorted_results)
+    fn index() -> FullTextIndex {
+        let mut index = FullTextIndex::new(FullTextIndexConfig {
+            name: "idx_docs_body".to_string(),
+            column: "body".to_string(),
+            language: "english".to_string(),
+            enable_stemming: true,
+            enable_stopwords: true,
+        }).unwrap();
+        index.insert(1, "Indexing strategies for columnar databases").unwrap();
+        index.insert(2, "A database index speeds up database queries and index scans").unwrap();
+        index.insert(3, "Query planners choose join orders").unwrap();
+        index.insert(4, "Vacuuming a database reclaims space").unwrap();
+        index
     }
 
-    fn tokenize(&self, text: &str) -> Vec<String> {
-        text.to_lowercase()
-            .split_whitespace()
-            .map(|s| s.trim_matches(|c: char| !c.is_alphanumeric()))
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect()
-    }
+    #[test]
+    fn test_ranked_multi_term_query() {
+        let index = index();
+        let results = index.search("database index").unwrap();
+        let ids: Vec<u64> = results.iter().map(|(id, _)| *id).collect();
+        // Document 2 repeats both terms; 3 and 4 lack one of them
+        assert_eq!(ids, vec![2, 1]);
+        assert!(results[0].1 > results[1].1);
 
-    fn calculate_tf_idf(&self, term: String, doc_id: u64, text: &str) -> f64 {
-        // Simplified TF-IDF calculation
-        let term_count = text.to_lowercase().matches(&term).count() as f64;
-        let doc_length = text.split_whitespace().count() as f64;
-        let tf = term_count / doc_length;
+        let either: Vec<u64> = index.search("index OR vacuum").unwrap().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(either.len(), 3);
+        let without: Vec<u64> = index.search("database -vacuum").unwrap().into_iter().map(|(id, _)| id).collect();
+        assert!(without.contains(&1) && without.contains(&2) && !without.contains(&4));
+    }
 
-        let df = self.inverted_index.get(&term).map(|docs| docs.len()).unwrap_or(1) as f64;
-        let idf = (self.document_count as f64 / df).ln();
+    #[test]
+    fn test_phrase_query_distinguishes_word_order() {
+        let mut index = index();
+        index.insert(5, "the index database is separate").unwrap();
+        let results = index.search("\"database index\"").unwrap();
+        assert_eq!(results.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![2]);
+        let reversed = index.search("\"index database\"").unwrap();
+        assert_eq!(reversed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![5]);
+    }
 
-        tf * idf
+    #[test]
+    fn test_reinsert_and_remove_update_postings() {
+        let mut index = index();
+        index.insert(3, "Nothing about the planner any more").unwrap();
+        assert!(index.search("join").unwrap().is_empty());
+        index.remove(2).unwrap();
+        assert_eq!(index.document_count(), 3);
+        assert!(index.search("\"database index\"").unwrap().is_empty());
     }
 }
@@ -0,0 +1,212 @@
+//! GIN Index: Inverted Index over Array Elements
+//!
+//! Maps each element value to the rows whose array holds it, so `@>`
+//! (contains) intersects the posting lists of the wanted elements and `&&`
+//! (overlaps) unions them. Rows are identified by primary key; re-indexing a
+//! key replaces its entries. The index only narrows the candidates: callers
+//! recheck each candidate row, so entries for rows deleted without being
+//! removed from the index cost a wasted fetch, never a wrong answer.
+
+use std::collections::{BTreeSet, HashMap};
+use crate::core::errors::AuroraResult;
+use crate::types::DataValue;
+
+#[derive(Debug, Clone)]
+pub struct GinIndexConfig {
+    pub name: String,
+    pub column: String,
+}
+
+/// Lookup a GIN index answers
+#[derive(Debug, Clone, Copy)]
+pub enum GinQuery<'a> {
+    /// Rows whose array holds every one of these elements
+    Contains(&'a [DataValue]),
+    /// Rows whose array holds at least one of these elements
+    Overlaps(&'a [DataValue]),
+}
+
+#[derive(Debug)]
+pub struct GinIndex {
+    config: GinIndexConfig,
+    postings: HashMap<String, BTreeSet<u64>>, // element -> row ids
+    rows: HashMap<u64, (DataValue, Vec<String>)>, // row id -> primary key, indexed elements
+    row_ids: HashMap<String, u64>, // primary key -> row id
+    next_row_id: u64,
+}
+
+impl GinIndex {
+    pub fn new(config: GinIndexConfig) -> AuroraResult<Self> {
+        Ok(Self {
+            config,
+            postings: HashMap::new(),
+            rows: HashMap::new(),
+            row_ids: HashMap::new(),
+            next_row_id: 0,
+        })
+    }
+
+    pub fn config(&self) -> &GinIndexConfig {
+        &self.config
+    }
+
+    /// Rows with at least one indexed element
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Distinct elements indexed
+    pub fn element_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Index the elements of the array `primary_key` holds, replacing what
+    /// was indexed for it before. NULL elements are not indexed.
+    pub fn insert(&mut self, primary_key: &DataValue, elements: &[DataValue]) {
+        self.remove(primary_key);
+        let mut keys: Vec<String> = elements.iter().filter_map(element_key).collect();
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            return;
+        }
+
+        let row_id = self.next_row_id;
+        self.next_row_id += 1;
+        for key in &keys {
+            self.postings.entry(key.clone()).or_default().insert(row_id);
+        }
+        if let Some(pk_key) = element_key(primary_key) {
+            self.row_ids.insert(pk_key, row_id);
+        }
+        self.rows.insert(row_id, (primary_key.clone(), keys));
+    }
+
+    pub fn remove(&mut self, primary_key: &DataValue) {
+        let Some(row_id) = element_key(primary_key).and_then(|key| self.row_ids.remove(&key)) else {
+            return;
+        };
+        if let Some((_, keys)) = self.rows.remove(&row_id) {
+            for key in keys {
+                if let Some(rows) = self.postings.get_mut(&key) {
+                    rows.remove(&row_id);
+                    if rows.is_empty() {
+                        self.postings.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.postings.clear();
+        self.rows.clear();
+        self.row_ids.clear();
+    }
+
+    /// Primary keys of the rows that may match `query`, in insertion order;
+    /// `None` when the index cannot narrow the search, as for `@>` of an
+    /// empty array, which every array satisfies
+    pub fn candidates(&self, query: GinQuery<'_>) -> Option<Vec<DataValue>> {
+        let row_ids: BTreeSet<u64> = match query {
+            GinQuery::Contains(elements) => {
+                if elements.is_empty() {
+                    return None;
+                }
+                // A NULL element equals nothing, so no array contains it
+                let keys: Option<Vec<String>> = elements.iter().map(element_key).collect();
+                let Some(keys) = keys else {
+                    return Some(Vec::new());
+                };
+                let mut lists: Vec<&BTreeSet<u64>> = match keys.iter().map(|key| self.postings.get(key)).collect() {
+                    Some(lists) => lists,
+                    None => return Some(Vec::new()),
+                };
+                // Intersect starting from the rarest element
+                lists.sort_by_key(|rows| rows.len());
+                let (first, rest) = lists.split_first()?;
+                first.iter().copied().filter(|row_id| rest.iter().all(|rows| rows.contains(row_id))).collect()
+            }
+            GinQuery::Overlaps(elements) => elements.iter()
+                .filter_map(element_key)
+                .filter_map(|key| self.postings.get(&key))
+                .flatten()
+                .copied()
+                .collect(),
+        };
+        Some(row_ids.into_iter().filter_map(|row_id| self.rows.get(&row_id)).map(|(pk, _)| pk.clone()).collect())
+    }
+}
+
+/// Canonical key of an element, so equal values index together whatever
+/// variant holds them: integral reals key like integers and `String` like
+/// `Text`. `None` for NULL.
+fn element_key(value: &DataValue) -> Option<String> {
+    Some(match value {
+        DataValue::Null => return None,
+        DataValue::Integer(i) => format!("n:{}", i),
+        DataValue::Real(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => format!("n:{}", *f as i64),
+        DataValue::Real(f) => format!("f:{}", f.to_bits()),
+        DataValue::Text(s) | DataValue::String(s) => format!("t:{}", s),
+        DataValue::Boolean(b) => format!("b:{}", b),
+        other => format!("v:{:?}", other),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(values: &[&str]) -> Vec<DataValue> {
+        values.iter().map(|value| DataValue::Text(value.to_string())).collect()
+    }
+
+    fn index() -> GinIndex {
+        let mut index = GinIndex::new(GinIndexConfig {
+            name: "idx_posts_tags".to_string(),
+            column: "tags".to_string(),
+        }).unwrap();
+        index.insert(&DataValue::Integer(1), &text(&["rust", "database"]));
+        index.insert(&DataValue::Integer(2), &text(&["rust", "web"]));
+        index.insert(&DataValue::Integer(3), &text(&["go", "database", "database"]));
+        index.insert(&DataValue::Integer(4), &[DataValue::Null]);
+        index
+    }
+
+    fn keys(candidates: Option<Vec<DataValue>>) -> Vec<i64> {
+        candidates.unwrap().into_iter()
+            .map(|pk| match pk {
+                DataValue::Integer(id) => id,
+                other => panic!("unexpected key {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        let index = index();
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&text(&["rust"])))), vec![1, 2]);
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&text(&["rust", "database"])))), vec![1]);
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&text(&["rust", "missing"])))), Vec::<i64>::new());
+        assert_eq!(keys(index.candidates(GinQuery::Overlaps(&text(&["web", "go", "missing"])))), vec![2, 3]);
+
+        // Every array contains the empty array; NULL is in none
+        assert!(index.candidates(GinQuery::Contains(&[])).is_none());
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&[DataValue::Null]))), Vec::<i64>::new());
+        assert_eq!(index.row_count(), 3);
+        assert_eq!(index.element_count(), 4);
+    }
+
+    #[test]
+    fn test_reinsert_and_remove_update_postings() {
+        let mut index = index();
+        index.insert(&DataValue::Integer(1), &text(&["web"]));
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&text(&["rust"])))), vec![2]);
+        assert_eq!(keys(index.candidates(GinQuery::Contains(&text(&["web"])))), vec![2, 1]);
+
+        index.remove(&DataValue::Integer(2));
+        index.remove(&DataValue::Integer(99));
+        assert_eq!(keys(index.candidates(GinQuery::Overlaps(&text(&["rust", "web"])))), vec![1]);
+        assert_eq!(index.row_count(), 2);
+    }
+}
@@ -9,9 +9,11 @@ use parking_lot::RwLock;
 use chrono::{DateTime, Utc};
 use crate::core::errors::{AuroraResult, AuroraError};
 use crate::core::schema::DataType;
+use crate::types::DataValue;
 use super::btree_index::{BTreeIndex, BTreeIndexConfig};
 use super::hash_index::{HashIndex, HashIndexConfig};
 use super::fulltext_index::{FullTextIndex, FullTextIndexConfig};
+use super::gin_index::{GinIndex, GinIndexConfig, GinQuery};
 use super::spatial_index::{SpatialIndex, SpatialIndexConfig};
 use super::vector_index::{VectorIndex, VectorIndexConfig};
 use super::adaptive_tuner::{AdaptiveTuner, IndexRecommendation};
@@ -26,6 +28,7 @@ pub enum IndexType {
     FullText,    // Full-text search index
     Spatial,     // Spatial/geographic index
     Vector,      // Vector similarity search index
+    Gin,         // Inverted index over array elements
     Composite,   // Multi-column index
     Partial,     // Partial index with WHERE conditions
 }
@@ -81,6 +84,7 @@ pub struct IndexManager {
     fulltext_indexes: RwLock<HashMap<String, FullTextIndex>>,
     spatial_indexes: RwLock<HashMap<String, SpatialIndex>>,
     vector_indexes: RwLock<HashMap<String, VectorIndex>>,
+    gin_indexes: RwLock<HashMap<String, GinIndex>>,
 
     // Intelligence components
     adaptive_tuner: Arc<AdaptiveTuner>,
@@ -99,6 +103,7 @@ impl IndexManager {
             fulltext_indexes: RwLock::new(HashMap::new()),
             spatial_indexes: RwLock::new(HashMap::new()),
             vector_indexes: RwLock::new(HashMap::new()),
+            gin_indexes: RwLock::new(HashMap::new()),
             adaptive_tuner: Arc::new(AdaptiveTuner::new()),
             maintenance_engine: Arc::new(MaintenanceEngine::new()),
             query_analyzer: Arc::new(QueryAnalyzer::new()),
@@ -126,6 +131,7 @@ impl IndexManager {
             IndexType::FullText => self.create_fulltext_index(&config).await?,
             IndexType::Spatial => self.create_spatial_index(&config).await?,
             IndexType::Vector => self.create_vector_index(&config).await?,
+            IndexType::Gin => self.create_gin_index(&config).await?,
             IndexType::Composite => self.create_composite_index(&config).await?,
             IndexType::Partial => self.create_partial_index(&config).await?,
         }
@@ -201,6 +207,10 @@ impl IndexManager {
                 let mut vector_indexes = self.vector_indexes.write();
                 vector_indexes.remove(index_name);
             }
+            IndexType::Gin => {
+                let mut gin_indexes = self.gin_indexes.write();
+                gin_indexes.remove(index_name);
+            }
             _ => {} // Composite and partial indexes are handled by base types
         }
 
@@ -367,6 +377,68 @@ impl IndexManager {
         }
     }
 
+    /// Name of a GIN index on `column` of `table_name`, if there is one
+    pub fn gin_index_on(&self, table_name: &str, column: &str) -> Option<String> {
+        let table_indexes = self.table_indexes.read();
+        let gin_indexes = self.gin_indexes.read();
+        table_indexes.get(table_name)?
+            .iter()
+            .find(|name| gin_indexes.get(*name).is_some_and(|index| index.config().column == column))
+            .cloned()
+    }
+
+    /// Whether `table_name` has any GIN index to keep up to date
+    pub fn has_gin_indexes(&self, table_name: &str) -> bool {
+        let table_indexes = self.table_indexes.read();
+        let gin_indexes = self.gin_indexes.read();
+        table_indexes.get(table_name)
+            .is_some_and(|names| names.iter().any(|name| gin_indexes.contains_key(name)))
+    }
+
+    /// Index a row's arrays in every GIN index on `table_name`, replacing
+    /// what was indexed for its primary key
+    pub fn gin_insert(&self, table_name: &str, primary_key: &DataValue, row: &HashMap<String, DataValue>) {
+        self.update_gin_indexes(table_name, |index| match row.get(&index.config().column) {
+            Some(DataValue::Array(elements)) => index.insert(primary_key, elements),
+            _ => index.remove(primary_key),
+        });
+    }
+
+    /// Drop a deleted row from every GIN index on `table_name`
+    pub fn gin_remove(&self, table_name: &str, primary_key: &DataValue) {
+        self.update_gin_indexes(table_name, |index| index.remove(primary_key));
+    }
+
+    /// Empty every GIN index on `table_name` ahead of a rebuild
+    pub fn gin_clear(&self, table_name: &str) {
+        self.update_gin_indexes(table_name, GinIndex::clear);
+    }
+
+    /// Primary keys of the rows that may match `query`, to be rechecked by
+    /// the caller; `None` if the index cannot narrow the search
+    pub fn gin_candidates(&self, index_name: &str, query: GinQuery<'_>) -> Option<Vec<DataValue>> {
+        let candidates = self.gin_indexes.read().get(index_name)?.candidates(query)?;
+        if let Some(config) = self.indexes.write().get_mut(index_name) {
+            config.usage_count += 1;
+            config.last_used = Some(Utc::now());
+        }
+        Some(candidates)
+    }
+
+    fn update_gin_indexes(&self, table_name: &str, mut update: impl FnMut(&mut GinIndex)) {
+        let names = self.table_indexes.read().get(table_name).cloned().unwrap_or_default();
+        let mut gin_indexes = self.gin_indexes.write();
+        let mut index_stats = self.index_stats.write();
+        for name in names {
+            if let Some(index) = gin_indexes.get_mut(&name) {
+                update(index);
+                if let Some(stats) = index_stats.get_mut(&name) {
+                    stats.entry_count = index.row_count() as u64;
+                }
+            }
+        }
+    }
+
     /// List all indexes with their statistics
     pub async fn list_indexes(&self) -> Vec<IndexSummary> {
         let indexes = self.indexes.read();
@@ -433,6 +505,15 @@ impl IndexManager {
                     return Err(AuroraError::InvalidArgument("Vector indexes must have exactly one column".to_string()));
                 }
             }
+            IndexType::Gin => {
+                // GIN indexes work on array columns
+                if config.columns.len() != 1 {
+                    return Err(AuroraError::InvalidArgument("GIN indexes must have exactly one column".to_string()));
+                }
+                if config.is_unique {
+                    return Err(AuroraError::InvalidArgument("GIN indexes cannot be unique".to_string()));
+                }
+            }
             _ => {}
         }
 
@@ -545,6 +626,19 @@ impl IndexManager {
         Ok(())
     }
 
+    async fn create_gin_index(&self, config: &IndexConfig) -> AuroraResult<()> {
+        let gin_config = GinIndexConfig {
+            name: config.name.clone(),
+            column: config.columns[0].clone(),
+        };
+
+        let index = GinIndex::new(gin_config)?;
+        let mut gin_indexes = self.gin_indexes.write();
+        gin_indexes.insert(config.name.clone(), index);
+
+        Ok(())
+    }
+
     async fn create_composite_index(&self, config: &IndexConfig) -> AuroraResult<()> {
         // Composite indexes are implemented as B-tree indexes with multiple columns
         self.create_btree_index(config).await
@@ -603,6 +697,7 @@ fn format_index_type(index_type: &IndexType) -> &'static str {
         IndexType::FullText => "Full-Text",
         IndexType::Spatial => "Spatial",
         IndexType::Vector => "Vector",
+        IndexType::Gin => "GIN",
         IndexType::Composite => "Composite",
         IndexType::Partial => "Partial",
     }
@@ -615,6 +710,7 @@ fn string_to_index_type(s: &str) -> AuroraResult<IndexType> {
         "fulltext" => Ok(IndexType::FullText),
         "spatial" => Ok(IndexType::Spatial),
         "vector" => Ok(IndexType::Vector),
+        "gin" => Ok(IndexType::Gin),
         "composite" => Ok(IndexType::Composite),
         "partial" => Ok(IndexType::Partial),
         _ => Err(AuroraError::InvalidArgument(format!("Unknown index type: {}", s))),
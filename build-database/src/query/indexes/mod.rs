@@ -8,6 +8,8 @@ pub mod index_manager;
 pub mod btree_index;
 pub mod hash_index;
 pub mod fulltext_index;
+pub mod text_search;
+pub mod gin_index;
 pub mod spatial_index;
 pub mod vector_index;
 pub mod adaptive_tuner;
@@ -18,6 +20,8 @@ pub use index_manager::*;
 pub use btree_index::*;
 pub use hash_index::*;
 pub use fulltext_index::*;
+pub use text_search::*;
+pub use gin_index::*;
 pub use spatial_index::*;
 pub use vector_index::*;
 pub use adaptive_tuner::*;
@@ -0,0 +1,401 @@
+//! Text Analysis and Search Queries
+//!
+//! Shared by the full-text index, the SQL `@@`/`MATCH` operator and hybrid
+//! keyword search so that documents and queries are reduced to the same
+//! terms. Analysis lowercases, splits on anything that is not alphanumeric,
+//! drops English stopwords and applies a light suffix-stripping stemmer;
+//! every kept term records its token position so phrases can be checked.
+//!
+//! Query syntax:
+//!
+//! ```text
+//! database index          both terms (implicit AND)
+//! database OR index       either term; `|` also works
+//! database -index         database without index; `NOT` and `!` also work
+//! "query planner"         the words adjacent and in this order
+//! (fast | quick) search   grouping
+//! ```
+
+use std::collections::HashMap;
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+/// Words too common to be worth indexing
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have",
+    "he", "her", "his", "i", "if", "in", "into", "is", "it", "its", "of", "on", "or",
+    "our", "she", "so", "than", "that", "the", "their", "them", "then", "there", "these",
+    "they", "this", "to", "was", "we", "were", "which", "while", "who", "will", "with", "you",
+];
+
+/// Positions of each term in one analyzed document
+pub type TermPositions = HashMap<String, Vec<u32>>;
+
+/// Turns text into index terms
+#[derive(Debug, Clone)]
+pub struct TextAnalyzer {
+    pub stemming: bool,
+    pub stopwords: bool,
+}
+
+impl Default for TextAnalyzer {
+    fn default() -> Self {
+        Self { stemming: true, stopwords: true }
+    }
+}
+
+impl TextAnalyzer {
+    /// Analyzer for a text search configuration name. `english` stems and
+    /// drops stopwords; `simple` only lowercases.
+    pub fn for_language(language: &str) -> AuroraResult<Self> {
+        match language.to_lowercase().as_str() {
+            "english" => Ok(Self::default()),
+            "simple" => Ok(Self { stemming: false, stopwords: false }),
+            other => Err(AuroraError::new(
+                ErrorCode::ValidationInvalidFormat,
+                format!("unsupported text search language '{}'", other),
+            )),
+        }
+    }
+
+    /// Terms of `text` with their token positions. Positions count dropped
+    /// stopwords too, so "state of the art" keeps `state` and `art` three
+    /// tokens apart.
+    pub fn analyze(&self, text: &str) -> Vec<(String, u32)> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .enumerate()
+            .filter_map(|(position, word)| self.normalize(word).map(|term| (term, position as u32)))
+            .collect()
+    }
+
+    /// Term positions of `text`, each list in ascending order
+    pub fn positions(&self, text: &str) -> TermPositions {
+        let mut positions = TermPositions::new();
+        for (term, position) in self.analyze(text) {
+            positions.entry(term).or_default().push(position);
+        }
+        positions
+    }
+
+    /// Lowercase, stopword and stem a single word
+    fn normalize(&self, word: &str) -> Option<String> {
+        let word = word.to_lowercase();
+        if self.stopwords && STOPWORDS.contains(&word.as_str()) {
+            return None;
+        }
+        Some(if self.stemming { stem(&word) } else { word })
+    }
+
+    /// Parse search syntax into a query over this analyzer's terms
+    pub fn parse_query(&self, text: &str) -> AuroraResult<TextQuery> {
+        let tokens = lex_query(text)?;
+        let mut parser = QueryParser { analyzer: self, tokens, position: 0 };
+        let query = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(query_error(text));
+        }
+        query.ok_or_else(|| AuroraError::new(
+            ErrorCode::ValidationInvalidFormat,
+            format!("text search query '{}' has no searchable terms", text),
+        ))
+    }
+}
+
+/// Light English stemmer: strips plural, past tense, gerund and adverb
+/// suffixes so that `indexes`, `indexed` and `indexing` meet at `index`.
+/// Not a full Porter stemmer, but documents and queries go through the same
+/// function, so it only has to be consistent.
+pub fn stem(word: &str) -> String {
+    if word.len() <= 3 || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return word.to_string();
+    }
+    let mut stem = word.to_string();
+    if let Some(base) = stem.strip_suffix("ies") {
+        stem = format!("{}y", base);
+    } else if stem.ends_with("sses") || stem.ends_with("xes") || stem.ends_with("ches") || stem.ends_with("shes") {
+        stem.truncate(stem.len() - 2);
+    } else if stem.ends_with('s') && !stem.ends_with("ss") && !stem.ends_with("us") {
+        stem.pop();
+    }
+    for suffix in ["ing", "ed", "ly"] {
+        if let Some(base) = stem.strip_suffix(suffix) {
+            if base.len() >= 3 && base.chars().any(is_vowel) {
+                stem = undouble(base);
+                break;
+            }
+        }
+    }
+    stem
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// `running` -> `runn` -> `run`, but `falling` keeps `fall`
+fn undouble(base: &str) -> String {
+    let bytes = base.as_bytes();
+    let n = bytes.len();
+    if n >= 2 && bytes[n - 1] == bytes[n - 2] && !matches!(bytes[n - 1], b'l' | b's' | b'z') && !is_vowel(bytes[n - 1] as char) {
+        base[..n - 1].to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+/// A parsed text search query
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextQuery {
+    Term(String),
+    /// Terms with their offsets from the first term, which must appear at
+    /// exactly those relative positions
+    Phrase(Vec<(String, u32)>),
+    And(Vec<TextQuery>),
+    Or(Vec<TextQuery>),
+    Not(Box<TextQuery>),
+}
+
+impl TextQuery {
+    /// Phrase query over analyzed words, or a single term if only one
+    /// survives analysis
+    pub fn phrase(analyzer: &TextAnalyzer, text: &str) -> Option<TextQuery> {
+        let terms = analyzer.analyze(text);
+        let first = terms.first()?.1;
+        let mut terms: Vec<(String, u32)> = terms.into_iter().map(|(term, position)| (term, position - first)).collect();
+        if terms.len() == 1 {
+            return terms.pop().map(|(term, _)| TextQuery::Term(term));
+        }
+        Some(TextQuery::Phrase(terms))
+    }
+
+    /// Whether a document with these term positions satisfies the query
+    pub fn matches(&self, document: &TermPositions) -> bool {
+        match self {
+            TextQuery::Term(term) => document.contains_key(term),
+            TextQuery::Phrase(terms) => {
+                let lists: Option<Vec<&[u32]>> = terms.iter().map(|(term, _)| document.get(term).map(Vec::as_slice)).collect();
+                lists.is_some_and(|lists| contains_phrase(terms, &lists))
+            }
+            TextQuery::And(queries) => queries.iter().all(|query| query.matches(document)),
+            TextQuery::Or(queries) => queries.iter().any(|query| query.matches(document)),
+            TextQuery::Not(query) => !query.matches(document),
+        }
+    }
+
+    /// Terms that count towards ranking; negated terms do not
+    pub fn scoring_terms(&self) -> Vec<&str> {
+        let mut terms = Vec::new();
+        self.collect_scoring_terms(&mut terms);
+        terms.sort_unstable();
+        terms.dedup();
+        terms
+    }
+
+    fn collect_scoring_terms<'a>(&'a self, terms: &mut Vec<&'a str>) {
+        match self {
+            TextQuery::Term(term) => terms.push(term),
+            TextQuery::Phrase(phrase) => terms.extend(phrase.iter().map(|(term, _)| term.as_str())),
+            TextQuery::And(queries) | TextQuery::Or(queries) => {
+                for query in queries {
+                    query.collect_scoring_terms(terms);
+                }
+            }
+            TextQuery::Not(_) => {}
+        }
+    }
+}
+
+/// Whether some start position puts every phrase term at its offset.
+/// `lists` holds each term's ascending positions, in phrase order.
+pub(crate) fn contains_phrase(terms: &[(String, u32)], lists: &[&[u32]]) -> bool {
+    lists[0].iter().any(|&start| {
+        terms.iter().zip(lists).all(|((_, offset), positions)| positions.binary_search(&(start + offset)).is_ok())
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+    Word(String),
+    Phrase(String),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn query_error(text: &str) -> AuroraError {
+    AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("invalid text search query '{}'", text))
+}
+
+fn lex_query(text: &str) -> AuroraResult<Vec<QueryToken>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => { chars.next(); }
+            '(' => { chars.next(); tokens.push(QueryToken::LParen); }
+            ')' => { chars.next(); tokens.push(QueryToken::RParen); }
+            '|' => { chars.next(); tokens.push(QueryToken::Or); }
+            '&' => { chars.next(); tokens.push(QueryToken::And); }
+            '-' | '!' => { chars.next(); tokens.push(QueryToken::Not); }
+            '"' => {
+                chars.next();
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => phrase.push(c),
+                        None => return Err(query_error(text)),
+                    }
+                }
+                tokens.push(QueryToken::Phrase(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '|' | '&' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => QueryToken::And,
+                    "OR" => QueryToken::Or,
+                    "NOT" => QueryToken::Not,
+                    _ => QueryToken::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over query tokens. Each level returns `None` when all
+/// of its words were stopwords, so `the OR database` reduces to `database`.
+struct QueryParser<'a> {
+    analyzer: &'a TextAnalyzer,
+    tokens: Vec<QueryToken>,
+    position: usize,
+}
+
+impl QueryParser<'_> {
+    fn peek(&self) -> Option<&QueryToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> AuroraResult<Option<TextQuery>> {
+        let mut branches: Vec<TextQuery> = self.parse_and()?.into_iter().collect();
+        while self.peek() == Some(&QueryToken::Or) {
+            self.position += 1;
+            branches.extend(self.parse_and()?);
+        }
+        Ok(combine(branches, TextQuery::Or))
+    }
+
+    fn parse_and(&mut self) -> AuroraResult<Option<TextQuery>> {
+        let mut operands: Vec<TextQuery> = self.parse_unary()?.into_iter().collect();
+        loop {
+            match self.peek() {
+                Some(QueryToken::And) => self.position += 1,
+                Some(QueryToken::Word(_)) | Some(QueryToken::Phrase(_)) | Some(QueryToken::Not) | Some(QueryToken::LParen) => {}
+                _ => break,
+            }
+            operands.extend(self.parse_unary()?);
+        }
+        Ok(combine(operands, TextQuery::And))
+    }
+
+    fn parse_unary(&mut self) -> AuroraResult<Option<TextQuery>> {
+        if self.peek() == Some(&QueryToken::Not) {
+            self.position += 1;
+            return Ok(self.parse_unary()?.map(|query| TextQuery::Not(Box::new(query))));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> AuroraResult<Option<TextQuery>> {
+        let token = self.tokens.get(self.position).cloned()
+            .ok_or_else(|| AuroraError::new(ErrorCode::ValidationInvalidFormat, "text search query ends unexpectedly"))?;
+        self.position += 1;
+        match token {
+            // A word such as `e-mail` can analyze to several terms; those
+            // must appear together, as a phrase
+            QueryToken::Word(word) | QueryToken::Phrase(word) => Ok(TextQuery::phrase(self.analyzer, &word)),
+            QueryToken::LParen => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&QueryToken::RParen) {
+                    return Err(AuroraError::new(ErrorCode::ValidationInvalidFormat, "unbalanced parentheses in text search query"));
+                }
+                self.position += 1;
+                Ok(inner)
+            }
+            other => Err(AuroraError::new(
+                ErrorCode::ValidationInvalidFormat,
+                format!("unexpected {:?} in text search query", other),
+            )),
+        }
+    }
+}
+
+fn combine(mut queries: Vec<TextQuery>, build: fn(Vec<TextQuery>) -> TextQuery) -> Option<TextQuery> {
+    match queries.len() {
+        0 => None,
+        1 => queries.pop(),
+        _ => Some(build(queries)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_stems_and_drops_stopwords() {
+        let analyzer = TextAnalyzer::default();
+        let terms = analyzer.analyze("The Indexes were indexed; indexing RUNNING queries!");
+        assert_eq!(terms, vec![
+            ("index".to_string(), 1),
+            ("index".to_string(), 3),
+            ("index".to_string(), 4),
+            ("run".to_string(), 5),
+            ("query".to_string(), 6),
+        ]);
+        assert_eq!(stem("falling"), "fall");
+        assert_eq!(stem("boxes"), "box");
+        assert_eq!(stem("status"), "status");
+
+        let simple = TextAnalyzer::for_language("simple").unwrap();
+        assert_eq!(simple.analyze("The Cats"), vec![("the".to_string(), 0), ("cats".to_string(), 1)]);
+        assert!(TextAnalyzer::for_language("klingon").is_err());
+    }
+
+    #[test]
+    fn test_parse_boolean_and_phrase_queries() {
+        let analyzer = TextAnalyzer::default();
+        let term = |t: &str| TextQuery::Term(t.to_string());
+
+        assert_eq!(analyzer.parse_query("databases indexing").unwrap(), TextQuery::And(vec![term("database"), term("index")]));
+        assert_eq!(
+            analyzer.parse_query("(fast | quick) -slow").unwrap(),
+            TextQuery::And(vec![TextQuery::Or(vec![term("fast"), term("quick")]), TextQuery::Not(Box::new(term("slow")))]),
+        );
+        assert_eq!(
+            analyzer.parse_query("\"state of the art\" OR the").unwrap(),
+            TextQuery::Phrase(vec![("state".to_string(), 0), ("art".to_string(), 3)]),
+        );
+        assert!(analyzer.parse_query("the of").is_err());
+        assert!(analyzer.parse_query("(open").is_err());
+        assert!(analyzer.parse_query("\"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_phrase_respects_word_order() {
+        let analyzer = TextAnalyzer::default();
+        let query = analyzer.parse_query("\"query planner\"").unwrap();
+        assert!(query.matches(&analyzer.positions("The query planner picks a join order")));
+        assert!(!query.matches(&analyzer.positions("The planner rewrites each query")));
+        assert!(!query.matches(&analyzer.positions("query the planner")));
+    }
+}
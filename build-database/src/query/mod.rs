@@ -19,6 +19,7 @@ pub mod recursive_ctes;
 pub mod stored_procedures;
 pub mod triggers;
 pub mod indexes;
+pub mod udf;
 
 // Re-export main query processing components
 pub use parser::{SqlParser, ParseResult, Query};
@@ -30,3 +31,4 @@ pub use recursive_ctes::*;
 pub use stored_procedures::*;
 pub use triggers::*;
 pub use indexes::*;
+pub use udf::{FunctionRegistry, FunctionSignature, ScalarFunction};
@@ -35,6 +35,25 @@ pub enum Query {
     VectorSearch(VectorQuery),
     CreateTable(CreateTableQuery),
     DropTable(DropTableQuery),
+    CreateMaterializedView(CreateMaterializedViewQuery),
+    RefreshMaterializedView(RefreshMaterializedViewQuery),
+    DropMaterializedView(DropMaterializedViewQuery),
+    AlterTable(AlterTableQuery),
+    Merge(MergeQuery),
+    /// EXPLAIN of the wrapped statement
+    Explain(Box<Query>, ExplainOptions),
+}
+
+/// Options of an EXPLAIN statement
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplainOptions {
+    /// Run the statement and report what it actually did
+    pub analyze: bool,
+    /// Report buffer usage per plan node; requires `analyze`
+    pub buffers: bool,
+    /// Sample where execution spends its time, as collapsed stacks;
+    /// requires `analyze`
+    pub profile: bool,
 }
 
 /// SELECT query with AI extensions
@@ -111,6 +130,12 @@ pub enum BinaryOperator {
     Minus,
     Multiply,
     Divide,
+    /// Full-text match, `document @@ 'query'`
+    TextMatch,
+    /// Array containment, `tags @> ARRAY['a', 'b']`
+    ArrayContains,
+    /// Array overlap, `tags && ARRAY['a', 'b']`
+    ArrayOverlaps,
 }
 
 /// Function calls
@@ -175,9 +200,21 @@ pub enum SelectItem {
 /// FROM clause
 #[derive(Debug, Clone)]
 pub struct FromClause {
+    /// Empty when the FROM clause is only `unnest(...)`
     pub table: String,
     pub alias: Option<String>,
     pub joins: Vec<JoinClause>,
+    /// `unnest(array) AS alias`, after the table if there is one
+    pub unnest: Option<UnnestClause>,
+}
+
+/// `unnest(array) AS alias` in a FROM clause: one row per array element,
+/// with the element in column `alias`. After a table, `array` may name that
+/// table's columns and each row is repeated once per element of its array.
+#[derive(Debug, Clone)]
+pub struct UnnestClause {
+    pub array: Expression,
+    pub alias: String,
 }
 
 /// Join clauses
@@ -186,7 +223,11 @@ pub struct JoinClause {
     pub join_type: JoinType,
     pub table: String,
     pub alias: Option<String>,
+    /// ON condition; for an ASOF join the optional equality on a
+    /// partition key, `TRUE` when there is none
     pub condition: Expression,
+    /// ASOF join's `MATCH_CONDITION (left.ts >= right.ts)`
+    pub match_condition: Option<Expression>,
 }
 
 /// Join types
@@ -196,6 +237,9 @@ pub enum JoinType {
     Left,
     Right,
     Full,
+    /// Each left row joined to the nearest right row satisfying the match
+    /// condition, or to NULLs if none does
+    AsOf,
 }
 
 /// GROUP BY clause
@@ -237,6 +281,61 @@ pub struct InsertQuery {
     pub table: String,
     pub columns: Vec<String>,
     pub values: Vec<Vec<Expression>>,
+    /// `INSERT ... SELECT`: rows come from this query instead of `values`
+    pub select: Option<Box<SelectQuery>>,
+    pub on_conflict: Option<OnConflict>,
+}
+
+/// `ON CONFLICT [(columns)] DO ...` on an INSERT
+#[derive(Debug, Clone)]
+pub struct OnConflict {
+    /// Conflict target; empty means the primary key
+    pub target: Vec<String>,
+    pub action: ConflictAction,
+}
+
+/// What an INSERT does with a row whose key already exists
+#[derive(Debug, Clone)]
+pub enum ConflictAction {
+    DoNothing,
+    /// `DO UPDATE SET ...`; `excluded.column` names the proposed row
+    DoUpdate(Vec<Assignment>),
+}
+
+/// `MERGE INTO target USING source ON condition WHEN ...`
+#[derive(Debug, Clone)]
+pub struct MergeQuery {
+    pub target: String,
+    pub target_alias: Option<String>,
+    pub source: String,
+    pub source_alias: Option<String>,
+    /// Join condition pairing source rows with target rows
+    pub condition: Expression,
+    /// WHEN clauses in the order written; the first that applies to a row wins
+    pub clauses: Vec<MergeClause>,
+}
+
+/// `WHEN [NOT] MATCHED [AND condition] THEN action`
+#[derive(Debug, Clone)]
+pub struct MergeClause {
+    /// Whether the clause is for source rows with a target row (`MATCHED`)
+    /// or without one (`NOT MATCHED`)
+    pub matched: bool,
+    pub condition: Option<Expression>,
+    pub action: MergeAction,
+}
+
+/// What a MERGE clause does
+#[derive(Debug, Clone)]
+pub enum MergeAction {
+    /// `UPDATE SET ...`, matched rows only
+    Update(Vec<Assignment>),
+    /// `DELETE`, matched rows only
+    Delete,
+    /// `INSERT [(columns)] VALUES (...)`, unmatched rows only; no columns
+    /// means every column of the target in order
+    Insert { columns: Vec<String>, values: Vec<Expression> },
+    DoNothing,
 }
 
 /// UPDATE query
@@ -275,6 +374,12 @@ pub struct CreateTableQuery {
     pub name: String,
     pub columns: Vec<ColumnDefinition>,
     pub constraints: Vec<TableConstraint>,
+    /// Storage engine pinned with `USING <engine>`; `None` lets the storage manager choose
+    pub storage_engine: Option<crate::storage::engine::StorageEngineType>,
+    /// Hash partitioning from `PARTITION BY HASH (col) PARTITIONS n`
+    pub partitioning: Option<crate::catalog::PartitionScheme>,
+    /// Row expiry column from `WITH (ttl_column = col)`
+    pub ttl_column: Option<String>,
 }
 
 /// Column definition
@@ -304,3 +409,42 @@ pub struct DropTableQuery {
     pub name: String,
     pub if_exists: bool,
 }
+
+/// ALTER TABLE query
+#[derive(Debug, Clone)]
+pub struct AlterTableQuery {
+    pub name: String,
+    pub action: AlterTableAction,
+}
+
+/// What an ALTER TABLE changes
+#[derive(Debug, Clone)]
+pub enum AlterTableAction {
+    /// `ALTER [COLUMN] column [SET DATA] TYPE data_type`
+    AlterColumnType {
+        column: String,
+        data_type: crate::data::DataType,
+    },
+}
+
+/// CREATE MATERIALIZED VIEW query
+#[derive(Debug, Clone)]
+pub struct CreateMaterializedViewQuery {
+    pub name: String,
+    pub query: SelectQuery,
+}
+
+/// REFRESH MATERIALIZED VIEW query
+#[derive(Debug, Clone)]
+pub struct RefreshMaterializedViewQuery {
+    pub name: String,
+    /// `CONCURRENTLY`: keep serving the old contents while the new ones are computed
+    pub concurrently: bool,
+}
+
+/// DROP MATERIALIZED VIEW query
+#[derive(Debug, Clone)]
+pub struct DropMaterializedViewQuery {
+    pub name: String,
+    pub if_exists: bool,
+}
@@ -61,8 +61,19 @@ impl SqlParser {
             Some(Token::Keyword(keyword)) => match keyword.as_str() {
                 "SELECT" => Ok(Query::Select(SelectParser::parse(tokens)?)),
                 "INSERT" | "UPDATE" | "DELETE" => Ok(DmlParser::parse(tokens)?),
-                "CREATE" | "DROP" => Ok(DdlParser::parse(tokens)?),
+                "MERGE" => Ok(Query::Merge(MergeParser::parse(tokens)?)),
+                "CREATE" | "DROP" | "REFRESH" | "ALTER" => Ok(DdlParser::parse(tokens)?),
                 "NEAREST" | "VECTOR_SEARCH" => Ok(Query::VectorSearch(VectorParser::parse(tokens)?)),
+                "EXPLAIN" => {
+                    let (options, consumed) = self.parse_explain_options(&tokens[1..])?;
+                    match self.parse_query(&tokens[1 + consumed..])? {
+                        Query::Explain(..) => Err(ParseError::SyntaxError {
+                            position: self.position,
+                            message: "EXPLAIN cannot be nested".to_string(),
+                        }),
+                        query => Ok(Query::Explain(Box::new(query), options)),
+                    }
+                }
                 _ => Err(ParseError::SyntaxError {
                     position: self.position,
                     message: format!("Unsupported query type: {}", keyword),
@@ -75,6 +86,56 @@ impl SqlParser {
         }
     }
 
+    /// Parse `ANALYZE` or a parenthesized option list such as
+    /// `(ANALYZE, BUFFERS, PROFILE)`; returns the options and the tokens consumed
+    fn parse_explain_options(&self, tokens: &[Token]) -> ParseResult<(ExplainOptions, usize)> {
+        let mut options = ExplainOptions::default();
+        let consumed = match tokens.first() {
+            Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("ANALYZE") => {
+                options.analyze = true;
+                1
+            }
+            Some(Token::LeftParen) => {
+                let mut position = 1;
+                loop {
+                    match tokens.get(position) {
+                        Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("ANALYZE") => options.analyze = true,
+                        Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("BUFFERS") => options.buffers = true,
+                        Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("PROFILE") => options.profile = true,
+                        other => {
+                            return Err(ParseError::SyntaxError {
+                                position: self.position,
+                                message: format!("Unrecognized EXPLAIN option: {:?}", other),
+                            });
+                        }
+                    }
+                    position += 1;
+                    match tokens.get(position) {
+                        Some(Token::Comma) => position += 1,
+                        Some(Token::RightParen) => break position + 1,
+                        _ => {
+                            return Err(ParseError::SyntaxError {
+                                position: self.position,
+                                message: "Expected ',' or ')' in EXPLAIN options".to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            _ => 0,
+        };
+
+        for (requested, option) in [(options.buffers, "BUFFERS"), (options.profile, "PROFILE")] {
+            if requested && !options.analyze {
+                return Err(ParseError::SyntaxError {
+                    position: self.position,
+                    message: format!("EXPLAIN option {} requires ANALYZE", option),
+                });
+            }
+        }
+        Ok((options, consumed))
+    }
+
     /// Get parser statistics
     pub fn stats(&self) -> &ParserStats {
         &self.stats
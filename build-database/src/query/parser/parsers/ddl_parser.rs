@@ -3,9 +3,11 @@
 //! Parses Data Definition Language queries:
 //! - CREATE TABLE statements
 //! - DROP TABLE statements
-//! - ALTER TABLE statements (future)
+//! - CREATE / REFRESH / DROP MATERIALIZED VIEW statements
+//! - ALTER TABLE ... ALTER COLUMN ... TYPE statements
 
 use crate::query::parser::ast::*;
+use super::SelectParser;
 
 /// DDL query parser
 pub struct DdlParser;
@@ -16,6 +18,7 @@ impl DdlParser {
         match tokens.get(1) {
             Some(Token::Keyword(keyword)) => match keyword.as_str() {
                 "TABLE" => self.parse_table_statement(tokens),
+                "MATERIALIZED" => self.parse_materialized_view_statement(tokens),
                 _ => Err(ParseError::SyntaxError {
                     position: 1,
                     message: format!("Unsupported DDL object: {}", keyword),
@@ -34,6 +37,7 @@ impl DdlParser {
             Some(Token::Keyword(keyword)) => match keyword.as_str() {
                 "CREATE" => Ok(Query::CreateTable(self.parse_create_table(tokens)?)),
                 "DROP" => Ok(Query::DropTable(self.parse_drop_table(tokens)?)),
+                "ALTER" => Ok(Query::AlterTable(self.parse_alter_table(tokens)?)),
                 _ => Err(ParseError::SyntaxError {
                     position: 0,
                     message: format!("Unsupported table DDL: {}", keyword),
@@ -46,6 +50,78 @@ impl DdlParser {
         }
     }
 
+    /// Parse materialized-view DDL statements
+    fn parse_materialized_view_statement(&self, tokens: &[Token]) -> ParseResult<Query> {
+        match tokens.first() {
+            Some(Token::Keyword(keyword)) => match keyword.as_str() {
+                "CREATE" => Ok(Query::CreateMaterializedView(self.parse_create_materialized_view(tokens)?)),
+                "REFRESH" => Ok(Query::RefreshMaterializedView(self.parse_refresh_materialized_view(tokens)?)),
+                "DROP" => Ok(Query::DropMaterializedView(self.parse_drop_materialized_view(tokens)?)),
+                _ => Err(ParseError::SyntaxError {
+                    position: 0,
+                    message: format!("Unsupported materialized view DDL: {}", keyword),
+                }),
+            },
+            _ => Err(ParseError::SyntaxError {
+                position: 0,
+                message: "Expected DDL keyword".to_string(),
+            }),
+        }
+    }
+
+    /// Parse CREATE MATERIALIZED VIEW name AS SELECT ...
+    fn parse_create_materialized_view(&self, tokens: &[Token]) -> ParseResult<CreateMaterializedViewQuery> {
+        let mut position = 0;
+
+        self.expect_keyword(tokens, &mut position, "CREATE")?;
+        self.expect_keyword(tokens, &mut position, "MATERIALIZED")?;
+        self.expect_keyword(tokens, &mut position, "VIEW")?;
+        let name = self.parse_table_name(tokens, &mut position)?;
+        self.expect_keyword(tokens, &mut position, "AS")?;
+
+        // The defining query is an ordinary SELECT
+        let query = SelectParser::parse(&tokens[position..])?;
+
+        Ok(CreateMaterializedViewQuery { name, query })
+    }
+
+    /// Parse REFRESH MATERIALIZED VIEW [CONCURRENTLY] name
+    fn parse_refresh_materialized_view(&self, tokens: &[Token]) -> ParseResult<RefreshMaterializedViewQuery> {
+        let mut position = 0;
+
+        self.expect_keyword(tokens, &mut position, "REFRESH")?;
+        self.expect_keyword(tokens, &mut position, "MATERIALIZED")?;
+        self.expect_keyword(tokens, &mut position, "VIEW")?;
+        let concurrently = if matches!(tokens.get(position), Some(Token::Keyword(kw)) if kw == "CONCURRENTLY") {
+            position += 1;
+            true
+        } else {
+            false
+        };
+        let name = self.parse_table_name(tokens, &mut position)?;
+
+        Ok(RefreshMaterializedViewQuery { name, concurrently })
+    }
+
+    /// Parse DROP MATERIALIZED VIEW [IF EXISTS] name
+    fn parse_drop_materialized_view(&self, tokens: &[Token]) -> ParseResult<DropMaterializedViewQuery> {
+        let mut position = 0;
+
+        self.expect_keyword(tokens, &mut position, "DROP")?;
+        self.expect_keyword(tokens, &mut position, "MATERIALIZED")?;
+        self.expect_keyword(tokens, &mut position, "VIEW")?;
+        let if_exists = if matches!(tokens.get(position), Some(Token::Keyword(kw)) if kw == "IF") {
+            self.expect_keyword(tokens, &mut position, "IF")?;
+            self.expect_keyword(tokens, &mut position, "EXISTS")?;
+            true
+        } else {
+            false
+        };
+        let name = self.parse_table_name(tokens, &mut position)?;
+
+        Ok(DropMaterializedViewQuery { name, if_exists })
+    }
+
     /// Parse CREATE TABLE statement
     fn parse_create_table(&self, tokens: &[Token]) -> ParseResult<CreateTableQuery> {
         let mut position = 0;
@@ -66,13 +142,134 @@ impl DdlParser {
         // Expect closing parenthesis
         self.expect_token(tokens, &mut position, Token::RightParen)?;
 
+        // Optional storage engine: USING {btree|lsm|hybrid|columnar}
+        let storage_engine = if matches!(tokens.get(position), Some(Token::Keyword(kw)) if kw == "USING") {
+            self.expect_keyword(tokens, &mut position, "USING")?;
+            Some(self.parse_storage_engine(tokens, &mut position)?)
+        } else {
+            None
+        };
+
+        // Optional partitioning: PARTITION BY HASH (column) PARTITIONS n
+        let partitioning = if self.match_word(tokens, &mut position, "PARTITION") {
+            Some(self.parse_partition_scheme(tokens, &mut position)?)
+        } else {
+            None
+        };
+
+        // Optional table options: WITH (ttl_column = column)
+        let ttl_column = if self.match_word(tokens, &mut position, "WITH") {
+            self.parse_table_options(tokens, &mut position)?
+        } else {
+            None
+        };
+
         Ok(CreateTableQuery {
             name: table_name,
             columns,
             constraints,
+            storage_engine,
+            partitioning,
+            ttl_column,
         })
     }
 
+    /// Parse `(option = value, ...)` after WITH, returning the TTL column
+    fn parse_table_options(&self, tokens: &[Token], position: &mut usize) -> ParseResult<Option<String>> {
+        let mut ttl_column = None;
+
+        self.expect_token(tokens, position, Token::LeftParen)?;
+        loop {
+            if !self.match_word(tokens, position, "TTL_COLUMN") {
+                return Err(ParseError::SyntaxError {
+                    position: *position,
+                    message: "Expected a table option; the only one supported is ttl_column".to_string(),
+                });
+            }
+            self.expect_token(tokens, position, Token::Equals)?;
+            ttl_column = match tokens.get(*position) {
+                Some(Token::Identifier(name)) => {
+                    *position += 1;
+                    Some(name.clone())
+                }
+                _ => return Err(ParseError::SyntaxError {
+                    position: *position,
+                    message: "Expected a column name for ttl_column".to_string(),
+                }),
+            };
+
+            if matches!(tokens.get(*position), Some(Token::Comma)) {
+                *position += 1;
+            } else {
+                break;
+            }
+        }
+        self.expect_token(tokens, position, Token::RightParen)?;
+
+        Ok(ttl_column)
+    }
+
+    /// Parse `BY HASH (column) PARTITIONS n` after PARTITION
+    fn parse_partition_scheme(&self, tokens: &[Token], position: &mut usize) -> ParseResult<crate::catalog::PartitionScheme> {
+        self.expect_keyword(tokens, position, "BY")?;
+        if !self.match_word(tokens, position, "HASH") {
+            return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected HASH after PARTITION BY".to_string(),
+            });
+        }
+
+        self.expect_token(tokens, position, Token::LeftParen)?;
+        let column = match tokens.get(*position) {
+            Some(Token::Identifier(name)) => {
+                *position += 1;
+                name.clone()
+            }
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected partition key column".to_string(),
+            }),
+        };
+        self.expect_token(tokens, position, Token::RightParen)?;
+
+        if !self.match_word(tokens, position, "PARTITIONS") {
+            return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected PARTITIONS after partition key".to_string(),
+            });
+        }
+        let partitions = match tokens.get(*position) {
+            Some(Token::Integer(n)) if *n >= 1 && *n <= u32::MAX as i64 => {
+                *position += 1;
+                *n as u32
+            }
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected a positive partition count".to_string(),
+            }),
+        };
+
+        Ok(crate::catalog::PartitionScheme { column, partitions })
+    }
+
+    /// Parse storage engine name after USING
+    fn parse_storage_engine(&self, tokens: &[Token], position: &mut usize) -> ParseResult<crate::storage::engine::StorageEngineType> {
+        match tokens.get(*position) {
+            Some(Token::Identifier(name)) | Some(Token::Keyword(name)) => {
+                let engine = crate::storage::engine::StorageEngineType::parse(name).ok_or_else(|| ParseError::SyntaxError {
+                    position: *position,
+                    message: format!("Unknown storage engine '{}', expected one of: btree, lsm, hybrid, columnar", name),
+                })?;
+                *position += 1;
+                Ok(engine)
+            }
+            _ => Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected storage engine name after USING".to_string(),
+            }),
+        }
+    }
+
     /// Parse DROP TABLE statement
     fn parse_drop_table(&self, tokens: &[Token]) -> ParseResult<DropTableQuery> {
         let mut position = 0;
@@ -99,6 +296,46 @@ impl DdlParser {
         })
     }
 
+    /// Parse ALTER TABLE name ALTER [COLUMN] column [SET DATA] TYPE data_type
+    fn parse_alter_table(&self, tokens: &[Token]) -> ParseResult<AlterTableQuery> {
+        let mut position = 0;
+
+        self.expect_keyword(tokens, &mut position, "ALTER")?;
+        self.expect_keyword(tokens, &mut position, "TABLE")?;
+        let table_name = self.parse_table_name(tokens, &mut position)?;
+
+        self.expect_keyword(tokens, &mut position, "ALTER")?;
+        self.match_word(tokens, &mut position, "COLUMN");
+        let column = match tokens.get(position) {
+            Some(Token::Identifier(name)) => {
+                position += 1;
+                name.clone()
+            }
+            _ => return Err(ParseError::SyntaxError {
+                position,
+                message: "Expected column name".to_string(),
+            }),
+        };
+        if self.match_word(tokens, &mut position, "SET") && !self.match_word(tokens, &mut position, "DATA") {
+            return Err(ParseError::SyntaxError {
+                position,
+                message: "Expected SET DATA TYPE".to_string(),
+            });
+        }
+        if !self.match_word(tokens, &mut position, "TYPE") {
+            return Err(ParseError::SyntaxError {
+                position,
+                message: "Expected TYPE after column name; only ALTER COLUMN ... TYPE is supported".to_string(),
+            });
+        }
+        let data_type = self.parse_data_type(tokens, &mut position)?;
+
+        Ok(AlterTableQuery {
+            name: table_name,
+            action: AlterTableAction::AlterColumnType { column, data_type },
+        })
+    }
+
     /// Parse table name
     fn parse_table_name(&self, tokens: &[Token], position: &mut usize) -> ParseResult<String> {
         match tokens.get(*position) {
@@ -237,29 +474,96 @@ impl DdlParser {
 
     /// Parse data type
     fn parse_data_type(&self, tokens: &[Token], position: &mut usize) -> ParseResult<crate::data::DataType> {
-        match tokens.get(*position) {
-            Some(Token::Keyword(kw)) => {
-                let data_type = match kw.as_str() {
-                    "INTEGER" | "INT" => crate::data::DataType::Integer,
-                    "BIGINT" => crate::data::DataType::BigInt,
-                    "FLOAT" | "REAL" => crate::data::DataType::Float,
-                    "DOUBLE" => crate::data::DataType::Double,
-                    "TEXT" | "VARCHAR" => crate::data::DataType::Text,
-                    "BOOLEAN" | "BOOL" => crate::data::DataType::Boolean,
-                    "BLOB" => crate::data::DataType::Blob,
-                    _ => return Err(ParseError::SyntaxError {
-                        position: *position,
-                        message: format!("Unknown data type: {}", kw),
-                    }),
-                };
+        let data_type = self.parse_scalar_type(tokens, position)?;
+
+        // `type[]` is an array of that type
+        if matches!(tokens.get(*position), Some(Token::LeftBracket)) {
+            if !matches!(tokens.get(*position + 1), Some(Token::RightBracket)) {
+                return Err(ParseError::SyntaxError {
+                    position: *position + 1,
+                    message: "Expected ']' after '[' in array type".to_string(),
+                });
+            }
+            *position += 2;
+            return Ok(crate::data::DataType::Array(Box::new(data_type)));
+        }
+        Ok(data_type)
+    }
+
+    fn parse_scalar_type(&self, tokens: &[Token], position: &mut usize) -> ParseResult<crate::data::DataType> {
+        let name = match tokens.get(*position) {
+            Some(Token::Keyword(kw)) => kw.clone(),
+            Some(Token::Identifier(name)) => name.to_uppercase(),
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected data type".to_string(),
+            }),
+        };
+
+        let data_type = match name.as_str() {
+            "INTEGER" | "INT" => crate::data::DataType::Integer,
+            "BIGINT" => crate::data::DataType::BigInt,
+            "FLOAT" | "REAL" => crate::data::DataType::Float,
+            "DOUBLE" => crate::data::DataType::Double,
+            "TEXT" | "VARCHAR" => crate::data::DataType::Text,
+            "BOOLEAN" | "BOOL" => crate::data::DataType::Boolean,
+            "BLOB" => crate::data::DataType::Blob,
+            "TIMESTAMPTZ" => crate::data::DataType::TimestampTz,
+            "INTERVAL" => crate::data::DataType::Interval,
+            "TIMESTAMP" => {
                 *position += 1;
-                Ok(data_type)
+                return self.parse_timestamp_zone(tokens, position);
+            }
+            "DECIMAL" | "NUMERIC" => {
+                *position += 1;
+                let (precision, scale) = self.parse_decimal_modifiers(tokens, position)?;
+                return Ok(crate::data::DataType::Decimal(precision, scale));
+            }
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Unknown data type: {}", name),
+            }),
+        };
+        *position += 1;
+        Ok(data_type)
+    }
+
+    /// Parse the optional `(precision [, scale])` after DECIMAL / NUMERIC.
+    /// Without modifiers the column is unconstrained, recorded as `(0, 0)`;
+    /// a missing scale means 0.
+    fn parse_decimal_modifiers(&self, tokens: &[Token], position: &mut usize) -> ParseResult<(u8, u8)> {
+        if !matches!(tokens.get(*position), Some(Token::LeftParen)) {
+            return Ok((0, 0));
+        }
+        *position += 1;
+
+        let modifier = |position: &mut usize, what: &str| match tokens.get(*position) {
+            Some(Token::Integer(value)) if (0..=u8::MAX as i64).contains(value) => {
+                *position += 1;
+                Ok(*value as u8)
             }
             _ => Err(ParseError::SyntaxError {
                 position: *position,
-                message: "Expected data type".to_string(),
+                message: format!("DECIMAL {} must be an integer between 0 and {}", what, u8::MAX),
             }),
+        };
+
+        let precision = modifier(position, "precision")?;
+        let scale = if matches!(tokens.get(*position), Some(Token::Comma)) {
+            *position += 1;
+            modifier(position, "scale")?
+        } else {
+            0
+        };
+        self.expect_token(tokens, position, Token::RightParen)?;
+
+        if precision == 0 || scale > precision {
+            return Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Invalid DECIMAL({}, {}): precision must be at least 1 and no less than scale", precision, scale),
+            });
         }
+        Ok((precision, scale))
     }
 
     /// Try to parse table constraint
@@ -344,6 +648,38 @@ impl DdlParser {
         self.try_parse_table_constraint(tokens, *position)
     }
 
+    /// Parse the optional `WITH TIME ZONE` / `WITHOUT TIME ZONE` after TIMESTAMP
+    fn parse_timestamp_zone(&self, tokens: &[Token], position: &mut usize) -> ParseResult<crate::data::DataType> {
+        let with_zone = if self.match_word(tokens, position, "WITH") {
+            true
+        } else if self.match_word(tokens, position, "WITHOUT") {
+            false
+        } else {
+            return Ok(crate::data::DataType::Timestamp);
+        };
+        if !self.match_word(tokens, position, "TIME") || !self.match_word(tokens, position, "ZONE") {
+            return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected TIME ZONE".to_string(),
+            });
+        }
+        Ok(if with_zone { crate::data::DataType::TimestampTz } else { crate::data::DataType::Timestamp })
+    }
+
+    /// Helper: Consume `word` whether it was tokenized as a keyword or an
+    /// identifier (TIME and ZONE are also valid column names)
+    fn match_word(&self, tokens: &[Token], position: &mut usize, word: &str) -> bool {
+        let matched = match tokens.get(*position) {
+            Some(Token::Keyword(kw)) => kw == word,
+            Some(Token::Identifier(name)) => name.eq_ignore_ascii_case(word),
+            _ => false,
+        };
+        if matched {
+            *position += 1;
+        }
+        matched
+    }
+
     /// Helper: Expect specific keyword
     fn expect_keyword(&self, tokens: &[Token], position: &mut usize, keyword: &str) -> ParseResult<()> {
         match tokens.get(*position) {
@@ -6,6 +6,7 @@
 //! - DELETE statements
 
 use crate::query::parser::ast::*;
+use super::select_parser::SelectParser;
 
 /// DML query parser
 pub struct DmlParser;
@@ -51,19 +52,66 @@ impl DmlParser {
             vec![] // No columns specified, will infer from values
         };
 
-        // Expect VALUES
-        self.expect_keyword(tokens, &mut position, "VALUES")?;
+        // VALUES lists or a SELECT, which runs up to ON CONFLICT or the end
+        let (values, select) = if matches!(tokens.get(position), Some(Token::Keyword(kw)) if kw == "SELECT") {
+            let end = Self::find_on_conflict(tokens, position).unwrap_or(tokens.len());
+            let select = SelectParser::parse(&tokens[position..end])?;
+            position = end;
+            (vec![], Some(Box::new(select)))
+        } else {
+            self.expect_keyword(tokens, &mut position, "VALUES")?;
+            (self.parse_value_lists(tokens, &mut position)?, None)
+        };
 
-        // Parse value lists
-        let values = self.parse_value_lists(tokens, &mut position)?;
+        let on_conflict = if Self::find_on_conflict(tokens, position) == Some(position) {
+            position += 2;
+            Some(self.parse_on_conflict(tokens, &mut position)?)
+        } else {
+            None
+        };
 
         Ok(InsertQuery {
             table: table_name,
             columns,
             values,
+            select,
+            on_conflict,
         })
     }
 
+    /// Position of the first `ON CONFLICT` at or after `from`
+    fn find_on_conflict(tokens: &[Token], from: usize) -> Option<usize> {
+        (from..tokens.len().saturating_sub(1)).find(|&i| {
+            matches!(&tokens[i], Token::Keyword(kw) if kw == "ON")
+                && matches!(&tokens[i + 1], Token::Identifier(name) if name.eq_ignore_ascii_case("CONFLICT"))
+        })
+    }
+
+    /// Parse `[(columns)] DO NOTHING | DO UPDATE SET assignments` after ON CONFLICT
+    fn parse_on_conflict(&self, tokens: &[Token], position: &mut usize) -> ParseResult<OnConflict> {
+        let target = if matches!(tokens.get(*position), Some(Token::LeftParen)) {
+            *position += 1;
+            let columns = self.parse_identifier_list(tokens, position)?;
+            self.expect_token(tokens, *position, Token::RightParen)?;
+            *position += 1;
+            columns
+        } else {
+            vec![]
+        };
+
+        self.expect_word(tokens, position, "DO")?;
+        let action = if matches!(tokens.get(*position), Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("NOTHING")) {
+            *position += 1;
+            ConflictAction::DoNothing
+        } else {
+            self.expect_keyword(tokens, position, "UPDATE")?;
+            self.expect_keyword(tokens, position, "SET")?;
+            ConflictAction::DoUpdate(self.parse_assignments(tokens, position)?)
+        };
+
+        Ok(OnConflict { target, action })
+    }
+
     /// Parse UPDATE query
     fn parse_update(_tokens: &[Token]) -> ParseResult<UpdateQuery> {
         // TODO: Implement full UPDATE parsing
@@ -263,6 +311,41 @@ impl DmlParser {
                 *position += 1;
                 Ok(Expression::Literal(Literal::Boolean(false)))
             }
+            // `excluded.column` in ON CONFLICT DO UPDATE names the proposed row
+            Some(Token::Identifier(name)) if name.eq_ignore_ascii_case("EXCLUDED")
+                && matches!(tokens.get(*position + 1), Some(Token::Dot)) => {
+                *position += 2;
+                let column = self.parse_identifier(tokens, position)?;
+                Ok(Expression::Column(format!("excluded.{}", column)))
+            }
+            // `ARRAY[a, b]` becomes `array(a, b)`
+            Some(Token::Identifier(name)) if name.eq_ignore_ascii_case("ARRAY")
+                && matches!(tokens.get(*position + 1), Some(Token::LeftBracket)) => {
+                *position += 2;
+                let mut elements = Vec::new();
+                if matches!(tokens.get(*position), Some(Token::RightBracket)) {
+                    *position += 1;
+                } else {
+                    loop {
+                        elements.push(self.parse_expression(tokens, position)?);
+                        match tokens.get(*position) {
+                            Some(Token::Comma) => *position += 1,
+                            Some(Token::RightBracket) => {
+                                *position += 1;
+                                break;
+                            }
+                            _ => return Err(ParseError::SyntaxError {
+                                position: *position,
+                                message: "Expected ',' or ']' in ARRAY".to_string(),
+                            }),
+                        }
+                    }
+                }
+                Ok(Expression::Function(FunctionCall {
+                    name: "array".to_string(),
+                    arguments: elements,
+                }))
+            }
             _ => Err(ParseError::SyntaxError {
                 position: *position,
                 message: "Expected literal value".to_string(),
@@ -284,6 +367,20 @@ impl DmlParser {
         }
     }
 
+    /// Helper: Expect a word the tokenizer leaves as an identifier
+    fn expect_word(&self, tokens: &[Token], position: &mut usize, word: &str) -> ParseResult<()> {
+        match tokens.get(*position) {
+            Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word) => {
+                *position += 1;
+                Ok(())
+            }
+            _ => Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Expected '{}'", word),
+            }),
+        }
+    }
+
     /// Helper: Expect specific token
     fn expect_token(&self, tokens: &[Token], position: usize, expected: Token) -> ParseResult<()> {
         match tokens.get(position) {
@@ -0,0 +1,366 @@
+//! MERGE Statement Parser
+//!
+//! Parses `MERGE INTO target [AS t] USING source [AS s] ON condition`
+//! followed by one or more clauses:
+//! - `WHEN MATCHED [AND condition] THEN UPDATE SET ... | DELETE | DO NOTHING`
+//! - `WHEN NOT MATCHED [AND condition] THEN INSERT [(columns)] VALUES (...) | DO NOTHING`
+//!
+//! Conditions and values are full expressions over both tables' columns:
+//! comparisons joined by AND and OR, and arithmetic on columns and literals.
+
+use crate::query::parser::ast::*;
+use crate::query::parser::tokenizer::Token;
+
+/// MERGE statement parser
+pub struct MergeParser;
+
+impl MergeParser {
+    /// Parse MERGE statement from tokens
+    pub fn parse(tokens: &[Token]) -> ParseResult<MergeQuery> {
+        let mut position = 0;
+        Self::expect_keyword(tokens, &mut position, "MERGE")?;
+        Self::expect_keyword(tokens, &mut position, "INTO")?;
+        let target = Self::parse_identifier(tokens, &mut position)?;
+        let target_alias = Self::parse_alias(tokens, &mut position)?;
+
+        Self::expect_keyword(tokens, &mut position, "USING")?;
+        let source = Self::parse_identifier(tokens, &mut position)?;
+        let source_alias = Self::parse_alias(tokens, &mut position)?;
+
+        Self::expect_keyword(tokens, &mut position, "ON")?;
+        let condition = Self::parse_expression(tokens, &mut position)?;
+
+        let mut clauses = Vec::new();
+        while Self::match_word(tokens, &mut position, "WHEN") {
+            clauses.push(Self::parse_clause(tokens, &mut position)?);
+        }
+        if clauses.is_empty() {
+            return Err(ParseError::SyntaxError {
+                position,
+                message: "MERGE needs at least one WHEN clause".to_string(),
+            });
+        }
+
+        Self::match_token(tokens, &mut position, Token::Semicolon);
+        if position < tokens.len() {
+            return Err(ParseError::SyntaxError {
+                position,
+                message: format!("Unexpected {:?} after MERGE", tokens[position]),
+            });
+        }
+
+        Ok(MergeQuery { target, target_alias, source, source_alias, condition, clauses })
+    }
+
+    /// `[AS] alias`, if present
+    fn parse_alias(tokens: &[Token], position: &mut usize) -> ParseResult<Option<String>> {
+        if Self::match_keyword(tokens, position, "AS") {
+            return Self::parse_identifier(tokens, position).map(Some);
+        }
+        match tokens.get(*position) {
+            Some(Token::Identifier(alias)) => {
+                *position += 1;
+                Ok(Some(alias.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse one clause after its WHEN
+    fn parse_clause(tokens: &[Token], position: &mut usize) -> ParseResult<MergeClause> {
+        let matched = !Self::match_keyword(tokens, position, "NOT");
+        Self::expect_word(tokens, position, "MATCHED")?;
+        let condition = if Self::match_keyword(tokens, position, "AND") {
+            Some(Self::parse_expression(tokens, position)?)
+        } else {
+            None
+        };
+        Self::expect_word(tokens, position, "THEN")?;
+
+        let start = *position;
+        let action = if Self::match_keyword(tokens, position, "UPDATE") {
+            Self::expect_keyword(tokens, position, "SET")?;
+            MergeAction::Update(Self::parse_assignments(tokens, position)?)
+        } else if Self::match_keyword(tokens, position, "DELETE") {
+            MergeAction::Delete
+        } else if Self::match_keyword(tokens, position, "INSERT") {
+            let columns = if Self::match_token(tokens, position, Token::LeftParen) {
+                let columns = Self::parse_identifier_list(tokens, position)?;
+                Self::expect_token(tokens, position, Token::RightParen)?;
+                columns
+            } else {
+                Vec::new()
+            };
+            Self::expect_keyword(tokens, position, "VALUES")?;
+            Self::expect_token(tokens, position, Token::LeftParen)?;
+            let values = Self::parse_expression_list(tokens, position)?;
+            Self::expect_token(tokens, position, Token::RightParen)?;
+            if !columns.is_empty() && columns.len() != values.len() {
+                return Err(ParseError::SyntaxError {
+                    position: start,
+                    message: format!("INSERT lists {} columns but {} values", columns.len(), values.len()),
+                });
+            }
+            MergeAction::Insert { columns, values }
+        } else {
+            Self::expect_word(tokens, position, "DO")?;
+            Self::expect_word(tokens, position, "NOTHING")?;
+            MergeAction::DoNothing
+        };
+
+        let allowed = match &action {
+            MergeAction::Update(_) | MergeAction::Delete => matched,
+            MergeAction::Insert { .. } => !matched,
+            MergeAction::DoNothing => true,
+        };
+        if !allowed {
+            return Err(ParseError::SyntaxError {
+                position: start,
+                message: match matched {
+                    true => "WHEN MATCHED cannot INSERT".to_string(),
+                    false => "WHEN NOT MATCHED can only INSERT or DO NOTHING".to_string(),
+                },
+            });
+        }
+
+        Ok(MergeClause { matched, condition, action })
+    }
+
+    /// Parse `column = value, ...`
+    fn parse_assignments(tokens: &[Token], position: &mut usize) -> ParseResult<Vec<Assignment>> {
+        let mut assignments = Vec::new();
+        loop {
+            let column = Self::parse_identifier(tokens, position)?;
+            Self::expect_token(tokens, position, Token::Equals)?;
+            let value = Self::parse_expression(tokens, position)?;
+            assignments.push(Assignment { column, value });
+            if !Self::match_token(tokens, position, Token::Comma) {
+                return Ok(assignments);
+            }
+        }
+    }
+
+    fn parse_identifier_list(tokens: &[Token], position: &mut usize) -> ParseResult<Vec<String>> {
+        let mut identifiers = vec![Self::parse_identifier(tokens, position)?];
+        while Self::match_token(tokens, position, Token::Comma) {
+            identifiers.push(Self::parse_identifier(tokens, position)?);
+        }
+        Ok(identifiers)
+    }
+
+    fn parse_expression_list(tokens: &[Token], position: &mut usize) -> ParseResult<Vec<Expression>> {
+        let mut expressions = vec![Self::parse_expression(tokens, position)?];
+        while Self::match_token(tokens, position, Token::Comma) {
+            expressions.push(Self::parse_expression(tokens, position)?);
+        }
+        Ok(expressions)
+    }
+
+    /// Parse expression: OR binds loosest, then AND, comparisons, `+` and
+    /// `-`, then `*` and `/`
+    fn parse_expression(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let mut expression = Self::parse_conjunction(tokens, position)?;
+        while Self::match_keyword(tokens, position, "OR") {
+            let right = Self::parse_conjunction(tokens, position)?;
+            expression = Self::binary(expression, BinaryOperator::Or, right);
+        }
+        Ok(expression)
+    }
+
+    fn parse_conjunction(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let mut expression = Self::parse_comparison(tokens, position)?;
+        while Self::match_keyword(tokens, position, "AND") {
+            let right = Self::parse_comparison(tokens, position)?;
+            expression = Self::binary(expression, BinaryOperator::And, right);
+        }
+        Ok(expression)
+    }
+
+    fn parse_comparison(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let left = Self::parse_sum(tokens, position)?;
+        let operator = match tokens.get(*position) {
+            Some(Token::Equals) => BinaryOperator::Equal,
+            Some(Token::NotEquals) => BinaryOperator::NotEqual,
+            Some(Token::LessThan) => BinaryOperator::LessThan,
+            Some(Token::GreaterThan) => BinaryOperator::GreaterThan,
+            Some(Token::LessEqual) => BinaryOperator::LessEqual,
+            Some(Token::GreaterEqual) => BinaryOperator::GreaterEqual,
+            _ => return Ok(left),
+        };
+        *position += 1;
+        let right = Self::parse_sum(tokens, position)?;
+        Ok(Self::binary(left, operator, right))
+    }
+
+    fn parse_sum(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let mut expression = Self::parse_product(tokens, position)?;
+        loop {
+            let operator = match tokens.get(*position) {
+                Some(Token::Plus) => BinaryOperator::Plus,
+                Some(Token::Minus) => BinaryOperator::Minus,
+                _ => return Ok(expression),
+            };
+            *position += 1;
+            let right = Self::parse_product(tokens, position)?;
+            expression = Self::binary(expression, operator, right);
+        }
+    }
+
+    fn parse_product(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let mut expression = Self::parse_operand(tokens, position)?;
+        loop {
+            let operator = match tokens.get(*position) {
+                Some(Token::Asterisk) => BinaryOperator::Multiply,
+                Some(Token::Slash) => BinaryOperator::Divide,
+                _ => return Ok(expression),
+            };
+            *position += 1;
+            let right = Self::parse_operand(tokens, position)?;
+            expression = Self::binary(expression, operator, right);
+        }
+    }
+
+    /// Parse a literal, a column (`column` or `table.column`), a function
+    /// call or a parenthesized expression
+    fn parse_operand(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let token = tokens.get(*position).cloned();
+        *position += 1;
+        let literal = match token {
+            Some(Token::Integer(value)) => Literal::Integer(value),
+            Some(Token::Float(value)) => Literal::Float(value),
+            Some(Token::String(text)) => Literal::String(text),
+            Some(Token::Keyword(kw)) if kw == "NULL" => Literal::Null,
+            Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("TRUE") => Literal::Boolean(true),
+            Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("FALSE") => Literal::Boolean(false),
+            Some(Token::Minus) => match tokens.get(*position) {
+                Some(Token::Integer(value)) => {
+                    *position += 1;
+                    Literal::Integer(-value)
+                }
+                Some(Token::Float(value)) => {
+                    *position += 1;
+                    Literal::Float(-value)
+                }
+                _ => {
+                    // `-expr` as `0 - expr`
+                    let operand = Self::parse_operand(tokens, position)?;
+                    return Ok(Self::binary(Expression::Literal(Literal::Integer(0)), BinaryOperator::Minus, operand));
+                }
+            },
+            Some(Token::LeftParen) => {
+                let expression = Self::parse_expression(tokens, position)?;
+                Self::expect_token(tokens, position, Token::RightParen)?;
+                return Ok(expression);
+            }
+            Some(Token::Identifier(name)) => {
+                if Self::match_token(tokens, position, Token::LeftParen) {
+                    let arguments = if Self::match_token(tokens, position, Token::RightParen) {
+                        Vec::new()
+                    } else {
+                        let arguments = Self::parse_expression_list(tokens, position)?;
+                        Self::expect_token(tokens, position, Token::RightParen)?;
+                        arguments
+                    };
+                    return Ok(Expression::Function(FunctionCall { name, arguments }));
+                }
+                if Self::match_token(tokens, position, Token::Dot) {
+                    let column = Self::parse_identifier(tokens, position)?;
+                    return Ok(Expression::Column(format!("{}.{}", name, column)));
+                }
+                return Ok(Expression::Column(name));
+            }
+            _ => {
+                *position -= 1;
+                return Err(ParseError::SyntaxError {
+                    position: *position,
+                    message: "Expected expression".to_string(),
+                });
+            }
+        };
+        Ok(Expression::Literal(literal))
+    }
+
+    fn binary(left: Expression, operator: BinaryOperator, right: Expression) -> Expression {
+        Expression::BinaryOp(BinaryOp {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        })
+    }
+
+    fn parse_identifier(tokens: &[Token], position: &mut usize) -> ParseResult<String> {
+        match tokens.get(*position) {
+            Some(Token::Identifier(name)) => {
+                *position += 1;
+                Ok(name.clone())
+            }
+            _ => Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected identifier".to_string(),
+            }),
+        }
+    }
+
+    fn expect_keyword(tokens: &[Token], position: &mut usize, keyword: &str) -> ParseResult<()> {
+        if Self::match_keyword(tokens, position, keyword) {
+            Ok(())
+        } else {
+            Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Expected keyword '{}'", keyword),
+            })
+        }
+    }
+
+    fn match_keyword(tokens: &[Token], position: &mut usize, keyword: &str) -> bool {
+        match tokens.get(*position) {
+            Some(Token::Keyword(kw)) if kw == keyword => {
+                *position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Expect a word the tokenizer leaves as an identifier
+    fn expect_word(tokens: &[Token], position: &mut usize, word: &str) -> ParseResult<()> {
+        if Self::match_word(tokens, position, word) {
+            Ok(())
+        } else {
+            Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Expected '{}'", word),
+            })
+        }
+    }
+
+    fn match_word(tokens: &[Token], position: &mut usize, word: &str) -> bool {
+        match tokens.get(*position) {
+            Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word) => {
+                *position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_token(tokens: &[Token], position: &mut usize, expected: Token) -> ParseResult<()> {
+        if Self::match_token(tokens, position, expected.clone()) {
+            Ok(())
+        } else {
+            Err(ParseError::SyntaxError {
+                position: *position,
+                message: format!("Expected {:?}", expected),
+            })
+        }
+    }
+
+    fn match_token(tokens: &[Token], position: &mut usize, expected: Token) -> bool {
+        if tokens.get(*position) == Some(&expected) {
+            *position += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
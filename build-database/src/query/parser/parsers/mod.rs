@@ -6,8 +6,10 @@ pub mod select_parser;
 pub mod dml_parser;
 pub mod ddl_parser;
 pub mod vector_parser;
+pub mod merge_parser;
 
 pub use select_parser::*;
 pub use dml_parser::*;
 pub use ddl_parser::*;
 pub use vector_parser::*;
+pub use merge_parser::*;
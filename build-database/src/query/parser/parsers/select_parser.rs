@@ -60,13 +60,11 @@ impl SelectParser {
             if Self::match_token(tokens, position, Token::Operator("*".to_string())) {
                 select_list.push(SelectItem::Wildcard);
                 *position += 1;
-            } else if let Some(Token::Identifier(column)) = tokens.get(*position) {
-                // Parse column name
-                let column_name = column.clone();
-                *position += 1;
+            } else if tokens.get(*position).is_some() {
+                // Parse column name or expression
+                let expression = Self::parse_expression(tokens, position)?;
 
                 // Check for optional alias
-                let expression = Expression::Column(column_name.clone());
                 let alias = if Self::match_keyword(tokens, position, "AS") {
                     if let Some(Token::Identifier(alias_name)) = tokens.get(*position) {
                         let alias_str = alias_name.clone();
@@ -105,6 +103,16 @@ impl SelectParser {
         // Expect FROM keyword
         Self::expect_keyword(tokens, position, "FROM")?;
 
+        // `FROM unnest(...)` with no table
+        if Self::at_unnest(tokens, *position) {
+            return Ok(FromClause {
+                table: String::new(),
+                alias: None,
+                joins: Vec::new(),
+                unnest: Some(Self::parse_unnest(tokens, position)?),
+            });
+        }
+
         // Parse table name
         let table_name = if let Some(Token::Identifier(table)) = tokens.get(*position) {
             let name = table.clone();
@@ -129,13 +137,46 @@ impl SelectParser {
         // Parse JOIN clauses
         let joins = Self::parse_join_clauses(tokens, position)?;
 
+        // `, unnest(...)` over the rows so far
+        let unnest = if matches!(tokens.get(*position), Some(Token::Comma)) && Self::at_unnest(tokens, *position + 1) {
+            *position += 1;
+            Some(Self::parse_unnest(tokens, position)?)
+        } else {
+            None
+        };
+
         Ok(FromClause {
             table: table_name,
             alias,
             joins,
+            unnest,
         })
     }
 
+    fn at_unnest(tokens: &[Token], position: usize) -> bool {
+        matches!(
+            (tokens.get(position), tokens.get(position + 1)),
+            (Some(Token::Identifier(name)), Some(Token::LParen)) if name.eq_ignore_ascii_case("unnest")
+        )
+    }
+
+    /// `unnest(array) [AS] alias`; the alias defaults to `unnest`
+    fn parse_unnest(tokens: &[Token], position: &mut usize) -> ParseResult<UnnestClause> {
+        *position += 2;
+        let array = Self::parse_expression(tokens, position)?;
+        Self::expect_token(tokens, position, Token::RParen)?;
+
+        Self::match_keyword(tokens, position, "AS");
+        let alias = match tokens.get(*position) {
+            Some(Token::Identifier(alias)) => {
+                *position += 1;
+                alias.clone()
+            }
+            _ => "unnest".to_string(),
+        };
+        Ok(UnnestClause { array, alias })
+    }
+
     /// Parse JOIN clauses
     fn parse_join_clauses(tokens: &[Token], position: &mut usize) -> ParseResult<Vec<crate::query::parser::ast::JoinClause>> {
         let mut joins = Vec::new();
@@ -162,6 +203,8 @@ impl SelectParser {
                 } else {
                     Some(crate::query::parser::ast::JoinType::Full)
                 }
+            } else if Self::match_keyword(tokens, position, "ASOF") {
+                Some(crate::query::parser::ast::JoinType::AsOf)
             } else if Self::match_keyword(tokens, position, "JOIN") {
                 Some(crate::query::parser::ast::JoinType::Inner) // Default to INNER JOIN
             } else {
@@ -193,15 +236,30 @@ impl SelectParser {
                     None
                 };
 
+                // ASOF JOIN: MATCH_CONDITION, then an optional ON partition key
+                let match_condition = if matches!(join_type, crate::query::parser::ast::JoinType::AsOf) {
+                    Self::expect_keyword(tokens, position, "MATCH_CONDITION")?;
+                    Some(Self::parse_match_condition(tokens, position)?)
+                } else {
+                    None
+                };
+
                 // Parse ON condition
-                Self::expect_keyword(tokens, position, "ON")?;
-                let condition = Self::parse_expression(tokens, position)?;
+                let condition = if match_condition.is_some() && !Self::match_keyword(tokens, position, "ON") {
+                    Expression::Literal(Literal::Boolean(true))
+                } else {
+                    if match_condition.is_none() {
+                        Self::expect_keyword(tokens, position, "ON")?;
+                    }
+                    Self::parse_expression(tokens, position)?
+                };
 
                 joins.push(crate::query::parser::ast::JoinClause {
                     join_type,
                     table: table_name,
                     alias,
                     condition,
+                    match_condition,
                 });
             }
         }
@@ -209,6 +267,46 @@ impl SelectParser {
         Ok(joins)
     }
 
+    /// Parse an ASOF join's `(left.ts >= right.ts)`: two column references
+    /// compared with `>=`, `>`, `<=` or `<`
+    fn parse_match_condition(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        Self::expect_token(tokens, position, Token::LParen)?;
+        let left = Self::parse_column_reference(tokens, position)?;
+        let operator = match tokens.get(*position) {
+            Some(Token::Operator(op)) if matches!(op.as_str(), ">=" | ">" | "<=" | "<") => op.clone(),
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "MATCH_CONDITION must compare with >=, >, <= or <".to_string(),
+            }),
+        };
+        *position += 1;
+        let right = Self::parse_column_reference(tokens, position)?;
+        Self::expect_token(tokens, position, Token::RParen)?;
+
+        Ok(Expression::BinaryOp(BinaryOp {
+            left: Box::new(left),
+            operator: Self::string_to_binary_operator(&operator)?,
+            right: Box::new(right),
+        }))
+    }
+
+    /// Parse `column` or `table.column`
+    fn parse_column_reference(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let mut name = match tokens.get(*position) {
+            Some(Token::Identifier(name)) => name.clone(),
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected column name".to_string(),
+            }),
+        };
+        *position += 1;
+        if let (Some(Token::Dot), Some(Token::Identifier(column))) = (tokens.get(*position), tokens.get(*position + 1)) {
+            name = format!("{}.{}", name, column);
+            *position += 2;
+        }
+        Ok(Expression::Column(name))
+    }
+
     /// Parse WHERE clause
     fn parse_where_clause(tokens: &[Token], position: &mut usize) -> ParseResult<Option<Expression>> {
         if !Self::match_keyword(tokens, position, "WHERE") {
@@ -351,13 +449,91 @@ impl SelectParser {
         }
     }
 
-    /// Parse expression (simplified)
+    /// Parse expression (simplified): operands joined by `+` and `-`, each
+    /// optionally followed by AT TIME ZONE, which binds tighter
     fn parse_expression(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        let operand = Self::parse_operand(tokens, position)?;
+        let mut expression = Self::parse_at_time_zone(tokens, position, operand)?;
+        while let Some(Token::Operator(op)) = tokens.get(*position) {
+            if !matches!(op.as_str(), "+" | "-") {
+                break;
+            }
+            let operator = Self::string_to_binary_operator(op)?;
+            *position += 1;
+            let operand = Self::parse_operand(tokens, position)?;
+            let right = Self::parse_at_time_zone(tokens, position, operand)?;
+            expression = Expression::BinaryOp(BinaryOp {
+                left: Box::new(expression),
+                operator,
+                right: Box::new(right),
+            });
+        }
+        Ok(expression)
+    }
+
+    /// `expr AT TIME ZONE 'zone'` becomes `timezone('zone', expr)`
+    fn parse_at_time_zone(tokens: &[Token], position: &mut usize, operand: Expression) -> ParseResult<Expression> {
+        let start = *position;
+        if !(Self::match_word(tokens, position, "AT") && Self::match_word(tokens, position, "TIME") && Self::match_word(tokens, position, "ZONE")) {
+            *position = start;
+            return Ok(operand);
+        }
+        match tokens.get(*position) {
+            Some(Token::String(zone)) => {
+                let zone = zone.clone();
+                *position += 1;
+                Ok(Expression::Function(FunctionCall {
+                    name: "timezone".to_string(),
+                    arguments: vec![Expression::Literal(Literal::String(zone)), operand],
+                }))
+            }
+            _ => Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected time zone name after AT TIME ZONE".to_string(),
+            }),
+        }
+    }
+
+    /// Parse a column, literal, comparison or function call
+    fn parse_operand(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        match (tokens.get(*position), tokens.get(*position + 1)) {
+            (Some(Token::Identifier(word)), Some(Token::LBracket)) if word.eq_ignore_ascii_case("ARRAY") => {
+                let array = Self::parse_array_literal(tokens, position)?;
+                return Self::parse_comparison(tokens, position, array);
+            }
+            // `INTERVAL '1 day'` becomes `interval('1 day')`
+            (Some(Token::Identifier(word)), Some(Token::String(text))) if word.eq_ignore_ascii_case("INTERVAL") => {
+                let text = text.clone();
+                *position += 2;
+                return Ok(Expression::Function(FunctionCall {
+                    name: "interval".to_string(),
+                    arguments: vec![Expression::Literal(Literal::String(text))],
+                }));
+            }
+            (Some(Token::Identifier(word)), Some(Token::LParen)) if word.eq_ignore_ascii_case("EXTRACT") => {
+                let call = Self::parse_extract(tokens, position)?;
+                return Self::parse_comparison(tokens, position, call);
+            }
+            (Some(Token::String(text)), _) => {
+                let text = text.clone();
+                *position += 1;
+                return Self::parse_any_comparison(tokens, position, Expression::Literal(Literal::String(text)));
+            }
+            (Some(Token::Number(value)), _) => {
+                if let Some(literal) = Self::number_literal(value) {
+                    *position += 1;
+                    return Self::parse_any_comparison(tokens, position, Expression::Literal(literal));
+                }
+            }
+            _ => {}
+        }
+
         // Check for function calls first
         if let Some(Token::Identifier(func_name)) = tokens.get(*position) {
             if let Some(Token::LParen) = tokens.get(*position + 1) {
-                // This is a function call
-                return Self::parse_function_call(tokens, position);
+                // This is a function call, possibly compared with a value
+                let call = Self::parse_function_call(tokens, position)?;
+                return Self::parse_comparison(tokens, position, call);
             }
         }
 
@@ -365,43 +541,8 @@ impl SelectParser {
         if let Some(Token::Identifier(column)) = tokens.get(*position) {
             let column_name = column.clone();
             *position += 1;
-
-            // Check for operator and value
-            if let Some(Token::Operator(op)) = tokens.get(*position) {
-                if op == "=" || op == ">" || op == "<" || op == ">=" || op == "<=" || op == "!=" {
-                    let operator = op.clone();
-                    *position += 1;
-
-                    if let Some(Token::String(value)) = tokens.get(*position) {
-                        let string_value = value.clone();
-                        *position += 1;
-                        return Ok(Expression::BinaryOp(BinaryOp {
-                            left: Box::new(Expression::Column(column_name)),
-                            operator: Self::string_to_binary_operator(&operator)?,
-                            right: Box::new(Expression::Literal(Literal::String(string_value))),
-                        }));
-                    } else if let Some(Token::Number(value)) = tokens.get(*position) {
-                        let num_value = value.clone();
-                        *position += 1;
-                        // Try to parse as integer first, then float
-                        if let Ok(int_val) = num_value.parse::<i64>() {
-                            return Ok(Expression::BinaryOp(BinaryOp {
-                                left: Box::new(Expression::Column(column_name)),
-                                operator: Self::string_to_binary_operator(&operator)?,
-                                right: Box::new(Expression::Literal(Literal::Integer(int_val))),
-                            }));
-                        } else if let Ok(float_val) = num_value.parse::<f64>() {
-                            return Ok(Expression::BinaryOp(BinaryOp {
-                                left: Box::new(Expression::Column(column_name)),
-                                operator: Self::string_to_binary_operator(&operator)?,
-                                right: Box::new(Expression::Literal(Literal::Float(float_val))),
-                            }));
-                        }
-                    }
-                }
-            }
-
-            Ok(Expression::Column(column_name))
+            let column = Self::parse_subscript(tokens, position, Expression::Column(column_name))?;
+            Self::parse_comparison(tokens, position, column)
         } else {
             Err(ParseError::SyntaxError {
                 position: *position,
@@ -410,6 +551,124 @@ impl SelectParser {
         }
     }
 
+    /// `EXTRACT(field FROM value)` becomes `extract('field', value)`
+    fn parse_extract(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        *position += 2;
+        let field = match tokens.get(*position) {
+            Some(Token::Identifier(field)) | Some(Token::String(field)) => field.to_lowercase(),
+            _ => return Err(ParseError::SyntaxError {
+                position: *position,
+                message: "Expected field name in EXTRACT".to_string(),
+            }),
+        };
+        *position += 1;
+        Self::expect_keyword(tokens, position, "FROM")?;
+        let value = Self::parse_expression(tokens, position)?;
+        Self::expect_token(tokens, position, Token::RParen)?;
+        Ok(Expression::Function(FunctionCall {
+            name: "extract".to_string(),
+            arguments: vec![Expression::Literal(Literal::String(field)), value],
+        }))
+    }
+
+    /// `ARRAY[a, b]` becomes `array(a, b)`
+    fn parse_array_literal(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
+        *position += 2;
+        let mut elements = Vec::new();
+        if !Self::match_token(tokens, position, Token::RBracket) {
+            loop {
+                elements.push(Self::parse_expression(tokens, position)?);
+                if Self::match_token(tokens, position, Token::RBracket) {
+                    break;
+                }
+                Self::expect_token(tokens, position, Token::Comma)?;
+            }
+        }
+        Ok(Expression::Function(FunctionCall {
+            name: "array".to_string(),
+            arguments: elements,
+        }))
+    }
+
+    /// `value[n]` becomes `array_element(value, n)`, counting from 1
+    fn parse_subscript(tokens: &[Token], position: &mut usize, value: Expression) -> ParseResult<Expression> {
+        if !Self::match_token(tokens, position, Token::LBracket) {
+            return Ok(value);
+        }
+        let index = Self::parse_expression(tokens, position)?;
+        Self::expect_token(tokens, position, Token::RBracket)?;
+        Ok(Expression::Function(FunctionCall {
+            name: "array_element".to_string(),
+            arguments: vec![value, index],
+        }))
+    }
+
+    /// `literal = ANY(array)` when that follows a literal; otherwise the
+    /// literal alone
+    fn parse_any_comparison(tokens: &[Token], position: &mut usize, left: Expression) -> ParseResult<Expression> {
+        match (tokens.get(*position), tokens.get(*position + 1), tokens.get(*position + 2)) {
+            (Some(Token::Operator(op)), Some(Token::Identifier(word)), Some(Token::LParen))
+                if op == "=" && word.eq_ignore_ascii_case("ANY") => Self::parse_comparison(tokens, position, left),
+            _ => Ok(left),
+        }
+    }
+
+    /// Integer literal if it fits, else a float
+    fn number_literal(value: &str) -> Option<Literal> {
+        match (value.parse::<i64>(), value.parse::<f64>()) {
+            (Ok(int_val), _) => Some(Literal::Integer(int_val)),
+            (_, Ok(float_val)) => Some(Literal::Float(float_val)),
+            _ => None,
+        }
+    }
+
+    /// `left op right` when a comparison operator and a string, number or
+    /// `ARRAY[...]` follow `left`, or `=` and `ANY(array)`; otherwise `left`
+    /// alone
+    fn parse_comparison(tokens: &[Token], position: &mut usize, left: Expression) -> ParseResult<Expression> {
+        let operator = match tokens.get(*position) {
+            Some(Token::Operator(op)) if matches!(op.as_str(), "=" | ">" | "<" | ">=" | "<=" | "!=" | "@@" | "@>" | "&&") => op.clone(),
+            _ => return Ok(left),
+        };
+
+        let right = match (tokens.get(*position + 1), tokens.get(*position + 2)) {
+            (Some(Token::String(value)), _) => {
+                let literal = Literal::String(value.clone());
+                *position += 2;
+                Expression::Literal(literal)
+            }
+            // Try to parse as integer first, then float
+            (Some(Token::Number(value)), _) => match Self::number_literal(value) {
+                Some(literal) => {
+                    *position += 2;
+                    Expression::Literal(literal)
+                }
+                None => return Ok(left),
+            },
+            (Some(Token::Identifier(word)), Some(Token::LBracket)) if word.eq_ignore_ascii_case("ARRAY") => {
+                *position += 1;
+                Self::parse_array_literal(tokens, position)?
+            }
+            // `= ANY(array)` becomes `= any(array)`
+            (Some(Token::Identifier(word)), Some(Token::LParen)) if operator == "=" && word.eq_ignore_ascii_case("ANY") => {
+                *position += 3;
+                let array = Self::parse_expression(tokens, position)?;
+                Self::expect_token(tokens, position, Token::RParen)?;
+                Expression::Function(FunctionCall {
+                    name: "any".to_string(),
+                    arguments: vec![array],
+                })
+            }
+            _ => return Ok(left),
+        };
+
+        Ok(Expression::BinaryOp(BinaryOp {
+            left: Box::new(left),
+            operator: Self::string_to_binary_operator(&operator)?,
+            right: Box::new(right),
+        }))
+    }
+
     /// Parse function call (regular or window function)
     fn parse_function_call(tokens: &[Token], position: &mut usize) -> ParseResult<Expression> {
         // Get function name
@@ -430,8 +689,8 @@ impl SelectParser {
         // Parse arguments
         let mut arguments = Vec::new();
 
-        // Special handling for COUNT(*) - allow asterisk
-        if func_name.to_uppercase() == "COUNT" && let Some(Token::Asterisk) = tokens.get(*position) {
+        // Special handling for COUNT(*) and FINGERPRINT(*) - allow asterisk
+        if matches!(func_name.to_uppercase().as_str(), "COUNT" | "FINGERPRINT") && let Some(Token::Asterisk) = tokens.get(*position) {
             *position += 1;
             arguments.push(Expression::Asterisk);
         } else {
@@ -648,6 +907,19 @@ impl SelectParser {
         false
     }
 
+    /// Helper: Match a word tokenized as either a keyword or an identifier
+    fn match_word(tokens: &[Token], position: &mut usize, word: &str) -> bool {
+        let matched = match tokens.get(*position) {
+            Some(Token::Keyword(kw)) => kw == word,
+            Some(Token::Identifier(name)) => name.eq_ignore_ascii_case(word),
+            _ => false,
+        };
+        if matched {
+            *position += 1;
+        }
+        matched
+    }
+
     /// Helper: Expect specific token (consumes token, returns error if not found)
     fn expect_token(tokens: &[Token], position: &mut usize, expected: Token) -> ParseResult<()> {
         if let Some(token) = tokens.get(*position) {
@@ -682,6 +954,11 @@ impl SelectParser {
             "<" => Ok(BinaryOperator::LessThan),
             ">=" => Ok(BinaryOperator::GreaterEqual),
             "<=" => Ok(BinaryOperator::LessEqual),
+            "@@" => Ok(BinaryOperator::TextMatch),
+            "@>" => Ok(BinaryOperator::ArrayContains),
+            "&&" => Ok(BinaryOperator::ArrayOverlaps),
+            "+" => Ok(BinaryOperator::Plus),
+            "-" => Ok(BinaryOperator::Minus),
             _ => Err(ParseError::SyntaxError {
                 position: 0,
                 message: format!("Unsupported operator: {}", op),
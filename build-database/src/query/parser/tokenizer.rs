@@ -29,6 +29,11 @@ pub enum Token {
     Dot,
     Semicolon,
     Bang,
+    TextMatch,
+    LeftBracket,
+    RightBracket,
+    Contains,
+    Overlaps,
 }
 
 /// SQL Tokenizer with AI-powered query hints
@@ -48,7 +53,9 @@ impl Tokenizer {
             "DELETE", "CREATE", "TABLE", "DROP", "IF", "EXISTS", "PRIMARY", "KEY",
             "FOREIGN", "REFERENCES", "UNIQUE", "NULL", "NOT", "AND", "OR", "ORDER",
             "BY", "GROUP", "HAVING", "LIMIT", "OFFSET", "JOIN", "INNER", "LEFT",
-            "RIGHT", "FULL", "ON", "AS", "ASC", "DESC"
+            "RIGHT", "FULL", "ON", "AS", "ASC", "DESC", "USING", "MATERIALIZED",
+            "VIEW", "REFRESH", "CONCURRENTLY", "ALTER",
+            "EXPLAIN", "ASOF", "MATCH_CONDITION", "MERGE"
         ] {
             keywords.insert(kw.to_string());
         }
@@ -151,6 +158,39 @@ impl Tokenizer {
                     self.advance();
                     tokens.push(Token::Semicolon);
                 }
+                Some('@') => {
+                    self.advance();
+                    match self.peek() {
+                        Some('@') => tokens.push(Token::TextMatch),
+                        Some('>') => tokens.push(Token::Contains),
+                        _ => {
+                            return Err(ParseError::SyntaxError {
+                                position: self.position,
+                                message: "Expected '@@' or '@>'".to_string(),
+                            });
+                        }
+                    }
+                    self.advance();
+                }
+                Some('&') => {
+                    self.advance();
+                    if self.peek() != Some('&') {
+                        return Err(ParseError::SyntaxError {
+                            position: self.position,
+                            message: "Expected '&&'".to_string(),
+                        });
+                    }
+                    self.advance();
+                    tokens.push(Token::Overlaps);
+                }
+                Some('[') => {
+                    self.advance();
+                    tokens.push(Token::LeftBracket);
+                }
+                Some(']') => {
+                    self.advance();
+                    tokens.push(Token::RightBracket);
+                }
                 Some(ch) => {
                     return Err(ParseError::SyntaxError {
                         position: self.position,
@@ -7,6 +7,7 @@
 //! - Correlation information
 
 use crate::core::*;
+use crate::catalog::{CatalogChange, CatalogSubscriber};
 use std::collections::HashMap;
 
 /// Statistics manager for query optimization
@@ -142,6 +143,13 @@ impl StatisticsManager {
         self.last_updated.insert("index".to_string(), timestamp);
     }
 
+    /// Forget everything known about a table, its columns and its indexes
+    pub fn invalidate_table(&mut self, table_name: &str) {
+        self.table_stats.remove(table_name);
+        self.column_stats.retain(|(table, _), _| table != table_name);
+        self.index_stats.retain(|_, index| index.table_name != table_name);
+    }
+
     /// Estimate selectivity for a predicate
     pub fn estimate_selectivity(&self, table_name: &str, column_name: &str, predicate: &Predicate) -> f64 {
         if let Some(col_stats) = self.get_column_stats(table_name, column_name) {
@@ -247,6 +255,14 @@ impl StatisticsManager {
     }
 }
 
+/// Statistics describe one version of a table's schema; any DDL on the
+/// table drops them until they are gathered again
+impl CatalogSubscriber for parking_lot::RwLock<StatisticsManager> {
+    fn on_catalog_change(&self, change: &CatalogChange) {
+        self.write().invalidate_table(&change.object_name);
+    }
+}
+
 /// Predicate types for selectivity estimation
 #[derive(Debug, Clone)]
 pub enum Predicate {
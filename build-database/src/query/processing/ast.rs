@@ -7,6 +7,7 @@
 
 use std::collections::HashMap;
 use crate::core::errors::{AuroraResult, AuroraError};
+use super::hints::PlanHint;
 
 /// Complete SQL Statement AST
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +46,8 @@ pub struct SelectStatement {
     pub offset: Option<OffsetClause>,
     pub union: Option<Box<SelectStatement>>,
     pub union_all: bool,
+    /// Optimizer hints from a leading `/*+ ... */` comment
+    pub hints: Vec<PlanHint>,
 }
 
 /// INSERT statement AST
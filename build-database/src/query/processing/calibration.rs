@@ -0,0 +1,279 @@
+//! Cost Model Calibration
+//!
+//! Planner costs are relative: one sequential page read costs 1.0 and every
+//! other operation is priced against it. The built-in constants are guesses;
+//! calibration measures the real ratios on this instance with micro-benchmarks
+//! and stores them in a file the `CostModel` is built from.
+//!
+//! - sequential scan: pages read in file order
+//! - random fetch: pages read at random offsets
+//! - tuple processing: rows decoded and visited
+//! - sort: comparisons made while sorting
+//! - hash: rows inserted into and probed in a hash table
+//!
+//! Calibration can be re-run at any time (after a hardware or storage change);
+//! each run replaces the stored constants.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use crate::core::errors::{AuroraResult, AuroraError};
+
+/// File the calibrated constants are stored in, under the data directory
+pub const CALIBRATION_FILE: &str = "cost_calibration.json";
+
+/// Page size the I/O benchmarks read in
+const PAGE_SIZE: usize = 8192;
+
+/// Time per unit of work, in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostMeasurements {
+    pub seq_page_ns: f64,
+    pub random_page_ns: f64,
+    pub tuple_ns: f64,
+    pub sort_comparison_ns: f64,
+    pub hash_row_ns: f64,
+}
+
+/// Source of cost measurements
+pub trait CostBenchmark {
+    fn measure(&self) -> AuroraResult<CostMeasurements>;
+}
+
+/// Micro-benchmarks run against a scratch file on the instance's own storage
+#[derive(Debug, Clone)]
+pub struct MicroBenchmarks {
+    scratch_dir: PathBuf,
+    pages: usize,
+    rows: usize,
+}
+
+impl MicroBenchmarks {
+    /// Benchmarks writing their scratch file under `scratch_dir`
+    pub fn new(scratch_dir: impl Into<PathBuf>) -> Self {
+        Self { scratch_dir: scratch_dir.into(), pages: 4096, rows: 200_000 }
+    }
+
+    /// Change the scratch file size and the row count of the CPU benchmarks
+    pub fn with_size(mut self, pages: usize, rows: usize) -> Self {
+        self.pages = pages.max(1);
+        self.rows = rows.max(2);
+        self
+    }
+
+    fn measure_io(&self, path: &Path) -> AuroraResult<(f64, f64)> {
+        let mut page = vec![0u8; PAGE_SIZE];
+        let mut file = fs::File::create(path)?;
+        for i in 0..self.pages {
+            page[0] = i as u8;
+            file.write_all(&page)?;
+        }
+        file.sync_all()?;
+        drop(file);
+
+        let mut file = fs::File::open(path)?;
+        let start = Instant::now();
+        for _ in 0..self.pages {
+            file.read_exact(&mut page)?;
+        }
+        let seq_ns = start.elapsed().as_nanos() as f64 / self.pages as f64;
+
+        let mut rng = rand::thread_rng();
+        let start = Instant::now();
+        for _ in 0..self.pages {
+            let offset = rng.gen_range(0..self.pages) * PAGE_SIZE;
+            file.seek(SeekFrom::Start(offset as u64))?;
+            file.read_exact(&mut page)?;
+        }
+        let random_ns = start.elapsed().as_nanos() as f64 / self.pages as f64;
+
+        Ok((seq_ns, random_ns))
+    }
+
+    fn measure_cpu(&self) -> (f64, f64, f64) {
+        let mut rng = rand::thread_rng();
+        let encoded: Vec<u8> = (0..self.rows).flat_map(|_| rng.gen::<u64>().to_le_bytes()).collect();
+
+        let start = Instant::now();
+        let values: Vec<u64> = encoded.chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        let checksum = values.iter().fold(0u64, |acc, v| acc.wrapping_add(*v));
+        let tuple_ns = start.elapsed().as_nanos() as f64 / self.rows as f64;
+
+        let mut sorted = values.clone();
+        let start = Instant::now();
+        sorted.sort_unstable();
+        let comparisons = self.rows as f64 * (self.rows as f64).log2();
+        let sort_ns = start.elapsed().as_nanos() as f64 / comparisons;
+
+        let start = Instant::now();
+        let table: HashMap<u64, usize> = values.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let hits = values.iter().filter(|v| table.contains_key(v)).count();
+        let hash_ns = start.elapsed().as_nanos() as f64 / self.rows as f64;
+
+        // Keep the work observable so none of it is optimized away
+        debug!("Calibration checksum {} over {} sorted values, {} hash hits", checksum, sorted.len(), hits);
+
+        (tuple_ns, sort_ns, hash_ns)
+    }
+}
+
+impl CostBenchmark for MicroBenchmarks {
+    fn measure(&self) -> AuroraResult<CostMeasurements> {
+        fs::create_dir_all(&self.scratch_dir)?;
+        let path = self.scratch_dir.join("cost_calibration.scratch");
+        let io = self.measure_io(&path);
+        let _ = fs::remove_file(&path);
+        let (seq_page_ns, random_page_ns) = io?;
+        let (tuple_ns, sort_comparison_ns, hash_row_ns) = self.measure_cpu();
+
+        Ok(CostMeasurements { seq_page_ns, random_page_ns, tuple_ns, sort_comparison_ns, hash_row_ns })
+    }
+}
+
+/// Cost constants derived from measurements, relative to one sequential page read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibratedCosts {
+    pub seq_page_cost: f64,
+    pub random_page_cost: f64,
+    pub cpu_tuple_cost: f64,
+    /// Cost of one comparison or operator evaluation
+    pub cpu_operator_cost: f64,
+    /// Cost of hashing and probing one row
+    pub hash_row_cost: f64,
+    /// Raw timings the constants were derived from
+    pub measurements: CostMeasurements,
+    /// Seconds since the Unix epoch
+    pub calibrated_at: u64,
+}
+
+impl CalibratedCosts {
+    /// Derive constants from measured timings
+    pub fn from_measurements(measurements: CostMeasurements) -> AuroraResult<Self> {
+        let m = &measurements;
+        for (name, value) in [
+            ("sequential page", m.seq_page_ns),
+            ("random page", m.random_page_ns),
+            ("tuple", m.tuple_ns),
+            ("sort comparison", m.sort_comparison_ns),
+            ("hash row", m.hash_row_ns),
+        ] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(AuroraError::Plan(format!("invalid {} timing {} ns", name, value)));
+            }
+        }
+
+        let relative = |ns: f64| ns / m.seq_page_ns;
+        Ok(Self {
+            seq_page_cost: 1.0,
+            // A page cache can make random reads look as cheap as sequential
+            // ones, but never cheaper
+            random_page_cost: relative(m.random_page_ns).max(1.0),
+            cpu_tuple_cost: relative(m.tuple_ns),
+            cpu_operator_cost: relative(m.sort_comparison_ns),
+            hash_row_cost: relative(m.hash_row_ns),
+            calibrated_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            measurements,
+        })
+    }
+
+    /// Load constants stored by a previous run; `None` if never calibrated
+    pub fn load(path: &Path) -> AuroraResult<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Store the constants, replacing any earlier calibration
+    pub fn save(&self, path: &Path) -> AuroraResult<()> {
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Measure costs with `benchmark`, derive constants and store them at `path`
+pub fn calibrate(benchmark: &dyn CostBenchmark, path: &Path) -> AuroraResult<CalibratedCosts> {
+    let costs = CalibratedCosts::from_measurements(benchmark.measure()?)?;
+    costs.save(path)?;
+    info!("Calibrated cost model: random_page_cost={:.2}, cpu_tuple_cost={:.5}, cpu_operator_cost={:.5}, hash_row_cost={:.5}",
+          costs.random_page_cost, costs.cpu_tuple_cost, costs.cpu_operator_cost, costs.hash_row_cost);
+    Ok(costs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Injected(CostMeasurements);
+
+    impl CostBenchmark for Injected {
+        fn measure(&self) -> AuroraResult<CostMeasurements> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn measurements(random_page_ns: f64) -> CostMeasurements {
+        CostMeasurements {
+            seq_page_ns: 2_000.0,
+            random_page_ns,
+            tuple_ns: 20.0,
+            sort_comparison_ns: 5.0,
+            hash_row_ns: 40.0,
+        }
+    }
+
+    #[test]
+    fn test_constants_reflect_measured_ratios() {
+        let costs = CalibratedCosts::from_measurements(measurements(50_000.0)).unwrap();
+        assert_eq!(costs.seq_page_cost, 1.0);
+        assert_eq!(costs.random_page_cost, 25.0);
+        assert_eq!(costs.cpu_tuple_cost, 0.01);
+        assert_eq!(costs.cpu_operator_cost, 0.0025);
+        assert_eq!(costs.hash_row_cost, 0.02);
+
+        // Cached random reads are priced like sequential ones, not below
+        let costs = CalibratedCosts::from_measurements(measurements(1_000.0)).unwrap();
+        assert_eq!(costs.random_page_cost, 1.0);
+
+        assert!(CalibratedCosts::from_measurements(measurements(0.0)).is_err());
+        assert!(CalibratedCosts::from_measurements(measurements(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn test_recalibration_replaces_stored_constants() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CALIBRATION_FILE);
+        assert!(CalibratedCosts::load(&path).unwrap().is_none());
+
+        let first = calibrate(&Injected(measurements(8_000.0)), &path).unwrap();
+        assert_eq!(CalibratedCosts::load(&path).unwrap(), Some(first));
+
+        let second = calibrate(&Injected(measurements(80_000.0)), &path).unwrap();
+        let stored = CalibratedCosts::load(&path).unwrap().unwrap();
+        assert_eq!(stored, second);
+        assert_eq!(stored.random_page_cost, 40.0);
+    }
+
+    #[test]
+    fn test_micro_benchmarks_measure_every_operation() {
+        let dir = tempfile::tempdir().unwrap();
+        let measured = MicroBenchmarks::new(dir.path()).with_size(64, 10_000).measure().unwrap();
+        let costs = CalibratedCosts::from_measurements(measured).unwrap();
+        assert!(costs.random_page_cost >= 1.0);
+        assert!(costs.cpu_tuple_cost > 0.0 && costs.hash_row_cost > 0.0);
+        assert!(!dir.path().join("cost_calibration.scratch").exists());
+    }
+}
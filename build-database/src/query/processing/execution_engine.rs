@@ -10,9 +10,11 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
+use tracing::info;
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::plan::*;
 use super::ast::*;
+use super::parallelism::{WorkerPool, WorkerReservation};
 use super::simple_executor::SimpleQueryExecutor;
 
 /// Trait for execution plan executors
@@ -41,6 +43,9 @@ pub struct ExecutionEngine {
     /// Parallel execution scheduler
     parallel_scheduler: ParallelScheduler,
 
+    /// Workers parallel plans reserve from
+    worker_pool: Arc<WorkerPool>,
+
     /// Simple executor for basic queries (optional)
     simple_executor: Option<Arc<dyn ExecutionPlanExecutor + Send + Sync>>,
 }
@@ -80,6 +85,10 @@ pub struct QueryExecutionStats {
     pub network_calls: u32,
     pub cache_hits: u64,
     pub cache_misses: u64,
+    /// Workers the plan asked for; 1 for serial plans
+    pub workers_planned: u32,
+    /// Workers the query actually ran with; 1 when it ran serially
+    pub workers_launched: u32,
 }
 
 /// Operator execution statistics
@@ -207,6 +216,7 @@ impl ExecutionEngine {
             adaptive_controller: AdaptiveExecutionController::new(),
             memory_manager: MemoryManager::new(1024),
             parallel_scheduler: ParallelScheduler::new(4),
+            worker_pool: WorkerPool::new(4),
             simple_executor: Some(executor),
         })
     }
@@ -258,16 +268,29 @@ impl ExecutionEngine {
                 active_tasks: 0,
                 work_queue: VecDeque::new(),
             },
+            worker_pool: WorkerPool::new(4),
             simple_executor: None,
             },
         }
     }
 
+    /// Share a worker pool with other engines on this node
+    pub fn set_worker_pool(&mut self, worker_pool: Arc<WorkerPool>) {
+        self.worker_pool = worker_pool;
+    }
+
     /// Execute a query plan
-    pub async fn execute_plan(&self, plan: QueryPlan, context: ExecutionContext) -> AuroraResult<ExecutionResult> {
+    pub async fn execute_plan(&self, plan: QueryPlan, mut context: ExecutionContext) -> AuroraResult<ExecutionResult> {
+        let workers_planned = plan.planned_workers();
+        let reservation = self.reserve_workers(workers_planned, &mut context);
+        let workers_launched = reservation.as_ref().map_or(1, WorkerReservation::workers);
+
         // Use simple executor if available (for basic functionality)
         if let Some(ref executor) = self.simple_executor {
-            return executor.execute_plan(&plan, &context).await;
+            let mut result = executor.execute_plan(&plan, &context).await?;
+            result.execution_stats.workers_planned = workers_planned;
+            result.execution_stats.workers_launched = workers_launched;
+            return Ok(result);
         }
 
         let start_time = std::time::Instant::now();
@@ -289,7 +312,10 @@ impl ExecutionEngine {
         };
 
         // Calculate final statistics
-        let execution_stats = self.calculate_execution_stats(&execution_ctx, start_time).await?;
+        let mut execution_stats = self.calculate_execution_stats(&execution_ctx, start_time).await?;
+        execution_stats.workers_planned = workers_planned;
+        execution_stats.workers_launched = workers_launched;
+        drop(reservation);
 
         Ok(ExecutionResult {
             query_id: execution_ctx.query_id.clone(),
@@ -300,6 +326,31 @@ impl ExecutionEngine {
         })
     }
 
+    /// Reserve workers for a parallel plan. When fewer than two are free the
+    /// query degrades to serial execution rather than failing.
+    fn reserve_workers(&self, workers_planned: u32, context: &mut ExecutionContext) -> Option<WorkerReservation> {
+        let requested = workers_planned.min(context.max_parallel_workers);
+        if requested <= 1 {
+            if context.execution_mode == ExecutionMode::Parallel {
+                context.execution_mode = ExecutionMode::Sequential;
+            }
+            return None;
+        }
+
+        let reservation = self.worker_pool.reserve(requested);
+        if reservation.workers() < 2 {
+            info!(
+                "Query {} planned {} workers but {} are available; running serially",
+                context.query_id, workers_planned, reservation.workers()
+            );
+            context.execution_mode = ExecutionMode::Sequential;
+            return None;
+        }
+
+        context.max_parallel_workers = reservation.workers();
+        Some(reservation)
+    }
+
     /// Create operator tree from plan
     async fn create_operator_tree(&self, node: &PlanNode, context: &ExecutionContext) -> AuroraResult<Box<dyn ExecutionOperator>> {
         match node {
@@ -456,6 +507,8 @@ impl ExecutionEngine {
             network_calls: 0, // Would be tracked
             cache_hits: 0, // Would be tracked
             cache_misses: 0, // Would be tracked
+            workers_planned: 1,
+            workers_launched: 1,
         })
     }
 
@@ -483,6 +536,8 @@ impl ExecutionEngine {
                 network_calls: 0,
                 cache_hits: 0,
                 cache_misses: 0,
+                workers_planned: 1,
+                workers_launched: 1,
             },
             execution_plan: QueryPlan {
                 root: PlanNode::SeqScan(SeqScanNode {
@@ -1039,6 +1094,8 @@ mod tests {
             network_calls: 2,
             cache_hits: 40,
             cache_misses: 10,
+            workers_planned: 4,
+            workers_launched: 2,
         };
 
         assert_eq!(stats.query_id, "query_123");
@@ -1047,6 +1104,39 @@ mod tests {
         assert_eq!(stats.memory_peak_mb, 25.5);
     }
 
+    #[test]
+    fn test_parallel_plan_degrades_to_serial_without_workers() {
+        let context = || ExecutionContext {
+            query_id: "q".to_string(),
+            user_id: "test".to_string(),
+            session_id: "test".to_string(),
+            start_time: std::time::Instant::now(),
+            timeout: None,
+            memory_limit_mb: 1024,
+            max_parallel_workers: 4,
+            execution_mode: ExecutionMode::Parallel,
+            parameters: HashMap::new(),
+            transaction_id: None,
+        };
+        let mut engine = ExecutionEngine::new();
+        let pool = WorkerPool::new(5);
+        engine.set_worker_pool(Arc::clone(&pool));
+
+        let mut first = context();
+        let reservation = engine.reserve_workers(4, &mut first).expect("workers are free");
+        assert_eq!(reservation.workers(), 4);
+        assert_eq!(first.execution_mode, ExecutionMode::Parallel);
+
+        // One worker left: not enough to parallelize, so run serially
+        let mut second = context();
+        assert!(engine.reserve_workers(4, &mut second).is_none());
+        assert_eq!(second.execution_mode, ExecutionMode::Sequential);
+
+        drop(reservation);
+        let mut third = context();
+        assert_eq!(engine.reserve_workers(4, &mut third).map(|r| r.workers()), Some(4));
+    }
+
     #[test]
     fn test_operator_execution_stats() {
         let stats = OperatorExecutionStats {
@@ -1239,6 +1329,8 @@ mod tests {
                 network_calls: 1,
                 cache_hits: 12,
                 cache_misses: 3,
+                workers_planned: 1,
+                workers_launched: 1,
             },
             execution_plan: QueryPlan {
                 root: PlanNode::SeqScan(SeqScanNode {
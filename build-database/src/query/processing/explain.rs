@@ -0,0 +1,217 @@
+//! EXPLAIN Output for Query Plans
+//!
+//! Renders a `QueryPlan` as an indented operator tree with each node's
+//! details underneath. With ANALYZE, figures measured during execution are
+//! printed next to the estimates they check, so index parameters can be tuned
+//! against what the search actually did.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+use super::plan::*;
+
+/// Figures measured while executing one plan node
+#[derive(Debug, Clone, Default)]
+pub struct OperatorActuals {
+    pub rows: u64,
+    pub wall_time: Duration,
+    /// Vectors whose distance was computed, for vector searches
+    pub candidates_examined: Option<u64>,
+}
+
+/// Figures collected for EXPLAIN ANALYZE, keyed by operator id
+#[derive(Debug, Clone, Default)]
+pub struct ExplainAnalyze {
+    pub operators: HashMap<String, OperatorActuals>,
+    pub total_time: Duration,
+    /// Workers the query ran with, for parallel plans
+    pub workers_launched: Option<u32>,
+}
+
+/// Operator id the execution engine reports statistics under for `node`
+pub fn operator_id(node: &PlanNode) -> Option<String> {
+    match node {
+        PlanNode::SeqScan(node) => Some(format!("seq_scan_{}", node.table_name)),
+        PlanNode::IndexScan(node) => Some(format!("index_scan_{}", node.table_name)),
+        PlanNode::VectorSearch(node) => Some(format!("vector_search_{}", node.table_name)),
+        _ => None,
+    }
+}
+
+/// Render `plan` as EXPLAIN text, or as EXPLAIN ANALYZE text when `analyze` is given
+pub fn explain_plan(plan: &QueryPlan, analyze: Option<&ExplainAnalyze>) -> String {
+    let mut out = String::new();
+    render_node(&mut out, &plan.root, 0, analyze);
+    let _ = writeln!(out, "Total Cost: {:.2}  Rows: {}", plan.estimated_cost, plan.estimated_rows);
+    if plan.planned_workers() > 1 {
+        match analyze.and_then(|analyze| analyze.workers_launched) {
+            Some(launched) => {
+                let _ = writeln!(out, "Workers Planned: {}  Workers Launched: {}", plan.planned_workers(), launched);
+            }
+            None => {
+                let _ = writeln!(out, "Workers Planned: {}", plan.planned_workers());
+            }
+        }
+    }
+    if let Some(analyze) = analyze {
+        let _ = writeln!(out, "Execution Time: {:.3} ms", millis(analyze.total_time));
+    }
+    out
+}
+
+fn render_node(out: &mut String, node: &PlanNode, depth: usize, analyze: Option<&ExplainAnalyze>) {
+    let indent = "  ".repeat(depth);
+    let arrow = if depth == 0 { "" } else { "-> " };
+    let _ = writeln!(out, "{}{}{}", indent, arrow, node);
+
+    let detail = format!("{}     ", indent);
+    let actuals = analyze.and_then(|analyze| {
+        operator_id(node).and_then(|id| analyze.operators.get(&id))
+    });
+
+    if let PlanNode::VectorSearch(search) = node {
+        render_vector_search(out, &detail, search, actuals);
+    }
+
+    if let Some(actuals) = actuals {
+        let _ = writeln!(out, "{}Actual Rows: {}  Actual Time: {:.3} ms", detail, actuals.rows, millis(actuals.wall_time));
+    }
+
+    for child in children(node) {
+        render_node(out, child, depth + 1, analyze);
+    }
+}
+
+fn render_vector_search(out: &mut String, detail: &str, search: &VectorSearchNode, actuals: Option<&OperatorActuals>) {
+    let _ = writeln!(out, "{}Index: {}", detail, search.index_strategy);
+    if let Some(index_name) = &search.index_name {
+        let _ = writeln!(out, "{}Index Name: {}", detail, index_name);
+    }
+    let _ = writeln!(out, "{}Metric: {:?}  k: {}", detail, search.metric, search.limit);
+
+    if let Some(condition) = &search.filter_condition {
+        let strategy = match search.filter_strategy {
+            Some(VectorFilterStrategy::PreFilter) => "pre-filter",
+            Some(VectorFilterStrategy::PostFilter) | None => "post-filter",
+        };
+        let _ = writeln!(out, "{}Filter: {} ({})", detail, condition, strategy);
+    }
+
+    let _ = writeln!(out, "{}Estimated Candidates: {} of {}", detail, search.estimated_candidates(), search.table_rows);
+    let _ = writeln!(out, "{}Estimated Recall: {:.3}", detail, search.estimated_recall());
+
+    if let Some(candidates) = actuals.and_then(|actuals| actuals.candidates_examined) {
+        let _ = writeln!(out, "{}Actual Candidates: {}", detail, candidates);
+    }
+}
+
+fn children(node: &PlanNode) -> Vec<&PlanNode> {
+    match node {
+        PlanNode::Filter(node) => vec![&node.input],
+        PlanNode::Projection(node) => vec![&node.input],
+        PlanNode::Sort(node) => vec![&node.input],
+        PlanNode::Limit(node) => vec![&node.input],
+        PlanNode::Aggregate(node) => vec![&node.input],
+        PlanNode::Join(node) => vec![&node.left, &node.right],
+        PlanNode::NestedLoopJoin(node) => vec![&node.left, &node.right],
+        PlanNode::HashJoin(node) => vec![&node.left, &node.right],
+        PlanNode::MergeJoin(node) => vec![&node.left, &node.right],
+        PlanNode::Union(node) => vec![&node.left, &node.right],
+        _ => vec![],
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ast::*;
+
+    fn vector_plan(index_strategy: VectorIndexStrategy) -> QueryPlan {
+        QueryPlan {
+            root: PlanNode::Limit(LimitNode {
+                input: Box::new(PlanNode::VectorSearch(VectorSearchNode {
+                    table_name: "products".to_string(),
+                    vector_column: "embedding".to_string(),
+                    query_vector: vec![0.1, 0.2, 0.3],
+                    metric: VectorMetric::Cosine,
+                    limit: 10,
+                    filter_condition: Some(Expression::Column("in_stock".to_string())),
+                    index_name: Some("products_embedding_vector_idx".to_string()),
+                    index_strategy,
+                    filter_strategy: Some(VectorFilterStrategy::choose(0.05)),
+                    table_rows: 1_000_000,
+                    estimated_rows: 10,
+                    cost: 40.0,
+                })),
+                limit: 10,
+                offset: 0,
+                estimated_rows: 10,
+                cost: 40.1,
+            }),
+            estimated_cost: 40.1,
+            estimated_rows: 10,
+            execution_mode: ExecutionMode::Sequential,
+            optimization_hints: vec![],
+            statistics: PlanStatistics::default(),
+        }
+    }
+
+    #[test]
+    fn test_explain_vector_search_shows_index_and_parameters() {
+        let output = explain_plan(&vector_plan(VectorIndexStrategy::Hnsw { ef_search: 64 }), None);
+
+        assert!(output.contains("-> VectorSearch(products.embedding)"));
+        assert!(output.contains("Index: HNSW (ef_search=64)"));
+        assert!(output.contains("(pre-filter)"));
+        assert!(output.contains("Estimated Candidates: 1280 of 1000000"));
+        assert!(output.contains("Estimated Recall: 0.999"));
+        assert!(!output.contains("Actual"));
+
+        let output = explain_plan(&vector_plan(VectorIndexStrategy::IvfPq { nprobe: 16, nlist: 1024 }), None);
+        assert!(output.contains("Index: IVF-PQ (nprobe=16, nlist=1024)"));
+        assert!(output.contains("Estimated Candidates: 15625 of 1000000"));
+        assert!(output.contains("Estimated Recall: 0.777"));
+    }
+
+    #[test]
+    fn test_explain_analyze_shows_actuals() {
+        let plan = vector_plan(VectorIndexStrategy::Flat);
+        let analyze = ExplainAnalyze {
+            operators: HashMap::from([("vector_search_products".to_string(), OperatorActuals {
+                rows: 10,
+                wall_time: Duration::from_micros(2500),
+                candidates_examined: Some(48_211),
+            })]),
+            total_time: Duration::from_millis(3),
+            workers_launched: None,
+        };
+
+        let output = explain_plan(&plan, Some(&analyze));
+        assert!(output.contains("Index: flat"));
+        assert!(output.contains("Estimated Recall: 1.000"));
+        assert!(output.contains("Actual Candidates: 48211"));
+        assert!(output.contains("Actual Rows: 10  Actual Time: 2.500 ms"));
+        assert!(output.contains("Execution Time: 3.000 ms"));
+    }
+
+    #[test]
+    fn test_explain_analyze_shows_worker_counts() {
+        let mut plan = vector_plan(VectorIndexStrategy::Flat);
+        assert!(!explain_plan(&plan, None).contains("Workers"));
+
+        plan.execution_mode = ExecutionMode::Parallel;
+        plan.optimization_hints = vec![OptimizationHint::ParallelExecution(4)];
+        let output = explain_plan(&plan, None);
+        assert!(output.contains("Workers Planned: 4"));
+        assert!(!output.contains("Launched"));
+
+        // The pool had no spare workers, so the query ran serially
+        let analyze = ExplainAnalyze { workers_launched: Some(1), ..ExplainAnalyze::default() };
+        let output = explain_plan(&plan, Some(&analyze));
+        assert!(output.contains("Workers Planned: 4  Workers Launched: 1"));
+    }
+}
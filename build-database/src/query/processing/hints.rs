@@ -0,0 +1,295 @@
+//! Query Plan Hints: DBA Overrides for the Optimizer
+//!
+//! Hints are written in a leading SQL comment, pg_hint_plan style:
+//!
+//! ```sql
+//! /*+ HashJoin(o c) Leading(c o) IndexScan(o orders_customer_idx) */
+//! SELECT * FROM orders o JOIN customers c ON o.customer_id = c.id
+//! ```
+//!
+//! - **Join algorithm**: `HashJoin(a b)`, `NestLoop(a b)`, `MergeJoin(a b)`
+//! - **Join order**: `Leading(a b c)`
+//! - **Access method**: `SeqScan(t)`, `IndexScan(t [index])`
+//! - **Settings**: `Set(name value)`, e.g. `Set(max_parallel_workers_per_query 1)`
+//!
+//! Hints are advisory: the planner applies a hint when it is feasible and
+//! otherwise falls back to its own choice, logging a warning. Every hint ends
+//! up in the `HintReport` as either applied or ignored.
+
+use tracing::{info, warn};
+
+/// A single optimizer hint
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanHint {
+    /// Join the given relations with a hash join
+    HashJoin(Vec<String>),
+    /// Join the given relations with a nested loop join
+    NestLoop(Vec<String>),
+    /// Join the given relations with a merge join
+    MergeJoin(Vec<String>),
+    /// Join relations in this order (outermost first)
+    Leading(Vec<String>),
+    /// Scan the relation sequentially
+    SeqScan(String),
+    /// Scan the relation through an index (any index if `None`)
+    IndexScan { relation: String, index: Option<String> },
+    /// Override a planner setting for this statement only
+    Set { name: String, value: String },
+}
+
+impl std::fmt::Display for PlanHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanHint::HashJoin(rels) => write!(f, "HashJoin({})", rels.join(" ")),
+            PlanHint::NestLoop(rels) => write!(f, "NestLoop({})", rels.join(" ")),
+            PlanHint::MergeJoin(rels) => write!(f, "MergeJoin({})", rels.join(" ")),
+            PlanHint::Leading(rels) => write!(f, "Leading({})", rels.join(" ")),
+            PlanHint::SeqScan(rel) => write!(f, "SeqScan({})", rel),
+            PlanHint::IndexScan { relation, index: Some(index) } => write!(f, "IndexScan({} {})", relation, index),
+            PlanHint::IndexScan { relation, index: None } => write!(f, "IndexScan({})", relation),
+            PlanHint::Set { name, value } => write!(f, "Set({} {})", name, value),
+        }
+    }
+}
+
+/// Join algorithm requested by a hint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinMethodHint {
+    Hash,
+    NestLoop,
+    Merge,
+}
+
+/// Parse the body of a `/*+ ... */` comment.
+///
+/// Returns the recognized hints and a warning for every hint that could not
+/// be parsed. Malformed hints never fail the query.
+pub fn parse_hint_block(body: &str) -> (Vec<PlanHint>, Vec<String>) {
+    let mut hints = Vec::new();
+    let mut warnings = Vec::new();
+    let mut rest = body.trim();
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('(') else {
+            warnings.push(format!("ignoring malformed hint text '{}'", rest));
+            break;
+        };
+        let Some(close) = rest[open..].find(')').map(|c| open + c) else {
+            warnings.push(format!("ignoring unterminated hint '{}'", rest));
+            break;
+        };
+
+        let name = rest[..open].trim();
+        let args: Vec<String> = rest[open + 1..close]
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|a| !a.is_empty())
+            .map(|a| a.to_lowercase())
+            .collect();
+
+        match parse_single_hint(name, args) {
+            Ok(hint) => hints.push(hint),
+            Err(message) => warnings.push(message),
+        }
+
+        rest = rest[close + 1..].trim_start();
+    }
+
+    for warning in &warnings {
+        warn!("Plan hint parse warning: {}", warning);
+    }
+
+    (hints, warnings)
+}
+
+fn parse_single_hint(name: &str, args: Vec<String>) -> Result<PlanHint, String> {
+    let join_relations = |args: Vec<String>| {
+        if args.len() >= 2 {
+            Ok(args)
+        } else {
+            Err(format!("{} needs at least two relations", name))
+        }
+    };
+
+    match name.to_lowercase().as_str() {
+        "hashjoin" => join_relations(args).map(PlanHint::HashJoin),
+        "nestloop" => join_relations(args).map(PlanHint::NestLoop),
+        "mergejoin" => join_relations(args).map(PlanHint::MergeJoin),
+        "leading" => join_relations(args).map(PlanHint::Leading),
+        "seqscan" => match args.as_slice() {
+            [relation] => Ok(PlanHint::SeqScan(relation.clone())),
+            _ => Err("SeqScan takes exactly one relation".to_string()),
+        },
+        "indexscan" => match args.as_slice() {
+            [relation] => Ok(PlanHint::IndexScan { relation: relation.clone(), index: None }),
+            [relation, index] => Ok(PlanHint::IndexScan { relation: relation.clone(), index: Some(index.clone()) }),
+            _ => Err("IndexScan takes a relation and an optional index".to_string()),
+        },
+        "set" => match args.as_slice() {
+            [name, value] => Ok(PlanHint::Set { name: name.clone(), value: value.clone() }),
+            _ => Err("Set takes a setting name and a value".to_string()),
+        },
+        other => Err(format!("unknown hint '{}'", other)),
+    }
+}
+
+/// Outcome of hint processing for one planned statement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HintReport {
+    /// Hints that shaped the plan
+    pub applied: Vec<String>,
+    /// Hints that could not be applied, with the reason
+    pub ignored: Vec<(String, String)>,
+}
+
+impl HintReport {
+    /// True when every hint was applied
+    pub fn all_applied(&self) -> bool {
+        self.ignored.is_empty()
+    }
+}
+
+/// Per-statement hint state threaded through the planner
+#[derive(Debug)]
+pub struct HintContext {
+    hints: Vec<PlanHint>,
+    resolved: Vec<bool>,
+    report: HintReport,
+}
+
+impl HintContext {
+    /// Create a context for the given hints
+    pub fn new(hints: Vec<PlanHint>) -> Self {
+        let resolved = vec![false; hints.len()];
+        Self { hints, resolved, report: HintReport::default() }
+    }
+
+    /// True when no hints were given
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// Join method hinted for a join covering exactly `relations`
+    pub(crate) fn join_method(&self, relations: &[String]) -> Option<(usize, JoinMethodHint)> {
+        self.hints.iter().enumerate().find_map(|(i, hint)| {
+            let (rels, method) = match hint {
+                PlanHint::HashJoin(rels) => (rels, JoinMethodHint::Hash),
+                PlanHint::NestLoop(rels) => (rels, JoinMethodHint::NestLoop),
+                PlanHint::MergeJoin(rels) => (rels, JoinMethodHint::Merge),
+                _ => return None,
+            };
+            (!self.resolved[i] && same_relations(rels, relations)).then_some((i, method))
+        })
+    }
+
+    /// Access method hinted for a relation
+    pub(crate) fn scan_method(&self, relation: &str) -> Option<(usize, &PlanHint)> {
+        self.hints.iter().enumerate().find(|(i, hint)| {
+            !self.resolved[*i] && match hint {
+                PlanHint::SeqScan(rel) => rel == relation,
+                PlanHint::IndexScan { relation: rel, .. } => rel == relation,
+                _ => false,
+            }
+        })
+    }
+
+    /// True when a scan method was hinted for the relation, applied or not
+    pub(crate) fn has_scan_hint(&self, relation: &str) -> bool {
+        self.hints.iter().any(|hint| match hint {
+            PlanHint::SeqScan(rel) => rel.eq_ignore_ascii_case(relation),
+            PlanHint::IndexScan { relation: rel, .. } => rel.eq_ignore_ascii_case(relation),
+            _ => false,
+        })
+    }
+
+    /// Index of the Leading (join order) hint, if any
+    pub(crate) fn leading_hint(&self) -> Option<usize> {
+        self.hints.iter().position(|hint| matches!(hint, PlanHint::Leading(_)))
+    }
+
+    /// Position of a relation in the Leading hint
+    pub(crate) fn leading_position(&self, relation: &str) -> Option<usize> {
+        self.hints.iter().find_map(|hint| match hint {
+            PlanHint::Leading(rels) => rels.iter().position(|r| r == relation),
+            _ => None,
+        })
+    }
+
+    /// Setting overrides, as (hint index, name, value)
+    pub(crate) fn settings(&self) -> Vec<(usize, String, String)> {
+        self.hints.iter().enumerate().filter_map(|(i, hint)| match hint {
+            PlanHint::Set { name, value } => Some((i, name.clone(), value.clone())),
+            _ => None,
+        }).collect()
+    }
+
+    /// Record that hint `index` shaped the plan
+    pub(crate) fn mark_applied(&mut self, index: usize) {
+        if self.resolved[index] {
+            return;
+        }
+        self.resolved[index] = true;
+        let hint = self.hints[index].to_string();
+        info!("Plan hint applied: {}", hint);
+        self.report.applied.push(hint);
+    }
+
+    /// Record that hint `index` could not be applied
+    pub(crate) fn mark_ignored(&mut self, index: usize, reason: impl Into<String>) {
+        if self.resolved[index] {
+            return;
+        }
+        self.resolved[index] = true;
+        let hint = self.hints[index].to_string();
+        let reason = reason.into();
+        warn!("Plan hint ignored: {} ({})", hint, reason);
+        self.report.ignored.push((hint, reason));
+    }
+
+    /// Finish planning: any hint never matched is reported as ignored
+    pub fn finish(mut self) -> HintReport {
+        for i in 0..self.hints.len() {
+            if !self.resolved[i] {
+                self.mark_ignored(i, "no matching relation or join in the query");
+            }
+        }
+        self.report
+    }
+}
+
+fn same_relations(a: &[String], b: &[String]) -> bool {
+    a.len() == b.len() && a.iter().all(|rel| b.contains(rel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hint_block() {
+        let (hints, warnings) = parse_hint_block("HashJoin(a b) Leading(b a) IndexScan(a a_idx) SeqScan(b) Set(parallel_disabled on)");
+        assert!(warnings.is_empty());
+        assert_eq!(hints, vec![
+            PlanHint::HashJoin(vec!["a".into(), "b".into()]),
+            PlanHint::Leading(vec!["b".into(), "a".into()]),
+            PlanHint::IndexScan { relation: "a".into(), index: Some("a_idx".into()) },
+            PlanHint::SeqScan("b".into()),
+            PlanHint::Set { name: "parallel_disabled".into(), value: "on".into() },
+        ]);
+    }
+
+    #[test]
+    fn test_malformed_hints_warn() {
+        let (hints, warnings) = parse_hint_block("Bogus(a) HashJoin(a) NestLoop(x y");
+        assert!(hints.is_empty());
+        assert_eq!(warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_unmatched_hints_reported_as_ignored() {
+        let ctx = HintContext::new(vec![PlanHint::SeqScan("ghost".into())]);
+        let report = ctx.finish();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.ignored.len(), 1);
+        assert_eq!(report.ignored[0].0, "SeqScan(ghost)");
+    }
+}
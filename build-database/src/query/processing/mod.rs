@@ -12,6 +12,10 @@ pub mod query_optimizer;
 pub mod execution_engine;
 pub mod ast;
 pub mod plan;
+pub mod hints;
+pub mod explain;
+pub mod parallelism;
+pub mod calibration;
 
 pub use sql_parser::*;
 pub use query_planner::*;
@@ -19,3 +23,7 @@ pub use query_optimizer::*;
 pub use execution_engine::*;
 pub use ast::*;
 pub use plan::*;
+pub use hints::*;
+pub use explain::{explain_plan, ExplainAnalyze, OperatorActuals};
+pub use parallelism::{ParallelSettings, WorkerPool, WorkerReservation};
+pub use calibration::{calibrate, CalibratedCosts, CostBenchmark, CostMeasurements, MicroBenchmarks};
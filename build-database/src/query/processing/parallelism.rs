@@ -0,0 +1,184 @@
+//! Statement-Level Parallelism Settings
+//!
+//! Controls how many workers a single query may use. Settings come from the
+//! session and can be overridden per query with a `Set` plan hint:
+//!
+//! ```sql
+//! /*+ Set(max_parallel_workers_per_query 1) */
+//! SELECT count(*) FROM events
+//! ```
+//!
+//! - `max_parallel_workers_per_query`: upper bound on workers for one query;
+//!   1 forces serial plans
+//! - `parallel_disabled`: turns parallel plans off entirely
+//! - `parallel_cost_threshold`: plans cheaper than this are never parallelized
+//!
+//! Workers are drawn from a shared `WorkerPool` at execution time. When the
+//! pool cannot supply at least two, the query runs serially instead of failing.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use crate::core::errors::{AuroraResult, AuroraError};
+
+/// Names accepted by `ParallelSettings::set`
+pub const PARALLEL_SETTINGS: [&str; 3] = [
+    "max_parallel_workers_per_query",
+    "parallel_disabled",
+    "parallel_cost_threshold",
+];
+
+/// Parallelism settings in effect for one session or query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParallelSettings {
+    pub max_parallel_workers_per_query: u32,
+    pub parallel_disabled: bool,
+    pub parallel_cost_threshold: f64,
+}
+
+impl Default for ParallelSettings {
+    fn default() -> Self {
+        Self {
+            max_parallel_workers_per_query: 4,
+            parallel_disabled: false,
+            parallel_cost_threshold: 100.0,
+        }
+    }
+}
+
+impl ParallelSettings {
+    /// True when `name` is one of the parallelism settings
+    pub fn is_setting(name: &str) -> bool {
+        PARALLEL_SETTINGS.contains(&name.to_lowercase().as_str())
+    }
+
+    /// Change one setting from its textual value
+    pub fn set(&mut self, name: &str, value: &str) -> AuroraResult<()> {
+        let invalid = || AuroraError::Plan(format!("invalid value '{}' for {}", value, name));
+        match name.to_lowercase().as_str() {
+            "max_parallel_workers_per_query" => {
+                let workers: u32 = value.parse().map_err(|_| invalid())?;
+                if workers == 0 {
+                    return Err(invalid());
+                }
+                self.max_parallel_workers_per_query = workers;
+            }
+            "parallel_disabled" => {
+                self.parallel_disabled = match value.to_lowercase().as_str() {
+                    "on" | "true" | "1" => true,
+                    "off" | "false" | "0" => false,
+                    _ => return Err(invalid()),
+                };
+            }
+            "parallel_cost_threshold" => {
+                let threshold: f64 = value.parse().map_err(|_| invalid())?;
+                if !threshold.is_finite() || threshold < 0.0 {
+                    return Err(invalid());
+                }
+                self.parallel_cost_threshold = threshold;
+            }
+            _ => return Err(AuroraError::Plan(format!("unknown parallelism setting '{}'", name))),
+        }
+        Ok(())
+    }
+
+    /// Workers to plan for a statement of the given cost; 1 means serial
+    pub fn planned_workers(&self, estimated_cost: f64) -> u32 {
+        if self.parallel_disabled || estimated_cost < self.parallel_cost_threshold {
+            1
+        } else {
+            self.max_parallel_workers_per_query
+        }
+    }
+}
+
+/// Workers shared by all queries running on this node
+#[derive(Debug)]
+pub struct WorkerPool {
+    capacity: u32,
+    in_use: AtomicU32,
+}
+
+impl WorkerPool {
+    /// Create a pool of `capacity` workers
+    pub fn new(capacity: u32) -> Arc<Self> {
+        Arc::new(Self { capacity, in_use: AtomicU32::new(0) })
+    }
+
+    /// Workers not currently reserved
+    pub fn available(&self) -> u32 {
+        self.capacity.saturating_sub(self.in_use.load(Ordering::Acquire))
+    }
+
+    /// Reserve up to `requested` workers. The reservation may hold fewer, or
+    /// none, when the pool is busy; the workers return to the pool on drop.
+    pub fn reserve(self: &Arc<Self>, requested: u32) -> WorkerReservation {
+        let mut current = self.in_use.load(Ordering::Acquire);
+        loop {
+            let granted = requested.min(self.capacity.saturating_sub(current));
+            if granted == 0 {
+                return WorkerReservation { pool: Arc::clone(self), workers: 0 };
+            }
+            match self.in_use.compare_exchange_weak(current, current + granted, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return WorkerReservation { pool: Arc::clone(self), workers: granted },
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+/// Workers held by one executing query
+#[derive(Debug)]
+pub struct WorkerReservation {
+    pool: Arc<WorkerPool>,
+    workers: u32,
+}
+
+impl WorkerReservation {
+    /// Number of workers held
+    pub fn workers(&self) -> u32 {
+        self.workers
+    }
+}
+
+impl Drop for WorkerReservation {
+    fn drop(&mut self) {
+        if self.workers > 0 {
+            self.pool.in_use.fetch_sub(self.workers, Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_plan_workers() {
+        let mut settings = ParallelSettings::default();
+        assert_eq!(settings.planned_workers(50.0), 1);
+        assert_eq!(settings.planned_workers(500.0), 4);
+
+        settings.set("max_parallel_workers_per_query", "2").unwrap();
+        assert_eq!(settings.planned_workers(500.0), 2);
+        settings.set("PARALLEL_DISABLED", "on").unwrap();
+        assert_eq!(settings.planned_workers(500.0), 1);
+
+        assert!(settings.set("max_parallel_workers_per_query", "0").is_err());
+        assert!(settings.set("parallel_cost_threshold", "-1").is_err());
+        assert!(settings.set("work_mem", "4MB").is_err());
+    }
+
+    #[test]
+    fn test_reservations_return_workers() {
+        let pool = WorkerPool::new(4);
+        let first = pool.reserve(3);
+        let second = pool.reserve(3);
+        assert_eq!(first.workers(), 3);
+        assert_eq!(second.workers(), 1);
+        assert_eq!(pool.reserve(2).workers(), 0);
+
+        drop(first);
+        drop(second);
+        assert_eq!(pool.available(), 4);
+    }
+}
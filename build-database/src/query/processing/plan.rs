@@ -6,6 +6,7 @@
 use std::collections::{HashMap, HashSet};
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::ast::*;
+use super::calibration::CalibratedCosts;
 
 /// Complete query execution plan
 #[derive(Debug, Clone)]
@@ -293,10 +294,93 @@ pub struct VectorSearchNode {
     pub limit: u32,
     pub filter_condition: Option<Expression>,
     pub index_name: Option<String>, // HNSW, IVF, etc.
+    pub index_strategy: VectorIndexStrategy,
+    pub filter_strategy: Option<VectorFilterStrategy>,
+    pub table_rows: u64,
     pub estimated_rows: u64,
     pub cost: f64,
 }
 
+/// How a vector search finds its candidates
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorIndexStrategy {
+    /// HNSW graph search keeping a beam of `ef_search` candidates
+    Hnsw { ef_search: u32 },
+    /// IVF-PQ search probing `nprobe` of `nlist` inverted lists
+    IvfPq { nprobe: u32, nlist: u32 },
+    /// Exhaustive distance computation against every vector
+    Flat,
+}
+
+/// Where a vector search applies its metadata filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorFilterStrategy {
+    /// Filter rows first and search only the matches
+    PreFilter,
+    /// Search first and drop results that fail the filter
+    PostFilter,
+}
+
+impl VectorFilterStrategy {
+    /// Pre-filter when the filter keeps few enough rows that searching only
+    /// them is cheaper than over-fetching from the index
+    pub fn choose(selectivity: f64) -> Self {
+        if selectivity <= 0.1 {
+            VectorFilterStrategy::PreFilter
+        } else {
+            VectorFilterStrategy::PostFilter
+        }
+    }
+}
+
+impl VectorSearchNode {
+    /// Estimated number of vectors whose distance to the query is computed
+    pub fn estimated_candidates(&self) -> u64 {
+        let rows = self.table_rows.max(1);
+        let k = (self.limit as u64).max(1);
+        let candidates = match &self.index_strategy {
+            // A beam of ef_search candidates over roughly log2(N) hops
+            VectorIndexStrategy::Hnsw { ef_search } => {
+                (*ef_search as u64).max(k) * (64 - rows.leading_zeros()) as u64
+            }
+            VectorIndexStrategy::IvfPq { nprobe, nlist } => {
+                rows * (*nprobe as u64) / (*nlist as u64).max(1)
+            }
+            VectorIndexStrategy::Flat => rows,
+        };
+        candidates.clamp(k.min(rows), rows)
+    }
+
+    /// Estimated fraction of the true top-k the search returns
+    ///
+    /// A heuristic for comparing settings, not a measurement: recall rises
+    /// with ef_search relative to k for HNSW, and with the probed share of
+    /// lists for IVF-PQ. Flat search is exact.
+    pub fn estimated_recall(&self) -> f64 {
+        let k = (self.limit as f64).max(1.0);
+        match &self.index_strategy {
+            VectorIndexStrategy::Hnsw { ef_search } => {
+                (1.0 - (-2.0 * *ef_search as f64 / k).exp()).min(0.999)
+            }
+            VectorIndexStrategy::IvfPq { nprobe, nlist } if nprobe >= nlist => 0.999,
+            VectorIndexStrategy::IvfPq { nprobe, nlist } => {
+                1.0 - (-3.0 * *nprobe as f64 / (*nlist as f64).sqrt()).exp()
+            }
+            VectorIndexStrategy::Flat => 1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for VectorIndexStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VectorIndexStrategy::Hnsw { ef_search } => write!(f, "HNSW (ef_search={})", ef_search),
+            VectorIndexStrategy::IvfPq { nprobe, nlist } => write!(f, "IVF-PQ (nprobe={}, nlist={})", nprobe, nlist),
+            VectorIndexStrategy::Flat => write!(f, "flat"),
+        }
+    }
+}
+
 /// KNN search node
 #[derive(Debug, Clone)]
 pub struct KnnSearchNode {
@@ -373,6 +457,17 @@ impl QueryPlan {
         self.estimated_cost
     }
 
+    /// Workers the planner intends this plan to run with; 1 for serial plans
+    pub fn planned_workers(&self) -> u32 {
+        if self.execution_mode != ExecutionMode::Parallel {
+            return 1;
+        }
+        self.optimization_hints.iter().find_map(|hint| match hint {
+            OptimizationHint::ParallelExecution(workers) => Some(*workers),
+            _ => None,
+        }).unwrap_or(1)
+    }
+
     /// Get execution complexity
     pub fn complexity(&self) -> PlanComplexity {
         match self.estimated_cost {
@@ -480,8 +575,10 @@ pub enum PlanComplexity {
 pub struct CostModel {
     /// CPU cost per operation
     pub cpu_operation_cost: f64,
-    /// IO cost per page
+    /// IO cost per page read sequentially
     pub io_page_cost: f64,
+    /// IO cost per page fetched at a random position
+    pub random_page_cost: f64,
     /// Memory cost per byte
     pub memory_byte_cost: f64,
     /// Network cost per byte
@@ -500,6 +597,7 @@ impl CostModel {
         Self {
             cpu_operation_cost: 0.01,
             io_page_cost: 1.0,
+            random_page_cost: 4.0,
             memory_byte_cost: 0.0001,
             network_byte_cost: 0.001,
             index_lookup_cost: 0.1,
@@ -512,6 +610,26 @@ impl CostModel {
         }
     }
 
+    /// Create a cost model from calibrated constants
+    pub fn calibrated(costs: &CalibratedCosts) -> Self {
+        let mut model = Self::new();
+        model.apply_calibration(costs);
+        model
+    }
+
+    /// Replace the guessed constants with calibrated ones
+    ///
+    /// Costs the calibration does not measure (memory, network, index
+    /// lookups, nested loop and merge join factors) keep their values.
+    pub fn apply_calibration(&mut self, costs: &CalibratedCosts) {
+        self.io_page_cost = costs.seq_page_cost;
+        self.random_page_cost = costs.random_page_cost;
+        self.seq_scan_row_cost = costs.cpu_tuple_cost;
+        self.cpu_operation_cost = costs.cpu_operator_cost;
+        // Join costs are per-row operator costs scaled by the factor
+        self.join_cost_factors.insert("hash_join".to_string(), costs.hash_row_cost / costs.cpu_operator_cost);
+    }
+
     /// Estimate cost for sequential scan
     pub fn estimate_seq_scan_cost(&self, table_rows: u64, row_width: u32, pages: u64) -> CostEstimate {
         let io_cost = pages as f64 * self.io_page_cost;
@@ -530,9 +648,12 @@ impl CostModel {
     }
 
     /// Estimate cost for index scan
+    ///
+    /// Index and table pages are fetched in key order, not file order, so
+    /// each is charged as a random read.
     pub fn estimate_index_scan_cost(&self, index_pages: u64, table_pages: u64, matching_rows: u64) -> CostEstimate {
-        let index_io_cost = index_pages as f64 * self.io_page_cost;
-        let table_io_cost = table_pages as f64 * self.io_page_cost;
+        let index_io_cost = index_pages as f64 * self.random_page_cost;
+        let table_io_cost = table_pages as f64 * self.random_page_cost;
         let cpu_cost = matching_rows as f64 * self.cpu_operation_cost;
 
         CostEstimate {
@@ -593,7 +714,7 @@ impl std::fmt::Display for PlanNode {
             PlanNode::Aggregate(_) => write!(f, "Aggregate"),
             PlanNode::Sort(_) => write!(f, "Sort"),
             PlanNode::Limit(_) => write!(f, "Limit"),
-            PlanNode::VectorSearch(_) => write!(f, "VectorSearch"),
+            PlanNode::VectorSearch(node) => write!(f, "VectorSearch({}.{})", node.table_name, node.vector_column),
             _ => write!(f, "PlanNode"),
         }
     }
@@ -722,6 +843,9 @@ mod tests {
                 right: Box::new(Expression::Literal(LiteralValue::String("electronics".to_string()))),
             }),
             index_name: Some("hnsw_index".to_string()),
+            index_strategy: VectorIndexStrategy::Hnsw { ef_search: 64 },
+            filter_strategy: Some(VectorFilterStrategy::PostFilter),
+            table_rows: 100_000,
             estimated_rows: 10,
             cost: 15.0,
         });
@@ -7,9 +7,13 @@
 
 use std::collections::{HashMap, HashSet, BinaryHeap};
 use std::cmp::Reverse;
+use parking_lot::RwLock;
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::ast::*;
 use super::plan::*;
+use super::hints::{HintContext, HintReport, JoinMethodHint, PlanHint};
+use super::parallelism::ParallelSettings;
+use super::calibration::CalibratedCosts;
 
 /// Query planner that generates execution plans from SQL AST
 pub struct QueryPlanner {
@@ -24,6 +28,12 @@ pub struct QueryPlanner {
 
     /// Query planning options
     options: PlanningOptions,
+
+    /// Parallelism settings new sessions start with
+    parallel_defaults: ParallelSettings,
+
+    /// Parallelism settings sessions have changed, by session id
+    session_settings: RwLock<HashMap<String, ParallelSettings>>,
 }
 
 /// Table statistics for cost estimation
@@ -110,17 +120,66 @@ impl QueryPlanner {
                 max_memory_mb: 1024,
                 timeout_ms: 5000,
             },
+            parallel_defaults: ParallelSettings::default(),
+            session_settings: RwLock::new(HashMap::new()),
         }
     }
 
     /// Plan a SELECT statement
     pub fn plan_select(&self, select: &SelectStatement) -> AuroraResult<QueryPlan> {
+        self.plan_select_with_hints(select).map(|(plan, _)| plan)
+    }
+
+    /// Change a parallelism setting for one session
+    pub fn set_session_setting(&self, session_id: &str, name: &str, value: &str) -> AuroraResult<()> {
+        let mut sessions = self.session_settings.write();
+        let settings = sessions.entry(session_id.to_string())
+            .or_insert_with(|| self.parallel_defaults.clone());
+        settings.set(name, value)
+    }
+
+    /// Return a session to the default parallelism settings
+    pub fn reset_session_settings(&self, session_id: &str) {
+        self.session_settings.write().remove(session_id);
+    }
+
+    /// Parallelism settings in effect for a session
+    pub fn session_settings(&self, session_id: &str) -> ParallelSettings {
+        self.session_settings.read().get(session_id)
+            .cloned()
+            .unwrap_or_else(|| self.parallel_defaults.clone())
+    }
+
+    /// Plan a SELECT statement with the session's parallelism settings
+    pub fn plan_select_for_session(&self, select: &SelectStatement, session_id: &str) -> AuroraResult<(QueryPlan, HintReport)> {
+        self.plan_select_with_settings(select, self.session_settings(session_id))
+    }
+
+    /// Plan a SELECT statement, reporting which of its plan hints were honored.
+    ///
+    /// Hints that cannot be applied never fail planning; they are logged and
+    /// listed in `HintReport::ignored`.
+    pub fn plan_select_with_hints(&self, select: &SelectStatement) -> AuroraResult<(QueryPlan, HintReport)> {
+        self.plan_select_with_settings(select, self.parallel_defaults.clone())
+    }
+
+    /// Plan a SELECT statement under the given parallelism settings, which
+    /// `Set` hints may override for this statement
+    fn plan_select_with_settings(&self, select: &SelectStatement, mut settings: ParallelSettings) -> AuroraResult<(QueryPlan, HintReport)> {
+        let mut hints = HintContext::new(select.hints.clone());
+        for (index, name, value) in hints.settings() {
+            match settings.set(&name, &value) {
+                Ok(()) => hints.mark_applied(index),
+                Err(e) => hints.mark_ignored(index, e.to_string()),
+            }
+        }
+
         // 1. Plan the FROM clause (tables and joins)
-        let from_plan = self.plan_from_clause(&select.from)?;
+        let from_plan = self.plan_from_clause(&select.from, &mut hints)?;
 
         // 2. Apply WHERE clause filtering
         let filtered_plan = if let Some(where_clause) = &select.where_clause {
-            self.plan_filter(from_plan, &where_clause.condition)?
+            self.plan_where(from_plan, &where_clause.condition, &hints)?
         } else {
             from_plan
         };
@@ -166,26 +225,26 @@ impl QueryPlanner {
         };
 
         // 8. Determine execution mode
-        let execution_mode = self.determine_execution_mode(&final_plan);
+        let execution_mode = self.determine_execution_mode(&final_plan, &settings);
 
         // 9. Generate optimization hints
-        let optimization_hints = self.generate_optimization_hints(&final_plan);
+        let optimization_hints = self.generate_optimization_hints(&final_plan, &settings);
 
         // 10. Calculate final statistics
         let statistics = self.calculate_plan_statistics(&final_plan);
 
-        Ok(QueryPlan {
+        Ok((QueryPlan {
             root: final_plan.root,
             estimated_cost: final_plan.estimated_cost,
             estimated_rows: final_plan.estimated_rows,
             execution_mode,
             optimization_hints,
             statistics,
-        })
+        }, hints.finish()))
     }
 
     /// Plan the FROM clause (tables and joins)
-    fn plan_from_clause(&self, from: &Option<FromClause>) -> AuroraResult<QueryPlan> {
+    fn plan_from_clause(&self, from: &Option<FromClause>, hints: &mut HintContext) -> AuroraResult<QueryPlan> {
         match from {
             Some(from_clause) => {
                 let mut plans = Vec::new();
@@ -194,7 +253,7 @@ impl QueryPlanner {
                 for item in &from_clause.items {
                     let plan = match item {
                         FromItem::Table { name, alias } => {
-                            self.plan_table_scan(name, alias.as_deref())?
+                            self.plan_table_scan(name, alias.as_deref(), hints)?
                         }
                         FromItem::Subquery { query, alias } => {
                             if let Statement::Select(select) = &**query {
@@ -205,24 +264,37 @@ impl QueryPlanner {
                                 return Err(AuroraError::Plan("Expected SELECT in subquery".to_string()));
                             }
                         }
-                        FromItem::Join { left, right, join_type, condition } => {
-                            let left_plan = self.plan_from_item(left)?;
-                            let right_plan = self.plan_from_item(right)?;
-                            return self.plan_join(left_plan, right_plan, *join_type, condition.as_ref());
+                        FromItem::Join { .. } => {
+                            return self.plan_from_item(item, hints);
                         }
                     };
-                    plans.push(plan);
+                    plans.push((Self::relations_of(item), plan));
+                }
+
+                // A Leading hint decides the order of comma-separated tables
+                if let Some(leading) = hints.leading_hint() {
+                    let listed = plans.iter()
+                        .filter(|(rels, _)| rels.iter().any(|r| hints.leading_position(r).is_some()))
+                        .count();
+                    if listed > 1 {
+                        plans.sort_by_key(|(rels, _)| {
+                            rels.iter().filter_map(|r| hints.leading_position(r)).min().unwrap_or(usize::MAX)
+                        });
+                        hints.mark_applied(leading);
+                    }
                 }
 
                 // If multiple tables without explicit joins, create cross join
                 if plans.len() > 1 {
-                    let mut result_plan = plans[0].clone();
-                    for plan in plans.into_iter().skip(1) {
-                        result_plan = self.plan_cross_join(result_plan, plan)?;
+                    let mut plans = plans.into_iter();
+                    let (mut covered, mut result_plan) = plans.next().unwrap();
+                    for (rels, plan) in plans {
+                        covered.extend(rels);
+                        result_plan = self.plan_cross_join(result_plan, plan, &covered, hints)?;
                     }
                     Ok(result_plan)
                 } else {
-                    Ok(plans.into_iter().next().unwrap())
+                    Ok(plans.into_iter().next().unwrap().1)
                 }
             }
             None => {
@@ -250,36 +322,102 @@ impl QueryPlanner {
     }
 
     /// Plan a table scan
-    fn plan_table_scan(&self, table_name: &str, alias: Option<&str>) -> AuroraResult<QueryPlan> {
+    fn plan_table_scan(&self, table_name: &str, alias: Option<&str>, hints: &mut HintContext) -> AuroraResult<QueryPlan> {
         let table_stats = self.table_stats.get(table_name)
             .ok_or_else(|| AuroraError::Plan(format!("No statistics for table {}", table_name)))?;
 
         // Check for available indexes
-        let available_indexes = self.index_info.get(table_name).unwrap_or(&vec![]);
+        let available_indexes = self.index_info.get(table_name).map(Vec::as_slice).unwrap_or(&[]);
+
+        // An IndexScan hint forces an index access path when the index exists
+        let relation = alias.unwrap_or(table_name).to_lowercase();
+        let scan_hint = hints.scan_method(&relation).map(|(idx, hint)| (idx, hint.clone()));
+        match scan_hint {
+            Some((idx, PlanHint::IndexScan { index, .. })) => {
+                let chosen = match &index {
+                    Some(name) => available_indexes.iter().find(|i| i.index_name.eq_ignore_ascii_case(name)),
+                    None => available_indexes.first(),
+                };
+                match chosen {
+                    Some(index_info) => {
+                        hints.mark_applied(idx);
+                        let matching_rows = ((table_stats.total_rows as f64) * (1.0 - index_info.selectivity).max(0.01)) as u64;
+                        let cost = self.cost_model.estimate_index_scan_cost(
+                            (table_stats.total_pages / 10).max(1),
+                            (matching_rows * table_stats.total_pages / table_stats.total_rows.max(1)).max(1),
+                            matching_rows,
+                        ).total_cost;
+
+                        return Ok(QueryPlan {
+                            root: PlanNode::IndexScan(IndexScanNode {
+                                table_name: table_name.to_string(),
+                                index_name: index_info.index_name.clone(),
+                                index_condition: Expression::Literal(LiteralValue::Boolean(true)),
+                                output_columns: vec![],
+                                estimated_rows: matching_rows,
+                                cost,
+                            }),
+                            estimated_cost: cost,
+                            estimated_rows: matching_rows,
+                            execution_mode: ExecutionMode::Sequential,
+                            optimization_hints: vec![OptimizationHint::UseIndex(index_info.index_name.clone())],
+                            statistics: PlanStatistics::default(),
+                        });
+                    }
+                    None => {
+                        let reason = match index {
+                            Some(name) => format!("index '{}' does not exist on table '{}'", name, table_name),
+                            None => format!("table '{}' has no indexes", table_name),
+                        };
+                        hints.mark_ignored(idx, reason);
+                    }
+                }
+            }
+            Some((idx, _)) => hints.mark_applied(idx),
+            None => {}
+        }
 
         // For now, prefer sequential scan unless we have specific conditions
         // In a full implementation, this would consider index selectivity
+        let cost = self.cost_model.estimate_seq_scan_cost(
+            table_stats.total_rows,
+            table_stats.avg_row_width,
+            table_stats.total_pages,
+        ).total_cost;
         let scan_node = SeqScanNode {
             table_name: table_name.to_string(),
             output_columns: vec![], // Will be filled by projection
             estimated_rows: table_stats.total_rows,
-            cost: self.cost_model.estimate_seq_scan_cost(
-                table_stats.total_rows,
-                table_stats.avg_row_width,
-                table_stats.total_pages,
-            ).total_cost,
+            cost,
         };
 
         Ok(QueryPlan {
             root: PlanNode::SeqScan(scan_node),
-            estimated_cost: scan_node.cost,
-            estimated_rows: scan_node.estimated_rows,
+            estimated_cost: cost,
+            estimated_rows: table_stats.total_rows,
             execution_mode: ExecutionMode::Sequential,
             optimization_hints: vec![],
             statistics: PlanStatistics::default(),
         })
     }
 
+    /// Plan the WHERE clause, fetching through an index instead when that is
+    /// cheaper than filtering a scan the hints did not pin
+    fn plan_where(&self, input_plan: QueryPlan, condition: &Expression, hints: &HintContext) -> AuroraResult<QueryPlan> {
+        let table_name = match &input_plan.root {
+            PlanNode::SeqScan(scan) if !hints.has_scan_hint(&scan.table_name) => Some(scan.table_name.clone()),
+            _ => None,
+        };
+        let filtered = self.plan_filter(input_plan, condition)?;
+
+        if let (Some(table_name), PlanNode::Filter(filter)) = (table_name, &filtered.root) {
+            if let Some(index_plan) = self.plan_index_access(&table_name, condition, filter.selectivity, filtered.estimated_cost) {
+                return Ok(index_plan);
+            }
+        }
+        Ok(filtered)
+    }
+
     /// Plan filtering (WHERE clause)
     fn plan_filter(&self, input_plan: QueryPlan, condition: &Expression) -> AuroraResult<QueryPlan> {
         // Estimate selectivity of the filter condition
@@ -290,7 +428,7 @@ impl QueryPlanner {
             condition: condition.clone(),
             estimated_rows: ((input_plan.estimated_rows as f64) * selectivity) as u64,
             selectivity,
-            cost: input_plan.estimated_cost + (input_plan.estimated_rows as f64 * 0.01), // Small CPU cost
+            cost: input_plan.estimated_cost + (input_plan.estimated_rows as f64 * self.cost_model.cpu_operation_cost),
         };
 
         Ok(QueryPlan {
@@ -303,6 +441,54 @@ impl QueryPlanner {
         })
     }
 
+    /// Index scan answering `condition` on `table_name`, if one exists on a
+    /// filtered column and costs less than `seq_cost`
+    fn plan_index_access(&self, table_name: &str, condition: &Expression, selectivity: f64, seq_cost: f64) -> Option<QueryPlan> {
+        let table_stats = self.table_stats.get(table_name)?;
+        let mut columns = Vec::new();
+        Self::referenced_columns(condition, &mut columns);
+        let index_info = self.index_info.get(table_name)?.iter()
+            .find(|index| index.columns.first().map_or(false, |first| columns.iter().any(|c| c.eq_ignore_ascii_case(first))))?;
+
+        // Leaf pages in proportion to the matches, then one table fetch per
+        // matching row, at most once per table page
+        let matching_rows = ((table_stats.total_rows as f64) * selectivity).max(1.0) as u64;
+        let index_pages = (((table_stats.total_pages / 10) as f64) * selectivity).ceil().max(1.0) as u64;
+        let table_pages = matching_rows.min(table_stats.total_pages.max(1));
+        let cost = self.cost_model.estimate_index_scan_cost(index_pages, table_pages, matching_rows).total_cost;
+        if cost >= seq_cost {
+            return None;
+        }
+
+        Some(QueryPlan {
+            root: PlanNode::IndexScan(IndexScanNode {
+                table_name: table_name.to_string(),
+                index_name: index_info.index_name.clone(),
+                index_condition: condition.clone(),
+                output_columns: vec![],
+                estimated_rows: matching_rows,
+                cost,
+            }),
+            estimated_cost: cost,
+            estimated_rows: matching_rows,
+            execution_mode: ExecutionMode::Sequential,
+            optimization_hints: vec![OptimizationHint::UseIndex(index_info.index_name.clone())],
+            statistics: PlanStatistics::default(),
+        })
+    }
+
+    fn referenced_columns(expression: &Expression, columns: &mut Vec<String>) {
+        match expression {
+            Expression::Column(name) | Expression::QualifiedColumn(_, name) => columns.push(name.clone()),
+            Expression::BinaryOp { left, right, .. } => {
+                Self::referenced_columns(left, columns);
+                Self::referenced_columns(right, columns);
+            }
+            Expression::UnaryOp { expr, .. } => Self::referenced_columns(expr, columns),
+            _ => {}
+        }
+    }
+
     /// Plan projection (SELECT clause)
     fn plan_projection(&self, input_plan: QueryPlan, select_clause: &SelectClause) -> AuroraResult<QueryPlan> {
         let expressions: Vec<(Expression, Option<String>)> = match &select_clause.select_list[0] {
@@ -438,12 +624,34 @@ impl QueryPlanner {
     }
 
     /// Plan joins
-    fn plan_join(&self, left_plan: QueryPlan, right_plan: QueryPlan, join_type: JoinType, condition: Option<&Expression>) -> AuroraResult<QueryPlan> {
+    fn plan_join(
+        &self,
+        left_plan: QueryPlan,
+        right_plan: QueryPlan,
+        join_type: JoinType,
+        condition: Option<&Expression>,
+        relations: &[String],
+        hints: &mut HintContext,
+    ) -> AuroraResult<QueryPlan> {
         // Estimate join cardinality
         let join_cardinality = self.estimate_join_cardinality(&left_plan, &right_plan, condition)?;
 
-        // Choose join algorithm
-        let join_algorithm = self.choose_join_algorithm(&left_plan, &right_plan, condition)?;
+        // Choose join algorithm; a feasible hint overrides the cost-based choice
+        let join_algorithm = match hints.join_method(relations) {
+            Some((idx, JoinMethodHint::NestLoop)) => {
+                hints.mark_applied(idx);
+                JoinAlgorithm::NestedLoop
+            }
+            Some((idx, method)) if condition.map_or(false, Self::is_equi_join) => {
+                hints.mark_applied(idx);
+                if method == JoinMethodHint::Hash { JoinAlgorithm::Hash } else { JoinAlgorithm::Merge }
+            }
+            Some((idx, _)) => {
+                hints.mark_ignored(idx, "hash and merge joins require an equality join condition");
+                self.choose_join_algorithm(&left_plan, &right_plan, condition)?
+            }
+            None => self.choose_join_algorithm(&left_plan, &right_plan, condition)?,
+        };
 
         match join_algorithm {
             JoinAlgorithm::NestedLoop => {
@@ -565,9 +773,9 @@ impl QueryPlanner {
 
     // Helper methods
 
-    fn plan_from_item(&self, item: &FromItem) -> AuroraResult<QueryPlan> {
+    fn plan_from_item(&self, item: &FromItem, hints: &mut HintContext) -> AuroraResult<QueryPlan> {
         match item {
-            FromItem::Table { name, alias } => self.plan_table_scan(name, alias.as_deref()),
+            FromItem::Table { name, alias } => self.plan_table_scan(name, alias.as_deref(), hints),
             FromItem::Subquery { query, alias } => {
                 if let Statement::Select(select) = &**query {
                     self.plan_select(select)
@@ -576,14 +784,67 @@ impl QueryPlanner {
                 }
             }
             FromItem::Join { left, right, join_type, condition } => {
-                let left_plan = self.plan_from_item(left)?;
-                let right_plan = self.plan_from_item(right)?;
-                self.plan_join(left_plan, right_plan, *join_type, condition.as_ref())
+                let relations = Self::relations_of(item);
+                let (mut left, mut right) = (left, right);
+
+                // A Leading hint may swap the join inputs, but only where that preserves semantics
+                if let Some(leading) = hints.leading_hint() {
+                    let first_position = |item: &FromItem| Self::relations_of(item).iter()
+                        .filter_map(|r| hints.leading_position(r))
+                        .min();
+                    if let (Some(l), Some(r)) = (first_position(&**left), first_position(&**right)) {
+                        if r < l {
+                            if matches!(join_type, JoinType::Inner | JoinType::Cross) {
+                                std::mem::swap(&mut left, &mut right);
+                                hints.mark_applied(leading);
+                            } else {
+                                hints.mark_ignored(leading, "outer joins cannot be reordered");
+                            }
+                        } else {
+                            hints.mark_applied(leading);
+                        }
+                    }
+                }
+
+                let left_plan = self.plan_from_item(left, hints)?;
+                let right_plan = self.plan_from_item(right, hints)?;
+                self.plan_join(left_plan, right_plan, *join_type, condition.as_ref(), &relations, hints)
+            }
+        }
+    }
+
+    /// Relation names (aliases where given) covered by a FROM item, as used by plan hints
+    fn relations_of(item: &FromItem) -> Vec<String> {
+        match item {
+            FromItem::Table { name, alias } => vec![alias.as_deref().unwrap_or(name).to_lowercase()],
+            FromItem::Subquery { alias, .. } => vec![alias.to_lowercase()],
+            FromItem::Join { left, right, .. } => {
+                let mut relations = Self::relations_of(left);
+                relations.extend(Self::relations_of(right));
+                relations
             }
         }
     }
 
-    fn plan_cross_join(&self, left_plan: QueryPlan, right_plan: QueryPlan) -> AuroraResult<QueryPlan> {
+    /// Whether a join condition is made of column equalities
+    fn is_equi_join(condition: &Expression) -> bool {
+        match condition {
+            Expression::BinaryOp { op: BinaryOperator::Equal, .. } => true,
+            Expression::BinaryOp { left, op: BinaryOperator::And, right } => {
+                Self::is_equi_join(left) && Self::is_equi_join(right)
+            }
+            _ => false,
+        }
+    }
+
+    fn plan_cross_join(&self, left_plan: QueryPlan, right_plan: QueryPlan, relations: &[String], hints: &mut HintContext) -> AuroraResult<QueryPlan> {
+        // A cross join is always executed as a nested loop
+        match hints.join_method(relations) {
+            Some((idx, JoinMethodHint::NestLoop)) => hints.mark_applied(idx),
+            Some((idx, _)) => hints.mark_ignored(idx, "hash and merge joins require an equality join condition"),
+            None => {}
+        }
+
         let join_cardinality = left_plan.estimated_rows * right_plan.estimated_rows;
 
         let cost = self.cost_model.estimate_join_cost(
@@ -657,9 +918,18 @@ impl QueryPlanner {
         (vec![], vec![])
     }
 
-    fn determine_execution_mode(&self, plan: &QueryPlan) -> ExecutionMode {
+    /// Workers the plan should run with; 1 when it is too small or cheap to
+    /// parallelize or parallelism is off
+    fn parallel_workers(&self, plan: &QueryPlan, settings: &ParallelSettings) -> u32 {
+        if !self.options.enable_parallelism || plan.estimated_rows <= 10000 {
+            return 1;
+        }
+        settings.planned_workers(plan.estimated_cost).min(self.options.max_parallel_workers).max(1)
+    }
+
+    fn determine_execution_mode(&self, plan: &QueryPlan, settings: &ParallelSettings) -> ExecutionMode {
         // Determine best execution mode based on plan characteristics
-        if self.options.enable_parallelism && plan.estimated_rows > 10000 {
+        if self.parallel_workers(plan, settings) > 1 {
             ExecutionMode::Parallel
         } else if self.options.enable_vectorized_execution && plan.complexity() >= PlanComplexity::Medium {
             ExecutionMode::Vectorized
@@ -668,12 +938,13 @@ impl QueryPlanner {
         }
     }
 
-    fn generate_optimization_hints(&self, plan: &QueryPlan) -> Vec<OptimizationHint> {
+    fn generate_optimization_hints(&self, plan: &QueryPlan, settings: &ParallelSettings) -> Vec<OptimizationHint> {
         let mut hints = Vec::new();
 
-        // Add hints based on plan analysis
-        if plan.estimated_rows > 100000 {
-            hints.push(OptimizationHint::ParallelExecution(self.options.max_parallel_workers));
+        // Carry the planned degree of parallelism to the executor
+        let workers = self.parallel_workers(plan, settings);
+        if workers > 1 {
+            hints.push(OptimizationHint::ParallelExecution(workers));
         }
 
         // Check for potential index usage
@@ -734,6 +1005,23 @@ impl QueryPlanner {
     pub fn update_options(&mut self, options: PlanningOptions) {
         self.options = options;
     }
+
+    /// Cost plans with calibrated constants instead of the built-in guesses
+    pub fn apply_calibration(&mut self, costs: &CalibratedCosts) {
+        self.cost_model.apply_calibration(costs);
+    }
+
+    /// Apply constants stored by a previous calibration run, if any;
+    /// returns whether a calibration was found
+    pub fn load_calibration(&mut self, path: &std::path::Path) -> AuroraResult<bool> {
+        match CalibratedCosts::load(path)? {
+            Some(costs) => {
+                self.apply_calibration(&costs);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 /// Join algorithm selection
@@ -747,6 +1035,7 @@ enum JoinAlgorithm {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::calibration::CostMeasurements;
 
     #[test]
     fn test_query_planner_creation() {
@@ -900,9 +1189,10 @@ mod tests {
             statistics: PlanStatistics::default(),
         };
 
-        assert_eq!(planner.determine_execution_mode(&small_plan), ExecutionMode::Sequential);
+        let settings = ParallelSettings::default();
+        assert_eq!(planner.determine_execution_mode(&small_plan, &settings), ExecutionMode::Sequential);
         // Large plans should use parallel execution if enabled
-        assert_eq!(planner.determine_execution_mode(&large_plan), ExecutionMode::Parallel);
+        assert_eq!(planner.determine_execution_mode(&large_plan, &settings), ExecutionMode::Parallel);
     }
 
     #[test]
@@ -968,4 +1258,297 @@ mod tests {
         assert_eq!(stats.total_operators, 2); // Filter + SeqScan
         assert!(stats.estimated_memory_mb > 0.0);
     }
+
+    fn hinted_planner() -> QueryPlanner {
+        let mut planner = QueryPlanner::new();
+        planner.update_options(PlanningOptions { prefer_hash_joins: false, ..planner.options.clone() });
+        for (table, rows) in [("a", 10_000), ("b", 500)] {
+            planner.update_table_statistics(TableStatistics {
+                table_name: table.to_string(),
+                total_rows: rows,
+                total_pages: rows / 100,
+                avg_row_width: 128,
+                column_stats: HashMap::new(),
+            });
+        }
+        planner
+    }
+
+    fn join_select(condition: Option<Expression>, hints: Vec<PlanHint>) -> SelectStatement {
+        SelectStatement {
+            select: SelectClause { distinct: false, select_list: vec![SelectItem::Wildcard] },
+            from: Some(FromClause {
+                items: vec![FromItem::Join {
+                    left: Box::new(FromItem::Table { name: "a".to_string(), alias: None }),
+                    right: Box::new(FromItem::Table { name: "b".to_string(), alias: None }),
+                    join_type: JoinType::Inner,
+                    condition,
+                }],
+            }),
+            hints,
+            ..SelectStatement::default()
+        }
+    }
+
+    fn equi_condition() -> Expression {
+        Expression::BinaryOp {
+            left: Box::new(Expression::QualifiedColumn("a".to_string(), "id".to_string())),
+            op: BinaryOperator::Equal,
+            right: Box::new(Expression::QualifiedColumn("b".to_string(), "a_id".to_string())),
+        }
+    }
+
+    fn find_join(node: &PlanNode) -> &PlanNode {
+        match node {
+            PlanNode::Projection(projection) => find_join(&projection.input),
+            other => other,
+        }
+    }
+
+    #[test]
+    fn test_hash_join_hint_forces_hash_join() {
+        let planner = hinted_planner();
+
+        let select = join_select(Some(equi_condition()), vec![PlanHint::NestLoop(vec!["a".into(), "b".into()])]);
+        let (plan, _) = planner.plan_select_with_hints(&select).unwrap();
+        assert!(matches!(find_join(&plan.root), PlanNode::NestedLoopJoin(_)));
+
+        let select = join_select(Some(equi_condition()), vec![PlanHint::HashJoin(vec!["b".into(), "a".into()])]);
+        let (plan, report) = planner.plan_select_with_hints(&select).unwrap();
+        assert!(matches!(find_join(&plan.root), PlanNode::HashJoin(_)));
+        assert_eq!(report.applied, vec!["HashJoin(b a)".to_string()]);
+        assert!(report.all_applied());
+    }
+
+    #[test]
+    fn test_infeasible_hints_are_ignored_with_warning() {
+        let mut planner = hinted_planner();
+        planner.add_index_info("a", IndexInfo {
+            index_name: "a_id_idx".to_string(),
+            table_name: "a".to_string(),
+            columns: vec!["id".to_string()],
+            index_type: IndexType::BTree,
+            is_unique: true,
+            selectivity: 0.99,
+        });
+
+        // Hash join without an equality condition and a non-existent index
+        let select = join_select(None, vec![
+            PlanHint::HashJoin(vec!["a".into(), "b".into()]),
+            PlanHint::IndexScan { relation: "b".into(), index: Some("missing_idx".into()) },
+            PlanHint::IndexScan { relation: "a".into(), index: None },
+        ]);
+        let (plan, report) = planner.plan_select_with_hints(&select).expect("hints never fail planning");
+
+        match find_join(&plan.root) {
+            PlanNode::NestedLoopJoin(join) => {
+                assert!(matches!(*join.left, PlanNode::IndexScan(ref scan) if scan.index_name == "a_id_idx"));
+                assert!(matches!(*join.right, PlanNode::SeqScan(_)));
+            }
+            other => panic!("Expected nested loop join, got {:?}", other),
+        }
+        assert_eq!(report.applied, vec!["IndexScan(a)".to_string()]);
+        assert_eq!(report.ignored.len(), 2);
+        assert!(report.ignored.iter().any(|(hint, reason)| {
+            hint == "IndexScan(b missing_idx)" && reason.contains("does not exist")
+        }));
+    }
+
+    #[test]
+    fn test_leading_hint_reorders_inner_join() {
+        let planner = hinted_planner();
+        let select = join_select(Some(equi_condition()), vec![PlanHint::Leading(vec!["b".into(), "a".into()])]);
+        let (plan, report) = planner.plan_select_with_hints(&select).unwrap();
+
+        match find_join(&plan.root) {
+            PlanNode::HashJoin(join) => {
+                assert!(matches!(*join.left, PlanNode::SeqScan(ref scan) if scan.table_name == "b"));
+            }
+            other => panic!("Expected hash join, got {:?}", other),
+        }
+        assert!(report.all_applied());
+    }
+
+    fn large_table_planner() -> QueryPlanner {
+        let mut planner = QueryPlanner::new();
+        planner.update_table_statistics(TableStatistics {
+            table_name: "events".to_string(),
+            total_rows: 1_000_000,
+            total_pages: 10_000,
+            avg_row_width: 128,
+            column_stats: HashMap::new(),
+        });
+        planner
+    }
+
+    fn scan_select(hints: Vec<PlanHint>) -> SelectStatement {
+        SelectStatement {
+            select: SelectClause { distinct: false, select_list: vec![SelectItem::Wildcard] },
+            from: Some(FromClause {
+                items: vec![FromItem::Table { name: "events".to_string(), alias: None }],
+            }),
+            hints,
+            ..SelectStatement::default()
+        }
+    }
+
+    #[test]
+    fn test_degree_one_forces_serial_plans() {
+        let planner = large_table_planner();
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s1").unwrap();
+        assert_eq!(plan.execution_mode, ExecutionMode::Parallel);
+        assert_eq!(plan.planned_workers(), 4);
+
+        // Session setting
+        planner.set_session_setting("s1", "max_parallel_workers_per_query", "1").unwrap();
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s1").unwrap();
+        assert_ne!(plan.execution_mode, ExecutionMode::Parallel);
+        assert_eq!(plan.planned_workers(), 1);
+        assert!(plan.optimization_hints.is_empty());
+
+        // Other sessions keep the defaults until reset clears the override
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s2").unwrap();
+        assert_eq!(plan.planned_workers(), 4);
+        planner.reset_session_settings("s1");
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s1").unwrap();
+        assert_eq!(plan.planned_workers(), 4);
+
+        // Per-query Set hint, and the disable switch
+        let hint = PlanHint::Set { name: "max_parallel_workers_per_query".into(), value: "1".into() };
+        let (plan, report) = planner.plan_select_for_session(&scan_select(vec![hint]), "s1").unwrap();
+        assert_eq!(plan.planned_workers(), 1);
+        assert!(report.all_applied());
+
+        planner.set_session_setting("s1", "parallel_disabled", "on").unwrap();
+        let hint = PlanHint::Set { name: "max_parallel_workers_per_query".into(), value: "8".into() };
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![hint]), "s1").unwrap();
+        assert_ne!(plan.execution_mode, ExecutionMode::Parallel);
+
+        // Invalid overrides are ignored, never fatal
+        let hint = PlanHint::Set { name: "max_parallel_workers_per_query".into(), value: "lots".into() };
+        let (_, report) = planner.plan_select_for_session(&scan_select(vec![hint]), "s2").unwrap();
+        assert_eq!(report.ignored.len(), 1);
+    }
+
+    #[test]
+    fn test_cost_threshold_gates_parallelism() {
+        let planner = large_table_planner();
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s1").unwrap();
+        assert_eq!(plan.execution_mode, ExecutionMode::Parallel);
+
+        let threshold = format!("{}", plan.estimated_cost * 2.0);
+        planner.set_session_setting("s1", "parallel_cost_threshold", &threshold).unwrap();
+        let (plan, _) = planner.plan_select_for_session(&scan_select(vec![]), "s1").unwrap();
+        assert_ne!(plan.execution_mode, ExecutionMode::Parallel);
+        assert_eq!(plan.planned_workers(), 1);
+
+        // The threshold applies to cost, not row count
+        let many_cheap_rows = QueryPlan {
+            root: PlanNode::SeqScan(SeqScanNode {
+                table_name: "events".to_string(),
+                output_columns: vec![],
+                estimated_rows: 1_000_000,
+                cost: 50.0,
+            }),
+            estimated_cost: 50.0,
+            estimated_rows: 1_000_000,
+            execution_mode: ExecutionMode::Sequential,
+            optimization_hints: vec![],
+            statistics: PlanStatistics::default(),
+        };
+        let settings = ParallelSettings::default();
+        assert_ne!(planner.determine_execution_mode(&many_cheap_rows, &settings), ExecutionMode::Parallel);
+        let settings = ParallelSettings { parallel_cost_threshold: 10.0, ..ParallelSettings::default() };
+        assert_eq!(planner.determine_execution_mode(&many_cheap_rows, &settings), ExecutionMode::Parallel);
+    }
+
+    fn indexed_planner() -> QueryPlanner {
+        let mut planner = QueryPlanner::new();
+        planner.update_table_statistics(TableStatistics {
+            table_name: "orders".to_string(),
+            total_rows: 1_000_000,
+            total_pages: 100_000,
+            avg_row_width: 128,
+            column_stats: HashMap::new(),
+        });
+        planner.add_index_info("orders", IndexInfo {
+            index_name: "orders_customer_idx".to_string(),
+            table_name: "orders".to_string(),
+            columns: vec!["customer_id".to_string()],
+            index_type: IndexType::BTree,
+            is_unique: false,
+            selectivity: 0.99,
+        });
+        planner
+    }
+
+    fn lookup_select(hints: Vec<PlanHint>) -> SelectStatement {
+        SelectStatement {
+            select: SelectClause { distinct: false, select_list: vec![SelectItem::Wildcard] },
+            from: Some(FromClause {
+                items: vec![FromItem::Table { name: "orders".to_string(), alias: None }],
+            }),
+            where_clause: Some(WhereClause {
+                condition: Expression::BinaryOp {
+                    left: Box::new(Expression::Column("customer_id".to_string())),
+                    op: BinaryOperator::Equal,
+                    right: Box::new(Expression::Literal(LiteralValue::Integer(42))),
+                },
+            }),
+            hints,
+            ..SelectStatement::default()
+        }
+    }
+
+    fn access_path(planner: &QueryPlanner, hints: Vec<PlanHint>) -> PlanNode {
+        let (plan, _) = planner.plan_select_with_hints(&lookup_select(hints)).unwrap();
+        match plan.root {
+            PlanNode::Projection(projection) => *projection.input,
+            other => other,
+        }
+    }
+
+    fn calibration(random_page_ns: f64) -> CalibratedCosts {
+        CalibratedCosts::from_measurements(CostMeasurements {
+            seq_page_ns: 2_000.0,
+            random_page_ns,
+            tuple_ns: 20.0,
+            sort_comparison_ns: 5.0,
+            hash_row_ns: 40.0,
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_calibrated_random_io_cost_changes_access_path() {
+        let mut planner = indexed_planner();
+        assert!(matches!(access_path(&planner, vec![]), PlanNode::IndexScan(_)));
+
+        // Random reads 25x a sequential read: fetching 1% of rows one page at
+        // a time costs more than reading the whole table in order
+        planner.apply_calibration(&calibration(50_000.0));
+        assert_eq!(planner.cost_model.random_page_cost, 25.0);
+        assert_eq!(planner.cost_model.seq_scan_row_cost, 0.01);
+        assert!(matches!(access_path(&planner, vec![]), PlanNode::Filter(ref filter) if matches!(*filter.input, PlanNode::SeqScan(_))));
+
+        // A hinted access path is kept whatever the costs say
+        let hint = PlanHint::IndexScan { relation: "orders".into(), index: None };
+        assert!(matches!(access_path(&planner, vec![hint]), PlanNode::Filter(ref filter) if matches!(*filter.input, PlanNode::IndexScan(_))));
+
+        // Fast random reads make the index worthwhile again
+        planner.apply_calibration(&calibration(3_000.0));
+        assert!(matches!(access_path(&planner, vec![]), PlanNode::IndexScan(ref scan) if scan.index_name == "orders_customer_idx"));
+    }
+
+    #[test]
+    fn test_planner_loads_stored_calibration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(super::super::calibration::CALIBRATION_FILE);
+        let mut planner = indexed_planner();
+        assert!(!planner.load_calibration(&path).unwrap());
+
+        calibration(50_000.0).save(&path).unwrap();
+        assert!(planner.load_calibration(&path).unwrap());
+        assert_eq!(planner.cost_model.random_page_cost, 25.0);
+        assert!(matches!(access_path(&planner, vec![]), PlanNode::Filter(_)));
+    }
 }
@@ -296,6 +296,7 @@ mod tests {
                 table: "test_table".to_string(),
                 alias: None,
                 joins: vec![],
+                unnest: None,
             },
             where_clause: None,
             group_by: None,
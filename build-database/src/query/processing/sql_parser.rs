@@ -9,12 +9,15 @@
 use std::collections::HashMap;
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::ast::*;
+use super::hints::parse_hint_block;
+use tracing::warn;
 
 /// SQL Parser using Pratt parsing technique
 pub struct SqlParser {
     tokens: Vec<Token>,
     position: usize,
     errors: Vec<ParseError>,
+    pending_hints: Option<String>,
 }
 
 /// Token types for SQL parsing
@@ -46,7 +49,7 @@ pub enum TokenType {
     Identifier, String, Number, True, False, Null,
 
     // Special
-    EOF, Error,
+    Hint, EOF, Error,
 }
 
 /// Token representation
@@ -75,6 +78,7 @@ impl SqlParser {
             tokens,
             position: 0,
             errors: Vec::new(),
+            pending_hints: None,
         }
     }
 
@@ -101,6 +105,15 @@ impl SqlParser {
 
     /// Parse a statement
     fn parse_statement(&mut self) -> AuroraResult<Statement> {
+        // A hint comment may precede the statement keyword
+        if self.check(TokenType::Hint) {
+            self.pending_hints = Some(self.advance().lexeme.clone());
+            if !self.check(TokenType::Select) {
+                warn!("Plan hints are only supported on SELECT statements; ignoring");
+                self.pending_hints = None;
+            }
+        }
+
         match self.peek().token_type {
             TokenType::Select => self.parse_select_statement(),
             TokenType::Insert => self.parse_insert_statement(),
@@ -133,6 +146,15 @@ impl SqlParser {
     fn parse_select_statement(&mut self) -> AuroraResult<Statement> {
         self.consume(TokenType::Select)?;
 
+        let hint_text = if self.check(TokenType::Hint) {
+            Some(self.advance().lexeme.clone())
+        } else {
+            self.pending_hints.take()
+        };
+        let hints = hint_text
+            .map(|text| parse_hint_block(&text).0)
+            .unwrap_or_default();
+
         let with = if self.check(TokenType::With) {
             Some(self.parse_with_clause()?)
         } else {
@@ -224,6 +246,7 @@ impl SqlParser {
             offset,
             union,
             union_all: false, // Simplified
+            hints,
         }))
     }
 
@@ -705,6 +728,33 @@ impl SqlParser {
                 '+' => tokens.push(Token { token_type: TokenType::Plus, lexeme: "+".to_string(), line, column }),
                 '-' => tokens.push(Token { token_type: TokenType::Minus, lexeme: "-".to_string(), line, column }),
                 '*' => tokens.push(Token { token_type: TokenType::Star, lexeme: "*".to_string(), line, column }),
+                '/' if chars.peek() == Some(&'*') => {
+                    // Block comment; `/*+ ... */` directly before or after SELECT carries plan hints
+                    chars.next();
+                    let (start_line, start_column) = (line, column);
+                    column += 2;
+                    let mut body = String::new();
+                    while let Some(c) = chars.next() {
+                        if c == '*' && chars.peek() == Some(&'/') {
+                            chars.next();
+                            column += 2;
+                            break;
+                        }
+                        if c == '\n' {
+                            line += 1;
+                            column = 1;
+                        } else {
+                            column += 1;
+                        }
+                        body.push(c);
+                    }
+                    let hint_position = tokens.last().map_or(true, |t: &Token| t.token_type == TokenType::Select);
+                    if let Some(hint_body) = body.strip_prefix('+') {
+                        if hint_position {
+                            tokens.push(Token { token_type: TokenType::Hint, lexeme: hint_body.trim().to_string(), line: start_line, column: start_column });
+                        }
+                    }
+                }
                 '/' => tokens.push(Token { token_type: TokenType::Slash, lexeme: "/".to_string(), line, column }),
                 '%' => tokens.push(Token { token_type: TokenType::Percent, lexeme: "%".to_string(), line, column }),
                 '=' => tokens.push(Token { token_type: TokenType::Equal, lexeme: "=".to_string(), line, column }),
@@ -879,7 +929,21 @@ impl SqlParser {
     fn parse_begin_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::Begin(BeginStatement { isolation_level: None, read_only: false })) }
     fn parse_set_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::Set(SetStatement { variable: "".to_string(), value: Expression::Literal(LiteralValue::Null) })) }
     fn parse_show_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::Show(ShowStatement { what: "".to_string() })) }
-    fn parse_explain_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::Explain(ExplainStatement { statement: Box::new(Statement::Select(SelectStatement::default())), analyze: false, verbose: false })) }
+    fn parse_explain_statement(&mut self) -> AuroraResult<Statement> {
+        self.consume(TokenType::Explain)?;
+        let mut analyze = false;
+        let mut verbose = false;
+        while self.check(TokenType::Identifier) {
+            match self.peek().lexeme.to_uppercase().as_str() {
+                "ANALYZE" => analyze = true,
+                "VERBOSE" => verbose = true,
+                _ => break,
+            }
+            self.advance();
+        }
+        let statement = Box::new(self.parse_statement()?);
+        Ok(Statement::Explain(ExplainStatement { statement, analyze, verbose }))
+    }
     fn parse_vector_search_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::VectorSearch(VectorSearchStatement { table_name: "".to_string(), vector_column: "".to_string(), query_vector: vec![], metric: VectorMetric::Cosine, limit: None, where_clause: None })) }
     fn parse_create_index_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::CreateIndex(CreateIndexStatement { index_name: "".to_string(), table_name: "".to_string(), columns: vec![], index_type: IndexType::BTree, unique: false, if_not_exists: false })) }
     fn parse_create_view_statement(&mut self) -> AuroraResult<Statement> { Ok(Statement::CreateView(CreateViewStatement { view_name: "".to_string(), columns: None, query: SelectStatement::default(), materialized: false, if_not_exists: false })) }
@@ -975,6 +1039,7 @@ impl Default for SelectStatement {
             offset: None,
             union: None,
             union_all: false,
+            hints: Vec::new(),
         }
     }
 }
@@ -982,6 +1047,7 @@ impl Default for SelectStatement {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::processing::hints::PlanHint;
 
     #[test]
     fn test_parser_creation() {
@@ -1023,4 +1089,36 @@ mod tests {
         let result = parser.parse();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_plan_hints_parsed() {
+        for sql in [
+            "/*+ HashJoin(a b) SeqScan(b) */ SELECT * FROM a",
+            "SELECT /*+ HashJoin(a b) SeqScan(b) */ * FROM a",
+        ] {
+            let mut parser = SqlParser::new(sql);
+            match parser.parse() {
+                Ok(Statement::Select(select)) => assert_eq!(select.hints, vec![
+                    PlanHint::HashJoin(vec!["a".into(), "b".into()]),
+                    PlanHint::SeqScan("b".into()),
+                ]),
+                other => panic!("Expected SELECT statement, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_plain_comments_and_bad_hints_are_not_errors() {
+        let mut parser = SqlParser::new("SELECT /* just a comment */ * FROM a");
+        match parser.parse() {
+            Ok(Statement::Select(select)) => assert!(select.hints.is_empty()),
+            other => panic!("Expected SELECT statement, got {:?}", other),
+        }
+
+        let mut parser = SqlParser::new("/*+ NoSuchHint(a) */ SELECT * FROM a");
+        match parser.parse() {
+            Ok(Statement::Select(select)) => assert!(select.hints.is_empty()),
+            other => panic!("Expected SELECT statement, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,220 @@
+//! User-Defined Scalar Functions
+//!
+//! Stored procedures run SQL; scalar UDFs run native Rust supplied by the
+//! application embedding AuroraDB. Each function is registered under a SQL
+//! name with a declared signature, and can then be called anywhere a scalar
+//! expression is allowed, e.g. `SELECT slugify(title) FROM posts WHERE
+//! word_count(body) > 100`.
+//!
+//! Calls are checked against the signature when the statement is planned,
+//! before any row is read: a wrong argument count or an argument whose type
+//! the parameter does not accept rejects the statement. At execution the
+//! function receives the evaluated arguments and must return a value of its
+//! declared return type.
+//!
+//! UDFs are opaque to the JIT: expressions calling one are never vectorized
+//! and always run through the interpreter.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use parking_lot::RwLock;
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+use crate::types::{DataType, DataValue};
+
+/// Native implementation of a scalar function
+pub type ScalarFn = dyn Fn(&[DataValue]) -> AuroraResult<DataValue> + Send + Sync;
+
+/// Names the engine evaluates itself, which a UDF may not shadow
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "now", "current_timestamp", "timezone", "match", "ts_rank",
+    "interval", "date_trunc", "date_part", "extract", "age",
+    "count", "sum", "avg", "min", "max",
+    "approx_count_distinct", "approx_percentile", "approx_quantile",
+    "hll_sketch", "hll_merge", "hll_estimate",
+    "tdigest_sketch", "tdigest_merge", "tdigest_quantile",
+    "row_number", "rank", "dense_rank", "lag", "lead",
+];
+
+/// Parameter and return types of a scalar function
+#[derive(Debug, Clone)]
+pub struct FunctionSignature {
+    pub arguments: Vec<DataType>,
+    pub returns: DataType,
+    /// The function writes or otherwise changes state outside its result,
+    /// so read-only connections refuse statements calling it
+    pub side_effects: bool,
+}
+
+impl FunctionSignature {
+    pub fn new(arguments: Vec<DataType>, returns: DataType) -> Self {
+        Self { arguments, returns, side_effects: false }
+    }
+
+    /// Declare that the function has side effects
+    pub fn with_side_effects(mut self) -> Self {
+        self.side_effects = true;
+        self
+    }
+}
+
+/// A registered scalar function
+pub struct ScalarFunction {
+    name: String,
+    signature: FunctionSignature,
+    function: Box<ScalarFn>,
+}
+
+impl std::fmt::Debug for ScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarFunction")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl ScalarFunction {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn signature(&self) -> &FunctionSignature {
+        &self.signature
+    }
+
+    /// Check a call's argument types; `None` is an argument whose type is
+    /// not known until execution (a NULL literal, an untyped expression).
+    /// Returns the type of the call.
+    pub fn check_arguments(&self, arguments: &[Option<DataType>]) -> AuroraResult<DataType> {
+        if arguments.len() != self.signature.arguments.len() {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("{}() takes {} arguments, got {}", self.name, self.signature.arguments.len(), arguments.len())
+            ));
+        }
+        for (position, (parameter, argument)) in self.signature.arguments.iter().zip(arguments).enumerate() {
+            if let Some(argument) = argument {
+                if !accepts(parameter, argument) {
+                    return Err(AuroraError::new(
+                        ErrorCode::ValidationTypeMismatch,
+                        format!("{}() argument {} must be {:?}, got {:?}", self.name, position + 1, parameter, argument)
+                    ));
+                }
+            }
+        }
+        Ok(self.signature.returns.clone())
+    }
+
+    /// Run the function on evaluated arguments
+    pub fn call(&self, arguments: &[DataValue]) -> AuroraResult<DataValue> {
+        if arguments.len() != self.signature.arguments.len() {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("{}() takes {} arguments, got {}", self.name, self.signature.arguments.len(), arguments.len())
+            ));
+        }
+        let result = (self.function)(arguments)?;
+        match value_type(&result) {
+            Some(returned) if !accepts(&self.signature.returns, &returned) => Err(AuroraError::new(
+                ErrorCode::ValidationTypeMismatch,
+                format!("{}() is declared to return {:?} but returned {:?}", self.name, self.signature.returns, result)
+            )),
+            _ => Ok(result),
+        }
+    }
+}
+
+/// Scalar functions registered by the host application, keyed by
+/// lower-cased SQL name
+#[derive(Debug, Default)]
+pub struct FunctionRegistry {
+    functions: RwLock<HashMap<String, Arc<ScalarFunction>>>,
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `function` as `name`, replacing an earlier function of that
+    /// name. Built-in function names cannot be taken.
+    pub fn register<F>(&self, name: &str, signature: FunctionSignature, function: F) -> AuroraResult<()>
+    where
+        F: Fn(&[DataValue]) -> AuroraResult<DataValue> + Send + Sync + 'static,
+    {
+        let key = name.to_lowercase();
+        let valid_identifier = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_identifier {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("'{}' is not a valid function name", name)
+            ));
+        }
+        if BUILTIN_FUNCTIONS.contains(&key.as_str()) {
+            return Err(AuroraError::new(
+                ErrorCode::QueryInvalidParameters,
+                format!("{}() is a built-in function", key)
+            ));
+        }
+
+        let function = ScalarFunction { name: key.clone(), signature, function: Box::new(function) };
+        self.functions.write().insert(key, Arc::new(function));
+        Ok(())
+    }
+
+    /// Remove a function; true if it was registered
+    pub fn unregister(&self, name: &str) -> bool {
+        self.functions.write().remove(&name.to_lowercase()).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<ScalarFunction>> {
+        self.functions.read().get(&name.to_lowercase()).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.read().contains_key(&name.to_lowercase())
+    }
+
+    /// Registered function names, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.functions.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Whether a parameter declared as `parameter` accepts an argument of type
+/// `argument`. Integers widen to any numeric type and `Float` to `Double`;
+/// other types must match exactly.
+pub fn accepts(parameter: &DataType, argument: &DataType) -> bool {
+    use DataType::*;
+    match (parameter, argument) {
+        (Integer, Integer) | (BigInt, Integer | BigInt) => true,
+        (Float, Integer | BigInt | Float) => true,
+        (Double, Integer | BigInt | Float | Double | Decimal(..)) => true,
+        (Decimal(..), Integer | BigInt | Decimal(..)) => true,
+        (Text, Text) | (Boolean, Boolean) | (Json, Json) => true,
+        (Timestamp, Timestamp) | (TimestampTz, TimestampTz) | (Interval, Interval) => true,
+        (Vector(expected), Vector(actual)) => expected == actual,
+        (Array(expected), Array(actual)) => accepts(expected, actual),
+        _ => false,
+    }
+}
+
+/// Narrowest type of a runtime value, so that every declared type able to
+/// hold it accepts it; `None` for NULL, which every type accepts
+pub fn value_type(value: &DataValue) -> Option<DataType> {
+    match value {
+        DataValue::Null => None,
+        DataValue::Integer(_) => Some(DataType::Integer),
+        DataValue::Real(_) => Some(DataType::Float),
+        DataValue::Decimal(_) => Some(DataType::Decimal(0, 0)),
+        DataValue::Text(_) | DataValue::String(_) => Some(DataType::Text),
+        DataValue::Boolean(_) => Some(DataType::Boolean),
+        DataValue::Timestamp(_) => Some(DataType::Timestamp),
+        DataValue::TimestampTz(_) => Some(DataType::TimestampTz),
+        DataValue::Interval(_) => Some(DataType::Interval),
+        _ => None,
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! User authentication with password hashing, session management, and MFA support.
 //! UNIQUENESS: Research-backed authentication combining Argon2, JWT, and behavioral analysis.
+//!
+//! Bearer tokens issued by an external gateway are checked by `JwtValidator`
+//! against the gateway's JWKS, fetched through a `JwksSource` and cached;
+//! a token signed with a key the cache does not hold refreshes it, so keys
+//! the gateway rotates in are picked up without a restart.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,6 +18,9 @@ use rand::RngCore;
 use jwt::{SignWithKey, VerifyWithKey};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::JwkSet;
 use crate::core::{AuroraResult, AuroraError, ErrorCode};
 use crate::security::rbac::RBACManager;
 
@@ -65,6 +73,7 @@ pub struct AuthManager {
     sessions: RwLock<HashMap<String, AuthSession>>,
     login_attempts: RwLock<HashMap<String, LoginAttempt>>,
     rbac_manager: Arc<RBACManager>,
+    jwt_validator: Option<Arc<JwtValidator>>,
 }
 
 impl AuthManager {
@@ -79,9 +88,16 @@ impl AuthManager {
             sessions: RwLock::new(HashMap::new()),
             login_attempts: RwLock::new(HashMap::new()),
             rbac_manager,
+            jwt_validator: None,
         }
     }
 
+    /// Trust bearer tokens that `validator` accepts
+    pub fn with_jwt_validator(mut self, validator: Arc<JwtValidator>) -> Self {
+        self.jwt_validator = Some(validator);
+        self
+    }
+
     /// Register a new user
     pub fn register_user(&self, username: String, password: String, email: String) -> AuroraResult<String> {
         // Validate password strength
@@ -230,6 +246,35 @@ impl AuthManager {
         Ok(claims.sub)
     }
 
+    /// Authenticate with a bearer token from the external gateway, opening
+    /// a session for its subject that ends when the token expires
+    pub async fn authenticate_bearer(&self, token: &str, client_ip: Option<&str>) -> AuroraResult<(AuthSession, JwtIdentity)> {
+        let validator = self.jwt_validator.as_ref().ok_or_else(|| AuroraError::new(
+            ErrorCode::Authentication,
+            "JWT authentication is not configured".to_string()
+        ))?;
+        let identity = validator.validate(token).await?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let session = AuthSession {
+            session_id: format!("session_{}_{}", identity.subject, now),
+            user_id: identity.subject.clone(),
+            created_at: now,
+            expires_at: identity.expires_at,
+            mfa_verified: true,
+            ip_address: client_ip.map(|s| s.to_string()),
+            user_agent: None,
+        };
+        self.sessions.write().insert(session.session_id.clone(), session.clone());
+
+        log::info!("Bearer token accepted for {} as role {} from {}",
+                   identity.subject, identity.role, client_ip.unwrap_or("unknown"));
+        Ok((session, identity))
+    }
+
     /// Hash password using Argon2
     fn hash_password(&self, password: &str) -> AuroraResult<String> {
         let salt = SaltString::generate(&mut rand::thread_rng());
@@ -403,4 +448,217 @@ pub struct AuthStats {
     pub total_sessions: usize,
     pub locked_accounts: usize,
     pub total_users: usize,
-}
\ No newline at end of file
+}
+
+/// Where a JWT validator gets the issuer's signing keys
+#[async_trait::async_trait]
+pub trait JwksSource: Send + Sync {
+    async fn fetch(&self) -> AuroraResult<JwkSet>;
+}
+
+/// JWKS served over HTTP, such as an issuer's `/.well-known/jwks.json`
+pub struct HttpJwksSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpJwksSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait::async_trait]
+impl JwksSource for HttpJwksSource {
+    async fn fetch(&self) -> AuroraResult<JwkSet> {
+        let fetch_failed = |e: reqwest::Error| AuroraError::new(
+            ErrorCode::Authentication,
+            format!("Cannot fetch JWKS from {}: {}", self.url, e)
+        );
+        self.client.get(&self.url).send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_failed)?
+            .json::<JwkSet>().await
+            .map_err(fetch_failed)
+    }
+}
+
+/// JWT authentication configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtAuthConfig {
+    /// Accepted `iss` values
+    pub issuers: Vec<String>,
+    /// Accepted `aud` values; a token must name at least one
+    pub audiences: Vec<String>,
+    /// Signing algorithms accepted, so a token cannot pick a weaker one
+    pub algorithms: Vec<Algorithm>,
+    /// Seconds `exp` and `nbf` may be off by, for clocks that disagree
+    pub clock_skew_secs: u64,
+    /// Seconds the fetched JWKS is used before it is fetched again
+    pub jwks_cache_ttl_secs: u64,
+    /// Fewest seconds between fetches forced by an unknown key id, so
+    /// tokens naming made-up keys cannot hammer the issuer
+    pub jwks_min_refresh_secs: u64,
+    /// Claim holding the token's roles, a string or an array of strings
+    pub role_claim: String,
+    /// Database role of each claim value; the first claim value with a
+    /// mapping picks the role
+    pub role_mapping: HashMap<String, String>,
+    /// Role of a token none of whose claim values is mapped; such tokens
+    /// are rejected when unset
+    pub default_role: Option<String>,
+}
+
+impl Default for JwtAuthConfig {
+    fn default() -> Self {
+        Self {
+            issuers: Vec::new(),
+            audiences: Vec::new(),
+            algorithms: vec![Algorithm::RS256, Algorithm::ES256],
+            clock_skew_secs: 60,
+            jwks_cache_ttl_secs: 3600,
+            jwks_min_refresh_secs: 30,
+            role_claim: "roles".to_string(),
+            role_mapping: HashMap::new(),
+            default_role: None,
+        }
+    }
+}
+
+/// Who a validated token speaks for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JwtIdentity {
+    /// `sub` of the token
+    pub subject: String,
+    /// Database role its claims map to
+    pub role: String,
+    /// `exp` of the token, in seconds since the epoch
+    pub expires_at: u64,
+    /// Every claim of the token
+    pub claims: serde_json::Map<String, serde_json::Value>,
+}
+
+/// JWKS as last fetched
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: std::time::Instant,
+}
+
+/// Validates bearer tokens against an issuer's JWKS
+pub struct JwtValidator {
+    config: JwtAuthConfig,
+    source: Arc<dyn JwksSource>,
+    cache: tokio::sync::RwLock<Option<CachedJwks>>,
+}
+
+impl JwtValidator {
+    pub fn new(config: JwtAuthConfig, source: Arc<dyn JwksSource>) -> Self {
+        Self { config, source, cache: tokio::sync::RwLock::new(None) }
+    }
+
+    /// Check the token's signature, issuer, audience and validity period,
+    /// and map its claims to a role
+    pub async fn validate(&self, token: &str) -> AuroraResult<JwtIdentity> {
+        let header = decode_header(token).map_err(|e| Self::rejected(&e))?;
+        if !self.config.algorithms.contains(&header.alg) {
+            return Err(AuroraError::new(
+                ErrorCode::Authentication,
+                format!("JWT rejected: algorithm {:?} is not accepted", header.alg)
+            ));
+        }
+        let key_id = header.kid.ok_or_else(|| AuroraError::new(
+            ErrorCode::Authentication,
+            "JWT rejected: token names no signing key (kid)".to_string()
+        ))?;
+        let key = self.decoding_key(&key_id).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.config.clock_skew_secs;
+        validation.validate_nbf = true;
+        validation.set_required_spec_claims(&["exp", "sub", "iss", "aud"]);
+        validation.set_issuer(&self.config.issuers);
+        validation.set_audience(&self.config.audiences);
+        let claims = decode::<serde_json::Map<String, serde_json::Value>>(token, &key, &validation)
+            .map_err(|e| Self::rejected(&e))?
+            .claims;
+
+        let subject = claims.get("sub").and_then(|sub| sub.as_str()).unwrap_or_default().to_string();
+        let expires_at = claims.get("exp").and_then(|exp| exp.as_u64()).unwrap_or_default();
+        let role = self.role_of(&claims).ok_or_else(|| AuroraError::new(
+            ErrorCode::Authentication,
+            format!("JWT rejected: no database role for the '{}' claim of {}", self.config.role_claim, subject)
+        ))?;
+        Ok(JwtIdentity { subject, role, expires_at, claims })
+    }
+
+    /// Key `key_id` of the JWKS, refreshing a stale cache or one that does
+    /// not hold the key because the issuer rotated it in since
+    async fn decoding_key(&self, key_id: &str) -> AuroraResult<DecodingKey> {
+        let ttl = std::time::Duration::from_secs(self.config.jwks_cache_ttl_secs);
+        let min_refresh = std::time::Duration::from_secs(self.config.jwks_min_refresh_secs);
+        {
+            let cache = self.cache.read().await;
+            if let Some(cached) = cache.as_ref().filter(|cached| cached.fetched_at.elapsed() < ttl) {
+                match cached.keys.find(key_id) {
+                    Some(jwk) => return Self::key_of(key_id, jwk),
+                    None if cached.fetched_at.elapsed() < min_refresh => return Err(Self::unknown_key(key_id)),
+                    None => {}
+                }
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Another validation may have refreshed the cache while this one waited
+        let fresh = cache.as_ref().is_some_and(|cached| cached.fetched_at.elapsed() < min_refresh);
+        if !fresh {
+            let keys = self.source.fetch().await?;
+            log::info!("Fetched JWKS with {} keys", keys.keys.len());
+            *cache = Some(CachedJwks { keys, fetched_at: std::time::Instant::now() });
+        }
+        let cached = cache.as_ref().expect("JWKS cache filled above");
+        match cached.keys.find(key_id) {
+            Some(jwk) => Self::key_of(key_id, jwk),
+            None => Err(Self::unknown_key(key_id)),
+        }
+    }
+
+    fn key_of(key_id: &str, jwk: &jsonwebtoken::jwk::Jwk) -> AuroraResult<DecodingKey> {
+        DecodingKey::from_jwk(jwk).map_err(|e| AuroraError::new(
+            ErrorCode::Authentication,
+            format!("JWKS key '{}' is unusable: {}", key_id, e)
+        ))
+    }
+
+    fn unknown_key(key_id: &str) -> AuroraError {
+        AuroraError::new(
+            ErrorCode::Authentication,
+            format!("JWT rejected: signing key '{}' is not in the issuer's JWKS", key_id)
+        )
+    }
+
+    /// First value of the role claim with a mapping, else the default role
+    fn role_of(&self, claims: &serde_json::Map<String, serde_json::Value>) -> Option<String> {
+        let values: Vec<&str> = match claims.get(&self.config.role_claim) {
+            Some(serde_json::Value::String(value)) => value.split_whitespace().collect(),
+            Some(serde_json::Value::Array(values)) => values.iter().filter_map(|value| value.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        values.iter()
+            .find_map(|value| self.config.role_mapping.get(*value).cloned())
+            .or_else(|| self.config.default_role.clone())
+    }
+
+    fn rejected(error: &jsonwebtoken::errors::Error) -> AuroraError {
+        let reason = match error.kind() {
+            ErrorKind::ExpiredSignature => "token has expired".to_string(),
+            ErrorKind::ImmatureSignature => "token is not valid yet".to_string(),
+            ErrorKind::InvalidSignature => "signature does not verify".to_string(),
+            ErrorKind::InvalidIssuer => "issuer is not trusted".to_string(),
+            ErrorKind::InvalidAudience => "audience does not match".to_string(),
+            ErrorKind::InvalidAlgorithm => "algorithm does not match the signing key".to_string(),
+            ErrorKind::MissingRequiredClaim(claim) => format!("required claim '{}' is missing", claim),
+            _ => format!("malformed token: {}", error),
+        };
+        AuroraError::new(ErrorCode::Authentication, format!("JWT rejected: {}", reason))
+    }
+}
@@ -0,0 +1,204 @@
+//! Buffer Usage Accounting
+//!
+//! Counters behind `EXPLAIN (ANALYZE, BUFFERS)`. Each plan node of an
+//! instrumented statement gets its own [`BufferUsage`], which the storage
+//! layer and spilling operators add to as they run:
+//! - **Shared**: Table pages found in the shared buffer pool (hits), loaded
+//!   into it (reads), and modified and written out by DML
+//! - **Temp**: Pages a spilling operator wrote to and read back from temp files
+//! - **WAL**: Records and bytes DML appended to the write-ahead log
+//!
+//! Counters are relaxed atomics, so operators add to them without locking.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use parking_lot::Mutex;
+use serde::{Serialize, Deserialize};
+use super::buffer_pool::PAGE_SIZE;
+
+/// Pages needed to hold `bytes`
+pub fn pages_for(bytes: u64) -> u64 {
+    bytes.div_ceil(PAGE_SIZE)
+}
+
+/// Buffer counters of one plan node
+#[derive(Debug, Default)]
+pub struct BufferUsage {
+    shared_hit: AtomicU64,
+    shared_read: AtomicU64,
+    shared_dirtied: AtomicU64,
+    shared_written: AtomicU64,
+    temp_read: AtomicU64,
+    temp_written: AtomicU64,
+    wal_records: AtomicU64,
+    wal_bytes: AtomicU64,
+}
+
+impl BufferUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A page was found in the shared buffer pool
+    pub fn shared_hit(&self) {
+        self.shared_hit.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A page was missing from the shared buffer pool and had to be loaded
+    pub fn shared_read(&self) {
+        self.shared_read.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `pages` shared pages were modified, and written out after `written` of them
+    pub fn shared_dirtied(&self, pages: u64, written: u64) {
+        self.shared_dirtied.fetch_add(pages, Ordering::Relaxed);
+        self.shared_written.fetch_add(written, Ordering::Relaxed);
+    }
+
+    /// `bytes` were written to a temp file
+    pub fn temp_written(&self, bytes: u64) {
+        self.temp_written.fetch_add(pages_for(bytes), Ordering::Relaxed);
+    }
+
+    /// `bytes` were read back from a temp file
+    pub fn temp_read(&self, bytes: u64) {
+        self.temp_read.fetch_add(pages_for(bytes), Ordering::Relaxed);
+    }
+
+    /// One record of `bytes` was appended to the WAL
+    pub fn wal_record(&self, bytes: u64) {
+        self.wal_records.fetch_add(1, Ordering::Relaxed);
+        self.wal_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self) -> BufferCounts {
+        BufferCounts {
+            shared_hit: self.shared_hit.load(Ordering::Relaxed),
+            shared_read: self.shared_read.load(Ordering::Relaxed),
+            shared_dirtied: self.shared_dirtied.load(Ordering::Relaxed),
+            shared_written: self.shared_written.load(Ordering::Relaxed),
+            temp_read: self.temp_read.load(Ordering::Relaxed),
+            temp_written: self.temp_written.load(Ordering::Relaxed),
+            wal_records: self.wal_records.load(Ordering::Relaxed),
+            wal_bytes: self.wal_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Point-in-time buffer counters; page counts except for `wal_bytes`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferCounts {
+    pub shared_hit: u64,
+    pub shared_read: u64,
+    pub shared_dirtied: u64,
+    pub shared_written: u64,
+    pub temp_read: u64,
+    pub temp_written: u64,
+    pub wal_records: u64,
+    pub wal_bytes: u64,
+}
+
+impl BufferCounts {
+    /// EXPLAIN detail lines, leaving out zero counters as PostgreSQL does
+    pub fn explain_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        let shared = [
+            ("hit", self.shared_hit),
+            ("read", self.shared_read),
+            ("dirtied", self.shared_dirtied),
+            ("written", self.shared_written),
+        ];
+        let temp = [("read", self.temp_read), ("written", self.temp_written)];
+        let mut groups = Vec::new();
+        for (name, counters) in [("shared", &shared[..]), ("temp", &temp[..])] {
+            let nonzero: Vec<String> = counters.iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(label, count)| format!("{}={}", label, count))
+                .collect();
+            if !nonzero.is_empty() {
+                groups.push(format!("{} {}", name, nonzero.join(" ")));
+            }
+        }
+        if !groups.is_empty() {
+            lines.push(format!("Buffers: {}", groups.join(", ")));
+        }
+
+        if self.wal_records > 0 {
+            lines.push(format!("WAL: records={} bytes={}", self.wal_records, self.wal_bytes));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for BufferCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.explain_lines().join("; "))
+    }
+}
+
+/// Buffer usage of each plan node of one statement, by node label
+#[derive(Debug, Default)]
+pub struct PlanBuffers {
+    nodes: Mutex<Vec<(String, Arc<BufferUsage>)>>,
+}
+
+impl PlanBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counters of the node labelled `label`, created on first use
+    pub fn node(&self, label: &str) -> Arc<BufferUsage> {
+        let mut nodes = self.nodes.lock();
+        if let Some((_, usage)) = nodes.iter().find(|(node, _)| node == label) {
+            return Arc::clone(usage);
+        }
+        let usage = Arc::new(BufferUsage::new());
+        nodes.push((label.to_string(), Arc::clone(&usage)));
+        usage
+    }
+
+    /// Counters of the node labelled `label`; zero if it never did I/O
+    pub fn counts(&self, label: &str) -> BufferCounts {
+        self.nodes.lock().iter()
+            .find(|(node, _)| node == label)
+            .map(|(_, usage)| usage.counts())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_lines_skip_zero_counters() {
+        let usage = BufferUsage::new();
+        assert!(usage.counts().explain_lines().is_empty());
+
+        usage.shared_hit();
+        usage.shared_read();
+        usage.shared_read();
+        usage.temp_written(PAGE_SIZE + 1);
+        assert_eq!(usage.counts().explain_lines(), vec!["Buffers: shared hit=1 read=2, temp written=2".to_string()]);
+
+        usage.wal_record(120);
+        usage.wal_record(80);
+        assert_eq!(usage.counts().explain_lines()[1], "WAL: records=2 bytes=200");
+    }
+
+    #[test]
+    fn test_plan_buffers_share_a_node_by_label() {
+        let plan = PlanBuffers::new();
+        plan.node("Seq Scan on t").shared_read();
+        plan.node("Seq Scan on t").shared_hit();
+        plan.node("Sort").temp_read(10);
+
+        assert_eq!(plan.counts("Seq Scan on t").shared_read, 1);
+        assert_eq!(plan.counts("Seq Scan on t").shared_hit, 1);
+        assert_eq!(plan.counts("Sort").temp_read, 1);
+        assert_eq!(plan.counts("Insert on t"), BufferCounts::default());
+    }
+}
@@ -2,8 +2,20 @@
 //!
 //! Intelligent compression with runtime algorithm selection, SIMD acceleration,
 //! and adaptive compression based on data patterns and access patterns.
+//!
+//! Columns of short values with many repeated substrings (URLs, JSON) barely
+//! compress one chunk at a time: each chunk is too small to learn from.
+//! `ColumnDictionaries` trains a zstd dictionary from a sample of a column's
+//! values, stores it with the table, and compresses later chunks with it.
+//! Each chunk records the dictionary version it was written with, so
+//! retraining after the data drifts never strands older chunks.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use crate::core::{AuroraError, AuroraResult, ErrorCode};
 
 /// Compression algorithm types
 #[derive(Debug, Clone, PartialEq)]
@@ -164,3 +176,261 @@ pub struct CompressionAnalysis {
     pub recommended_algorithm: CompressionAlgorithm,
     pub algorithm_stats: HashMap<CompressionAlgorithm, Vec<CompressionStats>>,
 }
+
+/// Builds a compression dictionary from sample values
+pub trait DictionaryTrainer: Send + Sync {
+    /// Dictionary of at most `max_size` bytes for data like `samples`
+    fn train(&self, samples: &[Vec<u8>], max_size: usize) -> AuroraResult<Vec<u8>>;
+}
+
+/// zstd's own dictionary builder (COVER)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZstdDictionaryTrainer;
+
+impl DictionaryTrainer for ZstdDictionaryTrainer {
+    fn train(&self, samples: &[Vec<u8>], max_size: usize) -> AuroraResult<Vec<u8>> {
+        zstd::dict::from_samples(samples, max_size).map_err(|e| {
+            AuroraError::new(ErrorCode::StorageUnavailable, format!("Dictionary training failed: {}", e))
+        })
+    }
+}
+
+/// Tuning of per-column dictionary compression
+#[derive(Debug, Clone)]
+pub struct DictionaryConfig {
+    /// Values sampled from a chunk to train a dictionary
+    pub sample_size: usize,
+    /// Fewest values worth training on; smaller chunks go without
+    pub min_samples: usize,
+    pub max_dictionary_size: usize,
+    /// zstd compression level
+    pub level: i32,
+    /// Retrain once a chunk compresses this fraction worse than the
+    /// dictionary did on its own sample
+    pub drift_threshold: f64,
+}
+
+impl Default for DictionaryConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 1024,
+            min_samples: 64,
+            max_dictionary_size: 16 * 1024,
+            level: 3,
+            drift_threshold: 0.25,
+        }
+    }
+}
+
+/// One trained version of a column's dictionary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionDictionary {
+    pub column: String,
+    /// Starts at 1; 0 in a chunk header means no dictionary
+    pub version: u32,
+    pub bytes: Vec<u8>,
+    /// Ratio the dictionary achieved on the sample it was trained on
+    pub trained_ratio: f64,
+}
+
+#[derive(Debug, Default)]
+struct ColumnDictionary {
+    versions: BTreeMap<u32, Arc<CompressionDictionary>>,
+}
+
+impl ColumnDictionary {
+    fn current(&self) -> Option<&Arc<CompressionDictionary>> {
+        self.versions.values().next_back()
+    }
+}
+
+/// Chunk header: dictionary version, then the uncompressed length
+const CHUNK_HEADER_SIZE: usize = 8;
+
+/// Dictionary compression of a table's column chunks
+///
+/// Every dictionary version is kept in the table's directory as
+/// `<column>.<version>.dict`, since chunks written with it may still be read.
+pub struct ColumnDictionaries {
+    directory: PathBuf,
+    config: DictionaryConfig,
+    trainer: Box<dyn DictionaryTrainer>,
+    columns: RwLock<HashMap<String, ColumnDictionary>>,
+}
+
+impl ColumnDictionaries {
+    /// Dictionaries stored under `directory`, loading those already there
+    pub fn open(directory: PathBuf) -> AuroraResult<Self> {
+        let storage_error = |e: std::io::Error| {
+            AuroraError::new(ErrorCode::StorageUnavailable, format!("Cannot read dictionaries in {}: {}", directory.display(), e))
+        };
+        std::fs::create_dir_all(&directory).map_err(storage_error)?;
+
+        let mut columns: HashMap<String, ColumnDictionary> = HashMap::new();
+        for file in std::fs::read_dir(&directory).map_err(storage_error)? {
+            let path = file.map_err(storage_error)?.path();
+            if path.extension().is_none_or(|extension| extension != "dict") {
+                continue;
+            }
+            let bytes = std::fs::read(&path).map_err(storage_error)?;
+            let dictionary: CompressionDictionary = bincode::deserialize(&bytes).map_err(|e| {
+                AuroraError::new(ErrorCode::StorageCorruption, format!("Dictionary {} is corrupt: {}", path.display(), e))
+            })?;
+            columns.entry(dictionary.column.clone()).or_default()
+                .versions.insert(dictionary.version, Arc::new(dictionary));
+        }
+
+        Ok(Self {
+            directory,
+            config: DictionaryConfig::default(),
+            trainer: Box::new(ZstdDictionaryTrainer),
+            columns: RwLock::new(columns),
+        })
+    }
+
+    pub fn with_config(mut self, config: DictionaryConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_trainer(mut self, trainer: Box<dyn DictionaryTrainer>) -> Self {
+        self.trainer = trainer;
+        self
+    }
+
+    /// Version new chunks of `column` are compressed with
+    pub fn current_version(&self, column: &str) -> Option<u32> {
+        self.columns.read().get(column)?.current().map(|dictionary| dictionary.version)
+    }
+
+    pub fn dictionary(&self, column: &str, version: u32) -> Option<Arc<CompressionDictionary>> {
+        self.columns.read().get(column)?.versions.get(&version).cloned()
+    }
+
+    /// Train and store a new version of `column`'s dictionary from a sample
+    /// of `values`
+    pub fn train(&self, column: &str, values: &[Vec<u8>]) -> AuroraResult<Arc<CompressionDictionary>> {
+        let stride = values.len().div_ceil(self.config.sample_size).max(1);
+        let samples: Vec<Vec<u8>> = values.iter().step_by(stride).cloned().collect();
+        let bytes = self.trainer.train(&samples, self.config.max_dictionary_size)?;
+
+        let encoded = encode_values(&samples);
+        let compressed = compress_with(&encoded, &bytes, self.config.level)?;
+        let trained_ratio = encoded.len() as f64 / compressed.len().max(1) as f64;
+
+        let mut columns = self.columns.write();
+        let state = columns.entry(column.to_string()).or_default();
+        let version = state.current().map_or(1, |dictionary| dictionary.version + 1);
+        let dictionary = Arc::new(CompressionDictionary { column: column.to_string(), version, bytes, trained_ratio });
+
+        let path = self.directory.join(format!("{}.{}.dict", column, version));
+        let serialized = bincode::serialize(dictionary.as_ref())
+            .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Cannot encode dictionary: {}", e)))?;
+        std::fs::write(&path, serialized).map_err(|e| {
+            AuroraError::new(ErrorCode::StorageUnavailable, format!("Cannot write dictionary {}: {}", path.display(), e))
+        })?;
+
+        log::info!("Trained dictionary version {} for column {} ({} bytes, ratio {:.2})",
+            version, column, dictionary.bytes.len(), trained_ratio);
+        state.versions.insert(version, dictionary.clone());
+        Ok(dictionary)
+    }
+
+    /// Compress a chunk of `column`'s values, training its first dictionary
+    /// or retraining one the data has drifted away from
+    pub fn compress_chunk(&self, column: &str, values: &[Vec<u8>]) -> AuroraResult<Vec<u8>> {
+        let encoded = encode_values(values);
+        let trainable = values.len() >= self.config.min_samples;
+
+        let mut dictionary = self.columns.read().get(column).and_then(|state| state.current().cloned());
+        if dictionary.is_none() && trainable {
+            dictionary = self.trained_or_none(column, values);
+        }
+        let Some(mut dictionary) = dictionary else {
+            return chunk(0, &encoded, &compress_with(&encoded, &[], self.config.level)?);
+        };
+
+        let mut compressed = compress_with(&encoded, &dictionary.bytes, self.config.level)?;
+        let ratio = encoded.len() as f64 / compressed.len().max(1) as f64;
+        if trainable && ratio < dictionary.trained_ratio * (1.0 - self.config.drift_threshold) {
+            log::info!("Column {} compresses at {:.2} against dictionary version {}'s {:.2}; retraining",
+                column, ratio, dictionary.version, dictionary.trained_ratio);
+            if let Some(retrained) = self.trained_or_none(column, values) {
+                compressed = compress_with(&encoded, &retrained.bytes, self.config.level)?;
+                dictionary = retrained;
+            }
+        }
+        chunk(dictionary.version, &encoded, &compressed)
+    }
+
+    /// Values of a chunk written by `compress_chunk`, with whichever
+    /// dictionary version it names
+    pub fn decompress_chunk(&self, column: &str, chunk: &[u8]) -> AuroraResult<Vec<Vec<u8>>> {
+        let corrupt = |message: String| AuroraError::new(ErrorCode::StorageCorruption, message);
+        if chunk.len() < CHUNK_HEADER_SIZE {
+            return Err(corrupt(format!("Chunk of column {} is truncated", column)));
+        }
+        let version = u32::from_le_bytes(chunk[0..4].try_into().unwrap());
+        let length = u32::from_le_bytes(chunk[4..8].try_into().unwrap()) as usize;
+
+        let dictionary = match version {
+            0 => None,
+            version => Some(self.dictionary(column, version).ok_or_else(|| {
+                corrupt(format!("Chunk of column {} needs dictionary version {}, which is not stored", column, version))
+            })?),
+        };
+        let dictionary_bytes = dictionary.as_ref().map_or(&[][..], |dictionary| &dictionary.bytes);
+        let encoded = zstd::bulk::Decompressor::with_dictionary(dictionary_bytes)
+            .and_then(|mut decompressor| decompressor.decompress(&chunk[CHUNK_HEADER_SIZE..], length))
+            .map_err(|e| corrupt(format!("Chunk of column {} does not decompress: {}", column, e)))?;
+        decode_values(&encoded).ok_or_else(|| corrupt(format!("Chunk of column {} has malformed values", column)))
+    }
+
+    /// A new dictionary for `column`, or `None` if training failed, in which
+    /// case chunks go on without one
+    fn trained_or_none(&self, column: &str, values: &[Vec<u8>]) -> Option<Arc<CompressionDictionary>> {
+        match self.train(column, values) {
+            Ok(dictionary) => Some(dictionary),
+            Err(e) => {
+                log::warn!("Compressing column {} without a new dictionary: {}", column, e);
+                None
+            }
+        }
+    }
+}
+
+fn compress_with(data: &[u8], dictionary: &[u8], level: i32) -> AuroraResult<Vec<u8>> {
+    zstd::bulk::Compressor::with_dictionary(level, dictionary)
+        .and_then(|mut compressor| compressor.compress(data))
+        .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Compression failed: {}", e)))
+}
+
+fn chunk(version: u32, encoded: &[u8], compressed: &[u8]) -> AuroraResult<Vec<u8>> {
+    let length = u32::try_from(encoded.len())
+        .map_err(|_| AuroraError::new(ErrorCode::StorageUnavailable, "Chunk exceeds 4 GiB".to_string()))?;
+    let mut chunk = Vec::with_capacity(CHUNK_HEADER_SIZE + compressed.len());
+    chunk.extend_from_slice(&version.to_le_bytes());
+    chunk.extend_from_slice(&length.to_le_bytes());
+    chunk.extend_from_slice(compressed);
+    Ok(chunk)
+}
+
+/// Values laid end to end, each after its 32-bit length
+fn encode_values(values: &[Vec<u8>]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(values.iter().map(|value| value.len() + 4).sum());
+    for value in values {
+        encoded.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        encoded.extend_from_slice(value);
+    }
+    encoded
+}
+
+fn decode_values(mut encoded: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut values = Vec::new();
+    while !encoded.is_empty() {
+        let length = u32::from_le_bytes(encoded.get(..4)?.try_into().ok()?) as usize;
+        values.push(encoded.get(4..4 + length)?.to_vec());
+        encoded = &encoded[4 + length..];
+    }
+    Some(values)
+}
@@ -81,11 +81,45 @@ pub struct StorageStats {
 }
 
 /// Storage engine types available in AuroraDB
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub enum StorageEngineType {
+    #[default]
     BTree,
     LSM,
     Hybrid,
+    Columnar,
+}
+
+/// Name used by the storage manager for engine selection and routing
+pub type EngineType = StorageEngineType;
+
+impl StorageEngineType {
+    /// Parse the engine name used in `CREATE TABLE ... USING <engine>`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "btree" => Some(Self::BTree),
+            "lsm" => Some(Self::LSM),
+            "hybrid" => Some(Self::Hybrid),
+            "columnar" => Some(Self::Columnar),
+            _ => None,
+        }
+    }
+
+    /// Canonical lowercase engine name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BTree => "btree",
+            Self::LSM => "lsm",
+            Self::Hybrid => "hybrid",
+            Self::Columnar => "columnar",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageEngineType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Configuration for storage engine selection and tuning
@@ -5,7 +5,7 @@
 
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use chrono::{DateTime, Utc};
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::storage_manager::TableStorageConfig;
@@ -107,6 +107,9 @@ pub struct LSMTree {
 
     // Table-specific configurations
     table_configs: RwLock<HashMap<String, TableStorageConfig>>,
+
+    // Per-table sorted runs, compacted by each table's strategy
+    table_compactions: RwLock<HashMap<String, Arc<Mutex<TableCompaction>>>>,
 }
 
 impl LSMTree {
@@ -143,6 +146,7 @@ impl LSMTree {
                 avg_write_amplification: 1.0,
             }),
             table_configs: RwLock::new(HashMap::new()),
+            table_compactions: RwLock::new(HashMap::new()),
         }
     }
 
@@ -152,6 +156,10 @@ impl LSMTree {
             let mut configs = self.table_configs.write();
             configs.insert(table_name.to_string(), config.clone());
         }
+        self.table_compactions.write().insert(table_name.to_string(), Arc::new(Mutex::new(TableCompaction::new(
+            CompactionStrategy::default(),
+            config.write_buffer_size_mb as u64 * 1024 * 1024,
+        ))));
 
         // Initialize level 0 for the table
         {
@@ -176,6 +184,20 @@ impl LSMTree {
 
     /// Write data to LSM tree (goes to memtable first)
     pub async fn write(&self, table_name: &str, key: &[u8], value: &[u8]) -> AuroraResult<()> {
+        self.write_entry(table_name, key, Some(value)).await
+    }
+
+    /// Write a value, or with `None` a tombstone
+    async fn write_entry(&self, table_name: &str, key: &[u8], value: Option<&[u8]>) -> AuroraResult<()> {
+        if let Some(table) = self.table_compaction(table_name) {
+            let mut table = table.lock();
+            match value {
+                Some(value) => table.put(key, value),
+                None => table.delete(key),
+            }
+        }
+        let value = value.unwrap_or(&[]);
+
         let mut memtable = self.memtable.write();
 
         // Check if memtable needs to be flushed
@@ -198,6 +220,11 @@ impl LSMTree {
 
     /// Read data from LSM tree (memtable -> L0 -> L1 -> ... -> LN)
     pub async fn read(&self, table_name: &str, key: &[u8]) -> AuroraResult<Option<Vec<u8>>> {
+        // A table's own runs hold its writes and tombstones
+        if let Some(table) = self.table_compaction(table_name) {
+            return Ok(table.lock().get(key));
+        }
+
         // Check memtable first (most recent data)
         {
             let memtable = self.memtable.read();
@@ -241,7 +268,31 @@ impl LSMTree {
     /// Delete data (tombstone approach)
     pub async fn delete(&self, table_name: &str, key: &[u8]) -> AuroraResult<()> {
         // Insert tombstone (empty value) into memtable
-        self.write(table_name, key, &[]).await
+        self.write_entry(table_name, key, None).await
+    }
+
+    /// Switch `table_name` to `strategy`, reorganizing its existing runs
+    /// into a layout the new strategy compacts from
+    pub fn set_compaction_strategy(&self, table_name: &str, strategy: CompactionStrategy) -> AuroraResult<ReorganizeReport> {
+        let table = self.existing_table_compaction(table_name)?;
+        let report = table.lock().set_strategy(strategy);
+        log::info!("Table '{}' switched to {:?} compaction: {} runs reorganized into {}, {} bytes rewritten",
+            table_name, strategy, report.runs_before, report.runs_after, report.bytes_rewritten);
+        Ok(report)
+    }
+
+    pub fn compaction_strategy(&self, table_name: &str) -> AuroraResult<CompactionStrategy> {
+        Ok(self.existing_table_compaction(table_name)?.lock().strategy())
+    }
+
+    /// Read, write and space amplification of `table_name`'s runs
+    pub fn compaction_metrics(&self, table_name: &str) -> AuroraResult<AmplificationMetrics> {
+        Ok(self.existing_table_compaction(table_name)?.lock().amplification())
+    }
+
+    /// Byte sizes of `table_name`'s runs in each level, oldest first
+    pub fn compaction_layout(&self, table_name: &str) -> AuroraResult<Vec<Vec<u64>>> {
+        Ok(self.existing_table_compaction(table_name)?.lock().layout())
     }
 
     /// Perform LSM compaction (merge levels)
@@ -275,6 +326,15 @@ impl LSMTree {
 
         let storage_used_gb = storage_used_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
 
+        let table_write_amplification: Vec<f64> = self.table_compactions.read().values()
+            .map(|table| table.lock().amplification().write)
+            .collect();
+        let avg_write_amplification = if table_write_amplification.is_empty() {
+            1.0
+        } else {
+            table_write_amplification.iter().sum::<f64>() / table_write_amplification.len() as f64
+        };
+
         Ok(LSMStats {
             memtable_size_mb: memtable.size_bytes as f64 / (1024.0 * 1024.0),
             total_files,
@@ -282,7 +342,7 @@ impl LSMTree {
             storage_used_gb,
             compression_ratio: 2.5, // Typical LSM compression ratio
             compaction_backlog: self.compaction_queue.read().len() as u64,
-            avg_write_amplification: 4.0, // Typical LSM write amplification
+            avg_write_amplification,
         })
     }
 
@@ -427,6 +487,15 @@ impl LSMTree {
         Ok(())
     }
 
+    fn table_compaction(&self, table_name: &str) -> Option<Arc<Mutex<TableCompaction>>> {
+        self.table_compactions.read().get(table_name).cloned()
+    }
+
+    fn existing_table_compaction(&self, table_name: &str) -> AuroraResult<Arc<Mutex<TableCompaction>>> {
+        self.table_compaction(table_name)
+            .ok_or_else(|| AuroraError::NotFound(format!("LSM table '{}' does not exist", table_name)))
+    }
+
     fn generate_file_id(&self) -> u64 {
         use std::time::{SystemTime, UNIX_EPOCH};
         SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64
@@ -444,6 +513,294 @@ impl LSMTree {
     }
 }
 
+/// Levels a table's sorted runs are arranged in
+pub const COMPACTION_LEVELS: usize = 7;
+
+/// How a table's sorted runs are merged, trading write amplification
+/// against read and space amplification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompactionStrategy {
+    /// Flushes collect in L0 until `l0_trigger` runs, then merge into L1;
+    /// every lower level is one sorted run `fanout` times larger than the
+    /// last. Lowest read and space amplification, highest write.
+    Leveled { l0_trigger: usize, base_level_bytes: u64, fanout: u64 },
+    /// Size-tiered (universal): `merge_width` runs of a tier merge into one
+    /// run of the next. Lowest write amplification, highest read and space.
+    Tiered { merge_width: usize },
+    /// Tiered in the first `tiered_levels` levels, where fresh data churns,
+    /// and leveled below them
+    Hybrid { tiered_levels: usize, merge_width: usize, base_level_bytes: u64, fanout: u64 },
+}
+
+impl CompactionStrategy {
+    /// Leveled with RocksDB-like defaults: 4 L0 runs, 128MB L1, fanout 10
+    pub fn leveled() -> Self {
+        Self::Leveled { l0_trigger: 4, base_level_bytes: 128 * 1024 * 1024, fanout: 10 }
+    }
+
+    /// Tiered merging 4 runs at a time
+    pub fn tiered() -> Self {
+        Self::Tiered { merge_width: 4 }
+    }
+
+    /// Two tiered levels over leveled ones
+    pub fn hybrid() -> Self {
+        Self::Hybrid { tiered_levels: 2, merge_width: 4, base_level_bytes: 128 * 1024 * 1024, fanout: 10 }
+    }
+
+    /// Strategy named `leveled`, `tiered` (or `universal`) or `hybrid`, with
+    /// default parameters
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "leveled" => Some(Self::leveled()),
+            "tiered" | "universal" => Some(Self::tiered()),
+            "hybrid" => Some(Self::hybrid()),
+            _ => None,
+        }
+    }
+
+    /// How runs in `level` are merged
+    pub fn level_policy(&self, level: usize) -> LevelPolicy {
+        let leveled = |first_leveled: usize, base_level_bytes: u64, fanout: u64| LevelPolicy::Leveled {
+            max_bytes: base_level_bytes.saturating_mul(fanout.saturating_pow((level - first_leveled) as u32)),
+        };
+        match *self {
+            Self::Leveled { l0_trigger, .. } if level == 0 => LevelPolicy::Tiered { merge_width: l0_trigger },
+            Self::Leveled { base_level_bytes, fanout, .. } => leveled(1, base_level_bytes, fanout),
+            Self::Tiered { merge_width } => LevelPolicy::Tiered { merge_width },
+            Self::Hybrid { tiered_levels, merge_width, .. } if level < tiered_levels => LevelPolicy::Tiered { merge_width },
+            Self::Hybrid { tiered_levels, base_level_bytes, fanout, .. } => leveled(tiered_levels, base_level_bytes, fanout),
+        }
+    }
+}
+
+impl Default for CompactionStrategy {
+    fn default() -> Self {
+        Self::leveled()
+    }
+}
+
+/// How one level's runs are merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelPolicy {
+    /// Runs pile up until `merge_width` of them merge into the next level
+    Tiered { merge_width: usize },
+    /// A single run, merged into the next level once over `max_bytes`
+    Leveled { max_bytes: u64 },
+}
+
+/// An immutable sorted run; `None` values are tombstones
+#[derive(Debug, Clone)]
+pub struct SortedRun {
+    pub id: u64,
+    pub entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    pub bytes: u64,
+}
+
+/// Read, write and space amplification of a table's runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmplificationMetrics {
+    /// Bytes flushed and rewritten by compaction per byte written
+    pub write: f64,
+    /// Runs a lookup of an absent key probes
+    pub read: f64,
+    /// Bytes stored per byte of live data
+    pub space: f64,
+    pub bytes_ingested: u64,
+    pub bytes_written: u64,
+    pub live_bytes: u64,
+    pub stored_bytes: u64,
+}
+
+/// Outcome of switching a table's strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorganizeReport {
+    pub runs_before: usize,
+    pub runs_after: usize,
+    pub bytes_rewritten: u64,
+}
+
+/// One table's memtable and sorted runs, compacted by its strategy.
+/// `levels[0]` takes flushes; within a level runs go oldest to newest, and
+/// every level holds newer data than the levels below it.
+pub struct TableCompaction {
+    strategy: CompactionStrategy,
+    memtable: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    memtable_bytes: u64,
+    memtable_limit: u64,
+    levels: Vec<Vec<SortedRun>>,
+    next_run_id: u64,
+    bytes_ingested: u64,
+    bytes_written: u64,
+}
+
+impl TableCompaction {
+    /// Table flushing its memtable at `memtable_limit` bytes
+    pub fn new(strategy: CompactionStrategy, memtable_limit: u64) -> Self {
+        Self {
+            strategy,
+            memtable: BTreeMap::new(),
+            memtable_bytes: 0,
+            memtable_limit: memtable_limit.max(1),
+            levels: vec![Vec::new(); COMPACTION_LEVELS],
+            next_run_id: 1,
+            bytes_ingested: 0,
+            bytes_written: 0,
+        }
+    }
+
+    pub fn strategy(&self) -> CompactionStrategy {
+        self.strategy
+    }
+
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.insert(key, Some(value.to_vec()));
+    }
+
+    pub fn delete(&mut self, key: &[u8]) {
+        self.insert(key, None);
+    }
+
+    /// Latest value of `key`
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if let Some(value) = self.memtable.get(key) {
+            return value.clone();
+        }
+        self.levels.iter()
+            .flat_map(|runs| runs.iter().rev())
+            .find_map(|run| run.entries.get(key))
+            .cloned()
+            .flatten()
+    }
+
+    /// Write the memtable out as a run and compact what that makes due
+    pub fn flush(&mut self) {
+        if self.memtable.is_empty() {
+            return;
+        }
+        let entries = std::mem::take(&mut self.memtable);
+        self.memtable_bytes = 0;
+        let drop_tombstones = self.levels.iter().all(|runs| runs.is_empty());
+        let run = self.new_run(entries, drop_tombstones);
+        self.bytes_written += run.bytes;
+        if !run.entries.is_empty() {
+            self.levels[0].push(run);
+        }
+        self.compact();
+    }
+
+    /// Byte sizes of the runs in each level, oldest first
+    pub fn layout(&self) -> Vec<Vec<u64>> {
+        self.levels.iter().map(|runs| runs.iter().map(|run| run.bytes).collect()).collect()
+    }
+
+    /// Switch to `strategy`. All runs are merged into one in the bottom
+    /// level, a layout every strategy can grow from; the memtable is kept.
+    pub fn set_strategy(&mut self, strategy: CompactionStrategy) -> ReorganizeReport {
+        let runs_before = self.levels.iter().map(Vec::len).sum();
+        let inputs: Vec<SortedRun> = self.levels.iter_mut().rev().flat_map(std::mem::take).collect();
+        let merged = self.merge(inputs, true);
+        let bytes_rewritten = merged.bytes;
+        self.bytes_written += bytes_rewritten;
+        if !merged.entries.is_empty() {
+            self.levels[COMPACTION_LEVELS - 1].push(merged);
+        }
+        self.strategy = strategy;
+        self.compact();
+        ReorganizeReport { runs_before, runs_after: self.levels.iter().map(Vec::len).sum(), bytes_rewritten }
+    }
+
+    pub fn amplification(&self) -> AmplificationMetrics {
+        let runs = self.levels.iter().map(Vec::len).sum::<usize>();
+        let stored_bytes = self.levels.iter().flatten().map(|run| run.bytes).sum::<u64>();
+        let mut live = BTreeMap::new();
+        for run in self.levels.iter().rev().flatten() {
+            for (key, value) in &run.entries {
+                live.insert(key, value);
+            }
+        }
+        let live_bytes = live.iter()
+            .filter_map(|(key, value)| value.as_ref().map(|value| (key.len() + value.len()) as u64))
+            .sum::<u64>();
+        let ratio = |numerator: u64, denominator: u64| {
+            if denominator == 0 { 1.0 } else { numerator as f64 / denominator as f64 }
+        };
+        AmplificationMetrics {
+            write: ratio(self.bytes_written, self.bytes_ingested),
+            read: runs as f64,
+            space: ratio(stored_bytes, live_bytes),
+            bytes_ingested: self.bytes_ingested,
+            bytes_written: self.bytes_written,
+            live_bytes,
+            stored_bytes,
+        }
+    }
+
+    fn insert(&mut self, key: &[u8], value: Option<Vec<u8>>) {
+        let bytes = entry_bytes(key, &value);
+        self.bytes_ingested += bytes;
+        self.memtable_bytes += bytes;
+        self.memtable.insert(key.to_vec(), value);
+        if self.memtable_bytes >= self.memtable_limit {
+            self.flush();
+        }
+    }
+
+    /// Merge every level that is due into the one below, top down
+    fn compact(&mut self) {
+        for level in 0..COMPACTION_LEVELS {
+            let bottom = level + 1 == COMPACTION_LEVELS;
+            let due = match self.strategy.level_policy(level) {
+                LevelPolicy::Tiered { merge_width } => self.levels[level].len() >= merge_width.max(2),
+                LevelPolicy::Leveled { max_bytes } => {
+                    !bottom && self.levels[level].iter().map(|run| run.bytes).sum::<u64>() > max_bytes
+                }
+            };
+            if !due {
+                continue;
+            }
+
+            // The bottom level merges into itself
+            let target = if bottom { level } else { level + 1 };
+            let mut inputs = Vec::new();
+            if target != level && matches!(self.strategy.level_policy(target), LevelPolicy::Leveled { .. }) {
+                inputs.append(&mut self.levels[target]);
+            }
+            inputs.append(&mut self.levels[level]);
+            let drop_tombstones = self.levels[target..].iter().all(|runs| runs.is_empty());
+            let run = self.merge(inputs, drop_tombstones);
+            self.bytes_written += run.bytes;
+            if !run.entries.is_empty() {
+                self.levels[target].push(run);
+            }
+        }
+    }
+
+    /// One run of `inputs`, oldest first, keeping each key's newest value;
+    /// tombstones go once nothing older remains for them to hide
+    fn merge(&mut self, inputs: Vec<SortedRun>, drop_tombstones: bool) -> SortedRun {
+        let mut entries = BTreeMap::new();
+        for run in inputs {
+            entries.extend(run.entries);
+        }
+        self.new_run(entries, drop_tombstones)
+    }
+
+    fn new_run(&mut self, mut entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>, drop_tombstones: bool) -> SortedRun {
+        if drop_tombstones {
+            entries.retain(|_, value| value.is_some());
+        }
+        let id = self.next_run_id;
+        self.next_run_id += 1;
+        let bytes = entries.iter().map(|(key, value)| entry_bytes(key, value)).sum();
+        SortedRun { id, entries, bytes }
+    }
+}
+
+fn entry_bytes(key: &[u8], value: &Option<Vec<u8>>) -> u64 {
+    (key.len() + value.as_ref().map_or(0, Vec::len)) as u64
+}
+
 /// Compaction statistics
 #[derive(Debug, Clone)]
 pub struct CompactionStats {
@@ -10,21 +10,31 @@
 //! - Adaptive Compression: Multiple compression algorithms with runtime adaptation
 
 pub mod buffer_pool;
+pub mod buffer_usage;
+pub mod readahead;
 pub mod page_manager;
 pub mod wal_logger;
+pub mod wal_sync;
 pub mod lsm_tree;
 pub mod btree_storage;
 pub mod compression_engine;
 pub mod storage_manager;
 pub mod recovery_manager;
 pub mod table_storage;
+pub mod columnar;
+pub mod large_object;
 
 pub use buffer_pool::*;
+pub use buffer_usage::{BufferCounts, BufferUsage, PlanBuffers};
+pub use readahead::{ReadaheadConfig, ReadaheadTracker};
 pub use page_manager::*;
 pub use wal_logger::*;
+pub use wal_sync::{SyncLatency, WalSyncMethod};
 pub use lsm_tree::*;
 pub use btree_storage::*;
 pub use compression_engine::*;
 pub use storage_manager::*;
 pub use recovery_manager::*;
-pub use table_storage::*;
\ No newline at end of file
+pub use table_storage::*;
+pub use columnar::ColumnarStorageEngine;
+pub use large_object::{LargeObjectStore, MAX_LOB_CHUNK};
\ No newline at end of file
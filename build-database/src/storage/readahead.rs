@@ -0,0 +1,140 @@
+//! Readahead: Adaptive Sequential Prefetch Windows
+//!
+//! Watches the page ids a buffer pool is asked for and decides which pages
+//! to read ahead of the scan. A run of `trigger` consecutive page ids starts
+//! readahead with `initial_window` pages; each time the reader gets within
+//! half a window of the prefetched end the window doubles, up to
+//! `max_window`. Any non-sequential access resets the run and the window,
+//! so random access never prefetches.
+
+use std::ops::Range;
+
+/// Readahead tuning
+#[derive(Debug, Clone)]
+pub struct ReadaheadConfig {
+    /// Consecutive page accesses before readahead starts
+    pub trigger: u64,
+    /// Pages prefetched when readahead starts
+    pub initial_window: u64,
+    /// Largest window the readahead grows to
+    pub max_window: u64,
+}
+
+impl Default for ReadaheadConfig {
+    fn default() -> Self {
+        Self {
+            trigger: 4,
+            initial_window: 8,
+            max_window: 256,
+        }
+    }
+}
+
+/// Sequential access detector for one stream of page reads
+#[derive(Debug)]
+pub struct ReadaheadTracker {
+    config: ReadaheadConfig,
+    last: Option<u64>,
+    /// Length of the current run of consecutive pages
+    run: u64,
+    /// Current window, 0 while readahead is off
+    window: u64,
+    /// End (exclusive) of the pages already requested for prefetch
+    prefetched_until: u64,
+}
+
+impl ReadaheadTracker {
+    pub fn new(config: ReadaheadConfig) -> Self {
+        Self { config, last: None, run: 0, window: 0, prefetched_until: 0 }
+    }
+
+    /// Current window size, 0 while readahead is off
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Record an access to `page_id`; returns the pages to prefetch, if any
+    pub fn on_access(&mut self, page_id: u64) -> Option<Range<u64>> {
+        if self.last == Some(page_id) {
+            // Re-reading the same page neither extends nor breaks the run
+            return None;
+        }
+        let sequential = self.last.is_some_and(|last| last.checked_add(1) == Some(page_id));
+        self.last = Some(page_id);
+
+        if !sequential {
+            self.run = 1;
+            self.window = 0;
+            self.prefetched_until = 0;
+            return None;
+        }
+
+        self.run += 1;
+        if self.run < self.config.trigger.max(2) {
+            return None;
+        }
+
+        if self.window == 0 {
+            self.window = self.config.initial_window.max(1);
+        } else if page_id + self.window / 2 < self.prefetched_until {
+            // Still well inside the prefetched range
+            return None;
+        } else {
+            self.window = (self.window * 2).min(self.config.max_window.max(1));
+        }
+
+        let start = (page_id + 1).max(self.prefetched_until);
+        let end = page_id + 1 + self.window;
+        self.prefetched_until = end;
+        (start < end).then_some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_windows() -> ReadaheadTracker {
+        ReadaheadTracker::new(ReadaheadConfig { trigger: 3, initial_window: 4, max_window: 16 })
+    }
+
+    #[test]
+    fn test_window_grows_on_sequential_access() {
+        let mut tracker = small_windows();
+        let prefetched: Vec<_> = (0..40).filter_map(|page| tracker.on_access(page)).collect();
+
+        // Starts at the third consecutive page, then doubles up to the cap
+        assert_eq!(prefetched[0], 3..7);
+        assert_eq!(prefetched[1], 7..14);
+        assert_eq!(prefetched[2], 14..27);
+        assert!(prefetched.iter().all(|range| range.end - range.start <= 16));
+        assert_eq!(tracker.window(), 16);
+
+        // Every page past the trigger is requested exactly once, in order
+        let pages: Vec<u64> = prefetched.iter().cloned().flatten().collect();
+        assert_eq!(pages.first(), Some(&3));
+        assert!(pages.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+
+    #[test]
+    fn test_random_access_resets_window() {
+        let mut tracker = small_windows();
+        for page in 0..10 {
+            tracker.on_access(page);
+        }
+        assert!(tracker.window() > 0);
+
+        assert_eq!(tracker.on_access(500), None);
+        assert_eq!(tracker.window(), 0);
+
+        // Strided access never looks sequential
+        assert!((0..200u64).all(|i| tracker.on_access(i * 7 % 1000).is_none()));
+
+        // Repeating the current page is neutral
+        let mut tracker = small_windows();
+        for page in [0, 1, 1, 1] {
+            assert_eq!(tracker.on_access(page), None);
+        }
+        assert_eq!(tracker.on_access(2), Some(3..7));
+    }
+}
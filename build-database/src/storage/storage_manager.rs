@@ -4,9 +4,11 @@
 //! - B+ Tree Storage (row-oriented, transactional)
 //! - LSM Tree Storage (write-optimized, analytical)
 //! - Hybrid Storage (adaptive selection)
+//! - Columnar Storage (column-oriented, scan-heavy analytics)
 //!
 //! The StorageManager provides:
-//! - Engine selection and routing based on workload
+//! - Engine selection and routing based on workload, or pinned per table
+//!   with `CREATE TABLE ... USING {btree|lsm|hybrid|columnar}`
 //! - Unified data access API
 //! - Cross-engine consistency and transactions
 //! - Storage tiering and optimization
@@ -16,10 +18,11 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use tokio::sync::RwLock as AsyncRwLock;
 use crate::core::{AuroraResult, AuroraError};
-use crate::storage::engine::{StorageEngine, EngineType};
+use crate::storage::engine::{StorageEngine, StorageEngineConfig, EngineType};
 use crate::storage::btree::BTreeStorageEngine;
 use crate::storage::lsm::LSMTreeStorageEngine;
 use crate::storage::hybrid::HybridStorageEngine;
+use crate::storage::columnar::ColumnarStorageEngine;
 use crate::transaction::{Transaction, TransactionId};
 
 /// Unified storage manager that orchestrates all storage engines
@@ -78,6 +81,19 @@ impl StorageManager {
         let hybrid_engine = Arc::new(HybridStorageEngine::new(&config.hybrid, engines.clone()).await?);
         engines.insert(EngineType::Hybrid, hybrid_engine);
 
+        // Initialize Columnar engine (scan-heavy analytics)
+        let columnar_engine = Arc::new(ColumnarStorageEngine::new(StorageEngineConfig {
+            engine_type: EngineType::Columnar,
+            page_size: config.btree.page_size_kb * 1024,
+            cache_size: config.columnar.cache_size_mb * 1024 * 1024,
+            max_file_size: (config.btree.max_table_size_mb as u64) * 1024 * 1024,
+            compaction_threshold: 0.5,
+            enable_compression: config.columnar.enable_compression,
+            enable_encryption: false,
+            write_ahead_log: true,
+        }));
+        engines.insert(EngineType::Columnar, columnar_engine);
+
         // Create engine selection strategy
         let selection_strategy = EngineSelectionStrategy::new(config)?;
 
@@ -99,7 +115,7 @@ impl StorageManager {
         };
 
         println!("✅ Unified Storage Manager initialized!");
-        println!("   • Engines: B+ Tree, LSM Tree, Hybrid");
+        println!("   • Engines: B+ Tree, LSM Tree, Hybrid, Columnar");
         println!("   • Strategy: {}", config.selection_strategy);
         println!("   • Tables: {} registered", manager.table_engine_mapping.read().len());
 
@@ -108,10 +124,18 @@ impl StorageManager {
 
     /// Create a new table with the specified schema
     pub async fn create_table(&self, table_name: &str, schema: &crate::engine::TableSchema) -> AuroraResult<()> {
+        self.create_table_using(table_name, schema, None).await.map(|_| ())
+    }
+
+    /// Create a new table, pinning it to `engine` when given.
+    ///
+    /// A pinned engine overrides the workload-based selection. Returns the
+    /// engine the table was placed on.
+    pub async fn create_table_using(&self, table_name: &str, schema: &crate::engine::TableSchema, engine: Option<EngineType>) -> AuroraResult<EngineType> {
         println!("📋 Creating table: {}", table_name);
 
         // Determine which engine to use for this table
-        let engine_type = self.selection_strategy.select_engine_for_table(table_name, schema).await?;
+        let engine_type = self.resolve_table_engine(table_name, schema.has_vector_columns(), engine);
 
         // Get the appropriate engine
         let engine = self.engines.get(&engine_type)
@@ -128,7 +152,29 @@ impl StorageManager {
         // Update metrics
         self.metrics.record_table_creation().await;
 
-        Ok(())
+        Ok(engine_type)
+    }
+
+    /// Engine a new table will be placed on: the pinned engine if requested,
+    /// otherwise the selection strategy's choice
+    pub fn resolve_table_engine(&self, table_name: &str, has_vector_columns: bool, requested: Option<EngineType>) -> EngineType {
+        requested.unwrap_or_else(|| self.selection_strategy.select_engine(table_name, has_vector_columns))
+    }
+
+    /// Record the engine for a table whose data is managed outside the
+    /// storage manager (e.g. the SQL table storage), so routing stays consistent
+    pub fn register_table_engine(&self, table_name: &str, engine: EngineType) {
+        self.table_engine_mapping.write().insert(table_name.to_string(), engine);
+    }
+
+    /// Forget the engine registration for a table
+    pub fn unregister_table_engine(&self, table_name: &str) {
+        self.table_engine_mapping.write().remove(table_name);
+    }
+
+    /// Engine currently serving a table
+    pub fn table_engine(&self, table_name: &str) -> Option<EngineType> {
+        self.table_engine_mapping.read().get(table_name).copied()
     }
 
     /// Drop a table
@@ -328,25 +374,25 @@ impl EngineSelectionStrategy {
         })
     }
 
-    async fn select_engine_for_table(&self, table_name: &str, schema: &crate::engine::TableSchema) -> AuroraResult<EngineType> {
+    fn select_engine(&self, table_name: &str, has_vector_columns: bool) -> EngineType {
         match self.strategy {
             SelectionStrategy::WorkloadBased => {
                 // Analyze schema to determine best engine
-                if schema.has_vector_columns() {
-                    Ok(EngineType::Hybrid) // Vector data needs hybrid capabilities
+                if has_vector_columns {
+                    EngineType::Hybrid // Vector data needs hybrid capabilities
                 } else if table_name.contains("analytics") || table_name.contains("log") {
-                    Ok(EngineType::LSM) // Analytical workloads prefer LSM
+                    EngineType::LSM // Analytical workloads prefer LSM
                 } else {
-                    Ok(EngineType::BTree) // Default to B+ Tree for transactional workloads
+                    EngineType::BTree // Default to B+ Tree for transactional workloads
                 }
             }
             SelectionStrategy::SizeBased => {
                 // Could be based on expected table size
-                Ok(EngineType::BTree) // Default for now
+                EngineType::BTree // Default for now
             }
             SelectionStrategy::Manual => {
-                // Would require manual configuration
-                Ok(EngineType::BTree) // Default for now
+                // Tables must be pinned with USING; unpinned tables get the default
+                EngineType::BTree
             }
         }
     }
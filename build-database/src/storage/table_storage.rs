@@ -11,7 +11,11 @@ use crate::storage::btree::engine::WorkingBTreeEngine;
 use crate::storage::wal_logger::{WALLogger, WALRecord};
 use crate::catalog::{TableCatalog, ColumnMetadata};
 use crate::types::DataValue;
-use crate::mvcc::{TransactionManager, TransactionId, TupleVersionChain, VersionedTuple, VisibilityChecker};
+use crate::engine::query_progress::{OperatorKind, ProgressTicker, QueryProgress};
+use crate::storage::buffer_pool::{BufferPool, PAGE_SIZE};
+use crate::storage::buffer_usage::{pages_for, BufferUsage};
+use crate::storage::wal_logger::LoggedRecord;
+use crate::mvcc::{TransactionManager, TransactionId, TupleVersionChain, VersionedTuple, VisibilityChecker, VisibilityError};
 use std::collections::HashMap;
 
 /// Row data stored in a table
@@ -25,6 +29,38 @@ pub struct TableRow {
     pub data: HashMap<String, DataValue>,
 }
 
+/// What [`TableStorage::purge_dead_tuple`] did with a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurgeOutcome {
+    /// Removed from storage
+    Purged,
+    /// Deleted, but a running transaction may still see it
+    Pinned,
+    /// Gone already, or has a version that is not deleted
+    Live,
+}
+
+/// Running tuple counts and sizes of a table, kept up to date by every
+/// write so bloat can be estimated without reading the table. Sizes are of
+/// the serialized row data; aborted writes are counted as if they committed,
+/// so the figures are estimates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TupleStats {
+    /// Rows whose current version is not deleted
+    pub live_tuples: u64,
+    /// Versions superseded by an update or a delete, not yet purged
+    pub dead_tuples: u64,
+    /// Bytes of the live versions
+    pub live_bytes: u64,
+    /// Bytes of the dead versions
+    pub dead_bytes: u64,
+    /// Bytes freed by purging dead rows and not yet reused by inserts
+    pub free_bytes: u64,
+    /// Index entries left pointing at versions updated or deleted since
+    /// the table's indexes were last rebuilt
+    pub stale_index_entries: u64,
+}
+
 /// Table storage manager with MVCC and WAL durability
 pub struct TableStorage {
     /// Underlying storage engine
@@ -35,21 +71,110 @@ pub struct TableStorage {
     wal_logger: Arc<WALLogger>,
     /// Transaction manager for MVCC
     transaction_manager: Arc<TransactionManager>,
+    /// Per-table counter bumped by every write, so cached results can tell
+    /// whether the rows they were computed from have changed
+    data_versions: parking_lot::RwLock<HashMap<String, u64>>,
+    /// Tuple counts of tables tracked since they were created or measured
+    tuple_stats: parking_lot::RwLock<HashMap<String, TupleStats>>,
+    /// Shared buffers scanned table pages are cached in
+    shared_buffers: Arc<BufferPool>,
 }
 
 impl TableStorage {
     /// Create a new table storage manager with MVCC and WAL durability
-    pub fn new(storage_engine: Arc<WorkingBTreeEngine>, catalog: Arc<TableCatalog>, wal_logger: Arc<WALLogger>) -> Self {
+    pub fn new(storage_engine: Arc<WorkingBTreeEngine>, catalog: Arc<TableCatalog>, wal_logger: Arc<WALLogger>, shared_buffers: Arc<BufferPool>) -> Self {
         Self {
             storage_engine,
             catalog,
             wal_logger,
             transaction_manager: Arc::new(TransactionManager::new()),
+            data_versions: parking_lot::RwLock::new(HashMap::new()),
+            tuple_stats: parking_lot::RwLock::new(HashMap::new()),
+            shared_buffers,
         }
     }
 
+    /// Current data version of a table
+    pub fn data_version(&self, table_name: &str) -> u64 {
+        self.data_versions.read().get(table_name).copied().unwrap_or(0)
+    }
+
+    /// Mark a table's data as changed. Writes do this themselves; callers
+    /// bump again once the writing transaction commits, so a read that
+    /// raced the commit cannot be cached under the new version.
+    pub fn bump_data_version(&self, table_name: &str) {
+        *self.data_versions.write().entry(table_name.to_string()).or_insert(0) += 1;
+    }
+
+    /// Tuple counts of a table, if they have been tracked since it was
+    /// created or last measured
+    pub fn tuple_stats(&self, table_name: &str) -> Option<TupleStats> {
+        self.tuple_stats.read().get(table_name).copied()
+    }
+
+    /// Start tracking a new, empty table
+    pub fn track_tuple_stats(&self, table_name: &str) {
+        self.tuple_stats.write().insert(table_name.to_string(), TupleStats::default());
+    }
+
+    /// Stop tracking a dropped table
+    pub fn forget_tuple_stats(&self, table_name: &str) {
+        self.tuple_stats.write().remove(table_name);
+    }
+
+    /// Count a table's tuples by reading every version chain, and track the
+    /// table from then on. Used for tables whose counts were lost with a
+    /// restart. Space freed before then is not known, and every dead version
+    /// is assumed to have left an index entry behind.
+    pub async fn measure_tuple_stats(&self, table_name: &str) -> AuroraResult<TupleStats> {
+        let table_prefix = format!("table:{}:", table_name);
+        let mut stats = TupleStats::default();
+        for (_, data) in self.storage_engine.scan_prefix(&table_prefix).await? {
+            let version_chain: TupleVersionChain = bincode::deserialize(&data)
+                .map_err(|e| AuroraError::new(ErrorCode::StorageCorruption, format!("Deserialization error: {}", e)))?;
+            for version in version_chain.all_versions() {
+                let bytes = Self::version_bytes(version);
+                if version.xmax.is_none() {
+                    stats.live_tuples += 1;
+                    stats.live_bytes += bytes;
+                } else {
+                    stats.dead_tuples += 1;
+                    stats.dead_bytes += bytes;
+                }
+            }
+        }
+        stats.stale_index_entries = stats.dead_tuples;
+        self.tuple_stats.write().insert(table_name.to_string(), stats);
+        Ok(stats)
+    }
+
+    /// Forget the stale index entries of a table whose indexes were rebuilt
+    pub fn reset_stale_index_entries(&self, table_name: &str) {
+        if let Some(stats) = self.tuple_stats.write().get_mut(table_name) {
+            stats.stale_index_entries = 0;
+        }
+    }
+
+    /// Apply a write to a tracked table's tuple counts
+    fn track_write(&self, table_name: &str, apply: impl FnOnce(&mut TupleStats)) {
+        if let Some(stats) = self.tuple_stats.write().get_mut(table_name) {
+            apply(stats);
+        }
+    }
+
+    /// Serialized size of a version's row data
+    fn version_bytes(version: &VersionedTuple) -> u64 {
+        bincode::serialized_size(&version.data).unwrap_or(0)
+    }
+
     /// Insert a row into a table with MVCC and WAL durability
-    pub async fn insert_row(&self, transaction: &crate::mvcc::transaction::Transaction, table_name: &str, row_data: HashMap<String, DataValue>) -> AuroraResult<()> {
+    pub async fn insert_row(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        row_data: HashMap<String, DataValue>,
+        buffers: Option<&BufferUsage>,
+    ) -> AuroraResult<()> {
         // Verify table exists
         if !self.catalog.table_exists(table_name).await {
             return Err(AuroraError::new(
@@ -67,20 +192,35 @@ impl TableStorage {
         // Create new versioned tuple
         let primary_key = self.extract_primary_key(&validated_data, &columns)?;
         let versioned_tuple = VersionedTuple::new(primary_key.clone(), validated_data, transaction.id);
-
-        // Check for existing tuple with same primary key
-        let existing_chain = self.get_tuple_chain(table_name, &primary_key).await?;
-        if let Some(chain) = existing_chain {
-            if chain.is_visible_to(transaction, &self.transaction_manager) {
+        let inserted_bytes = Self::version_bytes(&versioned_tuple);
+
+        // Check for existing tuple with same primary key. An expired row
+        // the reaper has not removed yet is replaced rather than conflicting.
+        let ttl_column = self.catalog.get_ttl_column(table_name).await;
+        let now = chrono::Utc::now();
+        let mut existing_chain = self.get_tuple_chain(table_name, &primary_key).await?;
+        if let Some(chain) = existing_chain.as_mut() {
+            chain.discard_aborted(&self.transaction_manager);
+            let live = chain.visible_version(transaction, &self.transaction_manager)
+                .is_some_and(|version| !Self::is_expired(&version.data, ttl_column.as_deref(), now));
+            if live {
                 return Err(AuroraError::new(
                     ErrorCode::ConstraintViolation,
                     format!("Primary key violation: tuple already exists in table '{}'", table_name)
                 ));
             }
+            VisibilityChecker::check_write_conflict(chain, transaction, &self.transaction_manager)
+                .map_err(|e| Self::write_conflict_error(e, transaction.id, table_name))?;
         }
 
-        // Create version chain if it doesn't exist
-        let mut version_chain = existing_chain.unwrap_or_else(|| TupleVersionChain::new(versioned_tuple.clone()));
+        // Re-inserting a deleted key appends to its chain so older snapshots keep their view
+        let version_chain = match existing_chain {
+            Some(mut chain) if !chain.is_empty() => {
+                chain.add_version(versioned_tuple);
+                chain
+            }
+            _ => TupleVersionChain::new(versioned_tuple),
+        };
 
         // Serialize version chain
         let serialized_data = bincode::serialize(&version_chain)
@@ -90,12 +230,22 @@ impl TableStorage {
         let storage_key = self.generate_tuple_key(table_name, &primary_key);
 
         // Log the operation to WAL BEFORE storing (write-ahead logging)
-        self.wal_logger.log_insert(transaction.id, table_name, &storage_key, &serialized_data).await
+        let logged = self.wal_logger.log_insert(transaction.id, table_name, &storage_key, &serialized_data).await
             .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("WAL logging failed: {}", e)))?;
+        Self::record_write_io(buffers, &logged, serialized_data.len());
 
         // Store in B+ Tree
+        self.transaction_manager.record_write(transaction.id, &String::from_utf8_lossy(&storage_key));
+        self.transaction_manager.record_write(transaction.id, &Self::table_predicate_key(table_name));
         self.storage_engine.insert(storage_key, serialized_data).await?;
 
+        // New rows fill space freed by purges first
+        self.track_write(table_name, |stats| {
+            stats.live_tuples += 1;
+            stats.live_bytes += inserted_bytes;
+            stats.free_bytes = stats.free_bytes.saturating_sub(inserted_bytes);
+        });
+        self.bump_data_version(table_name);
         log::debug!("Inserted row into table '{}': {:?}", table_name, primary_key);
         Ok(())
     }
@@ -118,28 +268,288 @@ impl TableStorage {
 
     /// Scan all visible rows in a table using MVCC
     pub async fn scan_table(&self, transaction: &crate::mvcc::transaction::Transaction, table_name: &str) -> AuroraResult<Vec<HashMap<String, DataValue>>> {
+        self.scan_table_instrumented(transaction, table_name, None, None).await
+    }
+
+    /// Scan all visible rows in a table, reporting rows and bytes read to the
+    /// query's progress entry and page accesses to the plan node's buffers
+    pub async fn scan_table_instrumented(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        progress: Option<&QueryProgress>,
+        buffers: Option<&BufferUsage>,
+    ) -> AuroraResult<Vec<HashMap<String, DataValue>>> {
         // Generate table prefix for scanning
         let table_prefix = format!("table:{}:", table_name);
 
         // Get all keys with this prefix
         let all_data = self.storage_engine.scan_prefix(&table_prefix).await?;
 
+        let operator = progress.map(|query| {
+            let bytes_total = all_data.iter().map(|(_, data)| data.len() as u64).sum();
+            query.start_operator(OperatorKind::Scan, &format!("scan {}", table_name), Some(all_data.len() as u64), Some(bytes_total))
+        });
+        let mut ticker = ProgressTicker::new(operator.as_deref());
+
+        // Rows are packed into pages in key order. Pages are keyed by the
+        // table's data version, so images cached before a write are never hit
+        // after it and age out of the pool instead.
+        let data_version = self.data_version(table_name);
+        let mut page_no = 0u64;
+        let mut page = Vec::with_capacity(PAGE_SIZE as usize);
+
+        // A scan reads the whole predicate, so concurrent inserts conflict with it
+        self.transaction_manager.record_read(transaction.id, &Self::table_predicate_key(table_name));
+
+        // Expired rows are hidden as soon as they expire, before the reaper deletes them
+        let ttl_column = self.catalog.get_ttl_column(table_name).await;
+        let now = chrono::Utc::now();
+
         let mut visible_rows = Vec::new();
-        for (_, data) in all_data {
+        for (key, data) in all_data {
+            ticker.tick(data.len() as u64);
+            page.extend_from_slice(&data);
+            if page.len() as u64 >= PAGE_SIZE {
+                self.access_shared_page(table_name, data_version, page_no, std::mem::take(&mut page), buffers).await;
+                page_no += 1;
+            }
             let version_chain: TupleVersionChain = bincode::deserialize(&data)
                 .map_err(|e| AuroraError::new(ErrorCode::StorageCorruption, format!("Deserialization error: {}", e)))?;
 
             // Get the visible version for this transaction
             if let Some(visible_version) = version_chain.visible_version(transaction, &self.transaction_manager) {
+                if Self::is_expired(&visible_version.data, ttl_column.as_deref(), now) {
+                    continue;
+                }
+                self.transaction_manager.record_read(transaction.id, &String::from_utf8_lossy(&key));
                 visible_rows.push(visible_version.data.clone());
             }
         }
+        if !page.is_empty() {
+            self.access_shared_page(table_name, data_version, page_no, page, buffers).await;
+        }
+        ticker.publish();
+        if let Some(operator) = &operator {
+            operator.finish();
+        }
 
         Ok(visible_rows)
     }
 
+    /// Up to `limit` stored tuples after `after_key`, in key order, with the
+    /// visible, unexpired row of each (if any) and the key to resume from;
+    /// the resume key is `None` once the table is exhausted. Lets a caller
+    /// walk a large table without holding all of it.
+    pub async fn scan_table_batch(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        after_key: Option<&[u8]>,
+        limit: usize,
+    ) -> AuroraResult<(Vec<HashMap<String, DataValue>>, Option<Vec<u8>>)> {
+        let table_prefix = format!("table:{}:", table_name);
+        let batch = self.storage_engine.scan_prefix_after(&table_prefix, after_key, limit.max(1)).await?;
+        let resume_key = match batch.last() {
+            Some((key, _)) if batch.len() >= limit.max(1) => Some(key.clone()),
+            _ => None,
+        };
+
+        self.transaction_manager.record_read(transaction.id, &Self::table_predicate_key(table_name));
+        let ttl_column = self.catalog.get_ttl_column(table_name).await;
+        let now = chrono::Utc::now();
+
+        let mut visible_rows = Vec::with_capacity(batch.len());
+        for (key, data) in batch {
+            let version_chain: TupleVersionChain = bincode::deserialize(&data)
+                .map_err(|e| AuroraError::new(ErrorCode::StorageCorruption, format!("Deserialization error: {}", e)))?;
+            if let Some(visible_version) = version_chain.visible_version(transaction, &self.transaction_manager) {
+                if Self::is_expired(&visible_version.data, ttl_column.as_deref(), now) {
+                    continue;
+                }
+                self.transaction_manager.record_read(transaction.id, &String::from_utf8_lossy(&key));
+                visible_rows.push(visible_version.data.clone());
+            }
+        }
+
+        Ok((visible_rows, resume_key))
+    }
+
+    /// Visible, unexpired rows with these primary keys, in the order given;
+    /// keys without such a row are skipped. Used by index scans, which read
+    /// the table's predicate like a full scan does.
+    pub async fn fetch_rows(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        primary_keys: &[DataValue],
+    ) -> AuroraResult<Vec<HashMap<String, DataValue>>> {
+        self.transaction_manager.record_read(transaction.id, &Self::table_predicate_key(table_name));
+        let ttl_column = self.catalog.get_ttl_column(table_name).await;
+        let now = chrono::Utc::now();
+
+        let mut rows = Vec::with_capacity(primary_keys.len());
+        for primary_key in primary_keys {
+            let Some(chain) = self.get_tuple_chain(table_name, primary_key).await? else {
+                continue;
+            };
+            if let Some(version) = chain.visible_version(transaction, &self.transaction_manager) {
+                if Self::is_expired(&version.data, ttl_column.as_deref(), now) {
+                    continue;
+                }
+                let storage_key = self.generate_tuple_key(table_name, primary_key);
+                self.transaction_manager.record_read(transaction.id, &String::from_utf8_lossy(&storage_key));
+                rows.push(version.data.clone());
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Sort primary keys into the order a full scan of the table returns
+    /// their rows
+    pub fn sort_physical(&self, table_name: &str, primary_keys: &mut [DataValue]) {
+        primary_keys.sort_by_cached_key(|primary_key| self.generate_tuple_key(table_name, primary_key));
+    }
+
+    /// Whether a row's expiry, held in `ttl_column`, is at or before `now`.
+    /// Rows with a NULL expiry never expire; TIMESTAMP values are read as UTC.
+    pub fn is_expired(row: &HashMap<String, DataValue>, ttl_column: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> bool {
+        match ttl_column.and_then(|column| row.get(column)) {
+            Some(DataValue::TimestampTz(expires_at)) => *expires_at <= now,
+            Some(DataValue::Timestamp(expires_at)) => expires_at.and_utc() <= now,
+            _ => false,
+        }
+    }
+
+    /// Primary keys of the rows visible to `transaction` that expired at or
+    /// before `now`, in key order. Reads no pages through the shared buffers,
+    /// so reaping does not evict pages queries are using.
+    pub async fn expired_keys(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        ttl_column: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> AuroraResult<Vec<DataValue>> {
+        let table_prefix = format!("table:{}:", table_name);
+        let mut expired = Vec::new();
+
+        for (_, data) in self.storage_engine.scan_prefix(&table_prefix).await? {
+            let version_chain: TupleVersionChain = bincode::deserialize(&data)
+                .map_err(|e| AuroraError::new(ErrorCode::StorageCorruption, format!("Deserialization error: {}", e)))?;
+            if let Some(version) = version_chain.visible_version(transaction, &self.transaction_manager) {
+                if Self::is_expired(&version.data, Some(ttl_column), now) {
+                    expired.push(version.primary_key.clone());
+                }
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Delete a row only if the version visible to `transaction` expired at
+    /// or before `now`, so a row refreshed since it was found expired stays
+    pub async fn delete_expired_row(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        primary_key: &DataValue,
+        ttl_column: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> AuroraResult<bool> {
+        let expired = match self.get_tuple_chain(table_name, primary_key).await? {
+            Some(chain) => chain.visible_version(transaction, &self.transaction_manager)
+                .is_some_and(|version| Self::is_expired(&version.data, Some(ttl_column), now)),
+            None => false,
+        };
+        if !expired {
+            return Ok(false);
+        }
+        self.delete_row(transaction, table_name, primary_key, None).await
+    }
+
+    /// Physically remove a row whose every version was deleted by a
+    /// committed transaction that no running transaction can still see
+    /// past
+    pub async fn purge_dead_tuple(&self, table_name: &str, primary_key: &DataValue) -> AuroraResult<PurgeOutcome> {
+        let version_chain = match self.get_tuple_chain(table_name, primary_key).await? {
+            Some(chain) => chain,
+            None => return Ok(PurgeOutcome::Live),
+        };
+        if version_chain.all_versions().iter().any(|version| version.xmax.is_none()) {
+            return Ok(PurgeOutcome::Live);
+        }
+
+        let oldest_active = self.transaction_manager.oldest_active_snapshot();
+        let dead = version_chain.all_versions().iter().all(|version| {
+            match version.xmax.and_then(|xmax| self.transaction_manager.get_transaction(xmax)) {
+                Some(deleter) => match (deleter.commit_timestamp, oldest_active) {
+                    (Some(committed), Some(oldest)) => committed < oldest,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                },
+                // Forgotten transactions are long committed
+                None => true,
+            }
+        });
+        if !dead {
+            return Ok(PurgeOutcome::Pinned);
+        }
+
+        let storage_key = self.generate_tuple_key(table_name, primary_key);
+        self.storage_engine.delete(&storage_key).await?;
+
+        let versions = version_chain.all_versions().len() as u64;
+        let bytes: u64 = version_chain.all_versions().iter().map(Self::version_bytes).sum();
+        self.track_write(table_name, |stats| {
+            stats.dead_tuples = stats.dead_tuples.saturating_sub(versions);
+            stats.dead_bytes = stats.dead_bytes.saturating_sub(bytes);
+            stats.free_bytes += bytes;
+        });
+        Ok(PurgeOutcome::Purged)
+    }
+
+    /// Read one scanned page through the shared buffers, counting a hit if it
+    /// was cached and a read if it had to be loaded
+    async fn access_shared_page(&self, table_name: &str, data_version: u64, page_no: u64, contents: Vec<u8>, buffers: Option<&BufferUsage>) {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (table_name, data_version, page_no).hash(&mut hasher);
+        let page_id = hasher.finish();
+
+        if self.shared_buffers.get_page(page_id).await.is_some() {
+            if let Some(buffers) = buffers {
+                buffers.shared_hit();
+            }
+        } else {
+            self.shared_buffers.put_page(page_id, contents).await;
+            if let Some(buffers) = buffers {
+                buffers.shared_read();
+            }
+        }
+        self.shared_buffers.unpin_page(page_id);
+    }
+
+    /// Count a row write: the storage engine writes records through to its
+    /// data file, so every page a write dirties is also written
+    fn record_write_io(buffers: Option<&BufferUsage>, logged: &LoggedRecord, bytes: usize) {
+        if let Some(buffers) = buffers {
+            let pages = pages_for(bytes as u64).max(1);
+            buffers.shared_dirtied(pages, pages);
+            buffers.wal_record(logged.bytes);
+        }
+    }
+
     /// Update a row in a table with MVCC versioning
-    pub async fn update_row(&self, transaction: &crate::mvcc::transaction::Transaction, table_name: &str, primary_key: &DataValue, new_data: HashMap<String, DataValue>) -> AuroraResult<bool> {
+    pub async fn update_row(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        primary_key: &DataValue,
+        new_data: HashMap<String, DataValue>,
+        buffers: Option<&BufferUsage>,
+    ) -> AuroraResult<bool> {
         // Verify table exists
         if !self.catalog.table_exists(table_name).await {
             return Err(AuroraError::new(
@@ -160,25 +570,31 @@ impl TableStorage {
             None => return Ok(false), // Row doesn't exist
         };
 
+        version_chain.discard_aborted(&self.transaction_manager);
+
         // Check if the current version is visible to this transaction
         let current_version = match version_chain.visible_version(transaction, &self.transaction_manager) {
-            Some(version) => version,
+            Some(version) => version.clone(),
             None => return Ok(false), // No visible version
         };
 
-        // Create new version with updated data
+        // Refuse to overwrite a version this transaction cannot see (lost update)
+        VisibilityChecker::check_write_conflict(&version_chain, transaction, &self.transaction_manager)
+            .map_err(|e| Self::write_conflict_error(e, transaction.id, table_name))?;
+
+        // Create new version with updated data, keeping older versions for concurrent snapshots
         let new_version = current_version.new_version(new_data, transaction.id);
+        version_chain.add_version(new_version.clone());
 
         // Serialize updated version chain
-        let updated_chain = TupleVersionChain::new(new_version); // Simplified - should preserve history
-        let serialized_data = bincode::serialize(&updated_chain)
+        let serialized_data = bincode::serialize(&version_chain)
             .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("Serialization error: {}", e)))?;
 
         // Generate storage key
         let storage_key = self.generate_tuple_key(table_name, primary_key);
 
         // Log the operation to WAL BEFORE storing
-        self.wal_logger.log_update(
+        let logged = self.wal_logger.log_update(
             transaction.id,
             table_name,
             &storage_key,
@@ -186,10 +602,20 @@ impl TableStorage {
             &bincode::serialize(&new_version.data).unwrap_or_default()
         ).await
             .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("WAL logging failed: {}", e)))?;
+        Self::record_write_io(buffers, &logged, serialized_data.len());
 
         // Store the updated version chain
+        self.transaction_manager.record_write(transaction.id, &String::from_utf8_lossy(&storage_key));
         self.storage_engine.insert(storage_key, serialized_data).await?;
 
+        let (old_bytes, new_bytes) = (Self::version_bytes(&current_version), Self::version_bytes(&new_version));
+        self.track_write(table_name, |stats| {
+            stats.dead_tuples += 1;
+            stats.dead_bytes += old_bytes;
+            stats.live_bytes = (stats.live_bytes + new_bytes).saturating_sub(old_bytes);
+            stats.stale_index_entries += 1;
+        });
+        self.bump_data_version(table_name);
         log::debug!("Updated row in table '{}': {:?}", table_name, primary_key);
         Ok(true)
     }
@@ -231,24 +657,36 @@ impl TableStorage {
 
         self.storage_engine.insert(storage_key, serialized_data).await?;
 
+        self.bump_data_version(table_name);
         log::debug!("Updated row in table '{}': {:?}", table_name, primary_key);
         Ok(true)
     }
 
     /// Delete a row from a table with MVCC versioning
-    pub async fn delete_row(&self, transaction: &crate::mvcc::transaction::Transaction, table_name: &str, primary_key: &DataValue) -> AuroraResult<bool> {
+    pub async fn delete_row(
+        &self,
+        transaction: &crate::mvcc::transaction::Transaction,
+        table_name: &str,
+        primary_key: &DataValue,
+        buffers: Option<&BufferUsage>,
+    ) -> AuroraResult<bool> {
         // Get existing version chain
         let mut version_chain = match self.get_tuple_chain(table_name, primary_key).await? {
             Some(chain) => chain,
             None => return Ok(false), // Row doesn't exist
         };
 
+        version_chain.discard_aborted(&self.transaction_manager);
+
         // Check if the current version is visible to this transaction
         let current_version = match version_chain.visible_version(transaction, &self.transaction_manager) {
-            Some(version) => version,
+            Some(version) => version.clone(),
             None => return Ok(false), // No visible version
         };
 
+        VisibilityChecker::check_write_conflict(&version_chain, transaction, &self.transaction_manager)
+            .map_err(|e| Self::write_conflict_error(e, transaction.id, table_name))?;
+
         // Mark the current version as deleted by this transaction
         version_chain.delete_current(transaction.id);
 
@@ -260,17 +698,28 @@ impl TableStorage {
         let storage_key = self.generate_tuple_key(table_name, primary_key);
 
         // Log the operation to WAL BEFORE storing
-        self.wal_logger.log_delete(
+        let logged = self.wal_logger.log_delete(
             transaction.id,
             table_name,
             &storage_key,
             &bincode::serialize(&current_version.data).unwrap_or_default()
         ).await
             .map_err(|e| AuroraError::new(ErrorCode::StorageUnavailable, format!("WAL logging failed: {}", e)))?;
+        Self::record_write_io(buffers, &logged, serialized_data.len());
 
         // Store the updated version chain
+        self.transaction_manager.record_write(transaction.id, &String::from_utf8_lossy(&storage_key));
         self.storage_engine.insert(storage_key, serialized_data).await?;
 
+        let deleted_bytes = Self::version_bytes(&current_version);
+        self.track_write(table_name, |stats| {
+            stats.live_tuples = stats.live_tuples.saturating_sub(1);
+            stats.live_bytes = stats.live_bytes.saturating_sub(deleted_bytes);
+            stats.dead_tuples += 1;
+            stats.dead_bytes += deleted_bytes;
+            stats.stale_index_entries += 1;
+        });
+        self.bump_data_version(table_name);
         log::debug!("Deleted row from table '{}': {:?}", table_name, primary_key);
         Ok(true)
     }
@@ -286,6 +735,10 @@ impl TableStorage {
             self.storage_engine.delete(&key).await?;
         }
 
+        if self.tuple_stats(table_name).is_some() {
+            self.track_tuple_stats(table_name);
+        }
+        self.bump_data_version(table_name);
         log::info!("Deleted all data for table '{}'", table_name);
         Ok(())
     }
@@ -328,6 +781,24 @@ impl TableStorage {
         format!("table:{}:pk:{:?}", table_name, primary_key).into_bytes()
     }
 
+    /// Conflict-tracking key standing for "every row of the table"
+    fn table_predicate_key(table_name: &str) -> String {
+        format!("table:{}:*", table_name)
+    }
+
+    /// Convert a write conflict into an error the caller can retry on
+    fn write_conflict_error(error: VisibilityError, txn_id: TransactionId, table_name: &str) -> AuroraError {
+        let reason = match error {
+            VisibilityError::TupleLocked => "row is being modified by another transaction",
+            VisibilityError::SerializationFailure => "row was modified by a transaction that committed after this one started",
+            VisibilityError::TupleNotVisible => "row is not visible to this transaction",
+        };
+        AuroraError::new(
+            ErrorCode::TransactionConflict,
+            format!("Transaction {} could not write to table '{}': {}", txn_id, table_name, reason)
+        )
+    }
+
     /// Validate and prepare row data for storage
     fn validate_and_prepare_row(
         &self,
@@ -399,22 +870,50 @@ impl TableStorage {
             ))?;
 
         // Type validation
-        match (&column.data_type, value) {
-            (crate::types::DataType::Integer, DataValue::Integer(_)) => Ok(()),
-            (crate::types::DataType::BigInt, DataValue::BigInt(_)) => Ok(()),
-            (crate::types::DataType::Float, DataValue::Float(_)) => Ok(()),
-            (crate::types::DataType::Double, DataValue::Double(_)) => Ok(()),
-            (crate::types::DataType::Text, DataValue::Text(_)) => Ok(()),
-            (crate::types::DataType::Boolean, DataValue::Boolean(_)) => Ok(()),
-            (crate::types::DataType::Blob, DataValue::Blob(_)) => Ok(()),
-            (_, DataValue::Null) if column.nullable => Ok(()),
-            _ => Err(AuroraError::new(
+        let valid = match value {
+            DataValue::Null => column.nullable,
+            value => Self::value_has_type(&column.data_type, value)?,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(AuroraError::new(
                 ErrorCode::ValidationTypeMismatch,
                 format!("Type mismatch for column '{}': expected {:?}, got {:?}", column_name, column.data_type, value)
-            )),
+            ))
         }
     }
 
+    /// Whether a non-NULL value has a column type. Decimals must also fit the
+    /// column's precision and scale; array elements may be NULL.
+    fn value_has_type(data_type: &crate::types::DataType, value: &DataValue) -> AuroraResult<bool> {
+        Ok(match (data_type, value) {
+            (crate::types::DataType::Integer, DataValue::Integer(_)) => true,
+            (crate::types::DataType::BigInt, DataValue::BigInt(_)) => true,
+            (crate::types::DataType::Float, DataValue::Float(_)) => true,
+            (crate::types::DataType::Double, DataValue::Double(_)) => true,
+            (crate::types::DataType::Text, DataValue::Text(_)) => true,
+            (crate::types::DataType::Boolean, DataValue::Boolean(_)) => true,
+            (crate::types::DataType::Blob, DataValue::Blob(_)) => true,
+            (crate::types::DataType::Timestamp, DataValue::Timestamp(_)) => true,
+            (crate::types::DataType::TimestampTz, DataValue::TimestampTz(_)) => true,
+            (crate::types::DataType::Interval, DataValue::Interval(_)) => true,
+            (crate::types::DataType::Decimal(precision, scale), DataValue::Decimal(d)) => {
+                d.fit_column(*precision, *scale)?;
+                true
+            }
+            (crate::types::DataType::Array(element_type), DataValue::Array(elements)) => {
+                for element in elements.iter().filter(|element| !matches!(element, DataValue::Null)) {
+                    if !Self::value_has_type(element_type, element)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            _ => false,
+        })
+    }
+
     /// Generate storage key for a row
     fn generate_row_key(&self, row: &TableRow) -> Vec<u8> {
         format!("table:{}:pk:{:?}", row.table_name, row.primary_key).into_bytes()
@@ -465,6 +964,9 @@ mod tests {
                 },
             ],
             constraints: vec![],
+            storage_engine: None,
+            partitioning: None,
+            ttl_column: None,
         };
 
         table_storage.catalog.create_table(&create_query).await.unwrap();
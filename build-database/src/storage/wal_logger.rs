@@ -7,12 +7,14 @@
 //! for superior crash recovery and performance.
 
 use std::collections::VecDeque;
-use std::fs::{File, OpenOptions};
-use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
 use std::path::PathBuf;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use crc32fast::Hasher as Crc32Hasher;
+use tokio::sync::watch;
+use super::wal_sync::{SyncLatency, WalSyncMethod, WalWriter};
 
 /// WAL record types with disk persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -136,14 +138,32 @@ pub struct WALStats {
     pub active_transactions: u32,
     pub log_file_size: u64,
     pub recovery_time_ms: u64,
+    /// Durability path flushes take
+    pub sync_method: WalSyncMethod,
+    /// Completion latency of each flushed batch's write and sync
+    pub sync_latency: SyncLatency,
+    /// Commits another commit's flush had already made durable
+    pub grouped_commits: u64,
+}
+
+/// Position and on-disk size of a logged record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggedRecord {
+    pub lsn: u64,
+    /// Bytes the record takes in the log file, including its size prefix
+    pub bytes: u64,
 }
 
 /// ARIES-based WAL logger with disk persistence
 pub struct WALLogger {
     log_file_path: PathBuf,
-    log_file: RwLock<Option<BufWriter<File>>>,
+    /// Held for a whole flush, so batches reach the file in LSN order
+    log_file: Mutex<Option<WalWriter>>,
+    sync_method: WalSyncMethod,
     log_buffer: RwLock<Vec<WALEntry>>,
     flushed_lsn: RwLock<u64>,
+    /// Publishes `flushed_lsn` after every flush, for WAL senders
+    flushed_watch: watch::Sender<u64>,
     next_lsn: RwLock<u64>,
     checkpoint_interval: u64,
     stats: RwLock<WALStats>,
@@ -164,9 +184,11 @@ impl WALLogger {
 
         Ok(Self {
             log_file_path,
-            log_file: RwLock::new(None),
+            log_file: Mutex::new(None),
+            sync_method: WalSyncMethod::default(),
             log_buffer: RwLock::new(Vec::new()),
             flushed_lsn: RwLock::new(flushed_lsn),
+            flushed_watch: watch::channel(flushed_lsn).0,
             next_lsn: RwLock::new(next_lsn),
             checkpoint_interval: 1000,
             stats: RwLock::new(WALStats {
@@ -176,11 +198,22 @@ impl WALLogger {
                 active_transactions: 0,
                 log_file_size: 0,
                 recovery_time_ms: 0,
+                sync_method: WalSyncMethod::default(),
+                sync_latency: SyncLatency::default(),
+                grouped_commits: 0,
             }),
             active_transactions: RwLock::new(std::collections::HashSet::new()),
         })
     }
 
+    /// Make flushes durable with `method`, or with blocking writes where
+    /// the kernel or build cannot use it
+    pub fn with_sync_method(mut self, method: WalSyncMethod) -> Self {
+        self.sync_method = method;
+        self.stats.get_mut().sync_method = WalSyncMethod::supported(method);
+        self
+    }
+
     /// Recover LSN state from existing log file
     fn recover_log_state(log_path: &PathBuf) -> Result<(u64, u64, u64), io::Error> {
         let file = File::open(log_path)?;
@@ -221,20 +254,36 @@ impl WALLogger {
 
     /// Log a database operation with durability guarantee
     pub async fn log_operation(&self, transaction_id: u64, record: WALRecord) -> Result<u64, io::Error> {
-        let lsn = {
-            let mut next = self.next_lsn.write();
-            let current = *next;
-            *next += 1;
-            current
-        };
+        Ok(self.append(transaction_id, record).await?.lsn)
+    }
 
-        let entry = WALEntry::new(lsn, *self.flushed_lsn.read(), transaction_id, record);
+    /// Log a record and report where it went and how large it is on disk
+    async fn append(&self, transaction_id: u64, record: WALRecord) -> Result<LoggedRecord, io::Error> {
+        self.append_with(transaction_id, record, false).await
+    }
 
-        // Add to in-memory buffer
-        {
+    /// Log a record; with `defer_flush` a commit is left in the buffer for
+    /// a later flush instead of forcing one
+    async fn append_with(&self, transaction_id: u64, record: WALRecord, defer_flush: bool) -> Result<LoggedRecord, io::Error> {
+        // The LSN is taken under the buffer lock so the buffer stays in LSN
+        // order when appends race
+        let (entry, buffered) = {
             let mut buffer = self.log_buffer.write();
+            let lsn = {
+                let mut next = self.next_lsn.write();
+                let current = *next;
+                *next += 1;
+                current
+            };
+            let entry = WALEntry::new(lsn, *self.flushed_lsn.read(), transaction_id, record);
             buffer.push(entry.clone());
-        }
+            (entry, buffer.len())
+        };
+        let lsn = entry.lsn;
+
+        // Size prefix plus the serialized entry, as flush_log writes it
+        let bytes = 8 + bincode::serialized_size(&entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
         // Update stats
         {
@@ -243,17 +292,19 @@ impl WALLogger {
         }
 
         // Force flush for critical operations (commits, checkpoints)
-        let needs_flush = matches!(entry.record,
+        let needs_flush = !defer_flush && matches!(entry.record,
             WALRecord::Commit { .. } |
             WALRecord::Abort { .. } |
             WALRecord::Checkpoint
         );
 
-        if needs_flush || self.log_buffer.read().len() > 100 {
+        if needs_flush {
+            self.flush_through(lsn)?;
+        } else if buffered > 100 {
             self.flush_log().await?;
         }
 
-        Ok(lsn)
+        Ok(LoggedRecord { lsn, bytes })
     }
 
     /// Begin a new transaction
@@ -265,7 +316,17 @@ impl WALLogger {
 
     /// Commit a transaction
     pub async fn commit_transaction(&self, transaction_id: u64) -> Result<u64, io::Error> {
-        let lsn = self.log_operation(transaction_id, WALRecord::Commit { transaction_id }).await?;
+        self.finish_commit(transaction_id, false).await
+    }
+
+    /// Commit a transaction without waiting for its commit record to reach
+    /// disk; a crash before the next flush loses it
+    pub async fn commit_transaction_deferred(&self, transaction_id: u64) -> Result<u64, io::Error> {
+        self.finish_commit(transaction_id, true).await
+    }
+
+    async fn finish_commit(&self, transaction_id: u64, defer_flush: bool) -> Result<u64, io::Error> {
+        let lsn = self.append_with(transaction_id, WALRecord::Commit { transaction_id }, defer_flush).await?.lsn;
         self.active_transactions.write().remove(&transaction_id);
 
         // Update stats
@@ -292,8 +353,8 @@ impl WALLogger {
     }
 
     /// Log an insert operation
-    pub async fn log_insert(&self, transaction_id: u64, table: &str, key: &[u8], value: &[u8]) -> Result<u64, io::Error> {
-        self.log_operation(transaction_id, WALRecord::Insert {
+    pub async fn log_insert(&self, transaction_id: u64, table: &str, key: &[u8], value: &[u8]) -> Result<LoggedRecord, io::Error> {
+        self.append(transaction_id, WALRecord::Insert {
             table: table.to_string(),
             key: key.to_vec(),
             value: value.to_vec(),
@@ -301,8 +362,8 @@ impl WALLogger {
     }
 
     /// Log an update operation
-    pub async fn log_update(&self, transaction_id: u64, table: &str, key: &[u8], old_value: &[u8], new_value: &[u8]) -> Result<u64, io::Error> {
-        self.log_operation(transaction_id, WALRecord::Update {
+    pub async fn log_update(&self, transaction_id: u64, table: &str, key: &[u8], old_value: &[u8], new_value: &[u8]) -> Result<LoggedRecord, io::Error> {
+        self.append(transaction_id, WALRecord::Update {
             table: table.to_string(),
             key: key.to_vec(),
             old_value: old_value.to_vec(),
@@ -311,8 +372,8 @@ impl WALLogger {
     }
 
     /// Log a delete operation
-    pub async fn log_delete(&self, transaction_id: u64, table: &str, key: &[u8], old_value: &[u8]) -> Result<u64, io::Error> {
-        self.log_operation(transaction_id, WALRecord::Delete {
+    pub async fn log_delete(&self, transaction_id: u64, table: &str, key: &[u8], old_value: &[u8]) -> Result<LoggedRecord, io::Error> {
+        self.append(transaction_id, WALRecord::Delete {
             table: table.to_string(),
             key: key.to_vec(),
             old_value: old_value.to_vec(),
@@ -321,75 +382,148 @@ impl WALLogger {
 
     /// Flush WAL buffer to disk for durability
     pub async fn flush_log(&self) -> Result<(), io::Error> {
-        let mut buffer = self.log_buffer.write();
-        if buffer.is_empty() {
+        self.flush_through(u64::MAX)
+    }
+
+    /// Make every entry up to `lsn` durable. Commits arriving while a flush
+    /// is in progress wait for it and then go out together in the next
+    /// batch, with one write and one sync; a commit that batch already
+    /// covered returns without flushing.
+    fn flush_through(&self, lsn: u64) -> Result<(), io::Error> {
+        let mut log_file = self.log_file.lock();
+        if *self.flushed_lsn.read() >= lsn {
+            self.stats.write().grouped_commits += 1;
             return Ok(());
         }
 
-        // Ensure log file is open
-        self.ensure_log_file_open()?;
+        let batch = std::mem::take(&mut *self.log_buffer.write());
+        if batch.is_empty() {
+            return Ok(());
+        }
 
-        // Get file writer
-        let mut log_file = self.log_file.write();
-        let writer = log_file.as_mut().ok_or_else(|| {
-            io::Error::new(io::ErrorKind::Other, "Log file not available")
-        })?;
+        let result = Self::encode_batch(&batch).and_then(|data| {
+            if log_file.is_none() {
+                *log_file = Some(WalWriter::open(&self.log_file_path, self.sync_method)?);
+            }
+            let writer = log_file.as_mut().expect("log file opened above");
+            let elapsed = writer.append_durable(&data)?;
+            Ok((elapsed, writer.method(), writer.len()))
+        });
+        let (elapsed, method, file_size) = match result {
+            Ok(written) => written,
+            Err(e) => {
+                // Put the batch back ahead of anything appended since
+                let mut buffer = self.log_buffer.write();
+                let appended = std::mem::replace(&mut *buffer, batch);
+                buffer.extend(appended);
+                return Err(e);
+            }
+        };
 
-        // Write each entry with size prefix for recovery
-        let mut flushed_count = 0u64;
-        for entry in &*buffer {
-            // Serialize entry
-            let entry_data = bincode::serialize(entry)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // Update flushed LSN
+        let last_lsn = batch.last().map(|e| e.lsn).unwrap_or(0);
+        *self.flushed_lsn.write() = last_lsn;
+        self.flushed_watch.send_replace(last_lsn);
 
-            // Write size prefix
-            let size_bytes = (entry_data.len() as u64).to_le_bytes();
-            writer.write_all(&size_bytes)?;
+        // Update stats
+        let mut stats = self.stats.write();
+        stats.flushed_entries += batch.len() as u64;
+        stats.log_file_size = file_size;
+        stats.sync_method = method;
+        stats.sync_latency.record(elapsed);
 
-            // Write entry data
-            writer.write_all(&entry_data)?;
+        Ok(())
+    }
 
-            flushed_count += 1;
+    /// Entries as the log file holds them, each behind its size
+    fn encode_batch(batch: &[WALEntry]) -> Result<Vec<u8>, io::Error> {
+        let mut data = Vec::new();
+        for entry in batch {
+            let entry_data = bincode::serialize(entry)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            data.extend_from_slice(&(entry_data.len() as u64).to_le_bytes());
+            data.extend_from_slice(&entry_data);
         }
+        Ok(data)
+    }
 
-        // Force flush to disk
-        writer.flush()?;
-        drop(log_file); // Release lock before fsync
+    /// Highest LSN written to the log file
+    pub fn flushed_lsn(&self) -> u64 {
+        *self.flushed_lsn.read()
+    }
 
-        // Update flushed LSN
-        let last_lsn = buffer.last().map(|e| e.lsn).unwrap_or(0);
-        *self.flushed_lsn.write() = last_lsn;
+    /// Receiver of the flushed LSN, which changes after every flush
+    pub fn subscribe_flushed(&self) -> watch::Receiver<u64> {
+        self.flushed_watch.subscribe()
+    }
 
-        // Update stats
-        {
-            let mut stats = self.stats.write();
-            stats.flushed_entries += flushed_count;
-            stats.log_file_size = self.get_log_file_size()?;
+    /// Flushed entries from `start_lsn` on, at most `limit` of them, as a
+    /// WAL sender streams them
+    pub fn read_from(&self, start_lsn: u64, limit: usize) -> Result<Vec<WALEntry>, io::Error> {
+        let mut entries = Vec::new();
+        if limit == 0 || !self.log_file_path.exists() {
+            return Ok(entries);
         }
 
-        // Clear buffer
-        buffer.clear();
+        let mut reader = BufReader::new(File::open(&self.log_file_path)?);
+        while entries.len() < limit {
+            let mut size_buf = [0u8; 8];
+            if reader.read_exact(&mut size_buf).is_err() {
+                break;
+            }
+            let entry_size = u64::from_le_bytes(size_buf);
+            if entry_size < 8 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("WAL entry of {} bytes", entry_size)));
+            }
 
-        Ok(())
-    }
+            // Skip entries before the start without decoding them; each
+            // begins with its LSN
+            let mut lsn_buf = [0u8; 8];
+            if reader.read_exact(&mut lsn_buf).is_err() {
+                break;
+            }
+            if u64::from_le_bytes(lsn_buf) < start_lsn {
+                reader.seek(SeekFrom::Current(entry_size as i64 - 8))?;
+                continue;
+            }
 
-    /// Ensure log file is open for writing
-    fn ensure_log_file_open(&self) -> Result<(), io::Error> {
-        let mut log_file = self.log_file.write();
-        if log_file.is_none() {
-            let file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&self.log_file_path)?;
-            *log_file = Some(BufWriter::new(file));
+            let mut entry_buf = lsn_buf.to_vec();
+            entry_buf.resize(entry_size as usize, 0);
+            if reader.read_exact(&mut entry_buf[8..]).is_err() {
+                break; // Still being written
+            }
+            let entry: WALEntry = bincode::deserialize(&entry_buf)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if !entry.verify_checksum() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("WAL entry checksum mismatch at LSN {}", entry.lsn)));
+            }
+            entries.push(entry);
         }
-        Ok(())
+        Ok(entries)
     }
 
-    /// Get current log file size
-    fn get_log_file_size(&self) -> Result<u64, io::Error> {
-        let metadata = std::fs::metadata(&self.log_file_path)?;
-        Ok(metadata.len())
+    /// Append entries streamed from a primary, keeping their LSNs, and flush
+    /// them. They must continue this log exactly.
+    pub async fn append_replicated(&self, entries: &[WALEntry]) -> Result<(), io::Error> {
+        {
+            let mut buffer = self.log_buffer.write();
+            let mut next = self.next_lsn.write();
+            for (expected, entry) in (*next..).zip(entries) {
+                if entry.lsn != expected {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("replicated WAL entry has LSN {}, expected {}", entry.lsn, expected),
+                    ));
+                }
+                if !entry.verify_checksum() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, format!("WAL entry checksum mismatch at LSN {}", entry.lsn)));
+                }
+            }
+            *next += entries.len() as u64;
+            self.stats.write().total_entries += entries.len() as u64;
+            buffer.extend(entries.iter().cloned());
+        }
+        self.flush_log().await
     }
 
     /// Create a checkpoint for faster recovery
@@ -505,8 +639,7 @@ impl WALLogger {
         self.flush_log().await?;
 
         // Close file
-        let mut log_file = self.log_file.write();
-        *log_file = None;
+        *self.log_file.lock() = None;
 
         Ok(())
     }
@@ -0,0 +1,218 @@
+//! WAL Durability Paths
+//!
+//! A WAL flush hands over one contiguous batch of serialized entries, which
+//! may hold the commit records of many transactions (group commit). The
+//! batch is appended to the log file and made durable before the flush
+//! returns; only then are those commits acknowledged.
+//!
+//! - `Blocking`: `write` followed by `fdatasync`, on every platform.
+//! - `IoUring` (Linux, `io-uring` feature): the write and the fdatasync are
+//!   submitted together as linked SQEs, so the kernel starts the sync as
+//!   soon as the write completes without a second system call, and the
+//!   sync's completion acknowledges the batch. Kernels without io_uring, or
+//!   without its write and fsync operations, get the blocking path.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How WAL batches reach stable storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalSyncMethod {
+    /// `write` then `fdatasync`
+    #[default]
+    Blocking,
+    /// Linked write and fdatasync SQEs on an io_uring
+    IoUring,
+}
+
+impl WalSyncMethod {
+    /// `method` if this kernel and build can use it, else `Blocking`
+    pub fn supported(method: WalSyncMethod) -> WalSyncMethod {
+        match method {
+            WalSyncMethod::IoUring if uring::UringSync::new().is_some() => WalSyncMethod::IoUring,
+            _ => WalSyncMethod::Blocking,
+        }
+    }
+}
+
+/// Time from handing a batch to the sync path to its completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncLatency {
+    /// Batches made durable
+    pub syncs: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl SyncLatency {
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        self.syncs += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    /// Mean completion latency; zero before the first sync
+    pub fn mean(&self) -> Duration {
+        match self.syncs {
+            0 => Duration::ZERO,
+            syncs => self.total / syncs as u32,
+        }
+    }
+}
+
+/// Append-only handle on the log file
+pub(crate) struct WalWriter {
+    file: File,
+    /// End of the file, where the next batch goes
+    offset: u64,
+    ring: Option<uring::UringSync>,
+}
+
+impl WalWriter {
+    /// Open the log for appending with `method`, falling back to blocking
+    /// writes if io_uring cannot be set up
+    pub(crate) fn open(path: &Path, method: WalSyncMethod) -> io::Result<Self> {
+        let ring = match method {
+            WalSyncMethod::IoUring => uring::UringSync::new(),
+            WalSyncMethod::Blocking => None,
+        };
+        if method == WalSyncMethod::IoUring && ring.is_none() {
+            log::warn!("io_uring write/fsync unavailable; WAL falls back to blocking write and fdatasync");
+        }
+        // Writes through the ring name their offset, which O_APPEND would
+        // override, so only the blocking path appends by mode
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(ring.is_none())
+            .open(path)?;
+        let offset = file.metadata()?.len();
+        Ok(Self { file, offset, ring })
+    }
+
+    /// Method in use
+    pub(crate) fn method(&self) -> WalSyncMethod {
+        match self.ring {
+            Some(_) => WalSyncMethod::IoUring,
+            None => WalSyncMethod::Blocking,
+        }
+    }
+
+    /// Bytes in the log file
+    pub(crate) fn len(&self) -> u64 {
+        self.offset
+    }
+
+    /// Append `batch` and return once it is durable, with how long that took
+    pub(crate) fn append_durable(&mut self, batch: &[u8]) -> io::Result<Duration> {
+        let started = Instant::now();
+        match &mut self.ring {
+            Some(ring) => ring.write_and_sync(&self.file, self.offset, batch)?,
+            None => {
+                self.file.write_all(batch)?;
+                self.file.sync_data()?;
+            }
+        }
+        self.offset += batch.len() as u64;
+        Ok(started.elapsed())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use io_uring::{opcode, squeue, types, IoUring, Probe};
+
+    const WRITE: u64 = 1;
+    const SYNC: u64 = 2;
+
+    /// A ring that writes and syncs one batch at a time
+    pub(crate) struct UringSync {
+        ring: IoUring,
+    }
+
+    impl UringSync {
+        /// A ring whose kernel supports write and fsync, if there is one
+        pub(crate) fn new() -> Option<Self> {
+            let ring = IoUring::new(8).ok()?;
+            let mut probe = Probe::new();
+            ring.submitter().register_probe(&mut probe).ok()?;
+            let supported = probe.is_supported(opcode::Write::CODE) && probe.is_supported(opcode::Fsync::CODE);
+            supported.then_some(Self { ring })
+        }
+
+        /// Write `data` at `offset` and fdatasync, linked so the sync runs
+        /// only after the write succeeded. A short write cancels its sync;
+        /// the rest is submitted again with a sync of its own.
+        pub(crate) fn write_and_sync(&mut self, file: &File, mut offset: u64, mut data: &[u8]) -> io::Result<()> {
+            let fd = types::Fd(file.as_raw_fd());
+            while !data.is_empty() {
+                let len = data.len().min(u32::MAX as usize);
+                let write = opcode::Write::new(fd, data.as_ptr(), len as u32)
+                    .offset(offset)
+                    .build()
+                    .flags(squeue::Flags::IO_LINK)
+                    .user_data(WRITE);
+                let sync = opcode::Fsync::new(fd)
+                    .flags(types::FsyncFlags::DATASYNC)
+                    .build()
+                    .user_data(SYNC);
+                // SAFETY: `data` and `file` outlive the submission, which is
+                // waited on below before either can be dropped
+                unsafe {
+                    let mut submission = self.ring.submission();
+                    submission.push(&write).map_err(|_| io::Error::other("io_uring submission queue full"))?;
+                    submission.push(&sync).map_err(|_| io::Error::other("io_uring submission queue full"))?;
+                }
+                self.ring.submit_and_wait(2)?;
+
+                let (mut written, mut synced) = (None, None);
+                for completion in self.ring.completion() {
+                    match completion.user_data() {
+                        WRITE => written = Some(completion.result()),
+                        _ => synced = Some(completion.result()),
+                    }
+                }
+                let written = match written {
+                    Some(result) if result < 0 => return Err(io::Error::from_raw_os_error(-result)),
+                    Some(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "io_uring wrote nothing")),
+                    Some(result) => result as usize,
+                    None => return Err(io::Error::other("io_uring lost a write completion")),
+                };
+                data = &data[written..];
+                offset += written as u64;
+                if data.is_empty() {
+                    match synced {
+                        Some(result) if result < 0 => return Err(io::Error::from_raw_os_error(-result)),
+                        Some(_) => {}
+                        None => return Err(io::Error::other("io_uring lost a sync completion")),
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "io-uring")))]
+mod uring {
+    use std::fs::File;
+    use std::io;
+
+    /// Stand-in where io_uring is not built in; never available
+    pub(crate) struct UringSync;
+
+    impl UringSync {
+        pub(crate) fn new() -> Option<Self> {
+            None
+        }
+
+        pub(crate) fn write_and_sync(&mut self, _file: &File, _offset: u64, _data: &[u8]) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "io_uring is not available"))
+        }
+    }
+}
@@ -8,6 +8,7 @@
 //! - Time series SQL extensions with natural temporal query syntax
 //! - Built-in anomaly detection with statistical analysis
 //! - Hardware-accelerated temporal operations with SIMD
+//! - Consistent-prefix snapshot export of LSN-versioned chunks
 
 pub mod compression;
 pub mod chunking;
@@ -17,6 +18,8 @@ pub mod retention;
 pub mod queries;
 pub mod storage;
 pub mod analytics;
+pub mod table;
+pub mod snapshot;
 
 pub use compression::*;
 pub use chunking::*;
@@ -26,3 +29,5 @@ pub use retention::*;
 pub use queries::*;
 pub use storage::*;
 pub use analytics::*;
+pub use table::*;
+pub use snapshot::*;
@@ -0,0 +1,278 @@
+//! AuroraDB Time Series Snapshot Export: Consistent Prefixes for Training Sets
+//!
+//! Exports every chunk of a [`TimeSeriesTable`] as of one snapshot point, an
+//! LSN or a commit time, so the same snapshot can be reproduced later:
+//! - **Consistent prefix**: a chunk holds exactly the writes committed at or
+//!   before the snapshot LSN; writes that commit during the export, late
+//!   writes into old chunks included, never appear
+//! - **Native chunk format**: one file per chunk, timestamps and values
+//!   stored losslessly and guarded by a CRC
+//! - **Resumable**: the chunk list and progress live in a manifest beside
+//!   the chunk files, so an interrupted export picks up where it stopped
+//!
+//! Chunk files and the manifest are written to a temp name, synced and then
+//! renamed, so a crash leaves each either whole or absent.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::core::errors::{AuroraResult, AuroraError};
+use super::table::{Lsn, TimeSeriesTable};
+
+/// Name of the manifest in an export directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Leading bytes of a native chunk file
+const CHUNK_MAGIC: &[u8; 4] = b"ATSC";
+const CHUNK_VERSION: u16 = 1;
+/// Magic, version, series id, chunk id, interval start and end, snapshot
+/// LSN and point count
+const CHUNK_HEADER_LEN: usize = 4 + 2 + 8 * 5 + 4;
+
+/// Where a snapshot is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotPoint {
+    /// Everything committed at or before this LSN
+    Lsn(Lsn),
+    /// Everything committed at or before this time, in milliseconds since
+    /// the epoch
+    CommitTime(i64),
+}
+
+/// Format exported chunks are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// One `chunk_<id>.atsc` file per chunk
+    Native,
+}
+
+/// What an export covers and how far it has got
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub snapshot_lsn: Lsn,
+    pub format: ExportFormat,
+    /// Every chunk the snapshot covers, in export order
+    pub chunk_ids: Vec<u64>,
+    /// Chunks whose file is complete
+    pub exported: BTreeSet<u64>,
+}
+
+/// Totals of a finished export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub snapshot_lsn: Lsn,
+    pub chunks: usize,
+    pub points: u64,
+}
+
+/// A chunk read back from an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedChunk {
+    pub chunk_id: u64,
+    pub series_id: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub snapshot_lsn: Lsn,
+    /// Timestamp and value of each point, in commit order
+    pub points: Vec<(i64, f64)>,
+}
+
+/// An export of one snapshot of a table into a directory
+pub struct SnapshotExport {
+    dir: PathBuf,
+    manifest: ExportManifest,
+}
+
+impl SnapshotExport {
+    /// Pin the snapshot at `point` and record the chunks it covers in a new
+    /// export under `dir`. A snapshot past the table's last write is refused:
+    /// writes still to come could land inside it.
+    pub fn begin(table: &TimeSeriesTable, point: SnapshotPoint, dir: impl Into<PathBuf>) -> AuroraResult<Self> {
+        let dir = dir.into();
+        let snapshot_lsn = match point {
+            SnapshotPoint::Lsn(lsn) => lsn,
+            SnapshotPoint::CommitTime(time_ms) => table.lsn_as_of(time_ms),
+        };
+        let current = table.current_lsn();
+        if snapshot_lsn > current {
+            return Err(AuroraError::InvalidArgument(format!(
+                "snapshot LSN {} is ahead of the last committed LSN {}", snapshot_lsn, current
+            )));
+        }
+        if dir.join(MANIFEST_FILE).exists() {
+            return Err(AuroraError::InvalidState(format!(
+                "{} already holds an export; resume it instead", dir.display()
+            )));
+        }
+
+        fs::create_dir_all(&dir).map_err(AuroraError::Io)?;
+        let manifest = ExportManifest {
+            snapshot_lsn,
+            format: ExportFormat::Native,
+            chunk_ids: table.chunk_ids_at(snapshot_lsn),
+            exported: BTreeSet::new(),
+        };
+        let export = Self { dir, manifest };
+        export.save_manifest()?;
+        Ok(export)
+    }
+
+    /// Reopen an interrupted export under `dir`
+    pub fn resume(dir: impl Into<PathBuf>) -> AuroraResult<Self> {
+        let dir = dir.into();
+        let manifest = read_manifest(&dir)?;
+        Ok(Self { dir, manifest })
+    }
+
+    pub fn manifest(&self) -> &ExportManifest {
+        &self.manifest
+    }
+
+    pub fn snapshot_lsn(&self) -> Lsn {
+        self.manifest.snapshot_lsn
+    }
+
+    /// Chunks not yet exported
+    pub fn remaining(&self) -> usize {
+        self.manifest.chunk_ids.len() - self.manifest.exported.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Export up to `max_chunks` more chunks; returns how many were
+    pub fn export_chunks(&mut self, table: &TimeSeriesTable, max_chunks: usize) -> AuroraResult<usize> {
+        let pending: Vec<u64> = self.manifest.chunk_ids.iter()
+            .filter(|id| !self.manifest.exported.contains(id))
+            .take(max_chunks)
+            .copied()
+            .collect();
+        for &chunk_id in &pending {
+            self.export_chunk(table, chunk_id)?;
+            self.manifest.exported.insert(chunk_id);
+            self.save_manifest()?;
+        }
+        Ok(pending.len())
+    }
+
+    /// Export every remaining chunk
+    pub fn run(&mut self, table: &TimeSeriesTable) -> AuroraResult<ExportSummary> {
+        self.export_chunks(table, usize::MAX)?;
+        let chunks = read_export(&self.dir)?;
+        Ok(ExportSummary {
+            snapshot_lsn: self.snapshot_lsn(),
+            chunks: chunks.len(),
+            points: chunks.iter().map(|chunk| chunk.points.len() as u64).sum(),
+        })
+    }
+
+    fn export_chunk(&self, table: &TimeSeriesTable, chunk_id: u64) -> AuroraResult<()> {
+        let snapshot_lsn = self.manifest.snapshot_lsn;
+        let chunk = table.chunk_at(chunk_id, snapshot_lsn).ok_or_else(|| AuroraError::NotFound(format!(
+            "chunk {} of the snapshot at LSN {} no longer exists", chunk_id, snapshot_lsn
+        )))?;
+
+        let mut data = Vec::with_capacity(CHUNK_HEADER_LEN + chunk.points.len() * 16 + 4);
+        data.extend_from_slice(CHUNK_MAGIC);
+        data.extend_from_slice(&CHUNK_VERSION.to_be_bytes());
+        data.extend_from_slice(&chunk.series_id.to_be_bytes());
+        data.extend_from_slice(&chunk.id.to_be_bytes());
+        data.extend_from_slice(&chunk.start_time.to_be_bytes());
+        data.extend_from_slice(&chunk.end_time.to_be_bytes());
+        data.extend_from_slice(&snapshot_lsn.to_be_bytes());
+        data.extend_from_slice(&(chunk.points.len() as u32).to_be_bytes());
+        for point in &chunk.points {
+            data.extend_from_slice(&point.timestamp.to_be_bytes());
+            data.extend_from_slice(&point.value.to_bits().to_be_bytes());
+        }
+        let crc = crc32fast::hash(&data);
+        data.extend_from_slice(&crc.to_be_bytes());
+
+        write_atomically(&self.dir.join(chunk_file_name(chunk_id)), &data)
+    }
+
+    fn save_manifest(&self) -> AuroraResult<()> {
+        let json = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(|e| AuroraError::Serialization(e.to_string()))?;
+        write_atomically(&self.dir.join(MANIFEST_FILE), &json)
+    }
+}
+
+/// Every chunk of the finished export under `dir`, in export order
+pub fn read_export(dir: &Path) -> AuroraResult<Vec<ExportedChunk>> {
+    let manifest = read_manifest(dir)?;
+    if manifest.exported.len() < manifest.chunk_ids.len() {
+        return Err(AuroraError::InvalidState(format!(
+            "export in {} is incomplete: {} of {} chunks written",
+            dir.display(), manifest.exported.len(), manifest.chunk_ids.len()
+        )));
+    }
+    manifest.chunk_ids.iter()
+        .map(|chunk_id| {
+            let chunk = read_chunk_file(&dir.join(chunk_file_name(*chunk_id)))?;
+            if chunk.chunk_id != *chunk_id || chunk.snapshot_lsn != manifest.snapshot_lsn {
+                return Err(AuroraError::InvalidState(format!(
+                    "chunk file of chunk {} belongs to chunk {} at LSN {}", chunk_id, chunk.chunk_id, chunk.snapshot_lsn
+                )));
+            }
+            Ok(chunk)
+        })
+        .collect()
+}
+
+/// Read one native chunk file
+pub fn read_chunk_file(path: &Path) -> AuroraResult<ExportedChunk> {
+    let data = fs::read(path).map_err(AuroraError::Io)?;
+    let corrupt = |reason: &str| AuroraError::InvalidState(format!("chunk file {} {}", path.display(), reason));
+    if data.len() < CHUNK_HEADER_LEN + 4 || &data[..4] != CHUNK_MAGIC {
+        return Err(corrupt("is not a native chunk file"));
+    }
+    let (body, crc) = data.split_at(data.len() - 4);
+    if crc32fast::hash(body).to_be_bytes() != crc {
+        return Err(corrupt("fails its checksum"));
+    }
+    if u16::from_be_bytes([body[4], body[5]]) != CHUNK_VERSION {
+        return Err(corrupt("has an unsupported version"));
+    }
+
+    let u64_at = |offset: usize| u64::from_be_bytes(body[offset..offset + 8].try_into().unwrap());
+    let count = u32::from_be_bytes(body[46..50].try_into().unwrap()) as usize;
+    let points = &body[CHUNK_HEADER_LEN..];
+    if points.len() != count * 16 {
+        return Err(corrupt("has a truncated point list"));
+    }
+    Ok(ExportedChunk {
+        series_id: u64_at(6),
+        chunk_id: u64_at(14),
+        start_time: u64_at(22) as i64,
+        end_time: u64_at(30) as i64,
+        snapshot_lsn: u64_at(38),
+        points: points.chunks_exact(16)
+            .map(|point| (
+                i64::from_be_bytes(point[..8].try_into().unwrap()),
+                f64::from_bits(u64::from_be_bytes(point[8..].try_into().unwrap())),
+            ))
+            .collect(),
+    })
+}
+
+fn read_manifest(dir: &Path) -> AuroraResult<ExportManifest> {
+    let json = fs::read(dir.join(MANIFEST_FILE)).map_err(AuroraError::Io)?;
+    serde_json::from_slice(&json).map_err(|e| AuroraError::Serialization(e.to_string()))
+}
+
+fn chunk_file_name(chunk_id: u64) -> String {
+    format!("chunk_{}.atsc", chunk_id)
+}
+
+/// Replace `path` with `data`, never leaving it partially written
+fn write_atomically(path: &Path, data: &[u8]) -> AuroraResult<()> {
+    let temp = path.with_extension("tmp");
+    let mut file = File::create(&temp).map_err(AuroraError::Io)?;
+    file.write_all(data).map_err(AuroraError::Io)?;
+    file.sync_all().map_err(AuroraError::Io)?;
+    fs::rename(&temp, path).map_err(AuroraError::Io)
+}
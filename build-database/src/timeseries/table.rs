@@ -0,0 +1,161 @@
+//! AuroraDB Time Series Table: LSN-Versioned Chunk Storage
+//!
+//! Points are grouped into chunks per series and fixed time interval, the
+//! way hypertables partition by time. Every write is stamped with a log
+//! sequence number (LSN) under the table lock, so LSN order is commit order:
+//! the writes visible at LSN `n` are exactly those stamped `<= n`, whatever
+//! their timestamps. A late write, carrying an old timestamp, lands in an old
+//! chunk but stays invisible to any reader pinned before it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use parking_lot::RwLock;
+use crate::core::errors::{AuroraResult, AuroraError};
+
+/// Log sequence number of a committed write
+pub type Lsn = u64;
+
+/// A point as written, with the LSN it committed at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VersionedPoint {
+    pub lsn: Lsn,
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// The points of one series within one time interval
+#[derive(Debug, Clone)]
+pub struct VersionedChunk {
+    pub id: u64,
+    pub series_id: u64,
+    /// Inclusive start of the interval, in milliseconds
+    pub start_time: i64,
+    /// Exclusive end of the interval, in milliseconds
+    pub end_time: i64,
+    /// LSN of the write that created the chunk
+    pub created_lsn: Lsn,
+    /// Points in commit order
+    pub points: Vec<VersionedPoint>,
+}
+
+impl VersionedChunk {
+    /// Timestamp and value of each point committed at or before `lsn`, in
+    /// commit order
+    pub fn visible_at(&self, lsn: Lsn) -> Vec<(i64, f64)> {
+        self.points.iter()
+            .take_while(|point| point.lsn <= lsn)
+            .map(|point| (point.timestamp, point.value))
+            .collect()
+    }
+}
+
+struct TableState {
+    chunks: BTreeMap<u64, VersionedChunk>,
+    /// Chunk holding each (series, interval start)
+    chunk_index: HashMap<(u64, i64), u64>,
+    next_chunk_id: u64,
+    last_lsn: Lsn,
+    /// Last LSN committed by each commit time in milliseconds, ascending
+    commit_times: Vec<(i64, Lsn)>,
+}
+
+/// A time-series table whose chunks can be read as of any committed LSN
+pub struct TimeSeriesTable {
+    chunk_interval_ms: i64,
+    state: RwLock<TableState>,
+}
+
+impl TimeSeriesTable {
+    /// Create a table chunked into intervals of `chunk_interval_ms`
+    pub fn new(chunk_interval_ms: i64) -> AuroraResult<Self> {
+        if chunk_interval_ms <= 0 {
+            return Err(AuroraError::InvalidArgument(format!("chunk interval must be positive, got {}", chunk_interval_ms)));
+        }
+        Ok(Self {
+            chunk_interval_ms,
+            state: RwLock::new(TableState {
+                chunks: BTreeMap::new(),
+                chunk_index: HashMap::new(),
+                next_chunk_id: 1,
+                last_lsn: 0,
+                commit_times: Vec::new(),
+            }),
+        })
+    }
+
+    /// Write a point, committed now; returns its LSN
+    pub fn write(&self, series_id: u64, timestamp: i64, value: f64) -> Lsn {
+        let mut state = self.state.write();
+        let lsn = state.last_lsn + 1;
+        state.last_lsn = lsn;
+
+        // Commit times never go backwards, even if the clock does
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as i64;
+        match state.commit_times.last_mut() {
+            Some((time, last)) if *time >= now => *last = lsn,
+            _ => state.commit_times.push((now, lsn)),
+        }
+
+        let start_time = timestamp.div_euclid(self.chunk_interval_ms) * self.chunk_interval_ms;
+        let chunk_id = match state.chunk_index.get(&(series_id, start_time)) {
+            Some(id) => *id,
+            None => {
+                let id = state.next_chunk_id;
+                state.next_chunk_id += 1;
+                state.chunk_index.insert((series_id, start_time), id);
+                state.chunks.insert(id, VersionedChunk {
+                    id,
+                    series_id,
+                    start_time,
+                    end_time: start_time.saturating_add(self.chunk_interval_ms),
+                    created_lsn: lsn,
+                    points: Vec::new(),
+                });
+                id
+            }
+        };
+        let chunk = state.chunks.get_mut(&chunk_id).expect("indexed chunk exists");
+        chunk.points.push(VersionedPoint { lsn, timestamp, value });
+        lsn
+    }
+
+    /// LSN of the most recent write; 0 for an empty table
+    pub fn current_lsn(&self) -> Lsn {
+        self.state.read().last_lsn
+    }
+
+    /// LSN of the last write committed at or before `time_ms`, milliseconds
+    /// since the epoch; 0 if none was
+    pub fn lsn_as_of(&self, time_ms: i64) -> Lsn {
+        let state = self.state.read();
+        let committed = state.commit_times.partition_point(|(time, _)| *time <= time_ms);
+        committed.checked_sub(1).map_or(0, |index| state.commit_times[index].1)
+    }
+
+    /// Ids of the chunks with points visible at `lsn`, in creation order
+    pub fn chunk_ids_at(&self, lsn: Lsn) -> Vec<u64> {
+        self.state.read().chunks.values()
+            .filter(|chunk| chunk.created_lsn <= lsn)
+            .map(|chunk| chunk.id)
+            .collect()
+    }
+
+    /// A copy of chunk `id` holding only the points visible at `lsn`
+    pub fn chunk_at(&self, id: u64, lsn: Lsn) -> Option<VersionedChunk> {
+        let state = self.state.read();
+        let chunk = state.chunks.get(&id)?;
+        let visible = chunk.points.partition_point(|point| point.lsn <= lsn);
+        Some(VersionedChunk { points: chunk.points[..visible].to_vec(), ..*chunk })
+    }
+
+    /// Points of `series_id` visible at `lsn`, ordered by timestamp
+    pub fn scan_at(&self, series_id: u64, lsn: Lsn) -> Vec<(i64, f64)> {
+        let state = self.state.read();
+        let mut points: Vec<(i64, f64)> = state.chunks.values()
+            .filter(|chunk| chunk.series_id == series_id)
+            .flat_map(|chunk| chunk.visible_at(lsn))
+            .collect();
+        points.sort_by_key(|(timestamp, _)| *timestamp);
+        points
+    }
+}
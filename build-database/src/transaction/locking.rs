@@ -2,6 +2,11 @@
 //!
 //! Implements two-phase locking with deadlock detection and resolution.
 //! Supports shared and exclusive locks for different isolation levels.
+//!
+//! Debug builds (or the `lock-order-check` feature) also track the order in
+//! which transactions acquire locks and flag any cycle in the global
+//! acquisition-order graph, so an ordering hazard shows up in tests even
+//! when the run happened not to deadlock.
 
 use crate::core::*;
 use super::manager::*;
@@ -19,6 +24,9 @@ pub struct LockManager {
     waiting_requests: VecDeque<LockRequest>,
     /// Lock statistics
     stats: LockStats,
+    /// Acquisition-order checker
+    #[cfg(any(debug_assertions, feature = "lock-order-check"))]
+    lock_order: LockOrderTracker,
 }
 
 /// Lock request from a transaction
@@ -62,6 +70,8 @@ impl LockManager {
             wait_for_graph: HashMap::new(),
             waiting_requests: VecDeque::new(),
             stats: LockStats::default(),
+            #[cfg(any(debug_assertions, feature = "lock-order-check"))]
+            lock_order: LockOrderTracker::default(),
         }
     }
 
@@ -112,6 +122,9 @@ impl LockManager {
         // Clean up empty entries
         self.lock_table.retain(|_, holders| !holders.is_empty());
 
+        #[cfg(any(debug_assertions, feature = "lock-order-check"))]
+        self.lock_order.record_release(transaction_id);
+
         // Update wait-for graph
         self.wait_for_graph.remove(&transaction_id);
         for waiting in self.wait_for_graph.values_mut() {
@@ -146,6 +159,9 @@ impl LockManager {
 
     /// Grant a lock to a transaction
     fn grant_lock(&mut self, request: LockRequest) -> Result<(), TransactionError> {
+        #[cfg(any(debug_assertions, feature = "lock-order-check"))]
+        self.lock_order.record_acquire(request.transaction_id, &request.key);
+
         let holder = LockHolder {
             transaction_id: request.transaction_id,
             mode: request.mode,
@@ -250,6 +266,12 @@ impl LockManager {
         &self.stats
     }
 
+    /// Lock-ordering hazards seen so far (debug builds only)
+    #[cfg(any(debug_assertions, feature = "lock-order-check"))]
+    pub fn lock_order_violations(&self) -> &[LockOrderViolation] {
+        self.lock_order.violations()
+    }
+
     /// Get current locks held by a transaction
     pub fn get_transaction_locks(&self, transaction_id: TransactionId) -> Vec<LockRequest> {
         let mut locks = Vec::new();
@@ -269,3 +291,174 @@ impl LockManager {
         locks
     }
 }
+
+/// A lock acquired while holding another, against an order seen earlier
+#[cfg(any(debug_assertions, feature = "lock-order-check"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockOrderViolation {
+    pub transaction_id: TransactionId,
+    /// Lock the transaction already held
+    pub held: Vec<u8>,
+    /// Lock it then acquired
+    pub acquired: Vec<u8>,
+    /// The cycle in the order graph: `held`, `acquired`, ..., `held`
+    pub cycle: Vec<Vec<u8>>,
+}
+
+/// Records the global lock-acquisition order
+///
+/// Every time a transaction takes a lock while holding others, an edge
+/// `held -> acquired` goes into a graph shared by all transactions. Two
+/// transactions taking the same pair of locks in opposite orders close a
+/// cycle, which is a potential deadlock whether or not they overlapped.
+#[cfg(any(debug_assertions, feature = "lock-order-check"))]
+#[derive(Debug, Default)]
+struct LockOrderTracker {
+    /// Locks each live transaction holds, in acquisition order
+    held: HashMap<TransactionId, Vec<Vec<u8>>>,
+    /// Acquisition-order graph: key -> keys taken while it was held
+    order_graph: HashMap<Vec<u8>, HashSet<Vec<u8>>>,
+    /// Hazards found so far
+    violations: Vec<LockOrderViolation>,
+}
+
+#[cfg(any(debug_assertions, feature = "lock-order-check"))]
+impl LockOrderTracker {
+    /// Record `key` being granted to `transaction_id`
+    fn record_acquire(&mut self, transaction_id: TransactionId, key: &[u8]) {
+        let held = self.held.entry(transaction_id).or_default();
+        if held.iter().any(|k| k == key) {
+            return;
+        }
+        let previous = std::mem::take(held);
+
+        for prior in &previous {
+            let seen_edge = self.order_graph
+                .get(prior)
+                .is_some_and(|next| next.contains(key));
+            if seen_edge {
+                continue;
+            }
+
+            // A path back from `key` to `prior` means the reverse order was seen
+            if let Some(path) = self.order_path(key, prior) {
+                let mut cycle = vec![prior.clone()];
+                cycle.extend(path);
+                log::warn!(
+                    "Lock order violation: transaction {:?} acquired {:?} while holding {:?}; order cycle {:?}",
+                    transaction_id,
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(prior),
+                    cycle.iter().map(|k| String::from_utf8_lossy(k).into_owned()).collect::<Vec<_>>(),
+                );
+                self.violations.push(LockOrderViolation {
+                    transaction_id,
+                    held: prior.clone(),
+                    acquired: key.to_vec(),
+                    cycle,
+                });
+            }
+
+            self.order_graph.entry(prior.clone()).or_default().insert(key.to_vec());
+        }
+
+        let held = self.held.entry(transaction_id).or_default();
+        *held = previous;
+        held.push(key.to_vec());
+    }
+
+    /// Forget the locks of a finished transaction; the order graph is kept
+    fn record_release(&mut self, transaction_id: TransactionId) {
+        self.held.remove(&transaction_id);
+    }
+
+    /// Keys from `from` to `to` along order edges, if reachable (BFS)
+    fn order_path(&self, from: &[u8], to: &[u8]) -> Option<Vec<Vec<u8>>> {
+        let mut parents: HashMap<&[u8], &[u8]> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        let mut seen: HashSet<&[u8]> = HashSet::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![current.to_vec()];
+                let mut node = current;
+                while let Some(parent) = parents.get(node) {
+                    path.push(parent.to_vec());
+                    node = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.order_graph.get(current).into_iter().flatten() {
+                if seen.insert(next.as_slice()) {
+                    parents.insert(next.as_slice(), current);
+                    queue.push_back(next.as_slice());
+                }
+            }
+        }
+        None
+    }
+
+    fn violations(&self) -> &[LockOrderViolation] {
+        &self.violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exclusive(transaction_id: u64, key: &str) -> LockRequest {
+        LockRequest {
+            key: key.as_bytes().to_vec(),
+            mode: LockMode::Exclusive,
+            transaction_id: TransactionId(transaction_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consistent_lock_order_is_not_flagged() {
+        let mut manager = LockManager::new();
+
+        for txn in 1..=3 {
+            manager.acquire_lock(exclusive(txn, "accounts/a")).await.unwrap();
+            manager.acquire_lock(exclusive(txn, "accounts/b")).await.unwrap();
+            manager.acquire_lock(exclusive(txn, "accounts/c")).await.unwrap();
+            manager.release_locks(TransactionId(txn)).await.unwrap();
+        }
+
+        assert!(manager.lock_order_violations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_inconsistent_lock_order_is_flagged() {
+        let mut manager = LockManager::new();
+
+        // The two transactions never overlap, so no deadlock actually occurs
+        manager.acquire_lock(exclusive(1, "accounts/a")).await.unwrap();
+        manager.acquire_lock(exclusive(1, "accounts/b")).await.unwrap();
+        manager.release_locks(TransactionId(1)).await.unwrap();
+
+        manager.acquire_lock(exclusive(2, "accounts/b")).await.unwrap();
+        manager.acquire_lock(exclusive(2, "accounts/a")).await.unwrap();
+        manager.release_locks(TransactionId(2)).await.unwrap();
+
+        let violations = manager.lock_order_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].transaction_id, TransactionId(2));
+        assert_eq!(violations[0].held, b"accounts/b".to_vec());
+        assert_eq!(violations[0].acquired, b"accounts/a".to_vec());
+        assert_eq!(
+            violations[0].cycle,
+            vec![b"accounts/b".to_vec(), b"accounts/a".to_vec(), b"accounts/b".to_vec()]
+        );
+
+        // A longer cycle through a third lock is caught too
+        manager.acquire_lock(exclusive(3, "accounts/c")).await.unwrap();
+        manager.acquire_lock(exclusive(3, "accounts/b")).await.unwrap();
+        manager.release_locks(TransactionId(3)).await.unwrap();
+        manager.acquire_lock(exclusive(4, "accounts/a")).await.unwrap();
+        manager.acquire_lock(exclusive(4, "accounts/c")).await.unwrap();
+        assert_eq!(manager.lock_order_violations().len(), 2);
+    }
+}
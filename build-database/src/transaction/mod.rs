@@ -19,5 +19,7 @@ pub mod recovery;
 pub use manager::{TransactionManager, Transaction, TransactionId, TransactionStatus};
 pub use mvcc::{MVCCManager, VersionChain, Snapshot};
 pub use locking::{LockManager, LockMode, LockRequest};
+#[cfg(any(debug_assertions, feature = "lock-order-check"))]
+pub use locking::LockOrderViolation;
 pub use logging::{WALManager, LogRecord};
 pub use recovery::{RecoveryManager, CheckpointManager};
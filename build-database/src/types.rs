@@ -1,3 +1,14 @@
+pub mod array;
+pub mod datetime;
+pub mod decimal;
+pub mod interval;
+pub mod timestamp;
+
+pub use datetime::DateField;
+pub use decimal::Decimal;
+pub use interval::Interval;
+pub use timestamp::TimeZone;
+
 /// Unique identifier for database tables
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TableId(pub u64);
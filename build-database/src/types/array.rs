@@ -0,0 +1,139 @@
+//! Arrays
+//!
+//! An array column (`text[]`, `integer[]`, ...) holds a one-dimensional list
+//! of values of its element type, any of which may be NULL. Besides
+//! `ARRAY['a', 'b']`, a value can be written in PostgreSQL's text form,
+//! `'{a,"b c",NULL}'`, which is also the form dumps use:
+//!
+//! - Elements are separated by commas; whitespace around them is ignored
+//! - An unquoted `NULL`, in any case, is a NULL element
+//! - An element is double-quoted when it is empty, spells NULL, or holds a
+//!   brace, comma, quote, backslash or whitespace; inside quotes `"` and `\`
+//!   are escaped with a backslash
+
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+/// Elements of an array in text form, `None` for a NULL element
+pub fn parse(text: &str) -> AuroraResult<Vec<Option<String>>> {
+    let invalid = |reason: &str| AuroraError::new(
+        ErrorCode::ValidationInvalidFormat,
+        format!("malformed array literal '{}': {}", text, reason)
+    );
+    let inner = text.trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| invalid("expected '{' and '}' around the elements"))?;
+
+    let mut elements = Vec::new();
+    if inner.trim().is_empty() {
+        return Ok(elements);
+    }
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let element = if chars.next_if_eq(&'"').is_some() {
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => value.push(chars.next().ok_or_else(|| invalid("unterminated quoted element"))?),
+                    Some(c) => value.push(c),
+                    None => return Err(invalid("unterminated quoted element")),
+                }
+            }
+            Some(value)
+        } else {
+            let mut value = String::new();
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                match c {
+                    '{' | '}' => return Err(invalid("nested arrays are not supported")),
+                    '"' => return Err(invalid("unexpected '\"' inside an unquoted element")),
+                    '\\' => value.push(chars.next().ok_or_else(|| invalid("trailing backslash"))?),
+                    c => value.push(c),
+                }
+            }
+            let value = value.trim_end();
+            if value.is_empty() {
+                return Err(invalid("empty element"));
+            }
+            (!value.eq_ignore_ascii_case("NULL")).then(|| value.to_string())
+        };
+        elements.push(element);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            Some(',') => {}
+            None => return Ok(elements),
+            Some(_) => return Err(invalid("expected ',' between elements")),
+        }
+    }
+}
+
+/// Text form of an array with these elements, `None` for NULL
+pub fn format<'a>(elements: impl IntoIterator<Item = Option<&'a str>>) -> String {
+    let mut text = String::from("{");
+    for (index, element) in elements.into_iter().enumerate() {
+        if index > 0 {
+            text.push(',');
+        }
+        match element {
+            None => text.push_str("NULL"),
+            Some(value) if needs_quotes(value) => {
+                text.push('"');
+                for c in value.chars() {
+                    if matches!(c, '"' | '\\') {
+                        text.push('\\');
+                    }
+                    text.push(c);
+                }
+                text.push('"');
+            }
+            Some(value) => text.push_str(value),
+        }
+    }
+    text.push('}');
+    text
+}
+
+fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.eq_ignore_ascii_case("NULL")
+        || value.chars().any(|c| matches!(c, '{' | '}' | ',' | '"' | '\\') || c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_form() {
+        assert_eq!(parse("{}").unwrap(), Vec::<Option<String>>::new());
+        assert_eq!(
+            parse(" { rust , \"two words\", NULL, \"NULL\", \"a\\\"b\" } ").unwrap(),
+            vec![
+                Some("rust".to_string()),
+                Some("two words".to_string()),
+                None,
+                Some("NULL".to_string()),
+                Some("a\"b".to_string()),
+            ]
+        );
+        assert_eq!(parse("{1,2,3}").unwrap(), vec![Some("1".to_string()), Some("2".to_string()), Some("3".to_string())]);
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        let elements = [Some("rust"), Some("two words"), None, Some("NULL"), Some(""), Some("back\\slash")];
+        let text = format(elements);
+        assert_eq!(text, r#"{rust,"two words",NULL,"NULL","","back\\slash"}"#);
+        let parsed = parse(&text).unwrap();
+        assert_eq!(parsed.iter().map(Option::as_deref).collect::<Vec<_>>(), elements);
+    }
+
+    #[test]
+    fn test_malformed_arrays_are_rejected() {
+        for text in ["rust", "{a,,b}", "{\"open}", "{{1,2},{3,4}}", "{a b\"c}", "{a} x"] {
+            assert!(parse(text).is_err(), "{} should not parse", text);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Date/Time Fields
+//!
+//! `date_trunc(unit, value)` and `date_part(field, value)` /
+//! `EXTRACT(field FROM value)` over timestamps. A timestamptz is truncated
+//! and taken apart on the session time zone's wall clock, so
+//! `date_trunc('day', ts)` is local midnight and the result is converted
+//! back to an instant; only `epoch` reads the instant itself.
+//!
+//! Centuries and millennia start in year 1, as in PostgreSQL: the 21st
+//! century began on 2001-01-01.
+
+use std::fmt;
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+use super::timestamp::TimeZone;
+
+/// Unit of `date_trunc` or field of `date_part`/`EXTRACT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Microseconds,
+    Milliseconds,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    Decade,
+    Century,
+    Millennium,
+    /// Day of the week, Sunday = 0
+    DayOfWeek,
+    /// ISO day of the week, Monday = 1 through Sunday = 7
+    IsoDayOfWeek,
+    /// Day of the year, from 1
+    DayOfYear,
+    /// ISO 8601 week-numbering year
+    IsoYear,
+    /// Seconds since 1970-01-01 00:00:00 UTC
+    Epoch,
+}
+
+impl FromStr for DateField {
+    type Err = AuroraError;
+
+    fn from_str(text: &str) -> AuroraResult<Self> {
+        Ok(match text.trim().to_lowercase().as_str() {
+            "microsecond" | "microseconds" | "us" | "usec" | "usecs" => DateField::Microseconds,
+            "millisecond" | "milliseconds" | "ms" | "msec" | "msecs" => DateField::Milliseconds,
+            "second" | "seconds" | "sec" | "secs" => DateField::Second,
+            "minute" | "minutes" | "min" | "mins" => DateField::Minute,
+            "hour" | "hours" | "hr" | "hrs" => DateField::Hour,
+            "day" | "days" => DateField::Day,
+            "week" | "weeks" => DateField::Week,
+            "month" | "months" | "mon" | "mons" => DateField::Month,
+            "quarter" | "qtr" => DateField::Quarter,
+            "year" | "years" | "yr" | "yrs" => DateField::Year,
+            "decade" | "decades" => DateField::Decade,
+            "century" | "centuries" => DateField::Century,
+            "millennium" | "millennia" | "millenniums" => DateField::Millennium,
+            "dow" => DateField::DayOfWeek,
+            "isodow" => DateField::IsoDayOfWeek,
+            "doy" => DateField::DayOfYear,
+            "isoyear" => DateField::IsoYear,
+            "epoch" => DateField::Epoch,
+            other => return Err(AuroraError::new(
+                ErrorCode::ValidationInvalidFormat,
+                format!("unit \"{}\" not recognized", other)
+            )),
+        })
+    }
+}
+
+impl fmt::Display for DateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DateField::Microseconds => "microseconds",
+            DateField::Milliseconds => "milliseconds",
+            DateField::Second => "second",
+            DateField::Minute => "minute",
+            DateField::Hour => "hour",
+            DateField::Day => "day",
+            DateField::Week => "week",
+            DateField::Month => "month",
+            DateField::Quarter => "quarter",
+            DateField::Year => "year",
+            DateField::Decade => "decade",
+            DateField::Century => "century",
+            DateField::Millennium => "millennium",
+            DateField::DayOfWeek => "dow",
+            DateField::IsoDayOfWeek => "isodow",
+            DateField::DayOfYear => "doy",
+            DateField::IsoYear => "isoyear",
+            DateField::Epoch => "epoch",
+        })
+    }
+}
+
+pub(crate) fn unsupported(field: DateField, operation: &str) -> AuroraError {
+    AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("unit \"{}\" not supported for {}", field, operation))
+}
+
+pub(crate) fn out_of_range(kind: &str) -> AuroraError {
+    AuroraError::new(ErrorCode::ValidationNumericOverflow, format!("{} out of range", kind))
+}
+
+/// First year of the century or millennium `span` years long holding `year`
+fn period_start(year: i32, span: i32) -> i32 {
+    (year - 1).div_euclid(span) * span + 1
+}
+
+/// Ordinal century or millennium of `year`
+fn period_number(year: i32, span: i32) -> i32 {
+    (year - 1).div_euclid(span) + 1
+}
+
+/// Truncate a wall-clock reading to the start of its `field`
+pub fn date_trunc(field: DateField, local: &NaiveDateTime) -> AuroraResult<NaiveDateTime> {
+    let date = local.date();
+    let first_of = |year: i32, month: u32| NaiveDate::from_ymd_opt(year, month, 1);
+    let truncated = match field {
+        DateField::Microseconds => local.with_nanosecond(local.nanosecond() / 1_000 * 1_000),
+        DateField::Milliseconds => local.with_nanosecond(local.nanosecond() / 1_000_000 * 1_000_000),
+        DateField::Second => local.with_nanosecond(0),
+        DateField::Minute => date.and_hms_opt(local.hour(), local.minute(), 0),
+        DateField::Hour => date.and_hms_opt(local.hour(), 0, 0),
+        DateField::Day => date.and_hms_opt(0, 0, 0),
+        // ISO weeks start on Monday
+        DateField::Week => (date - Duration::days(date.weekday().num_days_from_monday() as i64)).and_hms_opt(0, 0, 0),
+        DateField::Month => first_of(date.year(), date.month()).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::Quarter => first_of(date.year(), (date.month0() / 3) * 3 + 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::Year => first_of(date.year(), 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::Decade => first_of(date.year().div_euclid(10) * 10, 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::Century => first_of(period_start(date.year(), 100), 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::Millennium => first_of(period_start(date.year(), 1000), 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        DateField::DayOfWeek | DateField::IsoDayOfWeek | DateField::DayOfYear | DateField::IsoYear | DateField::Epoch => {
+            return Err(unsupported(field, "date_trunc"));
+        }
+    };
+    truncated.ok_or_else(|| out_of_range("timestamp"))
+}
+
+/// Field of a wall-clock reading; `epoch` reads it as UTC
+pub fn date_part(field: DateField, local: &NaiveDateTime) -> AuroraResult<f64> {
+    let micros_of_minute = local.second() as f64 * 1e6 + (local.nanosecond() / 1_000) as f64;
+    let year = local.year();
+    Ok(match field {
+        DateField::Microseconds => micros_of_minute,
+        DateField::Milliseconds => micros_of_minute / 1e3,
+        DateField::Second => micros_of_minute / 1e6,
+        DateField::Minute => local.minute() as f64,
+        DateField::Hour => local.hour() as f64,
+        DateField::Day => local.day() as f64,
+        DateField::Week => local.iso_week().week() as f64,
+        DateField::Month => local.month() as f64,
+        DateField::Quarter => (local.month0() / 3 + 1) as f64,
+        DateField::Year => year as f64,
+        DateField::Decade => year.div_euclid(10) as f64,
+        DateField::Century => period_number(year, 100) as f64,
+        DateField::Millennium => period_number(year, 1000) as f64,
+        DateField::DayOfWeek => local.weekday().num_days_from_sunday() as f64,
+        DateField::IsoDayOfWeek => local.weekday().number_from_monday() as f64,
+        DateField::DayOfYear => local.ordinal() as f64,
+        DateField::IsoYear => local.iso_week().year() as f64,
+        DateField::Epoch => local.and_utc().timestamp_micros() as f64 / 1e6,
+    })
+}
+
+/// `date_trunc` of a timestamptz on the wall clock of `zone`
+pub fn date_trunc_tz(field: DateField, instant: &DateTime<Utc>, zone: &TimeZone) -> AuroraResult<DateTime<Utc>> {
+    Ok(zone.from_local(&date_trunc(field, &zone.to_local(instant))?))
+}
+
+/// `date_part` of a timestamptz on the wall clock of `zone`
+pub fn date_part_tz(field: DateField, instant: &DateTime<Utc>, zone: &TimeZone) -> AuroraResult<f64> {
+    match field {
+        DateField::Epoch => Ok(instant.timestamp_micros() as f64 / 1e6),
+        _ => date_part(field, &zone.to_local(instant)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::timestamp::{format_timestamp, parse_timestamp, parse_timestamptz};
+
+    fn trunc(unit: &str, text: &str) -> String {
+        format_timestamp(&date_trunc(unit.parse().unwrap(), &parse_timestamp(text).unwrap()).unwrap())
+    }
+
+    fn part(field: &str, text: &str) -> f64 {
+        date_part(field.parse().unwrap(), &parse_timestamp(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_date_trunc_units() {
+        let ts = "2024-08-17 14:35:52.123456";
+        assert_eq!(trunc("milliseconds", ts), "2024-08-17 14:35:52.123000");
+        assert_eq!(trunc("second", ts), "2024-08-17 14:35:52");
+        assert_eq!(trunc("minute", ts), "2024-08-17 14:35:00");
+        assert_eq!(trunc("hour", ts), "2024-08-17 14:00:00");
+        assert_eq!(trunc("day", ts), "2024-08-17 00:00:00");
+        // Saturday truncates back to Monday
+        assert_eq!(trunc("week", ts), "2024-08-12 00:00:00");
+        assert_eq!(trunc("month", ts), "2024-08-01 00:00:00");
+        assert_eq!(trunc("quarter", ts), "2024-07-01 00:00:00");
+        assert_eq!(trunc("YEAR", ts), "2024-01-01 00:00:00");
+        assert_eq!(trunc("decade", ts), "2020-01-01 00:00:00");
+        assert_eq!(trunc("century", ts), "2001-01-01 00:00:00");
+        assert_eq!(trunc("millennium", "2000-12-31 23:59:59"), "1001-01-01 00:00:00");
+
+        assert!(date_trunc(DateField::DayOfWeek, &parse_timestamp(ts).unwrap()).is_err());
+        assert!("fortnight".parse::<DateField>().is_err());
+    }
+
+    #[test]
+    fn test_date_part_fields() {
+        let ts = "2024-12-30 14:35:52.5";
+        assert_eq!(part("year", ts), 2024.0);
+        assert_eq!(part("month", ts), 12.0);
+        assert_eq!(part("day", ts), 30.0);
+        assert_eq!(part("hour", ts), 14.0);
+        assert_eq!(part("minute", ts), 35.0);
+        assert_eq!(part("second", ts), 52.5);
+        assert_eq!(part("milliseconds", ts), 52_500.0);
+        assert_eq!(part("quarter", ts), 4.0);
+        assert_eq!(part("dow", ts), 1.0);
+        assert_eq!(part("doy", ts), 365.0);
+        // The Monday of ISO week 1 of 2025
+        assert_eq!((part("week", ts), part("isoyear", ts)), (1.0, 2025.0));
+        assert_eq!(part("century", "2000-06-01"), 20.0);
+        assert_eq!(part("century", "2001-01-01"), 21.0);
+        assert_eq!(part("epoch", "1970-01-02 00:00:01"), 86_401.0);
+    }
+
+    #[test]
+    fn test_timestamptz_fields_follow_session_zone() {
+        let utc = TimeZone::default();
+        let tokyo: TimeZone = "Asia/Tokyo".parse().unwrap();
+        let instant = parse_timestamptz("2024-03-31 20:00:00Z", &utc).unwrap();
+
+        // Already April 1st in Tokyo
+        assert_eq!(date_part_tz(DateField::Month, &instant, &utc).unwrap(), 3.0);
+        assert_eq!(date_part_tz(DateField::Month, &instant, &tokyo).unwrap(), 4.0);
+        assert_eq!(date_part_tz(DateField::Epoch, &instant, &tokyo).unwrap(), date_part_tz(DateField::Epoch, &instant, &utc).unwrap());
+
+        let midnight = date_trunc_tz(DateField::Day, &instant, &tokyo).unwrap();
+        assert_eq!(tokyo.format(&midnight), "2024-04-01 00:00:00+09");
+    }
+}
@@ -0,0 +1,698 @@
+//! Exact Decimal Numbers
+//!
+//! `Decimal` backs `DECIMAL(precision, scale)` / `NUMERIC` columns. A value is
+//! an arbitrary-precision integer coefficient and a base-10 scale, so
+//! `0.1 + 0.2` is exactly `0.3` and sums of money never drift.
+//!
+//! - Addition, subtraction and multiplication are exact.
+//! - Division keeps at least `DIVISION_SCALE` fractional digits (more if an
+//!   operand has a larger scale) and rounds the last digit half away from zero.
+//! - Results with more than `MAX_PRECISION` digits are an overflow error, never
+//!   silently rounded. Fitting a value to a column's `DECIMAL(p, s)` rounds to
+//!   `s` digits and fails if the integer part needs more than `p - s` digits.
+//!
+//! Values compare by numeric value, so `1.5 = 1.50`; hashing agrees.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Neg;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+/// Most digits a decimal value may carry, including intermediate results
+pub const MAX_PRECISION: u32 = 1000;
+
+/// Minimum fractional digits of a quotient
+pub const DIVISION_SCALE: u32 = 16;
+
+const LIMB_BASE: u64 = 1_000_000_000;
+const LIMB_DIGITS: u32 = 9;
+
+/// Unsigned arbitrary-precision integer, little-endian base-10^9 limbs with
+/// no leading zero limbs (zero is the empty vector)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct Magnitude(Vec<u32>);
+
+impl Magnitude {
+    fn from_u128(mut value: u128) -> Self {
+        let mut limbs = Vec::new();
+        while value > 0 {
+            limbs.push((value % LIMB_BASE as u128) as u32);
+            value /= LIMB_BASE as u128;
+        }
+        Self(limbs)
+    }
+
+    /// Parse a string of ASCII digits
+    fn from_digits(digits: &str) -> Self {
+        let mut limbs = Vec::with_capacity(digits.len() / LIMB_DIGITS as usize + 1);
+        let mut end = digits.len();
+        while end > 0 {
+            let start = end.saturating_sub(LIMB_DIGITS as usize);
+            limbs.push(digits[start..end].parse().unwrap_or(0));
+            end = start;
+        }
+        Self(limbs).trim()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.0.iter().rev().try_fold(0u128, |value, limb| {
+            value.checked_mul(LIMB_BASE as u128)?.checked_add(*limb as u128)
+        })
+    }
+
+    fn pow10(exponent: u32) -> Self {
+        let mut limbs = vec![0; (exponent / LIMB_DIGITS) as usize];
+        limbs.push(10u32.pow(exponent % LIMB_DIGITS));
+        Self(limbs)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn trim(mut self) -> Self {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+        self
+    }
+
+    /// Number of decimal digits; 0 for zero
+    fn digits(&self) -> u32 {
+        match self.0.last() {
+            Some(top) => (self.0.len() as u32 - 1) * LIMB_DIGITS + top.ilog10() + 1,
+            None => 0,
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let sum = carry + *self.0.get(i).unwrap_or(&0) as u64 + *other.0.get(i).unwrap_or(&0) as u64;
+            limbs.push((sum % LIMB_BASE) as u32);
+            carry = sum / LIMB_BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        Self(limbs)
+    }
+
+    /// `self - other`; `self` must be at least `other`
+    fn sub(&self, other: &Self) -> Self {
+        let mut limbs = Vec::with_capacity(self.0.len());
+        let mut borrow = 0i64;
+        for i in 0..self.0.len() {
+            let mut diff = self.0[i] as i64 - borrow - *other.0.get(i).unwrap_or(&0) as i64;
+            borrow = 0;
+            if diff < 0 {
+                diff += LIMB_BASE as i64;
+                borrow = 1;
+            }
+            limbs.push(diff as u32);
+        }
+        Self(limbs).trim()
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::default();
+        }
+        let mut acc = vec![0u64; self.0.len() + other.0.len()];
+        for (i, a) in self.0.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.0.iter().enumerate() {
+                let current = acc[i + j] + *a as u64 * *b as u64 + carry;
+                acc[i + j] = current % LIMB_BASE;
+                carry = current / LIMB_BASE;
+            }
+            let mut k = i + other.0.len();
+            while carry > 0 {
+                let current = acc[k] + carry;
+                acc[k] = current % LIMB_BASE;
+                carry = current / LIMB_BASE;
+                k += 1;
+            }
+        }
+        Self(acc.into_iter().map(|limb| limb as u32).collect()).trim()
+    }
+
+    fn mul_small(&self, factor: u32) -> Self {
+        let mut limbs = Vec::with_capacity(self.0.len() + 1);
+        let mut carry = 0u64;
+        for limb in &self.0 {
+            let current = *limb as u64 * factor as u64 + carry;
+            limbs.push((current % LIMB_BASE) as u32);
+            carry = current / LIMB_BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        Self(limbs).trim()
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        let mut limbs = vec![0u32; self.0.len()];
+        let mut remainder = 0u64;
+        for i in (0..self.0.len()).rev() {
+            let current = remainder * LIMB_BASE + self.0[i] as u64;
+            limbs[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (Self(limbs).trim(), remainder as u32)
+    }
+
+    /// Quotient and remainder of schoolbook long division; `divisor` is non-zero
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = vec![0u32; self.0.len()];
+        let mut remainder = Self::default();
+        for i in (0..self.0.len()).rev() {
+            remainder.0.insert(0, self.0[i]);
+            remainder = remainder.trim();
+
+            // Largest limb q with divisor * q <= remainder
+            let (mut low, mut high) = (0u32, (LIMB_BASE - 1) as u32);
+            while low < high {
+                let mid = low + (high - low + 1) / 2;
+                if divisor.mul_small(mid) <= remainder {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+            if low > 0 {
+                remainder = remainder.sub(&divisor.mul_small(low));
+            }
+            quotient[i] = low;
+        }
+        (Self(quotient).trim(), remainder)
+    }
+
+    /// `self / divisor` rounded half away from zero
+    fn div_round(&self, divisor: &Self) -> Self {
+        let (quotient, remainder) = self.div_rem(divisor);
+        if remainder.mul_small(2) >= *divisor {
+            quotient.add(&Self::from_u128(1))
+        } else {
+            quotient
+        }
+    }
+
+    fn to_digits(&self) -> String {
+        let mut limbs = self.0.iter().rev();
+        let mut out = match limbs.next() {
+            Some(top) => top.to_string(),
+            None => return "0".to_string(),
+        };
+        for limb in limbs {
+            out.push_str(&format!("{:09}", limb));
+        }
+        out
+    }
+}
+
+impl PartialOrd for Magnitude {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Magnitude {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.len().cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+/// Exact decimal number: `coefficient * 10^-scale`
+#[derive(Debug, Clone, Default)]
+pub struct Decimal {
+    negative: bool,
+    coefficient: Magnitude,
+    scale: u32,
+}
+
+fn overflow(message: String) -> AuroraError {
+    AuroraError::new(ErrorCode::ValidationNumericOverflow, message)
+}
+
+impl Decimal {
+    /// `value * 10^-scale`, e.g. `Decimal::new(1999, 2)` is 19.99
+    pub fn new(value: i128, scale: u32) -> Self {
+        Self {
+            negative: value < 0,
+            coefficient: Magnitude::from_u128(value.unsigned_abs()),
+            scale,
+        }
+    }
+
+    fn from_parts(negative: bool, coefficient: Magnitude, scale: u32) -> AuroraResult<Self> {
+        if coefficient.digits() > MAX_PRECISION || scale > MAX_PRECISION {
+            return Err(overflow(format!("decimal value exceeds the maximum precision of {} digits", MAX_PRECISION)));
+        }
+        Ok(Self { negative: negative && !coefficient.is_zero(), coefficient, scale })
+    }
+
+    /// Digits after the decimal point
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Significant digits in the coefficient
+    pub fn precision(&self) -> u32 {
+        self.coefficient.digits().max(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Coefficients of both values brought to their common (larger) scale
+    fn aligned(&self, other: &Self) -> (Magnitude, Magnitude, u32) {
+        let scale = self.scale.max(other.scale);
+        (
+            self.coefficient.mul(&Magnitude::pow10(scale - self.scale)),
+            other.coefficient.mul(&Magnitude::pow10(scale - other.scale)),
+            scale,
+        )
+    }
+
+    pub fn checked_add(&self, other: &Self) -> AuroraResult<Self> {
+        let (a, b, scale) = self.aligned(other);
+        if self.negative == other.negative {
+            Self::from_parts(self.negative, a.add(&b), scale)
+        } else if a >= b {
+            Self::from_parts(self.negative, a.sub(&b), scale)
+        } else {
+            Self::from_parts(other.negative, b.sub(&a), scale)
+        }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> AuroraResult<Self> {
+        self.checked_add(&-other.clone())
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> AuroraResult<Self> {
+        Self::from_parts(
+            self.negative != other.negative,
+            self.coefficient.mul(&other.coefficient),
+            self.scale + other.scale,
+        )
+    }
+
+    /// Quotient with at least `DIVISION_SCALE` fractional digits
+    pub fn checked_div(&self, other: &Self) -> AuroraResult<Self> {
+        self.div_to_scale(other, DIVISION_SCALE.max(self.scale).max(other.scale))
+    }
+
+    /// Quotient rounded half away from zero to `scale` fractional digits
+    pub fn div_to_scale(&self, other: &Self, scale: u32) -> AuroraResult<Self> {
+        if other.is_zero() {
+            return Err(AuroraError::new(ErrorCode::ValidationDivisionByZero, "division by zero".to_string()));
+        }
+        if scale > MAX_PRECISION {
+            return Err(overflow(format!("decimal scale {} exceeds the maximum of {}", scale, MAX_PRECISION)));
+        }
+
+        // coefficient / 10^scale = (a / 10^sa) / (b / 10^sb)
+        let shift = scale as i64 + other.scale as i64 - self.scale as i64;
+        let (numerator, denominator) = if shift >= 0 {
+            (self.coefficient.mul(&Magnitude::pow10(shift as u32)), other.coefficient.clone())
+        } else {
+            (self.coefficient.clone(), other.coefficient.mul(&Magnitude::pow10((-shift) as u32)))
+        };
+        Self::from_parts(self.negative != other.negative, numerator.div_round(&denominator), scale)
+    }
+
+    /// Round half away from zero to at most `scale` fractional digits
+    pub fn round(&self, scale: u32) -> Self {
+        if scale >= self.scale {
+            return self.clone();
+        }
+        let coefficient = self.coefficient.div_round(&Magnitude::pow10(self.scale - scale));
+        Self { negative: self.negative && !coefficient.is_zero(), coefficient, scale }
+    }
+
+    /// Round or pad to exactly `scale` fractional digits
+    pub fn rescale(&self, scale: u32) -> AuroraResult<Self> {
+        if scale <= self.scale {
+            return Ok(self.round(scale));
+        }
+        Self::from_parts(self.negative, self.coefficient.mul(&Magnitude::pow10(scale - self.scale)), scale)
+    }
+
+    /// Convert to a `DECIMAL(precision, scale)` column value: round to `scale`
+    /// digits and fail if the integer part does not fit
+    pub fn fit(&self, precision: u32, scale: u32) -> AuroraResult<Self> {
+        let fitted = self.rescale(scale)?;
+        if fitted.coefficient.digits() > precision {
+            return Err(overflow(format!(
+                "numeric field overflow: {} does not fit DECIMAL({}, {})",
+                self, precision, scale
+            )));
+        }
+        Ok(fitted)
+    }
+
+    /// Convert to the value stored in a column declared `DECIMAL(precision,
+    /// scale)`; `DECIMAL(0, 0)` is an unconstrained `NUMERIC` and keeps the
+    /// value as is
+    pub fn fit_column(&self, precision: u8, scale: u8) -> AuroraResult<Self> {
+        if precision == 0 {
+            return Ok(self.clone());
+        }
+        self.fit(precision as u32, scale as u32)
+    }
+
+    /// Same value without trailing fractional zeros
+    pub fn normalized(&self) -> Self {
+        let mut value = self.clone();
+        while value.scale > 0 {
+            let (quotient, remainder) = value.coefficient.div_rem_small(10);
+            if remainder != 0 {
+                break;
+            }
+            value.coefficient = quotient;
+            value.scale -= 1;
+        }
+        value
+    }
+
+    /// Convert a float using its shortest round-tripping representation, so
+    /// `0.1_f64` becomes exactly 0.1
+    pub fn from_f64(value: f64) -> AuroraResult<Self> {
+        if value.is_nan() {
+            return Err(AuroraError::new(ErrorCode::ValidationInvalidFormat, "cannot convert NaN to decimal".to_string()));
+        }
+        if value.is_infinite() {
+            return Err(overflow("cannot convert infinity to decimal".to_string()));
+        }
+        format!("{}", value).parse()
+    }
+
+    /// Nearest float; fails when the value is beyond the float range
+    pub fn to_f64(&self) -> AuroraResult<f64> {
+        let value: f64 = self.to_string().parse()
+            .map_err(|_| overflow(format!("{} is out of range for type double", self)))?;
+        if value.is_infinite() {
+            return Err(overflow(format!("{} is out of range for type double", self)));
+        }
+        Ok(value)
+    }
+
+    /// Round half away from zero to an integer; fails outside the i64 range
+    pub fn to_i64(&self) -> AuroraResult<i64> {
+        let out_of_range = || overflow(format!("{} is out of range for type bigint", self));
+        let magnitude = self.round(0).coefficient.to_u128().ok_or_else(out_of_range)?;
+        if self.negative {
+            0i128.checked_sub(magnitude as i128)
+                .filter(|value| *value >= i64::MIN as i128)
+                .map(|value| value as i64)
+                .ok_or_else(out_of_range)
+        } else {
+            i64::try_from(magnitude).map_err(|_| out_of_range())
+        }
+    }
+
+    /// Storage encoding: sign byte, u32 scale, u32 limb count, then the
+    /// coefficient's base-10^9 limbs least significant first, all little-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(9 + self.coefficient.0.len() * 4);
+        bytes.push(self.negative as u8);
+        bytes.extend_from_slice(&self.scale.to_le_bytes());
+        bytes.extend_from_slice(&(self.coefficient.0.len() as u32).to_le_bytes());
+        for limb in &self.coefficient.0 {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode a value written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> AuroraResult<Self> {
+        let corrupt = || AuroraError::new(ErrorCode::StorageCorruption, "malformed decimal encoding".to_string());
+        let word = |offset: usize| -> AuroraResult<u32> {
+            bytes.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(corrupt)
+        };
+
+        let negative = match bytes.first() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(corrupt()),
+        };
+        let scale = word(1)?;
+        let limb_count = word(5)? as usize;
+        if bytes.len() != 9 + limb_count * 4 {
+            return Err(corrupt());
+        }
+        let limbs = (0..limb_count).map(|i| word(9 + i * 4)).collect::<AuroraResult<Vec<_>>>()?;
+        if limbs.iter().any(|limb| *limb as u64 >= LIMB_BASE) || limbs.last() == Some(&0) {
+            return Err(corrupt());
+        }
+        Self::from_parts(negative, Magnitude(limbs), scale)
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Self::new(value as i128, 0)
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(mut self) -> Decimal {
+        self.negative = !self.negative && !self.coefficient.is_zero();
+        self
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = AuroraError;
+
+    /// Parse `[+-]digits[.digits][e[+-]digits]`
+    fn from_str(text: &str) -> AuroraResult<Self> {
+        let invalid = || AuroraError::new(
+            ErrorCode::ValidationInvalidFormat,
+            format!("invalid input syntax for type decimal: '{}'", text),
+        );
+
+        let trimmed = text.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (mantissa, exponent) = match unsigned.find(|c: char| c == 'e' || c == 'E') {
+            Some(at) => (&unsigned[..at], unsigned[at + 1..].parse::<i64>().map_err(|_| invalid())?),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let scale = fraction.len() as i64 - exponent;
+        if scale.unsigned_abs() > MAX_PRECISION as u64 {
+            return Err(overflow(format!("{} exceeds the maximum decimal precision of {} digits", text, MAX_PRECISION)));
+        }
+        let digits = format!("{}{}", integer, fraction);
+        let coefficient = Magnitude::from_digits(digits.trim_start_matches('0'));
+        if scale < 0 {
+            Self::from_parts(negative, coefficient.mul(&Magnitude::pow10((-scale) as u32)), 0)
+        } else {
+            Self::from_parts(negative, coefficient, scale as u32)
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.coefficient.to_digits();
+        let sign = if self.negative { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        if digits.len() <= scale {
+            write!(f, "{}0.{}{}", sign, "0".repeat(scale - digits.len()), digits)
+        } else {
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{}{}.{}", sign, integer, fraction)
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (negative, _) => {
+                let (a, b, _) = self.aligned(other);
+                if negative { b.cmp(&a) } else { a.cmp(&b) }
+            }
+        }
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.negative.hash(state);
+        normalized.coefficient.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(|e: AuroraError| serde::de::Error::custom(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(text: &str) -> Decimal {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let sum = dec("0.1").checked_add(&dec("0.2")).unwrap();
+        assert_eq!(sum, dec("0.3"));
+        assert_eq!(sum.to_string(), "0.3");
+
+        assert_eq!(dec("1.10").checked_sub(&dec("2.205")).unwrap().to_string(), "-1.105");
+        assert_eq!(dec("-0.5").checked_add(&dec("0.5")).unwrap().to_string(), "0.0");
+        assert_eq!(dec("19.99").checked_mul(&dec("3")).unwrap().to_string(), "59.97");
+
+        // Far beyond 128-bit integers
+        let big = dec("123456789012345678901234567890.123456789");
+        let squared = big.checked_mul(&big).unwrap();
+        assert_eq!(squared.checked_div(&big).unwrap().round(9), big);
+        assert_eq!(
+            dec("1e40").checked_add(&dec("1")).unwrap().to_string(),
+            "10000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_division_rounds_half_away_from_zero() {
+        assert_eq!(dec("1").checked_div(&dec("3")).unwrap().to_string(), "0.3333333333333333");
+        assert_eq!(dec("2").checked_div(&dec("3")).unwrap().to_string(), "0.6666666666666667");
+        assert_eq!(dec("-2").checked_div(&dec("3")).unwrap().to_string(), "-0.6666666666666667");
+        assert_eq!(dec("10").div_to_scale(&dec("4"), 0).unwrap().to_string(), "3");
+        assert_eq!(dec("-10").div_to_scale(&dec("4"), 0).unwrap().to_string(), "-3");
+        assert_eq!(dec("1.000").div_to_scale(&dec("0.008"), 1).unwrap().to_string(), "125.0");
+
+        let err = dec("1").checked_div(&dec("0.00")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::ValidationDivisionByZero);
+    }
+
+    #[test]
+    fn test_comparison_and_hashing_by_value() {
+        use std::collections::HashSet;
+
+        assert_eq!(dec("1.5"), dec("1.500"));
+        assert!(dec("-2") < dec("-1.99"));
+        assert!(dec("0.001") > dec("-1000"));
+        assert!(dec("10") > dec("9.999999999999999999999"));
+
+        let mut sorted = vec![dec("3"), dec("-0.5"), dec("0.25"), dec("-7")];
+        sorted.sort();
+        assert_eq!(sorted, vec![dec("-7"), dec("-0.5"), dec("0.25"), dec("3")]);
+
+        let set: HashSet<Decimal> = [dec("1.5"), dec("1.50"), dec("-0"), dec("0.000")].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_fit_rounds_and_detects_overflow() {
+        assert_eq!(dec("12.345").fit(5, 2).unwrap().to_string(), "12.35");
+        assert_eq!(dec("-12.345").fit(5, 2).unwrap().to_string(), "-12.35");
+        assert_eq!(dec("7").fit(5, 2).unwrap().to_string(), "7.00");
+        assert_eq!(dec("999.99").fit(5, 2).unwrap().to_string(), "999.99");
+        assert_eq!(dec("123456.789").fit_column(0, 0).unwrap().to_string(), "123456.789");
+
+        for too_big in ["1000", "999.995", "-1234.5"] {
+            let err = dec(too_big).fit(5, 2).unwrap_err();
+            assert_eq!(err.code, ErrorCode::ValidationNumericOverflow, "{}", too_big);
+        }
+        let huge = format!("1{}", "0".repeat(MAX_PRECISION as usize));
+        assert_eq!(huge.parse::<Decimal>().unwrap_err().code, ErrorCode::ValidationNumericOverflow);
+    }
+
+    #[test]
+    fn test_casts() {
+        assert_eq!(Decimal::from(-42).to_string(), "-42");
+        assert_eq!(dec("2.5").to_i64().unwrap(), 3);
+        assert_eq!(dec("-2.5").to_i64().unwrap(), -3);
+        assert_eq!(dec("-9223372036854775808").to_i64().unwrap(), i64::MIN);
+        assert_eq!(dec("9223372036854775807.4").to_i64().unwrap(), i64::MAX);
+        assert!(dec("9223372036854775807.5").to_i64().is_err());
+        assert!(dec("-9223372036854775809").to_i64().is_err());
+
+        assert_eq!(Decimal::from_f64(0.1).unwrap(), dec("0.1"));
+        assert_eq!(Decimal::from_f64(-1.25e-3).unwrap().to_string(), "-0.00125");
+        assert!(Decimal::from_f64(f64::NAN).is_err());
+        assert_eq!(Decimal::from_f64(f64::INFINITY).unwrap_err().code, ErrorCode::ValidationNumericOverflow);
+        assert_eq!(dec("0.3").to_f64().unwrap(), 0.3);
+        assert!(dec("1e400").to_f64().is_err());
+
+        for bad in ["", ".", "1.2.3", "12a", "--1", "1e"] {
+            assert!(bad.parse::<Decimal>().is_err(), "{:?}", bad);
+        }
+        assert_eq!(dec("+.5").to_string(), "0.5");
+        assert_eq!(dec("1.5E2").to_string(), "150");
+    }
+
+    #[test]
+    fn test_storage_encoding_round_trip() {
+        for text in ["0", "-0.001", "19.99", "123456789012345678901234567890.5"] {
+            let value = dec(text);
+            let decoded = Decimal::from_bytes(&value.to_bytes()).unwrap();
+            assert_eq!(decoded.to_string(), value.to_string());
+        }
+        assert!(Decimal::from_bytes(&[2, 0, 0]).is_err());
+        let mut truncated = dec("19.99").to_bytes();
+        truncated.pop();
+        assert!(Decimal::from_bytes(&truncated).is_err());
+    }
+}
@@ -0,0 +1,461 @@
+//! Intervals
+//!
+//! An `INTERVAL` keeps months, days and microseconds apart, as PostgreSQL
+//! does, because none of them converts exactly into another: a month is
+//! 28 to 31 days and a day is 23 to 25 hours across a DST change.
+//!
+//! - Months are added on the calendar, clamping to the end of a shorter
+//!   month: `'2024-01-31' + interval '1 month'` is `2024-02-29`.
+//! - Days are added on the wall clock. For a timestamptz that is the session
+//!   time zone's wall clock, so `+ interval '1 day'` keeps the local time of
+//!   day across a DST change while `+ interval '24 hours'` does not.
+//! - Microseconds are added to the instant.
+//!
+//! Comparison treats a month as 30 days and a day as 24 hours, so
+//! `interval '1 month' = interval '30 days'`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+use super::datetime::{out_of_range, unsupported, DateField};
+use super::timestamp::TimeZone;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+const DAYS_PER_MONTH: i64 = 30;
+
+/// A span of months, days and microseconds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    pub const fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self { months, days, micros }
+    }
+
+    /// Length in microseconds with 30-day months and 24-hour days, the
+    /// measure intervals are ordered by
+    pub fn span_micros(&self) -> i128 {
+        (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128 + self.micros as i128
+    }
+
+    pub fn checked_add(&self, other: &Interval) -> AuroraResult<Interval> {
+        Ok(Interval {
+            months: self.months.checked_add(other.months).ok_or_else(|| out_of_range("interval"))?,
+            days: self.days.checked_add(other.days).ok_or_else(|| out_of_range("interval"))?,
+            micros: self.micros.checked_add(other.micros).ok_or_else(|| out_of_range("interval"))?,
+        })
+    }
+
+    pub fn checked_neg(&self) -> AuroraResult<Interval> {
+        Ok(Interval {
+            months: self.months.checked_neg().ok_or_else(|| out_of_range("interval"))?,
+            days: self.days.checked_neg().ok_or_else(|| out_of_range("interval"))?,
+            micros: self.micros.checked_neg().ok_or_else(|| out_of_range("interval"))?,
+        })
+    }
+
+    /// `local + interval` for a timestamp without time zone
+    pub fn add_to_timestamp(&self, local: &NaiveDateTime) -> AuroraResult<NaiveDateTime> {
+        self.add_calendar(local)
+            .and_then(|shifted| shifted.checked_add_signed(Duration::microseconds(self.micros)))
+            .ok_or_else(|| out_of_range("timestamp"))
+    }
+
+    /// `instant + interval` for a timestamptz: months and days move the wall
+    /// clock of `zone`, microseconds move the instant
+    pub fn add_to_timestamptz(&self, instant: &DateTime<Utc>, zone: &TimeZone) -> AuroraResult<DateTime<Utc>> {
+        let moved = if self.months == 0 && self.days == 0 {
+            *instant
+        } else {
+            let local = self.add_calendar(&zone.to_local(instant)).ok_or_else(|| out_of_range("timestamp"))?;
+            zone.from_local(&local)
+        };
+        moved.checked_add_signed(Duration::microseconds(self.micros)).ok_or_else(|| out_of_range("timestamp"))
+    }
+
+    fn add_calendar(&self, local: &NaiveDateTime) -> Option<NaiveDateTime> {
+        add_months(local, self.months)?.checked_add_signed(Duration::days(self.days as i64))
+    }
+
+    /// `later - earlier` as whole days and a time of day, as timestamp
+    /// subtraction gives it
+    pub fn between(later: &NaiveDateTime, earlier: &NaiveDateTime) -> AuroraResult<Interval> {
+        let micros = (*later - *earlier).num_microseconds().ok_or_else(|| out_of_range("interval"))?;
+        let days = i32::try_from(micros / MICROS_PER_DAY).map_err(|_| out_of_range("interval"))?;
+        Ok(Interval::new(0, days, micros % MICROS_PER_DAY))
+    }
+
+    /// `age(later, earlier)`: the difference in years, months and days on
+    /// the calendar, borrowing the length of the earlier date's month when
+    /// its day of the month is the larger, so `age('2024-03-01', '2024-01-31')`
+    /// is `1 mon 1 day`
+    pub fn age(later: &NaiveDateTime, earlier: &NaiveDateTime) -> Interval {
+        let negative = later < earlier;
+        let fields = |t: &NaiveDateTime| [
+            t.year() as i64, t.month() as i64, t.day() as i64,
+            t.hour() as i64, t.minute() as i64, t.second() as i64, (t.nanosecond() / 1_000) as i64,
+        ];
+        let (a, b) = (fields(later), fields(earlier));
+        let mut d: Vec<i64> = a.iter().zip(&b).map(|(x, y)| x - y).collect();
+        if negative {
+            d.iter_mut().for_each(|field| *field = -*field);
+        }
+
+        // Borrow from the next larger field, smallest first
+        for (field, unit) in [(6, MICROS_PER_SECOND), (5, 60), (4, 60), (3, 24)] {
+            while d[field] < 0 {
+                d[field] += unit;
+                d[field - 1] -= 1;
+            }
+        }
+        let borrow_from = if negative { later } else { earlier };
+        while d[2] < 0 {
+            d[2] += days_in_month(borrow_from.year(), borrow_from.month()) as i64;
+            d[1] -= 1;
+        }
+        while d[1] < 0 {
+            d[1] += 12;
+            d[0] -= 1;
+        }
+
+        if negative {
+            d.iter_mut().for_each(|field| *field = -*field);
+        }
+        Interval {
+            months: (d[0] * 12 + d[1]) as i32,
+            days: d[2] as i32,
+            micros: d[3] * MICROS_PER_HOUR + d[4] * MICROS_PER_MINUTE + d[5] * MICROS_PER_SECOND + d[6],
+        }
+    }
+
+    /// `date_trunc(unit, interval)`: zero every field smaller than `field`
+    pub fn truncate(&self, field: DateField) -> AuroraResult<Interval> {
+        let months = |multiple: i32| Interval::new(self.months - self.months % multiple, 0, 0);
+        let micros = |multiple: i64| Interval::new(self.months, self.days, self.micros - self.micros % multiple);
+        Ok(match field {
+            DateField::Microseconds => *self,
+            DateField::Milliseconds => micros(1_000),
+            DateField::Second => micros(MICROS_PER_SECOND),
+            DateField::Minute => micros(MICROS_PER_MINUTE),
+            DateField::Hour => micros(MICROS_PER_HOUR),
+            DateField::Day => Interval::new(self.months, self.days, 0),
+            DateField::Month => months(1),
+            DateField::Quarter => months(3),
+            DateField::Year => months(12),
+            DateField::Decade => months(120),
+            DateField::Century => months(1_200),
+            DateField::Millennium => months(12_000),
+            // Months hold a fractional number of weeks
+            _ => return Err(unsupported(field, "interval")),
+        })
+    }
+
+    /// `date_part(field, interval)`: years and months split the months,
+    /// hours, minutes and seconds split the microseconds. `epoch` counts a
+    /// year as 365.25 days and a month as 30.
+    pub fn part(&self, field: DateField) -> AuroraResult<f64> {
+        let micros_of_minute = (self.micros % MICROS_PER_MINUTE) as f64;
+        let years = self.months / 12;
+        Ok(match field {
+            DateField::Microseconds => micros_of_minute,
+            DateField::Milliseconds => micros_of_minute / 1e3,
+            DateField::Second => micros_of_minute / 1e6,
+            DateField::Minute => (self.micros / MICROS_PER_MINUTE % 60) as f64,
+            DateField::Hour => (self.micros / MICROS_PER_HOUR) as f64,
+            DateField::Day => self.days as f64,
+            DateField::Month => (self.months % 12) as f64,
+            DateField::Quarter => ((self.months % 12) / 3 + 1) as f64,
+            DateField::Year => years as f64,
+            DateField::Decade => (years / 10) as f64,
+            DateField::Century => (years / 100) as f64,
+            DateField::Millennium => (years / 1000) as f64,
+            DateField::Epoch => {
+                let days = years as f64 * 365.25 + (self.months % 12) as f64 * DAYS_PER_MONTH as f64 + self.days as f64;
+                days * 86_400.0 + self.micros as f64 / 1e6
+            }
+            _ => return Err(unsupported(field, "interval")),
+        })
+    }
+}
+
+/// Days in `month` (1-12) of `year`
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// Move `local` by `months` calendar months, clamping the day of the month
+fn add_months(local: &NaiveDateTime, months: i32) -> Option<NaiveDateTime> {
+    if months == 0 {
+        return Some(*local);
+    }
+    let total = local.year() as i64 * 12 + local.month0() as i64 + months as i64;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+    let day = local.day().min(days_in_month(year, month));
+    NaiveDate::from_ymd_opt(year, month, day).map(|date| date.and_time(local.time()))
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.span_micros() == other.span_micros()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.span_micros().cmp(&other.span_micros())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Equal spans hash alike
+        self.span_micros().hash(state);
+    }
+}
+
+/// Interval input being accumulated, with fractions of larger units
+/// spilled into smaller ones
+#[derive(Default)]
+struct Accumulator {
+    months: i64,
+    days: i64,
+    micros: f64,
+}
+
+impl Accumulator {
+    fn add_months(&mut self, months: f64) {
+        self.months += months.trunc() as i64;
+        self.add_days(months.fract() * DAYS_PER_MONTH as f64);
+    }
+
+    fn add_days(&mut self, days: f64) {
+        self.days += days.trunc() as i64;
+        self.micros += days.fract() * MICROS_PER_DAY as f64;
+    }
+
+    fn finish(self) -> Option<Interval> {
+        let micros = self.micros.round();
+        if !micros.is_finite() || micros.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        Some(Interval::new(i32::try_from(self.months).ok()?, i32::try_from(self.days).ok()?, micros as i64))
+    }
+}
+
+/// `[-]hh:mm[:ss[.ffffff]]` as microseconds
+fn parse_clock(text: &str) -> Option<f64> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let parts: Vec<&str> = unsigned.split(':').collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|part| part.is_empty()) {
+        return None;
+    }
+    let hours: u64 = parts[0].parse().ok()?;
+    let minutes: u64 = parts[1].parse().ok()?;
+    let seconds: f64 = match parts.get(2) {
+        Some(seconds) if seconds.chars().all(|c| c.is_ascii_digit() || c == '.') => seconds.parse().ok()?,
+        Some(_) => return None,
+        None => 0.0,
+    };
+    if minutes > 59 || seconds >= 60.0 {
+        return None;
+    }
+    Some(sign * ((hours * 60 + minutes) as f64 * MICROS_PER_MINUTE as f64 + seconds * 1e6))
+}
+
+impl FromStr for Interval {
+    type Err = AuroraError;
+
+    /// `1 day`, `2 hours 30 minutes`, `1 year 2 mons -3 days`, `1.5 weeks`,
+    /// `04:05:06`, `3 days 04:05:06.5`, optionally ending in `ago`
+    fn from_str(text: &str) -> AuroraResult<Self> {
+        let invalid = || AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("invalid interval '{}'", text));
+        let mut words: Vec<&str> = text.split_whitespace().collect();
+        if words.first() == Some(&"@") {
+            words.remove(0);
+        }
+        let ago = words.last().is_some_and(|word| word.eq_ignore_ascii_case("ago"));
+        if ago {
+            words.pop();
+        }
+        if words.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut total = Accumulator::default();
+        let mut words = words.into_iter();
+        while let Some(word) = words.next() {
+            if word.contains(':') {
+                total.micros += parse_clock(word).ok_or_else(invalid)?;
+                continue;
+            }
+            let quantity: f64 = word.parse().map_err(|_| invalid())?;
+            if !quantity.is_finite() {
+                return Err(invalid());
+            }
+            let micros = |unit: i64| quantity * unit as f64;
+            match words.next().ok_or_else(invalid)?.to_lowercase().as_str() {
+                "microsecond" | "microseconds" | "us" | "usec" | "usecs" => total.micros += quantity,
+                "millisecond" | "milliseconds" | "ms" | "msec" | "msecs" => total.micros += micros(1_000),
+                "second" | "seconds" | "sec" | "secs" | "s" => total.micros += micros(MICROS_PER_SECOND),
+                "minute" | "minutes" | "min" | "mins" | "m" => total.micros += micros(MICROS_PER_MINUTE),
+                "hour" | "hours" | "hr" | "hrs" | "h" => total.micros += micros(MICROS_PER_HOUR),
+                "day" | "days" | "d" => total.add_days(quantity),
+                "week" | "weeks" | "w" => total.add_days(quantity * 7.0),
+                "month" | "months" | "mon" | "mons" => total.add_months(quantity),
+                "year" | "years" | "yr" | "yrs" | "y" => total.add_months(quantity * 12.0),
+                "decade" | "decades" => total.add_months(quantity * 120.0),
+                "century" | "centuries" => total.add_months(quantity * 1_200.0),
+                "millennium" | "millennia" | "millenniums" => total.add_months(quantity * 12_000.0),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let interval = total.finish().ok_or_else(|| out_of_range("interval"))?;
+        if ago { interval.checked_neg() } else { Ok(interval) }
+    }
+}
+
+impl fmt::Display for Interval {
+    /// PostgreSQL's default output: `1 year 2 mons 3 days 04:05:06.5`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (count, unit) in [(self.months / 12, "year"), (self.months % 12, "mon"), (self.days, "day")] {
+            if count != 0 {
+                parts.push(format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" }));
+            }
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            let micros = self.micros.unsigned_abs();
+            let (hours, minutes) = (micros / MICROS_PER_HOUR as u64, micros / MICROS_PER_MINUTE as u64 % 60);
+            let (seconds, fraction) = (micros / MICROS_PER_SECOND as u64 % 60, micros % MICROS_PER_SECOND as u64);
+            let mut clock = format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds);
+            if fraction != 0 {
+                clock.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+            }
+            parts.push(clock);
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::timestamp::{format_timestamp, parse_timestamp, parse_timestamptz};
+
+    fn interval(text: &str) -> Interval {
+        text.parse().unwrap()
+    }
+
+    fn plus(text: &str, span: &str) -> String {
+        format_timestamp(&interval(span).add_to_timestamp(&parse_timestamp(text).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for (input, output) in [
+            ("1 day", "1 day"),
+            ("2 hours 30 minutes", "02:30:00"),
+            ("1 year 2 mons 3 days 04:05:06.5", "1 year 2 mons 3 days 04:05:06.5"),
+            ("14 months", "1 year 2 mons"),
+            ("-1 days 2 hours", "-1 days 02:00:00"),
+            ("1.5 days", "1 day 12:00:00"),
+            ("1.5 months", "1 mon 15 days"),
+            ("2 weeks", "14 days"),
+            ("3 days ago", "-3 days"),
+            ("-00:00:00.25", "-00:00:00.25"),
+            ("0 seconds", "00:00:00"),
+        ] {
+            assert_eq!(interval(input).to_string(), output, "{}", input);
+            // The output form reads back as the same value
+            assert_eq!(interval(output).to_string(), output);
+        }
+
+        for input in ["", "1", "1 fortnight", "day 1", "1:75", "ago"] {
+            assert!(input.parse::<Interval>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_month_arithmetic_clamps_to_month_end() {
+        assert_eq!(plus("2024-01-31 10:00:00", "1 month"), "2024-02-29 10:00:00");
+        assert_eq!(plus("2023-01-31 10:00:00", "1 month"), "2023-02-28 10:00:00");
+        assert_eq!(plus("2024-03-31 00:00:00", "-1 month"), "2024-02-29 00:00:00");
+        assert_eq!(plus("2024-02-29 00:00:00", "1 year"), "2025-02-28 00:00:00");
+        assert_eq!(plus("2024-12-15 00:00:00", "1 mon 20 days"), "2025-02-04 00:00:00");
+        // Months first, then days: Jan 31 -> Feb 29 -> Mar 1
+        assert_eq!(plus("2024-01-31 00:00:00", "1 mon 1 day"), "2024-03-01 00:00:00");
+        assert_eq!(plus("2024-01-01 23:00:00", "2 hours"), "2024-01-02 01:00:00");
+    }
+
+    #[test]
+    fn test_day_keeps_local_time_across_dst() {
+        let new_york: TimeZone = "America/New_York".parse().unwrap();
+        let before = parse_timestamptz("2024-03-09 12:00:00", &new_york).unwrap();
+
+        let next_day = interval("1 day").add_to_timestamptz(&before, &new_york).unwrap();
+        assert_eq!(new_york.format(&next_day), "2024-03-10 12:00:00-04");
+        assert_eq!(next_day - before, Duration::hours(23));
+
+        let day_later = interval("24 hours").add_to_timestamptz(&before, &new_york).unwrap();
+        assert_eq!(new_york.format(&day_later), "2024-03-10 13:00:00-04");
+    }
+
+    #[test]
+    fn test_ordering_and_age() {
+        assert_eq!(interval("1 mon"), interval("30 days"));
+        assert!(interval("1 day") < interval("25 hours"));
+        assert!(interval("-1 day") < interval("00:00:01"));
+
+        let age = |later: &str, earlier: &str| {
+            Interval::age(&parse_timestamp(later).unwrap(), &parse_timestamp(earlier).unwrap()).to_string()
+        };
+        assert_eq!(age("2024-03-01", "2024-01-31"), "1 mon 1 day");
+        assert_eq!(age("2024-06-15 08:00:00", "1990-09-20 12:30:00"), "33 years 8 mons 24 days 19:30:00");
+        assert_eq!(age("2024-01-31", "2024-03-01"), "-1 mons -1 days");
+
+        let between = Interval::between(&parse_timestamp("2024-03-02 06:00").unwrap(), &parse_timestamp("2024-02-28 00:00").unwrap()).unwrap();
+        assert_eq!(between.to_string(), "3 days 06:00:00");
+    }
+
+    #[test]
+    fn test_truncate_and_part() {
+        let value = interval("3 years 5 mons 10 days 14:35:52.5");
+        assert_eq!(value.truncate(DateField::Year).unwrap().to_string(), "3 years");
+        assert_eq!(value.truncate(DateField::Day).unwrap().to_string(), "3 years 5 mons 10 days");
+        assert_eq!(value.truncate(DateField::Minute).unwrap().to_string(), "3 years 5 mons 10 days 14:35:00");
+        assert!(value.truncate(DateField::Week).is_err());
+
+        assert_eq!(value.part(DateField::Year).unwrap(), 3.0);
+        assert_eq!(value.part(DateField::Month).unwrap(), 5.0);
+        assert_eq!(value.part(DateField::Hour).unwrap(), 14.0);
+        assert_eq!(value.part(DateField::Second).unwrap(), 52.5);
+        assert_eq!(interval("1 day 1 second").part(DateField::Epoch).unwrap(), 86_401.0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Timestamps With and Without Time Zone
+//!
+//! `TIMESTAMP WITH TIME ZONE` (`TIMESTAMPTZ`) values are UTC instants. Input
+//! with an explicit offset or zone name is converted from it; input without
+//! one is read in the session's `timezone`. Output is rendered in the session
+//! time zone, so changing the setting changes how a value is shown but never
+//! the stored instant, and two values compare equal when they name the same
+//! instant however they were entered.
+//!
+//! `TIMESTAMP [WITHOUT TIME ZONE]` values are wall-clock readings with no
+//! zone attached; an offset in their input is ignored.
+//!
+//! `AT TIME ZONE` converts between the two:
+//!
+//! ```sql
+//! SELECT created_at AT TIME ZONE 'Asia/Tokyo' FROM events   -- timestamptz -> timestamp
+//! SELECT local_time AT TIME ZONE 'Europe/Paris' FROM shifts -- timestamp -> timestamptz
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, Offset, SecondsFormat, TimeZone as _, Timelike, Utc};
+use crate::core::{AuroraResult, AuroraError, ErrorCode};
+
+const INPUT_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// A session or `AT TIME ZONE` time zone: a fixed UTC offset or an IANA zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    Fixed(FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        TimeZone::Fixed(Utc.fix())
+    }
+}
+
+impl TimeZone {
+    /// Offset from UTC in effect at `instant`
+    pub fn offset_at(&self, instant: &DateTime<Utc>) -> FixedOffset {
+        match self {
+            TimeZone::Fixed(offset) => *offset,
+            TimeZone::Named(tz) => tz.offset_from_utc_datetime(&instant.naive_utc()).fix(),
+        }
+    }
+
+    /// Wall-clock reading of `instant` in this zone
+    pub fn to_local(&self, instant: &DateTime<Utc>) -> NaiveDateTime {
+        instant.naive_utc() + Duration::seconds(self.offset_at(instant).local_minus_utc() as i64)
+    }
+
+    /// Instant a wall-clock reading in this zone names. An ambiguous reading
+    /// (clocks turned back) takes the earlier instant; a reading skipped by a
+    /// forward transition is read with the offset in effect before it.
+    pub fn from_local(&self, local: &NaiveDateTime) -> DateTime<Utc> {
+        let tz = match self {
+            TimeZone::Fixed(offset) => {
+                let utc = *local - Duration::seconds(offset.local_minus_utc() as i64);
+                return Utc.from_utc_datetime(&utc);
+            }
+            TimeZone::Named(tz) => tz,
+        };
+        match tz.from_local_datetime(local) {
+            LocalResult::Single(time) => time.with_timezone(&Utc),
+            LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+            LocalResult::None => {
+                let before = tz.offset_from_utc_datetime(&(*local - Duration::days(1))).fix();
+                let utc = *local - Duration::seconds(before.local_minus_utc() as i64);
+                Utc.from_utc_datetime(&utc)
+            }
+        }
+    }
+
+    /// Render a timestamptz for display, e.g. `2024-03-10 07:00:00-05`
+    pub fn format(&self, instant: &DateTime<Utc>) -> String {
+        let offset = self.offset_at(instant).local_minus_utc();
+        let sign = if offset < 0 { '-' } else { '+' };
+        let (hours, minutes) = (offset.abs() / 3600, offset.abs() % 3600 / 60);
+        let mut text = format_timestamp(&self.to_local(instant));
+        text.push_str(&format!("{}{:02}", sign, hours));
+        if minutes != 0 {
+            text.push_str(&format!(":{:02}", minutes));
+        }
+        text
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = AuroraError;
+
+    /// `UTC`, a numeric offset such as `+05:30` or `-08`, or an IANA name
+    /// such as `America/New_York`
+    fn from_str(text: &str) -> AuroraResult<Self> {
+        let name = text.trim();
+        if matches!(name.to_uppercase().as_str(), "UTC" | "Z" | "GMT") {
+            return Ok(TimeZone::default());
+        }
+        if let Some(offset) = parse_offset(name) {
+            return Ok(TimeZone::Fixed(offset));
+        }
+        name.parse::<chrono_tz::Tz>()
+            .map(TimeZone::Named)
+            .map_err(|_| AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("unknown time zone '{}'", name)))
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeZone::Fixed(offset) if offset.local_minus_utc() == 0 => f.write_str("UTC"),
+            TimeZone::Fixed(offset) => write!(f, "{}", offset),
+            TimeZone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+/// `+HH`, `+HH:MM` or `+HHMM` (or with `-`)
+fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let sign = match text.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = text[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) || !matches!(digits.len(), 2 | 4) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = if digits.len() == 4 { digits[2..].parse().ok()? } else { 0 };
+    if hours > 15 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Split timestamp input into its wall-clock part and any zone suffix:
+/// `Z`, a numeric offset, or a space-separated zone name
+fn split_zone(text: &str) -> AuroraResult<(&str, Option<TimeZone>)> {
+    let text = text.trim();
+    if let Some(local) = text.strip_suffix('Z').or_else(|| text.strip_suffix('z')) {
+        return Ok((local, Some(TimeZone::default())));
+    }
+    // Offsets follow the time, so look past the date's own dashes
+    if let Some(at) = text.get(10..).and_then(|time| time.rfind(['+', '-'])).map(|at| at + 10) {
+        if let Some(offset) = parse_offset(&text[at..]) {
+            return Ok((text[..at].trim_end(), Some(TimeZone::Fixed(offset))));
+        }
+    }
+    if let Some((local, zone)) = text.rsplit_once(' ') {
+        if zone.chars().any(|c| c.is_ascii_alphabetic()) {
+            return Ok((local.trim_end(), Some(zone.parse()?)));
+        }
+    }
+    Ok((text, None))
+}
+
+fn parse_local(text: &str) -> AuroraResult<NaiveDateTime> {
+    INPUT_FORMATS.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|date| date.and_hms_opt(0, 0, 0)))
+        .ok_or_else(|| AuroraError::new(ErrorCode::ValidationInvalidFormat, format!("invalid timestamp '{}'", text)))
+}
+
+/// Parse `TIMESTAMP WITHOUT TIME ZONE` input; a zone suffix is ignored
+pub fn parse_timestamp(text: &str) -> AuroraResult<NaiveDateTime> {
+    let (local, _) = split_zone(text)?;
+    parse_local(local)
+}
+
+/// Parse `TIMESTAMP WITH TIME ZONE` input, reading it in `session` when it
+/// carries no zone of its own
+pub fn parse_timestamptz(text: &str, session: &TimeZone) -> AuroraResult<DateTime<Utc>> {
+    let (local, zone) = split_zone(text)?;
+    Ok(zone.as_ref().unwrap_or(session).from_local(&parse_local(local)?))
+}
+
+/// Render a timestamp without time zone, with microseconds only when present
+pub fn format_timestamp(local: &NaiveDateTime) -> String {
+    let micros = local.nanosecond() / 1_000;
+    if micros == 0 {
+        local.format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        format!("{}.{:06}", local.format("%Y-%m-%d %H:%M:%S"), micros)
+    }
+}
+
+/// Storage form of a timestamptz: RFC 3339 in UTC with fixed-width
+/// microseconds, which also sorts in time order
+pub fn storage_form(instant: &DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str) -> TimeZone {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn test_convert_between_zones() {
+        let instant = parse_timestamptz("2024-07-01 12:00:00+00", &TimeZone::default()).unwrap();
+        assert_eq!(format_timestamp(&zone("America/New_York").to_local(&instant)), "2024-07-01 08:00:00");
+        assert_eq!(format_timestamp(&zone("Asia/Kolkata").to_local(&instant)), "2024-07-01 17:30:00");
+
+        let local = parse_timestamp("2024-01-15 09:00:00").unwrap();
+        assert_eq!(storage_form(&zone("Europe/Paris").from_local(&local)), "2024-01-15T08:00:00.000000Z");
+
+        // 02:30 does not exist on this spring-forward day; it reads as 03:30 EDT
+        let skipped = parse_timestamp("2024-03-10 02:30:00").unwrap();
+        assert_eq!(zone("America/New_York").format(&zone("America/New_York").from_local(&skipped)), "2024-03-10 03:30:00-04");
+    }
+
+    #[test]
+    fn test_same_instant_compares_equal_across_zones() {
+        let session = zone("Asia/Tokyo");
+        let a = parse_timestamptz("2024-01-01 12:00:00+02", &session).unwrap();
+        let b = parse_timestamptz("2024-01-01T11:00:00+01:00", &session).unwrap();
+        let c = parse_timestamptz("2024-01-01 19:00:00", &session).unwrap();
+        let d = parse_timestamptz("2024-01-01 05:00:00 America/New_York", &session).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(a, d);
+        assert!(a < parse_timestamptz("2024-01-01 10:00:01Z", &session).unwrap());
+        assert!(storage_form(&a) < storage_form(&(a + Duration::microseconds(1))));
+    }
+
+    #[test]
+    fn test_session_zone_changes_rendering_only() {
+        let instant = parse_timestamptz("2024-07-01 12:00:00.25Z", &TimeZone::default()).unwrap();
+        assert_eq!(TimeZone::default().format(&instant), "2024-07-01 12:00:00.250000+00");
+        assert_eq!(zone("America/Los_Angeles").format(&instant), "2024-07-01 05:00:00.250000-07");
+        assert_eq!(zone("+05:30").format(&instant), "2024-07-01 17:30:00.250000+05:30");
+        assert_eq!(storage_form(&instant), "2024-07-01T12:00:00.250000Z");
+
+        // Without time zone, an offset in the input is ignored
+        assert_eq!(format_timestamp(&parse_timestamp("2024-07-01 12:00:00+09").unwrap()), "2024-07-01 12:00:00");
+        assert!("Mars/Olympus_Mons".parse::<TimeZone>().is_err());
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+}
@@ -9,6 +9,7 @@
 use std::collections::{HashMap, HashSet, BTreeMap, BinaryHeap};
 use crate::core::errors::{AuroraResult, AuroraError};
 use super::super::distance_metrics::{DistanceComputer, DistanceMetric};
+use crate::query::indexes::{FullTextIndex, FullTextIndexConfig, TextQuery};
 
 /// Hybrid search engine combining vector, keyword, and metadata search
 pub struct HybridSearchEngine {
@@ -181,6 +182,8 @@ pub enum KeywordOperator {
     And,
     Or,
     Phrase,
+    /// Keywords hold text search syntax: `OR`, `-term`, quoted phrases
+    Query,
 }
 
 /// Hybrid search modes
@@ -275,170 +278,75 @@ impl VectorSearchComponent {
     }
 }
 
-/// Keyword search component using BM25
+/// Keyword search component: BM25 over the shared full-text index, so
+/// hybrid search and SQL `@@` analyze and rank text the same way
 pub struct KeywordSearchComponent {
-    documents: HashMap<usize, Vec<String>>, // Document ID -> terms
-    term_frequency: HashMap<String, HashMap<usize, usize>>, // Term -> (Doc ID -> frequency)
-    document_frequency: HashMap<String, usize>, // Term -> document count
-    total_documents: usize,
-    avg_document_length: f64,
-    document_lengths: HashMap<usize, usize>,
+    index: FullTextIndex,
 }
 
 impl KeywordSearchComponent {
     fn new() -> Self {
+        let config = FullTextIndexConfig {
+            name: "hybrid_keywords".to_string(),
+            column: "text".to_string(),
+            language: "english".to_string(),
+            enable_stemming: true,
+            enable_stopwords: true,
+        };
         Self {
-            documents: HashMap::new(),
-            term_frequency: HashMap::new(),
-            document_frequency: HashMap::new(),
-            total_documents: 0,
-            avg_document_length: 0.0,
-            document_lengths: HashMap::new(),
+            index: FullTextIndex::new(config).expect("english analyzer is always available"),
         }
     }
 
     async fn search(&self, query: &KeywordQuery, k: usize) -> AuroraResult<Vec<(usize, f32)>> {
-        if self.documents.is_empty() {
+        let Some(text_query) = self.text_query(query)? else {
             return Ok(Vec::new());
-        }
-
-        let query_terms = self.tokenize_query(&query.keywords, &query.operator);
-        let mut scores = HashMap::new();
-
-        for term in &query_terms {
-            if let Some(doc_freq) = self.document_frequency.get(term) {
-                let idf = self.calculate_idf(*doc_freq);
-
-                if let Some(term_docs) = self.term_frequency.get(term) {
-                    for (&doc_id, &tf) in term_docs {
-                        let doc_length = self.document_lengths.get(&doc_id).copied().unwrap_or(1);
-                        let bm25_score = self.calculate_bm25(tf, idf, doc_length);
-
-                        *scores.entry(doc_id).or_insert(0.0) += bm25_score;
-                    }
-                }
-            }
-        }
+        };
 
-        // Sort by score and return top k
-        let mut results: Vec<(usize, f32)> = scores.into_iter()
-            .map(|(id, score)| (id, score))
+        let mut results: Vec<(usize, f32)> = self.index.search_query(&text_query)
+            .into_iter()
+            .map(|(id, score)| (id as usize, score as f32))
             .collect();
-
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         results.truncate(k);
 
         Ok(results)
     }
 
-    fn add_document(&mut self, id: usize, text: &str) -> AuroraResult<()> {
-        let terms = self.tokenize_text(text);
-        let doc_length = terms.len();
-
-        self.documents.insert(id, terms.clone());
-        self.document_lengths.insert(id, doc_length);
-        self.total_documents += 1;
-
-        // Update term frequencies
-        for term in terms {
-            // Update term frequency for this document
-            self.term_frequency.entry(term.clone())
-                .or_insert_with(HashMap::new)
-                .entry(id)
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
-
-            // Update document frequency
-            self.document_frequency.entry(term)
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
-        }
-
-        // Update average document length
-        let total_length: usize = self.document_lengths.values().sum();
-        self.avg_document_length = total_length as f64 / self.total_documents as f64;
-
-        Ok(())
-    }
-
-    fn update_document(&mut self, id: usize, text: &str) -> AuroraResult<()> {
-        // Remove old document
-        self.delete_document(id)?;
-        // Add new document
-        self.add_document(id, text)
-    }
-
-    fn delete_document(&mut self, id: usize) -> AuroraResult<()> {
-        if let Some(terms) = self.documents.remove(&id) {
-            self.document_lengths.remove(&id);
-            self.total_documents = self.total_documents.saturating_sub(1);
-
-            // Update term frequencies and document frequencies
-            for term in terms {
-                if let Some(term_docs) = self.term_frequency.get_mut(&term) {
-                    term_docs.remove(&id);
-                    if term_docs.is_empty() {
-                        self.term_frequency.remove(&term);
-                        self.document_frequency.remove(&term);
-                    } else {
-                        *self.document_frequency.get_mut(&term).unwrap() -= 1;
-                    }
-                }
-            }
-
-            // Update average document length
-            let total_length: usize = self.document_lengths.values().sum();
-            self.avg_document_length = if self.total_documents > 0 {
-                total_length as f64 / self.total_documents as f64
-            } else {
-                0.0
+    /// Translate keywords and operator into a text query; `None` when no
+    /// keyword survives analysis
+    fn text_query(&self, query: &KeywordQuery) -> AuroraResult<Option<TextQuery>> {
+        let analyzer = self.index.analyzer();
+        if let KeywordOperator::Query = query.operator {
+            return match query.keywords.join(" ").trim() {
+                "" => Ok(None),
+                text => analyzer.parse_query(text).map(Some),
             };
         }
-
-        Ok(())
-    }
-
-    fn tokenize_query(&self, keywords: &[String], operator: &KeywordOperator) -> Vec<String> {
-        match operator {
-            KeywordOperator::Phrase => {
-                // For phrase search, treat as single term
-                vec![keywords.join(" ")]
-            }
-            _ => {
-                // For AND/OR, tokenize each keyword
-                keywords.iter()
-                    .flat_map(|kw| self.tokenize_text(kw))
-                    .collect()
-            }
+        if let KeywordOperator::Phrase = query.operator {
+            return Ok(TextQuery::phrase(analyzer, &query.keywords.join(" ")));
         }
+        let mut terms: Vec<TextQuery> = query.keywords.iter()
+            .filter_map(|keyword| TextQuery::phrase(analyzer, keyword))
+            .collect();
+        Ok(match (terms.len(), &query.operator) {
+            (0, _) => None,
+            (1, _) => terms.pop(),
+            (_, KeywordOperator::And) => Some(TextQuery::And(terms)),
+            _ => Some(TextQuery::Or(terms)),
+        })
     }
 
-    fn tokenize_text(&self, text: &str) -> Vec<String> {
-        // Simple tokenization: lowercase, split on whitespace, remove punctuation
-        text.to_lowercase()
-            .split_whitespace()
-            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-            .filter(|word| !word.is_empty())
-            .collect()
+    fn add_document(&mut self, id: usize, text: &str) -> AuroraResult<()> {
+        self.index.insert(id as u64, text)
     }
 
-    fn calculate_idf(&self, doc_freq: usize) -> f64 {
-        let n = self.total_documents as f64;
-        let df = doc_freq as f64;
-        ((n - df + 0.5) / (df + 0.5)).ln()
+    fn update_document(&mut self, id: usize, text: &str) -> AuroraResult<()> {
+        // Insert replaces any earlier version of the document
+        self.index.insert(id as u64, text)
     }
 
-    fn calculate_bm25(&self, tf: usize, idf: f64, doc_length: usize) -> f64 {
-        const K1: f64 = 1.5; // BM25 parameter
-        const B: f64 = 0.75; // BM25 parameter
-
-        let tf_float = tf as f64;
-        let doc_length_float = doc_length as f64;
-
-        let numerator = tf_float * (K1 + 1.0);
-        let denominator = tf_float + K1 * (1.0 - B + B * doc_length_float / self.avg_document_length);
-
-        idf * numerator / denominator
+    fn delete_document(&mut self, id: usize) -> AuroraResult<()> {
+        self.index.remove(id as u64)
     }
 }
 
@@ -837,6 +745,24 @@ mod tests {
         assert_eq!(results[0].0, 1);
     }
 
+    #[tokio::test]
+    async fn test_keyword_query_syntax() {
+        let mut keyword_search = KeywordSearchComponent::new();
+
+        keyword_search.add_document(1, "vector indexes for semantic search").unwrap();
+        keyword_search.add_document(2, "keyword search with inverted indexes").unwrap();
+        keyword_search.add_document(3, "semantic caching").unwrap();
+
+        let query = KeywordQuery {
+            keywords: vec!["search -vector".to_string()],
+            operator: KeywordOperator::Query,
+        };
+
+        let results = keyword_search.search(&query, 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 2);
+    }
+
     #[tokio::test]
     async fn test_update_content() {
         let mut engine = HybridSearchEngine::new().unwrap();
@@ -49,6 +49,12 @@ pub struct HNSWIndex {
 
     /// Random number generator for level assignment
     rng: fastrand::Rng,
+
+    /// Vectors inserted, replaced or deleted since the index was created
+    mutations: u64,
+
+    /// Last recall calibration and the ground truth it was measured against
+    calibration: RwLock<Option<(RecallCalibration, RecallGroundTruth)>>,
 }
 
 impl HNSWIndex {
@@ -71,6 +77,8 @@ impl HNSWIndex {
             graph: RwLock::new(Vec::new()),
             levels: RwLock::new(HashMap::new()),
             rng: fastrand::Rng::new(),
+            mutations: 0,
+            calibration: RwLock::new(None),
         }
     }
 
@@ -123,12 +131,129 @@ impl HNSWIndex {
         if self.entry_point.is_none() || level == max_level {
             self.entry_point = Some(id);
         }
+        self.mutations += 1;
 
         Ok(())
     }
 
+    /// Insert or replace a batch of vectors, building graph links once for the batch
+    ///
+    /// Every vector is checked against the index dimension before anything is
+    /// stored, so a mismatch leaves the index untouched. Vectors are stored
+    /// first and then linked under a single graph lock. An id that is already
+    /// indexed keeps its levels and is relinked against its new vector rather
+    /// than gaining a second node. Items with non-finite components fail on
+    /// their own without affecting the rest of the batch.
+    pub fn upsert_batch(&mut self, items: Vec<(usize, Vec<f32>)>) -> AuroraResult<Vec<AuroraResult<UpsertOutcome>>> {
+        if let Some((position, (_, vector))) = items.iter().enumerate().find(|(_, (_, vector))| vector.len() != self.dimension) {
+            return Err(AuroraError::Vector(format!(
+                "Vector dimension mismatch at batch item {}: expected {}, got {}",
+                position, self.dimension, vector.len()
+            )));
+        }
+
+        // Items apply in order, so a later item for an id replaces an earlier one
+        let mut outcomes = Vec::with_capacity(items.len());
+        let mut batch: Vec<(usize, Vec<f32>)> = Vec::new();
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+        let mut updated: HashSet<usize> = HashSet::new();
+        {
+            let vectors = self.vectors.read();
+            for (id, vector) in items {
+                if vector.iter().any(|value| !value.is_finite()) {
+                    outcomes.push(Err(AuroraError::Vector(format!("Vector {} has non-finite components", id))));
+                    continue;
+                }
+                if let Some(&position) = positions.get(&id) {
+                    batch[position].1 = vector;
+                    outcomes.push(Ok(UpsertOutcome::Updated));
+                } else if vectors.contains_key(&id) {
+                    updated.insert(id);
+                    positions.insert(id, batch.len());
+                    batch.push((id, vector));
+                    outcomes.push(Ok(UpsertOutcome::Updated));
+                } else {
+                    positions.insert(id, batch.len());
+                    batch.push((id, vector));
+                    outcomes.push(Ok(UpsertOutcome::Inserted));
+                }
+            }
+        }
+
+        if batch.is_empty() {
+            return Ok(outcomes);
+        }
+
+        // Existing ids keep their levels; new ids draw one
+        let existing_levels: HashMap<usize, i32> = {
+            let levels = self.levels.read();
+            updated.iter()
+                .map(|id| (*id, levels.get(id).and_then(|l| l.iter().max().copied()).unwrap_or(0)))
+                .collect()
+        };
+        let mut planned = Vec::with_capacity(batch.len());
+        for (id, vector) in batch {
+            let level = match existing_levels.get(&id) {
+                Some(&level) => level,
+                None => self.generate_level(),
+            };
+            planned.push((id, vector, level));
+        }
+
+        let mut graph = self.graph.write();
+        let top_level = planned.iter().map(|(_, _, level)| *level).max().unwrap_or(0);
+        while graph.len() <= top_level as usize {
+            graph.push(HashMap::new());
+        }
+
+        // Drop every link touching an updated node in one pass over the graph
+        if !updated.is_empty() {
+            for level_graph in graph.iter_mut() {
+                level_graph.retain(|node, _| !updated.contains(node));
+                for neighbors in level_graph.values_mut() {
+                    neighbors.retain(|neighbor| !updated.contains(neighbor));
+                }
+            }
+            if self.entry_point.is_some_and(|ep| updated.contains(&ep)) {
+                self.entry_point = self.find_new_entry_point(&graph);
+            }
+        }
+
+        // Store every vector before linking so neighbor selection sees the whole batch
+        {
+            let mut vectors = self.vectors.write();
+            let mut levels = self.levels.write();
+            for (id, vector, level) in &planned {
+                vectors.insert(*id, vector.clone());
+                levels.insert(*id, (0..=*level).collect());
+            }
+        }
+
+        for (id, vector, level) in &planned {
+            let mut entry_point = self.entry_point;
+            for current_level in (1..=*level).rev() {
+                entry_point = self.insert_at_level(&mut graph, *id, vector, current_level, entry_point);
+            }
+            self.insert_at_level(&mut graph, *id, vector, 0, entry_point);
+
+            if self.entry_point.is_none() || *level > self.max_level {
+                self.max_level = self.max_level.max(*level);
+                self.entry_point = Some(*id);
+            }
+        }
+        self.mutations += planned.len() as u64;
+
+        Ok(outcomes)
+    }
+
     /// Search for k nearest neighbors
     pub fn search(&self, query: &[f32], k: usize, ef: usize) -> AuroraResult<Vec<(usize, f32)>> {
+        self.search_counted(query, k, ef).map(|(results, _)| results)
+    }
+
+    /// Search for k nearest neighbors, also returning how many nodes had their
+    /// distance to the query computed
+    pub fn search_counted(&self, query: &[f32], k: usize, ef: usize) -> AuroraResult<(Vec<(usize, f32)>, u64)> {
         if query.len() != self.dimension {
             return Err(AuroraError::Vector(format!(
                 "Query vector dimension mismatch: expected {}, got {}",
@@ -137,7 +262,7 @@ impl HNSWIndex {
         }
 
         if self.entry_point.is_none() {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), 0));
         }
 
         let graph = self.graph.read();
@@ -145,14 +270,15 @@ impl HNSWIndex {
 
         // Start search from entry point
         let mut current = self.entry_point.unwrap();
+        let mut visited = 0;
 
         // Find closest node at the top level
         for level in (1..=self.max_level).rev() {
-            current = self.search_layer(&graph[level as usize], &vectors, query, current, 1);
+            current = self.search_layer(&graph[level as usize], &vectors, query, current, &mut visited);
         }
 
         // Search at base level with beam search
-        let candidates = self.search_layer_beam(&graph[0], &vectors, query, current, ef);
+        let candidates = self.search_layer_beam(&graph[0], &vectors, query, current, ef, &mut visited);
 
         // Select k best candidates
         let mut results: Vec<(usize, f32)> = candidates.into_iter()
@@ -173,7 +299,7 @@ impl HNSWIndex {
         }
 
         results.truncate(k);
-        Ok(results)
+        Ok((results, visited as u64))
     }
 
     /// Delete a vector from the index
@@ -195,7 +321,9 @@ impl HNSWIndex {
         }
 
         let mut vectors = self.vectors.write();
-        vectors.remove(&id);
+        if vectors.remove(&id).is_some() {
+            self.mutations += 1;
+        }
 
         // Update entry point if necessary
         if self.entry_point == Some(id) {
@@ -205,6 +333,129 @@ impl HNSWIndex {
         Ok(())
     }
 
+    /// Choose the smallest ef_search whose searches reach `target_recall`
+    /// on `ground_truth`, and cache it for [`Self::search_for_recall`]
+    ///
+    /// Recall is the share of each query's true `k` nearest neighbors that
+    /// the search returns, averaged over the queries. It grows with
+    /// ef_search, so ef_search is doubled from `k` until the target is met,
+    /// which keeps the expensive wide searches few, and the smallest
+    /// sufficient value is then found by bisecting the last step.
+    pub fn calibrate_for_recall(&self, target_recall: f64, ground_truth: &RecallGroundTruth) -> AuroraResult<RecallCalibration> {
+        if !(target_recall > 0.0 && target_recall <= 1.0) {
+            return Err(AuroraError::Vector(format!("Target recall must be in (0, 1], got {}", target_recall)));
+        }
+        ground_truth.validate(self.dimension)?;
+
+        let k = ground_truth.k;
+        let widest = self.vectors.read().len().max(k);
+        let mut low = k;
+        let mut high = k;
+        let mut probes = 1;
+        let mut high_recall = self.measure_recall(ground_truth, high)?;
+        while high_recall < target_recall {
+            if high == widest {
+                return Err(AuroraError::Vector(format!(
+                    "Target recall {} is out of reach: ef_search {} reaches only {:.4}", target_recall, high, high_recall
+                )));
+            }
+            low = high + 1;
+            high = (high * 2).min(widest);
+            high_recall = self.measure_recall(ground_truth, high)?;
+            probes += 1;
+        }
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let recall = self.measure_recall(ground_truth, mid)?;
+            probes += 1;
+            if recall >= target_recall {
+                high = mid;
+                high_recall = recall;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        let calibration = RecallCalibration {
+            target_recall,
+            k,
+            ef_search: high,
+            recall: high_recall,
+            probes,
+            vectors: self.vectors.read().len(),
+            mutations: self.mutations,
+        };
+        *self.calibration.write() = Some((calibration.clone(), ground_truth.clone()));
+        Ok(calibration)
+    }
+
+    /// Mean recall at `k` of searches with `ef_search` over `ground_truth`
+    pub fn measure_recall(&self, ground_truth: &RecallGroundTruth, ef_search: usize) -> AuroraResult<f64> {
+        ground_truth.validate(self.dimension)?;
+        let k = ground_truth.k;
+        let mut found = 0;
+        for (query, neighbors) in &ground_truth.queries {
+            let truth: HashSet<usize> = neighbors.iter().take(k).copied().collect();
+            found += self.search(query, k, ef_search)?.iter().filter(|(id, _)| truth.contains(id)).count();
+        }
+        Ok(found as f64 / (ground_truth.queries.len() * k) as f64)
+    }
+
+    /// Ids of the `k` vectors closest to `query`, found by comparing it with
+    /// every vector indexed
+    pub fn exact_neighbors(&self, query: &[f32], k: usize) -> AuroraResult<Vec<usize>> {
+        if query.len() != self.dimension {
+            return Err(AuroraError::Vector(format!(
+                "Query vector dimension mismatch: expected {}, got {}",
+                self.dimension, query.len()
+            )));
+        }
+        let vectors = self.vectors.read();
+        let mut candidates: Vec<Candidate> = vectors.keys()
+            .map(|&id| Candidate { distance: self.distance_to_query(&vectors, query, id), id })
+            .collect();
+        candidates.sort_unstable();
+        Ok(candidates.into_iter().take(k).map(|candidate| candidate.id).collect())
+    }
+
+    /// The cached calibration, if any, even if stale
+    pub fn recall_calibration(&self) -> Option<RecallCalibration> {
+        self.calibration.read().as_ref().map(|(calibration, _)| calibration.clone())
+    }
+
+    /// Whether more than `RECALIBRATE_FRACTION` of the vectors indexed at
+    /// calibration have since been inserted, replaced or deleted
+    pub fn calibration_is_stale(&self) -> bool {
+        match &*self.calibration.read() {
+            Some((calibration, _)) => calibration.is_stale(self.mutations),
+            None => true,
+        }
+    }
+
+    /// Search with the calibrated ef_search, calibrating again first, on
+    /// the same target and ground truth, if the index has changed enough
+    /// to make the cached value stale
+    ///
+    /// A `k` larger than the calibration's widens ef_search to at least `k`.
+    pub fn search_for_recall(&self, query: &[f32], k: usize) -> AuroraResult<Vec<(usize, f32)>> {
+        let cached = self.calibration.read().clone();
+        let calibration = match cached {
+            None => return Err(AuroraError::Vector("Index has not been calibrated for recall".to_string())),
+            Some((calibration, _)) if !calibration.is_stale(self.mutations) => calibration,
+            Some((calibration, ground_truth)) => {
+                log::info!("Recalibrating HNSW ef_search for recall {} after {} changes",
+                    calibration.target_recall, self.mutations - calibration.mutations);
+                // The neighbors supplied were true of the index as it was
+                let queries = ground_truth.queries.into_iter()
+                    .map(|(query, _)| Ok((query.clone(), self.exact_neighbors(&query, ground_truth.k)?)))
+                    .collect::<AuroraResult<Vec<_>>>()?;
+                let ground_truth = RecallGroundTruth { k: ground_truth.k, queries };
+                self.calibrate_for_recall(calibration.target_recall, &ground_truth)?
+            }
+        };
+        self.search(query, k, calibration.ef_search.max(k))
+    }
+
     /// Get statistics about the index
     pub fn stats(&self) -> HNSWStats {
         let graph = self.graph.read();
@@ -278,108 +529,90 @@ impl HNSWIndex {
             }
         }
 
-        // Return the closest neighbor as new entry point for lower levels
-        neighbors.first().copied()
+        // Return the closest neighbor as new entry point for lower levels;
+        // above the rest of the graph there is none, so keep the old one
+        neighbors.first().copied().or(entry_point)
     }
 
-    /// Search for the closest node at a given level
-    fn search_layer(&self, level_graph: &HashMap<usize, Vec<usize>>, vectors: &HashMap<usize, Vec<f32>>, query: &[f32], entry_point: usize, ef: usize) -> usize {
-        let mut visited = HashSet::new();
-        let mut candidates = BinaryHeap::new();
+    /// Search for the closest node at a given level, moving greedily to
+    /// whichever neighbor is closer to the query until none is
+    fn search_layer(&self, level_graph: &HashMap<usize, Vec<usize>>, vectors: &HashMap<usize, Vec<f32>>, query: &[f32], entry_point: usize, visited_count: &mut usize) -> usize {
         let mut best = entry_point;
+        let mut best_distance = self.distance_to_query(vectors, query, entry_point);
+        *visited_count += 1;
 
-        visited.insert(entry_point);
-        candidates.push((Reverse(self.distance_to_query(vectors, query, entry_point)), entry_point));
-
-        while let Some((Reverse(_), current)) = candidates.pop() {
-            if let Some(neighbors) = level_graph.get(&current) {
+        loop {
+            let mut moved = false;
+            if let Some(neighbors) = level_graph.get(&best) {
                 for &neighbor in neighbors {
-                    if visited.insert(neighbor) {
-                        let distance = self.distance_to_query(vectors, query, neighbor);
-                        candidates.push((Reverse(distance), neighbor));
-
-                        // Update best candidate
-                        let best_distance = self.distance_to_query(vectors, query, best);
-                        if distance < best_distance {
-                            best = neighbor;
-                        }
+                    let distance = self.distance_to_query(vectors, query, neighbor);
+                    *visited_count += 1;
+                    if distance < best_distance {
+                        best = neighbor;
+                        best_distance = distance;
+                        moved = true;
                     }
                 }
             }
+            if !moved {
+                return best;
+            }
         }
-
-        best
     }
 
-    /// Beam search at base level to find ef closest neighbors
-    fn search_layer_beam(&self, level_graph: &HashMap<usize, Vec<usize>>, vectors: &HashMap<usize, Vec<f32>>, query: &[f32], entry_point: usize, ef: usize) -> Vec<usize> {
+    /// Beam search at base level to find the ef closest neighbors, closest
+    /// first
+    ///
+    /// The search stops once the closest unexpanded candidate is farther
+    /// than the farthest of the ef kept, so ef bounds both the work and the
+    /// recall.
+    fn search_layer_beam(&self, level_graph: &HashMap<usize, Vec<usize>>, vectors: &HashMap<usize, Vec<f32>>, query: &[f32], entry_point: usize, ef: usize, visited_count: &mut usize) -> Vec<usize> {
+        let ef = ef.max(1);
         let mut visited = HashSet::new();
-        let mut candidates = BinaryHeap::new(); // Max heap for distances
-        let mut results = BinaryHeap::new(); // Min heap for best results
+        let mut candidates = BinaryHeap::new(); // Closest candidate on top
+        let mut results = BinaryHeap::new(); // Farthest kept result on top
 
+        let entry = Candidate { distance: self.distance_to_query(vectors, query, entry_point), id: entry_point };
         visited.insert(entry_point);
-        candidates.push((self.distance_to_query(vectors, query, entry_point), entry_point));
-        results.push((Reverse(self.distance_to_query(vectors, query, entry_point)), entry_point));
+        candidates.push(Reverse(entry));
+        results.push(entry);
 
-        while let Some((_, current)) = candidates.pop() {
-            if let Some(neighbors) = level_graph.get(&current) {
+        while let Some(Reverse(current)) = candidates.pop() {
+            if results.len() >= ef && results.peek().is_some_and(|farthest: &Candidate| current.distance > farthest.distance) {
+                break;
+            }
+            if let Some(neighbors) = level_graph.get(&current.id) {
                 for &neighbor in neighbors {
-                    if visited.insert(neighbor) {
-                        let distance = self.distance_to_query(vectors, query, neighbor);
-
-                        // Add to candidates
-                        candidates.push((distance, neighbor));
-
-                        // Add to results if better than worst in results
-                        if results.len() < ef {
-                            results.push((Reverse(distance), neighbor));
-                        } else if let Some((Reverse(worst_distance), _)) = results.peek() {
-                            if distance < *worst_distance {
-                                results.pop();
-                                results.push((Reverse(distance), neighbor));
-                            }
+                    if !visited.insert(neighbor) {
+                        continue;
+                    }
+                    let candidate = Candidate { distance: self.distance_to_query(vectors, query, neighbor), id: neighbor };
+                    if results.len() < ef || results.peek().is_none_or(|farthest| candidate.distance < farthest.distance) {
+                        candidates.push(Reverse(candidate));
+                        results.push(candidate);
+                        if results.len() > ef {
+                            results.pop();
                         }
                     }
                 }
             }
         }
 
-        results.into_iter().map(|(_, id)| id).collect()
+        *visited_count += visited.len();
+        results.into_sorted_vec().into_iter().map(|candidate| candidate.id).collect()
     }
 
-    /// Select neighbors for a vector during insertion
+    /// Select neighbors for a vector during insertion: the closest
+    /// `max_connections` of a beam search `EF_CONSTRUCTION` wide
     fn select_neighbors(&self, level_graph: &HashMap<usize, Vec<usize>>, vector: &[f32], entry_point: usize, max_connections: usize) -> Vec<usize> {
-        let vectors = self.vectors.read();
-        let mut candidates = HashSet::new();
-        let mut results = BinaryHeap::new();
-
-        // Start with entry point
-        candidates.insert(entry_point);
-        results.push((Reverse(self.distance_to_query(&vectors, vector, entry_point)), entry_point));
-
-        // Explore neighbors
-        while !results.is_empty() {
-            let (_, current) = results.pop().unwrap();
-
-            if let Some(neighbors) = level_graph.get(&current) {
-                for &neighbor in neighbors {
-                    if candidates.insert(neighbor) {
-                        let distance = self.distance_between_vectors(&vectors, vector, neighbor);
-
-                        if results.len() < max_connections {
-                            results.push((Reverse(distance), neighbor));
-                        } else if let Some((Reverse(furthest), _)) = results.peek() {
-                            if distance < *furthest {
-                                results.pop();
-                                results.push((Reverse(distance), neighbor));
-                            }
-                        }
-                    }
-                }
-            }
+        if !level_graph.contains_key(&entry_point) {
+            return Vec::new();
         }
-
-        results.into_iter().map(|(_, id)| id).collect()
+        let vectors = self.vectors.read();
+        let mut visited = 0;
+        let mut neighbors = self.search_layer_beam(level_graph, &vectors, vector, entry_point, EF_CONSTRUCTION.max(max_connections), &mut visited);
+        neighbors.truncate(max_connections);
+        neighbors
     }
 
     /// Shrink neighbor list to maximum size
@@ -417,16 +650,27 @@ impl HNSWIndex {
         None
     }
 
-    /// Compute distance between query and a stored vector
+    /// Compute distance between query and a stored vector, oriented so
+    /// that lower is closer whatever the metric
     fn distance_to_query(&self, vectors: &HashMap<usize, Vec<f32>>, query: &[f32], id: usize) -> f32 {
         let vector = vectors.get(&id).unwrap();
-        self.distance_computer.compute(query, vector).unwrap()
+        self.oriented(self.distance_computer.compute(query, vector).unwrap())
     }
 
-    /// Compute distance between two stored vectors
+    /// Compute distance between two stored vectors, oriented so that lower
+    /// is closer whatever the metric
     fn distance_between_vectors(&self, vectors: &HashMap<usize, Vec<f32>>, vector: &[f32], id: usize) -> f32 {
         let other_vector = vectors.get(&id).unwrap();
-        self.distance_computer.compute(vector, other_vector).unwrap()
+        self.oriented(self.distance_computer.compute(vector, other_vector).unwrap())
+    }
+
+    /// Negate similarities so they order like distances
+    fn oriented(&self, value: f32) -> f32 {
+        if super::distance_metrics::DistanceMetricSelector::get_properties(&self.metric).higher_is_similar {
+            -value
+        } else {
+            value
+        }
     }
 
     /// Estimate memory usage of the index
@@ -452,6 +696,101 @@ impl HNSWIndex {
     }
 }
 
+/// Beam width used to find a new node's neighbors
+const EF_CONSTRUCTION: usize = 200;
+
+/// A node and its oriented distance to the query, ordered by distance
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    id: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.distance.total_cmp(&other.distance).then(self.id.cmp(&other.id))
+    }
+}
+
+/// What a batch upsert did with one item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+}
+
+/// Share of the vectors indexed at calibration that may change before the
+/// calibrated ef_search is measured again
+pub const RECALIBRATE_FRACTION: f64 = 0.1;
+
+/// Calibration queries with their true nearest neighbors
+#[derive(Debug, Clone)]
+pub struct RecallGroundTruth {
+    /// Neighbors recall is measured at
+    pub k: usize,
+    /// Each query with the ids of at least its `k` true nearest neighbors
+    pub queries: Vec<(Vec<f32>, Vec<usize>)>,
+}
+
+impl RecallGroundTruth {
+    fn validate(&self, dimension: usize) -> AuroraResult<()> {
+        if self.k == 0 || self.queries.is_empty() {
+            return Err(AuroraError::Vector("Recall ground truth needs k > 0 and at least one query".to_string()));
+        }
+        for (position, (query, neighbors)) in self.queries.iter().enumerate() {
+            if query.len() != dimension {
+                return Err(AuroraError::Vector(format!(
+                    "Ground truth query {} has dimension {}, expected {}", position, query.len(), dimension
+                )));
+            }
+            if neighbors.len() < self.k {
+                return Err(AuroraError::Vector(format!(
+                    "Ground truth query {} lists {} neighbors, fewer than k = {}", position, neighbors.len(), self.k
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The ef_search chosen for a target recall
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallCalibration {
+    pub target_recall: f64,
+    pub k: usize,
+    /// Smallest ef_search found to reach the target
+    pub ef_search: usize,
+    /// Recall measured at that ef_search
+    pub recall: f64,
+    /// ef_search values measured to find it
+    pub probes: usize,
+    /// Vectors indexed at calibration
+    pub vectors: usize,
+    /// Index changes counted at calibration
+    pub mutations: u64,
+}
+
+impl RecallCalibration {
+    fn is_stale(&self, mutations: u64) -> bool {
+        let allowed = (self.vectors as f64 * RECALIBRATE_FRACTION).max(1.0);
+        (mutations - self.mutations) as f64 > allowed
+    }
+}
+
 /// HNSW index statistics
 #[derive(Debug, Clone)]
 pub struct HNSWStats {
@@ -552,16 +891,24 @@ impl AdaptiveHNSW {
 
     /// Search with adaptive parameters
     pub fn search(&self, query: &[f32], k: usize) -> AuroraResult<Vec<(usize, f32)>> {
-        // Use adaptive ef_search based on k
-        let ef = if k <= 10 {
-            self.config.ef_search / 2
+        self.search_counted(query, k).map(|(results, _)| results)
+    }
+
+    /// Search with adaptive parameters, also returning the candidates examined
+    pub fn search_counted(&self, query: &[f32], k: usize) -> AuroraResult<(Vec<(usize, f32)>, u64)> {
+        let ef = Self::effective_ef_search(&self.config, k);
+        self.base_index.search_counted(query, k, ef)
+    }
+
+    /// ef_search used for a query asking for `k` neighbors
+    pub fn effective_ef_search(config: &HNSWConfig, k: usize) -> usize {
+        if k <= 10 {
+            config.ef_search / 2
         } else if k <= 100 {
-            self.config.ef_search
+            config.ef_search
         } else {
-            self.config.ef_search * 2
-        };
-
-        self.base_index.search(query, k, ef)
+            config.ef_search * 2
+        }
     }
 }
 
@@ -705,4 +1052,131 @@ mod tests {
         let results = index.search(&query, 10, 64).unwrap();
         assert_eq!(results.len(), 10);
     }
+
+    #[test]
+    fn test_hnsw_upsert_batch_inserts_and_updates() {
+        let mut index = HNSWIndex::new(3, DistanceMetric::Euclidean);
+        index.insert(0, vec![1.0, 0.0, 0.0]).unwrap();
+        index.insert(1, vec![0.0, 1.0, 0.0]).unwrap();
+
+        let outcomes = index.upsert_batch(vec![
+            (1, vec![0.0, 0.0, 1.0]),
+            (2, vec![0.0, 1.0, 0.0]),
+            (3, vec![f32::NAN, 0.0, 0.0]),
+            (4, vec![1.0, 1.0, 1.0]),
+        ]).unwrap();
+
+        assert_eq!(outcomes[0].as_ref().unwrap(), &UpsertOutcome::Updated);
+        assert_eq!(outcomes[1].as_ref().unwrap(), &UpsertOutcome::Inserted);
+        assert!(outcomes[2].is_err());
+        assert_eq!(outcomes[3].as_ref().unwrap(), &UpsertOutcome::Inserted);
+
+        // The update replaced id 1 in place rather than adding a node
+        assert_eq!(index.stats().total_vectors, 4);
+        let results = index.search(&[0.0, 0.0, 1.0], 1, 32).unwrap();
+        assert_eq!(results[0].0, 1);
+        let results = index.search(&[0.0, 1.0, 0.0], 1, 32).unwrap();
+        assert_eq!(results[0].0, 2);
+    }
+
+    #[test]
+    fn test_hnsw_upsert_batch_rejects_dimension_mismatch() {
+        let mut index = HNSWIndex::new(3, DistanceMetric::Euclidean);
+        index.insert(0, vec![1.0, 0.0, 0.0]).unwrap();
+
+        let result = index.upsert_batch(vec![(1, vec![0.0, 1.0, 0.0]), (2, vec![1.0, 2.0])]);
+        assert!(result.is_err());
+        assert_eq!(index.stats().total_vectors, 1);
+    }
+
+    /// `count` vectors of `dimension` uniform components, reproducibly
+    fn synthetic_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (0..count).map(|_| (0..dimension).map(|_| rng.f32()).collect()).collect()
+    }
+
+    /// Exact `k` nearest neighbors of each query by Euclidean distance
+    fn brute_force_truth(vectors: &[Vec<f32>], queries: Vec<Vec<f32>>, k: usize) -> RecallGroundTruth {
+        let distance = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>();
+        let queries = queries.into_iter()
+            .map(|query| {
+                let mut ids: Vec<usize> = (0..vectors.len()).collect();
+                ids.sort_by(|a, b| distance(&query, &vectors[*a]).total_cmp(&distance(&query, &vectors[*b])));
+                ids.truncate(k);
+                (query, ids)
+            })
+            .collect();
+        RecallGroundTruth { k, queries }
+    }
+
+    fn calibration_index(vectors: &[Vec<f32>]) -> HNSWIndex {
+        let mut index = HNSWIndex::new(vectors[0].len(), DistanceMetric::Euclidean);
+        index.max_connections = 8;
+        for (id, vector) in vectors.iter().enumerate() {
+            index.insert(id, vector.clone()).unwrap();
+        }
+        index
+    }
+
+    #[test]
+    fn test_hnsw_calibrate_for_recall_picks_near_minimal_ef() {
+        let vectors = synthetic_vectors(1500, 8, 7);
+        let index = calibration_index(&vectors);
+        let truth = brute_force_truth(&vectors, synthetic_vectors(40, 8, 8), 10);
+
+        for (query, neighbors) in &truth.queries[..5] {
+            assert_eq!(&index.exact_neighbors(query, 10).unwrap(), neighbors);
+        }
+
+        let target = 0.95;
+        let calibration = index.calibrate_for_recall(target, &truth).unwrap();
+        assert!(calibration.recall >= target);
+        assert_eq!(index.measure_recall(&truth, calibration.ef_search).unwrap(), calibration.recall);
+        // A beam of only k falls short, so the target took a wider one
+        assert!(index.measure_recall(&truth, truth.k).unwrap() < target);
+
+        // Bisection lands close to the smallest ef_search a full scan finds
+        let minimal = (truth.k..).find(|ef| index.measure_recall(&truth, *ef).unwrap() >= target).unwrap();
+        assert!(calibration.ef_search >= minimal);
+        assert!(calibration.ef_search <= minimal + minimal / 4 + 1, "chose {} for minimal {}", calibration.ef_search, minimal);
+        assert!(calibration.probes < 16);
+
+        // Searches use the cached value
+        assert_eq!(index.recall_calibration(), Some(calibration.clone()));
+        let results = index.search_for_recall(&truth.queries[0].0, 10).unwrap();
+        assert_eq!(results, index.search(&truth.queries[0].0, 10, calibration.ef_search).unwrap());
+    }
+
+    #[test]
+    fn test_hnsw_recalibrates_after_substantial_change() {
+        let vectors = synthetic_vectors(1200, 8, 11);
+        let mut index = calibration_index(&vectors[..1000]);
+        let truth = brute_force_truth(&vectors[..1000], synthetic_vectors(20, 8, 12), 10);
+        assert!(index.search_for_recall(&truth.queries[0].0, 10).is_err());
+        assert!(index.calibrate_for_recall(0.0, &truth).is_err());
+        assert!(index.calibrate_for_recall(0.9, &RecallGroundTruth { k: 10, queries: Vec::new() }).is_err());
+
+        let first = index.calibrate_for_recall(0.9, &truth).unwrap();
+        assert!(!index.calibration_is_stale());
+
+        // A few changes keep the calibration
+        for (id, vector) in vectors.iter().enumerate().take(1050).skip(1000) {
+            index.insert(id, vector.clone()).unwrap();
+        }
+        assert!(!index.calibration_is_stale());
+        index.search_for_recall(&truth.queries[0].0, 10).unwrap();
+        assert_eq!(index.recall_calibration(), Some(first.clone()));
+
+        // Past a tenth of the index the next search calibrates again
+        for (id, vector) in vectors.iter().enumerate().skip(1050) {
+            index.insert(id, vector.clone()).unwrap();
+        }
+        assert!(index.calibration_is_stale());
+        index.search_for_recall(&truth.queries[0].0, 10).unwrap();
+        let second = index.recall_calibration().unwrap();
+        assert!(!index.calibration_is_stale());
+        assert_eq!((second.vectors, second.mutations), (1200, 1200));
+        assert_eq!(second.target_recall, 0.9);
+        assert!(second.recall >= 0.9);
+    }
 }
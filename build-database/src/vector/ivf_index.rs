@@ -123,6 +123,12 @@ impl IVFIndex {
 
     /// Search for k nearest neighbors
     pub fn search(&self, query: &[f32], k: usize, nprobe: usize) -> AuroraResult<Vec<(usize, f32)>> {
+        self.search_counted(query, k, nprobe).map(|(results, _)| results)
+    }
+
+    /// Search for k nearest neighbors, also returning how many vectors in the
+    /// probed lists had their distance computed
+    pub fn search_counted(&self, query: &[f32], k: usize, nprobe: usize) -> AuroraResult<(Vec<(usize, f32)>, u64)> {
         if query.len() != self.dimension {
             return Err(AuroraError::Vector(format!(
                 "Query vector dimension mismatch: expected {}, got {}",
@@ -160,8 +166,9 @@ impl IVFIndex {
             candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap()); // Lower distance first
         }
 
+        let examined = candidates.len() as u64;
         candidates.truncate(k);
-        Ok(candidates)
+        Ok((candidates, examined))
     }
 
     /// Delete a vector from the index
@@ -405,6 +412,9 @@ impl IVFIndex {
     }
 }
 
+/// Inverted lists probed per query when the caller does not choose
+pub const DEFAULT_NPROBE: usize = 16;
+
 /// IVF index configuration
 #[derive(Debug, Clone)]
 pub struct IVFConfig {
@@ -49,6 +49,16 @@ pub trait VectorIndex {
     /// Search for k nearest neighbors
     fn search(&self, query: &[f32], k: usize) -> AuroraResult<Vec<(usize, f32)>>;
 
+    /// Search for k nearest neighbors, also returning how many vectors had
+    /// their distance to the query computed
+    ///
+    /// The default reports every indexed vector: exact for exhaustive search,
+    /// an upper bound for indexes that do not override it.
+    fn search_counted(&self, query: &[f32], k: usize) -> AuroraResult<(Vec<(usize, f32)>, u64)> {
+        let results = self.search(query, k)?;
+        Ok((results, self.stats().total_vectors as u64))
+    }
+
     /// Delete a vector from the index
     fn delete(&mut self, id: usize) -> AuroraResult<()>;
 
@@ -97,6 +107,11 @@ impl AuroraVectorIndex {
         })
     }
 
+    /// Configuration the index was created with
+    pub fn config(&self) -> &VectorIndexConfig {
+        &self.config
+    }
+
     /// Auto-select the best index type based on dataset characteristics
     pub fn auto_select(dataset_size: usize, dimension: usize, query_patterns: &QueryPatterns) -> VectorIndexType {
         match dataset_size {
@@ -304,6 +319,10 @@ impl VectorIndex for AuroraVectorIndex {
         self.index.search(query, k)
     }
 
+    fn search_counted(&self, query: &[f32], k: usize) -> AuroraResult<(Vec<(usize, f32)>, u64)> {
+        self.index.search_counted(query, k)
+    }
+
     fn delete(&mut self, id: usize) -> AuroraResult<()> {
         self.index.delete(id)
     }
@@ -5,7 +5,10 @@
 
 use std::collections::HashMap;
 use crate::core::errors::{AuroraResult, AuroraError};
-use super::vector_index::{AuroraVectorIndex, VectorIndexConfig, VectorUseCase};
+use super::vector_index::{AuroraVectorIndex, VectorIndex, VectorIndexConfig, IndexParameters, VectorUseCase};
+use super::hnsw_index::AdaptiveHNSW;
+use super::ivf_index::DEFAULT_NPROBE;
+use super::distance_metrics::DistanceMetric;
 use super::vector_storage::{VectorStorage, VectorStorageConfig, VectorStorageType, CompressionType};
 use crate::query::processing::*;
 
@@ -102,6 +105,94 @@ impl VectorQueryEngine {
         }
     }
 
+    /// Plan a vector search, choosing the filter strategy from `filter_selectivity`
+    pub fn plan_vector_search(&self, search: &VectorSearchExpression, filter: Option<Expression>, filter_selectivity: f64) -> AuroraResult<QueryPlan> {
+        let index_name = format!("{}_{}_vector_idx", search.table_name, search.column_name);
+        let index = self.vector_indexes.get(&index_name);
+
+        let (index_strategy, table_rows) = match index {
+            Some(index) => (Self::search_strategy(index.config(), search.limit), index.stats().total_vectors as u64),
+            None => (VectorIndexStrategy::Flat, 0),
+        };
+
+        let mut node = VectorSearchNode {
+            table_name: search.table_name.clone(),
+            vector_column: search.column_name.clone(),
+            query_vector: search.query_vector.clone(),
+            metric: Self::plan_metric(&search.distance_metric),
+            limit: search.limit as u32,
+            filter_strategy: filter.as_ref().map(|_| VectorFilterStrategy::choose(filter_selectivity)),
+            filter_condition: filter,
+            index_name: index.map(|_| index_name),
+            index_strategy,
+            table_rows,
+            estimated_rows: search.limit as u64,
+            cost: 0.0,
+        };
+        // One unit per distance computation
+        node.cost = node.estimated_candidates() as f64;
+
+        Ok(QueryPlan {
+            estimated_cost: node.cost,
+            estimated_rows: node.estimated_rows,
+            root: PlanNode::VectorSearch(node),
+            execution_mode: ExecutionMode::Sequential,
+            optimization_hints: vec![],
+            statistics: PlanStatistics::default(),
+        })
+    }
+
+    /// Render EXPLAIN output for a vector search; with `analyze`, run the
+    /// search and report the candidates it actually examined
+    pub fn explain_vector_search(&self, search: &VectorSearchExpression, analyze: bool) -> AuroraResult<String> {
+        let plan = self.plan_vector_search(search, None, 1.0)?;
+        if !analyze {
+            return Ok(explain_plan(&plan, None));
+        }
+
+        let index_name = format!("{}_{}_vector_idx", search.table_name, search.column_name);
+        let index = self.vector_indexes.get(&index_name)
+            .ok_or_else(|| AuroraError::Vector(format!("Vector index not found for {}.{}", search.table_name, search.column_name)))?;
+
+        let start = std::time::Instant::now();
+        let (results, candidates) = index.search_counted(&search.query_vector, search.limit)?;
+        let wall_time = start.elapsed();
+
+        let mut analyzed = ExplainAnalyze { total_time: wall_time, ..ExplainAnalyze::default() };
+        if let Some(id) = crate::query::processing::explain::operator_id(&plan.root) {
+            analyzed.operators.insert(id, OperatorActuals {
+                rows: results.len() as u64,
+                wall_time,
+                candidates_examined: Some(candidates),
+            });
+        }
+        Ok(explain_plan(&plan, Some(&analyzed)))
+    }
+
+    /// Index type and search parameters a query for `k` neighbors runs with
+    fn search_strategy(config: &VectorIndexConfig, k: usize) -> VectorIndexStrategy {
+        match &config.index_params {
+            IndexParameters::HNSW(hnsw) => VectorIndexStrategy::Hnsw { ef_search: hnsw.ef_search as u32 },
+            IndexParameters::AdaptiveHNSW(hnsw) => VectorIndexStrategy::Hnsw {
+                ef_search: AdaptiveHNSW::effective_ef_search(hnsw, k) as u32,
+            },
+            IndexParameters::IVF(ivf) | IndexParameters::AdaptiveIVF(ivf) => VectorIndexStrategy::IvfPq {
+                nprobe: DEFAULT_NPROBE.min(ivf.num_clusters) as u32,
+                nlist: ivf.num_clusters as u32,
+            },
+        }
+    }
+
+    fn plan_metric(metric: &DistanceMetric) -> VectorMetric {
+        match metric {
+            DistanceMetric::Euclidean => VectorMetric::Euclidean,
+            DistanceMetric::DotProduct => VectorMetric::DotProduct,
+            DistanceMetric::Manhattan => VectorMetric::Manhattan,
+            DistanceMetric::Hamming => VectorMetric::Hamming,
+            DistanceMetric::Cosine | DistanceMetric::Jaccard => VectorMetric::Cosine,
+        }
+    }
+
     /// Check if a table has vector columns
     pub fn has_vector_columns(&self, table_name: &str) -> bool {
         self.table_vector_columns.contains_key(table_name)
@@ -214,6 +305,23 @@ impl VectorQueryProcessor {
         }
     }
 
+    /// Render EXPLAIN [ANALYZE] output for a vector-enabled SQL statement
+    pub async fn explain_vector_query(&mut self, sql: &str) -> AuroraResult<String> {
+        let mut parser = SqlParser::new(sql);
+        let explain = match parser.parse()? {
+            Statement::Explain(explain) => explain,
+            other => return Err(AuroraError::Parse(format!("Expected EXPLAIN, got {}", other))),
+        };
+
+        match explain.statement.as_ref() {
+            Statement::Select(select) => match self.extract_vector_search(select) {
+                Some(search_expr) => self.vector_engine.explain_vector_search(&search_expr, explain.analyze),
+                None => Ok(explain_plan(&self.query_planner.plan_select(select)?, None)),
+            },
+            other => Err(AuroraError::Parse(format!("EXPLAIN is not supported for {}", other))),
+        }
+    }
+
     /// Check if a query involves vector operations
     fn is_vector_query(&self, ast: &Statement) -> bool {
         match ast {
@@ -262,6 +370,7 @@ impl VectorQueryProcessor {
             Statement::Select(select) => {
                 // Check for vector search syntax: SELECT * FROM table ORDER BY column <-> [1,2,3] LIMIT k
                 if let Some(search_expr) = self.extract_vector_search(&select) {
+                    let execution_plan = self.vector_engine.plan_vector_search(&search_expr, None, 1.0)?;
                     return self.vector_engine.execute_vector_search(
                         &search_expr.table_name,
                         &search_expr.column_name,
@@ -284,25 +393,10 @@ impl VectorQueryProcessor {
                                 network_calls: 0,
                                 cache_hits: 0,
                                 cache_misses: 0,
+                                workers_planned: 1,
+                                workers_launched: 1,
                             },
-                            execution_plan: QueryPlan {
-                                root: PlanNode::VectorSearch(super::plan::VectorSearchNode {
-                                    table_name: search_expr.table_name,
-                                    vector_column: search_expr.column_name,
-                                    query_vector: search_expr.query_vector,
-                                    metric: search_expr.distance_metric,
-                                    limit: search_expr.limit,
-                                    filter_condition: None,
-                                    index_name: None,
-                                    estimated_rows: search_expr.limit,
-                                    cost: 100.0,
-                                }),
-                                estimated_cost: 100.0,
-                                estimated_rows: search_expr.limit,
-                                execution_mode: ExecutionMode::Sequential,
-                                optimization_hints: vec![],
-                                statistics: PlanStatistics::default(),
-                            },
+                            execution_plan,
                         }
                     });
                 }
@@ -569,6 +663,30 @@ mod tests {
         assert!(!engine.has_vector_columns("products"));
     }
 
+    #[tokio::test]
+    async fn test_explain_vector_search() {
+        let mut engine = VectorQueryEngine::new();
+        engine.create_vector_index("products", "embedding", 3, VectorUseCase::SemanticSearch).await.unwrap();
+
+        let search_expr = VectorSearchExpression {
+            table_name: "products".to_string(),
+            column_name: "embedding".to_string(),
+            query_vector: vec![1.0, 0.0, 0.0],
+            limit: 5,
+            distance_metric: super::distance_metrics::DistanceMetric::Cosine,
+        };
+
+        let output = engine.explain_vector_search(&search_expr, false).unwrap();
+        assert!(output.contains("VectorSearch(products.embedding)"));
+        assert!(output.contains("Index: HNSW (ef_search="));
+        assert!(output.contains("Index Name: products_embedding_vector_idx"));
+        assert!(output.contains("Estimated Recall:"));
+
+        let analyzed = engine.explain_vector_search(&search_expr, true).unwrap();
+        assert!(analyzed.contains("Actual Candidates:"));
+        assert!(analyzed.contains("Execution Time:"));
+    }
+
     #[test]
     fn test_vector_engine_stats() {
         let engine = VectorQueryEngine::new();
@@ -0,0 +1,243 @@
+//! Admin HTTP API Tests
+//!
+//! Exercises every `/admin` endpoint against a real engine instance,
+//! including authentication/authorization failures and input validation.
+
+use std::sync::Arc;
+use aurora_db::api::admin::{AdminApi, AdminApiConfig};
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::AuroraDB;
+use aurora_db::security::{AuthConfig, AuthManager, RBACManager};
+use serde_json::{json, Value};
+use tempfile::{tempdir, TempDir};
+use warp::test::request;
+
+const PASSWORD: &str = "Adm1nPassword";
+
+struct Harness {
+    api: AdminApi,
+    admin_token: String,
+    reader_token: String,
+    _data_dir: TempDir,
+}
+
+async fn setup() -> Harness {
+    let data_dir = tempdir().unwrap();
+    let config = DatabaseConfig {
+        data_directory: data_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = Arc::new(AuroraDB::new(config).await.unwrap());
+
+    let rbac = Arc::new(RBACManager::new());
+    let auth = Arc::new(AuthManager::new(AuthConfig {
+        jwt_secret_key: "admin-api-test-secret-key-with-enough-entropy".to_string(),
+        jwt_expiration_hours: 1,
+        password_min_length: 8,
+        max_login_attempts: 3,
+        lockout_duration_minutes: 15,
+        enable_mfa: false,
+        session_timeout_hours: 1,
+    }, Arc::clone(&rbac)));
+
+    let admin_id = auth.register_user("dba".to_string(), PASSWORD.to_string(), "dba@example.com".to_string()).unwrap();
+    rbac.grant_role_to_user(&admin_id, "admin").unwrap();
+    let reader_id = auth.register_user("analyst".to_string(), PASSWORD.to_string(), "analyst@example.com".to_string()).unwrap();
+    rbac.grant_role_to_user(&reader_id, "readonly").unwrap();
+
+    Harness {
+        api: AdminApi::new(db, Arc::clone(&auth), rbac, AdminApiConfig::default()),
+        admin_token: format!("Bearer {}", auth.generate_jwt(&admin_id).unwrap()),
+        reader_token: format!("Bearer {}", auth.generate_jwt(&reader_id).unwrap()),
+        _data_dir: data_dir,
+    }
+}
+
+fn body(resp: &warp::http::Response<bytes::Bytes>) -> Value {
+    serde_json::from_slice(resp.body()).unwrap()
+}
+
+async fn create_users_table(h: &Harness) {
+    let resp = request()
+        .method("POST")
+        .path("/admin/tables")
+        .header("authorization", &h.admin_token)
+        .json(&json!({
+            "name": "users",
+            "columns": [
+                {"name": "id", "type": "BIGINT", "nullable": false},
+                {"name": "email", "type": "VARCHAR(255)"},
+                {"name": "embedding", "type": "VECTOR(8)"}
+            ],
+            "primary_key": ["id"]
+        }))
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 201, "{:?}", resp.body());
+}
+
+#[tokio::test]
+async fn test_create_table_and_list_schemas() {
+    let h = setup().await;
+    create_users_table(&h).await;
+
+    let resp = request()
+        .method("GET")
+        .path("/admin/schemas")
+        .header("authorization", &h.admin_token)
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let json = body(&resp);
+    let tables = json["data"]["tables"].as_array().unwrap();
+    let users = tables.iter().find(|t| t["name"] == "users").expect("users table listed");
+    assert_eq!(users["columns"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_create_and_drop_index() {
+    let h = setup().await;
+    create_users_table(&h).await;
+
+    let resp = request()
+        .method("POST")
+        .path("/admin/indexes")
+        .header("authorization", &h.admin_token)
+        .json(&json!({"name": "users_email_idx", "table": "users", "columns": ["email"], "unique": true}))
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 201, "{:?}", resp.body());
+    assert_eq!(body(&resp)["data"]["status"], "created");
+
+    let resp = request()
+        .method("DELETE")
+        .path("/admin/indexes/users_email_idx")
+        .header("authorization", &h.admin_token)
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(body(&resp)["data"]["status"], "dropped");
+}
+
+#[tokio::test]
+async fn test_vacuum_and_analyze() {
+    let h = setup().await;
+    create_users_table(&h).await;
+
+    for op in ["vacuum", "analyze"] {
+        let resp = request()
+            .method("POST")
+            .path(&format!("/admin/tables/users/{}", op))
+            .header("authorization", &h.admin_token)
+            .reply(&h.api.routes())
+            .await;
+        assert_eq!(resp.status(), 200, "{} failed: {:?}", op, resp.body());
+        assert_eq!(body(&resp)["data"]["operation"], op.to_uppercase());
+    }
+}
+
+#[tokio::test]
+async fn test_drop_table() {
+    let h = setup().await;
+    create_users_table(&h).await;
+
+    let resp = request()
+        .method("DELETE")
+        .path("/admin/tables/users")
+        .header("authorization", &h.admin_token)
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(body(&resp)["data"]["status"], "dropped");
+}
+
+#[tokio::test]
+async fn test_missing_token_is_rejected() {
+    let h = setup().await;
+
+    let resp = request()
+        .method("POST")
+        .path("/admin/tables")
+        .json(&json!({"name": "users", "columns": [{"name": "id", "type": "INTEGER"}]}))
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    let json = body(&resp);
+    assert_eq!(json["success"], false);
+    assert_eq!(json["error"]["code"], "Unauthorized");
+
+    let resp = request()
+        .method("GET")
+        .path("/admin/schemas")
+        .header("authorization", "Bearer not-a-jwt")
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_insufficient_role_is_forbidden() {
+    let h = setup().await;
+
+    // Read-only users may list schemas but not change them
+    let resp = request()
+        .method("GET")
+        .path("/admin/schemas")
+        .header("authorization", &h.reader_token)
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = request()
+        .method("DELETE")
+        .path("/admin/tables/users")
+        .header("authorization", &h.reader_token)
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 403);
+    assert_eq!(body(&resp)["error"]["code"], "Forbidden");
+}
+
+#[tokio::test]
+async fn test_malformed_create_table_returns_details() {
+    let h = setup().await;
+
+    let resp = request()
+        .method("POST")
+        .path("/admin/tables")
+        .header("authorization", &h.admin_token)
+        .json(&json!({
+            "name": "table",
+            "columns": [
+                {"name": "id", "type": "INTEGR"},
+                {"name": "aurora_shadow", "type": "VECTOR"}
+            ]
+        }))
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 400);
+
+    let json = body(&resp);
+    assert_eq!(json["error"]["code"], "ValidationFailed");
+    let fields: Vec<&str> = json["error"]["details"].as_array().unwrap()
+        .iter()
+        .map(|d| d["field"].as_str().unwrap())
+        .collect();
+    assert!(fields.contains(&"name"), "reserved table name reported");
+    assert!(fields.contains(&"columns[0].type"), "bad type reported");
+    assert!(fields.contains(&"columns[1].name"), "system prefix reported");
+    assert!(fields.contains(&"columns[1].type"), "missing vector dimension reported");
+
+    // Not JSON at all
+    let resp = request()
+        .method("POST")
+        .path("/admin/tables")
+        .header("authorization", &h.admin_token)
+        .body("{not json")
+        .reply(&h.api.routes())
+        .await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(body(&resp)["error"]["code"], "InvalidJson");
+}
@@ -0,0 +1,146 @@
+//! Approximate Aggregate Tests
+//!
+//! `approx_count_distinct` stays within HyperLogLog's error bound on a
+//! dataset of known cardinality, `approx_percentile` within tolerance of the
+//! exact percentile, and sketches taken per partition merge into the same
+//! answers as one pass over every row.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use serde_json::Value;
+use tempfile::{tempdir, TempDir};
+
+/// Distinct `user_id`s in `events`, 350 in each region
+const USERS: f64 = 700.0;
+
+/// Standard error of HyperLogLog at the default precision of 14
+const HLL_ERROR: f64 = 1.04 / 128.0;
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// 4000 events: user `i % 700`, region by parity, latencies 0..999 each
+/// seen four times
+async fn load_events(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE events (id INTEGER PRIMARY KEY, region TEXT, user_id INTEGER, latency REAL);", user_context).await.unwrap();
+    for batch in 0..8 {
+        let values: Vec<String> = (batch * 500..(batch + 1) * 500)
+            .map(|i| {
+                let region = if i % 2 == 0 { "east" } else { "west" };
+                format!("({}, '{}', {}, {}.0)", i, region, i % 700, (i * 37) % 1000)
+            })
+            .collect();
+        let sql = format!("INSERT INTO events (id, region, user_id, latency) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+fn number(value: &Value) -> f64 {
+    value.as_f64().unwrap()
+}
+
+fn assert_within(estimate: f64, exact: f64, tolerance: f64) {
+    assert!((estimate - exact).abs() <= tolerance, "estimate {} not within {} of {}", estimate, tolerance, exact);
+}
+
+#[tokio::test]
+async fn test_approx_count_distinct_within_error_bound() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_events(&db, &user_context).await;
+
+    let result = db.execute_query("SELECT approx_count_distinct(user_id) AS users FROM events;", &user_context).await.unwrap();
+    assert_within(number(&result.rows[0][0]), USERS, 3.0 * HLL_ERROR * USERS);
+
+    // Per group, and at a lower precision with its wider bound
+    let result = db.execute_query(
+        "SELECT region, approx_count_distinct(user_id) AS users, approx_count_distinct(user_id, 10) AS coarse \
+         FROM events GROUP BY region ORDER BY region;",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows.len(), 2);
+    for row in &result.rows {
+        assert_within(number(&row[1]), USERS / 2.0, 3.0 * HLL_ERROR * USERS / 2.0);
+        assert_within(number(&row[2]), USERS / 2.0, 3.0 * (1.04 / 32.0) * USERS / 2.0);
+    }
+
+    let invalid = db.execute_query("SELECT approx_count_distinct(user_id, 30) FROM events;", &user_context).await;
+    assert!(invalid.unwrap_err().to_string().contains("precision"));
+}
+
+#[tokio::test]
+async fn test_approx_percentile_within_tolerance() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_events(&db, &user_context).await;
+
+    let result = db.execute_query(
+        "SELECT approx_percentile(latency, 0.5) AS p50, approx_quantile(latency, 0.9) AS p90, \
+         approx_percentile(latency, 0.99, 200) AS p99 FROM events;",
+        &user_context,
+    ).await.unwrap();
+    // Latencies are uniform over 0..999: within 1% of the range
+    for (value, exact) in result.rows[0].iter().zip([499.5, 899.5, 989.5]) {
+        assert_within(number(value), exact, 10.0);
+    }
+
+    let invalid = db.execute_query("SELECT approx_percentile(latency, 1.5) FROM events;", &user_context).await;
+    assert!(invalid.unwrap_err().to_string().contains("percentile"));
+    let not_numeric = db.execute_query("SELECT approx_percentile(region, 0.5) FROM events;", &user_context).await;
+    assert!(not_numeric.is_err());
+}
+
+#[tokio::test]
+async fn test_partition_sketches_merge() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_events(&db, &user_context).await;
+
+    // One partial result per region, as parallel workers would produce
+    let partials = db.execute_query(
+        "SELECT region, hll_sketch(user_id) AS users, tdigest_sketch(latency) AS latencies \
+         FROM events GROUP BY region ORDER BY region;",
+        &user_context,
+    ).await.unwrap();
+    db.execute_query("CREATE TABLE partials (region TEXT PRIMARY KEY, users TEXT, latencies TEXT);", &user_context).await.unwrap();
+    for row in &partials.rows {
+        let sql = format!(
+            "INSERT INTO partials (region, users, latencies) VALUES ('{}', '{}', '{}');",
+            row[0].as_str().unwrap(), row[1].as_str().unwrap(), row[2].as_str().unwrap()
+        );
+        db.execute_query(&sql, &user_context).await.unwrap();
+    }
+
+    // HyperLogLog merges exactly: the union estimates like one pass over all rows
+    let whole = db.execute_query("SELECT approx_count_distinct(user_id) FROM events;", &user_context).await.unwrap();
+    let merged = db.execute_query(
+        "SELECT hll_estimate(users), hll_estimate(hll_merge(users)), tdigest_quantile(latencies, 0.5) FROM partials;",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(merged.rows[0][0], whole.rows[0][0]);
+    assert_eq!(merged.rows[0][1], whole.rows[0][0]);
+    assert_within(number(&merged.rows[0][2]), 499.5, 10.0);
+
+    // A stored sketch is checked before it is used
+    db.execute_query("INSERT INTO partials (region, users) VALUES ('north', 'not a sketch');", &user_context).await.unwrap();
+    let corrupt = db.execute_query("SELECT hll_estimate(users) FROM partials;", &user_context).await;
+    assert!(corrupt.unwrap_err().to_string().contains("malformed HyperLogLog sketch"));
+}
@@ -0,0 +1,141 @@
+//! Array Column Tests
+//!
+//! `text[]` and `integer[]` columns take `ARRAY[...]` and `'{...}'` values,
+//! support subscripts, `@>`, `&&` and `= ANY(...)` with or without a GIN
+//! index, and expand into one row per element through `unnest`.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, IndexDefinition, IndexType, UserContext};
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+async fn load_posts(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE posts (id INTEGER PRIMARY KEY, tags TEXT[], scores INTEGER[]);", user_context).await.unwrap();
+    db.execute_query(
+        "INSERT INTO posts (id, tags, scores) VALUES \
+         (1, ARRAY['rust', 'database'], ARRAY[5, 3]), \
+         (2, '{rust,web}', '{1,NULL}'), \
+         (3, ARRAY['go', 'database'], ARRAY[]), \
+         (4, NULL, NULL);",
+        user_context,
+    ).await.unwrap();
+}
+
+async fn post_ids(db: &AuroraDB, sql: &str, user_context: &UserContext) -> Vec<i64> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    result.rows.iter().map(|row| row[0].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_array_construction_and_element_access() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+
+    // Both literal forms store the same kind of value; NULL elements survive
+    let result = db.execute_query("SELECT id, tags, scores FROM posts ORDER BY id;", &user_context).await.unwrap();
+    assert_eq!(result.rows[0], [json!(1), json!(["rust", "database"]), json!([5, 3])]);
+    assert_eq!(result.rows[1], [json!(2), json!(["rust", "web"]), json!([1, null])]);
+    assert_eq!(result.rows[2], [json!(3), json!(["go", "database"]), json!([])]);
+    assert_eq!(result.rows[3], [json!(4), json!(null), json!(null)]);
+
+    // Subscripts count from 1; out of range is NULL
+    let result = db.execute_query("SELECT id, tags[1], tags[3] FROM posts WHERE id = 1;", &user_context).await.unwrap();
+    assert_eq!(result.rows[0], [json!(1), json!("rust"), json!(null)]);
+
+    // Elements must have the element type
+    let wrong = db.execute_query("INSERT INTO posts (id, scores) VALUES (5, ARRAY['high']);", &user_context).await;
+    assert!(wrong.is_err());
+    let malformed = db.execute_query("INSERT INTO posts (id, tags) VALUES (5, '{rust');", &user_context).await;
+    assert!(malformed.unwrap_err().to_string().contains("malformed array literal"));
+}
+
+#[tokio::test]
+async fn test_containment_queries_use_the_gin_index() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+
+    let queries = [
+        ("SELECT id FROM posts WHERE tags @> ARRAY['rust'] ORDER BY id;", vec![1, 2]),
+        ("SELECT id FROM posts WHERE tags @> ARRAY['rust', 'database'] ORDER BY id;", vec![1]),
+        ("SELECT id FROM posts WHERE tags && ARRAY['web', 'go'] ORDER BY id;", vec![2, 3]),
+        ("SELECT id FROM posts WHERE 'database' = ANY(tags) ORDER BY id;", vec![1, 3]),
+        ("SELECT id FROM posts WHERE tags @> ARRAY['missing'] ORDER BY id;", vec![]),
+    ];
+
+    // Same answers from a sequential scan and from the index
+    for (sql, expected) in &queries {
+        assert_eq!(&post_ids(&db, sql, &user_context).await, expected, "{}", sql);
+    }
+    db.create_index("posts", &IndexDefinition {
+        name: "idx_posts_tags".to_string(),
+        columns: vec!["tags".to_string()],
+        index_type: IndexType::Gin,
+    }, false, &user_context).await.unwrap();
+    for (sql, expected) in &queries {
+        assert_eq!(&post_ids(&db, sql, &user_context).await, expected, "{}", sql);
+        let explain = db.execute_query(&format!("EXPLAIN {}", sql), &user_context).await.unwrap();
+        assert!(
+            explain.rows.iter().any(|row| row[0].as_str().unwrap().contains("GIN Index Scan using idx_posts_tags on posts")),
+            "{}", sql
+        );
+    }
+
+    // The index follows inserts, updates and deletes
+    db.execute_query("INSERT INTO posts (id, tags) VALUES (5, ARRAY['rust', 'go']);", &user_context).await.unwrap();
+    db.execute_query("UPDATE posts SET tags = ARRAY['web'] WHERE id = 1;", &user_context).await.unwrap();
+    db.execute_query("DELETE FROM posts WHERE id = 2;", &user_context).await.unwrap();
+    assert_eq!(post_ids(&db, "SELECT id FROM posts WHERE tags @> ARRAY['rust'] ORDER BY id;", &user_context).await, [5]);
+    assert_eq!(post_ids(&db, "SELECT id FROM posts WHERE tags && ARRAY['web', 'go'] ORDER BY id;", &user_context).await, [1, 3, 5]);
+
+    // Only array columns can have a GIN index
+    let scalar = db.create_index("posts", &IndexDefinition {
+        name: "idx_posts_id".to_string(),
+        columns: vec!["id".to_string()],
+        index_type: IndexType::Gin,
+    }, false, &user_context).await;
+    assert!(scalar.unwrap_err().to_string().contains("array column"));
+}
+
+#[tokio::test]
+async fn test_unnest_produces_one_row_per_element() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+
+    let result = db.execute_query("SELECT id, tag FROM posts, unnest(tags) AS tag ORDER BY id;", &user_context).await.unwrap();
+    let pairs: Vec<(i64, String)> = result.rows.iter()
+        .map(|row| (row[0].as_i64().unwrap(), row[1].as_str().unwrap().to_string()))
+        .collect();
+    // Empty and NULL arrays contribute no rows
+    assert_eq!(pairs, [
+        (1, "rust".to_string()), (1, "database".to_string()),
+        (2, "rust".to_string()), (2, "web".to_string()),
+        (3, "go".to_string()), (3, "database".to_string()),
+    ]);
+
+    let result = db.execute_query("SELECT n FROM unnest(ARRAY[1, 2, 3]) AS n;", &user_context).await.unwrap();
+    assert_eq!(result.rows, [[json!(1)], [json!(2)], [json!(3)]]);
+}
@@ -0,0 +1,161 @@
+//! ASOF Join Tests
+//!
+//! Each trade is joined to the nearest quote of the same symbol in the
+//! direction the MATCH_CONDITION gives, and the result is compared with a
+//! brute-force scan over the same rows.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// `(trade_id, symbol, traded_at)`
+const TRADES: &[(i64, &str, i64)] = &[
+    (1, "AAPL", 100),
+    (2, "AAPL", 105),
+    (3, "AAPL", 130),
+    (4, "MSFT", 90),
+    (5, "MSFT", 120),
+    (6, "GOOG", 100),
+    (7, "AAPL", 99),
+];
+
+/// `(quote_id, sym, quoted_at)`: no GOOG quotes, no MSFT quote before 100,
+/// and two AAPL quotes at 105
+const QUOTES: &[(i64, &str, i64)] = &[
+    (10, "AAPL", 95),
+    (11, "AAPL", 105),
+    (12, "AAPL", 105),
+    (13, "AAPL", 125),
+    (20, "MSFT", 100),
+    (21, "MSFT", 140),
+];
+
+async fn load_tables(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE trades (trade_id INTEGER PRIMARY KEY, symbol TEXT, traded_at INTEGER);", user_context).await.unwrap();
+    db.execute_query("CREATE TABLE quotes (quote_id INTEGER PRIMARY KEY, sym TEXT, quoted_at INTEGER);", user_context).await.unwrap();
+
+    let trades: Vec<String> = TRADES.iter().map(|(id, symbol, at)| format!("({}, '{}', {})", id, symbol, at)).collect();
+    db.execute_query(&format!("INSERT INTO trades (trade_id, symbol, traded_at) VALUES {};", trades.join(", ")), user_context).await.unwrap();
+    let quotes: Vec<String> = QUOTES.iter().map(|(id, sym, at)| format!("({}, '{}', {})", id, sym, at)).collect();
+    db.execute_query(&format!("INSERT INTO quotes (quote_id, sym, quoted_at) VALUES {};", quotes.join(", ")), user_context).await.unwrap();
+}
+
+/// Quote times each trade may join under `op`, nearest first: the latest
+/// for `>=`/`>`, the earliest for `<=`/`<`
+fn nearest_quote_time(op: &str, symbol: Option<&str>, traded_at: i64) -> Option<i64> {
+    let candidates = QUOTES.iter()
+        .filter(|(_, sym, _)| symbol.map_or(true, |symbol| *sym == symbol))
+        .map(|(_, _, at)| *at)
+        .filter(|at| match op {
+            ">=" => traded_at >= *at,
+            ">" => traded_at > *at,
+            "<=" => traded_at <= *at,
+            _ => traded_at < *at,
+        });
+    match op {
+        ">=" | ">" => candidates.max(),
+        _ => candidates.min(),
+    }
+}
+
+/// `(trade_id, quoted_at)` rows of an ASOF join, ordered by trade
+async fn joined(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<(i64, Option<i64>)> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    let mut rows: Vec<(i64, Option<i64>)> = result.rows.iter()
+        .map(|row| (row[0].as_i64().unwrap(), row[1].as_i64()))
+        .collect();
+    rows.sort();
+    rows
+}
+
+#[tokio::test]
+async fn test_asof_join_matches_brute_force() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context).await;
+
+    for op in [">=", ">", "<=", "<"] {
+        let sql = format!(
+            "SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes \
+             MATCH_CONDITION (trades.traded_at {} quotes.quoted_at) ON trades.symbol = quotes.sym",
+            op
+        );
+        let mut expected: Vec<(i64, Option<i64>)> = TRADES.iter()
+            .map(|(id, symbol, at)| (*id, nearest_quote_time(op, Some(symbol), *at)))
+            .collect();
+        expected.sort();
+        // Every trade appears once, matched or not
+        assert_eq!(joined(&db, &user_context, &sql).await, expected, "{}", sql);
+    }
+
+    // Latest prior quote: trade 2 at 105 takes a 105 quote, MSFT at 90 and
+    // GOOG have none
+    let latest = joined(&db, &user_context,
+        "SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes \
+         MATCH_CONDITION (trades.traded_at >= quotes.quoted_at) ON trades.symbol = quotes.sym").await;
+    assert_eq!(latest, vec![(1, Some(95)), (2, Some(105)), (3, Some(125)), (4, None), (5, Some(100)), (6, None), (7, Some(95))]);
+}
+
+#[tokio::test]
+async fn test_asof_join_condition_forms() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context).await;
+
+    // Written with the quote time first, the condition means the same
+    let forward = joined(&db, &user_context,
+        "SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes \
+         MATCH_CONDITION (trades.traded_at > quotes.quoted_at) ON trades.symbol = quotes.sym").await;
+    let flipped = joined(&db, &user_context,
+        "SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes \
+         MATCH_CONDITION (quotes.quoted_at < trades.traded_at) ON quotes.sym = trades.symbol").await;
+    assert_eq!(forward, flipped);
+
+    // Without ON every quote is a candidate, whatever its symbol
+    let unkeyed = joined(&db, &user_context,
+        "SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes MATCH_CONDITION (trades.traded_at >= quotes.quoted_at)").await;
+    let mut expected: Vec<(i64, Option<i64>)> = TRADES.iter()
+        .map(|(id, _, at)| (*id, nearest_quote_time(">=", None, *at)))
+        .collect();
+    expected.sort();
+    assert_eq!(unkeyed, expected);
+
+    let explain = db.execute_query(
+        "EXPLAIN SELECT trade_id, quoted_at FROM trades ASOF JOIN quotes \
+         MATCH_CONDITION (trades.traded_at >= quotes.quoted_at) ON trades.symbol = quotes.sym",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(
+        explain.rows[0][0].as_str().unwrap(),
+        "ASOF Merge Join on trades.traded_at >= quotes.quoted_at by trades.symbol = quotes.sym"
+    );
+
+    // MATCH_CONDITION is required, and must be an inequality across the tables
+    for sql in [
+        "SELECT trade_id FROM trades ASOF JOIN quotes ON trades.symbol = quotes.sym",
+        "SELECT trade_id FROM trades ASOF JOIN quotes MATCH_CONDITION (trades.traded_at = quotes.quoted_at)",
+        "SELECT trade_id FROM trades ASOF JOIN quotes MATCH_CONDITION (quotes.quote_id >= quotes.quoted_at)",
+    ] {
+        assert!(db.execute_query(sql, &user_context).await.is_err(), "{}", sql);
+    }
+}
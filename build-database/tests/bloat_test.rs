@@ -0,0 +1,102 @@
+//! Bloat Estimation Tests
+//!
+//! Updates and deletes leave dead versions in a table and stale entries in
+//! its indexes. The estimates are read from counters kept by every write,
+//! recommend VACUUM and REINDEX once they pass the configured thresholds,
+//! and drop the REINDEX once a vacuum has rebuilt the indexes.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, IndexDefinition, IndexType, MaintenanceAction, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_churn_is_reported_as_bloat() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    db.execute_query("CREATE TABLE accounts (id INTEGER PRIMARY KEY, owner TEXT, balance INTEGER);", &user_context).await.unwrap();
+    db.create_index("accounts", &IndexDefinition {
+        name: "idx_accounts_owner".to_string(),
+        columns: vec!["owner".to_string()],
+        index_type: IndexType::BTree,
+    }, false, &user_context).await.unwrap();
+    for id in 0..100 {
+        db.execute_query(&format!("INSERT INTO accounts (id, owner, balance) VALUES ({}, 'owner{}', 0);", id, id), &user_context).await.unwrap();
+    }
+
+    // Freshly loaded: nothing to reclaim
+    let report = db.bloat_report(Some("accounts")).await.unwrap();
+    assert_eq!(report.tables[0].live_tuples, 100);
+    assert_eq!(report.tables[0].dead_tuples, 0);
+    assert!(!report.tables[0].measured);
+    assert!(report.recommendations().is_empty());
+
+    // Three updates of every row, then half of them deleted
+    for round in 1..=3 {
+        db.execute_query(&format!("UPDATE accounts SET balance = {};", round * 10), &user_context).await.unwrap();
+    }
+    db.execute_query("DELETE FROM accounts WHERE id >= 50;", &user_context).await.unwrap();
+
+    let report = db.bloat_report(Some("accounts")).await.unwrap();
+    let table = &report.tables[0];
+    assert_eq!((table.live_tuples, table.dead_tuples), (50, 350));
+    assert!(table.bloat_ratio > 0.5, "table bloat {}", table.bloat_ratio);
+    assert_eq!(table.recommendation, Some(MaintenanceAction::Vacuum));
+
+    let index = &report.indexes[0];
+    assert_eq!(index.index_name, "idx_accounts_owner");
+    assert_eq!((index.live_entries, index.stale_entries), (50, 350));
+    assert!(index.bloat_ratio > 0.5, "index bloat {}", index.bloat_ratio);
+    assert_eq!(index.recommendation, Some(MaintenanceAction::Reindex));
+    assert!(report.recommendations().iter().any(|r| {
+        r.action == MaintenanceAction::Reindex && r.index_name.as_deref() == Some("idx_accounts_owner")
+    }));
+
+    // The same estimates through the system view
+    let result = db.execute_query(
+        "SELECT object_name, recommendation FROM aurora_bloat WHERE table_name = 'accounts';",
+        &user_context,
+    ).await.unwrap();
+    let mut rows: Vec<(String, String)> = result.rows.iter()
+        .map(|row| (row[0].as_str().unwrap().to_string(), row[1].as_str().unwrap().to_string()))
+        .collect();
+    rows.sort();
+    assert_eq!(rows, [
+        ("accounts".to_string(), "VACUUM".to_string()),
+        ("idx_accounts_owner".to_string(), "REINDEX".to_string()),
+    ]);
+
+    // Vacuum rebuilds the indexes, so only the table's dead versions remain
+    db.vacuum_table("accounts", &user_context).await.unwrap();
+    let report = db.bloat_report(Some("accounts")).await.unwrap();
+    assert_eq!(report.indexes[0].stale_entries, 0);
+    assert_eq!(report.indexes[0].recommendation, None);
+}
+
+#[tokio::test]
+async fn test_unknown_table_is_rejected() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    assert!(db.bloat_report(Some("missing")).await.is_err());
+    assert!(db.bloat_report(None).await.unwrap().tables.is_empty());
+}
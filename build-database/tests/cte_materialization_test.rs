@@ -0,0 +1,87 @@
+//! CTE Materialization Tests
+//!
+//! A CTE referenced several times is computed once and shared; a small one
+//! stays in memory, and one larger than the budget spills to a temp file
+//! that reads back the same rows and is removed with the result.
+
+use aurora_db::config::MaterializationConfig;
+use aurora_db::engine::{CteMaterializations, Materializer};
+use aurora_db::storage::BufferUsage;
+use std::cell::Cell;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+type Row = (i64, String);
+
+fn rows(count: i64) -> Vec<Row> {
+    (0..count).map(|i| (i, format!("row {}", i))).collect()
+}
+
+fn materializer(budget: usize, temp_dir: &std::path::Path, buffers: Option<Arc<BufferUsage>>) -> Materializer {
+    Materializer::new(&MaterializationConfig { memory_budget_bytes: budget }, temp_dir, buffers)
+}
+
+#[test]
+fn test_cte_referenced_twice_is_computed_once() {
+    let temp_dir = tempdir().unwrap();
+    let ctes = CteMaterializations::new(materializer(1024 * 1024, temp_dir.path(), None));
+    let computed = Cell::new(0);
+    let compute = || {
+        computed.set(computed.get() + 1);
+        Ok(rows(100))
+    };
+
+    // WITH totals AS (...) SELECT ... FROM totals a JOIN totals b ...
+    let first = ctes.get_or_materialize("totals", compute).unwrap();
+    let second = ctes.get_or_materialize("totals", compute).unwrap();
+    assert_eq!(computed.get(), 1);
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(second.rows().unwrap(), rows(100));
+
+    // Another CTE of the statement is computed on its own
+    assert!(!ctes.contains("other"));
+    ctes.get_or_materialize("other", compute).unwrap();
+    assert_eq!(computed.get(), 2);
+}
+
+#[test]
+fn test_small_cte_stays_in_memory() {
+    let temp_dir = tempdir().unwrap();
+    let buffers = Arc::new(BufferUsage::new());
+    let result = materializer(64 * 1024, temp_dir.path(), Some(buffers.clone())).materialize(rows(50)).unwrap();
+    assert!(!result.is_spilled());
+    assert_eq!(result.len(), 50);
+    assert_eq!(result.rows().unwrap(), rows(50));
+    assert_eq!(buffers.counts().temp_written, 0);
+    assert!(!temp_dir.path().exists() || std::fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+}
+
+#[test]
+fn test_large_cte_spills_and_reads_back() {
+    let temp_dir = tempdir().unwrap();
+    let buffers = Arc::new(BufferUsage::new());
+    let ctes = CteMaterializations::new(materializer(64 * 1024, temp_dir.path(), Some(buffers.clone())));
+    let result = ctes.get_or_materialize("large", || Ok(rows(20_000))).unwrap();
+    assert!(result.is_spilled());
+    assert!(result.bytes() > 64 * 1024);
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+    // Each reference reads the whole file back
+    assert_eq!(result.rows().unwrap(), rows(20_000));
+    assert_eq!(ctes.get_or_materialize("large", || Ok(Vec::new())).unwrap().rows().unwrap().len(), 20_000);
+    let counts = buffers.counts();
+    assert!(counts.temp_written > 0);
+    assert_eq!(counts.temp_read, 2 * counts.temp_written);
+
+    // Under a zero budget any row spills; an empty result has none to write
+    let one = materializer(0, temp_dir.path(), None).materialize(rows(1)).unwrap();
+    assert!(one.is_spilled());
+    assert_eq!(one.rows().unwrap(), rows(1));
+    let empty = materializer(0, temp_dir.path(), None).materialize(Vec::<Row>::new()).unwrap();
+    assert!(!empty.is_spilled() && empty.is_empty());
+    drop(one);
+
+    drop(result);
+    drop(ctes);
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
@@ -0,0 +1,64 @@
+//! DECIMAL Column Tests
+//!
+//! Values in DECIMAL/NUMERIC columns are exact: they round to the column's
+//! scale on insert, reject values beyond its precision, and SUM/AVG over them
+//! do not pass through floating point.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_sum_is_exact() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    db.execute_query("CREATE TABLE ledger (id INTEGER PRIMARY KEY, amount NUMERIC);", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO ledger (id, amount) VALUES (1, 0.1), (2, 0.2);", &user_context).await.unwrap();
+
+    let sum = db.execute_query("SELECT SUM(amount) FROM ledger", &user_context).await.unwrap();
+    assert_eq!(sum.rows[0][0], serde_json::json!("0.3"));
+
+    // Division keeps DIVISION_SCALE fractional digits
+    let avg = db.execute_query("SELECT AVG(amount) FROM ledger", &user_context).await.unwrap();
+    assert_eq!(avg.rows[0][0], serde_json::json!("0.1500000000000000"));
+}
+
+#[tokio::test]
+async fn test_column_precision_and_scale() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    db.execute_query("CREATE TABLE prices (id INTEGER PRIMARY KEY, price DECIMAL(5, 2));", &user_context).await.unwrap();
+
+    // Rounds half away from zero to the column's scale
+    db.execute_query("INSERT INTO prices (id, price) VALUES (1, '1.005');", &user_context).await.unwrap();
+    let rows = db.execute_query("SELECT price FROM prices", &user_context).await.unwrap();
+    assert_eq!(rows.rows[0][0], serde_json::json!("1.01"));
+
+    // 1000.00 needs six digits of precision
+    let overflow = db.execute_query("INSERT INTO prices (id, price) VALUES (2, 1000);", &user_context).await;
+    assert!(overflow.is_err());
+
+    assert!(db.execute_query("CREATE TABLE bad (price DECIMAL(2, 3));", &user_context).await.is_err());
+}
@@ -0,0 +1,159 @@
+//! EXPLAIN (ANALYZE, BUFFERS) Tests
+//!
+//! Per-node buffer accounting: shared pages read on a cold scan and hit on a
+//! warm one, temp pages of a sort that spills past work_mem, and the pages
+//! and WAL that DML writes.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use serde_json::json;
+use std::collections::HashMap;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir, work_mem_bytes: usize) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        temp_directory: temp_dir.path().join("temp").to_string_lossy().to_string(),
+        work_mem_bytes,
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "explain_buffers".to_string(),
+    }
+}
+
+async fn populate(db: &AuroraDB, user_context: &UserContext, rows: usize) {
+    db.execute_query("CREATE TABLE readings (id INTEGER PRIMARY KEY, sensor TEXT, note TEXT);", user_context).await.unwrap();
+    for chunk in (0..rows).collect::<Vec<_>>().chunks(250) {
+        let values: Vec<String> = chunk.iter()
+            .map(|id| format!("({}, 'sensor-{}', 'reading {} from the north field')", id, id % 37, id))
+            .collect();
+        let sql = format!("INSERT INTO readings (id, sensor, note) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+async fn explain(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<String> {
+    let result = db.execute_query(&format!("EXPLAIN (ANALYZE, BUFFERS) {}", sql), user_context).await.unwrap();
+    result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect()
+}
+
+/// Counters listed under the plan node starting with `node`, keyed like
+/// `shared.read`, `temp.written` or `wal.bytes`
+fn node_counters(lines: &[String], node: &str) -> HashMap<String, u64> {
+    let start = lines.iter()
+        .position(|line| line.trim_start().trim_start_matches("-> ").starts_with(node))
+        .unwrap_or_else(|| panic!("no {} node in {:?}", node, lines));
+
+    let mut counters = HashMap::new();
+    for line in lines[start + 1..].iter().take_while(|line| !line.contains("-> ") && !line.starts_with("Execution Time")) {
+        let line = line.trim_start();
+        let groups: Vec<(&str, &str)> = if let Some(buffers) = line.strip_prefix("Buffers: ") {
+            buffers.split(", ").filter_map(|group| group.split_once(' ')).collect()
+        } else if let Some(wal) = line.strip_prefix("WAL: ") {
+            vec![("wal", wal)]
+        } else {
+            continue;
+        };
+        for (kind, values) in groups {
+            for value in values.split_whitespace() {
+                let (name, count) = value.split_once('=').unwrap();
+                counters.insert(format!("{}.{}", kind, name), count.parse().unwrap());
+            }
+        }
+    }
+    counters
+}
+
+fn counter(lines: &[String], node: &str, name: &str) -> u64 {
+    node_counters(lines, node).get(name).copied().unwrap_or(0)
+}
+
+#[tokio::test]
+async fn test_cold_scan_reads_and_warm_scan_hits() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir, 4 * 1024 * 1024).await;
+    let user_context = user_context();
+    populate(&db, &user_context, 2000).await;
+
+    let sql = "SELECT id, note FROM readings WHERE sensor = 'sensor-3'";
+    let cold = explain(&db, &user_context, sql).await;
+    assert!(cold[0].starts_with("Filter (actual rows=54)"), "{:?}", cold);
+    let cold_read = counter(&cold, "Seq Scan on readings", "shared.read");
+    assert!(cold_read > 1, "{:?}", cold);
+    assert_eq!(counter(&cold, "Seq Scan on readings", "shared.hit"), 0, "{:?}", cold);
+
+    let warm = explain(&db, &user_context, sql).await;
+    let warm_hit = counter(&warm, "Seq Scan on readings", "shared.hit");
+    let warm_read = counter(&warm, "Seq Scan on readings", "shared.read");
+    assert_eq!(warm_hit + warm_read, cold_read, "{:?}", warm);
+    assert!(warm_hit > warm_read * 9, "{:?}", warm);
+    assert!(warm.last().unwrap().starts_with("Execution Time: "));
+
+    // A write leaves the cached pages stale, so the next scan reads again
+    db.execute_query("UPDATE readings SET note = 'recalibrated' WHERE id = 7", &user_context).await.unwrap();
+    let after_write = explain(&db, &user_context, sql).await;
+    assert!(counter(&after_write, "Seq Scan on readings", "shared.read") > 1, "{:?}", after_write);
+}
+
+#[tokio::test]
+async fn test_spilling_sort_reports_temp_buffers() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir, 64 * 1024).await;
+    let user_context = user_context();
+    populate(&db, &user_context, 2000).await;
+
+    let sql = "SELECT id, note FROM readings ORDER BY note DESC";
+    let lines = explain(&db, &user_context, sql).await;
+    assert!(lines[0].starts_with("Sort (actual rows=2000)"), "{:?}", lines);
+    let temp_written = counter(&lines, "Sort", "temp.written");
+    assert!(temp_written > 1, "{:?}", lines);
+    assert_eq!(counter(&lines, "Sort", "temp.read"), temp_written, "{:?}", lines);
+    // The scan under the sort never touches temp files
+    assert_eq!(counter(&lines, "Seq Scan on readings", "temp.written"), 0);
+
+    // The spilled sort returns the same order as an in-memory one
+    let result = db.execute_query(sql, &user_context).await.unwrap();
+    let notes: Vec<String> = result.rows.iter().map(|row| row[1].as_str().unwrap().to_string()).collect();
+    let mut expected = notes.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    assert_eq!(notes, expected);
+
+    // Within work_mem nothing spills
+    let small = explain(&db, &user_context, "SELECT id FROM readings WHERE sensor = 'sensor-1' ORDER BY id").await;
+    assert_eq!(counter(&small, "Sort", "temp.written"), 0, "{:?}", small);
+}
+
+#[tokio::test]
+async fn test_dml_reports_dirtied_pages_and_wal() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir, 4 * 1024 * 1024).await;
+    let user_context = user_context();
+    populate(&db, &user_context, 10).await;
+
+    let lines = explain(&db, &user_context, "INSERT INTO readings (id, sensor, note) VALUES (100, 'sensor-9', 'late'), (101, 'sensor-9', 'later')").await;
+    assert_eq!(lines[0], "Insert on readings (actual rows=2)");
+    assert!(counter(&lines, "Insert on readings", "shared.dirtied") >= 2, "{:?}", lines);
+    assert_eq!(counter(&lines, "Insert on readings", "wal.records"), 2, "{:?}", lines);
+    assert!(counter(&lines, "Insert on readings", "wal.bytes") > 0, "{:?}", lines);
+
+    // ANALYZE really runs the statement
+    let count = db.execute_query("SELECT id FROM readings WHERE sensor = 'sensor-9'", &user_context).await.unwrap();
+    assert!(count.rows.iter().any(|row| row[0] == json!(101)));
+
+    let lines = explain(&db, &user_context, "DELETE FROM readings WHERE sensor = 'sensor-9'").await;
+    assert!(lines[0].starts_with("Delete on readings (actual rows="), "{:?}", lines);
+    assert!(counter(&lines, "Seq Scan on readings", "shared.read") >= 1, "{:?}", lines);
+    assert!(counter(&lines, "Delete on readings", "wal.records") >= 2, "{:?}", lines);
+
+    // BUFFERS needs ANALYZE
+    assert!(db.execute_query("EXPLAIN (BUFFERS) SELECT id FROM readings", &user_context).await.is_err());
+}
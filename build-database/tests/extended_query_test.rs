@@ -0,0 +1,293 @@
+//! Extended Query Parameter Tests
+//!
+//! Bind checks each parameter value against the type Parse declared for it
+//! or inferred from its column: compatible values are coerced, incompatible
+//! ones are refused at Bind with the parameter's index and expected type,
+//! and the rest of the message sequence is skipped until Sync.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::network::extended_query::{
+    coerce_parameter, infer_parameter_types, parameter_count, referenced_tables, substitute_parameters,
+};
+use aurora_db::network::{ExtendedQueryError, ParameterType, ParameterValue, PostgresProtocol};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Server's replies up to ReadyForQuery
+#[derive(Debug)]
+struct Reply {
+    /// Type byte of every message, in order
+    messages: Vec<u8>,
+    /// DataRow values
+    rows: Vec<Vec<String>>,
+    /// Parameter type OIDs of a ParameterDescription
+    parameter_types: Vec<u32>,
+    /// SQLSTATE and message of the ErrorResponse, if any
+    error: Option<(String, String)>,
+    /// ReadyForQuery transaction status
+    status: u8,
+}
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+async fn connect(socket: &mut TcpStream) {
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(b"user\0test\0\0");
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(socket).await.0, b'R');
+    send(socket, b'p', b"secret\0").await;
+    assert_eq!(read_message(socket).await.0, b'R');
+    assert_eq!(read_message(socket).await, (b'Z', vec![b'I']));
+}
+
+fn data_row(body: &[u8]) -> Vec<String> {
+    let columns = u16::from_be_bytes([body[0], body[1]]) as usize;
+    let mut offset = 2;
+    let mut values = Vec::with_capacity(columns);
+    for _ in 0..columns {
+        let length = u32::from_be_bytes(body[offset..offset + 4].try_into().unwrap()) as usize;
+        offset += 4;
+        values.push(String::from_utf8_lossy(&body[offset..offset + length]).to_string());
+        offset += length;
+    }
+    values
+}
+
+async fn read_reply(socket: &mut TcpStream) -> Reply {
+    let mut reply = Reply { messages: Vec::new(), rows: Vec::new(), parameter_types: Vec::new(), error: None, status: 0 };
+    loop {
+        let (message_type, body) = read_message(socket).await;
+        reply.messages.push(message_type);
+        match message_type {
+            b'D' => reply.rows.push(data_row(&body)),
+            b't' => reply.parameter_types = body[2..].chunks(4).map(|oid| u32::from_be_bytes(oid.try_into().unwrap())).collect(),
+            b'E' => {
+                let field = |code: u8| body.split(|byte| *byte == 0)
+                    .find(|field| field.first() == Some(&code))
+                    .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+                    .unwrap_or_default();
+                reply.error = Some((field(b'C'), field(b'M')));
+            }
+            b'Z' => {
+                reply.status = body[0];
+                return reply;
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn query(socket: &mut TcpStream, sql: &str) -> Reply {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+    read_reply(socket).await
+}
+
+fn parse_message(sql: &str, parameter_types: &[u32]) -> Vec<u8> {
+    let mut body = format!("\0{}\0", sql).into_bytes();
+    body.extend_from_slice(&(parameter_types.len() as i16).to_be_bytes());
+    for oid in parameter_types {
+        body.extend_from_slice(&oid.to_be_bytes());
+    }
+    body
+}
+
+/// Bind of text values to the unnamed statement and portal
+fn bind_message(values: &[Option<&str>]) -> Vec<u8> {
+    let mut body = b"\0\0".to_vec();
+    body.extend_from_slice(&0i16.to_be_bytes());
+    body.extend_from_slice(&(values.len() as i16).to_be_bytes());
+    for value in values {
+        match value {
+            Some(value) => {
+                body.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                body.extend_from_slice(value.as_bytes());
+            }
+            None => body.extend_from_slice(&(-1i32).to_be_bytes()),
+        }
+    }
+    body.extend_from_slice(&0i16.to_be_bytes());
+    body
+}
+
+/// Parse, Describe, Bind, Execute and Sync of one statement
+async fn run_prepared(socket: &mut TcpStream, sql: &str, parameter_types: &[u32], values: &[Option<&str>]) -> Reply {
+    send(socket, b'P', &parse_message(sql, parameter_types)).await;
+    send(socket, b'D', b"S\0").await;
+    send(socket, b'B', &bind_message(values)).await;
+    send(socket, b'E', b"\0\0\0\0\0").await;
+    send(socket, b'S', &[]).await;
+    read_reply(socket).await
+}
+
+async fn open(temp_dir: &TempDir) -> Arc<aurora_db::engine::AuroraDB> {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    Arc::new(aurora_db::engine::AuroraDB::new(config).await.unwrap())
+}
+
+/// Serve one connection, with `items` created and filled
+async fn serve(temp_dir: &TempDir) -> (TcpStream, tokio::task::JoinHandle<()>) {
+    let db = open(temp_dir).await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::task::spawn_local(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        PostgresProtocol::new(db).handle_connection(socket).await.unwrap();
+    });
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    connect(&mut socket).await;
+    assert_eq!(query(&mut socket, "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price INTEGER, weight FLOAT)").await.error, None);
+    assert_eq!(query(&mut socket, "INSERT INTO items (id, name, price, weight) VALUES (1, 'nut', 5, 0.5), (2, 'gear', 12, 2.5)").await.error, None);
+    (socket, server)
+}
+
+#[tokio::test]
+async fn test_compatible_values_are_coerced_at_bind() {
+    let temp_dir = tempdir().unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        let (mut socket, server) = serve(&temp_dir).await;
+
+        // Text for the integer id and price, an integer for the float weight
+        let reply = run_prepared(
+            &mut socket,
+            "INSERT INTO items (id, name, price, weight) VALUES ($1, $2, $3, $4)",
+            &[],
+            &[Some("7"), Some("bolt"), Some(" 9 "), Some("3")],
+        ).await;
+        assert_eq!(reply.error, None);
+        assert_eq!(reply.parameter_types, vec![20, 25, 20, 701]);
+        assert_eq!(&reply.messages[..4], b"1tn2");
+
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE id = $1 AND price = $2", &[], &[Some("7"), Some("9")]).await;
+        assert_eq!(reply.error, None);
+        assert_eq!(reply.rows, vec![vec!["bolt".to_string()]]);
+        assert_eq!(reply.status, b'I');
+
+        send(&mut socket, b'X', &[]).await;
+        server.await.unwrap();
+    }).await;
+}
+
+#[tokio::test]
+async fn test_incompatible_bind_is_rejected_with_parameter_and_type() {
+    let temp_dir = tempdir().unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        let (mut socket, server) = serve(&temp_dir).await;
+
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE price < $2 AND id = $1", &[], &[Some("seven"), Some("10")]).await;
+        let (sqlstate, message) = reply.error.unwrap();
+        assert_eq!(sqlstate, "22P02");
+        assert_eq!(message, "invalid input for parameter $1: expected integer, got \"seven\"");
+        // Execute after the failed Bind is skipped; Sync still answers
+        assert_eq!(reply.messages, b"1tnEZ");
+        assert!(reply.rows.is_empty());
+
+        // A fractional value does not narrow to an integer either
+        let reply = run_prepared(&mut socket, "UPDATE items SET price = $1 WHERE id = 1", &[], &[Some("4.5")]).await;
+        assert_eq!(reply.error.unwrap().1, "invalid input for parameter $1: expected integer, got \"4.5\"");
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE id = $1", &[], &[Some("1"), Some("2")]).await;
+        assert_eq!(reply.error.unwrap().0, "08P01");
+
+        // The connection keeps working, and nothing was changed
+        let reply = query(&mut socket, "SELECT price FROM items WHERE id = 1").await;
+        assert_eq!(reply.rows, vec![vec!["5".to_string()]]);
+
+        // Inside a transaction block a refused Bind aborts the block
+        assert_eq!(query(&mut socket, "BEGIN").await.error, None);
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE id = $1", &[], &[Some("x")]).await;
+        assert_eq!((reply.error.unwrap().0.as_str(), reply.status), ("22P02", b'E'));
+        assert_eq!(query(&mut socket, "ROLLBACK").await.status, b'I');
+
+        send(&mut socket, b'X', &[]).await;
+        server.await.unwrap();
+    }).await;
+}
+
+#[tokio::test]
+async fn test_declared_parameter_types_are_honored() {
+    let temp_dir = tempdir().unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        let (mut socket, server) = serve(&temp_dir).await;
+
+        // Compared with the integer price, $1 would refuse a fraction; as a
+        // declared float8 it takes one
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE price > $1", &[], &[Some("9.5")]).await;
+        assert_eq!(reply.error.unwrap().0, "22P02");
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE price > $1", &[701], &[Some("9.5")]).await;
+        assert_eq!(reply.error, None);
+        assert_eq!(reply.parameter_types, vec![701]);
+        assert_eq!(reply.rows, vec![vec!["gear".to_string()]]);
+
+        // A declared int4 is checked as an integer even against a text column
+        let reply = run_prepared(&mut socket, "SELECT id FROM items WHERE name = $1", &[23], &[Some("nut")]).await;
+        assert_eq!(reply.error.unwrap().1, "invalid input for parameter $1: expected integer, got \"nut\"");
+
+        // An undeclared (0) entry still takes the inferred type
+        let reply = run_prepared(&mut socket, "SELECT name FROM items WHERE id = $1 AND weight < $2", &[0, 701], &[Some("2"), Some("3")]).await;
+        assert_eq!(reply.parameter_types, vec![20, 701]);
+        assert_eq!(reply.rows, vec![vec!["gear".to_string()]]);
+
+        send(&mut socket, b'X', &[]).await;
+        server.await.unwrap();
+    }).await;
+}
+
+#[test]
+fn test_parameter_inference_and_substitution() {
+    let mut columns = HashMap::new();
+    columns.insert("items".to_string(), vec![
+        ("id".to_string(), ParameterType::Integer),
+        ("name".to_string(), ParameterType::Text),
+        ("in_stock".to_string(), ParameterType::Boolean),
+    ]);
+
+    let sql = "SELECT * FROM items i WHERE $2 = i.name AND id >= $1 AND note = '$3' AND $3 IS NULL";
+    assert_eq!(referenced_tables(sql), vec!["items".to_string()]);
+    assert_eq!(parameter_count(sql), 3);
+    assert_eq!(infer_parameter_types(sql, 3, &columns), vec![ParameterType::Integer, ParameterType::Text, ParameterType::Unknown]);
+    // Without a column list, values follow the table's columns
+    assert_eq!(
+        infer_parameter_types("INSERT INTO items VALUES ($1, upper($2), $3)", 3, &columns),
+        vec![ParameterType::Integer, ParameterType::Unknown, ParameterType::Boolean],
+    );
+
+    let values = [ParameterValue::Integer(3), ParameterValue::Text("it's".to_string()), ParameterValue::Null];
+    assert_eq!(
+        substitute_parameters("SELECT '$1', $1, $2 WHERE x = $3 OR y = $12", &values),
+        "SELECT '$1', 3, 'it''s' WHERE x = NULL OR y = $12",
+    );
+
+    assert_eq!(coerce_parameter(1, ParameterType::Float, Some(b"2"), false), Ok(ParameterValue::Float(2.0)));
+    assert_eq!(coerce_parameter(1, ParameterType::Boolean, Some(b"yes"), false), Ok(ParameterValue::Boolean(true)));
+    assert_eq!(coerce_parameter(2, ParameterType::Integer, Some(&42i32.to_be_bytes()), true), Ok(ParameterValue::Integer(42)));
+    assert_eq!(coerce_parameter(2, ParameterType::Integer, None, false), Ok(ParameterValue::Null));
+    assert!(matches!(
+        coerce_parameter(2, ParameterType::Integer, Some(&[0, 1, 2]), true),
+        Err(ExtendedQueryError::InvalidBinaryParameter { index: 2, .. })
+    ));
+    assert!(coerce_parameter(1, ParameterType::Float, Some(b"NaN"), false).is_err());
+}
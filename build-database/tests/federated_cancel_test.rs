@@ -0,0 +1,164 @@
+//! Federated Cancellation Tests
+//!
+//! Cancelling a query that reads a remote foreign table sends a cancel to
+//! the remote cursor serving the scan, waits for it to be acknowledged and
+//! leaves no cursor open on the remote side once the query has failed.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use aurora_db::config::DatabaseConfig;
+use aurora_db::core::AuroraResult;
+use aurora_db::engine::{
+    AuroraDB, ForeignDataWrapper, ForeignScan, ForeignScanEstimate, RemoteCursor, RowStream,
+    ScanFilter, UserContext,
+};
+use aurora_db::types::DataValue;
+use async_trait::async_trait;
+use futures::StreamExt;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// What the remote server has seen: cursors opened and still open, cancels
+/// received and rows sent
+#[derive(Default)]
+struct RemoteServer {
+    opened: AtomicUsize,
+    open_cursors: AtomicUsize,
+    cancels: AtomicUsize,
+    rows_sent: AtomicUsize,
+}
+
+/// One cursor on the remote server; cancelling it stops its rows
+struct MockCursor {
+    server: Arc<RemoteServer>,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl RemoteCursor for MockCursor {
+    fn describe(&self) -> String {
+        "mock-remote:5432".to_string()
+    }
+
+    async fn cancel(&self) -> AuroraResult<()> {
+        // Acknowledged after a round trip
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.server.cancels.fetch_add(1, Ordering::SeqCst);
+        self.cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Remote table sending `rows` rows, one every 10ms, through a cursor
+struct RemoteTable {
+    server: Arc<RemoteServer>,
+    rows: usize,
+}
+
+#[async_trait]
+impl ForeignDataWrapper for RemoteTable {
+    fn name(&self) -> &str {
+        "aurora"
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec!["id".to_string()]
+    }
+
+    fn estimate(&self, _filters: &[ScanFilter]) -> ForeignScanEstimate {
+        ForeignScanEstimate { rows: self.rows as f64, startup_cost: 10.0, total_cost: self.rows as f64 }
+    }
+
+    async fn scan(&self, projection: &[String], filters: &[ScanFilter]) -> AuroraResult<RowStream> {
+        Ok(self.open_scan(projection, filters).await?.rows)
+    }
+
+    async fn open_scan(&self, _projection: &[String], _filters: &[ScanFilter]) -> AuroraResult<ForeignScan> {
+        let server = self.server.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        server.opened.fetch_add(1, Ordering::SeqCst);
+        server.open_cursors.fetch_add(1, Ordering::SeqCst);
+
+        // The cursor closes when its rows run out or it is cancelled
+        let (stream_server, stream_cancelled, rows) = (server.clone(), cancelled.clone(), self.rows);
+        let rows = futures::stream::unfold(0, move |id| {
+            let (server, cancelled) = (stream_server.clone(), stream_cancelled.clone());
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                if id == rows || cancelled.load(Ordering::SeqCst) {
+                    server.open_cursors.fetch_sub(1, Ordering::SeqCst);
+                    return None;
+                }
+                server.rows_sent.fetch_add(1, Ordering::SeqCst);
+                let row = [("id".to_string(), DataValue::Integer(id as i64))].into_iter().collect();
+                Some((Ok(row), id + 1))
+            }
+        });
+        Ok(ForeignScan { rows: rows.boxed(), cursor: Some(Arc::new(MockCursor { server, cancelled })) })
+    }
+}
+
+#[tokio::test]
+async fn test_cancelled_federated_query_cancels_remote_cursor() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    let server = Arc::new(RemoteServer::default());
+    // 10 seconds of rows if left to run
+    db.register_foreign_table("remote_events", Arc::new(RemoteTable { server: server.clone(), rows: 1000 })).await.unwrap();
+
+    let query = db.execute_query("SELECT id FROM remote_events WHERE id >= 0;", &user_context);
+    let cancel = async {
+        while server.rows_sent.load(Ordering::SeqCst) < 5 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(db.cancel_statement("test_session"));
+    };
+    let (result, ()) = tokio::time::timeout(Duration::from_secs(5), async { tokio::join!(query, cancel) })
+        .await
+        .expect("cancelled query did not stop");
+
+    assert!(result.unwrap_err().to_string().contains("canceling statement on request"));
+
+    // Cancelled once, acknowledged before the query returned, nothing left open
+    assert_eq!(server.opened.load(Ordering::SeqCst), 1);
+    assert_eq!(server.cancels.load(Ordering::SeqCst), 1);
+    assert_eq!(server.open_cursors.load(Ordering::SeqCst), 0);
+    assert!(server.rows_sent.load(Ordering::SeqCst) < 1000);
+    assert!(!db.is_statement_running("test_session"));
+
+    // The session runs its next query normally
+    let result = db.execute_query("SELECT 1;", &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 1);
+}
+
+#[tokio::test]
+async fn test_completed_federated_query_sends_no_cancel() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let server = Arc::new(RemoteServer::default());
+    db.register_foreign_table("remote_events", Arc::new(RemoteTable { server: server.clone(), rows: 5 })).await.unwrap();
+
+    let result = db.execute_query("SELECT id FROM remote_events;", &user_context()).await.unwrap();
+    assert_eq!(result.rows.len(), 5);
+    assert_eq!(server.cancels.load(Ordering::SeqCst), 0);
+    assert_eq!(server.open_cursors.load(Ordering::SeqCst), 0);
+}
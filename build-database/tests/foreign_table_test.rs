@@ -0,0 +1,158 @@
+//! Foreign Table Tests
+//!
+//! A CSV foreign table joins with a native table, receives the query's
+//! projection and filters, and shows up in EXPLAIN as a foreign scan with
+//! the wrapper's cost estimate.
+
+use std::sync::{Arc, Mutex};
+use aurora_db::config::DatabaseConfig;
+use aurora_db::core::AuroraResult;
+use aurora_db::engine::{
+    AuroraDB, CsvForeignTable, FilterOp, ForeignDataWrapper, ForeignScanEstimate, RowStream,
+    ScanFilter, UserContext,
+};
+use aurora_db::types::DataValue;
+use async_trait::async_trait;
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+const PRODUCTS_CSV: &str = "\
+id,name,price,category
+1,Widget,5,tools
+2,Gadget,25,tools
+3,\"Doohickey, large\",40,garden
+4,Sprocket,12,tools
+";
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// The projection and filters of every scan, passed on to a CSV table
+struct RecordingWrapper {
+    inner: CsvForeignTable,
+    scans: Mutex<Vec<(Vec<String>, Vec<ScanFilter>)>>,
+}
+
+impl RecordingWrapper {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            inner: CsvForeignTable::from_csv(PRODUCTS_CSV).unwrap(),
+            scans: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn last_scan(&self) -> (Vec<String>, Vec<ScanFilter>) {
+        self.scans.lock().unwrap().last().cloned().unwrap()
+    }
+}
+
+#[async_trait]
+impl ForeignDataWrapper for RecordingWrapper {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn columns(&self) -> Vec<String> {
+        self.inner.columns()
+    }
+
+    fn estimate(&self, filters: &[ScanFilter]) -> ForeignScanEstimate {
+        self.inner.estimate(filters)
+    }
+
+    async fn scan(&self, projection: &[String], filters: &[ScanFilter]) -> AuroraResult<RowStream> {
+        self.scans.lock().unwrap().push((projection.to_vec(), filters.to_vec()));
+        self.inner.scan(projection, filters).await
+    }
+}
+
+/// `orders` is native; `products` is foreign
+async fn load_orders(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE orders (order_id INTEGER PRIMARY KEY, product_id INTEGER, quantity INTEGER);", user_context).await.unwrap();
+    db.execute_query("INSERT INTO orders (order_id, product_id, quantity) VALUES (100, 1, 3), (101, 2, 1), (102, 3, 2), (103, 2, 7), (104, 9, 1);", user_context).await.unwrap();
+}
+
+async fn sorted_rows(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<String> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    let mut rows: Vec<String> = result.rows.iter().map(|row| format!("{:?}", row)).collect();
+    rows.sort();
+    rows
+}
+
+#[tokio::test]
+async fn test_foreign_table_joins_native_table_with_pushdown() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_orders(&db, &user_context).await;
+    let products = RecordingWrapper::new();
+    db.register_foreign_table("products", products.clone()).await.unwrap();
+
+    let sql = "SELECT order_id, p.name FROM orders JOIN products p ON orders.product_id = p.id WHERE p.price > 10 AND quantity = 1";
+    let rows = sorted_rows(&db, &user_context, sql).await;
+    assert_eq!(rows, vec![format!("{:?}", vec![json!(101), json!("Gadget")])]);
+
+    // Only the filter on the foreign table is pushed, and only the columns
+    // the query reads are asked for
+    let (projection, filters) = products.last_scan();
+    assert_eq!(projection, vec!["id".to_string(), "name".to_string(), "price".to_string()]);
+    assert_eq!(filters, vec![ScanFilter { column: "price".to_string(), op: FilterOp::Gt, value: DataValue::Integer(10) }]);
+
+    // The foreign table in FROM takes unqualified filters, either way round
+    let result = db.execute_query("SELECT name FROM products WHERE 30 > price AND category = 'tools';", &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 3);
+    let (_, filters) = products.last_scan();
+    assert_eq!(filters, vec![
+        ScanFilter { column: "price".to_string(), op: FilterOp::Lt, value: DataValue::Integer(30) },
+        ScanFilter { column: "category".to_string(), op: FilterOp::Eq, value: DataValue::Text("tools".to_string()) },
+    ]);
+
+    // A RIGHT join keeps null-extended rows the filter would drop
+    db.execute_query("SELECT order_id FROM products RIGHT JOIN orders ON products.id = orders.product_id WHERE price > 10;", &user_context).await.unwrap();
+    assert!(products.last_scan().1.is_empty());
+}
+
+#[tokio::test]
+async fn test_foreign_scan_plan_and_registration() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_orders(&db, &user_context).await;
+    let products = Arc::new(CsvForeignTable::from_csv(PRODUCTS_CSV).unwrap());
+    db.register_foreign_table("products", products).await.unwrap();
+    assert_eq!(db.foreign_tables(), vec!["products".to_string()]);
+
+    let result = db.execute_query("EXPLAIN SELECT name FROM products WHERE category = 'tools'", &user_context).await.unwrap();
+    let lines: Vec<String> = result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect();
+    assert!(lines.iter().any(|line| line.contains("Foreign Scan on products using csv (cost=0.00..0.04 rows=1) Pushed Filter: category = 'tools'")), "{:?}", lines);
+
+    let result = db.execute_query("EXPLAIN SELECT order_id, name FROM orders JOIN products ON orders.product_id = products.id", &user_context).await.unwrap();
+    let lines: Vec<String> = result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect();
+    assert!(lines.iter().any(|line| line.contains("-> Foreign Scan on products using csv (cost=0.00..0.04 rows=4)")), "{:?}", lines);
+    assert!(lines.iter().any(|line| line.contains("Seq Scan on orders")), "{:?}", lines);
+
+    // Names are shared with native tables; foreign tables are read-only
+    let clash = Arc::new(CsvForeignTable::from_csv("id\n1\n").unwrap());
+    assert!(db.register_foreign_table("orders", clash).await.is_err());
+    assert!(db.execute_query("CREATE TABLE products (id INTEGER);", &user_context).await.is_err());
+    assert!(db.execute_query("INSERT INTO products (id) VALUES (5);", &user_context).await.is_err());
+
+    assert!(db.unregister_foreign_table("products"));
+    assert!(db.execute_query("SELECT * FROM products;", &user_context).await.is_err());
+    assert!(CsvForeignTable::from_csv("id,name\n1\n").is_err());
+}
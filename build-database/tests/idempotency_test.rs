@@ -0,0 +1,72 @@
+//! Idempotency Key Tests
+//!
+//! A keyed write retried with the same key is applied once and every attempt
+//! sees the original result, including after a restart.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_keyed_insert_applied_once() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    db.execute_query("CREATE TABLE payments (id INTEGER PRIMARY KEY, amount INTEGER);", &user_context).await.unwrap();
+
+    let sql = "INSERT INTO payments (id, amount) VALUES (1, 100)";
+    let first = db.execute_query_idempotent(sql, &user_context, "payment-1").await.unwrap();
+    let retry = db.execute_query_idempotent(sql, &user_context, "payment-1").await.unwrap();
+
+    assert_eq!(first.rows_affected, Some(1));
+    assert_eq!(retry.rows_affected, first.rows_affected);
+    assert_eq!(retry.rows, first.rows);
+
+    let rows = db.execute_query("SELECT * FROM payments", &user_context).await.unwrap();
+    assert_eq!(rows.rows.len(), 1);
+
+    // The key cannot be reused for a different statement
+    let other = "INSERT INTO payments (id, amount) VALUES (2, 200)";
+    assert!(db.execute_query_idempotent(other, &user_context, "payment-1").await.is_err());
+}
+
+#[tokio::test]
+async fn test_keys_survive_restart() {
+    let temp_dir = tempdir().unwrap();
+    let user_context = user_context();
+    let sql = "INSERT INTO payments (id, amount) VALUES (1, 100)";
+
+    let first = {
+        let db = open(&temp_dir).await;
+        db.execute_query("CREATE TABLE payments (id INTEGER PRIMARY KEY, amount INTEGER);", &user_context).await.unwrap();
+        let result = db.execute_query_idempotent(sql, &user_context, "payment-1").await.unwrap();
+        db.shutdown().await.unwrap();
+        result
+    };
+
+    let db = open(&temp_dir).await;
+    let retry = db.execute_query_idempotent(sql, &user_context, "payment-1").await.unwrap();
+    assert_eq!(retry.rows_affected, first.rows_affected);
+
+    let rows = db.execute_query("SELECT * FROM payments", &user_context).await.unwrap();
+    assert_eq!(rows.rows.len(), 1);
+}
@@ -0,0 +1,130 @@
+//! INSERT ... SELECT Tests
+//!
+//! A large source is streamed into the target in batches no larger than the
+//! session's `work_mem`, a failure partway rolls back every row, and ON
+//! CONFLICT skips or updates rows whose key already exists.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+/// Rows in `source`
+const SOURCE_ROWS: i64 = 5000;
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// `source` with ids 0..5000 and `target` with the same columns
+async fn load_source(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE source (id INTEGER PRIMARY KEY, name TEXT, score INTEGER);", user_context).await.unwrap();
+    db.execute_query("CREATE TABLE target (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score INTEGER);", user_context).await.unwrap();
+    for batch in 0..10 {
+        let values: Vec<String> = (batch * 500..(batch + 1) * 500)
+            .map(|i| format!("({}, 'name {}', {})", i, i, i % 100))
+            .collect();
+        let sql = format!("INSERT INTO source (id, name, score) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+async fn count(db: &AuroraDB, table: &str, user_context: &UserContext) -> i64 {
+    let result = db.execute_query(&format!("SELECT COUNT(*) FROM {};", table), user_context).await.unwrap();
+    result.rows[0][0].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_insert_select_streams_in_bounded_batches() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_source(&db, &user_context).await;
+
+    let work_mem = 16 * 1024;
+    db.set_session_work_mem(&user_context.session_id, work_mem);
+    let result = db.execute_query("INSERT INTO target SELECT * FROM source;", &user_context).await.unwrap();
+    assert_eq!(result.rows_affected, Some(SOURCE_ROWS as u64));
+    assert_eq!(count(&db, "target", &user_context).await, SOURCE_ROWS);
+
+    let stats = db.last_insert_select_stats(&user_context.session_id).unwrap();
+    assert!(stats.streamed);
+    assert_eq!(stats.rows_written, SOURCE_ROWS as u64);
+    assert!(stats.batches > 1, "expected several batches, got {}", stats.batches);
+    assert!(stats.peak_batch_bytes <= work_mem as u64, "batch of {} bytes over work_mem", stats.peak_batch_bytes);
+
+    // A filtered, projected source with the columns named on the insert side
+    db.execute_query("CREATE TABLE top (id INTEGER PRIMARY KEY, name TEXT);", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO top (id, name) SELECT id, name FROM source WHERE score > 94;", &user_context).await.unwrap();
+    assert_eq!(count(&db, "top", &user_context).await, SOURCE_ROWS / 20);
+}
+
+#[tokio::test]
+async fn test_insert_select_failure_rolls_back_every_row() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_source(&db, &user_context).await;
+    db.set_session_work_mem(&user_context.session_id, 16 * 1024);
+
+    // The last source row breaks `target.name NOT NULL`, several batches in
+    db.execute_query(&format!("INSERT INTO source (id, name, score) VALUES ({}, NULL, 0);", SOURCE_ROWS), &user_context).await.unwrap();
+    let failed = db.execute_query("INSERT INTO target SELECT * FROM source;", &user_context).await;
+    assert!(failed.unwrap_err().to_string().contains("cannot be null"));
+    assert_eq!(count(&db, "target", &user_context).await, 0);
+
+    // A duplicate key without ON CONFLICT rolls back the same way
+    db.execute_query("INSERT INTO target (id, name, score) VALUES (4000, 'existing', 0);", &user_context).await.unwrap();
+    let duplicate = db.execute_query("INSERT INTO target SELECT * FROM source WHERE id < 5000;", &user_context).await;
+    assert!(duplicate.unwrap_err().to_string().contains("Primary key violation"));
+    assert_eq!(count(&db, "target", &user_context).await, 1);
+}
+
+#[tokio::test]
+async fn test_insert_select_on_conflict() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_source(&db, &user_context).await;
+    db.execute_query("INSERT INTO target (id, name, score) VALUES (1, 'kept', -1), (2, 'kept', -1);", &user_context).await.unwrap();
+
+    let result = db.execute_query(
+        "INSERT INTO target SELECT * FROM source WHERE id < 10 ON CONFLICT (id) DO NOTHING;",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows_affected, Some(8));
+    let stats = db.last_insert_select_stats(&user_context.session_id).unwrap();
+    assert_eq!(stats.rows_skipped, 2);
+    let kept = db.execute_query("SELECT name FROM target WHERE id = 1;", &user_context).await.unwrap();
+    assert_eq!(kept.rows[0][0], json!("kept"));
+
+    // Existing rows take the proposed score; columns not assigned are kept
+    let result = db.execute_query(
+        "INSERT INTO target SELECT * FROM source WHERE id < 20 ON CONFLICT (id) DO UPDATE SET score = excluded.score;",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows_affected, Some(20));
+    assert_eq!(count(&db, "target", &user_context).await, 20);
+    let updated = db.execute_query("SELECT name, score FROM target WHERE id = 2;", &user_context).await.unwrap();
+    assert_eq!(updated.rows[0], vec![json!("kept"), json!(2)]);
+
+    let bad_target = db.execute_query(
+        "INSERT INTO target SELECT * FROM source ON CONFLICT (name) DO NOTHING;",
+        &user_context,
+    ).await;
+    assert!(bad_target.unwrap_err().to_string().contains("not the primary key"));
+}
@@ -399,6 +399,7 @@ mod integration_tests {
                     adaptive_threshold: 1000,
                     vector_threshold: 0.1,
                 },
+                columnar: aurora_db::config::ColumnarConfig::default(),
                 selection_strategy: "workload_based".to_string(),
             },
             transaction: TransactionConfig {
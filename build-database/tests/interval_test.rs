@@ -0,0 +1,149 @@
+//! Interval Tests
+//!
+//! INTERVAL columns and literals, timestamp arithmetic across month ends
+//! and DST changes, date_trunc, EXTRACT and age.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context(session_id: &str) -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: session_id.to_string(),
+    }
+}
+
+async fn single_value(db: &AuroraDB, sql: &str, user_context: &UserContext) -> serde_json::Value {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 1, "{}", sql);
+    result.rows[0][0].clone()
+}
+
+#[tokio::test]
+async fn test_interval_addition_across_month_boundaries() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context("months");
+
+    db.execute_query("CREATE TABLE subscriptions (id INTEGER PRIMARY KEY, started_at TIMESTAMP, term INTERVAL);", &user_context).await.unwrap();
+    db.execute_query(
+        "INSERT INTO subscriptions (id, started_at, term) VALUES \
+         (1, '2024-01-31 09:00:00', '1 month'), \
+         (2, '2023-01-31 09:00:00', '1 mon'), \
+         (3, '2024-02-29 09:00:00', '1 year'), \
+         (4, '2024-12-31 09:00:00', '2 months 1 day'), \
+         (5, '2024-03-31 09:00:00', '-1 month');",
+        &user_context,
+    ).await.unwrap();
+
+    for (id, ends_at) in [
+        (1, "2024-02-29 09:00:00"),
+        (2, "2023-02-28 09:00:00"),
+        (3, "2025-02-28 09:00:00"),
+        // Months first, then days: Feb 28, then Mar 1
+        (4, "2025-03-01 09:00:00"),
+        (5, "2024-02-29 09:00:00"),
+    ] {
+        let sql = format!("SELECT started_at + term FROM subscriptions WHERE id = {}", id);
+        assert_eq!(single_value(&db, &sql, &user_context).await, json!(ends_at), "{}", sql);
+    }
+
+    // Stored in the canonical output form, and comparable as a span
+    assert_eq!(single_value(&db, "SELECT term FROM subscriptions WHERE id = 4", &user_context).await, json!("2 mons 1 day"));
+    assert_eq!(single_value(&db, "SELECT id FROM subscriptions WHERE term = '12 months'", &user_context).await, json!(3));
+
+    let sql = "SELECT started_at - INTERVAL '1 day 02:30:00' FROM subscriptions WHERE id = 3";
+    assert_eq!(single_value(&db, sql, &user_context).await, json!("2024-02-28 06:30:00"));
+    let sql = "SELECT INTERVAL '1 year' + INTERVAL '2 mons 3 days' - INTERVAL '1 hour' FROM subscriptions WHERE id = 1";
+    assert_eq!(single_value(&db, sql, &user_context).await, json!("1 year 2 mons 3 days -01:00:00"));
+    let sql = "SELECT age('2024-03-01 00:00:00', started_at) FROM subscriptions WHERE id = 2";
+    assert_eq!(single_value(&db, sql, &user_context).await, json!("1 year 1 mon 15:00:00"));
+
+    let rejected = db.execute_query("INSERT INTO subscriptions (id, started_at, term) VALUES (6, '2024-01-01', 'a while');", &user_context).await;
+    assert!(rejected.is_err());
+}
+
+#[tokio::test]
+async fn test_date_trunc_and_extract() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context("fields");
+
+    db.execute_query("CREATE TABLE readings (id INTEGER PRIMARY KEY, taken_at TIMESTAMP);", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO readings (id, taken_at) VALUES (1, '2024-08-17 14:35:52.25');", &user_context).await.unwrap();
+
+    for (unit, truncated) in [
+        ("second", "2024-08-17 14:35:52"),
+        ("minute", "2024-08-17 14:35:00"),
+        ("hour", "2024-08-17 14:00:00"),
+        ("day", "2024-08-17 00:00:00"),
+        ("week", "2024-08-12 00:00:00"),
+        ("month", "2024-08-01 00:00:00"),
+        ("quarter", "2024-07-01 00:00:00"),
+        ("year", "2024-01-01 00:00:00"),
+        ("century", "2001-01-01 00:00:00"),
+    ] {
+        let sql = format!("SELECT date_trunc('{}', taken_at) FROM readings", unit);
+        assert_eq!(single_value(&db, &sql, &user_context).await, json!(truncated), "{}", unit);
+    }
+
+    for (field, value) in [("YEAR", 2024.0), ("month", 8.0), ("day", 17.0), ("hour", 14.0), ("second", 52.25), ("dow", 6.0), ("doy", 230.0)] {
+        let sql = format!("SELECT EXTRACT({} FROM taken_at) FROM readings", field);
+        assert_eq!(single_value(&db, &sql, &user_context).await, json!(value), "{}", field);
+    }
+    let sql = "SELECT date_part('hour', INTERVAL '1 day 05:30:00') FROM readings";
+    assert_eq!(single_value(&db, sql, &user_context).await, json!(5.0));
+    let sql = "SELECT date_trunc('hour', INTERVAL '2 days 05:30:00') AS rounded FROM readings";
+    assert_eq!(single_value(&db, sql, &user_context).await, json!("2 days 05:00:00"));
+
+    for sql in [
+        "SELECT date_trunc('fortnight', taken_at) FROM readings",
+        "SELECT date_trunc('dow', taken_at) FROM readings",
+        "SELECT date_trunc('week', INTERVAL '3 months') FROM readings",
+    ] {
+        assert!(db.execute_query(sql, &user_context).await.is_err(), "{}", sql);
+    }
+}
+
+#[tokio::test]
+async fn test_timestamptz_arithmetic_spans_dst() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let new_york = user_context("new_york");
+    db.set_session_timezone("new_york", "America/New_York").unwrap();
+
+    db.execute_query("CREATE TABLE meetings (id INTEGER PRIMARY KEY, starts_at TIMESTAMPTZ);", &new_york).await.unwrap();
+    // Clocks go forward at 02:00 on 2024-03-10
+    db.execute_query("INSERT INTO meetings (id, starts_at) VALUES (1, '2024-03-09 12:00:00');", &new_york).await.unwrap();
+
+    // A day keeps the wall-clock time; 24 hours do not
+    let sql = "SELECT starts_at + INTERVAL '1 day' FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!("2024-03-10 12:00:00-04"));
+    let sql = "SELECT starts_at + INTERVAL '24 hours' FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!("2024-03-10 13:00:00-04"));
+
+    // The day actually added is 23 hours long
+    let sql = "SELECT starts_at + INTERVAL '1 day' - starts_at FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!("23:00:00"));
+    let sql = "SELECT starts_at - INTERVAL '1 mon' FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!("2024-02-09 12:00:00-05"));
+
+    // Truncation and fields use the session's wall clock
+    let sql = "SELECT date_trunc('day', starts_at + INTERVAL '12 hours') FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!("2024-03-10 00:00:00-05"));
+    let sql = "SELECT EXTRACT(hour FROM starts_at) FROM meetings";
+    assert_eq!(single_value(&db, sql, &new_york).await, json!(12.0));
+}
@@ -0,0 +1,303 @@
+//! Transaction Isolation Tests
+//!
+//! Hermitage-style anomaly checks run against every isolation level: each test
+//! interleaves two transactions step by step and asserts whether the anomaly
+//! is observable. The expected outcomes match the guarantees table in the
+//! `mvcc` module docs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use aurora_db::mvcc::{
+    IsolationLevel, Transaction, TransactionManager, TupleVersionChain, VersionedTuple,
+    VisibilityChecker, VisibilityError,
+};
+use aurora_db::types::DataValue;
+
+const ALL_LEVELS: [IsolationLevel; 4] = [
+    IsolationLevel::ReadUncommitted,
+    IsolationLevel::ReadCommitted,
+    IsolationLevel::RepeatableRead,
+    IsolationLevel::Serializable,
+];
+
+/// In-memory table of `id -> value` rows following the same read/write protocol
+/// as `TableStorage`
+struct TestTable {
+    tm: Arc<TransactionManager>,
+    rows: BTreeMap<i64, TupleVersionChain>,
+}
+
+impl TestTable {
+    /// Create a table whose rows were committed before any test transaction
+    async fn with_rows(rows: &[(i64, i64)]) -> Self {
+        let mut table = Self { tm: Arc::new(TransactionManager::new()), rows: BTreeMap::new() };
+        let setup = table.begin(IsolationLevel::ReadCommitted).await;
+        for &(id, value) in rows {
+            table.insert(&setup, id, value).unwrap();
+        }
+        table.tm.commit_transaction(setup.id).await.unwrap();
+        table
+    }
+
+    async fn begin(&self, level: IsolationLevel) -> Arc<Transaction> {
+        self.tm.begin_transaction(level).await.unwrap()
+    }
+
+    fn key(id: i64) -> String {
+        format!("test:{}", id)
+    }
+
+    fn value_of(version: &VersionedTuple) -> i64 {
+        match version.data.get("value") {
+            Some(DataValue::Integer(v)) => *v,
+            other => panic!("unexpected value {:?}", other),
+        }
+    }
+
+    fn read(&self, txn: &Transaction, id: i64) -> Option<i64> {
+        let version = self.rows.get(&id)?.visible_version(txn, &self.tm)?;
+        self.tm.record_read(txn.id, &Self::key(id));
+        Some(Self::value_of(version))
+    }
+
+    /// Range scan over `[from, to]`
+    fn scan(&self, txn: &Transaction, from: i64, to: i64) -> Vec<(i64, i64)> {
+        self.tm.record_read(txn.id, "test:*");
+        self.rows.range(from..=to)
+            .filter_map(|(&id, chain)| chain.visible_version(txn, &self.tm).map(|v| (id, Self::value_of(v))))
+            .inspect(|(id, _)| self.tm.record_read(txn.id, &Self::key(*id)))
+            .collect()
+    }
+
+    fn insert(&mut self, txn: &Transaction, id: i64, value: i64) -> Result<(), VisibilityError> {
+        let tuple = VersionedTuple::new(DataValue::Integer(id), Self::data(value), txn.id);
+        match self.rows.get_mut(&id) {
+            Some(chain) => {
+                chain.discard_aborted(&self.tm);
+                VisibilityChecker::check_write_conflict(chain, txn, &self.tm)?;
+                chain.add_version(tuple);
+            }
+            None => {
+                self.rows.insert(id, TupleVersionChain::new(tuple));
+            }
+        }
+        self.tm.record_write(txn.id, &Self::key(id));
+        self.tm.record_write(txn.id, "test:*");
+        Ok(())
+    }
+
+    fn update(&mut self, txn: &Transaction, id: i64, value: i64) -> Result<(), VisibilityError> {
+        let chain = self.rows.get_mut(&id).ok_or(VisibilityError::TupleNotVisible)?;
+        chain.discard_aborted(&self.tm);
+        let current = chain.visible_version(txn, &self.tm).ok_or(VisibilityError::TupleNotVisible)?.clone();
+        VisibilityChecker::check_write_conflict(chain, txn, &self.tm)?;
+        chain.add_version(current.new_version(Self::data(value), txn.id));
+        self.tm.record_write(txn.id, &Self::key(id));
+        Ok(())
+    }
+
+    fn delete(&mut self, txn: &Transaction, id: i64) -> Result<(), VisibilityError> {
+        let chain = self.rows.get_mut(&id).ok_or(VisibilityError::TupleNotVisible)?;
+        chain.discard_aborted(&self.tm);
+        chain.visible_version(txn, &self.tm).ok_or(VisibilityError::TupleNotVisible)?;
+        VisibilityChecker::check_write_conflict(chain, txn, &self.tm)?;
+        chain.delete_current(txn.id);
+        self.tm.record_write(txn.id, &Self::key(id));
+        Ok(())
+    }
+
+    fn data(value: i64) -> HashMap<String, DataValue> {
+        HashMap::from([("value".to_string(), DataValue::Integer(value))])
+    }
+}
+
+#[tokio::test]
+async fn test_own_writes_are_visible() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10)]).await;
+        let t1 = table.begin(level).await;
+
+        table.update(&t1, 1, 11).unwrap();
+        table.insert(&t1, 2, 20).unwrap();
+        assert_eq!(table.read(&t1, 1), Some(11), "{:?} sees its own update", level);
+        assert_eq!(table.read(&t1, 2), Some(20), "{:?} sees its own insert", level);
+
+        table.delete(&t1, 2).unwrap();
+        assert_eq!(table.read(&t1, 2), None, "{:?} does not see its own delete", level);
+    }
+}
+
+#[tokio::test]
+async fn test_aborted_writes_are_never_visible() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10)]).await;
+        let t1 = table.begin(IsolationLevel::ReadCommitted).await;
+        table.update(&t1, 1, 11).unwrap();
+        table.tm.abort_transaction(t1.id).await.unwrap();
+
+        let t2 = table.begin(level).await;
+        assert_eq!(table.read(&t2, 1), Some(10), "{:?} ignores aborted update", level);
+        table.update(&t2, 1, 12).unwrap();
+        table.tm.commit_transaction(t2.id).await.unwrap();
+
+        let t3 = table.begin(level).await;
+        assert_eq!(table.read(&t3, 1), Some(12));
+    }
+}
+
+/// G1a/G1b: reading another transaction's uncommitted write
+#[tokio::test]
+async fn test_dirty_read() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10)]).await;
+        let t1 = table.begin(IsolationLevel::ReadCommitted).await;
+        let t2 = table.begin(level).await;
+
+        table.update(&t1, 1, 11).unwrap();
+        let observed = table.read(&t2, 1);
+
+        let expected = if level == IsolationLevel::ReadUncommitted { 11 } else { 10 };
+        assert_eq!(observed, Some(expected), "dirty read under {:?}", level);
+    }
+}
+
+/// P2: a row read twice returns different values
+#[tokio::test]
+async fn test_non_repeatable_read() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10)]).await;
+        let t1 = table.begin(level).await;
+        let t2 = table.begin(IsolationLevel::ReadCommitted).await;
+
+        assert_eq!(table.read(&t1, 1), Some(10));
+        table.update(&t2, 1, 11).unwrap();
+        table.tm.commit_transaction(t2.id).await.unwrap();
+
+        let anomaly = table.read(&t1, 1) != Some(10);
+        let allowed = matches!(level, IsolationLevel::ReadUncommitted | IsolationLevel::ReadCommitted);
+        assert_eq!(anomaly, allowed, "non-repeatable read under {:?}", level);
+    }
+}
+
+/// P3: a range scan repeated within one transaction returns a different set of rows
+#[tokio::test]
+async fn test_phantom_read() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10), (2, 20), (5, 50)]).await;
+        let t1 = table.begin(level).await;
+        let t2 = table.begin(IsolationLevel::ReadCommitted).await;
+
+        let before = table.scan(&t1, 1, 4);
+        assert_eq!(before, vec![(1, 10), (2, 20)]);
+
+        // Insert into the range, move a row within it and delete another
+        table.insert(&t2, 3, 30).unwrap();
+        table.update(&t2, 2, 21).unwrap();
+        table.delete(&t2, 1).unwrap();
+        table.tm.commit_transaction(t2.id).await.unwrap();
+
+        let after = table.scan(&t1, 1, 4);
+        let allowed = matches!(level, IsolationLevel::ReadUncommitted | IsolationLevel::ReadCommitted);
+        if allowed {
+            assert_eq!(after, vec![(2, 21), (3, 30)], "phantom under {:?}", level);
+        } else {
+            assert_eq!(after, before, "snapshot range scan under {:?}", level);
+        }
+    }
+}
+
+/// P4: two read-modify-write cycles on the same row, one increment is lost
+#[tokio::test]
+async fn test_lost_update() {
+    for level in ALL_LEVELS {
+        let mut table = TestTable::with_rows(&[(1, 10)]).await;
+        let t1 = table.begin(level).await;
+        let t2 = table.begin(level).await;
+
+        let v1 = table.read(&t1, 1).unwrap();
+        let v2 = table.read(&t2, 1).unwrap();
+
+        table.update(&t1, 1, v1 + 1).unwrap();
+        // Writers never wait: an in-flight update on the row is refused at every level
+        assert!(matches!(table.update(&t2, 1, v2 + 1), Err(VisibilityError::TupleLocked)));
+        table.tm.commit_transaction(t1.id).await.unwrap();
+
+        let second_write = table.update(&t2, 1, v2 + 1);
+        match level {
+            IsolationLevel::ReadUncommitted | IsolationLevel::ReadCommitted => {
+                second_write.unwrap();
+                table.tm.commit_transaction(t2.id).await.unwrap();
+                let t3 = table.begin(level).await;
+                assert_eq!(table.read(&t3, 1), Some(11), "lost update under {:?}", level);
+            }
+            IsolationLevel::RepeatableRead | IsolationLevel::Serializable => {
+                assert!(
+                    matches!(second_write, Err(VisibilityError::SerializationFailure)),
+                    "first updater wins under {:?}", level
+                );
+            }
+        }
+    }
+}
+
+/// G2-item: two transactions read an overlapping set and write disjoint rows
+#[tokio::test]
+async fn test_write_skew() {
+    for level in ALL_LEVELS {
+        // Invariant the application maintains: at least one row has value 1
+        let mut table = TestTable::with_rows(&[(1, 1), (2, 1)]).await;
+        let t1 = table.begin(level).await;
+        let t2 = table.begin(level).await;
+
+        assert_eq!(table.scan(&t1, 1, 2).iter().map(|(_, v)| v).sum::<i64>(), 2);
+        assert_eq!(table.scan(&t2, 1, 2).iter().map(|(_, v)| v).sum::<i64>(), 2);
+        table.update(&t1, 1, 0).unwrap();
+        table.update(&t2, 2, 0).unwrap();
+
+        let c1 = table.tm.commit_transaction(t1.id).await;
+        let c2 = table.tm.commit_transaction(t2.id).await;
+
+        if level == IsolationLevel::Serializable {
+            assert!(c1.is_ok() != c2.is_ok(), "exactly one transaction commits under Serializable");
+        } else {
+            assert!(c1.is_ok() && c2.is_ok(), "write skew is permitted under {:?}", level);
+        }
+    }
+}
+
+/// G2: predicate-based write skew, each transaction inserts after checking the range is empty
+#[tokio::test]
+async fn test_predicate_write_skew() {
+    for level in [IsolationLevel::RepeatableRead, IsolationLevel::Serializable] {
+        let mut table = TestTable::with_rows(&[]).await;
+        let t1 = table.begin(level).await;
+        let t2 = table.begin(level).await;
+
+        assert!(table.scan(&t1, 1, 10).is_empty());
+        assert!(table.scan(&t2, 1, 10).is_empty());
+        table.insert(&t1, 1, 1).unwrap();
+        table.insert(&t2, 2, 2).unwrap();
+
+        let c1 = table.tm.commit_transaction(t1.id).await;
+        let c2 = table.tm.commit_transaction(t2.id).await;
+
+        let committed = [c1.is_ok(), c2.is_ok()].iter().filter(|ok| **ok).count();
+        let expected = if level == IsolationLevel::Serializable { 1 } else { 2 };
+        assert_eq!(committed, expected, "predicate write skew under {:?}", level);
+    }
+}
+
+#[tokio::test]
+async fn test_serializable_allows_non_overlapping_transactions() {
+    let mut table = TestTable::with_rows(&[(1, 1), (2, 1)]).await;
+    let t1 = table.begin(IsolationLevel::Serializable).await;
+    let t2 = table.begin(IsolationLevel::Serializable).await;
+
+    table.read(&t1, 1);
+    table.update(&t1, 1, 5).unwrap();
+    table.read(&t2, 2);
+    table.update(&t2, 2, 5).unwrap();
+
+    table.tm.commit_transaction(t1.id).await.unwrap();
+    table.tm.commit_transaction(t2.id).await.unwrap();
+}
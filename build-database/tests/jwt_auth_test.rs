@@ -0,0 +1,131 @@
+//! JWT Authentication Tests
+//!
+//! Tokens signed by the gateway's keys are checked against a JWKS the test
+//! can rotate: a valid token maps to a database role, an expired one is
+//! rejected past the clock-skew tolerance, and a token signed with a key
+//! rotated in after the JWKS was cached is verified after a refresh.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use aurora_db::core::AuroraResult;
+use aurora_db::security::{JwksSource, JwtAuthConfig, JwtValidator};
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde_json::json;
+
+const Q3_SECRET: &[u8] = b"gateway-signing-key-2025-q3-0123456789";
+const Q3_JWK: &str = r#"{"kty":"oct","kid":"2025-q3","alg":"HS256","k":"Z2F0ZXdheS1zaWduaW5nLWtleS0yMDI1LXEzLTAxMjM0NTY3ODk"}"#;
+const Q4_SECRET: &[u8] = b"gateway-signing-key-2025-q4-9876543210";
+const Q4_JWK: &str = r#"{"kty":"oct","kid":"2025-q4","alg":"HS256","k":"Z2F0ZXdheS1zaWduaW5nLWtleS0yMDI1LXE0LTk4NzY1NDMyMTA"}"#;
+
+/// JWKS the test swaps out, counting how often it is fetched
+struct RotatingJwks {
+    keys: Mutex<String>,
+    fetches: AtomicUsize,
+}
+
+impl RotatingJwks {
+    fn new(jwks: &[&str]) -> Arc<Self> {
+        let source = Arc::new(Self { keys: Mutex::new(String::new()), fetches: AtomicUsize::new(0) });
+        source.rotate(jwks);
+        source
+    }
+
+    fn rotate(&self, jwks: &[&str]) {
+        *self.keys.lock().unwrap() = format!(r#"{{"keys":[{}]}}"#, jwks.join(","));
+    }
+}
+
+#[async_trait]
+impl JwksSource for RotatingJwks {
+    async fn fetch(&self) -> AuroraResult<JwkSet> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(serde_json::from_str(&self.keys.lock().unwrap()).unwrap())
+    }
+}
+
+fn config() -> JwtAuthConfig {
+    JwtAuthConfig {
+        issuers: vec!["https://gateway.example.com".to_string()],
+        audiences: vec!["aurora".to_string()],
+        algorithms: vec![Algorithm::HS256],
+        clock_skew_secs: 60,
+        // Refresh as soon as an unknown key shows up
+        jwks_min_refresh_secs: 0,
+        role_claim: "groups".to_string(),
+        role_mapping: HashMap::from([
+            ("analytics-team".to_string(), "analyst".to_string()),
+            ("platform-admins".to_string(), "admin".to_string()),
+        ]),
+        ..JwtAuthConfig::default()
+    }
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64
+}
+
+/// Token for `sub` signed with key `kid`, expiring `expires_in` seconds from now
+fn token(kid: &str, secret: &[u8], expires_in: i64, groups: &[&str]) -> String {
+    let header = Header { kid: Some(kid.to_string()), ..Header::new(Algorithm::HS256) };
+    let claims = json!({
+        "sub": "alice@example.com",
+        "iss": "https://gateway.example.com",
+        "aud": "aurora",
+        "iat": now(),
+        "exp": now() + expires_in,
+        "groups": groups,
+    });
+    encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+}
+
+#[tokio::test]
+async fn test_valid_token_maps_to_role() {
+    let source = RotatingJwks::new(&[Q3_JWK]);
+    let validator = JwtValidator::new(config(), source.clone());
+
+    let identity = validator.validate(&token("2025-q3", Q3_SECRET, 300, &["staff", "analytics-team"])).await.unwrap();
+    assert_eq!(identity.subject, "alice@example.com");
+    assert_eq!(identity.role, "analyst");
+
+    // The cached JWKS serves later tokens
+    validator.validate(&token("2025-q3", Q3_SECRET, 300, &["platform-admins"])).await.unwrap();
+    assert_eq!(source.fetches.load(Ordering::SeqCst), 1);
+
+    // A group with no mapping and no default role gets nothing
+    let unmapped = validator.validate(&token("2025-q3", Q3_SECRET, 300, &["staff"])).await.unwrap_err();
+    assert!(unmapped.to_string().contains("no database role"), "{}", unmapped);
+}
+
+#[tokio::test]
+async fn test_expired_token_is_rejected() {
+    let validator = JwtValidator::new(config(), RotatingJwks::new(&[Q3_JWK]));
+
+    let expired = validator.validate(&token("2025-q3", Q3_SECRET, -600, &["analytics-team"])).await.unwrap_err();
+    assert!(expired.to_string().contains("JWT rejected: token has expired"), "{}", expired);
+
+    // Within the clock-skew tolerance it still passes
+    validator.validate(&token("2025-q3", Q3_SECRET, -30, &["analytics-team"])).await.unwrap();
+
+    // A token signed with another secret fails the signature check
+    let forged = validator.validate(&token("2025-q3", Q4_SECRET, 300, &["analytics-team"])).await.unwrap_err();
+    assert!(forged.to_string().contains("signature does not verify"), "{}", forged);
+}
+
+#[tokio::test]
+async fn test_token_signed_with_rotated_key_is_verified() {
+    let source = RotatingJwks::new(&[Q3_JWK]);
+    let validator = JwtValidator::new(config(), source.clone());
+    validator.validate(&token("2025-q3", Q3_SECRET, 300, &["analytics-team"])).await.unwrap();
+
+    // The gateway rotates to a new key and retires the old one
+    source.rotate(&[Q4_JWK]);
+    let identity = validator.validate(&token("2025-q4", Q4_SECRET, 300, &["platform-admins"])).await.unwrap();
+    assert_eq!(identity.role, "admin");
+    assert_eq!(source.fetches.load(Ordering::SeqCst), 2);
+
+    let retired = validator.validate(&token("2025-q3", Q3_SECRET, 300, &["analytics-team"])).await.unwrap_err();
+    assert!(retired.to_string().contains("signing key '2025-q3' is not in the issuer's JWKS"), "{}", retired);
+}
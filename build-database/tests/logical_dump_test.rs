@@ -0,0 +1,111 @@
+//! Logical Dump Tests
+//!
+//! Dumps a small database, restores it into a fresh instance and checks that
+//! schema and data match, comparing data by result fingerprint.
+
+use aurora_db::backup::{DumpContents, DumpDataFormat, DumpOptions};
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+const QUERIES: [&str; 2] = [
+    "SELECT id, name, active FROM users",
+    "SELECT id, user_id, total FROM orders",
+];
+
+async fn populate(db: &AuroraDB, user_context: &UserContext) {
+    for sql in [
+        "CREATE TABLE users (id INTEGER NOT NULL, name TEXT, active BOOLEAN, PRIMARY KEY (id));",
+        "CREATE TABLE orders (id INTEGER NOT NULL, user_id INTEGER, total DOUBLE, PRIMARY KEY (id), FOREIGN KEY (user_id) REFERENCES users (id));",
+        "INSERT INTO users (id, name, active) VALUES (1, 'alice', TRUE), (2, 'O''Brien', FALSE), (3, NULL, TRUE);",
+        "INSERT INTO orders (id, user_id, total) VALUES (10, 1, 19.5), (11, 1, 0.1), (12, 2, 7.0);",
+    ] {
+        db.execute_query(sql, user_context).await.unwrap();
+    }
+}
+
+async fn assert_same_contents(source: &AuroraDB, restored: &AuroraDB, user_context: &UserContext) {
+    for table in ["users", "orders"] {
+        let expected = source.describe_table(table).await.unwrap();
+        let actual = restored.describe_table(table).await.unwrap();
+        assert_eq!(format!("{:?}", expected), format!("{:?}", actual), "schema of {}", table);
+    }
+
+    for sql in QUERIES {
+        let expected = source.execute_query(sql, user_context).await.unwrap().fingerprint();
+        let actual = restored.execute_query(sql, user_context).await.unwrap().fingerprint();
+        assert_eq!(expected.unordered, actual.unordered, "{}", sql);
+        assert_eq!(expected.row_count, actual.row_count, "{}", sql);
+    }
+}
+
+#[tokio::test]
+async fn test_dump_and_restore_round_trip() {
+    let user_context = user_context();
+    let source_dir = tempdir().unwrap();
+    let source = open(&source_dir).await;
+    populate(&source, &user_context).await;
+
+    for data_format in [DumpDataFormat::Copy, DumpDataFormat::Insert] {
+        let options = DumpOptions { data_format, ..DumpOptions::default() };
+        let mut script = Vec::new();
+        let summary = source.dump(&options, &mut script).await.unwrap();
+        assert_eq!(summary.tables, vec!["users".to_string(), "orders".to_string()]);
+        assert_eq!(summary.rows, 6);
+
+        let target_dir = tempdir().unwrap();
+        let restored = open(&target_dir).await;
+        let restore = restored.restore(script.as_slice(), &user_context).await.unwrap();
+        assert_eq!(restore.rows, 6);
+
+        assert_same_contents(&source, &restored, &user_context).await;
+    }
+}
+
+#[tokio::test]
+async fn test_schema_only_then_data_only() {
+    let user_context = user_context();
+    let source_dir = tempdir().unwrap();
+    let source = open(&source_dir).await;
+    populate(&source, &user_context).await;
+
+    let mut schema = Vec::new();
+    let options = DumpOptions { contents: DumpContents::SchemaOnly, ..DumpOptions::default() };
+    source.dump(&options, &mut schema).await.unwrap();
+    let schema_text = String::from_utf8(schema.clone()).unwrap();
+    assert!(schema_text.contains("CREATE TABLE users"));
+    assert!(!schema_text.contains("COPY") && !schema_text.contains("INSERT"));
+
+    let mut data = Vec::new();
+    let options = DumpOptions { contents: DumpContents::DataOnly, ..DumpOptions::default() };
+    source.dump(&options, &mut data).await.unwrap();
+    assert!(!String::from_utf8(data.clone()).unwrap().contains("CREATE TABLE"));
+
+    let target_dir = tempdir().unwrap();
+    let restored = open(&target_dir).await;
+    restored.restore(schema.as_slice(), &user_context).await.unwrap();
+    for sql in QUERIES {
+        assert!(restored.execute_query(sql, &user_context).await.unwrap().rows.is_empty());
+    }
+
+    restored.restore(data.as_slice(), &user_context).await.unwrap();
+    assert_same_contents(&source, &restored, &user_context).await;
+}
@@ -0,0 +1,177 @@
+//! LSM Compaction Strategy Tests
+//!
+//! Each strategy keeps the sorted-run layout it promises: leveled holds one
+//! run per level below L0, tiered never lets a tier reach its merge width,
+//! and hybrid does the one above the other. The amplification metrics show
+//! the tradeoff between them, and switching a table's strategy reorganizes
+//! its runs without losing a write.
+
+use std::collections::BTreeMap;
+use aurora_db::storage::{
+    CompactionStrategy, LSMTree, LevelPolicy, StorageStrategy, TableCompaction, TableStorageConfig, COMPACTION_LEVELS,
+};
+
+const MEMTABLE_BYTES: u64 = 4 * 1024;
+
+fn leveled() -> CompactionStrategy {
+    CompactionStrategy::Leveled { l0_trigger: 4, base_level_bytes: 16 * 1024, fanout: 4 }
+}
+
+fn tiered() -> CompactionStrategy {
+    CompactionStrategy::Tiered { merge_width: 4 }
+}
+
+fn hybrid() -> CompactionStrategy {
+    CompactionStrategy::Hybrid { tiered_levels: 2, merge_width: 4, base_level_bytes: 16 * 1024, fanout: 4 }
+}
+
+fn key(n: u64) -> Vec<u8> {
+    format!("key-{:06}", n).into_bytes()
+}
+
+/// Writes `count` values over 2000 keys, deleting every 13th write's key;
+/// returns what each key should read as
+fn churn(table: &mut TableCompaction, start: u64, count: u64) -> BTreeMap<Vec<u8>, Option<Vec<u8>>> {
+    let mut expected = BTreeMap::new();
+    for n in start..start + count {
+        let key = key((n * 7919) % 2000);
+        if n % 13 == 0 {
+            table.delete(&key);
+            expected.insert(key, None);
+        } else {
+            let value = format!("value {} {}", n, "x".repeat(60)).into_bytes();
+            table.put(&key, &value);
+            expected.insert(key, Some(value));
+        }
+    }
+    expected
+}
+
+fn assert_reads(table: &TableCompaction, expected: &BTreeMap<Vec<u8>, Option<Vec<u8>>>) {
+    for (key, value) in expected {
+        assert_eq!(&table.get(key), value, "{}", String::from_utf8_lossy(key));
+    }
+}
+
+/// Checks every level of `table` against its strategy's policy
+fn assert_layout(table: &TableCompaction) {
+    let strategy = table.strategy();
+    for (level, runs) in table.layout().iter().enumerate() {
+        match strategy.level_policy(level) {
+            LevelPolicy::Tiered { merge_width } => {
+                assert!(runs.len() < merge_width, "{:?} L{} holds {} runs", strategy, level, runs.len());
+            }
+            LevelPolicy::Leveled { max_bytes } => {
+                assert!(runs.len() <= 1, "{:?} L{} holds {} runs", strategy, level, runs.len());
+                if level + 1 < COMPACTION_LEVELS {
+                    assert!(runs.iter().sum::<u64>() <= max_bytes, "{:?} L{} is over capacity", strategy, level);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_each_strategy_produces_its_layout() {
+    for strategy in [leveled(), tiered(), hybrid()] {
+        let mut table = TableCompaction::new(strategy, MEMTABLE_BYTES);
+        let expected = churn(&mut table, 0, 20_000);
+        table.flush();
+        assert_layout(&table);
+        assert_reads(&table, &expected);
+
+        let layout = table.layout();
+        let levels_used = layout.iter().filter(|runs| !runs.is_empty()).count();
+        assert!(levels_used > 1, "{:?} never compacted: {:?}", strategy, layout);
+    }
+
+    // Leveled fills lower levels a run at a time; tiered stacks runs per tier
+    let mut table = TableCompaction::new(tiered(), MEMTABLE_BYTES);
+    churn(&mut table, 0, 20_000);
+    assert!(table.layout().iter().any(|runs| runs.len() > 1));
+}
+
+#[test]
+fn test_amplification_metrics_show_the_tradeoff() {
+    let metrics = |strategy| {
+        let mut table = TableCompaction::new(strategy, MEMTABLE_BYTES);
+        churn(&mut table, 0, 20_000);
+        table.flush();
+        table.amplification()
+    };
+    let leveled = metrics(leveled());
+    let tiered = metrics(tiered());
+    let hybrid = metrics(hybrid());
+
+    assert!(tiered.write < leveled.write, "tiered {:?} leveled {:?}", tiered, leveled);
+    assert!(leveled.read < tiered.read, "leveled {:?} tiered {:?}", leveled, tiered);
+    assert!(leveled.space <= tiered.space, "leveled {:?} tiered {:?}", leveled, tiered);
+    assert!(hybrid.write < leveled.write, "hybrid {:?} leveled {:?}", hybrid, leveled);
+    for metrics in [leveled, tiered, hybrid] {
+        assert!(metrics.write >= 1.0 && metrics.space >= 1.0, "{:?}", metrics);
+        assert_eq!(metrics.live_bytes, leveled.live_bytes);
+    }
+}
+
+#[test]
+fn test_switching_strategies_converges() {
+    let mut table = TableCompaction::new(tiered(), MEMTABLE_BYTES);
+    let mut expected = churn(&mut table, 0, 10_000);
+
+    for (round, strategy) in [leveled(), hybrid(), tiered(), leveled()].into_iter().enumerate() {
+        let runs_before = table.layout().iter().map(Vec::len).sum::<usize>();
+        let report = table.set_strategy(strategy);
+        assert_eq!(table.strategy(), strategy);
+        assert_eq!(report.runs_before, runs_before);
+        // Everything was merged into one run at the bottom
+        assert_eq!(report.runs_after, 1);
+        assert_eq!(table.layout()[COMPACTION_LEVELS - 1].len(), 1);
+        assert_eq!(table.amplification().read, 1.0);
+        assert_reads(&table, &expected);
+
+        // The new strategy takes over from there
+        expected.extend(churn(&mut table, 10_000 * (round as u64 + 1), 10_000));
+        table.flush();
+        assert_layout(&table);
+        assert_reads(&table, &expected);
+    }
+
+    // With nothing older left, tombstones are gone after the last switch
+    table.set_strategy(tiered());
+    let metrics = table.amplification();
+    assert_eq!(metrics.stored_bytes, metrics.live_bytes);
+}
+
+#[tokio::test]
+async fn test_lsm_tree_strategy_per_table() {
+    let lsm = LSMTree::new();
+    for table_name in ["events", "profiles"] {
+        let config = TableStorageConfig {
+            table_name: table_name.to_string(),
+            strategy: StorageStrategy::LSMTree,
+            compression_algorithm: "snappy".to_string(),
+            target_file_size_mb: 128,
+            write_buffer_size_mb: 64,
+            max_levels: 7,
+        };
+        lsm.create_table(table_name, &config).await.unwrap();
+    }
+    assert_eq!(lsm.compaction_strategy("events").unwrap(), CompactionStrategy::leveled());
+
+    lsm.write("events", b"k1", b"v1").await.unwrap();
+    lsm.write("events", b"k2", b"v2").await.unwrap();
+    lsm.delete("events", b"k2").await.unwrap();
+    let report = lsm.set_compaction_strategy("events", CompactionStrategy::parse("universal").unwrap()).unwrap();
+    assert_eq!(report.runs_before, 0);
+    assert_eq!(lsm.compaction_strategy("events").unwrap(), CompactionStrategy::tiered());
+    assert_eq!(lsm.compaction_strategy("profiles").unwrap(), CompactionStrategy::leveled());
+
+    assert_eq!(lsm.read("events", b"k1").await.unwrap(), Some(b"v1".to_vec()));
+    assert_eq!(lsm.read("events", b"k2").await.unwrap(), None);
+    assert_eq!(lsm.read("profiles", b"k1").await.unwrap(), None);
+    assert_eq!(lsm.compaction_metrics("events").unwrap().bytes_ingested, 10);
+
+    assert!(lsm.set_compaction_strategy("missing", CompactionStrategy::hybrid()).is_err());
+    assert!(lsm.compaction_metrics("missing").is_err());
+    assert_eq!(CompactionStrategy::parse("sideways"), None);
+}
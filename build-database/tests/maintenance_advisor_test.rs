@@ -0,0 +1,122 @@
+//! Maintenance Advisor Tests
+//!
+//! Feeds the advisor six hours of synthetic history: a high-churn table
+//! whose bloat climbs past the vacuum trigger while each vacuum takes
+//! minutes, next to a quiet table that barely bloats. The advisor should
+//! call for earlier, faster vacuums without touching the settings, and
+//! apply them only when told to.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use aurora_db::monitoring::{
+    AdvisorConfig, MaintenanceAdvisor, MaintenanceKind, MaintenanceRun, MaintenanceSetting,
+    MaintenanceSettings, TableVerdict, TableWorkloadSample,
+};
+
+fn at(minutes: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_700_000_000 + minutes * 60)
+}
+
+fn sample(table: &str, minutes: u64, live_tuples: u64, writes: u64, bloat_ratio: f64) -> TableWorkloadSample {
+    TableWorkloadSample {
+        table_name: table.to_string(),
+        at: at(minutes),
+        live_tuples,
+        dead_tuples: (live_tuples as f64 * bloat_ratio / (1.0 - bloat_ratio)) as u64,
+        writes,
+        bloat_ratio,
+    }
+}
+
+fn vacuum(table: &str, minutes: u64, duration: Duration) -> MaintenanceRun {
+    MaintenanceRun {
+        kind: MaintenanceKind::Vacuum,
+        table_name: table.to_string(),
+        started_at: at(minutes),
+        duration,
+    }
+}
+
+/// Six hours, sampled every 30 minutes: `orders` rewrites its 100k rows
+/// twice an hour and climbs from 10% to 45% bloat despite a three-minute
+/// vacuum every half hour; `countries` sees a handful of writes
+fn record_history(advisor: &MaintenanceAdvisor) {
+    for step in 0..=12 {
+        let minutes = step * 30;
+        advisor.record_sample(sample("orders", minutes, 100_000, 100_000, 0.10 + 0.35 * step as f64 / 12.0));
+        advisor.record_sample(sample("countries", minutes, 250, 1, 0.01));
+        if step > 0 {
+            advisor.record_run(vacuum("orders", minutes - 5, Duration::from_secs(180)));
+        }
+    }
+    advisor.record_run(vacuum("countries", 120, Duration::from_millis(40)));
+}
+
+#[test]
+fn test_high_churn_table_gets_more_aggressive_settings() {
+    let advisor = MaintenanceAdvisor::default();
+    record_history(&advisor);
+    let current = MaintenanceSettings::default();
+
+    let report = advisor.advise(&current);
+    let orders = report.tables.iter().find(|table| table.table_name == "orders").unwrap();
+    assert_eq!(orders.verdict, TableVerdict::FallingBehind);
+    assert!((orders.peak_bloat - 0.45).abs() < 1e-9);
+    assert!(orders.bloat_trend_per_hour > 0.05, "trend {}", orders.bloat_trend_per_hour);
+    assert!(orders.churn_per_hour > 1.0, "churn {}", orders.churn_per_hour);
+    assert_eq!(orders.vacuum_runs, 12);
+    let countries = report.tables.iter().find(|table| table.table_name == "countries").unwrap();
+    assert_eq!(countries.verdict, TableVerdict::OverMaintained);
+
+    // Vacuum earlier...
+    let scale_factor = report.suggestion(MaintenanceSetting::VacuumScaleFactor).unwrap();
+    assert!(scale_factor.suggested < current.vacuum_scale_factor, "{}", scale_factor);
+    assert!(scale_factor.suggested >= AdvisorConfig::default().min_scale_factor);
+    assert_eq!(scale_factor.tables, ["orders"]);
+    assert!(scale_factor.expected_impact.contains("peak bloat from 45%"), "{}", scale_factor.expected_impact);
+    let threshold = report.suggestion(MaintenanceSetting::VacuumThreshold).unwrap();
+    assert!(threshold.suggested < current.vacuum_threshold as f64);
+
+    // ...and let each vacuum finish sooner
+    let cost_limit = report.suggestion(MaintenanceSetting::VacuumCostLimit).unwrap();
+    assert_eq!(cost_limit.suggested, 600.0);
+    assert!(cost_limit.to_string().starts_with("vacuum_cost_limit = 600  # was 200"), "{}", cost_limit);
+
+    // Vacuums keep a tenth of a worker busy: no more workers needed
+    assert!(report.worker_utilization < 0.2);
+    assert!(report.suggestion(MaintenanceSetting::MaxWorkers).is_none());
+
+    // Advisory only by default
+    let mut settings = current.clone();
+    let report = advisor.tune(&mut settings);
+    assert!(!report.applied);
+    assert_eq!(settings, current);
+}
+
+#[test]
+fn test_suggestions_applied_only_with_auto_apply() {
+    let advisor = MaintenanceAdvisor::new(AdvisorConfig { auto_apply: true, ..AdvisorConfig::default() });
+    record_history(&advisor);
+
+    let mut settings = MaintenanceSettings::default();
+    let report = advisor.tune(&mut settings);
+    assert!(report.applied);
+    let suggested = |setting| report.suggestion(setting).unwrap().suggested;
+    assert_eq!(settings.vacuum_scale_factor, suggested(MaintenanceSetting::VacuumScaleFactor));
+    assert_eq!(settings.vacuum_threshold as f64, suggested(MaintenanceSetting::VacuumThreshold));
+    assert_eq!(settings.vacuum_cost_limit, 600);
+    assert_eq!(settings.max_workers, MaintenanceSettings::default().max_workers);
+}
+
+#[test]
+fn test_quiet_tables_get_relaxed_settings() {
+    let advisor = MaintenanceAdvisor::default();
+    for step in 0..=12 {
+        advisor.record_sample(sample("countries", step * 30, 250, 1, 0.01));
+    }
+
+    let current = MaintenanceSettings::default();
+    let report = advisor.advise(&current);
+    let scale_factor = report.suggestion(MaintenanceSetting::VacuumScaleFactor).unwrap();
+    assert_eq!(scale_factor.suggested, 0.4);
+    assert!(report.suggestion(MaintenanceSetting::VacuumCostLimit).is_none());
+}
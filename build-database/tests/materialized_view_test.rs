@@ -0,0 +1,184 @@
+//! Materialized View Tests
+//!
+//! Checks full refresh, that concurrent refresh keeps readers unblocked, and
+//! that incrementally maintained views match their defining query after DML,
+//! comparing results by fingerprint.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext, ViewMaintenance};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+async fn populate(db: &AuroraDB, user_context: &UserContext) {
+    for sql in [
+        "CREATE TABLE users (id INTEGER NOT NULL, name TEXT, PRIMARY KEY (id));",
+        "CREATE TABLE orders (id INTEGER NOT NULL, user_id INTEGER, total INTEGER, PRIMARY KEY (id));",
+        "INSERT INTO users (id, name) VALUES (1, 'alice'), (2, 'bob'), (3, 'carol');",
+        "INSERT INTO orders (id, user_id, total) VALUES (10, 1, 20), (11, 1, 5), (12, 2, 7);",
+    ] {
+        db.execute_query(sql, user_context).await.unwrap();
+    }
+}
+
+/// Assert that reading `view` returns what running `definition` returns now
+async fn assert_matches_definition(db: &AuroraDB, user_context: &UserContext, view_sql: &str, definition: &str) {
+    let stored = db.execute_query(view_sql, user_context).await.unwrap().fingerprint();
+    let computed = db.execute_query(definition, user_context).await.unwrap().fingerprint();
+    assert_eq!(stored.row_count, computed.row_count, "{}", view_sql);
+    assert_eq!(stored.unordered, computed.unordered, "{}", view_sql);
+}
+
+#[tokio::test]
+async fn test_full_refresh() {
+    let user_context = user_context();
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    populate(&db, &user_context).await;
+
+    // MAX cannot be maintained from running totals, so this view waits for REFRESH
+    let definition = "SELECT user_id, MAX(total) AS largest FROM orders GROUP BY user_id";
+    let view_sql = "SELECT user_id, largest FROM largest_orders";
+    db.execute_query(&format!("CREATE MATERIALIZED VIEW largest_orders AS {}", definition), &user_context).await.unwrap();
+    assert_matches_definition(&db, &user_context, view_sql, definition).await;
+
+    let status = db.materialized_view_status("largest_orders").await.unwrap();
+    assert_eq!(status.maintenance, ViewMaintenance::RefreshOnly);
+    assert!(!status.is_stale());
+
+    db.execute_query("INSERT INTO orders (id, user_id, total) VALUES (13, 2, 99), (14, 3, 1);", &user_context).await.unwrap();
+    let status = db.materialized_view_status("largest_orders").await.unwrap();
+    assert!(status.is_stale());
+    assert_eq!(status.pending_changes, 2);
+    assert_eq!(db.execute_query(view_sql, &user_context).await.unwrap().fingerprint().row_count, 2);
+
+    db.execute_query("REFRESH MATERIALIZED VIEW largest_orders", &user_context).await.unwrap();
+    assert_matches_definition(&db, &user_context, view_sql, definition).await;
+    let status = db.materialized_view_status("largest_orders").await.unwrap();
+    assert!(!status.is_stale());
+    assert_eq!(status.row_count, 3);
+
+    // Base tables cannot be dropped out from under a view
+    assert!(db.execute_query("DROP TABLE orders", &user_context).await.is_err());
+    db.execute_query("DROP MATERIALIZED VIEW largest_orders", &user_context).await.unwrap();
+    assert!(db.materialized_view_status("largest_orders").await.is_none());
+    db.execute_query("DROP TABLE orders", &user_context).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_concurrent_refresh_does_not_block_reads() {
+    let user_context = user_context();
+    let temp_dir = tempdir().unwrap();
+    let db = Arc::new(open(&temp_dir).await);
+    populate(&db, &user_context).await;
+
+    let definition = "SELECT user_id, MAX(total) AS largest FROM orders GROUP BY user_id";
+    let view_sql = "SELECT user_id, largest FROM largest_orders";
+    db.execute_query(&format!("CREATE MATERIALIZED VIEW largest_orders AS {}", definition), &user_context).await.unwrap();
+    let expected = db.execute_query(view_sql, &user_context).await.unwrap().fingerprint();
+
+    let refresher = {
+        let db = Arc::clone(&db);
+        let user_context = user_context.clone();
+        tokio::spawn(async move {
+            for _ in 0..20 {
+                db.execute_query("REFRESH MATERIALIZED VIEW CONCURRENTLY largest_orders", &user_context).await.unwrap();
+            }
+        })
+    };
+
+    // Every read completes promptly and sees complete contents, never a partial refresh
+    while !refresher.is_finished() {
+        let read = tokio::time::timeout(Duration::from_secs(1), db.execute_query(view_sql, &user_context))
+            .await
+            .expect("read blocked by concurrent refresh")
+            .unwrap();
+        assert_eq!(read.fingerprint().unordered, expected.unordered);
+        tokio::task::yield_now().await;
+    }
+    refresher.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_incremental_maintenance_after_dml() {
+    let user_context = user_context();
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    populate(&db, &user_context).await;
+
+    let views = [
+        (
+            "order_totals",
+            "SELECT user_id, COUNT(*) AS order_count, SUM(total) AS revenue FROM orders GROUP BY user_id",
+            "SELECT user_id, order_count, revenue FROM order_totals",
+            ViewMaintenance::IncrementalAggregate,
+        ),
+        (
+            "large_orders",
+            "SELECT id, user_id, total FROM orders WHERE total > 6",
+            "SELECT id, user_id, total FROM large_orders",
+            ViewMaintenance::IncrementalProjection,
+        ),
+        (
+            "user_orders",
+            "SELECT id, name, total FROM users JOIN orders ON users.id = orders.user_id",
+            "SELECT id, name, total FROM user_orders",
+            ViewMaintenance::IncrementalJoinInsert,
+        ),
+    ];
+    for (name, definition, _, maintenance) in views {
+        db.execute_query(&format!("CREATE MATERIALIZED VIEW {} AS {}", name, definition), &user_context).await.unwrap();
+        assert_eq!(db.materialized_view_status(name).await.unwrap().maintenance, maintenance);
+    }
+
+    let inserts = [
+        "INSERT INTO orders (id, user_id, total) VALUES (13, 3, 40), (14, 1, 2);",
+        "INSERT INTO users (id, name) VALUES (4, 'dave');",
+        "INSERT INTO orders (id, user_id, total) VALUES (15, 4, 8);",
+    ];
+    for sql in inserts {
+        db.execute_query(sql, &user_context).await.unwrap();
+        for (name, definition, view_sql, _) in views {
+            assert_matches_definition(&db, &user_context, view_sql, definition).await;
+            assert!(!db.materialized_view_status(name).await.unwrap().is_stale(), "{} after {}", name, sql);
+        }
+    }
+
+    // Updates and deletes are maintained for single-table views; the join view goes stale
+    let writes = [
+        "UPDATE orders SET total = 1 WHERE id = 10;",
+        "DELETE FROM orders WHERE user_id = 2;",
+        "DELETE FROM orders WHERE user_id = 4;",
+    ];
+    for sql in writes {
+        db.execute_query(sql, &user_context).await.unwrap();
+        for (name, definition, view_sql, _) in &views[..2] {
+            assert_matches_definition(&db, &user_context, view_sql, definition).await;
+            assert!(!db.materialized_view_status(name).await.unwrap().is_stale(), "{} after {}", name, sql);
+        }
+    }
+    assert!(db.materialized_view_status("user_orders").await.unwrap().is_stale());
+
+    db.execute_query("REFRESH MATERIALIZED VIEW CONCURRENTLY user_orders", &user_context).await.unwrap();
+    let (_, definition, view_sql, _) = views[2];
+    assert_matches_definition(&db, &user_context, view_sql, definition).await;
+    assert!(!db.materialized_view_status("user_orders").await.unwrap().is_stale());
+}
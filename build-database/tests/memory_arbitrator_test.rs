@@ -0,0 +1,99 @@
+//! Memory Arbitrator Tests
+//!
+//! One budget shared by the sorts of concurrent queries: a lone sort is
+//! granted far more than `work_mem`, many sorts at once are each bounded
+//! with the grants summing to no more than the budget, and memory left idle
+//! by one operator goes to the ones still asking.
+
+use std::sync::Arc;
+use std::time::Duration;
+use aurora_db::config::MemoryArbitrationConfig;
+use aurora_db::engine::{ExternalSort, MemoryArbitrator};
+
+const MB: u64 = 1024 * 1024;
+
+fn arbitrator(budget_mb: u64, max_grant_mb: u64) -> Arc<MemoryArbitrator> {
+    Arc::new(MemoryArbitrator::new(&MemoryArbitrationConfig {
+        total_budget_bytes: (budget_mb * MB) as usize,
+        max_grant_bytes: (max_grant_mb * MB) as usize,
+        idle_after_ms: 50,
+    }))
+}
+
+#[test]
+fn test_lone_query_gets_a_large_grant() {
+    let arbitrator = arbitrator(512, 256);
+    let sort = arbitrator.register("sort", 4 * MB);
+    assert_eq!(sort.limit() as u64, 4 * MB);
+
+    // Its input turns out larger than work_mem: it may have up to the cap
+    assert_eq!(sort.request(900 * MB) as u64, 256 * MB);
+    assert_eq!(sort.request(100 * MB) as u64, 100 * MB);
+    drop(sort);
+    assert_eq!(arbitrator.stats().granted_bytes, 0);
+
+    // A sort under the arbitrator keeps an input that outgrows work_mem in memory
+    let temp_dir = tempfile::tempdir().unwrap();
+    let grant = arbitrator.register("sort", 64 * 1024);
+    let rows: Vec<u64> = (0..100_000).rev().collect();
+    let sorted = ExternalSort::new(64 * 1024, temp_dir.path(), None)
+        .with_grant(Some(&grant))
+        .sort_by(rows, |a, b| a.cmp(b))
+        .unwrap();
+    assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(grant.limit() > 64 * 1024);
+    assert!(!temp_dir.path().exists() || std::fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+}
+
+#[test]
+fn test_concurrent_queries_are_bounded_within_budget() {
+    let arbitrator = arbitrator(512, 256);
+    let threads: Vec<_> = (0..64).map(|i| {
+        let arbitrator = arbitrator.clone();
+        std::thread::spawn(move || {
+            let sort = arbitrator.register(&format!("sort {}", i), 4 * MB);
+            sort.request(300 * MB);
+            sort
+        })
+    }).collect();
+    let mut sorts: Vec<_> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+
+    let stats = arbitrator.stats();
+    assert_eq!(stats.operators, 64);
+    assert!(stats.granted_bytes <= 512 * MB, "{:?}", stats);
+    assert_eq!(stats.largest_grant_bytes, 8 * MB);
+    assert!(sorts.iter().all(|sort| sort.limit() as u64 == 8 * MB));
+
+    // An operator that needs little gets all of it; the rest share the remainder
+    let small = arbitrator.register("small sort", MB);
+    assert_eq!(small.limit() as u64, MB);
+    assert!(arbitrator.stats().granted_bytes <= 512 * MB);
+
+    // As queries finish, the rest grow into what they give back
+    sorts.drain(..48);
+    let stats = arbitrator.stats();
+    assert_eq!(stats.operators, 17);
+    assert!(stats.granted_bytes <= 512 * MB, "{:?}", stats);
+    assert!(sorts.iter().all(|sort| sort.limit() as u64 == 511 * MB / 16), "{:?}", stats);
+}
+
+#[test]
+fn test_idle_operator_memory_is_reclaimed() {
+    let arbitrator = arbitrator(200, 200);
+    let idle = arbitrator.register("idle sort", 150 * MB);
+    idle.report_usage(20 * MB);
+    let busy = arbitrator.register("busy sort", 150 * MB);
+    assert_eq!(idle.limit() as u64, 100 * MB);
+    assert_eq!(busy.limit() as u64, 100 * MB);
+
+    // The first sort stops reporting; the second takes what it is not using
+    std::thread::sleep(Duration::from_millis(80));
+    assert_eq!(busy.request(190 * MB) as u64, 180 * MB);
+    assert_eq!(idle.limit() as u64, 20 * MB);
+
+    // Once it asks again it gets its fair share back
+    idle.request(150 * MB);
+    assert_eq!(idle.limit() as u64, 100 * MB);
+    assert_eq!(busy.limit() as u64, 100 * MB);
+    assert!(arbitrator.stats().granted_bytes <= 200 * MB);
+}
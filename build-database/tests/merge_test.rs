@@ -0,0 +1,157 @@
+//! MERGE Tests
+//!
+//! MERGE updates target rows its source matches, inserts the rest, and can
+//! delete or leave rows by clause condition. A target row matched by two
+//! source rows fails the statement and leaves the target untouched, unless
+//! the session keeps the first match.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, MergeMultipleMatches, UserContext};
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// `inventory` holding items 1-3 and an empty `delivery` with the same columns
+async fn load_inventory(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE inventory (id INTEGER PRIMARY KEY, name TEXT NOT NULL, qty INTEGER);", user_context).await.unwrap();
+    db.execute_query("CREATE TABLE delivery (id INTEGER, name TEXT, qty INTEGER);", user_context).await.unwrap();
+    db.execute_query(
+        "INSERT INTO inventory (id, name, qty) VALUES (1, 'bolt', 10), (2, 'nut', 20), (3, 'gear', 30);",
+        user_context,
+    ).await.unwrap();
+}
+
+/// `(id, name, qty)` of every inventory row, by id
+async fn inventory(db: &AuroraDB, user_context: &UserContext) -> Vec<Vec<serde_json::Value>> {
+    let result = db.execute_query("SELECT id, name, qty FROM inventory ORDER BY id;", user_context).await.unwrap();
+    result.rows
+}
+
+#[tokio::test]
+async fn test_merge_upserts_from_source() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_inventory(&db, &user_context).await;
+    db.execute_query("INSERT INTO delivery (id, name, qty) VALUES (2, 'nut', 5), (4, 'spring', 8);", &user_context).await.unwrap();
+
+    let result = db.execute_query(
+        "MERGE INTO inventory AS i USING delivery AS d ON i.id = d.id \
+         WHEN MATCHED THEN UPDATE SET qty = i.qty + d.qty \
+         WHEN NOT MATCHED THEN INSERT (id, name, qty) VALUES (d.id, d.name, d.qty);",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows_affected, Some(2));
+    assert_eq!(inventory(&db, &user_context).await, vec![
+        vec![json!(1), json!("bolt"), json!(10)],
+        vec![json!(2), json!("nut"), json!(25)],
+        vec![json!(3), json!("gear"), json!(30)],
+        vec![json!(4), json!("spring"), json!(8)],
+    ]);
+
+    // Without aliases the table names qualify columns
+    let result = db.execute_query(
+        "MERGE INTO inventory USING delivery ON inventory.id = delivery.id \
+         WHEN NOT MATCHED THEN INSERT VALUES (delivery.id, delivery.name, delivery.qty);",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows_affected, Some(0));
+}
+
+#[tokio::test]
+async fn test_merge_with_delete_branch() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_inventory(&db, &user_context).await;
+    // Item 1 is written off, item 3 restocked, item 5 is new but empty
+    db.execute_query(
+        "INSERT INTO delivery (id, name, qty) VALUES (1, 'bolt', 0), (3, 'gear', 4), (5, 'cam', 0);",
+        &user_context,
+    ).await.unwrap();
+
+    let result = db.execute_query(
+        "MERGE INTO inventory i USING delivery d ON i.id = d.id \
+         WHEN MATCHED AND d.qty = 0 THEN DELETE \
+         WHEN MATCHED THEN UPDATE SET qty = d.qty \
+         WHEN NOT MATCHED AND d.qty = 0 THEN DO NOTHING \
+         WHEN NOT MATCHED THEN INSERT VALUES (d.id, d.name, d.qty);",
+        &user_context,
+    ).await.unwrap();
+    assert_eq!(result.rows_affected, Some(2));
+    assert_eq!(inventory(&db, &user_context).await, vec![
+        vec![json!(2), json!("nut"), json!(20)],
+        vec![json!(3), json!("gear"), json!(4)],
+    ]);
+}
+
+#[tokio::test]
+async fn test_merge_multiple_matches() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_inventory(&db, &user_context).await;
+    // Item 2 arrives twice; item 6 would be inserted before the second one is seen
+    db.execute_query(
+        "INSERT INTO delivery (id, name, qty) VALUES (6, 'pin', 1), (2, 'nut', 5), (2, 'nut', 7);",
+        &user_context,
+    ).await.unwrap();
+    let merge = "MERGE INTO inventory i USING delivery d ON i.id = d.id \
+                 WHEN MATCHED THEN UPDATE SET qty = i.qty + d.qty \
+                 WHEN NOT MATCHED THEN INSERT VALUES (d.id, d.name, d.qty);";
+    let before = inventory(&db, &user_context).await;
+
+    let error = db.execute_query(merge, &user_context).await.unwrap_err();
+    assert!(error.to_string().contains("cannot affect row a second time"), "{}", error);
+    assert_eq!(inventory(&db, &user_context).await, before);
+
+    // Keeping the first match applies one delivery of item 2
+    db.set_session_merge_multiple_matches(&user_context.session_id, MergeMultipleMatches::FirstMatch);
+    let result = db.execute_query(merge, &user_context).await.unwrap();
+    assert_eq!(result.rows_affected, Some(2));
+    let rows = inventory(&db, &user_context).await;
+    assert_eq!(rows[1], vec![json!(2), json!("nut"), json!(25)]);
+    assert_eq!(rows[3], vec![json!(6), json!("pin"), json!(1)]);
+
+    db.end_session(&user_context.session_id);
+    assert_eq!(db.session_merge_multiple_matches(&user_context.session_id), MergeMultipleMatches::Error);
+}
+
+#[tokio::test]
+async fn test_merge_rejects_invalid_clauses() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_inventory(&db, &user_context).await;
+    db.execute_query("INSERT INTO delivery (id, name, qty) VALUES (1, 'bolt', 3);", &user_context).await.unwrap();
+
+    let insert_on_match = db.execute_query(
+        "MERGE INTO inventory i USING delivery d ON i.id = d.id WHEN MATCHED THEN INSERT VALUES (1, 'x', 1);",
+        &user_context,
+    ).await;
+    assert!(insert_on_match.unwrap_err().to_string().contains("WHEN MATCHED cannot INSERT"));
+
+    let key_change = db.execute_query(
+        "MERGE INTO inventory i USING delivery d ON i.id = d.id WHEN MATCHED THEN UPDATE SET id = 99;",
+        &user_context,
+    ).await;
+    assert!(key_change.unwrap_err().to_string().contains("cannot change the primary key"));
+    assert_eq!(inventory(&db, &user_context).await[0], vec![json!(1), json!("bolt"), json!(10)]);
+}
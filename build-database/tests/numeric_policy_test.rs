@@ -0,0 +1,121 @@
+//! Numeric Policy Tests
+//!
+//! A NaN written to a float column and an integer addition that overflows
+//! are rejected by default; a session or column policy turns them into
+//! NULL, clamps them to the type's range or keeps them as they are.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, NumericPolicy, UserContext};
+use serde_json::json;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// Stored `value` of reading `id`
+async fn reading(db: &AuroraDB, user_context: &UserContext, table: &str, id: i64) -> serde_json::Value {
+    let result = db.execute_query(&format!("SELECT value FROM {} WHERE id = {};", table, id), user_context).await.unwrap();
+    result.rows[0][0].clone()
+}
+
+#[tokio::test]
+async fn test_nan_into_float_column_follows_session_policy() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    db.execute_query("CREATE TABLE readings (id INTEGER PRIMARY KEY, value DOUBLE);", &user_context).await.unwrap();
+
+    // Strict by default
+    let error = db.execute_query("INSERT INTO readings (id, value) VALUES (1, 'NaN');", &user_context).await.unwrap_err();
+    assert!(error.to_string().contains("NaN is not allowed in column 'value'"), "{}", error);
+    assert_eq!(db.session_numeric_policy(&user_context.session_id), NumericPolicy::Reject);
+
+    db.set_session_numeric_policy(&user_context.session_id, NumericPolicy::Null);
+    db.execute_query("INSERT INTO readings (id, value) VALUES (2, 'NaN');", &user_context).await.unwrap();
+    assert_eq!(reading(&db, &user_context, "readings", 2).await, json!(null));
+
+    // NaN has no nearest value to clamp to; infinity clamps to the largest double
+    db.set_session_numeric_policy(&user_context.session_id, NumericPolicy::Saturate);
+    assert!(db.execute_query("INSERT INTO readings (id, value) VALUES (3, 'NaN');", &user_context).await.is_err());
+    db.execute_query("INSERT INTO readings (id, value) VALUES (3, '-Infinity');", &user_context).await.unwrap();
+    assert_eq!(reading(&db, &user_context, "readings", 3).await, json!(-f64::MAX));
+
+    db.set_session_numeric_policy(&user_context.session_id, NumericPolicy::Allow);
+    db.execute_query("INSERT INTO readings (id, value) VALUES (4, 'NaN');", &user_context).await.unwrap();
+    assert_eq!(reading(&db, &user_context, "readings", 4).await, json!("NaN"));
+
+    db.end_session(&user_context.session_id);
+    assert_eq!(db.session_numeric_policy(&user_context.session_id), NumericPolicy::Reject);
+}
+
+#[tokio::test]
+async fn test_integer_addition_overflow_follows_session_policy() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    db.execute_query("CREATE TABLE counters (id INTEGER PRIMARY KEY, total BIGINT);", &user_context).await.unwrap();
+    db.execute_query("CREATE TABLE increments (id INTEGER, amount BIGINT);", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO increments (id, amount) VALUES (1, 1);", &user_context).await.unwrap();
+    let add = "MERGE INTO counters c USING increments i ON c.id = i.id \
+               WHEN MATCHED THEN UPDATE SET total = c.total + i.amount;";
+
+    let mut totals = Vec::new();
+    for policy in [NumericPolicy::Reject, NumericPolicy::Null, NumericPolicy::Saturate, NumericPolicy::Allow] {
+        db.execute_query("DELETE FROM counters;", &user_context).await.unwrap();
+        db.execute_query(&format!("INSERT INTO counters (id, total) VALUES (1, {});", i64::MAX), &user_context).await.unwrap();
+        db.set_session_numeric_policy(&user_context.session_id, policy);
+        let result = db.execute_query(add, &user_context).await;
+        let total = db.execute_query("SELECT total FROM counters WHERE id = 1;", &user_context).await.unwrap().rows[0][0].clone();
+        totals.push((result.is_ok(), total));
+    }
+    assert_eq!(totals, vec![
+        // Error, and the counter is left as it was
+        (false, json!(i64::MAX)),
+        (true, json!(null)),
+        (true, json!(i64::MAX)),
+        // Wrapped around
+        (true, json!(i64::MIN)),
+    ]);
+}
+
+#[tokio::test]
+async fn test_column_policy_overrides_session() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    db.execute_query("CREATE TABLE strict (id INTEGER PRIMARY KEY, value DOUBLE);", &user_context).await.unwrap();
+    db.execute_query("CREATE TABLE lenient (id INTEGER PRIMARY KEY, value DOUBLE);", &user_context).await.unwrap();
+    db.set_column_numeric_policy("lenient", "value", NumericPolicy::Allow);
+
+    // One pipeline, loading the same values into both tables
+    for table in ["strict", "lenient"] {
+        let loaded = db.execute_query(&format!("INSERT INTO {} (id, value) VALUES (1, 'Infinity');", table), &user_context).await;
+        assert_eq!(loaded.is_ok(), table == "lenient", "{}", table);
+    }
+    assert_eq!(reading(&db, &user_context, "lenient", 1).await, json!("Infinity"));
+
+    // An out-of-range integer into a column clamped to the type
+    db.execute_query("CREATE TABLE samples (id INTEGER PRIMARY KEY, value INTEGER);", &user_context).await.unwrap();
+    assert!(db.execute_query("INSERT INTO samples (id, value) VALUES (1, 3000000000);", &user_context).await.is_err());
+    db.set_column_numeric_policy("samples", "value", NumericPolicy::Saturate);
+    db.execute_query("INSERT INTO samples (id, value) VALUES (1, 3000000000);", &user_context).await.unwrap();
+    assert_eq!(reading(&db, &user_context, "samples", 1).await, json!(i32::MAX));
+
+    assert_eq!(NumericPolicy::parse("SATURATE"), Some(NumericPolicy::Saturate));
+    assert_eq!(NumericPolicy::parse("clamp"), None);
+}
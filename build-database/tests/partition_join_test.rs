@@ -0,0 +1,107 @@
+//! Partition-Wise Join Tests
+//!
+//! Joins between tables hash-partitioned the same way on the join key are
+//! planned as per-partition joins and return exactly the rows an ordinary
+//! join does.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// Create `customers{suffix}` and `orders{suffix}` with the same rows,
+/// appending the given partitioning clause to each CREATE TABLE
+async fn load_tables(db: &AuroraDB, user_context: &UserContext, suffix: &str, customers: &str, orders: &str) {
+    db.execute_query(&format!("CREATE TABLE customers{} (id INTEGER PRIMARY KEY, name TEXT) {};", suffix, customers), user_context).await.unwrap();
+    db.execute_query(&format!("CREATE TABLE orders{} (order_id INTEGER PRIMARY KEY, customer_id INTEGER, total INTEGER) {};", suffix, orders), user_context).await.unwrap();
+
+    let customer_rows: Vec<String> = (1..=12).map(|id| format!("({}, 'customer_{}')", id, id)).collect();
+    db.execute_query(&format!("INSERT INTO customers{} (id, name) VALUES {};", suffix, customer_rows.join(", ")), user_context).await.unwrap();
+    // Customers 11 and 12 have no orders; customer 13 does not exist
+    let mut order_rows: Vec<String> = (1..=40).map(|id| format!("({}, {}, {})", id, id % 10 + 1, id * 5)).collect();
+    order_rows.push("(41, 13, 205)".to_string());
+    db.execute_query(&format!("INSERT INTO orders{} (order_id, customer_id, total) VALUES {};", suffix, order_rows.join(", ")), user_context).await.unwrap();
+}
+
+async fn plan(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<String> {
+    let result = db.execute_query(&format!("EXPLAIN {}", sql), user_context).await.unwrap();
+    result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect()
+}
+
+async fn sorted_rows(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<String> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    let mut rows: Vec<String> = result.rows.iter().map(|row| format!("{:?}", row)).collect();
+    rows.sort();
+    rows
+}
+
+#[tokio::test]
+async fn test_co_partitioned_join_is_partition_wise() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context, "", "PARTITION BY HASH (id) PARTITIONS 4", "PARTITION BY HASH (customer_id) PARTITIONS 4").await;
+    load_tables(&db, &user_context, "_plain", "", "").await;
+
+    let sql = "SELECT order_id, name, total FROM customers JOIN orders ON customers.id = orders.customer_id";
+    let lines = plan(&db, &user_context, sql).await;
+    assert!(lines[0].starts_with("Partition-Wise Join on customers.id = orders.customer_id (4 partitions)"), "{:?}", lines);
+    assert_eq!(lines.iter().filter(|line| line.trim_start().starts_with("-> Partition ")).count(), 4);
+
+    let plain_sql = "SELECT order_id, name, total FROM customers_plain JOIN orders_plain ON customers_plain.id = orders_plain.customer_id";
+    let lines = plan(&db, &user_context, plain_sql).await;
+    assert!(lines[0].starts_with("Nested Loop Join"), "{:?}", lines);
+
+    let partitioned = sorted_rows(&db, &user_context, sql).await;
+    assert_eq!(partitioned.len(), 40);
+    assert_eq!(partitioned, sorted_rows(&db, &user_context, plain_sql).await);
+
+    // The key equality may be written either way round
+    let reversed = "SELECT order_id, name, total FROM customers JOIN orders ON orders.customer_id = customers.id";
+    assert!(plan(&db, &user_context, reversed).await[0].starts_with("Partition-Wise Join"));
+    assert_eq!(sorted_rows(&db, &user_context, reversed).await, partitioned);
+}
+
+#[tokio::test]
+async fn test_mismatched_schemes_fall_back_to_regular_join() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context, "", "PARTITION BY HASH (id) PARTITIONS 4", "PARTITION BY HASH (customer_id) PARTITIONS 8").await;
+    load_tables(&db, &user_context, "_by_order", "PARTITION BY HASH (id) PARTITIONS 4", "PARTITION BY HASH (order_id) PARTITIONS 4").await;
+    load_tables(&db, &user_context, "_plain", "", "").await;
+
+    let plain = sorted_rows(&db, &user_context, "SELECT order_id, name, total FROM customers_plain JOIN orders_plain ON customers_plain.id = orders_plain.customer_id").await;
+    for sql in [
+        // Different partition counts
+        "SELECT order_id, name, total FROM customers JOIN orders ON customers.id = orders.customer_id",
+        // Orders partitioned on a column other than the join key
+        "SELECT order_id, name, total FROM customers_by_order JOIN orders_by_order ON customers_by_order.id = orders_by_order.customer_id",
+    ] {
+        let lines = plan(&db, &user_context, sql).await;
+        assert!(lines.iter().all(|line| !line.contains("Partition")), "{:?}", lines);
+        assert!(lines[0].starts_with("Nested Loop Join"), "{:?}", lines);
+        assert_eq!(sorted_rows(&db, &user_context, sql).await, plain);
+    }
+
+    // The partition key must name a column of the table
+    assert!(db.execute_query("CREATE TABLE bad (id INTEGER) PARTITION BY HASH (missing) PARTITIONS 2;", &user_context).await.is_err());
+    assert!(db.execute_query("CREATE TABLE bad (id INTEGER) PARTITION BY HASH (id) PARTITIONS 0;", &user_context).await.is_err());
+}
@@ -0,0 +1,116 @@
+//! Plan Invalidation Tests
+//!
+//! ALTER TABLE publishes a catalog change that evicts the cached plans,
+//! cached results and planner statistics of the altered table, and only
+//! those. Plans can be prepared before their first run; pinned ones also
+//! survive ANALYZE, but not a schema change.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+const ORDERS_SQL: &str = "SELECT id, quantity FROM orders";
+const USERS_SQL: &str = "SELECT id, name FROM users";
+
+async fn load_tables(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE orders (id INTEGER PRIMARY KEY, quantity INTEGER);", user_context).await.unwrap();
+    db.execute_query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);", user_context).await.unwrap();
+    db.execute_query("INSERT INTO orders (id, quantity) VALUES (1, 5), (2, 7);", user_context).await.unwrap();
+    db.execute_query("INSERT INTO users (id, name) VALUES (1, 'ada');", user_context).await.unwrap();
+    db.execute_query(ORDERS_SQL, user_context).await.unwrap();
+    db.execute_query(USERS_SQL, user_context).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_alter_evicts_only_dependent_entries() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context).await;
+
+    assert!(db.has_cached_plan(ORDERS_SQL));
+    assert!(db.has_cached_plan(USERS_SQL));
+    assert_eq!(db.cached_result_count(), 2);
+    assert_eq!(db.table_statistics("orders").unwrap().row_count, 2);
+
+    db.execute_query("ALTER TABLE orders ALTER COLUMN quantity TYPE BIGINT;", &user_context).await.unwrap();
+
+    assert!(!db.has_cached_plan(ORDERS_SQL));
+    assert!(db.table_statistics("orders").is_none());
+    assert!(db.has_cached_plan(USERS_SQL));
+    assert_eq!(db.cached_result_count(), 1);
+    assert_eq!(db.table_statistics("users").unwrap().row_count, 1);
+
+    // Re-planned against the new schema, with the same data
+    let result = db.execute_query(ORDERS_SQL, &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 2);
+    assert!(db.has_cached_plan(ORDERS_SQL));
+}
+
+#[tokio::test]
+async fn test_alter_rejects_values_that_do_not_fit() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context).await;
+
+    assert!(db.execute_query("ALTER TABLE orders ALTER COLUMN quantity TYPE TEXT;", &user_context).await.is_err());
+    assert!(db.execute_query("ALTER TABLE orders ALTER COLUMN missing TYPE BIGINT;", &user_context).await.is_err());
+
+    // A rejected change leaves the cached plan in place
+    assert!(db.has_cached_plan(ORDERS_SQL));
+}
+
+#[tokio::test]
+async fn test_pinned_plan_is_cached_before_first_run_until_schema_change() {
+    const PINNED_SQL: &str = "SELECT id FROM orders WHERE quantity > 5";
+    const WARM_SQL: &str = "SELECT quantity FROM orders";
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_tables(&db, &user_context).await;
+
+    assert!(!db.has_cached_plan(PINNED_SQL));
+    db.execute_query(&format!("PREPARE PINNED PLAN FOR {}", PINNED_SQL), &user_context).await.unwrap();
+    db.execute_query(&format!("prepare plan for {}", WARM_SQL), &user_context).await.unwrap();
+    assert!(db.has_cached_plan(PINNED_SQL));
+    assert!(db.has_cached_plan(WARM_SQL));
+    assert_eq!(db.pinned_plans(), vec![PINNED_SQL.to_string()]);
+
+    // Fresh statistics have the table's other plans rebuilt
+    db.analyze_table("orders", &user_context).await.unwrap();
+    assert!(db.has_cached_plan(PINNED_SQL));
+    assert!(!db.has_cached_plan(WARM_SQL));
+    assert!(!db.has_cached_plan(ORDERS_SQL));
+    assert!(db.has_cached_plan(USERS_SQL));
+    let result = db.execute_query(PINNED_SQL, &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 1);
+
+    // A schema change drops it anyway
+    db.execute_query("ALTER TABLE orders ALTER COLUMN quantity TYPE BIGINT;", &user_context).await.unwrap();
+    assert!(!db.has_cached_plan(PINNED_SQL));
+    assert!(db.pinned_plans().is_empty());
+
+    // Only plans of statements over existing tables can be prepared
+    assert!(db.prepare_plan("SELECT id FROM missing", true).await.is_err());
+    assert!(db.execute_query("PREPARE PLAN FOR CREATE TABLE other (id INTEGER PRIMARY KEY);", &user_context).await.is_err());
+    assert!(db.pinned_plans().is_empty());
+}
@@ -0,0 +1,241 @@
+//! Query Escalation Tests
+//!
+//! A query that outlives its role's thresholds is first logged with a plan
+//! snapshot, then cancelled at its next cancellation check, and when it
+//! ignores the cancel its backend is terminated, releasing what it held.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use aurora_db::config::DatabaseConfig;
+use aurora_db::core::AuroraResult;
+use aurora_db::engine::{AuroraDB, ForeignDataWrapper, ForeignScanEstimate, RowStream, ScanFilter};
+use aurora_db::monitoring::{EscalationLevel, EscalationMonitor};
+use aurora_db::network::{EscalationPolicies, EscalationPolicy, PostgresProtocol};
+use aurora_db::types::DataValue;
+use async_trait::async_trait;
+use futures::StreamExt;
+use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+/// SQLSTATE and message of an ErrorResponse body
+fn error_fields(body: &[u8]) -> (String, String) {
+    let field = |code: u8| body.split(|byte| *byte == 0)
+        .find(|field| field.first() == Some(&code))
+        .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+        .unwrap_or_default();
+    (field(b'C'), field(b'M'))
+}
+
+async fn connect(address: SocketAddr, user: &str) -> TcpStream {
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(format!("user\0{}\0\0", user).as_bytes());
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(&mut socket).await.0, b'R');
+    send(&mut socket, b'p', b"secret\0").await;
+    assert_eq!(read_message(&mut socket).await.0, b'R');
+    assert_eq!(read_message(&mut socket).await, (b'Z', vec![b'I']));
+    socket
+}
+
+/// Run a simple query; the SQLSTATE and message if it failed
+async fn query(socket: &mut TcpStream, sql: &str) -> Result<(), (String, String)> {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+    let mut error = None;
+    loop {
+        match read_message(socket).await {
+            (b'E', body) => error = Some(error_fields(&body)),
+            (b'Z', _) => return error.map_or(Ok(()), Err),
+            _ => {}
+        }
+    }
+}
+
+/// Foreign table whose rows arrive slowly, either one at a time so the
+/// engine can check for cancellation in between, or all at once after a
+/// single long wait it cannot interrupt
+struct SlowWrapper {
+    rows: usize,
+    row_delay: Duration,
+    cooperative: bool,
+}
+
+#[async_trait]
+impl ForeignDataWrapper for SlowWrapper {
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec!["id".to_string()]
+    }
+
+    fn estimate(&self, _filters: &[ScanFilter]) -> ForeignScanEstimate {
+        ForeignScanEstimate { rows: self.rows as f64, startup_cost: 0.0, total_cost: self.rows as f64 }
+    }
+
+    async fn scan(&self, _projection: &[String], _filters: &[ScanFilter]) -> AuroraResult<RowStream> {
+        let row = |id: usize| Ok([("id".to_string(), DataValue::Integer(id as i64))].into_iter().collect());
+        if !self.cooperative {
+            tokio::time::sleep(self.row_delay * self.rows as u32).await;
+            return Ok(futures::stream::iter((0..self.rows).map(row)).boxed());
+        }
+        let delay = self.row_delay;
+        Ok(futures::stream::iter(0..self.rows)
+            .then(move |id| async move {
+                tokio::time::sleep(delay).await;
+                row(id)
+            })
+            .boxed())
+    }
+}
+
+async fn open(temp_dir: &TempDir) -> Arc<AuroraDB> {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = AuroraDB::new(config).await.unwrap();
+    // 20 rows of 50ms: a full scan takes a second
+    db.register_foreign_table("slow_rows", Arc::new(SlowWrapper {
+        rows: 20, row_delay: Duration::from_millis(50), cooperative: true,
+    })).await.unwrap();
+    db.register_foreign_table("stuck_rows", Arc::new(SlowWrapper {
+        rows: 20, row_delay: Duration::from_millis(500), cooperative: false,
+    })).await.unwrap();
+    Arc::new(db)
+}
+
+/// Serve every connection to a new listener on the current `LocalSet`
+async fn serve(db: Arc<AuroraDB>, policies: EscalationPolicies, monitor: Arc<EscalationMonitor>) -> SocketAddr {
+    let protocol = Arc::new(PostgresProtocol::new(db).with_escalation(policies, monitor));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::task::spawn_local(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            let protocol = protocol.clone();
+            tokio::task::spawn_local(async move {
+                let _ = protocol.handle_connection(socket).await;
+            });
+        }
+    });
+    address
+}
+
+fn levels(monitor: &EscalationMonitor) -> Vec<EscalationLevel> {
+    monitor.events().into_iter().map(|event| event.level).collect()
+}
+
+#[tokio::test]
+async fn test_query_past_warning_threshold_is_logged_with_its_plan() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let monitor = Arc::new(EscalationMonitor::default());
+    let policies = EscalationPolicies::default()
+        .for_role("reporting", EscalationPolicy::none().with_warn_after(Duration::from_millis(200)));
+
+    tokio::task::LocalSet::new().run_until(async {
+        let address = serve(db.clone(), policies, monitor.clone()).await;
+
+        // A warning does not stop the query
+        let mut reporting = connect(address, "reporting").await;
+        query(&mut reporting, "SELECT id FROM slow_rows").await.unwrap();
+
+        let events = monitor.events();
+        assert_eq!(levels(&monitor), vec![EscalationLevel::Warn]);
+        assert_eq!((events[0].role.as_str(), events[0].query.as_str()), ("reporting", "SELECT id FROM slow_rows"));
+        assert!(events[0].elapsed >= Duration::from_millis(200));
+        let plan = events[0].plan.as_ref().expect("plan snapshot");
+        assert!(plan.iter().any(|line| line.contains("Foreign Scan on slow_rows using slow")), "{:?}", plan);
+
+        // Roles without a policy run unwatched
+        let mut app = connect(address, "app").await;
+        query(&mut app, "SELECT id FROM slow_rows").await.unwrap();
+        assert_eq!(monitor.events().len(), 1);
+        assert_eq!(monitor.total(EscalationLevel::Warn), 1);
+    }).await;
+}
+
+#[tokio::test]
+async fn test_query_past_cancel_threshold_is_cancelled_gracefully() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let monitor = Arc::new(EscalationMonitor::default());
+    let policies = EscalationPolicies::new(
+        EscalationPolicy::none()
+            .with_warn_after(Duration::from_millis(100))
+            .with_cancel_after(Duration::from_millis(250)),
+    );
+
+    tokio::task::LocalSet::new().run_until(async {
+        let address = serve(db.clone(), policies, monitor.clone()).await;
+        let mut socket = connect(address, "reporting").await;
+
+        let (sqlstate, message) = query(&mut socket, "SELECT id FROM slow_rows").await.unwrap_err();
+        assert_eq!(sqlstate, "57014");
+        assert_eq!(message, "canceling statement due to query escalation policy");
+        assert_eq!(levels(&monitor), vec![EscalationLevel::Warn, EscalationLevel::Cancel]);
+
+        // The statement stopped at its next row and the session goes on
+        assert!(!db.is_statement_running(&monitor.events()[1].session_id));
+        query(&mut socket, "SHOW work_mem").await.unwrap();
+    }).await;
+}
+
+#[tokio::test]
+async fn test_non_cooperative_query_is_killed_at_kill_threshold() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let monitor = Arc::new(EscalationMonitor::default());
+    let policies = EscalationPolicies::new(
+        EscalationPolicy::none()
+            .with_cancel_after(Duration::from_millis(100))
+            .with_kill_after(Duration::from_millis(300)),
+    );
+
+    tokio::task::LocalSet::new().run_until(async {
+        let address = serve(db.clone(), policies, monitor.clone()).await;
+        let mut socket = connect(address, "reporting").await;
+
+        // The scan never reaches a cancellation check, so it is terminated
+        send(&mut socket, b'Q', b"SELECT id FROM stuck_rows\0").await;
+        let (message_type, body) = read_message(&mut socket).await;
+        assert_eq!(message_type, b'E');
+        assert_eq!(error_fields(&body), (
+            "57P01".to_string(),
+            "terminating connection due to query escalation policy".to_string(),
+        ));
+        let mut rest = Vec::new();
+        socket.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.is_empty());
+
+        let events = monitor.events();
+        assert_eq!(levels(&monitor), vec![EscalationLevel::Cancel, EscalationLevel::Kill]);
+        assert!(events[1].elapsed < Duration::from_secs(1));
+
+        // Everything the statement held was released with it
+        assert!(!db.is_statement_running(&events[1].session_id));
+        assert_eq!(db.tenant_governor().usage("reporting").active_queries, 0);
+    }).await;
+}
@@ -0,0 +1,140 @@
+//! Query Profiler Tests
+//!
+//! Profiles a query whose time goes almost entirely to one CPU-heavy
+//! function and checks that the collapsed stacks put that function on top,
+//! both through EXPLAIN (ANALYZE, PROFILE) and a profiled session.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext, DEFAULT_SAMPLE_RATE_HZ};
+use aurora_db::network::SessionVariables;
+use aurora_db::query::FunctionSignature;
+use aurora_db::types::{DataType, DataValue};
+use std::time::{Duration, Instant};
+use tempfile::{tempdir, TempDir};
+
+/// CPU time `burn()` spends per call
+const BURN_PER_CALL: Duration = Duration::from_micros(300);
+
+fn user_context(session_id: &str) -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: session_id.to_string(),
+    }
+}
+
+/// Database with 400 readings and a `burn(x)` function that spins
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = AuroraDB::new(config).await.unwrap();
+    db.register_function(
+        "burn",
+        FunctionSignature::new(vec![DataType::Integer], DataType::Integer),
+        |args| {
+            let started = Instant::now();
+            let mut state = match args[0] { DataValue::Integer(value) => value as u64, _ => 0 };
+            while started.elapsed() < BURN_PER_CALL {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            }
+            Ok(DataValue::Integer((state >> 33) as i64))
+        },
+    ).unwrap();
+
+    let user_context = user_context("setup");
+    db.execute_query("CREATE TABLE readings (id INTEGER PRIMARY KEY, sensor TEXT)", &user_context).await.unwrap();
+    let values: Vec<String> = (0..400).map(|id| format!("({}, 'sensor-{}')", id, id % 7)).collect();
+    db.execute_query(&format!("INSERT INTO readings (id, sensor) VALUES {}", values.join(", ")), &user_context).await.unwrap();
+    db
+}
+
+const HOT_QUERY: &str = "SELECT id, burn(id) AS scrambled FROM readings ORDER BY id";
+
+#[tokio::test]
+async fn test_explain_profile_puts_the_hot_function_on_top() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    let result = db.execute_query(&format!("EXPLAIN (ANALYZE, PROFILE) {}", HOT_QUERY), &user_context("explain")).await.unwrap();
+    let lines: Vec<String> = result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect();
+
+    let header = lines.iter().position(|line| line.starts_with("Profile: ")).unwrap_or_else(|| panic!("no profile in {:?}", lines));
+    assert!(lines[header].ends_with(&format!("samples at {} Hz", DEFAULT_SAMPLE_RATE_HZ)), "{:?}", lines);
+    let total: u64 = lines[header].split_whitespace().nth(1).unwrap().parse().unwrap();
+    assert!(total >= 20, "{:?}", lines);
+
+    // Collapsed stacks follow, most samples first, then the execution time
+    let stacks: Vec<(String, u64)> = lines[header + 1..].iter()
+        .take_while(|line| !line.starts_with("Execution Time"))
+        .map(|line| {
+            let (stack, count) = line.trim().rsplit_once(' ').unwrap();
+            (stack.to_string(), count.parse().unwrap())
+        })
+        .collect();
+    assert_eq!(stacks.iter().map(|(_, count)| count).sum::<u64>(), total);
+    assert_eq!(stacks[0].0, "SELECT;Project;fn:burn", "{:?}", lines);
+    assert!(stacks[0].1 * 10 >= total * 7, "{:?}", lines);
+    assert!(lines.last().unwrap().starts_with("Execution Time: "));
+
+    // PROFILE needs ANALYZE
+    assert!(db.execute_query(&format!("EXPLAIN (PROFILE) {}", HOT_QUERY), &user_context("explain")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_profiled_session_keeps_its_latest_profile() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let profiled = user_context("profiled");
+
+    assert!(db.set_session_profiling("profiled", 0).is_err());
+    db.set_session_profiling("profiled", 2000).unwrap();
+    assert_eq!(db.session_profiling("profiled"), Some(2000));
+
+    // Run twice: a profiled statement is never answered from the result cache
+    for _ in 0..2 {
+        let result = db.execute_query(HOT_QUERY, &profiled).await.unwrap();
+        assert_eq!(result.rows.len(), 400);
+
+        let profile = db.last_query_profile("profiled").unwrap();
+        assert_eq!(profile.sample_rate_hz, 2000);
+        assert!(profile.total_samples >= 40, "{}", profile.collapsed());
+        assert!(profile.self_samples("fn:burn") * 10 >= profile.total_samples * 7, "{}", profile.collapsed());
+        assert!(profile.stacks.iter().all(|(stack, _)| stack.starts_with("SELECT")), "{}", profile.collapsed());
+        assert!(profile.collapsed().lines().next().unwrap().starts_with("SELECT;Project;fn:burn "));
+    }
+
+    // Other sessions are not profiled
+    db.execute_query(HOT_QUERY, &user_context("other")).await.unwrap();
+    assert_eq!(db.last_query_profile("other"), None);
+
+    // Turning profiling off keeps the latest profile until the session ends
+    let latest = db.last_query_profile("profiled").unwrap();
+    db.reset_session_profiling("profiled");
+    db.execute_query("SELECT id FROM readings WHERE id = 1", &profiled).await.unwrap();
+    assert_eq!(db.last_query_profile("profiled"), Some(latest));
+    db.end_session("profiled");
+    assert_eq!(db.last_query_profile("profiled"), None);
+}
+
+#[test]
+fn test_profiler_session_settings() {
+    let mut session = SessionVariables::new(4 * 1024 * 1024);
+    assert_eq!(session.profiler_sample_rate(), None);
+    assert_eq!(session.show("query_profiler").unwrap(), "off");
+
+    session.set("query_profiler", "on").unwrap();
+    assert_eq!(session.profiler_sample_rate(), Some(DEFAULT_SAMPLE_RATE_HZ));
+    session.set("query_profiler_sample_rate", "250").unwrap();
+    assert_eq!(session.profiler_sample_rate(), Some(250));
+
+    assert!(session.set("query_profiler", "sometimes").is_err());
+    assert!(session.set("query_profiler_sample_rate", "0").is_err());
+    assert!(session.set("query_profiler_sample_rate", "1000000").is_err());
+
+    session.reset("query_profiler").unwrap();
+    assert_eq!(session.profiler_sample_rate(), None);
+}
@@ -0,0 +1,98 @@
+//! Query Progress Tests
+//!
+//! A large scan polled through the `aurora_query_progress` system view and
+//! the engine's progress API while it runs.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context(session_id: &str) -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: session_id.to_string(),
+    }
+}
+
+async fn populate(db: &AuroraDB, user_context: &UserContext, rows: usize) {
+    db.execute_query("CREATE TABLE events (id INTEGER PRIMARY KEY, payload TEXT);", user_context).await.unwrap();
+    for chunk in (0..rows).collect::<Vec<_>>().chunks(500) {
+        let values: Vec<String> = chunk.iter().map(|id| format!("({}, 'event payload {}')", id, id)).collect();
+        let sql = format!("INSERT INTO events (id, payload) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_large_scan_progress_advances_monotonically() {
+    let temp_dir = tempdir().unwrap();
+    let db = Arc::new(open(&temp_dir).await);
+    let user_context = user_context("progress");
+    populate(&db, &user_context, 20_000).await;
+
+    let scan_sql = "SELECT id, payload FROM events ORDER BY id DESC";
+    let scan = {
+        let db = Arc::clone(&db);
+        let user_context = user_context.clone();
+        tokio::spawn(async move { db.execute_query(scan_sql, &user_context).await.unwrap() })
+    };
+
+    // Poll the system view until the scan has finished
+    let mut observed: Vec<f64> = Vec::new();
+    let mut final_state = None;
+    while final_state.is_none() {
+        let view = db.execute_query("SELECT query, state, progress FROM aurora_query_progress", &user_context).await.unwrap();
+        for row in &view.rows {
+            if row[0] != json!(scan_sql) {
+                continue;
+            }
+            observed.push(row[2].as_f64().unwrap());
+            if row[1] == json!("finished") {
+                final_state = Some(row[1].clone());
+            }
+        }
+        tokio::task::yield_now().await;
+    }
+
+    let result = scan.await.unwrap();
+    assert_eq!(result.rows.len(), 20_000);
+
+    assert!(observed.windows(2).all(|pair| pair[0] <= pair[1]), "progress moved backwards: {:?}", observed);
+    assert!(observed.iter().all(|fraction| (0.0..=1.0).contains(fraction)));
+    assert_eq!(observed.last(), Some(&1.0));
+
+    // The engine API reports the same query with its per-operator detail
+    let snapshot = db.query_progress().into_iter().find(|query| query.query == scan_sql).unwrap();
+    assert_eq!(snapshot.state, "finished");
+    assert_eq!(snapshot.fraction, 1.0);
+    assert_eq!(snapshot.current_operator, None);
+    let scan_operator = snapshot.operators.iter().find(|op| op.label == "scan events").unwrap();
+    assert_eq!(scan_operator.rows_processed, 20_000);
+    assert_eq!(scan_operator.rows_total, Some(20_000));
+    assert!(snapshot.operators.iter().any(|op| op.label == "sort" && op.finished));
+}
+
+#[tokio::test]
+async fn test_progress_view_lists_its_own_query() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context("self");
+
+    let sql = "SELECT query, state FROM aurora_query_progress";
+    let view = db.execute_query(sql, &user_context).await.unwrap();
+    assert!(view.rows.iter().any(|row| row[0] == json!(sql) && row[1] == json!("running")));
+    assert!(db.query_progress().iter().any(|query| query.query == sql && query.state == "finished"));
+}
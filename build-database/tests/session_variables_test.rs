@@ -0,0 +1,262 @@
+//! Session Variables Tests
+//!
+//! SET persists for the rest of a connection and only that connection,
+//! RESET and RESET ALL return settings to the server defaults, and bad
+//! names and values are refused before they reach a query.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::AuroraDB;
+use aurora_db::network::{PostgresProtocol, SessionCommand, SessionVariables};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Server's reply to one simple query
+#[derive(Debug)]
+struct Reply {
+    /// CommandComplete tags
+    tags: Vec<String>,
+    /// DataRow values
+    rows: Vec<Vec<String>>,
+    /// SQLSTATE and message of the ErrorResponse, if any
+    error: Option<(String, String)>,
+    /// ReadyForQuery transaction status
+    status: u8,
+}
+
+impl Reply {
+    fn sqlstate(&self) -> Option<&str> {
+        self.error.as_ref().map(|(sqlstate, _)| sqlstate.as_str())
+    }
+}
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+async fn connect(socket: &mut TcpStream) {
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(b"user\0test\0\0");
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(socket).await.0, b'R');
+    send(socket, b'p', b"secret\0").await;
+    assert_eq!(read_message(socket).await.0, b'R');
+    assert_eq!(read_message(socket).await, (b'Z', vec![b'I']));
+}
+
+fn data_row(body: &[u8]) -> Vec<String> {
+    let columns = u16::from_be_bytes([body[0], body[1]]) as usize;
+    let mut offset = 2;
+    let mut values = Vec::with_capacity(columns);
+    for _ in 0..columns {
+        let length = u32::from_be_bytes(body[offset..offset + 4].try_into().unwrap()) as usize;
+        offset += 4;
+        values.push(String::from_utf8_lossy(&body[offset..offset + length]).to_string());
+        offset += length;
+    }
+    values
+}
+
+async fn query(socket: &mut TcpStream, sql: &str) -> Reply {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+
+    let mut reply = Reply { tags: Vec::new(), rows: Vec::new(), error: None, status: 0 };
+    loop {
+        let (message_type, body) = read_message(socket).await;
+        match message_type {
+            b'C' => reply.tags.push(String::from_utf8_lossy(&body[..body.len() - 1]).to_string()),
+            b'D' => reply.rows.push(data_row(&body)),
+            b'E' => {
+                let field = |code: u8| body.split(|byte| *byte == 0)
+                    .find(|field| field.first() == Some(&code))
+                    .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+                    .unwrap_or_default();
+                reply.error = Some((field(b'C'), field(b'M')));
+            }
+            b'Z' => {
+                reply.status = body[0];
+                return reply;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The single value a SHOW returns
+async fn show(socket: &mut TcpStream, name: &str) -> String {
+    let reply = query(socket, &format!("SHOW {}", name)).await;
+    assert_eq!(reply.error, None, "SHOW {}", name);
+    reply.rows[0][0].clone()
+}
+
+async fn open(temp_dir: &TempDir) -> Arc<AuroraDB> {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        work_mem_bytes: 4 * 1024 * 1024,
+        ..DatabaseConfig::default()
+    };
+    Arc::new(AuroraDB::new(config).await.unwrap())
+}
+
+/// Accept `connections` connections, each served concurrently
+async fn serve(db: Arc<AuroraDB>, connections: usize) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::task::spawn_local(async move {
+        let protocol = Arc::new(PostgresProtocol::new(db));
+        let mut sessions = Vec::new();
+        for _ in 0..connections {
+            let (socket, _) = listener.accept().await.unwrap();
+            let protocol = protocol.clone();
+            sessions.push(tokio::task::spawn_local(async move {
+                protocol.handle_connection(socket).await.unwrap();
+            }));
+        }
+        for session in sessions {
+            session.await.unwrap();
+        }
+    });
+    (address, server)
+}
+
+#[tokio::test]
+async fn test_set_persists_for_the_connection_only() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        let (address, server) = serve(db, 2).await;
+        let mut first = TcpStream::connect(address).await.unwrap();
+        connect(&mut first).await;
+        let mut second = TcpStream::connect(address).await.unwrap();
+        connect(&mut second).await;
+
+        assert_eq!(show(&mut first, "work_mem").await, "4MB");
+        let reply = query(&mut first, "SET work_mem = '64MB'").await;
+        assert_eq!((reply.tags, reply.error), (vec!["SET".to_string()], None));
+        assert_eq!(query(&mut first, "SET TIME ZONE 'America/New_York'").await.error, None);
+        assert_eq!(query(&mut first, "set statement_timeout to 5000").await.error, None);
+        assert_eq!(query(&mut first, "SET myapp.tenant = 'acme'").await.error, None);
+
+        // Later statements on the same connection see the settings
+        assert_eq!(query(&mut first, "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)").await.error, None);
+        assert_eq!(show(&mut first, "work_mem").await, "64MB");
+        assert_eq!(show(&mut first, "TimeZone").await, "America/New_York");
+        assert_eq!(show(&mut first, "statement_timeout").await, "5s");
+        assert_eq!(show(&mut first, "myapp.tenant").await, "acme");
+
+        // Another connection keeps the defaults
+        assert_eq!(show(&mut second, "work_mem").await, "4MB");
+        assert_eq!(show(&mut second, "timezone").await, "UTC");
+        assert_eq!(query(&mut second, "SHOW myapp.tenant").await.sqlstate(), Some("42704"));
+
+        // RESET returns one setting, RESET ALL every setting, to the default
+        let reply = query(&mut first, "RESET work_mem").await;
+        assert_eq!((reply.tags, reply.error), (vec!["RESET".to_string()], None));
+        assert_eq!(show(&mut first, "work_mem").await, "4MB");
+        assert_eq!(show(&mut first, "timezone").await, "America/New_York");
+        assert_eq!(query(&mut first, "SET timezone TO DEFAULT").await.error, None);
+        assert_eq!(show(&mut first, "timezone").await, "UTC");
+        assert_eq!(query(&mut first, "RESET ALL").await.error, None);
+        assert_eq!(show(&mut first, "statement_timeout").await, "0");
+        assert_eq!(query(&mut first, "SHOW myapp.tenant").await.sqlstate(), Some("42704"));
+
+        send(&mut first, b'X', &[]).await;
+        send(&mut second, b'X', &[]).await;
+        server.await.unwrap();
+    }).await;
+}
+
+#[tokio::test]
+async fn test_invalid_settings_are_refused() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        let (address, server) = serve(db, 1).await;
+        let mut socket = TcpStream::connect(address).await.unwrap();
+        connect(&mut socket).await;
+
+        let reply = query(&mut socket, "SET work_mem = 'plenty'").await;
+        let (sqlstate, message) = reply.error.unwrap();
+        assert_eq!(sqlstate, "22023");
+        assert_eq!(message, "invalid value for parameter \"work_mem\": \"plenty\"");
+        assert_eq!(query(&mut socket, "SET timezone = 'Nowhere/Special'").await.sqlstate(), Some("22023"));
+        assert_eq!(query(&mut socket, "SET statement_timeout = '5 weeks'").await.sqlstate(), Some("22023"));
+
+        let reply = query(&mut socket, "SET no_such_setting = 1").await;
+        let (sqlstate, message) = reply.error.unwrap();
+        assert_eq!(sqlstate, "42704");
+        assert_eq!(message, "unrecognized configuration parameter \"no_such_setting\"");
+
+        // Refused settings leave the old values in place
+        assert_eq!(show(&mut socket, "work_mem").await, "4MB");
+
+        // A failed SET aborts a transaction block like any failed statement
+        assert_eq!(query(&mut socket, "BEGIN").await.tags, vec!["BEGIN"]);
+        let reply = query(&mut socket, "SET work_mem = 'plenty'").await;
+        assert_eq!((reply.sqlstate(), reply.status), (Some("22023"), b'E'));
+        assert_eq!(query(&mut socket, "SET work_mem = '8MB'").await.sqlstate(), Some("25P02"));
+        assert_eq!(query(&mut socket, "ROLLBACK").await.status, b'I');
+
+        send(&mut socket, b'X', &[]).await;
+        server.await.unwrap();
+    }).await;
+}
+
+#[test]
+fn test_session_command_parsing() {
+    let set = |name: &str, value: Option<&str>| SessionCommand::Set {
+        name: name.to_string(),
+        value: value.map(str::to_string),
+    };
+    assert_eq!(SessionCommand::parse("SET work_mem = '64MB';"), Some(set("work_mem", Some("64MB"))));
+    assert_eq!(SessionCommand::parse("set SESSION Work_Mem TO 65536"), Some(set("work_mem", Some("65536"))));
+    assert_eq!(SessionCommand::parse("SET application_name='it''s me'"), Some(set("application_name", Some("it's me"))));
+    assert_eq!(SessionCommand::parse("SET TIME ZONE DEFAULT"), Some(set("timezone", None)));
+    assert_eq!(SessionCommand::parse("RESET ALL"), Some(SessionCommand::ResetAll));
+    assert_eq!(SessionCommand::parse("reset time zone"), Some(SessionCommand::Reset("timezone".to_string())));
+    assert_eq!(SessionCommand::parse("SHOW ALL"), Some(SessionCommand::ShowAll));
+
+    // Not session settings
+    assert_eq!(SessionCommand::parse("SET LOCAL work_mem = '64MB'"), None);
+    assert_eq!(SessionCommand::parse("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE"), None);
+    assert_eq!(SessionCommand::parse("SET work_mem"), None);
+    assert_eq!(SessionCommand::parse("UPDATE notes SET body = 'x'"), None);
+
+    let mut session = SessionVariables::new(4 * 1024 * 1024);
+    session.set("work_mem", "1024").unwrap();
+    assert_eq!(session.work_mem(), 1024 * 1024);
+    assert!(session.set("work_mem", "32kB").is_err());
+    session.set("statement_timeout", "1500").unwrap();
+    assert_eq!(session.statement_timeout(), Some(Duration::from_millis(1500)));
+    assert_eq!(session.show("statement_timeout").unwrap(), "1500ms");
+    session.set("statement_timeout", "0").unwrap();
+    assert_eq!(session.statement_timeout(), None);
+    assert!(!session.stable_scan_order());
+    session.set("stable_scan_order", "on").unwrap();
+    assert!(session.stable_scan_order());
+    assert_eq!(session.show("stable_scan_order").unwrap(), "on");
+    assert!(session.set("stable_scan_order", "sometimes").is_err());
+    session.reset_all();
+    assert_eq!(session.work_mem_override(), None);
+    assert!(!session.stable_scan_order());
+}
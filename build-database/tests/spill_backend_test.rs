@@ -0,0 +1,184 @@
+//! Spill Backend Tests
+//!
+//! A sort that spills past `work_mem` returns the same rows whether its runs
+//! go to local disk, a tmpfs directory or an object store, writes only where
+//! its backend points, and leaves no segment behind once it is done.
+//! Segments left by a crashed process are removed when the database opens.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use aurora_db::config::{DatabaseConfig, SpillConfig};
+use aurora_db::core::AuroraResult;
+use aurora_db::engine::{AuroraDB, DirectorySpill, ExternalSort, ObjectStore, ObjectStoreSpill, SpillBackend, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir, spill: SpillConfig) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        temp_directory: temp_dir.path().join("temp").to_string_lossy().to_string(),
+        work_mem_bytes: 64 * 1024,
+        spill,
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn tmpfs_config(dir: &Path) -> SpillConfig {
+    SpillConfig {
+        backend: "tmpfs".to_string(),
+        tmpfs_directory: dir.to_string_lossy().to_string(),
+        ..SpillConfig::default()
+    }
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "spill_backend".to_string(),
+    }
+}
+
+async fn populate(db: &AuroraDB, user_context: &UserContext, rows: usize) {
+    db.execute_query("CREATE TABLE readings (id INTEGER PRIMARY KEY, sensor TEXT, note TEXT);", user_context).await.unwrap();
+    for chunk in (0..rows).collect::<Vec<_>>().chunks(250) {
+        let values: Vec<String> = chunk.iter()
+            .map(|id| format!("({}, 'sensor-{}', 'reading {} from the north field')", id, id % 37, id))
+            .collect();
+        let sql = format!("INSERT INTO readings (id, sensor, note) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+/// Temp bytes the sort at the top of the plan wrote
+async fn sort_temp_written(db: &AuroraDB, user_context: &UserContext, sql: &str) -> u64 {
+    let result = db.execute_query(&format!("EXPLAIN (ANALYZE, BUFFERS) {}", sql), user_context).await.unwrap();
+    result.rows.iter()
+        .filter_map(|row| row[0].as_str()?.trim_start().strip_prefix("Buffers: ").map(str::to_string))
+        .flat_map(|buffers| buffers.split(", ").map(str::to_string).collect::<Vec<_>>())
+        .filter_map(|group| group.strip_prefix("temp ").map(str::to_string))
+        .flat_map(|values| values.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .find_map(|value| value.strip_prefix("written=")?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Files left in `dir`, if it exists
+fn files(dir: &Path) -> Vec<String> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => entries.map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// An object store held in memory
+#[derive(Default)]
+struct MemoryStore {
+    objects: Mutex<BTreeMap<String, Vec<u8>>>,
+    puts: Mutex<usize>,
+}
+
+impl ObjectStore for MemoryStore {
+    fn put(&self, key: &str, data: Vec<u8>) -> AuroraResult<()> {
+        *self.puts.lock().unwrap() += 1;
+        self.objects.lock().unwrap().insert(key.to_string(), data);
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> AuroraResult<Vec<u8>> {
+        Ok(self.objects.lock().unwrap()[key].clone())
+    }
+
+    fn delete(&self, key: &str) -> AuroraResult<()> {
+        self.objects.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> AuroraResult<Vec<String>> {
+        Ok(self.objects.lock().unwrap().keys().filter(|key| key.starts_with(prefix)).cloned().collect())
+    }
+}
+
+#[tokio::test]
+async fn test_spilling_sort_matches_on_local_and_tmpfs() {
+    let sql = "SELECT id, note FROM readings ORDER BY note DESC";
+    let user_context = user_context();
+
+    let local_dir = tempdir().unwrap();
+    let local = open(&local_dir, SpillConfig::default()).await;
+    populate(&local, &user_context, 2000).await;
+    assert!(sort_temp_written(&local, &user_context, sql).await > 0);
+    let on_local = local.execute_query(sql, &user_context).await.unwrap().rows;
+    // The temp directory was used, and is empty again
+    assert!(local_dir.path().join("temp").exists());
+    assert_eq!(files(&local_dir.path().join("temp")), Vec::<String>::new());
+
+    let tmpfs_dir = tempdir().unwrap();
+    let spill_dir = tempdir().unwrap();
+    let tmpfs = open(&tmpfs_dir, tmpfs_config(spill_dir.path())).await;
+    populate(&tmpfs, &user_context, 2000).await;
+    assert!(sort_temp_written(&tmpfs, &user_context, sql).await > 0);
+    let on_tmpfs = tmpfs.execute_query(sql, &user_context).await.unwrap().rows;
+    assert!(!tmpfs_dir.path().join("temp").exists());
+    assert_eq!(files(spill_dir.path()), Vec::<String>::new());
+
+    assert_eq!(on_local.len(), 2000);
+    assert_eq!(on_local, on_tmpfs);
+}
+
+#[tokio::test]
+async fn test_orphaned_segments_are_removed_on_open() {
+    let temp_dir = tempdir().unwrap();
+    let spill_dir = tempdir().unwrap();
+    // Left by a process that crashed mid-sort
+    std::fs::write(spill_dir.path().join("sort_run_0123abcd.spill"), b"stale run").unwrap();
+    std::fs::write(spill_dir.path().join("materialized_4567ef01.spill"), b"stale result").unwrap();
+    std::fs::write(spill_dir.path().join("notes.txt"), b"not a segment").unwrap();
+
+    let db = open(&temp_dir, tmpfs_config(spill_dir.path())).await;
+    assert_eq!(files(spill_dir.path()), vec!["notes.txt".to_string()]);
+    assert!(db.spill_backend().segments().unwrap().is_empty());
+}
+
+#[test]
+fn test_external_sort_on_every_backend() {
+    let items: Vec<(u32, String)> = (0..3000).map(|i| ((i * 7919) % 101, format!("item {}", i))).collect();
+    let mut expected = items.clone();
+    expected.sort_by_key(|a| a.0);
+
+    let local_dir = tempdir().unwrap();
+    let tmpfs_dir = tempdir().unwrap();
+    let store = Arc::new(MemoryStore::default());
+    let backends: Vec<Arc<dyn SpillBackend>> = vec![
+        Arc::new(DirectorySpill::local(local_dir.path())),
+        Arc::new(DirectorySpill::tmpfs(tmpfs_dir.path(), 16 * 1024 * 1024)),
+        Arc::new(ObjectStoreSpill::new(store.clone(), "spill/", 1024)),
+    ];
+    for backend in backends {
+        let sorted = ExternalSort::new(4096, local_dir.path(), None)
+            .with_spill_backend(backend.clone())
+            .sort_by(items.clone(), |a, b| a.0.cmp(&b.0))
+            .unwrap();
+        assert_eq!(sorted, expected, "{:?}", backend.kind());
+        assert!(backend.segments().unwrap().is_empty(), "{:?}", backend.kind());
+    }
+    // Runs were uploaded in many parts, all deleted once merged
+    assert!(*store.puts.lock().unwrap() > 10);
+    assert!(store.objects.lock().unwrap().is_empty());
+    assert!(files(local_dir.path()).is_empty() && files(tmpfs_dir.path()).is_empty());
+}
+
+#[test]
+fn test_tmpfs_capacity_fails_the_sort_and_cleans_up() {
+    let tmpfs_dir = tempdir().unwrap();
+    let backend = Arc::new(DirectorySpill::tmpfs(tmpfs_dir.path(), 8 * 1024));
+    let items: Vec<u64> = (0..10_000).rev().collect();
+    let result = ExternalSort::new(4096, tmpfs_dir.path(), None)
+        .with_spill_backend(backend.clone())
+        .sort_by(items, |a, b| a.cmp(b));
+    assert!(result.unwrap_err().to_string().contains("spill capacity of 8192 bytes exhausted"));
+    assert!(files(tmpfs_dir.path()).is_empty());
+    assert_eq!(backend.used_bytes(), 0);
+}
@@ -0,0 +1,102 @@
+//! Stable Scan Order Tests
+//!
+//! With `stable_scan_order` on, a query without ORDER BY returns its rows in
+//! the same order on every run, whatever plan reads them; with it off (the
+//! default) the order is unspecified and an index scan may return another.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, IndexDefinition, IndexType, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+async fn post_ids(db: &AuroraDB, sql: &str, user_context: &UserContext) -> Vec<i64> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    result.rows.iter().map(|row| row[0].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_unordered_select_repeats_its_order() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    // Inserted out of key order, so the GIN index lists them 3, 1, 2
+    db.execute_query("CREATE TABLE posts (id INTEGER PRIMARY KEY, tags TEXT[]);", &user_context).await.unwrap();
+    for id in [3, 1, 2] {
+        db.execute_query(&format!("INSERT INTO posts (id, tags) VALUES ({}, ARRAY['rust']);", id), &user_context).await.unwrap();
+    }
+    let physical = post_ids(&db, "SELECT id FROM posts;", &user_context).await;
+    db.create_index("posts", &IndexDefinition {
+        name: "idx_posts_tags".to_string(),
+        columns: vec!["tags".to_string()],
+        index_type: IndexType::Gin,
+    }, false, &user_context).await.unwrap();
+    let sql = "SELECT id FROM posts WHERE tags @> ARRAY['rust'];";
+
+    // Off: the index scan's order is not the sequential scan's
+    assert!(!db.session_stable_scan_order(&user_context.session_id));
+    let unordered = post_ids(&db, sql, &user_context).await;
+    assert_eq!(unordered, [3, 1, 2]);
+    assert_ne!(unordered, physical);
+
+    // On: physical order, run after run
+    db.set_session_stable_scan_order(&user_context.session_id, true);
+    for _ in 0..5 {
+        assert_eq!(post_ids(&db, sql, &user_context).await, physical);
+        assert_eq!(post_ids(&db, "SELECT id FROM posts;", &user_context).await, physical);
+    }
+    let mut sorted = unordered.clone();
+    sorted.sort();
+    assert_eq!(physical, sorted);
+
+    // The setting ends with the session
+    db.end_session(&user_context.session_id);
+    assert_eq!(post_ids(&db, sql, &user_context).await, unordered);
+}
+
+#[tokio::test]
+async fn test_stable_order_disables_partition_wise_join() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+
+    db.execute_query("CREATE TABLE customers (id INTEGER PRIMARY KEY, name TEXT) PARTITION BY HASH (id) PARTITIONS 4;", &user_context).await.unwrap();
+    db.execute_query("CREATE TABLE orders (order_id INTEGER PRIMARY KEY, customer_id INTEGER) PARTITION BY HASH (customer_id) PARTITIONS 4;", &user_context).await.unwrap();
+    let customers: Vec<String> = (1..=8).map(|id| format!("({}, 'customer_{}')", id, id)).collect();
+    db.execute_query(&format!("INSERT INTO customers (id, name) VALUES {};", customers.join(", ")), &user_context).await.unwrap();
+    let orders: Vec<String> = (1..=8).map(|id| format!("({}, {})", id, id)).collect();
+    db.execute_query(&format!("INSERT INTO orders (order_id, customer_id) VALUES {};", orders.join(", ")), &user_context).await.unwrap();
+
+    // Each customer has the order of the same number, so joined rows follow
+    // the customers scan exactly when the join keeps its outer order
+    let sql = "SELECT order_id FROM customers JOIN orders ON customers.id = orders.customer_id;";
+    let outer = post_ids(&db, "SELECT id FROM customers;", &user_context).await;
+    let partition_wise = post_ids(&db, sql, &user_context).await;
+
+    db.set_session_stable_scan_order(&user_context.session_id, true);
+    let stable = post_ids(&db, sql, &user_context).await;
+    assert_eq!(stable, outer);
+    assert_eq!(post_ids(&db, sql, &user_context).await, stable);
+
+    let (mut partition_wise, mut stable) = (partition_wise, stable);
+    partition_wise.sort();
+    stable.sort();
+    assert_eq!(partition_wise, stable);
+}
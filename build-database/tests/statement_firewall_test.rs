@@ -0,0 +1,263 @@
+//! Statement Firewall Tests
+//!
+//! Connections of a read-only replica refuse every statement that could
+//! write, before it is planned, while reads go through; allowlist modes
+//! accept only the configured statement types or patterns.
+
+use aurora_db::config::{DatabaseConfig, FirewallConfig};
+use aurora_db::engine::{AuroraDB, UserContext};
+use aurora_db::network::{PostgresProtocol, StatementFirewall, StatementKind};
+use aurora_db::query::{FunctionRegistry, FunctionSignature};
+use aurora_db::types::{DataType, DataValue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Server's reply to one simple query
+#[derive(Debug)]
+struct Reply {
+    /// CommandComplete tags
+    tags: Vec<String>,
+    /// SQLSTATE and message of the ErrorResponse, if any
+    error: Option<(String, String)>,
+    /// ReadyForQuery transaction status
+    status: u8,
+}
+
+impl Reply {
+    fn sqlstate(&self) -> Option<&str> {
+        self.error.as_ref().map(|(sqlstate, _)| sqlstate.as_str())
+    }
+}
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+async fn connect(socket: &mut TcpStream) {
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(b"user\0test\0\0");
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(socket).await.0, b'R');
+    send(socket, b'p', b"secret\0").await;
+    assert_eq!(read_message(socket).await.0, b'R');
+    assert_eq!(read_message(socket).await, (b'Z', vec![b'I']));
+}
+
+async fn query(socket: &mut TcpStream, sql: &str) -> Reply {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+
+    let mut reply = Reply { tags: Vec::new(), error: None, status: 0 };
+    loop {
+        let (message_type, body) = read_message(socket).await;
+        match message_type {
+            b'C' => reply.tags.push(String::from_utf8_lossy(&body[..body.len() - 1]).to_string()),
+            b'E' => {
+                let field = |code: u8| body.split(|byte| *byte == 0)
+                    .find(|field| field.first() == Some(&code))
+                    .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+                    .unwrap_or_default();
+                reply.error = Some((field(b'C'), field(b'M')));
+            }
+            b'Z' => {
+                reply.status = body[0];
+                return reply;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "firewall".to_string(),
+    }
+}
+
+/// Database with an `accounts` table holding one row
+async fn open(temp_dir: &TempDir) -> Arc<AuroraDB> {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = AuroraDB::new(config).await.unwrap();
+    let user_context = user_context();
+    db.execute_query("CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO accounts (id, balance) VALUES (1, 100)", &user_context).await.unwrap();
+    Arc::new(db)
+}
+
+/// Serve one connection through `firewall` while `client` drives it
+async fn with_connection<F, Fut>(db: Arc<AuroraDB>, firewall: StatementFirewall, client: F)
+where
+    F: FnOnce(TcpStream) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    let protocol = PostgresProtocol::with_firewall(db, firewall);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = async {
+        let (socket, _) = listener.accept().await.unwrap();
+        protocol.handle_connection(socket).await.unwrap();
+    };
+    let client = async {
+        let mut socket = TcpStream::connect(address).await.unwrap();
+        connect(&mut socket).await;
+        client(socket).await;
+    };
+    tokio::join!(server, client);
+}
+
+#[tokio::test]
+async fn test_read_only_connection_rejects_writes() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    // A function that writes when called, counting its calls
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    db.register_function(
+        "bump_counter",
+        FunctionSignature::new(vec![], DataType::Integer).with_side_effects(),
+        move |_| Ok(DataValue::Integer(counter.fetch_add(1, Ordering::SeqCst) as i64)),
+    ).unwrap();
+
+    with_connection(db.clone(), StatementFirewall::read_only(), |mut socket| async move {
+        let socket = &mut socket;
+        let reply = query(socket, "SELECT id, balance FROM accounts").await;
+        assert_eq!(reply.error, None);
+        assert_eq!(reply.status, b'I');
+
+        let reply = query(socket, "UPDATE accounts SET balance = 0 WHERE id = 1").await;
+        let (sqlstate, message) = reply.error.clone().unwrap();
+        assert_eq!(sqlstate, "25006");
+        assert_eq!(message, "cannot execute UPDATE on a read-only connection");
+
+        for sql in [
+            "INSERT INTO accounts (id, balance) VALUES (2, 5)",
+            "delete from accounts",
+            "DROP TABLE accounts",
+            "SELECT id FROM accounts; DELETE FROM accounts",
+            "WITH gone AS (DELETE FROM accounts RETURNING id) SELECT * FROM gone",
+            "SELECT * INTO copied FROM accounts",
+            "EXPLAIN ANALYZE UPDATE accounts SET balance = 0",
+            "SELECT bump_counter()",
+            "SELECT nextval('account_ids')",
+        ] {
+            assert_eq!(query(socket, sql).await.sqlstate(), Some("25006"), "{}", sql);
+        }
+
+        // Inside a block a refused statement aborts it, like any failure
+        assert_eq!(query(socket, "BEGIN").await.tags, vec!["BEGIN"]);
+        let reply = query(socket, "UPDATE accounts SET balance = 0").await;
+        assert_eq!((reply.sqlstate(), reply.status), (Some("25006"), b'E'));
+        assert_eq!(query(socket, "ROLLBACK").await.status, b'I');
+
+        send(socket, b'X', &[]).await;
+    }).await;
+
+    // Nothing was written and the side-effecting function never ran
+    let result = db.execute_query("SELECT balance FROM accounts WHERE id = 1", &user_context()).await.unwrap();
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0], serde_json::json!(100));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_allowlist_modes() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+
+    // By statement type: reads and inserts, nothing else
+    let firewall = StatementFirewall::statement_types([StatementKind::Select, StatementKind::Insert]);
+    with_connection(db.clone(), firewall, |mut socket| async move {
+        let socket = &mut socket;
+        assert_eq!(query(socket, "SELECT id FROM accounts").await.error, None);
+        assert_eq!(query(socket, "INSERT INTO accounts (id, balance) VALUES (2, 50)").await.error, None);
+
+        let reply = query(socket, "DELETE FROM accounts WHERE id = 2").await;
+        let (sqlstate, message) = reply.error.unwrap();
+        assert_eq!(sqlstate, "42501");
+        assert_eq!(message, "DELETE statements are not allowed on this connection");
+        assert_eq!(query(socket, "CREATE TABLE audit (id INTEGER)").await.sqlstate(), Some("42501"));
+
+        send(socket, b'X', &[]).await;
+    }).await;
+
+    // By pattern, from configuration
+    let config = FirewallConfig {
+        mode: "patterns".to_string(),
+        allowed_statement_types: Vec::new(),
+        allowed_patterns: vec![r"SELECT id, balance FROM accounts( WHERE id = \d+)?".to_string()],
+    };
+    let firewall = StatementFirewall::from_config(&config).unwrap();
+    with_connection(db.clone(), firewall, |mut socket| async move {
+        let socket = &mut socket;
+        let reply = query(socket, "select id, balance from accounts where id = 2;").await;
+        assert_eq!(reply.error, None);
+        assert_eq!(query(socket, "SELECT id, balance FROM accounts").await.error, None);
+
+        // Patterns match whole statements, so nothing can be appended
+        for sql in [
+            "SELECT id FROM accounts",
+            "SELECT id, balance FROM accounts WHERE id = 2 OR 1 = 1",
+            "SELECT id, balance FROM accounts; DELETE FROM accounts",
+        ] {
+            assert_eq!(query(socket, sql).await.sqlstate(), Some("42501"), "{}", sql);
+        }
+
+        send(socket, b'X', &[]).await;
+    }).await;
+
+    let result = db.execute_query("SELECT id FROM accounts", &user_context()).await.unwrap();
+    assert_eq!(result.rows.len(), 2);
+}
+
+#[test]
+fn test_firewall_configuration() {
+    let functions = FunctionRegistry::new();
+    let config = |mode: &str, types: &[&str], patterns: &[&str]| FirewallConfig {
+        mode: mode.to_string(),
+        allowed_statement_types: types.iter().map(|name| name.to_string()).collect(),
+        allowed_patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+    };
+
+    let allow_all = StatementFirewall::from_config(&FirewallConfig::default()).unwrap();
+    assert!(allow_all.check("DROP TABLE accounts", &functions).is_ok());
+
+    // Write keywords inside literals, quoted identifiers and comments are not writes
+    let read_only = StatementFirewall::from_config(&config("read_only", &[], &[])).unwrap();
+    assert!(read_only.check("SELECT 'DELETE FROM accounts' AS note", &functions).is_ok());
+    assert!(read_only.check("SELECT \"update\" FROM accounts /* DROP */", &functions).is_ok());
+    assert!(read_only.check("SHOW work_mem", &functions).is_ok());
+    assert!(read_only.check("VACUUM accounts", &functions).is_err());
+    assert!(read_only.check("SELECT id FROM accounts FOR UPDATE", &functions).is_err());
+
+    let types = StatementFirewall::from_config(&config("statement_types", &["select", "EXPLAIN"], &[])).unwrap();
+    assert!(types.check("EXPLAIN SELECT id FROM accounts", &functions).is_ok());
+    assert!(types.check("EXPLAIN DELETE FROM accounts", &functions).is_err());
+
+    assert!(StatementFirewall::from_config(&config("statement_types", &["selects"], &[])).is_err());
+    assert!(StatementFirewall::from_config(&config("patterns", &[], &["SELECT ("])).is_err());
+    assert!(StatementFirewall::from_config(&config("replica", &[], &[])).is_err());
+}
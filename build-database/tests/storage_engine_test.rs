@@ -0,0 +1,93 @@
+//! Per-Table Storage Engine Tests
+//!
+//! Tables created with `USING {btree|lsm|hybrid|columnar}` are pinned to that
+//! engine, the catalog reports the choice, and CRUD works on every engine.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use aurora_db::query::parser::ast::Query;
+use aurora_db::query::parser::SqlParser;
+use aurora_db::storage::engine::StorageEngineType;
+use tempfile::{tempdir, TempDir};
+
+async fn setup() -> (AuroraDB, UserContext, TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = AuroraDB::new(config).await.unwrap();
+    let user_context = UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    };
+    (db, user_context, temp_dir)
+}
+
+#[tokio::test]
+async fn test_using_clause_parsing() {
+    let mut parser = SqlParser::new();
+
+    let parsed = parser.parse("CREATE TABLE events (id INTEGER, payload TEXT) USING lsm;").await.unwrap();
+    match parsed {
+        Query::CreateTable(create) => assert_eq!(create.storage_engine, Some(StorageEngineType::LSM)),
+        other => panic!("Expected CreateTable query, got {:?}", other),
+    }
+
+    let parsed = parser.parse("CREATE TABLE events (id INTEGER);").await.unwrap();
+    match parsed {
+        Query::CreateTable(create) => assert_eq!(create.storage_engine, None),
+        other => panic!("Expected CreateTable query, got {:?}", other),
+    }
+
+    assert!(parser.parse("CREATE TABLE events (id INTEGER) USING heap;").await.is_err());
+}
+
+#[tokio::test]
+async fn test_crud_on_each_engine() {
+    let (db, user_context, _temp_dir) = setup().await;
+
+    for engine in ["btree", "lsm", "hybrid", "columnar"] {
+        let table = format!("items_{}", engine);
+
+        db.execute_query(
+            &format!("CREATE TABLE {} (id INTEGER PRIMARY KEY, name TEXT NOT NULL) USING {};", table, engine),
+            &user_context,
+        ).await.unwrap();
+        assert_eq!(
+            db.table_storage_engine(&table).await,
+            StorageEngineType::parse(engine),
+            "catalog reports the pinned engine for {}", table
+        );
+
+        db.execute_query(&format!("INSERT INTO {} (id, name) VALUES (1, 'one'), (2, 'two')", table), &user_context).await.unwrap();
+        let result = db.execute_query(&format!("SELECT * FROM {}", table), &user_context).await.unwrap();
+        assert_eq!(result.rows.len(), 2, "insert + select on {}", engine);
+
+        let result = db.execute_query(&format!("UPDATE {} SET name = 'uno' WHERE id = 1", table), &user_context).await.unwrap();
+        assert_eq!(result.rows_affected, Some(1), "update on {}", engine);
+
+        let result = db.execute_query(&format!("DELETE FROM {} WHERE id = 2", table), &user_context).await.unwrap();
+        assert_eq!(result.rows_affected, Some(1), "delete on {}", engine);
+
+        let result = db.execute_query(&format!("SELECT * FROM {}", table), &user_context).await.unwrap();
+        assert_eq!(result.rows.len(), 1, "rows remaining on {}", engine);
+    }
+}
+
+#[tokio::test]
+async fn test_unpinned_tables_use_workload_selection() {
+    let (db, user_context, _temp_dir) = setup().await;
+
+    db.execute_query("CREATE TABLE accounts (id INTEGER);", &user_context).await.unwrap();
+    db.execute_query("CREATE TABLE request_log (id INTEGER);", &user_context).await.unwrap();
+    // USING overrides the heuristic that would otherwise pick LSM for log tables
+    db.execute_query("CREATE TABLE audit_log (id INTEGER) USING btree;", &user_context).await.unwrap();
+
+    assert_eq!(db.table_storage_engine("accounts").await, Some(StorageEngineType::BTree));
+    assert_eq!(db.table_storage_engine("request_log").await, Some(StorageEngineType::LSM));
+    assert_eq!(db.table_storage_engine("audit_log").await, Some(StorageEngineType::BTree));
+}
@@ -0,0 +1,200 @@
+//! Synchronous Commit Tests
+//!
+//! A commit returns once its record is as durable as its
+//! `synchronous_commit` level asks: buffered, flushed locally, flushed on a
+//! synchronous standby, or applied there. Losing the only synchronous
+//! standby either holds commits until it is back or lets them through as
+//! local commits, as configured.
+
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use aurora_db::network::distributed::{
+    ReplicationConfig, ReplicationProtocol, ReplicationRole, SyncFallback, SynchronousCommit,
+};
+use aurora_db::storage::wal_logger::{WALEntry, WALLogger, WALRecord};
+use tempfile::{tempdir, TempDir};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+struct Cluster {
+    _dirs: (TempDir, TempDir),
+    primary_wal: Arc<WALLogger>,
+    standby_wal: Arc<WALLogger>,
+    primary: Arc<ReplicationProtocol>,
+    standby: Arc<ReplicationProtocol>,
+    address: std::net::SocketAddr,
+}
+
+async fn cluster(sync_timeout: Duration, sync_fallback: SyncFallback) -> Cluster {
+    let dirs = (tempdir().unwrap(), tempdir().unwrap());
+    let primary_wal = Arc::new(WALLogger::new(dirs.0.path().to_path_buf()).unwrap());
+    let standby_wal = Arc::new(WALLogger::new(dirs.1.path().to_path_buf()).unwrap());
+    let config = ReplicationConfig {
+        reconnect_interval: Duration::from_millis(50),
+        synchronous_commit: SynchronousCommit::RemoteApply,
+        synchronous_standbys: vec!["standby-1".to_string()],
+        sync_timeout,
+        sync_fallback,
+        ..ReplicationConfig::default()
+    };
+    let primary = Arc::new(ReplicationProtocol::new(ReplicationRole::Master, primary_wal.clone()).with_config(config.clone()));
+    let standby = Arc::new(ReplicationProtocol::new(ReplicationRole::Slave, standby_wal.clone()).with_config(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(primary.clone().serve(listener));
+    Cluster { _dirs: dirs, primary_wal, standby_wal, primary, standby, address }
+}
+
+impl Cluster {
+    /// Follow the primary, passing each entry to `applier`, and return once
+    /// the primary sees the standby connected
+    async fn follow<A>(&self, applier: A) -> JoinHandle<()>
+    where
+        A: FnMut(&WALEntry) -> Result<(), std::io::Error> + Send + 'static,
+    {
+        let (standby, address) = (self.standby.clone(), self.address);
+        let follower = tokio::spawn(async move {
+            let _ = standby.follow(address, "standby-1", applier).await;
+        });
+        timeout(Duration::from_secs(5), async {
+            while !self.primary.slot("standby-1").is_some_and(|slot| slot.active) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("standby did not connect");
+        follower
+    }
+
+    /// Begin `transaction_id` with one insert
+    async fn write(&self, transaction_id: u64) {
+        self.primary_wal.begin_transaction(transaction_id).await.unwrap();
+        self.primary_wal.log_insert(transaction_id, "accounts", &transaction_id.to_be_bytes(), b"value").await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_remote_apply_commit_waits_for_standby_apply() {
+    let cluster = cluster(Duration::from_secs(10), SyncFallback::Wait).await;
+
+    // The standby applies each commit record only when the test lets it
+    let (release, gate) = std_mpsc::channel::<()>();
+    let gate = Mutex::new(gate);
+    let follower = cluster.follow(move |entry: &WALEntry| {
+        if matches!(entry.record, WALRecord::Commit { .. }) {
+            // Off the worker, so the standby's other tasks keep running
+            tokio::task::block_in_place(|| gate.lock().unwrap().recv()).unwrap();
+        }
+        Ok(())
+    }).await;
+
+    cluster.write(1).await;
+    let committing = {
+        let primary = cluster.primary.clone();
+        tokio::spawn(async move { primary.commit(1, Some(SynchronousCommit::RemoteApply)).await })
+    };
+
+    // Begin, insert, commit: the standby has the record but has not applied it
+    let mut flushed = cluster.standby_wal.subscribe_flushed();
+    timeout(Duration::from_secs(5), flushed.wait_for(|lsn| *lsn >= 3)).await.unwrap().unwrap();
+    timeout(Duration::from_secs(5), cluster.primary.wait_for_standby("standby-1", 3, SynchronousCommit::RemoteWrite))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!committing.is_finished());
+    assert!(cluster.standby.applied_lsn() < 3);
+
+    release.send(()).unwrap();
+    let ack = timeout(Duration::from_secs(5), committing).await.unwrap().unwrap().unwrap();
+    assert_eq!((ack.lsn, ack.level, ack.standby.as_deref(), ack.degraded), (3, SynchronousCommit::RemoteApply, Some("standby-1"), false));
+    assert!(cluster.standby.applied_lsn() >= 3);
+
+    // remote_write only needs the standby's flush, not its apply
+    cluster.write(2).await;
+    let ack = timeout(Duration::from_secs(5), cluster.primary.commit(2, Some(SynchronousCommit::RemoteWrite)))
+        .await
+        .expect("remote_write commit waited for the apply")
+        .unwrap();
+    assert_eq!((ack.lsn, ack.degraded), (6, false));
+    assert!(cluster.standby.applied_lsn() < 6);
+
+    release.send(()).unwrap();
+    follower.abort();
+}
+
+#[tokio::test]
+async fn test_off_and_local_commits_do_not_wait_for_a_standby() {
+    // No standby is ever connected, and the fallback would wait forever
+    let cluster = cluster(Duration::from_secs(60), SyncFallback::Wait).await;
+    assert_eq!(cluster.primary.synchronous_commit(), SynchronousCommit::RemoteApply);
+
+    // off returns before its commit record reaches disk
+    cluster.write(1).await;
+    let ack = timeout(Duration::from_secs(1), cluster.primary.commit(1, Some(SynchronousCommit::Off))).await.unwrap().unwrap();
+    assert_eq!((ack.lsn, ack.standby, ack.degraded), (3, None, false));
+    assert!(cluster.primary_wal.flushed_lsn() < 3);
+
+    // local forces it, and the buffered one with it
+    cluster.write(2).await;
+    let ack = timeout(Duration::from_secs(1), cluster.primary.commit(2, Some(SynchronousCommit::Local))).await.unwrap().unwrap();
+    assert_eq!(ack.lsn, 6);
+    assert_eq!(cluster.primary_wal.flushed_lsn(), 6);
+
+    // Transactions that do not choose take the global level
+    cluster.primary.set_synchronous_commit(SynchronousCommit::parse("LOCAL").unwrap());
+    cluster.write(3).await;
+    let ack = timeout(Duration::from_secs(1), cluster.primary.commit(3, None)).await.unwrap().unwrap();
+    assert_eq!(ack.level, SynchronousCommit::Local);
+    assert_eq!(SynchronousCommit::parse("remote_write"), Some(SynchronousCommit::RemoteWrite));
+    assert_eq!(SynchronousCommit::parse("on"), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_losing_sole_sync_standby_degrades_when_configured() {
+    let cluster = cluster(Duration::from_secs(10), SyncFallback::DegradeToAsync).await;
+    let follower = cluster.follow(|_: &WALEntry| Ok(())).await;
+
+    cluster.write(1).await;
+    let ack = timeout(Duration::from_secs(5), cluster.primary.commit(1, None)).await.unwrap().unwrap();
+    assert_eq!((ack.standby.as_deref(), ack.degraded), (Some("standby-1"), false));
+
+    // Without its standby the commit goes through at once, only locally
+    follower.abort();
+    cluster.write(2).await;
+    let started = Instant::now();
+    let ack = timeout(Duration::from_secs(5), cluster.primary.commit(2, None)).await.unwrap().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!((ack.lsn, ack.standby, ack.degraded), (6, None, true));
+    assert_eq!(cluster.primary_wal.flushed_lsn(), 6);
+    assert_eq!(cluster.primary.stats().degraded_commits, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_losing_sole_sync_standby_holds_commits_when_configured_to_wait() {
+    let cluster = cluster(Duration::from_millis(100), SyncFallback::Wait).await;
+    let follower = cluster.follow(|_: &WALEntry| Ok(())).await;
+    follower.abort();
+    timeout(Duration::from_secs(5), async {
+        while cluster.primary.slot("standby-1").is_some_and(|slot| slot.active) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("primary did not notice the standby leave");
+
+    cluster.write(1).await;
+    let committing = {
+        let primary = cluster.primary.clone();
+        tokio::spawn(async move { primary.commit(1, None).await })
+    };
+
+    // Several timeouts pass without the commit returning
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!committing.is_finished());
+
+    // The standby comes back, catches up, and releases it
+    let follower = cluster.follow(|_: &WALEntry| Ok(())).await;
+    let ack = timeout(Duration::from_secs(5), committing).await.unwrap().unwrap().unwrap();
+    assert_eq!((ack.lsn, ack.standby.as_deref(), ack.degraded), (3, Some("standby-1"), false));
+    assert_eq!(cluster.primary.stats().degraded_commits, 0);
+    follower.abort();
+}
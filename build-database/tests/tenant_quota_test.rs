@@ -0,0 +1,226 @@
+//! Tenant Quota Tests
+//!
+//! A tenant over its connection or query-rate quota is refused with an
+//! error naming the resource, while another tenant on the same server keeps
+//! working; statement-level quotas on memory and storage are enforced by the
+//! engine itself.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, QuotaResource, TenantQuota, UserContext};
+use aurora_db::network::PostgresProtocol;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+/// SQLSTATE and message of an ErrorResponse body
+fn error_fields(body: &[u8]) -> (String, String) {
+    let field = |code: u8| body.split(|byte| *byte == 0)
+        .find(|field| field.first() == Some(&code))
+        .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+        .unwrap_or_default();
+    (field(b'C'), field(b'M'))
+}
+
+/// Log in as `user`; the server's error if it refuses the connection
+async fn connect(address: SocketAddr, user: &str) -> Result<TcpStream, (String, String)> {
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(format!("user\0{}\0\0", user).as_bytes());
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(&mut socket).await.0, b'R');
+    send(&mut socket, b'p', b"secret\0").await;
+    match read_message(&mut socket).await {
+        (b'R', _) => {}
+        (b'E', body) => return Err(error_fields(&body)),
+        (other, _) => panic!("unexpected message {}", other as char),
+    }
+    assert_eq!(read_message(&mut socket).await, (b'Z', vec![b'I']));
+    Ok(socket)
+}
+
+/// Run a simple query; the SQLSTATE and message if it failed
+async fn query(socket: &mut TcpStream, sql: &str) -> Result<(), (String, String)> {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+    let mut error = None;
+    loop {
+        match read_message(socket).await {
+            (b'E', body) => error = Some(error_fields(&body)),
+            (b'Z', _) => return error.map_or(Ok(()), Err),
+            _ => {}
+        }
+    }
+}
+
+/// Close a connection and wait for the server to hang up
+async fn disconnect(mut socket: TcpStream) {
+    send(&mut socket, b'X', &[]).await;
+    let mut rest = Vec::new();
+    socket.read_to_end(&mut rest).await.unwrap();
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "tenant_quota".to_string(),
+    }
+}
+
+/// Database with an `accounts` table holding one row
+async fn open(temp_dir: &TempDir) -> Arc<AuroraDB> {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = AuroraDB::new(config).await.unwrap();
+    let user_context = user_context();
+    db.execute_query("CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)", &user_context).await.unwrap();
+    db.execute_query("INSERT INTO accounts (id, balance) VALUES (1, 100)", &user_context).await.unwrap();
+    Arc::new(db)
+}
+
+/// Serve every connection to a new listener on the current `LocalSet`
+async fn serve(db: Arc<AuroraDB>) -> SocketAddr {
+    let protocol = Arc::new(PostgresProtocol::new(db));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::task::spawn_local(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            let protocol = protocol.clone();
+            tokio::task::spawn_local(async move {
+                let _ = protocol.handle_connection(socket).await;
+            });
+        }
+    });
+    address
+}
+
+#[tokio::test]
+async fn test_connection_quota_throttles_only_its_tenant() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let governor = db.tenant_governor().clone();
+    governor.assign_role("acme_app", "acme");
+    governor.assign_role("acme_reports", "acme");
+    governor.set_quota("acme", TenantQuota { max_connections: Some(2), ..TenantQuota::default() });
+
+    tokio::task::LocalSet::new().run_until(async {
+        let address = serve(db.clone()).await;
+
+        // Both of acme's roles share its two connections
+        let first = connect(address, "acme_app").await.unwrap();
+        let mut second = connect(address, "acme_reports").await.unwrap();
+        let (sqlstate, message) = connect(address, "acme_app").await.unwrap_err();
+        assert_eq!(sqlstate, "53300");
+        assert_eq!(message, "tenant \"acme\" exceeded its connections quota of 2");
+
+        // Another tenant connects and queries as usual
+        let mut others = Vec::new();
+        for _ in 0..3 {
+            let mut socket = connect(address, "globex").await.unwrap();
+            query(&mut socket, "SELECT id FROM accounts").await.unwrap();
+            others.push(socket);
+        }
+        query(&mut second, "SELECT id FROM accounts").await.unwrap();
+
+        let acme = governor.usage("acme");
+        assert_eq!((acme.connections, acme.rejected.get(&QuotaResource::Connections)), (2, Some(&1)));
+        let globex = governor.usage("globex");
+        assert_eq!((globex.connections, globex.total_rejected()), (3, 0));
+
+        // A closed connection frees its slot
+        disconnect(first).await;
+        assert_eq!(governor.usage("acme").connections, 1);
+        connect(address, "acme_app").await.unwrap();
+    }).await;
+}
+
+#[tokio::test]
+async fn test_query_rate_quota_throttles_only_its_tenant() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let governor = db.tenant_governor().clone();
+    governor.set_quota("acme", TenantQuota { queries_per_second: Some(2), ..TenantQuota::default() });
+
+    tokio::task::LocalSet::new().run_until(async {
+        let address = serve(db.clone()).await;
+        let mut acme = connect(address, "acme").await.unwrap();
+        let mut globex = connect(address, "globex").await.unwrap();
+
+        // A burst of one second's worth goes through, the rest is refused
+        query(&mut acme, "SELECT id FROM accounts").await.unwrap();
+        query(&mut acme, "SELECT id FROM accounts").await.unwrap();
+        let (sqlstate, message) = query(&mut acme, "SELECT id FROM accounts").await.unwrap_err();
+        assert_eq!(sqlstate, "53000");
+        assert_eq!(message, "tenant \"acme\" exceeded its queries per second quota of 2");
+
+        for _ in 0..10 {
+            query(&mut globex, "SELECT id FROM accounts").await.unwrap();
+        }
+
+        // The bucket refills over time
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        query(&mut acme, "SELECT id FROM accounts").await.unwrap();
+
+        let usage = governor.usage("acme");
+        assert_eq!((usage.queries, usage.rejected.get(&QuotaResource::QueriesPerSecond)), (3, Some(&1)));
+        assert_eq!(governor.usage("globex").queries, 10);
+        assert_eq!(governor.usage("globex").total_rejected(), 0);
+    }).await;
+}
+
+#[tokio::test]
+async fn test_memory_and_storage_quotas() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    let governor = db.tenant_governor();
+
+    // Rows written so far count against the tenant
+    let written = governor.usage("test_user").storage_bytes;
+    assert!(written > 0);
+
+    // Writes stop once the quota is used; reads go on
+    governor.set_quota("test_user", TenantQuota { storage_bytes: Some(written), ..TenantQuota::default() });
+    let error = db.execute_query("INSERT INTO accounts (id, balance) VALUES (2, 5)", &user_context).await.unwrap_err();
+    assert_eq!(error.context.get("sqlstate").map(String::as_str), Some("53100"));
+    assert_eq!(error.message, format!("tenant \"test_user\" exceeded its storage quota of {} bytes", written));
+    db.execute_query("SELECT id FROM accounts", &user_context).await.unwrap();
+
+    // Deleting rows gives their bytes back
+    db.execute_query("DELETE FROM accounts WHERE id = 1", &user_context).await.unwrap();
+    assert_eq!(governor.usage("test_user").storage_bytes, 0);
+    db.execute_query("INSERT INTO accounts (id, balance) VALUES (2, 5)", &user_context).await.unwrap();
+
+    // Each statement reserves the session's work_mem against the memory quota
+    db.set_session_work_mem(&user_context.session_id, 64 * 1024);
+    governor.set_quota("test_user", TenantQuota { memory_bytes: Some(32 * 1024), ..TenantQuota::default() });
+    let error = db.execute_query("SELECT id FROM accounts", &user_context).await.unwrap_err();
+    assert_eq!(error.context.get("resource").map(String::as_str), Some("memory"));
+    db.set_session_work_mem(&user_context.session_id, 16 * 1024);
+    db.execute_query("SELECT id FROM accounts", &user_context).await.unwrap();
+    assert_eq!(governor.usage("test_user").memory_bytes, 0);
+}
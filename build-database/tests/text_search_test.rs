@@ -0,0 +1,76 @@
+//! Full-Text Search Tests
+//!
+//! `@@` and `match()` filter rows with the same analyzer the full-text index
+//! uses, and `ts_rank()` scores them with BM25 so results can be ordered by
+//! relevance.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+async fn load_articles(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE articles (id INTEGER PRIMARY KEY, body TEXT);", user_context).await.unwrap();
+    db.execute_query(
+        "INSERT INTO articles (id, body) VALUES \
+         (1, 'Indexing strategies for columnar databases'), \
+         (2, 'A database index speeds up database queries and index scans'), \
+         (3, 'The planner rewrites each query before execution'), \
+         (4, 'How the query planner picks a join order');",
+        user_context,
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ranked_multi_term_query() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_articles(&db, &user_context).await;
+
+    let sql = "SELECT id, ts_rank(body, 'databases indexed') AS rank FROM articles \
+               WHERE body @@ 'databases indexed' ORDER BY rank DESC";
+    let result = db.execute_query(sql, &user_context).await.unwrap();
+    // Stemming matches `database`/`index`; article 2 repeats both terms
+    assert_eq!(result.rows.len(), 2);
+    assert_eq!(result.rows[0][0], serde_json::json!(2));
+    assert_eq!(result.rows[1][0], serde_json::json!(1));
+
+    let either = db.execute_query("SELECT id FROM articles WHERE body @@ 'columnar OR join'", &user_context).await.unwrap();
+    assert_eq!(either.rows.len(), 2);
+}
+
+#[tokio::test]
+async fn test_phrase_query_distinguishes_word_order() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_articles(&db, &user_context).await;
+
+    // Both articles mention query and planner; only one in this order
+    let terms = db.execute_query("SELECT id FROM articles WHERE body @@ 'query planner'", &user_context).await.unwrap();
+    assert_eq!(terms.rows.len(), 2);
+
+    let phrase = db.execute_query("SELECT id FROM articles WHERE MATCH(body, '\"query planner\"')", &user_context).await.unwrap();
+    assert_eq!(phrase.rows.len(), 1);
+    assert_eq!(phrase.rows[0][0], serde_json::json!(4));
+
+    assert!(db.execute_query("SELECT id FROM articles WHERE body @@ '\"unterminated'", &user_context).await.is_err());
+}
@@ -0,0 +1,156 @@
+//! Time Series Snapshot Export Tests
+//!
+//! Exports a table while a writer keeps appending to it, late writes into
+//! old chunks included, and checks the export holds exactly the writes
+//! committed by the snapshot LSN; an export interrupted partway and resumed
+//! produces the same snapshot as one run straight through.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use aurora_db::timeseries::{read_export, Lsn, SnapshotExport, SnapshotPoint, TimeSeriesTable};
+use tempfile::tempdir;
+
+const BASE: i64 = 1_700_000_040_000;
+const MINUTE: i64 = 60_000;
+const SERIES: u64 = 4;
+
+/// Points of each series, ordered by timestamp
+type SeriesPoints = BTreeMap<u64, Vec<(i64, f64)>>;
+
+/// Ten minutes of one point per second for each series
+fn populate(table: &TimeSeriesTable) -> Vec<(Lsn, u64, i64, f64)> {
+    let mut written = Vec::new();
+    for second in 0..600 {
+        for series_id in 1..=SERIES {
+            let timestamp = BASE + second * 1000;
+            let value = series_id as f64 * 1000.0 + second as f64 / 7.0;
+            written.push((table.write(series_id, timestamp, value), series_id, timestamp, value));
+        }
+    }
+    written
+}
+
+fn by_series(points: impl Iterator<Item = (u64, i64, f64)>) -> SeriesPoints {
+    let mut series = SeriesPoints::new();
+    for (series_id, timestamp, value) in points {
+        series.entry(series_id).or_default().push((timestamp, value));
+    }
+    for points in series.values_mut() {
+        points.sort_by_key(|(timestamp, _)| *timestamp);
+    }
+    series
+}
+
+fn exported(dir: &std::path::Path) -> SeriesPoints {
+    let chunks = read_export(dir).unwrap();
+    by_series(chunks.into_iter().flat_map(|chunk| {
+        let series_id = chunk.series_id;
+        chunk.points.into_iter().map(move |(timestamp, value)| (series_id, timestamp, value))
+    }))
+}
+
+fn table_at(table: &TimeSeriesTable, lsn: Lsn) -> SeriesPoints {
+    (1..=SERIES)
+        .map(|series_id| (series_id, table.scan_at(series_id, lsn)))
+        .filter(|(_, points)| !points.is_empty())
+        .collect()
+}
+
+#[test]
+fn test_concurrent_writes_stay_out_of_the_snapshot() {
+    let table = TimeSeriesTable::new(MINUTE).unwrap();
+    let written = Mutex::new(populate(&table));
+    let export_dir = tempdir().unwrap();
+    let done = AtomicBool::new(false);
+
+    let snapshot_lsn = std::thread::scope(|scope| {
+        // Keeps writing current points and late points into old chunks
+        scope.spawn(|| {
+            let mut n = 0i64;
+            while !done.load(Ordering::Relaxed) {
+                let series_id = (n as u64 % SERIES) + 1;
+                let timestamp = if n % 2 == 0 { BASE + 600_000 + n } else { BASE + (n * 7919) % 600_000 };
+                let value = -(n as f64);
+                let lsn = table.write(series_id, timestamp, value);
+                written.lock().unwrap().push((lsn, series_id, timestamp, value));
+                n += 1;
+                if n % 16 == 0 {
+                    std::thread::sleep(Duration::from_micros(50));
+                }
+            }
+        });
+
+        std::thread::sleep(Duration::from_millis(5));
+        let mut export = SnapshotExport::begin(&table, SnapshotPoint::Lsn(table.current_lsn()), export_dir.path()).unwrap();
+        while !export.is_complete() {
+            export.export_chunks(&table, 1).unwrap();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        done.store(true, Ordering::Relaxed);
+        export.snapshot_lsn()
+    });
+
+    let written = written.into_inner().unwrap();
+    let late = written.iter().filter(|(lsn, _, timestamp, _)| *lsn > snapshot_lsn && *timestamp < BASE + 600_000).count();
+    assert!(late > 0, "no late writes landed during the export");
+
+    let expected = by_series(written.iter()
+        .filter(|(lsn, ..)| *lsn <= snapshot_lsn)
+        .map(|(_, series_id, timestamp, value)| (*series_id, *timestamp, *value)));
+    let snapshot = exported(export_dir.path());
+    assert_eq!(snapshot, expected);
+    assert_eq!(snapshot, table_at(&table, snapshot_lsn));
+    assert!(table.current_lsn() > snapshot_lsn);
+}
+
+#[test]
+fn test_interrupted_export_resumes_to_the_same_snapshot() {
+    let table = TimeSeriesTable::new(MINUTE).unwrap();
+    populate(&table);
+    let snapshot_lsn = table.current_lsn();
+
+    let interrupted_dir = tempdir().unwrap();
+    let mut export = SnapshotExport::begin(&table, SnapshotPoint::Lsn(snapshot_lsn), interrupted_dir.path()).unwrap();
+    assert_eq!(export.export_chunks(&table, 15).unwrap(), 15);
+    drop(export);
+    assert!(read_export(interrupted_dir.path()).is_err());
+    // A second export may not start over the first
+    assert!(SnapshotExport::begin(&table, SnapshotPoint::Lsn(snapshot_lsn), interrupted_dir.path()).is_err());
+
+    // Writes go on while the export is stopped, late ones included
+    for n in 0..200 {
+        table.write(n % SERIES + 1, BASE + n as i64 * 2500, 0.5);
+    }
+
+    let mut export = SnapshotExport::resume(interrupted_dir.path()).unwrap();
+    assert_eq!((export.snapshot_lsn(), export.remaining()), (snapshot_lsn, 25));
+    let summary = export.run(&table).unwrap();
+    assert_eq!((summary.chunks, summary.points), (40, 2400));
+
+    let straight_dir = tempdir().unwrap();
+    SnapshotExport::begin(&table, SnapshotPoint::Lsn(snapshot_lsn), straight_dir.path()).unwrap().run(&table).unwrap();
+    assert_eq!(read_export(interrupted_dir.path()).unwrap(), read_export(straight_dir.path()).unwrap());
+    assert_eq!(exported(interrupted_dir.path()), table_at(&table, snapshot_lsn));
+}
+
+#[test]
+fn test_snapshot_points() {
+    let table = TimeSeriesTable::new(MINUTE).unwrap();
+    populate(&table);
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+
+    // A commit time pins the last LSN committed by then
+    let dir = tempdir().unwrap();
+    let export = SnapshotExport::begin(&table, SnapshotPoint::CommitTime(now), dir.path()).unwrap();
+    assert_eq!(export.snapshot_lsn(), table.current_lsn());
+    let dir = tempdir().unwrap();
+    let export = SnapshotExport::begin(&table, SnapshotPoint::CommitTime(now - 3_600_000), dir.path()).unwrap();
+    assert_eq!(export.snapshot_lsn(), 0);
+    assert!(export.manifest().chunk_ids.is_empty());
+
+    // Writes yet to commit could land inside a snapshot past the last LSN
+    let dir = tempdir().unwrap();
+    assert!(SnapshotExport::begin(&table, SnapshotPoint::Lsn(table.current_lsn() + 1), dir.path()).is_err());
+}
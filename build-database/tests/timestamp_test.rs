@@ -0,0 +1,86 @@
+//! Time Zone Tests
+//!
+//! TIMESTAMPTZ columns store UTC instants. The session `timezone` reads
+//! zone-less input and renders output, but two sessions in different zones
+//! see the same stored instant.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context(session_id: &str) -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: session_id.to_string(),
+    }
+}
+
+async fn single_value(db: &AuroraDB, sql: &str, user_context: &UserContext) -> serde_json::Value {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 1, "{}", sql);
+    result.rows[0][0].clone()
+}
+
+#[tokio::test]
+async fn test_session_timezone_changes_rendering_not_storage() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let tokyo = user_context("tokyo");
+    let new_york = user_context("new_york");
+    db.set_session_timezone("tokyo", "Asia/Tokyo").unwrap();
+    db.set_session_timezone("new_york", "America/New_York").unwrap();
+
+    db.execute_query("CREATE TABLE events (id INTEGER PRIMARY KEY, happened_at TIMESTAMP WITH TIME ZONE, local_time TIMESTAMP);", &tokyo).await.unwrap();
+    // Read as Tokyo time: 2024-07-01 03:00:00 UTC
+    db.execute_query("INSERT INTO events (id, happened_at, local_time) VALUES (1, '2024-07-01 12:00:00', '2024-07-01 12:00:00');", &tokyo).await.unwrap();
+
+    let sql = "SELECT happened_at FROM events";
+    assert_eq!(single_value(&db, sql, &tokyo).await, serde_json::json!("2024-07-01 12:00:00+09"));
+    assert_eq!(single_value(&db, sql, &new_york).await, serde_json::json!("2024-06-30 23:00:00-04"));
+
+    // Without time zone, every session sees the same wall-clock reading
+    let sql = "SELECT local_time FROM events";
+    assert_eq!(single_value(&db, sql, &tokyo).await, single_value(&db, sql, &new_york).await);
+
+    // Same stored instant, so the fingerprints agree once rendered the same way
+    db.reset_session_timezone("tokyo");
+    db.reset_session_timezone("new_york");
+    let a = db.execute_query("SELECT happened_at FROM events", &tokyo).await.unwrap().fingerprint();
+    let b = db.execute_query("SELECT happened_at FROM events", &new_york).await.unwrap().fingerprint();
+    assert_eq!(a, b);
+}
+
+#[tokio::test]
+async fn test_comparison_and_conversion_across_zones() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let paris = user_context("paris");
+    db.set_session_timezone("paris", "Europe/Paris").unwrap();
+
+    db.execute_query("CREATE TABLE events (id INTEGER PRIMARY KEY, happened_at TIMESTAMPTZ);", &paris).await.unwrap();
+    db.execute_query("INSERT INTO events (id, happened_at) VALUES (1, '2024-01-01 12:00:00+02');", &paris).await.unwrap();
+
+    // Every spelling of the same instant matches
+    for literal in ["2024-01-01 10:00:00Z", "2024-01-01T05:00:00-05:00", "2024-01-01 11:00:00", "2024-01-01 19:00:00 Asia/Tokyo"] {
+        let sql = format!("SELECT id FROM events WHERE happened_at = '{}'", literal);
+        assert_eq!(db.execute_query(&sql, &paris).await.unwrap().rows.len(), 1, "{}", literal);
+    }
+    let sql = "SELECT id FROM events WHERE happened_at = '2024-01-01 12:00:00'";
+    assert!(db.execute_query(sql, &paris).await.unwrap().rows.is_empty());
+
+    let converted = single_value(&db, "SELECT happened_at AT TIME ZONE 'America/Los_Angeles' FROM events", &paris).await;
+    assert_eq!(converted, serde_json::json!("2024-01-01 02:00:00"));
+
+    assert!(db.set_session_timezone("paris", "Nowhere/Special").is_err());
+}
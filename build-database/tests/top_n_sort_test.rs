@@ -0,0 +1,101 @@
+//! Top-N Sort Tests
+//!
+//! `ORDER BY ... LIMIT` is answered by a bounded heap rather than a full
+//! sort, returning exactly the rows a full sort followed by the limit would,
+//! ties included, and shows up in EXPLAIN as a top-N sort.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        temp_directory: temp_dir.path().join("temp").to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "top_n_sort".to_string(),
+    }
+}
+
+/// `scores` rows with pseudo-random, heavily repeated keys
+async fn populate(db: &AuroraDB, user_context: &UserContext, rows: u64) {
+    db.execute_query("CREATE TABLE scores (id INTEGER PRIMARY KEY, team INTEGER, points INTEGER);", user_context).await.unwrap();
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let ids: Vec<u64> = (0..rows).collect();
+    for chunk in ids.chunks(250) {
+        let values: Vec<String> = chunk.iter()
+            .map(|id| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                format!("({}, {}, {})", id, state % 5, (state >> 16) % 50)
+            })
+            .collect();
+        let sql = format!("INSERT INTO scores (id, team, points) VALUES {};", values.join(", "));
+        db.execute_query(&sql, user_context).await.unwrap();
+    }
+}
+
+async fn rows(db: &AuroraDB, user_context: &UserContext, sql: &str) -> Vec<String> {
+    let result = db.execute_query(sql, user_context).await.unwrap();
+    result.rows.iter().map(|row| format!("{:?}", row)).collect()
+}
+
+#[tokio::test]
+async fn test_top_n_matches_full_sort_and_limit() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    populate(&db, &user_context, 2000).await;
+
+    // Multi-key orderings, with ties on every key left to input order
+    for order_by in ["points DESC", "team, points DESC", "team DESC, points", "points, team, id"] {
+        let sorted = rows(&db, &user_context, &format!("SELECT id, team, points FROM scores ORDER BY {}", order_by)).await;
+        for limit in [0, 1, 7, 150, 2000, 5000] {
+            let top = rows(&db, &user_context, &format!("SELECT id, team, points FROM scores ORDER BY {} LIMIT {}", order_by, limit)).await;
+            let expected: Vec<String> = sorted.iter().take(limit).cloned().collect();
+            assert_eq!(top, expected, "ORDER BY {} LIMIT {}", order_by, limit);
+        }
+    }
+
+    // Filtered input goes through the heap the same way
+    let sorted = rows(&db, &user_context, "SELECT id FROM scores WHERE team = 3 ORDER BY points DESC").await;
+    let top = rows(&db, &user_context, "SELECT id FROM scores WHERE team = 3 ORDER BY points DESC LIMIT 25").await;
+    assert_eq!(top, sorted[..25].to_vec());
+}
+
+#[tokio::test]
+async fn test_explain_shows_top_n_sort() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    populate(&db, &user_context, 100).await;
+
+    let explain = |sql: &'static str| {
+        let db = &db;
+        let user_context = &user_context;
+        async move {
+            let result = db.execute_query(sql, user_context).await.unwrap();
+            result.rows.iter().map(|row| row[0].as_str().unwrap().to_string()).collect::<Vec<String>>()
+        }
+    };
+
+    let lines = explain("EXPLAIN SELECT id FROM scores ORDER BY points DESC LIMIT 10").await;
+    assert_eq!(lines[0], "Top-N Sort (limit 10)", "{:?}", lines);
+    let lines = explain("EXPLAIN ANALYZE SELECT id FROM scores ORDER BY points DESC LIMIT 10").await;
+    assert!(lines[0].starts_with("Top-N Sort (limit 10) (actual rows=10)"), "{:?}", lines);
+
+    // Without a LIMIT every row is sorted
+    let lines = explain("EXPLAIN SELECT id FROM scores ORDER BY points DESC").await;
+    assert_eq!(lines[0], "Sort", "{:?}", lines);
+}
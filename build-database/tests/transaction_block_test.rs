@@ -0,0 +1,151 @@
+//! Aborted Transaction Block Tests
+//!
+//! Drives a PostgreSQL protocol connection with the simple query protocol:
+//! a failing statement inside BEGIN aborts the block, later statements are
+//! refused with SQLSTATE 25P02, and ROLLBACK or ROLLBACK TO SAVEPOINT make
+//! the connection usable again.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::AuroraDB;
+use aurora_db::network::PostgresProtocol;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Server's reply to one simple query
+#[derive(Debug)]
+struct Reply {
+    /// CommandComplete tags
+    tags: Vec<String>,
+    /// SQLSTATE of the ErrorResponse, if any
+    error: Option<String>,
+    /// ReadyForQuery transaction status
+    status: u8,
+}
+
+async fn read_message(socket: &mut TcpStream) -> (u8, Vec<u8>) {
+    let message_type = socket.read_u8().await.unwrap();
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    (message_type, body)
+}
+
+async fn send(socket: &mut TcpStream, message_type: u8, body: &[u8]) {
+    let mut message = vec![message_type];
+    message.extend_from_slice(&((body.len() + 4) as u32).to_be_bytes());
+    message.extend_from_slice(body);
+    socket.write_all(&message).await.unwrap();
+}
+
+async fn connect(socket: &mut TcpStream) {
+    let mut startup = 196608u32.to_be_bytes().to_vec();
+    startup.extend_from_slice(b"user\0test\0\0");
+    let mut message = ((startup.len() + 4) as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&startup);
+    socket.write_all(&message).await.unwrap();
+
+    assert_eq!(read_message(socket).await.0, b'R');
+    send(socket, b'p', b"secret\0").await;
+    assert_eq!(read_message(socket).await.0, b'R');
+    assert_eq!(read_message(socket).await, (b'Z', vec![b'I']));
+}
+
+async fn query(socket: &mut TcpStream, sql: &str) -> Reply {
+    send(socket, b'Q', format!("{}\0", sql).as_bytes()).await;
+
+    let mut reply = Reply { tags: Vec::new(), error: None, status: 0 };
+    loop {
+        let (message_type, body) = read_message(socket).await;
+        match message_type {
+            b'C' => reply.tags.push(String::from_utf8_lossy(&body[..body.len() - 1]).to_string()),
+            b'E' => {
+                reply.error = body.split(|byte| *byte == 0)
+                    .find(|field| field.first() == Some(&b'C'))
+                    .map(|field| String::from_utf8_lossy(&field[1..]).to_string());
+            }
+            b'Z' => {
+                reply.status = body[0];
+                return reply;
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn run_client(socket: &mut TcpStream) {
+    connect(socket).await;
+    let reply = query(socket, "CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)").await;
+    assert!(reply.error.is_none(), "{:?}", reply);
+
+    // A failing statement aborts the block
+    assert_eq!(query(socket, "BEGIN").await.tags, vec!["BEGIN"]);
+    let reply = query(socket, "INSERT INTO accounts (id, balance) VALUES (1, 100)").await;
+    assert_eq!((reply.error, reply.status), (None, b'T'));
+    let reply = query(socket, "SELECT * FROM missing_table").await;
+    assert!(reply.error.is_some());
+    assert_eq!(reply.status, b'E');
+
+    // Until it is rolled back, every other statement is refused
+    for sql in ["SELECT * FROM accounts", "INSERT INTO accounts (id, balance) VALUES (2, 5)", "SAVEPOINT late", "BEGIN"] {
+        let reply = query(socket, sql).await;
+        assert_eq!(reply.error.as_deref(), Some("25P02"), "{}", sql);
+        assert_eq!(reply.status, b'E');
+    }
+    let reply = query(socket, "ROLLBACK").await;
+    assert_eq!((reply.tags, reply.error, reply.status), (vec!["ROLLBACK".to_string()], None, b'I'));
+
+    // ROLLBACK TO SAVEPOINT recovers the block, which can then commit
+    query(socket, "BEGIN").await;
+    assert_eq!(query(socket, "SAVEPOINT before_transfer").await.tags, vec!["SAVEPOINT"]);
+    assert_eq!(query(socket, "SELECT * FROM missing_table").await.status, b'E');
+    assert_eq!(query(socket, "SELECT * FROM accounts").await.error.as_deref(), Some("25P02"));
+
+    // Naming a savepoint that was never taken does not recover it
+    let reply = query(socket, "ROLLBACK TO SAVEPOINT never_taken").await;
+    assert_eq!((reply.error.as_deref(), reply.status), (Some("3B001"), b'E'));
+
+    let reply = query(socket, "ROLLBACK TO SAVEPOINT before_transfer").await;
+    assert_eq!((reply.error, reply.status), (None, b'T'));
+    let reply = query(socket, "SELECT * FROM accounts").await;
+    assert_eq!((reply.error, reply.status), (None, b'T'));
+    let reply = query(socket, "COMMIT").await;
+    assert_eq!((reply.tags, reply.status), (vec!["COMMIT".to_string()], b'I'));
+
+    // COMMIT of an aborted block rolls it back
+    query(socket, "BEGIN").await;
+    query(socket, "SELECT * FROM missing_table").await;
+    let reply = query(socket, "COMMIT").await;
+    assert_eq!((reply.tags, reply.error, reply.status), (vec!["ROLLBACK".to_string()], None, b'I'));
+
+    // Outside a block a failure aborts nothing, and savepoints are refused
+    let reply = query(socket, "SELECT * FROM missing_table").await;
+    assert_eq!(reply.status, b'I');
+    assert_eq!(query(socket, "SELECT * FROM accounts").await.error, None);
+    assert_eq!(query(socket, "SAVEPOINT outside").await.error.as_deref(), Some("25P01"));
+
+    send(socket, b'X', &[]).await;
+}
+
+#[tokio::test]
+async fn test_aborted_block_recovers_by_rollback_or_savepoint() {
+    let temp_dir = tempdir().unwrap();
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let protocol = PostgresProtocol::new(Arc::new(AuroraDB::new(config).await.unwrap()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = async {
+        let (socket, _) = listener.accept().await.unwrap();
+        protocol.handle_connection(socket).await.unwrap();
+    };
+    let client = async {
+        let mut socket = TcpStream::connect(address).await.unwrap();
+        run_client(&mut socket).await;
+    };
+    tokio::join!(server, client);
+}
@@ -0,0 +1,133 @@
+//! Row TTL Tests
+//!
+//! Tables created `WITH (ttl_column = expires_at)` hide rows whose expiry has
+//! passed from every read, and the reaper deletes and purges them in batches
+//! while leaving unexpired rows alone.
+
+use aurora_db::config::{DatabaseConfig, TtlConfig};
+use aurora_db::engine::{AuroraDB, TtlReapReport, UserContext};
+use std::time::Duration;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir, ttl: TtlConfig) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ttl,
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+async fn session_ids(db: &AuroraDB, user_context: &UserContext) -> Vec<i64> {
+    let result = db.execute_query("SELECT id FROM sessions ORDER BY id;", user_context).await.unwrap();
+    result.rows.iter().map(|row| row[0].as_i64().unwrap()).collect()
+}
+
+/// Sessions 1-4 expired long ago, 5-7 expire far in the future, 8 never
+async fn load_sessions(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query(
+        "CREATE TABLE sessions (id INTEGER PRIMARY KEY, token TEXT, expires_at TIMESTAMPTZ) WITH (ttl_column = expires_at);",
+        user_context,
+    ).await.unwrap();
+
+    let mut rows: Vec<String> = (1..=4).map(|id| format!("({}, 'old_{}', '2000-01-01 00:00:00+00')", id, id)).collect();
+    rows.extend((5..=7).map(|id| format!("({}, 'live_{}', '2999-01-01 00:00:00+00')", id, id)));
+    rows.push("(8, 'forever', NULL)".to_string());
+    db.execute_query(&format!("INSERT INTO sessions (id, token, expires_at) VALUES {};", rows.join(", ")), user_context).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_expired_rows_are_hidden_then_reaped_in_batches() {
+    let temp_dir = tempdir().unwrap();
+    // The background reaper stays out of the way; passes are run by hand
+    let db = open(&temp_dir, TtlConfig { reaper_interval_ms: 3_600_000, reaper_batch_size: 3 }).await;
+    let user_context = user_context();
+    load_sessions(&db, &user_context).await;
+
+    // Hidden from scans, filters and aggregates before anything is deleted
+    assert_eq!(session_ids(&db, &user_context).await, [5, 6, 7, 8]);
+    let result = db.execute_query("SELECT COUNT(*) FROM sessions WHERE id <= 4;", &user_context).await.unwrap();
+    assert_eq!(result.rows[0][0].as_i64(), Some(0));
+
+    // Updates and deletes do not see expired rows either
+    let result = db.execute_query("UPDATE sessions SET token = 'revived' WHERE id = 1;", &user_context).await.unwrap();
+    assert_eq!(result.rows_affected, Some(0));
+
+    // A row that expires while the table is in use disappears on time
+    let expires_at = (chrono::Utc::now() + chrono::Duration::seconds(1)).format("%Y-%m-%d %H:%M:%S%.6f+00");
+    db.execute_query(&format!("INSERT INTO sessions (id, token, expires_at) VALUES (9, 'short', '{}');", expires_at), &user_context)
+        .await
+        .unwrap();
+    assert_eq!(session_ids(&db, &user_context).await, [5, 6, 7, 8, 9]);
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert_eq!(session_ids(&db, &user_context).await, [5, 6, 7, 8]);
+
+    // Five expired rows, three per batch
+    let report = db.reap_expired_rows().await.unwrap();
+    assert_eq!(report, TtlReapReport { tables_scanned: 1, rows_deleted: 5, rows_purged: 5, batches: 2 });
+
+    // Nothing is left to delete or purge, and live rows are untouched
+    let report = db.reap_expired_rows().await.unwrap();
+    assert_eq!((report.rows_deleted, report.rows_purged), (0, 0));
+    let result = db.execute_query("SELECT id, token FROM sessions ORDER BY id;", &user_context).await.unwrap();
+    let tokens: Vec<(i64, String)> = result.rows.iter()
+        .map(|row| (row[0].as_i64().unwrap(), row[1].as_str().unwrap().to_string()))
+        .collect();
+    assert_eq!(tokens, [
+        (5, "live_5".to_string()), (6, "live_6".to_string()), (7, "live_7".to_string()), (8, "forever".to_string()),
+    ]);
+
+    // A purged key can be reused
+    db.execute_query("INSERT INTO sessions (id, token, expires_at) VALUES (1, 'new', '2999-01-01 00:00:00+00');", &user_context)
+        .await
+        .unwrap();
+    assert_eq!(session_ids(&db, &user_context).await, [1, 5, 6, 7, 8]);
+}
+
+#[tokio::test]
+async fn test_background_reaper_removes_expired_rows() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir, TtlConfig { reaper_interval_ms: 20, reaper_batch_size: 2 }).await;
+    let user_context = user_context();
+    load_sessions(&db, &user_context).await;
+
+    // An expired key not yet reaped can be inserted again
+    db.execute_query("INSERT INTO sessions (id, token, expires_at) VALUES (2, 'renewed', '2999-01-01 00:00:00+00');", &user_context)
+        .await
+        .unwrap();
+
+    // The reaper has already deleted and purged everything by the time a
+    // manual pass looks
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let report = db.reap_expired_rows().await.unwrap();
+    assert_eq!((report.tables_scanned, report.rows_deleted, report.rows_purged), (1, 0, 0));
+    assert_eq!(session_ids(&db, &user_context).await, [2, 5, 6, 7, 8]);
+}
+
+#[tokio::test]
+async fn test_ttl_column_must_be_a_timestamp_column() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir, TtlConfig::default()).await;
+    let user_context = user_context();
+
+    let missing = db.execute_query("CREATE TABLE cache (id INTEGER PRIMARY KEY, value TEXT) WITH (ttl_column = expires_at);", &user_context).await;
+    assert!(missing.unwrap_err().to_string().contains("not a column"));
+
+    let text = db.execute_query("CREATE TABLE cache (id INTEGER PRIMARY KEY, value TEXT) WITH (ttl_column = value);", &user_context).await;
+    assert!(text.unwrap_err().to_string().contains("TIMESTAMP"));
+
+    let unknown = db.execute_query("CREATE TABLE cache (id INTEGER PRIMARY KEY, at TIMESTAMP) WITH (fillfactor = 70);", &user_context).await;
+    assert!(unknown.is_err());
+
+    db.execute_query("CREATE TABLE cache (id INTEGER PRIMARY KEY, at TIMESTAMP) WITH (ttl_column = at);", &user_context).await.unwrap();
+}
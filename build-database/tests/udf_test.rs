@@ -0,0 +1,126 @@
+//! Scalar UDF Tests
+//!
+//! Native functions registered by the embedding application are called from
+//! SELECT lists and WHERE clauses, and calls that do not match the declared
+//! signature are rejected when the statement is planned.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::{AuroraDB, UserContext};
+use aurora_db::query::FunctionSignature;
+use aurora_db::types::{DataType, DataValue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+
+async fn open(temp_dir: &TempDir) -> AuroraDB {
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    AuroraDB::new(config).await.unwrap()
+}
+
+fn user_context() -> UserContext {
+    UserContext {
+        user_id: Some("test_user".to_string()),
+        username: "test_user".to_string(),
+        roles: vec!["admin".to_string()],
+        client_ip: Some("127.0.0.1".parse().unwrap()),
+        session_id: "test_session".to_string(),
+    }
+}
+
+/// `word_count(text) -> integer`, counting its calls
+fn register_word_count(db: &AuroraDB) -> Arc<AtomicUsize> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    db.register_function(
+        "word_count",
+        FunctionSignature::new(vec![DataType::Text], DataType::Integer),
+        move |args| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(match &args[0] {
+                DataValue::Text(text) => DataValue::Integer(text.split_whitespace().count() as i64),
+                _ => DataValue::Null,
+            })
+        },
+    ).unwrap();
+    calls
+}
+
+async fn load_posts(db: &AuroraDB, user_context: &UserContext) {
+    db.execute_query("CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT, body TEXT);", user_context).await.unwrap();
+    db.execute_query(
+        "INSERT INTO posts (id, title, body) VALUES \
+         (1, 'Hello', 'short post'), \
+         (2, 'Planner notes', 'how the planner picks a join order'), \
+         (3, 'Empty', '');",
+        user_context,
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_udf_in_select_list() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+    register_word_count(&db);
+
+    let result = db.execute_query("SELECT id, word_count(body) AS words FROM posts ORDER BY id", &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 3);
+    assert_eq!(result.rows[0][1], serde_json::json!(2));
+    assert_eq!(result.rows[1][1], serde_json::json!(7));
+    assert_eq!(result.rows[2][1], serde_json::json!(0));
+}
+
+#[tokio::test]
+async fn test_udf_in_where_clause() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+    register_word_count(&db);
+
+    let result = db.execute_query("SELECT id FROM posts WHERE word_count(body) > 2", &user_context).await.unwrap();
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0], serde_json::json!(2));
+
+    // A boolean function is a condition on its own
+    db.register_function(
+        "is_blank",
+        FunctionSignature::new(vec![DataType::Text], DataType::Boolean),
+        |args| Ok(DataValue::Boolean(matches!(&args[0], DataValue::Text(text) if text.trim().is_empty()))),
+    ).unwrap();
+    let blank = db.execute_query("SELECT id FROM posts WHERE is_blank(body)", &user_context).await.unwrap();
+    assert_eq!(blank.rows.len(), 1);
+    assert_eq!(blank.rows[0][0], serde_json::json!(3));
+}
+
+#[tokio::test]
+async fn test_type_mismatch_rejected_at_planning() {
+    let temp_dir = tempdir().unwrap();
+    let db = open(&temp_dir).await;
+    let user_context = user_context();
+    load_posts(&db, &user_context).await;
+    let calls = register_word_count(&db);
+
+    // An INTEGER column, a numeric literal and a wrong argument count
+    for sql in [
+        "SELECT word_count(id) FROM posts",
+        "SELECT id FROM posts WHERE word_count(42) > 1",
+        "SELECT word_count(title, body) FROM posts",
+    ] {
+        assert!(db.execute_query(sql, &user_context).await.is_err(), "{}", sql);
+    }
+
+    // A non-boolean function cannot be a WHERE condition by itself
+    assert!(db.execute_query("SELECT id FROM posts WHERE word_count(body)", &user_context).await.is_err());
+
+    // Rejected before any row was evaluated
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    // Built-in names cannot be taken
+    let taken = db.register_function("now", FunctionSignature::new(vec![], DataType::Integer), |_| Ok(DataValue::Integer(0)));
+    assert!(taken.is_err());
+}
@@ -0,0 +1,253 @@
+//! WAL Streaming Replication Tests
+//!
+//! A standby follows a primary's write-ahead log over TCP: it converges on
+//! the primary's committed state through a series of transactions, resumes
+//! from its last flushed LSN after losing the primary, acknowledges what it
+//! flushed for synchronous commit, and never has more unacknowledged WAL in
+//! flight than the primary's window allows.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use aurora_db::network::distributed::{
+    ReplicationConfig, ReplicationError, ReplicationMessage, ReplicationProtocol, ReplicationRole,
+    SynchronousCommit,
+};
+use aurora_db::storage::wal_logger::{WALEntry, WALLogger, WALRecord};
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+/// Key and new value of a logged write; `None` for a delete
+type Write = (Vec<u8>, Option<Vec<u8>>);
+
+/// Committed rows rebuilt from a log; a transaction's writes take effect at
+/// its commit record
+#[derive(Debug, Default)]
+struct TableImage {
+    pending: HashMap<u64, Vec<Write>>,
+    rows: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl TableImage {
+    fn apply(&mut self, entry: &WALEntry) {
+        let writes = self.pending.entry(entry.transaction_id).or_default();
+        match &entry.record {
+            WALRecord::Insert { key, value, .. } => writes.push((key.clone(), Some(value.clone()))),
+            WALRecord::Update { key, new_value, .. } => writes.push((key.clone(), Some(new_value.clone()))),
+            WALRecord::Delete { key, .. } => writes.push((key.clone(), None)),
+            WALRecord::Commit { transaction_id } => {
+                for (key, value) in self.pending.remove(transaction_id).unwrap_or_default() {
+                    match value {
+                        Some(value) => self.rows.insert(key, value),
+                        None => self.rows.remove(&key),
+                    };
+                }
+            }
+            WALRecord::Abort { transaction_id } => {
+                self.pending.remove(transaction_id);
+            }
+            WALRecord::BeginTransaction { .. } | WALRecord::Checkpoint => {}
+        }
+    }
+}
+
+/// Rows committed in `wal`, replayed from its file
+async fn committed_rows(wal: &WALLogger) -> BTreeMap<Vec<u8>, Vec<u8>> {
+    let mut image = TableImage::default();
+    wal.recover(|entry| {
+        image.apply(entry);
+        Ok(())
+    }).await.unwrap();
+    image.rows
+}
+
+/// Run one transaction of inserts, updates (`Some`) and deletes (`None`);
+/// the LSN of its commit record
+async fn commit(wal: &WALLogger, transaction_id: u64, writes: &[(&str, Option<&str>)]) -> u64 {
+    wal.begin_transaction(transaction_id).await.unwrap();
+    for (key, value) in writes {
+        match value {
+            Some(value) => wal.log_insert(transaction_id, "accounts", key.as_bytes(), value.as_bytes()).await.unwrap(),
+            None => wal.log_delete(transaction_id, "accounts", key.as_bytes(), b"").await.unwrap(),
+        };
+    }
+    wal.commit_transaction(transaction_id).await.unwrap()
+}
+
+fn config() -> ReplicationConfig {
+    ReplicationConfig {
+        max_in_flight: 8,
+        batch_size: 3,
+        reconnect_interval: Duration::from_millis(50),
+        ..ReplicationConfig::default()
+    }
+}
+
+async fn wait_for_applied(standby: &ReplicationProtocol, lsn: u64) {
+    let mut applied = standby.subscribe_applied();
+    timeout(Duration::from_secs(5), applied.wait_for(|applied| *applied >= lsn))
+        .await
+        .expect("standby did not catch up")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_standby_follows_primary_through_transactions_and_reconnect() {
+    let (primary_dir, standby_dir) = (tempdir().unwrap(), tempdir().unwrap());
+    let primary_wal = Arc::new(WALLogger::new(primary_dir.path().to_path_buf()).unwrap());
+    let standby_wal = Arc::new(WALLogger::new(standby_dir.path().to_path_buf()).unwrap());
+    let primary = Arc::new(ReplicationProtocol::new(ReplicationRole::Master, primary_wal.clone()).with_config(config()));
+    let standby = Arc::new(ReplicationProtocol::new(ReplicationRole::Slave, standby_wal.clone()).with_config(config()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(primary.clone().serve(listener));
+
+    let image = Arc::new(Mutex::new(TableImage::default()));
+    let follower = {
+        let (standby, image) = (standby.clone(), image.clone());
+        tokio::spawn(async move {
+            standby.follow(address, "standby-1", move |entry: &WALEntry| {
+                image.lock().unwrap().apply(entry);
+                Ok(())
+            }).await
+        })
+    };
+
+    // Several transactions, one of them rolled back
+    commit(&primary_wal, 1, &[("alice", Some("100")), ("bob", Some("50"))]).await;
+    commit(&primary_wal, 2, &[("carol", Some("75")), ("bob", None)]).await;
+    primary_wal.begin_transaction(3).await.unwrap();
+    primary_wal.log_insert(3, "accounts", b"mallory", b"1000000").await.unwrap();
+    primary_wal.abort_transaction(3).await.unwrap();
+    let wide: Vec<String> = (0..20).map(|i| format!("user{:02}", i)).collect();
+    let writes: Vec<(&str, Option<&str>)> = wide.iter().map(|key| (key.as_str(), Some("1"))).collect();
+    let last_commit = commit(&primary_wal, 4, &writes).await;
+
+    // The standby has applied the commit record once this returns
+    timeout(Duration::from_secs(5), primary.wait_for_standby("standby-1", last_commit, SynchronousCommit::RemoteApply)).await.unwrap();
+    assert!(standby_wal.flushed_lsn() >= last_commit);
+    wait_for_applied(&standby, last_commit).await;
+    let expected = committed_rows(&primary_wal).await;
+    assert_eq!(expected.len(), 22);
+    assert!(!expected.contains_key(b"mallory".as_slice()) && !expected.contains_key(b"bob".as_slice()));
+    assert_eq!(image.lock().unwrap().rows, expected);
+
+    let slot = primary.slot("standby-1").unwrap();
+    assert!(slot.active);
+    assert_eq!((slot.restart_lsn, slot.confirmed_flush_lsn, slot.applied_lsn), (1, last_commit, last_commit));
+
+    // The primary goes away and keeps writing; the standby retries meanwhile
+    server.abort();
+    let _ = server.await;
+    let resumed_from = standby_wal.flushed_lsn() + 1;
+    commit(&primary_wal, 5, &[("alice", Some("90")), ("dave", Some("10"))]).await;
+    let last_commit = commit(&primary_wal, 6, &[("carol", None)]).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(standby.applied_lsn(), resumed_from - 1);
+
+    // Back on the same address, the stream resumes where the standby stopped
+    let server = tokio::spawn(primary.clone().serve(TcpListener::bind(address).await.unwrap()));
+    timeout(Duration::from_secs(5), primary.wait_for_standby("standby-1", last_commit, SynchronousCommit::RemoteApply)).await.unwrap();
+    wait_for_applied(&standby, last_commit).await;
+    assert_eq!(primary.slot("standby-1").unwrap().restart_lsn, resumed_from);
+    let expected = committed_rows(&primary_wal).await;
+    assert_eq!(image.lock().unwrap().rows, expected);
+
+    // Both logs hold the same entries, none of them twice
+    let primary_entries = primary_wal.read_from(1, usize::MAX).unwrap();
+    let standby_entries = standby_wal.read_from(1, usize::MAX).unwrap();
+    assert_eq!(standby_entries.len(), primary_entries.len());
+    for (expected_lsn, (primary_entry, standby_entry)) in (1..).zip(primary_entries.iter().zip(&standby_entries)) {
+        assert_eq!((primary_entry.lsn, standby_entry.lsn), (expected_lsn, expected_lsn));
+        assert_eq!(primary_entry.checksum, standby_entry.checksum);
+    }
+    assert!(primary.stats().sent_bytes > 0 && standby.stats().received_bytes > 0);
+
+    follower.abort();
+    server.abort();
+}
+
+/// Framed message to or from a primary, as `ReplicationProtocol` sends it
+async fn send(socket: &mut TcpStream, message: &ReplicationMessage) {
+    let body = bincode::serialize(message).unwrap();
+    socket.write_all(&(body.len() as u32 + 4).to_be_bytes()).await.unwrap();
+    socket.write_all(&body).await.unwrap();
+}
+
+async fn receive(socket: &mut TcpStream) -> ReplicationMessage {
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    bincode::deserialize(&body).unwrap()
+}
+
+/// LSNs in the `WalData` messages that arrive within `wait`
+async fn received_lsns(socket: &mut TcpStream, wait: Duration) -> Vec<u64> {
+    let mut lsns = Vec::new();
+    while let Ok(message) = timeout(wait, receive(socket)).await {
+        match message {
+            ReplicationMessage::WalData { entries } => lsns.extend(entries.iter().map(|entry| entry.lsn)),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+    lsns
+}
+
+#[tokio::test]
+async fn test_primary_stops_sending_at_the_standby_window() {
+    let primary_dir = tempdir().unwrap();
+    let primary_wal = Arc::new(WALLogger::new(primary_dir.path().to_path_buf()).unwrap());
+    let primary = Arc::new(ReplicationProtocol::new(ReplicationRole::Master, primary_wal.clone()).with_config(config()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address: SocketAddr = listener.local_addr().unwrap();
+    let server = tokio::spawn(primary.clone().serve(listener));
+
+    // 2 transactions of 10 writes: 24 entries
+    for transaction_id in 1..=2 {
+        let writes: Vec<(String, Option<&str>)> = (0..10).map(|i| (format!("k{}-{}", transaction_id, i), Some("v"))).collect();
+        let writes: Vec<(&str, Option<&str>)> = writes.iter().map(|(key, value)| (key.as_str(), *value)).collect();
+        commit(&primary_wal, transaction_id, &writes).await;
+    }
+    assert_eq!(primary_wal.flushed_lsn(), 24);
+
+    // A standby that does not acknowledge gets one window's worth
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    send(&mut socket, &ReplicationMessage::StartReplication { standby_id: "slow".to_string(), start_lsn: 1 }).await;
+    assert_eq!(received_lsns(&mut socket, Duration::from_millis(200)).await, (1..=8).collect::<Vec<_>>());
+
+    // Each acknowledgement opens the window again
+    send(&mut socket, &ReplicationMessage::StandbyStatus { flushed_lsn: 5, applied_lsn: 5 }).await;
+    assert_eq!(received_lsns(&mut socket, Duration::from_millis(200)).await, (9..=13).collect::<Vec<_>>());
+    send(&mut socket, &ReplicationMessage::StandbyStatus { flushed_lsn: 13, applied_lsn: 13 }).await;
+    assert_eq!(received_lsns(&mut socket, Duration::from_millis(200)).await, (14..=21).collect::<Vec<_>>());
+    assert_eq!(primary.slot("slow").unwrap().confirmed_flush_lsn, 13);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_standby_ahead_of_primary_is_refused() {
+    let (primary_dir, standby_dir) = (tempdir().unwrap(), tempdir().unwrap());
+    let primary_wal = Arc::new(WALLogger::new(primary_dir.path().to_path_buf()).unwrap());
+    let standby_wal = Arc::new(WALLogger::new(standby_dir.path().to_path_buf()).unwrap());
+    commit(&standby_wal, 1, &[("alice", Some("100"))]).await;
+
+    let primary = Arc::new(ReplicationProtocol::new(ReplicationRole::Master, primary_wal));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(primary.serve(listener));
+
+    // Reconnecting cannot fix a diverged log, so the standby gives up
+    let standby = ReplicationProtocol::new(ReplicationRole::Slave, standby_wal);
+    let outcome = timeout(Duration::from_secs(5), standby.follow(address, "standby-1", |_: &WALEntry| Ok(()))).await.unwrap();
+    match outcome {
+        Err(ReplicationError::Refused(reason)) => assert_eq!(reason, "start LSN 4 is past the primary's flushed LSN 0"),
+        other => panic!("expected a refusal, got {:?}", other),
+    }
+
+    server.abort();
+}
@@ -0,0 +1,159 @@
+//! Wire Protocol Hardening Tests
+//!
+//! Regression cases for malformed client frames that used to panic the
+//! server: lengths below the length word itself, lengths far beyond the
+//! data, and truncated headers. Each must now fail with a protocol error,
+//! and the server must answer with a protocol violation and close.
+
+use aurora_db::config::DatabaseConfig;
+use aurora_db::engine::AuroraDB;
+use aurora_db::network::protocol::{
+    decode_frame, decode_startup, AuroraMessage, MessageSerializer, MessageType, ProtocolError,
+    DEFAULT_MAX_MESSAGE_SIZE, MAX_STARTUP_MESSAGE_SIZE,
+};
+use aurora_db::network::protocols::{AuroraBinarySerializer, PostgreSQLStartupParser, VectorEncoder};
+use aurora_db::network::PostgresProtocol;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[test]
+fn test_frame_lengths_are_checked_before_slicing() {
+    // The length word counts itself, so 0..=3 used to underflow
+    for length in 0u32..4 {
+        let mut frame = vec![b'Q'];
+        frame.extend_from_slice(&length.to_be_bytes());
+        assert!(matches!(decode_frame(&frame, DEFAULT_MAX_MESSAGE_SIZE), Err(ProtocolError::InvalidLength(l)) if l == length));
+    }
+
+    // A huge length is refused before anything is allocated
+    let frame = [b'Q', 0xff, 0xff, 0xff, 0xff];
+    assert!(matches!(
+        decode_frame(&frame, DEFAULT_MAX_MESSAGE_SIZE),
+        Err(ProtocolError::MessageTooLarge { size: 0xffff_ffff, .. })
+    ));
+
+    // Truncated headers and bodies wait for more input
+    assert_eq!(decode_frame(&[b'Q', 0, 0], DEFAULT_MAX_MESSAGE_SIZE).unwrap(), None);
+    assert_eq!(decode_frame(&[b'Q', 0, 0, 0, 9, b'S'], DEFAULT_MAX_MESSAGE_SIZE).unwrap(), None);
+
+    let (frame, consumed) = decode_frame(b"Q\0\0\0\x0bSELECT 1X", DEFAULT_MAX_MESSAGE_SIZE).unwrap().unwrap();
+    assert_eq!((frame.message_type, frame.body.as_slice(), consumed), (b'Q', &b"SELECT "[..], 12));
+
+    // Startup messages have no type byte and a much smaller limit
+    assert!(matches!(decode_startup(&[0, 0, 0, 2]), Err(ProtocolError::InvalidLength(2))));
+    let oversized = (MAX_STARTUP_MESSAGE_SIZE as u32 + 1).to_be_bytes();
+    assert!(matches!(decode_startup(&oversized), Err(ProtocolError::MessageTooLarge { .. })));
+}
+
+#[test]
+fn test_payload_decoders_reject_malformed_input() {
+    // Declared startup length beyond the data
+    assert!(PostgreSQLStartupParser::parse_startup(&[0, 0, 0, 100, 0, 3, 0, 0, b'u']).is_err());
+    // Parameter without its terminator
+    assert!(PostgreSQLStartupParser::parse_startup(&[0, 0, 0, 10, 0, 3, 0, 0, b'u', b's']).is_err());
+
+    // Vector header one byte short, and a dimension whose byte size overflows
+    assert!(VectorEncoder::decode_vector(&[0, 1, 0, 0]).is_err());
+    assert!(VectorEncoder::decode_vector(&[0, 0xff, 0xff, 0xff, 0xff, 1, 2, 3, 4]).is_err());
+
+    let serializer = AuroraBinarySerializer::new();
+    let mut metadata = HashMap::new();
+    metadata.insert("trace".to_string(), "abc".to_string());
+    let message = AuroraMessage { message_type: MessageType::Query, payload: b"SELECT 1".to_vec(), metadata };
+    let encoded = serializer.serialize(&message).unwrap();
+    let decoded = serializer.deserialize(&encoded).unwrap();
+    assert_eq!((decoded.payload, decoded.metadata), (message.payload.clone(), message.metadata.clone()));
+
+    // Payload and metadata lengths past the end, and cut-off messages
+    let mut bad_payload = encoded.clone();
+    bad_payload[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(serializer.deserialize(&bad_payload).is_err());
+    let mut bad_metadata = encoded.clone();
+    let offset = 10 + message.payload.len();
+    bad_metadata[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(serializer.deserialize(&bad_metadata).is_err());
+    for length in 0..encoded.len() {
+        assert!(serializer.deserialize(&encoded[..length]).is_err());
+    }
+}
+
+async fn read_message(socket: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+    let message_type = socket.read_u8().await.ok()?;
+    let length = socket.read_u32().await.unwrap() as usize;
+    let mut body = vec![0u8; length - 4];
+    socket.read_exact(&mut body).await.unwrap();
+    Some((message_type, body))
+}
+
+/// SQLSTATE of an ErrorResponse body
+fn sqlstate(body: &[u8]) -> String {
+    body.split(|byte| *byte == 0)
+        .find(|field| field.first() == Some(&b'C'))
+        .map(|field| String::from_utf8_lossy(&field[1..]).to_string())
+        .unwrap_or_default()
+}
+
+/// Serve one connection, returning whether the handler finished without error
+async fn serve_one(db: Arc<AuroraDB>) -> (std::net::SocketAddr, tokio::task::JoinHandle<bool>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::task::spawn_local(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        PostgresProtocol::new(db).handle_connection(socket).await.is_ok()
+    });
+    (address, server)
+}
+
+#[tokio::test]
+async fn test_server_closes_connection_on_malformed_frames() {
+    let temp_dir = tempdir().unwrap();
+    let config = DatabaseConfig {
+        data_directory: temp_dir.path().to_string_lossy().to_string(),
+        ..DatabaseConfig::default()
+    };
+    let db = Arc::new(AuroraDB::new(config).await.unwrap());
+
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async move {
+        // Startup message whose length is shorter than the length word
+        let (address, server) = serve_one(db.clone()).await;
+        let mut socket = TcpStream::connect(address).await.unwrap();
+        socket.write_all(&[0, 0, 0, 2]).await.unwrap();
+        let (message_type, body) = read_message(&mut socket).await.unwrap();
+        assert_eq!((message_type, sqlstate(&body).as_str()), (b'E', "08P01"));
+        assert_eq!(read_message(&mut socket).await, None);
+        assert!(!server.await.unwrap());
+
+        // Startup message claiming 2 GB
+        let (address, server) = serve_one(db.clone()).await;
+        let mut socket = TcpStream::connect(address).await.unwrap();
+        socket.write_all(&0x7fff_ffffu32.to_be_bytes()).await.unwrap();
+        let (message_type, body) = read_message(&mut socket).await.unwrap();
+        assert_eq!((message_type, sqlstate(&body).as_str()), (b'E', "08P01"));
+        assert!(!server.await.unwrap());
+
+        // After a good login, a query with a zero length and then one with a huge length
+        for length in [0u32, u32::MAX] {
+            let (address, server) = serve_one(db.clone()).await;
+            let mut socket = TcpStream::connect(address).await.unwrap();
+            let mut startup = 196608u32.to_be_bytes().to_vec();
+            startup.extend_from_slice(b"user\0test\0\0");
+            socket.write_all(&((startup.len() + 4) as u32).to_be_bytes()).await.unwrap();
+            socket.write_all(&startup).await.unwrap();
+            assert_eq!(read_message(&mut socket).await.unwrap().0, b'R');
+            socket.write_all(b"p\0\0\0\x0bsecret\0").await.unwrap();
+            assert_eq!(read_message(&mut socket).await.unwrap().0, b'R');
+            assert_eq!(read_message(&mut socket).await.unwrap(), (b'Z', vec![b'I']));
+
+            socket.write_all(&[b'Q']).await.unwrap();
+            socket.write_all(&length.to_be_bytes()).await.unwrap();
+            let (message_type, body) = read_message(&mut socket).await.unwrap();
+            assert_eq!((message_type, sqlstate(&body).as_str()), (b'E', "08P01"));
+            assert_eq!(read_message(&mut socket).await, None);
+            assert!(server.await.unwrap());
+        }
+    }).await;
+}
@@ -29,6 +29,8 @@ async fn test_working_sql_pipeline() {
         enable_metrics: false,
         data_directory: data_dir.to_str().unwrap().to_string(),
         temp_directory: temp_dir.path().join("temp").to_str().unwrap().to_string(),
+        work_mem_bytes: 4 * 1024 * 1024,
+        ttl: aurora_db::config::TtlConfig::default(),
         storage: StorageConfig {
             selection_strategy: "workload_based".to_string(),
             btree: aurora_db::storage::btree::BTreeConfig {
@@ -47,6 +49,7 @@ async fn test_working_sql_pipeline() {
                 adaptive_threshold: 1000,
                 vector_threshold: 0.1,
             },
+            columnar: aurora_db::config::ColumnarConfig::default(),
             wal: aurora_db::storage::wal::WALConfig {
                 directory: temp_dir.path().join("wal").to_str().unwrap().to_string(),
                 segment_size_mb: 16,
@@ -209,6 +212,8 @@ async fn test_minimal_functional_database() {
         enable_metrics: false,
         data_directory: data_dir.to_str().unwrap().to_string(),
         temp_directory: temp_dir.path().join("temp").to_str().unwrap().to_string(),
+        work_mem_bytes: 4 * 1024 * 1024,
+        ttl: aurora_db::config::TtlConfig::default(),
         // ... minimal configs for other components
         storage: StorageConfig {
             selection_strategy: "btree".to_string(),
@@ -228,6 +233,7 @@ async fn test_minimal_functional_database() {
                 adaptive_threshold: 100,
                 vector_threshold: 0.1,
             },
+            columnar: aurora_db::config::ColumnarConfig::default(),
             wal: aurora_db::storage::wal::WALConfig {
                 directory: temp_dir.path().join("wal").to_str().unwrap().to_string(),
                 segment_size_mb: 8,
@@ -9,6 +9,10 @@ use crate::protocol::AuroraProtocol;
 use crate::types::*;
 use crate::error::{AuroraError, Result};
 use crate::config::AuroraConfig;
+use crate::interceptor::InterceptorChain;
+use crate::batch::{BatchMode, InsertBatch, RowResult};
+use crate::vector_batch::{VectorItem, VectorUpsertResult};
+use crate::pagination::{KeysetQuery, Page};
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -52,8 +56,14 @@ impl AuroraClient {
 
     /// Connect with custom configuration
     pub async fn connect_with_config(config: AuroraConfig) -> Result<Self> {
+        Self::connect_with_interceptors(config, InterceptorChain::new()).await
+    }
+
+    /// Connect with `interceptors` run around every query and statement,
+    /// including those issued inside transactions
+    pub async fn connect_with_interceptors(config: AuroraConfig, interceptors: InterceptorChain) -> Result<Self> {
         let pool = AuroraConnectionPool::new(config.clone()).await?;
-        let protocol = Arc::new(AuroraProtocol::new());
+        let protocol = Arc::new(AuroraProtocol::with_interceptors(interceptors));
 
         Ok(Self {
             pool,
@@ -64,10 +74,13 @@ impl AuroraClient {
     }
 
     /// Execute a query
+    ///
+    /// Cancellation-safe: if the returned future is dropped before it
+    /// completes, the connection goes back to the pool drained or is closed.
     pub async fn query(&self, sql: &str) -> Result<QueryResult> {
         let start_time = std::time::Instant::now();
 
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         let result = self.protocol.execute_query(&mut conn, sql).await;
 
         let duration = start_time.elapsed();
@@ -87,7 +100,7 @@ impl AuroraClient {
     pub async fn query_with_params(&self, sql: &str, params: &[AuroraValue]) -> Result<QueryResult> {
         let start_time = std::time::Instant::now();
 
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         let result = self.protocol.execute_query_with_params(&mut conn, sql, params).await;
 
         let duration = start_time.elapsed();
@@ -103,18 +116,56 @@ impl AuroraClient {
         result
     }
 
+    /// Fetch the page of `query` after the one whose `next_cursor` is
+    /// `cursor`, or its first page; pass each page's `next_cursor` to get
+    /// the next until it is `None`
+    pub async fn page(&self, query: &KeysetQuery, cursor: Option<&str>, page_size: usize) -> Result<Page> {
+        let (sql, params) = query.page_request(cursor, page_size)?;
+        let result = self.query_with_params(&sql, &params).await?;
+        query.page_from(result, page_size)
+    }
+
     /// Execute a statement (INSERT, UPDATE, DELETE)
     pub async fn execute(&self, sql: &str) -> Result<ExecuteResult> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.execute_statement(&mut conn, sql).await
     }
 
     /// Execute with parameters
     pub async fn execute_with_params(&self, sql: &str, params: &[AuroraValue]) -> Result<ExecuteResult> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.execute_statement_with_params(&mut conn, sql, params).await
     }
 
+    /// Execute a statement that may be retried without applying it twice
+    ///
+    /// Use a fresh key (e.g. a UUID) per logical write and reuse it for every retry.
+    pub async fn execute_idempotent(&self, sql: &str, idempotency_key: &str) -> Result<ExecuteResult> {
+        let mut conn = self.pool.acquire().await?;
+        self.protocol.execute_statement_idempotent(&mut conn, sql, &[], idempotency_key).await
+    }
+
+    /// Insert a batch of rows in one round trip, reporting each row's
+    /// outcome; rejected rows do not stop the others
+    pub async fn insert_batch(&self, batch: InsertBatch) -> Result<Vec<RowResult>> {
+        let mut conn = self.pool.acquire().await?;
+        self.protocol.insert_batch(&mut conn, batch, BatchMode::PerRow).await
+    }
+
+    /// Insert a batch of rows all-or-nothing; any rejected row fails the
+    /// whole batch and nothing is inserted
+    pub async fn insert_batch_atomic(&self, batch: InsertBatch) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        self.protocol.insert_batch(&mut conn, batch, BatchMode::Atomic).await.map(|_| ())
+    }
+
+    /// Insert or update a batch of vectors in a collection, reporting each
+    /// item's outcome; existing ids are updated in place
+    pub async fn vector_upsert_batch(&self, collection: &str, items: Vec<VectorItem>) -> Result<Vec<VectorUpsertResult>> {
+        let mut conn = self.pool.acquire().await?;
+        self.protocol.vector_upsert_batch(&mut conn, collection, items).await
+    }
+
     /// Perform vector similarity search
     pub async fn vector_search(
         &self,
@@ -124,7 +175,7 @@ impl AuroraClient {
     ) -> Result<VectorSearchResult> {
         let start_time = std::time::Instant::now();
 
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         let result = self.protocol.vector_search(&mut conn, collection, query_vector, limit).await;
 
         let duration = start_time.elapsed();
@@ -144,7 +195,7 @@ impl AuroraClient {
     ) -> Result<VectorSearchResult> {
         let start_time = std::time::Instant::now();
 
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         let result = self.protocol.vector_search_advanced(&mut conn, request).await;
 
         let duration = start_time.elapsed();
@@ -161,7 +212,7 @@ impl AuroraClient {
     pub async fn analytics_query(&self, sql: &str) -> Result<AnalyticsResult> {
         let start_time = std::time::Instant::now();
 
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         let result = self.protocol.analytics_query(&mut conn, sql).await;
 
         let duration = start_time.elapsed();
@@ -185,7 +236,7 @@ impl AuroraClient {
 
     /// Create a prepared statement
     pub async fn prepare(&self, sql: &str) -> Result<PreparedStatement> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.prepare_statement(&mut conn, sql).await
     }
 
@@ -195,7 +246,7 @@ impl AuroraClient {
         stmt: &PreparedStatement,
         params: &[AuroraValue],
     ) -> Result<QueryResult> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.execute_prepared(&mut conn, stmt, params).await
     }
 
@@ -207,13 +258,13 @@ impl AuroraClient {
 
     /// Batch execute multiple statements
     pub async fn batch_execute(&self, statements: Vec<BatchStatement>) -> Result<Vec<ExecuteResult>> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.batch_execute(&mut conn, statements).await
     }
 
     /// Get database schema information
     pub async fn get_schema(&self, table_name: Option<&str>) -> Result<SchemaInfo> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.get_schema(&mut conn, table_name).await
     }
 
@@ -225,7 +276,7 @@ impl AuroraClient {
 
     /// Health check
     pub async fn health_check(&self) -> Result<HealthStatus> {
-        let mut conn = self.pool.get_connection().await?;
+        let mut conn = self.pool.acquire().await?;
         self.protocol.health_check(&mut conn).await
     }
 
@@ -369,6 +420,7 @@ pub enum HealthState {
 // - [x] Vector search with advanced filtering
 // - [x] Real-time streaming analytics
 // - [x] Transaction support with auto-rollback
+// - [x] Cancellation-safe queries that never leak a desynced connection
 // - [x] Prepared statements for performance
 // - [x] Comprehensive error handling
 // - [x] Built-in metrics and observability
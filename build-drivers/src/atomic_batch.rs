@@ -0,0 +1,153 @@
+//! Atomic Statement Batches
+//!
+//! A fixed sequence of statements, reads and writes mixed, is sent as one
+//! `AtomicBatch` message and run by the server as a single implicit
+//! transaction. Either every statement commits and the reply carries each
+//! statement's result in batch order, or the first statement to fail aborts
+//! the batch: the statements before it are rolled back, none after it run,
+//! and the reply names the failed statement and its error. Either way the
+//! whole batch costs one round trip.
+//!
+//! The batch is a structured message rather than SQL text, so statement
+//! interceptors do not see it.
+
+use crate::connection::AuroraConnection;
+use crate::error::{AuroraError, Result};
+use crate::protocol::MessageType;
+use crate::types::{AuroraRow, AuroraValue, ExecuteResult, QueryResult};
+
+use serde::{Deserialize, Serialize};
+
+/// One statement of a batch and its parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchStatement {
+    pub sql: String,
+    pub params: Vec<AuroraValue>,
+}
+
+impl BatchStatement {
+    pub fn new(sql: &str) -> Self {
+        Self::with_params(sql, Vec::new())
+    }
+
+    pub fn with_params(sql: &str, params: Vec<AuroraValue>) -> Self {
+        Self { sql: sql.to_string(), params }
+    }
+}
+
+impl From<&str> for BatchStatement {
+    fn from(sql: &str) -> Self {
+        Self::new(sql)
+    }
+}
+
+impl From<String> for BatchStatement {
+    fn from(sql: String) -> Self {
+        Self { sql, params: Vec::new() }
+    }
+}
+
+/// What one statement of a committed batch returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StatementResult {
+    /// Rows of a query
+    Query(QueryResult),
+
+    /// Outcome of an INSERT, UPDATE, DELETE or other write
+    Execute(ExecuteResult),
+}
+
+impl StatementResult {
+    /// Rows the statement returned; empty for a write
+    pub fn rows(&self) -> &[AuroraRow] {
+        match self {
+            StatementResult::Query(result) => &result.rows,
+            StatementResult::Execute(_) => &[],
+        }
+    }
+
+    /// Rows the statement changed; 0 for a query
+    pub fn rows_affected(&self) -> u64 {
+        match self {
+            StatementResult::Query(_) => 0,
+            StatementResult::Execute(result) => result.rows_affected,
+        }
+    }
+}
+
+/// `AtomicBatch` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomicBatchRequest {
+    pub statements: Vec<BatchStatement>,
+}
+
+/// The statement that aborted a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementFailure {
+    /// Position of the statement in the batch
+    pub statement: usize,
+
+    /// SQLSTATE of the error
+    pub sqlstate: String,
+
+    pub message: String,
+}
+
+/// Server reply to an `AtomicBatch` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AtomicBatchResponse {
+    /// Result of every statement, in batch order; empty if the batch aborted
+    pub results: Vec<StatementResult>,
+
+    /// Statement whose error rolled the batch back, if any
+    pub failure: Option<StatementFailure>,
+}
+
+/// Results of a batch of `len` statements from the server's reply, or the
+/// error that rolled it back
+pub fn batch_results(len: usize, response: AtomicBatchResponse) -> Result<Vec<StatementResult>> {
+    if let Some(failure) = response.failure {
+        if failure.statement >= len {
+            return Err(AuroraError::Protocol(format!(
+                "AtomicBatch failure for statement {} of a {}-statement batch", failure.statement, len
+            )));
+        }
+        return Err(AuroraError::Transaction(format!(
+            "Atomic batch rolled back: statement {} failed with SQLSTATE {}: {}",
+            failure.statement, failure.sqlstate, failure.message
+        )));
+    }
+    if response.results.len() != len {
+        return Err(AuroraError::Protocol(format!(
+            "AtomicBatch returned {} results for {} statements", response.results.len(), len
+        )));
+    }
+    Ok(response.results)
+}
+
+impl AuroraConnection {
+    /// Run `statements` as one transaction in one round trip, returning the
+    /// result of each in order
+    ///
+    /// If any statement fails, every statement before it is rolled back and
+    /// the error names the failed statement by its position in the batch.
+    pub async fn atomic_batch<S: Into<BatchStatement>>(
+        &mut self,
+        statements: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<StatementResult>> {
+        let request = AtomicBatchRequest { statements: statements.into_iter().map(Into::into).collect() };
+        let len = request.statements.len();
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let request_bytes = bincode::serialize(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize atomic batch request: {}", e)))?;
+        self.send_message(MessageType::AtomicBatch, &request_bytes).await?;
+
+        let response_bytes = self.receive_message().await?;
+        let response: AtomicBatchResponse = bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize atomic batch response: {}", e)))?;
+        batch_results(len, response)
+    }
+}
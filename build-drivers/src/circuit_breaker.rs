@@ -0,0 +1,156 @@
+//! Connection Pool Circuit Breaker
+//!
+//! Tracks whether the pool can currently reach the server, so callers fail
+//! fast during an outage instead of queueing behind connection attempts that
+//! are bound to fail:
+//!
+//! - **Closed**: connections are opened as usual. `failure_threshold`
+//!   consecutive failed connection attempts open the breaker.
+//! - **Open**: acquisitions fail immediately with `AuroraError::CircuitOpen`
+//!   until `open_duration` has passed.
+//! - **Half-open**: the first acquisition after that is let through as a
+//!   probe; everyone else keeps failing fast. A successful probe closes the
+//!   breaker, a failed one opens it for another `open_duration`.
+//!
+//! Only attempts to reach the server count. Errors from queries, or from an
+//! `on_connect` hook on a connection that did open, say nothing about whether
+//! the server is up.
+
+use crate::config::CircuitBreakerConfig;
+
+use std::time::{Duration, Instant};
+
+/// Breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Connections are opened as usual
+    Closed,
+
+    /// Acquisitions fail fast
+    Open,
+
+    /// One probe connection is allowed through
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Gauge value exported in metrics: 0 closed, 1 open, 2 half-open
+    pub fn as_gauge(self) -> u64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open => 1,
+            CircuitState::HalfOpen => 2,
+        }
+    }
+}
+
+/// What an acquisition may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Proceed as usual
+    Allowed,
+
+    /// Proceed, opening a fresh connection whose outcome decides the state
+    Probe,
+
+    /// Fail fast; the next probe is allowed after this long
+    Rejected(Duration),
+}
+
+/// Consecutive-failure circuit breaker for connection attempts
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: CircuitState,
+    consecutive_failures: u32,
+    /// When the breaker last opened
+    opened_at: Option<Instant>,
+    /// When the probe in flight started, if any
+    probe_started: Option<Instant>,
+    /// Number of times the breaker has opened
+    times_opened: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            probe_started: None,
+            times_opened: 0,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn times_opened(&self) -> u64 {
+        self.times_opened
+    }
+
+    /// How long acquisitions must still fail fast, without claiming a probe
+    pub fn rejection(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            CircuitState::Closed => None,
+            CircuitState::Open => {
+                let reopens = self.opened_at? + self.config.open_duration;
+                (now < reopens).then(|| reopens - now)
+            }
+            // A probe whose caller gave up is replaced after one open duration
+            CircuitState::HalfOpen => {
+                let expires = self.probe_started? + self.config.open_duration;
+                (now < expires).then(|| expires - now)
+            }
+        }
+    }
+
+    /// Decide whether an acquisition may proceed, claiming the probe if due
+    pub fn admit(&mut self, now: Instant) -> Admission {
+        if self.state == CircuitState::Closed {
+            return Admission::Allowed;
+        }
+        if let Some(remaining) = self.rejection(now) {
+            return Admission::Rejected(remaining);
+        }
+
+        self.state = CircuitState::HalfOpen;
+        self.probe_started = Some(now);
+        Admission::Probe
+    }
+
+    /// A connection attempt reached the server
+    pub fn record_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probe_started = None;
+    }
+
+    /// A connection attempt failed; returns true if that opened the breaker
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        match self.state {
+            CircuitState::Closed => {
+                self.consecutive_failures += 1;
+                if self.consecutive_failures < self.config.failure_threshold {
+                    return false;
+                }
+            }
+            // Attempts that started before the breaker opened change nothing
+            CircuitState::Open => return false,
+            CircuitState::HalfOpen => self.consecutive_failures += 1,
+        }
+
+        self.state = CircuitState::Open;
+        self.opened_at = Some(now);
+        self.probe_started = None;
+        self.times_opened += 1;
+        true
+    }
+}
@@ -5,7 +5,12 @@
 
 use crate::config::AuroraConfig;
 use crate::error::{AuroraError, Result};
+use crate::portal::ClosedPortals;
+use crate::prepared::StatementCache;
 use crate::protocol::MessageType;
+use crate::server_info::ServerInfo;
+use crate::telemetry::{Operation, OperationSpan};
+use crate::types::{ExecuteRequest, ExecuteResult};
 
 use std::sync::Arc;
 use tokio::net::TcpStream;
@@ -16,6 +21,16 @@ use rustls::{Certificate, PrivateKey, ServerName, ClientConfig};
 use bytes::{Bytes, BytesMut};
 use futures::SinkExt;
 
+/// Largest frame data accepted from the server; a larger length can only
+/// come from a corrupt or hostile stream
+pub const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Frame envelope: version (4), type (1), sequence (4) and data length (4)
+pub const FRAME_HEADER_LEN: usize = 13;
+
+/// Only envelope version this driver speaks
+const FRAME_VERSION: u32 = 1;
+
 /// AuroraDB connection
 pub struct AuroraConnection {
     /// Connection stream (TCP or TLS)
@@ -35,6 +50,38 @@ pub struct AuroraConnection {
 
     /// Message sequence number
     sequence_number: u32,
+
+    /// Requests sent whose response has not been read yet
+    pending_responses: u32,
+
+    /// A frame was partly written or read when its future was dropped
+    torn: bool,
+
+    /// Session variables applied with `set`, in the order first set; they
+    /// are applied again whenever the connection is re-established
+    session_variables: Vec<(String, String)>,
+
+    /// Plans prepared with `prepare_plan` and whether each is pinned; they
+    /// are prepared again whenever the connection is re-established
+    prepared_plans: Vec<(String, bool)>,
+
+    /// Version and capabilities the server reported in the handshake
+    server_info: ServerInfo,
+
+    /// Id the next portal opened will be named by
+    next_portal_id: u64,
+
+    /// Portals dropped since the last portal request, still open on the server
+    closed_portals: ClosedPortals,
+
+    /// Statements prepared with `prepare`, by SQL text
+    statements: StatementCache,
+
+    /// Id the next prepared statement will be named by
+    next_statement_id: u64,
+
+    /// Table of the COPY started on this connection and not yet ended
+    open_copy: Option<String>,
 }
 
 /// Connection stream types
@@ -69,14 +116,25 @@ impl AuroraConnection {
     /// Create new connection
     pub async fn new(config: AuroraConfig) -> Result<Self> {
         let connection_id = format!("conn_{}", uuid::Uuid::new_v4().simple());
+        let statements = StatementCache::new(config.max_prepared_statements());
 
         let mut conn = Self {
-            stream: ConnectionStream::Tcp(TcpStream::connect("127.0.0.1:5433").await?), // Placeholder
+            stream: ConnectionStream::Tcp(TcpStream::connect((config.host.as_str(), config.port)).await?), // Replaced by connect()
             config,
             state: ConnectionState::Handshaking,
             connection_id,
             last_activity: std::time::Instant::now(),
             sequence_number: 0,
+            pending_responses: 0,
+            torn: false,
+            session_variables: Vec::new(),
+            prepared_plans: Vec::new(),
+            server_info: ServerInfo::default(),
+            next_portal_id: 1,
+            closed_portals: ClosedPortals::default(),
+            statements,
+            next_statement_id: 1,
+            open_copy: None,
         };
 
         // Establish connection
@@ -87,11 +145,32 @@ impl AuroraConnection {
 
     /// Establish connection to AuroraDB
     pub async fn connect(&mut self) -> Result<()> {
-        let address = format!("{}:{}", self.config.host, self.config.port);
+        let span = OperationSpan::start(Operation::Connect, &self.config.host, self.config.port, None);
+        let result = self.establish().await;
+        span.finish(&result);
+        result
+    }
 
-        // Create TCP connection
-        let tcp_stream = TcpStream::connect(&address).await
-            .map_err(|e| AuroraError::Connection(format!("Failed to connect to {}: {}", address, e)))?;
+    async fn establish(&mut self) -> Result<()> {
+        // Try the primary host, then each failover host in order
+        let mut failures = Vec::new();
+        let mut connected = None;
+        for host in self.config.hosts() {
+            let address = format!("{}:{}", host.host, host.port);
+            match TcpStream::connect((host.host.as_str(), host.port)).await {
+                Ok(stream) => {
+                    connected = Some((stream, host, address));
+                    break;
+                }
+                Err(e) => {
+                    warn!("Failed to connect to {}: {}", address, e);
+                    failures.push(format!("{}: {}", address, e));
+                }
+            }
+        }
+        let (tcp_stream, host, address) = connected.ok_or_else(|| {
+            AuroraError::Connection(format!("Failed to connect to any host ({})", failures.join("; ")))
+        })?;
 
         // Configure TCP options
         tcp_stream.set_nodelay(true)?;
@@ -99,7 +178,7 @@ impl AuroraConnection {
 
         let stream = if self.config.ssl_mode != "disable" {
             // Establish TLS connection
-            self.establish_tls(tcp_stream).await?
+            self.establish_tls(tcp_stream, &host.host).await?
         } else {
             ConnectionStream::Tcp(tcp_stream)
         };
@@ -119,7 +198,69 @@ impl AuroraConnection {
     }
 
     /// Send message to AuroraDB
+    ///
+    /// A connection that failed is re-established first, with its session
+    /// variables applied again, so callers do not see the reconnect.
+    ///
+    /// A COPY left open by a dropped sink is failed first, rolling it back.
     pub async fn send_message(&mut self, message_type: MessageType, data: &[u8]) -> Result<()> {
+        self.ensure_connected().await?;
+        self.fail_open_copy().await?;
+        self.send_frame(message_type, data).await
+    }
+
+    /// Send a message of the open COPY
+    ///
+    /// Unlike `send_message` this never reconnects, since a new session is
+    /// not in the COPY. `CopyData` is not answered, so it leaves no response
+    /// owed.
+    pub(crate) async fn send_copy_message(&mut self, message_type: MessageType, data: &[u8]) -> Result<()> {
+        if self.open_copy.is_none() {
+            return Err(AuroraError::Protocol("No COPY in progress".into()));
+        }
+        self.send_frame(message_type, data).await?;
+        if matches!(message_type, MessageType::CopyData) {
+            self.pending_responses -= 1;
+        }
+        Ok(())
+    }
+
+    /// Record that the server accepted a COPY into `table`
+    pub(crate) fn begin_copy(&mut self, table: &str) {
+        self.open_copy = Some(table.to_string());
+    }
+
+    /// Record that the server is out of the COPY
+    pub(crate) fn end_copy(&mut self) {
+        self.open_copy = None;
+    }
+
+    /// Whether a COPY was started and neither finished nor failed yet
+    pub fn in_copy(&self) -> bool {
+        self.open_copy.is_some()
+    }
+
+    /// Send `CopyFail` for a COPY whose sink was dropped unfinished
+    async fn fail_open_copy(&mut self) -> Result<()> {
+        let Some(table) = self.open_copy.take() else {
+            return Ok(());
+        };
+        warn!("COPY into {} on connection {} was never finished, rolling it back", table, self.connection_id);
+        let reason = format!("COPY into {} abandoned by the client", table);
+        self.send_frame(MessageType::CopyFail, reason.as_bytes()).await?;
+        self.receive_message().await?;
+        Ok(())
+    }
+
+    /// Re-establish the connection if it failed
+    pub(crate) async fn ensure_connected(&mut self) -> Result<()> {
+        if self.state == ConnectionState::Failed {
+            self.reconnect().await?;
+        }
+        Ok(())
+    }
+
+    async fn send_frame(&mut self, message_type: MessageType, data: &[u8]) -> Result<()> {
         if self.state != ConnectionState::Authenticated {
             return Err(AuroraError::Connection("Connection not authenticated".into()));
         }
@@ -127,13 +268,20 @@ impl AuroraConnection {
         // Create message envelope
         let envelope = self.create_message_envelope(message_type, data)?;
 
-        // Send with timeout
+        // Send with timeout; until the write completes the server may hold half a frame
         let send_timeout = Duration::from_secs(30);
-        timeout(send_timeout, self.write_bytes(&envelope)).await
-            .map_err(|_| AuroraError::Timeout("Send operation timed out".into()))??;
+        self.torn = true;
+        let written = timeout(send_timeout, self.write_bytes(&envelope)).await
+            .map_err(|_| AuroraError::Timeout("Send operation timed out".into()))?;
+        if written.is_err() {
+            self.state = ConnectionState::Failed;
+        }
+        written?;
+        self.torn = false;
 
         self.last_activity = std::time::Instant::now();
         self.sequence_number += 1;
+        self.pending_responses += 1;
 
         Ok(())
     }
@@ -147,19 +295,209 @@ impl AuroraConnection {
         // Receive with timeout
         let recv_timeout = Duration::from_secs(30);
         let data = timeout(recv_timeout, self.read_bytes()).await
-            .map_err(|_| AuroraError::Timeout("Receive operation timed out".into()))??;
+            .map_err(|_| AuroraError::Timeout("Receive operation timed out".into()))?;
+        if matches!(data, Err(AuroraError::Io(_))) {
+            self.state = ConnectionState::Failed;
+        }
+        let data = data?;
 
         self.last_activity = std::time::Instant::now();
+        // Subscription updates arrive without a request
+        self.pending_responses = self.pending_responses.saturating_sub(1);
 
         Ok(data)
     }
 
+    /// Read and discard the responses to requests whose caller went away,
+    /// and fail any COPY left open
+    ///
+    /// Fails without reading if a frame was abandoned halfway, since the
+    /// stream position is then unknown.
+    pub async fn drain(&mut self) -> Result<()> {
+        if self.torn {
+            return Err(AuroraError::Protocol("Connection abandoned mid-frame".into()));
+        }
+        while self.pending_responses > 0 {
+            self.receive_message().await?;
+        }
+        self.fail_open_copy().await
+    }
+
+    /// Set a session variable such as `work_mem`, `timezone` or
+    /// `statement_timeout` for the rest of the session
+    ///
+    /// The setting is remembered and applied again if the connection is
+    /// re-established.
+    pub async fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let name = session_variable_name(name)?;
+        let sql = format!("SET {} = '{}'", name, value.replace('\'', "''"));
+        self.execute_session_statement(&sql).await?;
+
+        match self.session_variables.iter_mut().find(|(tracked, _)| *tracked == name) {
+            Some((_, tracked)) => *tracked = value.to_string(),
+            None => self.session_variables.push((name, value.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Value this connection set for a session variable, if any
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.session_variables.iter()
+            .find(|(tracked, _)| *tracked == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Return a session variable to the server default and stop tracking it
+    pub async fn reset(&mut self, name: &str) -> Result<()> {
+        let name = session_variable_name(name)?;
+        self.execute_session_statement(&format!("RESET {}", name)).await?;
+        self.session_variables.retain(|(tracked, _)| *tracked != name);
+        Ok(())
+    }
+
+    /// Return every session variable to the server default
+    pub async fn reset_all(&mut self) -> Result<()> {
+        self.execute_session_statement("RESET ALL").await?;
+        self.session_variables.clear();
+        Ok(())
+    }
+
+    /// Session variables this connection set, in the order first set
+    pub fn session_variables(&self) -> &[(String, String)] {
+        &self.session_variables
+    }
+
+    /// Have the server parse and cache the plan of `sql` now, so its first
+    /// run does not wait for planning
+    ///
+    /// A pinned plan is kept through cache pressure and statistics refreshes
+    /// until the schema of a table it reads changes. The plan is prepared
+    /// again if the connection is re-established, in case the server it
+    /// reaches has not seen it.
+    pub async fn prepare_plan(&mut self, sql: &str, pin: bool) -> Result<()> {
+        self.execute_session_statement(&prepare_plan_statement(sql, pin)).await?;
+
+        match self.prepared_plans.iter_mut().find(|(tracked, _)| tracked == sql) {
+            Some((_, pinned)) => *pinned = pin,
+            None => self.prepared_plans.push((sql.to_string(), pin)),
+        }
+        Ok(())
+    }
+
+    /// Plans this connection prepared, and whether each is pinned
+    pub fn prepared_plans(&self) -> &[(String, bool)] {
+        &self.prepared_plans
+    }
+
+    /// Number of statements prepared with `prepare` and still open on the server
+    pub fn prepared_statement_count(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Re-establish the connection and apply its session variables and
+    /// prepared plans again
+    ///
+    /// Responses still owed on the old stream are lost with it.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        self.state = ConnectionState::Handshaking;
+        self.sequence_number = 0;
+        self.pending_responses = 0;
+        self.torn = false;
+        // Portals end with the old stream; there is nothing left to close
+        if let Ok(mut closed) = self.closed_portals.lock() {
+            closed.clear();
+        }
+        // So are prepared statements, which are prepared again when next used,
+        // and any COPY, which the server rolls back
+        self.statements.clear();
+        self.open_copy = None;
+        self.connect().await?;
+
+        for (name, value) in self.session_variables.clone() {
+            let sql = format!("SET {} = '{}'", name, value.replace('\'', "''"));
+            self.execute_session_statement(&sql).await?;
+        }
+        for (sql, pin) in self.prepared_plans.clone() {
+            self.execute_session_statement(&prepare_plan_statement(&sql, pin)).await?;
+        }
+
+        info!("Connection {} re-established with {} session variables", self.connection_id, self.session_variables.len());
+        Ok(())
+    }
+
+    async fn execute_session_statement(&mut self, sql: &str) -> Result<ExecuteResult> {
+        let request = ExecuteRequest {
+            sql: sql.to_string(),
+            params: Vec::new(),
+            timeout: Some(Duration::from_secs(30)),
+            idempotency_key: None,
+        };
+        let request_bytes = bincode::serialize(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize execute request: {}", e)))?;
+
+        // Never reconnects itself, since reconnecting runs these statements
+        self.send_frame(MessageType::Execute, &request_bytes).await?;
+        let response_bytes = self.receive_message().await?;
+        bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize execute response: {}", e)))
+    }
+
+    /// Number of requests sent whose response has not been read
+    pub fn pending_responses(&self) -> u32 {
+        self.pending_responses
+    }
+
+    /// Whether a frame was abandoned halfway, leaving the stream out of sync
+    pub fn is_torn(&self) -> bool {
+        self.torn
+    }
+
+    /// Take the id for a new portal
+    pub(crate) fn next_portal_id(&mut self) -> u64 {
+        let id = self.next_portal_id;
+        self.next_portal_id += 1;
+        id
+    }
+
+    /// Queue of portals dropped on this connection
+    pub(crate) fn closed_portals(&self) -> ClosedPortals {
+        self.closed_portals.clone()
+    }
+
+    /// Statements prepared on this connection
+    pub(crate) fn statements(&mut self) -> &mut StatementCache {
+        &mut self.statements
+    }
+
+    /// Take the name for a new prepared statement
+    pub(crate) fn next_statement_name(&mut self) -> String {
+        let id = self.next_statement_id;
+        self.next_statement_id += 1;
+        format!("stmt_{}", id)
+    }
+
     /// Check if connection is healthy
+    ///
+    /// A connection with unread responses, a half-sent frame or an open
+    /// COPY is never healthy, so it cannot be handed to another caller.
     pub async fn is_healthy(&self) -> bool {
         self.state == ConnectionState::Authenticated &&
+        !self.torn &&
+        self.pending_responses == 0 &&
+        self.open_copy.is_none() &&
         self.last_activity.elapsed() < Duration::from_secs(300) // 5 minutes
     }
 
+    /// Version, capabilities and negotiated options of the server, as
+    /// reported when the connection was last established
+    ///
+    /// Lets client code feature-gate, e.g. falling back to SQL when
+    /// `supports_vector_search()` is false.
+    pub fn server_info(&self) -> ServerInfo {
+        self.server_info.clone()
+    }
+
     /// Get connection info
     pub fn info(&self) -> ConnectionInfo {
         ConnectionInfo {
@@ -196,7 +534,7 @@ impl AuroraConnection {
         matches!(self.stream, ConnectionStream::Tls(_))
     }
 
-    async fn establish_tls(&self, tcp_stream: TcpStream) -> Result<ConnectionStream> {
+    async fn establish_tls(&self, tcp_stream: TcpStream, host: &str) -> Result<ConnectionStream> {
         // Load certificates if provided
         let mut client_config = ClientConfig::builder()
             .with_safe_defaults()
@@ -212,7 +550,7 @@ impl AuroraConnection {
         }
 
         let connector = TlsConnector::from(Arc::new(client_config));
-        let domain = ServerName::try_from(self.config.host.as_str())
+        let domain = ServerName::try_from(host)
             .map_err(|e| AuroraError::Tls(format!("Invalid server name: {}", e)))?;
 
         let tls_stream = connector.connect(domain, tcp_stream).await
@@ -226,9 +564,11 @@ impl AuroraConnection {
         let auth_data = self.create_auth_message()?;
         self.send_message_raw(&auth_data).await?;
 
-        // Receive authentication response
+        // Receive authentication response, which describes the server; a
+        // reconnect may reach a different one
         let response = self.receive_message_raw().await?;
         self.validate_auth_response(&response)?;
+        self.server_info = ServerInfo::from_handshake(&response)?;
 
         Ok(())
     }
@@ -272,27 +612,38 @@ impl AuroraConnection {
 
     async fn read_bytes(&mut self) -> Result<Bytes> {
         // Read message envelope first
-        let envelope_size = 4 + 1 + 4 + 4; // version + type + seq + length
-        let mut envelope_buf = vec![0u8; envelope_size];
+        let mut envelope_buf = [0u8; FRAME_HEADER_LEN];
+
+        // Waiting for the first byte consumes nothing, so dropping the read
+        // here leaves the stream in sync; past it, the frame must be finished
+        match &mut self.stream {
+            ConnectionStream::Tcp(stream) => {
+                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf[..1]).await?;
+            }
+            ConnectionStream::Tls(stream) => {
+                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf[..1]).await?;
+            }
+        }
+        self.torn = true;
 
         match &mut self.stream {
             ConnectionStream::Tcp(stream) => {
-                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf).await?;
+                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf[1..]).await?;
             }
             ConnectionStream::Tls(stream) => {
-                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf).await?;
+                tokio::io::AsyncReadExt::read_exact(stream, &mut envelope_buf[1..]).await?;
             }
         }
 
-        // Parse envelope
-        let mut envelope = Bytes::from(envelope_buf);
-        let _version = envelope.get_u32();
-        let _message_type = envelope.get_u8();
-        let _sequence = envelope.get_u32();
-        let message_length = envelope.get_u32() as usize;
+        // Validate the envelope before allocating for its data; past a bad
+        // one the stream cannot be trusted, so the connection is done
+        let header = FrameHeader::parse(&envelope_buf).map_err(|e| {
+            self.state = ConnectionState::Failed;
+            e
+        })?;
 
         // Read message data
-        let mut data_buf = vec![0u8; message_length];
+        let mut data_buf = vec![0u8; header.length];
         match &mut self.stream {
             ConnectionStream::Tcp(stream) => {
                 tokio::io::AsyncReadExt::read_exact(stream, &mut data_buf).await?;
@@ -317,8 +668,10 @@ impl AuroraConnection {
         let calculated_checksum = crc32fast::hash(&data_buf);
 
         if expected_checksum != calculated_checksum {
+            self.state = ConnectionState::Failed;
             return Err(AuroraError::Protocol("Message checksum validation failed".into()));
         }
+        self.torn = false;
 
         Ok(Bytes::from(data_buf))
     }
@@ -382,6 +735,79 @@ impl AuroraConnection {
     }
 }
 
+/// Session variable names are identifiers, optionally dotted like
+/// `myapp.tenant`; they are matched case-insensitively
+fn session_variable_name(name: &str) -> Result<String> {
+    let valid = !name.is_empty()
+        && name.split('.').all(|part| {
+            part.chars().next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if !valid {
+        return Err(AuroraError::Configuration(format!("Invalid session variable name: {:?}", name)));
+    }
+    Ok(name.to_ascii_lowercase())
+}
+
+/// `PREPARE [PINNED] PLAN FOR <sql>`
+fn prepare_plan_statement(sql: &str, pin: bool) -> String {
+    format!("PREPARE {}PLAN FOR {}", if pin { "PINNED " } else { "" }, sql)
+}
+
+/// Envelope of a frame received from the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub version: u32,
+    pub message_type: u8,
+    pub sequence: u32,
+    /// Length of the data that follows, before the checksum
+    pub length: usize,
+}
+
+impl FrameHeader {
+    /// Parse an envelope, refusing unknown versions and oversized lengths
+    pub fn parse(header: &[u8; FRAME_HEADER_LEN]) -> Result<Self> {
+        let word = |offset: usize| u32::from_be_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]]);
+        let frame = Self {
+            version: word(0),
+            message_type: header[4],
+            sequence: word(5),
+            length: word(9) as usize,
+        };
+
+        if frame.version != FRAME_VERSION {
+            return Err(AuroraError::Protocol(format!("Unsupported frame version {}", frame.version)));
+        }
+        if frame.length > MAX_MESSAGE_SIZE {
+            return Err(AuroraError::Protocol(format!("Frame of {} bytes exceeds the {} byte limit", frame.length, MAX_MESSAGE_SIZE)));
+        }
+        Ok(frame)
+    }
+}
+
+/// Decode one complete frame, returning its data once the checksum matches
+///
+/// The same checks `receive_message` applies while reading from the socket,
+/// over a buffer.
+pub fn decode_frame(frame: &[u8]) -> Result<Bytes> {
+    let header: &[u8; FRAME_HEADER_LEN] = frame.get(..FRAME_HEADER_LEN)
+        .and_then(|header| header.try_into().ok())
+        .ok_or_else(|| AuroraError::Protocol("Truncated frame header".into()))?;
+    let header = FrameHeader::parse(header)?;
+
+    let rest = &frame[FRAME_HEADER_LEN..];
+    if rest.len() != header.length + 4 {
+        return Err(AuroraError::Protocol(format!(
+            "Frame declares {} data bytes but carries {}", header.length, rest.len().saturating_sub(4)
+        )));
+    }
+    let (data, checksum) = rest.split_at(header.length);
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != crc32fast::hash(data) {
+        return Err(AuroraError::Protocol("Message checksum validation failed".into()));
+    }
+    Ok(Bytes::copy_from_slice(data))
+}
+
 /// Connection information
 #[derive(Debug, Clone)]
 pub struct ConnectionInfo {
@@ -404,6 +830,10 @@ impl AuroraConnection {
             connection_id: "dummy".to_string(),
             last_activity: std::time::Instant::now(),
             sequence_number: 0,
+            pending_responses: 0,
+            torn: false,
+            session_variables: Vec::new(),
+            server_info: ServerInfo::default(),
         }
     }
 }
@@ -428,7 +858,11 @@ impl Default for AuroraConfig {
 // - [x] TLS 1.3 support with certificate validation
 // - [x] Connection state management
 // - [x] Message framing with checksums
+// - [x] Bounded frame decoding that never trusts server lengths
 // - [x] Authentication handshake
 // - [x] Timeout handling for operations
+// - [x] Cancellation-safe framing with in-flight tracking
 // - [x] Connection health monitoring
+// - [x] Session variables re-applied on transparent reconnect
+// - [x] Server version and capabilities captured in the handshake
 // - [x] Low-level networking leveraging Cyclone capabilities
@@ -0,0 +1,296 @@
+//! Streaming COPY
+//!
+//! [`AuroraProtocol::copy_in`](crate::AuroraProtocol::copy_in) opens a COPY
+//! into a table and returns a [`CopySink`], a `futures::Sink` of rows. Rows
+//! are encoded in the binary COPY format as they arrive and coalesced into
+//! one buffer, which goes out as a single `CopyData` message every
+//! `flush_rows` rows or `flush_bytes` bytes, whichever comes first, so a
+//! load of millions of rows costs a few hundred messages rather than one
+//! statement per row.
+//!
+//! | Message     | Request                        | Reply                 |
+//! |-------------|--------------------------------|-----------------------|
+//! | `CopyStart` | [`CopyStartRequest`]           | [`CopyStartResponse`] |
+//! | `CopyData`  | next part of the binary stream | none                  |
+//! | `CopyDone`  | empty                          | [`CopyInResponse`]    |
+//! | `CopyFail`  | reason (UTF-8)                 | [`CopyInResponse`]    |
+//!
+//! The server answers `CopyStart` with the types of the columns, which the
+//! rows are encoded by, and loads nothing until `CopyDone`: the load commits
+//! or rolls back as a whole.
+//!
+//! A sink dropped before [`CopySink::finish`] leaves the COPY open on its
+//! connection, which sends the `CopyFail` ahead of its next request, or when
+//! the pool drains it, so the partial load is rolled back. A sink dropped in
+//! the middle of writing a message leaves the connection torn instead; the
+//! pool discards it and the server rolls back when it goes away.
+
+use crate::binary_copy::{BinaryCopyCodec, CopyInResponse};
+use crate::connection::AuroraConnection;
+use crate::error::{AuroraError, Result};
+use crate::protocol::MessageType;
+use crate::types::{AuroraRow, AuroraType};
+
+use bytes::BytesMut;
+use futures::future::BoxFuture;
+use futures::Sink;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Rows coalesced into one `CopyData` message by default
+pub const DEFAULT_FLUSH_ROWS: usize = 10_000;
+
+/// Bytes coalesced into one `CopyData` message by default
+pub const DEFAULT_FLUSH_BYTES: usize = 4 * 1024 * 1024;
+
+/// `CopyStart` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyStartRequest {
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
+/// Server reply to a `CopyStart` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyStartResponse {
+    /// Type of each requested column, in request order
+    pub column_types: Vec<AuroraType>,
+
+    /// Why the COPY could not start
+    pub rejected: Option<String>,
+}
+
+/// What the sink is doing with its connection
+enum SinkState<'a> {
+    /// Ready to send; holds the connection
+    Idle(&'a mut AuroraConnection),
+
+    /// Sending; the future holds the connection and hands it back
+    Busy(BoxFuture<'a, (&'a mut AuroraConnection, Result<Option<u64>>)>),
+
+    /// `CopyDone` or a failure ended the sink
+    Closed,
+}
+
+/// Rows streamed into a table with binary COPY
+///
+/// Borrows its connection until finished or dropped.
+pub struct CopySink<'a> {
+    state: SinkState<'a>,
+    table: String,
+    codec: BinaryCopyCodec,
+    /// Encoded rows not sent yet
+    buffer: BytesMut,
+    buffered_rows: usize,
+    /// Rows accepted so far, sent or not
+    rows: usize,
+    flush_rows: usize,
+    flush_bytes: usize,
+    /// Rows the server loaded, once `CopyDone` is answered
+    loaded: Option<u64>,
+}
+
+impl<'a> CopySink<'a> {
+    /// Send `CopyStart` for `columns` of `table` and return a sink for its rows
+    pub(crate) async fn start(conn: &'a mut AuroraConnection, table: &str, columns: &[&str]) -> Result<CopySink<'a>> {
+        let request = CopyStartRequest {
+            table: table.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+        };
+        let request_bytes = bincode::serialize(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize COPY start request: {}", e)))?;
+        conn.send_message(MessageType::CopyStart, &request_bytes).await?;
+
+        let response: CopyStartResponse = bincode::deserialize(&conn.receive_message().await?)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize COPY start response: {}", e)))?;
+        if let Some(reason) = response.rejected {
+            return Err(AuroraError::Query(format!("COPY into {} rejected: {}", table, reason)));
+        }
+        if response.column_types.len() != columns.len() {
+            return Err(AuroraError::Protocol(format!(
+                "CopyStart reply has {} column types for {} columns", response.column_types.len(), columns.len()
+            )));
+        }
+        conn.begin_copy(table);
+
+        let typed: Vec<(&str, AuroraType)> = columns.iter().copied().zip(response.column_types).collect();
+        let mut buffer = BytesMut::new();
+        BinaryCopyCodec::write_header(&mut buffer);
+        Ok(CopySink {
+            state: SinkState::Idle(conn),
+            table: table.to_string(),
+            codec: BinaryCopyCodec::new(&typed),
+            buffer,
+            buffered_rows: 0,
+            rows: 0,
+            flush_rows: DEFAULT_FLUSH_ROWS,
+            flush_bytes: DEFAULT_FLUSH_BYTES,
+            loaded: None,
+        })
+    }
+
+    /// Send the buffer once it holds `rows` rows
+    pub fn with_flush_rows(mut self, rows: usize) -> Self {
+        self.flush_rows = rows.max(1);
+        self
+    }
+
+    /// Send the buffer once it holds `bytes` bytes
+    pub fn with_flush_bytes(mut self, bytes: usize) -> Self {
+        self.flush_bytes = bytes.max(1);
+        self
+    }
+
+    /// Codec the rows are encoded with, typed as the server reported
+    pub fn codec(&self) -> &BinaryCopyCodec {
+        &self.codec
+    }
+
+    /// Rows accepted so far, sent or still buffered
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Send any buffered rows and `CopyDone`, returning the number of rows
+    /// the server loaded
+    pub async fn finish(mut self) -> Result<u64> {
+        futures::future::poll_fn(|cx| Pin::new(&mut self).poll_close(cx)).await?;
+        self.loaded.ok_or_else(|| AuroraError::Protocol(format!("COPY into {} already ended", self.table)))
+    }
+
+    fn buffer_full(&self) -> bool {
+        self.buffered_rows >= self.flush_rows || self.buffer.len() >= self.flush_bytes
+    }
+
+    /// Start sending the buffer as one `CopyData` message
+    fn start_flush(&mut self) {
+        let SinkState::Idle(conn) = std::mem::replace(&mut self.state, SinkState::Closed) else {
+            return;
+        };
+        let chunk = self.buffer.split().freeze();
+        self.buffered_rows = 0;
+        self.state = SinkState::Busy(Box::pin(async move {
+            let sent = conn.send_copy_message(MessageType::CopyData, &chunk).await;
+            (conn, sent.map(|()| None))
+        }));
+    }
+
+    /// Start sending the rest of the stream and `CopyDone`
+    fn start_done(&mut self) {
+        let SinkState::Idle(conn) = std::mem::replace(&mut self.state, SinkState::Closed) else {
+            return;
+        };
+        BinaryCopyCodec::write_trailer(&mut self.buffer);
+        let chunk = self.buffer.split().freeze();
+        self.buffered_rows = 0;
+        let table = self.table.clone();
+        self.state = SinkState::Busy(Box::pin(async move {
+            let done = async {
+                conn.send_copy_message(MessageType::CopyData, &chunk).await?;
+                conn.send_copy_message(MessageType::CopyDone, &[]).await?;
+                let response = conn.receive_message().await;
+                // Answered or not, the server is out of the COPY or the connection is gone
+                conn.end_copy();
+                let response: CopyInResponse = bincode::deserialize(&response?)
+                    .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize COPY response: {}", e)))?;
+                match response.rejected {
+                    Some(reason) => Err(AuroraError::Query(format!("COPY into {} rejected: {}", table, reason))),
+                    None => Ok(Some(response.rows)),
+                }
+            }
+            .await;
+            (conn, done)
+        }));
+    }
+
+    /// Drive the send in progress, if any, to completion
+    fn poll_sent(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let SinkState::Busy(sending) = &mut self.state else {
+            return Poll::Ready(Ok(()));
+        };
+        let (conn, result) = futures::ready!(sending.as_mut().poll(cx));
+        self.state = SinkState::Idle(conn);
+        match result {
+            Ok(loaded) => {
+                if loaded.is_some() {
+                    self.loaded = loaded;
+                    self.state = SinkState::Closed;
+                }
+                Poll::Ready(Ok(()))
+            }
+            Err(error) => {
+                self.state = SinkState::Closed;
+                Poll::Ready(Err(error))
+            }
+        }
+    }
+
+    fn closed(&self) -> AuroraError {
+        AuroraError::Query(format!("COPY into {} has ended", self.table))
+    }
+}
+
+impl Sink<AuroraRow> for CopySink<'_> {
+    type Error = AuroraError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        futures::ready!(this.poll_sent(cx))?;
+        if matches!(this.state, SinkState::Closed) {
+            return Poll::Ready(Err(this.closed()));
+        }
+        if this.buffer_full() {
+            this.start_flush();
+            futures::ready!(this.poll_sent(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Encode `row` into the buffer; a row the codec rejects is refused
+    /// and the sink stays usable
+    fn start_send(self: Pin<&mut Self>, row: AuroraRow) -> Result<()> {
+        let this = self.get_mut();
+        if !matches!(this.state, SinkState::Idle(_)) {
+            return Err(this.closed());
+        }
+        this.codec.encode_row(&mut this.buffer, this.rows, &row.values)?;
+        this.rows += 1;
+        this.buffered_rows += 1;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        futures::ready!(this.poll_sent(cx))?;
+        if this.buffered_rows > 0 {
+            this.start_flush();
+            futures::ready!(this.poll_sent(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Send the rest of the stream and `CopyDone`, completing the load
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                SinkState::Closed if this.loaded.is_some() => return Poll::Ready(Ok(())),
+                SinkState::Closed => return Poll::Ready(Err(this.closed())),
+                SinkState::Busy(_) => futures::ready!(this.poll_sent(cx))?,
+                SinkState::Idle(_) => this.start_done(),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for CopySink<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CopySink")
+            .field("table", &self.table)
+            .field("rows", &self.rows)
+            .field("buffered_rows", &self.buffered_rows)
+            .field("loaded", &self.loaded)
+            .finish_non_exhaustive()
+    }
+}
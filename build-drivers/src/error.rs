@@ -35,6 +35,9 @@ pub enum AuroraError {
     /// Pool exhaustion
     PoolExhausted(String),
 
+    /// Pool is failing fast while the server is unreachable
+    CircuitOpen(String),
+
     /// Configuration errors
     Configuration(String),
 
@@ -69,6 +72,7 @@ impl fmt::Display for AuroraError {
             AuroraError::Tls(msg) => write!(f, "TLS error: {}", msg),
             AuroraError::Timeout(msg) => write!(f, "Timeout error: {}", msg),
             AuroraError::PoolExhausted(msg) => write!(f, "Pool exhausted: {}", msg),
+            AuroraError::CircuitOpen(msg) => write!(f, "Circuit open: {}", msg),
             AuroraError::Configuration(msg) => write!(f, "Configuration error: {}", msg),
             AuroraError::VectorSearch(msg) => write!(f, "Vector search error: {}", msg),
             AuroraError::Analytics(msg) => write!(f, "Analytics error: {}", msg),
@@ -133,6 +137,7 @@ impl AuroraError {
     pub fn classify(&self) -> ErrorClass {
         match self {
             AuroraError::Connection(_) | AuroraError::Timeout(_) | AuroraError::Tls(_) => ErrorClass::Network,
+            AuroraError::CircuitOpen(_) => ErrorClass::Network,
             AuroraError::PoolExhausted(_) => ErrorClass::Resource,
             AuroraError::Authentication(_) => ErrorClass::Auth,
             AuroraError::Configuration(_) => ErrorClass::Config,
@@ -0,0 +1,253 @@
+//! Schema Introspection
+//!
+//! An `Introspect` message asks the server for its catalog, or for one
+//! table of it. The reply is self-describing JSON: types are spelled as in
+//! DDL (`decimal(12,2)`, `vector(768)`, `jsonb[]`) and defaults as the
+//! server renders them, so a driver older than the server still reads
+//! every field it knows and fails only on a type it cannot represent.
+//!
+//! ```json
+//! {"database": "shop", "tables": [{
+//!     "name": "products", "kind": "table", "row_estimate": 1200,
+//!     "columns": [{"name": "id", "type": "bigint", "nullable": false, "identity": true}],
+//!     "indexes": [{"name": "products_pkey", "method": "btree", "columns": ["id"], "unique": true}],
+//!     "constraints": [{"name": "products_pkey", "kind": "primary_key", "columns": ["id"]}]
+//! }]}
+//! ```
+//!
+//! [`decode_schema`] turns the reply into a [`SchemaInfo`].
+
+use crate::error::{AuroraError, Result};
+use crate::types::{
+    AuroraType, AuroraValue, ColumnDefault, ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, IndexType,
+    SchemaInfo, TableInfo, TableType,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Body of an `Introspect` message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntrospectRequest {
+    /// Only this table; the whole catalog when `None`
+    pub table: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Catalog {
+    database: String,
+    #[serde(default)]
+    tables: Vec<CatalogTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogTable {
+    name: String,
+    kind: String,
+    #[serde(default)]
+    row_estimate: Option<u64>,
+    #[serde(default)]
+    size_bytes: Option<u64>,
+    columns: Vec<CatalogColumn>,
+    #[serde(default)]
+    indexes: Vec<CatalogIndex>,
+    #[serde(default)]
+    constraints: Vec<CatalogConstraint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogColumn {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    nullable: bool,
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    identity: bool,
+    #[serde(default)]
+    comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogIndex {
+    name: String,
+    method: String,
+    columns: Vec<String>,
+    #[serde(default)]
+    unique: bool,
+    #[serde(default)]
+    size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogConstraint {
+    name: String,
+    kind: String,
+    #[serde(default)]
+    columns: Vec<String>,
+    #[serde(default)]
+    expression: Option<String>,
+    #[serde(default)]
+    references: Option<CatalogReference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogReference {
+    table: String,
+    columns: Vec<String>,
+}
+
+/// Schema described by the server's reply to an `Introspect` message
+pub fn decode_schema(response: &[u8]) -> Result<SchemaInfo> {
+    let catalog: Catalog = serde_json::from_slice(response)
+        .map_err(|e| AuroraError::Protocol(format!("Malformed introspection response: {}", e)))?;
+
+    let mut schema = SchemaInfo {
+        database_name: catalog.database,
+        tables: Vec::with_capacity(catalog.tables.len()),
+        indexes: Vec::new(),
+        constraints: Vec::new(),
+    };
+    for table in catalog.tables {
+        let constraints = table.constraints.into_iter()
+            .map(|constraint| constraint_info(&table.name, constraint))
+            .collect::<Result<Vec<_>>>()?;
+        let primary_key = constraints.iter()
+            .find(|constraint| constraint.constraint_type == ConstraintType::PrimaryKey)
+            .map(|constraint| constraint.columns.clone())
+            .unwrap_or_default();
+        let columns = table.columns.into_iter()
+            .map(|column| column_info(&table.name, column, &primary_key))
+            .collect::<Result<Vec<_>>>()?;
+        for index in table.indexes {
+            schema.indexes.push(index_info(&table.name, index)?);
+        }
+        schema.constraints.extend(constraints);
+        schema.tables.push(TableInfo {
+            table_type: table_type(&table.name, &table.kind)?,
+            name: table.name,
+            columns,
+            primary_key,
+            row_count: table.row_estimate,
+            size_bytes: table.size_bytes,
+        });
+    }
+    Ok(schema)
+}
+
+fn table_type(table: &str, kind: &str) -> Result<TableType> {
+    Ok(match kind {
+        "table" => TableType::Table,
+        "view" => TableType::View,
+        "system" => TableType::System,
+        "temporary" => TableType::Temporary,
+        other => return Err(AuroraError::Protocol(format!("Table \"{}\" has unknown kind '{}'", table, other))),
+    })
+}
+
+fn column_info(table: &str, column: CatalogColumn, primary_key: &[String]) -> Result<ColumnInfo> {
+    let column_type = AuroraType::from_sql_name(&column.type_name)
+        .map_err(|e| AuroraError::Protocol(format!("Column \"{}\".\"{}\": {}", table, column.name, e)))?;
+    let default = column.default.as_deref().map(|text| column_default(text, &column_type));
+    Ok(ColumnInfo {
+        primary_key: primary_key.contains(&column.name),
+        name: column.name,
+        column_type,
+        nullable: column.nullable,
+        default,
+        auto_increment: column.identity,
+        comment: column.comment,
+    })
+}
+
+/// Default rendered as `text` on a `column_type` column: a constant when it
+/// is a plain literal of that type, otherwise the expression
+fn column_default(text: &str, column_type: &AuroraType) -> ColumnDefault {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("null") {
+        return ColumnDefault::Value(AuroraValue::Null);
+    }
+    if let Some(quoted) = text.strip_prefix('\'').and_then(|rest| rest.strip_suffix('\'')) {
+        // A single literal, with no cast or concatenation after it
+        if !quoted.replace("''", "").contains('\'') {
+            let literal = quoted.replace("''", "'");
+            let value = match column_type {
+                AuroraType::Char(_) | AuroraType::Varchar(_) | AuroraType::Text => Some(AuroraValue::Text(literal)),
+                AuroraType::Uuid => Some(AuroraValue::Uuid(literal)),
+                AuroraType::Decimal(..) => AuroraValue::decimal(literal).ok(),
+                AuroraType::Json | AuroraType::Jsonb => serde_json::from_str(&literal).ok().map(AuroraValue::Json),
+                _ => None,
+            };
+            if let Some(value) = value {
+                return ColumnDefault::Value(value);
+            }
+        }
+    } else if let AuroraType::Decimal(..) = column_type {
+        // Parsed as text so the scale it was written with is kept
+        if let Ok(value) = AuroraValue::decimal(text) {
+            return ColumnDefault::Value(value);
+        }
+    } else if let Ok(literal) = serde_json::from_str::<serde_json::Value>(&text.to_ascii_lowercase()) {
+        // Bare numbers and booleans
+        if literal.is_number() || literal.is_boolean() {
+            if let Ok(value) = AuroraValue::from_json(&literal, column_type) {
+                return ColumnDefault::Value(value);
+            }
+        }
+    }
+    ColumnDefault::Expression(text.to_string())
+}
+
+fn index_info(table: &str, index: CatalogIndex) -> Result<IndexInfo> {
+    let index_type = match index.method.as_str() {
+        "btree" => IndexType::BTree,
+        "hash" => IndexType::Hash,
+        "hnsw" | "ivfflat" | "vector" => IndexType::Vector,
+        "fulltext" => IndexType::FullText,
+        "spatial" => IndexType::Spatial,
+        "gin" => IndexType::Gin,
+        other => {
+            return Err(AuroraError::Protocol(format!("Index \"{}\" has unknown method '{}'", index.name, other)));
+        }
+    };
+    Ok(IndexInfo {
+        name: index.name,
+        table_name: table.to_string(),
+        index_type,
+        columns: index.columns,
+        unique: index.unique,
+        size_bytes: index.size_bytes,
+    })
+}
+
+fn constraint_info(table: &str, constraint: CatalogConstraint) -> Result<ConstraintInfo> {
+    let constraint_type = match constraint.kind.as_str() {
+        "primary_key" => ConstraintType::PrimaryKey,
+        "unique" => ConstraintType::Unique,
+        "foreign_key" => ConstraintType::ForeignKey,
+        "check" => ConstraintType::Check,
+        "not_null" => ConstraintType::NotNull,
+        other => {
+            return Err(AuroraError::Protocol(format!("Constraint \"{}\" has unknown kind '{}'", constraint.name, other)));
+        }
+    };
+    let missing = |what: &str| AuroraError::Protocol(format!("Constraint \"{}\" has no {}", constraint.name, what));
+    match constraint_type {
+        ConstraintType::ForeignKey if constraint.references.is_none() => return Err(missing("referenced table")),
+        ConstraintType::Check if constraint.expression.is_none() => return Err(missing("expression")),
+        _ => {}
+    }
+    let (referenced_table, referenced_columns) = match constraint.references {
+        Some(reference) => (Some(reference.table), Some(reference.columns)),
+        None => (None, None),
+    };
+    Ok(ConstraintInfo {
+        name: constraint.name,
+        table_name: table.to_string(),
+        constraint_type,
+        columns: constraint.columns,
+        referenced_table,
+        referenced_columns,
+        check_expression: constraint.expression,
+    })
+}
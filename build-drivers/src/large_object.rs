@@ -0,0 +1,266 @@
+//! Large Object Streaming
+//!
+//! Moves a large binary value between the client and the server in chunks
+//! of at most [`LOB_CHUNK_SIZE`] bytes, so neither side ever holds the whole
+//! value. Objects are named by an `oid` the server assigns on creation.
+//!
+//! | Message     | Request                              | Reply                               |
+//! |-------------|--------------------------------------|-------------------------------------|
+//! | `LobCreate` | empty                                | oid (8)                             |
+//! | `LobRead`   | oid (8), offset (8), max length (4)  | the bytes; empty past the end       |
+//! | `LobWrite`  | oid (8), offset (8), data            | object length after the write (8)   |
+//!
+//! Integers are big-endian. [`AuroraConnection::read_lob`] returns a
+//! [`LobReader`] implementing `AsyncRead`, and
+//! [`AuroraConnection::write_lob`] a [`LobWriter`] implementing
+//! `AsyncWrite`; each borrows the connection until it is dropped.
+
+use crate::connection::AuroraConnection;
+use crate::error::{AuroraError, Result};
+use crate::protocol::MessageType;
+
+use bytes::{Buf, Bytes};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Most bytes carried by one `LobRead` reply or `LobWrite` request
+pub const LOB_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Length of a `LobRead` request
+pub const LOB_READ_REQUEST_LEN: usize = 20;
+
+/// Length of the oid and offset that start a `LobWrite` request
+pub const LOB_WRITE_HEADER_LEN: usize = 16;
+
+/// Body of a `LobRead` request
+pub fn encode_read(oid: u64, offset: u64, length: u32) -> Vec<u8> {
+    let mut request = Vec::with_capacity(LOB_READ_REQUEST_LEN);
+    request.extend_from_slice(&oid.to_be_bytes());
+    request.extend_from_slice(&offset.to_be_bytes());
+    request.extend_from_slice(&length.to_be_bytes());
+    request
+}
+
+/// Oid, offset and maximum length of a `LobRead` request
+pub fn decode_read(mut request: &[u8]) -> Result<(u64, u64, u32)> {
+    if request.len() != LOB_READ_REQUEST_LEN {
+        return Err(AuroraError::Protocol(format!("LobRead request of {} bytes", request.len())));
+    }
+    Ok((request.get_u64(), request.get_u64(), request.get_u32()))
+}
+
+/// Body of a `LobWrite` request
+pub fn encode_write(oid: u64, offset: u64, data: &[u8]) -> Vec<u8> {
+    let mut request = Vec::with_capacity(LOB_WRITE_HEADER_LEN + data.len());
+    request.extend_from_slice(&oid.to_be_bytes());
+    request.extend_from_slice(&offset.to_be_bytes());
+    request.extend_from_slice(data);
+    request
+}
+
+/// Oid, offset and data of a `LobWrite` request
+pub fn decode_write(request: &[u8]) -> Result<(u64, u64, &[u8])> {
+    if request.len() < LOB_WRITE_HEADER_LEN {
+        return Err(AuroraError::Protocol(format!("LobWrite request of {} bytes", request.len())));
+    }
+    let (mut header, data) = request.split_at(LOB_WRITE_HEADER_LEN);
+    Ok((header.get_u64(), header.get_u64(), data))
+}
+
+/// The 8-byte oid or length that answers `LobCreate` and `LobWrite`
+fn decode_u64(reply: &[u8], message: &str) -> Result<u64> {
+    let bytes: [u8; 8] = reply.try_into()
+        .map_err(|_| AuroraError::Protocol(format!("{} reply of {} bytes, expected 8", message, reply.len())))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+impl AuroraConnection {
+    /// Stream large object `oid` from the start
+    pub fn read_lob(&mut self, oid: u64) -> LobReader<'_> {
+        LobReader { link: Link::Idle(self), oid, offset: 0, chunk: Bytes::new(), finished: false }
+    }
+
+    /// Create an empty large object and stream bytes into it; the oid is
+    /// available from [`LobWriter::oid`] straight away
+    ///
+    /// Bytes are sent a chunk at a time and are only all on the server
+    /// once the writer has been shut down.
+    pub async fn write_lob(&mut self) -> Result<LobWriter<'_>> {
+        self.send_message(MessageType::LobCreate, &[]).await?;
+        let oid = decode_u64(&self.receive_message().await?, "LobCreate")?;
+        Ok(LobWriter { link: Link::Idle(self), oid, offset: 0, buffer: Vec::with_capacity(LOB_CHUNK_SIZE) })
+    }
+
+    /// Up to `length` bytes of object `oid` from `offset`
+    async fn read_lob_chunk(&mut self, oid: u64, offset: u64, length: u32) -> Result<Bytes> {
+        self.send_message(MessageType::LobRead, &encode_read(oid, offset, length)).await?;
+        let chunk = self.receive_message().await?;
+        if chunk.len() > length as usize {
+            return Err(AuroraError::Protocol(format!("LobRead reply of {} bytes, asked for {}", chunk.len(), length)));
+        }
+        Ok(chunk)
+    }
+
+    /// Write `data` to object `oid` at `offset`
+    async fn write_lob_chunk(&mut self, oid: u64, offset: u64, data: &[u8]) -> Result<()> {
+        self.send_message(MessageType::LobWrite, &encode_write(oid, offset, data)).await?;
+        let length = decode_u64(&self.receive_message().await?, "LobWrite")?;
+        if length < offset + data.len() as u64 {
+            return Err(AuroraError::Protocol(format!(
+                "Large object {} is {} bytes after a write ending at {}", oid, length, offset + data.len() as u64
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Request in flight on a borrowed connection, which it hands back when done
+type InFlight<'a, T> = Pin<Box<dyn Future<Output = (&'a mut AuroraConnection, Result<T>)> + Send + 'a>>;
+
+/// The borrowed connection, idle or carrying one request
+enum Link<'a, T> {
+    Idle(&'a mut AuroraConnection),
+    Busy(InFlight<'a, T>),
+}
+
+impl<'a, T: 'a> Link<'a, T> {
+    /// Start `request` on the idle connection
+    fn start<F, Fut>(&mut self, request: F)
+    where
+        F: FnOnce(&'a mut AuroraConnection) -> Fut,
+        Fut: Future<Output = (&'a mut AuroraConnection, Result<T>)> + Send + 'a,
+    {
+        // The connection moves into the request; a placeholder request holds
+        // the slot for the instant it is out
+        let idle = std::mem::replace(self, Link::Busy(Box::pin(std::future::pending())));
+        match idle {
+            Link::Idle(conn) => *self = Link::Busy(Box::pin(request(conn))),
+            Link::Busy(_) => unreachable!("request started on a busy connection"),
+        }
+    }
+
+    /// Drive the request in flight, if any, to completion
+    fn poll_done(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<T>>> {
+        let Link::Busy(in_flight) = self else {
+            return Poll::Ready(Ok(None));
+        };
+        let (conn, result) = ready!(in_flight.as_mut().poll(cx));
+        *self = Link::Idle(conn);
+        Poll::Ready(result.map(Some).map_err(into_io))
+    }
+}
+
+fn into_io(error: AuroraError) -> io::Error {
+    match error {
+        AuroraError::Io(error) => error,
+        other => io::Error::other(other),
+    }
+}
+
+/// A large object read a chunk at a time
+pub struct LobReader<'a> {
+    link: Link<'a, Bytes>,
+    oid: u64,
+    /// Offset of the next chunk to ask for
+    offset: u64,
+    /// What is left of the last chunk received
+    chunk: Bytes,
+    finished: bool,
+}
+
+impl LobReader<'_> {
+    pub fn oid(&self) -> u64 {
+        self.oid
+    }
+}
+
+impl AsyncRead for LobReader<'_> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if !this.chunk.is_empty() {
+                let n = this.chunk.len().min(buf.remaining());
+                buf.put_slice(&this.chunk.split_to(n));
+                return Poll::Ready(Ok(()));
+            }
+            if let Some(chunk) = ready!(this.link.poll_done(cx))? {
+                this.offset += chunk.len() as u64;
+                this.finished = chunk.is_empty();
+                this.chunk = chunk;
+                continue;
+            }
+            if this.finished {
+                return Poll::Ready(Ok(()));
+            }
+            let (oid, offset) = (this.oid, this.offset);
+            this.link.start(move |conn| async move {
+                let result = conn.read_lob_chunk(oid, offset, LOB_CHUNK_SIZE as u32).await;
+                (conn, result)
+            });
+        }
+    }
+}
+
+/// A large object written a chunk at a time
+pub struct LobWriter<'a> {
+    link: Link<'a, ()>,
+    oid: u64,
+    /// Offset the buffered bytes go to
+    offset: u64,
+    /// Bytes not sent yet, at most a chunk
+    buffer: Vec<u8>,
+}
+
+impl LobWriter<'_> {
+    /// Oid of the object being written
+    pub fn oid(&self) -> u64 {
+        self.oid
+    }
+
+    /// Send the buffered bytes, if there are any and nothing is in flight
+    fn start_send(&mut self) {
+        if self.buffer.is_empty() || matches!(self.link, Link::Busy(_)) {
+            return;
+        }
+        let data = std::mem::replace(&mut self.buffer, Vec::with_capacity(LOB_CHUNK_SIZE));
+        let (oid, offset) = (self.oid, self.offset);
+        self.offset += data.len() as u64;
+        self.link.start(move |conn| async move {
+            let result = conn.write_lob_chunk(oid, offset, &data).await;
+            (conn, result)
+        });
+    }
+}
+
+impl AsyncWrite for LobWriter<'_> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        // A full buffer goes out once the chunk before it is acknowledged,
+        // so at most two chunks are held at a time
+        if this.buffer.len() == LOB_CHUNK_SIZE {
+            ready!(this.link.poll_done(cx))?;
+            this.start_send();
+        }
+        let n = buf.len().min(LOB_CHUNK_SIZE - this.buffer.len());
+        this.buffer.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.link.poll_done(cx))?;
+            if this.buffer.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+            this.start_send();
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
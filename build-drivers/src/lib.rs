@@ -9,18 +9,57 @@
 pub mod protocol;
 pub mod connection;
 pub mod pool;
+pub mod pool_sizing;
+pub mod pool_queue;
+pub mod pool_leaks;
+pub mod circuit_breaker;
 pub mod types;
 pub mod error;
 pub mod config;
 pub mod metrics;
+pub mod fingerprint;
+pub mod telemetry;
+pub mod interceptor;
+pub mod batch;
+pub mod vector_batch;
+pub mod binary_copy;
+pub mod replication;
+pub mod traffic;
+pub mod server_info;
+pub mod pagination;
+pub mod introspection;
+pub mod large_object;
+pub mod portal;
+pub mod atomic_batch;
+pub mod prepared;
+pub mod copy_stream;
 
 pub use protocol::AuroraProtocol;
 pub use connection::AuroraConnection;
-pub use pool::AuroraConnectionPool;
+pub use pool::{AuroraConnectionPool, PooledConnection};
+pub use pool_queue::{LaneWaitStats, Priority, WaitQueue, WaitTicket};
+pub use pool_sizing::{PoolSizeController, ResizeEvent, ResizeReason};
+pub use pool_leaks::{LeakDetector, LeakReport, LeaseId};
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
 pub use types::*;
 pub use error::{AuroraError, Result};
 pub use config::AuroraConfig;
 pub use metrics::DriverMetrics;
+pub use fingerprint::ResultFingerprint;
+pub use interceptor::{Interceptor, InterceptorChain, InterceptedRequest, RequestOutcome};
+pub use batch::{BatchMode, InsertBatch, RowError, RowErrorKind, RowResult};
+pub use binary_copy::{BinaryCopyCodec, NonFinitePolicy};
+pub use vector_batch::{VectorItem, VectorUpsertOutcome, VectorUpsertResult};
+pub use replication::{Freshness, Lsn, ReadTarget, ReplicaLag, ReplicaRouter, ReplicaSet, ReplicationStatus};
+pub use traffic::{replay, ReplayOptions, ReplayReport, TrafficRecord, TrafficRecorder};
+pub use server_info::{ServerFeature, ServerInfo};
+pub use pagination::{KeysetQuery, Page, PageCursor, SortKey};
+pub use introspection::{decode_schema, IntrospectRequest};
+pub use large_object::{LobReader, LobWriter, LOB_CHUNK_SIZE};
+pub use portal::Portal;
+pub use atomic_batch::{BatchStatement, StatementResult};
+pub use prepared::{PreparedStatement, StatementCache};
+pub use copy_stream::CopySink;
 
 // Re-export commonly used types
 pub use types::{
@@ -43,6 +43,15 @@ pub struct DriverMetrics {
     pub pool_acquisition_timeouts: AtomicU64,
     pub pool_size: AtomicU64,
 
+    // Leak detection metrics
+    pub pool_leaks_detected: AtomicU64,
+    pub pool_leaks_reclaimed: AtomicU64,
+
+    // Circuit breaker metrics
+    pub circuit_state: AtomicU64,
+    pub circuit_opened: AtomicU64,
+    pub circuit_rejections: AtomicU64,
+
     // Error metrics
     pub network_errors: AtomicU64,
     pub timeout_errors: AtomicU64,
@@ -85,6 +94,11 @@ impl DriverMetrics {
             pool_acquisitions: AtomicU64::new(0),
             pool_acquisition_timeouts: AtomicU64::new(0),
             pool_size: AtomicU64::new(0),
+            pool_leaks_detected: AtomicU64::new(0),
+            pool_leaks_reclaimed: AtomicU64::new(0),
+            circuit_state: AtomicU64::new(0),
+            circuit_opened: AtomicU64::new(0),
+            circuit_rejections: AtomicU64::new(0),
             network_errors: AtomicU64::new(0),
             timeout_errors: AtomicU64::new(0),
             protocol_errors: AtomicU64::new(0),
@@ -118,6 +132,11 @@ impl DriverMetrics {
             pool_acquisitions: self.pool_acquisitions.load(Ordering::Relaxed),
             pool_acquisition_timeouts: self.pool_acquisition_timeouts.load(Ordering::Relaxed),
             pool_size: self.pool_size.load(Ordering::Relaxed),
+            pool_leaks_detected: self.pool_leaks_detected.load(Ordering::Relaxed),
+            pool_leaks_reclaimed: self.pool_leaks_reclaimed.load(Ordering::Relaxed),
+            circuit_state: self.circuit_state.load(Ordering::Relaxed),
+            circuit_opened: self.circuit_opened.load(Ordering::Relaxed),
+            circuit_rejections: self.circuit_rejections.load(Ordering::Relaxed),
             network_errors: self.network_errors.load(Ordering::Relaxed),
             timeout_errors: self.timeout_errors.load(Ordering::Relaxed),
             protocol_errors: self.protocol_errors.load(Ordering::Relaxed),
@@ -156,6 +175,28 @@ impl DriverMetrics {
         output.push_str(&format!("# TYPE {}_queries_failed_total counter\n", prefix));
         output.push_str(&format!("{}_queries_failed_total {}\n", prefix, snapshot.queries_failed));
 
+        // Circuit breaker metrics
+        output.push_str(&format!("# HELP {}_pool_circuit_state Pool circuit breaker state (0 closed, 1 open, 2 half-open)\n", prefix));
+        output.push_str(&format!("# TYPE {}_pool_circuit_state gauge\n", prefix));
+        output.push_str(&format!("{}_pool_circuit_state {}\n", prefix, snapshot.circuit_state));
+
+        output.push_str(&format!("# HELP {}_pool_circuit_opened_total Times the pool circuit breaker opened\n", prefix));
+        output.push_str(&format!("# TYPE {}_pool_circuit_opened_total counter\n", prefix));
+        output.push_str(&format!("{}_pool_circuit_opened_total {}\n", prefix, snapshot.circuit_opened));
+
+        output.push_str(&format!("# HELP {}_pool_circuit_rejections_total Acquisitions failed fast by the circuit breaker\n", prefix));
+        output.push_str(&format!("# TYPE {}_pool_circuit_rejections_total counter\n", prefix));
+        output.push_str(&format!("{}_pool_circuit_rejections_total {}\n", prefix, snapshot.circuit_rejections));
+
+        // Leak detection metrics
+        output.push_str(&format!("# HELP {}_pool_leaks_detected_total Connections held past the leak detection threshold\n", prefix));
+        output.push_str(&format!("# TYPE {}_pool_leaks_detected_total counter\n", prefix));
+        output.push_str(&format!("{}_pool_leaks_detected_total {}\n", prefix, snapshot.pool_leaks_detected));
+
+        output.push_str(&format!("# HELP {}_pool_leaks_reclaimed_total Leaked connections reclaimed by the pool\n", prefix));
+        output.push_str(&format!("# TYPE {}_pool_leaks_reclaimed_total counter\n", prefix));
+        output.push_str(&format!("{}_pool_leaks_reclaimed_total {}\n", prefix, snapshot.pool_leaks_reclaimed));
+
         // Vector search metrics
         output.push_str(&format!("# HELP {}_vector_searches_total Total vector searches\n", prefix));
         output.push_str(&format!("# TYPE {}_vector_searches_total counter\n", prefix));
@@ -232,6 +273,10 @@ impl DriverMetrics {
         self.pool_acquisitions.store(0, Ordering::Relaxed);
         self.pool_acquisition_timeouts.store(0, Ordering::Relaxed);
         self.pool_size.store(0, Ordering::Relaxed);
+        self.pool_leaks_detected.store(0, Ordering::Relaxed);
+        self.pool_leaks_reclaimed.store(0, Ordering::Relaxed);
+        self.circuit_opened.store(0, Ordering::Relaxed);
+        self.circuit_rejections.store(0, Ordering::Relaxed);
         self.network_errors.store(0, Ordering::Relaxed);
         self.timeout_errors.store(0, Ordering::Relaxed);
         self.protocol_errors.store(0, Ordering::Relaxed);
@@ -267,6 +312,12 @@ pub struct MetricsSnapshot {
     pub pool_acquisitions: u64,
     pub pool_acquisition_timeouts: u64,
     pub pool_size: u64,
+    pub pool_leaks_detected: u64,
+    pub pool_leaks_reclaimed: u64,
+    /// 0 closed, 1 open, 2 half-open
+    pub circuit_state: u64,
+    pub circuit_opened: u64,
+    pub circuit_rejections: u64,
     pub network_errors: u64,
     pub timeout_errors: u64,
     pub protocol_errors: u64,
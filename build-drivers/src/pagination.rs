@@ -0,0 +1,267 @@
+//! Keyset Pagination
+//!
+//! Pages through a query's rows by remembering the sort key of the last row
+//! returned instead of an offset, so each page costs the same however deep
+//! it is and rows inserted or deleted between pages neither shift rows
+//! into the next page twice nor out of it unseen.
+//!
+//! A [`KeysetQuery`] is the query and its ORDER BY keys, which together must
+//! identify a row uniquely (end with the primary key). Each page wraps the
+//! query as
+//!
+//! ```sql
+//! SELECT * FROM (<query>) AS keyset_page
+//! WHERE (k1, k2) > ($1, $2)
+//! ORDER BY k1, k2 LIMIT <page size + 1>
+//! ```
+//!
+//! and returns its rows with an opaque cursor token for the next page. Keys
+//! sorted in different directions are compared one by one instead
+//! (`k1 > $1 OR (k1 = $1 AND k2 < $2)`). Key columns may not be NULL.
+//!
+//! A token names the keys it was made for, so it is refused by a query
+//! sorted differently.
+
+use crate::error::{AuroraError, Result};
+use crate::types::{AuroraColumn, AuroraRow, AuroraValue, QueryResult};
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Prefix of every cursor token, bumped if the encoding changes
+const TOKEN_VERSION: &str = "k1";
+
+/// Alias of the wrapped query in page SQL
+const PAGE_ALIAS: &str = "keyset_page";
+
+/// One ORDER BY key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+/// A query paged by its sort keys
+#[derive(Debug, Clone)]
+pub struct KeysetQuery {
+    sql: String,
+    keys: Vec<SortKey>,
+}
+
+impl KeysetQuery {
+    /// Page `sql`, a query without ORDER BY or LIMIT, by the keys added with
+    /// [`order_by`](Self::order_by) and [`order_by_desc`](Self::order_by_desc)
+    pub fn new(sql: &str) -> Self {
+        Self {
+            sql: sql.trim().trim_end_matches(';').trim_end().to_string(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Sort by `column` ascending after the keys added so far
+    pub fn order_by(mut self, column: &str) -> Self {
+        self.keys.push(SortKey { column: column.to_string(), descending: false });
+        self
+    }
+
+    /// Sort by `column` descending after the keys added so far
+    pub fn order_by_desc(mut self, column: &str) -> Self {
+        self.keys.push(SortKey { column: column.to_string(), descending: true });
+        self
+    }
+
+    pub fn keys(&self) -> &[SortKey] {
+        &self.keys
+    }
+
+    /// SQL and parameters of the page after `cursor`, or of the first page
+    pub fn page_request(&self, cursor: Option<&str>, page_size: usize) -> Result<(String, Vec<AuroraValue>)> {
+        if self.keys.is_empty() {
+            return Err(AuroraError::Query("keyset pagination needs at least one ORDER BY key".to_string()));
+        }
+        if page_size == 0 {
+            return Err(AuroraError::Query("page size must be at least 1".to_string()));
+        }
+
+        let mut sql = format!("SELECT * FROM ({}) AS {}", self.sql, PAGE_ALIAS);
+        let params = match cursor {
+            Some(token) => {
+                let cursor = PageCursor::decode(token)?;
+                cursor.check_keys(&self.keys)?;
+                sql.push_str(" WHERE ");
+                sql.push_str(&self.continuation());
+                cursor.values
+            }
+            None => Vec::new(),
+        };
+        let order: Vec<String> = self.keys.iter()
+            .map(|key| if key.descending { format!("{} DESC", key.column) } else { key.column.clone() })
+            .collect();
+        // One extra row says whether there is a next page
+        sql.push_str(&format!(" ORDER BY {} LIMIT {}", order.join(", "), page_size + 1));
+        Ok((sql, params))
+    }
+
+    /// Rows of a page from the result of its `page_request`, with the cursor
+    /// of the next page if there is one
+    pub fn page_from(&self, result: QueryResult, page_size: usize) -> Result<Page> {
+        let positions = self.key_positions(&result.columns)?;
+        let mut rows = result.rows;
+        let has_more = rows.len() > page_size;
+        rows.truncate(page_size);
+
+        let next_cursor = match (has_more, rows.last()) {
+            (true, Some(last)) => Some(PageCursor::after(&self.keys, &positions, last)?.encode()),
+            _ => None,
+        };
+        Ok(Page { rows, columns: result.columns, next_cursor })
+    }
+
+    /// `WHERE` condition selecting the rows after the cursor's key values,
+    /// bound as `$1..$n`
+    fn continuation(&self) -> String {
+        let uniform = self.keys.iter().all(|key| key.descending == self.keys[0].descending);
+        let op = |key: &SortKey| if key.descending { "<" } else { ">" };
+        if uniform {
+            let columns: Vec<&str> = self.keys.iter().map(|key| key.column.as_str()).collect();
+            let params: Vec<String> = (1..=self.keys.len()).map(|i| format!("${}", i)).collect();
+            return match columns.as_slice() {
+                [column] => format!("{} {} $1", column, op(&self.keys[0])),
+                _ => format!("({}) {} ({})", columns.join(", "), op(&self.keys[0]), params.join(", ")),
+            };
+        }
+
+        // After the cursor on the first key where the row differs from it
+        let alternatives: Vec<String> = self.keys.iter().enumerate()
+            .map(|(i, key)| {
+                let mut terms: Vec<String> = self.keys[..i].iter().enumerate()
+                    .map(|(j, earlier)| format!("{} = ${}", earlier.column, j + 1))
+                    .collect();
+                terms.push(format!("{} {} ${}", key.column, op(key), i + 1));
+                match terms.len() {
+                    1 => terms.remove(0),
+                    _ => format!("({})", terms.join(" AND ")),
+                }
+            })
+            .collect();
+        alternatives.join(" OR ")
+    }
+
+    /// Where each key column is in the result
+    fn key_positions(&self, columns: &[AuroraColumn]) -> Result<Vec<usize>> {
+        self.keys.iter()
+            .map(|key| {
+                columns.iter().position(|column| column.name == key.column).ok_or_else(|| {
+                    AuroraError::Query(format!("sort key '{}' is not a column of the paged query", key.column))
+                })
+            })
+            .collect()
+    }
+}
+
+/// One page of a keyset-paged query
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub rows: Vec<AuroraRow>,
+    pub columns: Vec<AuroraColumn>,
+    /// Token of the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Key values of the last row of a page, and the keys they are values of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageCursor {
+    pub keys: Vec<SortKey>,
+    pub values: Vec<AuroraValue>,
+}
+
+impl PageCursor {
+    /// Cursor after `row`, whose key columns are at `positions`
+    fn after(keys: &[SortKey], positions: &[usize], row: &AuroraRow) -> Result<Self> {
+        let values = keys.iter().zip(positions)
+            .map(|(key, &position)| match row.values.get(position) {
+                None | Some(AuroraValue::Null) => Err(AuroraError::Query(format!(
+                    "sort key '{}' is NULL; keyset pagination needs non-null keys", key.column
+                ))),
+                Some(value) => Ok(value.clone()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { keys: keys.to_vec(), values })
+    }
+
+    /// Opaque token for the cursor, safe in URLs
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursor serializes");
+        let mut token = String::with_capacity(TOKEN_VERSION.len() + 1 + json.len() * 2);
+        token.push_str(TOKEN_VERSION);
+        token.push('.');
+        for byte in json {
+            token.push_str(&format!("{:02x}", byte));
+        }
+        token
+    }
+
+    /// Cursor of a token made by [`encode`](Self::encode)
+    pub fn decode(token: &str) -> Result<Self> {
+        let malformed = || AuroraError::Query("malformed page cursor".to_string());
+        let hex = token.strip_prefix(TOKEN_VERSION).and_then(|rest| rest.strip_prefix('.')).ok_or_else(malformed)?;
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return Err(malformed());
+        }
+        let bytes = (0..hex.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| malformed()))
+            .collect::<Result<Vec<u8>>>()?;
+        let cursor: Self = serde_json::from_slice(&bytes).map_err(|_| malformed())?;
+        if cursor.values.len() != cursor.keys.len() {
+            return Err(malformed());
+        }
+        Ok(cursor)
+    }
+
+    fn check_keys(&self, keys: &[SortKey]) -> Result<()> {
+        if self.keys != keys {
+            return Err(AuroraError::Query(
+                "page cursor was made for a query with different ORDER BY keys".to_string()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Order of two rows' key values under `keys`, as the server sorts them;
+/// values of types without a natural order compare by their debug form
+pub fn compare_keys(keys: &[SortKey], a: &[AuroraValue], b: &[AuroraValue]) -> Ordering {
+    keys.iter().zip(a.iter().zip(b))
+        .map(|(key, (a, b))| {
+            let ordering = compare_values(a, b);
+            if key.descending { ordering.reverse() } else { ordering }
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn compare_values(a: &AuroraValue, b: &AuroraValue) -> Ordering {
+    use AuroraValue::*;
+    let integer = |value: &AuroraValue| match value {
+        TinyInt(i) => Some(*i as i64),
+        SmallInt(i) => Some(*i as i64),
+        Int(i) => Some(*i as i64),
+        BigInt(i) => Some(*i),
+        _ => None,
+    };
+    if let (Some(a), Some(b)) = (integer(a), integer(b)) {
+        return a.cmp(&b);
+    }
+    match (a, b) {
+        (Float(a), Float(b)) => a.total_cmp(b),
+        (Double(a), Double(b)) => a.total_cmp(b),
+        (Text(a), Text(b)) | (Uuid(a), Uuid(b)) => a.cmp(b),
+        (Bool(a), Bool(b)) => a.cmp(b),
+        (Binary(a), Binary(b)) => a.cmp(b),
+        (Date(a), Date(b)) => a.cmp(b),
+        (Time(a), Time(b)) | (Timestamp(a), Timestamp(b)) => a.cmp(b),
+        // By the instant, whatever zone each was rendered in
+        (TimestampTz(a, _), TimestampTz(b, _)) => a.cmp(b),
+        _ => format!("{:?}", a).cmp(&format!("{:?}", b)),
+    }
+}
@@ -3,16 +3,33 @@
 //! Advanced connection pooling with circuit breakers, health checks,
 //! load balancing, and automatic failover for AuroraDB drivers.
 
+use crate::circuit_breaker::{Admission, CircuitBreaker, CircuitState};
 use crate::connection::AuroraConnection;
 use crate::config::{AuroraConfig, PoolConfig};
-use crate::error::{AuroraError, Result};
+use crate::error::{AuroraError, ErrorClass, Result};
 use crate::metrics::DriverMetrics;
+use crate::pool_leaks::{LeakDetector, LeakReport, LeaseId};
+use crate::pool_queue::{LaneWaitStats, Priority, WaitQueue, WaitTicket};
+use crate::pool_sizing::{PoolSizeController, ResizeEvent};
 
 use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::sync::{Mutex, Semaphore, Notify};
+use futures::future::BoxFuture;
+use tokio::sync::{Mutex, Notify};
 use tokio::time::{timeout, Duration, Instant};
 
+/// Hook run on every newly created connection before it is handed out or pooled.
+///
+/// Use it to run `SET` commands, preload prepared statements or set session
+/// variables so every pooled connection starts in the same warm state.
+pub type OnConnectHook = Arc<dyn for<'c> Fn(&'c mut AuroraConnection) -> BoxFuture<'c, Result<()>> + Send + Sync>;
+
+/// How long a returned connection may take to deliver the responses its
+/// previous caller abandoned before it is discarded instead
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// AuroraDB connection pool
 pub struct AuroraConnectionPool {
     /// Pool configuration
@@ -27,27 +44,70 @@ pub struct AuroraConnectionPool {
     /// Connection factory configuration
     connection_config: AuroraConfig,
 
-    /// Pool semaphore for limiting concurrent connections
-    semaphore: Arc<Semaphore>,
+    /// Acquisitions waiting for a connection, by priority
+    queue: Arc<std::sync::Mutex<WaitQueue>>,
 
     /// Shutdown notification
     shutdown_notify: Arc<Notify>,
 
     /// Pool metrics
     metrics: Arc<DriverMetrics>,
+
+    /// Warmup hook run once per physical connection
+    on_connect: Option<OnConnectHook>,
+
+    /// Target size controller, when adaptive sizing is enabled
+    sizing: Option<Arc<Mutex<PoolSizeController>>>,
+
+    /// Wakes acquisitions waiting for a connection to be returned
+    connection_returned: Arc<Notify>,
+
+    /// Fail-fast breaker for connection attempts, when enabled
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
+
+    /// Tracker of checked-out guards, when leak detection is enabled
+    leak_detector: Option<Arc<std::sync::Mutex<LeakDetector>>>,
 }
 
 impl AuroraConnectionPool {
     /// Create new connection pool
     pub async fn new(config: AuroraConfig) -> Result<Self> {
+        Self::build(config, None).await
+    }
+
+    /// Create new connection pool that runs `hook` on every new connection.
+    ///
+    /// A connection whose hook fails is closed instead of being pooled, and the
+    /// hook's error is returned to whoever triggered the connection.
+    pub async fn with_on_connect<F>(config: AuroraConfig, hook: F) -> Result<Self>
+    where
+        F: for<'c> Fn(&'c mut AuroraConnection) -> BoxFuture<'c, Result<()>> + Send + Sync + 'static,
+    {
+        Self::build(config, Some(Arc::new(hook))).await
+    }
+
+    async fn build(config: AuroraConfig, on_connect: Option<OnConnectHook>) -> Result<Self> {
         let pool = Self {
             config: config.pool.clone(),
             available: Arc::new(Mutex::new(VecDeque::new())),
             total_connections: Arc::new(Mutex::new(0)),
-            connection_config: config,
-            semaphore: Arc::new(Semaphore::new(config.pool.max_connections as usize)),
+            queue: Arc::new(std::sync::Mutex::new(WaitQueue::new(config.pool.priority_aging))),
+            connection_config: config.clone(),
             shutdown_notify: Arc::new(Notify::new()),
             metrics: Arc::new(DriverMetrics::new()),
+            on_connect,
+            sizing: config.pool.adaptive.clone().map(|adaptive| {
+                Arc::new(Mutex::new(PoolSizeController::new(
+                    adaptive,
+                    config.pool.min_connections as usize,
+                    config.pool.max_connections as usize,
+                )))
+            }),
+            connection_returned: Arc::new(Notify::new()),
+            circuit_breaker: config.pool.circuit_breaker.clone()
+                .map(|breaker| Arc::new(Mutex::new(CircuitBreaker::new(breaker)))),
+            leak_detector: config.pool.leak_detection.clone()
+                .map(|leaks| Arc::new(std::sync::Mutex::new(LeakDetector::new(leaks)))),
         };
 
         // Initialize minimum connections
@@ -55,72 +115,171 @@ impl AuroraConnectionPool {
 
         // Start background maintenance
         pool.start_maintenance_task();
+        pool.start_sizing_task();
+        pool.start_leak_detection_task();
 
         Ok(pool)
     }
 
     /// Get a connection from the pool
+    ///
+    /// Fails immediately with `AuroraError::CircuitOpen` while the circuit
+    /// breaker is open.
     pub async fn get_connection(&self) -> Result<AuroraConnection> {
-        let start_time = Instant::now();
+        self.get_connection_with_priority(Priority::Normal).await
+    }
 
-        // Acquire semaphore permit
-        let permit = timeout(self.config.acquire_timeout, self.semaphore.acquire()).await
-            .map_err(|_| AuroraError::PoolExhausted("Connection acquisition timeout".into()))?;
+    /// Get a connection from the pool, waiting in `priority`'s lane
+    ///
+    /// Once the pool is at its target size, waiters are served highest
+    /// priority first and in arrival order within a lane; a waiter moves up
+    /// a lane every `priority_aging`, so low-priority callers are delayed by
+    /// high-priority load but never starved.
+    pub async fn get_connection_with_priority(&self, priority: Priority) -> Result<AuroraConnection> {
+        let start_time = Instant::now();
+        let probe = self.admit().await?;
 
         // Update metrics
         self.metrics.pool_acquisitions.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        // Try to get existing connection
-        if let Some(connection) = self.get_available_connection().await {
-            if self.is_connection_valid(&connection).await {
-                // Update metrics
-                self.metrics.pool_size.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if probe {
+            // Idle connections may predate the outage; only a new one tells
+            let connection = self.create_new_connection().await?;
+            self.metrics.pool_size.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            return Ok(connection);
+        }
 
-                drop(permit); // Release permit since we got a connection
-                return Ok(connection);
-            } else {
-                // Connection is invalid, create new one
-                self.close_invalid_connection(connection).await;
+        let result = self.acquire_within_target(priority, start_time + self.config.acquire_timeout).await;
+        let wait = start_time.elapsed();
+        self.record_acquire(wait).await;
+        match &result {
+            Ok(_) => {
+                self.metrics.pool_size.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                self.queue.lock().unwrap().record_wait(priority, wait);
+            }
+            Err(AuroraError::PoolExhausted(_)) => {
+                self.metrics.pool_acquisition_timeouts.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             }
+            Err(_) => {}
         }
+        result
+    }
 
-        // Create new connection
-        let connection = self.create_new_connection().await?;
-
-        // Update metrics
-        self.metrics.pool_size.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    /// Get a connection that goes back to the pool when dropped
+    ///
+    /// Safe to hold across a cancelled request: if the future using it is
+    /// dropped mid-query, the connection is drained or discarded before it
+    /// can be checked out again.
+    pub async fn acquire(&self) -> Result<PooledConnection> {
+        self.acquire_with_priority(Priority::Normal).await
+    }
 
-        drop(permit); // Release permit
-        Ok(connection)
+    /// Get a connection that goes back to the pool when dropped, waiting in
+    /// `priority`'s lane
+    ///
+    /// With leak detection enabled the checkout is tracked until the guard
+    /// is dropped.
+    pub async fn acquire_with_priority(&self, priority: Priority) -> Result<PooledConnection> {
+        let connection = self.get_connection_with_priority(priority).await?;
+        let lease = self.leak_detector.as_ref().map(|detector| {
+            detector.lock().unwrap().check_out(&connection.info().connection_id, std::time::Instant::now())
+        });
+        Ok(PooledConnection {
+            connection: Some(connection),
+            pool: self.clone(),
+            lease,
+        })
     }
 
     /// Return connection to pool
-    pub async fn return_connection(&self, mut connection: AuroraConnection) -> Result<()> {
+    ///
+    /// A connection abandoned mid-frame is discarded. One that still owes
+    /// responses, or is still in a COPY, is drained in the background and
+    /// pooled only once the stream is back in sync.
+    pub async fn return_connection(&self, connection: AuroraConnection) -> Result<()> {
+        if connection.is_torn() {
+            warn!("Connection {} abandoned mid-frame, discarding", connection.info().connection_id);
+            self.release_discarded(connection).await;
+            return Ok(());
+        }
+
+        if connection.pending_responses() > 0 || connection.in_copy() {
+            self.drain_in_background(connection);
+            return Ok(());
+        }
+
+        self.pool_connection(connection).await
+    }
+
+    async fn pool_connection(&self, mut connection: AuroraConnection) -> Result<()> {
         // Check if connection is still valid
         if !self.is_connection_valid(&connection).await {
-            self.close_invalid_connection(connection).await;
+            self.release_discarded(connection).await;
             return Ok(());
         }
 
         // Reset connection state if needed
         self.reset_connection(&mut connection).await?;
 
-        // Return to pool if not at capacity
+        // Return to pool if not above the target size
         let total = *self.total_connections.lock().await;
-        if total <= self.config.max_connections as usize {
+        if total <= self.target_size().await {
             let mut available = self.available.lock().await;
             available.push_back(connection);
         } else {
-            // Pool is full, close connection
-            connection.close().await?;
+            // Pool is above target, close connection
+            self.discard_connection(connection).await;
         }
 
         // Update metrics
         self.metrics.pool_size.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+        self.connection_returned.notify_waiters();
 
         Ok(())
     }
 
+    /// Warn about guards held past the leak threshold and reclaim those held
+    /// past `reclaim_after`
+    ///
+    /// Runs every `check_interval` in the background; exposed so callers can
+    /// check on their own schedule. Each checkout is reported once when it
+    /// passes the threshold and once more if it is reclaimed.
+    pub async fn check_leaks(&self) -> Vec<LeakReport> {
+        let Some(detector) = &self.leak_detector else { return Vec::new() };
+        let reports = detector.lock().unwrap().scan(std::time::Instant::now());
+
+        for report in &reports {
+            if report.reclaimed {
+                warn!(
+                    "Reclaiming connection {} checked out for {:?}; it will be closed when its guard is dropped",
+                    report.connection_id, report.held_for,
+                );
+                self.metrics.pool_leaks_reclaimed.fetch_add(1, Ordering::Relaxed);
+                {
+                    let mut total = self.total_connections.lock().await;
+                    *total = total.saturating_sub(1);
+                }
+                self.metrics.pool_size.fetch_sub(1, Ordering::Relaxed);
+                self.connection_returned.notify_waiters();
+                continue;
+            }
+
+            self.metrics.pool_leaks_detected.fetch_add(1, Ordering::Relaxed);
+            match &report.backtrace {
+                Some(backtrace) => warn!(
+                    "Connection {} checked out for {:?} without being returned, possible leak; acquired at:\n{}",
+                    report.connection_id, report.held_for, backtrace,
+                ),
+                None => warn!(
+                    "Connection {} checked out for {:?} without being returned, possible leak \
+                     (enable the `leak-backtrace` feature to record where it was acquired)",
+                    report.connection_id, report.held_for,
+                ),
+            }
+        }
+        reports
+    }
+
     /// Get pool statistics
     pub async fn stats(&self) -> PoolStats {
         let available_count = self.available.lock().await.len();
@@ -131,10 +290,79 @@ impl AuroraConnectionPool {
             available_connections: available_count,
             active_connections: total_count.saturating_sub(available_count),
             max_connections: self.config.max_connections as usize,
-            waiting_requests: self.semaphore.available_permits(),
+            target_size: self.target_size().await,
+            waiting_requests: self.queue.lock().unwrap().len(),
+            circuit_state: self.circuit_state().await,
         }
     }
 
+    /// Wait times of the acquisitions served from `priority`'s lane
+    pub fn lane_wait_stats(&self, priority: Priority) -> LaneWaitStats {
+        self.queue.lock().unwrap().lane_stats(priority)
+    }
+
+    /// Current circuit breaker state; always closed when the breaker is disabled
+    pub async fn circuit_state(&self) -> CircuitState {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.lock().await.state(),
+            None => CircuitState::Closed,
+        }
+    }
+
+    /// Pool metrics, including the circuit breaker state
+    pub fn metrics(&self) -> &DriverMetrics {
+        &self.metrics
+    }
+
+    /// Number of connections the pool currently aims to keep
+    ///
+    /// Equals `max_connections` unless adaptive sizing is enabled.
+    pub async fn target_size(&self) -> usize {
+        match &self.sizing {
+            Some(sizing) => sizing.lock().await.target(),
+            None => self.config.max_connections as usize,
+        }
+    }
+
+    /// Recent adaptive resize events, oldest first
+    pub async fn resize_events(&self) -> Vec<ResizeEvent> {
+        match &self.sizing {
+            Some(sizing) => sizing.lock().await.events(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Close the current sizing window and apply the controller's decision
+    ///
+    /// Runs every `evaluation_interval` in the background; exposed so callers
+    /// can drive the controller on their own schedule.
+    pub async fn evaluate_sizing(&self) -> Option<ResizeEvent> {
+        let sizing = self.sizing.as_ref()?;
+        let in_use = self.in_use().await;
+        let event = {
+            let mut controller = sizing.lock().await;
+            controller.record_in_use(in_use);
+            controller.evaluate()
+        }?;
+
+        info!("Resized connection pool from {} to {} ({:?})", event.from, event.to, event.reason);
+        if event.to > event.from {
+            // Waiters may now open new connections
+            self.connection_returned.notify_waiters();
+        } else {
+            // Close idle connections above the new target
+            let mut available = self.available.lock().await;
+            while *self.total_connections.lock().await > event.to {
+                match available.pop_front() {
+                    Some(connection) => self.discard_connection(connection).await,
+                    None => break,
+                }
+            }
+        }
+
+        Some(event)
+    }
+
     /// Close all connections and shutdown pool
     pub async fn close(&self) -> Result<()> {
         self.shutdown_notify.notify_waiters();
@@ -168,15 +396,180 @@ impl AuroraConnectionPool {
     }
 
     async fn create_new_connection(&self) -> Result<AuroraConnection> {
-        let connection = AuroraConnection::new(self.connection_config.clone()).await?;
+        let connection = self.open_connection().await?;
+        *self.total_connections.lock().await += 1;
+        Ok(connection)
+    }
+
+    /// Open and warm up a physical connection without counting it
+    async fn open_connection(&self) -> Result<AuroraConnection> {
+        let result = AuroraConnection::new(self.connection_config.clone()).await;
+        self.record_connect(&result).await;
+        let mut connection = result?;
+
+        // Warm the connection up; never hand out one the hook could not configure
+        if let Some(hook) = &self.on_connect {
+            if let Err(e) = hook(&mut connection).await {
+                warn!("on_connect hook failed for {}, discarding connection: {}", connection.info().connection_id, e);
+                let _ = connection.close().await;
+                self.metrics.connection_errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return Err(e);
+            }
+        }
 
         // Update metrics
         self.metrics.connections_created.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        *self.total_connections.lock().await += 1;
 
         Ok(connection)
     }
 
+    /// Acquire a connection without letting the pool exceed its target size,
+    /// queueing by priority for a returned connection once the target is reached
+    async fn acquire_within_target(&self, priority: Priority, deadline: Instant) -> Result<AuroraConnection> {
+        let mut place: Option<QueuePlace> = None;
+        loop {
+            // Register before checking, so a return in between is not missed
+            let returned = self.connection_returned.notified();
+            self.check_circuit().await?;
+
+            // Newcomers go straight in only while nobody is queued; otherwise
+            // only the waiter the queue picks may take a connection
+            let my_turn = {
+                let mut queue = self.queue.lock().unwrap();
+                let now = std::time::Instant::now();
+                match &place {
+                    Some(place) => queue.next(now) == Some(place.ticket),
+                    None if queue.is_empty() => true,
+                    None => {
+                        let ticket = queue.push(priority, now);
+                        place = Some(QueuePlace { ticket, pool: self.clone() });
+                        queue.next(now) == Some(ticket)
+                    }
+                }
+            };
+
+            if my_turn {
+                if let Some(connection) = self.take_within_target().await? {
+                    return Ok(connection);
+                }
+                if place.is_none() {
+                    let ticket = self.queue.lock().unwrap().push(priority, std::time::Instant::now());
+                    place = Some(QueuePlace { ticket, pool: self.clone() });
+                }
+            }
+
+            tokio::time::timeout_at(deadline, returned).await
+                .map_err(|_| AuroraError::PoolExhausted("Connection acquisition timeout".into()))?;
+        }
+    }
+
+    /// An idle connection, or a new one if the pool is below its target size
+    async fn take_within_target(&self) -> Result<Option<AuroraConnection>> {
+        while let Some(connection) = self.get_available_connection().await {
+            if self.is_connection_valid(&connection).await {
+                return Ok(Some(connection));
+            }
+            self.discard_connection(connection).await;
+        }
+
+        // Reserve a slot under the lock so concurrent callers cannot overshoot
+        let reserved = {
+            let mut total = self.total_connections.lock().await;
+            if *total < self.target_size().await {
+                *total += 1;
+                true
+            } else {
+                false
+            }
+        };
+        if !reserved {
+            return Ok(None);
+        }
+        match self.open_connection().await {
+            Ok(connection) => Ok(Some(connection)),
+            Err(e) => {
+                let mut total = self.total_connections.lock().await;
+                *total = total.saturating_sub(1);
+                Err(e)
+            }
+        }
+    }
+
+    /// Check the circuit breaker, returning whether this caller is the probe
+    async fn admit(&self) -> Result<bool> {
+        let Some(breaker) = &self.circuit_breaker else { return Ok(false) };
+        let mut breaker = breaker.lock().await;
+        let admission = breaker.admit(std::time::Instant::now());
+        self.metrics.circuit_state.store(breaker.state().as_gauge(), Ordering::Relaxed);
+
+        match admission {
+            Admission::Allowed => Ok(false),
+            Admission::Probe => {
+                info!("Circuit breaker half-open, probing the server");
+                Ok(true)
+            }
+            Admission::Rejected(retry_in) => Err(self.reject(&breaker, retry_in)),
+        }
+    }
+
+    /// Fail if the breaker is open, without claiming the probe
+    async fn check_circuit(&self) -> Result<()> {
+        let Some(breaker) = &self.circuit_breaker else { return Ok(()) };
+        let breaker = breaker.lock().await;
+        match breaker.rejection(std::time::Instant::now()) {
+            Some(retry_in) => Err(self.reject(&breaker, retry_in)),
+            None => Ok(()),
+        }
+    }
+
+    fn reject(&self, breaker: &CircuitBreaker, retry_in: Duration) -> AuroraError {
+        self.metrics.circuit_rejections.fetch_add(1, Ordering::Relaxed);
+        AuroraError::CircuitOpen(format!(
+            "{} consecutive connection failures, next attempt in {:?}",
+            breaker.consecutive_failures(),
+            retry_in,
+        ))
+    }
+
+    /// Feed the outcome of a connection attempt to the circuit breaker
+    ///
+    /// Only failures to reach the server count; a refused login still
+    /// proves the server is up.
+    async fn record_connect(&self, result: &Result<AuroraConnection>) {
+        let Some(breaker) = &self.circuit_breaker else { return };
+        let mut breaker = breaker.lock().await;
+
+        match result {
+            Err(e) if e.classify() == ErrorClass::Network => {
+                if breaker.record_failure(std::time::Instant::now()) {
+                    warn!("Circuit breaker opened after {} consecutive connection failures: {}", breaker.consecutive_failures(), e);
+                    self.metrics.circuit_opened.fetch_add(1, Ordering::Relaxed);
+                    // Waiters fail fast instead of sitting out their timeout
+                    self.connection_returned.notify_waiters();
+                }
+            }
+            _ => {
+                if breaker.state() != CircuitState::Closed {
+                    info!("Circuit breaker closed, server reachable again");
+                }
+                breaker.record_success();
+            }
+        }
+        self.metrics.circuit_state.store(breaker.state().as_gauge(), Ordering::Relaxed);
+    }
+
+    async fn record_acquire(&self, wait: Duration) {
+        if let Some(sizing) = &self.sizing {
+            let in_use = self.in_use().await;
+            sizing.lock().await.record_acquire(wait, in_use);
+        }
+    }
+
+    async fn in_use(&self) -> usize {
+        let available = self.available.lock().await.len();
+        self.total_connections.lock().await.saturating_sub(available)
+    }
+
     async fn get_available_connection(&self) -> Option<AuroraConnection> {
         let mut available = self.available.lock().await;
         available.pop_front()
@@ -201,9 +594,40 @@ impl AuroraConnectionPool {
         connection.is_healthy().await
     }
 
-    async fn close_invalid_connection(&self, connection: AuroraConnection) {
+    /// Read the responses a dropped caller left unread, then pool the connection
+    fn drain_in_background(&self, mut connection: AuroraConnection) {
+        let pool = self.clone();
+
+        tokio::spawn(async move {
+            match timeout(DRAIN_TIMEOUT, connection.drain()).await {
+                Ok(Ok(())) => {
+                    if let Err(e) = pool.pool_connection(connection).await {
+                        error!("Failed to pool drained connection: {}", e);
+                    }
+                }
+                Ok(Err(e)) => {
+                    warn!("Draining connection {} failed, discarding: {}", connection.info().connection_id, e);
+                    pool.release_discarded(connection).await;
+                }
+                Err(_) => {
+                    warn!("Draining connection {} timed out, discarding", connection.info().connection_id);
+                    pool.release_discarded(connection).await;
+                }
+            }
+        });
+    }
+
+    /// Discard a checked-out connection and wake anyone waiting for a slot
+    async fn release_discarded(&self, connection: AuroraConnection) {
+        self.discard_connection(connection).await;
+        self.metrics.pool_size.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+        self.connection_returned.notify_waiters();
+    }
+
+    async fn discard_connection(&self, connection: AuroraConnection) {
         let _ = connection.close().await;
-        *self.total_connections.lock().await = self.total_connections.lock().await.saturating_sub(1);
+        let mut total = self.total_connections.lock().await;
+        *total = total.saturating_sub(1);
         self.metrics.connections_closed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
@@ -235,6 +659,54 @@ impl AuroraConnectionPool {
         });
     }
 
+    fn start_sizing_task(&self) {
+        let interval = match &self.config.adaptive {
+            Some(adaptive) => adaptive.evaluation_interval,
+            None => return,
+        };
+        let pool = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        pool.evaluate_sizing().await;
+                    }
+                    _ = pool.shutdown_notify.notified() => {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    fn start_leak_detection_task(&self) {
+        let interval = match &self.leak_detector {
+            Some(detector) => detector.lock().unwrap().config().check_interval,
+            None => return,
+        };
+        let pool = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        pool.check_leaks().await;
+                    }
+                    _ = pool.shutdown_notify.notified() => {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
     async fn perform_maintenance(&self) -> Result<()> {
         let mut available = self.available.lock().await;
         let mut to_remove = Vec::new();
@@ -249,7 +721,7 @@ impl AuroraConnectionPool {
         // Remove invalid connections
         for &index in to_remove.iter().rev() {
             if let Some(connection) = available.remove(index) {
-                self.close_invalid_connection(connection).await;
+                self.discard_connection(connection).await;
             }
         }
 
@@ -257,7 +729,8 @@ impl AuroraConnectionPool {
         let current_count = available.len() + *self.total_connections.lock().await;
         let min_connections = self.config.min_connections as usize;
 
-        if current_count < min_connections {
+        // Leave reconnecting to the probe while the breaker is not closed
+        if current_count < min_connections && self.circuit_state().await == CircuitState::Closed {
             let to_create = min_connections - current_count;
 
             for _ in 0..to_create {
@@ -278,7 +751,81 @@ pub struct PoolStats {
     pub available_connections: usize,
     pub active_connections: usize,
     pub max_connections: usize,
+    pub target_size: usize,
     pub waiting_requests: usize,
+    pub circuit_state: CircuitState,
+}
+
+/// A waiter's place in the pool's queue, given up when dropped
+///
+/// Dropping covers every way out of an acquisition: served, timed out,
+/// failed or cancelled. The remaining waiters are woken because the head of
+/// the queue may have changed.
+struct QueuePlace {
+    ticket: WaitTicket,
+    pool: AuroraConnectionPool,
+}
+
+impl Drop for QueuePlace {
+    fn drop(&mut self) {
+        let mut queue = self.pool.queue.lock().unwrap();
+        if queue.remove(self.ticket) && !queue.is_empty() {
+            self.pool.connection_returned.notify_waiters();
+        }
+    }
+}
+
+/// A checked-out connection that returns itself to the pool when dropped
+///
+/// If leak detection reclaimed the connection while it was held, dropping
+/// the guard closes the connection instead, as its slot is already reused.
+pub struct PooledConnection {
+    connection: Option<AuroraConnection>,
+    pool: AuroraConnectionPool,
+    lease: Option<LeaseId>,
+}
+
+impl Deref for PooledConnection {
+    type Target = AuroraConnection;
+
+    fn deref(&self) -> &AuroraConnection {
+        self.connection.as_ref().expect("connection present until drop")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut AuroraConnection {
+        self.connection.as_mut().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let Some(mut connection) = self.connection.take() else { return };
+        let pool = self.pool.clone();
+        let reclaimed = match (&pool.leak_detector, self.lease) {
+            (Some(detector), Some(lease)) => !detector.lock().unwrap().check_in(lease),
+            _ => false,
+        };
+
+        // Drop cannot await, so the return (and any drain) runs as its own task
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if reclaimed => {
+                handle.spawn(async move {
+                    let _ = connection.close().await;
+                    pool.metrics.connections_closed.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = pool.return_connection(connection).await {
+                        error!("Failed to return connection to pool: {}", e);
+                    }
+                });
+            }
+            Err(_) => warn!("No runtime to return connection {} to the pool", connection.info().connection_id),
+        }
+    }
 }
 
 impl Clone for AuroraConnectionPool {
@@ -290,9 +837,14 @@ impl Clone for AuroraConnectionPool {
             available: Arc::clone(&self.available),
             total_connections: Arc::clone(&self.total_connections),
             connection_config: self.connection_config.clone(),
-            semaphore: Arc::clone(&self.semaphore),
+            queue: Arc::clone(&self.queue),
             shutdown_notify: Arc::clone(&self.shutdown_notify),
-            metrics: Arc::new(DriverMetrics::new()), // Separate metrics for clone
+            metrics: Arc::clone(&self.metrics),
+            on_connect: self.on_connect.clone(),
+            sizing: self.sizing.clone(),
+            connection_returned: Arc::clone(&self.connection_returned),
+            circuit_breaker: self.circuit_breaker.clone(),
+            leak_detector: self.leak_detector.clone(),
         }
     }
 }
@@ -304,3 +856,9 @@ impl Clone for AuroraConnectionPool {
 // - [x] Background maintenance and cleanup
 // - [x] Comprehensive pool statistics
 // - [x] Configurable pool behavior
+// - [x] Per-connection warmup hook
+// - [x] Adaptive sizing with hysteresis
+// - [x] Cancellation-safe checkout with background drain
+// - [x] Fail-fast circuit breaker with single-probe recovery
+// - [x] Priority lanes with aging against starvation
+// - [x] Leak detection with optional reclamation of held connections
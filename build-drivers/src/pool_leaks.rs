@@ -0,0 +1,132 @@
+//! Connection Leak Detection
+//!
+//! Tracks every connection checked out through a `PooledConnection` guard
+//! and reports the ones held for longer than `threshold`, the usual sign of
+//! a guard that is never dropped:
+//!
+//! - **Warn** once per checkout when it passes `threshold`. With the
+//!   `leak-backtrace` feature the report carries the backtrace of the
+//!   acquisition, pointing at the code holding the guard.
+//! - **Reclaim** a checkout that passes `reclaim_after`, if set. The lease is
+//!   revoked so the pool can give its slot to someone else; when the guard is
+//!   finally dropped it finds the lease gone and closes the connection instead
+//!   of returning it.
+//!
+//! Capturing a backtrace on every checkout is too slow to leave on in
+//! production by default, hence the feature flag.
+
+use crate::config::LeakDetectionConfig;
+
+use std::backtrace::Backtrace;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// One checkout tracked by the detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeaseId(u64);
+
+#[derive(Debug)]
+struct Lease {
+    connection_id: String,
+    acquired_at: Instant,
+    backtrace: Option<Arc<Backtrace>>,
+    reported: bool,
+}
+
+/// A connection held past the leak threshold
+#[derive(Debug, Clone)]
+pub struct LeakReport {
+    pub connection_id: String,
+
+    /// How long the connection had been checked out
+    pub held_for: Duration,
+
+    /// Where the connection was acquired; `None` unless the `leak-backtrace`
+    /// feature is enabled
+    pub backtrace: Option<Arc<Backtrace>>,
+
+    /// Whether the pool took the connection's slot back
+    pub reclaimed: bool,
+}
+
+/// Bookkeeping of checked-out connections
+#[derive(Debug)]
+pub struct LeakDetector {
+    config: LeakDetectionConfig,
+    next_lease: u64,
+    leases: BTreeMap<u64, Lease>,
+}
+
+impl LeakDetector {
+    pub fn new(config: LeakDetectionConfig) -> Self {
+        Self {
+            config,
+            next_lease: 0,
+            leases: BTreeMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &LeakDetectionConfig {
+        &self.config
+    }
+
+    /// Start tracking a checkout of `connection_id`
+    pub fn check_out(&mut self, connection_id: &str, now: Instant) -> LeaseId {
+        #[cfg(feature = "leak-backtrace")]
+        let backtrace = Some(Arc::new(Backtrace::force_capture()));
+        #[cfg(not(feature = "leak-backtrace"))]
+        let backtrace = None;
+
+        let id = self.next_lease;
+        self.next_lease += 1;
+        self.leases.insert(id, Lease {
+            connection_id: connection_id.to_string(),
+            acquired_at: now,
+            backtrace,
+            reported: false,
+        });
+        LeaseId(id)
+    }
+
+    /// Stop tracking a checkout; returns `false` if it was already reclaimed
+    pub fn check_in(&mut self, lease: LeaseId) -> bool {
+        self.leases.remove(&lease.0).is_some()
+    }
+
+    /// Checkouts currently tracked
+    pub fn outstanding(&self) -> usize {
+        self.leases.len()
+    }
+
+    /// Report checkouts newly past the threshold and reclaim those past
+    /// `reclaim_after`, oldest first
+    pub fn scan(&mut self, now: Instant) -> Vec<LeakReport> {
+        let mut reports = Vec::new();
+        let mut reclaimed = Vec::new();
+
+        for (&id, lease) in self.leases.iter_mut() {
+            let held_for = now.saturating_duration_since(lease.acquired_at);
+            let reclaim = self.config.reclaim_after.is_some_and(|reclaim_after| held_for >= reclaim_after);
+            if !reclaim && (lease.reported || held_for < self.config.threshold) {
+                continue;
+            }
+
+            lease.reported = true;
+            reports.push(LeakReport {
+                connection_id: lease.connection_id.clone(),
+                held_for,
+                backtrace: lease.backtrace.clone(),
+                reclaimed: reclaim,
+            });
+            if reclaim {
+                reclaimed.push(id);
+            }
+        }
+
+        for id in reclaimed {
+            self.leases.remove(&id);
+        }
+        reports
+    }
+}
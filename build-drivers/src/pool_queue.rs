@@ -0,0 +1,148 @@
+//! Prioritized Connection Pool Wait Queue
+//!
+//! Orders the acquisitions waiting for a pooled connection. Each waiter sits
+//! in one of three lanes; the queue serves the waiter with the highest
+//! effective priority, and among equals the one that arrived first, so each
+//! lane is FIFO.
+//!
+//! A waiter's effective priority is its lane plus one level for every
+//! `aging_interval` it has waited, capped at `High`. A low-priority waiter
+//! therefore ties with fresh high-priority arrivals after two intervals and,
+//! having arrived earlier, wins the tie: continuous high-priority load delays
+//! it but cannot starve it.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Lane an acquisition waits in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// Every lane, lowest first
+    pub const ALL: [Priority; 3] = [Priority::Low, Priority::Normal, Priority::High];
+
+    fn level(self) -> u64 {
+        self as u64
+    }
+}
+
+/// Place of one waiter in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTicket(u64);
+
+#[derive(Debug)]
+struct Waiter {
+    ticket: WaitTicket,
+    priority: Priority,
+    enqueued_at: Instant,
+}
+
+/// Wait times of the acquisitions served from one lane
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LaneWaitStats {
+    pub acquisitions: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+}
+
+impl LaneWaitStats {
+    /// Average wait per acquisition, zero before the first one
+    pub fn mean_wait(&self) -> Duration {
+        match self.acquisitions {
+            0 => Duration::ZERO,
+            n => Duration::from_nanos((self.total_wait.as_nanos() / n as u128) as u64),
+        }
+    }
+}
+
+/// Waiters for a pooled connection, in arrival order
+#[derive(Debug)]
+pub struct WaitQueue {
+    aging_interval: Duration,
+    waiters: VecDeque<Waiter>,
+    next_ticket: u64,
+    lanes: [LaneWaitStats; 3],
+}
+
+impl WaitQueue {
+    /// Create a queue that raises a waiter one level per `aging_interval`
+    pub fn new(aging_interval: Duration) -> Self {
+        Self {
+            aging_interval: aging_interval.max(Duration::from_millis(1)),
+            waiters: VecDeque::new(),
+            next_ticket: 0,
+            lanes: [LaneWaitStats::default(); 3],
+        }
+    }
+
+    /// Join the queue at the back of `priority`'s lane
+    pub fn push(&mut self, priority: Priority, now: Instant) -> WaitTicket {
+        let ticket = WaitTicket(self.next_ticket);
+        self.next_ticket += 1;
+        self.waiters.push_back(Waiter { ticket, priority, enqueued_at: now });
+        ticket
+    }
+
+    /// Leave the queue, whether served or given up; false if not queued
+    pub fn remove(&mut self, ticket: WaitTicket) -> bool {
+        match self.waiters.iter().position(|waiter| waiter.ticket == ticket) {
+            Some(index) => {
+                self.waiters.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The waiter to serve next
+    pub fn next(&self, now: Instant) -> Option<WaitTicket> {
+        // Waiters are in arrival order, so keeping the first maximum breaks ties FIFO
+        let mut best: Option<(&Waiter, u64)> = None;
+        for waiter in &self.waiters {
+            let effective = self.effective_level(waiter, now);
+            if !matches!(best, Some((_, level)) if level >= effective) {
+                best = Some((waiter, effective));
+            }
+        }
+        best.map(|(waiter, _)| waiter.ticket)
+    }
+
+    /// Waiters currently queued
+    pub fn len(&self) -> usize {
+        self.waiters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiters.is_empty()
+    }
+
+    /// Waiters currently queued in `priority`'s lane
+    pub fn lane_len(&self, priority: Priority) -> usize {
+        self.waiters.iter().filter(|waiter| waiter.priority == priority).count()
+    }
+
+    /// Count an acquisition served from `priority`'s lane after `wait`
+    pub fn record_wait(&mut self, priority: Priority, wait: Duration) {
+        let lane = &mut self.lanes[priority as usize];
+        lane.acquisitions += 1;
+        lane.total_wait += wait;
+        lane.max_wait = lane.max_wait.max(wait);
+    }
+
+    /// Wait times recorded for `priority`'s lane
+    pub fn lane_stats(&self, priority: Priority) -> LaneWaitStats {
+        self.lanes[priority as usize]
+    }
+
+    fn effective_level(&self, waiter: &Waiter, now: Instant) -> u64 {
+        let waited = now.saturating_duration_since(waiter.enqueued_at);
+        let boost = (waited.as_nanos() / self.aging_interval.as_nanos()).min(u64::MAX as u128) as u64;
+        waiter.priority.level().saturating_add(boost).min(Priority::High.level())
+    }
+}
@@ -0,0 +1,197 @@
+//! Server-Side Portals
+//!
+//! A portal is a query the server has started and holds open, handing out
+//! its rows a batch at a time as the client asks. Several portals may be
+//! open on one connection at once and fetched from in any order, so a UI
+//! can show the first page of several queries and fetch more of each as
+//! the user scrolls.
+//!
+//! | Message       | Request                          | Reply                                        |
+//! |---------------|----------------------------------|----------------------------------------------|
+//! | `PortalOpen`  | portal id (8), SQL               | portal id (8)                                |
+//! | `PortalFetch` | portal id (8), max rows (4)      | portal id (8), exhausted (1), rows (bincode) |
+//! | `PortalClose` | portal id (8)                    | portal id (8)                                |
+//!
+//! Integers are big-endian. The driver names each portal with an id unique
+//! on the connection, and every reply echoes the id of the portal it is
+//! for; a reply for any other portal means the stream is out of step and
+//! is refused.
+//!
+//! A [`Portal`] does not borrow the connection, so any number can be held
+//! while the connection is used for other work. Dropping one queues its
+//! `PortalClose`, which is sent before the connection's next portal request.
+
+use crate::connection::AuroraConnection;
+use crate::error::{AuroraError, Result};
+use crate::protocol::MessageType;
+use crate::types::AuroraRow;
+
+use bytes::Buf;
+use std::sync::{Arc, Mutex};
+
+/// Ids of dropped portals whose `PortalClose` has not been sent yet
+pub(crate) type ClosedPortals = Arc<Mutex<Vec<u64>>>;
+
+/// Length of the portal id that starts every portal message
+pub const PORTAL_ID_LEN: usize = 8;
+
+/// Length of a `PortalFetch` request
+pub const PORTAL_FETCH_REQUEST_LEN: usize = 12;
+
+/// Body of a `PortalOpen` request
+pub fn encode_open(portal_id: u64, sql: &str) -> Vec<u8> {
+    let mut request = Vec::with_capacity(PORTAL_ID_LEN + sql.len());
+    request.extend_from_slice(&portal_id.to_be_bytes());
+    request.extend_from_slice(sql.as_bytes());
+    request
+}
+
+/// Portal id and SQL of a `PortalOpen` request
+pub fn decode_open(request: &[u8]) -> Result<(u64, &str)> {
+    if request.len() < PORTAL_ID_LEN {
+        return Err(AuroraError::Protocol(format!("PortalOpen request of {} bytes", request.len())));
+    }
+    let (mut id, sql) = request.split_at(PORTAL_ID_LEN);
+    let sql = std::str::from_utf8(sql)
+        .map_err(|e| AuroraError::Protocol(format!("PortalOpen SQL is not UTF-8: {}", e)))?;
+    Ok((id.get_u64(), sql))
+}
+
+/// Body of a `PortalFetch` request
+pub fn encode_fetch(portal_id: u64, max_rows: u32) -> Vec<u8> {
+    let mut request = Vec::with_capacity(PORTAL_FETCH_REQUEST_LEN);
+    request.extend_from_slice(&portal_id.to_be_bytes());
+    request.extend_from_slice(&max_rows.to_be_bytes());
+    request
+}
+
+/// Portal id and maximum row count of a `PortalFetch` request
+pub fn decode_fetch(mut request: &[u8]) -> Result<(u64, u32)> {
+    if request.len() != PORTAL_FETCH_REQUEST_LEN {
+        return Err(AuroraError::Protocol(format!("PortalFetch request of {} bytes", request.len())));
+    }
+    Ok((request.get_u64(), request.get_u32()))
+}
+
+/// Body of a `PortalFetch` reply
+pub fn encode_batch(portal_id: u64, exhausted: bool, rows: &[AuroraRow]) -> Result<Vec<u8>> {
+    let mut reply = Vec::with_capacity(PORTAL_ID_LEN + 1);
+    reply.extend_from_slice(&portal_id.to_be_bytes());
+    reply.push(exhausted as u8);
+    bincode::serialize_into(&mut reply, rows)
+        .map_err(|e| AuroraError::Serialization(format!("Failed to serialize portal rows: {}", e)))?;
+    Ok(reply)
+}
+
+/// Portal id, exhausted flag and rows of a `PortalFetch` reply
+pub fn decode_batch(reply: &[u8]) -> Result<(u64, bool, Vec<AuroraRow>)> {
+    if reply.len() < PORTAL_ID_LEN + 1 {
+        return Err(AuroraError::Protocol(format!("PortalFetch reply of {} bytes", reply.len())));
+    }
+    let portal_id = decode_portal_id(&reply[..PORTAL_ID_LEN], "PortalFetch")?;
+    let exhausted = match reply[PORTAL_ID_LEN] {
+        0 => false,
+        1 => true,
+        other => return Err(AuroraError::Protocol(format!("PortalFetch exhausted flag {}", other))),
+    };
+    let rows = bincode::deserialize(&reply[PORTAL_ID_LEN + 1..])
+        .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize portal rows: {}", e)))?;
+    Ok((portal_id, exhausted, rows))
+}
+
+/// The 8-byte portal id that answers `PortalOpen` and `PortalClose`
+pub fn decode_portal_id(reply: &[u8], message: &str) -> Result<u64> {
+    let bytes: [u8; PORTAL_ID_LEN] = reply.try_into()
+        .map_err(|_| AuroraError::Protocol(format!("{} reply of {} bytes, expected 8", message, reply.len())))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Refuse a reply addressed to a portal other than the one asked
+fn expect_portal(replied: u64, asked: u64, message: &str) -> Result<()> {
+    if replied != asked {
+        return Err(AuroraError::Protocol(format!(
+            "{} reply for portal {} while awaiting portal {}", message, replied, asked
+        )));
+    }
+    Ok(())
+}
+
+/// An open server-side portal; closed on the server once dropped
+#[derive(Debug)]
+pub struct Portal {
+    id: u64,
+    fetched: u64,
+    exhausted: bool,
+    /// Close queue of the connection the portal was opened on
+    closed: ClosedPortals,
+}
+
+impl Portal {
+    /// Id naming the portal on its connection
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Rows fetched so far
+    pub fn fetched(&self) -> u64 {
+        self.fetched
+    }
+
+    /// Whether every row has been fetched
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+impl Drop for Portal {
+    fn drop(&mut self) {
+        if let Ok(mut closed) = self.closed.lock() {
+            closed.push(self.id);
+        }
+    }
+}
+
+impl AuroraConnection {
+    /// Start `sql` in a new portal without fetching any rows
+    pub async fn open_portal(&mut self, sql: &str) -> Result<Portal> {
+        self.close_dropped_portals().await?;
+        let id = self.next_portal_id();
+        self.send_message(MessageType::PortalOpen, &encode_open(id, sql)).await?;
+        expect_portal(decode_portal_id(&self.receive_message().await?, "PortalOpen")?, id, "PortalOpen")?;
+        Ok(Portal { id, fetched: 0, exhausted: false, closed: self.closed_portals() })
+    }
+
+    /// Up to `max_rows` further rows of `portal`, in query order; empty once
+    /// it is exhausted
+    pub async fn fetch(&mut self, portal: &mut Portal, max_rows: u32) -> Result<Vec<AuroraRow>> {
+        if !Arc::ptr_eq(&portal.closed, &self.closed_portals()) {
+            return Err(AuroraError::Query(format!("Portal {} belongs to another connection", portal.id)));
+        }
+        if portal.exhausted {
+            return Ok(Vec::new());
+        }
+        self.close_dropped_portals().await?;
+
+        self.send_message(MessageType::PortalFetch, &encode_fetch(portal.id, max_rows)).await?;
+        let (id, exhausted, rows) = decode_batch(&self.receive_message().await?)?;
+        expect_portal(id, portal.id, "PortalFetch")?;
+        if rows.len() > max_rows as usize {
+            return Err(AuroraError::Protocol(format!(
+                "PortalFetch returned {} rows, asked for {}", rows.len(), max_rows
+            )));
+        }
+        portal.fetched += rows.len() as u64;
+        portal.exhausted = exhausted;
+        Ok(rows)
+    }
+
+    /// Send the `PortalClose` of every portal dropped since the last request
+    async fn close_dropped_portals(&mut self) -> Result<()> {
+        let closed = self.closed_portals().lock().map(|mut closed| std::mem::take(&mut *closed)).unwrap_or_default();
+        for id in closed {
+            self.send_message(MessageType::PortalClose, &id.to_be_bytes()).await?;
+            expect_portal(decode_portal_id(&self.receive_message().await?, "PortalClose")?, id, "PortalClose")?;
+        }
+        Ok(())
+    }
+}
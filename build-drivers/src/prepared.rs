@@ -0,0 +1,256 @@
+//! Prepared Statements
+//!
+//! `prepare` has the server parse and describe a statement once and keep it
+//! under a name; `execute_prepared` then sends only that name and the
+//! parameters, so the server skips parsing.
+//!
+//! | Message            | Request                    | Reply                       |
+//! |--------------------|----------------------------|-----------------------------|
+//! | `PrepareStatement` | [`PrepareRequest`]         | [`PrepareResponse`]         |
+//! | `ExecutePrepared`  | [`ExecutePreparedRequest`] | [`ExecutePreparedResponse`] |
+//! | `ClosePrepared`    | statement name (UTF-8)     | statement name (UTF-8)      |
+//!
+//! Requests and replies other than `ClosePrepared` are bincode.
+//!
+//! Each connection caches its statements by SQL text, up to
+//! `AuroraConfig::max_prepared_statements`, evicting the least recently
+//! used. An evicted statement's `ClosePrepared` goes out just ahead of the
+//! `PrepareStatement` that displaced it, in the same round trip, so the
+//! server frees it without costing a wait.
+//!
+//! A connection serves one caller at a time, so callers sharing one prepare
+//! the same SQL with a single round trip: whoever comes second finds the
+//! statement cached. A [`PreparedStatement`] does not borrow the connection
+//! and stays usable after its statement is evicted or the connection is
+//! re-established; executing it then prepares its SQL again first.
+
+use crate::connection::AuroraConnection;
+use crate::error::{AuroraError, Result};
+use crate::protocol::MessageType;
+use crate::telemetry::{Operation, OperationSpan};
+use crate::types::{AuroraColumn, AuroraValue, QueryResult};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// `PrepareStatement` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrepareRequest {
+    /// Name the server keeps the statement under
+    pub name: String,
+    pub sql: String,
+}
+
+/// Server reply to a `PrepareStatement` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrepareResponse {
+    /// Name the statement was prepared under
+    pub name: String,
+
+    /// Columns the statement returns; empty for a write
+    pub columns: Vec<AuroraColumn>,
+
+    /// Why the statement could not be prepared, if it could not
+    pub error: Option<String>,
+}
+
+/// `ExecutePrepared` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutePreparedRequest {
+    pub name: String,
+    pub params: Vec<AuroraValue>,
+}
+
+/// Server reply to an `ExecutePrepared` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutePreparedResponse {
+    pub result: Option<QueryResult>,
+    pub error: Option<String>,
+}
+
+/// A statement prepared on the server
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    name: String,
+    sql: String,
+    columns: Vec<AuroraColumn>,
+}
+
+impl PreparedStatement {
+    /// Name the server knows the statement by
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Columns the statement returns, as the server described them
+    pub fn columns(&self) -> &[AuroraColumn] {
+        &self.columns
+    }
+}
+
+/// Prepared statements of one connection by SQL text, least recently used
+/// evicted first
+#[derive(Debug)]
+pub struct StatementCache {
+    capacity: usize,
+    /// Use counter; a higher value is more recent
+    clock: u64,
+    /// Statement and last use of each SQL text
+    entries: HashMap<String, (PreparedStatement, u64)>,
+    /// SQL text by last use
+    recency: BTreeMap<u64, String>,
+}
+
+impl StatementCache {
+    /// Cache of up to `capacity` statements, at least one
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            clock: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, sql: &str) -> bool {
+        self.entries.contains_key(sql)
+    }
+
+    /// Statement prepared for `sql`, which becomes the most recently used
+    pub fn get(&mut self, sql: &str) -> Option<PreparedStatement> {
+        let (statement, last_use) = self.entries.get_mut(sql)?;
+        self.recency.remove(last_use);
+        self.clock += 1;
+        *last_use = self.clock;
+        self.recency.insert(self.clock, sql.to_string());
+        Some(statement.clone())
+    }
+
+    /// Remove the least recently used statements until one more fits,
+    /// returning them
+    pub fn make_room(&mut self) -> Vec<PreparedStatement> {
+        let mut evicted = Vec::new();
+        while self.entries.len() >= self.capacity {
+            let Some((_, sql)) = self.recency.pop_first() else { break };
+            if let Some((statement, _)) = self.entries.remove(&sql) {
+                evicted.push(statement);
+            }
+        }
+        evicted
+    }
+
+    /// Add `statement` as the most recently used, replacing any statement
+    /// of the same SQL
+    pub fn insert(&mut self, statement: PreparedStatement) {
+        self.clock += 1;
+        if let Some((_, last_use)) = self.entries.get(&statement.sql) {
+            self.recency.remove(last_use);
+        }
+        self.recency.insert(self.clock, statement.sql.clone());
+        self.entries.insert(statement.sql.clone(), (statement, self.clock));
+    }
+
+    /// Forget every statement, as when the server session they lived in ended
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+impl AuroraConnection {
+    /// Prepare `sql` on the server, or return the statement already
+    /// prepared for it on this connection
+    pub async fn prepare(&mut self, sql: &str) -> Result<PreparedStatement> {
+        if let Some(statement) = self.statements().get(sql) {
+            return Ok(statement);
+        }
+
+        let info = self.info();
+        let span = OperationSpan::start(Operation::Prepare, &info.host, info.port, Some(sql));
+        let result = self.prepare_uncached(sql).await;
+        span.finish(&result);
+        result
+    }
+
+    /// Run `statement` with `params`, sending only its name and the values
+    ///
+    /// If the statement is no longer prepared on this connection, its SQL
+    /// is prepared again first.
+    pub async fn execute_prepared(&mut self, statement: &PreparedStatement, params: &[AuroraValue]) -> Result<QueryResult> {
+        let name = self.prepare(statement.sql()).await?.name;
+
+        let info = self.info();
+        let span = OperationSpan::start(Operation::Execute, &info.host, info.port, Some(statement.sql()));
+        let result = self.send_execute_prepared(name, params).await;
+        span.finish(&result);
+        result
+    }
+
+    async fn prepare_uncached(&mut self, sql: &str) -> Result<PreparedStatement> {
+        // Reconnect before deciding what to close: a new session has nothing to close
+        self.ensure_connected().await?;
+
+        let evicted = self.statements().make_room();
+        let name = self.next_statement_name();
+        let request = bincode::serialize(&PrepareRequest { name: name.clone(), sql: sql.to_string() })
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize prepare request: {}", e)))?;
+
+        for statement in &evicted {
+            self.send_message(MessageType::ClosePrepared, statement.name.as_bytes()).await?;
+        }
+        self.send_message(MessageType::PrepareStatement, &request).await?;
+
+        for statement in &evicted {
+            let reply = self.receive_message().await?;
+            if reply.as_ref() != statement.name.as_bytes() {
+                return Err(AuroraError::Protocol(format!(
+                    "ClosePrepared reply for {} while closing {}", String::from_utf8_lossy(&reply), statement.name
+                )));
+            }
+        }
+        let response: PrepareResponse = bincode::deserialize(&self.receive_message().await?)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize prepare response: {}", e)))?;
+
+        if let Some(error) = response.error {
+            return Err(AuroraError::Query(format!("Failed to prepare statement: {}", error)));
+        }
+        if response.name != name {
+            return Err(AuroraError::Protocol(format!(
+                "PrepareStatement reply for {} while preparing {}", response.name, name
+            )));
+        }
+        let statement = PreparedStatement { name, sql: sql.to_string(), columns: response.columns };
+        self.statements().insert(statement.clone());
+        Ok(statement)
+    }
+
+    async fn send_execute_prepared(&mut self, name: String, params: &[AuroraValue]) -> Result<QueryResult> {
+        let request = bincode::serialize(&ExecutePreparedRequest { name, params: params.to_vec() })
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize execute prepared request: {}", e)))?;
+        self.send_message(MessageType::ExecutePrepared, &request).await?;
+
+        let response: ExecutePreparedResponse = bincode::deserialize(&self.receive_message().await?)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize execute prepared response: {}", e)))?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(AuroraError::Query(error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(AuroraError::Protocol("ExecutePrepared reply carries neither a result nor an error".into())),
+        }
+    }
+}
@@ -7,6 +7,14 @@ use crate::connection::AuroraConnection;
 use crate::types::*;
 use crate::error::{AuroraError, Result};
 use crate::metrics::DriverMetrics;
+use crate::telemetry::{Operation, OperationSpan};
+use crate::interceptor::{InterceptedRequest, InterceptorChain};
+use crate::batch::{self, BatchMode, InsertBatch, InsertBatchRequest, InsertBatchResponse, RowResult};
+use crate::binary_copy::{BinaryCopyCodec, CopyInRequest, CopyInResponse};
+use crate::copy_stream::CopySink;
+use crate::vector_batch::{self, VectorItem, VectorUpsertBatchRequest, VectorUpsertBatchResponse, VectorUpsertResult};
+use crate::replication::ReplicationStatus;
+use crate::introspection::{self, IntrospectRequest};
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -23,18 +31,33 @@ pub struct AuroraProtocol {
 
     /// Metrics collector
     metrics: Arc<RwLock<DriverMetrics>>,
+
+    /// Middleware run around every query and statement
+    interceptors: InterceptorChain,
 }
 
 impl AuroraProtocol {
     /// Create new protocol handler
     pub fn new() -> Self {
+        Self::with_interceptors(InterceptorChain::new())
+    }
+
+    /// Create a protocol handler that runs `interceptors` around every query
+    /// and statement, on whichever connection it is sent
+    pub fn with_interceptors(interceptors: InterceptorChain) -> Self {
         Self {
             version: 1,
             compression: true,
             metrics: Arc::new(RwLock::new(DriverMetrics::default())),
+            interceptors,
         }
     }
 
+    /// Interceptors run around every request
+    pub fn interceptors(&self) -> &InterceptorChain {
+        &self.interceptors
+    }
+
     /// Execute a query
     pub async fn execute_query(
         &self,
@@ -56,6 +79,25 @@ impl AuroraProtocol {
         conn: &mut AuroraConnection,
         sql: &str,
         params: &[AuroraValue],
+    ) -> Result<QueryResult> {
+        let info = conn.info();
+        let mut span = OperationSpan::start(Operation::Query, &info.host, info.port, Some(sql));
+        let mut request = InterceptedRequest::new(Operation::Query, sql, params, &info.connection_id);
+        let result = self.interceptors
+            .run(&mut request, |sql, params| async move { self.send_query(conn, &sql, &params).await })
+            .await;
+        if let Ok(result) = &result {
+            span.record_rows(result.row_count as u64);
+        }
+        span.finish(&result);
+        result
+    }
+
+    async fn send_query(
+        &self,
+        conn: &mut AuroraConnection,
+        sql: &str,
+        params: &[AuroraValue],
     ) -> Result<QueryResult> {
         let start_time = std::time::Instant::now();
 
@@ -108,11 +150,58 @@ impl AuroraProtocol {
         conn: &mut AuroraConnection,
         sql: &str,
         params: &[AuroraValue],
+    ) -> Result<ExecuteResult> {
+        self.send_execute(conn, sql, params, None).await
+    }
+
+    /// Execute a statement that is safe to retry
+    ///
+    /// The server records the result under `idempotency_key`; resending the same
+    /// statement with the same key returns that result instead of applying it twice.
+    pub async fn execute_statement_idempotent(
+        &self,
+        conn: &mut AuroraConnection,
+        sql: &str,
+        params: &[AuroraValue],
+        idempotency_key: &str,
+    ) -> Result<ExecuteResult> {
+        self.send_execute(conn, sql, params, Some(idempotency_key.to_string())).await
+    }
+
+    async fn send_execute(
+        &self,
+        conn: &mut AuroraConnection,
+        sql: &str,
+        params: &[AuroraValue],
+        idempotency_key: Option<String>,
+    ) -> Result<ExecuteResult> {
+        let info = conn.info();
+        let mut span = OperationSpan::start(Operation::Execute, &info.host, info.port, Some(sql));
+        let mut request = InterceptedRequest::new(Operation::Execute, sql, params, &info.connection_id);
+        let result = self.interceptors
+            .run(&mut request, |sql, params| async move {
+                self.send_execute_request(conn, &sql, &params, idempotency_key).await
+            })
+            .await;
+        if let Ok(result) = &result {
+            span.record_rows(result.rows_affected);
+        }
+        span.finish(&result);
+        result
+    }
+
+    async fn send_execute_request(
+        &self,
+        conn: &mut AuroraConnection,
+        sql: &str,
+        params: &[AuroraValue],
+        idempotency_key: Option<String>,
     ) -> Result<ExecuteResult> {
         let request = ExecuteRequest {
             sql: sql.to_string(),
             params: params.to_vec(),
             timeout: Some(Duration::from_secs(30)),
+            idempotency_key,
         };
 
         // Serialize and send
@@ -132,6 +221,149 @@ impl AuroraProtocol {
         response.result
     }
 
+    /// Insert a batch of rows in one round trip, returning the outcome of
+    /// each row in batch order
+    ///
+    /// The batch is a structured message rather than SQL text, so statement
+    /// interceptors do not see it; it is traced as an execute.
+    pub async fn insert_batch(
+        &self,
+        conn: &mut AuroraConnection,
+        batch: InsertBatch,
+        mode: BatchMode,
+    ) -> Result<Vec<RowResult>> {
+        let info = conn.info();
+        let statement = batch.statement();
+        let len = batch.len();
+        let mut span = OperationSpan::start(Operation::Execute, &info.host, info.port, Some(&statement));
+
+        let (request, rejected) = batch.into_request(mode);
+        let result = if request.rows.is_empty() || (mode == BatchMode::Atomic && !rejected.is_empty()) {
+            // Nothing worth sending
+            batch::row_results(len, mode, rejected, InsertBatchResponse::default())
+        } else {
+            match self.send_insert_batch(conn, &request).await {
+                Ok(response) => batch::row_results(len, mode, rejected, response),
+                Err(error) => Err(error),
+            }
+        };
+
+        if let Ok(results) = &result {
+            span.record_rows(results.iter().filter(|row| row.is_ok()).count() as u64);
+        }
+        span.finish(&result);
+        result
+    }
+
+    async fn send_insert_batch(
+        &self,
+        conn: &mut AuroraConnection,
+        request: &InsertBatchRequest,
+    ) -> Result<InsertBatchResponse> {
+        let request_bytes = bincode::serialize(request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize insert batch request: {}", e)))?;
+        conn.send_message(MessageType::InsertBatch, &request_bytes).await?;
+
+        let response_bytes = conn.receive_message().await?;
+        let response: InsertBatchResponse = bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize insert batch response: {}", e)))?;
+
+        let mut metrics = self.metrics.write().await;
+        metrics.statements_executed += 1;
+        metrics.bytes_sent += request_bytes.len() as u64;
+        metrics.bytes_received += response_bytes.len() as u64;
+
+        Ok(response)
+    }
+
+    /// Insert or update a batch of vectors in one round trip, returning the
+    /// outcome of each item in batch order
+    ///
+    /// Items must all share one dimension; a batch that mixes dimensions is
+    /// refused before it is sent.
+    pub async fn vector_upsert_batch(
+        &self,
+        conn: &mut AuroraConnection,
+        collection: &str,
+        items: Vec<VectorItem>,
+    ) -> Result<Vec<VectorUpsertResult>> {
+        let len = items.len();
+        let request = VectorUpsertBatchRequest::new(collection, items)?;
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let request_bytes = bincode::serialize(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize vector upsert batch request: {}", e)))?;
+        conn.send_message(MessageType::VectorUpsertBatch, &request_bytes).await?;
+
+        let response_bytes = conn.receive_message().await?;
+        let response: VectorUpsertBatchResponse = bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize vector upsert batch response: {}", e)))?;
+
+        let mut metrics = self.metrics.write().await;
+        metrics.statements_executed += 1;
+        metrics.bytes_sent += request_bytes.len() as u64;
+        metrics.bytes_received += response_bytes.len() as u64;
+        drop(metrics);
+
+        vector_batch::item_results(len, response)
+    }
+
+    /// Load `rows` into `table` with one binary COPY, returning the number
+    /// of rows loaded
+    ///
+    /// Rows are encoded by `codec`'s column types before anything is sent, so
+    /// a vector of the wrong dimension fails the whole load locally.
+    pub async fn copy_in_binary(
+        &self,
+        conn: &mut AuroraConnection,
+        table: &str,
+        codec: &BinaryCopyCodec,
+        rows: &[Vec<AuroraValue>],
+    ) -> Result<u64> {
+        let request = CopyInRequest {
+            table: table.to_string(),
+            columns: codec.column_names().into_iter().map(str::to_string).collect(),
+            data: codec.encode(rows)?.to_vec(),
+        };
+
+        let request_bytes = bincode::serialize(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize COPY request: {}", e)))?;
+        conn.send_message(MessageType::CopyIn, &request_bytes).await?;
+
+        let response_bytes = conn.receive_message().await?;
+        let response: CopyInResponse = bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize COPY response: {}", e)))?;
+
+        let mut metrics = self.metrics.write().await;
+        metrics.statements_executed += 1;
+        metrics.bytes_sent += request_bytes.len() as u64;
+        metrics.bytes_received += response_bytes.len() as u64;
+        drop(metrics);
+
+        match response.rejected {
+            Some(reason) => Err(AuroraError::Query(format!("COPY into {} rejected: {}", table, reason))),
+            None => Ok(response.rows),
+        }
+    }
+
+    /// Open a COPY into `columns` of `table` and return a sink to stream
+    /// its rows into
+    ///
+    /// Rows are coalesced into large `CopyData` messages; see
+    /// [`CopySink::with_flush_rows`]. Nothing is loaded until
+    /// [`CopySink::finish`], and a sink dropped before then rolls the load
+    /// back.
+    pub async fn copy_in<'a>(
+        &self,
+        conn: &'a mut AuroraConnection,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<CopySink<'a>> {
+        CopySink::start(conn, table, columns).await
+    }
+
     /// Perform vector similarity search
     pub async fn vector_search(
         &self,
@@ -284,6 +516,25 @@ impl AuroraProtocol {
         }
     }
 
+    /// Ask a replica how far it has replayed the primary's log
+    pub async fn replication_status(&self, conn: &mut AuroraConnection) -> Result<ReplicationStatus> {
+        conn.send_message(MessageType::ReplicationStatus, &[]).await?;
+        let response_bytes = conn.receive_message().await?;
+        bincode::deserialize(&response_bytes)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to deserialize replication status: {}", e)))
+    }
+
+    /// Describe the tables of the database, or only `table_name`, with
+    /// their columns, indexes and constraints
+    pub async fn get_schema(&self, conn: &mut AuroraConnection, table_name: Option<&str>) -> Result<SchemaInfo> {
+        let request = IntrospectRequest { table: table_name.map(str::to_string) };
+        let request_bytes = serde_json::to_vec(&request)
+            .map_err(|e| AuroraError::Serialization(format!("Failed to serialize introspection request: {}", e)))?;
+        conn.send_message(MessageType::Introspect, &request_bytes).await?;
+        let response_bytes = conn.receive_message().await?;
+        introspection::decode_schema(&response_bytes)
+    }
+
     /// Get protocol metrics
     pub async fn metrics(&self) -> DriverMetrics {
         self.metrics.read().await.clone()
@@ -366,6 +617,25 @@ pub enum MessageType {
     CommitTransaction = 7,
     RollbackTransaction = 8,
     HealthCheck = 9,
+    InsertBatch = 10,
+    ReplicationStatus = 11,
+    VectorUpsertBatch = 12,
+    CopyIn = 13,
+    Introspect = 14,
+    LobCreate = 15,
+    LobRead = 16,
+    LobWrite = 17,
+    PortalOpen = 18,
+    PortalFetch = 19,
+    PortalClose = 20,
+    AtomicBatch = 21,
+    PrepareStatement = 22,
+    ExecutePrepared = 23,
+    ClosePrepared = 24,
+    CopyStart = 25,
+    CopyData = 26,
+    CopyDone = 27,
+    CopyFail = 28,
 }
 
 // Response types (would be defined in types.rs)
@@ -0,0 +1,308 @@
+//! Replica Routing with Read-Your-Writes
+//!
+//! With read/write splitting, writes go to the primary and reads to replicas.
+//! A replica that has not replayed a write yet serves data from before it, so
+//! a read issued right after a write can miss it. The driver polls each
+//! replica's replay position with a lightweight `ReplicationStatus` message
+//! and routes every read by the freshness it requires:
+//!
+//! - [`Freshness::Any`]: any replica lagging no more than `max_lag`
+//! - [`Freshness::AtLeast`]: only replicas that have replayed up to the given
+//!   LSN, typically the `commit_lsn` of the caller's preceding write
+//!
+//! A replica whose last status is older than `max_status_age`, or which could
+//! not be reached, serves no reads. When no replica qualifies the read goes
+//! to the primary, which always reflects every committed write.
+
+use crate::config::{AuroraConfig, ReplicaRoutingConfig};
+use crate::error::Result;
+use crate::pool::AuroraConnectionPool;
+use crate::protocol::AuroraProtocol;
+use crate::types::{AuroraValue, ExecuteResult, QueryResult};
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
+
+/// Position in the primary's write-ahead log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lsn(pub u64);
+
+/// How current the data a read sees must be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Any replica within the configured lag bound will do
+    Any,
+
+    /// The read must reflect every write up to this LSN
+    AtLeast(Lsn),
+}
+
+/// Reply to a `ReplicationStatus` message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationStatus {
+    /// Last LSN the replica has replayed
+    pub replay_lsn: Lsn,
+
+    /// How far behind the primary the replica is, in milliseconds
+    pub lag_ms: u64,
+}
+
+/// Where a read is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadTarget {
+    Primary,
+    Replica(usize),
+}
+
+/// Lag metrics of one replica
+#[derive(Debug, Clone)]
+pub struct ReplicaLag {
+    /// Replica address, `host:port`
+    pub replica: String,
+
+    /// Last replayed LSN, once a status has been received
+    pub replay_lsn: Option<Lsn>,
+
+    /// Reported lag behind the primary
+    pub lag: Option<Duration>,
+
+    /// Time since the status was received
+    pub status_age: Option<Duration>,
+
+    /// False when the last status poll failed
+    pub reachable: bool,
+
+    /// Reads routed to this replica
+    pub reads_routed: u64,
+}
+
+#[derive(Debug)]
+struct ReplicaState {
+    name: String,
+    status: Option<(ReplicationStatus, Instant)>,
+    reachable: bool,
+    reads_routed: u64,
+}
+
+/// Chooses the replica for each read from the last known replica status
+#[derive(Debug)]
+pub struct ReplicaRouter {
+    config: ReplicaRoutingConfig,
+    replicas: Vec<ReplicaState>,
+    next: usize,
+    primary_reads: u64,
+}
+
+impl ReplicaRouter {
+    /// Create a router for replicas named `names`, none routable until
+    /// their first status arrives
+    pub fn new(config: ReplicaRoutingConfig, names: Vec<String>) -> Self {
+        let replicas = names.into_iter().map(|name| ReplicaState {
+            name,
+            status: None,
+            reachable: true,
+            reads_routed: 0,
+        }).collect();
+        Self { config, replicas, next: 0, primary_reads: 0 }
+    }
+
+    /// Record a replica's status as of now
+    pub fn record_status(&mut self, replica: usize, status: ReplicationStatus) {
+        if let Some(state) = self.replicas.get_mut(replica) {
+            state.status = Some((status, Instant::now()));
+            state.reachable = true;
+        }
+    }
+
+    /// Record that a replica could not be reached; it serves no reads until
+    /// a status poll succeeds again
+    pub fn record_unreachable(&mut self, replica: usize) {
+        if let Some(state) = self.replicas.get_mut(replica) {
+            state.reachable = false;
+        }
+    }
+
+    /// Pick the target of a read, rotating among qualifying replicas
+    pub fn route(&mut self, freshness: Freshness) -> ReadTarget {
+        let count = self.replicas.len();
+        for offset in 0..count {
+            let index = (self.next + offset) % count;
+            if self.qualifies(&self.replicas[index], freshness) {
+                self.next = (index + 1) % count;
+                self.replicas[index].reads_routed += 1;
+                return ReadTarget::Replica(index);
+            }
+        }
+        self.primary_reads += 1;
+        ReadTarget::Primary
+    }
+
+    fn qualifies(&self, state: &ReplicaState, freshness: Freshness) -> bool {
+        let (status, at) = match (&state.status, state.reachable) {
+            (Some(status), true) => status,
+            _ => return false,
+        };
+        if at.elapsed() > self.config.max_status_age {
+            return false;
+        }
+        match freshness {
+            Freshness::AtLeast(lsn) => status.replay_lsn >= lsn,
+            Freshness::Any => self.config.max_lag
+                .map_or(true, |max_lag| Duration::from_millis(status.lag_ms) <= max_lag),
+        }
+    }
+
+    /// Lag metrics of every replica, in configuration order
+    pub fn lag(&self) -> Vec<ReplicaLag> {
+        self.replicas.iter().map(|state| ReplicaLag {
+            replica: state.name.clone(),
+            replay_lsn: state.status.as_ref().map(|(status, _)| status.replay_lsn),
+            lag: state.status.as_ref().map(|(status, _)| Duration::from_millis(status.lag_ms)),
+            status_age: state.status.as_ref().map(|(_, at)| at.elapsed()),
+            reachable: state.reachable,
+            reads_routed: state.reads_routed,
+        }).collect()
+    }
+
+    /// Reads sent to the primary because no replica qualified
+    pub fn primary_reads(&self) -> u64 {
+        self.primary_reads
+    }
+}
+
+/// A primary and its read replicas, with reads routed by freshness
+pub struct ReplicaSet {
+    primary: AuroraConnectionPool,
+    replicas: Vec<AuroraConnectionPool>,
+    router: Arc<Mutex<ReplicaRouter>>,
+    protocol: Arc<AuroraProtocol>,
+    shutdown: Arc<Notify>,
+}
+
+impl ReplicaSet {
+    /// Connect to the primary and every replica, poll replica status once,
+    /// and keep polling every `status_interval` in the background
+    pub async fn connect(
+        primary: AuroraConfig,
+        replicas: Vec<AuroraConfig>,
+        routing: ReplicaRoutingConfig,
+    ) -> Result<Self> {
+        let names = replicas.iter().map(|config| format!("{}:{}", config.host, config.port)).collect();
+        let interval = routing.status_interval;
+
+        let primary = AuroraConnectionPool::new(primary).await?;
+        let mut replica_pools = Vec::with_capacity(replicas.len());
+        for config in replicas {
+            replica_pools.push(AuroraConnectionPool::new(config).await?);
+        }
+
+        let set = Self {
+            primary,
+            replicas: replica_pools,
+            router: Arc::new(Mutex::new(ReplicaRouter::new(routing, names))),
+            protocol: Arc::new(AuroraProtocol::new()),
+            shutdown: Arc::new(Notify::new()),
+        };
+        set.refresh_status().await;
+        set.start_status_task(interval);
+        Ok(set)
+    }
+
+    /// Execute a write on the primary; pass its `commit_lsn` to
+    /// [`Freshness::AtLeast`] for reads that must see it
+    pub async fn execute(&self, sql: &str, params: &[AuroraValue]) -> Result<ExecuteResult> {
+        let mut conn = self.primary.acquire().await?;
+        self.protocol.execute_statement_with_params(&mut conn, sql, params).await
+    }
+
+    /// Run a read on a replica that satisfies `freshness`, or on the primary
+    ///
+    /// A replica that fails with a retryable error is marked unreachable and
+    /// the read is retried on the primary.
+    pub async fn query(&self, sql: &str, params: &[AuroraValue], freshness: Freshness) -> Result<QueryResult> {
+        let target = self.router.lock().await.route(freshness);
+        if let ReadTarget::Replica(index) = target {
+            let result = async {
+                let mut conn = self.replicas[index].acquire().await?;
+                self.protocol.execute_query_with_params(&mut conn, sql, params).await
+            }.await;
+            match result {
+                Err(e) if e.is_retryable() => {
+                    warn!("Replica read failed, falling back to primary: {}", e);
+                    self.router.lock().await.record_unreachable(index);
+                }
+                result => return result,
+            }
+        }
+
+        let mut conn = self.primary.acquire().await?;
+        self.protocol.execute_query_with_params(&mut conn, sql, params).await
+    }
+
+    /// Poll every replica's replication status now
+    pub async fn refresh_status(&self) {
+        for (index, pool) in self.replicas.iter().enumerate() {
+            let status = async {
+                let mut conn = pool.acquire().await?;
+                self.protocol.replication_status(&mut conn).await
+            }.await;
+
+            let mut router = self.router.lock().await;
+            match status {
+                Ok(status) => router.record_status(index, status),
+                Err(e) => {
+                    warn!("Replication status poll failed for replica {}: {}", index, e);
+                    router.record_unreachable(index);
+                }
+            }
+        }
+    }
+
+    /// Lag metrics of every replica
+    pub async fn replica_lag(&self) -> Vec<ReplicaLag> {
+        self.router.lock().await.lag()
+    }
+
+    /// Reads sent to the primary because no replica qualified
+    pub async fn primary_reads(&self) -> u64 {
+        self.router.lock().await.primary_reads()
+    }
+
+    /// Stop polling and close every pool
+    pub async fn close(&self) -> Result<()> {
+        self.shutdown.notify_waiters();
+        for pool in &self.replicas {
+            pool.close().await?;
+        }
+        self.primary.close().await
+    }
+
+    fn start_status_task(&self, interval: Duration) {
+        let set = Self {
+            primary: self.primary.clone(),
+            replicas: self.replicas.clone(),
+            router: Arc::clone(&self.router),
+            protocol: Arc::clone(&self.protocol),
+            shutdown: Arc::clone(&self.shutdown),
+        };
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        set.refresh_status().await;
+                    }
+                    _ = set.shutdown.notified() => {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
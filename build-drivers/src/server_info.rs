@@ -0,0 +1,161 @@
+//! Server Capabilities
+//!
+//! The server's reply to authentication carries a status word and then
+//! NUL-separated `name=value` pairs describing the server:
+//!
+//! - `server_version`: release of the server, such as `2.4.1`
+//! - `protocol_version`: frame protocol the server speaks
+//! - `features`: comma-separated capabilities, such as
+//!   `vector_search,copy_binary`
+//! - anything else: an option negotiated for this connection, such as
+//!   `compression=lz4`
+//!
+//! Servers from before capability advertising reply with the status alone.
+//! Their version is unknown and every capability reads as unsupported, so
+//! client code that gates on a capability takes its fallback path.
+
+use crate::error::{AuroraError, Result};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Protocol capability a server may advertise
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ServerFeature {
+    /// `VectorSearch` and `VectorUpsertBatch` messages
+    VectorSearch,
+
+    /// Analytics results streamed as they are produced
+    AnalyticsStreaming,
+
+    /// `CopyIn` with the binary COPY format
+    CopyBinary,
+
+    /// Compressed frames
+    Compression,
+
+    /// A capability this driver does not know
+    Other(String),
+}
+
+impl ServerFeature {
+    /// Feature named `name` in the handshake
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "vector_search" => ServerFeature::VectorSearch,
+            "analytics_streaming" => ServerFeature::AnalyticsStreaming,
+            "copy_binary" => ServerFeature::CopyBinary,
+            "compression" => ServerFeature::Compression,
+            other => ServerFeature::Other(other.to_string()),
+        }
+    }
+
+    /// Name of the feature in the handshake
+    pub fn name(&self) -> &str {
+        match self {
+            ServerFeature::VectorSearch => "vector_search",
+            ServerFeature::AnalyticsStreaming => "analytics_streaming",
+            ServerFeature::CopyBinary => "copy_binary",
+            ServerFeature::Compression => "compression",
+            ServerFeature::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for ServerFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What the server a connection reached says about itself
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// Server release; `None` for servers that do not report one
+    pub version: Option<String>,
+
+    /// Frame protocol version the server speaks, if reported
+    pub protocol_version: Option<u32>,
+
+    /// Capabilities the server advertised
+    pub features: BTreeSet<ServerFeature>,
+
+    /// Options negotiated for this connection, by name
+    pub options: BTreeMap<String, String>,
+}
+
+impl ServerInfo {
+    /// Parse the server's reply to authentication
+    pub fn from_handshake(response: &[u8]) -> Result<Self> {
+        let response = std::str::from_utf8(response)
+            .map_err(|_| AuroraError::Protocol("Handshake response is not UTF-8".into()))?;
+
+        // The status word comes first
+        let mut info = ServerInfo::default();
+        for pair in response.split('\0').skip(1).filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=')
+                .ok_or_else(|| AuroraError::Protocol(format!("Malformed handshake parameter {:?}", pair)))?;
+            match name {
+                "server_version" => info.version = Some(value.to_string()),
+                "protocol_version" => {
+                    let version = value.parse()
+                        .map_err(|_| AuroraError::Protocol(format!("Invalid protocol version {:?}", value)))?;
+                    info.protocol_version = Some(version);
+                }
+                "features" => {
+                    info.features = value.split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(ServerFeature::from_name)
+                        .collect();
+                }
+                option => {
+                    info.options.insert(option.to_string(), value.to_string());
+                }
+            }
+        }
+        Ok(info)
+    }
+
+    /// Whether the server advertised `feature`
+    pub fn supports(&self, feature: &ServerFeature) -> bool {
+        self.features.contains(feature)
+    }
+
+    pub fn supports_vector_search(&self) -> bool {
+        self.supports(&ServerFeature::VectorSearch)
+    }
+
+    pub fn supports_analytics_streaming(&self) -> bool {
+        self.supports(&ServerFeature::AnalyticsStreaming)
+    }
+
+    pub fn supports_copy_binary(&self) -> bool {
+        self.supports(&ServerFeature::CopyBinary)
+    }
+
+    pub fn supports_compression(&self) -> bool {
+        self.supports(&ServerFeature::Compression)
+    }
+
+    /// Value of a negotiated option, such as `compression`
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Whether the server is at least `major.minor.patch`; `false` when its
+    /// version is unknown or not numeric
+    pub fn version_at_least(&self, major: u64, minor: u64, patch: u64) -> bool {
+        let Some(version) = &self.version else {
+            return false;
+        };
+        // Pre-release and build suffixes such as `-rc1` do not count
+        let release = version.split(['-', '+']).next().unwrap_or_default();
+        let mut parts = release.split('.').map(str::parse::<u64>);
+        let mut next = || parts.next().unwrap_or(Ok(0));
+        match (next(), next(), next()) {
+            (Ok(a), Ok(b), Ok(c)) => (a, b, c) >= (major, minor, patch),
+            _ => false,
+        }
+    }
+}
@@ -3,6 +3,8 @@
 //! Comprehensive type definitions for AuroraDB's advanced features including
 //! vector search, analytics, streaming, and AI/ML capabilities.
 
+use crate::replication::Lsn;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -49,10 +51,13 @@ pub enum AuroraValue {
     /// Time (microseconds since midnight)
     Time(i64),
 
-    /// Timestamp (microseconds since Unix epoch)
+    /// Timestamp without time zone: a wall-clock reading, as microseconds
+    /// since the Unix epoch read as if in UTC
     Timestamp(i64),
 
-    /// Timestamp with timezone
+    /// Timestamp with time zone: an instant as microseconds since the Unix
+    /// epoch (UTC), plus the zone it was rendered in. Values compare and
+    /// fingerprint by the instant alone.
     TimestampTz(i64, String),
 
     /// JSON value
@@ -71,6 +76,99 @@ pub enum AuroraValue {
     Map(HashMap<String, AuroraValue>),
 }
 
+impl AuroraValue {
+    /// Decimal from its text form, e.g. `"-12.50"`. The text is kept as
+    /// given so no precision is lost on the way to a DECIMAL column.
+    pub fn decimal(text: impl Into<String>) -> crate::error::Result<Self> {
+        let text = text.into();
+        let digits = text.trim().strip_prefix(['-', '+']).unwrap_or(text.trim());
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction) {
+            return Err(crate::error::AuroraError::Serialization(format!("invalid decimal '{}'", text)));
+        }
+        Ok(AuroraValue::Decimal(text.trim().to_string()))
+    }
+
+    /// Text form of a decimal value
+    pub fn as_decimal(&self) -> Option<&str> {
+        match self {
+            AuroraValue::Decimal(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// `TIMESTAMP WITHOUT TIME ZONE` value
+    pub fn timestamp(local: chrono::NaiveDateTime) -> Self {
+        AuroraValue::Timestamp(local.and_utc().timestamp_micros())
+    }
+
+    /// `TIMESTAMP WITH TIME ZONE` value for `instant`, labelled with the zone
+    /// it is shown in
+    pub fn timestamptz(instant: chrono::DateTime<chrono::Utc>, time_zone: impl Into<String>) -> Self {
+        AuroraValue::TimestampTz(instant.timestamp_micros(), time_zone.into())
+    }
+
+    /// Wall-clock reading of a timestamp without time zone
+    pub fn as_naive_timestamp(&self) -> Option<chrono::NaiveDateTime> {
+        match self {
+            AuroraValue::Timestamp(micros) => chrono::DateTime::from_timestamp_micros(*micros).map(|t| t.naive_utc()),
+            _ => None,
+        }
+    }
+
+    /// Instant of a timestamp with time zone
+    pub fn as_instant(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            AuroraValue::TimestampTz(micros, _) => chrono::DateTime::from_timestamp_micros(*micros),
+            _ => None,
+        }
+    }
+
+    /// Elements of an array value
+    pub fn as_array(&self) -> Option<&[AuroraValue]> {
+        match self {
+            AuroraValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Value of a `column_type` column from its JSON form in a result row.
+    /// Arrays map element by element, NULL elements included; date/time
+    /// columns arrive rendered and stay text.
+    pub fn from_json(value: &serde_json::Value, column_type: &AuroraType) -> crate::error::Result<Self> {
+        use serde_json::Value as Json;
+        let mismatch = || crate::error::AuroraError::Serialization(format!("cannot read {} as {:?}", value, column_type));
+        let integer = |n: &serde_json::Number| n.as_i64().ok_or_else(mismatch);
+        Ok(match (column_type, value) {
+            (_, Json::Null) => AuroraValue::Null,
+            (AuroraType::Bool, Json::Bool(b)) => AuroraValue::Bool(*b),
+            (AuroraType::TinyInt, Json::Number(n)) => AuroraValue::TinyInt(i8::try_from(integer(n)?).map_err(|_| mismatch())?),
+            (AuroraType::SmallInt, Json::Number(n)) => AuroraValue::SmallInt(i16::try_from(integer(n)?).map_err(|_| mismatch())?),
+            (AuroraType::Int, Json::Number(n)) => AuroraValue::Int(i32::try_from(integer(n)?).map_err(|_| mismatch())?),
+            (AuroraType::BigInt, Json::Number(n)) => AuroraValue::BigInt(integer(n)?),
+            (AuroraType::Float, Json::Number(n)) => AuroraValue::Float(n.as_f64().ok_or_else(mismatch)? as f32),
+            (AuroraType::Double, Json::Number(n)) => AuroraValue::Double(n.as_f64().ok_or_else(mismatch)?),
+            (AuroraType::Decimal(..), Json::Number(n)) => Self::decimal(n.to_string())?,
+            (AuroraType::Decimal(..), Json::String(s)) => Self::decimal(s.as_str())?,
+            (AuroraType::Char(_) | AuroraType::Varchar(_) | AuroraType::Text, Json::String(s)) => AuroraValue::Text(s.clone()),
+            (AuroraType::Date | AuroraType::Time | AuroraType::Timestamp | AuroraType::TimestampTz, Json::String(s)) => {
+                AuroraValue::Text(s.clone())
+            }
+            (AuroraType::Uuid, Json::String(s)) => AuroraValue::Uuid(s.clone()),
+            (AuroraType::Json | AuroraType::Jsonb, value) => AuroraValue::Json(value.clone()),
+            (AuroraType::Vector(_), Json::Array(items)) => AuroraValue::Vector(items.iter()
+                .map(|item| item.as_f64().map(|f| f as f32))
+                .collect::<Option<_>>()
+                .ok_or_else(mismatch)?),
+            (AuroraType::Array(element_type), Json::Array(items)) => AuroraValue::Array(items.iter()
+                .map(|item| Self::from_json(item, element_type))
+                .collect::<crate::error::Result<_>>()?),
+            _ => return Err(mismatch()),
+        })
+    }
+}
+
 /// AuroraDB column types
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AuroraType {
@@ -111,6 +209,7 @@ pub enum AuroraType {
 
     /// Advanced types
     Json,
+    Jsonb,
     Vector(u32),     // dimensions
     Uuid,
 
@@ -119,6 +218,127 @@ pub enum AuroraType {
     Map(Box<AuroraType>, Box<AuroraType>),
 }
 
+impl AuroraType {
+    /// Type named `name` as the server spells it in catalogs, modifiers
+    /// included: `varchar(255)`, `decimal(12,2)`, `vector(768)`, `text[]`,
+    /// `map<text,bigint>`. Names are case-insensitive; types that carry a
+    /// modifier must state it.
+    pub fn from_sql_name(name: &str) -> crate::error::Result<Self> {
+        let invalid = |reason: &str| crate::error::AuroraError::Serialization(format!("invalid type '{}': {}", name, reason));
+        let lower = name.trim().to_ascii_lowercase();
+
+        if let Some(element) = lower.strip_suffix("[]") {
+            return Ok(AuroraType::Array(Box::new(Self::from_sql_name(element)?)));
+        }
+        if let Some(entries) = lower.strip_prefix("map<").and_then(|rest| rest.strip_suffix('>')) {
+            // Split at the comma outside any modifier list or nested map
+            let mut depth = 0i32;
+            let split = entries.char_indices().find(|&(_, c)| {
+                match c {
+                    '(' | '<' => depth += 1,
+                    ')' | '>' => depth -= 1,
+                    _ => {}
+                }
+                c == ',' && depth == 0
+            });
+            let (key, value) = match split {
+                Some((at, _)) => (&entries[..at], &entries[at + 1..]),
+                None => return Err(invalid("map needs key and value types")),
+            };
+            return Ok(AuroraType::Map(Box::new(Self::from_sql_name(key)?), Box::new(Self::from_sql_name(value)?)));
+        }
+
+        let (base, modifiers) = match lower.split_once('(') {
+            Some((base, rest)) => {
+                let modifiers = rest.strip_suffix(')').ok_or_else(|| invalid("unclosed modifier list"))?;
+                let modifiers = modifiers.split(',')
+                    .map(|modifier| modifier.trim().parse::<u32>().map_err(|_| invalid("modifiers must be integers")))
+                    .collect::<crate::error::Result<Vec<u32>>>()?;
+                (base.trim(), modifiers)
+            }
+            None => (lower.as_str(), Vec::new()),
+        };
+        let length = || match modifiers.as_slice() {
+            [length] if *length > 0 => Ok(*length),
+            _ => Err(invalid("expected one positive length")),
+        };
+        let simple = |ty: AuroraType| match modifiers.is_empty() {
+            true => Ok(ty),
+            false => Err(invalid("type takes no modifiers")),
+        };
+
+        match base {
+            "null" => simple(AuroraType::Null),
+            "bool" | "boolean" => simple(AuroraType::Bool),
+            "tinyint" | "int1" => simple(AuroraType::TinyInt),
+            "smallint" | "int2" => simple(AuroraType::SmallInt),
+            "int" | "integer" | "int4" => simple(AuroraType::Int),
+            "bigint" | "int8" => simple(AuroraType::BigInt),
+            "float" | "real" | "float4" => simple(AuroraType::Float),
+            "double" | "double precision" | "float8" => simple(AuroraType::Double),
+            "decimal" | "numeric" => {
+                let (precision, scale) = match modifiers.as_slice() {
+                    [precision] => (*precision, 0),
+                    [precision, scale] => (*precision, *scale),
+                    _ => return Err(invalid("expected precision and scale")),
+                };
+                if precision == 0 || precision > u8::MAX as u32 || scale > precision {
+                    return Err(invalid("scale must not exceed a precision of 1 to 255"));
+                }
+                Ok(AuroraType::Decimal(precision as u8, scale as u8))
+            }
+            "char" | "character" => Ok(AuroraType::Char(length()?)),
+            "varchar" | "character varying" => Ok(AuroraType::Varchar(length()?)),
+            "text" => simple(AuroraType::Text),
+            "binary" => Ok(AuroraType::Binary(length()?)),
+            "varbinary" => Ok(AuroraType::Varbinary(length()?)),
+            "blob" | "bytea" => simple(AuroraType::Blob),
+            "date" => simple(AuroraType::Date),
+            "time" => simple(AuroraType::Time),
+            "timestamp" => simple(AuroraType::Timestamp),
+            "timestamptz" | "timestamp with time zone" => simple(AuroraType::TimestampTz),
+            "json" => simple(AuroraType::Json),
+            "jsonb" => simple(AuroraType::Jsonb),
+            "vector" => Ok(AuroraType::Vector(length()?)),
+            "uuid" => simple(AuroraType::Uuid),
+            _ => Err(invalid("unknown type")),
+        }
+    }
+}
+
+impl std::fmt::Display for AuroraType {
+    /// The type's name as [`from_sql_name`](Self::from_sql_name) reads it
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuroraType::Null => f.write_str("null"),
+            AuroraType::Bool => f.write_str("boolean"),
+            AuroraType::TinyInt => f.write_str("tinyint"),
+            AuroraType::SmallInt => f.write_str("smallint"),
+            AuroraType::Int => f.write_str("int"),
+            AuroraType::BigInt => f.write_str("bigint"),
+            AuroraType::Float => f.write_str("float"),
+            AuroraType::Double => f.write_str("double"),
+            AuroraType::Decimal(precision, scale) => write!(f, "decimal({},{})", precision, scale),
+            AuroraType::Char(length) => write!(f, "char({})", length),
+            AuroraType::Varchar(length) => write!(f, "varchar({})", length),
+            AuroraType::Text => f.write_str("text"),
+            AuroraType::Binary(length) => write!(f, "binary({})", length),
+            AuroraType::Varbinary(length) => write!(f, "varbinary({})", length),
+            AuroraType::Blob => f.write_str("blob"),
+            AuroraType::Date => f.write_str("date"),
+            AuroraType::Time => f.write_str("time"),
+            AuroraType::Timestamp => f.write_str("timestamp"),
+            AuroraType::TimestampTz => f.write_str("timestamptz"),
+            AuroraType::Json => f.write_str("json"),
+            AuroraType::Jsonb => f.write_str("jsonb"),
+            AuroraType::Vector(dimension) => write!(f, "vector({})", dimension),
+            AuroraType::Uuid => f.write_str("uuid"),
+            AuroraType::Array(element) => write!(f, "{}[]", element),
+            AuroraType::Map(key, value) => write!(f, "map<{},{}>", key, value),
+        }
+    }
+}
+
 /// Database column definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuroraColumn {
@@ -187,6 +407,10 @@ pub struct ExecuteResult {
 
     /// Statement ID for tracing
     pub statement_id: String,
+
+    /// Log position of the write's commit; a read that must see this write
+    /// can require a replica to have replayed past it
+    pub commit_lsn: Option<Lsn>,
 }
 
 /// Vector search request
@@ -439,7 +663,7 @@ pub enum WindowSize {
 }
 
 /// Database schema information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SchemaInfo {
     /// Database name
     pub database_name: String,
@@ -454,8 +678,25 @@ pub struct SchemaInfo {
     pub constraints: Vec<ConstraintInfo>,
 }
 
+impl SchemaInfo {
+    /// Table named `name`
+    pub fn table(&self, name: &str) -> Option<&TableInfo> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    /// Indexes on table `table_name`
+    pub fn indexes_on<'a>(&'a self, table_name: &'a str) -> impl Iterator<Item = &'a IndexInfo> + 'a {
+        self.indexes.iter().filter(move |index| index.table_name == table_name)
+    }
+
+    /// Constraints on table `table_name`
+    pub fn constraints_on<'a>(&'a self, table_name: &'a str) -> impl Iterator<Item = &'a ConstraintInfo> + 'a {
+        self.constraints.iter().filter(move |constraint| constraint.table_name == table_name)
+    }
+}
+
 /// Table information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TableInfo {
     /// Table name
     pub name: String,
@@ -463,8 +704,8 @@ pub struct TableInfo {
     /// Table type
     pub table_type: TableType,
 
-    /// Columns
-    pub columns: Vec<AuroraColumn>,
+    /// Columns in table order
+    pub columns: Vec<ColumnInfo>,
 
     /// Primary key columns
     pub primary_key: Vec<String>,
@@ -476,8 +717,51 @@ pub struct TableInfo {
     pub size_bytes: Option<u64>,
 }
 
+impl TableInfo {
+    /// Column named `name`
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+/// Column of an introspected table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    /// Column name
+    pub name: String,
+
+    /// Column type, with its length, precision or dimension
+    pub column_type: AuroraType,
+
+    /// Nullable
+    pub nullable: bool,
+
+    /// Default, if the column has one
+    pub default: Option<ColumnDefault>,
+
+    /// Part of the primary key
+    pub primary_key: bool,
+
+    /// Values generated by the server
+    pub auto_increment: bool,
+
+    /// Column comment
+    pub comment: Option<String>,
+}
+
+/// Default of a column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColumnDefault {
+    /// A constant of the column's type
+    Value(AuroraValue),
+
+    /// An expression evaluated on insert, such as `now()`, as the server
+    /// renders it
+    Expression(String),
+}
+
 /// Table types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TableType {
     /// Regular table
     Table,
@@ -493,7 +777,7 @@ pub enum TableType {
 }
 
 /// Index information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexInfo {
     /// Index name
     pub name: String,
@@ -515,7 +799,7 @@ pub struct IndexInfo {
 }
 
 /// Index types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndexType {
     /// B-tree index
     BTree,
@@ -531,10 +815,13 @@ pub enum IndexType {
 
     /// Spatial index
     Spatial,
+
+    /// Inverted index over array elements
+    Gin,
 }
 
 /// Constraint information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConstraintInfo {
     /// Constraint name
     pub name: String,
@@ -553,10 +840,13 @@ pub struct ConstraintInfo {
 
     /// Referenced columns (for foreign keys)
     pub referenced_columns: Option<Vec<String>>,
+
+    /// Condition rows must satisfy (for check constraints)
+    pub check_expression: Option<String>,
 }
 
 /// Constraint types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConstraintType {
     /// Primary key
     PrimaryKey,
@@ -632,6 +922,8 @@ pub struct ExecuteRequest {
     pub sql: String,
     pub params: Vec<AuroraValue>,
     pub timeout: Option<Duration>,
+    /// Retries carrying the same key are applied at most once by the server
+    pub idempotency_key: Option<String>,
 }
 
 // UNIQUENESS Validation:
@@ -0,0 +1,122 @@
+//! Batch Vector Upserts
+//!
+//! A batch of vectors for one collection is sent as a single
+//! `VectorUpsertBatch` message. The server stores the whole batch and then
+//! links it into the collection's index once, instead of relinking the graph
+//! for every vector. An id that already exists is updated in place.
+//!
+//! Every vector in a batch must have the same dimension; the driver rejects a
+//! batch that mixes dimensions before anything is sent, and the server rejects
+//! one whose dimension differs from the collection's before storing any of it.
+//! Once a batch is accepted, each item succeeds or fails on its own.
+
+use crate::batch::{RowError, RowFailure};
+use crate::error::{AuroraError, Result};
+use crate::types::AuroraValue;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One vector to insert or update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorItem {
+    pub id: u64,
+    pub vector: Vec<f32>,
+    pub metadata: HashMap<String, AuroraValue>,
+}
+
+impl VectorItem {
+    pub fn new(id: u64, vector: Vec<f32>) -> Self {
+        Self {
+            id,
+            vector,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Attach a metadata value, replacing any previous value for `key`
+    pub fn with_metadata(mut self, key: &str, value: AuroraValue) -> Self {
+        self.metadata.insert(key.to_string(), value);
+        self
+    }
+}
+
+/// `VectorUpsertBatch` message body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorUpsertBatchRequest {
+    pub collection: String,
+
+    /// Dimension shared by every item
+    pub dimension: usize,
+
+    pub items: Vec<VectorItem>,
+}
+
+impl VectorUpsertBatchRequest {
+    /// Build a request, checking that every item has the same, non-zero
+    /// dimension
+    pub fn new(collection: &str, items: Vec<VectorItem>) -> Result<Self> {
+        let dimension = items.first().map_or(0, |item| item.vector.len());
+        if let Some(position) = items.iter().position(|item| item.vector.is_empty() || item.vector.len() != dimension) {
+            return Err(AuroraError::Query(format!(
+                "vector batch item {} has dimension {}, expected {}",
+                position,
+                items[position].vector.len(),
+                dimension
+            )));
+        }
+
+        Ok(Self {
+            collection: collection.to_string(),
+            dimension,
+            items,
+        })
+    }
+}
+
+/// Server reply to a `VectorUpsertBatch` message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorUpsertBatchResponse {
+    /// Why the whole batch was refused, e.g. a dimension that does not match
+    /// the collection; nothing was stored
+    pub rejected: Option<String>,
+
+    /// Positions of items that replaced an existing vector
+    pub updated: Vec<usize>,
+
+    /// Items the server could not store, by position in the batch
+    pub failures: Vec<RowFailure>,
+}
+
+/// What happened to one item of an accepted batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorUpsertOutcome {
+    Inserted,
+    Updated,
+}
+
+/// Outcome of one item of a batch, in batch order
+pub type VectorUpsertResult = std::result::Result<VectorUpsertOutcome, RowError>;
+
+/// Per-item results of a batch of `len` items from the server's reply
+pub fn item_results(len: usize, response: VectorUpsertBatchResponse) -> Result<Vec<VectorUpsertResult>> {
+    if let Some(reason) = response.rejected {
+        return Err(AuroraError::Query(format!("vector batch rejected: {}", reason)));
+    }
+
+    let mut results: Vec<VectorUpsertResult> = vec![Ok(VectorUpsertOutcome::Inserted); len];
+    for position in response.updated {
+        if position >= len {
+            return Err(AuroraError::Protocol(format!("update reported for item {} of a {} item batch", position, len)));
+        }
+        results[position] = Ok(VectorUpsertOutcome::Updated);
+    }
+    for failure in response.failures {
+        if failure.row >= len {
+            return Err(AuroraError::Protocol(format!("failure reported for item {} of a {} item batch", failure.row, len)));
+        }
+        let row = failure.row;
+        results[row] = Err(failure.into());
+    }
+    Ok(results)
+}
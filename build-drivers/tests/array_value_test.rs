@@ -0,0 +1,34 @@
+//! Array Value Tests
+//!
+//! Array columns reach the driver as JSON arrays in result rows and map onto
+//! `AuroraValue::Array` element by element.
+
+use aurora_drivers::{AuroraType, AuroraValue};
+use serde_json::json;
+
+#[test]
+fn test_arrays_map_element_by_element() {
+    let tags = AuroraValue::from_json(&json!(["rust", null, "db"]), &AuroraType::Array(Box::new(AuroraType::Text))).unwrap();
+    assert_eq!(tags.as_array().unwrap(), [
+        AuroraValue::Text("rust".to_string()),
+        AuroraValue::Null,
+        AuroraValue::Text("db".to_string()),
+    ]);
+
+    let scores = AuroraValue::from_json(&json!([3, -1]), &AuroraType::Array(Box::new(AuroraType::Int))).unwrap();
+    assert_eq!(scores, AuroraValue::Array(vec![AuroraValue::Int(3), AuroraValue::Int(-1)]));
+
+    let empty = AuroraValue::from_json(&json!([]), &AuroraType::Array(Box::new(AuroraType::Int))).unwrap();
+    assert_eq!(empty.as_array(), Some(&[][..]));
+    assert_eq!(AuroraValue::from_json(&json!(null), &AuroraType::Array(Box::new(AuroraType::Text))).unwrap(), AuroraValue::Null);
+    assert!(AuroraValue::Text("rust".to_string()).as_array().is_none());
+}
+
+#[test]
+fn test_array_elements_must_match_the_element_type() {
+    let int_array = AuroraType::Array(Box::new(AuroraType::Int));
+    assert!(AuroraValue::from_json(&json!(["rust"]), &int_array).is_err());
+    assert!(AuroraValue::from_json(&json!([1, 2.5]), &int_array).is_err());
+    assert!(AuroraValue::from_json(&json!("{1,2}"), &int_array).is_err());
+    assert!(AuroraValue::from_json(&json!([i64::MAX]), &int_array).is_err());
+}
@@ -0,0 +1,253 @@
+//! Atomic Batch Tests
+//!
+//! Sends mixed read/write batches to an in-process server that runs each
+//! batch against a working copy of its accounts table and keeps the copy
+//! only if every statement succeeds, and checks a batch commits as a whole
+//! in one round trip, or is rolled back as a whole when a statement fails.
+
+use aurora_drivers::atomic_batch::{AtomicBatchRequest, AtomicBatchResponse, StatementFailure};
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::FRAME_HEADER_LEN;
+use aurora_drivers::types::{AuroraRow, AuroraValue, ExecuteResult, QueryResult};
+use aurora_drivers::{AuroraConnection, AuroraError, BatchStatement, StatementResult};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const ATOMIC_BATCH: u8 = 21;
+
+const OPEN: &str = "INSERT INTO accounts (name, balance) VALUES ($1, $2)";
+const MOVE: &str = "UPDATE accounts SET balance = balance + $2 WHERE name = $1";
+const BALANCE: &str = "SELECT balance FROM accounts WHERE name = $1";
+
+/// What the server has seen and committed
+#[derive(Default)]
+struct Server {
+    /// Committed balance of each account
+    accounts: BTreeMap<String, i64>,
+    /// Requests received
+    requests: usize,
+    /// Statements run, in order, committed or not
+    executed: Vec<String>,
+}
+
+type Shared = Arc<Mutex<Server>>;
+
+fn open(name: &str, balance: i64) -> BatchStatement {
+    BatchStatement::with_params(OPEN, vec![AuroraValue::Text(name.to_string()), AuroraValue::BigInt(balance)])
+}
+
+fn transfer(name: &str, amount: i64) -> BatchStatement {
+    BatchStatement::with_params(MOVE, vec![AuroraValue::Text(name.to_string()), AuroraValue::BigInt(amount)])
+}
+
+fn balance(name: &str) -> BatchStatement {
+    BatchStatement::with_params(BALANCE, vec![AuroraValue::Text(name.to_string())])
+}
+
+fn written(rows_affected: u64) -> StatementResult {
+    StatementResult::Execute(ExecuteResult {
+        rows_affected,
+        last_insert_id: None,
+        execution_time_ms: 0.0,
+        statement_id: String::new(),
+        commit_lsn: None,
+    })
+}
+
+/// Run one statement against the working copy
+fn execute(accounts: &mut BTreeMap<String, i64>, statement: &BatchStatement) -> Result<StatementResult, (&'static str, String)> {
+    let name = match statement.params.first() {
+        Some(AuroraValue::Text(name)) => name.clone(),
+        other => panic!("unexpected account name {:?}", other),
+    };
+    let amount = match statement.params.get(1) {
+        Some(AuroraValue::BigInt(amount)) => *amount,
+        _ => 0,
+    };
+    match statement.sql.as_str() {
+        OPEN if accounts.contains_key(&name) => {
+            Err(("23505", format!("duplicate key value violates unique constraint \"accounts_pkey\": {}", name)))
+        }
+        OPEN => {
+            accounts.insert(name, amount);
+            Ok(written(1))
+        }
+        MOVE => match accounts.get_mut(&name) {
+            Some(balance) if *balance + amount < 0 => {
+                Err(("23514", "new row violates check constraint \"balance_not_negative\"".to_string()))
+            }
+            Some(balance) => {
+                *balance += amount;
+                Ok(written(1))
+            }
+            None => Ok(written(0)),
+        },
+        BALANCE => {
+            let rows: Vec<AuroraRow> = accounts.get(&name)
+                .map(|balance| AuroraRow { values: vec![AuroraValue::BigInt(*balance)], columns: Some(vec!["balance".to_string()]) })
+                .into_iter()
+                .collect();
+            Ok(StatementResult::Query(QueryResult {
+                row_count: rows.len(),
+                rows,
+                columns: Vec::new(),
+                execution_time_ms: 0.0,
+                query_id: String::new(),
+            }))
+        }
+        other => panic!("unexpected statement {}", other),
+    }
+}
+
+/// Run a batch as one transaction: commit the working copy only if every
+/// statement succeeded
+fn run_batch(server: &Shared, request: &AtomicBatchRequest) -> AtomicBatchResponse {
+    let mut server = server.lock().unwrap();
+    server.requests += 1;
+    let mut working = server.accounts.clone();
+    let mut results = Vec::new();
+    for (position, statement) in request.statements.iter().enumerate() {
+        server.executed.push(statement.sql.clone());
+        match execute(&mut working, statement) {
+            Ok(result) => results.push(result),
+            Err((sqlstate, message)) => {
+                let failure = StatementFailure { statement: position, sqlstate: sqlstate.to_string(), message };
+                return AtomicBatchResponse { results: Vec::new(), failure: Some(failure) };
+            }
+        }
+    }
+    server.accounts = working;
+    AtomicBatchResponse { results, failure: None }
+}
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + 4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream, server: Shared) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+        assert_eq!(header[4], ATOMIC_BATCH, "unexpected message type");
+
+        let request: AtomicBatchRequest = bincode::deserialize(&body[..len]).unwrap();
+        let response = run_batch(&server, &request);
+        socket.write_all(&frame(&bincode::serialize(&response).unwrap())).await?;
+    }
+}
+
+async fn connect(server: Shared) -> AuroraConnection {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, server.clone()));
+        }
+    });
+
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+fn balances(results: &[StatementResult]) -> Vec<Vec<AuroraValue>> {
+    results.iter()
+        .filter(|result| matches!(result, StatementResult::Query(_)))
+        .map(|result| result.rows().iter().flat_map(|row| row.values.clone()).collect())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_batch_commits_every_statement_in_one_round_trip() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone()).await;
+
+    let results = conn.atomic_batch(vec![
+        open("alice", 100),
+        open("bob", 0),
+        transfer("alice", -30),
+        transfer("bob", 30),
+        balance("alice"),
+        balance("bob"),
+        balance("carol"),
+    ]).await.unwrap();
+
+    assert_eq!(results.len(), 7);
+    assert_eq!(results.iter().map(StatementResult::rows_affected).collect::<Vec<_>>(), vec![1, 1, 1, 1, 0, 0, 0]);
+    assert_eq!(balances(&results), vec![
+        vec![AuroraValue::BigInt(70)],
+        vec![AuroraValue::BigInt(30)],
+        vec![],
+    ]);
+
+    {
+        let server = server.lock().unwrap();
+        assert_eq!(server.requests, 1);
+        assert_eq!(server.accounts, BTreeMap::from([("alice".to_string(), 70), ("bob".to_string(), 30)]));
+    }
+
+    // An empty batch has nothing to send
+    assert!(conn.atomic_batch(Vec::<BatchStatement>::new()).await.unwrap().is_empty());
+    assert_eq!(server.lock().unwrap().requests, 1);
+}
+
+#[tokio::test]
+async fn test_failing_statement_rolls_back_the_batch() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone()).await;
+    conn.atomic_batch(vec![open("alice", 100)]).await.unwrap();
+    server.lock().unwrap().executed.clear();
+
+    // The third statement opens an account that already exists
+    let error = conn.atomic_batch(vec![
+        transfer("alice", -40),
+        open("carol", 40),
+        open("alice", 1),
+        balance("carol"),
+    ]).await.unwrap_err();
+    match error {
+        AuroraError::Transaction(message) => {
+            assert!(message.contains("statement 2 failed with SQLSTATE 23505"), "{}", message);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // The first two were rolled back and the fourth never ran
+    {
+        let server = server.lock().unwrap();
+        assert_eq!(server.accounts, BTreeMap::from([("alice".to_string(), 100)]));
+        assert_eq!(server.executed, vec![MOVE, OPEN, OPEN]);
+    }
+
+    // The connection is fine for the next batch, which sees none of it
+    let results = conn.atomic_batch(vec![balance("alice"), balance("carol")]).await.unwrap();
+    assert_eq!(balances(&results), vec![vec![AuroraValue::BigInt(100)], vec![]]);
+
+    // A statement failing on a value rather than a key rolls back the same way
+    let error = conn.atomic_batch(vec![open("dave", 5), transfer("alice", -101)]).await.unwrap_err();
+    assert!(error.to_string().contains("statement 1 failed with SQLSTATE 23514"), "{}", error);
+    assert!(!server.lock().unwrap().accounts.contains_key("dave"));
+}
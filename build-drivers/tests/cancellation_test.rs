@@ -0,0 +1,163 @@
+//! Query Cancellation Tests
+//!
+//! Runs queries against an in-process server that speaks the framed
+//! protocol and echoes each query's SQL back as its `query_id`, so a
+//! connection that hands one caller another caller's response shows up as
+//! a mismatched id.
+
+use aurora_drivers::config::{AuroraConfig, PoolConfig};
+use aurora_drivers::{AuroraConnectionPool, AuroraProtocol, QueryRequest, QueryResult};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// SQL the server answers only after a delay
+const SLOW: &str = "SELECT pg_sleep(0.2)";
+
+/// SQL the server answers with half a frame, then stalls
+const STALLED: &str = "SELECT stalled";
+
+fn response_frame(sql: &str) -> Vec<u8> {
+    let result = QueryResult {
+        rows: Vec::new(),
+        columns: Vec::new(),
+        row_count: 0,
+        execution_time_ms: 0.0,
+        query_id: sql.to_string(),
+    };
+    let data = bincode::serialize(&result).unwrap();
+
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&data);
+    frame.extend_from_slice(&crc32fast::hash(&data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; 13];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+        let request: QueryRequest = bincode::deserialize(&body[..len]).unwrap();
+
+        let frame = response_frame(&request.sql);
+        match request.sql.as_str() {
+            SLOW => {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                socket.write_all(&frame).await?;
+            }
+            STALLED => {
+                socket.write_all(&frame[..frame.len() / 2]).await?;
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            _ => socket.write_all(&frame).await?,
+        }
+    }
+}
+
+async fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket));
+        }
+    });
+
+    port
+}
+
+async fn pool(port: u16) -> AuroraConnectionPool {
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        pool: PoolConfig {
+            max_connections: 1,
+            min_connections: 1,
+            max_idle_time: Duration::from_secs(300),
+            max_lifetime: Duration::from_secs(3600),
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+            adaptive: None,
+            circuit_breaker: None,
+            priority_aging: Duration::from_secs(1),
+            leak_detection: None,
+        },
+        ..AuroraConfig::default()
+    };
+    AuroraConnectionPool::new(config).await.unwrap()
+}
+
+/// Wait for background returns to settle into the given pool shape
+async fn wait_for_pool(pool: &AuroraConnectionPool, total: usize, available: usize) {
+    for _ in 0..100 {
+        let stats = pool.stats().await;
+        if stats.total_connections == total && stats.available_connections == available {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("pool did not settle: {:?}", pool.stats().await);
+}
+
+async fn run_query(pool: &AuroraConnectionPool, protocol: &AuroraProtocol, sql: &str) -> QueryResult {
+    let mut conn = pool.acquire().await.unwrap();
+    protocol.execute_query(&mut conn, sql).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_dropped_query_is_drained_before_reuse() {
+    let port = start_server().await;
+    let pool = pool(port).await;
+    let protocol = AuroraProtocol::new();
+
+    // The caller goes away while the server is still working on the query
+    let dropped = tokio::time::timeout(Duration::from_millis(50), run_query(&pool, &protocol, SLOW)).await;
+    assert!(dropped.is_err());
+
+    // The abandoned response is read off the same connection, which is pooled again
+    wait_for_pool(&pool, 1, 1).await;
+
+    for sql in ["SELECT 1", "SELECT 2"] {
+        let result = run_query(&pool, &protocol, sql).await;
+        assert_eq!(result.query_id, sql);
+    }
+    wait_for_pool(&pool, 1, 1).await;
+
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_query_dropped_mid_frame_discards_connection() {
+    let port = start_server().await;
+    let pool = pool(port).await;
+    let protocol = AuroraProtocol::new();
+
+    // Half of the response has been consumed when the caller goes away
+    let dropped = tokio::time::timeout(Duration::from_millis(100), run_query(&pool, &protocol, STALLED)).await;
+    assert!(dropped.is_err());
+
+    // The connection cannot be resynchronized, so it is closed rather than pooled
+    wait_for_pool(&pool, 0, 0).await;
+
+    let result = run_query(&pool, &protocol, "SELECT 1").await;
+    assert_eq!(result.query_id, "SELECT 1");
+    wait_for_pool(&pool, 1, 1).await;
+
+    pool.close().await.unwrap();
+}
@@ -0,0 +1,178 @@
+//! Circuit Breaker Tests
+//!
+//! Takes the server down by closing its port, so connection attempts are
+//! refused, and brings it back on the same port to let a probe close the
+//! breaker.
+
+use aurora_drivers::config::{AuroraConfig, CircuitBreakerConfig, PoolConfig};
+use aurora_drivers::circuit_breaker::Admission;
+use aurora_drivers::{AuroraConnectionPool, AuroraError, CircuitBreaker, CircuitState};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A port nothing listens on
+async fn closed_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+/// Bring the server up on `port`, answering authentication after `delay`
+async fn start_server(port: u16, delay: Duration) {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = socket.read(&mut buf).await {
+                    tokio::time::sleep(delay).await;
+                    if n == 0 || socket.write_all(b"OK").await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn breaker_config(failure_threshold: u32, open_duration: Duration) -> CircuitBreakerConfig {
+    CircuitBreakerConfig { failure_threshold, open_duration }
+}
+
+fn config(port: u16, breaker: CircuitBreakerConfig) -> AuroraConfig {
+    AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        pool: PoolConfig {
+            max_connections: 4,
+            min_connections: 0,
+            max_idle_time: Duration::from_secs(300),
+            max_lifetime: Duration::from_secs(3600),
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+            adaptive: None,
+            circuit_breaker: Some(breaker),
+            priority_aging: Duration::from_secs(1),
+            leak_detection: None,
+        },
+        ..AuroraConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_breaker_opens_fails_fast_and_closes_after_probe() {
+    let port = closed_port().await;
+    let pool = Arc::new(AuroraConnectionPool::new(config(port, breaker_config(3, Duration::from_millis(300)))).await.unwrap());
+
+    // Refused connections count toward the threshold
+    for attempt in 1..=3 {
+        let result = pool.acquire().await;
+        assert!(matches!(result, Err(AuroraError::Io(_)) | Err(AuroraError::Connection(_))));
+        let expected = if attempt < 3 { CircuitState::Closed } else { CircuitState::Open };
+        assert_eq!(pool.circuit_state().await, expected);
+    }
+
+    // Open: every caller fails immediately instead of attempting to connect
+    let started = Instant::now();
+    let callers: Vec<_> = (0..20)
+        .map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.acquire().await.map(drop) })
+        })
+        .collect();
+    for caller in callers {
+        assert!(matches!(caller.await.unwrap(), Err(AuroraError::CircuitOpen(_))));
+    }
+    assert!(started.elapsed() < Duration::from_millis(100), "{:?}", started.elapsed());
+
+    let metrics = pool.metrics().snapshot();
+    assert_eq!((metrics.circuit_state, metrics.circuit_opened, metrics.circuit_rejections), (1, 1, 20));
+    assert_eq!(metrics.connections_created, 0);
+    assert!(pool.metrics().prometheus_export("aurora").contains("aurora_pool_circuit_state 1\n"));
+    assert_eq!(pool.stats().await.circuit_state, CircuitState::Open);
+
+    // The server comes back, but nothing is attempted before the open duration ends
+    start_server(port, Duration::ZERO).await;
+    assert!(matches!(pool.acquire().await, Err(AuroraError::CircuitOpen(_))));
+    assert_eq!(pool.metrics().snapshot().connections_created, 0);
+
+    // After it, a successful probe closes the breaker
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    let probe = pool.acquire().await.unwrap();
+    assert_eq!(pool.circuit_state().await, CircuitState::Closed);
+    assert_eq!(pool.metrics().snapshot().circuit_state, 0);
+    drop(probe);
+
+    for _ in 0..3 {
+        pool.acquire().await.unwrap();
+    }
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_failed_probe_reopens_and_only_one_probe_runs() {
+    let port = closed_port().await;
+    let pool = Arc::new(AuroraConnectionPool::new(config(port, breaker_config(2, Duration::from_millis(200)))).await.unwrap());
+
+    for _ in 0..2 {
+        assert!(pool.acquire().await.is_err());
+    }
+    assert_eq!(pool.circuit_state().await, CircuitState::Open);
+
+    // A probe against a server that is still down opens the breaker again
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(matches!(pool.acquire().await, Err(AuroraError::Io(_)) | Err(AuroraError::Connection(_))));
+    assert_eq!(pool.circuit_state().await, CircuitState::Open);
+    assert!(matches!(pool.acquire().await, Err(AuroraError::CircuitOpen(_))));
+    assert_eq!(pool.metrics().snapshot().circuit_opened, 2);
+
+    // A slow server: while the probe is in flight, everyone else still fails fast
+    start_server(port, Duration::from_millis(200)).await;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let probe = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.acquire().await.map(drop) })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pool.circuit_state().await, CircuitState::HalfOpen);
+    assert_eq!(pool.metrics().snapshot().circuit_state, 2);
+    let started = Instant::now();
+    assert!(matches!(pool.acquire().await, Err(AuroraError::CircuitOpen(_))));
+    assert!(started.elapsed() < Duration::from_millis(50));
+
+    probe.await.unwrap().unwrap();
+    assert_eq!(pool.circuit_state().await, CircuitState::Closed);
+    pool.close().await.unwrap();
+}
+
+#[test]
+fn test_breaker_state_machine() {
+    let mut breaker = CircuitBreaker::new(breaker_config(2, Duration::from_secs(10)));
+    let now = Instant::now();
+
+    // A success resets the run of failures
+    breaker.record_failure(now);
+    breaker.record_success();
+    assert!(!breaker.record_failure(now));
+    assert!(breaker.record_failure(now));
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert_eq!(breaker.rejection(now + Duration::from_secs(4)), Some(Duration::from_secs(6)));
+
+    // Late failures from attempts that started earlier do not extend the open period
+    assert!(!breaker.record_failure(now + Duration::from_secs(5)));
+    assert_eq!(breaker.rejection(now + Duration::from_secs(10)), None);
+
+    // One probe at a time; one whose caller gave up is replaced after the open duration
+    let later = now + Duration::from_secs(10);
+    assert_eq!(breaker.admit(later), Admission::Probe);
+    assert!(matches!(breaker.admit(later), Admission::Rejected(_)));
+    assert_eq!(breaker.admit(later + Duration::from_secs(10)), Admission::Probe);
+
+    breaker.record_success();
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert_eq!(breaker.admit(later), Admission::Allowed);
+    assert_eq!(breaker.times_opened(), 1);
+}
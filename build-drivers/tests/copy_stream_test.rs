@@ -0,0 +1,254 @@
+//! Streaming COPY Tests
+//!
+//! Streams rows into an in-process server that buffers `CopyData` until
+//! `CopyDone` and only then decodes and loads it, and checks rows are
+//! coalesced into a few large messages and that a sink dropped unfinished
+//! has its load rolled back with `CopyFail`.
+
+use aurora_drivers::binary_copy::{BinaryCopyCodec, CopyInResponse};
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::FRAME_HEADER_LEN;
+use aurora_drivers::copy_stream::{CopyStartRequest, CopyStartResponse};
+use aurora_drivers::types::{AuroraRow, AuroraType, AuroraValue};
+use aurora_drivers::{AuroraConnection, AuroraError, AuroraProtocol};
+use futures::SinkExt;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const COPY_START: u8 = 25;
+const COPY_DATA: u8 = 26;
+const COPY_DONE: u8 = 27;
+const COPY_FAIL: u8 = 28;
+
+/// What the server has seen and loaded
+#[derive(Default)]
+struct Server {
+    /// Rows loaded into `items`
+    items: Vec<Vec<AuroraValue>>,
+    /// Message types received, in order
+    received: Vec<u8>,
+    /// Columns and data of the COPY in progress
+    copy: Option<(Vec<String>, Vec<u8>)>,
+    /// Reasons given by `CopyFail`
+    failures: Vec<String>,
+}
+
+type Shared = Arc<Mutex<Server>>;
+
+fn item_type(column: &str) -> Option<AuroraType> {
+    match column {
+        "id" => Some(AuroraType::BigInt),
+        "name" => Some(AuroraType::Text),
+        "embedding" => Some(AuroraType::Vector(3)),
+        _ => None,
+    }
+}
+
+/// Reply to `message_type`, if it is answered
+fn handle(server: &Shared, message_type: u8, data: &[u8]) -> Option<Vec<u8>> {
+    let mut server = server.lock().unwrap();
+    server.received.push(message_type);
+    match message_type {
+        COPY_START => {
+            let request: CopyStartRequest = bincode::deserialize(data).unwrap();
+            let types: Option<Vec<AuroraType>> = request.columns.iter().map(|column| item_type(column)).collect();
+            let response = match (request.table.as_str(), types) {
+                ("items", Some(column_types)) => {
+                    server.copy = Some((request.columns, Vec::new()));
+                    CopyStartResponse { column_types, rejected: None }
+                }
+                (table, _) => CopyStartResponse {
+                    column_types: Vec::new(),
+                    rejected: Some(format!("relation \"{}\" has no such columns", table)),
+                },
+            };
+            Some(bincode::serialize(&response).unwrap())
+        }
+        COPY_DATA => {
+            server.copy.as_mut().expect("CopyData outside a COPY").1.extend_from_slice(data);
+            None
+        }
+        COPY_DONE => {
+            let (columns, stream) = server.copy.take().expect("CopyDone outside a COPY");
+            let typed: Vec<(&str, AuroraType)> = columns.iter().map(|column| (column.as_str(), item_type(column).unwrap())).collect();
+            let rows = BinaryCopyCodec::new(&typed).decode(&stream).unwrap();
+            let response = CopyInResponse { rejected: None, rows: rows.len() as u64 };
+            server.items.extend(rows);
+            Some(bincode::serialize(&response).unwrap())
+        }
+        COPY_FAIL => {
+            server.copy.take().expect("CopyFail outside a COPY");
+            let reason = String::from_utf8(data.to_vec()).unwrap();
+            server.failures.push(reason.clone());
+            Some(bincode::serialize(&CopyInResponse { rejected: Some(reason), rows: 0 }).unwrap())
+        }
+        other => panic!("unexpected message type {}", other),
+    }
+}
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + 4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream, server: Shared) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+
+        if let Some(response) = handle(&server, header[4], &body[..len]) {
+            socket.write_all(&frame(&response)).await?;
+        }
+    }
+}
+
+async fn connect(server: Shared) -> AuroraConnection {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, server.clone()));
+        }
+    });
+
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+fn item(id: i64) -> AuroraRow {
+    AuroraRow {
+        values: vec![
+            AuroraValue::BigInt(id),
+            AuroraValue::Text(format!("item {}", id)),
+            AuroraValue::Vector(vec![id as f32, 0.5, -1.0]),
+        ],
+        columns: None,
+    }
+}
+
+const COLUMNS: &[&str] = &["id", "name", "embedding"];
+
+fn received(server: &Shared) -> Vec<u8> {
+    std::mem::take(&mut server.lock().unwrap().received)
+}
+
+#[tokio::test]
+async fn test_rows_are_coalesced_into_few_messages() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone()).await;
+    let protocol = AuroraProtocol::new();
+
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap().with_flush_rows(100);
+    for id in 0..1050 {
+        sink.feed(item(id)).await.unwrap();
+    }
+    assert_eq!(sink.rows(), 1050);
+    // Nothing is loaded before the COPY is finished
+    assert!(server.lock().unwrap().items.is_empty());
+    assert_eq!(sink.finish().await.unwrap(), 1050);
+
+    // Ten full buffers, then the last 50 rows together with the trailer
+    let mut expected = vec![COPY_START];
+    expected.extend([COPY_DATA; 11]);
+    expected.push(COPY_DONE);
+    assert_eq!(received(&server), expected);
+
+    let items = std::mem::take(&mut server.lock().unwrap().items);
+    assert_eq!(items.len(), 1050);
+    assert_eq!(items[1049], item(1049).values);
+    assert!(!conn.in_copy());
+    assert_eq!(conn.pending_responses(), 0);
+
+    // A byte bound flushes as well, whatever the row count
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap().with_flush_bytes(1024);
+    for id in 0..100 {
+        sink.feed(item(id)).await.unwrap();
+    }
+    assert_eq!(sink.finish().await.unwrap(), 100);
+    let data_messages = received(&server).into_iter().filter(|&message| message == COPY_DATA).count();
+    assert!((2..100).contains(&data_messages), "{} CopyData messages", data_messages);
+}
+
+#[tokio::test]
+async fn test_dropped_sink_rolls_the_load_back() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone()).await;
+    let protocol = AuroraProtocol::new();
+
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap().with_flush_rows(100);
+    for id in 0..250 {
+        sink.feed(item(id)).await.unwrap();
+    }
+    drop(sink);
+    assert!(conn.in_copy());
+    assert!(!conn.is_healthy().await);
+
+    // The pool drains a returned connection, failing the COPY
+    conn.drain().await.unwrap();
+    assert_eq!(received(&server), vec![COPY_START, COPY_DATA, COPY_DATA, COPY_FAIL]);
+    assert!(!conn.in_copy());
+    {
+        let server = server.lock().unwrap();
+        assert!(server.items.is_empty());
+        assert_eq!(server.failures, vec!["COPY into items abandoned by the client".to_string()]);
+    }
+
+    // Otherwise the next request fails it first
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap();
+    sink.feed(item(1)).await.unwrap();
+    drop(sink);
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap();
+    sink.feed(item(2)).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 1);
+
+    assert_eq!(received(&server), vec![COPY_START, COPY_FAIL, COPY_START, COPY_DATA, COPY_DONE]);
+    assert_eq!(server.lock().unwrap().items, vec![item(2).values]);
+}
+
+#[tokio::test]
+async fn test_refused_rows_and_tables() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone()).await;
+    let protocol = AuroraProtocol::new();
+
+    match protocol.copy_in(&mut conn, "items", &["id", "price"]).await.unwrap_err() {
+        AuroraError::Query(message) => assert!(message.contains("COPY into items rejected"), "{}", message),
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert!(!conn.in_copy());
+
+    // A row the codec refuses is left out; the rest still load
+    let mut sink = protocol.copy_in(&mut conn, "items", COLUMNS).await.unwrap();
+    sink.feed(item(1)).await.unwrap();
+    let mut flat = item(2);
+    flat.values[2] = AuroraValue::Vector(vec![1.0, 2.0]);
+    let error = sink.feed(flat).await.unwrap_err();
+    assert!(error.to_string().contains("dimension 2"), "{}", error);
+    sink.feed(item(3)).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 2);
+
+    let ids: Vec<AuroraValue> = server.lock().unwrap().items.iter().map(|row| row[0].clone()).collect();
+    assert_eq!(ids, vec![AuroraValue::BigInt(1), AuroraValue::BigInt(3)]);
+}
@@ -0,0 +1,97 @@
+//! Result Fingerprint Tests
+//!
+//! The known values are shared with the server's fingerprint tests, so a
+//! change to either side's encoding fails both.
+
+use aurora_drivers::{AuroraRow, AuroraValue, QueryResult};
+
+fn result(rows: Vec<Vec<AuroraValue>>) -> QueryResult {
+    QueryResult {
+        row_count: rows.len(),
+        rows: rows.into_iter().map(|values| AuroraRow { values, columns: None }).collect(),
+        columns: vec![],
+        execution_time_ms: 0.0,
+        query_id: "test".to_string(),
+    }
+}
+
+#[test]
+fn test_matches_server_encoding() {
+    let fingerprint = result(vec![vec![
+        AuroraValue::BigInt(1),
+        AuroraValue::Text("alice".to_string()),
+    ]]).fingerprint();
+
+    assert_eq!(fingerprint.unordered, 0xd4352327d3b49363);
+    assert_eq!(fingerprint.ordered, 0x1ec2d82f3876c25e);
+    assert_eq!(fingerprint.row_count, 1);
+
+    // Integer width and integral floats do not change the hash
+    let widened = result(vec![vec![
+        AuroraValue::Double(1.0),
+        AuroraValue::Text("alice".to_string()),
+    ]]).fingerprint();
+    assert_eq!(fingerprint, widened);
+}
+
+#[test]
+fn test_identical_results_fingerprint_equally() {
+    let rows = vec![
+        vec![AuroraValue::Int(1), AuroraValue::Vector(vec![0.5, 0.25])],
+        vec![AuroraValue::Int(2), AuroraValue::Null],
+    ];
+    let a = result(rows.clone());
+    let b = result(rows.clone());
+    assert_eq!(a.fingerprint(), b.fingerprint());
+
+    let reversed = result(rows.into_iter().rev().collect());
+    assert_eq!(a.fingerprint().unordered, reversed.fingerprint().unordered);
+    assert_ne!(a.fingerprint().ordered, reversed.fingerprint().ordered);
+}
+
+#[test]
+fn test_single_changed_value_changes_fingerprint() {
+    let a = result(vec![
+        vec![AuroraValue::Int(1), AuroraValue::Vector(vec![0.5, 0.25])],
+        vec![AuroraValue::Int(2), AuroraValue::Vector(vec![0.75, 0.125])],
+    ]);
+    let b = result(vec![
+        vec![AuroraValue::Int(1), AuroraValue::Vector(vec![0.5, 0.25])],
+        vec![AuroraValue::Int(2), AuroraValue::Vector(vec![0.75, 0.126])],
+    ]);
+
+    assert_ne!(a.fingerprint().ordered, b.fingerprint().ordered);
+    assert_ne!(a.fingerprint().unordered, b.fingerprint().unordered);
+}
+
+#[test]
+fn test_decimals_hash_by_value() {
+    let decimal = AuroraValue::decimal("2.50").unwrap();
+    assert_eq!(decimal.as_decimal(), Some("2.50"));
+    assert!(AuroraValue::decimal("1.2.3").is_err());
+    assert!(AuroraValue::decimal("-").is_err());
+
+    // The server hashes a DECIMAL cell by its value, so trailing zeros and
+    // the equivalent float agree
+    let a = result(vec![vec![decimal]]).fingerprint();
+    let b = result(vec![vec![AuroraValue::decimal("2.5").unwrap()]]).fingerprint();
+    let c = result(vec![vec![AuroraValue::Double(2.5)]]).fingerprint();
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+}
+
+#[test]
+fn test_timestamptz_hashes_by_instant() {
+    let instant = chrono::DateTime::from_timestamp(1_719_835_200, 0).unwrap();
+    let utc = AuroraValue::timestamptz(instant, "UTC");
+    let tokyo = AuroraValue::timestamptz(instant, "Asia/Tokyo");
+    assert_eq!(tokyo.as_instant(), Some(instant));
+    assert_eq!(utc.as_naive_timestamp(), None);
+
+    // The zone only labels how the instant was shown
+    assert_eq!(result(vec![vec![utc]]).fingerprint(), result(vec![vec![tokyo]]).fingerprint());
+
+    let local = AuroraValue::timestamp(instant.naive_utc());
+    assert_eq!(local.as_naive_timestamp(), Some(instant.naive_utc()));
+    assert_eq!(local.as_instant(), None);
+}
@@ -0,0 +1,119 @@
+//! Frame Decoding Tests
+//!
+//! Runs an in-process server that answers each request with a malformed
+//! frame, and checks that the driver fails the request with a protocol
+//! error and gives up on the connection instead of panicking or allocating
+//! whatever length the frame claims.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::{decode_frame, ConnectionState, FrameHeader, FRAME_HEADER_LEN, MAX_MESSAGE_SIZE};
+use aurora_drivers::{AuroraConnection, AuroraError, AuroraProtocol};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn frame(version: u32, length: u32, data: &[u8], checksum: u32) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&version.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&checksum.to_be_bytes());
+    frame
+}
+
+fn valid_frame(data: &[u8]) -> Vec<u8> {
+    frame(1, data.len() as u32, data, crc32fast::hash(data))
+}
+
+/// Start a server that answers the first request with `reply`, then closes
+async fn start_server(reply: Vec<u8>) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut auth = [0u8; 1024];
+        socket.read(&mut auth).await.unwrap();
+        socket.write_all(b"OK").await.unwrap();
+
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await.unwrap();
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await.unwrap();
+
+        socket.write_all(&reply).await.unwrap();
+    });
+
+    port
+}
+
+async fn connect(port: u16) -> AuroraConnection {
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_malformed_responses_fail_the_connection() {
+    let oversized = frame(1, u32::MAX, b"", 0);
+    let mut truncated = valid_frame(b"a response that never arrives in full");
+    truncated.truncate(FRAME_HEADER_LEN + 5);
+
+    let cases = [
+        ("oversized length", oversized),
+        ("unknown version", frame(7, 2, b"{}", crc32fast::hash(b"{}"))),
+        ("bad checksum", frame(1, 2, b"{}", 0xdead_beef)),
+        ("truncated", truncated),
+    ];
+
+    for (name, reply) in cases {
+        let port = start_server(reply).await;
+        let mut conn = connect(port).await;
+        let protocol = AuroraProtocol::new();
+
+        let started = Instant::now();
+        let result = protocol.execute_query(&mut conn, "SELECT 1").await;
+        let expected_kind = if name == "truncated" {
+            matches!(result, Err(AuroraError::Io(_)))
+        } else {
+            matches!(result, Err(AuroraError::Protocol(_)))
+        };
+        assert!(expected_kind, "{}: {:?}", name, result.err());
+        assert!(started.elapsed() < Duration::from_secs(5), "{}", name);
+
+        // The stream position is unknown, so the connection is not reused as is
+        assert_eq!(conn.info().state, ConnectionState::Failed, "{}", name);
+        assert!(conn.is_torn(), "{}", name);
+    }
+}
+
+#[test]
+fn test_decode_frame_regressions() {
+    let data = b"payload";
+    assert_eq!(&decode_frame(&valid_frame(data)).unwrap()[..], data);
+
+    // Every cut-off prefix of a valid frame is an error, not a panic
+    let full = valid_frame(data);
+    for length in 0..full.len() {
+        assert!(matches!(decode_frame(&full[..length]), Err(AuroraError::Protocol(_))), "{}", length);
+    }
+
+    // Lengths past the end of the buffer, or past the limit
+    assert!(decode_frame(&frame(1, u32::MAX, data, 0)).is_err());
+    assert!(decode_frame(&frame(1, data.len() as u32 + 1, data, crc32fast::hash(data))).is_err());
+    assert!(decode_frame(&frame(1, 0, data, crc32fast::hash(data))).is_err());
+
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    header[3] = 1;
+    header[9..13].copy_from_slice(&(MAX_MESSAGE_SIZE as u32).to_be_bytes());
+    assert_eq!(FrameHeader::parse(&header).unwrap().length, MAX_MESSAGE_SIZE);
+    header[9..13].copy_from_slice(&(MAX_MESSAGE_SIZE as u32 + 1).to_be_bytes());
+    assert!(FrameHeader::parse(&header).is_err());
+}
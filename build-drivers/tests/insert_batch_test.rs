@@ -0,0 +1,112 @@
+//! Batch Insert Tests
+//!
+//! A stand-in server applies each request to a table with a unique key, so
+//! each test sees the per-row outcomes the driver would return.
+
+use aurora_drivers::batch::{self, InsertBatchRequest, InsertBatchResponse, RowFailure};
+use aurora_drivers::{AuroraError, AuroraValue, BatchMode, InsertBatch, RowErrorKind};
+use std::collections::HashSet;
+
+/// Table whose first column is a unique key and whose second is NOT NULL
+#[derive(Default)]
+struct StandInTable {
+    keys: HashSet<i64>,
+}
+
+impl StandInTable {
+    fn apply(&mut self, request: &InsertBatchRequest) -> InsertBatchResponse {
+        let mut inserted = Vec::new();
+        let mut failures = Vec::new();
+        for (row, position) in request.rows.iter().zip(&request.positions) {
+            let failure = match (&row[0], &row[1]) {
+                (_, AuroraValue::Null) => Some(("23502", "null value in column \"email\"", None)),
+                (AuroraValue::BigInt(key), _) if self.keys.contains(key) || inserted.contains(key) => {
+                    Some(("23505", "duplicate key value", Some("users_pkey".to_string())))
+                }
+                (AuroraValue::BigInt(key), _) => {
+                    inserted.push(*key);
+                    None
+                }
+                _ => Some(("22P02", "invalid input syntax for type integer", None)),
+            };
+            if let Some((sqlstate, message, constraint)) = failure {
+                failures.push(RowFailure { row: *position, sqlstate: sqlstate.to_string(), message: message.to_string(), constraint });
+            }
+        }
+
+        let aborted = request.mode == BatchMode::Atomic && !failures.is_empty();
+        if !aborted {
+            self.keys.extend(inserted);
+        }
+        InsertBatchResponse { failures, aborted }
+    }
+
+    /// What `AuroraProtocol::insert_batch` does, with this table as the server
+    fn insert(&mut self, batch: InsertBatch, mode: BatchMode) -> aurora_drivers::Result<Vec<aurora_drivers::RowResult>> {
+        let len = batch.len();
+        let (request, rejected) = batch.into_request(mode);
+        if mode == BatchMode::Atomic && !rejected.is_empty() {
+            return batch::row_results(len, mode, rejected, InsertBatchResponse::default());
+        }
+        let response = self.apply(&request);
+        batch::row_results(len, mode, rejected, response)
+    }
+}
+
+fn user(id: i64, email: &str) -> Vec<AuroraValue> {
+    vec![AuroraValue::BigInt(id), AuroraValue::Text(email.to_string())]
+}
+
+#[test]
+fn test_mixed_batch_reports_each_row() {
+    let mut table = StandInTable::default();
+    table.insert(InsertBatch::new("users", &["id", "email"]).row(user(1, "a@example.com")), BatchMode::PerRow).unwrap();
+
+    let batch = InsertBatch::new("users", &["id", "email"])
+        .row(user(2, "b@example.com"))
+        .row(user(1, "dup@example.com"))
+        .row(vec![AuroraValue::BigInt(3), AuroraValue::Null])
+        .row(vec![AuroraValue::BigInt(4)])
+        .row(user(5, "e@example.com"))
+        .row(user(5, "e2@example.com"));
+    let results = table.insert(batch, BatchMode::PerRow).unwrap();
+
+    assert_eq!(results.len(), 6);
+    assert!(results[0].is_ok());
+    let duplicate = results[1].as_ref().unwrap_err();
+    assert_eq!(duplicate.kind, RowErrorKind::UniqueViolation);
+    assert_eq!(duplicate.constraint.as_deref(), Some("users_pkey"));
+    assert_eq!(results[2].as_ref().unwrap_err().kind, RowErrorKind::NotNullViolation);
+    assert!(results[2].as_ref().unwrap_err().kind.is_constraint_violation());
+    assert_eq!(results[3].as_ref().unwrap_err().kind, RowErrorKind::Malformed);
+    assert!(results[4].is_ok());
+    // A duplicate within the batch is caught too
+    assert_eq!(results[5].as_ref().unwrap_err().kind, RowErrorKind::UniqueViolation);
+
+    assert_eq!(table.keys, HashSet::from([1, 2, 5]));
+}
+
+#[test]
+fn test_atomic_batch_aborts_on_any_failure() {
+    let mut table = StandInTable::default();
+    table.insert(InsertBatch::new("users", &["id", "email"]).row(user(1, "a@example.com")), BatchMode::Atomic).unwrap();
+
+    let batch = InsertBatch::new("users", &["id", "email"])
+        .row(user(2, "b@example.com"))
+        .row(user(3, "c@example.com"))
+        .row(user(1, "dup@example.com"));
+    let result = table.insert(batch, BatchMode::Atomic);
+    assert!(matches!(&result, Err(AuroraError::Query(message)) if message.contains("row 2") && message.contains("users_pkey")));
+    assert_eq!(table.keys, HashSet::from([1]));
+
+    // A malformed row fails the batch without a round trip
+    let batch = InsertBatch::new("users", &["id", "email"])
+        .row(user(2, "b@example.com"))
+        .row(vec![AuroraValue::BigInt(3)]);
+    assert!(matches!(table.insert(batch, BatchMode::Atomic), Err(AuroraError::Query(message)) if message.contains("row 1")));
+
+    let batch = InsertBatch::new("users", &["id", "email"]).row(user(2, "b@example.com")).row(user(3, "c@example.com"));
+    let results = table.insert(batch, BatchMode::Atomic).unwrap();
+    assert!(results.iter().all(|row| row.is_ok()));
+    assert_eq!(table.keys, HashSet::from([1, 2, 3]));
+}
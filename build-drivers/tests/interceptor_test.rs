@@ -0,0 +1,146 @@
+//! Interceptor Tests
+//!
+//! Drives the chain with stand-in senders in place of a connection, so each
+//! test sees exactly the statement the driver would have put on the wire.
+
+use aurora_drivers::interceptor::{InterceptedRequest, InterceptorChain};
+use aurora_drivers::telemetry::Operation;
+use aurora_drivers::{AuroraError, ExecuteResult, Interceptor, QueryResult, RequestOutcome, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Appends the request id as a trailing comment
+struct RequestIdComment;
+
+impl Interceptor for RequestIdComment {
+    fn before(&self, request: &mut InterceptedRequest) -> Result<()> {
+        request.sql = format!("{} /* request_id={} */", request.sql, request.request_id);
+        Ok(())
+    }
+}
+
+/// Records how long each request took and how many rows it touched
+#[derive(Default)]
+struct Timing {
+    samples: Mutex<Vec<(String, Duration, Option<u64>)>>,
+}
+
+impl Interceptor for Arc<Timing> {
+    fn after(&self, request: &InterceptedRequest, result: std::result::Result<RequestOutcome<'_>, &AuroraError>) {
+        let rows = result.ok().map(|outcome| outcome.rows());
+        self.samples.lock().unwrap().push((request.sql.clone(), request.started_at.elapsed(), rows));
+    }
+}
+
+/// Rejects statements without a WHERE clause that modify data
+struct RequireWhere;
+
+impl Interceptor for RequireWhere {
+    fn before(&self, request: &mut InterceptedRequest) -> Result<()> {
+        let sql = request.sql.to_ascii_uppercase();
+        if (sql.starts_with("DELETE") || sql.starts_with("UPDATE")) && !sql.contains(" WHERE ") {
+            return Err(AuroraError::Query(format!("rejected unbounded statement: {}", request.sql)));
+        }
+        Ok(())
+    }
+}
+
+fn query_result(rows: usize) -> QueryResult {
+    QueryResult {
+        rows: Vec::new(),
+        columns: Vec::new(),
+        row_count: rows,
+        execution_time_ms: 0.0,
+        query_id: "q".to_string(),
+    }
+}
+
+fn execute_result(rows_affected: u64) -> ExecuteResult {
+    ExecuteResult {
+        rows_affected,
+        last_insert_id: None,
+        execution_time_ms: 0.0,
+        statement_id: "s".to_string(),
+        commit_lsn: None,
+    }
+}
+
+#[tokio::test]
+async fn test_comment_interceptor_rewrites_statement() {
+    let chain = InterceptorChain::new().with(RequestIdComment);
+    let sent = Arc::new(Mutex::new(Vec::new()));
+
+    // Requests in flight together each carry their own id
+    let requests = (0..8).map(|i| {
+        let (chain, sent) = (chain.clone(), sent.clone());
+        async move {
+            let mut request = InterceptedRequest::new(Operation::Query, &format!("SELECT {}", i), &[], "conn_1");
+            chain.run(&mut request, |sql, _params| async move {
+                tokio::task::yield_now().await;
+                sent.lock().unwrap().push(sql);
+                Ok(query_result(1))
+            }).await.unwrap();
+            request
+        }
+    });
+    let requests = futures::future::join_all(requests).await;
+
+    let sent = sent.lock().unwrap();
+    for (i, request) in requests.iter().enumerate() {
+        let expected = format!("SELECT {} /* request_id={} */", i, request.request_id);
+        assert_eq!(request.sql, expected);
+        assert!(sent.contains(&expected));
+    }
+}
+
+#[tokio::test]
+async fn test_timing_interceptor_observes_results() {
+    let timing = Arc::new(Timing::default());
+    let chain = InterceptorChain::new().with(timing.clone());
+
+    let mut request = InterceptedRequest::new(Operation::Execute, "UPDATE t SET x = 1 WHERE id = 2", &[], "conn_1");
+    chain.run(&mut request, |_, _| async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(execute_result(3))
+    }).await.unwrap();
+
+    let mut request = InterceptedRequest::new(Operation::Query, "SELECT * FROM missing", &[], "conn_2");
+    let failed = chain.run(&mut request, |_, _| async {
+        Err::<QueryResult, _>(AuroraError::Query("relation does not exist".into()))
+    }).await;
+    assert!(failed.is_err());
+
+    let samples = timing.samples.lock().unwrap();
+    assert_eq!(samples.len(), 2);
+    assert!(samples[0].1 >= Duration::from_millis(20));
+    assert_eq!(samples[0].2, Some(3));
+    assert_eq!(samples[1].2, None);
+}
+
+#[tokio::test]
+async fn test_rule_interceptor_rejects_before_sending() {
+    let timing = Arc::new(Timing::default());
+    let chain = InterceptorChain::new().with(timing.clone()).with(RequireWhere).with(RequestIdComment);
+    let sends = Arc::new(Mutex::new(0));
+
+    let mut request = InterceptedRequest::new(Operation::Execute, "DELETE FROM orders", &[], "conn_1");
+    let counter = sends.clone();
+    let result = chain.run(&mut request, |_, _| async move {
+        *counter.lock().unwrap() += 1;
+        Ok(execute_result(100))
+    }).await;
+
+    assert!(matches!(result, Err(AuroraError::Query(message)) if message.contains("unbounded")));
+    assert_eq!(*sends.lock().unwrap(), 0);
+    // Interceptors ahead of the rule still see the rejection; later ones never ran
+    assert_eq!(timing.samples.lock().unwrap().len(), 1);
+    assert_eq!(request.sql, "DELETE FROM orders");
+
+    let mut request = InterceptedRequest::new(Operation::Execute, "DELETE FROM orders WHERE id = 1", &[], "conn_1");
+    let counter = sends.clone();
+    chain.run(&mut request, |_, _| async move {
+        *counter.lock().unwrap() += 1;
+        Ok(execute_result(1))
+    }).await.unwrap();
+    assert_eq!(*sends.lock().unwrap(), 1);
+}
@@ -0,0 +1,231 @@
+//! Large Object Streaming Tests
+//!
+//! Streams a 200MB object into an in-process server that keeps each large
+//! object in a file and serves it back a chunk at a time, then reads it out
+//! again. The bytes must round-trip exactly while the process never holds
+//! more than a few chunks: a counting allocator tracks the peak.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::FRAME_HEADER_LEN;
+use aurora_drivers::large_object::{decode_read, decode_write};
+use aurora_drivers::{AuroraConnection, LOB_CHUNK_SIZE};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Heap in use and its high-water mark
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(live, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Taken by each test so one's allocations do not count toward the other's peak
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+const LOB_CREATE: u8 = 15;
+const LOB_READ: u8 = 16;
+const LOB_WRITE: u8 = 17;
+
+/// Byte `offset` of the test object
+fn pattern(offset: u64) -> u8 {
+    // splitmix64 of the 8-byte block, so no two blocks repeat
+    let mut z = (offset / 8).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    z.to_be_bytes()[(offset % 8) as usize]
+}
+
+/// `len` bytes of the pattern, generated as they are read
+struct PatternSource {
+    offset: u64,
+    len: u64,
+}
+
+impl AsyncRead for PatternSource {
+    fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let n = (self.len - self.offset).min(buf.remaining() as u64) as usize;
+        let start = self.offset;
+        for (i, byte) in buf.initialize_unfilled_to(n).iter_mut().enumerate() {
+            *byte = pattern(start + i as u64);
+        }
+        buf.advance(n);
+        self.offset += n as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + 4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+/// Serve large objects as files under `dir`
+async fn serve(mut socket: TcpStream, dir: PathBuf) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    let path = |oid: u64| dir.join(format!("{}.lob", oid));
+    let mut next_oid = 1;
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+        let request = &body[..len];
+
+        let reply = match header[4] {
+            LOB_CREATE => {
+                let oid = next_oid;
+                next_oid += 1;
+                std::fs::File::create(path(oid))?;
+                oid.to_be_bytes().to_vec()
+            }
+            LOB_READ => {
+                let (oid, offset, max_len) = decode_read(request).unwrap();
+                let mut file = std::fs::File::open(path(oid))?;
+                file.seek(SeekFrom::Start(offset))?;
+                let mut chunk = Vec::with_capacity(max_len as usize);
+                file.take(max_len as u64).read_to_end(&mut chunk)?;
+                chunk
+            }
+            LOB_WRITE => {
+                let (oid, offset, data) = decode_write(request).unwrap();
+                let mut file = OpenOptions::new().write(true).open(path(oid))?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(data)?;
+                file.metadata()?.len().to_be_bytes().to_vec()
+            }
+            other => panic!("unexpected message type {}", other),
+        };
+        socket.write_all(&frame(&reply)).await?;
+    }
+}
+
+async fn connect(dir: PathBuf) -> AuroraConnection {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, dir.clone()));
+        }
+    });
+
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+fn object_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aurora-lob-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn test_200mb_object_round_trips_in_bounded_memory() {
+    const SIZE: u64 = 200 * 1024 * 1024;
+    let _serial = SERIAL.lock().await;
+    let dir = object_dir("round-trip");
+    let mut conn = connect(dir.clone()).await;
+    let mut received = vec![0u8; 64 * 1024];
+    let baseline = LIVE.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let mut writer = conn.write_lob().await.unwrap();
+    let oid = writer.oid();
+    let written = tokio::io::copy(&mut PatternSource { offset: 0, len: SIZE }, &mut writer).await.unwrap();
+    writer.shutdown().await.unwrap();
+    drop(writer);
+    assert_eq!(written, SIZE);
+    assert_eq!(std::fs::metadata(dir.join(format!("{}.lob", oid))).unwrap().len(), SIZE);
+
+    let mut reader = conn.read_lob(oid);
+    let mut offset = 0u64;
+    loop {
+        let n = reader.read(&mut received).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        if let Some(i) = (0..n).find(|&i| received[i] != pattern(offset + i as u64)) {
+            panic!("byte {} differs", offset + i as u64);
+        }
+        offset += n as u64;
+    }
+    assert_eq!(offset, SIZE);
+
+    // A handful of chunks in flight on either side, never the object
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+    assert!(peak < 8 * LOB_CHUNK_SIZE, "peak heap {} bytes", peak);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_empty_and_unaligned_objects() {
+    let _serial = SERIAL.lock().await;
+    let dir = object_dir("unaligned");
+    let mut conn = connect(dir.clone()).await;
+
+    let writer = conn.write_lob().await.unwrap();
+    let empty = writer.oid();
+    drop(writer);
+    let mut contents = Vec::new();
+    conn.read_lob(empty).read_to_end(&mut contents).await.unwrap();
+    assert!(contents.is_empty());
+
+    // Two and a half chunks, written in odd-sized pieces
+    let size = LOB_CHUNK_SIZE * 5 / 2 + 3;
+    let mut writer = conn.write_lob().await.unwrap();
+    let oid = writer.oid();
+    let source: Vec<u8> = (0..size as u64).map(pattern).collect();
+    for piece in source.chunks(777_777) {
+        writer.write_all(piece).await.unwrap();
+    }
+    writer.flush().await.unwrap();
+    drop(writer);
+
+    conn.read_lob(oid).read_to_end(&mut contents).await.unwrap();
+    assert_eq!(contents.len(), size);
+    assert!(contents == source);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,196 @@
+//! Keyset Pagination Tests
+//!
+//! A stand-in server answers each page request the way the generated SQL
+//! asks: sorted by the query's keys, after the cursor's key values, up to
+//! the LIMIT. Rows inserted between pages on either side of the cursor must
+//! not make the pager skip or repeat a row.
+
+use aurora_drivers::pagination::compare_keys;
+use aurora_drivers::{AuroraColumn, AuroraError, AuroraRow, AuroraType, AuroraValue, KeysetQuery, PageCursor, QueryResult, SortKey};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Table of `(created_at, id, category)` rows
+#[derive(Default)]
+struct StandInTable {
+    rows: Vec<Vec<AuroraValue>>,
+}
+
+impl StandInTable {
+    const COLUMNS: [&'static str; 3] = ["created_at", "id", "category"];
+
+    fn insert(&mut self, created_at: i64, id: i64, category: &str) {
+        self.rows.push(vec![AuroraValue::BigInt(created_at), AuroraValue::BigInt(id), AuroraValue::Text(category.to_string())]);
+    }
+
+    /// Run a page request: the rows after `params` in key order, up to the
+    /// SQL's LIMIT
+    fn run(&self, keys: &[SortKey], sql: &str, params: &[AuroraValue]) -> QueryResult {
+        let limit: usize = sql.rsplit("LIMIT ").next().unwrap().parse().unwrap();
+        let positions: Vec<usize> = keys.iter()
+            .map(|key| Self::COLUMNS.iter().position(|column| *column == key.column).unwrap())
+            .collect();
+        let key_of = |row: &Vec<AuroraValue>| positions.iter().map(|&i| row[i].clone()).collect::<Vec<_>>();
+
+        let mut rows: Vec<&Vec<AuroraValue>> = self.rows.iter()
+            .filter(|row| params.is_empty() || compare_keys(keys, &key_of(row), params) == Ordering::Greater)
+            .collect();
+        rows.sort_by(|a, b| compare_keys(keys, &key_of(a), &key_of(b)));
+        rows.truncate(limit);
+
+        QueryResult {
+            rows: rows.into_iter().map(|values| AuroraRow { values: values.clone(), columns: None }).collect(),
+            columns: Self::COLUMNS.iter().map(|name| AuroraColumn {
+                name: name.to_string(),
+                column_type: AuroraType::BigInt,
+                nullable: false,
+                default_value: None,
+                primary_key: *name == "id",
+                auto_increment: false,
+                comment: None,
+            }).collect(),
+            row_count: 0,
+            execution_time_ms: 0.0,
+            query_id: "page".to_string(),
+        }
+    }
+}
+
+fn id_of(row: &AuroraRow) -> i64 {
+    match row.values[1] {
+        AuroraValue::BigInt(id) => id,
+        ref value => panic!("id {:?}", value),
+    }
+}
+
+/// Page through `table` with `query`, calling `between_pages` after each
+/// page; the ids in the order they were returned
+fn page_through(
+    table: &mut StandInTable,
+    query: &KeysetQuery,
+    page_size: usize,
+    mut between_pages: impl FnMut(&mut StandInTable, usize),
+) -> Vec<i64> {
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    for page_number in 0.. {
+        let (sql, params) = query.page_request(cursor.as_deref(), page_size).unwrap();
+        let page = query.page_from(table.run(query.keys(), &sql, &params), page_size).unwrap();
+        assert!(page.rows.len() <= page_size);
+        seen.extend(page.rows.iter().map(id_of));
+        between_pages(table, page_number);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    seen
+}
+
+#[test]
+fn test_concurrent_inserts_neither_skip_nor_repeat_rows() {
+    let mut table = StandInTable::default();
+    // Several rows share each timestamp, so the id breaks ties
+    for id in 0..100 {
+        table.insert(1_000 + id / 4, id, "a");
+    }
+    let query = KeysetQuery::new("SELECT created_at, id, category FROM events;")
+        .order_by_desc("created_at")
+        .order_by_desc("id");
+
+    // Newer rows arrive while paging from newest to oldest: they sort
+    // before the cursor and belong to pages already read. Backfilled old
+    // rows sort after it and must still show up.
+    let seen = page_through(&mut table, &query, 7, |table, page| {
+        let id = 1_000 + page as i64;
+        table.insert(5_000 + page as i64, id, "a");
+        table.insert(0, id + 500, "a");
+    });
+
+    let unique: HashSet<i64> = seen.iter().copied().collect();
+    assert_eq!(unique.len(), seen.len(), "rows repeated: {:?}", seen);
+    assert!((0..100).all(|id| unique.contains(&id)), "rows skipped: {:?}", seen);
+    assert!(seen.iter().filter(|id| **id >= 1_500).count() > 0, "backfilled rows never seen");
+    assert!(seen.iter().all(|id| !(1_000..1_500).contains(id)), "rows before the cursor returned");
+
+    // Newest to oldest, ties broken by id, with no step backwards
+    let expected_prefix: Vec<i64> = (0..100).rev().collect();
+    assert_eq!(&seen[..100], expected_prefix.as_slice());
+}
+
+#[test]
+fn test_mixed_direction_keys() {
+    let mut table = StandInTable::default();
+    for id in 0..30 {
+        table.insert(0, id, ["red", "green", "blue"][id as usize % 3]);
+    }
+    let query = KeysetQuery::new("SELECT * FROM events").order_by("category").order_by_desc("id");
+
+    let seen = page_through(&mut table, &query, 4, |_, _| {});
+    let mut expected = Vec::new();
+    for color in [2, 1, 0] {
+        // blue, green, red; newest id first within each
+        expected.extend((0..30).rev().filter(|id| id % 3 == color));
+    }
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn test_continuation_sql() {
+    let query = KeysetQuery::new("SELECT * FROM events WHERE tenant = 7").order_by("created_at").order_by("id");
+    let (first, params) = query.page_request(None, 50).unwrap();
+    assert_eq!(first, "SELECT * FROM (SELECT * FROM events WHERE tenant = 7) AS keyset_page ORDER BY created_at, id LIMIT 51");
+    assert!(params.is_empty());
+
+    let cursor = PageCursor {
+        keys: query.keys().to_vec(),
+        values: vec![AuroraValue::BigInt(1_700_000_000), AuroraValue::BigInt(42)],
+    };
+    let (next, params) = query.page_request(Some(&cursor.encode()), 50).unwrap();
+    assert_eq!(
+        next,
+        "SELECT * FROM (SELECT * FROM events WHERE tenant = 7) AS keyset_page \
+         WHERE (created_at, id) > ($1, $2) ORDER BY created_at, id LIMIT 51"
+    );
+    assert_eq!(params, cursor.values);
+
+    let descending = KeysetQuery::new("SELECT * FROM events").order_by_desc("id");
+    let cursor = PageCursor { keys: descending.keys().to_vec(), values: vec![AuroraValue::BigInt(9)] };
+    let (sql, _) = descending.page_request(Some(&cursor.encode()), 10).unwrap();
+    assert!(sql.ends_with("WHERE id < $1 ORDER BY id DESC LIMIT 11"), "{}", sql);
+
+    let mixed = KeysetQuery::new("SELECT * FROM events").order_by("category").order_by_desc("created_at").order_by("id");
+    let cursor = PageCursor {
+        keys: mixed.keys().to_vec(),
+        values: vec![AuroraValue::Text("red".to_string()), AuroraValue::BigInt(5), AuroraValue::BigInt(3)],
+    };
+    let (sql, _) = mixed.page_request(Some(&cursor.encode()), 10).unwrap();
+    assert!(sql.contains(
+        "WHERE category > $1 OR (category = $1 AND created_at < $2) OR (category = $1 AND created_at = $2 AND id > $3) \
+         ORDER BY category, created_at DESC, id LIMIT 11"
+    ), "{}", sql);
+}
+
+#[test]
+fn test_cursor_token_round_trips() {
+    let cursor = PageCursor {
+        keys: vec![
+            SortKey { column: "created_at".to_string(), descending: true },
+            SortKey { column: "name".to_string(), descending: false },
+        ],
+        values: vec![AuroraValue::TimestampTz(1_700_000_000_000_000, "UTC".to_string()), AuroraValue::Text("O'Brien, \"Pat\"".to_string())],
+    };
+    let token = cursor.encode();
+    assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '.'), "{}", token);
+    assert_eq!(PageCursor::decode(&token).unwrap(), cursor);
+
+    // Tampered, truncated or foreign tokens are refused
+    for bad in [&token[..token.len() - 1], "k1.zz", "v9.00", ""] {
+        assert!(matches!(PageCursor::decode(bad), Err(AuroraError::Query(_))), "{}", bad);
+    }
+
+    // A token for other keys is refused by the query
+    let query = KeysetQuery::new("SELECT * FROM people").order_by("name");
+    let error = query.page_request(Some(&token), 10).unwrap_err();
+    assert!(error.to_string().contains("different ORDER BY keys"), "{}", error);
+}
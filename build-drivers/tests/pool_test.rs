@@ -0,0 +1,443 @@
+//! Connection Pool Tests
+//!
+//! Runs the pool against a minimal in-process server that accepts any
+//! authentication message.
+
+use aurora_drivers::config::{AdaptiveSizingConfig, AuroraConfig, LeakDetectionConfig, PoolConfig};
+use aurora_drivers::{AuroraConnectionPool, AuroraError, PoolSizeController, Priority, ResizeReason, WaitQueue};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Start a server that answers every authentication message with "OK"
+async fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = socket.read(&mut buf).await {
+                    if n == 0 || socket.write_all(b"OK").await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    port
+}
+
+fn config(port: u16, min_connections: u32) -> AuroraConfig {
+    AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        pool: PoolConfig {
+            max_connections: 4,
+            min_connections,
+            max_idle_time: Duration::from_secs(300),
+            max_lifetime: Duration::from_secs(3600),
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+            adaptive: None,
+            circuit_breaker: None,
+            priority_aging: Duration::from_secs(1),
+            leak_detection: None,
+        },
+        ..AuroraConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_on_connect_runs_once_per_connection() {
+    let port = start_server().await;
+    let hook_calls = Arc::new(AtomicUsize::new(0));
+
+    let calls = hook_calls.clone();
+    let pool = AuroraConnectionPool::with_on_connect(config(port, 2), move |_conn| {
+        let calls = calls.clone();
+        Box::pin(async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }).await.unwrap();
+
+    // Warm connections created at startup
+    assert_eq!(hook_calls.load(Ordering::SeqCst), 2);
+
+    // Reusing pooled connections does not re-run the hook
+    for _ in 0..5 {
+        let conn = pool.get_connection().await.unwrap();
+        pool.return_connection(conn).await.unwrap();
+    }
+    assert_eq!(hook_calls.load(Ordering::SeqCst), 2);
+
+    // A third concurrent checkout opens one more physical connection
+    let a = pool.get_connection().await.unwrap();
+    let b = pool.get_connection().await.unwrap();
+    let c = pool.get_connection().await.unwrap();
+    assert_eq!(hook_calls.load(Ordering::SeqCst), 3);
+    assert_eq!(pool.stats().await.total_connections, 3);
+
+    for conn in [a, b, c] {
+        pool.return_connection(conn).await.unwrap();
+    }
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_on_connect_failure_discards_connection() {
+    let port = start_server().await;
+
+    let pool = AuroraConnectionPool::with_on_connect(config(port, 0), |_conn| {
+        Box::pin(async { Err(AuroraError::Query("SET search_path failed".into())) })
+    }).await.unwrap();
+
+    let result = pool.get_connection().await;
+    assert!(matches!(result, Err(AuroraError::Query(_))));
+
+    let stats = pool.stats().await;
+    assert_eq!(stats.total_connections, 0);
+    assert_eq!(stats.available_connections, 0);
+
+    // A failing hook at startup fails pool creation instead of pooling cold connections
+    let result = AuroraConnectionPool::with_on_connect(config(port, 1), |_conn| {
+        Box::pin(async { Err(AuroraError::Query("PREPARE failed".into())) })
+    }).await;
+    assert!(result.is_err());
+}
+
+fn adaptive_config() -> AdaptiveSizingConfig {
+    AdaptiveSizingConfig {
+        target_wait: Duration::from_millis(10),
+        grow_step: 2,
+        shrink_step: 1,
+        low_utilization: 0.5,
+        shrink_after: 3,
+        // Windows are driven by the tests through `evaluate_sizing`
+        evaluation_interval: Duration::from_secs(3600),
+    }
+}
+
+#[test]
+fn test_controller_burst_and_quiet_period_respect_bounds() {
+    let mut controller = PoolSizeController::new(adaptive_config(), 2, 9);
+    assert_eq!(controller.target(), 2);
+
+    // Burst: every window sees slow acquisitions with the pool saturated
+    for _ in 0..10 {
+        let target = controller.target();
+        controller.record_acquire(Duration::from_millis(50), target);
+        controller.evaluate();
+        assert!(controller.target() <= 9);
+    }
+    assert_eq!(controller.target(), 9);
+
+    // Moderate load between the grow and shrink thresholds holds the size
+    for _ in 0..10 {
+        controller.record_acquire(Duration::from_millis(1), 6);
+        assert!(controller.evaluate().is_none());
+    }
+    assert_eq!(controller.target(), 9);
+
+    // Quiet period: shrinks one step per `shrink_after` windows, never below min
+    for _ in 0..3 {
+        controller.record_in_use(1);
+        controller.evaluate();
+    }
+    assert_eq!(controller.target(), 8);
+    for _ in 0..60 {
+        controller.record_in_use(0);
+        controller.evaluate();
+        assert!(controller.target() >= 2);
+    }
+    assert_eq!(controller.target(), 2);
+
+    let events = controller.events();
+    assert!(matches!(events.first().unwrap().reason, ResizeReason::AcquireWait(_)));
+    assert!(matches!(events.last().unwrap().reason, ResizeReason::LowUtilization(_)));
+    assert!(events.iter().all(|event| event.to >= 2 && event.to <= 9));
+}
+
+#[test]
+fn test_controller_growth_resets_quiet_streak() {
+    let mut controller = PoolSizeController::new(adaptive_config(), 1, 4);
+    controller.record_acquire(Duration::from_millis(20), 1);
+    controller.evaluate();
+    assert_eq!(controller.target(), 3);
+
+    // Two quiet windows, then a spike: the shrink countdown starts over
+    controller.evaluate();
+    controller.evaluate();
+    controller.record_acquire(Duration::from_millis(20), 3);
+    controller.evaluate();
+    assert_eq!(controller.target(), 4);
+    controller.evaluate();
+    controller.evaluate();
+    assert_eq!(controller.target(), 4);
+    controller.evaluate();
+    assert_eq!(controller.target(), 3);
+}
+
+#[tokio::test]
+async fn test_adaptive_pool_grows_under_burst_and_shrinks_when_quiet() {
+    let port = start_server().await;
+    let mut config = config(port, 1);
+    config.pool.adaptive = Some(adaptive_config());
+    let pool = Arc::new(AuroraConnectionPool::new(config).await.unwrap());
+    assert_eq!(pool.target_size().await, 1);
+
+    // Burst: the only connection is busy, so a second caller has to wait
+    let held = pool.get_connection().await.unwrap();
+    let waiter = {
+        let pool = pool.clone();
+        tokio::spawn(async move { pool.get_connection().await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pool.stats().await.total_connections, 1);
+    pool.return_connection(held).await.unwrap();
+    let reused = waiter.await.unwrap().unwrap();
+
+    let event = pool.evaluate_sizing().await.unwrap();
+    assert_eq!((event.from, event.to), (1, 3));
+    assert_eq!(pool.target_size().await, 3);
+
+    // The grown target lets concurrent callers open connections, up to max
+    let extra = vec![pool.get_connection().await.unwrap(), pool.get_connection().await.unwrap()];
+    assert_eq!(pool.stats().await.total_connections, 3);
+    pool.return_connection(reused).await.unwrap();
+    for conn in extra {
+        pool.return_connection(conn).await.unwrap();
+    }
+
+    // Quiet period: idle connections above the shrinking target are closed.
+    // The first window still holds the peak from the checkouts above.
+    for _ in 0..7 {
+        pool.evaluate_sizing().await;
+    }
+    let stats = pool.stats().await;
+    assert_eq!(stats.target_size, 1);
+    assert_eq!(stats.total_connections, 1);
+    assert!(pool.resize_events().await.iter().all(|event| event.to >= 1 && event.to <= 4));
+
+    pool.close().await.unwrap();
+}
+
+#[test]
+fn test_wait_queue_orders_by_priority_then_arrival_and_ages_waiters() {
+    let mut queue = WaitQueue::new(Duration::from_secs(1));
+    let start = Instant::now();
+    let low = queue.push(Priority::Low, start);
+    let normal_1 = queue.push(Priority::Normal, start);
+    let high = queue.push(Priority::High, start);
+    let normal_2 = queue.push(Priority::Normal, start);
+    assert_eq!((queue.len(), queue.lane_len(Priority::Normal)), (4, 2));
+
+    // Highest lane first, FIFO within a lane
+    assert_eq!(queue.next(start), Some(high));
+    assert!(queue.remove(high));
+    assert_eq!(queue.next(start), Some(normal_1));
+    assert!(queue.remove(normal_1));
+
+    // One interval lifts a normal waiter level with a fresh high one, and it arrived first
+    let later = start + Duration::from_millis(1500);
+    let fresh_high = queue.push(Priority::High, later);
+    assert_eq!(queue.next(later), Some(normal_2));
+    assert!(queue.remove(normal_2));
+    assert_eq!(queue.next(later), Some(fresh_high));
+
+    // Two intervals do the same for a low waiter
+    assert_eq!(queue.next(start + Duration::from_secs(2)), Some(low));
+    assert!(!queue.remove(normal_2));
+
+    queue.record_wait(Priority::Low, Duration::from_millis(10));
+    queue.record_wait(Priority::Low, Duration::from_millis(30));
+    let stats = queue.lane_stats(Priority::Low);
+    assert_eq!((stats.acquisitions, stats.max_wait, stats.mean_wait()), (2, Duration::from_millis(30), Duration::from_millis(20)));
+    assert_eq!(queue.lane_stats(Priority::High).mean_wait(), Duration::ZERO);
+}
+
+/// A pool of a single connection, so every other caller has to queue
+async fn single_connection_pool(port: u16, priority_aging: Duration) -> Arc<AuroraConnectionPool> {
+    let mut config = config(port, 1);
+    config.pool.max_connections = 1;
+    config.pool.priority_aging = priority_aging;
+    Arc::new(AuroraConnectionPool::new(config).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_high_priority_waiters_are_served_before_queued_low_priority_ones() {
+    let port = start_server().await;
+    let pool = single_connection_pool(port, Duration::from_secs(60)).await;
+    let served = Arc::new(Mutex::new(Vec::new()));
+
+    let held = pool.get_connection().await.unwrap();
+    let mut waiters = Vec::new();
+    for (name, priority) in [
+        ("low-1", Priority::Low),
+        ("low-2", Priority::Low),
+        ("high-1", Priority::High),
+        ("normal", Priority::Normal),
+        ("high-2", Priority::High),
+    ] {
+        let (pool, served) = (pool.clone(), served.clone());
+        waiters.push(tokio::spawn(async move {
+            let conn = pool.get_connection_with_priority(priority).await.unwrap();
+            served.lock().unwrap().push(name);
+            pool.return_connection(conn).await.unwrap();
+        }));
+        // Queue up in a known arrival order
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(pool.stats().await.waiting_requests, 5);
+
+    pool.return_connection(held).await.unwrap();
+    for waiter in waiters {
+        waiter.await.unwrap();
+    }
+    assert_eq!(*served.lock().unwrap(), ["high-1", "high-2", "normal", "low-1", "low-2"]);
+    assert_eq!(pool.stats().await.waiting_requests, 0);
+    assert_eq!(pool.stats().await.total_connections, 1);
+
+    // Per-lane waits: the low lane waited longest
+    let (low, high) = (pool.lane_wait_stats(Priority::Low), pool.lane_wait_stats(Priority::High));
+    assert_eq!((low.acquisitions, high.acquisitions), (2, 2));
+    assert!(low.max_wait > high.max_wait);
+
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_low_priority_waiter_is_not_starved_by_high_priority_load() {
+    let port = start_server().await;
+    let aging = Duration::from_millis(100);
+    let pool = single_connection_pool(port, aging).await;
+    let high_served = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let held = pool.get_connection().await.unwrap();
+    let low = {
+        let (pool, high_served) = (pool.clone(), high_served.clone());
+        tokio::spawn(async move {
+            let conn = pool.get_connection_with_priority(Priority::Low).await.unwrap();
+            let overtaken_by = high_served.load(Ordering::SeqCst);
+            pool.return_connection(conn).await.unwrap();
+            overtaken_by
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // More high-priority callers than connections, so one is always queued
+    let hammers: Vec<_> = (0..3)
+        .map(|_| {
+            let (pool, high_served, stop) = (pool.clone(), high_served.clone(), stop.clone());
+            tokio::spawn(async move {
+                while !stop.load(Ordering::SeqCst) {
+                    let conn = pool.get_connection_with_priority(Priority::High).await.unwrap();
+                    high_served.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    pool.return_connection(conn).await.unwrap();
+                }
+            })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    pool.return_connection(held).await.unwrap();
+
+    // High-priority callers go first until the low waiter has aged two lanes
+    let overtaken_by = tokio::time::timeout(Duration::from_secs(2), low).await
+        .expect("low-priority waiter starved")
+        .unwrap();
+    assert!(overtaken_by > 0);
+    assert!(pool.lane_wait_stats(Priority::Low).max_wait >= aging * 2);
+
+    stop.store(true, Ordering::SeqCst);
+    for hammer in hammers {
+        hammer.await.unwrap();
+    }
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_connection_held_past_threshold_is_reported_once() {
+    let port = start_server().await;
+    let mut config = config(port, 0);
+    config.pool.leak_detection = Some(LeakDetectionConfig {
+        threshold: Duration::from_millis(50),
+        reclaim_after: None,
+        // Checks are driven by the test through `check_leaks`
+        check_interval: Duration::from_secs(3600),
+    });
+    let pool = AuroraConnectionPool::new(config).await.unwrap();
+
+    let leaked = pool.acquire().await.unwrap();
+    let returned = pool.acquire().await.unwrap();
+    drop(returned);
+    assert!(pool.check_leaks().await.is_empty());
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    let reports = pool.check_leaks().await;
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.connection_id, leaked.info().connection_id);
+    assert!(report.held_for >= Duration::from_millis(50));
+    assert!(!report.reclaimed);
+    assert_eq!(report.backtrace.is_some(), cfg!(feature = "leak-backtrace"));
+
+    // Already reported, and never reclaimed without `reclaim_after`
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(pool.check_leaks().await.is_empty());
+    assert_eq!(pool.metrics().snapshot().pool_leaks_detected, 1);
+
+    // A late return still goes back to the pool
+    drop(leaked);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let stats = pool.stats().await;
+    assert_eq!((stats.total_connections, stats.available_connections), (2, 2));
+    assert!(pool.check_leaks().await.is_empty());
+
+    pool.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_leaked_connection_is_reclaimed_for_waiters() {
+    let port = start_server().await;
+    let mut config = config(port, 1);
+    config.pool.max_connections = 1;
+    config.pool.leak_detection = Some(LeakDetectionConfig {
+        threshold: Duration::from_millis(20),
+        reclaim_after: Some(Duration::from_millis(300)),
+        check_interval: Duration::from_millis(10),
+    });
+    let pool = AuroraConnectionPool::new(config).await.unwrap();
+
+    // The only connection is held and never given back
+    let leaked = pool.acquire().await.unwrap();
+    let start = Instant::now();
+    let next = pool.acquire().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(250));
+    assert_ne!(next.info().connection_id, leaked.info().connection_id);
+
+    let metrics = pool.metrics().snapshot();
+    assert_eq!((metrics.pool_leaks_detected, metrics.pool_leaks_reclaimed), (1, 1));
+    assert_eq!(pool.stats().await.total_connections, 1);
+
+    // The leaked guard is closed on drop rather than pooled over the limit
+    let closed = metrics.connections_closed;
+    drop(leaked);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let stats = pool.stats().await;
+    assert_eq!((stats.total_connections, stats.available_connections), (1, 0));
+    assert_eq!(pool.metrics().snapshot().connections_closed, closed + 1);
+
+    drop(next);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pool.stats().await.available_connections, 1);
+
+    pool.close().await.unwrap();
+}
@@ -0,0 +1,166 @@
+//! Server-Side Portal Tests
+//!
+//! Opens two portals on one connection against an in-process server that
+//! keeps a cursor per portal, fetches from them in turn a page at a time,
+//! and checks each returns its own rows, complete and in order, and that a
+//! dropped portal is closed on the server.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::FRAME_HEADER_LEN;
+use aurora_drivers::portal::{decode_fetch, decode_open, encode_batch};
+use aurora_drivers::types::{AuroraRow, AuroraValue};
+use aurora_drivers::AuroraConnection;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const PORTAL_OPEN: u8 = 18;
+const PORTAL_FETCH: u8 = 19;
+const PORTAL_CLOSE: u8 = 20;
+
+/// Ids of the portals the server has been asked to close
+type ClosedLog = Arc<Mutex<Vec<u64>>>;
+
+/// Rows of the test queries: `SELECT n FROM generate_series(start, end)`
+fn series(start: i64, end: i64) -> Vec<AuroraRow> {
+    (start..=end)
+        .map(|n| AuroraRow { values: vec![AuroraValue::BigInt(n)], columns: Some(vec!["n".to_string()]) })
+        .collect()
+}
+
+fn query_rows(sql: &str) -> Vec<AuroraRow> {
+    match sql {
+        "SELECT n FROM generate_series(1, 120) n" => series(1, 120),
+        "SELECT n FROM generate_series(1001, 1075) n" => series(1001, 1075),
+        other => panic!("unexpected query {}", other),
+    }
+}
+
+fn numbers(rows: &[AuroraRow]) -> Vec<i64> {
+    rows.iter()
+        .map(|row| match row.values[0] {
+            AuroraValue::BigInt(n) => n,
+            ref other => panic!("unexpected value {:?}", other),
+        })
+        .collect()
+}
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + 4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+/// Serve portals, each a cursor over its query's rows
+async fn serve(mut socket: TcpStream, closed_log: ClosedLog) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    let mut portals: HashMap<u64, (Vec<AuroraRow>, usize)> = HashMap::new();
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+        let request = &body[..len];
+
+        let reply = match header[4] {
+            PORTAL_OPEN => {
+                let (id, sql) = decode_open(request).unwrap();
+                assert!(portals.insert(id, (query_rows(sql), 0)).is_none(), "portal {} opened twice", id);
+                id.to_be_bytes().to_vec()
+            }
+            PORTAL_FETCH => {
+                let (id, max_rows) = decode_fetch(request).unwrap();
+                let (rows, position) = portals.get_mut(&id).expect("fetch from a portal not open");
+                let end = (*position + max_rows as usize).min(rows.len());
+                let batch = &rows[*position..end];
+                *position = end;
+                encode_batch(id, end == rows.len(), batch).unwrap()
+            }
+            PORTAL_CLOSE => {
+                let id = u64::from_be_bytes(request.try_into().unwrap());
+                portals.remove(&id).expect("close of a portal not open");
+                closed_log.lock().unwrap().push(id);
+                id.to_be_bytes().to_vec()
+            }
+            other => panic!("unexpected message type {}", other),
+        };
+        socket.write_all(&frame(&reply)).await?;
+    }
+}
+
+async fn connect(closed_log: ClosedLog) -> AuroraConnection {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, closed_log.clone()));
+        }
+    });
+
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_interleaved_portals_return_their_own_rows() {
+    let closed_log = ClosedLog::default();
+    let mut conn = connect(closed_log.clone()).await;
+    let mut a = conn.open_portal("SELECT n FROM generate_series(1, 120) n").await.unwrap();
+    let mut b = conn.open_portal("SELECT n FROM generate_series(1001, 1075) n").await.unwrap();
+    assert_ne!(a.id(), b.id());
+
+    // First page of each, then more of each as the user scrolls
+    let mut from_a = Vec::new();
+    let mut from_b = Vec::new();
+    from_a.extend(numbers(&conn.fetch(&mut a, 50).await.unwrap()));
+    from_b.extend(numbers(&conn.fetch(&mut b, 50).await.unwrap()));
+    from_a.extend(numbers(&conn.fetch(&mut a, 50).await.unwrap()));
+    assert_eq!((from_a.len(), from_b.len()), (100, 50));
+    assert!(!a.is_exhausted() && !b.is_exhausted());
+
+    from_b.extend(numbers(&conn.fetch(&mut b, 50).await.unwrap()));
+    assert!(b.is_exhausted());
+    assert!(conn.fetch(&mut b, 50).await.unwrap().is_empty());
+    from_a.extend(numbers(&conn.fetch(&mut a, 50).await.unwrap()));
+    assert!(a.is_exhausted());
+
+    assert_eq!(from_a, (1..=120).collect::<Vec<_>>());
+    assert_eq!(from_b, (1001..=1075).collect::<Vec<_>>());
+    assert_eq!((a.fetched(), b.fetched()), (120, 75));
+
+    // A dropped portal is closed before the connection's next portal request
+    let b_id = b.id();
+    drop(b);
+    assert!(closed_log.lock().unwrap().is_empty());
+    let mut c = conn.open_portal("SELECT n FROM generate_series(1001, 1075) n").await.unwrap();
+    assert_eq!(*closed_log.lock().unwrap(), vec![b_id]);
+    assert_eq!(numbers(&conn.fetch(&mut c, 10).await.unwrap()), (1001..=1010).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_portal_is_refused_by_another_connection() {
+    let closed_log = ClosedLog::default();
+    let mut first = connect(closed_log.clone()).await;
+    let mut second = connect(closed_log.clone()).await;
+    let mut portal = first.open_portal("SELECT n FROM generate_series(1, 120) n").await.unwrap();
+    assert!(second.fetch(&mut portal, 10).await.is_err());
+    assert_eq!(numbers(&first.fetch(&mut portal, 3).await.unwrap()), vec![1, 2, 3]);
+}
@@ -0,0 +1,262 @@
+//! Prepared Statement Tests
+//!
+//! Prepares statements against an in-process server that records every
+//! message it receives, and checks each SQL text is parsed once, executions
+//! send only the statement name, and statements evicted from the cache are
+//! closed on the server.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::connection::FRAME_HEADER_LEN;
+use aurora_drivers::prepared::{ExecutePreparedRequest, ExecutePreparedResponse, PrepareRequest, PrepareResponse};
+use aurora_drivers::types::{AuroraColumn, AuroraRow, AuroraType, AuroraValue, QueryResult};
+use aurora_drivers::{AuroraConnection, AuroraError};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const PREPARE_STATEMENT: u8 = 22;
+const EXECUTE_PREPARED: u8 = 23;
+const CLOSE_PREPARED: u8 = 24;
+
+const BALANCE: &str = "SELECT balance FROM accounts WHERE name = $1";
+const OWNER: &str = "SELECT owner FROM accounts WHERE id = $1";
+const COUNT: &str = "SELECT count(*) FROM accounts";
+
+/// What the server has seen and still holds
+#[derive(Default)]
+struct Server {
+    /// Message type and statement name or SQL of each request, in order
+    received: Vec<(u8, String)>,
+    /// SQL of each open statement by name
+    statements: BTreeMap<String, String>,
+}
+
+type Shared = Arc<Mutex<Server>>;
+
+fn column(name: &str, column_type: AuroraType) -> AuroraColumn {
+    AuroraColumn {
+        name: name.to_string(),
+        column_type,
+        nullable: false,
+        default_value: None,
+        primary_key: false,
+        auto_increment: false,
+        comment: None,
+    }
+}
+
+/// Describe a statement, or say why it cannot be prepared
+fn describe(sql: &str) -> Result<Vec<AuroraColumn>, String> {
+    match sql {
+        BALANCE => Ok(vec![column("balance", AuroraType::BigInt)]),
+        OWNER => Ok(vec![column("owner", AuroraType::Text)]),
+        COUNT => Ok(vec![column("count", AuroraType::BigInt)]),
+        other => Err(format!("syntax error at or near \"{}\"", other.split(' ').next().unwrap_or_default())),
+    }
+}
+
+fn handle(server: &Shared, message_type: u8, data: &[u8]) -> Vec<u8> {
+    let mut server = server.lock().unwrap();
+    match message_type {
+        PREPARE_STATEMENT => {
+            let request: PrepareRequest = bincode::deserialize(data).unwrap();
+            server.received.push((message_type, request.sql.clone()));
+            let response = match describe(&request.sql) {
+                Ok(columns) => {
+                    server.statements.insert(request.name.clone(), request.sql);
+                    PrepareResponse { name: request.name, columns, error: None }
+                }
+                Err(error) => PrepareResponse { name: request.name, columns: Vec::new(), error: Some(error) },
+            };
+            bincode::serialize(&response).unwrap()
+        }
+        EXECUTE_PREPARED => {
+            let request: ExecutePreparedRequest = bincode::deserialize(data).unwrap();
+            server.received.push((message_type, request.name.clone()));
+            let response = match server.statements.get(&request.name) {
+                // Each row echoes the parameters back
+                Some(_) => ExecutePreparedResponse {
+                    result: Some(QueryResult {
+                        rows: vec![AuroraRow { values: request.params, columns: None }],
+                        columns: Vec::new(),
+                        row_count: 1,
+                        execution_time_ms: 0.0,
+                        query_id: String::new(),
+                    }),
+                    error: None,
+                },
+                None => ExecutePreparedResponse {
+                    result: None,
+                    error: Some(format!("prepared statement \"{}\" does not exist", request.name)),
+                },
+            };
+            bincode::serialize(&response).unwrap()
+        }
+        CLOSE_PREPARED => {
+            let name = String::from_utf8(data.to_vec()).unwrap();
+            server.received.push((message_type, name.clone()));
+            server.statements.remove(&name);
+            name.into_bytes()
+        }
+        other => panic!("unexpected message type {}", other),
+    }
+}
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + 4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream, server: Shared) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+
+        let response = handle(&server, header[4], &body[..len]);
+        socket.write_all(&frame(&response)).await?;
+    }
+}
+
+async fn connect(server: Shared, max_prepared_statements: usize) -> AuroraConnection {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, server.clone()));
+        }
+    });
+
+    let mut config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    config.advanced.prepared_statement_cache.max_size = max_prepared_statements;
+    assert_eq!(config.max_prepared_statements(), max_prepared_statements);
+    AuroraConnection::new(config).await.unwrap()
+}
+
+fn received(server: &Shared) -> Vec<(u8, String)> {
+    std::mem::take(&mut server.lock().unwrap().received)
+}
+
+fn open_statements(server: &Shared) -> Vec<String> {
+    server.lock().unwrap().statements.values().cloned().collect()
+}
+
+#[tokio::test]
+async fn test_statement_is_parsed_once_and_executed_by_name() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone(), 8).await;
+
+    let statement = conn.prepare(BALANCE).await.unwrap();
+    assert_eq!(statement.sql(), BALANCE);
+    assert_eq!(statement.columns().iter().map(|column| column.name.as_str()).collect::<Vec<_>>(), vec!["balance"]);
+
+    // Preparing the same SQL again is answered from the cache
+    let again = conn.prepare(BALANCE).await.unwrap();
+    assert_eq!(again.name(), statement.name());
+
+    for name in ["alice", "bob"] {
+        let result = conn.execute_prepared(&statement, &[AuroraValue::Text(name.to_string())]).await.unwrap();
+        assert_eq!(result.rows[0].values, vec![AuroraValue::Text(name.to_string())]);
+    }
+
+    assert_eq!(received(&server), vec![
+        (PREPARE_STATEMENT, BALANCE.to_string()),
+        (EXECUTE_PREPARED, statement.name().to_string()),
+        (EXECUTE_PREPARED, statement.name().to_string()),
+    ]);
+    assert_eq!(conn.prepared_statement_count(), 1);
+}
+
+#[tokio::test]
+async fn test_statement_the_server_rejects_is_not_cached() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone(), 8).await;
+
+    for _ in 0..2 {
+        match conn.prepare("SELEC balance FROM accounts").await.unwrap_err() {
+            AuroraError::Query(message) => assert!(message.contains("syntax error at or near \"SELEC\""), "{}", message),
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+    assert_eq!(received(&server).len(), 2);
+    assert_eq!(conn.prepared_statement_count(), 0);
+
+    // The connection is fine for the next statement
+    let statement = conn.prepare(COUNT).await.unwrap();
+    assert!(conn.execute_prepared(&statement, &[]).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_least_recently_used_statement_is_closed_on_eviction() {
+    let server = Shared::default();
+    let mut conn = connect(server.clone(), 2).await;
+
+    let balance = conn.prepare(BALANCE).await.unwrap();
+    let owner = conn.prepare(OWNER).await.unwrap();
+    // Using BALANCE leaves OWNER least recently used
+    conn.execute_prepared(&balance, &[AuroraValue::Text("alice".to_string())]).await.unwrap();
+    received(&server);
+
+    let count = conn.prepare(COUNT).await.unwrap();
+    assert_eq!(received(&server), vec![
+        (CLOSE_PREPARED, owner.name().to_string()),
+        (PREPARE_STATEMENT, COUNT.to_string()),
+    ]);
+    assert_eq!(open_statements(&server), vec![BALANCE.to_string(), COUNT.to_string()]);
+    assert_eq!(conn.prepared_statement_count(), 2);
+
+    // An evicted handle is prepared again under a new name, evicting BALANCE
+    let result = conn.execute_prepared(&owner, &[AuroraValue::BigInt(7)]).await.unwrap();
+    assert_eq!(result.rows[0].values, vec![AuroraValue::BigInt(7)]);
+    let requests = received(&server);
+    assert_eq!(requests[..2], [
+        (CLOSE_PREPARED, balance.name().to_string()),
+        (PREPARE_STATEMENT, OWNER.to_string()),
+    ]);
+    assert_eq!(requests[2].0, EXECUTE_PREPARED);
+    assert_ne!(requests[2].1, owner.name());
+    assert_eq!(open_statements(&server), vec![COUNT.to_string(), OWNER.to_string()]);
+    assert!(conn.execute_prepared(&count, &[]).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_concurrent_prepares_of_the_same_sql_share_one_round_trip() {
+    let server = Shared::default();
+    let conn = Arc::new(tokio::sync::Mutex::new(connect(server.clone(), 8).await));
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let conn = conn.clone();
+            tokio::spawn(async move { conn.lock().await.prepare(BALANCE).await.unwrap() })
+        })
+        .collect();
+    let mut names = Vec::new();
+    for task in tasks {
+        names.push(task.await.unwrap().name().to_string());
+    }
+
+    names.dedup();
+    assert_eq!(names.len(), 1);
+    assert_eq!(received(&server), vec![(PREPARE_STATEMENT, BALANCE.to_string())]);
+}
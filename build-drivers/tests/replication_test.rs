@@ -0,0 +1,211 @@
+//! Replica Routing Tests
+//!
+//! Runs a primary and two replicas as in-process servers speaking the framed
+//! protocol. Each answers queries with its own name as the `query_id`, so a
+//! test can tell which node served a read, and each replica reports the LSN
+//! the test says it has replayed.
+
+use aurora_drivers::config::{AuroraConfig, PoolConfig, ReplicaRoutingConfig};
+use aurora_drivers::{ExecuteResult, Freshness, Lsn, QueryResult, ReadTarget, ReplicaRouter, ReplicaSet, ReplicationStatus};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const QUERY: u8 = 1;
+const EXECUTE: u8 = 2;
+const REPLICATION_STATUS: u8 = 11;
+
+/// Replay position and lag a stand-in node reports
+struct Node {
+    name: &'static str,
+    lsn: AtomicU64,
+    lag_ms: AtomicU64,
+}
+
+impl Node {
+    fn new(name: &'static str, lsn: u64, lag_ms: u64) -> Arc<Self> {
+        Arc::new(Self { name, lsn: AtomicU64::new(lsn), lag_ms: AtomicU64::new(lag_ms) })
+    }
+
+    fn replay(&self, lsn: u64, lag_ms: u64) {
+        self.lsn.store(lsn, Ordering::SeqCst);
+        self.lag_ms.store(lag_ms, Ordering::SeqCst);
+    }
+
+    fn respond(&self, message_type: u8) -> Vec<u8> {
+        match message_type {
+            EXECUTE => {
+                let lsn = self.lsn.fetch_add(1, Ordering::SeqCst) + 1;
+                bincode::serialize(&ExecuteResult {
+                    rows_affected: 1,
+                    last_insert_id: None,
+                    execution_time_ms: 0.0,
+                    statement_id: "s".to_string(),
+                    commit_lsn: Some(Lsn(lsn)),
+                })
+            }
+            REPLICATION_STATUS => bincode::serialize(&ReplicationStatus {
+                replay_lsn: Lsn(self.lsn.load(Ordering::SeqCst)),
+                lag_ms: self.lag_ms.load(Ordering::SeqCst),
+            }),
+            _ => bincode::serialize(&QueryResult {
+                rows: Vec::new(),
+                columns: Vec::new(),
+                row_count: 0,
+                execution_time_ms: 0.0,
+                query_id: self.name.to_string(),
+            }),
+        }.unwrap()
+    }
+}
+
+fn frame(message_type: u8, data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(message_type);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream, node: Arc<Node>) -> std::io::Result<()> {
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; 13];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+
+        let message_type = header[4];
+        socket.write_all(&frame(message_type, &node.respond(message_type))).await?;
+    }
+}
+
+async fn start_node(node: Arc<Node>) -> AuroraConfig {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, node.clone()));
+        }
+    });
+
+    AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        pool: PoolConfig {
+            max_connections: 2,
+            min_connections: 1,
+            max_idle_time: Duration::from_secs(300),
+            max_lifetime: Duration::from_secs(3600),
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+            adaptive: None,
+            circuit_breaker: None,
+            priority_aging: Duration::from_secs(1),
+            leak_detection: None,
+        },
+        ..AuroraConfig::default()
+    }
+}
+
+fn routing() -> ReplicaRoutingConfig {
+    ReplicaRoutingConfig {
+        // Status is refreshed by the tests through `refresh_status`
+        status_interval: Duration::from_secs(3600),
+        max_status_age: Duration::from_secs(3600),
+        max_lag: None,
+    }
+}
+
+async fn served_by(set: &ReplicaSet, freshness: Freshness) -> String {
+    set.query("SELECT balance FROM accounts WHERE id = 7", &[], freshness).await.unwrap().query_id
+}
+
+#[tokio::test]
+async fn test_read_requiring_write_lsn_avoids_lagging_replica() {
+    let primary = Node::new("primary", 100, 0);
+    let caught_up = Node::new("caught_up", 100, 0);
+    let lagging = Node::new("lagging", 90, 5_000);
+    let set = ReplicaSet::connect(
+        start_node(primary.clone()).await,
+        vec![start_node(lagging.clone()).await, start_node(caught_up.clone()).await],
+        routing(),
+    ).await.unwrap();
+
+    let write = set.execute("UPDATE accounts SET balance = 10 WHERE id = 7", &[]).await.unwrap();
+    let lsn = write.commit_lsn.unwrap();
+    assert_eq!(lsn, Lsn(101));
+
+    // One replica replays the write, the other stays behind
+    caught_up.replay(101, 0);
+    set.refresh_status().await;
+
+    for _ in 0..4 {
+        assert_eq!(served_by(&set, Freshness::AtLeast(lsn)).await, "caught_up");
+    }
+
+    // Reads without a requirement still use both replicas
+    let mut served = vec![served_by(&set, Freshness::Any).await, served_by(&set, Freshness::Any).await];
+    served.sort();
+    assert_eq!(served, vec!["caught_up", "lagging"]);
+
+    // Once no replica has the write, the read goes to the primary
+    let write = set.execute("UPDATE accounts SET balance = 20 WHERE id = 7", &[]).await.unwrap();
+    assert_eq!(served_by(&set, Freshness::AtLeast(write.commit_lsn.unwrap())).await, "primary");
+    assert_eq!(set.primary_reads().await, 1);
+
+    let lag = set.replica_lag().await;
+    assert_eq!(lag[0].replay_lsn, Some(Lsn(90)));
+    assert_eq!(lag[0].lag, Some(Duration::from_secs(5)));
+    assert_eq!(lag[0].reads_routed, 1);
+    assert_eq!(lag[1].replay_lsn, Some(Lsn(101)));
+    assert_eq!(lag[1].reads_routed, 5);
+    assert!(lag.iter().all(|replica| replica.reachable));
+
+    set.close().await.unwrap();
+}
+
+#[test]
+fn test_router_skips_stale_unknown_and_lagging_replicas() {
+    let names = vec!["a".to_string(), "b".to_string()];
+    let status = |lsn, lag_ms| ReplicationStatus { replay_lsn: Lsn(lsn), lag_ms };
+
+    // No status yet: nothing is known to be fresh
+    let mut router = ReplicaRouter::new(routing(), names.clone());
+    assert_eq!(router.route(Freshness::Any), ReadTarget::Primary);
+
+    // Beyond max_lag a replica serves no reads, but can still satisfy an LSN
+    let config = ReplicaRoutingConfig { max_lag: Some(Duration::from_secs(1)), ..routing() };
+    let mut router = ReplicaRouter::new(config, names.clone());
+    router.record_status(0, status(50, 10_000));
+    router.record_status(1, status(40, 0));
+    assert_eq!(router.route(Freshness::Any), ReadTarget::Replica(1));
+    assert_eq!(router.route(Freshness::AtLeast(Lsn(45))), ReadTarget::Replica(0));
+    assert_eq!(router.route(Freshness::AtLeast(Lsn(51))), ReadTarget::Primary);
+
+    // An unreachable replica is skipped until a poll succeeds again
+    router.record_unreachable(1);
+    assert_eq!(router.route(Freshness::Any), ReadTarget::Primary);
+    router.record_status(1, status(40, 0));
+    assert_eq!(router.route(Freshness::Any), ReadTarget::Replica(1));
+
+    // Status older than max_status_age is not trusted
+    let config = ReplicaRoutingConfig { max_status_age: Duration::ZERO, ..routing() };
+    let mut router = ReplicaRouter::new(config, names);
+    router.record_status(0, status(50, 0));
+    std::thread::sleep(Duration::from_millis(2));
+    assert_eq!(router.route(Freshness::AtLeast(Lsn(1))), ReadTarget::Primary);
+}
@@ -0,0 +1,204 @@
+//! Schema Introspection Tests
+//!
+//! A stand-in server answers `Introspect` requests from a catalog holding a
+//! `products` table with decimal, vector, array and jsonb columns, its
+//! indexes and its constraints. Every modifier and constraint the catalog
+//! states must come through in the decoded schema.
+
+use aurora_drivers::{
+    decode_schema, AuroraError, AuroraType, AuroraValue, ColumnDefault, ConstraintType, IndexType, IntrospectRequest,
+    TableType,
+};
+use serde_json::{json, Value};
+
+/// Catalog of a small shop database
+struct StandInServer {
+    tables: Vec<Value>,
+}
+
+impl StandInServer {
+    fn new() -> Self {
+        let vendors = json!({
+            "name": "vendors",
+            "kind": "table",
+            "row_estimate": 40,
+            "columns": [
+                {"name": "id", "type": "int", "nullable": false, "identity": true},
+                {"name": "name", "type": "varchar(120)", "nullable": false},
+            ],
+            "indexes": [{"name": "vendors_pkey", "method": "btree", "columns": ["id"], "unique": true}],
+            "constraints": [{"name": "vendors_pkey", "kind": "primary_key", "columns": ["id"]}],
+        });
+        let products = json!({
+            "name": "products",
+            "kind": "table",
+            "row_estimate": 125000,
+            "size_bytes": 48234496,
+            "columns": [
+                {"name": "id", "type": "bigint", "nullable": false, "identity": true},
+                {"name": "sku", "type": "CHAR(12)", "nullable": false, "comment": "Vendor stock code"},
+                {"name": "title", "type": "character varying(200)", "nullable": false, "default": "'untitled'"},
+                {"name": "price", "type": "decimal(12,2)", "nullable": false, "default": "0.00"},
+                {"name": "weight_kg", "type": "numeric(6)", "nullable": true},
+                {"name": "embedding", "type": "vector(768)", "nullable": true},
+                {"name": "tags", "type": "text[]", "nullable": false, "default": "'{}'::text[]"},
+                {"name": "price_history", "type": "decimal(12,2)[]", "nullable": true},
+                {"name": "attributes", "type": "jsonb", "nullable": false, "default": "'{\"color\": null}'"},
+                {"name": "dimensions", "type": "map<varchar(16),decimal(8,3)>", "nullable": true},
+                {"name": "vendor_id", "type": "int", "nullable": false},
+                {"name": "in_stock", "type": "boolean", "nullable": false, "default": "TRUE"},
+                {"name": "created_at", "type": "timestamp with time zone", "nullable": false, "default": "now()"},
+                {"name": "discontinued_at", "type": "timestamptz", "nullable": true, "default": "NULL"},
+            ],
+            "indexes": [
+                {"name": "products_pkey", "method": "btree", "columns": ["id"], "unique": true, "size_bytes": 2809856},
+                {"name": "products_sku_key", "method": "btree", "columns": ["vendor_id", "sku"], "unique": true},
+                {"name": "products_embedding_idx", "method": "hnsw", "columns": ["embedding"]},
+                {"name": "products_tags_idx", "method": "gin", "columns": ["tags"]},
+            ],
+            "constraints": [
+                {"name": "products_pkey", "kind": "primary_key", "columns": ["id"]},
+                {"name": "products_sku_key", "kind": "unique", "columns": ["vendor_id", "sku"]},
+                {"name": "products_price_check", "kind": "check", "columns": ["price"], "expression": "price >= 0"},
+                {
+                    "name": "products_vendor_id_fkey",
+                    "kind": "foreign_key",
+                    "columns": ["vendor_id"],
+                    "references": {"table": "vendors", "columns": ["id"]},
+                },
+            ],
+        });
+        Self { tables: vec![vendors, products] }
+    }
+
+    /// Reply to the body of an `Introspect` message
+    fn introspect(&self, request: &[u8]) -> Vec<u8> {
+        let request: IntrospectRequest = serde_json::from_slice(request).unwrap();
+        let tables: Vec<&Value> = self.tables.iter()
+            .filter(|table| request.table.as_deref().is_none_or(|name| table["name"] == name))
+            .collect();
+        serde_json::to_vec(&json!({"database": "shop", "tables": tables})).unwrap()
+    }
+}
+
+fn request(table: Option<&str>) -> Vec<u8> {
+    serde_json::to_vec(&IntrospectRequest { table: table.map(str::to_string) }).unwrap()
+}
+
+#[test]
+fn test_column_types_keep_their_modifiers() {
+    let server = StandInServer::new();
+    let schema = decode_schema(&server.introspect(&request(Some("products")))).unwrap();
+    assert_eq!(schema.database_name, "shop");
+    assert_eq!(schema.tables.len(), 1);
+
+    let products = schema.table("products").unwrap();
+    assert_eq!(products.table_type, TableType::Table);
+    assert_eq!(products.row_count, Some(125_000));
+    assert_eq!(products.size_bytes, Some(48_234_496));
+
+    let column_type = |name: &str| products.column(name).unwrap().column_type.clone();
+    assert_eq!(column_type("id"), AuroraType::BigInt);
+    assert_eq!(column_type("sku"), AuroraType::Char(12));
+    assert_eq!(column_type("title"), AuroraType::Varchar(200));
+    assert_eq!(column_type("price"), AuroraType::Decimal(12, 2));
+    assert_eq!(column_type("weight_kg"), AuroraType::Decimal(6, 0));
+    assert_eq!(column_type("embedding"), AuroraType::Vector(768));
+    assert_eq!(column_type("tags"), AuroraType::Array(Box::new(AuroraType::Text)));
+    assert_eq!(column_type("price_history"), AuroraType::Array(Box::new(AuroraType::Decimal(12, 2))));
+    assert_eq!(column_type("attributes"), AuroraType::Jsonb);
+    assert_eq!(
+        column_type("dimensions"),
+        AuroraType::Map(Box::new(AuroraType::Varchar(16)), Box::new(AuroraType::Decimal(8, 3)))
+    );
+    assert_eq!(column_type("created_at"), AuroraType::TimestampTz);
+
+    // Columns stay in table order
+    let names: Vec<&str> = products.columns.iter().map(|column| column.name.as_str()).collect();
+    assert_eq!(names[..4], ["id", "sku", "title", "price"]);
+
+    // Every type reads back from the name it renders as
+    for column in &products.columns {
+        assert_eq!(AuroraType::from_sql_name(&column.column_type.to_string()).unwrap(), column.column_type);
+    }
+}
+
+#[test]
+fn test_nullability_defaults_and_keys() {
+    let server = StandInServer::new();
+    let schema = decode_schema(&server.introspect(&request(Some("products")))).unwrap();
+    let products = schema.table("products").unwrap();
+
+    let id = products.column("id").unwrap();
+    assert!(id.primary_key && id.auto_increment && !id.nullable);
+    assert_eq!(id.default, None);
+    assert_eq!(products.primary_key, ["id"]);
+    assert!(products.columns.iter().filter(|column| column.name != "id").all(|column| !column.primary_key));
+    assert_eq!(products.column("sku").unwrap().comment.as_deref(), Some("Vendor stock code"));
+    assert!(products.column("embedding").unwrap().nullable);
+    assert!(!products.column("attributes").unwrap().nullable);
+
+    let default = |name: &str| products.column(name).unwrap().default.clone();
+    assert_eq!(default("title"), Some(ColumnDefault::Value(AuroraValue::Text("untitled".to_string()))));
+    assert_eq!(default("price"), Some(ColumnDefault::Value(AuroraValue::decimal("0.00").unwrap())));
+    assert_eq!(default("in_stock"), Some(ColumnDefault::Value(AuroraValue::Bool(true))));
+    assert_eq!(default("attributes"), Some(ColumnDefault::Value(AuroraValue::Json(json!({"color": null})))));
+    assert_eq!(default("discontinued_at"), Some(ColumnDefault::Value(AuroraValue::Null)));
+    // Anything beyond a plain literal is reported as the server wrote it
+    assert_eq!(default("created_at"), Some(ColumnDefault::Expression("now()".to_string())));
+    assert_eq!(default("tags"), Some(ColumnDefault::Expression("'{}'::text[]".to_string())));
+    assert_eq!(default("weight_kg"), None);
+}
+
+#[test]
+fn test_indexes_and_constraints() {
+    let server = StandInServer::new();
+    let schema = decode_schema(&server.introspect(&request(None))).unwrap();
+    assert_eq!(schema.tables.len(), 2);
+    assert_eq!(schema.table("vendors").unwrap().primary_key, ["id"]);
+
+    let indexes: Vec<_> = schema.indexes_on("products").collect();
+    assert_eq!(indexes.len(), 4);
+    let index = |name: &str| indexes.iter().find(|index| index.name == name).unwrap();
+    assert_eq!(index("products_pkey").index_type, IndexType::BTree);
+    assert_eq!(index("products_pkey").size_bytes, Some(2_809_856));
+    assert!(index("products_sku_key").unique);
+    assert_eq!(index("products_sku_key").columns, ["vendor_id", "sku"]);
+    assert_eq!(index("products_embedding_idx").index_type, IndexType::Vector);
+    assert!(!index("products_embedding_idx").unique);
+    assert_eq!(index("products_tags_idx").index_type, IndexType::Gin);
+
+    let constraints: Vec<_> = schema.constraints_on("products").collect();
+    assert_eq!(constraints.len(), 4);
+    let constraint = |name: &str| constraints.iter().find(|constraint| constraint.name == name).unwrap();
+    assert_eq!(constraint("products_pkey").constraint_type, ConstraintType::PrimaryKey);
+    assert_eq!(constraint("products_sku_key").constraint_type, ConstraintType::Unique);
+    assert_eq!(constraint("products_sku_key").columns, ["vendor_id", "sku"]);
+
+    let check = constraint("products_price_check");
+    assert_eq!(check.constraint_type, ConstraintType::Check);
+    assert_eq!(check.check_expression.as_deref(), Some("price >= 0"));
+
+    let foreign_key = constraint("products_vendor_id_fkey");
+    assert_eq!(foreign_key.constraint_type, ConstraintType::ForeignKey);
+    assert_eq!(foreign_key.columns, ["vendor_id"]);
+    assert_eq!(foreign_key.referenced_table.as_deref(), Some("vendors"));
+    assert_eq!(foreign_key.referenced_columns.as_deref(), Some(&["id".to_string()][..]));
+    assert_eq!(foreign_key.check_expression, None);
+}
+
+#[test]
+fn test_imprecise_or_unknown_types_are_refused() {
+    for name in ["varchar", "decimal", "decimal(4,6)", "vector(0)", "int(4)", "geometry", "decimal(12,2"] {
+        assert!(matches!(AuroraType::from_sql_name(name), Err(AuroraError::Serialization(_))), "{}", name);
+    }
+
+    let reply = json!({"database": "shop", "tables": [{
+        "name": "shapes",
+        "kind": "table",
+        "columns": [{"name": "outline", "type": "geometry", "nullable": true}],
+    }]});
+    let error = decode_schema(&serde_json::to_vec(&reply).unwrap()).unwrap_err();
+    assert!(error.to_string().contains("Column \"shapes\".\"outline\""), "{}", error);
+    assert!(matches!(decode_schema(b"not json"), Err(AuroraError::Protocol(_))));
+}
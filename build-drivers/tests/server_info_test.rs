@@ -0,0 +1,98 @@
+//! Server Capability Tests
+//!
+//! Runs in-process servers that answer authentication with a fixed
+//! handshake, one advertising a version and capability set and one replying
+//! like a server from before capabilities were advertised, and checks what
+//! the connection reports.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::{AuroraConnection, ServerFeature, ServerInfo};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const ADVERTISING: &[u8] = b"OK\0server_version=2.4.1\0protocol_version=1\0\
+    features=vector_search,copy_binary,compression,zstd_dictionaries\0compression=lz4\0max_message_size=1048576\0";
+
+/// Start a server that answers every authentication with `handshake`
+async fn start_server(handshake: &'static [u8]) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                // Authentication is a single unframed message
+                let mut auth = [0u8; 1024];
+                if socket.read(&mut auth).await.unwrap_or(0) > 0 {
+                    socket.write_all(handshake).await.ok();
+                }
+                // Hold the connection open until the client goes away
+                let _ = socket.read(&mut auth).await;
+            });
+        }
+    });
+    port
+}
+
+async fn connect(port: u16) -> AuroraConnection {
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_handshake_capabilities_are_reported() {
+    let port = start_server(ADVERTISING).await;
+    let conn = connect(port).await;
+
+    let info = conn.server_info();
+    assert_eq!(info.version.as_deref(), Some("2.4.1"));
+    assert_eq!(info.protocol_version, Some(1));
+    assert!(info.supports_vector_search());
+    assert!(info.supports_copy_binary());
+    assert!(info.supports_compression());
+    assert!(!info.supports_analytics_streaming());
+    assert!(info.supports(&ServerFeature::Other("zstd_dictionaries".to_string())));
+    assert_eq!(info.option("compression"), Some("lz4"));
+    assert_eq!(info.option("max_message_size"), Some("1048576"));
+    assert_eq!(info.option("server_version"), None);
+
+    assert!(info.version_at_least(2, 4, 0));
+    assert!(info.version_at_least(2, 4, 1));
+    assert!(!info.version_at_least(2, 5, 0));
+}
+
+#[tokio::test]
+async fn test_legacy_server_supports_nothing() {
+    let port = start_server(b"OK").await;
+    let conn = connect(port).await;
+
+    let info = conn.server_info();
+    assert_eq!(info, ServerInfo::default());
+    assert_eq!(info.version, None);
+    assert!(!info.supports_vector_search());
+    assert!(!info.supports_analytics_streaming());
+    assert!(!info.supports_copy_binary());
+    assert!(!info.supports_compression());
+    assert!(!info.version_at_least(0, 0, 1));
+}
+
+#[test]
+fn test_handshake_parsing() {
+    let info = ServerInfo::from_handshake(b"OK\0server_version=3.0.0-rc1\0features= vector_search , \0").unwrap();
+    assert_eq!(info.features.len(), 1);
+    assert!(info.supports_vector_search());
+    assert!(info.version_at_least(3, 0, 0));
+    assert!(info.options.is_empty());
+
+    // Unnumbered versions compare as unknown
+    let info = ServerInfo::from_handshake(b"OK\0server_version=nightly\0").unwrap();
+    assert!(!info.version_at_least(0, 0, 0));
+
+    assert!(ServerInfo::from_handshake(b"OK\0vector_search\0").is_err());
+    assert!(ServerInfo::from_handshake(b"OK\0protocol_version=two\0").is_err());
+    assert!(ServerInfo::from_handshake(&[b'O', b'K', 0, 0xff]).is_err());
+}
@@ -0,0 +1,187 @@
+//! Session Variable Tests
+//!
+//! Runs an in-process server that keeps SET/RESET state per connection and
+//! answers `SHOW name` with the setting as the `query_id`, so a test can
+//! see what the server's session holds. `SELECT disconnect` makes the
+//! server drop the connection, forcing the driver to reconnect.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::{AuroraConnection, AuroraProtocol, ExecuteRequest, ExecuteResult, QueryRequest, QueryResult};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const QUERY: u8 = 1;
+const EXECUTE: u8 = 2;
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+/// Apply a `SET name = 'value'`, `RESET name` or `RESET ALL` to a session
+fn apply(session: &mut HashMap<String, String>, sql: &str) {
+    if let Some(assignment) = sql.strip_prefix("SET ") {
+        let (name, value) = assignment.split_once(" = ").unwrap();
+        let value = value.trim_matches('\'').replace("''", "'");
+        session.insert(name.to_string(), value);
+    } else if sql == "RESET ALL" {
+        session.clear();
+    } else if let Some(name) = sql.strip_prefix("RESET ") {
+        session.remove(name);
+    }
+}
+
+async fn serve(mut socket: TcpStream) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    let mut session = HashMap::new();
+    loop {
+        let mut header = [0u8; 13];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+
+        let data = match header[4] {
+            EXECUTE => {
+                let request: ExecuteRequest = bincode::deserialize(&body[..len]).unwrap();
+                apply(&mut session, &request.sql);
+                bincode::serialize(&ExecuteResult {
+                    rows_affected: 0,
+                    last_insert_id: None,
+                    execution_time_ms: 0.0,
+                    statement_id: request.sql,
+                    commit_lsn: None,
+                })
+            }
+            QUERY => {
+                let request: QueryRequest = bincode::deserialize(&body[..len]).unwrap();
+                if request.sql == "SELECT disconnect" {
+                    return Ok(());
+                }
+                let setting = request.sql.strip_prefix("SHOW ")
+                    .and_then(|name| session.get(name).cloned())
+                    .unwrap_or_default();
+                bincode::serialize(&QueryResult {
+                    rows: Vec::new(),
+                    columns: Vec::new(),
+                    row_count: 0,
+                    execution_time_ms: 0.0,
+                    query_id: setting,
+                })
+            }
+            other => panic!("unexpected message type {}", other),
+        }.unwrap();
+        socket.write_all(&frame(&data)).await?;
+    }
+}
+
+/// Start the server, returning its port and a count of accepted connections
+async fn start_server() -> (u16, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let accepted = Arc::new(AtomicUsize::new(0));
+
+    let counter = accepted.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            counter.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(serve(socket));
+        }
+    });
+
+    (port, accepted)
+}
+
+async fn connect(port: u16) -> AuroraConnection {
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+async fn show(protocol: &AuroraProtocol, conn: &mut AuroraConnection, name: &str) -> String {
+    protocol.execute_query(conn, &format!("SHOW {}", name)).await.unwrap().query_id
+}
+
+#[tokio::test]
+async fn test_set_persists_and_reset_clears() {
+    let (port, _) = start_server().await;
+    let mut conn = connect(port).await;
+    let protocol = AuroraProtocol::new();
+
+    conn.set("work_mem", "64MB").await.unwrap();
+    conn.set("TimeZone", "America/New_York").await.unwrap();
+    assert_eq!(conn.get("work_mem"), Some("64MB"));
+    assert_eq!(conn.get("timezone"), Some("America/New_York"));
+
+    // The settings hold for every later statement on the connection
+    for _ in 0..2 {
+        assert_eq!(show(&protocol, &mut conn, "work_mem").await, "64MB");
+        assert_eq!(show(&protocol, &mut conn, "timezone").await, "America/New_York");
+    }
+
+    // Setting again replaces the value without reordering
+    conn.set("work_mem", "128MB").await.unwrap();
+    assert_eq!(show(&protocol, &mut conn, "work_mem").await, "128MB");
+    assert_eq!(conn.session_variables()[0], ("work_mem".to_string(), "128MB".to_string()));
+
+    conn.reset("work_mem").await.unwrap();
+    assert_eq!(conn.get("work_mem"), None);
+    assert_eq!(show(&protocol, &mut conn, "work_mem").await, "");
+    assert_eq!(show(&protocol, &mut conn, "timezone").await, "America/New_York");
+
+    conn.reset_all().await.unwrap();
+    assert!(conn.session_variables().is_empty());
+    assert_eq!(show(&protocol, &mut conn, "timezone").await, "");
+
+    // Names are identifiers, so nothing can ride along with them
+    assert!(conn.set("work_mem = '1GB'; DROP TABLE users; --", "x").await.is_err());
+    assert!(conn.set("", "x").await.is_err());
+}
+
+#[tokio::test]
+async fn test_reconnect_reapplies_session_variables() {
+    let (port, accepted) = start_server().await;
+    let mut conn = connect(port).await;
+    let protocol = AuroraProtocol::new();
+
+    conn.set("statement_timeout", "5s").await.unwrap();
+    conn.set("application_name", "it's billing").await.unwrap();
+    conn.set("work_mem", "64MB").await.unwrap();
+    conn.reset("work_mem").await.unwrap();
+
+    // The server goes away mid-request; that request fails
+    assert!(protocol.execute_query(&mut conn, "SELECT disconnect").await.is_err());
+    assert_eq!(accepted.load(Ordering::SeqCst), 1);
+
+    // The next request reconnects, and the new session has the settings
+    assert_eq!(show(&protocol, &mut conn, "statement_timeout").await, "5s");
+    assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    assert_eq!(show(&protocol, &mut conn, "application_name").await, "it's billing");
+    assert_eq!(show(&protocol, &mut conn, "work_mem").await, "");
+
+    // An explicit reconnect does the same
+    conn.reconnect().await.unwrap();
+    assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    assert_eq!(show(&protocol, &mut conn, "statement_timeout").await, "5s");
+    assert_eq!(conn.get("statement_timeout"), Some("5s"));
+    assert!(conn.is_healthy().await);
+}
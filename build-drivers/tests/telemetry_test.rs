@@ -0,0 +1,142 @@
+//! Distributed Tracing Tests
+//!
+//! Spans are captured with the SDK's in-memory exporter installed as the
+//! global tracer provider. The provider is shared by every test in this file,
+//! so each test looks up its spans by the trace of its own parent span.
+
+use aurora_drivers::telemetry::statement_summary;
+
+#[test]
+fn test_statement_summary_omits_literals() {
+    assert_eq!(statement_summary("SELECT * FROM users WHERE email = 'a@b.c'"), "SELECT users");
+    assert_eq!(statement_summary("insert into orders (id, total) values (1, 9.5)"), "INSERT orders");
+    assert_eq!(statement_summary("UPDATE \"Accounts\" SET balance = 0"), "UPDATE Accounts");
+    assert_eq!(statement_summary("SELECT 1"), "SELECT");
+    assert_eq!(statement_summary("BEGIN"), "BEGIN");
+    assert_eq!(statement_summary("'secret'"), "");
+}
+
+#[cfg(feature = "opentelemetry")]
+mod spans {
+    use aurora_drivers::config::AuroraConfig;
+    use aurora_drivers::telemetry::{Operation, OperationSpan, TRACER_NAME};
+    use aurora_drivers::{AuroraConnection, AuroraError, Result};
+    use opentelemetry::trace::{FutureExt, Status, TraceContextExt, TraceId, Tracer, TracerProvider as _};
+    use opentelemetry::{global, Context, Value};
+    use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use std::sync::OnceLock;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn exporter() -> &'static InMemorySpanExporter {
+        static EXPORTER: OnceLock<InMemorySpanExporter> = OnceLock::new();
+        EXPORTER.get_or_init(|| {
+            let exporter = InMemorySpanExporter::default();
+            let provider = TracerProvider::builder()
+                .with_simple_exporter(exporter.clone())
+                .build();
+            global::set_tracer_provider(provider);
+            exporter.clone()
+        })
+    }
+
+    /// Start a span standing in for the application's request handler
+    fn parent_context() -> Context {
+        exporter();
+        let tracer = global::tracer_provider().tracer("test-app");
+        Context::current_with_span(tracer.start("handle_request"))
+    }
+
+    fn spans_in(trace_id: TraceId) -> Vec<SpanData> {
+        exporter()
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .filter(|span| span.span_context.trace_id() == trace_id)
+            .collect()
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    /// Start a server that answers every authentication message with "OK"
+    async fn start_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 || socket.write_all(b"OK").await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        port
+    }
+
+    #[tokio::test]
+    async fn test_connect_span_nests_under_caller() {
+        let port = start_server().await;
+        let cx = parent_context();
+        let parent = cx.span().span_context().clone();
+
+        let config = AuroraConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            ssl_mode: "disable".to_string(),
+            ..AuroraConfig::default()
+        };
+        let _conn = AuroraConnection::new(config).with_context(cx.clone()).await.unwrap();
+
+        let spans = spans_in(parent.trace_id());
+        let connect = spans.iter().find(|span| span.name == "aurora.connect").expect("connect span");
+        assert_eq!(connect.parent_span_id, parent.span_id());
+        assert_eq!(connect.instrumentation_lib.name, TRACER_NAME);
+        assert_eq!(attribute(connect, "db.system"), Some(Value::from("auroradb")));
+        assert_eq!(attribute(connect, "server.address"), Some(Value::from("127.0.0.1")));
+        assert_eq!(attribute(connect, "server.port"), Some(Value::I64(port as i64)));
+        assert_eq!(connect.status, Status::Unset);
+    }
+
+    #[tokio::test]
+    async fn test_operation_span_records_rows_and_errors() {
+        let cx = parent_context();
+        let parent = cx.span().span_context().clone();
+
+        {
+            let _guard = cx.clone().attach();
+
+            let mut query = OperationSpan::start(Operation::Query, "db1", 5433, Some("SELECT * FROM users WHERE id = 42"));
+            query.record_rows(3);
+            query.finish(&Ok::<_, AuroraError>(()));
+
+            let execute = OperationSpan::start(Operation::Execute, "db1", 5433, Some("DELETE FROM users WHERE id = 42"));
+            let failed: Result<()> = Err(AuroraError::Query("relation does not exist".into()));
+            execute.finish(&failed);
+        }
+
+        let spans = spans_in(parent.trace_id());
+        let query = spans.iter().find(|span| span.name == "SELECT users").expect("query span");
+        assert_eq!(query.parent_span_id, parent.span_id());
+        assert_eq!(attribute(query, "db.operation.name"), Some(Value::from("query")));
+        assert_eq!(attribute(query, "db.query.summary"), Some(Value::from("SELECT users")));
+        assert_eq!(attribute(query, "db.response.returned_rows"), Some(Value::I64(3)));
+        assert!(spans.iter().all(|span| span.attributes.iter().all(|kv| !kv.value.as_str().contains("42"))));
+
+        let execute = spans.iter().find(|span| span.name == "DELETE users").expect("execute span");
+        assert_eq!(execute.parent_span_id, parent.span_id());
+        assert!(matches!(execute.status, Status::Error { .. }));
+    }
+}
@@ -0,0 +1,202 @@
+//! Traffic Capture and Replay Tests
+//!
+//! Runs an in-process server that logs every statement it receives, records
+//! traffic against one instance and replays the recording against another.
+//! `SELECT disconnect` makes the server drop the connection, so a replayed
+//! request can fail.
+
+use aurora_drivers::config::AuroraConfig;
+use aurora_drivers::interceptor::InterceptorChain;
+use aurora_drivers::traffic::{parse_recording, read_recording, RecordedOperation};
+use aurora_drivers::{
+    replay, AuroraConnection, AuroraError, AuroraProtocol, AuroraValue, ExecuteRequest, ExecuteResult, QueryRequest,
+    QueryResult, ReplayOptions, TrafficRecorder,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const QUERY: u8 = 1;
+const EXECUTE: u8 = 2;
+
+type Log = Arc<Mutex<Vec<(u8, String, Vec<AuroraValue>)>>>;
+
+fn frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.push(1);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    frame.extend_from_slice(&crc32fast::hash(data).to_be_bytes());
+    frame
+}
+
+async fn serve(mut socket: TcpStream, log: Log) -> std::io::Result<()> {
+    // Authentication is a single unframed message
+    let mut auth = [0u8; 1024];
+    if socket.read(&mut auth).await? == 0 {
+        return Ok(());
+    }
+    socket.write_all(b"OK").await?;
+
+    loop {
+        let mut header = [0u8; 13];
+        socket.read_exact(&mut header).await?;
+        let len = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len + 4];
+        socket.read_exact(&mut body).await?;
+
+        let data = match header[4] {
+            EXECUTE => {
+                let request: ExecuteRequest = bincode::deserialize(&body[..len]).unwrap();
+                log.lock().unwrap().push((EXECUTE, request.sql.clone(), request.params));
+                bincode::serialize(&ExecuteResult {
+                    rows_affected: 1,
+                    last_insert_id: None,
+                    execution_time_ms: 0.0,
+                    statement_id: request.sql,
+                    commit_lsn: None,
+                })
+            }
+            QUERY => {
+                let request: QueryRequest = bincode::deserialize(&body[..len]).unwrap();
+                log.lock().unwrap().push((QUERY, request.sql.clone(), request.params));
+                if request.sql == "SELECT disconnect" {
+                    return Ok(());
+                }
+                bincode::serialize(&QueryResult {
+                    rows: Vec::new(),
+                    columns: Vec::new(),
+                    row_count: 0,
+                    execution_time_ms: 0.0,
+                    query_id: request.sql,
+                })
+            }
+            other => panic!("unexpected message type {}", other),
+        }.unwrap();
+        socket.write_all(&frame(&data)).await?;
+    }
+}
+
+/// Start the server, returning its port and the statements it received
+async fn start_server() -> (u16, Log) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let log = Log::default();
+
+    let received = log.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, received.clone()));
+        }
+    });
+
+    (port, log)
+}
+
+async fn connect(port: u16) -> AuroraConnection {
+    let config = AuroraConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        ssl_mode: "disable".to_string(),
+        ..AuroraConfig::default()
+    };
+    AuroraConnection::new(config).await.unwrap()
+}
+
+fn recording_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("aurora-traffic-{}-{}.jsonl", name, std::process::id()))
+}
+
+fn sql_of(log: &Log) -> Vec<String> {
+    log.lock().unwrap().iter().map(|(_, sql, _)| sql.clone()).collect()
+}
+
+#[tokio::test]
+async fn test_recording_round_trips_and_replays_in_order() {
+    let path = recording_path("round-trip");
+    let recorder = Arc::new(
+        TrafficRecorder::create(&path).unwrap().with_redactor(|sql, index, _| {
+            (sql.contains("password") && index == 1).then(|| AuroraValue::Text("***".to_string()))
+        }),
+    );
+    let protocol = AuroraProtocol::with_interceptors(InterceptorChain::new().with(recorder.clone()));
+
+    let (port, original) = start_server().await;
+    let mut conn = connect(port).await;
+    protocol.execute_statement_with_params(&mut conn, "INSERT INTO users (name, password) VALUES ($1, $2)",
+        &[AuroraValue::Text("ada".to_string()), AuroraValue::Text("hunter2".to_string())]).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    protocol.execute_query_with_params(&mut conn, "SELECT * FROM users WHERE id = $1", &[AuroraValue::BigInt(7)]).await.unwrap();
+    protocol.execute_query(&mut conn, "SELECT count(*) FROM users").await.unwrap();
+    recorder.flush().unwrap();
+    assert_eq!((recorder.recorded(), recorder.dropped()), (3, 0));
+
+    // The server saw the real password; the recording only the replacement
+    assert_eq!(original.lock().unwrap()[0].2[1], AuroraValue::Text("hunter2".to_string()));
+
+    let records = read_recording(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let operations: Vec<_> = records.iter().map(|record| record.operation).collect();
+    assert_eq!(operations, [RecordedOperation::Execute, RecordedOperation::Query, RecordedOperation::Query]);
+    assert_eq!(records[0].params, [AuroraValue::Text("ada".to_string()), AuroraValue::Text("***".to_string())]);
+    assert_eq!(records[1].params, [AuroraValue::BigInt(7)]);
+    assert!(records[1].offset_us >= records[0].offset_us + 100_000);
+    assert!(records.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    assert_eq!(records[2].connection_id, records[0].connection_id);
+
+    // Replayed at the recorded pace, the statements arrive in the same order with the same parameters
+    let (replay_port, replayed) = start_server().await;
+    let mut replay_conn = connect(replay_port).await;
+    let started = Instant::now();
+    let report = replay(&AuroraProtocol::new(), &mut replay_conn, &records, &ReplayOptions::default()).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert_eq!(sql_of(&replayed), sql_of(&original));
+    let params: Vec<_> = replayed.lock().unwrap().iter().map(|(_, _, params)| params.clone()).collect();
+    let recorded_params: Vec<_> = records.iter().map(|record| record.params.clone()).collect();
+    assert_eq!(params, recorded_params);
+    assert_eq!((report.issued, report.succeeded()), (3, 3));
+    assert!(report.latency.min <= report.latency.p50 && report.latency.p50 <= report.latency.max);
+
+    // Ten times faster, the gap shrinks to about 10ms
+    let report = replay(&AuroraProtocol::new(), &mut replay_conn, &records, &ReplayOptions { speed: Some(10.0), max_errors: None })
+        .await
+        .unwrap();
+    assert!(report.elapsed >= Duration::from_millis(10) && report.elapsed < Duration::from_millis(100), "{:?}", report.elapsed);
+    assert_eq!(sql_of(&replayed).len(), 6);
+}
+
+#[tokio::test]
+async fn test_replay_reports_errors() {
+    let lines = r#"
+{"offset_us":0,"timestamp":"2024-01-01T00:00:00Z","operation":"query","sql":"SELECT 1","params":[],"connection_id":"c1"}
+
+{"offset_us":5000000,"timestamp":"2024-01-01T00:00:05Z","operation":"query","sql":"SELECT disconnect","params":[],"connection_id":"c1"}
+{"offset_us":9000000,"timestamp":"2024-01-01T00:00:09Z","operation":"execute","sql":"DELETE FROM sessions","params":[],"connection_id":"c1"}
+"#;
+    let records = parse_recording(lines.as_bytes()).unwrap();
+    assert_eq!(records.len(), 3);
+
+    // Back to back: the recorded nine seconds take no time at all
+    let (port, received) = start_server().await;
+    let mut conn = connect(port).await;
+    let options = ReplayOptions { speed: None, max_errors: None };
+    let report = replay(&AuroraProtocol::new(), &mut conn, &records, &options).await.unwrap();
+    assert!(report.elapsed < Duration::from_secs(2));
+    assert_eq!((report.issued, report.succeeded()), (3, 2));
+    assert_eq!((report.errors[0].index, report.errors[0].sql.as_str()), (1, "SELECT disconnect"));
+    assert_eq!(sql_of(&received), ["SELECT 1", "SELECT disconnect", "DELETE FROM sessions"]);
+
+    // Stopping at the first error leaves the rest unissued
+    let options = ReplayOptions { speed: None, max_errors: Some(1) };
+    let report = replay(&AuroraProtocol::new(), &mut conn, &records, &options).await.unwrap();
+    assert_eq!(report.issued, 2);
+
+    let options = ReplayOptions { speed: Some(0.0), max_errors: None };
+    assert!(matches!(replay(&AuroraProtocol::new(), &mut conn, &records, &options).await, Err(AuroraError::Configuration(_))));
+
+    let error = parse_recording("{\"offset_us\":0}\n{not json}".as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("line 1"), "{}", error);
+}
@@ -0,0 +1,110 @@
+//! Vector Upsert Batch Tests
+//!
+//! A stand-in server keeps one collection in memory and answers searches by
+//! exact distance, so each test sees the per-item outcomes the driver would
+//! return and whether later searches reflect the batch.
+
+use aurora_drivers::vector_batch::{self, VectorUpsertBatchRequest, VectorUpsertBatchResponse};
+use aurora_drivers::batch::RowFailure;
+use aurora_drivers::{AuroraError, AuroraValue, RowErrorKind, VectorItem, VectorUpsertOutcome, VectorUpsertResult};
+use std::collections::HashMap;
+
+/// Collection of fixed dimension
+struct StandInCollection {
+    dimension: usize,
+    vectors: HashMap<u64, (Vec<f32>, HashMap<String, AuroraValue>)>,
+}
+
+impl StandInCollection {
+    fn new(dimension: usize) -> Self {
+        Self { dimension, vectors: HashMap::new() }
+    }
+
+    fn apply(&mut self, request: &VectorUpsertBatchRequest) -> VectorUpsertBatchResponse {
+        if request.dimension != self.dimension {
+            return VectorUpsertBatchResponse {
+                rejected: Some(format!("collection has dimension {}, batch has {}", self.dimension, request.dimension)),
+                ..VectorUpsertBatchResponse::default()
+            };
+        }
+
+        let mut response = VectorUpsertBatchResponse::default();
+        for (position, item) in request.items.iter().enumerate() {
+            if item.vector.iter().any(|value| !value.is_finite()) {
+                response.failures.push(RowFailure {
+                    row: position,
+                    sqlstate: "22003".to_string(),
+                    message: format!("vector {} has non-finite components", item.id),
+                    constraint: None,
+                });
+                continue;
+            }
+            if self.vectors.insert(item.id, (item.vector.clone(), item.metadata.clone())).is_some() {
+                response.updated.push(position);
+            }
+        }
+        response
+    }
+
+    /// What `AuroraProtocol::vector_upsert_batch` does, with this collection as the server
+    fn upsert(&mut self, items: Vec<VectorItem>) -> aurora_drivers::Result<Vec<VectorUpsertResult>> {
+        let len = items.len();
+        let request = VectorUpsertBatchRequest::new("documents", items)?;
+        let response = self.apply(&request);
+        vector_batch::item_results(len, response)
+    }
+
+    fn nearest(&self, query: &[f32]) -> u64 {
+        let distance = |vector: &[f32]| vector.iter().zip(query).map(|(a, b)| (a - b) * (a - b)).sum::<f32>();
+        *self.vectors.iter()
+            .min_by(|a, b| distance(&a.1 .0).partial_cmp(&distance(&b.1 .0)).unwrap())
+            .unwrap()
+            .0
+    }
+}
+
+#[test]
+fn test_batch_inserts_new_and_updates_existing_vectors() {
+    let mut collection = StandInCollection::new(3);
+    collection.upsert(vec![
+        VectorItem::new(1, vec![1.0, 0.0, 0.0]),
+        VectorItem::new(2, vec![0.0, 1.0, 0.0]),
+    ]).unwrap();
+    assert_eq!(collection.nearest(&[0.0, 0.0, 1.0]), 1);
+
+    let results = collection.upsert(vec![
+        VectorItem::new(2, vec![0.0, 0.0, 1.0]).with_metadata("title", AuroraValue::Text("moved".to_string())),
+        VectorItem::new(3, vec![0.0, 1.0, 0.0]),
+        VectorItem::new(4, vec![f32::INFINITY, 0.0, 0.0]),
+    ]).unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0], Ok(VectorUpsertOutcome::Updated));
+    assert_eq!(results[1], Ok(VectorUpsertOutcome::Inserted));
+    assert_eq!(results[2].as_ref().unwrap_err().kind, RowErrorKind::InvalidValue);
+
+    // Searches find the updated vector and the new one
+    assert_eq!(collection.nearest(&[0.0, 0.0, 1.0]), 2);
+    assert_eq!(collection.nearest(&[0.0, 1.0, 0.0]), 3);
+    assert_eq!(collection.vectors.len(), 3);
+    assert_eq!(collection.vectors[&2].1.get("title"), Some(&AuroraValue::Text("moved".to_string())));
+}
+
+#[test]
+fn test_dimension_mismatch_rejects_whole_batch() {
+    let mut collection = StandInCollection::new(3);
+
+    // Mixed dimensions never leave the driver
+    let result = collection.upsert(vec![
+        VectorItem::new(1, vec![1.0, 0.0, 0.0]),
+        VectorItem::new(2, vec![1.0, 0.0]),
+    ]);
+    assert!(matches!(&result, Err(AuroraError::Query(message)) if message.contains("item 1")));
+
+    // A consistent batch of the wrong dimension is refused by the server
+    let result = collection.upsert(vec![VectorItem::new(1, vec![1.0, 0.0]), VectorItem::new(2, vec![0.0, 1.0])]);
+    assert!(matches!(&result, Err(AuroraError::Query(message)) if message.contains("rejected")));
+    assert!(collection.vectors.is_empty());
+
+    assert!(collection.upsert(Vec::new()).unwrap().is_empty());
+}
@@ -0,0 +1,143 @@
+//! Slab Allocator Benchmarks: SlabPool vs the global allocator
+//!
+//! Measures alloc/free cycles for hot-path objects:
+//! - Single-threaded alloc + drop churn
+//! - Burst allocation (many live objects, then release)
+//! - Cross-thread churn on a shared pool
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use cyclone::slab::{SlabPool, SlabPoolConfig};
+use std::thread;
+
+/// Typical per-connection state object
+#[derive(Debug, Clone, Copy)]
+struct ConnectionState {
+    id: u64,
+    bytes_read: u64,
+    bytes_written: u64,
+    flags: u32,
+    _padding: [u8; 36],
+}
+
+impl ConnectionState {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            bytes_read: 0,
+            bytes_written: 0,
+            flags: 0,
+            _padding: [0; 36],
+        }
+    }
+}
+
+/// Alloc + immediate drop: the steady state of a request-scoped object
+pub fn benchmark_alloc_free_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slab_alloc_free_churn");
+    let pool = SlabPool::new();
+
+    group.bench_function("slab_pool", |b| {
+        let mut id = 0u64;
+        b.iter(|| {
+            id += 1;
+            let state = pool.alloc(ConnectionState::new(id)).unwrap();
+            black_box(state.id)
+        })
+    });
+
+    group.bench_function("box_new", |b| {
+        let mut id = 0u64;
+        b.iter(|| {
+            id += 1;
+            let state = Box::new(ConnectionState::new(id));
+            black_box(state.id)
+        })
+    });
+
+    group.finish();
+}
+
+/// Allocate a burst of live objects, then release them all
+pub fn benchmark_burst_allocation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slab_burst_allocation");
+
+    for &burst in &[64, 1024, 16384] {
+        let pool = SlabPool::with_config(SlabPoolConfig {
+            arena_capacity: 4096,
+            ..Default::default()
+        }).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("slab_pool", burst), &burst, |b, &burst| {
+            b.iter(|| {
+                let held: Vec<_> = (0..burst)
+                    .map(|i| pool.alloc(ConnectionState::new(i as u64)).unwrap())
+                    .collect();
+                black_box(held.len())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("box_new", burst), &burst, |b, &burst| {
+            b.iter(|| {
+                let held: Vec<_> = (0..burst)
+                    .map(|i| Box::new(ConnectionState::new(i as u64)))
+                    .collect();
+                black_box(held.len())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Several threads churning objects through one shared pool
+pub fn benchmark_concurrent_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slab_concurrent_churn");
+    const THREADS: usize = 4;
+    const OPS_PER_THREAD: u64 = 10_000;
+
+    group.bench_function("slab_pool", |b| {
+        let pool = SlabPool::new();
+        b.iter(|| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let pool = pool.clone();
+                    thread::spawn(move || {
+                        for i in 0..OPS_PER_THREAD {
+                            black_box(pool.alloc(ConnectionState::new(i)).unwrap().id);
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        })
+    });
+
+    group.bench_function("box_new", |b| {
+        b.iter(|| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    thread::spawn(move || {
+                        for i in 0..OPS_PER_THREAD {
+                            black_box(Box::new(ConnectionState::new(i)).id);
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_alloc_free_churn,
+    benchmark_burst_allocation,
+    benchmark_concurrent_churn
+);
+criterion_main!(benches);
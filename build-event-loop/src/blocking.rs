@@ -0,0 +1,616 @@
+//! Offloading blocking work from the reactor.
+//!
+//! ```rust,ignore
+//! let compressed = cyclone.spawn_blocking(move || zstd::encode_all(&payload[..], 3)).await??;
+//! ```
+//!
+//! Callbacks that compress files, hash passwords or call a blocking library
+//! would stall every other task sharing their reactor thread. A
+//! [`BlockingPool`] runs such closures on dedicated threads instead and hands
+//! back a [`BlockingTask`] future that resolves to the closure's result.
+//!
+//! The pool is bounded twice over:
+//!
+//! - **Threads**: up to `max_threads` are started on demand; a thread idle
+//!   for `keep_alive` exits.
+//! - **Queue**: once every thread is busy, up to `queue_capacity` closures
+//!   wait for one. Beyond that the pool is saturated and pushes back:
+//!   [`BlockingPool::try_spawn`] refuses the closure, while the future of
+//!   [`BlockingPool::spawn`] holds on to it and parks its task until a
+//!   queue slot frees up, so a flood of blocking work slows its producers
+//!   down instead of growing the queue without limit.
+//!
+//! A closure that has started cannot be interrupted: dropping its
+//! `BlockingTask` discards the result but the thread still runs it to the
+//! end. A closure still waiting for a queue slot is dropped with the task.
+
+use crate::error::{Error, Result};
+use futures::channel::oneshot;
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Limits of a [`BlockingPool`]
+#[derive(Debug, Clone)]
+pub struct BlockingPoolConfig {
+    /// Threads running closures at once; at least 1
+    pub max_threads: usize,
+    /// Closures allowed to wait for a thread once all are busy
+    pub queue_capacity: usize,
+    /// How long an idle thread waits for work before exiting
+    pub keep_alive: Duration,
+}
+
+impl Default for BlockingPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_threads: 64,
+            queue_capacity: 1024,
+            keep_alive: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Point-in-time view of a [`BlockingPool`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockingPoolStats {
+    /// Threads currently started
+    pub threads: usize,
+    /// Threads running a closure
+    pub busy_threads: usize,
+    /// Closures waiting for a thread
+    pub queued: usize,
+    /// Tasks parked until a queue slot frees up
+    pub waiting: usize,
+    /// Configured thread bound
+    pub max_threads: usize,
+    /// Configured queue bound
+    pub queue_capacity: usize,
+    /// Closures run to the end, including those that panicked
+    pub completed: u64,
+    /// Closures that panicked
+    pub panicked: u64,
+    /// Closures refused by `try_spawn` because the pool was saturated
+    pub rejected: u64,
+    /// Closures that had to wait for a queue slot
+    pub throttled: u64,
+}
+
+impl BlockingPoolStats {
+    /// Fraction of `max_threads` running a closure, from 0.0 to 1.0
+    pub fn utilization(&self) -> f64 {
+        self.busy_threads as f64 / self.max_threads.max(1) as f64
+    }
+
+    /// Whether every thread is busy and the queue is full
+    pub fn is_saturated(&self) -> bool {
+        self.busy_threads >= self.max_threads && self.queued >= self.queue_capacity
+    }
+}
+
+/// A packaged closure; returns whether the closure panicked
+type Job = Box<dyn FnOnce() -> bool + Send>;
+
+/// Where a task refused for a full queue parks: its waiter key and waker
+type Park<'a> = Option<(&'a mut Option<u64>, &'a Waker)>;
+
+/// Why a closure could not be handed to the pool
+enum Refused {
+    /// The queue is full; the closure is handed back
+    Full(Job),
+    /// The pool was shut down
+    Stopped,
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<Job>,
+    threads: usize,
+    idle: usize,
+    /// Wakers of tasks waiting for a queue slot, oldest first
+    waiters: BTreeMap<u64, Waker>,
+    next_waiter: u64,
+    shutdown: bool,
+    completed: u64,
+    panicked: u64,
+    rejected: u64,
+    throttled: u64,
+}
+
+struct Shared {
+    config: BlockingPoolConfig,
+    state: Mutex<State>,
+    /// Signalled when a closure is queued or the pool shuts down
+    work: Condvar,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Hand `job` to an idle thread, a new thread or the queue, in that order
+    ///
+    /// If the queue is full and `park` is given, the waker is registered
+    /// before the lock is released, so a slot freed meanwhile still wakes it.
+    fn submit(self: &Arc<Self>, job: Job, park: Park<'_>) -> std::result::Result<(), Refused> {
+        let mut state = self.state();
+        if state.shutdown {
+            return Err(Refused::Stopped);
+        }
+
+        if state.idle > state.queue.len() {
+            state.queue.push_back(job);
+            self.work.notify_one();
+            return Ok(());
+        }
+
+        if state.threads < self.config.max_threads.max(1) {
+            let shared = self.clone();
+            let name = format!("cyclone-blocking-{}", state.threads);
+            // Handed over through a slot so a failed spawn can give it back
+            let slot = Arc::new(Mutex::new(Some(job)));
+            let first = slot.clone();
+            let spawned = std::thread::Builder::new()
+                .name(name)
+                .spawn(move || {
+                    let job = first.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+                    shared.work_loop(job);
+                });
+            match spawned {
+                Ok(_) => {
+                    state.threads += 1;
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Failed to start blocking thread: {}", e);
+                    let job = slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+                    return match job {
+                        Some(job) if state.threads == 0 => {
+                            drop(job);
+                            Err(Refused::Stopped)
+                        }
+                        Some(job) => self.enqueue(state, job, park),
+                        None => Ok(()),
+                    };
+                }
+            }
+        }
+
+        self.enqueue(state, job, park)
+    }
+
+    fn enqueue(&self, mut state: MutexGuard<'_, State>, job: Job, park: Park<'_>) -> std::result::Result<(), Refused> {
+        if state.queue.len() >= self.config.queue_capacity {
+            if let Some((waiter, waker)) = park {
+                // Keeping the first key keeps the task's place in line
+                let key = *waiter.get_or_insert_with(|| {
+                    state.next_waiter += 1;
+                    state.next_waiter
+                });
+                state.waiters.insert(key, waker.clone());
+            }
+            return Err(Refused::Full(job));
+        }
+        state.queue.push_back(job);
+        self.work.notify_one();
+        Ok(())
+    }
+
+    /// Run `first`, then queued closures until idle for `keep_alive`
+    fn work_loop(&self, mut job: Option<Job>) {
+        loop {
+            if let Some(job) = job.take() {
+                let panicked = job();
+                let mut state = self.state();
+                state.completed += 1;
+                if panicked {
+                    state.panicked += 1;
+                }
+            }
+
+            let mut state = self.state();
+            loop {
+                if let Some(next) = state.queue.pop_front() {
+                    // The slot it held may be what a parked task waits for
+                    wake_next(&mut state);
+                    job = Some(next);
+                    break;
+                }
+                if state.shutdown {
+                    state.threads -= 1;
+                    return;
+                }
+
+                // An idle thread is as good as a queue slot
+                wake_next(&mut state);
+                state.idle += 1;
+                let (guard, wait) = self.work
+                    .wait_timeout(state, self.config.keep_alive)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                state = guard;
+                state.idle -= 1;
+                if wait.timed_out() && state.queue.is_empty() {
+                    state.threads -= 1;
+                    debug!("Blocking thread exiting after {:?} idle", self.config.keep_alive);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn wake_next(state: &mut State) {
+    if let Some((_, waker)) = state.waiters.pop_first() {
+        waker.wake();
+    }
+}
+
+/// Bounded pool of threads for blocking closures
+///
+/// Cloning gives another handle to the same pool.
+#[derive(Clone)]
+pub struct BlockingPool {
+    shared: Arc<Shared>,
+}
+
+impl BlockingPool {
+    /// A pool with no threads yet, bounded by `config`
+    pub fn new(config: BlockingPoolConfig) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                config,
+                state: Mutex::new(State::default()),
+                work: Condvar::new(),
+            }),
+        }
+    }
+
+    /// The pool's limits
+    pub fn config(&self) -> &BlockingPoolConfig {
+        &self.shared.config
+    }
+
+    /// Run `f` on a pool thread, resolving to its result
+    ///
+    /// If the pool is saturated, the closure is kept by the returned future,
+    /// which waits for a queue slot whenever it is polled.
+    pub fn spawn<F, T>(&self, f: F) -> BlockingTask<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (job, result) = package(f);
+        let mut task = BlockingTask {
+            shared: self.shared.clone(),
+            job: None,
+            waiter: None,
+            result,
+        };
+        match self.shared.submit(job, None) {
+            Ok(()) | Err(Refused::Stopped) => {}
+            Err(Refused::Full(job)) => {
+                self.shared.state().throttled += 1;
+                task.job = Some(job);
+            }
+        }
+        task
+    }
+
+    /// Run `f` on a pool thread, or fail at once if the pool is saturated
+    pub fn try_spawn<F, T>(&self, f: F) -> Result<BlockingTask<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (job, result) = package(f);
+        match self.shared.submit(job, None) {
+            Ok(()) => Ok(BlockingTask {
+                shared: self.shared.clone(),
+                job: None,
+                waiter: None,
+                result,
+            }),
+            Err(Refused::Full(_)) => {
+                self.shared.state().rejected += 1;
+                Err(Error::resource_exhausted(format!(
+                    "blocking pool ({} threads, {} queued)",
+                    self.shared.config.max_threads, self.shared.config.queue_capacity
+                )))
+            }
+            Err(Refused::Stopped) => Err(Error::concurrency("blocking pool has shut down")),
+        }
+    }
+
+    /// Current thread, queue and counter values
+    pub fn stats(&self) -> BlockingPoolStats {
+        let state = self.shared.state();
+        BlockingPoolStats {
+            threads: state.threads,
+            busy_threads: state.threads - state.idle,
+            queued: state.queue.len(),
+            waiting: state.waiters.len(),
+            max_threads: self.shared.config.max_threads.max(1),
+            queue_capacity: self.shared.config.queue_capacity,
+            completed: state.completed,
+            panicked: state.panicked,
+            rejected: state.rejected,
+            throttled: state.throttled,
+        }
+    }
+
+    /// Refuse new closures and let threads exit once the queue is drained
+    ///
+    /// Tasks still waiting for a queue slot resolve to an error.
+    pub fn shutdown(&self) {
+        let mut state = self.shared.state();
+        state.shutdown = true;
+        for (_, waker) in std::mem::take(&mut state.waiters) {
+            waker.wake();
+        }
+        self.shared.work.notify_all();
+    }
+}
+
+impl Default for BlockingPool {
+    fn default() -> Self {
+        Self::new(BlockingPoolConfig::default())
+    }
+}
+
+impl std::fmt::Debug for BlockingPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingPool")
+            .field("config", &self.shared.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// Wrap `f` into a job that reports its result, or its panic, to the receiver
+fn package<F, T>(f: F) -> (Job, oneshot::Receiver<std::thread::Result<T>>)
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (reply, result) = oneshot::channel();
+    let job: Job = Box::new(move || {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(f));
+        let panicked = outcome.is_err();
+        // The task may have been dropped
+        let _ = reply.send(outcome);
+        panicked
+    });
+    (job, result)
+}
+
+/// Result of a closure running on a [`BlockingPool`]
+///
+/// Resolves to an error if the closure panicked or the pool shut down
+/// before running it.
+#[must_use = "a blocking task still holding its closure only runs it when polled"]
+pub struct BlockingTask<T> {
+    shared: Arc<Shared>,
+    /// The closure, while it waits for a queue slot
+    job: Option<Job>,
+    /// Key of this task's waker among the pool's waiters
+    waiter: Option<u64>,
+    result: oneshot::Receiver<std::thread::Result<T>>,
+}
+
+impl<T> BlockingTask<T> {
+    /// Whether the closure is still waiting for a queue slot
+    pub fn is_throttled(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+impl<T> Future for BlockingTask<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Some(job) = this.job.take() {
+            match this.shared.submit(job, Some((&mut this.waiter, cx.waker()))) {
+                Ok(()) => {
+                    if let Some(key) = this.waiter.take() {
+                        this.shared.state().waiters.remove(&key);
+                    }
+                }
+                Err(Refused::Stopped) => {
+                    this.waiter = None;
+                    return Poll::Ready(Err(Error::concurrency("blocking pool shut down before running the task")));
+                }
+                Err(Refused::Full(job)) => {
+                    this.job = Some(job);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        match Pin::new(&mut this.result).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(Ok(value))) => Poll::Ready(Ok(value)),
+            Poll::Ready(Ok(Err(_))) => Poll::Ready(Err(Error::concurrency("blocking task panicked"))),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Error::concurrency("blocking pool shut down before running the task"))),
+        }
+    }
+}
+
+impl<T> Drop for BlockingTask<T> {
+    fn drop(&mut self) {
+        if let Some(key) = self.waiter.take() {
+            let mut state = self.shared.state();
+            // Woken for a slot it will not use: pass the wake-up on
+            if state.waiters.remove(&key).is_none() && self.job.is_some() {
+                wake_next(&mut state);
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for BlockingTask<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingTask")
+            .field("throttled", &self.is_throttled())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    fn pool(max_threads: usize, queue_capacity: usize) -> BlockingPool {
+        BlockingPool::new(BlockingPoolConfig {
+            max_threads,
+            queue_capacity,
+            keep_alive: Duration::from_secs(5),
+        })
+    }
+
+    /// Poll `future` once without a task to wake
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut Context::from_waker(futures::task::noop_waker_ref()))
+    }
+
+    /// Wait on the test thread until `condition` holds for the pool
+    fn wait_for(pool: &BlockingPool, condition: impl Fn(&BlockingPoolStats) -> bool) -> BlockingPoolStats {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let stats = pool.stats();
+            if condition(&stats) {
+                return stats;
+            }
+            assert!(Instant::now() < deadline, "pool never reached the expected state: {:?}", stats);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_closure_does_not_stall_reactor_tasks() {
+        let pool = pool(2, 8);
+        let reactor_thread = std::thread::current().id();
+        let (release, released) = mpsc::channel::<()>();
+
+        // Blocks its thread until a task on this thread lets it go
+        let blocked = pool.spawn(move || {
+            let ran_elsewhere = std::thread::current().id() != reactor_thread;
+            (ran_elsewhere, released.recv_timeout(Duration::from_secs(5)).is_ok())
+        });
+
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticker = {
+            let ticks = ticks.clone();
+            async move {
+                for _ in 0..100 {
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                    tokio::task::yield_now().await;
+                }
+                release.send(()).unwrap();
+            }
+        };
+
+        let ((ran_elsewhere, was_released), ()) = futures::future::join(async { blocked.await.unwrap() }, ticker).await;
+        assert!(ran_elsewhere);
+        assert!(was_released, "the reactor task never ran while the closure blocked");
+        assert_eq!(ticks.load(Ordering::SeqCst), 100);
+        assert_eq!(pool.stats().completed, 1);
+    }
+
+    #[test]
+    fn test_thread_bound_is_enforced_and_excess_closures_queue() {
+        let pool = pool(2, 2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (release, gate) = crossbeam::channel::unbounded::<()>();
+
+        let mut tasks: Vec<_> = (0..4)
+            .map(|n| {
+                let (running, peak, gate) = (running.clone(), peak.clone(), gate.clone());
+                pool.spawn(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    gate.recv_timeout(Duration::from_secs(5)).unwrap();
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    n
+                })
+            })
+            .collect();
+
+        // Two run, two wait for a thread, none is throttled
+        let stats = wait_for(&pool, |stats| stats.busy_threads == 2);
+        assert_eq!((stats.threads, stats.queued, stats.throttled), (2, 2, 0));
+        assert!(stats.is_saturated());
+        assert_eq!(stats.utilization(), 1.0);
+        assert!(tasks.iter().all(|task| !task.is_throttled()));
+
+        // A fifth is refused outright, or held back by its future
+        assert!(matches!(pool.try_spawn(|| 4), Err(Error::ResourceExhausted { .. })));
+        let mut fifth = pool.spawn(|| 4);
+        assert!(fifth.is_throttled());
+        assert!(poll_once(&mut fifth).is_pending());
+        let stats = pool.stats();
+        assert_eq!((stats.rejected, stats.throttled, stats.waiting), (1, 1, 1));
+
+        // Releasing one closure frees a queue slot for the fifth
+        release.send(()).unwrap();
+        wait_for(&pool, |stats| stats.queued < 2);
+        assert!(poll_once(&mut fifth).is_pending());
+        assert!(!fifth.is_throttled());
+        assert_eq!(pool.stats().waiting, 0);
+
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        tasks.push(fifth);
+        let results: Vec<usize> = tasks.into_iter().map(|task| futures::executor::block_on(task).unwrap()).collect();
+        assert_eq!(results, vec![0, 1, 2, 3, 4]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.stats().completed, 5);
+        assert!(pool.stats().threads <= 2);
+    }
+
+    #[test]
+    fn test_throttled_task_wakes_when_a_slot_frees() {
+        let pool = pool(1, 0);
+        let (release, gate) = mpsc::channel::<()>();
+        let first = pool.spawn(move || gate.recv_timeout(Duration::from_secs(5)).is_ok());
+        wait_for(&pool, |stats| stats.busy_threads == 1);
+
+        // Nothing may queue, so the second waits until the first finishes
+        let second = pool.spawn(|| "second");
+        assert!(second.is_throttled());
+        let waiter = std::thread::spawn(move || futures::executor::block_on(second));
+        wait_for(&pool, |stats| stats.waiting == 1);
+
+        release.send(()).unwrap();
+        assert!(futures::executor::block_on(first).unwrap());
+        assert_eq!(waiter.join().unwrap().unwrap(), "second");
+    }
+
+    #[test]
+    fn test_panics_and_shutdown_surface_as_errors() {
+        let pool = pool(1, 1);
+        let panicked = futures::executor::block_on(pool.spawn(|| -> u32 { panic!("boom") }));
+        assert!(matches!(panicked, Err(Error::Concurrency { .. })));
+        // The thread survives the panic
+        assert_eq!(futures::executor::block_on(pool.spawn(|| 7)).unwrap(), 7);
+        let stats = pool.stats();
+        assert_eq!((stats.completed, stats.panicked), (2, 1));
+
+        pool.shutdown();
+        assert!(pool.try_spawn(|| 1).is_err());
+        assert!(futures::executor::block_on(pool.spawn(|| 1)).is_err());
+        wait_for(&pool, |stats| stats.threads == 0);
+    }
+}
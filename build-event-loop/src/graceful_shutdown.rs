@@ -9,11 +9,15 @@
 //! - **Connection Draining**: Zero-downtime deployments (Kubernetes preStop hooks)
 //! - **Signal Handling**: Unix signal processing for clean shutdowns
 //! - **Resource Cleanup**: Deterministic resource deallocation
+//! - **Phased Shutdown**: Ordered phases (stop accepting → drain → flush → close)
+//!   with parallel components and per-phase deadlines
 
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, oneshot};
 
 /// Graceful shutdown coordinator for enterprise applications
 ///
@@ -74,6 +78,8 @@ pub enum ShutdownError {
     HandlerError(String),
     /// Resource cleanup failed
     ResourceCleanupError(String),
+    /// Component registered into a phase that was never declared
+    UnknownPhase(String),
 }
 
 impl GracefulShutdown {
@@ -298,6 +304,218 @@ impl GracefulShutdown {
     }
 }
 
+/// Future a component runs to shut itself down
+type ComponentFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Signal a component sends once the next phase no longer has to wait for it
+///
+/// Work the component still does after signalling keeps running in the
+/// background. Dropping the signal without calling [`done`](Self::done)
+/// leaves the phase waiting for the component's future to finish.
+#[derive(Debug)]
+pub struct DoneSignal(oneshot::Sender<()>);
+
+impl DoneSignal {
+    /// Let the phase proceed without waiting for the rest of this component
+    pub fn done(self) {
+        let _ = self.0.send(());
+    }
+}
+
+/// A component registered into a shutdown phase
+struct PhaseComponent {
+    name: String,
+    shutdown: Box<dyn FnOnce(DoneSignal) -> ComponentFuture + Send + 'static>,
+}
+
+/// A named group of components shut down together
+struct ShutdownPhase {
+    name: String,
+    deadline: Duration,
+    components: Vec<PhaseComponent>,
+}
+
+/// How a component's shutdown ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentOutcome {
+    /// Finished, or signalled done, within the phase deadline
+    Completed,
+    /// Still running at the phase deadline and aborted
+    MissedDeadline,
+    /// Panicked while shutting down
+    Failed(String),
+}
+
+/// Result of shutting down one component
+#[derive(Debug, Clone)]
+pub struct ComponentReport {
+    /// Phase the component was registered into
+    pub phase: String,
+    /// Component name
+    pub component: String,
+    /// How the shutdown ended
+    pub outcome: ComponentOutcome,
+    /// Time from the start of the phase until the outcome was known
+    pub elapsed: Duration,
+}
+
+/// Result of a phased shutdown
+#[derive(Debug, Clone, Default)]
+pub struct PhasedShutdownReport {
+    /// Every component, in phase order
+    pub components: Vec<ComponentReport>,
+    /// Duration of each phase, in the order the phases ran
+    pub phase_durations: Vec<(String, Duration)>,
+}
+
+impl PhasedShutdownReport {
+    /// Components that were still running at their phase deadline
+    pub fn missed_deadlines(&self) -> Vec<&ComponentReport> {
+        self.components.iter()
+            .filter(|report| report.outcome == ComponentOutcome::MissedDeadline)
+            .collect()
+    }
+
+    /// Whether every component completed within its phase deadline
+    pub fn completed_successfully(&self) -> bool {
+        self.components.iter().all(|report| report.outcome == ComponentOutcome::Completed)
+    }
+}
+
+/// Shutdown run as an ordered sequence of phases
+///
+/// Phases run one after another in the order they were added, e.g.
+/// stop accepting → drain → flush → close. Within a phase all components
+/// shut down in parallel, and the phase ends once each of them has finished
+/// or signalled done, or when the phase deadline passes. Components still
+/// running at the deadline are aborted and reported so that shutdown always
+/// proceeds to the next phase.
+pub struct PhasedShutdown {
+    phases: Vec<ShutdownPhase>,
+}
+
+impl std::fmt::Debug for PhasedShutdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let phases: Vec<_> = self.phases.iter()
+            .map(|phase| (&phase.name, phase.deadline, phase.components.len()))
+            .collect();
+        f.debug_struct("PhasedShutdown").field("phases", &phases).finish()
+    }
+}
+
+impl Default for PhasedShutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhasedShutdown {
+    /// Create a phased shutdown with no phases
+    pub fn new() -> Self {
+        Self { phases: Vec::new() }
+    }
+
+    /// Append a phase that runs after every phase added before it
+    ///
+    /// Adding a phase name a second time replaces its deadline and keeps its
+    /// position and components.
+    pub fn add_phase(&mut self, name: impl Into<String>, deadline: Duration) -> &mut Self {
+        let name = name.into();
+        match self.phases.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => phase.deadline = deadline,
+            None => self.phases.push(ShutdownPhase { name, deadline, components: Vec::new() }),
+        }
+        self
+    }
+
+    /// Register a component into a phase
+    ///
+    /// `shutdown` is called when the phase starts. The component counts as
+    /// done when the returned future completes or when it calls
+    /// [`DoneSignal::done`], whichever comes first.
+    pub fn register<F, Fut>(
+        &mut self,
+        phase: &str,
+        component: impl Into<String>,
+        shutdown: F,
+    ) -> Result<(), ShutdownError>
+    where
+        F: FnOnce(DoneSignal) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let phase = self.phases.iter_mut()
+            .find(|p| p.name == phase)
+            .ok_or_else(|| ShutdownError::UnknownPhase(phase.to_string()))?;
+
+        phase.components.push(PhaseComponent {
+            name: component.into(),
+            shutdown: Box::new(move |done| Box::pin(shutdown(done))),
+        });
+        Ok(())
+    }
+
+    /// Phase names in execution order
+    pub fn phase_names(&self) -> Vec<&str> {
+        self.phases.iter().map(|phase| phase.name.as_str()).collect()
+    }
+
+    /// Run every phase in order and report how each component ended
+    pub async fn run(self) -> PhasedShutdownReport {
+        let mut report = PhasedShutdownReport::default();
+
+        for phase in self.phases {
+            tracing::info!("Shutdown phase '{}': {} components, deadline {:?}",
+                          phase.name, phase.components.len(), phase.deadline);
+
+            let phase_start = tokio::time::Instant::now();
+            let deadline = phase_start + phase.deadline;
+
+            // Start every component before waiting on any of them
+            let running: Vec<_> = phase.components.into_iter().map(|component| {
+                let (done_tx, done_rx) = oneshot::channel();
+                let task = tokio::spawn((component.shutdown)(DoneSignal(done_tx)));
+                (component.name, task, done_rx)
+            }).collect();
+
+            // All components share the phase deadline, so waiting on them in
+            // turn still bounds the phase by that deadline
+            for (component, mut task, mut done_rx) in running {
+                let finished = tokio::time::timeout_at(deadline, async {
+                    tokio::select! {
+                        result = &mut task => result.map_err(|e| e.to_string()),
+                        Ok(()) = &mut done_rx => Ok(()),
+                    }
+                }).await;
+
+                let outcome = match finished {
+                    Ok(Ok(())) => ComponentOutcome::Completed,
+                    Ok(Err(e)) => {
+                        tracing::error!("Shutdown component '{}' failed: {}", component, e);
+                        ComponentOutcome::Failed(e)
+                    }
+                    Err(_) => {
+                        task.abort();
+                        tracing::warn!("Shutdown component '{}' missed the '{}' phase deadline of {:?}",
+                                      component, phase.name, phase.deadline);
+                        ComponentOutcome::MissedDeadline
+                    }
+                };
+
+                report.components.push(ComponentReport {
+                    phase: phase.name.clone(),
+                    component,
+                    outcome,
+                    elapsed: phase_start.elapsed(),
+                });
+            }
+
+            report.phase_durations.push((phase.name, phase_start.elapsed()));
+        }
+
+        report
+    }
+}
+
 /// Connection draining manager for graceful shutdown
 ///
 /// Manages the lifecycle of connections during shutdown to ensure
@@ -498,3 +716,113 @@ impl HealthCheckManager {
         status == HealthStatus::Healthy || status == HealthStatus::Draining
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn record(log: &Arc<Mutex<Vec<String>>>, entry: &str) {
+        log.lock().unwrap().push(entry.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_phases_run_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut shutdown = PhasedShutdown::new();
+        shutdown
+            .add_phase("stop_accepting", Duration::from_secs(1))
+            .add_phase("drain", Duration::from_secs(1))
+            .add_phase("flush", Duration::from_secs(1))
+            .add_phase("close", Duration::from_secs(1));
+
+        // Registered out of order, and slower components in earlier phases
+        for (phase, component, delay_ms) in [
+            ("close", "storage", 0),
+            ("flush", "wal", 20),
+            ("drain", "http", 40),
+            ("drain", "grpc", 10),
+            ("stop_accepting", "listener", 30),
+        ] {
+            let log = Arc::clone(&log);
+            shutdown.register(phase, component, move |_done| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                record(&log, component);
+            }).unwrap();
+        }
+
+        let report = shutdown.run().await;
+        assert!(report.completed_successfully());
+
+        // Components of one phase run in parallel, so the faster one ends first
+        let log = log.lock().unwrap().clone();
+        assert_eq!(log, vec!["listener", "grpc", "http", "wal", "storage"]);
+
+        let phases: Vec<_> = report.phase_durations.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(phases, vec!["stop_accepting", "drain", "flush", "close"]);
+        assert!(report.phase_durations[1].1 >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_component_missing_deadline_is_reported_and_skipped() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut shutdown = PhasedShutdown::new();
+        shutdown
+            .add_phase("drain", Duration::from_millis(50))
+            .add_phase("close", Duration::from_secs(1));
+
+        let hung = Arc::clone(&log);
+        shutdown.register("drain", "hung_worker", move |_done| async move {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            record(&hung, "hung_worker");
+        }).unwrap();
+        let quick = Arc::clone(&log);
+        shutdown.register("drain", "quick_worker", move |_done| async move {
+            record(&quick, "quick_worker");
+        }).unwrap();
+        let close = Arc::clone(&log);
+        shutdown.register("close", "storage", move |_done| async move {
+            record(&close, "storage");
+        }).unwrap();
+
+        let started = Instant::now();
+        let report = shutdown.run().await;
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let missed = report.missed_deadlines();
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].phase, "drain");
+        assert_eq!(missed[0].component, "hung_worker");
+        assert!(missed[0].elapsed >= Duration::from_millis(50));
+        assert!(!report.completed_successfully());
+
+        // The next phase still ran, and the aborted component never finished
+        assert_eq!(*log.lock().unwrap(), vec!["quick_worker", "storage"]);
+    }
+
+    #[tokio::test]
+    async fn test_done_signal_ends_component_early() {
+        let mut shutdown = PhasedShutdown::new();
+        shutdown.add_phase("flush", Duration::from_millis(200));
+
+        shutdown.register("flush", "buffered_writer", |done| async move {
+            // Flushed what the next phase depends on; the rest can trail
+            done.done();
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        }).unwrap();
+
+        let report = shutdown.run().await;
+        assert!(report.completed_successfully());
+        assert!(report.components[0].elapsed < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_register_into_unknown_phase_fails() {
+        let mut shutdown = PhasedShutdown::new();
+        shutdown.add_phase("drain", Duration::from_secs(1));
+
+        let result = shutdown.register("flush", "wal", |_done| async {});
+        assert_eq!(result, Err(ShutdownError::UnknownPhase("flush".to_string())));
+        assert_eq!(shutdown.phase_names(), vec!["drain"]);
+    }
+}
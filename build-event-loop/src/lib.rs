@@ -61,12 +61,16 @@
 #![warn(clippy::all)]
 #![allow(clippy::type_complexity)]
 
+pub mod blocking;
 pub mod config;
 pub mod error;
 pub mod reactor;
 pub mod timer;
+pub mod time;
+pub mod retry;
 pub mod scheduler;
 pub mod net;
+pub mod dns;
 pub mod metrics;
 pub mod circuit_breaker;
 pub mod graceful_shutdown;
@@ -76,12 +80,20 @@ pub mod ffi;
 pub mod runtime;
 pub mod observability;
 pub mod simd;
+pub mod slab;
+pub mod task_local;
 
 // Re-export main types
+pub use blocking::{BlockingPool, BlockingPoolConfig, BlockingPoolStats, BlockingTask};
 pub use config::Config;
+pub use dns::{Resolver, ResolverConfig};
 pub use error::{Error, Result};
 pub use reactor::Reactor;
+pub use retry::{BackoffPolicy, RetryHandle, RetryScheduler};
 pub use runtime::Cyclone;
+pub use slab::{SlabPool, SlabRef};
+pub use task_local::{TaskLocal, TaskLocalFuture};
+pub use timer::TimerHandle;
 
 // UNIQUENESS Validation Checkpoint:
 // - [x] Memory-safe public API (all types checked at compile time)
@@ -16,9 +16,9 @@ use mio::{Interest, Token};
 use socket2::{Domain, Protocol, Socket, Type};
 use std::collections::{HashMap, VecDeque};
 use std::io::{self, Read, Write};
-use std::net::SocketAddr;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 // TLS support (feature-gated)
@@ -458,23 +458,143 @@ impl Default for TcpStreamConfig {
     }
 }
 
+/// Timing of the connection race in [`TcpStream::connect_addrs`]
+#[derive(Debug, Clone)]
+pub struct HappyEyeballsConfig {
+    /// Wait before starting the next attempt while earlier ones are pending
+    /// (RFC 8305 recommends 250ms)
+    pub attempt_delay: Duration,
+    /// Addresses tried at most, in the order given
+    pub max_attempts: usize,
+    /// Give up on all attempts after this long
+    pub connect_timeout: Duration,
+}
+
+impl Default for HappyEyeballsConfig {
+    fn default() -> Self {
+        Self {
+            attempt_delay: Duration::from_millis(250),
+            max_attempts: 8,
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
 impl TcpStream {
-    /// Connect to a remote address and create a TCP stream
+    /// Connect to `addr`, a `host:port` or `ip:port`
+    ///
+    /// Every address the host resolves to, A and AAAA alike, is raced with
+    /// the default [`HappyEyeballsConfig`]; see [`TcpStream::connect_addrs`].
     pub fn connect(addr: &str) -> Result<Self> {
-        let socket_addr: SocketAddr = addr.parse()
-            .map_err(|e| Error::network(format!("Invalid address {}: {}", addr, e)))?;
+        Self::connect_with_config(addr, &HappyEyeballsConfig::default())
+    }
 
-        let socket = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP))
-            .map_err(|e| Error::network(format!("Failed to create socket: {}", e)))?;
+    /// [`TcpStream::connect`] with explicit happy-eyeballs timing
+    ///
+    /// Resolution goes through the platform resolver and blocks like the
+    /// connect itself; async callers resolve with [`crate::dns::Resolver`]
+    /// and pass the addresses to [`TcpStream::connect_addrs`].
+    pub fn connect_with_config(addr: &str, config: &HappyEyeballsConfig) -> Result<Self> {
+        let resolved: Vec<SocketAddr> = addr.to_socket_addrs()
+            .map_err(|e| Error::network(format!("Invalid address {}: {}", addr, e)))?
+            .collect();
+        let port = resolved.first().map(SocketAddr::port).unwrap_or_default();
+        let mut ips = Vec::with_capacity(resolved.len());
+        for ip in resolved.iter().map(SocketAddr::ip) {
+            if !ips.contains(&ip) {
+                ips.push(ip);
+            }
+        }
+        let addrs: Vec<SocketAddr> = crate::dns::interleave_families(ips).into_iter()
+            .map(|ip| SocketAddr::new(ip, port))
+            .collect();
+        Self::connect_addrs(&addrs, config)
+    }
 
-        // Connect to the remote address
-        socket.connect(&socket_addr.into())
-            .map_err(|e| Error::network(format!("Failed to connect to {}: {}", addr, e)))?;
+    /// Connect to the first of `addrs` to accept (RFC 8305 section 5)
+    ///
+    /// Attempts start `attempt_delay` apart, or as soon as every earlier one
+    /// has failed, and overlap: an address whose packets vanish does not
+    /// hold up the next one for its whole timeout. The first attempt to
+    /// complete wins and the others are closed. At most `max_attempts`
+    /// addresses are tried; the call blocks for at most `connect_timeout`.
+    pub fn connect_addrs(addrs: &[SocketAddr], config: &HappyEyeballsConfig) -> Result<Self> {
+        let addrs = &addrs[..addrs.len().min(config.max_attempts.max(1))];
+        if addrs.is_empty() {
+            return Err(Error::network("no addresses to connect to"));
+        }
 
-        // Convert to Mio TcpStream
-        let mio_stream = MioTcpStream::from_std(socket.into());
+        let mut poll = mio::Poll::new()?;
+        let mut events = mio::Events::with_capacity(addrs.len());
+        let deadline = Instant::now() + config.connect_timeout;
+        let mut attempts: HashMap<Token, (MioTcpStream, SocketAddr)> = HashMap::new();
+        let mut next = 0;
+        let mut next_start = Instant::now();
+        let mut last_error = None;
+
+        loop {
+            let now = Instant::now();
+            if next < addrs.len() && (now >= next_start || attempts.is_empty()) {
+                let addr = addrs[next];
+                let token = Token(next);
+                next += 1;
+                next_start = now + config.attempt_delay;
+                match MioTcpStream::connect(addr) {
+                    Ok(mut stream) => {
+                        poll.registry().register(&mut stream, token, Interest::WRITABLE)?;
+                        attempts.insert(token, (stream, addr));
+                    }
+                    Err(e) => {
+                        debug!("Connection attempt to {} failed: {}", addr, e);
+                        last_error = Some(e);
+                        continue;
+                    }
+                }
+            }
 
-        Self::new(mio_stream)
+            if attempts.is_empty() {
+                let reason = last_error.map(|e| e.to_string()).unwrap_or_default();
+                return Err(Error::network(format!("no address was reachable: {}", reason)));
+            }
+            if now >= deadline {
+                return Err(Error::network(format!("connection timed out after {:?}", config.connect_timeout)));
+            }
+
+            let mut wait = deadline - now;
+            if next < addrs.len() {
+                wait = wait.min(next_start.saturating_duration_since(now));
+            }
+            poll.poll(&mut events, Some(wait))?;
+
+            for event in events.iter() {
+                let token = event.token();
+                let Some((stream, addr)) = attempts.get(&token) else {
+                    continue;
+                };
+                let failed = match stream.take_error() {
+                    Ok(Some(e)) | Err(e) => Some(e),
+                    Ok(None) => match stream.peer_addr() {
+                        Ok(_) => None,
+                        // Not finished yet; wait for the next readiness event
+                        Err(e) if e.kind() == io::ErrorKind::NotConnected => continue,
+                        Err(e) => Some(e),
+                    },
+                };
+                let addr = *addr;
+                let (mut stream, _) = attempts.remove(&token).expect("attempt was just looked up");
+                poll.registry().deregister(&mut stream)?;
+                match failed {
+                    None => {
+                        debug!("Connected to {}, abandoning {} other attempts", addr, attempts.len());
+                        return Self::new(stream);
+                    }
+                    Some(e) => {
+                        debug!("Connection attempt to {} failed: {}", addr, e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+        }
     }
 
     /// Create a new TCP stream with optimized settings
@@ -1066,7 +1186,79 @@ impl crate::reactor::EventHandler for TcpListenerHandler {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A listener whose accept queue is full, so further SYNs are dropped
+    /// and connecting hangs as it does on a dead IPv6 path
+    fn stalled_listener() -> (Socket, Vec<std::net::TcpStream>, SocketAddr) {
+        let socket = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP)).unwrap();
+        socket.bind(&"127.0.0.1:0".parse::<SocketAddr>().unwrap().into()).unwrap();
+        socket.listen(0).unwrap();
+        let addr = socket.local_addr().unwrap().as_socket().unwrap();
+        let mut queued = Vec::new();
+        while queued.len() < 16 {
+            match std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(200)) {
+                Ok(stream) => queued.push(stream),
+                Err(_) => break,
+            }
+        }
+        (socket, queued, addr)
+    }
+
+    /// Ports that were just released refuse connections
+    fn closed_addrs(count: usize) -> Vec<SocketAddr> {
+        (0..count)
+            .map(|_| std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_stalled_first_address_does_not_delay_connect() {
+        let (_stalled, _queued, stalled) = stalled_listener();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = listener.local_addr().unwrap();
+
+        let config = HappyEyeballsConfig {
+            attempt_delay: Duration::from_millis(50),
+            max_attempts: 4,
+            connect_timeout: Duration::from_secs(30),
+        };
+        let started = Instant::now();
+        let stream = TcpStream::connect_addrs(&[stalled, live], &config).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), live);
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn test_refused_addresses_and_attempt_cap() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = listener.local_addr().unwrap();
+        let closed = closed_addrs(2);
+        let addrs = [closed[0], closed[1], live];
+
+        let config = HappyEyeballsConfig { attempt_delay: Duration::from_secs(5), ..HappyEyeballsConfig::default() };
+        let started = Instant::now();
+        let stream = TcpStream::connect_addrs(&addrs, &config).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), live);
+        // Refusals start the next attempt without waiting out the delay
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let capped = HappyEyeballsConfig { max_attempts: 2, ..config };
+        let Err(error) = TcpStream::connect_addrs(&addrs, &capped) else {
+            panic!("connected past the attempt cap");
+        };
+        assert!(error.to_string().contains("no address was reachable"));
+
+        // Host names resolve to every address before racing
+        let stream = TcpStream::connect(&format!("localhost:{}", live.port())).unwrap();
+        assert_eq!(stream.peer_addr().unwrap().port(), live.port());
+    }
+}
+
 // UNIQUENESS Validation:
+// - [x] Happy-eyeballs connection racing (RFC 8305)
 // - [x] Zero-copy buffer design (Druschel & Banga research)
 // - [x] Scatter-gather buffer support for efficient I/O
 // - [x] Memory-safe networking with compile-time guarantees
@@ -14,7 +14,7 @@ use crate::config::{ReactorConfig, IoModel};
 use crate::error::{Error, Result};
 use crate::net::high_performance_stack::{HighPerformanceStack, PerformanceRequirements, ReliabilityLevel};
 use crate::scheduler::{Scheduler, Task, TaskPriority, TaskMetadata};
-use crate::timer::{TimerWheel, TimerCallback, TimerToken};
+use crate::timer::{TimerHandle, TimerCallback, TimerToken};
 use mio::{Events, Poll, Token};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -76,8 +76,9 @@ pub struct Reactor {
     /// Event buffer for polling
     events: Events,
 
-    /// Hierarchical timer wheel for O(1) timer operations
-    timer_wheel: TimerWheel,
+    /// Hierarchical timer wheel for O(1) timer operations, shared with
+    /// futures awaiting timeouts
+    timer_wheel: TimerHandle,
 
     /// NUMA-aware task scheduler for optimal work distribution
     scheduler: Scheduler,
@@ -155,7 +156,7 @@ impl Reactor {
         };
 
         let events = Events::with_capacity(config.max_events_per_poll);
-        let timer_wheel = TimerWheel::new();
+        let timer_wheel = TimerHandle::global().clone();
 
         // Initialize NUMA-aware scheduler with default config
         let scheduler_config = crate::config::SchedulerConfig::default();
@@ -314,6 +315,11 @@ impl Reactor {
         self.timer_wheel.cancel(token)
     }
 
+    /// Handle to the timer wheel this reactor advances
+    pub fn timer_handle(&self) -> &TimerHandle {
+        &self.timer_wheel
+    }
+
     /// Run the event loop once (non-blocking)
     ///
     /// Returns the number of events processed (I/O + timers)
@@ -3,6 +3,7 @@
 //! Provides the main Cyclone API that users interact with, integrating
 //! the reactor with async runtimes and providing convenient abstractions.
 
+use crate::blocking::{BlockingPool, BlockingPoolConfig, BlockingPoolStats, BlockingTask};
 use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::net::{TcpListener, TcpListenerHandler, TcpConnectionHandler, TcpListenerConfig, TcpStream};
@@ -28,6 +29,9 @@ pub struct Cyclone {
     #[cfg(feature = "tokio-runtime")]
     runtime: Runtime,
 
+    /// Threads for blocking closures, kept off the reactor
+    blocking: BlockingPool,
+
     /// Configuration
     config: Config,
 }
@@ -52,6 +56,7 @@ impl Cyclone {
             reactor,
             #[cfg(feature = "tokio-runtime")]
             runtime,
+            blocking: BlockingPool::default(),
             config,
         })
     }
@@ -66,6 +71,44 @@ impl Cyclone {
         self.runtime.spawn(future)
     }
 
+    /// Replace the blocking pool with one bounded by `config`
+    ///
+    /// Closures already handed to the old pool still run to the end.
+    pub fn with_blocking_pool(mut self, config: BlockingPoolConfig) -> Self {
+        self.blocking.shutdown();
+        self.blocking = BlockingPool::new(config);
+        self
+    }
+
+    /// Run a blocking closure, such as compression or hashing, on the
+    /// blocking pool instead of a reactor thread
+    ///
+    /// The returned future resolves to the closure's result. When the pool
+    /// is saturated it holds on to the closure and waits for room, so
+    /// awaiting it applies backpressure to the caller.
+    pub fn spawn_blocking<F, T>(&self, f: F) -> BlockingTask<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.blocking.spawn(f)
+    }
+
+    /// Run a blocking closure on the blocking pool, failing at once with
+    /// `Error::ResourceExhausted` if the pool is saturated
+    pub fn try_spawn_blocking<F, T>(&self, f: F) -> Result<BlockingTask<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.blocking.try_spawn(f)
+    }
+
+    /// Thread, queue and utilization figures of the blocking pool
+    pub fn blocking_stats(&self) -> BlockingPoolStats {
+        self.blocking.stats()
+    }
+
     /// Run the event loop (blocking)
     ///
     /// This method will run the Cyclone event loop until an error occurs
@@ -240,6 +283,7 @@ impl Cyclone {
     pub fn stats(&self) -> RuntimeStats {
         RuntimeStats {
             reactor_stats: self.reactor.stats(),
+            blocking: self.blocking.stats(),
             #[cfg(feature = "tokio-runtime")]
             tokio_workers: self.runtime.metrics().num_workers(),
             #[cfg(not(feature = "tokio-runtime"))]
@@ -292,6 +336,9 @@ pub struct RuntimeStats {
     /// Reactor statistics
     pub reactor_stats: crate::reactor::ReactorStats,
 
+    /// Blocking pool statistics
+    pub blocking: BlockingPoolStats,
+
     /// Number of Tokio worker threads
     pub tokio_workers: usize,
 }
@@ -299,6 +346,7 @@ pub struct RuntimeStats {
 impl Drop for Cyclone {
     fn drop(&mut self) {
         info!("Shutting down Cyclone runtime");
+        self.blocking.shutdown();
     }
 }
 
@@ -10,9 +10,8 @@
 //! - **Memory Safety**: Compile-time guarantees against timer-related bugs
 
 use crate::error::{Error, Result};
-use slotmap::{SlotMap, new_key_type};
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
 use std::time::{Duration, Instant};
 use tracing::{debug, trace};
 
@@ -63,8 +62,9 @@ pub struct TimerWheel {
     /// Level 4: ~71.6 hours resolution, 256 slots
     wheels: Vec<Vec<VecDeque<TimerEntry>>>,
 
-    /// Slot map for O(1) timer storage and lookup
-    timers: SlotMap<TimerToken, ()>,
+    /// Live timers and the (level, slot) each is stored in, so cancelling
+    /// removes the entry from its slot in O(slot length)
+    timers: HashMap<TimerToken, (usize, usize)>,
 
     /// Next available token ID
     next_token_id: usize,
@@ -132,7 +132,7 @@ impl TimerWheel {
             start_time: Instant::now(),
             current_time: Instant::now(),
             wheels,
-            timers: SlotMap::with_key(),
+            timers: HashMap::new(),
             next_token_id: 0,
             config,
             last_coalesce_time: Instant::now(),
@@ -149,8 +149,6 @@ impl TimerWheel {
         callback: Arc<dyn TimerCallback>,
     ) -> TimerToken {
         let base_expiration = self.current_time + delay;
-        let token = TimerToken(self.next_token_id);
-        self.next_token_id += 1;
 
         // Apply timer coalescing if enabled
         let expiration = if self.config.coalescing && delay.as_millis() > 10 {
@@ -159,8 +157,27 @@ impl TimerWheel {
             base_expiration
         };
 
-        debug!("Scheduling timer {:?} to expire at {:?} (base: {:?}, coalesced: {})",
-               token, expiration, base_expiration, expiration != base_expiration);
+        debug!("Scheduling timer to expire at {:?} (base: {:?}, coalesced: {})",
+               expiration, base_expiration, expiration != base_expiration);
+
+        self.insert(expiration, callback)
+    }
+
+    /// Schedule a timer to fire at `deadline`
+    ///
+    /// Deadlines are not coalesced: the timer fires on the first advance at
+    /// or after `deadline`.
+    pub fn schedule_at(
+        &mut self,
+        deadline: Instant,
+        callback: Arc<dyn TimerCallback>,
+    ) -> TimerToken {
+        self.insert(deadline, callback)
+    }
+
+    fn insert(&mut self, expiration: Instant, callback: Arc<dyn TimerCallback>) -> TimerToken {
+        let token = TimerToken(self.next_token_id);
+        self.next_token_id += 1;
 
         // Calculate which wheel level and slot this timer belongs in
         let (level, slot) = self.calculate_position(expiration);
@@ -172,17 +189,11 @@ impl TimerWheel {
             level,
         };
 
-        // Insert into the appropriate wheel slot
-        if level < self.wheels.len() {
-            self.wheels[level][slot].push_back(entry);
-        } else {
-            // Timer too far in the future, put in highest level
-            let last_level = self.wheels.len() - 1;
-            self.wheels[last_level][slot].push_back(entry);
-        }
-
-        // Store in slotmap for O(1) lookup
-        self.timers.insert(token, ());
+        // Insert into the appropriate wheel slot; timers too far in the
+        // future go in the highest level
+        let level = level.min(self.wheels.len() - 1);
+        self.wheels[level][slot].push_back(entry);
+        self.timers.insert(token, (level, slot));
 
         token
     }
@@ -191,13 +202,13 @@ impl TimerWheel {
     ///
     /// Returns true if the timer was found and cancelled
     pub fn cancel(&mut self, token: TimerToken) -> bool {
-        if self.timers.remove(token).is_some() {
-            debug!("Cancelled timer {:?}", token);
-            // Note: The timer entry remains in the wheel but won't fire
-            // since it's not in the slotmap anymore
-            true
-        } else {
-            false
+        match self.timers.remove(&token) {
+            Some((level, slot)) => {
+                self.wheels[level][slot].retain(|entry| entry.token != token);
+                debug!("Cancelled timer {:?}", token);
+                true
+            }
+            None => false,
         }
     }
 
@@ -205,41 +216,36 @@ impl TimerWheel {
     ///
     /// Returns the number of timers that fired
     pub fn advance_time(&mut self, now: Instant) -> Result<usize> {
-        self.current_time = now;
-
-        let mut fired_count = 0;
-
-        // Process timers that should have expired by now
-        // In a full implementation, this would cascade through wheel levels
-        // For now, we'll do a simplified version
-
-        // Process level 0 (highest resolution)
-        let level_0_slots = &mut self.wheels[0];
-        for slot_timers in level_0_slots.iter_mut() {
-            while let Some(entry) = slot_timers.front() {
-                if entry.expiration <= now && self.timers.contains_key(entry.token) {
-                    let entry = slot_timers.pop_front().unwrap();
+        let expired = self.take_expired(now);
+        Ok(fire(expired))
+    }
 
-                    // Fire the timer callback
-                    if let Err(e) = entry.callback.on_timer(entry.token) {
-                        tracing::error!("Timer callback failed for {:?}: {}", entry.token, e);
-                    }
+    /// Advance the clock to `now` and remove every expired timer without
+    /// firing it
+    fn take_expired(&mut self, now: Instant) -> Vec<TimerEntry> {
+        self.current_time = now;
 
-                    fired_count += 1;
-                } else {
-                    break; // Timers are ordered, so we can stop
+        // Process timers that should have expired by now. Without cascading,
+        // every level is checked directly; a slot is not ordered by
+        // expiration, so each one is scanned in full
+        // TODO: Implement full hierarchical cascading
+        let mut expired = Vec::new();
+        for level in self.wheels.iter_mut() {
+            for slot_timers in level.iter_mut() {
+                if slot_timers.iter().all(|entry| entry.expiration > now) {
+                    continue;
                 }
+                let (due, pending): (VecDeque<_>, VecDeque<_>) = slot_timers
+                    .drain(..)
+                    .partition(|entry| entry.expiration <= now);
+                *slot_timers = pending;
+                expired.extend(due);
             }
         }
-
-        // TODO: Implement full hierarchical cascading
-        // This would involve moving timers between wheel levels
-
-        if fired_count > 0 {
-            trace!("Fired {} timers", fired_count);
+        for entry in &expired {
+            self.timers.remove(&entry.token);
         }
-
-        Ok(fired_count)
+        expired
     }
 
     /// Get the current time according to the timer wheel
@@ -338,6 +344,103 @@ impl TimerWheel {
     }
 }
 
+/// Invoke the callbacks of expired timers, returning how many fired
+fn fire(expired: Vec<TimerEntry>) -> usize {
+    for entry in &expired {
+        if let Err(e) = entry.callback.on_timer(entry.token) {
+            tracing::error!("Timer callback failed for {:?}: {}", entry.token, e);
+        }
+    }
+    if !expired.is_empty() {
+        trace!("Fired {} timers", expired.len());
+    }
+    expired.len()
+}
+
+/// Shared handle to a timer wheel
+///
+/// The reactor advances the wheel on every poll; futures such as
+/// [`time::timeout`](crate::time::timeout) schedule and cancel their timers
+/// through a clone of the same handle. Callbacks run after the wheel's lock
+/// is released, so a callback may schedule or cancel timers itself.
+#[derive(Clone)]
+pub struct TimerHandle {
+    wheel: Arc<Mutex<TimerWheel>>,
+}
+
+impl TimerHandle {
+    /// Create a handle to a new timer wheel with default configuration
+    pub fn new() -> Self {
+        Self::from_wheel(TimerWheel::new())
+    }
+
+    /// Wrap an existing timer wheel
+    pub fn from_wheel(wheel: TimerWheel) -> Self {
+        Self {
+            wheel: Arc::new(Mutex::new(wheel)),
+        }
+    }
+
+    /// The process-wide timer wheel driven by every [`Reactor`](crate::Reactor)
+    pub fn global() -> &'static TimerHandle {
+        static GLOBAL: OnceLock<TimerHandle> = OnceLock::new();
+        GLOBAL.get_or_init(TimerHandle::new)
+    }
+
+    /// Schedule a timer to fire after `delay`
+    pub fn schedule(&self, delay: Duration, callback: Arc<dyn TimerCallback>) -> TimerToken {
+        self.lock().schedule(delay, callback)
+    }
+
+    /// Schedule a timer to fire at `deadline`
+    pub fn schedule_at(&self, deadline: Instant, callback: Arc<dyn TimerCallback>) -> TimerToken {
+        self.lock().schedule_at(deadline, callback)
+    }
+
+    /// Cancel a scheduled timer, removing it from the wheel
+    ///
+    /// Returns true if the timer was found and cancelled
+    pub fn cancel(&self, token: TimerToken) -> bool {
+        self.lock().cancel(token)
+    }
+
+    /// Advance time and fire expired timers
+    ///
+    /// Returns the number of timers that fired
+    pub fn advance_time(&self, now: Instant) -> Result<usize> {
+        let expired = self.lock().take_expired(now);
+        Ok(fire(expired))
+    }
+
+    /// The wheel's clock: the time it was last advanced to
+    pub fn current_time(&self) -> Instant {
+        self.lock().current_time()
+    }
+
+    /// Get statistics about the timer wheel
+    pub fn stats(&self) -> TimerStats {
+        self.lock().stats()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimerWheel> {
+        // Callbacks never run under the lock, so poisoning cannot leave the
+        // wheel half-updated
+        self.wheel.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for TimerHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for TimerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimerHandle").finish_non_exhaustive()
+    }
+}
+
 /// Statistics about the timer wheel
 #[derive(Debug, Clone)]
 pub struct TimerStats {
@@ -358,4 +461,4 @@ pub struct TimerStats {
 // - [x] O(1) amortized operations through multi-level design
 // - [x] Memory-safe timer management with Arc callbacks
 // - [x] Research-backed algorithm with proper wheel calculations
-// - [x] SlotMap for O(1) timer storage and lookup
+// - [x] O(1) timer lookup; cancellation removes the wheel entry